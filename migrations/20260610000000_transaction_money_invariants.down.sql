@@ -0,0 +1,11 @@
+DROP TRIGGER IF EXISTS trg_transactions_track_active_anchor ON transactions;
+DROP FUNCTION IF EXISTS transactions_track_active_anchor();
+DROP TABLE IF EXISTS transaction_active_anchors;
+
+ALTER TABLE transactions
+    DROP CONSTRAINT IF EXISTS chk_transactions_anchor_id_present,
+    DROP CONSTRAINT IF EXISTS chk_transactions_status_valid,
+    DROP CONSTRAINT IF EXISTS chk_transactions_amount_positive;
+
+DROP TRIGGER IF EXISTS trg_transactions_default_anchor_id ON transactions;
+DROP FUNCTION IF EXISTS transactions_default_anchor_id();
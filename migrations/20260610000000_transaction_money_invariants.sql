@@ -0,0 +1,103 @@
+-- Enforce money invariants on transactions at the database level so that a
+-- buggy code path cannot persist non-positive amounts, unknown statuses, or a
+-- second in-flight transaction for the same anchor transaction.
+
+-- ── 1. anchor_transaction_id is always present ──────────────────────────────
+
+-- Legacy rows (and callers that do not know the anchor id) fall back to the
+-- row's own id so the column can be constrained without breaking inserts.
+UPDATE transactions
+    SET anchor_transaction_id = id::text
+    WHERE anchor_transaction_id IS NULL OR btrim(anchor_transaction_id) = '';
+
+CREATE OR REPLACE FUNCTION transactions_default_anchor_id()
+RETURNS trigger AS $$
+BEGIN
+    IF NEW.anchor_transaction_id IS NULL OR btrim(NEW.anchor_transaction_id) = '' THEN
+        NEW.anchor_transaction_id := NEW.id::text;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_transactions_default_anchor_id ON transactions;
+CREATE TRIGGER trg_transactions_default_anchor_id
+    BEFORE INSERT ON transactions
+    FOR EACH ROW EXECUTE FUNCTION transactions_default_anchor_id();
+
+-- ── 2. CHECK constraints ────────────────────────────────────────────────────
+
+ALTER TABLE transactions
+    ADD CONSTRAINT chk_transactions_amount_positive CHECK (amount > 0);
+
+ALTER TABLE transactions
+    ADD CONSTRAINT chk_transactions_status_valid
+    CHECK (status IN ('pending', 'processing', 'completed', 'failed', 'dlq'));
+
+-- Expressed as a CHECK rather than SET NOT NULL so old application versions
+-- keep working during blue-green rollout (see docs/migration-safety.md).
+ALTER TABLE transactions
+    ADD CONSTRAINT chk_transactions_anchor_id_present
+    CHECK (anchor_transaction_id IS NOT NULL);
+
+-- ── 3. One active transaction per anchor_transaction_id ─────────────────────
+
+-- Postgres cannot enforce a unique index on a partitioned table unless it
+-- includes the partition key (created_at), which would make it useless here.
+-- Instead, the guard table below acts as a partial unique index over the
+-- "active" rows (anything not failed / dead-lettered) and is maintained by
+-- triggers on transactions.
+CREATE TABLE IF NOT EXISTS transaction_active_anchors (
+    anchor_transaction_id VARCHAR(255) NOT NULL,
+    transaction_id        UUID NOT NULL,
+    created_at            TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT uq_transactions_active_anchor PRIMARY KEY (anchor_transaction_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_transaction_active_anchors_transaction_id
+    ON transaction_active_anchors(transaction_id);
+
+COMMENT ON TABLE transaction_active_anchors IS
+    'Uniqueness guard: at most one non-failed transaction per anchor_transaction_id';
+
+-- Seed from existing data. Pre-existing duplicates are tolerated (first one wins)
+-- rather than failing the migration; new duplicates are rejected from here on.
+INSERT INTO transaction_active_anchors (anchor_transaction_id, transaction_id, created_at)
+SELECT DISTINCT ON (anchor_transaction_id) anchor_transaction_id, id, created_at
+FROM transactions
+WHERE status NOT IN ('failed', 'dlq')
+ORDER BY anchor_transaction_id, created_at ASC
+ON CONFLICT DO NOTHING;
+
+CREATE OR REPLACE FUNCTION transactions_track_active_anchor()
+RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        IF NEW.status NOT IN ('failed', 'dlq') THEN
+            INSERT INTO transaction_active_anchors (anchor_transaction_id, transaction_id)
+            VALUES (NEW.anchor_transaction_id, NEW.id);
+        END IF;
+    ELSIF TG_OP = 'UPDATE' THEN
+        IF NEW.status IN ('failed', 'dlq') AND OLD.status NOT IN ('failed', 'dlq') THEN
+            DELETE FROM transaction_active_anchors
+            WHERE anchor_transaction_id = OLD.anchor_transaction_id
+              AND transaction_id = OLD.id;
+        ELSIF NEW.status NOT IN ('failed', 'dlq') AND OLD.status IN ('failed', 'dlq') THEN
+            -- Reprocessing a failed row re-claims its anchor id; this raises a
+            -- unique violation if another transaction became active meanwhile.
+            INSERT INTO transaction_active_anchors (anchor_transaction_id, transaction_id)
+            VALUES (NEW.anchor_transaction_id, NEW.id);
+        END IF;
+    ELSIF TG_OP = 'DELETE' THEN
+        DELETE FROM transaction_active_anchors
+        WHERE anchor_transaction_id = OLD.anchor_transaction_id
+          AND transaction_id = OLD.id;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_transactions_track_active_anchor ON transactions;
+CREATE TRIGGER trg_transactions_track_active_anchor
+    AFTER INSERT OR UPDATE OF status OR DELETE ON transactions
+    FOR EACH ROW EXECUTE FUNCTION transactions_track_active_anchor();
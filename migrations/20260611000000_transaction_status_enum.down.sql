@@ -0,0 +1,19 @@
+DROP CAST IF EXISTS (varchar AS transaction_status);
+DROP CAST IF EXISTS (text AS transaction_status);
+
+DROP TRIGGER IF EXISTS trg_transactions_track_active_anchor ON transactions;
+
+ALTER TABLE transactions ALTER COLUMN status DROP DEFAULT;
+ALTER TABLE transactions
+    ALTER COLUMN status TYPE VARCHAR(20) USING status::text;
+ALTER TABLE transactions ALTER COLUMN status SET DEFAULT 'pending';
+
+CREATE TRIGGER trg_transactions_track_active_anchor
+    AFTER INSERT OR UPDATE OF status OR DELETE ON transactions
+    FOR EACH ROW EXECUTE FUNCTION transactions_track_active_anchor();
+
+ALTER TABLE transactions
+    ADD CONSTRAINT chk_transactions_status_valid
+    CHECK (status IN ('pending', 'processing', 'completed', 'failed', 'dlq'));
+
+DROP TYPE IF EXISTS transaction_status;
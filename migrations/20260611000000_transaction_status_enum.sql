@@ -0,0 +1,67 @@
+-- Convert transactions.status from free-form VARCHAR to a Postgres enum so
+-- typos such as 'complated' can no longer be persisted. The Rust side maps the
+-- enum through `TransactionStatus` (sqlx::Type, type_name = "transaction_status").
+
+-- ── 1. Enum type ────────────────────────────────────────────────────────────
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'transaction_status') THEN
+        CREATE TYPE transaction_status AS ENUM (
+            'pending',
+            'processing',
+            'completed',
+            'failed',
+            'dlq'
+        );
+    END IF;
+END $$;
+
+-- ── 2. Normalize existing values ────────────────────────────────────────────
+
+-- Case / whitespace drift and known misspellings are folded onto the canonical
+-- value. Anything still unrecognised is parked as 'failed' so it surfaces in
+-- the review and reprocess flows instead of blocking the migration.
+UPDATE transactions
+    SET status = CASE lower(btrim(status))
+        WHEN 'pending'    THEN 'pending'
+        WHEN 'processing' THEN 'processing'
+        WHEN 'completed'  THEN 'completed'
+        WHEN 'complete'   THEN 'completed'
+        WHEN 'complated'  THEN 'completed'
+        WHEN 'success'    THEN 'completed'
+        WHEN 'failed'     THEN 'failed'
+        WHEN 'fail'       THEN 'failed'
+        WHEN 'error'      THEN 'failed'
+        WHEN 'dlq'        THEN 'dlq'
+        ELSE 'failed'
+    END
+    WHERE status NOT IN ('pending', 'processing', 'completed', 'failed', 'dlq');
+
+-- ── 3. Column type change ───────────────────────────────────────────────────
+
+-- The enum supersedes the CHECK constraint added with the money invariants.
+ALTER TABLE transactions DROP CONSTRAINT IF EXISTS chk_transactions_status_valid;
+
+-- Text writers keep working through the assignment casts in section 4.
+-- migration-safety: allow ALTER COLUMN .* TYPE
+
+-- Triggers listing the column in UPDATE OF pin its type; recreate around the change.
+DROP TRIGGER IF EXISTS trg_transactions_track_active_anchor ON transactions;
+
+ALTER TABLE transactions ALTER COLUMN status DROP DEFAULT;
+ALTER TABLE transactions
+    ALTER COLUMN status TYPE transaction_status USING status::transaction_status;
+ALTER TABLE transactions ALTER COLUMN status SET DEFAULT 'pending';
+
+CREATE TRIGGER trg_transactions_track_active_anchor
+    AFTER INSERT OR UPDATE OF status OR DELETE ON transactions
+    FOR EACH ROW EXECUTE FUNCTION transactions_track_active_anchor();
+
+-- ── 4. Compatibility for text writers ───────────────────────────────────────
+
+-- Application versions that still bind status as text (blue-green rollout,
+-- ad-hoc scripts) can keep inserting/updating; invalid values are still
+-- rejected by the enum input function.
+CREATE CAST (text AS transaction_status) WITH INOUT AS ASSIGNMENT;
+CREATE CAST (varchar AS transaction_status) WITH INOUT AS ASSIGNMENT;
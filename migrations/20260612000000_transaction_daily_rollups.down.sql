@@ -0,0 +1,4 @@
+DROP TRIGGER IF EXISTS trg_transactions_daily_rollup ON transactions;
+DROP FUNCTION IF EXISTS transactions_maintain_daily_rollup();
+DROP FUNCTION IF EXISTS transaction_rollup_apply(DATE, VARCHAR, transaction_status, BIGINT, NUMERIC);
+DROP TABLE IF EXISTS transaction_daily_rollups;
@@ -0,0 +1,75 @@
+-- Daily per-asset, per-status rollups of transaction counts and volume.
+-- Aggregate reads (GraphQL `transactionStats`) hit this small table instead of
+-- scanning the partitioned `transactions` table.
+
+-- ── 1. Rollup table ─────────────────────────────────────────────────────────
+
+CREATE TABLE IF NOT EXISTS transaction_daily_rollups (
+    day         DATE NOT NULL,
+    asset_code  VARCHAR(12) NOT NULL,
+    status      transaction_status NOT NULL,
+    tx_count    BIGINT NOT NULL DEFAULT 0,
+    volume      NUMERIC NOT NULL DEFAULT 0,
+    updated_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (day, asset_code, status)
+);
+
+CREATE INDEX IF NOT EXISTS idx_transaction_daily_rollups_asset
+    ON transaction_daily_rollups (asset_code, day);
+
+-- ── 2. Maintenance trigger ──────────────────────────────────────────────────
+
+-- Days are bucketed in UTC regardless of the session time zone.
+CREATE OR REPLACE FUNCTION transaction_rollup_apply(
+    p_day DATE,
+    p_asset_code VARCHAR,
+    p_status transaction_status,
+    p_count BIGINT,
+    p_volume NUMERIC
+) RETURNS VOID AS $$
+BEGIN
+    INSERT INTO transaction_daily_rollups (day, asset_code, status, tx_count, volume)
+    VALUES (p_day, p_asset_code, p_status, p_count, p_volume)
+    ON CONFLICT (day, asset_code, status) DO UPDATE
+        SET tx_count   = transaction_daily_rollups.tx_count + EXCLUDED.tx_count,
+            volume     = transaction_daily_rollups.volume + EXCLUDED.volume,
+            updated_at = NOW();
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION transactions_maintain_daily_rollup()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP IN ('UPDATE', 'DELETE') THEN
+        PERFORM transaction_rollup_apply(
+            (OLD.created_at AT TIME ZONE 'UTC')::date,
+            OLD.asset_code, OLD.status, -1, -OLD.amount
+        );
+    END IF;
+    IF TG_OP IN ('INSERT', 'UPDATE') THEN
+        PERFORM transaction_rollup_apply(
+            (NEW.created_at AT TIME ZONE 'UTC')::date,
+            NEW.asset_code, NEW.status, 1, NEW.amount
+        );
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_transactions_daily_rollup ON transactions;
+CREATE TRIGGER trg_transactions_daily_rollup
+    AFTER INSERT OR UPDATE OF status, amount, asset_code, created_at OR DELETE ON transactions
+    FOR EACH ROW EXECUTE FUNCTION transactions_maintain_daily_rollup();
+
+-- ── 3. Backfill ─────────────────────────────────────────────────────────────
+
+-- CREATE TRIGGER holds a lock that blocks concurrent writes until this
+-- migration commits, so the backfill and the trigger cannot double count.
+INSERT INTO transaction_daily_rollups (day, asset_code, status, tx_count, volume)
+SELECT (created_at AT TIME ZONE 'UTC')::date, asset_code, status, COUNT(*), SUM(amount)
+FROM transactions
+GROUP BY 1, 2, 3
+ON CONFLICT (day, asset_code, status) DO UPDATE
+    SET tx_count   = EXCLUDED.tx_count,
+        volume     = EXCLUDED.volume,
+        updated_at = NOW();
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_webhook_inbox_received_at;
+DROP INDEX IF EXISTS idx_webhook_inbox_hash_received;
+DROP TABLE IF EXISTS webhook_inbox;
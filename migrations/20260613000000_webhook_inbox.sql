@@ -0,0 +1,29 @@
+-- Content-hash deduplication for inbound callbacks. Some partners resend an
+-- identical webhook days after the first delivery, well past idempotency-key
+-- expiry; the ingestion path records a hash of every accepted payload here and
+-- returns the original transaction when the same hash arrives again within
+-- the configured lookback window (WEBHOOK_DEDUP_WINDOW_HOURS).
+
+-- ── 1. Inbox table ──────────────────────────────────────────────────────────
+
+CREATE TABLE IF NOT EXISTS webhook_inbox (
+    id                    UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    payload_hash          TEXT NOT NULL,
+    anchor_transaction_id VARCHAR(255),
+    -- transactions is partitioned on created_at, so no FK is possible here.
+    transaction_id        UUID NOT NULL,
+    received_at           TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- ── 2. Indexes ──────────────────────────────────────────────────────────────
+
+-- Lookback query: newest inbox row for a hash within the window.
+CREATE INDEX IF NOT EXISTS idx_webhook_inbox_hash_received
+    ON webhook_inbox(payload_hash, received_at DESC);
+
+-- Retention pruning.
+CREATE INDEX IF NOT EXISTS idx_webhook_inbox_received_at
+    ON webhook_inbox(received_at);
+
+COMMENT ON TABLE webhook_inbox IS
+    'Hashes of accepted callback payloads, used to deduplicate partner resends';
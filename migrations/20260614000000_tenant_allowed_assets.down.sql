@@ -0,0 +1,2 @@
+ALTER TABLE tenants DROP CONSTRAINT IF EXISTS chk_tenants_allowed_assets_upper;
+ALTER TABLE tenants DROP COLUMN IF EXISTS allowed_assets;
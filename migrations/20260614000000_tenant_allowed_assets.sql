@@ -0,0 +1,19 @@
+-- Per-partner asset allowlist. Deposits for an asset outside a partner's
+-- allowlist are rejected with ERR_TRANSACTION_007. NULL means "no
+-- restriction" so existing partners keep working until they are configured;
+-- an empty array blocks every asset.
+
+-- ── 1. Column ───────────────────────────────────────────────────────────────
+
+ALTER TABLE tenants ADD COLUMN IF NOT EXISTS allowed_assets TEXT[];
+
+COMMENT ON COLUMN tenants.allowed_assets IS
+    'Upper-case asset codes the partner may deposit; NULL = unrestricted';
+
+-- ── 2. Normalisation ────────────────────────────────────────────────────────
+
+-- Asset codes are compared case-sensitively in SQL; keep them upper-case.
+ALTER TABLE tenants ADD CONSTRAINT chk_tenants_allowed_assets_upper
+    CHECK (allowed_assets IS NULL OR array_to_string(allowed_assets, ',') = upper(array_to_string(allowed_assets, ',')))
+    NOT VALID;
+ALTER TABLE tenants VALIDATE CONSTRAINT chk_tenants_allowed_assets_upper;
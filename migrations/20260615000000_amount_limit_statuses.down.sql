@@ -0,0 +1,8 @@
+ALTER TABLE assets DROP CONSTRAINT IF EXISTS chk_assets_amount_limits;
+
+-- Postgres cannot drop enum values. Park affected rows as 'failed' so they
+-- surface in the reprocess flow once the application no longer knows the
+-- new labels; the values themselves remain on the type.
+UPDATE transactions
+    SET status = 'failed'
+    WHERE status IN ('refund_pending', 'compliance_review');
@@ -0,0 +1,19 @@
+-- Deposits outside an asset's configured [min_amount, max_amount] range are
+-- no longer queued for processing: sub-minimum deposits are parked in
+-- 'refund_pending' and over-maximum ones in 'compliance_review'. The limits
+-- themselves already live on `assets` (20260428000001).
+
+-- ── 1. Status values ────────────────────────────────────────────────────────
+
+ALTER TYPE transaction_status ADD VALUE IF NOT EXISTS 'refund_pending';
+ALTER TYPE transaction_status ADD VALUE IF NOT EXISTS 'compliance_review';
+
+-- ── 2. Limit sanity ─────────────────────────────────────────────────────────
+
+ALTER TABLE assets ADD CONSTRAINT chk_assets_amount_limits
+    CHECK (
+        (min_amount IS NULL OR min_amount >= 0)
+        AND (min_amount IS NULL OR max_amount IS NULL OR min_amount <= max_amount)
+    )
+    NOT VALID;
+ALTER TABLE assets VALIDATE CONSTRAINT chk_assets_amount_limits;
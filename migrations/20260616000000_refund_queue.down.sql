@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_refund_queue_status_created;
+DROP TABLE IF EXISTS refund_queue;
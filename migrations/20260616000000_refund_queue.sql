@@ -0,0 +1,40 @@
+-- Refund tasks for on-chain payments that cannot be matched to a deposit
+-- (missing or unknown memo, unregistered asset). Both the account monitor and
+-- daily reconciliation enqueue here; the refund returns the payment to its
+-- sender minus the network fee. Admins can hold, release, cancel or complete a
+-- task through /admin/refunds.
+
+-- ── 1. Queue table ──────────────────────────────────────────────────────────
+
+CREATE TABLE IF NOT EXISTS refund_queue (
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- Horizon payment operation id; one refund per inbound payment.
+    payment_id      TEXT NOT NULL UNIQUE,
+    source          VARCHAR(32) NOT NULL,
+    reason          VARCHAR(32) NOT NULL,
+    refund_to       VARCHAR(56) NOT NULL,
+    asset_code      VARCHAR(12) NOT NULL,
+    amount          NUMERIC NOT NULL,
+    network_fee     NUMERIC NOT NULL DEFAULT 0,
+    refund_amount   NUMERIC NOT NULL,
+    memo            TEXT,
+    status          VARCHAR(20) NOT NULL DEFAULT 'pending',
+    stellar_tx_hash VARCHAR(64),
+    note            TEXT,
+    updated_by      VARCHAR(255),
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_refund_queue_status
+        CHECK (status IN ('pending', 'held', 'cancelled', 'completed')),
+    CONSTRAINT chk_refund_queue_amounts
+        CHECK (amount > 0 AND network_fee >= 0 AND refund_amount >= 0)
+);
+
+-- ── 2. Indexes ──────────────────────────────────────────────────────────────
+
+-- Admin listing and the refund submitter both scan by status, oldest first.
+CREATE INDEX IF NOT EXISTS idx_refund_queue_status_created
+    ON refund_queue(status, created_at);
+
+COMMENT ON TABLE refund_queue IS
+    'Refunds owed for unmatched inbound on-chain payments';
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_export_jobs_status_created;
+DROP TABLE IF EXISTS export_jobs;
@@ -0,0 +1,35 @@
+-- Asynchronous transaction exports. POST /exports records a queued job with
+-- its filters; the scheduler's export runner claims it, writes a CSV or NDJSON
+-- file to the export directory and records the path and row count. GET
+-- /exports/:id reports progress and, once completed, a signed download URL.
+
+-- ── 1. Job table ────────────────────────────────────────────────────────────
+
+CREATE TABLE IF NOT EXISTS export_jobs (
+    id           UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    format       VARCHAR(10) NOT NULL,
+    -- Same filters as GET /transactions/search, captured at request time.
+    filters      JSONB NOT NULL DEFAULT '{}'::jsonb,
+    status       VARCHAR(20) NOT NULL DEFAULT 'queued',
+    file_path    TEXT,
+    row_count    BIGINT,
+    error        TEXT,
+    requested_by VARCHAR(255),
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at   TIMESTAMPTZ,
+    completed_at TIMESTAMPTZ,
+    updated_at   TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_export_jobs_format
+        CHECK (format IN ('csv', 'ndjson')),
+    CONSTRAINT chk_export_jobs_status
+        CHECK (status IN ('queued', 'running', 'completed', 'failed'))
+);
+
+-- ── 2. Indexes ──────────────────────────────────────────────────────────────
+
+-- The runner claims the oldest queued (or stale running) job.
+CREATE INDEX IF NOT EXISTS idx_export_jobs_status_created
+    ON export_jobs(status, created_at);
+
+COMMENT ON TABLE export_jobs IS
+    'Queued and finished transaction exports (CSV / NDJSON)';
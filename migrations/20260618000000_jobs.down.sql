@@ -0,0 +1,48 @@
+CREATE TABLE IF NOT EXISTS export_jobs (
+    id           UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    format       VARCHAR(10) NOT NULL,
+    filters      JSONB NOT NULL DEFAULT '{}'::jsonb,
+    status       VARCHAR(20) NOT NULL DEFAULT 'queued',
+    file_path    TEXT,
+    row_count    BIGINT,
+    error        TEXT,
+    requested_by VARCHAR(255),
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at   TIMESTAMPTZ,
+    completed_at TIMESTAMPTZ,
+    updated_at   TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_export_jobs_format
+        CHECK (format IN ('csv', 'ndjson')),
+    CONSTRAINT chk_export_jobs_status
+        CHECK (status IN ('queued', 'running', 'completed', 'failed'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_export_jobs_status_created
+    ON export_jobs(status, created_at);
+
+-- Cancelled exports have no equivalent; they are restored as failed.
+INSERT INTO export_jobs (
+    id, format, filters, status, file_path, row_count, error, requested_by,
+    created_at, started_at, completed_at, updated_at
+)
+SELECT
+    id,
+    params->>'format',
+    COALESCE(params->'filters', '{}'::jsonb),
+    CASE WHEN status = 'cancelled' THEN 'failed' ELSE status END,
+    -- Only the file name is kept on jobs; it lives under EXPORT_DIR.
+    result->>'file',
+    (result->>'row_count')::bigint,
+    CASE WHEN status = 'cancelled' THEN 'cancelled' ELSE error END,
+    requested_by,
+    created_at,
+    started_at,
+    completed_at,
+    updated_at
+FROM jobs
+WHERE kind = 'transaction_export'
+ON CONFLICT (id) DO NOTHING;
+
+DROP INDEX IF EXISTS idx_jobs_kind_created;
+DROP INDEX IF EXISTS idx_jobs_status_created;
+DROP TABLE IF EXISTS jobs;
@@ -0,0 +1,79 @@
+-- Generic long-running jobs (exports, reconciliation backfills, rollup
+-- recomputes). The scheduler's job runner claims queued jobs by kind, records
+-- progress and a resumable checkpoint as it goes, and honours cancellation
+-- requests at each checkpoint. A job whose heartbeat stops (crashed instance)
+-- is reclaimed and resumed from its last checkpoint.
+--
+-- Supersedes export_jobs: existing exports are carried over as
+-- 'transaction_export' jobs with the same ids, so issued job ids keep working.
+
+-- ── 1. Job table ────────────────────────────────────────────────────────────
+
+CREATE TABLE IF NOT EXISTS jobs (
+    id               UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    kind             VARCHAR(64) NOT NULL,
+    params           JSONB NOT NULL DEFAULT '{}'::jsonb,
+    status           VARCHAR(20) NOT NULL DEFAULT 'queued',
+    progress_current BIGINT NOT NULL DEFAULT 0,
+    -- NULL until the job knows how much work there is.
+    progress_total   BIGINT,
+    -- Kind-specific resume state, written together with progress.
+    checkpoint       JSONB,
+    result           JSONB,
+    error            TEXT,
+    cancel_requested BOOLEAN NOT NULL DEFAULT FALSE,
+    requested_by     VARCHAR(255),
+    created_at       TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at       TIMESTAMPTZ,
+    heartbeat_at     TIMESTAMPTZ,
+    completed_at     TIMESTAMPTZ,
+    updated_at       TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_jobs_status
+        CHECK (status IN ('queued', 'running', 'completed', 'failed', 'cancelled')),
+    CONSTRAINT chk_jobs_progress
+        CHECK (progress_current >= 0 AND (progress_total IS NULL OR progress_total >= 0))
+);
+
+-- ── 2. Indexes ──────────────────────────────────────────────────────────────
+
+-- The runner claims the oldest queued (or stale running) job of its kinds.
+CREATE INDEX IF NOT EXISTS idx_jobs_status_created
+    ON jobs(status, created_at);
+
+-- Status API listing by kind.
+CREATE INDEX IF NOT EXISTS idx_jobs_kind_created
+    ON jobs(kind, created_at DESC);
+
+COMMENT ON TABLE jobs IS
+    'Long-running background jobs with progress, checkpoints and cancellation';
+
+-- ── 3. Carry over export_jobs ───────────────────────────────────────────────
+
+INSERT INTO jobs (
+    id, kind, params, status, progress_current, progress_total, result, error,
+    requested_by, created_at, started_at, heartbeat_at, completed_at, updated_at
+)
+SELECT
+    id,
+    'transaction_export',
+    jsonb_build_object('format', format, 'filters', filters),
+    status,
+    COALESCE(row_count, 0),
+    row_count,
+    CASE WHEN file_path IS NOT NULL
+         THEN jsonb_build_object('file', regexp_replace(file_path, '^.*/', ''), 'row_count', row_count)
+    END,
+    error,
+    requested_by,
+    created_at,
+    started_at,
+    started_at,
+    completed_at,
+    updated_at
+FROM export_jobs
+ON CONFLICT (id) DO NOTHING;
+
+-- migration-safety: allow DROP TABLE
+-- export_jobs was only ever read by the export endpoints, which now use jobs.
+DROP INDEX IF EXISTS idx_export_jobs_status_created;
+DROP TABLE IF EXISTS export_jobs;
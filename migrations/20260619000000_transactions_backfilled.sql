@@ -0,0 +1,12 @@
+-- Flag transactions imported from Horizon history rather than received through
+-- the anchor callback. Backfill jobs (kind 'horizon_backfill') set it; every
+-- other insert path leaves the default.
+--
+-- Backfilled rows use anchor_transaction_id 'horizon:<payment id>', so the
+-- transaction_active_anchors guard keeps re-runs from importing a payment twice.
+
+ALTER TABLE transactions
+    ADD COLUMN IF NOT EXISTS backfilled BOOLEAN NOT NULL DEFAULT FALSE;
+
+COMMENT ON COLUMN transactions.backfilled IS
+    'TRUE when the row was imported from Horizon payment history';
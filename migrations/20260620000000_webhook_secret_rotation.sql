@@ -0,0 +1,8 @@
+-- Secret rotation for webhook subscriptions. Rotating moves the current secret
+-- to previous_secret; until previous_secret_expires_at the dispatcher signs
+-- every delivery with both, so subscribers can switch verifiers without
+-- rejecting events in between.
+
+ALTER TABLE webhook_endpoints
+    ADD COLUMN IF NOT EXISTS previous_secret TEXT,
+    ADD COLUMN IF NOT EXISTS previous_secret_expires_at TIMESTAMPTZ;
@@ -0,0 +1,4 @@
+ALTER TABLE webhook_endpoints
+    DROP COLUMN IF EXISTS pause_reason,
+    DROP COLUMN IF EXISTS paused_at,
+    DROP COLUMN IF EXISTS failing_since;
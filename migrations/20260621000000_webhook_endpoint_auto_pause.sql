@@ -0,0 +1,12 @@
+-- Automatic pause of webhook subscribers that keep failing.
+--
+-- failing_since marks the first failed delivery after the last success; once
+-- failures have continued for WEBHOOK_AUTO_PAUSE_AFTER_HOURS the dispatcher
+-- disables the endpoint, records why in paused_at / pause_reason and logs a
+-- row in webhook_endpoint_notifications. POST
+-- /admin/webhook-subscriptions/:id/enable clears all three.
+
+ALTER TABLE webhook_endpoints
+    ADD COLUMN IF NOT EXISTS failing_since TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS paused_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS pause_reason TEXT;
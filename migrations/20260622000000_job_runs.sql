@@ -0,0 +1,22 @@
+-- One row per run of a scheduled (cron) job. The scheduler records how each
+-- run ended: succeeded, failed, or timed_out when it exceeded its timeout and
+-- was cancelled (or killed once the cancellation grace period ran out).
+
+CREATE TABLE IF NOT EXISTS job_runs (
+    id          BIGSERIAL PRIMARY KEY,
+    job_name    VARCHAR(100) NOT NULL,
+    status      VARCHAR(20) NOT NULL,
+    error       TEXT,
+    started_at  TIMESTAMPTZ NOT NULL,
+    finished_at TIMESTAMPTZ NOT NULL,
+    duration_ms BIGINT NOT NULL,
+    CONSTRAINT chk_job_runs_status
+        CHECK (status IN ('succeeded', 'failed', 'timed_out'))
+);
+
+-- Latest runs of a job first.
+CREATE INDEX IF NOT EXISTS idx_job_runs_job_started
+    ON job_runs(job_name, started_at DESC);
+
+COMMENT ON TABLE job_runs IS
+    'Outcome of every scheduled job run, including timeouts';
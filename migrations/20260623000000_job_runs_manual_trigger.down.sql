@@ -0,0 +1,13 @@
+DELETE FROM job_runs WHERE status = 'running';
+
+ALTER TABLE job_runs DROP CONSTRAINT IF EXISTS chk_job_runs_trigger;
+ALTER TABLE job_runs DROP CONSTRAINT IF EXISTS chk_job_runs_status;
+ALTER TABLE job_runs
+    ADD CONSTRAINT chk_job_runs_status
+        CHECK (status IN ('succeeded', 'failed', 'timed_out'));
+
+ALTER TABLE job_runs
+    DROP COLUMN IF EXISTS params,
+    DROP COLUMN IF EXISTS trigger,
+    ALTER COLUMN duration_ms SET NOT NULL,
+    ALTER COLUMN finished_at SET NOT NULL;
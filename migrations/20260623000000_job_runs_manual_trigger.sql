@@ -0,0 +1,21 @@
+-- Manual job triggers (POST /admin/jobs/:name/run) need the run's id before
+-- it finishes, so runs are now recorded when they start, as 'running', and
+-- updated when they end. trigger tells scheduled runs from manual ones and
+-- params keeps the one-off parameters a manual run was given.
+
+ALTER TABLE job_runs
+    ALTER COLUMN finished_at DROP NOT NULL,
+    ALTER COLUMN duration_ms DROP NOT NULL,
+    ADD COLUMN IF NOT EXISTS trigger VARCHAR(20) NOT NULL DEFAULT 'schedule',
+    ADD COLUMN IF NOT EXISTS params JSONB;
+
+ALTER TABLE job_runs DROP CONSTRAINT IF EXISTS chk_job_runs_status;
+ALTER TABLE job_runs
+    ADD CONSTRAINT chk_job_runs_status
+        CHECK (status IN ('running', 'succeeded', 'failed', 'timed_out')) NOT VALID;
+ALTER TABLE job_runs VALIDATE CONSTRAINT chk_job_runs_status;
+
+ALTER TABLE job_runs
+    ADD CONSTRAINT chk_job_runs_trigger
+        CHECK (trigger IN ('schedule', 'manual')) NOT VALID;
+ALTER TABLE job_runs VALIDATE CONSTRAINT chk_job_runs_trigger;
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_webhook_deliveries_delivered_at;
+DROP INDEX IF EXISTS idx_job_runs_started_at;
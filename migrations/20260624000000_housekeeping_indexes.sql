@@ -0,0 +1,12 @@
+-- Indexes for the housekeeping job, which prunes old rows in small batches.
+-- Each batch selects the oldest rows by these columns, so without them every
+-- batch would scan the whole table.
+
+-- Old job runs, and runs left 'running' by a crashed instance.
+CREATE INDEX IF NOT EXISTS idx_job_runs_started_at
+    ON job_runs(started_at);
+
+-- Delivered webhooks, by delivery time.
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_delivered_at
+    ON webhook_deliveries(last_attempt_at)
+    WHERE status = 'delivered';
@@ -0,0 +1,25 @@
+-- History of circuit breaker state changes, automatic (failure threshold
+-- reached, trial request succeeded) and manual (operator override with a
+-- reason), so on-call can see why processing stalled.
+
+CREATE TABLE IF NOT EXISTS circuit_breaker_events (
+    id          BIGSERIAL PRIMARY KEY,
+    breaker     VARCHAR(100) NOT NULL,
+    from_state  VARCHAR(20) NOT NULL,
+    to_state    VARCHAR(20) NOT NULL,
+    reason      TEXT NOT NULL,
+    -- NULL for automatic transitions.
+    actor       VARCHAR(255),
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_circuit_breaker_events_states
+        CHECK (from_state IN ('open', 'closed') AND to_state IN ('open', 'closed'))
+);
+
+-- Latest events first, overall and per breaker.
+CREATE INDEX IF NOT EXISTS idx_circuit_breaker_events_occurred
+    ON circuit_breaker_events(occurred_at DESC);
+CREATE INDEX IF NOT EXISTS idx_circuit_breaker_events_breaker
+    ON circuit_breaker_events(breaker, occurred_at DESC);
+
+COMMENT ON TABLE circuit_breaker_events IS
+    'Circuit breaker state transitions, automatic and manual';
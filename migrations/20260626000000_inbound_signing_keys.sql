@@ -0,0 +1,30 @@
+-- Keys that sign inbound callbacks (X-Stellar-Signature). tenant_id NULL is
+-- the global scope, which replaces ANCHOR_WEBHOOK_SECRET once rotated; a
+-- partner's own keys replace the global ones for that partner. A key is valid
+-- in [active_from, expires_at), so a rotation can overlap the old and new key.
+--
+-- sealed_secret is encrypted with SIGNING_KEY_ENCRYPTION_KEY. It is NULL for
+-- the row a scope's first rotation writes for the key it inherited (the global
+-- key, or ANCHOR_WEBHOOK_SECRET), which expires with the overlap.
+
+CREATE TABLE IF NOT EXISTS inbound_signing_keys (
+    id            UUID PRIMARY KEY,
+    tenant_id     UUID REFERENCES tenants(tenant_id) ON DELETE CASCADE,
+    sealed_secret TEXT,
+    -- First 16 hex chars of the secret's SHA-256; NULL with sealed_secret.
+    fingerprint   VARCHAR(16),
+    active_from   TIMESTAMPTZ NOT NULL,
+    expires_at    TIMESTAMPTZ,
+    created_by    VARCHAR(255) NOT NULL,
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Live keys are loaded on every cache refresh.
+CREATE INDEX IF NOT EXISTS idx_inbound_signing_keys_live
+    ON inbound_signing_keys(tenant_id, active_from)
+    WHERE expires_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_inbound_signing_keys_expires
+    ON inbound_signing_keys(expires_at);
+
+COMMENT ON TABLE inbound_signing_keys IS
+    'Global and per-partner keys for inbound callback signatures, with activation windows';
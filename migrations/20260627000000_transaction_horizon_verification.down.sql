@@ -0,0 +1,3 @@
+ALTER TABLE transactions
+    DROP COLUMN IF EXISTS verification_attempts,
+    DROP COLUMN IF EXISTS stellar_tx_hash;
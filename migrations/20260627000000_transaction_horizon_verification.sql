@@ -0,0 +1,16 @@
+-- Completion of a pending transaction is gated on Horizon: the processor looks
+-- up stellar_tx_hash and only marks the row completed once the network reports
+-- that transaction as successful. Rows without a hash stay pending.
+--
+-- verification_attempts counts lookups that did not verify (not found yet,
+-- Horizon error); the processor fails the transaction and moves it to the DLQ
+-- once it reaches HORIZON_VERIFY_MAX_ATTEMPTS.
+
+ALTER TABLE transactions
+    ADD COLUMN IF NOT EXISTS stellar_tx_hash VARCHAR(64),
+    ADD COLUMN IF NOT EXISTS verification_attempts INTEGER NOT NULL DEFAULT 0;
+
+COMMENT ON COLUMN transactions.stellar_tx_hash IS
+    'Hash of the Stellar network transaction settling this deposit, hex-encoded';
+COMMENT ON COLUMN transactions.verification_attempts IS
+    'Horizon lookups that did not verify stellar_tx_hash';
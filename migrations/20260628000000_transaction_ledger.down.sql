@@ -0,0 +1,3 @@
+ALTER TABLE transactions
+    DROP COLUMN IF EXISTS closed_at,
+    DROP COLUMN IF EXISTS ledger;
@@ -0,0 +1,13 @@
+-- Where a completed transaction landed on the Stellar network, so partners can
+-- link to a block explorer. Set alongside stellar_tx_hash when Horizon
+-- verification succeeds; the account monitor and Horizon backfill know the
+-- close time but not the ledger sequence, and leave ledger NULL.
+
+ALTER TABLE transactions
+    ADD COLUMN IF NOT EXISTS ledger BIGINT,
+    ADD COLUMN IF NOT EXISTS closed_at TIMESTAMPTZ;
+
+COMMENT ON COLUMN transactions.ledger IS
+    'Sequence of the ledger that included stellar_tx_hash';
+COMMENT ON COLUMN transactions.closed_at IS
+    'Close time of that ledger';
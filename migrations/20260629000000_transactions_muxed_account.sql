@@ -0,0 +1,11 @@
+-- Muxed (M...) sender addresses are stored split: the base G... account stays
+-- in stellar_account, so lookups and settlements by account are unchanged, and
+-- the 64-bit muxed id goes here. NUMERIC because ids use the full unsigned
+-- 64-bit range, which BIGINT cannot hold.
+
+ALTER TABLE transactions
+    ADD COLUMN IF NOT EXISTS stellar_muxed_id NUMERIC(20, 0)
+        CHECK (stellar_muxed_id >= 0 AND stellar_muxed_id <= 18446744073709551615);
+
+COMMENT ON COLUMN transactions.stellar_muxed_id IS
+    'Muxed account id when the address arrived as M...; NULL for plain G... accounts';
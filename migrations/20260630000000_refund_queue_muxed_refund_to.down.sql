@@ -0,0 +1,4 @@
+-- Fails while any refund still targets an M... address; cancel or complete
+-- those first.
+-- migration-safety: allow ALTER COLUMN .* TYPE
+ALTER TABLE refund_queue ALTER COLUMN refund_to TYPE VARCHAR(56);
@@ -0,0 +1,6 @@
+-- Refunds of payments sent from a muxed account go back to the sender's M...
+-- address (69 characters), so the exchange can credit the right customer.
+-- Widening a VARCHAR only updates the catalog; no table rewrite.
+
+-- migration-safety: allow ALTER COLUMN .* TYPE
+ALTER TABLE refund_queue ALTER COLUMN refund_to TYPE VARCHAR(69);
@@ -0,0 +1,4 @@
+-- migration-safety: allow DROP TABLE
+DROP TABLE IF EXISTS quarantined_payments;
+-- migration-safety: allow DROP TABLE
+DROP TABLE IF EXISTS partner_trusted_assets;
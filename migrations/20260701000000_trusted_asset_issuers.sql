@@ -0,0 +1,46 @@
+-- An asset code alone does not identify a Stellar asset: anyone can issue a
+-- token called USDC. Each partner registers the (code, issuer) pairs it
+-- accepts; payments carrying any other issuer are quarantined instead of
+-- completing a deposit. A partner with no entries is unrestricted.
+
+-- ── 1. Trusted asset registry ───────────────────────────────────────────────
+
+CREATE TABLE IF NOT EXISTS partner_trusted_assets (
+    tenant_id    UUID NOT NULL REFERENCES tenants(tenant_id) ON DELETE CASCADE,
+    asset_code   VARCHAR(12) NOT NULL,
+    asset_issuer VARCHAR(56) NOT NULL,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (tenant_id, asset_code, asset_issuer),
+    CONSTRAINT chk_partner_trusted_assets_code
+        CHECK (asset_code = UPPER(asset_code))
+);
+
+COMMENT ON TABLE partner_trusted_assets IS
+    'Asset issuers each partner accepts deposits from';
+
+-- ── 2. Quarantine ───────────────────────────────────────────────────────────
+
+CREATE TABLE IF NOT EXISTS quarantined_payments (
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- Horizon payment operation id; quarantined once.
+    payment_id      TEXT NOT NULL UNIQUE,
+    source          VARCHAR(32) NOT NULL,
+    tenant_id       UUID,
+    -- Deposit the payment was presented for, when found by the processor.
+    transaction_id  UUID,
+    account         VARCHAR(56) NOT NULL,
+    from_account    VARCHAR(69),
+    asset_code      VARCHAR(12) NOT NULL,
+    asset_issuer    VARCHAR(56),
+    amount          NUMERIC NOT NULL,
+    memo            TEXT,
+    stellar_tx_hash VARCHAR(64),
+    reason          TEXT NOT NULL,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_quarantined_payments_created
+    ON quarantined_payments(created_at);
+
+COMMENT ON TABLE quarantined_payments IS
+    'Inbound payments held back because their asset issuer is not trusted';
@@ -0,0 +1,4 @@
+-- migration-safety: allow DROP TABLE
+DROP TABLE IF EXISTS structuring_reviews;
+-- migration-safety: allow DROP TABLE
+DROP TABLE IF EXISTS structuring_rules;
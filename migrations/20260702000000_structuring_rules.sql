@@ -0,0 +1,64 @@
+-- Structuring detection: many small deposits from one account inside a window
+-- to stay under reporting thresholds. Compliance defines rules (count and/or
+-- sum thresholds per account per window); the hourly structuring job opens a
+-- review item for each account a rule flags.
+
+-- ── 1. Rules ────────────────────────────────────────────────────────────────
+
+CREATE TABLE IF NOT EXISTS structuring_rules (
+    id                UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name              VARCHAR(100) NOT NULL UNIQUE,
+    -- NULL applies the rule to every asset, summed together.
+    asset_code        VARCHAR(12),
+    window_hours      INTEGER NOT NULL DEFAULT 24,
+    -- An account is flagged once every configured threshold is reached.
+    min_count         INTEGER,
+    min_total         NUMERIC,
+    -- Only deposits at or below this amount count; NULL counts all.
+    max_single_amount NUMERIC,
+    enabled           BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at        TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at        TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_structuring_rules_window
+        CHECK (window_hours BETWEEN 1 AND 720),
+    CONSTRAINT chk_structuring_rules_threshold
+        CHECK (min_count IS NOT NULL OR min_total IS NOT NULL),
+    CONSTRAINT chk_structuring_rules_amounts
+        CHECK ((min_count IS NULL OR min_count > 0)
+           AND (min_total IS NULL OR min_total > 0)
+           AND (max_single_amount IS NULL OR max_single_amount > 0))
+);
+
+COMMENT ON TABLE structuring_rules IS
+    'Per-account deposit count/sum thresholds flagging possible structuring';
+
+-- ── 2. Review items ─────────────────────────────────────────────────────────
+
+CREATE TABLE IF NOT EXISTS structuring_reviews (
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    rule_id         UUID NOT NULL REFERENCES structuring_rules(id) ON DELETE CASCADE,
+    stellar_account VARCHAR(56) NOT NULL,
+    window_start    TIMESTAMPTZ NOT NULL,
+    window_end      TIMESTAMPTZ NOT NULL,
+    tx_count        BIGINT NOT NULL,
+    total_amount    NUMERIC NOT NULL,
+    transaction_ids UUID[] NOT NULL,
+    status          VARCHAR(20) NOT NULL DEFAULT 'open',
+    note            TEXT,
+    resolved_by     VARCHAR(255),
+    resolved_at     TIMESTAMPTZ,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_structuring_reviews_status
+        CHECK (status IN ('open', 'dismissed', 'escalated'))
+);
+
+-- One open item per rule and account; later runs refresh it.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_structuring_reviews_open
+    ON structuring_reviews(rule_id, stellar_account) WHERE status = 'open';
+
+CREATE INDEX IF NOT EXISTS idx_structuring_reviews_status_created
+    ON structuring_reviews(status, created_at);
+
+COMMENT ON TABLE structuring_reviews IS
+    'Accounts flagged by a structuring rule, awaiting compliance review';
@@ -0,0 +1,4 @@
+-- migration-safety: allow DROP COLUMN
+ALTER TABLE transactions
+    DROP COLUMN IF EXISTS risk_reasons,
+    DROP COLUMN IF EXISTS risk_score;
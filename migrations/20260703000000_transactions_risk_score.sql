@@ -0,0 +1,12 @@
+-- Risk score assigned by the configured risk scorer when the processor first
+-- picks a deposit up (0 = no risk, 100 = highest), with the reasons behind it.
+-- Deposits scoring at or above RISK_REVIEW_THRESHOLD are held in
+-- compliance_review; approved ones keep their score and are not re-scored.
+
+ALTER TABLE transactions
+    ADD COLUMN IF NOT EXISTS risk_score SMALLINT
+        CHECK (risk_score >= 0 AND risk_score <= 100),
+    ADD COLUMN IF NOT EXISTS risk_reasons TEXT[];
+
+COMMENT ON COLUMN transactions.risk_score IS
+    'Risk score 0-100 from the risk scorer; NULL until the processor has scored the deposit';
@@ -0,0 +1,11 @@
+DROP TRIGGER IF EXISTS trg_structuring_reviews_review_queue ON structuring_reviews;
+DROP TRIGGER IF EXISTS trg_transactions_review_queue ON transactions;
+DROP FUNCTION IF EXISTS structuring_reviews_maintain_review_queue();
+DROP FUNCTION IF EXISTS transactions_maintain_review_queue();
+DROP FUNCTION IF EXISTS review_sla_due(VARCHAR);
+-- migration-safety: allow DROP TABLE
+DROP TABLE IF EXISTS review_comments;
+-- migration-safety: allow DROP TABLE
+DROP TABLE IF EXISTS review_items;
+-- migration-safety: allow DROP TABLE
+DROP TABLE IF EXISTS review_sla_policies;
@@ -0,0 +1,158 @@
+-- Compliance review queue: one item per held transaction (status
+-- compliance_review) and per structuring review, with reviewer assignment,
+-- an SLA due time and a comment thread. Triggers keep the queue in step with
+-- its subjects, whichever code path holds or releases them.
+
+-- ── 1. SLA policies ─────────────────────────────────────────────────────────
+
+CREATE TABLE IF NOT EXISTS review_sla_policies (
+    kind       VARCHAR(20) PRIMARY KEY,
+    sla_hours  INTEGER NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_review_sla_policies_kind CHECK (kind IN ('transaction', 'structuring')),
+    CONSTRAINT chk_review_sla_policies_hours CHECK (sla_hours BETWEEN 1 AND 720)
+);
+
+INSERT INTO review_sla_policies (kind, sla_hours) VALUES
+    ('transaction', 24),
+    ('structuring', 72)
+ON CONFLICT (kind) DO NOTHING;
+
+COMMENT ON TABLE review_sla_policies IS
+    'Hours a review item of each kind may stay unresolved before it is overdue';
+
+-- ── 2. Review items ─────────────────────────────────────────────────────────
+
+CREATE TABLE IF NOT EXISTS review_items (
+    id                    UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    kind                  VARCHAR(20) NOT NULL,
+    -- transactions is partitioned, so this cannot be a foreign key.
+    transaction_id        UUID,
+    structuring_review_id UUID REFERENCES structuring_reviews(id) ON DELETE CASCADE,
+    state                 VARCHAR(20) NOT NULL DEFAULT 'open',
+    assignee              VARCHAR(255),
+    assigned_at           TIMESTAMPTZ,
+    sla_due_at            TIMESTAMPTZ NOT NULL,
+    -- approved / rejected for transactions, dismissed / escalated for
+    -- structuring reviews.
+    resolution            VARCHAR(20),
+    resolved_by           VARCHAR(255),
+    resolved_at           TIMESTAMPTZ,
+    created_at            TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at            TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_review_items_state CHECK (state IN ('open', 'assigned', 'resolved')),
+    CONSTRAINT chk_review_items_subject CHECK (
+        (kind = 'transaction' AND transaction_id IS NOT NULL AND structuring_review_id IS NULL)
+        OR (kind = 'structuring' AND structuring_review_id IS NOT NULL AND transaction_id IS NULL)
+    ),
+    CONSTRAINT chk_review_items_assignee CHECK (state <> 'assigned' OR assignee IS NOT NULL),
+    CONSTRAINT chk_review_items_resolution CHECK ((state = 'resolved') = (resolution IS NOT NULL))
+);
+
+-- A transaction held again after approval gets a new item.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_review_items_transaction_unresolved
+    ON review_items(transaction_id) WHERE state <> 'resolved';
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_review_items_structuring
+    ON review_items(structuring_review_id);
+
+CREATE INDEX IF NOT EXISTS idx_review_items_state_due
+    ON review_items(state, sla_due_at);
+
+COMMENT ON TABLE review_items IS
+    'Compliance review queue: held transactions and structuring reviews';
+
+-- ── 3. Comments ─────────────────────────────────────────────────────────────
+
+CREATE TABLE IF NOT EXISTS review_comments (
+    id             UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    review_item_id UUID NOT NULL REFERENCES review_items(id) ON DELETE CASCADE,
+    author         VARCHAR(255) NOT NULL,
+    body           TEXT NOT NULL,
+    created_at     TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_review_comments_body CHECK (length(body) BETWEEN 1 AND 4000)
+);
+
+CREATE INDEX IF NOT EXISTS idx_review_comments_item
+    ON review_comments(review_item_id, created_at);
+
+-- ── 4. Queue maintenance ────────────────────────────────────────────────────
+
+CREATE OR REPLACE FUNCTION review_sla_due(p_kind VARCHAR)
+RETURNS TIMESTAMPTZ AS $$
+    SELECT NOW() + make_interval(hours => COALESCE(
+        (SELECT sla_hours FROM review_sla_policies WHERE kind = p_kind), 24
+    ));
+$$ LANGUAGE sql STABLE;
+
+-- Items resolved through the review API are already resolved when their
+-- subject changes; only changes made elsewhere (bulk status updates, the
+-- structuring endpoints) resolve them here.
+CREATE OR REPLACE FUNCTION transactions_maintain_review_queue()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.status = 'compliance_review'
+       AND (TG_OP = 'INSERT' OR OLD.status <> 'compliance_review') THEN
+        INSERT INTO review_items (kind, transaction_id, sla_due_at)
+        VALUES ('transaction', NEW.id, review_sla_due('transaction'))
+        ON CONFLICT (transaction_id) WHERE state <> 'resolved' DO NOTHING;
+    ELSIF TG_OP = 'UPDATE' AND OLD.status = 'compliance_review'
+          AND NEW.status <> 'compliance_review' THEN
+        UPDATE review_items SET
+            state       = 'resolved',
+            resolution  = CASE NEW.status WHEN 'pending' THEN 'approved'
+                                          WHEN 'failed' THEN 'rejected'
+                                          ELSE NEW.status::text END,
+            resolved_by = 'system',
+            resolved_at = NOW(),
+            updated_at  = NOW()
+        WHERE transaction_id = NEW.id AND state <> 'resolved';
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_transactions_review_queue ON transactions;
+CREATE TRIGGER trg_transactions_review_queue
+    AFTER INSERT OR UPDATE OF status ON transactions
+    FOR EACH ROW EXECUTE FUNCTION transactions_maintain_review_queue();
+
+CREATE OR REPLACE FUNCTION structuring_reviews_maintain_review_queue()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO review_items (kind, structuring_review_id, sla_due_at)
+        VALUES ('structuring', NEW.id, review_sla_due('structuring'))
+        ON CONFLICT (structuring_review_id) DO NOTHING;
+    ELSIF OLD.status = 'open' AND NEW.status <> 'open' THEN
+        UPDATE review_items SET
+            state       = 'resolved',
+            resolution  = NEW.status,
+            resolved_by = COALESCE(NEW.resolved_by, 'system'),
+            resolved_at = NOW(),
+            updated_at  = NOW()
+        WHERE structuring_review_id = NEW.id AND state <> 'resolved';
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_structuring_reviews_review_queue ON structuring_reviews;
+CREATE TRIGGER trg_structuring_reviews_review_queue
+    AFTER INSERT OR UPDATE OF status ON structuring_reviews
+    FOR EACH ROW EXECUTE FUNCTION structuring_reviews_maintain_review_queue();
+
+-- ── 5. Backfill ─────────────────────────────────────────────────────────────
+
+-- Items already waiting had no SLA; theirs starts now.
+INSERT INTO review_items (kind, transaction_id, sla_due_at, created_at)
+SELECT 'transaction', id, review_sla_due('transaction'), updated_at
+FROM transactions
+WHERE status = 'compliance_review'
+ON CONFLICT (transaction_id) WHERE state <> 'resolved' DO NOTHING;
+
+INSERT INTO review_items (kind, structuring_review_id, sla_due_at, created_at)
+SELECT 'structuring', id, review_sla_due('structuring'), created_at
+FROM structuring_reviews
+WHERE status = 'open'
+ON CONFLICT (structuring_review_id) DO NOTHING;
@@ -0,0 +1,35 @@
+-- Four-eyes approvals: admin status overrides and refund overrides above
+-- FOUR_EYES_THRESHOLD are stored here as proposals and only applied once a
+-- second principal approves them.
+
+CREATE TABLE IF NOT EXISTS approvals (
+    id            UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    kind          VARCHAR(32) NOT NULL,
+    -- The proposed action, replayed as-is on approval.
+    payload       JSONB NOT NULL,
+    -- Largest amount the action touches; what tripped the threshold.
+    amount        NUMERIC NOT NULL,
+    status        VARCHAR(20) NOT NULL DEFAULT 'pending',
+    proposed_by   VARCHAR(255) NOT NULL,
+    decided_by    VARCHAR(255),
+    decision_note TEXT,
+    decided_at    TIMESTAMPTZ,
+    -- Outcome of the applied action (approved only).
+    result        JSONB,
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at    TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_approvals_kind CHECK (kind IN ('status_override', 'refund_override')),
+    CONSTRAINT chk_approvals_status CHECK (status IN ('pending', 'approved', 'rejected')),
+    CONSTRAINT chk_approvals_decision CHECK (
+        (status = 'pending') = (decided_by IS NULL AND decided_at IS NULL)
+    ),
+    CONSTRAINT chk_approvals_four_eyes CHECK (
+        decided_by IS NULL OR lower(decided_by) <> lower(proposed_by)
+    )
+);
+
+CREATE INDEX IF NOT EXISTS idx_approvals_status_created
+    ON approvals(status, created_at);
+
+COMMENT ON TABLE approvals IS
+    'High-value admin actions awaiting, or decided by, a second approver';
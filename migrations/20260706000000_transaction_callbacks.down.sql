@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS idx_webhook_endpoints_transaction;
+-- Callback rows are meaningless without the column.
+DELETE FROM webhook_endpoints WHERE transaction_id IS NOT NULL;
+-- migration-safety: allow DROP COLUMN
+ALTER TABLE webhook_endpoints DROP COLUMN IF EXISTS transaction_id;
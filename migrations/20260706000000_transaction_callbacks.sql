@@ -0,0 +1,14 @@
+-- Per-transaction callbacks registered by wallets through the GraphQL
+-- registerTransactionCallback mutation. A callback is a webhook endpoint
+-- bound to one transaction: it receives every event of that transaction
+-- (event_types stays empty) and nothing else. Registering again replaces the
+-- URL and secret.
+
+ALTER TABLE webhook_endpoints
+    ADD COLUMN IF NOT EXISTS transaction_id UUID;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_webhook_endpoints_transaction
+    ON webhook_endpoints(transaction_id) WHERE transaction_id IS NOT NULL;
+
+COMMENT ON COLUMN webhook_endpoints.transaction_id IS
+    'Set for per-transaction callbacks; NULL for partner subscriptions';
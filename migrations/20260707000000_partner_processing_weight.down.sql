@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_transactions_pending_by_tenant;
+-- migration-safety: allow DROP COLUMN
+ALTER TABLE tenants DROP COLUMN IF EXISTS processing_weight;
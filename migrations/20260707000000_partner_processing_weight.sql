@@ -0,0 +1,13 @@
+-- Per-partner share of processor throughput. process_batch serves pending
+-- transactions round-robin by partner, taking up to processing_weight rows
+-- from each partner per round, so one partner's backlog cannot starve the
+-- others. Transactions without a partner are served as a weight-1 partner.
+
+ALTER TABLE tenants
+    ADD COLUMN IF NOT EXISTS processing_weight INTEGER NOT NULL DEFAULT 1
+        CHECK (processing_weight BETWEEN 1 AND 100);
+
+-- Candidate scan for process_batch's per-partner ranking.
+CREATE INDEX IF NOT EXISTS idx_transactions_pending_by_tenant
+    ON transactions(tenant_id, created_at)
+    WHERE status = 'pending' AND stellar_tx_hash IS NOT NULL;
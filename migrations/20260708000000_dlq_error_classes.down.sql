@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS idx_transaction_dlq_class_moved_at;
+-- migration-safety: allow DROP COLUMN
+ALTER TABLE transaction_dlq DROP COLUMN IF EXISTS error_signature;
+-- migration-safety: allow DROP COLUMN
+ALTER TABLE transaction_dlq DROP COLUMN IF EXISTS error_class;
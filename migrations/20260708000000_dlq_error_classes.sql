@@ -0,0 +1,23 @@
+-- Error taxonomy for DLQ entries (see services::dlq_errors). New rows are
+-- classified by the application; existing rows are backfilled here with the
+-- same fragment rules, in the same order. error_signature stays NULL for them.
+
+ALTER TABLE transaction_dlq
+    ADD COLUMN IF NOT EXISTS error_class VARCHAR(32) NOT NULL DEFAULT 'unknown'
+        CHECK (error_class IN ('network', 'horizon_rejection', 'validation', 'db', 'unknown')),
+    ADD COLUMN IF NOT EXISTS error_signature VARCHAR(16);
+
+UPDATE transaction_dlq SET error_class = CASE
+    WHEN error_reason ~* '(database|sqlx|pool timed out|deadlock|constraint|trusted asset lookup failed)'
+        THEN 'db'
+    WHEN error_reason ~* '(lookup failed|horizon unavailable|timed out|timeout|connection|network|dns|rate limit|too many requests|service unavailable|bad gateway)'
+        THEN 'network'
+    WHEN error_reason ~* '(failed on-chain|memo mismatch|not found on horizon)'
+        THEN 'horizon_rejection'
+    WHEN error_reason ~* '(issuer|invalid|validation|not allowed|not authorized|transition)'
+        THEN 'validation'
+    ELSE 'unknown'
+END;
+
+CREATE INDEX IF NOT EXISTS idx_transaction_dlq_class_moved_at
+    ON transaction_dlq(error_class, moved_to_dlq_at);
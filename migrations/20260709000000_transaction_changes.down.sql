@@ -0,0 +1,6 @@
+DROP TRIGGER IF EXISTS trg_transactions_record_tombstone ON transactions;
+DROP FUNCTION IF EXISTS transactions_record_tombstone();
+DROP TABLE IF EXISTS transaction_tombstones;
+DROP INDEX IF EXISTS idx_transactions_updated_at_id;
+DROP TRIGGER IF EXISTS trg_transactions_touch_updated_at ON transactions;
+DROP FUNCTION IF EXISTS transactions_touch_updated_at();
@@ -0,0 +1,49 @@
+-- Incremental sync for GET /transactions/changes. Consumers page through
+-- (updated_at, id), so every update must move updated_at forward, and rows
+-- that leave the table (deleted, or detached into the archive schema with
+-- their partition) leave a tombstone for consumers to drop their copy.
+
+-- ── 1. updated_at on every update ──────────────────────────────────────────
+
+CREATE OR REPLACE FUNCTION transactions_touch_updated_at()
+RETURNS TRIGGER AS $$
+BEGIN
+    NEW.updated_at := NOW();
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_transactions_touch_updated_at ON transactions;
+CREATE TRIGGER trg_transactions_touch_updated_at
+    BEFORE UPDATE ON transactions
+    FOR EACH ROW EXECUTE FUNCTION transactions_touch_updated_at();
+
+CREATE INDEX IF NOT EXISTS idx_transactions_updated_at_id
+    ON transactions(updated_at, id);
+
+-- ── 2. Tombstones ───────────────────────────────────────────────────────────
+
+CREATE TABLE IF NOT EXISTS transaction_tombstones (
+    transaction_id UUID NOT NULL,
+    tenant_id UUID,
+    reason VARCHAR(16) NOT NULL CHECK (reason IN ('deleted', 'archived')),
+    removed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (transaction_id, removed_at)
+);
+
+CREATE INDEX IF NOT EXISTS idx_transaction_tombstones_removed_at
+    ON transaction_tombstones(removed_at, transaction_id);
+
+CREATE OR REPLACE FUNCTION transactions_record_tombstone()
+RETURNS TRIGGER AS $$
+BEGIN
+    INSERT INTO transaction_tombstones (transaction_id, tenant_id, reason)
+    VALUES (OLD.id, OLD.tenant_id, 'deleted');
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_transactions_record_tombstone ON transactions;
+CREATE TRIGGER trg_transactions_record_tombstone
+    AFTER DELETE ON transactions
+    FOR EACH ROW EXECUTE FUNCTION transactions_record_tombstone();
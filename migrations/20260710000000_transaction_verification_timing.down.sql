@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS idx_transactions_verified_at;
+-- migration-safety: allow DROP COLUMN
+ALTER TABLE transactions
+    DROP COLUMN IF EXISTS verified_at,
+    DROP COLUMN IF EXISTS first_verification_at;
@@ -0,0 +1,16 @@
+-- When the processor first looked a transaction up on Horizon, and when the
+-- lookup finally verified it. Together with verification_attempts (lookups
+-- that did not verify) they give the retry distribution and time-to-success
+-- served by GET /stats/processor.
+
+ALTER TABLE transactions
+    ADD COLUMN IF NOT EXISTS first_verification_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS verified_at TIMESTAMPTZ;
+
+COMMENT ON COLUMN transactions.first_verification_at IS
+    'First Horizon lookup of stellar_tx_hash that counted as an attempt';
+COMMENT ON COLUMN transactions.verified_at IS
+    'When Horizon verification completed the transaction';
+
+CREATE INDEX IF NOT EXISTS idx_transactions_verified_at
+    ON transactions(verified_at) WHERE verified_at IS NOT NULL;
@@ -0,0 +1,6 @@
+-- migration-safety: allow DROP TABLE
+DROP TABLE IF EXISTS settlement_reversals;
+
+ALTER TABLE approvals DROP CONSTRAINT IF EXISTS chk_approvals_kind;
+ALTER TABLE approvals ADD CONSTRAINT chk_approvals_kind
+    CHECK (kind IN ('status_override', 'refund_override'));
@@ -0,0 +1,26 @@
+-- Voiding a settlement after the fact (POST /admin/settlements/:id/void).
+-- The settlement row is kept, marked voided; the reversal is recorded here as
+-- a compensating entry for the negated total, with the transactions it
+-- released back to unsettled. Voids always go through four-eyes approval.
+
+ALTER TABLE approvals DROP CONSTRAINT IF EXISTS chk_approvals_kind;
+ALTER TABLE approvals ADD CONSTRAINT chk_approvals_kind
+    CHECK (kind IN ('status_override', 'refund_override', 'settlement_void'));
+
+CREATE TABLE IF NOT EXISTS settlement_reversals (
+    id                       UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    settlement_id            UUID NOT NULL UNIQUE REFERENCES settlements(id),
+    asset_code               VARCHAR(12) NOT NULL,
+    -- Negated settlement total, so reversal and settlement sum to zero.
+    amount                   NUMERIC NOT NULL,
+    tx_count                 INTEGER NOT NULL,
+    released_transaction_ids UUID[] NOT NULL DEFAULT '{}',
+    previous_status          VARCHAR(20) NOT NULL,
+    reason                   TEXT NOT NULL,
+    proposed_by              VARCHAR(255) NOT NULL,
+    approved_by              VARCHAR(255) NOT NULL,
+    created_at               TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE settlement_reversals IS
+    'Compensating entries for voided settlements';
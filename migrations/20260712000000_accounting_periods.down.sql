@@ -0,0 +1,7 @@
+DROP TRIGGER IF EXISTS trg_settlements_period_lock ON settlements;
+DROP TRIGGER IF EXISTS trg_transactions_period_lock ON transactions;
+DROP FUNCTION IF EXISTS reject_closed_period_write();
+-- migration-safety: allow DROP TABLE
+DROP TABLE IF EXISTS accounting_adjustments;
+-- migration-safety: allow DROP TABLE
+DROP TABLE IF EXISTS accounting_periods;
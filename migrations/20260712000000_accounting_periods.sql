@@ -0,0 +1,75 @@
+-- Monthly accounting periods. A row exists only for a closed period, and a
+-- closed period is never reopened. Transactions and settlements created in
+-- a closed period can no longer be inserted, updated or deleted: the guard
+-- trigger below raises a check violation named chk_accounting_period_open,
+-- which the application maps to a dedicated "period closed" error.
+-- Corrections go into accounting_adjustments instead: each names the closed
+-- period it corrects, and its created_at places it in the open period it was
+-- posted in.
+
+CREATE TABLE IF NOT EXISTS accounting_periods (
+    id           UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- First day of the month, UTC.
+    period_start DATE NOT NULL UNIQUE,
+    starts_at    TIMESTAMPTZ NOT NULL,
+    ends_at      TIMESTAMPTZ NOT NULL,
+    closed_by    VARCHAR(255) NOT NULL,
+    closed_at    TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_accounting_periods_month CHECK (
+        EXTRACT(DAY FROM period_start) = 1 AND starts_at < ends_at
+    )
+);
+
+CREATE INDEX IF NOT EXISTS idx_accounting_periods_range
+    ON accounting_periods(starts_at, ends_at);
+
+CREATE TABLE IF NOT EXISTS accounting_adjustments (
+    id           UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    period_id    UUID NOT NULL REFERENCES accounting_periods(id),
+    entity_type  VARCHAR(20) NOT NULL CHECK (entity_type IN ('transaction', 'settlement')),
+    entity_id    UUID NOT NULL,
+    asset_code   VARCHAR(12) NOT NULL,
+    -- Signed correction to the entity's amount.
+    amount       NUMERIC NOT NULL CHECK (amount <> 0),
+    reason       TEXT NOT NULL,
+    created_by   VARCHAR(255) NOT NULL,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_accounting_adjustments_period
+    ON accounting_adjustments(period_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_accounting_adjustments_entity
+    ON accounting_adjustments(entity_type, entity_id);
+
+CREATE OR REPLACE FUNCTION reject_closed_period_write()
+RETURNS TRIGGER AS $$
+DECLARE
+    closed DATE;
+BEGIN
+    SELECT period_start INTO closed FROM accounting_periods
+    WHERE (TG_OP <> 'INSERT' AND OLD.created_at >= starts_at AND OLD.created_at < ends_at)
+       OR (TG_OP <> 'DELETE' AND NEW.created_at >= starts_at AND NEW.created_at < ends_at)
+    LIMIT 1;
+    IF FOUND THEN
+        RAISE EXCEPTION 'accounting period % is closed', to_char(closed, 'YYYY-MM')
+            USING ERRCODE = 'check_violation',
+                  CONSTRAINT = 'chk_accounting_period_open',
+                  DETAIL = format('%s %s', TG_TABLE_NAME,
+                                  CASE WHEN TG_OP = 'INSERT' THEN NEW.id ELSE OLD.id END);
+    END IF;
+    IF TG_OP = 'DELETE' THEN
+        RETURN OLD;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_transactions_period_lock ON transactions;
+CREATE TRIGGER trg_transactions_period_lock
+    BEFORE INSERT OR UPDATE OR DELETE ON transactions
+    FOR EACH ROW EXECUTE FUNCTION reject_closed_period_write();
+
+DROP TRIGGER IF EXISTS trg_settlements_period_lock ON settlements;
+CREATE TRIGGER trg_settlements_period_lock
+    BEFORE INSERT OR UPDATE OR DELETE ON settlements
+    FOR EACH ROW EXECUTE FUNCTION reject_closed_period_write();
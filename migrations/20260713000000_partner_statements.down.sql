@@ -0,0 +1,2 @@
+-- migration-safety: allow DROP TABLE
+DROP TABLE IF EXISTS partner_statements;
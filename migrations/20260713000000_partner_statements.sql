@@ -0,0 +1,24 @@
+-- Monthly partner statements, generated by the partner_statement job. One row
+-- per partner and month; regenerating a statement replaces its totals and
+-- files but keeps the id. The CSV and PDF live in the object store under
+-- statements/<tenant_id>/<YYYY-MM>.{csv,pdf}.
+
+CREATE TABLE IF NOT EXISTS partner_statements (
+    id           UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id    UUID NOT NULL REFERENCES tenants(tenant_id),
+    -- First day of the month, UTC.
+    period_start DATE NOT NULL,
+    -- Job that last generated the statement.
+    job_id       UUID NOT NULL,
+    -- Per-asset totals, as written to the files.
+    lines        JSONB NOT NULL DEFAULT '[]',
+    generated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT uq_partner_statements_period UNIQUE (tenant_id, period_start),
+    CONSTRAINT chk_partner_statements_month CHECK (EXTRACT(DAY FROM period_start) = 1)
+);
+
+CREATE INDEX IF NOT EXISTS idx_partner_statements_period
+    ON partner_statements(period_start);
+
+COMMENT ON TABLE partner_statements IS
+    'Monthly per-partner statements; files are kept in the object store';
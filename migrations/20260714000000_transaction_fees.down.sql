@@ -0,0 +1,3 @@
+-- migration-safety: allow DROP COLUMN
+ALTER TABLE transactions
+    DROP COLUMN IF EXISTS fee_amount;
@@ -0,0 +1,10 @@
+-- Fee the anchor charged on a transaction, in the transaction's asset, as
+-- reported by the partner in the callback's `amount_fee`. NULL when none was
+-- reported. Summed by GET /admin/stats/fees and by partner statements.
+
+ALTER TABLE transactions
+    ADD COLUMN IF NOT EXISTS fee_amount NUMERIC
+        CHECK (fee_amount >= 0 AND fee_amount <= amount);
+
+COMMENT ON COLUMN transactions.fee_amount IS
+    'Fee charged on the transaction in its asset; NULL when the partner reported none';
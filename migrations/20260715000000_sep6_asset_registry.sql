@@ -0,0 +1,23 @@
+-- Assets offered over SEP-6 and how: which directions are open, the fee
+-- charged, the instructions shown to depositors and the withdrawal types.
+-- Amount limits and whether the asset is accepted at all still come from
+-- `assets`; an asset missing from either table is not offered.
+
+CREATE TABLE IF NOT EXISTS sep6_assets (
+    asset_code           VARCHAR(12) PRIMARY KEY,
+    deposit_enabled      BOOLEAN NOT NULL DEFAULT TRUE,
+    withdraw_enabled     BOOLEAN NOT NULL DEFAULT TRUE,
+    fee_fixed            NUMERIC NOT NULL DEFAULT 0 CHECK (fee_fixed >= 0),
+    fee_percent          NUMERIC NOT NULL DEFAULT 0
+        CHECK (fee_percent >= 0 AND fee_percent <= 100),
+    -- Off-chain instructions returned as `how` by GET /sep6/deposit.
+    deposit_instructions TEXT NOT NULL DEFAULT '',
+    -- Accepted `type` values of GET /sep6/withdraw, e.g. {bank_account}.
+    withdraw_types       TEXT[] NOT NULL DEFAULT '{}',
+    created_at           TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at           TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_sep6_assets_code CHECK (asset_code = UPPER(asset_code))
+);
+
+COMMENT ON TABLE sep6_assets IS
+    'Assets offered over SEP-6 deposit and withdraw, with their fees';
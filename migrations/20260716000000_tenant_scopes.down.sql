@@ -0,0 +1,3 @@
+-- migration-safety: allow DROP COLUMN
+ALTER TABLE tenants
+    DROP COLUMN IF EXISTS scopes;
@@ -0,0 +1,9 @@
+-- Scopes granted to a partner's API key. `read:pii` lets REST responses
+-- carry the fields in PII_REDACTED_FIELDS unmasked (see
+-- middleware::redaction). Existing keys get no scopes.
+
+ALTER TABLE tenants
+    ADD COLUMN IF NOT EXISTS scopes TEXT[] NOT NULL DEFAULT '{}';
+
+COMMENT ON COLUMN tenants.scopes IS
+    'API key scopes, e.g. read:pii to see unmasked PII in responses';
@@ -0,0 +1,25 @@
+-- SEP-12 customers: the KYC record of a Stellar account (plus a memo when
+-- several customers share one account) for one customer type. `fields`
+-- holds the SEP-9 fields the customer provided; `status` is the SEP-12
+-- status, set to NEEDS_INFO or PROCESSING on every update and to ACCEPTED
+-- or REJECTED by compliance. Deposits can be gated on ACCEPTED.
+
+CREATE TABLE IF NOT EXISTS customers (
+    id            UUID PRIMARY KEY,
+    account       VARCHAR(56) NOT NULL,
+    -- '' when the account is not shared.
+    memo          TEXT NOT NULL DEFAULT '',
+    customer_type VARCHAR(64) NOT NULL DEFAULT 'default',
+    fields        JSONB NOT NULL DEFAULT '{}'::jsonb,
+    status        VARCHAR(16) NOT NULL DEFAULT 'NEEDS_INFO',
+    message       TEXT,
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at    TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT uq_customers_key UNIQUE (account, memo, customer_type),
+    CONSTRAINT chk_customers_status
+        CHECK (status IN ('NEEDS_INFO', 'PROCESSING', 'ACCEPTED', 'REJECTED')),
+    CONSTRAINT chk_customers_fields CHECK (jsonb_typeof(fields) = 'object')
+);
+
+COMMENT ON TABLE customers IS
+    'SEP-12 KYC records per Stellar account, memo and customer type';
@@ -0,0 +1,5 @@
+-- migration-safety: allow DROP TABLE/COLUMN
+ALTER TABLE settlements
+    DROP COLUMN IF EXISTS quoted_payouts;
+DROP TABLE IF EXISTS sep38_quotes;
+DROP TABLE IF EXISTS sep38_prices;
@@ -0,0 +1,42 @@
+-- SEP-38 quotes. `sep38_prices` is the rate card: what one unit of
+-- `buy_asset` costs in `sell_asset`, before the fee. Assets use the SEP-38
+-- identifiers `stellar:CODE[:ISSUER]` and `iso4217:CCY`.
+
+CREATE TABLE IF NOT EXISTS sep38_prices (
+    sell_asset  TEXT NOT NULL,
+    buy_asset   TEXT NOT NULL,
+    price       NUMERIC NOT NULL CHECK (price > 0),
+    -- Charged in `sell_asset`, as a percentage of the sell amount.
+    fee_percent NUMERIC NOT NULL DEFAULT 0
+        CHECK (fee_percent >= 0 AND fee_percent < 100),
+    updated_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (sell_asset, buy_asset),
+    CONSTRAINT chk_sep38_prices_assets CHECK (sell_asset <> buy_asset)
+);
+
+-- Firm quotes: the amounts and rate locked in for `account` until
+-- `expires_at`. A quote backs at most one transaction.
+CREATE TABLE IF NOT EXISTS sep38_quotes (
+    id             UUID PRIMARY KEY,
+    account        TEXT NOT NULL,
+    sell_asset     TEXT NOT NULL,
+    sell_amount    NUMERIC NOT NULL CHECK (sell_amount > 0),
+    buy_asset      TEXT NOT NULL,
+    buy_amount     NUMERIC NOT NULL CHECK (buy_amount > 0),
+    price          NUMERIC NOT NULL,
+    total_price    NUMERIC NOT NULL,
+    fee_total      NUMERIC NOT NULL DEFAULT 0,
+    expires_at     TIMESTAMPTZ NOT NULL,
+    transaction_id UUID UNIQUE,
+    created_at     TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_sep38_quotes_account ON sep38_quotes (account);
+
+-- Per buy asset, what the settlement's quoted transactions pay out at
+-- their locked-in rates, e.g. {"iso4217:NGN": "152000.0000000"}.
+ALTER TABLE settlements
+    ADD COLUMN IF NOT EXISTS quoted_payouts JSONB;
+
+COMMENT ON TABLE sep38_prices IS 'SEP-38 rate card: price of buy_asset in sell_asset';
+COMMENT ON TABLE sep38_quotes IS 'SEP-38 firm quotes and the transaction each backs';
@@ -0,0 +1,29 @@
+-- Every transaction the anchor signs, recorded before it is sent so a
+-- restart can tell what may already be on-chain. `transaction_id` is what
+-- the submission pays out (e.g. a settlement); a new attempt is only made
+-- once the previous one is known to have failed or expired, so a payout
+-- lands at most once.
+
+CREATE TABLE IF NOT EXISTS submissions (
+    id             UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    transaction_id UUID NOT NULL,
+    attempt        INTEGER NOT NULL CHECK (attempt > 0),
+    hash           TEXT NOT NULL UNIQUE,
+    -- Base64 signed envelope, resubmitted verbatim while in flight.
+    envelope_xdr   TEXT NOT NULL,
+    status         TEXT NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'succeeded', 'failed', 'expired')),
+    ledger         BIGINT,
+    -- Result code, e.g. `tx_success` or `tx_failed (op_underfunded)`.
+    result         TEXT,
+    -- Upper time bound of the transaction; past it, it can no longer land.
+    expires_at     TIMESTAMPTZ,
+    created_at     TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at     TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (transaction_id, attempt)
+);
+
+CREATE INDEX IF NOT EXISTS idx_submissions_pending
+    ON submissions (created_at) WHERE status = 'pending';
+
+COMMENT ON TABLE submissions IS 'Outbound Stellar submissions, one row per attempt';
@@ -0,0 +1,32 @@
+-- Claimable balances addressed to the anchor's distribution accounts, often
+-- created by senders whose payment could not land directly (e.g. the
+-- account had no trustline yet). The sweep job claims each one and records
+-- the deposit as a normal `transactions` row in `transaction_id`.
+
+CREATE TABLE IF NOT EXISTS claimable_balances (
+    id             UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- Horizon's id: hex `ClaimableBalanceID` XDR.
+    balance_id     TEXT NOT NULL UNIQUE,
+    claimant       TEXT NOT NULL,
+    -- Account that created (and sponsors) the balance: the sender.
+    sponsor        TEXT,
+    asset_code     TEXT NOT NULL,
+    asset_issuer   TEXT,
+    amount         NUMERIC NOT NULL CHECK (amount > 0),
+    -- Horizon's JSON form of the claimant's `ClaimPredicate`.
+    predicate      JSONB NOT NULL,
+    status         TEXT NOT NULL DEFAULT 'detected'
+        CHECK (status IN ('detected', 'claimed', 'gone')),
+    attempts       INTEGER NOT NULL DEFAULT 0,
+    last_error     TEXT,
+    claim_tx_hash  TEXT,
+    transaction_id UUID REFERENCES transactions (id),
+    detected_at    TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    claimed_at     TIMESTAMPTZ,
+    updated_at     TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_claimable_balances_detected
+    ON claimable_balances (detected_at) WHERE status = 'detected';
+
+COMMENT ON TABLE claimable_balances IS 'Claimable balances for distribution accounts and their claims';
@@ -0,0 +1,2 @@
+-- migration-safety: allow DROP TABLE/COLUMN
+DROP TABLE IF EXISTS stellar_transactions;
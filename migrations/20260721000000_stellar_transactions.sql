@@ -0,0 +1,16 @@
+-- Envelope and result XDR of the Stellar transactions the anchor has seen
+-- in a ledger: deposits it verified and transactions it submitted. Kept so
+-- support can inspect what a transaction did without going to Horizon.
+
+CREATE TABLE IF NOT EXISTS stellar_transactions (
+    hash         TEXT PRIMARY KEY,
+    ledger       BIGINT NOT NULL,
+    successful   BOOLEAN NOT NULL,
+    -- Base64 `TransactionEnvelope`.
+    envelope_xdr TEXT NOT NULL,
+    -- Base64 `TransactionResult`.
+    result_xdr   TEXT,
+    recorded_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE stellar_transactions IS 'Envelope and result XDR of Stellar transactions in a ledger';
@@ -0,0 +1,6 @@
+UPDATE transaction_dlq SET error_class = 'unknown' WHERE error_class = 'trustline';
+
+ALTER TABLE transaction_dlq DROP CONSTRAINT IF EXISTS transaction_dlq_error_class_check;
+ALTER TABLE transaction_dlq
+    ADD CONSTRAINT transaction_dlq_error_class_check
+        CHECK (error_class IN ('network', 'horizon_rejection', 'validation', 'db', 'unknown'));
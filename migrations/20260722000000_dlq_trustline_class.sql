@@ -0,0 +1,10 @@
+-- Withdrawals the processor fails because the destination cannot hold the
+-- asset (no trustline, or no room within its limit) get their own DLQ error
+-- class, 'trustline' (see services::dlq_errors).
+
+ALTER TABLE transaction_dlq DROP CONSTRAINT IF EXISTS transaction_dlq_error_class_check;
+ALTER TABLE transaction_dlq
+    ADD CONSTRAINT transaction_dlq_error_class_check
+        CHECK (error_class IN ('network', 'horizon_rejection', 'trustline', 'validation', 'db', 'unknown'))
+        NOT VALID;
+ALTER TABLE transaction_dlq VALIDATE CONSTRAINT transaction_dlq_error_class_check;
@@ -0,0 +1,20 @@
+ALTER TABLE transactions
+    DROP CONSTRAINT IF EXISTS chk_transactions_anchor_id_present;
+ALTER TABLE transactions
+    ADD CONSTRAINT chk_transactions_anchor_id_present
+    CHECK (anchor_transaction_id IS NOT NULL);
+
+CREATE OR REPLACE FUNCTION transactions_default_anchor_id()
+RETURNS trigger AS $$
+BEGIN
+    IF NEW.anchor_transaction_id IS NULL OR btrim(NEW.anchor_transaction_id) = '' THEN
+        NEW.anchor_transaction_id := NEW.id::text;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_transactions_default_anchor_id ON transactions;
+CREATE TRIGGER trg_transactions_default_anchor_id
+    BEFORE INSERT ON transactions
+    FOR EACH ROW EXECUTE FUNCTION transactions_default_anchor_id();
@@ -0,0 +1,21 @@
+-- Stop filling a missing anchor_transaction_id with the row's own id.
+--
+-- The BEFORE INSERT default made chk_transactions_anchor_id_present pass for
+-- callers that never supplied an anchor id, and the made-up ids then took
+-- slots in transaction_active_anchors. Inserts without an anchor id now fail
+-- the check, which surfaces as ConstraintViolation::MissingAnchorId.
+
+DROP TRIGGER IF EXISTS trg_transactions_default_anchor_id ON transactions;
+DROP FUNCTION IF EXISTS transactions_default_anchor_id();
+
+-- A blank anchor id is as missing as NULL; the default used to replace it.
+-- Rows stored before this point keep (or get, once) their id-based value.
+UPDATE transactions
+    SET anchor_transaction_id = id::text
+    WHERE anchor_transaction_id IS NULL OR btrim(anchor_transaction_id) = '';
+
+ALTER TABLE transactions
+    DROP CONSTRAINT IF EXISTS chk_transactions_anchor_id_present;
+ALTER TABLE transactions
+    ADD CONSTRAINT chk_transactions_anchor_id_present
+    CHECK (anchor_transaction_id IS NOT NULL AND btrim(anchor_transaction_id) <> '');
@@ -0,0 +1,33 @@
+-- migration-safety: allow DROP TABLE/COLUMN
+CREATE OR REPLACE FUNCTION transaction_rollup_apply(
+    p_day DATE,
+    p_asset_code VARCHAR,
+    p_status transaction_status,
+    p_count BIGINT,
+    p_volume NUMERIC
+) RETURNS VOID AS $$
+BEGIN
+    INSERT INTO transaction_daily_rollups (day, asset_code, status, tx_count, volume)
+    VALUES (p_day, p_asset_code, p_status, p_count, p_volume)
+    ON CONFLICT (day, asset_code, status) DO UPDATE
+        SET tx_count   = transaction_daily_rollups.tx_count + EXCLUDED.tx_count,
+            volume     = transaction_daily_rollups.volume + EXCLUDED.volume,
+            updated_at = NOW();
+END;
+$$ LANGUAGE plpgsql;
+
+-- Fold what has not been compacted yet before the deltas go away.
+WITH folded AS (
+    DELETE FROM transaction_rollup_deltas
+    RETURNING day, asset_code, status, tx_count, volume
+)
+INSERT INTO transaction_daily_rollups (day, asset_code, status, tx_count, volume)
+SELECT day, asset_code, status, SUM(tx_count), SUM(volume)
+FROM folded
+GROUP BY day, asset_code, status
+ON CONFLICT (day, asset_code, status) DO UPDATE
+    SET tx_count   = transaction_daily_rollups.tx_count + EXCLUDED.tx_count,
+        volume     = transaction_daily_rollups.volume + EXCLUDED.volume,
+        updated_at = NOW();
+
+DROP TABLE IF EXISTS transaction_rollup_deltas;
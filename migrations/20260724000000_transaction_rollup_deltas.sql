@@ -0,0 +1,35 @@
+-- Append-only deltas for transaction_daily_rollups.
+--
+-- The maintenance trigger used to upsert the single (day, asset_code, status)
+-- rollup row on every transaction write, so concurrent writes for one asset
+-- on one day queued on that row's lock. It now appends a delta row instead,
+-- which takes no shared lock; the `rollup_compaction` job folds deltas into
+-- the rollups every minute and readers add the ones not yet folded.
+
+CREATE TABLE IF NOT EXISTS transaction_rollup_deltas (
+    id          BIGSERIAL PRIMARY KEY,
+    day         DATE NOT NULL,
+    asset_code  VARCHAR(12) NOT NULL,
+    status      transaction_status NOT NULL,
+    tx_count    BIGINT NOT NULL,
+    volume      NUMERIC NOT NULL,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_transaction_rollup_deltas_day
+    ON transaction_rollup_deltas (day);
+
+-- Same signature, so transactions_maintain_daily_rollup and its trigger are
+-- unchanged.
+CREATE OR REPLACE FUNCTION transaction_rollup_apply(
+    p_day DATE,
+    p_asset_code VARCHAR,
+    p_status transaction_status,
+    p_count BIGINT,
+    p_volume NUMERIC
+) RETURNS VOID AS $$
+BEGIN
+    INSERT INTO transaction_rollup_deltas (day, asset_code, status, tx_count, volume)
+    VALUES (p_day, p_asset_code, p_status, p_count, p_volume);
+END;
+$$ LANGUAGE plpgsql;
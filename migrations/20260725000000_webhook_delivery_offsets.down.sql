@@ -0,0 +1,9 @@
+-- migration-safety: allow DROP TABLE/COLUMN
+DROP INDEX IF EXISTS idx_webhook_deliveries_endpoint_sequence;
+
+ALTER TABLE webhook_deliveries
+    DROP COLUMN IF EXISTS sequence;
+
+ALTER TABLE webhook_endpoints
+    DROP COLUMN IF EXISTS acked_sequence,
+    DROP COLUMN IF EXISTS last_sequence;
@@ -0,0 +1,38 @@
+-- Per-subscription delivery offsets.
+--
+-- Every delivery gets the next sequence number of its endpoint
+-- (last_sequence); numbers only grow but may skip values. acked_sequence is
+-- the endpoint's offset: each of its deliveries numbered at or below it has
+-- been acknowledged with a 2xx or given up on (moved to the DLQ). The
+-- dispatcher advances it after settling a delivery and, after a restart,
+-- only claims deliveries above it. Deliveries inserted without a sequence
+-- (before this migration was deployed everywhere) are always claimable.
+
+ALTER TABLE webhook_endpoints
+    ADD COLUMN IF NOT EXISTS last_sequence BIGINT NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS acked_sequence BIGINT NOT NULL DEFAULT 0;
+
+ALTER TABLE webhook_deliveries
+    ADD COLUMN IF NOT EXISTS sequence BIGINT;
+
+UPDATE webhook_deliveries d
+SET sequence = numbered.sequence
+FROM (
+    SELECT id,
+           ROW_NUMBER() OVER (PARTITION BY endpoint_id ORDER BY created_at, id) AS sequence
+    FROM webhook_deliveries
+) numbered
+WHERE d.id = numbered.id;
+
+UPDATE webhook_endpoints e
+SET last_sequence = COALESCE(
+        (SELECT MAX(d.sequence) FROM webhook_deliveries d WHERE d.endpoint_id = e.id),
+        0),
+    acked_sequence = COALESCE(
+        (SELECT MIN(d.sequence) - 1 FROM webhook_deliveries d
+         WHERE d.endpoint_id = e.id AND d.status IN ('pending', 'in_progress')),
+        (SELECT MAX(d.sequence) FROM webhook_deliveries d WHERE d.endpoint_id = e.id),
+        0);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_webhook_deliveries_endpoint_sequence
+    ON webhook_deliveries (endpoint_id, sequence);
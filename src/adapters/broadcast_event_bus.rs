@@ -0,0 +1,85 @@
+//! tokio broadcast implementation of EventBus.
+//!
+//! One bounded channel per event class, sized by
+//! [`event_channels::channel`]. A subscriber that falls more than the
+//! capacity behind gets a [`Delivery::Lagged`] in place of the events it
+//! missed, and the loss is counted in `broadcast_messages_lagged_total`.
+
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
+use tokio_stream::StreamExt;
+
+use crate::ports::{Delivery, EventBus, EventFilter, EventStream};
+use crate::services::event_channels::{self, EventClass};
+
+/// In-process event bus over a tokio broadcast channel.
+pub struct BroadcastEventBus<E> {
+    sender: broadcast::Sender<E>,
+    class: EventClass,
+}
+
+impl<E: Clone> BroadcastEventBus<E> {
+    /// Bus for `class`, with the class's configured capacity.
+    pub fn new(class: EventClass) -> Self {
+        Self {
+            sender: event_channels::channel(class),
+            class,
+        }
+    }
+}
+
+impl<E: Clone + Send + 'static> EventBus<E> for BroadcastEventBus<E> {
+    fn publish(&self, event: E) -> usize {
+        // Err only means nobody is subscribed.
+        self.sender.send(event).unwrap_or(0)
+    }
+
+    fn subscribe(&self, filter: EventFilter<E>) -> EventStream<E> {
+        let class = self.class;
+        let stream =
+            BroadcastStream::new(self.sender.subscribe()).filter_map(move |result| match result {
+                Ok(event) => filter(&event).then_some(Delivery::Event(event)),
+                Err(BroadcastStreamRecvError::Lagged(n)) => {
+                    event_channels::record_lag(class, n);
+                    Some(Delivery::Lagged(n))
+                }
+            });
+        Box::pin(stream)
+    }
+
+    fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ports::event_bus;
+
+    #[tokio::test]
+    async fn test_filtered_subscription_and_lag() {
+        let bus = BroadcastEventBus::<u8> {
+            sender: broadcast::channel(2).0,
+            class: EventClass::Admin,
+        };
+
+        assert_eq!(bus.publish(0), 0);
+        let mut evens = bus.subscribe(Box::new(|n: &u8| n % 2 == 0));
+        let mut everything = bus.subscribe(event_bus::all());
+        assert_eq!(bus.subscriber_count(), 2);
+        assert_eq!(bus.publish(1), 2);
+        assert_eq!(bus.publish(2), 2);
+        assert_eq!(evens.next().await, Some(Delivery::Event(2)));
+
+        for n in 3..6 {
+            bus.publish(n);
+        }
+        assert_eq!(everything.next().await, Some(Delivery::Lagged(3)));
+        assert_eq!(everything.next().await, Some(Delivery::Event(4)));
+        assert_eq!(everything.next().await, Some(Delivery::Event(5)));
+
+        drop(bus);
+        assert_eq!(everything.next().await, None);
+    }
+}
@@ -0,0 +1,174 @@
+//! Built-in heuristic adapter for RiskScorer.
+//!
+//! Adds points for each signal the deposit shows, capped at 100:
+//!
+//! | Signal                                                        | Points |
+//! |---------------------------------------------------------------|--------|
+//! | first deposit from the sender                                 | 25     |
+//! | amount at least `spike_multiplier`× the sender's average      | 30     |
+//! | `velocity_limit` or more deposits from the sender in 24 hours | 25     |
+//! | earlier deposits from the sender failed                       | 15     |
+//! | round amount: a whole multiple of `round_amount_unit`         | 10     |
+
+use async_trait::async_trait;
+use bigdecimal::BigDecimal;
+
+use crate::domain::Transaction;
+use crate::ports::{CustomerProfile, RiskAssessment, RiskResult, RiskScorer};
+
+const NEW_CUSTOMER_POINTS: u8 = 25;
+const SPIKE_POINTS: u8 = 30;
+const VELOCITY_POINTS: u8 = 25;
+const FAILED_HISTORY_POINTS: u8 = 15;
+const ROUND_AMOUNT_POINTS: u8 = 10;
+
+/// Scores deposits from the sender's history alone; needs no external service.
+#[derive(Debug, Clone)]
+pub struct HeuristicRiskScorer {
+    pub spike_multiplier: BigDecimal,
+    pub velocity_limit: i64,
+    pub round_amount_unit: BigDecimal,
+}
+
+impl Default for HeuristicRiskScorer {
+    fn default() -> Self {
+        Self {
+            spike_multiplier: BigDecimal::from(10),
+            velocity_limit: 10,
+            round_amount_unit: BigDecimal::from(1000),
+        }
+    }
+}
+
+impl HeuristicRiskScorer {
+    pub fn assess(&self, transaction: &Transaction, customer: &CustomerProfile) -> RiskAssessment {
+        let mut assessment = RiskAssessment::default();
+        let amount = &transaction.amount;
+
+        if customer.prior_deposits == 0 {
+            assessment.add(NEW_CUSTOMER_POINTS, "first deposit from this account");
+        }
+        if let Some(average) = &customer.average_amount {
+            if *average > BigDecimal::from(0) && *amount >= average * &self.spike_multiplier {
+                assessment.add(
+                    SPIKE_POINTS,
+                    format!(
+                        "amount is at least {}x the account's average deposit",
+                        self.spike_multiplier
+                    ),
+                );
+            }
+        }
+        if customer.deposits_last_24h >= self.velocity_limit {
+            assessment.add(
+                VELOCITY_POINTS,
+                format!(
+                    "{} deposits from this account in the last 24 hours",
+                    customer.deposits_last_24h
+                ),
+            );
+        }
+        if customer.failed_deposits > 0 {
+            assessment.add(
+                FAILED_HISTORY_POINTS,
+                format!(
+                    "{} earlier deposit(s) from this account failed",
+                    customer.failed_deposits
+                ),
+            );
+        }
+        if *amount >= self.round_amount_unit && (amount / &self.round_amount_unit).is_integer() {
+            assessment.add(
+                ROUND_AMOUNT_POINTS,
+                format!("round amount (multiple of {})", self.round_amount_unit),
+            );
+        }
+        assessment
+    }
+}
+
+#[async_trait]
+impl RiskScorer for HeuristicRiskScorer {
+    async fn score(
+        &self,
+        transaction: &Transaction,
+        customer: &CustomerProfile,
+    ) -> RiskResult<RiskAssessment> {
+        Ok(self.assess(transaction, customer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::str::FromStr;
+    use uuid::Uuid;
+
+    const ACCOUNT: &str = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+
+    fn deposit(amount: &str) -> Transaction {
+        Transaction::new(
+            Uuid::new_v4(),
+            Utc::now(),
+            ACCOUNT.to_string(),
+            BigDecimal::from_str(amount).unwrap(),
+            "USDC".to_string(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+    }
+
+    fn regular_customer() -> CustomerProfile {
+        CustomerProfile {
+            stellar_account: ACCOUNT.to_string(),
+            prior_deposits: 12,
+            average_amount: Some(BigDecimal::from(150)),
+            deposits_last_24h: 1,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_regular_deposit_scores_zero() {
+        let scorer = HeuristicRiskScorer::default();
+        let assessment = scorer.assess(&deposit("172.35"), &regular_customer());
+        assert_eq!(assessment, RiskAssessment::default());
+    }
+
+    #[test]
+    fn test_new_customer_with_round_amount() {
+        let scorer = HeuristicRiskScorer::default();
+        let customer = CustomerProfile {
+            stellar_account: ACCOUNT.to_string(),
+            ..Default::default()
+        };
+        let assessment = scorer.assess(&deposit("5000"), &customer);
+        assert_eq!(assessment.score, NEW_CUSTOMER_POINTS + ROUND_AMOUNT_POINTS);
+        assert_eq!(assessment.reasons.len(), 2);
+    }
+
+    #[test]
+    fn test_spike_velocity_and_failures_add_up() {
+        let scorer = HeuristicRiskScorer::default();
+        let customer = CustomerProfile {
+            deposits_last_24h: 14,
+            failed_deposits: 2,
+            ..regular_customer()
+        };
+        let assessment = scorer.assess(&deposit("2000"), &customer);
+        assert_eq!(
+            assessment.score,
+            SPIKE_POINTS + VELOCITY_POINTS + FAILED_HISTORY_POINTS + ROUND_AMOUNT_POINTS
+        );
+        assert!(
+            assessment.reasons[0].contains("average"),
+            "{:?}",
+            assessment.reasons
+        );
+    }
+}
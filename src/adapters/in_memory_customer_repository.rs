@@ -0,0 +1,78 @@
+//! In-process implementation of CustomerRepository.
+//!
+//! Records live only as long as the process, so this is for `--dev` mode
+//! and tests, never production.
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+use crate::ports::{Customer, CustomerKey, CustomerRepository, RepositoryError, RepositoryResult};
+
+/// Customer records in a mutex-guarded map keyed by id.
+#[derive(Default)]
+pub struct InMemoryCustomerRepository {
+    customers: Mutex<HashMap<Uuid, Customer>>,
+}
+
+impl InMemoryCustomerRepository {
+    fn customers(&self) -> std::sync::MutexGuard<'_, HashMap<Uuid, Customer>> {
+        self.customers.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl CustomerRepository for InMemoryCustomerRepository {
+    async fn get(&self, key: &CustomerKey) -> RepositoryResult<Option<Customer>> {
+        Ok(self.customers().values().find(|c| &c.key == key).cloned())
+    }
+
+    async fn get_by_id(&self, id: Uuid) -> RepositoryResult<Option<Customer>> {
+        Ok(self.customers().get(&id).cloned())
+    }
+
+    async fn list_by_account(&self, account: &str) -> RepositoryResult<Vec<Customer>> {
+        let mut found: Vec<Customer> = self
+            .customers()
+            .values()
+            .filter(|c| c.key.account == account)
+            .cloned()
+            .collect();
+        found.sort_by(|a, b| {
+            (&a.key.memo, &a.key.customer_type).cmp(&(&b.key.memo, &b.key.customer_type))
+        });
+        Ok(found)
+    }
+
+    async fn save(&self, customer: &Customer) -> RepositoryResult<Customer> {
+        let mut customers = self.customers();
+        if customers
+            .values()
+            .any(|c| c.key == customer.key && c.id != customer.id)
+        {
+            return Err(RepositoryError::Conflict(format!(
+                "customer {} already exists",
+                customer.key.account
+            )));
+        }
+        let saved = match customers.get(&customer.id) {
+            // The key and creation time of an existing record never change.
+            Some(existing) => Customer {
+                key: existing.key.clone(),
+                created_at: existing.created_at,
+                ..customer.clone()
+            },
+            None => customer.clone(),
+        };
+        customers.insert(saved.id, saved.clone());
+        Ok(saved)
+    }
+
+    async fn delete(&self, account: &str, memo: Option<&str>) -> RepositoryResult<u64> {
+        let mut customers = self.customers();
+        let before = customers.len();
+        customers.retain(|_, c| !(c.key.account == account && c.key.memo.as_deref() == memo));
+        Ok((before - customers.len()) as u64)
+    }
+}
@@ -0,0 +1,141 @@
+//! In-process implementation of IdempotencyStore.
+//!
+//! Keys live only as long as the process and are not shared between
+//! instances, so this is for `--dev` mode and tests, never production.
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::ports::idempotency_store::{CLAIM_TTL_SECS, RESPONSE_TTL_SECS};
+use crate::ports::{IdempotencyClaim, IdempotencyResult, IdempotencyStore, StoredResponse};
+
+enum Entry {
+    InFlight {
+        expires_at: Instant,
+    },
+    Completed {
+        response: StoredResponse,
+        expires_at: Instant,
+    },
+}
+
+impl Entry {
+    fn expires_at(&self) -> Instant {
+        match self {
+            Entry::InFlight { expires_at } | Entry::Completed { expires_at, .. } => *expires_at,
+        }
+    }
+}
+
+/// Idempotency keys in a mutex-guarded map, expired lazily on access.
+pub struct InMemoryIdempotencyStore {
+    claim_ttl: Duration,
+    response_ttl: Duration,
+    entries: Mutex<HashMap<(String, String), Entry>>,
+}
+
+impl Default for InMemoryIdempotencyStore {
+    fn default() -> Self {
+        Self::new(
+            Duration::from_secs(CLAIM_TTL_SECS),
+            Duration::from_secs(RESPONSE_TTL_SECS),
+        )
+    }
+}
+
+impl InMemoryIdempotencyStore {
+    pub fn new(claim_ttl: Duration, response_ttl: Duration) -> Self {
+        Self {
+            claim_ttl,
+            response_ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn claim_at(&self, scope: &str, key: &str, now: Instant) -> IdempotencyClaim {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.retain(|_, entry| entry.expires_at() > now);
+        let entry_key = (scope.to_string(), key.to_string());
+        match entries.get(&entry_key) {
+            Some(Entry::Completed { response, .. }) => {
+                IdempotencyClaim::Completed(response.clone())
+            }
+            Some(Entry::InFlight { .. }) => IdempotencyClaim::InFlight,
+            None => {
+                entries.insert(
+                    entry_key,
+                    Entry::InFlight {
+                        expires_at: now + self.claim_ttl,
+                    },
+                );
+                IdempotencyClaim::New
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl IdempotencyStore for InMemoryIdempotencyStore {
+    async fn claim(&self, scope: &str, key: &str) -> IdempotencyResult<IdempotencyClaim> {
+        Ok(self.claim_at(scope, key, Instant::now()))
+    }
+
+    async fn complete(
+        &self,
+        scope: &str,
+        key: &str,
+        response: StoredResponse,
+    ) -> IdempotencyResult<()> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.insert(
+            (scope.to_string(), key.to_string()),
+            Entry::Completed {
+                response,
+                expires_at: Instant::now() + self.response_ttl,
+            },
+        );
+        Ok(())
+    }
+
+    async fn release(&self, scope: &str, key: &str) -> IdempotencyResult<()> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.remove(&(scope.to_string(), key.to_string()));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_claim_complete_replay_and_expiry() {
+        let store = InMemoryIdempotencyStore::new(Duration::from_secs(5), Duration::from_secs(60));
+        assert_eq!(store.claim("t1", "k").await.unwrap(), IdempotencyClaim::New);
+        assert_eq!(
+            store.claim("t1", "k").await.unwrap(),
+            IdempotencyClaim::InFlight
+        );
+        // Scopes do not share keys.
+        assert_eq!(store.claim("t2", "k").await.unwrap(), IdempotencyClaim::New);
+
+        let response = StoredResponse {
+            status: 201,
+            body: "{}".to_string(),
+            content_type: Some("application/json".to_string()),
+        };
+        store.complete("t1", "k", response.clone()).await.unwrap();
+        assert_eq!(
+            store.claim("t1", "k").await.unwrap(),
+            IdempotencyClaim::Completed(response)
+        );
+
+        // A released claim can be taken again; an abandoned one expires.
+        store.release("t2", "k").await.unwrap();
+        assert_eq!(store.claim("t2", "k").await.unwrap(), IdempotencyClaim::New);
+        let later = Instant::now() + Duration::from_secs(6);
+        assert_eq!(store.claim_at("t2", "k", later), IdempotencyClaim::New);
+    }
+}
@@ -0,0 +1,228 @@
+//! Local-filesystem adapter for ObjectStore.
+//!
+//! Each key maps to the file `<root>/<key>`. Uploads are written to a hidden
+//! temporary file next to the target and renamed into place, so readers never
+//! see a partial object; hidden files are skipped when listing.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
+
+use crate::ports::object_store::{read_stream, validate_key};
+use crate::ports::{ByteStream, ObjectMeta, ObjectStore, ObjectStoreError, ObjectStoreResult};
+
+/// Object store rooted at a local directory.
+pub struct LocalObjectStore {
+    root: PathBuf,
+}
+
+impl LocalObjectStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn path(&self, key: &str) -> ObjectStoreResult<PathBuf> {
+        validate_key(key)?;
+        Ok(self.root.join(key))
+    }
+}
+
+fn not_found(key: &str, err: std::io::Error) -> ObjectStoreError {
+    if err.kind() == std::io::ErrorKind::NotFound {
+        ObjectStoreError::NotFound(key.to_string())
+    } else {
+        ObjectStoreError::Io(err)
+    }
+}
+
+async fn write_body(path: &Path, mut body: ByteStream) -> ObjectStoreResult<u64> {
+    let mut file = fs::File::create(path).await?;
+    let mut written = 0u64;
+    while let Some(chunk) = body.next().await {
+        let chunk = chunk?;
+        file.write_all(&chunk).await?;
+        written += chunk.len() as u64;
+    }
+    file.sync_all().await?;
+    Ok(written)
+}
+
+#[async_trait]
+impl ObjectStore for LocalObjectStore {
+    async fn put(&self, key: &str, body: ByteStream) -> ObjectStoreResult<u64> {
+        let path = self.path(key)?;
+        let dir = path.parent().unwrap_or(&self.root);
+        fs::create_dir_all(dir).await?;
+
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("object");
+        let tmp = dir.join(format!(".{name}.{}.upload", Uuid::new_v4()));
+        match write_body(&tmp, body).await {
+            Ok(written) => {
+                fs::rename(&tmp, &path).await?;
+                Ok(written)
+            }
+            Err(e) => {
+                let _ = fs::remove_file(&tmp).await;
+                Err(e)
+            }
+        }
+    }
+
+    async fn get(&self, key: &str) -> ObjectStoreResult<ByteStream> {
+        let file = fs::File::open(self.path(key)?)
+            .await
+            .map_err(|e| not_found(key, e))?;
+        Ok(read_stream(file))
+    }
+
+    async fn list(&self, prefix: &str) -> ObjectStoreResult<Vec<ObjectMeta>> {
+        // Only the directory holding the prefix's last segment can contain
+        // matches, so start the walk there.
+        let start = match prefix.rfind('/') {
+            Some(i) => {
+                let dir = &prefix[..i];
+                validate_key(dir)?;
+                (self.root.join(dir), format!("{dir}/"))
+            }
+            None => (self.root.clone(), String::new()),
+        };
+
+        let mut objects = Vec::new();
+        let mut pending = vec![start];
+        while let Some((dir, key_prefix)) = pending.pop() {
+            let mut entries = match fs::read_dir(&dir).await {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            };
+            while let Some(entry) = entries.next_entry().await? {
+                let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+                    continue;
+                };
+                if name.starts_with('.') {
+                    continue;
+                }
+                let key = format!("{key_prefix}{name}");
+                let meta = entry.metadata().await?;
+                if meta.is_dir() {
+                    pending.push((entry.path(), format!("{key}/")));
+                } else if meta.is_file() && key.starts_with(prefix) {
+                    objects.push(ObjectMeta {
+                        key,
+                        size: meta.len(),
+                        last_modified: meta.modified().ok().map(DateTime::<Utc>::from),
+                    });
+                }
+            }
+        }
+
+        objects.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(objects)
+    }
+
+    async fn delete(&self, key: &str) -> ObjectStoreResult<()> {
+        match fs::remove_file(self.path(key)?).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+
+    #[tokio::test]
+    async fn test_put_get_list_delete_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LocalObjectStore::new(dir.path());
+
+        store
+            .put_bytes("backup_a.sql.gz", Bytes::from_static(b"dump"))
+            .await
+            .unwrap();
+        store
+            .put_bytes("exports/1.csv", Bytes::from_static(b"id\n1\n"))
+            .await
+            .unwrap();
+        store
+            .put_bytes("exports/2.ndjson", Bytes::from_static(b"{}\n"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            store.get_bytes("exports/1.csv").await.unwrap(),
+            Bytes::from_static(b"id\n1\n")
+        );
+
+        let keys =
+            |objects: Vec<ObjectMeta>| objects.into_iter().map(|o| o.key).collect::<Vec<_>>();
+        assert_eq!(
+            keys(store.list("exports/").await.unwrap()),
+            vec!["exports/1.csv", "exports/2.ndjson"]
+        );
+        assert_eq!(
+            keys(store.list("").await.unwrap()),
+            vec!["backup_a.sql.gz", "exports/1.csv", "exports/2.ndjson"]
+        );
+        assert_eq!(
+            keys(store.list("backup_").await.unwrap()),
+            vec!["backup_a.sql.gz"]
+        );
+        assert!(store.list("missing/").await.unwrap().is_empty());
+        assert_eq!(store.list("exports/1").await.unwrap()[0].size, 5);
+
+        store.delete("exports/1.csv").await.unwrap();
+        store.delete("exports/1.csv").await.unwrap();
+        assert!(matches!(
+            store.get("exports/1.csv").await,
+            Err(ObjectStoreError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_failed_upload_leaves_no_object() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LocalObjectStore::new(dir.path());
+        let body: ByteStream = Box::pin(futures::stream::iter(vec![
+            Ok(Bytes::from_static(b"partial")),
+            Err(std::io::Error::other("connection reset")),
+        ]));
+
+        assert!(store.put("exports/1.csv", body).await.is_err());
+        assert!(store.list("").await.unwrap().is_empty());
+        assert_eq!(
+            std::fs::read_dir(dir.path().join("exports"))
+                .unwrap()
+                .count(),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejects_keys_outside_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LocalObjectStore::new(dir.path().join("store"));
+        assert!(matches!(
+            store.put_bytes("../escape", Bytes::new()).await,
+            Err(ObjectStoreError::InvalidKey(_))
+        ));
+        assert!(matches!(
+            store.list("../").await,
+            Err(ObjectStoreError::InvalidKey(_))
+        ));
+    }
+}
@@ -1,6 +1,174 @@
 //! Adapters: concrete implementations of ports.
 //! These connect the application to external systems (DB, APIs, etc.).
 
+pub mod broadcast_event_bus;
+pub mod heuristic_risk_scorer;
+pub mod in_memory_customer_repository;
+pub mod in_memory_idempotency_store;
+pub mod local_object_store;
+pub mod manual_clock;
+pub mod postgres_customer_repository;
 pub mod postgres_transaction_repository;
+pub mod random_id_generator;
+pub mod s3_object_store;
+pub mod secret_key_signer;
+pub mod sequential_id_generator;
+pub mod shadow_transaction_repository;
+#[cfg(feature = "sqlite-dev")]
+pub mod sqlite_transaction_repository;
+pub mod system_clock;
 
+pub use broadcast_event_bus::BroadcastEventBus;
+pub use heuristic_risk_scorer::HeuristicRiskScorer;
+pub use in_memory_customer_repository::InMemoryCustomerRepository;
+pub use in_memory_idempotency_store::InMemoryIdempotencyStore;
+pub use local_object_store::LocalObjectStore;
+pub use manual_clock::ManualClock;
+pub use postgres_customer_repository::PostgresCustomerRepository;
 pub use postgres_transaction_repository::PostgresTransactionRepository;
+pub use random_id_generator::RandomIdGenerator;
+pub use s3_object_store::{S3Config, S3ObjectStore};
+pub use secret_key_signer::SecretKeySigner;
+pub use sequential_id_generator::SequentialIdGenerator;
+pub use shadow_transaction_repository::ShadowTransactionRepository;
+#[cfg(feature = "sqlite-dev")]
+pub use sqlite_transaction_repository::SqliteTransactionRepository;
+pub use system_clock::SystemClock;
+
+use crate::ports::{
+    CustomerRepository, ObjectStore, RiskScorer, TransactionRepository, TransactionSigner,
+};
+use once_cell::sync::OnceCell;
+use sqlx::PgPool;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Table named by `SHADOW_WRITE_TABLE`, if shadow writes are enabled.
+pub fn shadow_write_table() -> Option<String> {
+    std::env::var("SHADOW_WRITE_TABLE")
+        .ok()
+        .filter(|t| !t.is_empty())
+}
+
+/// Postgres transaction repository, shadow-writing to `SHADOW_WRITE_TABLE`
+/// when it is set to a valid table name.
+pub fn transaction_repository(pool: PgPool) -> Arc<dyn TransactionRepository> {
+    let primary = Arc::new(PostgresTransactionRepository::new(pool.clone()));
+    let Some(table) = shadow_write_table() else {
+        return primary;
+    };
+    match PostgresTransactionRepository::with_table(pool, &table) {
+        Ok(shadow) => Arc::new(ShadowTransactionRepository::new(primary, Arc::new(shadow))),
+        Err(e) => {
+            tracing::warn!(error = %e, "SHADOW_WRITE_TABLE ignored");
+            primary
+        }
+    }
+}
+
+/// Postgres customer (SEP-12 KYC) repository.
+pub fn customer_repository(pool: PgPool) -> Arc<dyn CustomerRepository> {
+    Arc::new(PostgresCustomerRepository::new(pool))
+}
+
+/// Directory backups are written to (`BACKUP_DIR`, default `./backups`).
+/// Also the default root for local files when the `backup` feature is off.
+pub fn backup_dir() -> PathBuf {
+    PathBuf::from(std::env::var("BACKUP_DIR").unwrap_or_else(|_| "./backups".to_string()))
+}
+
+/// Directory the local object store is rooted at: `OBJECT_STORE_DIR`,
+/// defaulting to `BACKUP_DIR` so existing backup layouts keep working.
+pub fn local_object_store_dir() -> PathBuf {
+    std::env::var("OBJECT_STORE_DIR")
+        .ok()
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(backup_dir)
+}
+
+fn object_store_from_env() -> Arc<dyn ObjectStore> {
+    let local =
+        || -> Arc<dyn ObjectStore> { Arc::new(LocalObjectStore::new(local_object_store_dir())) };
+    match std::env::var("OBJECT_STORE").unwrap_or_default().as_str() {
+        "" | "local" => local(),
+        "s3" => match S3Config::from_env().and_then(S3ObjectStore::new) {
+            Ok(store) => Arc::new(store),
+            Err(e) => {
+                tracing::error!(error = %e, "OBJECT_STORE=s3 is misconfigured; using local storage");
+                local()
+            }
+        },
+        other => {
+            tracing::warn!(
+                backend = other,
+                "Unknown OBJECT_STORE ignored; using local storage"
+            );
+            local()
+        }
+    }
+}
+
+/// Process-wide file store for backups and exports, selected by
+/// `OBJECT_STORE` (`local`, the default, or `s3`) on first use.
+pub fn object_store() -> Arc<dyn ObjectStore> {
+    static STORE: OnceCell<Arc<dyn ObjectStore>> = OnceCell::new();
+    STORE.get_or_init(object_store_from_env).clone()
+}
+
+fn risk_scorer_from_env() -> Arc<dyn RiskScorer> {
+    match std::env::var("RISK_SCORER").unwrap_or_default().as_str() {
+        "" | "heuristic" => {}
+        other => {
+            tracing::warn!(
+                scorer = other,
+                "Unknown RISK_SCORER ignored; using heuristic scoring"
+            );
+        }
+    }
+    Arc::new(HeuristicRiskScorer::default())
+}
+
+/// Process-wide risk scorer used by the processor, selected by `RISK_SCORER`
+/// (`heuristic`, the default) on first use.
+pub fn risk_scorer() -> Arc<dyn RiskScorer> {
+    static SCORER: OnceCell<Arc<dyn RiskScorer>> = OnceCell::new();
+    SCORER.get_or_init(risk_scorer_from_env).clone()
+}
+
+fn transaction_signer_from_env() -> Option<Arc<dyn TransactionSigner>> {
+    match std::env::var("TRANSACTION_SIGNER")
+        .unwrap_or_default()
+        .as_str()
+    {
+        "" | "secret" => {}
+        other => {
+            tracing::error!(
+                signer = other,
+                "Unknown TRANSACTION_SIGNER; anchor transactions cannot be signed"
+            );
+            return None;
+        }
+    }
+    let secret = std::env::var("STELLAR_PAYOUT_SECRET")
+        .ok()
+        .filter(|s| !s.trim().is_empty())?;
+    match SecretKeySigner::from_secret(&secret) {
+        Ok(signer) => Some(Arc::new(signer)),
+        Err(e) => {
+            tracing::error!(
+                error = %e,
+                "STELLAR_PAYOUT_SECRET is invalid; anchor transactions cannot be signed"
+            );
+            None
+        }
+    }
+}
+
+/// Process-wide signer for the anchor's own transactions, selected by
+/// `TRANSACTION_SIGNER` (`secret`, the default, signing with
+/// `STELLAR_PAYOUT_SECRET`) on first use. `None` when it is not configured.
+pub fn transaction_signer() -> Option<Arc<dyn TransactionSigner>> {
+    static SIGNER: OnceCell<Option<Arc<dyn TransactionSigner>>> = OnceCell::new();
+    SIGNER.get_or_init(transaction_signer_from_env).clone()
+}
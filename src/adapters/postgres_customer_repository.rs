@@ -0,0 +1,143 @@
+//! Postgres implementation of CustomerRepository (table `customers`).
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::ports::{
+    Customer, CustomerKey, CustomerRepository, KycStatus, RepositoryError, RepositoryResult,
+};
+
+const COLUMNS: &str =
+    "id, account, memo, customer_type, fields, status, message, created_at, updated_at";
+
+/// Postgres-backed customer repository.
+#[derive(Clone)]
+pub struct PostgresCustomerRepository {
+    pool: PgPool,
+}
+
+impl PostgresCustomerRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct CustomerRow {
+    id: Uuid,
+    account: String,
+    memo: String,
+    customer_type: String,
+    fields: serde_json::Value,
+    status: String,
+    message: Option<String>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<CustomerRow> for Customer {
+    type Error = RepositoryError;
+
+    fn try_from(row: CustomerRow) -> Result<Self, Self::Error> {
+        let status = KycStatus::parse(&row.status).ok_or_else(|| {
+            RepositoryError::ConstraintViolation(format!("unknown KYC status {}", row.status))
+        })?;
+        let fields = match row.fields {
+            serde_json::Value::Object(map) => map,
+            _ => serde_json::Map::new(),
+        };
+        Ok(Customer {
+            id: row.id,
+            key: CustomerKey {
+                account: row.account,
+                memo: Some(row.memo).filter(|m| !m.is_empty()),
+                customer_type: row.customer_type,
+            },
+            fields,
+            status,
+            message: row.message,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+#[async_trait]
+impl CustomerRepository for PostgresCustomerRepository {
+    async fn get(&self, key: &CustomerKey) -> RepositoryResult<Option<Customer>> {
+        let sql = format!(
+            "SELECT {COLUMNS} FROM customers \
+             WHERE account = $1 AND memo = $2 AND customer_type = $3"
+        );
+        sqlx::query_as::<_, CustomerRow>(&sql)
+            .bind(&key.account)
+            .bind(key.memo.as_deref().unwrap_or_default())
+            .bind(&key.customer_type)
+            .fetch_optional(&self.pool)
+            .await?
+            .map(Customer::try_from)
+            .transpose()
+    }
+
+    async fn get_by_id(&self, id: Uuid) -> RepositoryResult<Option<Customer>> {
+        let sql = format!("SELECT {COLUMNS} FROM customers WHERE id = $1");
+        sqlx::query_as::<_, CustomerRow>(&sql)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .map(Customer::try_from)
+            .transpose()
+    }
+
+    async fn list_by_account(&self, account: &str) -> RepositoryResult<Vec<Customer>> {
+        let sql = format!(
+            "SELECT {COLUMNS} FROM customers WHERE account = $1 ORDER BY memo, customer_type"
+        );
+        sqlx::query_as::<_, CustomerRow>(&sql)
+            .bind(account)
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(Customer::try_from)
+            .collect()
+    }
+
+    async fn save(&self, customer: &Customer) -> RepositoryResult<Customer> {
+        let sql = format!(
+            r#"
+            INSERT INTO customers ({COLUMNS})
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (id) DO UPDATE SET
+                fields = EXCLUDED.fields,
+                status = EXCLUDED.status,
+                message = EXCLUDED.message,
+                updated_at = EXCLUDED.updated_at
+            RETURNING {COLUMNS}
+            "#
+        );
+        let row = sqlx::query_as::<_, CustomerRow>(&sql)
+            .bind(customer.id)
+            .bind(&customer.key.account)
+            .bind(customer.key.memo.as_deref().unwrap_or_default())
+            .bind(&customer.key.customer_type)
+            .bind(serde_json::Value::Object(customer.fields.clone()))
+            .bind(customer.status.as_str())
+            .bind(&customer.message)
+            .bind(customer.created_at)
+            .bind(customer.updated_at)
+            .fetch_one(&self.pool)
+            .await?;
+        row.try_into()
+    }
+
+    async fn delete(&self, account: &str, memo: Option<&str>) -> RepositoryResult<u64> {
+        let result = sqlx::query("DELETE FROM customers WHERE account = $1 AND memo = $2")
+            .bind(account)
+            .bind(memo.unwrap_or_default())
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -1,9 +1,11 @@
 //! Postgres implementation of TransactionRepository.
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use uuid::Uuid;
 
+use crate::db::models::TransactionStatus;
 use crate::domain::Transaction;
 use crate::ports::{RepositoryError, RepositoryResult, TransactionRepository};
 
@@ -11,20 +13,49 @@ use crate::ports::{RepositoryError, RepositoryResult, TransactionRepository};
 #[derive(Clone)]
 pub struct PostgresTransactionRepository {
     pool: PgPool,
+    table: String,
 }
 
 impl PostgresTransactionRepository {
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            table: "transactions".to_string(),
+        }
+    }
+
+    /// Repository over another table with the same columns as
+    /// `transactions`, e.g. the target of a schema migration being
+    /// shadow-written (see [`super::ShadowTransactionRepository`]).
+    pub fn with_table(pool: PgPool, table: &str) -> Result<Self, String> {
+        let valid = table
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+            && table.len() <= 63
+            && table
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !valid {
+            return Err(format!("Invalid table name: {table}"));
+        }
+        Ok(Self {
+            pool,
+            table: table.to_string(),
+        })
+    }
+
+    pub fn table(&self) -> &str {
+        &self.table
     }
 }
 
 #[async_trait]
 impl TransactionRepository for PostgresTransactionRepository {
     async fn insert(&self, tx: &Transaction) -> RepositoryResult<Transaction> {
-        let row = sqlx::query_as::<_, TransactionRow>(
+        let sql = format!(
             r#"
-            INSERT INTO transactions (
+            INSERT INTO {} (
                 id, stellar_account, amount, asset_code, status,
                 created_at, updated_at, anchor_transaction_id, callback_type, callback_status,
                 memo, memo_type, metadata
@@ -33,29 +64,32 @@ impl TransactionRepository for PostgresTransactionRepository {
                 created_at, updated_at, anchor_transaction_id, callback_type, callback_status,
                 memo, memo_type, metadata
             "#,
-        )
-        .bind(tx.id)
-        .bind(&tx.stellar_account)
-        .bind(&tx.amount)
-        .bind(&tx.asset_code)
-        .bind(&tx.status)
-        .bind(tx.created_at)
-        .bind(tx.updated_at)
-        .bind(&tx.anchor_transaction_id)
-        .bind(&tx.callback_type)
-        .bind(&tx.callback_status)
-        .bind(&tx.memo)
-        .bind(&tx.memo_type)
-        .bind(&tx.metadata)
-        .fetch_one(&self.pool)
-        .await
-        .map_err(RepositoryError::from)?;
+            self.table
+        );
+        let row = sqlx::query_as::<_, TransactionRow>(&sql)
+            .bind(tx.id)
+            .bind(&tx.stellar_account)
+            .bind(&tx.amount)
+            .bind(&tx.asset_code)
+            .bind(&tx.status)
+            .bind(tx.created_at)
+            .bind(tx.updated_at)
+            .bind(&tx.anchor_transaction_id)
+            .bind(&tx.callback_type)
+            .bind(&tx.callback_status)
+            .bind(&tx.memo)
+            .bind(&tx.memo_type)
+            .bind(&tx.metadata)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(RepositoryError::from)?;
 
         Ok(row.into_domain())
     }
 
     async fn get_by_id(&self, id: Uuid) -> RepositoryResult<Transaction> {
-        let row = sqlx::query_as::<_, TransactionRow>("SELECT * FROM transactions WHERE id = $1")
+        let sql = format!("SELECT * FROM {} WHERE id = $1", self.table);
+        let row = sqlx::query_as::<_, TransactionRow>(&sql)
             .bind(id)
             .fetch_optional(&self.pool)
             .await
@@ -66,14 +100,40 @@ impl TransactionRepository for PostgresTransactionRepository {
     }
 
     async fn list(&self, limit: i64, offset: i64) -> RepositoryResult<Vec<Transaction>> {
-        let rows = sqlx::query_as::<_, TransactionRow>(
-            "SELECT * FROM transactions ORDER BY created_at DESC LIMIT $1 OFFSET $2",
-        )
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(&self.pool)
-        .await
-        .map_err(RepositoryError::from)?;
+        let sql = format!(
+            "SELECT * FROM {} ORDER BY created_at DESC LIMIT $1 OFFSET $2",
+            self.table
+        );
+        let rows = sqlx::query_as::<_, TransactionRow>(&sql)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(RepositoryError::from)?;
+
+        Ok(rows.into_iter().map(|r| r.into_domain()).collect())
+    }
+
+    async fn list_created_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: i64,
+        offset: i64,
+    ) -> RepositoryResult<Vec<Transaction>> {
+        let sql = format!(
+            "SELECT * FROM {} WHERE created_at >= $1 AND created_at < $2 \
+             ORDER BY created_at, id LIMIT $3 OFFSET $4",
+            self.table
+        );
+        let rows = sqlx::query_as::<_, TransactionRow>(&sql)
+            .bind(from)
+            .bind(to)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(RepositoryError::from)?;
 
         Ok(rows.into_iter().map(|r| r.into_domain()).collect())
     }
@@ -86,7 +146,7 @@ struct TransactionRow {
     stellar_account: String,
     amount: bigdecimal::BigDecimal,
     asset_code: String,
-    status: String,
+    status: TransactionStatus,
     created_at: chrono::DateTime<chrono::Utc>,
     updated_at: chrono::DateTime<chrono::Utc>,
     anchor_transaction_id: Option<String>,
@@ -104,7 +164,7 @@ impl TransactionRow {
             stellar_account: self.stellar_account,
             amount: self.amount,
             asset_code: self.asset_code,
-            status: self.status,
+            status: self.status.to_string(),
             created_at: self.created_at,
             updated_at: self.updated_at,
             anchor_transaction_id: self.anchor_transaction_id,
@@ -0,0 +1,15 @@
+//! Id generator backed by random (v4) UUIDs.
+
+use uuid::Uuid;
+
+use crate::ports::IdGenerator;
+
+/// The production id generator: `Uuid::new_v4()`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RandomIdGenerator;
+
+impl IdGenerator for RandomIdGenerator {
+    fn new_id(&self) -> Uuid {
+        Uuid::new_v4()
+    }
+}
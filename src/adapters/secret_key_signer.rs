@@ -0,0 +1,79 @@
+//! In-process adapter for TransactionSigner, holding an `S...` secret seed.
+//!
+//! The seed lives in process memory for the life of the signer; use a KMS
+//! adapter where that is not acceptable.
+
+use async_trait::async_trait;
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use std::fmt;
+
+use crate::domain::{decode_secret_seed, StellarAddress};
+use crate::ports::{SignerResult, TransactionSigner};
+
+pub struct SecretKeySigner {
+    key: Ed25519KeyPair,
+    public_key: [u8; 32],
+}
+
+impl fmt::Debug for SecretKeySigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecretKeySigner")
+            .field(
+                "account",
+                &StellarAddress::from_ed25519(self.public_key).account(),
+            )
+            .finish_non_exhaustive()
+    }
+}
+
+impl SecretKeySigner {
+    pub fn new(seed: [u8; 32]) -> anyhow::Result<Self> {
+        let key = Ed25519KeyPair::from_seed_unchecked(&seed)
+            .map_err(|_| anyhow::anyhow!("not a valid ed25519 seed"))?;
+        let mut public_key = [0u8; 32];
+        public_key.copy_from_slice(key.public_key().as_ref());
+        Ok(Self { key, public_key })
+    }
+
+    /// Signer for an `S...` secret seed.
+    pub fn from_secret(secret: &str) -> anyhow::Result<Self> {
+        let seed = decode_secret_seed(secret)
+            .map_err(|_| anyhow::anyhow!("not a valid Stellar secret seed"))?;
+        Self::new(seed)
+    }
+}
+
+#[async_trait]
+impl TransactionSigner for SecretKeySigner {
+    fn public_key(&self) -> [u8; 32] {
+        self.public_key
+    }
+
+    async fn sign(&self, payload: &[u8]) -> SignerResult<Vec<u8>> {
+        Ok(self.key.sign(payload).as_ref().to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::signature::{UnparsedPublicKey, ED25519};
+
+    #[tokio::test]
+    async fn test_signature_verifies_against_public_key() {
+        let signer = SecretKeySigner::new([7; 32]).unwrap();
+        let signature = signer.sign(b"payload").await.unwrap();
+        assert!(UnparsedPublicKey::new(&ED25519, signer.public_key())
+            .verify(b"payload", &signature)
+            .is_ok());
+        assert!(format!("{signer:?}").starts_with("SecretKeySigner { account: \"G"));
+    }
+
+    #[test]
+    fn test_rejects_bad_secret() {
+        assert!(SecretKeySigner::from_secret(
+            "GBBD47UZQ5CSKQPV456PYYH4FSYJHBWGQJUVNMCNWZ2NBEHKQPW3KXKJ"
+        )
+        .is_err());
+    }
+}
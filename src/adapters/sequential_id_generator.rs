@@ -0,0 +1,44 @@
+//! Id generator producing a predictable sequence, for deterministic tests.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use uuid::Uuid;
+
+use crate::ports::IdGenerator;
+
+/// Hands out `00000000-0000-0000-0000-000000000001`, `...0002` and so on.
+#[derive(Debug, Default)]
+pub struct SequentialIdGenerator {
+    issued: AtomicU64,
+}
+
+impl SequentialIdGenerator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The id returned by the `n`th call to `new_id`, counting from 1.
+    pub fn nth(n: u64) -> Uuid {
+        Uuid::from_u128(n as u128)
+    }
+}
+
+impl IdGenerator for SequentialIdGenerator {
+    fn new_id(&self) -> Uuid {
+        Self::nth(self.issued.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids_follow_the_sequence() {
+        let ids = SequentialIdGenerator::new();
+        assert_eq!(ids.new_id(), SequentialIdGenerator::nth(1));
+        assert_eq!(
+            ids.new_id().to_string(),
+            "00000000-0000-0000-0000-000000000002"
+        );
+    }
+}
@@ -0,0 +1,204 @@
+//! Shadow-write (dual-write) TransactionRepository for schema migrations.
+//!
+//! During a cutover (typed status enum, partitioning, ...) the old store stays
+//! authoritative: every read and write goes to `primary`, and each successful
+//! insert is replayed against `shadow`, the new table. A failed shadow write is
+//! logged and counted but never fails the request. Once the
+//! `shadow_compare` job (see [`crate::services::shadow_compare`]) reports no
+//! divergences for long enough, `shadow` can be promoted.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::domain::Transaction;
+use crate::ports::{RepositoryResult, TransactionRepository};
+
+/// Shadow writes that failed since process start, across all instances of
+/// [`ShadowTransactionRepository`].
+pub static SHADOW_WRITE_FAILURES_TOTAL: AtomicU64 = AtomicU64::new(0);
+
+/// Writes to `primary` and mirrors them to `shadow`; reads only `primary`.
+pub struct ShadowTransactionRepository {
+    primary: Arc<dyn TransactionRepository>,
+    shadow: Arc<dyn TransactionRepository>,
+}
+
+impl ShadowTransactionRepository {
+    pub fn new(
+        primary: Arc<dyn TransactionRepository>,
+        shadow: Arc<dyn TransactionRepository>,
+    ) -> Self {
+        Self { primary, shadow }
+    }
+}
+
+#[async_trait]
+impl TransactionRepository for ShadowTransactionRepository {
+    async fn insert(&self, tx: &Transaction) -> RepositoryResult<Transaction> {
+        let inserted = self.primary.insert(tx).await?;
+
+        // Mirror what the primary stored, so defaults it filled in match.
+        if let Err(e) = self.shadow.insert(&inserted).await {
+            SHADOW_WRITE_FAILURES_TOTAL.fetch_add(1, Ordering::Relaxed);
+            tracing::warn!(
+                transaction_id = %inserted.id,
+                error = %e,
+                "Shadow write failed; primary write kept"
+            );
+        }
+        Ok(inserted)
+    }
+
+    async fn get_by_id(&self, id: Uuid) -> RepositoryResult<Transaction> {
+        self.primary.get_by_id(id).await
+    }
+
+    async fn list(&self, limit: i64, offset: i64) -> RepositoryResult<Vec<Transaction>> {
+        self.primary.list(limit, offset).await
+    }
+
+    async fn list_created_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: i64,
+        offset: i64,
+    ) -> RepositoryResult<Vec<Transaction>> {
+        self.primary
+            .list_created_between(from, to, limit, offset)
+            .await
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::ports::RepositoryError;
+    use bigdecimal::BigDecimal;
+    use std::sync::Mutex;
+
+    /// In-memory repository; `fail_inserts` makes every insert error.
+    #[derive(Default)]
+    pub(crate) struct MemoryRepository {
+        pub(crate) rows: Mutex<Vec<Transaction>>,
+        pub(crate) fail_inserts: bool,
+    }
+
+    #[async_trait]
+    impl TransactionRepository for MemoryRepository {
+        async fn insert(&self, tx: &Transaction) -> RepositoryResult<Transaction> {
+            if self.fail_inserts {
+                return Err(RepositoryError::ConstraintViolation(
+                    "shadow table rejected row".to_string(),
+                ));
+            }
+            self.rows.lock().unwrap().push(tx.clone());
+            Ok(tx.clone())
+        }
+
+        async fn get_by_id(&self, id: Uuid) -> RepositoryResult<Transaction> {
+            self.rows
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|t| t.id == id)
+                .cloned()
+                .ok_or_else(|| RepositoryError::NotFound(id.to_string()))
+        }
+
+        async fn list(&self, limit: i64, offset: i64) -> RepositoryResult<Vec<Transaction>> {
+            let rows = self.rows.lock().unwrap();
+            Ok(rows
+                .iter()
+                .rev()
+                .skip(offset as usize)
+                .take(limit as usize)
+                .cloned()
+                .collect())
+        }
+
+        async fn list_created_between(
+            &self,
+            from: DateTime<Utc>,
+            to: DateTime<Utc>,
+            limit: i64,
+            offset: i64,
+        ) -> RepositoryResult<Vec<Transaction>> {
+            let mut rows: Vec<Transaction> = self
+                .rows
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|t| t.created_at >= from && t.created_at < to)
+                .cloned()
+                .collect();
+            rows.sort_by_key(|t| (t.created_at, t.id));
+            Ok(rows
+                .into_iter()
+                .skip(offset as usize)
+                .take(limit as usize)
+                .collect())
+        }
+    }
+
+    pub(crate) fn sample_tx() -> Transaction {
+        Transaction::new(
+            Uuid::new_v4(),
+            Utc::now(),
+            "GBBD47UZQ5CSKQPV456PYYH4FSYJHBWGQJUVNMCNWZ2NBEHKQPW3KXKJ".to_string(),
+            BigDecimal::from(100),
+            "USD".to_string(),
+            Some("anchor-1".to_string()),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_insert_writes_both_stores() {
+        let primary = Arc::new(MemoryRepository::default());
+        let shadow = Arc::new(MemoryRepository::default());
+        let repo = ShadowTransactionRepository::new(primary.clone(), shadow.clone());
+
+        let tx = sample_tx();
+        repo.insert(&tx).await.unwrap();
+
+        assert_eq!(primary.rows.lock().unwrap().len(), 1);
+        assert_eq!(shadow.get_by_id(tx.id).await.unwrap().id, tx.id);
+    }
+
+    #[tokio::test]
+    async fn test_shadow_failure_does_not_fail_write() {
+        let primary = Arc::new(MemoryRepository::default());
+        let shadow = Arc::new(MemoryRepository {
+            fail_inserts: true,
+            ..Default::default()
+        });
+        let repo = ShadowTransactionRepository::new(primary.clone(), shadow);
+
+        let before = SHADOW_WRITE_FAILURES_TOTAL.load(Ordering::Relaxed);
+        let tx = sample_tx();
+        assert!(repo.insert(&tx).await.is_ok());
+        assert!(repo.get_by_id(tx.id).await.is_ok());
+        assert!(SHADOW_WRITE_FAILURES_TOTAL.load(Ordering::Relaxed) > before);
+    }
+
+    #[tokio::test]
+    async fn test_primary_failure_skips_shadow() {
+        let primary = Arc::new(MemoryRepository {
+            fail_inserts: true,
+            ..Default::default()
+        });
+        let shadow = Arc::new(MemoryRepository::default());
+        let repo = ShadowTransactionRepository::new(primary, shadow.clone());
+
+        assert!(repo.insert(&sample_tx()).await.is_err());
+        assert!(shadow.rows.lock().unwrap().is_empty());
+    }
+}
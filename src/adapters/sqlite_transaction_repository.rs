@@ -0,0 +1,282 @@
+//! SQLite implementation of TransactionRepository, behind the `sqlite-dev`
+//! feature.
+//!
+//! For local development and demos only (`synapse-core --dev`): it keeps the
+//! columns of `transactions` but none of the Postgres-only machinery (status
+//! enum, guard tables, partitions, triggers). Unsupported in production.
+//!
+//! SQLite has no UUID, NUMERIC or TIMESTAMPTZ types, so ids, amounts and
+//! timestamps are stored as text. Timestamps are written as fixed-width
+//! RFC 3339 in UTC, so comparing the text orders them correctly.
+
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, SecondsFormat, Utc};
+use sqlx::error::ErrorKind;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use uuid::Uuid;
+
+use crate::domain::Transaction;
+use crate::ports::{RepositoryError, RepositoryResult, TransactionRepository};
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS transactions (
+    id TEXT PRIMARY KEY,
+    stellar_account TEXT NOT NULL,
+    amount TEXT NOT NULL CHECK (CAST(amount AS REAL) > 0),
+    asset_code TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    anchor_transaction_id TEXT,
+    callback_type TEXT,
+    callback_status TEXT,
+    memo TEXT,
+    memo_type TEXT,
+    metadata TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_transactions_created_at ON transactions (created_at, id);
+CREATE UNIQUE INDEX IF NOT EXISTS uq_transactions_active_anchor
+    ON transactions (anchor_transaction_id)
+    WHERE anchor_transaction_id IS NOT NULL AND status <> 'failed';
+"#;
+
+const COLUMNS: &str = "id, stellar_account, amount, asset_code, status, created_at, updated_at, \
+     anchor_transaction_id, callback_type, callback_status, memo, memo_type, metadata";
+
+/// SQLite-backed transaction repository for `--dev` mode.
+#[derive(Clone)]
+pub struct SqliteTransactionRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteTransactionRepository {
+    /// Open (creating if missing) the database at `url`, e.g.
+    /// `sqlite://synapse-dev.db` or `sqlite::memory:`, and create the schema.
+    pub async fn connect(url: &str) -> Result<Self, sqlx::Error> {
+        let options = SqliteConnectOptions::from_str(url)?.create_if_missing(true);
+        // One connection: SQLite serializes writes anyway, and every
+        // connection to `sqlite::memory:` would otherwise get its own database.
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await?;
+        sqlx::raw_sql(SCHEMA).execute(&pool).await?;
+        Ok(Self { pool })
+    }
+
+    pub fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
+}
+
+fn timestamp(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+/// Map SQLite constraint failures onto the same variants the Postgres
+/// adapter produces.
+fn repository_error(err: sqlx::Error) -> RepositoryError {
+    let kind = match &err {
+        sqlx::Error::Database(db_err) => db_err.kind(),
+        _ => return RepositoryError::Database(err),
+    };
+    match kind {
+        ErrorKind::UniqueViolation => RepositoryError::Conflict(err.to_string()),
+        ErrorKind::CheckViolation | ErrorKind::NotNullViolation => {
+            RepositoryError::ConstraintViolation(err.to_string())
+        }
+        _ => RepositoryError::Database(err),
+    }
+}
+
+#[async_trait]
+impl TransactionRepository for SqliteTransactionRepository {
+    async fn insert(&self, tx: &Transaction) -> RepositoryResult<Transaction> {
+        let sql = format!(
+            "INSERT INTO transactions ({COLUMNS}) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) RETURNING {COLUMNS}"
+        );
+        let row = sqlx::query_as::<_, TransactionRow>(&sql)
+            .bind(tx.id.to_string())
+            .bind(&tx.stellar_account)
+            .bind(tx.amount.to_string())
+            .bind(&tx.asset_code)
+            .bind(&tx.status)
+            .bind(timestamp(tx.created_at))
+            .bind(timestamp(tx.updated_at))
+            .bind(&tx.anchor_transaction_id)
+            .bind(&tx.callback_type)
+            .bind(&tx.callback_status)
+            .bind(&tx.memo)
+            .bind(&tx.memo_type)
+            .bind(tx.metadata.as_ref().map(|m| m.to_string()))
+            .fetch_one(&self.pool)
+            .await
+            .map_err(repository_error)?;
+
+        row.into_domain()
+    }
+
+    async fn get_by_id(&self, id: Uuid) -> RepositoryResult<Transaction> {
+        let sql = format!("SELECT {COLUMNS} FROM transactions WHERE id = ?");
+        let row = sqlx::query_as::<_, TransactionRow>(&sql)
+            .bind(id.to_string())
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(repository_error)?;
+
+        row.ok_or_else(|| RepositoryError::NotFound(id.to_string()))?
+            .into_domain()
+    }
+
+    async fn list(&self, limit: i64, offset: i64) -> RepositoryResult<Vec<Transaction>> {
+        let sql =
+            format!("SELECT {COLUMNS} FROM transactions ORDER BY created_at DESC LIMIT ? OFFSET ?");
+        let rows = sqlx::query_as::<_, TransactionRow>(&sql)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(repository_error)?;
+
+        rows.into_iter().map(TransactionRow::into_domain).collect()
+    }
+
+    async fn list_created_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: i64,
+        offset: i64,
+    ) -> RepositoryResult<Vec<Transaction>> {
+        let sql = format!(
+            "SELECT {COLUMNS} FROM transactions WHERE created_at >= ? AND created_at < ? \
+             ORDER BY created_at, id LIMIT ? OFFSET ?"
+        );
+        let rows = sqlx::query_as::<_, TransactionRow>(&sql)
+            .bind(timestamp(from))
+            .bind(timestamp(to))
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(repository_error)?;
+
+        rows.into_iter().map(TransactionRow::into_domain).collect()
+    }
+}
+
+/// Internal row type for SQLx. Not exposed outside the adapter.
+#[derive(Debug, sqlx::FromRow)]
+struct TransactionRow {
+    id: String,
+    stellar_account: String,
+    amount: String,
+    asset_code: String,
+    status: String,
+    created_at: String,
+    updated_at: String,
+    anchor_transaction_id: Option<String>,
+    callback_type: Option<String>,
+    callback_status: Option<String>,
+    memo: Option<String>,
+    memo_type: Option<String>,
+    metadata: Option<String>,
+}
+
+fn decode<T, E>(column: &str, result: Result<T, E>) -> RepositoryResult<T>
+where
+    E: std::fmt::Display,
+{
+    result.map_err(|e| {
+        RepositoryError::Database(sqlx::Error::Decode(
+            format!("transactions.{column}: {e}").into(),
+        ))
+    })
+}
+
+impl TransactionRow {
+    fn into_domain(self) -> RepositoryResult<Transaction> {
+        let parse_time = |column: &str, value: &str| {
+            decode(
+                column,
+                DateTime::parse_from_rfc3339(value).map(|t| t.with_timezone(&Utc)),
+            )
+        };
+        Ok(Transaction {
+            id: decode("id", Uuid::parse_str(&self.id))?,
+            stellar_account: self.stellar_account,
+            amount: decode("amount", BigDecimal::from_str(&self.amount))?,
+            asset_code: self.asset_code,
+            status: self.status,
+            created_at: parse_time("created_at", &self.created_at)?,
+            updated_at: parse_time("updated_at", &self.updated_at)?,
+            anchor_transaction_id: self.anchor_transaction_id,
+            callback_type: self.callback_type,
+            callback_status: self.callback_status,
+            memo: self.memo,
+            memo_type: self.memo_type,
+            metadata: self
+                .metadata
+                .map(|m| decode("metadata", serde_json::from_str(&m)))
+                .transpose()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn deposit(amount: &str, anchor_id: &str) -> Transaction {
+        Transaction::new(
+            Uuid::new_v4(),
+            Utc::now(),
+            "GABC".to_string(),
+            BigDecimal::from_str(amount).unwrap(),
+            "USDC".to_string(),
+            Some(anchor_id.to_string()),
+            Some("deposit".to_string()),
+            None,
+            None,
+            None,
+            Some(serde_json::json!({"order": 7})),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_and_constraints() {
+        let repo = SqliteTransactionRepository::connect("sqlite::memory:")
+            .await
+            .unwrap();
+
+        let tx = deposit("100.50", "anchor-1");
+        repo.insert(&tx).await.unwrap();
+        let fetched = repo.get_by_id(tx.id).await.unwrap();
+        assert_eq!(fetched.amount, tx.amount);
+        assert_eq!(fetched.metadata, tx.metadata);
+
+        let in_window = repo
+            .list_created_between(tx.created_at, tx.created_at + Duration::seconds(1), 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(in_window.len(), 1);
+
+        assert!(matches!(
+            repo.insert(&deposit("5", "anchor-1")).await,
+            Err(RepositoryError::Conflict(_))
+        ));
+        assert!(matches!(
+            repo.insert(&deposit("0", "anchor-2")).await,
+            Err(RepositoryError::ConstraintViolation(_))
+        ));
+        assert!(matches!(
+            repo.get_by_id(Uuid::new_v4()).await,
+            Err(RepositoryError::NotFound(_))
+        ));
+    }
+}
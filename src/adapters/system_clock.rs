@@ -0,0 +1,15 @@
+//! Clock backed by the system's wall clock.
+
+use chrono::{DateTime, Utc};
+
+use crate::ports::Clock;
+
+/// The production clock: `Utc::now()`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
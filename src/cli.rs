@@ -1,7 +1,16 @@
+use chrono::NaiveDate;
 use clap::{Parser, Subcommand};
 use sqlx::PgPool;
-use synapse_core::config::Config;
+use synapse_core::config::{AppEnv, Config};
+use synapse_core::services::seed::{self, SeedOptions};
 use uuid::Uuid;
+#[cfg(feature = "backup")]
+use {
+    std::path::PathBuf,
+    synapse_core::services::backup::{BackupService, BackupType},
+    synapse_core::services::backup_keys::MasterKeyring,
+    synapse_core::services::backup_pitr::PitrRequest,
+};
 
 #[derive(Parser)]
 #[command(name = "synapse-core")]
@@ -9,6 +18,17 @@ use uuid::Uuid;
 pub struct Cli {
     #[command(subcommand)]
     pub command: Option<Commands>,
+
+    /// Check database, Redis, Horizon, object store and signing keys, print a
+    /// pass/fail report and exit (non-zero on failure)
+    #[arg(long)]
+    pub self_check: bool,
+
+    /// Serve a minimal deposit API backed by SQLite and in-memory
+    /// idempotency, without Postgres or Redis. Needs the `sqlite-dev` feature;
+    /// for local development and demos only, unsupported in production
+    #[arg(long)]
+    pub dev: bool,
 }
 
 #[derive(Subcommand)]
@@ -25,11 +45,42 @@ pub enum Commands {
     Db(DbCommands),
 
     /// Backup management commands
+    #[cfg(feature = "backup")]
     #[command(subcommand)]
     Backup(BackupCommands),
 
     /// Configuration validation
     Config,
+
+    /// Fill the database with a reproducible demo dataset: partners,
+    /// transactions, settlements, DLQ entries and audit logs
+    Seed {
+        /// The same seed (with the same other options) gives the same
+        /// dataset, ids included
+        #[arg(long, default_value_t = 1)]
+        seed: u64,
+
+        /// Number of transactions
+        #[arg(long, default_value_t = 1000)]
+        transactions: usize,
+
+        /// Number of partners (tenants)
+        #[arg(long, default_value_t = 5)]
+        partners: usize,
+
+        /// Spread transactions over this many days
+        #[arg(long, default_value_t = 30)]
+        days: u32,
+
+        /// Last day of the window (YYYY-MM-DD, UTC); defaults to today, so
+        /// pin it to reproduce a dataset on a later date
+        #[arg(long)]
+        until: Option<NaiveDate>,
+
+        /// Print what would be written without touching the database
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -59,6 +110,21 @@ pub enum TxCommands {
         #[arg(long, default_value = "text")]
         format: String,
     },
+
+    /// Queue an import of an account's past payments from Horizon
+    Backfill {
+        /// Receiving Stellar account
+        #[arg(value_name = "ACCOUNT")]
+        account: String,
+
+        /// First day to import (YYYY-MM-DD, UTC)
+        #[arg(long)]
+        from: NaiveDate,
+
+        /// Last day to import, inclusive (YYYY-MM-DD, UTC)
+        #[arg(long)]
+        to: NaiveDate,
+    },
 }
 
 #[derive(Subcommand)]
@@ -67,6 +133,7 @@ pub enum DbCommands {
     Migrate,
 }
 
+#[cfg(feature = "backup")]
 #[derive(Subcommand)]
 pub enum BackupCommands {
     /// Create a new backup
@@ -86,15 +153,43 @@ pub enum BackupCommands {
         filename: String,
     },
 
-    /// Restore to a specific point in time
+    /// Take a physical base backup for point-in-time recovery
+    BaseBackup,
+
+    /// Restore the latest base backup plus archived WAL to a point in time
+    /// into a new cluster, verify it and report RTO/RPO
     RestorePitr {
         /// Target timestamp (ISO 8601 format, e.g., 2026-01-15T10:30:00Z)
         #[arg(long)]
         timestamp: String,
+
+        /// Data directory for the restored cluster (must be missing or empty)
+        #[arg(long)]
+        data_dir: PathBuf,
+
+        /// Port for the restored cluster
+        #[arg(long, default_value_t = 5433)]
+        port: u16,
+
+        /// Leave the restored cluster running after verification
+        #[arg(long)]
+        keep_running: bool,
+
+        /// Give up if WAL replay takes longer than this many seconds
+        #[arg(long, default_value_t = 3600)]
+        recovery_timeout_secs: u64,
+
+        /// Output format: text or json
+        #[arg(long, default_value = "text")]
+        format: String,
     },
 
     /// Apply retention policy to clean old backups
     Cleanup,
+
+    /// Re-wrap backup data keys with the current master key
+    /// (BACKUP_ENCRYPTION_KEY) so retired master keys can be removed
+    Rewrap,
 }
 
 pub async fn handle_tx_force_complete(pool: &PgPool, tx_id: Uuid) -> anyhow::Result<()> {
@@ -130,22 +225,85 @@ pub async fn handle_tx_force_complete(pool: &PgPool, tx_id: Uuid) -> anyhow::Res
     }
 }
 
-pub async fn handle_db_migrate(config: &Config) -> anyhow::Result<()> {
-    use sqlx::migrate::Migrator;
-    use std::path::Path;
+/// Queue a `horizon_backfill` job; the server's job runner imports the
+/// payments and `GET /admin/jobs/:id` reports progress.
+pub async fn handle_tx_backfill(
+    pool: &PgPool,
+    account: &str,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> anyhow::Result<()> {
+    use synapse_core::services::horizon_backfill::HorizonBackfillParams;
+    use synapse_core::services::job_runner::JobKind;
+
+    let params = HorizonBackfillParams {
+        account: account.to_string(),
+        from,
+        to,
+    };
+    params.validate().map_err(|e| anyhow::anyhow!(e))?;
+
+    let job = crate::db::queries::insert_job(
+        pool,
+        JobKind::HorizonBackfill.as_str(),
+        &serde_json::to_value(&params)?,
+        Some("cli"),
+    )
+    .await?;
+
+    tracing::info!(job_id = %job.id, account, %from, %to, "Horizon backfill queued");
+    println!(
+        "✓ Backfill of {account} from {from} to {to} queued as job {}",
+        job.id
+    );
+    Ok(())
+}
 
+pub async fn handle_db_migrate(config: &Config) -> anyhow::Result<()> {
     let pool = crate::db::create_pool(config).await?;
-    let migrator = Migrator::new(Path::new("./migrations")).await?;
+    let lock_timeout = std::time::Duration::from_secs(config.migration_lock_timeout_secs);
 
     tracing::info!("Running database migrations...");
-    migrator.run(&pool).await?;
+    crate::db::migrations::run(&pool, lock_timeout).await?;
 
-    tracing::info!("Database migrations completed");
     println!("✓ Database migrations completed");
 
     Ok(())
 }
 
+pub async fn handle_seed(
+    config: &Config,
+    options: SeedOptions,
+    dry_run: bool,
+) -> anyhow::Result<()> {
+    if config.app_env == AppEnv::Production {
+        anyhow::bail!("Refusing to seed demo data with APP_ENV=production");
+    }
+
+    let data = seed::generate(&options);
+    let summary = if dry_run {
+        data.summary()
+    } else {
+        let pool = crate::db::create_pool(config).await?;
+        seed::insert(&pool, &data).await?
+    };
+
+    let verb = if dry_run { "Would seed" } else { "✓ Seeded" };
+    println!(
+        "{verb} seed {} ({} days to {}): {} partners, {} transactions, {} settlements, \
+         {} DLQ entries, {} audit log entries",
+        options.seed,
+        options.days,
+        options.until,
+        summary.partners,
+        summary.transactions,
+        summary.settlements,
+        summary.dlq,
+        summary.audit
+    );
+    Ok(())
+}
+
 pub fn handle_config_validate(config: &Config) -> anyhow::Result<()> {
     tracing::info!("Validating configuration...");
 
@@ -153,6 +311,7 @@ pub fn handle_config_validate(config: &Config) -> anyhow::Result<()> {
     println!("  Server Port: {}", config.server_port);
     println!("  Database URL: {}", mask_password(&config.database_url));
     println!("  Stellar Horizon URL: {}", config.stellar_horizon_url);
+    println!("  Migration Mode: {}", config.migration_mode.as_str());
 
     tracing::info!("Configuration is valid");
     println!("✓ Configuration is valid");
@@ -160,6 +319,15 @@ pub fn handle_config_validate(config: &Config) -> anyhow::Result<()> {
     Ok(())
 }
 
+pub async fn handle_self_check(config: &Config) -> anyhow::Result<()> {
+    let report = synapse_core::startup::self_check(config).await;
+    report.print();
+    if !report.is_ok() {
+        anyhow::bail!("Self-check failed");
+    }
+    Ok(())
+}
+
 fn mask_password(url: &str) -> String {
     if let Some(at_pos) = url.rfind('@') {
         if let Some(colon_pos) = url[..at_pos].rfind(':') {
@@ -175,20 +343,87 @@ fn mask_password(url: &str) -> String {
     url.to_string()
 }
 
-pub async fn handle_backup_run(_config: &Config, _backup_type_str: &str) -> anyhow::Result<()> {
-    anyhow::bail!("Backup service not yet implemented")
+#[cfg(feature = "backup")]
+fn backup_service(config: &Config) -> anyhow::Result<BackupService> {
+    let keyring = MasterKeyring::from_config(config)?;
+    Ok(BackupService::new(
+        config.database_url.clone(),
+        PathBuf::from(&config.backup_dir),
+        keyring,
+    )
+    .with_store(synapse_core::adapters::object_store()))
 }
 
-pub async fn handle_backup_list(_config: &Config) -> anyhow::Result<()> {
-    anyhow::bail!("Backup service not yet implemented")
+#[cfg(feature = "backup")]
+pub async fn handle_backup_run(config: &Config, backup_type_str: &str) -> anyhow::Result<()> {
+    let backup_type = match backup_type_str {
+        "hourly" => BackupType::Hourly,
+        "daily" => BackupType::Daily,
+        "monthly" => BackupType::Monthly,
+        other => anyhow::bail!("Invalid backup type '{other}' (hourly, daily or monthly)"),
+    };
+    let metadata = backup_service(config)?.create_backup(backup_type).await?;
+    println!(
+        "✓ Backup created: {} ({} bytes)",
+        metadata.filename, metadata.size_bytes
+    );
+    Ok(())
 }
 
-pub async fn handle_backup_restore(_config: &Config, _filename: &str) -> anyhow::Result<()> {
-    anyhow::bail!("Backup service not yet implemented")
+#[cfg(feature = "backup")]
+pub async fn handle_backup_list(config: &Config) -> anyhow::Result<()> {
+    let backups = backup_service(config)?.list_backups().await?;
+    if backups.is_empty() {
+        println!("No backups found");
+    }
+    for backup in backups {
+        let key = backup
+            .key_envelope
+            .as_ref()
+            .map(|e| format!(" key={}", e.key_id))
+            .unwrap_or_default();
+        println!(
+            "{} | {:?} | {} | {} bytes{}",
+            backup.filename, backup.backup_type, backup.timestamp, backup.size_bytes, key
+        );
+    }
+    Ok(())
+}
+
+#[cfg(feature = "backup")]
+pub async fn handle_backup_restore(config: &Config, filename: &str) -> anyhow::Result<()> {
+    backup_service(config)?.restore_backup(filename).await?;
+    println!("✓ Restored {filename}");
+    Ok(())
 }
 
-pub async fn handle_backup_cleanup(_config: &Config) -> anyhow::Result<()> {
-    anyhow::bail!("Backup service not yet implemented")
+#[cfg(feature = "backup")]
+pub async fn handle_backup_cleanup(config: &Config) -> anyhow::Result<()> {
+    backup_service(config)?.apply_retention_policy().await?;
+    println!("✓ Retention policy applied");
+    Ok(())
+}
+
+#[cfg(feature = "backup")]
+pub async fn handle_backup_rewrap(config: &Config) -> anyhow::Result<()> {
+    let service = backup_service(config)?;
+    let report = service.rewrap_keys().await?;
+
+    println!("Re-wrapped: {}", report.rewrapped.len());
+    for filename in &report.rewrapped {
+        println!("  - {filename}");
+    }
+    println!("Already current: {}", report.current);
+    if !report.legacy.is_empty() {
+        println!(
+            "⚠️  {} backup(s) predate envelope encryption and still need the master key they were written with:",
+            report.legacy.len()
+        );
+        for filename in &report.legacy {
+            println!("  - {filename}");
+        }
+    }
+    Ok(())
 }
 
 pub async fn handle_tx_reconcile(
@@ -287,10 +522,74 @@ pub async fn handle_tx_reconcile(
     Ok(())
 }
 
-#[allow(dead_code)]
+#[cfg(feature = "backup")]
+pub async fn handle_backup_base(config: &Config) -> anyhow::Result<()> {
+    let metadata = backup_service(config)?.create_base_backup().await?;
+    println!(
+        "✓ Base backup created: {} ({} bytes, WAL from {})",
+        metadata.label, metadata.size_bytes, metadata.start_wal_file
+    );
+    Ok(())
+}
+
+#[cfg(feature = "backup")]
 pub async fn handle_backup_restore_pitr(
-    _config: &Config,
-    _timestamp_str: &str,
+    config: &Config,
+    timestamp_str: &str,
+    data_dir: PathBuf,
+    port: u16,
+    keep_running: bool,
+    recovery_timeout_secs: u64,
+    format: &str,
 ) -> anyhow::Result<()> {
-    anyhow::bail!("PITR restore service not yet implemented")
+    let target_time = chrono::DateTime::parse_from_rfc3339(timestamp_str)
+        .map_err(|_| {
+            anyhow::anyhow!("Invalid timestamp format. Use ISO 8601 (e.g., 2026-01-15T10:30:00Z)")
+        })?
+        .with_timezone(&chrono::Utc);
+    let request = PitrRequest {
+        target_time,
+        data_dir,
+        port,
+        keep_running,
+        recovery_timeout: std::time::Duration::from_secs(recovery_timeout_secs),
+    };
+    let report = backup_service(config)?
+        .restore_point_in_time(&request)
+        .await?;
+
+    match format {
+        "json" => println!("{}", serde_json::to_string_pretty(&report)?),
+        _ => {
+            println!("Point-in-time restore to {}", report.target_time);
+            println!("  Base backup:   {}", report.base_backup);
+            println!("  WAL segments:  {}", report.wal_segments);
+            match report.recovered_to {
+                Some(t) => println!("  Recovered to:  {t}"),
+                None => println!("  Recovered to:  (no transactions replayed)"),
+            }
+            println!("  RTO:           {:.1}s", report.rto_secs);
+            match report.rpo_secs {
+                Some(rpo) => println!("  RPO:           {rpo}s"),
+                None => println!("  RPO:           unknown"),
+            }
+            println!("Verification:");
+            for check in &report.checks {
+                let mark = if check.passed { "✓" } else { "✗" };
+                println!("  {mark} {}: {}", check.name, check.detail);
+            }
+            if keep_running {
+                println!(
+                    "Restored cluster running on port {} ({})",
+                    report.port,
+                    report.data_dir.display()
+                );
+            }
+        }
+    }
+
+    if !report.passed() {
+        anyhow::bail!("Point-in-time restore verification failed");
+    }
+    Ok(())
 }
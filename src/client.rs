@@ -0,0 +1,467 @@
+//! Typed Rust client for the REST API, built with the `synapse-client`
+//! feature.
+//!
+//! Requests and responses use the same types the server handlers do, so a
+//! field added to [`Transaction`] or [`SubscriptionView`] reaches callers
+//! without a second definition to keep in step.
+//!
+//! | Area          | Methods                                                          | Auth        |
+//! |---------------|------------------------------------------------------------------|-------------|
+//! | Transactions  | `callback`, `get_transaction`, `list_transactions`               | `X-API-Key` |
+//! | Settlements   | `list_settlements`, `get_settlement`                             | `X-API-Key` |
+//! | Webhooks      | `create_webhook_subscription` … `test_webhook_subscription`      | admin key   |
+//! | DLQ           | `list_dlq`, `requeue_dlq`                                        | admin key   |
+//!
+//! Transaction and settlement calls go to `/api/v2`. The `fields` projection
+//! is not supported: the client always asks for whole records so they decode
+//! into the model types.
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), synapse_core::client::ClientError> {
+//! use synapse_core::client::SynapseClient;
+//!
+//! let client = SynapseClient::new("https://synapse.example.com")
+//!     .with_api_key("partner-key")
+//!     .with_admin_key("admin-key");
+//! let page = client.list_transactions(&Default::default()).await?;
+//! for tx in page.data {
+//!     println!("{} {}", tx.id, tx.status.as_str());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::db::models::{Settlement, Transaction};
+use crate::handlers::ack::AcceptedResponse;
+use crate::handlers::admin::webhook_subscriptions::{
+    CreateSubscriptionRequest, RotateSecretRequest, SubscriptionView, SubscriptionWithSecret,
+};
+use crate::handlers::dlq::DlqListResponse;
+use crate::handlers::settlements::{SettlementListQuery, SettlementListResponse};
+use crate::handlers::webhook::{CallbackPayload, ListQuery};
+use crate::services::webhook_dispatcher::PingResult;
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+const API_PREFIX: &str = "/api/v2";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    #[error("request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// The server answered with a non-success status.
+    #[error("{status}: {message}")]
+    Api {
+        status: StatusCode,
+        /// Error code from the body, e.g. `ERR_VALIDATION_001`.
+        code: Option<String>,
+        message: String,
+    },
+}
+
+impl ClientError {
+    /// HTTP status for [`ClientError::Api`].
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            ClientError::Api { status, .. } => Some(*status),
+            ClientError::Http(e) => e.status(),
+        }
+    }
+}
+
+/// `GET /transactions` page.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransactionPage {
+    pub data: Vec<Transaction>,
+    pub meta: PageMeta,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PageMeta {
+    pub next_cursor: Option<String>,
+    pub has_more: bool,
+}
+
+/// How the server acknowledged a callback; see [`crate::handlers::ack`].
+#[derive(Debug)]
+pub enum CallbackAck {
+    /// `201`: stored now.
+    Created(Transaction),
+    /// `200`: the same payload was seen inside the dedup window.
+    Duplicate(Transaction),
+    /// `202`: accepted for asynchronous processing.
+    Accepted(AcceptedResponse),
+}
+
+#[derive(Deserialize)]
+struct SubscriptionList {
+    subscriptions: Vec<SubscriptionView>,
+}
+
+/// Client for one Synapse deployment.
+#[derive(Debug, Clone)]
+pub struct SynapseClient {
+    http: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+    admin_key: Option<String>,
+}
+
+impl SynapseClient {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_http_client(reqwest::Client::new(), base_url)
+    }
+
+    /// Use a preconfigured `reqwest::Client` (timeouts, proxies, TLS).
+    pub fn with_http_client(http: reqwest::Client, base_url: impl Into<String>) -> Self {
+        Self {
+            http,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            api_key: None,
+            admin_key: None,
+        }
+    }
+
+    /// Partner key sent as `X-API-Key` on transaction and settlement calls.
+    pub fn with_api_key(mut self, key: impl Into<String>) -> Self {
+        self.api_key = Some(key.into());
+        self
+    }
+
+    /// Admin key sent as a bearer token on webhook and DLQ calls.
+    pub fn with_admin_key(mut self, key: impl Into<String>) -> Self {
+        self.admin_key = Some(key.into());
+        self
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    fn api(&self, method: Method, path: &str) -> RequestBuilder {
+        let req = self
+            .http
+            .request(method, self.url(&format!("{API_PREFIX}{path}")));
+        match &self.api_key {
+            Some(key) => req.header("X-API-Key", key),
+            None => req,
+        }
+    }
+
+    fn admin(&self, method: Method, path: &str) -> RequestBuilder {
+        let req = self.http.request(method, self.url(path));
+        match &self.admin_key {
+            Some(key) => req.bearer_auth(key),
+            None => req,
+        }
+    }
+
+    // ── Transactions ─────────────────────────────────────────────────────────
+
+    /// POST /callback
+    pub async fn callback(&self, payload: &CallbackPayload) -> Result<CallbackAck, ClientError> {
+        let res = check(
+            self.api(Method::POST, "/callback")
+                .json(payload)
+                .send()
+                .await?,
+        )
+        .await?;
+        Ok(match res.status() {
+            StatusCode::ACCEPTED => CallbackAck::Accepted(res.json().await?),
+            StatusCode::OK => CallbackAck::Duplicate(res.json().await?),
+            _ => CallbackAck::Created(res.json().await?),
+        })
+    }
+
+    /// GET /transactions/:id
+    pub async fn get_transaction(&self, id: Uuid) -> Result<Transaction, ClientError> {
+        json(self.api(Method::GET, &format!("/transactions/{id}"))).await
+    }
+
+    /// GET /transactions; follow `meta.next_cursor` for the next page.
+    pub async fn list_transactions(
+        &self,
+        query: &ListQuery,
+    ) -> Result<TransactionPage, ClientError> {
+        let query = ListQuery {
+            fields: None,
+            ..query.clone()
+        };
+        json(self.api(Method::GET, "/transactions").query(&query)).await
+    }
+
+    // ── Settlements ──────────────────────────────────────────────────────────
+
+    /// GET /settlements
+    pub async fn list_settlements(
+        &self,
+        query: &SettlementListQuery,
+    ) -> Result<SettlementListResponse, ClientError> {
+        let query = SettlementListQuery {
+            fields: None,
+            ..query.clone()
+        };
+        json(self.api(Method::GET, "/settlements").query(&query)).await
+    }
+
+    /// GET /settlements/:id
+    pub async fn get_settlement(&self, id: Uuid) -> Result<Settlement, ClientError> {
+        json(self.api(Method::GET, &format!("/settlements/{id}"))).await
+    }
+
+    // ── Webhook subscriptions ────────────────────────────────────────────────
+
+    /// POST /admin/webhook-subscriptions; the secret is only returned here
+    /// and on rotation.
+    pub async fn create_webhook_subscription(
+        &self,
+        request: &CreateSubscriptionRequest,
+    ) -> Result<SubscriptionWithSecret, ClientError> {
+        json(
+            self.admin(Method::POST, "/admin/webhook-subscriptions")
+                .json(request),
+        )
+        .await
+    }
+
+    /// GET /admin/webhook-subscriptions
+    pub async fn list_webhook_subscriptions(&self) -> Result<Vec<SubscriptionView>, ClientError> {
+        let list: SubscriptionList =
+            json(self.admin(Method::GET, "/admin/webhook-subscriptions")).await?;
+        Ok(list.subscriptions)
+    }
+
+    /// GET /admin/webhook-subscriptions/:id
+    pub async fn get_webhook_subscription(
+        &self,
+        id: Uuid,
+    ) -> Result<SubscriptionView, ClientError> {
+        json(self.admin(Method::GET, &subscription_path(id, ""))).await
+    }
+
+    /// DELETE /admin/webhook-subscriptions/:id
+    pub async fn delete_webhook_subscription(&self, id: Uuid) -> Result<(), ClientError> {
+        check(
+            self.admin(Method::DELETE, &subscription_path(id, ""))
+                .send()
+                .await?,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// POST /admin/webhook-subscriptions/:id/rotate-secret
+    pub async fn rotate_webhook_secret(
+        &self,
+        id: Uuid,
+        request: &RotateSecretRequest,
+    ) -> Result<SubscriptionWithSecret, ClientError> {
+        json(
+            self.admin(Method::POST, &subscription_path(id, "/rotate-secret"))
+                .json(request),
+        )
+        .await
+    }
+
+    /// POST /admin/webhook-subscriptions/:id/enable
+    pub async fn enable_webhook_subscription(
+        &self,
+        id: Uuid,
+    ) -> Result<SubscriptionView, ClientError> {
+        json(self.admin(Method::POST, &subscription_path(id, "/enable"))).await
+    }
+
+    /// POST /admin/webhook-subscriptions/:id/test
+    pub async fn test_webhook_subscription(&self, id: Uuid) -> Result<PingResult, ClientError> {
+        json(self.admin(Method::POST, &subscription_path(id, "/test"))).await
+    }
+
+    // ── Dead-letter queue ────────────────────────────────────────────────────
+
+    /// GET /dlq
+    pub async fn list_dlq(&self) -> Result<DlqListResponse, ClientError> {
+        json(self.admin(Method::GET, "/dlq")).await
+    }
+
+    /// POST /dlq/:id/requeue
+    pub async fn requeue_dlq(&self, id: Uuid) -> Result<(), ClientError> {
+        check(
+            self.admin(Method::POST, &format!("/dlq/{id}/requeue"))
+                .send()
+                .await?,
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+fn subscription_path(id: Uuid, suffix: &str) -> String {
+    format!("/admin/webhook-subscriptions/{id}{suffix}")
+}
+
+async fn json<T: DeserializeOwned>(req: RequestBuilder) -> Result<T, ClientError> {
+    Ok(check(req.send().await?).await?.json().await?)
+}
+
+/// Turn a non-2xx response into [`ClientError::Api`], reading the error body
+/// the server's `AppError` produces.
+async fn check(res: Response) -> Result<Response, ClientError> {
+    let status = res.status();
+    if status.is_success() {
+        return Ok(res);
+    }
+    let body = res.text().await.unwrap_or_default();
+    let parsed: Option<serde_json::Value> = serde_json::from_str(&body).ok();
+    let field = |name: &str| {
+        parsed
+            .as_ref()
+            .and_then(|v| v.get(name))
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    };
+    let message = field("detail")
+        .or_else(|| field("error"))
+        .unwrap_or_else(|| {
+            if body.is_empty() {
+                status.canonical_reason().unwrap_or("error").to_string()
+            } else {
+                body.clone()
+            }
+        });
+    Err(ClientError::Api {
+        status,
+        code: field("code"),
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+
+    fn transaction_json(id: Uuid) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "stellar_account": "GABCDEFGHIJKLMNOPQRSTUVWXYZ234567ABCDEFGHIJKLMNOPQRSTUVW",
+            "amount": "100.50",
+            "asset_code": "USDC",
+            "status": "pending",
+            "created_at": "2026-01-01T00:00:00Z",
+            "updated_at": "2026-01-01T00:00:00Z",
+            "anchor_transaction_id": null,
+            "callback_type": null,
+            "callback_status": null,
+            "settlement_id": null,
+            "memo": null,
+            "memo_type": null,
+            "metadata": null,
+            "trace_id": null
+        })
+    }
+
+    #[tokio::test]
+    async fn test_get_transaction_sends_api_key_to_v2() {
+        let mut server = mockito::Server::new_async().await;
+        let id = Uuid::new_v4();
+        let mock = server
+            .mock("GET", format!("/api/v2/transactions/{id}").as_str())
+            .match_header("X-API-Key", "partner-key")
+            .with_status(200)
+            .with_body(transaction_json(id).to_string())
+            .create_async()
+            .await;
+
+        let client = SynapseClient::new(format!("{}/", server.url())).with_api_key("partner-key");
+        let tx = client.get_transaction(id).await.unwrap();
+
+        assert_eq!(tx.id, id);
+        assert_eq!(tx.asset_code, "USDC");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_list_transactions_drops_field_projection() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v2/transactions")
+            .match_query(Matcher::Exact("limit=10".into()))
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "data": [transaction_json(Uuid::new_v4())],
+                    "meta": { "next_cursor": "abc", "has_more": true }
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let client = SynapseClient::new(server.url());
+        let page = client
+            .list_transactions(&ListQuery {
+                limit: Some(10),
+                fields: Some("id".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(page.data.len(), 1);
+        assert_eq!(page.meta.next_cursor.as_deref(), Some("abc"));
+        assert!(page.meta.has_more);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_admin_calls_use_bearer_key() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/admin/webhook-subscriptions")
+            .match_header("Authorization", "Bearer admin-key")
+            .with_status(200)
+            .with_body(r#"{"subscriptions":[]}"#)
+            .create_async()
+            .await;
+
+        let client = SynapseClient::new(server.url()).with_admin_key("admin-key");
+        assert!(client
+            .list_webhook_subscriptions()
+            .await
+            .unwrap()
+            .is_empty());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_error_body_becomes_api_error() {
+        let mut server = mockito::Server::new_async().await;
+        let id = Uuid::new_v4();
+        server
+            .mock("POST", format!("/dlq/{id}/requeue").as_str())
+            .with_status(404)
+            .with_body(
+                r#"{"error":"Not found","code":"ERR_NOT_FOUND_001","status":404,"detail":"DLQ entry not found"}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = SynapseClient::new(server.url()).with_admin_key("admin-key");
+        match client.requeue_dlq(id).await.unwrap_err() {
+            ClientError::Api {
+                status,
+                code,
+                message,
+            } => {
+                assert_eq!(status, StatusCode::NOT_FOUND);
+                assert_eq!(code.as_deref(), Some("ERR_NOT_FOUND_001"));
+                assert_eq!(message, "DLQ entry not found");
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::db::migrations::MigrationMode;
 use crate::secrets::SecretsManager;
 use anyhow::Result;
 use dotenvy::dotenv;
@@ -125,6 +126,8 @@ pub struct Config {
     pub database_url: String,
     pub database_replica_url: Option<String>,
     pub stellar_horizon_url: String,
+    /// Expected Horizon network passphrase, checked by `--self-check`.
+    pub stellar_network_passphrase: Option<String>,
     pub anchor_webhook_secret: String,
     pub redis_url: String,
     pub default_rate_limit: u32,
@@ -134,6 +137,10 @@ pub struct Config {
     pub allowed_ips: AllowedIps,
     pub backup_dir: String,
     pub backup_encryption_key: Option<String>,
+    /// Id recorded on data keys wrapped by `backup_encryption_key`.
+    pub backup_encryption_key_id: String,
+    /// Retired master keys still accepted for decryption, as `id:key,id:key`.
+    pub backup_previous_encryption_keys: Option<String>,
     pub db_timeouts: DbTimeoutConfig,
     pub otlp_endpoint: Option<String>,
     // CORS
@@ -147,6 +154,9 @@ pub struct Config {
     pub db_statement_timeout_ms: u64,
     pub db_idle_timeout_secs: u64,
     pub db_long_running_statement_timeout_ms: u64,
+    // Migrations
+    pub migration_mode: MigrationMode,
+    pub migration_lock_timeout_secs: u64,
     // Processor pool
     pub processor_workers: usize,
     pub processor_batch_size: u32,
@@ -160,9 +170,12 @@ pub struct Config {
     // Settlement batch limits
     pub settlement_max_batch_size: usize,
     pub settlement_min_tx_count: usize,
+    /// Per-job scheduler settings (`JOB_<NAME>_*`).
+    pub jobs: jobs::JobsConfig,
 }
 
 pub mod assets;
+pub mod jobs;
 impl Config {
     pub async fn load() -> anyhow::Result<Self> {
         // Determine profile before loading env files
@@ -212,6 +225,7 @@ impl Config {
             database_url,
             database_replica_url: env::var("DATABASE_REPLICA_URL").ok(),
             stellar_horizon_url: env::var("STELLAR_HORIZON_URL")?,
+            stellar_network_passphrase: env::var("STELLAR_NETWORK_PASSPHRASE").ok(),
             anchor_webhook_secret,
             redis_url: env::var("REDIS_URL")
                 .unwrap_or_else(|_| "redis://localhost:6379".to_string()),
@@ -226,6 +240,9 @@ impl Config {
             allowed_ips,
             backup_dir: env::var("BACKUP_DIR").unwrap_or_else(|_| "./backups".to_string()),
             backup_encryption_key: env::var("BACKUP_ENCRYPTION_KEY").ok(),
+            backup_encryption_key_id: env::var("BACKUP_ENCRYPTION_KEY_ID")
+                .unwrap_or_else(|_| "1".to_string()),
+            backup_previous_encryption_keys: env::var("BACKUP_PREVIOUS_ENCRYPTION_KEYS").ok(),
             db_timeouts: DbTimeoutConfig {
                 read_query_secs: env::var("DB_TIMEOUT_READ_SECS")
                     .unwrap_or_else(|_| "5".to_string())
@@ -266,6 +283,12 @@ impl Config {
             db_long_running_statement_timeout_ms: env::var("DB_LONG_RUNNING_STATEMENT_TIMEOUT_MS")
                 .unwrap_or_else(|_| "300000".to_string())
                 .parse()?,
+            migration_mode: MigrationMode::parse(
+                &env::var("MIGRATION_MODE").unwrap_or_else(|_| "startup".to_string()),
+            )?,
+            migration_lock_timeout_secs: env::var("MIGRATION_LOCK_TIMEOUT_SECS")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse()?,
             processor_workers: env::var("PROCESSOR_WORKERS")
                 .unwrap_or_else(|_| "4".to_string())
                 .parse()?,
@@ -293,6 +316,7 @@ impl Config {
             settlement_min_tx_count: env::var("SETTLEMENT_MIN_TX_COUNT")
                 .unwrap_or_else(|_| "1".to_string())
                 .parse()?,
+            jobs: jobs::JobsConfig::from_env()?,
         })
     }
 }
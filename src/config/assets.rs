@@ -72,6 +72,8 @@ mod tests {
             asset_issuer: issuer,
             metadata: None,
             enabled: true,
+            min_amount: None,
+            max_amount: None,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         }
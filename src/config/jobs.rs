@@ -0,0 +1,190 @@
+//! Per-job scheduler settings: the `[jobs]` section of the configuration,
+//! read from `JOB_<NAME>_*` environment variables where `<NAME>` is the job's
+//! name in upper case (`JOB_TRANSACTION_PROCESSOR_ENABLED=false`).
+//!
+//! | Variable                  | Effect                                            |
+//! |---------------------------|---------------------------------------------------|
+//! | `JOB_<NAME>_ENABLED`      | `false` to not schedule the job at all            |
+//! | `JOB_<NAME>_SCHEDULE`     | Cron expression replacing the job's built-in one  |
+//! | `JOB_<NAME>_BATCH_SIZE`   | Items per run, for jobs that work in batches      |
+//! | `JOB_<NAME>_CONCURRENCY`  | Runs of the job allowed to overlap (default 1)    |
+//! | `JOB_<NAME>_TIMEOUT_SECS` | Cancel a run after this long (default: the job's) |
+
+use anyhow::Context;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const PREFIX: &str = "JOB_";
+
+/// Settings for one scheduled job.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JobSettings {
+    pub enabled: bool,
+    /// Cron expression overriding [`Job::schedule`](crate::services::scheduler::Job::schedule).
+    pub schedule: Option<String>,
+    pub batch_size: Option<u32>,
+    pub concurrency: usize,
+    /// Overrides [`Job::timeout`](crate::services::scheduler::Job::timeout).
+    pub timeout: Option<Duration>,
+}
+
+impl Default for JobSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            schedule: None,
+            batch_size: None,
+            concurrency: 1,
+            timeout: None,
+        }
+    }
+}
+
+/// Settings for every job that has any configured; other jobs use
+/// [`JobSettings::default`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JobsConfig {
+    jobs: HashMap<String, JobSettings>,
+}
+
+fn parse_bool(var: &str, value: &str) -> anyhow::Result<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "1" => Ok(true),
+        "false" | "0" => Ok(false),
+        _ => anyhow::bail!("{var} must be true or false"),
+    }
+}
+
+impl JobsConfig {
+    pub fn from_env() -> anyhow::Result<Self> {
+        Self::from_vars(std::env::vars())
+    }
+
+    pub fn from_vars(vars: impl IntoIterator<Item = (String, String)>) -> anyhow::Result<Self> {
+        let mut config = Self::default();
+        for (var, value) in vars {
+            let Some(rest) = var.strip_prefix(PREFIX) else {
+                continue;
+            };
+            let (name, field) = if let Some(name) = rest.strip_suffix("_ENABLED") {
+                (name, "enabled")
+            } else if let Some(name) = rest.strip_suffix("_SCHEDULE") {
+                (name, "schedule")
+            } else if let Some(name) = rest.strip_suffix("_BATCH_SIZE") {
+                (name, "batch_size")
+            } else if let Some(name) = rest.strip_suffix("_CONCURRENCY") {
+                (name, "concurrency")
+            } else if let Some(name) = rest.strip_suffix("_TIMEOUT_SECS") {
+                (name, "timeout")
+            } else {
+                continue;
+            };
+            if name.is_empty() {
+                continue;
+            }
+
+            let settings = config.jobs.entry(name.to_ascii_lowercase()).or_default();
+            match field {
+                "enabled" => settings.enabled = parse_bool(&var, &value)?,
+                "schedule" => settings.schedule = Some(value.trim().to_string()),
+                "batch_size" => {
+                    let size: u32 = value
+                        .trim()
+                        .parse()
+                        .with_context(|| format!("{var} must be a positive integer"))?;
+                    if size == 0 {
+                        anyhow::bail!("{var} must be a positive integer");
+                    }
+                    settings.batch_size = Some(size);
+                }
+                "timeout" => {
+                    let secs: u64 = value
+                        .trim()
+                        .parse()
+                        .with_context(|| format!("{var} must be a positive integer"))?;
+                    if secs == 0 {
+                        anyhow::bail!("{var} must be a positive integer");
+                    }
+                    settings.timeout = Some(Duration::from_secs(secs));
+                }
+                _ => {
+                    let concurrency: usize = value
+                        .trim()
+                        .parse()
+                        .with_context(|| format!("{var} must be a positive integer"))?;
+                    if concurrency == 0 {
+                        anyhow::bail!("{var} must be a positive integer");
+                    }
+                    settings.concurrency = concurrency;
+                }
+            }
+        }
+        Ok(config)
+    }
+
+    /// Settings for the job called `name`.
+    pub fn get(&self, name: &str) -> JobSettings {
+        self.jobs.get(name).cloned().unwrap_or_default()
+    }
+
+    /// Replace the settings of the job called `name`.
+    pub fn with_job(mut self, name: impl Into<String>, settings: JobSettings) -> Self {
+        self.jobs.insert(name.into(), settings);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_from_vars_groups_settings_by_job() {
+        let config = JobsConfig::from_vars(vars(&[
+            ("JOB_TRANSACTION_PROCESSOR_ENABLED", "false"),
+            ("JOB_JOB_RUNNER_SCHEDULE", "*/30 * * * * *"),
+            ("JOB_JOB_RUNNER_BATCH_SIZE", "25"),
+            ("JOB_JOB_RUNNER_CONCURRENCY", "2"),
+            ("JOB_JOB_RUNNER_TIMEOUT_SECS", "900"),
+            ("JOB_UNRELATED", "x"),
+            ("DATABASE_URL", "postgres://"),
+        ]))
+        .unwrap();
+
+        assert!(!config.get("transaction_processor").enabled);
+        assert_eq!(
+            config.get("job_runner"),
+            JobSettings {
+                enabled: true,
+                schedule: Some("*/30 * * * * *".to_string()),
+                batch_size: Some(25),
+                concurrency: 2,
+                timeout: Some(Duration::from_secs(900)),
+            }
+        );
+        assert_eq!(config.get("daily_reconciliation"), JobSettings::default());
+    }
+
+    #[test]
+    fn test_from_vars_rejects_invalid_values() {
+        for (var, value) in [
+            ("JOB_X_ENABLED", "maybe"),
+            ("JOB_X_BATCH_SIZE", "0"),
+            ("JOB_X_BATCH_SIZE", "-1"),
+            ("JOB_X_CONCURRENCY", "0"),
+            ("JOB_X_TIMEOUT_SECS", "0"),
+        ] {
+            assert!(
+                JobsConfig::from_vars(vars(&[(var, value)])).is_err(),
+                "{var}={value} should be rejected"
+            );
+        }
+    }
+}
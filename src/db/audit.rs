@@ -8,6 +8,15 @@ use uuid::Uuid;
 /// Entity type constants for audit logs
 pub const ENTITY_TRANSACTION: &str = "transaction";
 pub const ENTITY_SETTLEMENT: &str = "settlement";
+pub const ENTITY_IDEMPOTENCY_KEY: &str = "idempotency_key";
+pub const ENTITY_REFUND: &str = "refund";
+pub const ENTITY_SIGNING_KEY: &str = "signing_key";
+pub const ENTITY_QUARANTINED_PAYMENT: &str = "quarantined_payment";
+pub const ENTITY_STRUCTURING_RULE: &str = "structuring_rule";
+pub const ENTITY_STRUCTURING_REVIEW: &str = "structuring_review";
+pub const ENTITY_REVIEW_ITEM: &str = "review_item";
+pub const ENTITY_APPROVAL: &str = "approval";
+pub const ENTITY_ACCOUNTING_PERIOD: &str = "accounting_period";
 
 /// Represents an audit log entry
 #[derive(Debug, Clone)]
@@ -0,0 +1,156 @@
+//! Mapping of database constraint violations to typed errors.
+//!
+//! The `transactions` table enforces its money invariants in Postgres (see
+//! `migrations/20260610000000_transaction_money_invariants.sql`). When one of
+//! those constraints fires, sqlx surfaces an opaque `sqlx::Error::Database`.
+//! [`classify`] turns it into a [`ConstraintViolation`] so callers can return a
+//! `409 Conflict` / `400 Bad Request` instead of a generic `500`.
+
+/// Guard-table primary key: one active transaction per anchor transaction id.
+pub const UQ_ACTIVE_ANCHOR: &str = "uq_transactions_active_anchor";
+/// `amount > 0`
+pub const CHK_AMOUNT_POSITIVE: &str = "chk_transactions_amount_positive";
+/// `status IN (...)`; superseded by the `transaction_status` enum but still
+/// present on databases rolled back past that migration.
+pub const CHK_STATUS_VALID: &str = "chk_transactions_status_valid";
+/// `anchor_transaction_id` is present and not blank.
+pub const CHK_ANCHOR_ID_PRESENT: &str = "chk_transactions_anchor_id_present";
+/// Raised by the period lock trigger on writes to rows in a closed
+/// accounting period (see `services::accounting`).
+pub const CHK_PERIOD_OPEN: &str = "chk_accounting_period_open";
+/// Postgres enum backing `transactions.status`.
+pub const STATUS_ENUM_TYPE: &str = "transaction_status";
+
+/// SQLSTATE `invalid_text_representation`, raised when a text value does not
+/// parse as an enum label.
+const INVALID_TEXT_REPRESENTATION: &str = "22P02";
+
+/// A database-enforced invariant that rejected a write.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConstraintViolation {
+    /// Another non-failed transaction already exists for this anchor id.
+    DuplicateActiveAnchor(String),
+    /// The amount was zero or negative.
+    NonPositiveAmount,
+    /// The status is not one of the known transaction statuses.
+    InvalidStatus,
+    /// The anchor transaction id was missing.
+    MissingAnchorId,
+    /// The row belongs to a closed accounting period.
+    PeriodClosed(String),
+}
+
+impl std::fmt::Display for ConstraintViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConstraintViolation::DuplicateActiveAnchor(detail) => write!(
+                f,
+                "an active transaction already exists for this anchor_transaction_id ({detail})"
+            ),
+            ConstraintViolation::NonPositiveAmount => write!(f, "amount must be greater than zero"),
+            ConstraintViolation::InvalidStatus => {
+                write!(f, "status is not a valid transaction status")
+            }
+            ConstraintViolation::MissingAnchorId => write!(f, "anchor_transaction_id is required"),
+            ConstraintViolation::PeriodClosed(message) => f.write_str(message),
+        }
+    }
+}
+
+impl ConstraintViolation {
+    /// Map a constraint name (and the server-provided detail, if any) to a violation.
+    pub fn from_constraint(name: &str, detail: Option<&str>) -> Option<Self> {
+        match name {
+            UQ_ACTIVE_ANCHOR => Some(ConstraintViolation::DuplicateActiveAnchor(
+                detail.unwrap_or("duplicate key").to_string(),
+            )),
+            CHK_AMOUNT_POSITIVE => Some(ConstraintViolation::NonPositiveAmount),
+            CHK_STATUS_VALID => Some(ConstraintViolation::InvalidStatus),
+            CHK_ANCHOR_ID_PRESENT => Some(ConstraintViolation::MissingAnchorId),
+            CHK_PERIOD_OPEN => Some(ConstraintViolation::PeriodClosed(format!(
+                "accounting period is closed ({})",
+                detail.unwrap_or("record is immutable")
+            ))),
+            _ => None,
+        }
+    }
+
+    /// Whether this violation represents a conflict with existing data (HTTP 409)
+    /// rather than invalid input (HTTP 400).
+    pub fn is_conflict(&self) -> bool {
+        matches!(
+            self,
+            ConstraintViolation::DuplicateActiveAnchor(_) | ConstraintViolation::PeriodClosed(_)
+        )
+    }
+}
+
+/// Classify a sqlx error as a known constraint violation.
+///
+/// Returns `None` for any error that is not one of the constraints listed in
+/// this module, so callers can fall back to their generic database handling.
+pub fn classify(err: &sqlx::Error) -> Option<ConstraintViolation> {
+    let db_err = match err {
+        sqlx::Error::Database(db_err) => db_err,
+        _ => return None,
+    };
+    if db_err.code().as_deref() == Some(INVALID_TEXT_REPRESENTATION)
+        && db_err.message().contains(STATUS_ENUM_TYPE)
+    {
+        return Some(ConstraintViolation::InvalidStatus);
+    }
+    let name = db_err.constraint()?;
+    if name == CHK_PERIOD_OPEN {
+        // The trigger's message names the period.
+        return Some(ConstraintViolation::PeriodClosed(
+            db_err.message().to_string(),
+        ));
+    }
+    let detail = db_err
+        .try_downcast_ref::<sqlx::postgres::PgDatabaseError>()
+        .and_then(|pg| pg.detail());
+    ConstraintViolation::from_constraint(name, detail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_known_constraints() {
+        assert_eq!(
+            ConstraintViolation::from_constraint(CHK_AMOUNT_POSITIVE, None),
+            Some(ConstraintViolation::NonPositiveAmount)
+        );
+        assert_eq!(
+            ConstraintViolation::from_constraint(CHK_STATUS_VALID, None),
+            Some(ConstraintViolation::InvalidStatus)
+        );
+        assert_eq!(
+            ConstraintViolation::from_constraint(CHK_ANCHOR_ID_PRESENT, None),
+            Some(ConstraintViolation::MissingAnchorId)
+        );
+        let dup = ConstraintViolation::from_constraint(
+            UQ_ACTIVE_ANCHOR,
+            Some("Key (anchor_transaction_id)=(a-1) already exists."),
+        )
+        .unwrap();
+        assert!(dup.is_conflict());
+        assert!(dup.to_string().contains("a-1"));
+
+        let closed =
+            ConstraintViolation::from_constraint(CHK_PERIOD_OPEN, Some("transactions 7f3c"))
+                .unwrap();
+        assert!(closed.is_conflict());
+        assert!(closed.to_string().contains("7f3c"));
+    }
+
+    #[test]
+    fn ignores_unknown_constraints_and_non_database_errors() {
+        assert_eq!(
+            ConstraintViolation::from_constraint("some_other_pkey", None),
+            None
+        );
+        assert_eq!(classify(&sqlx::Error::RowNotFound), None);
+    }
+}
@@ -73,6 +73,12 @@ pub async fn detach_and_archive_old_partitions(
         if let Some((y, m)) = parse_partition_name(&child) {
             let part_date = Utc.with_ymd_and_hms(y, m, 1, 0, 0, 0).single().unwrap();
             if part_date < cutoff {
+                // leave tombstones so change-feed consumers drop these rows
+                let tombstones = format!(
+                    "INSERT INTO transaction_tombstones (transaction_id, tenant_id, reason) \
+                     SELECT id, tenant_id, 'archived' FROM \"{child}\""
+                );
+                sqlx::query(&tombstones).execute(pool).await?;
                 // detach
                 let detach_sql = format!("ALTER TABLE transactions DETACH PARTITION \"{child}\"");
                 sqlx::query(&detach_sql).execute(pool).await?;
@@ -0,0 +1,266 @@
+//! Riding out a Postgres primary failover.
+//!
+//! While the primary fails over, every query errors until the pool's
+//! connections are replaced: sockets to the old primary are dead, or still
+//! open to a node that was demoted to a read-only standby. [`watch`] probes
+//! the primary in the background and, after `DB_FAILOVER_THRESHOLD`
+//! consecutive failed probes:
+//!
+//! 1. marks the instance not ready, so `/ready` answers `503` and the load
+//!    balancer stops routing here (this is not a drain: nothing shuts down);
+//! 2. closes the pools' idle connections after every failed probe, so the
+//!    replacements connect to whichever node is primary now;
+//! 3. keeps probing every `DB_FAILOVER_RECOVERY_INTERVAL_MS` and marks the
+//!    instance ready again once the deep check passes: a connection succeeds
+//!    and `pg_is_in_recovery()` says the node is a writable primary.
+//!
+//! Queries run through [`queries::with_timeout`](super::queries::with_timeout)
+//! call [`report`] on failure, so a connection-level error
+//! ([`is_connection_error`]) wakes the watchdog immediately instead of at the
+//! next scheduled probe.
+//!
+//! | Env var                            | Default | Meaning                           |
+//! |------------------------------------|---------|-----------------------------------|
+//! | `DB_FAILOVER_PROBE_INTERVAL_SECS`  | 5       | Probe interval while reachable    |
+//! | `DB_FAILOVER_RECOVERY_INTERVAL_MS` | 1000    | Probe interval while unreachable  |
+//! | `DB_FAILOVER_PROBE_TIMEOUT_MS`     | 2000    | Deadline for one probe            |
+//! | `DB_FAILOVER_THRESHOLD`            | 2       | Failed probes before not ready    |
+//!
+//! Each flip is logged and counted in `db_failover_transitions_total{to}`.
+
+use crate::metrics;
+use crate::readiness::ReadinessState;
+use opentelemetry::KeyValue;
+use sqlx::{Connection, PgPool};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+const DEFAULT_PROBE_INTERVAL_SECS: u64 = 5;
+const DEFAULT_RECOVERY_INTERVAL_MS: u64 = 1000;
+const DEFAULT_PROBE_TIMEOUT_MS: u64 = 2000;
+const DEFAULT_THRESHOLD: u32 = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailoverConfig {
+    pub probe_interval: Duration,
+    pub recovery_interval: Duration,
+    pub probe_timeout: Duration,
+    pub threshold: u32,
+}
+
+impl FailoverConfig {
+    pub fn from_env() -> Self {
+        let probe_interval_secs = std::env::var("DB_FAILOVER_PROBE_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(DEFAULT_PROBE_INTERVAL_SECS);
+        let recovery_interval_ms = std::env::var("DB_FAILOVER_RECOVERY_INTERVAL_MS")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(DEFAULT_RECOVERY_INTERVAL_MS);
+        let probe_timeout_ms = std::env::var("DB_FAILOVER_PROBE_TIMEOUT_MS")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(DEFAULT_PROBE_TIMEOUT_MS);
+        let threshold = std::env::var("DB_FAILOVER_THRESHOLD")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(DEFAULT_THRESHOLD);
+        Self {
+            probe_interval: Duration::from_secs(probe_interval_secs),
+            recovery_interval: Duration::from_millis(recovery_interval_ms),
+            probe_timeout: Duration::from_millis(probe_timeout_ms),
+            threshold,
+        }
+    }
+}
+
+/// Whether `err` means the connection or server is gone rather than the
+/// query being wrong.
+pub fn is_connection_error(err: &sqlx::Error) -> bool {
+    match err {
+        sqlx::Error::Io(_) | sqlx::Error::Tls(_) | sqlx::Error::PoolTimedOut => true,
+        sqlx::Error::Database(db_err) => db_err
+            .code()
+            .is_some_and(|code| is_connection_sqlstate(&code)),
+        _ => false,
+    }
+}
+
+/// Class 08 (connection exception), 57P01-57P03 (server shutting down or
+/// not yet accepting connections) and 25006 (a write reached a node that was
+/// demoted to read-only).
+fn is_connection_sqlstate(code: &str) -> bool {
+    code.starts_with("08") || matches!(code, "57P01" | "57P02" | "57P03" | "25006")
+}
+
+fn wakeup() -> &'static Notify {
+    static WAKEUP: OnceLock<Notify> = OnceLock::new();
+    WAKEUP.get_or_init(Notify::new)
+}
+
+/// Wake the watchdog if `err` is a connection-level failure.
+pub fn report(err: &sqlx::Error) {
+    if is_connection_error(err) {
+        wakeup().notify_one();
+    }
+}
+
+/// Deep check: the primary accepts a connection and is not a standby.
+async fn probe(pool: &PgPool, timeout: Duration) -> Result<(), String> {
+    let query = sqlx::query_scalar::<_, bool>("SELECT pg_is_in_recovery()").fetch_one(pool);
+    match tokio::time::timeout(timeout, query).await {
+        Ok(Ok(false)) => Ok(()),
+        Ok(Ok(true)) => Err("connected node is a standby in recovery".to_string()),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(format!("probe timed out after {}ms", timeout.as_millis())),
+    }
+}
+
+/// Close the idle connections of `pool`, returning how many were closed.
+pub async fn recycle_idle(pool: &PgPool) -> usize {
+    let mut closed = 0;
+    for _ in 0..pool.num_idle() {
+        let Some(conn) = pool.try_acquire() else {
+            break;
+        };
+        // Best effort: a dead socket cannot be closed cleanly.
+        let _ = conn.detach().close().await;
+        closed += 1;
+    }
+    closed
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Change {
+    Unreachable,
+    Reachable,
+}
+
+/// Consecutive probe failures and whether readiness is currently withheld.
+#[derive(Debug, Default)]
+struct Tracker {
+    failures: u32,
+    down: bool,
+}
+
+impl Tracker {
+    fn observe(&mut self, healthy: bool, threshold: u32) -> Option<Change> {
+        if healthy {
+            self.failures = 0;
+            return std::mem::take(&mut self.down).then_some(Change::Reachable);
+        }
+        self.failures += 1;
+        (!self.down && self.failures >= threshold).then(|| {
+            self.down = true;
+            Change::Unreachable
+        })
+    }
+}
+
+/// Probe the first of `pools` (all point at the primary) until the process
+/// exits, withholding readiness while it is unreachable and recycling every
+/// pool's idle connections after each failed probe.
+pub async fn watch(pools: Vec<PgPool>, readiness: ReadinessState) {
+    let Some(primary) = pools.first().cloned() else {
+        return;
+    };
+    let config = FailoverConfig::from_env();
+    let mut tracker = Tracker::default();
+
+    loop {
+        // Even when woken by a stream of failing queries, probe at most once
+        // per recovery interval.
+        tokio::time::sleep(config.recovery_interval).await;
+        if !tracker.down {
+            tokio::select! {
+                _ = tokio::time::sleep(
+                    config.probe_interval.saturating_sub(config.recovery_interval)
+                ) => {}
+                _ = wakeup().notified() => {}
+            }
+        }
+
+        let result = probe(&primary, config.probe_timeout).await;
+        match tracker.observe(result.is_ok(), config.threshold) {
+            Some(Change::Unreachable) => {
+                readiness.set_db_reachable(false);
+                metrics::db_failover_transitions_total()
+                    .add(1, &[KeyValue::new("to", "unreachable")]);
+                tracing::error!(
+                    failures = tracker.failures,
+                    error = result
+                        .as_ref()
+                        .err()
+                        .map(String::as_str)
+                        .unwrap_or_default(),
+                    "Primary database unreachable; marked not ready until it recovers"
+                );
+            }
+            Some(Change::Reachable) => {
+                readiness.set_db_reachable(true);
+                metrics::db_failover_transitions_total()
+                    .add(1, &[KeyValue::new("to", "reachable")]);
+                tracing::info!("Primary database reachable again; marked ready");
+            }
+            None => {}
+        }
+
+        if let Err(e) = result {
+            let mut closed = 0;
+            for pool in &pools {
+                closed += recycle_idle(pool).await;
+            }
+            crate::sampled_warn!(
+                "db.failover_probe",
+                e,
+                failures = tracker.failures,
+                closed,
+                error = %e,
+                "Database probe failed; recycled idle connections"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracker_flips_after_threshold_and_back() {
+        let mut tracker = Tracker::default();
+        assert_eq!(tracker.observe(false, 2), None);
+        assert_eq!(tracker.observe(false, 2), Some(Change::Unreachable));
+        assert_eq!(tracker.observe(false, 2), None);
+        assert_eq!(tracker.observe(true, 2), Some(Change::Reachable));
+        assert_eq!(tracker.observe(true, 2), None);
+
+        // A single blip below the threshold never flips readiness.
+        assert_eq!(tracker.observe(false, 2), None);
+        assert_eq!(tracker.observe(true, 2), None);
+        assert_eq!(tracker.failures, 0);
+    }
+
+    #[test]
+    fn test_connection_error_classification() {
+        assert!(is_connection_error(&sqlx::Error::PoolTimedOut));
+        assert!(is_connection_error(&sqlx::Error::Io(std::io::Error::from(
+            std::io::ErrorKind::ConnectionReset
+        ))));
+        assert!(!is_connection_error(&sqlx::Error::RowNotFound));
+        assert!(!is_connection_error(&sqlx::Error::PoolClosed));
+
+        for code in ["08006", "08001", "57P01", "25006"] {
+            assert!(is_connection_sqlstate(code), "{code}");
+        }
+        for code in ["23505", "40P01", "42P01"] {
+            assert!(!is_connection_sqlstate(code), "{code}");
+        }
+    }
+}
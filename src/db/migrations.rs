@@ -0,0 +1,257 @@
+//! Schema migrations and the startup schema check.
+//!
+//! `MIGRATION_MODE` decides who applies migrations:
+//!
+//! | Mode       | Behaviour                                                     |
+//! |------------|---------------------------------------------------------------|
+//! | `startup`  | Default. `serve` runs [`run`] before anything else starts     |
+//! | `external` | `serve` never migrates; a `synapse-core db migrate` job does  |
+//!
+//! Either way [`run`] holds a Postgres advisory lock while it migrates, so
+//! replicas starting together (or a migration job overlapping a rollout) take
+//! turns instead of racing, and a replica that waits longer than
+//! `MIGRATION_LOCK_TIMEOUT_SECS` gives up instead of hanging.
+//!
+//! Before serving, [`check_schema_version`] refuses to start if the newest
+//! applied migration is older than [`MIN_SCHEMA_VERSION`], or if a migration
+//! is recorded as failed. Bump [`MIN_SCHEMA_VERSION`] whenever code starts
+//! depending on a new migration, and add a row to the compatibility matrix in
+//! `docs/migration-safety.md`. [`schema_status`] backs `GET /admin/info`.
+
+use anyhow::Context;
+use serde::Serialize;
+use sqlx::migrate::Migrator;
+use sqlx::PgPool;
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+pub const MIGRATIONS_DIR: &str = "./migrations";
+
+/// Oldest schema this binary can serve against: the version of the newest
+/// migration its queries rely on.
+pub const MIN_SCHEMA_VERSION: i64 = 20260725000000;
+
+/// Advisory lock key held while migrating (`syn_mig` in ASCII).
+const MIGRATION_LOCK_KEY: i64 = 0x73796e5f6d6967;
+
+const LOCK_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Who applies migrations, from `MIGRATION_MODE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationMode {
+    /// The server migrates on startup.
+    Startup,
+    /// A separate job or `synapse-core db migrate` migrates; the server only
+    /// checks the schema version.
+    External,
+}
+
+impl MigrationMode {
+    pub fn parse(raw: &str) -> anyhow::Result<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "startup" => Ok(MigrationMode::Startup),
+            "external" => Ok(MigrationMode::External),
+            _ => anyhow::bail!("MIGRATION_MODE must be 'startup' or 'external'"),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MigrationMode::Startup => "startup",
+            MigrationMode::External => "external",
+        }
+    }
+}
+
+/// Apply pending migrations while holding the migration lock, waiting up to
+/// `lock_timeout` for another instance to release it.
+pub async fn run(pool: &PgPool, lock_timeout: Duration) -> anyhow::Result<()> {
+    let migrator = Migrator::new(Path::new(MIGRATIONS_DIR)).await?;
+
+    // Session-level lock, so it must stay on this one connection.
+    let mut conn = pool.acquire().await?;
+    let started = Instant::now();
+    let mut waiting = false;
+    loop {
+        let acquired: bool = sqlx::query_scalar("SELECT pg_try_advisory_lock($1)")
+            .bind(MIGRATION_LOCK_KEY)
+            .fetch_one(&mut *conn)
+            .await?;
+        if acquired {
+            break;
+        }
+        if started.elapsed() >= lock_timeout {
+            anyhow::bail!(
+                "Timed out after {}s waiting for another instance to finish migrating",
+                lock_timeout.as_secs()
+            );
+        }
+        if !waiting {
+            tracing::info!("Waiting for another instance to finish migrating");
+            waiting = true;
+        }
+        tokio::time::sleep(LOCK_POLL_INTERVAL).await;
+    }
+
+    let result = migrator.run(pool).await;
+
+    if let Err(e) = sqlx::query("SELECT pg_advisory_unlock($1)")
+        .bind(MIGRATION_LOCK_KEY)
+        .execute(&mut *conn)
+        .await
+    {
+        // The lock goes with the session; drop the connection so it is freed.
+        tracing::warn!("Failed to release migration lock: {}", e);
+        drop(conn.detach());
+    }
+
+    result?;
+    tracing::info!("Database migrations completed");
+    Ok(())
+}
+
+/// Schema state as reported by `GET /admin/info`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaStatus {
+    /// [`MIN_SCHEMA_VERSION`].
+    pub min_required_version: i64,
+    /// Newest successfully applied migration.
+    pub applied_version: Option<i64>,
+    /// Newest migration shipped in `./migrations` alongside the binary.
+    pub bundled_version: Option<i64>,
+    /// Bundled migrations not yet applied, oldest first.
+    pub pending: Vec<i64>,
+    /// Migrations recorded as failed.
+    pub failed: Vec<i64>,
+    /// Whether this binary can serve against the applied schema.
+    pub compatible: bool,
+}
+
+/// Compare the applied migrations with [`MIN_SCHEMA_VERSION`] and the
+/// bundled ones.
+pub async fn schema_status(pool: &PgPool) -> anyhow::Result<SchemaStatus> {
+    let rows: Vec<(i64, bool)> =
+        sqlx::query_as("SELECT version, success FROM _sqlx_migrations ORDER BY version")
+            .fetch_all(pool)
+            .await
+            .context("Failed to read _sqlx_migrations")?;
+    let bundled: Vec<i64> = Migrator::new(Path::new(MIGRATIONS_DIR))
+        .await?
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+        .map(|m| m.version)
+        .collect();
+    Ok(compare(&rows, &bundled))
+}
+
+fn compare(applied_rows: &[(i64, bool)], bundled: &[i64]) -> SchemaStatus {
+    let applied: BTreeSet<i64> = applied_rows
+        .iter()
+        .filter(|(_, success)| *success)
+        .map(|(version, _)| *version)
+        .collect();
+    let failed: Vec<i64> = applied_rows
+        .iter()
+        .filter(|(_, success)| !success)
+        .map(|(version, _)| *version)
+        .collect();
+    let applied_version = applied.iter().next_back().copied();
+    SchemaStatus {
+        min_required_version: MIN_SCHEMA_VERSION,
+        applied_version,
+        bundled_version: bundled.iter().max().copied(),
+        pending: bundled
+            .iter()
+            .filter(|v| !applied.contains(v))
+            .copied()
+            .collect(),
+        compatible: failed.is_empty() && applied_version.unwrap_or(0) >= MIN_SCHEMA_VERSION,
+        failed,
+    }
+}
+
+/// Newest applied migration version, failing if it is behind
+/// [`MIN_SCHEMA_VERSION`] or any migration is recorded as failed.
+pub async fn check_schema_version(pool: &PgPool) -> anyhow::Result<i64> {
+    ensure_compatible(&schema_status(pool).await?)
+}
+
+fn ensure_compatible(status: &SchemaStatus) -> anyhow::Result<i64> {
+    if let Some(version) = status.failed.first() {
+        anyhow::bail!("Migration {version} is recorded as failed; fix it before serving");
+    }
+    let applied = status.applied_version.unwrap_or(0);
+    if !status.compatible {
+        let missing: Vec<String> = status
+            .pending
+            .iter()
+            .filter(|v| **v <= MIN_SCHEMA_VERSION)
+            .map(i64::to_string)
+            .collect();
+        anyhow::bail!(
+            "Schema version {applied} is behind the minimum {MIN_SCHEMA_VERSION} this binary \
+             needs (missing: {}); run `synapse-core db migrate` first",
+            missing.join(", ")
+        );
+    }
+    Ok(applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_min_schema_version_names_a_migration() {
+        let prefix = format!("{MIN_SCHEMA_VERSION}_");
+        let found = std::fs::read_dir(MIGRATIONS_DIR)
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .any(|entry| entry.file_name().to_string_lossy().starts_with(&prefix));
+        assert!(found, "no migration named {prefix}*");
+    }
+
+    #[test]
+    fn test_compare_with_applied_migrations() {
+        let before = MIN_SCHEMA_VERSION - 1;
+        let after = MIN_SCHEMA_VERSION + 1;
+        let bundled = [before, MIN_SCHEMA_VERSION, after];
+
+        let status = compare(&[(before, true), (MIN_SCHEMA_VERSION, true)], &bundled);
+        assert!(status.compatible);
+        assert_eq!(status.pending, vec![after]);
+        assert_eq!(ensure_compatible(&status).unwrap(), MIN_SCHEMA_VERSION);
+
+        let status = compare(&[(before, true)], &bundled);
+        assert!(!status.compatible);
+        assert_eq!(status.applied_version, Some(before));
+        let err = ensure_compatible(&status).unwrap_err().to_string();
+        assert!(
+            err.contains(&format!("missing: {MIN_SCHEMA_VERSION})")),
+            "{err}"
+        );
+
+        let status = compare(&[(before, true), (MIN_SCHEMA_VERSION, false)], &bundled);
+        assert!(!status.compatible);
+        assert!(ensure_compatible(&status)
+            .unwrap_err()
+            .to_string()
+            .contains("failed"));
+
+        assert!(!compare(&[], &bundled).compatible);
+    }
+
+    #[test]
+    fn test_parse_migration_mode() {
+        assert_eq!(
+            MigrationMode::parse(" External ").unwrap(),
+            MigrationMode::External
+        );
+        assert_eq!(
+            MigrationMode::parse("startup").unwrap(),
+            MigrationMode::Startup
+        );
+        assert!(MigrationMode::parse("job").is_err());
+    }
+}
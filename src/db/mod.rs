@@ -39,6 +39,8 @@
 //!   [`crate::utils::retry::retry_with_backoff`]
 //! - Connection pool warm-up is fail-open: startup detects failures without
 //!   making pool creation brittle
+//! - A primary failover is ridden out by [`failover::watch`], which withholds
+//!   readiness and recycles pooled connections until the primary is back
 //! - See [database reconnection logic](../../docs/database-reconnection-logic.md)
 //!   for design, security, and recovery guidance
 
@@ -48,7 +50,10 @@ use std::sync::Arc;
 use std::time::Duration;
 
 pub mod audit;
+pub mod constraints;
 pub mod cron;
+pub mod failover;
+pub mod migrations;
 pub mod models;
 pub mod partition;
 pub mod pool_manager;
@@ -1,12 +1,20 @@
-use chrono::{DateTime, Utc};
+use crate::domain::StellarAddress;
+#[cfg(feature = "graphql")]
+use crate::graphql::pii;
+#[cfg(feature = "graphql")]
+use crate::graphql::scalars::{DateTimeScalar, DecimalScalar, StellarAccount, UuidScalar};
+use crate::stellar::TransactionRecord;
+use bigdecimal::ToPrimitive;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::types::BigDecimal;
 use sqlx::FromRow;
 use std::str::FromStr;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
-#[sqlx(type_name = "text")]
+/// Lifecycle status of a transaction, stored as the Postgres `transaction_status` enum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "transaction_status", rename_all = "lowercase")]
 pub enum TransactionStatus {
     #[serde(rename = "pending")]
     Pending,
@@ -16,19 +24,37 @@ pub enum TransactionStatus {
     Completed,
     #[serde(rename = "failed")]
     Failed,
+    #[serde(rename = "dlq")]
+    Dlq,
+    /// Below the asset's `min_amount`; awaiting refund to the sender.
+    #[serde(rename = "refund_pending")]
+    RefundPending,
+    /// Above the asset's `max_amount`; held until compliance approves it.
+    #[serde(rename = "compliance_review")]
+    ComplianceReview,
 }
 
-impl std::fmt::Display for TransactionStatus {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl TransactionStatus {
+    /// Wire representation, identical to the Postgres enum label.
+    pub fn as_str(&self) -> &'static str {
         match self {
-            TransactionStatus::Pending => write!(f, "pending"),
-            TransactionStatus::Processing => write!(f, "processing"),
-            TransactionStatus::Completed => write!(f, "completed"),
-            TransactionStatus::Failed => write!(f, "failed"),
+            TransactionStatus::Pending => "pending",
+            TransactionStatus::Processing => "processing",
+            TransactionStatus::Completed => "completed",
+            TransactionStatus::Failed => "failed",
+            TransactionStatus::Dlq => "dlq",
+            TransactionStatus::RefundPending => "refund_pending",
+            TransactionStatus::ComplianceReview => "compliance_review",
         }
     }
 }
 
+impl std::fmt::Display for TransactionStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 impl FromStr for TransactionStatus {
     type Err = String;
 
@@ -38,19 +64,41 @@ impl FromStr for TransactionStatus {
             "processing" => Ok(TransactionStatus::Processing),
             "completed" => Ok(TransactionStatus::Completed),
             "failed" => Ok(TransactionStatus::Failed),
+            "dlq" => Ok(TransactionStatus::Dlq),
+            "refund_pending" => Ok(TransactionStatus::RefundPending),
+            "compliance_review" => Ok(TransactionStatus::ComplianceReview),
             _ => Err(format!("Invalid transaction status: {}", s)),
         }
     }
 }
 
-#[derive(Debug, FromRow, Serialize, Deserialize, Clone)]
+// Compatibility with code (and tests) that still compare against string labels.
+impl PartialEq<str> for TransactionStatus {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for TransactionStatus {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for TransactionStatus {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+#[derive(FromRow, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "snake_case")]
 pub struct Transaction {
     pub id: Uuid,
     pub stellar_account: String,
     pub amount: BigDecimal,
     pub asset_code: String,
-    pub status: String,
+    pub status: TransactionStatus,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub anchor_transaction_id: Option<String>,
@@ -61,30 +109,119 @@ pub struct Transaction {
     pub memo_type: Option<String>,
     pub metadata: Option<serde_json::Value>,
     pub trace_id: Option<String>,
+    /// Imported from Horizon payment history by a `horizon_backfill` job.
+    #[serde(default)]
+    pub backfilled: bool,
+    /// Stellar network transaction settling this deposit; completion waits
+    /// until Horizon reports it successful.
+    #[serde(default)]
+    pub stellar_tx_hash: Option<String>,
+    /// Ledger that included `stellar_tx_hash`, when known.
+    #[serde(default)]
+    pub ledger: Option<i64>,
+    /// Close time of that ledger.
+    #[serde(default)]
+    pub closed_at: Option<DateTime<Utc>>,
+    /// Muxed account id when the sender address was `M...`; `stellar_account`
+    /// then holds its base `G...` account.
+    #[serde(default)]
+    pub stellar_muxed_id: Option<BigDecimal>,
+    /// Risk score from 0 to 100, set by the processor's
+    /// [`RiskScorer`](crate::ports::RiskScorer) when it first picks the
+    /// deposit up.
+    #[serde(default)]
+    pub risk_score: Option<i16>,
+    /// Why the scorer gave `risk_score`.
+    #[serde(default)]
+    pub risk_reasons: Option<Vec<String>>,
+    /// Fee charged on the transaction, in `asset_code`, when the partner
+    /// reported one.
+    #[serde(default)]
+    pub fee_amount: Option<BigDecimal>,
+}
+
+/// Partner metadata is redacted so `{:?}` in logs never exposes its values.
+impl std::fmt::Debug for Transaction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Transaction")
+            .field("id", &self.id)
+            .field("stellar_account", &self.stellar_account)
+            .field("amount", &self.amount)
+            .field("asset_code", &self.asset_code)
+            .field("status", &self.status)
+            .field("created_at", &self.created_at)
+            .field("updated_at", &self.updated_at)
+            .field("anchor_transaction_id", &self.anchor_transaction_id)
+            .field("callback_type", &self.callback_type)
+            .field("callback_status", &self.callback_status)
+            .field("settlement_id", &self.settlement_id)
+            .field("memo", &self.memo)
+            .field("memo_type", &self.memo_type)
+            .field(
+                "metadata",
+                &self
+                    .metadata
+                    .as_ref()
+                    .map(crate::utils::sanitize::redact_metadata),
+            )
+            .field("trace_id", &self.trace_id)
+            .field("backfilled", &self.backfilled)
+            .field("stellar_tx_hash", &self.stellar_tx_hash)
+            .field("ledger", &self.ledger)
+            .field("closed_at", &self.closed_at)
+            .field("stellar_muxed_id", &self.stellar_muxed_id)
+            .field("risk_score", &self.risk_score)
+            .field("risk_reasons", &self.risk_reasons)
+            .field("fee_amount", &self.fee_amount)
+            .finish()
+    }
+}
+
+impl From<&Transaction> for crate::domain::Transaction {
+    fn from(tx: &Transaction) -> Self {
+        Self {
+            id: tx.id,
+            stellar_account: tx.stellar_account.clone(),
+            amount: tx.amount.clone(),
+            asset_code: tx.asset_code.clone(),
+            status: tx.status.as_str().to_string(),
+            created_at: tx.created_at,
+            updated_at: tx.updated_at,
+            anchor_transaction_id: tx.anchor_transaction_id.clone(),
+            callback_type: tx.callback_type.clone(),
+            callback_status: tx.callback_status.clone(),
+            memo: tx.memo.clone(),
+            memo_type: tx.memo_type.clone(),
+            metadata: tx.metadata.clone(),
+        }
+    }
 }
 
+#[cfg(feature = "graphql")]
 #[async_graphql::Object]
 impl Transaction {
-    async fn id(&self) -> String {
-        self.id.to_string()
+    async fn id(&self) -> UuidScalar {
+        self.id.into()
     }
-    async fn stellar_account(&self) -> &str {
-        &self.stellar_account
+    /// Masked unless the caller may see PII (see [`crate::graphql::pii`]).
+    async fn stellar_account(&self, ctx: &async_graphql::Context<'_>) -> StellarAccount {
+        StellarAccount::from_stored(pii::mask_str(ctx, &self.stellar_account))
     }
-    async fn amount(&self) -> String {
-        self.amount.to_string()
+    async fn amount(&self) -> DecimalScalar {
+        self.amount.clone().into()
     }
     async fn asset_code(&self) -> &str {
         &self.asset_code
     }
+    /// Exposed as the plain status label so existing GraphQL clients are unaffected.
     async fn status(&self) -> &str {
-        &self.status
+        self.status.as_str()
     }
-    async fn created_at(&self) -> DateTime<Utc> {
-        self.created_at
+    async fn created_at(&self) -> DateTimeScalar {
+        self.created_at.into()
     }
-    async fn updated_at(&self) -> DateTime<Utc> {
-        self.updated_at
+    async fn updated_at(&self) -> DateTimeScalar {
+        self.updated_at.into()
     }
     async fn anchor_transaction_id(&self) -> Option<&str> {
         self.anchor_transaction_id.as_deref()
@@ -95,8 +232,8 @@ impl Transaction {
     async fn callback_status(&self) -> Option<&str> {
         self.callback_status.as_deref()
     }
-    async fn settlement_id(&self) -> Option<String> {
-        self.settlement_id.map(|id| id.to_string())
+    async fn settlement_id(&self) -> Option<UuidScalar> {
+        self.settlement_id.map(UuidScalar)
     }
     async fn memo(&self) -> Option<&str> {
         self.memo.as_deref()
@@ -104,6 +241,51 @@ impl Transaction {
     async fn memo_type(&self) -> Option<&str> {
         self.memo_type.as_deref()
     }
+    /// Partner-supplied metadata (JSON object); values are masked unless the
+    /// caller may see PII.
+    async fn metadata(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+    ) -> Option<async_graphql::Json<serde_json::Value>> {
+        self.metadata
+            .as_ref()
+            .map(|m| async_graphql::Json(pii::mask_json(ctx, m)))
+    }
+    /// True when imported from Horizon payment history.
+    async fn backfilled(&self) -> bool {
+        self.backfilled
+    }
+    /// Hash of the Stellar transaction that settled this deposit.
+    async fn stellar_tx_hash(&self) -> Option<&str> {
+        self.stellar_tx_hash.as_deref()
+    }
+    /// Ledger sequence that included the Stellar transaction.
+    async fn ledger(&self) -> Option<i64> {
+        self.ledger
+    }
+    async fn closed_at(&self) -> Option<DateTimeScalar> {
+        self.closed_at.map(Into::into)
+    }
+    /// Muxed account id, as a decimal string (ids exceed GraphQL `Int`).
+    async fn stellar_muxed_id(&self) -> Option<String> {
+        self.stellar_muxed_id.as_ref().map(ToString::to_string)
+    }
+    /// The `M...` address the deposit was sent from, when it was muxed.
+    /// Masked unless the caller may see PII.
+    async fn muxed_account(&self, ctx: &async_graphql::Context<'_>) -> Option<String> {
+        self.muxed_account().map(|m| pii::mask_str(ctx, &m))
+    }
+    /// Risk score from 0 to 100; null until the processor has scored it.
+    async fn risk_score(&self) -> Option<i32> {
+        self.risk_score.map(i32::from)
+    }
+    async fn risk_reasons(&self) -> Option<Vec<String>> {
+        self.risk_reasons.clone()
+    }
+    /// Fee charged on the transaction, in its asset.
+    async fn fee_amount(&self) -> Option<DecimalScalar> {
+        self.fee_amount.clone().map(Into::into)
+    }
 }
 
 impl Transaction {
@@ -124,7 +306,7 @@ impl Transaction {
             stellar_account,
             amount,
             asset_code,
-            status: TransactionStatus::Pending.to_string(),
+            status: TransactionStatus::Pending,
             created_at: Utc::now(),
             updated_at: Utc::now(),
             anchor_transaction_id,
@@ -135,6 +317,14 @@ impl Transaction {
             memo_type,
             metadata,
             trace_id: None,
+            backfilled: false,
+            stellar_tx_hash: None,
+            ledger: None,
+            closed_at: None,
+            stellar_muxed_id: None,
+            risk_score: None,
+            risk_reasons: None,
+            fee_amount: None,
         }
     }
 
@@ -142,6 +332,58 @@ impl Transaction {
         self.trace_id = trace_id;
         self
     }
+
+    /// The sender as an `M...` address, rebuilt from `stellar_account` and
+    /// `stellar_muxed_id`; `None` for plain accounts.
+    pub fn muxed_account(&self) -> Option<String> {
+        let id = self.stellar_muxed_id.as_ref()?.to_u64()?;
+        StellarAddress::from_parts(&self.stellar_account, Some(id))
+            .ok()
+            .map(|address| address.to_string())
+    }
+
+    /// Record the muxed id of an `M...` sender address.
+    pub fn with_muxed_id(mut self, muxed_id: Option<u64>) -> Self {
+        self.stellar_muxed_id = muxed_id.map(BigDecimal::from);
+        self
+    }
+
+    pub fn with_fee_amount(mut self, fee_amount: Option<BigDecimal>) -> Self {
+        self.fee_amount = fee_amount;
+        self
+    }
+
+    pub fn with_stellar_tx_hash(mut self, stellar_tx_hash: Option<String>) -> Self {
+        self.stellar_tx_hash = stellar_tx_hash;
+        self
+    }
+
+    /// Use the transaction's own id as its anchor transaction id, for
+    /// transactions this anchor opens itself (SEP-6, SEP-24, SEP-31), where
+    /// the two are the same.
+    pub fn with_own_anchor_id(mut self) -> Self {
+        self.anchor_transaction_id = Some(self.id.to_string());
+        self
+    }
+
+    /// Shallow-merge `patch` into this transaction's metadata: keys in
+    /// `patch` overwrite existing ones and a `null` value removes the key.
+    pub fn merged_metadata(&self, patch: &serde_json::Value) -> serde_json::Value {
+        let mut merged = match &self.metadata {
+            Some(serde_json::Value::Object(map)) => map.clone(),
+            _ => serde_json::Map::new(),
+        };
+        if let serde_json::Value::Object(changes) = patch {
+            for (key, value) in changes {
+                if value.is_null() {
+                    merged.remove(key);
+                } else {
+                    merged.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        serde_json::Value::Object(merged)
+    }
 }
 
 #[derive(Debug, FromRow, Serialize, Deserialize, Clone)]
@@ -159,36 +401,41 @@ pub struct Settlement {
     pub original_total_amount: Option<BigDecimal>,
     pub reviewed_by: Option<String>,
     pub reviewed_at: Option<DateTime<Utc>>,
+    /// What the settled SEP-38 quoted transactions pay out at their locked-in
+    /// rates, per buy asset (see [`crate::services::quotes::payouts`]).
+    #[serde(default)]
+    pub quoted_payouts: Option<serde_json::Value>,
 }
 
+#[cfg(feature = "graphql")]
 #[async_graphql::Object]
 impl Settlement {
-    async fn id(&self) -> String {
-        self.id.to_string()
+    async fn id(&self) -> UuidScalar {
+        self.id.into()
     }
     async fn asset_code(&self) -> &str {
         &self.asset_code
     }
-    async fn total_amount(&self) -> String {
-        self.total_amount.to_string()
+    async fn total_amount(&self) -> DecimalScalar {
+        self.total_amount.clone().into()
     }
     async fn tx_count(&self) -> i32 {
         self.tx_count
     }
-    async fn period_start(&self) -> DateTime<Utc> {
-        self.period_start
+    async fn period_start(&self) -> DateTimeScalar {
+        self.period_start.into()
     }
-    async fn period_end(&self) -> DateTime<Utc> {
-        self.period_end
+    async fn period_end(&self) -> DateTimeScalar {
+        self.period_end.into()
     }
     async fn status(&self) -> &str {
         &self.status
     }
-    async fn created_at(&self) -> DateTime<Utc> {
-        self.created_at
+    async fn created_at(&self) -> DateTimeScalar {
+        self.created_at.into()
     }
-    async fn updated_at(&self) -> DateTime<Utc> {
-        self.updated_at
+    async fn updated_at(&self) -> DateTimeScalar {
+        self.updated_at.into()
     }
 }
 
@@ -201,6 +448,11 @@ pub struct TransactionDlq {
     pub asset_code: String,
     pub anchor_transaction_id: Option<String>,
     pub error_reason: String,
+    /// See [`crate::services::dlq_errors::ErrorClass`].
+    pub error_class: String,
+    /// Hash of the normalised `error_reason`; `NULL` on rows older than the
+    /// classification.
+    pub error_signature: Option<String>,
     pub stack_trace: Option<String>,
     pub retry_count: i32,
     pub original_created_at: DateTime<Utc>,
@@ -258,6 +510,61 @@ mod tests {
         pool
     }
 
+    #[test]
+    fn test_merged_metadata_overwrites_and_removes_keys() {
+        let tx = Transaction::new(
+            "GABCDEF".to_string(),
+            BigDecimal::from(1),
+            "USD".to_string(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(serde_json::json!({"order_id": "1", "user_id": "u-1"})),
+        );
+        let merged = tx.merged_metadata(&serde_json::json!({"order_id": "2", "user_id": null}));
+        assert_eq!(merged, serde_json::json!({"order_id": "2"}));
+    }
+
+    #[test]
+    fn test_muxed_account_rebuilt_from_parts() {
+        let tx = Transaction::new(
+            "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ".to_string(),
+            BigDecimal::from(1),
+            "USD".to_string(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        assert_eq!(tx.muxed_account(), None);
+        assert_eq!(
+            tx.with_muxed_id(Some(1234)).muxed_account().as_deref(),
+            Some("MA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJUAAAAAAAAAAE2JUG6")
+        );
+    }
+
+    #[test]
+    fn test_debug_redacts_metadata() {
+        let tx = Transaction::new(
+            "GABCDEF".to_string(),
+            BigDecimal::from(1),
+            "USD".to_string(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(serde_json::json!({"email": "a@example.com"})),
+        );
+        let debug = format!("{tx:?}");
+        assert!(!debug.contains("a@example.com"));
+        assert!(debug.contains("email"));
+    }
+
     #[ignore = "Requires DATABASE_URL / Redis"]
     #[tokio::test]
     async fn test_insert_and_query_transaction() {
@@ -331,7 +638,7 @@ mod tests {
             "GABCDEF".to_string(),
             BigDecimal::from(100),
             "USD".to_string(),
-            None,
+            Some(Uuid::new_v4().to_string()),
             None,
             None,
             None,
@@ -352,7 +659,7 @@ mod tests {
             "GABCDEF".to_string(),
             BigDecimal::from(100),
             "USD".to_string(),
-            None,
+            Some(Uuid::new_v4().to_string()),
             None,
             None,
             None,
@@ -377,7 +684,7 @@ mod tests {
                 format!("GABCDEF_{}", i),
                 BigDecimal::from(100 + i),
                 "USD".to_string(),
-                None,
+                Some(Uuid::new_v4().to_string()),
                 None,
                 None,
                 None,
@@ -417,6 +724,10 @@ pub struct Asset {
     pub asset_issuer: Option<String>,
     pub metadata: Option<serde_json::Value>,
     pub enabled: bool,
+    /// Deposits below this amount are routed to `refund_pending`.
+    pub min_amount: Option<BigDecimal>,
+    /// Deposits above this amount are routed to `compliance_review`.
+    pub max_amount: Option<BigDecimal>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -424,7 +735,7 @@ pub struct Asset {
 impl Asset {
     /// Fetch all assets from the database.
     pub async fn fetch_all(pool: &sqlx::PgPool) -> Result<Vec<Self>, sqlx::Error> {
-        sqlx::query_as::<_, Self>("SELECT id, asset_code, asset_issuer, metadata, enabled, created_at, updated_at FROM assets ORDER BY asset_code")
+        sqlx::query_as::<_, Self>("SELECT id, asset_code, asset_issuer, metadata, enabled, min_amount, max_amount, created_at, updated_at FROM assets ORDER BY asset_code")
             .fetch_all(pool)
             .await
     }
@@ -439,4 +750,680 @@ impl Asset {
         .await?;
         Ok(exists)
     }
+
+    /// Issuer of `code`, when exactly one enabled asset has that code.
+    pub async fn sole_issuer(
+        pool: &sqlx::PgPool,
+        code: &str,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let issuers: Vec<String> = sqlx::query_scalar(
+            "SELECT asset_issuer FROM assets \
+             WHERE asset_code = $1 AND enabled = TRUE AND asset_issuer IS NOT NULL LIMIT 2",
+        )
+        .bind(code)
+        .fetch_all(pool)
+        .await?;
+        Ok(match issuers.as_slice() {
+            [issuer] => Some(issuer.clone()),
+            _ => None,
+        })
+    }
+}
+
+/// Entry of the SEP-6 asset registry (`sep6_assets`), with the amount limits
+/// of the matching enabled `assets` rows.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Sep6Asset {
+    pub asset_code: String,
+    pub deposit_enabled: bool,
+    pub withdraw_enabled: bool,
+    pub fee_fixed: BigDecimal,
+    pub fee_percent: BigDecimal,
+    pub deposit_instructions: String,
+    pub withdraw_types: Vec<String>,
+    pub min_amount: Option<BigDecimal>,
+    pub max_amount: Option<BigDecimal>,
+}
+
+impl Sep6Asset {
+    const SELECT: &'static str = r#"
+        SELECT r.asset_code, r.deposit_enabled, r.withdraw_enabled, r.fee_fixed,
+               r.fee_percent, r.deposit_instructions, r.withdraw_types,
+               (SELECT MAX(a.min_amount) FROM assets a
+                WHERE a.asset_code = r.asset_code AND a.enabled = TRUE) AS min_amount,
+               (SELECT MIN(a.max_amount) FROM assets a
+                WHERE a.asset_code = r.asset_code AND a.enabled = TRUE) AS max_amount
+        FROM sep6_assets r
+        WHERE EXISTS (
+            SELECT 1 FROM assets a WHERE a.asset_code = r.asset_code AND a.enabled = TRUE
+        )
+    "#;
+
+    /// Every asset offered over SEP-6, by code.
+    pub async fn fetch_all(pool: &sqlx::PgPool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(&format!("{} ORDER BY r.asset_code", Self::SELECT))
+            .fetch_all(pool)
+            .await
+    }
+
+    /// The registry entry of `code`, if it is offered over SEP-6.
+    pub async fn get(pool: &sqlx::PgPool, code: &str) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(&format!("{} AND r.asset_code = $1", Self::SELECT))
+            .bind(code)
+            .fetch_optional(pool)
+            .await
+    }
+}
+
+/// Entry of the SEP-38 rate card (`sep38_prices`): the price of one unit of
+/// `buy_asset` in `sell_asset`, before `fee_percent`.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Sep38Price {
+    pub sell_asset: String,
+    pub buy_asset: String,
+    pub price: BigDecimal,
+    pub fee_percent: BigDecimal,
+}
+
+impl Sep38Price {
+    /// The whole rate card.
+    pub async fn fetch_all(pool: &sqlx::PgPool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT sell_asset, buy_asset, price, fee_percent FROM sep38_prices \
+             ORDER BY sell_asset, buy_asset",
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The price of `buy_asset` in `sell_asset`, if one is offered.
+    pub async fn get(
+        pool: &sqlx::PgPool,
+        sell_asset: &str,
+        buy_asset: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT sell_asset, buy_asset, price, fee_percent FROM sep38_prices \
+             WHERE sell_asset = $1 AND buy_asset = $2",
+        )
+        .bind(sell_asset)
+        .bind(buy_asset)
+        .fetch_optional(pool)
+        .await
+    }
+}
+
+/// Row in `sep38_quotes`: a firm SEP-38 quote for `account`.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Sep38Quote {
+    pub id: Uuid,
+    pub account: String,
+    pub sell_asset: String,
+    pub sell_amount: BigDecimal,
+    pub buy_asset: String,
+    pub buy_amount: BigDecimal,
+    pub price: BigDecimal,
+    pub total_price: BigDecimal,
+    /// In `sell_asset`.
+    pub fee_total: BigDecimal,
+    pub expires_at: DateTime<Utc>,
+    /// The transaction this quote backs, once one was created with it.
+    pub transaction_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Sep38Quote {
+    pub async fn insert(&self, pool: &sqlx::PgPool) -> Result<Self, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            r#"
+            INSERT INTO sep38_quotes (
+                id, account, sell_asset, sell_amount, buy_asset, buy_amount,
+                price, total_price, fee_total, expires_at, created_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING *
+            "#,
+        )
+        .bind(self.id)
+        .bind(&self.account)
+        .bind(&self.sell_asset)
+        .bind(&self.sell_amount)
+        .bind(&self.buy_asset)
+        .bind(&self.buy_amount)
+        .bind(&self.price)
+        .bind(&self.total_price)
+        .bind(&self.fee_total)
+        .bind(self.expires_at)
+        .bind(self.created_at)
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn get(pool: &sqlx::PgPool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>("SELECT * FROM sep38_quotes WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Claim quote `id` for `transaction_id`. `false` when the quote is
+    /// already backing a transaction or expired before `now`.
+    pub async fn link_transaction(
+        pool: &sqlx::PgPool,
+        id: Uuid,
+        transaction_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE sep38_quotes SET transaction_id = $2 \
+             WHERE id = $1 AND transaction_id IS NULL AND expires_at > $3",
+        )
+        .bind(id)
+        .bind(transaction_id)
+        .bind(now)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Release quote `id` from `transaction_id`, e.g. when recording the
+    /// transaction failed.
+    pub async fn unlink_transaction(
+        pool: &sqlx::PgPool,
+        id: Uuid,
+        transaction_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE sep38_quotes SET transaction_id = NULL \
+             WHERE id = $1 AND transaction_id = $2",
+        )
+        .bind(id)
+        .bind(transaction_id)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// The quotes backing any of `transaction_ids`.
+    pub async fn for_transactions(
+        executor: impl sqlx::PgExecutor<'_>,
+        transaction_ids: &[Uuid],
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>("SELECT * FROM sep38_quotes WHERE transaction_id = ANY($1)")
+            .bind(transaction_ids)
+            .fetch_all(executor)
+            .await
+    }
+}
+
+/// Row in `submissions`: one attempt at submitting a transaction the anchor
+/// signed (see [`crate::services::submissions`]).
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Submission {
+    pub id: Uuid,
+    /// What the submission pays out, e.g. a settlement id.
+    pub transaction_id: Uuid,
+    pub attempt: i32,
+    pub hash: String,
+    pub envelope_xdr: String,
+    /// `pending`, `succeeded`, `failed` or `expired`.
+    pub status: String,
+    pub ledger: Option<i64>,
+    pub result: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Submission {
+    /// Record a new `pending` attempt. `None` when `(transaction_id,
+    /// attempt)` is already taken, i.e. another attempt raced this one.
+    pub async fn insert(
+        pool: &sqlx::PgPool,
+        transaction_id: Uuid,
+        attempt: i32,
+        hash: &str,
+        envelope_xdr: &str,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            r#"
+            INSERT INTO submissions (transaction_id, attempt, hash, envelope_xdr, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (transaction_id, attempt) DO NOTHING
+            RETURNING *
+            "#,
+        )
+        .bind(transaction_id)
+        .bind(attempt)
+        .bind(hash)
+        .bind(envelope_xdr)
+        .bind(expires_at)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// The latest attempt for `transaction_id`.
+    pub async fn latest(
+        pool: &sqlx::PgPool,
+        transaction_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT * FROM submissions WHERE transaction_id = $1 \
+             ORDER BY attempt DESC LIMIT 1",
+        )
+        .bind(transaction_id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Attempts whose outcome is not known yet, oldest first.
+    pub async fn pending(pool: &sqlx::PgPool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT * FROM submissions WHERE status = 'pending' ORDER BY created_at",
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Settle a `pending` attempt. `None` when it was no longer pending, so
+    /// a known outcome is never overwritten.
+    pub async fn resolve(
+        pool: &sqlx::PgPool,
+        id: Uuid,
+        status: &str,
+        ledger: Option<i64>,
+        result: Option<&str>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            r#"
+            UPDATE submissions
+            SET status = $2, ledger = $3, result = $4, updated_at = NOW()
+            WHERE id = $1 AND status = 'pending'
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(status)
+        .bind(ledger)
+        .bind(result)
+        .fetch_optional(pool)
+        .await
+    }
+}
+
+/// Row in `claimable_balances`: a claimable balance for one of the
+/// distribution accounts (see [`crate::services::claimable_balances`]).
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ClaimableBalance {
+    pub id: Uuid,
+    pub balance_id: String,
+    pub claimant: String,
+    pub sponsor: Option<String>,
+    pub asset_code: String,
+    /// `None` for native XLM.
+    pub asset_issuer: Option<String>,
+    pub amount: BigDecimal,
+    /// When the claimant may claim, as Horizon reports the predicate.
+    pub predicate: serde_json::Value,
+    /// `detected`, `claimed` or `gone`.
+    pub status: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub claim_tx_hash: Option<String>,
+    /// The deposit recorded for the claim.
+    pub transaction_id: Option<Uuid>,
+    pub detected_at: DateTime<Utc>,
+    pub claimed_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ClaimableBalance {
+    /// Record a newly seen balance; `id`, `status` and the claim fields are
+    /// left to the database. `false` when it was already known.
+    pub async fn insert_detected(&self, pool: &sqlx::PgPool) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO claimable_balances
+                (balance_id, claimant, sponsor, asset_code, asset_issuer, amount, predicate)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (balance_id) DO NOTHING
+            "#,
+        )
+        .bind(&self.balance_id)
+        .bind(&self.claimant)
+        .bind(&self.sponsor)
+        .bind(&self.asset_code)
+        .bind(&self.asset_issuer)
+        .bind(&self.amount)
+        .bind(&self.predicate)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Balances still to claim, oldest first.
+    pub async fn detected(pool: &sqlx::PgPool, limit: i64) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT * FROM claimable_balances WHERE status = 'detected' \
+             ORDER BY detected_at LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Count a failed claim; with `gone` the balance no longer exists and is
+    /// not tried again.
+    pub async fn record_failure(
+        pool: &sqlx::PgPool,
+        id: Uuid,
+        error: &str,
+        gone: bool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE claimable_balances
+            SET attempts = attempts + 1, last_error = $2,
+                status = CASE WHEN $3 THEN 'gone' ELSE status END, updated_at = NOW()
+            WHERE id = $1 AND status = 'detected'
+            "#,
+        )
+        .bind(id)
+        .bind(error)
+        .bind(gone)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+/// Row in `stellar_transactions`: the XDR of a Stellar transaction in a
+/// ledger, as Horizon reported it.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct StellarTransaction {
+    pub hash: String,
+    pub ledger: i64,
+    pub successful: bool,
+    pub envelope_xdr: String,
+    pub result_xdr: Option<String>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl StellarTransaction {
+    pub fn new(
+        hash: String,
+        ledger: i64,
+        successful: bool,
+        envelope_xdr: String,
+        result_xdr: Option<String>,
+    ) -> Self {
+        Self {
+            hash,
+            ledger,
+            successful,
+            envelope_xdr,
+            result_xdr,
+            recorded_at: Utc::now(),
+        }
+    }
+
+    /// From Horizon's record; `None` when it carries no envelope.
+    pub fn from_horizon(record: &TransactionRecord) -> Option<Self> {
+        Some(Self::new(
+            record.hash.clone(),
+            record.ledger,
+            record.successful,
+            record.envelope_xdr.clone()?,
+            record.result_xdr.clone(),
+        ))
+    }
+
+    /// Store the row unless its hash is already recorded; a transaction in
+    /// a ledger never changes.
+    pub async fn record(&self, executor: impl sqlx::PgExecutor<'_>) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO stellar_transactions (hash, ledger, successful, envelope_xdr, result_xdr)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (hash) DO NOTHING
+            "#,
+        )
+        .bind(&self.hash)
+        .bind(self.ledger)
+        .bind(self.successful)
+        .bind(&self.envelope_xdr)
+        .bind(&self.result_xdr)
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find(pool: &sqlx::PgPool, hash: &str) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as::<_, Self>("SELECT * FROM stellar_transactions WHERE hash = $1")
+            .bind(hash)
+            .fetch_optional(pool)
+            .await
+    }
+}
+
+/// Row in `refund_queue`: a refund owed for an unmatched inbound payment.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RefundTask {
+    pub id: Uuid,
+    pub payment_id: String,
+    pub source: String,
+    pub reason: String,
+    pub refund_to: String,
+    pub asset_code: String,
+    pub amount: BigDecimal,
+    pub network_fee: BigDecimal,
+    pub refund_amount: BigDecimal,
+    pub memo: Option<String>,
+    pub status: String,
+    pub stellar_tx_hash: Option<String>,
+    pub note: Option<String>,
+    pub updated_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Row in `partner_trusted_assets`: an asset issuer a partner accepts (see
+/// [`crate::services::asset_trust`]).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct TrustedAsset {
+    pub asset_code: String,
+    pub asset_issuer: String,
+}
+
+/// Row in `quarantined_payments`: an inbound payment held back because its
+/// asset issuer is not trusted by the receiving partner.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct QuarantinedPayment {
+    pub id: Uuid,
+    pub payment_id: String,
+    pub source: String,
+    pub tenant_id: Option<Uuid>,
+    pub transaction_id: Option<Uuid>,
+    pub account: String,
+    pub from_account: Option<String>,
+    pub asset_code: String,
+    pub asset_issuer: Option<String>,
+    pub amount: BigDecimal,
+    pub memo: Option<String>,
+    pub stellar_tx_hash: Option<String>,
+    pub reason: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Row in `structuring_rules`: deposit thresholds per account and window
+/// (see [`crate::services::structuring`]).
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct StructuringRule {
+    pub id: Uuid,
+    pub name: String,
+    pub asset_code: Option<String>,
+    pub window_hours: i32,
+    pub min_count: Option<i32>,
+    pub min_total: Option<BigDecimal>,
+    pub max_single_amount: Option<BigDecimal>,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Row in `structuring_reviews`: an account flagged by a structuring rule.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct StructuringReview {
+    pub id: Uuid,
+    pub rule_id: Uuid,
+    pub stellar_account: String,
+    pub window_start: DateTime<Utc>,
+    pub window_end: DateTime<Utc>,
+    pub tx_count: i64,
+    pub total_amount: BigDecimal,
+    pub transaction_ids: Vec<Uuid>,
+    pub status: String,
+    pub note: Option<String>,
+    pub resolved_by: Option<String>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Row in `review_items`: a held transaction or structuring review waiting in
+/// the compliance review queue (see [`crate::services::review_queue`]).
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ReviewItem {
+    pub id: Uuid,
+    pub kind: String,
+    pub transaction_id: Option<Uuid>,
+    pub structuring_review_id: Option<Uuid>,
+    pub state: String,
+    pub assignee: Option<String>,
+    pub assigned_at: Option<DateTime<Utc>>,
+    pub sla_due_at: DateTime<Utc>,
+    pub resolution: Option<String>,
+    pub resolved_by: Option<String>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Row in `review_comments`.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ReviewComment {
+    pub id: Uuid,
+    pub review_item_id: Uuid,
+    pub author: String,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Row in `settlement_reversals`: the compensating entry written when a
+/// settlement is voided after the fact. `amount` is the negated settlement
+/// total.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SettlementReversal {
+    pub id: Uuid,
+    pub settlement_id: Uuid,
+    pub asset_code: String,
+    pub amount: BigDecimal,
+    pub tx_count: i32,
+    pub released_transaction_ids: Vec<Uuid>,
+    pub previous_status: String,
+    pub reason: String,
+    pub proposed_by: String,
+    pub approved_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Row in `accounting_periods`: a closed month. Open periods have no row.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AccountingPeriod {
+    pub id: Uuid,
+    pub period_start: NaiveDate,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub closed_by: String,
+    pub closed_at: DateTime<Utc>,
+}
+
+/// Row in `accounting_adjustments`: a signed correction to a transaction or
+/// settlement in a closed period, which itself can no longer be changed.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AccountingAdjustment {
+    pub id: Uuid,
+    pub period_id: Uuid,
+    pub entity_type: String,
+    pub entity_id: Uuid,
+    pub asset_code: String,
+    pub amount: BigDecimal,
+    pub reason: String,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Row in `partner_statements`: one partner's statement for one month (see
+/// [`crate::services::statements`]).
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PartnerStatement {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub period_start: NaiveDate,
+    pub job_id: Uuid,
+    pub lines: serde_json::Value,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Row in `approvals`: a high-value admin action proposed by one principal
+/// and approved or rejected by another (see [`crate::services::approvals`]).
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Approval {
+    pub id: Uuid,
+    pub kind: String,
+    pub payload: serde_json::Value,
+    pub amount: BigDecimal,
+    pub status: String,
+    pub proposed_by: String,
+    pub decided_by: Option<String>,
+    pub decision_note: Option<String>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub result: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Row in `jobs`: a long-running background job (see
+/// [`crate::services::job_runner`]).
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BackgroundJob {
+    pub id: Uuid,
+    pub kind: String,
+    pub params: serde_json::Value,
+    pub status: String,
+    pub progress_current: i64,
+    pub progress_total: Option<i64>,
+    /// Kind-specific resume state; internal to the job runner.
+    #[serde(skip_serializing)]
+    pub checkpoint: Option<serde_json::Value>,
+    pub result: Option<serde_json::Value>,
+    pub error: Option<String>,
+    pub cancel_requested: bool,
+    pub requested_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub heartbeat_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Row in `transaction_tombstones`: a transaction that left `transactions`,
+/// reported by `GET /transactions/changes`.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TransactionTombstone {
+    pub transaction_id: Uuid,
+    pub tenant_id: Option<Uuid>,
+    /// `deleted` or `archived`.
+    pub reason: String,
+    pub removed_at: DateTime<Utc>,
 }
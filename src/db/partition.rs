@@ -1,4 +1,5 @@
 use crate::services::query_cache::QueryCache;
+use chrono::{Datelike, Months, NaiveDate};
 use sqlx::PgPool;
 use std::time::Duration;
 use tokio::time;
@@ -98,6 +99,24 @@ impl PartitionManager {
     }
 }
 
+/// Create the monthly `transactions` partition containing `day`, if missing.
+///
+/// `create_monthly_partition()` only provisions months ahead; this covers
+/// rows written for the current or past months, e.g. test databases and
+/// seeded demo data.
+pub async fn ensure_month_partition(pool: &PgPool, day: NaiveDate) -> Result<(), sqlx::Error> {
+    let start = day.with_day(1).unwrap_or(day);
+    let end = start + Months::new(1);
+    let name = format!("transactions_y{}m{:02}", start.year(), start.month());
+    sqlx::query(&format!(
+        "CREATE TABLE IF NOT EXISTS {name} PARTITION OF transactions \
+         FOR VALUES FROM ('{start}') TO ('{end}')"
+    ))
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -30,10 +30,27 @@
 //! - Tenant context set via [`set_tenant_context`] for RLS policy enforcement
 //! - Sensitive data (passwords, tokens) never logged; only query structure logged
 
-use crate::db::audit::{AuditLog, ENTITY_TRANSACTION};
-use crate::db::models::{Settlement, Transaction};
+use crate::db::audit::{
+    AuditLog, ENTITY_ACCOUNTING_PERIOD, ENTITY_APPROVAL, ENTITY_QUARANTINED_PAYMENT, ENTITY_REFUND,
+    ENTITY_REVIEW_ITEM, ENTITY_SETTLEMENT, ENTITY_SIGNING_KEY, ENTITY_STRUCTURING_REVIEW,
+    ENTITY_STRUCTURING_RULE, ENTITY_TRANSACTION,
+};
+use crate::db::models::{
+    AccountingAdjustment, AccountingPeriod, Approval, Asset, BackgroundJob, ClaimableBalance,
+    PartnerStatement, QuarantinedPayment, RefundTask, ReviewComment, ReviewItem, Settlement,
+    SettlementReversal, StructuringReview, StructuringRule, Transaction, TransactionStatus,
+    TransactionTombstone, TrustedAsset,
+};
+use crate::domain::StellarAddress;
+use crate::ports::{CustomerProfile, RiskAssessment};
+use crate::services::amount_limits::AmountLimits;
+use crate::services::asset_trust::PartnerTrust;
+use crate::services::statements::StatementLine;
+use crate::services::structuring::RuleSpec;
+use crate::services::webhook_dispatcher::WebhookEndpoint;
 use crate::tenant::TenantConfig;
-use chrono::{DateTime, Utc};
+use bigdecimal::ToPrimitive;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use sqlx::types::BigDecimal;
@@ -119,7 +136,8 @@ impl QueryTier {
 ///
 /// # Error Handling
 /// Returns `sqlx::Error::PoolTimedOut` if query exceeds tier-specific timeout.
-/// Other errors are propagated as-is.
+/// Other errors are propagated as-is; connection-level ones also wake the
+/// [failover watchdog](crate::db::failover).
 ///
 /// # Examples
 /// ```text
@@ -140,7 +158,10 @@ where
 {
     let dur = tier.duration();
     match timeout(dur, fut).await {
-        Ok(result) => result,
+        Ok(result) => result.map_err(|e| {
+            crate::db::failover::report(&e);
+            e
+        }),
         Err(_elapsed) => {
             DB_QUERY_TIMEOUT_TOTAL.fetch_add(1, Ordering::Relaxed);
             tracing::error!(
@@ -167,15 +188,39 @@ pub async fn lookup_api_key(pool: &PgPool, api_key: &str) -> Result<bool> {
     Ok(row.is_some())
 }
 
+/// Scopes granted to an active tenant's API key; `None` for an unknown key.
+pub async fn get_api_key_scopes(pool: &PgPool, api_key: &str) -> Result<Option<Vec<String>>> {
+    sqlx::query_scalar("SELECT scopes FROM tenants WHERE api_key = $1 AND is_active = true")
+        .bind(api_key)
+        .fetch_optional(pool)
+        .await
+}
+
 /// Load active tenant configuration used by request authentication and
 /// webhook signature validation. Secrets are returned for in-memory use only;
 /// callers must not log or persist them in audit records.
 pub async fn get_all_tenant_configs(pool: &PgPool) -> Result<Vec<TenantConfig>> {
-    let configs = sqlx::query_as::<_, TenantConfig>(
-        "SELECT tenant_id, name, webhook_secret, stellar_account, rate_limit_per_minute, is_active FROM tenants WHERE is_active = true",
+    let mut configs = sqlx::query_as::<_, TenantConfig>(
+        "SELECT tenant_id, name, webhook_secret, stellar_account, rate_limit_per_minute, \
+         is_active, allowed_assets, processing_weight FROM tenants WHERE is_active = true",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let trusted: Vec<(Uuid, String, String)> = sqlx::query_as(
+        "SELECT tenant_id, asset_code, asset_issuer FROM partner_trusted_assets \
+         ORDER BY asset_code, asset_issuer",
     )
     .fetch_all(pool)
     .await?;
+    for (tenant_id, asset_code, asset_issuer) in trusted {
+        if let Some(cfg) = configs.iter_mut().find(|c| c.tenant_id == tenant_id) {
+            cfg.trusted_assets.push(TrustedAsset {
+                asset_code,
+                asset_issuer,
+            });
+        }
+    }
     Ok(configs)
 }
 
@@ -298,6 +343,306 @@ pub async fn update_tenant_rate_limit(
     .await
 }
 
+/// Replace the asset allowlist for an active tenant. `None` removes the
+/// restriction. Returns the stored allowlist; `RowNotFound` if the tenant does
+/// not exist or is inactive.
+pub async fn update_tenant_allowed_assets(
+    pool: &PgPool,
+    tenant_id: uuid::Uuid,
+    allowed_assets: Option<&[String]>,
+    actor: &str,
+) -> Result<Option<Vec<String>>> {
+    with_timeout(
+        QueryTier::Write,
+        "UPDATE tenants SET allowed_assets = $1 WHERE tenant_id = $2 AND is_active = true",
+        async {
+            let mut db_tx = pool.begin().await?;
+
+            let old: Option<Option<Vec<String>>> = sqlx::query_scalar(
+                "SELECT allowed_assets FROM tenants WHERE tenant_id = $1 AND is_active = true FOR UPDATE",
+            )
+            .bind(tenant_id)
+            .fetch_optional(&mut *db_tx)
+            .await?;
+            let old = old.ok_or(sqlx::Error::RowNotFound)?;
+
+            sqlx::query(
+                "UPDATE tenants SET allowed_assets = $1, updated_at = NOW() WHERE tenant_id = $2 AND is_active = true",
+            )
+            .bind(allowed_assets)
+            .bind(tenant_id)
+            .execute(&mut *db_tx)
+            .await?;
+
+            AuditLog::log_field_update(
+                &mut db_tx,
+                tenant_id,
+                "tenant",
+                "allowed_assets",
+                serde_json::json!(old),
+                serde_json::json!(allowed_assets),
+                actor,
+            )
+            .await?;
+
+            db_tx.commit().await?;
+            Ok(allowed_assets.map(|a| a.to_vec()))
+        },
+    )
+    .await
+}
+
+/// Maximum processor weight of a tenant (see `process_batch`).
+pub const MAX_PROCESSING_WEIGHT: i32 = 100;
+
+/// Update the `processing_weight` for an active tenant.
+///
+/// # Validation
+/// - `new_weight` must be in `[1, MAX_PROCESSING_WEIGHT]`.
+/// - The tenant must exist and be active; returns `RowNotFound` otherwise.
+pub async fn update_tenant_processing_weight(
+    pool: &PgPool,
+    tenant_id: uuid::Uuid,
+    new_weight: i32,
+    actor: &str,
+) -> Result<i32> {
+    if !(1..=MAX_PROCESSING_WEIGHT).contains(&new_weight) {
+        return Err(sqlx::Error::Decode(
+            format!(
+                "processing_weight must be between 1 and {MAX_PROCESSING_WEIGHT}, got {new_weight}"
+            )
+            .into(),
+        ));
+    }
+
+    with_timeout(
+        QueryTier::Write,
+        "UPDATE tenants SET processing_weight = $1 WHERE tenant_id = $2 AND is_active = true",
+        async {
+            let mut db_tx = pool.begin().await?;
+
+            let old_weight: Option<i32> = sqlx::query_scalar(
+                "SELECT processing_weight FROM tenants WHERE tenant_id = $1 AND is_active = true FOR UPDATE",
+            )
+            .bind(tenant_id)
+            .fetch_optional(&mut *db_tx)
+            .await?;
+            let old_weight = old_weight.ok_or(sqlx::Error::RowNotFound)?;
+
+            sqlx::query(
+                "UPDATE tenants SET processing_weight = $1, updated_at = NOW() WHERE tenant_id = $2 AND is_active = true",
+            )
+            .bind(new_weight)
+            .bind(tenant_id)
+            .execute(&mut *db_tx)
+            .await?;
+
+            AuditLog::log_field_update(
+                &mut db_tx,
+                tenant_id,
+                "tenant",
+                "processing_weight",
+                serde_json::json!(old_weight),
+                serde_json::json!(new_weight),
+                actor,
+            )
+            .await?;
+
+            db_tx.commit().await?;
+            Ok(new_weight)
+        },
+    )
+    .await
+}
+
+/// Replace the trusted asset registry of an active tenant. An empty list
+/// removes the restriction. Returns the stored registry; `RowNotFound` if the
+/// tenant does not exist or is inactive.
+pub async fn replace_partner_trusted_assets(
+    pool: &PgPool,
+    tenant_id: uuid::Uuid,
+    assets: &[TrustedAsset],
+    actor: &str,
+) -> Result<Vec<TrustedAsset>> {
+    with_timeout(
+        QueryTier::Write,
+        "DELETE FROM partner_trusted_assets WHERE tenant_id = $1; INSERT ...",
+        async {
+            let mut db_tx = pool.begin().await?;
+
+            let exists: Option<Uuid> = sqlx::query_scalar(
+                "SELECT tenant_id FROM tenants WHERE tenant_id = $1 AND is_active = true FOR UPDATE",
+            )
+            .bind(tenant_id)
+            .fetch_optional(&mut *db_tx)
+            .await?;
+            exists.ok_or(sqlx::Error::RowNotFound)?;
+
+            let old = sqlx::query_as::<_, TrustedAsset>(
+                "SELECT asset_code, asset_issuer FROM partner_trusted_assets \
+                 WHERE tenant_id = $1 ORDER BY asset_code, asset_issuer",
+            )
+            .bind(tenant_id)
+            .fetch_all(&mut *db_tx)
+            .await?;
+
+            sqlx::query("DELETE FROM partner_trusted_assets WHERE tenant_id = $1")
+                .bind(tenant_id)
+                .execute(&mut *db_tx)
+                .await?;
+            let codes: Vec<&str> = assets.iter().map(|a| a.asset_code.as_str()).collect();
+            let issuers: Vec<&str> = assets.iter().map(|a| a.asset_issuer.as_str()).collect();
+            sqlx::query(
+                r#"
+                INSERT INTO partner_trusted_assets (tenant_id, asset_code, asset_issuer)
+                SELECT $1, code, issuer FROM UNNEST($2::text[], $3::text[]) AS t(code, issuer)
+                ON CONFLICT DO NOTHING
+                "#,
+            )
+            .bind(tenant_id)
+            .bind(&codes)
+            .bind(&issuers)
+            .execute(&mut *db_tx)
+            .await?;
+
+            AuditLog::log_field_update(
+                &mut db_tx,
+                tenant_id,
+                "tenant",
+                "trusted_assets",
+                json!(old),
+                json!(assets),
+                actor,
+            )
+            .await?;
+
+            db_tx.commit().await?;
+            Ok(assets.to_vec())
+        },
+    )
+    .await
+}
+
+/// The active partner receiving payments at `account`, with its trusted
+/// asset registry. `None` when no partner uses that account.
+pub async fn get_partner_trust_for_account(
+    pool: &PgPool,
+    account: &str,
+) -> Result<Option<PartnerTrust>> {
+    with_timeout(
+        QueryTier::Read,
+        "SELECT ... FROM tenants LEFT JOIN partner_trusted_assets ... WHERE stellar_account = $1",
+        async {
+            let rows: Vec<(Uuid, Option<String>, Option<String>)> = sqlx::query_as(
+                r#"
+                SELECT t.tenant_id, p.asset_code, p.asset_issuer
+                FROM tenants t
+                LEFT JOIN partner_trusted_assets p ON p.tenant_id = t.tenant_id
+                WHERE t.stellar_account = $1 AND t.is_active = true
+                ORDER BY t.created_at, t.tenant_id
+                "#,
+            )
+            .bind(account)
+            .fetch_all(pool)
+            .await?;
+
+            // Several partners sharing an account is a misconfiguration; the
+            // oldest one's registry applies.
+            let Some(&(tenant_id, _, _)) = rows.first() else {
+                return Ok(None);
+            };
+            let trusted = rows
+                .into_iter()
+                .filter(|(id, _, _)| *id == tenant_id)
+                .filter_map(|(_, code, issuer)| {
+                    Some(TrustedAsset {
+                        asset_code: code?,
+                        asset_issuer: issuer?,
+                    })
+                })
+                .collect();
+            Ok(Some(PartnerTrust { tenant_id, trusted }))
+        },
+    )
+    .await
+}
+
+/// Amount limits applied to deposits of `asset_code`.
+///
+/// When several enabled issuers share a code the most restrictive bounds win.
+/// An unknown or disabled asset yields no limits.
+pub async fn get_asset_amount_limits(pool: &PgPool, asset_code: &str) -> Result<AmountLimits> {
+    with_timeout(
+        QueryTier::Read,
+        "SELECT MAX(min_amount), MIN(max_amount) FROM assets WHERE asset_code = $1",
+        async {
+            sqlx::query_as::<_, AmountLimits>(
+                r#"
+                SELECT MAX(min_amount) AS min_amount, MIN(max_amount) AS max_amount
+                FROM assets
+                WHERE asset_code = $1 AND enabled = TRUE
+                "#,
+            )
+            .bind(asset_code)
+            .fetch_one(pool)
+            .await
+        },
+    )
+    .await
+}
+
+/// Replace an asset's amount limits and audit the change.
+pub async fn update_asset_amount_limits(
+    pool: &PgPool,
+    asset_id: uuid::Uuid,
+    limits: &AmountLimits,
+    actor: &str,
+) -> Result<Asset> {
+    with_timeout(
+        QueryTier::Write,
+        "UPDATE assets SET min_amount = $1, max_amount = $2 WHERE id = $3",
+        async {
+            let mut db_tx = pool.begin().await?;
+
+            let old = sqlx::query_as::<_, AmountLimits>(
+                "SELECT min_amount, max_amount FROM assets WHERE id = $1 FOR UPDATE",
+            )
+            .bind(asset_id)
+            .fetch_optional(&mut *db_tx)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+
+            let asset = sqlx::query_as::<_, Asset>(
+                r#"
+                UPDATE assets SET min_amount = $1, max_amount = $2, updated_at = NOW()
+                WHERE id = $3
+                RETURNING id, asset_code, asset_issuer, metadata, enabled, min_amount, max_amount, created_at, updated_at
+                "#,
+            )
+            .bind(&limits.min_amount)
+            .bind(&limits.max_amount)
+            .bind(asset_id)
+            .fetch_one(&mut *db_tx)
+            .await?;
+
+            AuditLog::log_field_update(
+                &mut db_tx,
+                asset_id,
+                "asset",
+                "amount_limits",
+                json!(old),
+                json!(limits),
+                actor,
+            )
+            .await?;
+
+            db_tx.commit().await?;
+            Ok(asset)
+        },
+    )
+    .await
+}
+
 /// Set the tenant context on a connection so PostgreSQL RLS policies fire correctly.
 /// Pass `None` for admin connections that should bypass RLS.
 pub async fn set_tenant_context(
@@ -326,6 +671,10 @@ pub async fn set_tenant_context(
 /// entry in the same SQL transaction, and invalidates aggregate caches only
 /// after commit. Handlers should use this helper instead of issuing their own
 /// INSERT so webhook persistence remains auditable and timeout protected.
+///
+/// Money invariants (positive amount, known status, one active transaction per
+/// `anchor_transaction_id`) are enforced by the database; violations can be
+/// classified with [`crate::db::constraints::classify`].
 pub async fn insert_transaction(pool: &PgPool, tx: &Transaction) -> Result<Transaction> {
     with_timeout(
         QueryTier::Write,
@@ -347,1235 +696,4166 @@ pub async fn insert_transaction(pool: &PgPool, tx: &Transaction) -> Result<Trans
     .await
 }
 
-async fn persist_transaction(
-    db_tx: &mut SqlxTransaction<'_, Postgres>,
+/// Record the claim of `balance` by `claim_tx_hash`: insert its deposit `tx`
+/// and link it to the balance in one SQL transaction. `None` when the
+/// balance was already claimed, so a deposit is never recorded twice.
+pub async fn record_claimable_balance_claim(
+    pool: &PgPool,
+    balance: &ClaimableBalance,
+    claim_tx_hash: &str,
     tx: &Transaction,
-) -> Result<Transaction> {
-    sqlx::query_as::<_, Transaction>(
+) -> Result<Option<Transaction>> {
+    let mut db_tx = pool.begin().await?;
+    let result = persist_transaction(&mut db_tx, tx).await?;
+    let claimed = sqlx::query(
         r#"
-        INSERT INTO transactions (
-            id, stellar_account, amount, asset_code, status,
-            created_at, updated_at, anchor_transaction_id, callback_type, callback_status,
-            settlement_id, memo, memo_type, metadata
-        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
-        RETURNING *
+        UPDATE claimable_balances
+        SET status = 'claimed', claim_tx_hash = $2, transaction_id = $3,
+            claimed_at = NOW(), updated_at = NOW()
+        WHERE id = $1 AND status = 'detected'
         "#,
     )
-    .bind(tx.id)
-    .bind(&tx.stellar_account)
-    .bind(&tx.amount)
-    .bind(&tx.asset_code)
-    .bind(&tx.status)
-    .bind(tx.created_at)
-    .bind(tx.updated_at)
-    .bind(&tx.anchor_transaction_id)
-    .bind(&tx.callback_type)
-    .bind(&tx.callback_status)
-    .bind(tx.settlement_id)
-    .bind(&tx.memo)
-    .bind(&tx.memo_type)
-    .bind(&tx.metadata)
-    .fetch_one(&mut **db_tx)
-    .await
+    .bind(balance.id)
+    .bind(claim_tx_hash)
+    .bind(result.id)
+    .execute(&mut *db_tx)
+    .await?;
+    if claimed.rows_affected() == 0 {
+        // Dropping `db_tx` rolls the deposit back.
+        return Ok(None);
+    }
+    audit_transaction_creation(&mut db_tx, &result).await?;
+    db_tx.commit().await?;
+
+    invalidate_transaction_caches(&result.asset_code).await;
+    Ok(Some(result))
 }
 
-async fn audit_transaction_creation(
-    db_tx: &mut SqlxTransaction<'_, Postgres>,
-    result: &Transaction,
-) -> Result<()> {
-    AuditLog::log_creation(
-        db_tx,
-        result.id,
-        ENTITY_TRANSACTION,
-        json!({
-            "stellar_account": result.stellar_account,
-            "amount": result.amount.to_string(),
-            "asset_code": result.asset_code,
-            "status": result.status,
-            "anchor_transaction_id": result.anchor_transaction_id,
-            "callback_type": result.callback_type,
-            "callback_status": result.callback_status,
-            "memo": result.memo,
-            "memo_type": result.memo_type,
-            "metadata": result.metadata,
-        }),
-        "system",
+/// Insert a callback transaction unless an identical payload was already
+/// accepted within `window`.
+///
+/// `payload_hash` is computed by
+/// [`crate::services::webhook_dedup::payload_hash`]. A transaction-scoped
+/// advisory lock on the hash serialises concurrent resends, so exactly one of
+/// them inserts. Returns the stored transaction and `true` when it is the
+/// original from an earlier delivery rather than a new row.
+pub async fn insert_transaction_deduplicated(
+    pool: &PgPool,
+    tx: &Transaction,
+    payload_hash: &str,
+    window: std::time::Duration,
+) -> Result<(Transaction, bool)> {
+    let since = Utc::now()
+        - chrono::Duration::from_std(window).unwrap_or_else(|_| chrono::Duration::days(7));
+
+    let (result, deduplicated) = with_timeout(
+        QueryTier::Write,
+        "INSERT INTO transactions ... (deduplicated via webhook_inbox)",
+        crate::utils::retry::retry_with_backoff(
+            "insert_transaction_deduplicated",
+            3,
+            100,
+            || async {
+                let mut db_tx = pool.begin().await?;
+
+                sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended($1, 0))")
+                    .bind(payload_hash)
+                    .execute(&mut *db_tx)
+                    .await?;
+
+                let original: Option<Transaction> = sqlx::query_as::<_, Transaction>(
+                    r#"
+                SELECT t.* FROM webhook_inbox i
+                JOIN transactions t ON t.id = i.transaction_id
+                WHERE i.payload_hash = $1 AND i.received_at >= $2
+                ORDER BY i.received_at DESC
+                LIMIT 1
+                "#,
+                )
+                .bind(payload_hash)
+                .bind(since)
+                .fetch_optional(&mut *db_tx)
+                .await?;
+
+                if let Some(original) = original {
+                    db_tx.commit().await?;
+                    return Ok((original, true));
+                }
+
+                let result = persist_transaction(&mut db_tx, tx).await?;
+                audit_transaction_creation(&mut db_tx, &result).await?;
+                sqlx::query(
+                    r#"
+                INSERT INTO webhook_inbox (payload_hash, anchor_transaction_id, transaction_id)
+                VALUES ($1, $2, $3)
+                "#,
+                )
+                .bind(payload_hash)
+                .bind(&result.anchor_transaction_id)
+                .bind(result.id)
+                .execute(&mut *db_tx)
+                .await?;
+
+                db_tx.commit().await?;
+                Ok((result, false))
+            },
+        ),
     )
-    .await
+    .await?;
+
+    if !deduplicated {
+        invalidate_transaction_caches(&result.asset_code).await;
+    }
+    Ok((result, deduplicated))
 }
 
-pub async fn get_transaction(pool: &PgPool, id: Uuid) -> Result<Transaction> {
-    with_timeout(
-        QueryTier::Read,
-        "SELECT * FROM transactions WHERE id = $1",
-        sqlx::query_as::<_, Transaction>("SELECT * FROM transactions WHERE id = $1")
-            .bind(id)
-            .fetch_one(pool),
-    )
-    .await
-}
-
-pub async fn list_transactions(
+/// Insert a transaction imported from Horizon history unless one with the
+/// same `anchor_transaction_id` is already active.
+///
+/// Backfilled rows carry `horizon:<payment id>` as their anchor id, so this is
+/// what makes re-running a backfill import each payment only once. Returns
+/// `None` when the payment was already present.
+pub async fn insert_backfilled_transaction(
     pool: &PgPool,
-    limit: i64,
-    cursor: Option<(DateTime<Utc>, Uuid)>,
-    backward: bool,
-) -> Result<Vec<Transaction>> {
-    with_timeout(
-        QueryTier::Read,
-        "SELECT * FROM transactions [cursor-paginated]",
+    tx: &Transaction,
+) -> Result<Option<Transaction>> {
+    let anchor_id = tx.anchor_transaction_id.as_deref().ok_or_else(|| {
+        sqlx::Error::Decode("backfilled transactions need an anchor_transaction_id".into())
+    })?;
+
+    let inserted = with_timeout(
+        QueryTier::Write,
+        "INSERT INTO transactions ... (backfill, unless anchor id is active)",
         async {
-            if let Some((ts, id)) = cursor {
-                if !backward {
-                    let q = sqlx::query_as::<_, Transaction>(
-                        "SELECT * FROM transactions WHERE (created_at, id) < ($1, $2) ORDER BY created_at DESC, id DESC LIMIT $3",
-                    )
-                    .bind(ts)
-                    .bind(id)
-                    .bind(limit)
-                    .fetch_all(pool)
-                    .await?;
-                    Ok(q)
-                } else {
-                    let mut rows = sqlx::query_as::<_, Transaction>(
-                        "SELECT * FROM transactions WHERE (created_at, id) > ($1, $2) ORDER BY created_at ASC, id ASC LIMIT $3",
-                    )
-                    .bind(ts)
-                    .bind(id)
-                    .bind(limit)
-                    .fetch_all(pool)
-                    .await?;
-                    rows.reverse();
-                    Ok(rows)
-                }
-            } else if !backward {
-                let q = sqlx::query_as::<_, Transaction>(
-                    "SELECT * FROM transactions ORDER BY created_at DESC, id DESC LIMIT $1",
-                )
-                .bind(limit)
-                .fetch_all(pool)
-                .await?;
-                Ok(q)
-            } else {
-                let mut rows = sqlx::query_as::<_, Transaction>(
-                    "SELECT * FROM transactions ORDER BY created_at ASC, id ASC LIMIT $1",
-                )
-                .bind(limit)
-                .fetch_all(pool)
+            let mut db_tx = pool.begin().await?;
+
+            sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended($1, 0))")
+                .bind(anchor_id)
+                .execute(&mut *db_tx)
                 .await?;
-                rows.reverse();
-                Ok(rows)
+
+            let existing: Option<Uuid> = sqlx::query_scalar(
+                "SELECT transaction_id FROM transaction_active_anchors WHERE anchor_transaction_id = $1",
+            )
+            .bind(anchor_id)
+            .fetch_optional(&mut *db_tx)
+            .await?;
+            if existing.is_some() {
+                db_tx.commit().await?;
+                return Ok(None);
             }
+
+            let result = persist_transaction(&mut db_tx, tx).await?;
+            audit_transaction_creation(&mut db_tx, &result).await?;
+            db_tx.commit().await?;
+            Ok(Some(result))
         },
     )
-    .await
+    .await?;
+
+    if let Some(result) = &inserted {
+        invalidate_transaction_caches(&result.asset_code).await;
+    }
+    Ok(inserted)
 }
 
-pub async fn list_transactions_filtered(
+/// Update a transaction's partner metadata and audit the change.
+///
+/// With `replace` the metadata is overwritten; otherwise `patch` is merged
+/// key-by-key (see [`Transaction::merged_metadata`]). The result is checked
+/// against [`crate::validation::validate_metadata`]; a violation is returned
+/// as `sqlx::Error::Decode`.
+pub async fn update_transaction_metadata(
     pool: &PgPool,
-    limit: i64,
-    cursor: Option<(DateTime<Utc>, Uuid)>,
-    backward: bool,
-    from_date: Option<DateTime<Utc>>,
-    to_date: Option<DateTime<Utc>>,
-) -> Result<Vec<Transaction>> {
+    id: uuid::Uuid,
+    patch: &serde_json::Value,
+    replace: bool,
+    actor: &str,
+) -> Result<Transaction> {
     with_timeout(
-        QueryTier::Read,
-        "SELECT * FROM transactions [filtered cursor-paginated]",
+        QueryTier::Write,
+        "UPDATE transactions SET metadata = $1 WHERE id = $2",
         async {
-            let mut conditions: Vec<String> = Vec::new();
-            let mut bind_idx = 1i32;
-
-            if cursor.is_some() {
-                if !backward {
-                    conditions.push(format!(
-                        "(created_at, id) < (${}, ${})",
-                        bind_idx,
-                        bind_idx + 1
-                    ));
-                } else {
-                    conditions.push(format!(
-                        "(created_at, id) > (${}, ${})",
-                        bind_idx,
-                        bind_idx + 1
-                    ));
-                }
-                bind_idx += 2;
-            }
-
-            if from_date.is_some() {
-                conditions.push(format!("created_at >= ${}", bind_idx));
-                bind_idx += 1;
-            }
-            if to_date.is_some() {
-                conditions.push(format!("created_at <= ${}", bind_idx));
-                bind_idx += 1;
-            }
+            let mut db_tx = pool.begin().await?;
 
-            let where_clause = if conditions.is_empty() {
-                String::new()
-            } else {
-                format!("WHERE {}", conditions.join(" AND "))
-            };
+            let current =
+                sqlx::query_as::<_, Transaction>("SELECT * FROM transactions WHERE id = $1 FOR UPDATE")
+                    .bind(id)
+                    .fetch_one(&mut *db_tx)
+                    .await?;
 
-            let order = if !backward {
-                "ORDER BY created_at DESC, id DESC"
+            let metadata = if replace {
+                patch.clone()
             } else {
-                "ORDER BY created_at ASC, id ASC"
+                current.merged_metadata(patch)
             };
+            crate::validation::validate_metadata(&metadata)
+                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
 
-            let sql = format!(
-                "SELECT * FROM transactions {} {} LIMIT ${}",
-                where_clause, order, bind_idx
-            );
-
-            let mut q = sqlx::query_as::<_, Transaction>(&sql);
+            let updated = sqlx::query_as::<_, Transaction>(
+                "UPDATE transactions SET metadata = $1, updated_at = NOW() WHERE id = $2 RETURNING *",
+            )
+            .bind(&metadata)
+            .bind(id)
+            .fetch_one(&mut *db_tx)
+            .await?;
 
-            if let Some((ts, id)) = cursor {
-                q = q.bind(ts).bind(id);
-            }
-            if let Some(from) = from_date {
-                q = q.bind(from);
-            }
-            if let Some(to) = to_date {
-                q = q.bind(to);
-            }
-            q = q.bind(limit);
+            // Values are partner data; the audit trail records which keys changed.
+            let keys = |v: &Option<serde_json::Value>| match v {
+                Some(serde_json::Value::Object(map)) => map.keys().cloned().collect::<Vec<_>>(),
+                _ => Vec::new(),
+            };
+            AuditLog::log_field_update(
+                &mut db_tx,
+                id,
+                ENTITY_TRANSACTION,
+                "metadata",
+                json!({ "keys": keys(&current.metadata) }),
+                json!({ "keys": keys(&updated.metadata) }),
+                actor,
+            )
+            .await?;
 
-            let mut rows = q.fetch_all(pool).await?;
-            if backward {
-                rows.reverse();
-            }
-            Ok(rows)
+            db_tx.commit().await?;
+            Ok(updated)
         },
     )
     .await
 }
 
-pub async fn get_unsettled_transactions(
-    executor: &mut SqlxTransaction<'_, Postgres>,
-    asset_code: &str,
-    end_time: DateTime<Utc>,
-) -> Result<Vec<Transaction>> {
-    with_timeout(
-        QueryTier::Read,
-        "SELECT * FROM transactions WHERE status = 'completed' AND settlement_id IS NULL FOR UPDATE",
-        sqlx::query_as::<_, Transaction>(
-            r#"
-        SELECT * FROM transactions
-        WHERE status = 'completed'
-        AND settlement_id IS NULL
-        AND asset_code = $1
-        AND updated_at <= $2
-        FOR UPDATE
-        "#,
-        )
-        .bind(asset_code)
-        .bind(end_time)
-        .fetch_all(&mut **executor),
-    )
-    .await
+/// Whether any transaction (in any status) carries `memo`.
+pub async fn transaction_exists_for_memo(pool: &PgPool, memo: &str) -> Result<bool> {
+    sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM transactions WHERE memo = $1)")
+        .bind(memo)
+        .fetch_one(pool)
+        .await
 }
 
-pub async fn update_transactions_settlement(
-    executor: &mut SqlxTransaction<'_, Postgres>,
-    tx_ids: &[Uuid],
-    settlement_id: Uuid,
-) -> Result<()> {
+/// Fields for a new `refund_queue` row.
+pub struct NewRefundTask<'a> {
+    pub payment_id: &'a str,
+    pub source: &'a str,
+    pub reason: &'a str,
+    pub refund_to: &'a str,
+    pub asset_code: &'a str,
+    pub amount: &'a BigDecimal,
+    pub network_fee: &'a BigDecimal,
+    pub refund_amount: &'a BigDecimal,
+    pub memo: Option<&'a str>,
+    pub status: &'a str,
+    pub note: Option<&'a str>,
+}
+
+/// Insert a refund task. Returns `None` if the payment is already queued.
+pub async fn insert_refund_task(
+    pool: &PgPool,
+    task: &NewRefundTask<'_>,
+) -> Result<Option<RefundTask>> {
     with_timeout(
         QueryTier::Write,
-        "UPDATE transactions SET settlement_id = $1 WHERE id = ANY($2)",
+        "INSERT INTO refund_queue (...) ON CONFLICT (payment_id) DO NOTHING",
         async {
-            sqlx::query(
-                "UPDATE transactions SET settlement_id = $1, updated_at = NOW() WHERE id = ANY($2)",
+            let mut db_tx = pool.begin().await?;
+            let inserted = sqlx::query_as::<_, RefundTask>(
+                r#"
+                INSERT INTO refund_queue (
+                    payment_id, source, reason, refund_to, asset_code,
+                    amount, network_fee, refund_amount, memo, status, note
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                ON CONFLICT (payment_id) DO NOTHING
+                RETURNING *
+                "#,
             )
-            .bind(settlement_id)
-            .bind(tx_ids)
-            .execute(&mut **executor)
+            .bind(task.payment_id)
+            .bind(task.source)
+            .bind(task.reason)
+            .bind(task.refund_to)
+            .bind(task.asset_code)
+            .bind(task.amount)
+            .bind(task.network_fee)
+            .bind(task.refund_amount)
+            .bind(task.memo)
+            .bind(task.status)
+            .bind(task.note)
+            .fetch_optional(&mut *db_tx)
             .await?;
 
-            // Audit log: record settlement_id update for each transaction
-            for tx_id in tx_ids {
-                AuditLog::log_field_update(
-                    executor,
-                    *tx_id,
-                    ENTITY_TRANSACTION,
-                    "settlement_id",
-                    json!(null),
-                    json!(settlement_id.to_string()),
-                    "system",
-                )
-                .await?;
+            if let Some(row) = &inserted {
+                AuditLog::log_creation(&mut db_tx, row.id, ENTITY_REFUND, json!(row), task.source)
+                    .await?;
             }
-
-            Ok(())
+            db_tx.commit().await?;
+            Ok(inserted)
         },
     )
     .await
 }
 
-// ---------------------------------------------------------------------------
-// Cache invalidation helper
-// ---------------------------------------------------------------------------
+/// Fields for a new `quarantined_payments` row.
+pub struct NewQuarantinedPayment<'a> {
+    pub payment_id: &'a str,
+    pub source: &'a str,
+    pub tenant_id: Option<Uuid>,
+    pub transaction_id: Option<Uuid>,
+    pub account: &'a str,
+    pub from_account: Option<&'a str>,
+    pub asset_code: &'a str,
+    pub asset_issuer: Option<&'a str>,
+    pub amount: &'a BigDecimal,
+    pub memo: Option<&'a str>,
+    pub stellar_tx_hash: Option<&'a str>,
+    pub reason: &'a str,
+}
 
-async fn invalidate_transaction_caches(asset_code: &str) {
-    if let Ok(redis_url) = std::env::var("REDIS_URL") {
-        if let Ok(cache) = crate::services::QueryCache::new(&redis_url).await {
-            let _ = cache.invalidate("query:status_counts").await;
-            let _ = cache.invalidate("query:daily_totals:*").await;
-            let _ = cache.invalidate("query:asset_stats").await;
-            let _ = cache
-                .invalidate_exact(&format!("query:asset_total:{}", asset_code))
-                .await;
-        }
+/// Quarantine a payment within `db_tx`. Returns `None` if it is already
+/// quarantined.
+pub async fn insert_quarantined_payment(
+    db_tx: &mut SqlxTransaction<'_, Postgres>,
+    payment: &NewQuarantinedPayment<'_>,
+) -> Result<Option<QuarantinedPayment>> {
+    let inserted = sqlx::query_as::<_, QuarantinedPayment>(
+        r#"
+        INSERT INTO quarantined_payments (
+            payment_id, source, tenant_id, transaction_id, account, from_account,
+            asset_code, asset_issuer, amount, memo, stellar_tx_hash, reason
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        ON CONFLICT (payment_id) DO NOTHING
+        RETURNING *
+        "#,
+    )
+    .bind(payment.payment_id)
+    .bind(payment.source)
+    .bind(payment.tenant_id)
+    .bind(payment.transaction_id)
+    .bind(payment.account)
+    .bind(payment.from_account)
+    .bind(payment.asset_code)
+    .bind(payment.asset_issuer)
+    .bind(payment.amount)
+    .bind(payment.memo)
+    .bind(payment.stellar_tx_hash)
+    .bind(payment.reason)
+    .fetch_optional(&mut **db_tx)
+    .await?;
+
+    if let Some(row) = &inserted {
+        AuditLog::log_creation(
+            db_tx,
+            row.id,
+            ENTITY_QUARANTINED_PAYMENT,
+            json!(row),
+            payment.source,
+        )
+        .await?;
     }
+    Ok(inserted)
 }
 
-/// Public cache invalidation function for use by other modules
-pub async fn invalidate_caches_for_asset(asset_code: &str) {
-    invalidate_transaction_caches(asset_code).await;
+/// Quarantined payments, newest first, optionally for one partner.
+pub async fn list_quarantined_payments(
+    pool: &PgPool,
+    tenant_id: Option<Uuid>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<QuarantinedPayment>> {
+    with_timeout(
+        QueryTier::Read,
+        "SELECT * FROM quarantined_payments ORDER BY created_at DESC",
+        async {
+            sqlx::query_as::<_, QuarantinedPayment>(
+                r#"
+                SELECT * FROM quarantined_payments
+                WHERE ($1::uuid IS NULL OR tenant_id = $1)
+                ORDER BY created_at DESC, id DESC
+                LIMIT $2 OFFSET $3
+                "#,
+            )
+            .bind(tenant_id)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(pool)
+            .await
+        },
+    )
+    .await
 }
 
-// ---------------------------------------------------------------------------
-// Settlement Queries
-// ---------------------------------------------------------------------------
-
-pub async fn insert_settlement(
-    executor: &mut SqlxTransaction<'_, Postgres>,
-    settlement: &Settlement,
-) -> Result<Settlement> {
+pub async fn get_refund_task(pool: &PgPool, id: uuid::Uuid) -> Result<RefundTask> {
+    sqlx::query_as::<_, RefundTask>("SELECT * FROM refund_queue WHERE id = $1")
+        .bind(id)
+        .fetch_one(pool)
+        .await
+}
+
+/// Lock refund task `id` for the rest of `db_tx`.
+pub async fn lock_refund_task(
+    db_tx: &mut SqlxTransaction<'_, Postgres>,
+    id: uuid::Uuid,
+) -> Result<RefundTask> {
+    sqlx::query_as::<_, RefundTask>("SELECT * FROM refund_queue WHERE id = $1 FOR UPDATE")
+        .bind(id)
+        .fetch_one(&mut **db_tx)
+        .await
+}
+
+/// Refund tasks, oldest first, optionally filtered by status.
+pub async fn list_refund_tasks(
+    pool: &PgPool,
+    status: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<RefundTask>> {
     with_timeout(
-        QueryTier::Write,
-        "INSERT INTO settlements ... RETURNING *",
-        sqlx::query_as::<_, Settlement>(
-            r#"
-        INSERT INTO settlements (
-            id, asset_code, total_amount, tx_count, period_start, period_end, status, created_at, updated_at
-        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
-        RETURNING *
-        "#,
-        )
-        .bind(settlement.id)
-        .bind(&settlement.asset_code)
-        .bind(&settlement.total_amount)
-        .bind(settlement.tx_count)
-        .bind(settlement.period_start)
-        .bind(settlement.period_end)
-        .bind(&settlement.status)
-        .bind(settlement.created_at)
-        .bind(settlement.updated_at)
-        .fetch_one(&mut **executor),
+        QueryTier::Read,
+        "SELECT * FROM refund_queue ORDER BY created_at",
+        async {
+            sqlx::query_as::<_, RefundTask>(
+                r#"
+                SELECT * FROM refund_queue
+                WHERE ($1::text IS NULL OR status = $1)
+                ORDER BY created_at ASC, id ASC
+                LIMIT $2 OFFSET $3
+                "#,
+            )
+            .bind(status)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(pool)
+            .await
+        },
     )
     .await
 }
 
-pub async fn get_settlement(pool: &PgPool, id: Uuid) -> Result<Settlement> {
+/// Changes written by an admin override on a refund task.
+pub struct RefundTaskUpdate<'a> {
+    pub status: &'a str,
+    pub refund_to: Option<&'a str>,
+    pub stellar_tx_hash: Option<&'a str>,
+    pub note: Option<&'a str>,
+}
+
+/// Apply an admin override to `current` within `db_tx` and audit it.
+///
+/// The update only lands if the row is still in `current.status`; `None`
+/// means another writer changed it first.
+pub async fn update_refund_task(
+    db_tx: &mut SqlxTransaction<'_, Postgres>,
+    current: &RefundTask,
+    update: &RefundTaskUpdate<'_>,
+    action: &str,
+    actor: &str,
+) -> Result<Option<RefundTask>> {
     with_timeout(
-        QueryTier::Read,
-        "SELECT * FROM settlements WHERE id = $1",
-        sqlx::query_as::<_, Settlement>("SELECT * FROM settlements WHERE id = $1")
-            .bind(id)
-            .fetch_one(pool),
+        QueryTier::Write,
+        "UPDATE refund_queue SET status = $1 ... WHERE id = $6 AND status = $7",
+        async {
+            let updated = sqlx::query_as::<_, RefundTask>(
+                r#"
+                UPDATE refund_queue SET
+                    status = $1,
+                    refund_to = COALESCE($2, refund_to),
+                    stellar_tx_hash = COALESCE($3, stellar_tx_hash),
+                    note = COALESCE($4, note),
+                    updated_by = $5,
+                    updated_at = NOW()
+                WHERE id = $6 AND status = $7
+                RETURNING *
+                "#,
+            )
+            .bind(update.status)
+            .bind(update.refund_to)
+            .bind(update.stellar_tx_hash)
+            .bind(update.note)
+            .bind(actor)
+            .bind(current.id)
+            .bind(&current.status)
+            .fetch_optional(&mut **db_tx)
+            .await?;
+
+            if let Some(row) = &updated {
+                AuditLog::log(
+                    db_tx,
+                    row.id,
+                    ENTITY_REFUND,
+                    action,
+                    Some(json!(current)),
+                    Some(json!(row)),
+                    actor,
+                )
+                .await?;
+            }
+            Ok(updated)
+        },
     )
     .await
 }
 
-pub async fn list_settlements(pool: &PgPool, limit: i64, offset: i64) -> Result<Vec<Settlement>> {
+pub async fn insert_job(
+    pool: &PgPool,
+    kind: &str,
+    params: &serde_json::Value,
+    requested_by: Option<&str>,
+) -> Result<BackgroundJob> {
     with_timeout(
-        QueryTier::Read,
-        "SELECT * FROM settlements ORDER BY created_at DESC LIMIT $1 OFFSET $2",
-        sqlx::query_as::<_, Settlement>(
-            "SELECT * FROM settlements ORDER BY created_at DESC LIMIT $1 OFFSET $2",
-        )
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(pool),
+        QueryTier::Write,
+        "INSERT INTO jobs (kind, params, requested_by)",
+        async {
+            sqlx::query_as::<_, BackgroundJob>(
+                r#"
+                INSERT INTO jobs (kind, params, requested_by)
+                VALUES ($1, $2, $3)
+                RETURNING *
+                "#,
+            )
+            .bind(kind)
+            .bind(params)
+            .bind(requested_by)
+            .fetch_one(pool)
+            .await
+        },
     )
     .await
 }
 
-/// Cursor-based settlement listing used by the settlements handler.
-pub async fn list_settlements_cursor(
+pub async fn get_job(pool: &PgPool, id: Uuid) -> Result<BackgroundJob> {
+    sqlx::query_as::<_, BackgroundJob>("SELECT * FROM jobs WHERE id = $1")
+        .bind(id)
+        .fetch_one(pool)
+        .await
+}
+
+/// Jobs, newest first, optionally filtered by kind and status.
+pub async fn list_jobs(
     pool: &PgPool,
+    kind: Option<&str>,
+    status: Option<&str>,
     limit: i64,
-    cursor: Option<(DateTime<Utc>, Uuid)>,
-    backward: bool,
-) -> Result<Vec<Settlement>> {
+    offset: i64,
+) -> Result<Vec<BackgroundJob>> {
     with_timeout(
         QueryTier::Read,
-        "SELECT * FROM settlements [cursor-paginated]",
+        "SELECT * FROM jobs ORDER BY created_at DESC",
         async {
-            if let Some((ts, id)) = cursor {
-                if !backward {
-                    sqlx::query_as::<_, Settlement>(
-                        "SELECT * FROM settlements WHERE (created_at, id) < ($1, $2) ORDER BY created_at DESC, id DESC LIMIT $3",
-                    )
-                    .bind(ts).bind(id).bind(limit)
-                    .fetch_all(pool).await
-                } else {
-                    let mut rows = sqlx::query_as::<_, Settlement>(
-                        "SELECT * FROM settlements WHERE (created_at, id) > ($1, $2) ORDER BY created_at ASC, id ASC LIMIT $3",
-                    )
-                    .bind(ts).bind(id).bind(limit)
-                    .fetch_all(pool).await?;
-                    rows.reverse();
-                    Ok(rows)
-                }
-            } else if !backward {
-                sqlx::query_as::<_, Settlement>(
-                    "SELECT * FROM settlements ORDER BY created_at DESC, id DESC LIMIT $1",
-                )
-                .bind(limit)
-                .fetch_all(pool).await
-            } else {
-                let mut rows = sqlx::query_as::<_, Settlement>(
-                    "SELECT * FROM settlements ORDER BY created_at ASC, id ASC LIMIT $1",
+            sqlx::query_as::<_, BackgroundJob>(
+                r#"
+                SELECT * FROM jobs
+                WHERE ($1::text IS NULL OR kind = $1)
+                  AND ($2::text IS NULL OR status = $2)
+                ORDER BY created_at DESC, id DESC
+                LIMIT $3 OFFSET $4
+                "#,
+            )
+            .bind(kind)
+            .bind(status)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(pool)
+            .await
+        },
+    )
+    .await
+}
+
+/// Claim the oldest queued job of one of `kinds` and mark it `running`.
+///
+/// Running jobs whose heartbeat is older than `stale_before` (e.g. left by a
+/// crashed instance) are reclaimed and resume from their checkpoint.
+/// `SKIP LOCKED` lets several instances poll at once.
+pub async fn claim_next_job(
+    pool: &PgPool,
+    kinds: &[&str],
+    stale_before: DateTime<Utc>,
+) -> Result<Option<BackgroundJob>> {
+    with_timeout(
+        QueryTier::Write,
+        "UPDATE jobs SET status = 'running' ... FOR UPDATE SKIP LOCKED",
+        async {
+            sqlx::query_as::<_, BackgroundJob>(
+                r#"
+                UPDATE jobs SET
+                    status = 'running',
+                    started_at = COALESCE(started_at, NOW()),
+                    heartbeat_at = NOW(),
+                    updated_at = NOW()
+                WHERE id = (
+                    SELECT id FROM jobs
+                    WHERE kind = ANY($1)
+                      AND (status = 'queued'
+                           OR (status = 'running' AND heartbeat_at < $2))
+                    ORDER BY created_at ASC
+                    LIMIT 1
+                    FOR UPDATE SKIP LOCKED
                 )
-                .bind(limit)
-                .fetch_all(pool).await?;
-                rows.reverse();
-                Ok(rows)
-            }
+                RETURNING *
+                "#,
+            )
+            .bind(kinds)
+            .bind(stale_before)
+            .fetch_optional(pool)
+            .await
         },
     )
     .await
 }
 
-/// Update settlement status with reason; returns the updated settlement.
-pub async fn update_settlement_status(
+/// Record progress and the resume checkpoint of a running job.
+///
+/// Returns whether cancellation has been requested.
+pub async fn update_job_progress(
     pool: &PgPool,
     id: Uuid,
-    new_status: &str,
-    reason: Option<&str>,
-    new_total: Option<&sqlx::types::BigDecimal>,
-    actor: &str,
-) -> Result<Settlement> {
-    let mut db_tx = pool.begin().await?;
-
-    let current =
-        sqlx::query_as::<_, Settlement>("SELECT * FROM settlements WHERE id = $1 FOR UPDATE")
+    current: i64,
+    total: Option<i64>,
+    checkpoint: &serde_json::Value,
+) -> Result<bool> {
+    with_timeout(
+        QueryTier::Write,
+        "UPDATE jobs SET progress_current = $1 ... RETURNING cancel_requested",
+        async {
+            sqlx::query_scalar(
+                r#"
+                UPDATE jobs SET
+                    progress_current = $1,
+                    progress_total = COALESCE($2, progress_total),
+                    checkpoint = $3,
+                    heartbeat_at = NOW(),
+                    updated_at = NOW()
+                WHERE id = $4
+                RETURNING cancel_requested
+                "#,
+            )
+            .bind(current)
+            .bind(total)
+            .bind(checkpoint)
             .bind(id)
-            .fetch_optional(&mut *db_tx)
-            .await?
-            .ok_or(sqlx::Error::RowNotFound)?;
-
-    // Preserve original amount on first adjustment
-    let original_total = if current.original_total_amount.is_none() && new_total.is_some() {
-        Some(current.total_amount.clone())
-    } else {
-        current.original_total_amount.clone()
-    };
-
-    let updated = sqlx::query_as::<_, Settlement>(
-        r#"
-        UPDATE settlements SET
-            status = $1,
-            dispute_reason = COALESCE($2, dispute_reason),
-            total_amount = COALESCE($3, total_amount),
-            original_total_amount = COALESCE($4, original_total_amount),
-            reviewed_by = $5,
-            reviewed_at = NOW(),
-            updated_at = NOW()
-        WHERE id = $6
-        RETURNING *
-        "#,
+            .fetch_one(pool)
+            .await
+        },
     )
-    .bind(new_status)
-    .bind(reason)
-    .bind(new_total)
-    .bind(original_total)
-    .bind(actor)
-    .bind(id)
-    .fetch_one(&mut *db_tx)
-    .await?;
-
-    // If voided, release transactions back to unsettled
-    if new_status == "voided" {
-        sqlx::query(
-            "UPDATE transactions SET settlement_id = NULL, updated_at = NOW() WHERE settlement_id = $1",
-        )
-        .bind(id)
-        .execute(&mut *db_tx)
-        .await?;
-    }
+    .await
+}
 
-    crate::db::audit::AuditLog::log(
-        &mut db_tx,
-        id,
-        crate::db::audit::ENTITY_SETTLEMENT,
-        "status_update",
-        Some(serde_json::json!({ "status": current.status })),
-        Some(serde_json::json!({ "status": new_status, "reason": reason })),
-        actor,
+/// Move a running job to a terminal status.
+pub async fn finish_job(
+    pool: &PgPool,
+    id: Uuid,
+    status: &str,
+    result: Option<&serde_json::Value>,
+    error: Option<&str>,
+) -> Result<BackgroundJob> {
+    with_timeout(
+        QueryTier::Write,
+        "UPDATE jobs SET status = $1, result = $2, error = $3 WHERE id = $4",
+        async {
+            sqlx::query_as::<_, BackgroundJob>(
+                r#"
+                UPDATE jobs SET
+                    status = $1,
+                    result = $2,
+                    error = $3,
+                    completed_at = NOW(),
+                    updated_at = NOW()
+                WHERE id = $4
+                RETURNING *
+                "#,
+            )
+            .bind(status)
+            .bind(result)
+            .bind(error)
+            .bind(id)
+            .fetch_one(pool)
+            .await
+        },
     )
-    .await?;
-
-    db_tx.commit().await?;
-    Ok(updated)
+    .await
 }
 
-pub async fn get_unique_assets_to_settle(pool: &PgPool) -> Result<Vec<String>> {
+/// Request cancellation of a queued or running job.
+///
+/// Queued jobs are cancelled immediately; running jobs stop at their next
+/// checkpoint. `None` means the job is missing or already finished.
+pub async fn request_job_cancel(pool: &PgPool, id: Uuid) -> Result<Option<BackgroundJob>> {
     with_timeout(
-        QueryTier::Read,
-        "SELECT DISTINCT asset_code FROM transactions WHERE status = 'completed' AND settlement_id IS NULL",
+        QueryTier::Write,
+        "UPDATE jobs SET cancel_requested = TRUE WHERE id = $1",
         async {
-            let rows = sqlx::query(
-                "SELECT DISTINCT asset_code FROM transactions WHERE status = 'completed' AND settlement_id IS NULL"
+            sqlx::query_as::<_, BackgroundJob>(
+                r#"
+                UPDATE jobs SET
+                    cancel_requested = TRUE,
+                    status = CASE WHEN status = 'queued' THEN 'cancelled' ELSE status END,
+                    completed_at = CASE WHEN status = 'queued' THEN NOW() ELSE completed_at END,
+                    updated_at = NOW()
+                WHERE id = $1 AND status IN ('queued', 'running')
+                RETURNING *
+                "#,
             )
-            .fetch_all(pool)
-            .await?;
-
-            Ok(rows
-                .into_iter()
-                .map(|r| r.get::<String, _>("asset_code"))
-                .collect())
+            .bind(id)
+            .fetch_optional(pool)
+            .await
         },
     )
     .await
 }
 
-// ---------------------------------------------------------------------------
-// Transaction Search
-// ---------------------------------------------------------------------------
+/// Record the start of a scheduled job run; returns its `job_runs` id.
+pub async fn start_job_run(
+    pool: &PgPool,
+    job_name: &str,
+    trigger: &str,
+    params: Option<&serde_json::Value>,
+    started_at: DateTime<Utc>,
+) -> Result<i64> {
+    with_timeout(QueryTier::Write, "INSERT INTO job_runs", async {
+        sqlx::query_scalar(
+            r#"
+            INSERT INTO job_runs (job_name, status, trigger, params, started_at)
+            VALUES ($1, 'running', $2, $3, $4)
+            RETURNING id
+            "#,
+        )
+        .bind(job_name)
+        .bind(trigger)
+        .bind(params)
+        .bind(started_at)
+        .fetch_one(pool)
+        .await
+    })
+    .await
+}
 
-#[allow(clippy::too_many_arguments)]
-pub async fn search_transactions(
+/// Record how a scheduled job run ended.
+pub async fn finish_job_run(
     pool: &PgPool,
-    status: Option<&str>,
-    asset_code: Option<&str>,
-    min_amount: Option<&BigDecimal>,
-    max_amount: Option<&BigDecimal>,
-    from_date: Option<DateTime<Utc>>,
-    to_date: Option<DateTime<Utc>>,
-    stellar_account: Option<&str>,
-    limit: i64,
-    cursor: Option<(DateTime<Utc>, Uuid)>,
-) -> Result<(i64, Vec<Transaction>)> {
-    with_timeout(
-        QueryTier::Read,
-        "search_transactions [dynamic WHERE clause]",
-        async {
-            // Build dynamic WHERE clause
-            let mut conditions = Vec::new();
-            let mut param_count = 1;
+    id: i64,
+    status: &str,
+    error: Option<&str>,
+    finished_at: DateTime<Utc>,
+) -> Result<()> {
+    with_timeout(QueryTier::Write, "UPDATE job_runs", async {
+        sqlx::query(
+            r#"
+            UPDATE job_runs SET
+                status = $1,
+                error = $2,
+                finished_at = $3,
+                duration_ms = (EXTRACT(EPOCH FROM ($3 - started_at)) * 1000)::BIGINT
+            WHERE id = $4
+            "#,
+        )
+        .bind(status)
+        .bind(error)
+        .bind(finished_at)
+        .bind(id)
+        .execute(pool)
+        .await
+        .map(|_| ())
+    })
+    .await
+}
 
-            if status.is_some() {
-                conditions.push(format!("status = ${}", param_count));
-                param_count += 1;
-            }
+/// Delete inbox rows older than `cutoff`; they can no longer match a resend.
+pub async fn prune_webhook_inbox(pool: &PgPool, cutoff: DateTime<Utc>) -> Result<u64> {
+    let result = sqlx::query("DELETE FROM webhook_inbox WHERE received_at < $1")
+        .bind(cutoff)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected())
+}
 
-            if asset_code.is_some() {
-                conditions.push(format!("asset_code = ${}", param_count));
-                param_count += 1;
-            }
+// --- Webhook Subscription Queries ---
 
-            if min_amount.is_some() {
-                conditions.push(format!("amount >= ${}", param_count));
-                param_count += 1;
-            }
+pub async fn insert_webhook_endpoint(
+    pool: &PgPool,
+    url: &str,
+    secret: &str,
+    event_types: &[String],
+    max_delivery_rate: i32,
+    filter_rules: Option<&serde_json::Value>,
+) -> Result<WebhookEndpoint> {
+    with_timeout(
+        QueryTier::Write,
+        "INSERT INTO webhook_endpoints (url, secret, event_types, ...)",
+        async {
+            sqlx::query_as::<_, WebhookEndpoint>(
+                r#"
+                INSERT INTO webhook_endpoints
+                    (url, secret, event_types, max_delivery_rate, filter_rules)
+                VALUES ($1, $2, $3, $4, $5)
+                RETURNING *
+                "#,
+            )
+            .bind(url)
+            .bind(secret)
+            .bind(event_types)
+            .bind(max_delivery_rate)
+            .bind(filter_rules)
+            .fetch_one(pool)
+            .await
+        },
+    )
+    .await
+}
 
-            if max_amount.is_some() {
-                conditions.push(format!("amount <= ${}", param_count));
-                param_count += 1;
-            }
+/// Register, or replace, the callback for transaction `transaction_id` and
+/// audit it. The callback is re-enabled and its failure state cleared.
+pub async fn upsert_transaction_callback(
+    pool: &PgPool,
+    transaction_id: Uuid,
+    url: &str,
+    secret: &str,
+    actor: &str,
+) -> Result<WebhookEndpoint> {
+    with_timeout(
+        QueryTier::Write,
+        "INSERT INTO webhook_endpoints (url, secret, transaction_id) ON CONFLICT",
+        async {
+            let mut db_tx = pool.begin().await?;
+            let endpoint = sqlx::query_as::<_, WebhookEndpoint>(
+                r#"
+                INSERT INTO webhook_endpoints (url, secret, event_types, transaction_id)
+                VALUES ($1, $2, '{}', $3)
+                ON CONFLICT (transaction_id) WHERE transaction_id IS NOT NULL
+                DO UPDATE SET
+                    url = EXCLUDED.url,
+                    secret = EXCLUDED.secret,
+                    enabled = TRUE,
+                    previous_secret = NULL,
+                    previous_secret_expires_at = NULL,
+                    failing_since = NULL,
+                    paused_at = NULL,
+                    pause_reason = NULL,
+                    updated_at = NOW()
+                RETURNING *
+                "#,
+            )
+            .bind(url)
+            .bind(secret)
+            .bind(transaction_id)
+            .fetch_one(&mut *db_tx)
+            .await?;
 
-            if from_date.is_some() {
-                conditions.push(format!("created_at >= ${}", param_count));
-                param_count += 1;
-            }
+            AuditLog::log(
+                &mut db_tx,
+                transaction_id,
+                ENTITY_TRANSACTION,
+                "callback_registered",
+                None,
+                Some(json!({ "endpoint_id": endpoint.id, "url": url })),
+                actor,
+            )
+            .await?;
+            db_tx.commit().await?;
+            Ok(endpoint)
+        },
+    )
+    .await
+}
 
-            if to_date.is_some() {
-                conditions.push(format!("created_at <= ${}", param_count));
-                param_count += 1;
-            }
+/// Partner webhook subscriptions, oldest first. Per-transaction callbacks
+/// are left out.
+pub async fn list_webhook_endpoints(pool: &PgPool) -> Result<Vec<WebhookEndpoint>> {
+    with_timeout(
+        QueryTier::Read,
+        "SELECT * FROM webhook_endpoints ORDER BY created_at",
+        async {
+            sqlx::query_as::<_, WebhookEndpoint>(
+                "SELECT * FROM webhook_endpoints WHERE transaction_id IS NULL \
+                 ORDER BY created_at, id",
+            )
+            .fetch_all(pool)
+            .await
+        },
+    )
+    .await
+}
 
-            if stellar_account.is_some() {
-                conditions.push(format!("stellar_account = ${}", param_count));
-                param_count += 1;
-            }
+pub async fn get_webhook_endpoint(pool: &PgPool, id: Uuid) -> Result<WebhookEndpoint> {
+    with_timeout(
+        QueryTier::Read,
+        "SELECT * FROM webhook_endpoints WHERE id = $1",
+        async {
+            sqlx::query_as::<_, WebhookEndpoint>("SELECT * FROM webhook_endpoints WHERE id = $1")
+                .bind(id)
+                .fetch_one(pool)
+                .await
+        },
+    )
+    .await
+}
 
-            // Add cursor condition
-            if cursor.is_some() {
-                conditions.push(format!(
-                    "(created_at, id) < (${}, ${})",
-                    param_count,
-                    param_count + 1
-                ));
-                param_count += 2;
-            }
+/// Delete an endpoint together with its deliveries, DLQ entries and delivery
+/// events (all `ON DELETE CASCADE`). Returns `false` if it did not exist.
+pub async fn delete_webhook_endpoint(pool: &PgPool, id: Uuid) -> Result<bool> {
+    with_timeout(
+        QueryTier::Write,
+        "DELETE FROM webhook_endpoints WHERE id = $1",
+        async {
+            let result = sqlx::query("DELETE FROM webhook_endpoints WHERE id = $1")
+                .bind(id)
+                .execute(pool)
+                .await?;
+            Ok(result.rows_affected() > 0)
+        },
+    )
+    .await
+}
 
-            let where_clause = if conditions.is_empty() {
-                String::new()
-            } else {
-                format!("WHERE {}", conditions.join(" AND "))
-            };
+/// Replace an endpoint's signing secret, keeping the old one valid until
+/// `previous_expires_at`. `None` if the endpoint does not exist.
+pub async fn rotate_webhook_endpoint_secret(
+    pool: &PgPool,
+    id: Uuid,
+    new_secret: &str,
+    previous_expires_at: DateTime<Utc>,
+) -> Result<Option<WebhookEndpoint>> {
+    with_timeout(
+        QueryTier::Write,
+        "UPDATE webhook_endpoints SET secret = $2, previous_secret = secret WHERE id = $1",
+        async {
+            sqlx::query_as::<_, WebhookEndpoint>(
+                r#"
+                UPDATE webhook_endpoints
+                SET previous_secret = secret,
+                    previous_secret_expires_at = $3,
+                    secret = $2,
+                    updated_at = NOW()
+                WHERE id = $1
+                RETURNING *
+                "#,
+            )
+            .bind(id)
+            .bind(new_secret)
+            .bind(previous_expires_at)
+            .fetch_optional(pool)
+            .await
+        },
+    )
+    .await
+}
 
-            // Build count query
-            let count_query = format!(
-                "SELECT COUNT(*) as count FROM transactions {}",
-                where_clause
-            );
+/// Re-enable an endpoint and clear its pause and failure-streak state.
+/// Queued deliveries resume on the next dispatcher cycle. `None` if the
+/// endpoint does not exist.
+pub async fn enable_webhook_endpoint(pool: &PgPool, id: Uuid) -> Result<Option<WebhookEndpoint>> {
+    with_timeout(
+        QueryTier::Write,
+        "UPDATE webhook_endpoints SET enabled = TRUE WHERE id = $1",
+        async {
+            sqlx::query_as::<_, WebhookEndpoint>(
+                r#"
+                UPDATE webhook_endpoints
+                SET enabled = TRUE,
+                    failing_since = NULL,
+                    paused_at = NULL,
+                    pause_reason = NULL,
+                    updated_at = NOW()
+                WHERE id = $1
+                RETURNING *
+                "#,
+            )
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+        },
+    )
+    .await
+}
 
-            // Build data query with pagination.
-            // ORDER BY is aligned with idx_transactions_status_asset_created
-            // (status, asset_code, created_at DESC) so the planner can use an
-            // index scan instead of a sequential scan + sort.
-            let data_query = format!(
-                "SELECT * FROM transactions {} ORDER BY created_at DESC, id DESC LIMIT ${}",
-                where_clause, param_count
-            );
+async fn persist_transaction(
+    db_tx: &mut SqlxTransaction<'_, Postgres>,
+    tx: &Transaction,
+) -> Result<Transaction> {
+    sqlx::query_as::<_, Transaction>(
+        r#"
+        INSERT INTO transactions (
+            id, stellar_account, amount, asset_code, status,
+            created_at, updated_at, anchor_transaction_id, callback_type, callback_status,
+            settlement_id, memo, memo_type, metadata, backfilled, stellar_tx_hash,
+            ledger, closed_at, stellar_muxed_id, fee_amount
+        ) VALUES (
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18,
+            $19, $20
+        )
+        RETURNING *
+        "#,
+    )
+    .bind(tx.id)
+    .bind(&tx.stellar_account)
+    .bind(&tx.amount)
+    .bind(&tx.asset_code)
+    .bind(&tx.status)
+    .bind(tx.created_at)
+    .bind(tx.updated_at)
+    .bind(&tx.anchor_transaction_id)
+    .bind(&tx.callback_type)
+    .bind(&tx.callback_status)
+    .bind(tx.settlement_id)
+    .bind(&tx.memo)
+    .bind(&tx.memo_type)
+    .bind(&tx.metadata)
+    .bind(tx.backfilled)
+    .bind(&tx.stellar_tx_hash)
+    .bind(tx.ledger)
+    .bind(tx.closed_at)
+    .bind(&tx.stellar_muxed_id)
+    .bind(&tx.fee_amount)
+    .fetch_one(&mut **db_tx)
+    .await
+}
 
-            // Execute count query
-            let mut count_query_builder = sqlx::query(&count_query);
+async fn audit_transaction_creation(
+    db_tx: &mut SqlxTransaction<'_, Postgres>,
+    result: &Transaction,
+) -> Result<()> {
+    AuditLog::log_creation(
+        db_tx,
+        result.id,
+        ENTITY_TRANSACTION,
+        json!({
+            "stellar_account": result.stellar_account,
+            "amount": result.amount.to_string(),
+            "asset_code": result.asset_code,
+            "status": result.status,
+            "anchor_transaction_id": result.anchor_transaction_id,
+            "callback_type": result.callback_type,
+            "callback_status": result.callback_status,
+            "memo": result.memo,
+            "memo_type": result.memo_type,
+            "metadata": result.metadata,
+            "backfilled": result.backfilled,
+            "stellar_tx_hash": result.stellar_tx_hash,
+            "ledger": result.ledger,
+            "closed_at": result.closed_at,
+            "stellar_muxed_id": result.stellar_muxed_id.as_ref().map(ToString::to_string),
+        }),
+        "system",
+    )
+    .await
+}
 
-            if let Some(s) = status {
-                count_query_builder = count_query_builder.bind(s);
-            }
-            if let Some(a) = asset_code {
-                count_query_builder = count_query_builder.bind(a);
-            }
-            if let Some(min) = min_amount {
-                count_query_builder = count_query_builder.bind(min);
-            }
-            if let Some(max) = max_amount {
-                count_query_builder = count_query_builder.bind(max);
-            }
-            if let Some(from) = from_date {
-                count_query_builder = count_query_builder.bind(from);
-            }
-            if let Some(to) = to_date {
-                count_query_builder = count_query_builder.bind(to);
-            }
-            if let Some(acc) = stellar_account {
-                count_query_builder = count_query_builder.bind(acc);
-            }
+pub async fn get_transaction(pool: &PgPool, id: Uuid) -> Result<Transaction> {
+    with_timeout(
+        QueryTier::Read,
+        "SELECT * FROM transactions WHERE id = $1",
+        sqlx::query_as::<_, Transaction>("SELECT * FROM transactions WHERE id = $1")
+            .bind(id)
+            .fetch_one(pool),
+    )
+    .await
+}
+
+pub async fn list_transactions(
+    pool: &PgPool,
+    limit: i64,
+    cursor: Option<(DateTime<Utc>, Uuid)>,
+    backward: bool,
+) -> Result<Vec<Transaction>> {
+    with_timeout(
+        QueryTier::Read,
+        "SELECT * FROM transactions [cursor-paginated]",
+        async {
             if let Some((ts, id)) = cursor {
-                count_query_builder = count_query_builder.bind(ts).bind(id);
-            }
+                if !backward {
+                    let q = sqlx::query_as::<_, Transaction>(
+                        "SELECT * FROM transactions WHERE (created_at, id) < ($1, $2) ORDER BY created_at DESC, id DESC LIMIT $3",
+                    )
+                    .bind(ts)
+                    .bind(id)
+                    .bind(limit)
+                    .fetch_all(pool)
+                    .await?;
+                    Ok(q)
+                } else {
+                    let mut rows = sqlx::query_as::<_, Transaction>(
+                        "SELECT * FROM transactions WHERE (created_at, id) > ($1, $2) ORDER BY created_at ASC, id ASC LIMIT $3",
+                    )
+                    .bind(ts)
+                    .bind(id)
+                    .bind(limit)
+                    .fetch_all(pool)
+                    .await?;
+                    rows.reverse();
+                    Ok(rows)
+                }
+            } else if !backward {
+                let q = sqlx::query_as::<_, Transaction>(
+                    "SELECT * FROM transactions ORDER BY created_at DESC, id DESC LIMIT $1",
+                )
+                .bind(limit)
+                .fetch_all(pool)
+                .await?;
+                Ok(q)
+            } else {
+                let mut rows = sqlx::query_as::<_, Transaction>(
+                    "SELECT * FROM transactions ORDER BY created_at ASC, id ASC LIMIT $1",
+                )
+                .bind(limit)
+                .fetch_all(pool)
+                .await?;
+                rows.reverse();
+                Ok(rows)
+            }
+        },
+    )
+    .await
+}
+
+pub async fn list_transactions_filtered(
+    pool: &PgPool,
+    limit: i64,
+    cursor: Option<(DateTime<Utc>, Uuid)>,
+    backward: bool,
+    from_date: Option<DateTime<Utc>>,
+    to_date: Option<DateTime<Utc>>,
+) -> Result<Vec<Transaction>> {
+    with_timeout(
+        QueryTier::Read,
+        "SELECT * FROM transactions [filtered cursor-paginated]",
+        async {
+            let mut conditions: Vec<String> = Vec::new();
+            let mut bind_idx = 1i32;
+
+            if cursor.is_some() {
+                if !backward {
+                    conditions.push(format!(
+                        "(created_at, id) < (${}, ${})",
+                        bind_idx,
+                        bind_idx + 1
+                    ));
+                } else {
+                    conditions.push(format!(
+                        "(created_at, id) > (${}, ${})",
+                        bind_idx,
+                        bind_idx + 1
+                    ));
+                }
+                bind_idx += 2;
+            }
+
+            if from_date.is_some() {
+                conditions.push(format!("created_at >= ${}", bind_idx));
+                bind_idx += 1;
+            }
+            if to_date.is_some() {
+                conditions.push(format!("created_at <= ${}", bind_idx));
+                bind_idx += 1;
+            }
+
+            let where_clause = if conditions.is_empty() {
+                String::new()
+            } else {
+                format!("WHERE {}", conditions.join(" AND "))
+            };
+
+            let order = if !backward {
+                "ORDER BY created_at DESC, id DESC"
+            } else {
+                "ORDER BY created_at ASC, id ASC"
+            };
+
+            let sql = format!(
+                "SELECT * FROM transactions {} {} LIMIT ${}",
+                where_clause, order, bind_idx
+            );
+
+            let mut q = sqlx::query_as::<_, Transaction>(&sql);
+
+            if let Some((ts, id)) = cursor {
+                q = q.bind(ts).bind(id);
+            }
+            if let Some(from) = from_date {
+                q = q.bind(from);
+            }
+            if let Some(to) = to_date {
+                q = q.bind(to);
+            }
+            q = q.bind(limit);
+
+            let mut rows = q.fetch_all(pool).await?;
+            if backward {
+                rows.reverse();
+            }
+            Ok(rows)
+        },
+    )
+    .await
+}
+
+/// Transactions with `(updated_at, id)` after `after` and `updated_at` at or
+/// before `until`, oldest change first. Backs `GET /transactions/changes`.
+pub async fn list_transaction_changes(
+    pool: &PgPool,
+    after: (DateTime<Utc>, Uuid),
+    until: DateTime<Utc>,
+    tenant_id: Option<Uuid>,
+    limit: i64,
+) -> Result<Vec<Transaction>> {
+    with_timeout(
+        QueryTier::Read,
+        "SELECT * FROM transactions [changes since cursor]",
+        async {
+            sqlx::query_as::<_, Transaction>(
+                "SELECT * FROM transactions \
+                 WHERE (updated_at, id) > ($1, $2) AND updated_at <= $3 \
+                   AND ($4::uuid IS NULL OR tenant_id = $4) \
+                 ORDER BY updated_at, id LIMIT $5",
+            )
+            .bind(after.0)
+            .bind(after.1)
+            .bind(until)
+            .bind(tenant_id)
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+        },
+    )
+    .await
+}
+
+/// Tombstones with `(removed_at, transaction_id)` after `after` and
+/// `removed_at` at or before `until`, oldest first.
+pub async fn list_transaction_tombstones(
+    pool: &PgPool,
+    after: (DateTime<Utc>, Uuid),
+    until: DateTime<Utc>,
+    tenant_id: Option<Uuid>,
+    limit: i64,
+) -> Result<Vec<TransactionTombstone>> {
+    with_timeout(
+        QueryTier::Read,
+        "SELECT * FROM transaction_tombstones [since cursor]",
+        async {
+            sqlx::query_as::<_, TransactionTombstone>(
+                "SELECT transaction_id, tenant_id, reason, removed_at FROM transaction_tombstones \
+                 WHERE (removed_at, transaction_id) > ($1, $2) AND removed_at <= $3 \
+                   AND ($4::uuid IS NULL OR tenant_id = $4) \
+                 ORDER BY removed_at, transaction_id LIMIT $5",
+            )
+            .bind(after.0)
+            .bind(after.1)
+            .bind(until)
+            .bind(tenant_id)
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+        },
+    )
+    .await
+}
+
+pub async fn get_unsettled_transactions(
+    executor: &mut SqlxTransaction<'_, Postgres>,
+    asset_code: &str,
+    end_time: DateTime<Utc>,
+) -> Result<Vec<Transaction>> {
+    with_timeout(
+        QueryTier::Read,
+        "SELECT * FROM transactions WHERE status = 'completed' AND settlement_id IS NULL FOR UPDATE",
+        sqlx::query_as::<_, Transaction>(
+            r#"
+        SELECT * FROM transactions
+        WHERE status = 'completed'
+        AND settlement_id IS NULL
+        AND asset_code = $1
+        AND updated_at <= $2
+        FOR UPDATE
+        "#,
+        )
+        .bind(asset_code)
+        .bind(end_time)
+        .fetch_all(&mut **executor),
+    )
+    .await
+}
+
+pub async fn update_transactions_settlement(
+    executor: &mut SqlxTransaction<'_, Postgres>,
+    tx_ids: &[Uuid],
+    settlement_id: Uuid,
+) -> Result<()> {
+    with_timeout(
+        QueryTier::Write,
+        "UPDATE transactions SET settlement_id = $1 WHERE id = ANY($2)",
+        async {
+            sqlx::query(
+                "UPDATE transactions SET settlement_id = $1, updated_at = NOW() WHERE id = ANY($2)",
+            )
+            .bind(settlement_id)
+            .bind(tx_ids)
+            .execute(&mut **executor)
+            .await?;
+
+            // Audit log: record settlement_id update for each transaction
+            for tx_id in tx_ids {
+                AuditLog::log_field_update(
+                    executor,
+                    *tx_id,
+                    ENTITY_TRANSACTION,
+                    "settlement_id",
+                    json!(null),
+                    json!(settlement_id.to_string()),
+                    "system",
+                )
+                .await?;
+            }
+
+            Ok(())
+        },
+    )
+    .await
+}
+
+// ---------------------------------------------------------------------------
+// Cache invalidation helper
+// ---------------------------------------------------------------------------
+
+async fn invalidate_transaction_caches(asset_code: &str) {
+    if let Ok(redis_url) = std::env::var("REDIS_URL") {
+        if let Ok(cache) = crate::services::QueryCache::new(&redis_url).await {
+            let _ = cache.invalidate("query:status_counts").await;
+            let _ = cache.invalidate("query:daily_totals:*").await;
+            let _ = cache.invalidate("query:asset_stats").await;
+            let _ = cache
+                .invalidate_exact(&format!("query:asset_total:{}", asset_code))
+                .await;
+        }
+    }
+}
+
+/// Public cache invalidation function for use by other modules
+pub async fn invalidate_caches_for_asset(asset_code: &str) {
+    invalidate_transaction_caches(asset_code).await;
+}
+
+// ---------------------------------------------------------------------------
+// Settlement Queries
+// ---------------------------------------------------------------------------
+
+pub async fn insert_settlement(
+    executor: &mut SqlxTransaction<'_, Postgres>,
+    settlement: &Settlement,
+) -> Result<Settlement> {
+    with_timeout(
+        QueryTier::Write,
+        "INSERT INTO settlements ... RETURNING *",
+        sqlx::query_as::<_, Settlement>(
+            r#"
+        INSERT INTO settlements (
+            id, asset_code, total_amount, tx_count, period_start, period_end, status, created_at,
+            updated_at, quoted_payouts
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        RETURNING *
+        "#,
+        )
+        .bind(settlement.id)
+        .bind(&settlement.asset_code)
+        .bind(&settlement.total_amount)
+        .bind(settlement.tx_count)
+        .bind(settlement.period_start)
+        .bind(settlement.period_end)
+        .bind(&settlement.status)
+        .bind(settlement.created_at)
+        .bind(settlement.updated_at)
+        .bind(&settlement.quoted_payouts)
+        .fetch_one(&mut **executor),
+    )
+    .await
+}
+
+pub async fn get_settlement(pool: &PgPool, id: Uuid) -> Result<Settlement> {
+    with_timeout(
+        QueryTier::Read,
+        "SELECT * FROM settlements WHERE id = $1",
+        sqlx::query_as::<_, Settlement>("SELECT * FROM settlements WHERE id = $1")
+            .bind(id)
+            .fetch_one(pool),
+    )
+    .await
+}
+
+/// Distinct tenants whose transactions are included in a settlement.
+pub async fn get_settlement_partner_ids(pool: &PgPool, settlement_id: Uuid) -> Result<Vec<Uuid>> {
+    with_timeout(
+        QueryTier::Read,
+        "SELECT DISTINCT tenant_id FROM transactions WHERE settlement_id = $1",
+        sqlx::query_scalar::<_, Uuid>(
+            "SELECT DISTINCT tenant_id FROM transactions WHERE settlement_id = $1 AND tenant_id IS NOT NULL",
+        )
+        .bind(settlement_id)
+        .fetch_all(pool),
+    )
+    .await
+}
+
+pub async fn list_settlements(pool: &PgPool, limit: i64, offset: i64) -> Result<Vec<Settlement>> {
+    with_timeout(
+        QueryTier::Read,
+        "SELECT * FROM settlements ORDER BY created_at DESC LIMIT $1 OFFSET $2",
+        sqlx::query_as::<_, Settlement>(
+            "SELECT * FROM settlements ORDER BY created_at DESC LIMIT $1 OFFSET $2",
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool),
+    )
+    .await
+}
+
+/// Cursor-based settlement listing used by the settlements handler.
+pub async fn list_settlements_cursor(
+    pool: &PgPool,
+    limit: i64,
+    cursor: Option<(DateTime<Utc>, Uuid)>,
+    backward: bool,
+) -> Result<Vec<Settlement>> {
+    with_timeout(
+        QueryTier::Read,
+        "SELECT * FROM settlements [cursor-paginated]",
+        async {
+            if let Some((ts, id)) = cursor {
+                if !backward {
+                    sqlx::query_as::<_, Settlement>(
+                        "SELECT * FROM settlements WHERE (created_at, id) < ($1, $2) ORDER BY created_at DESC, id DESC LIMIT $3",
+                    )
+                    .bind(ts).bind(id).bind(limit)
+                    .fetch_all(pool).await
+                } else {
+                    let mut rows = sqlx::query_as::<_, Settlement>(
+                        "SELECT * FROM settlements WHERE (created_at, id) > ($1, $2) ORDER BY created_at ASC, id ASC LIMIT $3",
+                    )
+                    .bind(ts).bind(id).bind(limit)
+                    .fetch_all(pool).await?;
+                    rows.reverse();
+                    Ok(rows)
+                }
+            } else if !backward {
+                sqlx::query_as::<_, Settlement>(
+                    "SELECT * FROM settlements ORDER BY created_at DESC, id DESC LIMIT $1",
+                )
+                .bind(limit)
+                .fetch_all(pool).await
+            } else {
+                let mut rows = sqlx::query_as::<_, Settlement>(
+                    "SELECT * FROM settlements ORDER BY created_at ASC, id ASC LIMIT $1",
+                )
+                .bind(limit)
+                .fetch_all(pool).await?;
+                rows.reverse();
+                Ok(rows)
+            }
+        },
+    )
+    .await
+}
+
+/// Update settlement status with reason; returns the updated settlement.
+pub async fn update_settlement_status(
+    pool: &PgPool,
+    id: Uuid,
+    new_status: &str,
+    reason: Option<&str>,
+    new_total: Option<&sqlx::types::BigDecimal>,
+    actor: &str,
+) -> Result<Settlement> {
+    let mut db_tx = pool.begin().await?;
+
+    let current =
+        sqlx::query_as::<_, Settlement>("SELECT * FROM settlements WHERE id = $1 FOR UPDATE")
+            .bind(id)
+            .fetch_optional(&mut *db_tx)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+
+    // Preserve original amount on first adjustment
+    let original_total = if current.original_total_amount.is_none() && new_total.is_some() {
+        Some(current.total_amount.clone())
+    } else {
+        current.original_total_amount.clone()
+    };
+
+    let updated = sqlx::query_as::<_, Settlement>(
+        r#"
+        UPDATE settlements SET
+            status = $1,
+            dispute_reason = COALESCE($2, dispute_reason),
+            total_amount = COALESCE($3, total_amount),
+            original_total_amount = COALESCE($4, original_total_amount),
+            reviewed_by = $5,
+            reviewed_at = NOW(),
+            updated_at = NOW()
+        WHERE id = $6
+        RETURNING *
+        "#,
+    )
+    .bind(new_status)
+    .bind(reason)
+    .bind(new_total)
+    .bind(original_total)
+    .bind(actor)
+    .bind(id)
+    .fetch_one(&mut *db_tx)
+    .await?;
+
+    // If voided, release transactions back to unsettled
+    if new_status == "voided" {
+        sqlx::query(
+            "UPDATE transactions SET settlement_id = NULL, updated_at = NOW() WHERE settlement_id = $1",
+        )
+        .bind(id)
+        .execute(&mut *db_tx)
+        .await?;
+    }
+
+    crate::db::audit::AuditLog::log(
+        &mut db_tx,
+        id,
+        crate::db::audit::ENTITY_SETTLEMENT,
+        "status_update",
+        Some(serde_json::json!({ "status": current.status })),
+        Some(serde_json::json!({ "status": new_status, "reason": reason })),
+        actor,
+    )
+    .await?;
+
+    db_tx.commit().await?;
+    Ok(updated)
+}
+
+/// Void settlement `id` whatever its status, within `db_tx`: release its
+/// transactions back to unsettled, mark it `voided`, write the compensating
+/// [`SettlementReversal`] and audit the change. Returns `None` when the
+/// settlement was already voided.
+pub async fn void_settlement(
+    db_tx: &mut SqlxTransaction<'_, Postgres>,
+    id: Uuid,
+    reason: &str,
+    proposed_by: &str,
+    approved_by: &str,
+) -> Result<Option<(Settlement, SettlementReversal)>> {
+    let current =
+        sqlx::query_as::<_, Settlement>("SELECT * FROM settlements WHERE id = $1 FOR UPDATE")
+            .bind(id)
+            .fetch_optional(&mut **db_tx)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+    if current.status == "voided" {
+        return Ok(None);
+    }
+
+    let released: Vec<Uuid> = sqlx::query_scalar(
+        "UPDATE transactions SET settlement_id = NULL, updated_at = NOW() \
+         WHERE settlement_id = $1 RETURNING id",
+    )
+    .bind(id)
+    .fetch_all(&mut **db_tx)
+    .await?;
+
+    let voided = sqlx::query_as::<_, Settlement>(
+        r#"
+        UPDATE settlements SET
+            status = 'voided',
+            dispute_reason = $2,
+            reviewed_by = $3,
+            reviewed_at = NOW(),
+            updated_at = NOW()
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(reason)
+    .bind(approved_by)
+    .fetch_one(&mut **db_tx)
+    .await?;
+
+    let reversal = sqlx::query_as::<_, SettlementReversal>(
+        r#"
+        INSERT INTO settlement_reversals (
+            settlement_id, asset_code, amount, tx_count, released_transaction_ids,
+            previous_status, reason, proposed_by, approved_by
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(&current.asset_code)
+    .bind(-current.total_amount.clone())
+    .bind(released.len() as i32)
+    .bind(&released)
+    .bind(&current.status)
+    .bind(reason)
+    .bind(proposed_by)
+    .bind(approved_by)
+    .fetch_one(&mut **db_tx)
+    .await?;
+
+    crate::db::audit::AuditLog::log(
+        db_tx,
+        id,
+        crate::db::audit::ENTITY_SETTLEMENT,
+        "void",
+        Some(json!({
+            "status": current.status,
+            "total_amount": current.total_amount,
+            "tx_count": current.tx_count,
+        })),
+        Some(json!({
+            "status": "voided",
+            "reason": reason,
+            "reversal_id": reversal.id,
+            "released_transactions": released.len(),
+            "proposed_by": proposed_by,
+        })),
+        approved_by,
+    )
+    .await?;
+
+    Ok(Some((voided, reversal)))
+}
+
+/// Closed accounting periods, most recent first.
+pub async fn list_accounting_periods(pool: &PgPool, limit: i64) -> Result<Vec<AccountingPeriod>> {
+    sqlx::query_as::<_, AccountingPeriod>(
+        "SELECT * FROM accounting_periods ORDER BY period_start DESC LIMIT $1",
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+/// The closed period starting on `period_start`, if it is closed.
+pub async fn get_accounting_period(
+    pool: &PgPool,
+    period_start: NaiveDate,
+) -> Result<Option<AccountingPeriod>> {
+    sqlx::query_as::<_, AccountingPeriod>(
+        "SELECT * FROM accounting_periods WHERE period_start = $1",
+    )
+    .bind(period_start)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Transactions created in `[starts_at, ends_at)` that are not yet in a
+/// terminal status.
+pub async fn count_unfinished_transactions(
+    pool: &PgPool,
+    starts_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+) -> Result<i64> {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM transactions \
+         WHERE created_at >= $1 AND created_at < $2 \
+           AND status IN ('pending', 'processing', 'compliance_review', 'refund_pending')",
+    )
+    .bind(starts_at)
+    .bind(ends_at)
+    .fetch_one(pool)
+    .await
+}
+
+/// Close the period `[starts_at, ends_at)`. Returns `None` when it was
+/// already closed.
+pub async fn close_accounting_period(
+    pool: &PgPool,
+    period_start: NaiveDate,
+    starts_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+    actor: &str,
+) -> Result<Option<AccountingPeriod>> {
+    let mut db_tx = pool.begin().await?;
+    let closed = sqlx::query_as::<_, AccountingPeriod>(
+        r#"
+        INSERT INTO accounting_periods (period_start, starts_at, ends_at, closed_by)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (period_start) DO NOTHING
+        RETURNING *
+        "#,
+    )
+    .bind(period_start)
+    .bind(starts_at)
+    .bind(ends_at)
+    .bind(actor)
+    .fetch_optional(&mut *db_tx)
+    .await?;
+    let Some(closed) = closed else {
+        return Ok(None);
+    };
+
+    AuditLog::log(
+        &mut db_tx,
+        closed.id,
+        ENTITY_ACCOUNTING_PERIOD,
+        "close",
+        None,
+        Some(json!({
+            "period_start": closed.period_start,
+            "starts_at": closed.starts_at,
+            "ends_at": closed.ends_at,
+        })),
+        actor,
+    )
+    .await?;
+    db_tx.commit().await?;
+    Ok(Some(closed))
+}
+
+/// Creation time and asset of the transaction or settlement an adjustment
+/// targets, or `None` when it does not exist.
+pub async fn get_adjustable_entity(
+    pool: &PgPool,
+    entity_type: &str,
+    entity_id: Uuid,
+) -> Result<Option<(DateTime<Utc>, String)>> {
+    let sql = match entity_type {
+        ENTITY_TRANSACTION => "SELECT created_at, asset_code FROM transactions WHERE id = $1",
+        ENTITY_SETTLEMENT => "SELECT created_at, asset_code FROM settlements WHERE id = $1",
+        _ => return Ok(None),
+    };
+    sqlx::query_as(sql)
+        .bind(entity_id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub struct NewAccountingAdjustment<'a> {
+    pub period_id: Uuid,
+    pub entity_type: &'a str,
+    pub entity_id: Uuid,
+    pub asset_code: &'a str,
+    pub amount: &'a BigDecimal,
+    pub reason: &'a str,
+    pub created_by: &'a str,
+}
+
+/// Record an adjusting entry, audited against the entity it corrects.
+pub async fn insert_accounting_adjustment(
+    pool: &PgPool,
+    new: &NewAccountingAdjustment<'_>,
+) -> Result<AccountingAdjustment> {
+    let mut db_tx = pool.begin().await?;
+    let adjustment = sqlx::query_as::<_, AccountingAdjustment>(
+        r#"
+        INSERT INTO accounting_adjustments (
+            period_id, entity_type, entity_id, asset_code, amount, reason, created_by
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING *
+        "#,
+    )
+    .bind(new.period_id)
+    .bind(new.entity_type)
+    .bind(new.entity_id)
+    .bind(new.asset_code)
+    .bind(new.amount)
+    .bind(new.reason)
+    .bind(new.created_by)
+    .fetch_one(&mut *db_tx)
+    .await?;
+
+    AuditLog::log(
+        &mut db_tx,
+        new.entity_id,
+        new.entity_type,
+        "accounting_adjustment",
+        None,
+        Some(json!({
+            "adjustment_id": adjustment.id,
+            "period_id": adjustment.period_id,
+            "amount": adjustment.amount,
+            "reason": adjustment.reason,
+        })),
+        new.created_by,
+    )
+    .await?;
+    db_tx.commit().await?;
+    Ok(adjustment)
+}
+
+/// Adjustments posted against closed period `period_id`, oldest first.
+pub async fn list_accounting_adjustments(
+    pool: &PgPool,
+    period_id: Uuid,
+) -> Result<Vec<AccountingAdjustment>> {
+    sqlx::query_as::<_, AccountingAdjustment>(
+        "SELECT * FROM accounting_adjustments WHERE period_id = $1 ORDER BY created_at, id",
+    )
+    .bind(period_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Partners a statement run covers: every tenant, or just `tenant_id`, as
+/// `(tenant_id, name)` in id order.
+pub async fn list_statement_partners(
+    pool: &PgPool,
+    tenant_id: Option<Uuid>,
+) -> Result<Vec<(Uuid, String)>> {
+    sqlx::query_as::<_, (Uuid, String)>(
+        "SELECT tenant_id, name FROM tenants WHERE ($1::UUID IS NULL OR tenant_id = $1) \
+         ORDER BY tenant_id",
+    )
+    .bind(tenant_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Per-asset statement totals for one partner over `[starts_at, ends_at)`.
+///
+/// Volume and fees count the partner's transactions created in the window,
+/// completed ones for the amounts; settled amounts are the partner's share of
+/// settlements created in it, voided ones excluded. Refunds are sub-minimum
+/// deposits of the window, still `refund_pending` or already refunded
+/// (`failed` after `refund_pending`).
+pub async fn partner_statement_lines(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    starts_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+) -> Result<Vec<StatementLine>> {
+    with_timeout(
+        QueryTier::Admin,
+        "SELECT ... partner statement lines",
+        sqlx::query_as::<_, StatementLine>(
+            r#"
+            WITH volume AS (
+                SELECT asset_code,
+                       COUNT(*) AS transaction_count,
+                       COUNT(*) FILTER (WHERE status = 'completed') AS completed_count,
+                       COALESCE(SUM(amount) FILTER (WHERE status = 'completed'), 0) AS volume,
+                       COALESCE(SUM(fee_amount) FILTER (WHERE status = 'completed'), 0)
+                           AS fees_charged
+                FROM transactions
+                WHERE tenant_id = $1 AND created_at >= $2 AND created_at < $3
+                GROUP BY asset_code
+            ),
+            settled AS (
+                SELECT t.asset_code,
+                       COUNT(DISTINCT s.id) AS settlement_count,
+                       SUM(t.amount) AS settled_amount
+                FROM transactions t
+                JOIN settlements s ON s.id = t.settlement_id
+                WHERE t.tenant_id = $1 AND s.created_at >= $2 AND s.created_at < $3
+                  AND s.status <> 'voided'
+                GROUP BY t.asset_code
+            ),
+            refunds AS (
+                SELECT t.asset_code, COUNT(*) AS refund_count, SUM(t.amount) AS refund_amount
+                FROM transactions t
+                WHERE t.tenant_id = $1 AND t.created_at >= $2 AND t.created_at < $3
+                  AND (t.status = 'refund_pending'
+                       OR (t.status = 'failed' AND EXISTS (
+                           SELECT 1 FROM audit_logs a
+                           WHERE a.entity_id = t.id AND a.entity_type = 'transaction'
+                             AND a.old_val->>'status' = 'refund_pending')))
+                GROUP BY t.asset_code
+            )
+            SELECT asset_code,
+                   COALESCE(v.transaction_count, 0) AS transaction_count,
+                   COALESCE(v.completed_count, 0) AS completed_count,
+                   COALESCE(v.volume, 0) AS volume,
+                   COALESCE(v.fees_charged, 0) AS fees_charged,
+                   COALESCE(s.settlement_count, 0) AS settlement_count,
+                   COALESCE(s.settled_amount, 0) AS settled_amount,
+                   COALESCE(r.refund_count, 0) AS refund_count,
+                   COALESCE(r.refund_amount, 0) AS refund_amount
+            FROM volume v
+            FULL JOIN settled s USING (asset_code)
+            FULL JOIN refunds r USING (asset_code)
+            ORDER BY asset_code
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(starts_at)
+        .bind(ends_at)
+        .fetch_all(pool),
+    )
+    .await
+}
+
+/// Record a generated statement, replacing the totals of an earlier run for
+/// the same partner and month (the id is kept).
+pub async fn upsert_partner_statement(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    period_start: NaiveDate,
+    job_id: Uuid,
+    lines: &serde_json::Value,
+) -> Result<PartnerStatement> {
+    sqlx::query_as::<_, PartnerStatement>(
+        r#"
+        INSERT INTO partner_statements (tenant_id, period_start, job_id, lines)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (tenant_id, period_start) DO UPDATE
+            SET job_id = EXCLUDED.job_id, lines = EXCLUDED.lines, generated_at = NOW()
+        RETURNING *
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(period_start)
+    .bind(job_id)
+    .bind(lines)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn get_partner_statement(pool: &PgPool, id: Uuid) -> Result<Option<PartnerStatement>> {
+    sqlx::query_as::<_, PartnerStatement>("SELECT * FROM partner_statements WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Statements, latest month first, optionally narrowed to one month or
+/// partner.
+pub async fn list_partner_statements(
+    pool: &PgPool,
+    period_start: Option<NaiveDate>,
+    tenant_id: Option<Uuid>,
+    limit: i64,
+) -> Result<Vec<PartnerStatement>> {
+    sqlx::query_as::<_, PartnerStatement>(
+        r#"
+        SELECT * FROM partner_statements
+        WHERE ($1::DATE IS NULL OR period_start = $1)
+          AND ($2::UUID IS NULL OR tenant_id = $2)
+        ORDER BY period_start DESC, tenant_id
+        LIMIT $3
+        "#,
+    )
+    .bind(period_start)
+    .bind(tenant_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_unique_assets_to_settle(pool: &PgPool) -> Result<Vec<String>> {
+    with_timeout(
+        QueryTier::Read,
+        "SELECT DISTINCT asset_code FROM transactions WHERE status = 'completed' AND settlement_id IS NULL",
+        async {
+            let rows = sqlx::query(
+                "SELECT DISTINCT asset_code FROM transactions WHERE status = 'completed' AND settlement_id IS NULL"
+            )
+            .fetch_all(pool)
+            .await?;
+
+            Ok(rows
+                .into_iter()
+                .map(|r| r.get::<String, _>("asset_code"))
+                .collect())
+        },
+    )
+    .await
+}
+
+// ---------------------------------------------------------------------------
+// Transaction Search
+// ---------------------------------------------------------------------------
+
+#[allow(clippy::too_many_arguments)]
+pub async fn search_transactions(
+    pool: &PgPool,
+    status: Option<&str>,
+    asset_code: Option<&str>,
+    min_amount: Option<&BigDecimal>,
+    max_amount: Option<&BigDecimal>,
+    from_date: Option<DateTime<Utc>>,
+    to_date: Option<DateTime<Utc>>,
+    stellar_account: Option<&StellarAddress>,
+    metadata: Option<&serde_json::Value>,
+    limit: i64,
+    cursor: Option<(DateTime<Utc>, Uuid)>,
+) -> Result<(i64, Vec<Transaction>)> {
+    with_timeout(
+        QueryTier::Read,
+        "search_transactions [dynamic WHERE clause]",
+        async {
+            // Build dynamic WHERE clause
+            let mut conditions = Vec::new();
+            let mut param_count = 1;
+
+            if status.is_some() {
+                conditions.push(format!("status = ${}::transaction_status", param_count));
+                param_count += 1;
+            }
+
+            if asset_code.is_some() {
+                conditions.push(format!("asset_code = ${}", param_count));
+                param_count += 1;
+            }
+
+            if min_amount.is_some() {
+                conditions.push(format!("amount >= ${}", param_count));
+                param_count += 1;
+            }
+
+            if max_amount.is_some() {
+                conditions.push(format!("amount <= ${}", param_count));
+                param_count += 1;
+            }
+
+            if from_date.is_some() {
+                conditions.push(format!("created_at >= ${}", param_count));
+                param_count += 1;
+            }
+
+            if to_date.is_some() {
+                conditions.push(format!("created_at <= ${}", param_count));
+                param_count += 1;
+            }
+
+            // A `G...` account matches its muxed sub-accounts too; an `M...`
+            // address only its own id.
+            if let Some(address) = stellar_account {
+                conditions.push(format!("stellar_account = ${}", param_count));
+                param_count += 1;
+                if address.is_muxed() {
+                    conditions.push(format!("stellar_muxed_id = ${}", param_count));
+                    param_count += 1;
+                }
+            }
+
+            // Containment is served by idx_transactions_metadata_gin (jsonb_path_ops).
+            if metadata.is_some() {
+                conditions.push(format!("metadata @> ${}", param_count));
+                param_count += 1;
+            }
+
+            // Add cursor condition
+            if cursor.is_some() {
+                conditions.push(format!(
+                    "(created_at, id) < (${}, ${})",
+                    param_count,
+                    param_count + 1
+                ));
+                param_count += 2;
+            }
+
+            let where_clause = if conditions.is_empty() {
+                String::new()
+            } else {
+                format!("WHERE {}", conditions.join(" AND "))
+            };
+
+            // Build count query
+            let count_query = format!(
+                "SELECT COUNT(*) as count FROM transactions {}",
+                where_clause
+            );
+
+            // Build data query with pagination.
+            // ORDER BY is aligned with idx_transactions_status_asset_created
+            // (status, asset_code, created_at DESC) so the planner can use an
+            // index scan instead of a sequential scan + sort.
+            let data_query = format!(
+                "SELECT * FROM transactions {} ORDER BY created_at DESC, id DESC LIMIT ${}",
+                where_clause, param_count
+            );
+
+            // Execute count query
+            let mut count_query_builder = sqlx::query(&count_query);
+
+            if let Some(s) = status {
+                count_query_builder = count_query_builder.bind(s);
+            }
+            if let Some(a) = asset_code {
+                count_query_builder = count_query_builder.bind(a);
+            }
+            if let Some(min) = min_amount {
+                count_query_builder = count_query_builder.bind(min);
+            }
+            if let Some(max) = max_amount {
+                count_query_builder = count_query_builder.bind(max);
+            }
+            if let Some(from) = from_date {
+                count_query_builder = count_query_builder.bind(from);
+            }
+            if let Some(to) = to_date {
+                count_query_builder = count_query_builder.bind(to);
+            }
+            if let Some(address) = stellar_account {
+                count_query_builder = count_query_builder.bind(address.account());
+                if let Some(id) = address.muxed_id() {
+                    count_query_builder = count_query_builder.bind(BigDecimal::from(id));
+                }
+            }
+            if let Some(m) = metadata {
+                count_query_builder = count_query_builder.bind(m);
+            }
+            if let Some((ts, id)) = cursor {
+                count_query_builder = count_query_builder.bind(ts).bind(id);
+            }
+
+            let count_row = count_query_builder.fetch_one(pool).await?;
+            let total: i64 = count_row.try_get("count")?;
+
+            // Execute data query
+            let mut data_query_builder = sqlx::query_as::<_, Transaction>(&data_query);
+
+            if let Some(s) = status {
+                data_query_builder = data_query_builder.bind(s);
+            }
+            if let Some(a) = asset_code {
+                data_query_builder = data_query_builder.bind(a);
+            }
+            if let Some(min) = min_amount {
+                data_query_builder = data_query_builder.bind(min);
+            }
+            if let Some(max) = max_amount {
+                data_query_builder = data_query_builder.bind(max);
+            }
+            if let Some(from) = from_date {
+                data_query_builder = data_query_builder.bind(from);
+            }
+            if let Some(to) = to_date {
+                data_query_builder = data_query_builder.bind(to);
+            }
+            if let Some(address) = stellar_account {
+                data_query_builder = data_query_builder.bind(address.account());
+                if let Some(id) = address.muxed_id() {
+                    data_query_builder = data_query_builder.bind(BigDecimal::from(id));
+                }
+            }
+            if let Some(m) = metadata {
+                data_query_builder = data_query_builder.bind(m);
+            }
+            if let Some((ts, id)) = cursor {
+                data_query_builder = data_query_builder.bind(ts).bind(id);
+            }
+            data_query_builder = data_query_builder.bind(limit);
+
+            let transactions = data_query_builder.fetch_all(pool).await?;
+
+            Ok((total, transactions))
+        },
+    )
+    .await
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::Ordering;
+    use tokio::time::Duration;
+
+    /// Verify that `with_timeout` fires when the future exceeds the deadline.
+    #[tokio::test]
+    async fn test_with_timeout_triggers_on_slow_future() {
+        // Override env so the timeout is 1 ms
+        std::env::set_var("DB_TIMEOUT_READ_SECS", "0");
+
+        let result = with_timeout(QueryTier::Read, "SELECT pg_sleep(10)", async {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            // This line is never reached
+            Err::<(), _>(sqlx::Error::RowNotFound)
+        })
+        .await;
+
+        assert!(
+            matches!(result, Err(sqlx::Error::PoolTimedOut)),
+            "Expected PoolTimedOut, got {:?}",
+            result
+        );
+
+        // Counter must have been incremented
+        assert!(
+            DB_QUERY_TIMEOUT_TOTAL.load(Ordering::Relaxed) >= 1,
+            "Timeout counter should be >= 1"
+        );
+
+        // Restore
+        std::env::remove_var("DB_TIMEOUT_READ_SECS");
+    }
+
+    /// Verify that a fast future completes without triggering the timeout.
+    #[tokio::test]
+    async fn test_with_timeout_passes_fast_future() {
+        let before = DB_QUERY_TIMEOUT_TOTAL.load(Ordering::Relaxed);
+
+        let result = with_timeout(QueryTier::Read, "SELECT 1", async {
+            Ok::<i32, sqlx::Error>(42)
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(
+            DB_QUERY_TIMEOUT_TOTAL.load(Ordering::Relaxed),
+            before,
+            "Counter should not change for a fast query"
+        );
+    }
+}
+
+// --- Audit Log Search Query ---
+
+/// Parameters for searching audit logs across all entities.
+#[derive(Debug, Default)]
+pub struct AuditSearchParams<'a> {
+    pub actor: Option<&'a str>,
+    pub action: Option<&'a str>,
+    pub from_date: Option<DateTime<Utc>>,
+    pub to_date: Option<DateTime<Utc>>,
+    pub entity_type: Option<&'a str>,
+    pub limit: i64,
+    /// Cursor: (timestamp, id) of the last seen row for keyset pagination.
+    pub cursor: Option<(DateTime<Utc>, Uuid)>,
+}
+
+/// A single row returned by the audit search query.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditLogRow {
+    pub id: Uuid,
+    pub entity_id: Uuid,
+    pub entity_type: String,
+    pub action: String,
+    pub old_val: Option<serde_json::Value>,
+    pub new_val: Option<serde_json::Value>,
+    pub actor: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Search audit logs with optional filters and cursor-based pagination.
+/// Returns `(total_count, rows)`.
+#[allow(clippy::too_many_arguments)]
+pub async fn search_audit_logs(
+    pool: &PgPool,
+    params: &AuditSearchParams<'_>,
+) -> Result<(i64, Vec<AuditLogRow>)> {
+    with_timeout(
+        QueryTier::Read,
+        "search_audit_logs [dynamic WHERE clause]",
+        async {
+            let mut conditions: Vec<String> = Vec::new();
+            let mut p = 1usize;
+
+            if params.actor.is_some() {
+                conditions.push(format!("actor = ${p}"));
+                p += 1;
+            }
+            if params.action.is_some() {
+                conditions.push(format!("action = ${p}"));
+                p += 1;
+            }
+            if params.from_date.is_some() {
+                conditions.push(format!("timestamp >= ${p}"));
+                p += 1;
+            }
+            if params.to_date.is_some() {
+                conditions.push(format!("timestamp <= ${p}"));
+                p += 1;
+            }
+            if params.entity_type.is_some() {
+                conditions.push(format!("entity_type = ${p}"));
+                p += 1;
+            }
+            if params.cursor.is_some() {
+                conditions.push(format!("(timestamp, id) < (${p}, ${})", p + 1));
+                p += 2;
+            }
+
+            let where_clause = if conditions.is_empty() {
+                String::new()
+            } else {
+                format!("WHERE {}", conditions.join(" AND "))
+            };
+
+            // Bind helper — avoids repeating the bind sequence twice.
+            macro_rules! bind_filters {
+                ($q:expr) => {{
+                    let mut q = $q;
+                    if let Some(v) = params.actor {
+                        q = q.bind(v);
+                    }
+                    if let Some(v) = params.action {
+                        q = q.bind(v);
+                    }
+                    if let Some(v) = params.from_date {
+                        q = q.bind(v);
+                    }
+                    if let Some(v) = params.to_date {
+                        q = q.bind(v);
+                    }
+                    if let Some(v) = params.entity_type {
+                        q = q.bind(v);
+                    }
+                    if let Some((ts, id)) = params.cursor {
+                        q = q.bind(ts).bind(id);
+                    }
+                    q
+                }};
+            }
+
+            // Total count (ignores cursor so the caller always gets the full
+            // result-set size for the given filters).
+            let count_sql = format!(
+                "SELECT COUNT(*) FROM audit_logs {}",
+                // Strip cursor condition from count query
+                if conditions.is_empty() {
+                    String::new()
+                } else {
+                    let non_cursor: Vec<_> = conditions
+                        .iter()
+                        .filter(|c| !c.contains("timestamp, id"))
+                        .cloned()
+                        .collect();
+                    if non_cursor.is_empty() {
+                        String::new()
+                    } else {
+                        format!("WHERE {}", non_cursor.join(" AND "))
+                    }
+                }
+            );
+
+            // Re-bind without cursor for count
+            let mut count_q = sqlx::query(&count_sql);
+            if let Some(v) = params.actor {
+                count_q = count_q.bind(v);
+            }
+            if let Some(v) = params.action {
+                count_q = count_q.bind(v);
+            }
+            if let Some(v) = params.from_date {
+                count_q = count_q.bind(v);
+            }
+            if let Some(v) = params.to_date {
+                count_q = count_q.bind(v);
+            }
+            if let Some(v) = params.entity_type {
+                count_q = count_q.bind(v);
+            }
+
+            let total: i64 = count_q.fetch_one(pool).await?.try_get(0)?;
+
+            // Data query with cursor + limit
+            let data_sql = format!(
+                "SELECT id, entity_id, entity_type, action, old_val, new_val, actor, timestamp \
+                 FROM audit_logs {where_clause} \
+                 ORDER BY timestamp DESC, id DESC \
+                 LIMIT ${p}"
+            );
+
+            let data_q = bind_filters!(sqlx::query(&data_sql)).bind(params.limit);
+            let rows = data_q.fetch_all(pool).await?;
+
+            let logs = rows
+                .into_iter()
+                .map(|row| AuditLogRow {
+                    id: row.get("id"),
+                    entity_id: row.get("entity_id"),
+                    entity_type: row.get("entity_type"),
+                    action: row.get("action"),
+                    old_val: row.get("old_val"),
+                    new_val: row.get("new_val"),
+                    actor: row.get("actor"),
+                    timestamp: row.get("timestamp"),
+                })
+                .collect();
+
+            Ok((total, logs))
+        },
+    )
+    .await
+}
+
+// --- Audit Log Queries ---
+
+/// Retrieve audit logs for a specific entity using cursor-based pagination on (timestamp, id).
+pub async fn get_audit_logs(
+    pool: &PgPool,
+    entity_id: Uuid,
+    limit: i64,
+    cursor: Option<(DateTime<Utc>, Uuid)>,
+) -> Result<
+    Vec<(
+        Uuid,
+        Uuid,
+        String,
+        String,
+        Option<serde_json::Value>,
+        Option<serde_json::Value>,
+        String,
+        DateTime<Utc>,
+    )>,
+> {
+    let rows = if let Some((ts, cid)) = cursor {
+        sqlx::query(
+            r#"
+            SELECT id, entity_id, entity_type, action, old_val, new_val, actor, timestamp
+            FROM audit_logs
+            WHERE entity_id = $1 AND (timestamp, id) < ($2, $3)
+            ORDER BY timestamp DESC, id DESC
+            LIMIT $4
+            "#,
+        )
+        .bind(entity_id)
+        .bind(ts)
+        .bind(cid)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?
+    } else {
+        sqlx::query(
+            r#"
+            SELECT id, entity_id, entity_type, action, old_val, new_val, actor, timestamp
+            FROM audit_logs
+            WHERE entity_id = $1
+            ORDER BY timestamp DESC, id DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(entity_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?
+    };
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            (
+                row.get("id"),
+                row.get("entity_id"),
+                row.get("entity_type"),
+                row.get("action"),
+                row.get("old_val"),
+                row.get("new_val"),
+                row.get("actor"),
+                row.get("timestamp"),
+            )
+        })
+        .collect())
+}
+
+// --- Bulk Status Update ---
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkUpdateError {
+    pub transaction_id: Uuid,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkUpdateResult {
+    pub updated: usize,
+    pub failed: usize,
+    pub errors: Vec<BulkUpdateError>,
+}
+
+/// Bulk update transaction statuses within `db_tx`, validating each
+/// transition individually. Locks the requested rows, uses a single
+/// UPDATE ... WHERE id = ANY($1) for the valid subset, then audit-logs each
+/// successful update. Nothing lands until the caller commits.
+pub async fn bulk_update_transaction_status(
+    db_tx: &mut SqlxTransaction<'_, Postgres>,
+    transaction_ids: &[Uuid],
+    new_status: &str,
+    reason: Option<&str>,
+    actor: &str,
+) -> Result<BulkUpdateResult> {
+    use crate::validation::state_machine::validate_status_transition;
+
+    // Lock and fetch current statuses for all requested IDs in one query
+    let rows = sqlx::query(
+        "SELECT id, status::text AS status FROM transactions WHERE id = ANY($1) FOR UPDATE",
+    )
+    .bind(transaction_ids)
+    .fetch_all(&mut **db_tx)
+    .await?;
+
+    let current: std::collections::HashMap<Uuid, String> = rows
+        .into_iter()
+        .map(|r| (r.get::<Uuid, _>("id"), r.get::<String, _>("status")))
+        .collect();
+
+    let mut valid_ids: Vec<Uuid> = Vec::new();
+    let mut old_statuses: std::collections::HashMap<Uuid, String> =
+        std::collections::HashMap::new();
+    let mut errors: Vec<BulkUpdateError> = Vec::new();
+
+    for &id in transaction_ids {
+        match current.get(&id) {
+            None => errors.push(BulkUpdateError {
+                transaction_id: id,
+                error: "transaction not found".to_string(),
+            }),
+            Some(from) => match validate_status_transition(from, new_status) {
+                Ok(_) => {
+                    old_statuses.insert(id, from.clone());
+                    valid_ids.push(id);
+                }
+                Err(e) => errors.push(BulkUpdateError {
+                    transaction_id: id,
+                    error: e.to_string(),
+                }),
+            },
+        }
+    }
+
+    if valid_ids.is_empty() {
+        return Ok(BulkUpdateResult {
+            updated: 0,
+            failed: errors.len(),
+            errors,
+        });
+    }
+
+    sqlx::query(
+        "UPDATE transactions SET status = $1::transaction_status, updated_at = NOW() WHERE id = ANY($2)",
+    )
+        .bind(new_status)
+        .bind(&valid_ids)
+        .execute(&mut **db_tx)
+        .await?;
+
+    for &id in &valid_ids {
+        let old_status = old_statuses
+            .get(&id)
+            .map(|s| s.as_str())
+            .unwrap_or("unknown");
+        let mut new_val = serde_json::json!({ "status": new_status });
+        if let Some(r) = reason {
+            new_val["reason"] = serde_json::json!(r);
+        }
+        AuditLog::log(
+            db_tx,
+            id,
+            ENTITY_TRANSACTION,
+            "status_update",
+            Some(serde_json::json!({ "status": old_status })),
+            Some(new_val),
+            actor,
+        )
+        .await?;
+    }
+
+    let updated = valid_ids.len();
+    Ok(BulkUpdateResult {
+        updated,
+        failed: errors.len(),
+        errors,
+    })
+}
+
+// --- Aggregate Queries (Cacheable) ---
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusCount {
+    pub status: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyTotal {
+    pub date: String,
+    pub total_amount: BigDecimal,
+    pub tx_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetStats {
+    pub asset_code: String,
+    pub total_amount: BigDecimal,
+    pub tx_count: i64,
+    pub avg_amount: BigDecimal,
+}
+
+pub async fn get_status_counts(pool: &PgPool) -> Result<Vec<StatusCount>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT status::text AS status, COUNT(*) as count
+        FROM transactions
+        GROUP BY 1
+        ORDER BY 1
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| StatusCount {
+            status: row.get("status"),
+            count: row.get("count"),
+        })
+        .collect())
+}
+
+pub async fn get_daily_totals(pool: &PgPool, days: i32) -> Result<Vec<DailyTotal>> {
+    let end = Utc::now();
+    let start = end - chrono::Duration::days(days.into());
+    let sql = r#"
+        SELECT 
+            DATE(created_at)::text as date,
+            SUM(amount) as total_amount,
+            COUNT(*) as tx_count
+        FROM transactions
+        WHERE created_at >= $1
+          AND created_at < $2
+        GROUP BY DATE(created_at)
+        ORDER BY DATE(created_at) DESC
+        "#;
+
+    if cfg!(debug_assertions) {
+        let explain_rows = sqlx::query(&format!("EXPLAIN ANALYZE {}", sql))
+            .bind(start)
+            .bind(end)
+            .fetch_all(pool)
+            .await?;
+
+        let explain_plan = explain_rows
+            .into_iter()
+            .map(|row| row.get::<String, _>(0))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        tracing::debug!("get_daily_totals EXPLAIN ANALYZE:\n{}", explain_plan);
+    }
+
+    let rows = sqlx::query(sql)
+        .bind(start)
+        .bind(end)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| DailyTotal {
+            date: row.get("date"),
+            total_amount: row.get("total_amount"),
+            tx_count: row.get("tx_count"),
+        })
+        .collect())
+}
+
+pub async fn get_asset_stats(pool: &PgPool) -> Result<Vec<AssetStats>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            asset_code,
+            SUM(amount) as total_amount,
+            COUNT(*) as tx_count,
+            AVG(amount) as avg_amount
+        FROM transactions
+        GROUP BY asset_code
+        ORDER BY total_amount DESC
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| AssetStats {
+            asset_code: row.get("asset_code"),
+            total_amount: row.get("total_amount"),
+            tx_count: row.get("tx_count"),
+            avg_amount: row.get("avg_amount"),
+        })
+        .collect())
+}
+
+/// How many transactions verified after `retries` failed Horizon lookups.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryBucket {
+    pub retries: i32,
+    pub tx_count: i64,
+}
+
+/// DLQ entries of one error class.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DlqClassRate {
+    pub error_class: String,
+    pub entries: i64,
+    pub per_hour: f64,
+}
+
+/// Retry and backoff figures for the processor over a recent window.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessorStats {
+    pub window_hours: i32,
+    pub verified: i64,
+    pub retries_at_success: Vec<RetryBucket>,
+    /// Milliseconds from first Horizon lookup to verification.
+    pub time_to_success_p50_ms: Option<f64>,
+    pub time_to_success_p90_ms: Option<f64>,
+    pub time_to_success_p99_ms: Option<f64>,
+    pub dlq_entries: Vec<DlqClassRate>,
+}
+
+/// Retry distribution, time-to-success and DLQ entry rate per error class
+/// for transactions verified or dead-lettered in the last `hours`.
+pub async fn get_processor_stats(pool: &PgPool, hours: i32) -> Result<ProcessorStats> {
+    let since = Utc::now() - chrono::Duration::hours(hours as i64);
+
+    let retries = with_timeout(
+        QueryTier::Read,
+        "SELECT verification_attempts, COUNT(*) FROM transactions WHERE verified_at >= $1",
+        sqlx::query(
+            r#"
+            SELECT verification_attempts AS retries, COUNT(*) AS tx_count
+            FROM transactions
+            WHERE verified_at >= $1
+            GROUP BY 1
+            ORDER BY 1
+            "#,
+        )
+        .bind(since)
+        .fetch_all(pool),
+    )
+    .await?;
+
+    let timing = with_timeout(
+        QueryTier::Read,
+        "SELECT percentile_cont(...) FROM transactions WHERE verified_at >= $1",
+        sqlx::query(
+            r#"
+            WITH elapsed AS (
+                SELECT EXTRACT(EPOCH FROM verified_at - first_verification_at)::float8
+                       * 1000 AS ms
+                FROM transactions
+                WHERE verified_at >= $1 AND first_verification_at IS NOT NULL
+            )
+            SELECT percentile_cont(0.5) WITHIN GROUP (ORDER BY ms) AS p50,
+                   percentile_cont(0.9) WITHIN GROUP (ORDER BY ms) AS p90,
+                   percentile_cont(0.99) WITHIN GROUP (ORDER BY ms) AS p99
+            FROM elapsed
+            "#,
+        )
+        .bind(since)
+        .fetch_one(pool),
+    )
+    .await?;
+
+    let dlq = with_timeout(
+        QueryTier::Read,
+        "SELECT error_class, COUNT(*) FROM transaction_dlq WHERE moved_to_dlq_at >= $1",
+        sqlx::query(
+            r#"
+            SELECT error_class, COUNT(*) AS entries
+            FROM transaction_dlq
+            WHERE moved_to_dlq_at >= $1
+            GROUP BY 1
+            ORDER BY 2 DESC, 1
+            "#,
+        )
+        .bind(since)
+        .fetch_all(pool),
+    )
+    .await?;
+
+    let retries_at_success: Vec<RetryBucket> = retries
+        .into_iter()
+        .map(|row| RetryBucket {
+            retries: row.get("retries"),
+            tx_count: row.get("tx_count"),
+        })
+        .collect();
+    Ok(ProcessorStats {
+        window_hours: hours,
+        verified: retries_at_success.iter().map(|b| b.tx_count).sum(),
+        retries_at_success,
+        time_to_success_p50_ms: timing.get("p50"),
+        time_to_success_p90_ms: timing.get("p90"),
+        time_to_success_p99_ms: timing.get("p99"),
+        dlq_entries: dlq
+            .into_iter()
+            .map(|row| {
+                let entries: i64 = row.get("entries");
+                DlqClassRate {
+                    error_class: row.get("error_class"),
+                    entries,
+                    per_hour: entries as f64 / hours as f64,
+                }
+            })
+            .collect(),
+    })
+}
+
+/// Dimension for [`get_transaction_stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsGrouping {
+    Status,
+    Asset,
+    Day,
+}
+
+impl StatsGrouping {
+    fn column(self) -> &'static str {
+        match self {
+            StatsGrouping::Status => "status::text",
+            StatsGrouping::Asset => "asset_code",
+            StatsGrouping::Day => "day::text",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatsBucket {
+    pub key: String,
+    pub tx_count: i64,
+    pub volume: BigDecimal,
+}
 
-            let count_row = count_query_builder.fetch_one(pool).await?;
-            let total: i64 = count_row.try_get("count")?;
+/// Counts and volume since `since` (inclusive, UTC day), read from
+/// `transaction_daily_rollups` plus the deltas not yet compacted into it,
+/// rather than the transactions table.
+pub async fn get_transaction_stats(
+    pool: &PgPool,
+    since: chrono::NaiveDate,
+    group_by: StatsGrouping,
+) -> Result<Vec<StatsBucket>> {
+    // The grouping column comes from a closed enum, never from user input.
+    let sql = format!(
+        r#"
+        SELECT {col} AS key, SUM(tx_count)::BIGINT AS tx_count, SUM(volume) AS volume
+        FROM (
+            SELECT day, asset_code, status, tx_count, volume
+            FROM transaction_daily_rollups
+            WHERE day >= $1
+            UNION ALL
+            SELECT day, asset_code, status, tx_count, volume
+            FROM transaction_rollup_deltas
+            WHERE day >= $1
+        ) rollups
+        GROUP BY 1
+        HAVING SUM(tx_count) > 0
+        ORDER BY 1
+        "#,
+        col = group_by.column()
+    );
 
-            // Execute data query
-            let mut data_query_builder = sqlx::query_as::<_, Transaction>(&data_query);
+    let rows = with_timeout(
+        QueryTier::Read,
+        "SELECT ... FROM transaction_daily_rollups GROUP BY ...",
+        sqlx::query(&sql).bind(since).fetch_all(pool),
+    )
+    .await?;
 
-            if let Some(s) = status {
-                data_query_builder = data_query_builder.bind(s);
-            }
-            if let Some(a) = asset_code {
-                data_query_builder = data_query_builder.bind(a);
-            }
-            if let Some(min) = min_amount {
-                data_query_builder = data_query_builder.bind(min);
-            }
-            if let Some(max) = max_amount {
-                data_query_builder = data_query_builder.bind(max);
-            }
-            if let Some(from) = from_date {
-                data_query_builder = data_query_builder.bind(from);
-            }
-            if let Some(to) = to_date {
-                data_query_builder = data_query_builder.bind(to);
-            }
-            if let Some(acc) = stellar_account {
-                data_query_builder = data_query_builder.bind(acc);
-            }
-            if let Some((ts, id)) = cursor {
-                data_query_builder = data_query_builder.bind(ts).bind(id);
+    Ok(rows
+        .into_iter()
+        .map(|row| StatsBucket {
+            key: row.get("key"),
+            tx_count: row.get("tx_count"),
+            volume: row.get("volume"),
+        })
+        .collect())
+}
+
+/// Dimension for [`get_fee_revenue`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeeGrouping {
+    #[default]
+    Asset,
+    /// Partner and asset: fees in different assets are never added up.
+    Partner,
+}
+
+impl FeeGrouping {
+    fn columns(self) -> &'static str {
+        match self {
+            FeeGrouping::Asset => {
+                "t.asset_code, NULL::UUID AS tenant_id, NULL::TEXT AS partner_name"
             }
-            data_query_builder = data_query_builder.bind(limit);
+            FeeGrouping::Partner => "t.asset_code, t.tenant_id, p.name AS partner_name",
+        }
+    }
+}
 
-            let transactions = data_query_builder.fetch_all(pool).await?;
+/// Fee revenue of one asset, or of one partner in one asset.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct FeeRevenue {
+    pub asset_code: String,
+    /// Set when grouped by partner; absent for transactions of no partner.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub partner_name: Option<String>,
+    pub tx_count: i64,
+    pub volume: BigDecimal,
+    pub fees: BigDecimal,
+}
 
-            Ok((total, transactions))
+/// Fees charged on transactions created since `since` that completed,
+/// grouped by `group_by`. Transactions without a reported fee count towards
+/// `tx_count` and `volume` only.
+pub async fn get_fee_revenue(
+    pool: &PgPool,
+    since: DateTime<Utc>,
+    group_by: FeeGrouping,
+) -> Result<Vec<FeeRevenue>> {
+    // The grouping columns come from a closed enum, never from user input.
+    let sql = format!(
+        r#"
+        SELECT {cols},
+               COUNT(*) AS tx_count,
+               SUM(t.amount) AS volume,
+               COALESCE(SUM(t.fee_amount), 0) AS fees
+        FROM transactions t
+        LEFT JOIN tenants p ON p.tenant_id = t.tenant_id
+        WHERE t.status = 'completed' AND t.created_at >= $1
+        GROUP BY 1, 2, 3
+        ORDER BY 1, 3 NULLS LAST, 2
+        "#,
+        cols = group_by.columns()
+    );
+
+    with_timeout(
+        QueryTier::Read,
+        "SELECT ... SUM(fee_amount) FROM transactions GROUP BY ...",
+        sqlx::query_as::<_, FeeRevenue>(&sql)
+            .bind(since)
+            .fetch_all(pool),
+    )
+    .await
+}
+
+/// Fold every delta the maintenance trigger appended into
+/// `transaction_daily_rollups`. Deltas are deleted in the same statement, so
+/// each is counted once even if two compactions overlap. Returns the number
+/// of deltas folded.
+pub async fn compact_rollup_deltas(pool: &PgPool) -> Result<u64> {
+    let folded: i64 = with_timeout(
+        QueryTier::Admin,
+        "DELETE transaction_rollup_deltas / UPSERT transaction_daily_rollups",
+        sqlx::query_scalar(
+            r#"
+            WITH folded AS (
+                DELETE FROM transaction_rollup_deltas
+                RETURNING day, asset_code, status, tx_count, volume
+            ),
+            upserted AS (
+                INSERT INTO transaction_daily_rollups (day, asset_code, status, tx_count, volume)
+                SELECT day, asset_code, status, SUM(tx_count), SUM(volume)
+                FROM folded
+                GROUP BY day, asset_code, status
+                ON CONFLICT (day, asset_code, status) DO UPDATE
+                    SET tx_count   = transaction_daily_rollups.tx_count + EXCLUDED.tx_count,
+                        volume     = transaction_daily_rollups.volume + EXCLUDED.volume,
+                        updated_at = NOW()
+            )
+            SELECT COUNT(*) FROM folded
+            "#,
+        )
+        .fetch_one(pool),
+    )
+    .await?;
+    Ok(folded as u64)
+}
+
+/// Rebuild the rollup rows for one UTC day from `transactions`.
+///
+/// The delta table is locked against the maintenance trigger and compaction
+/// for the duration, so writes that land meanwhile wait and then append
+/// their deltas on top of the recomputed rows. Returns the number of rows
+/// written.
+pub async fn recompute_daily_rollup(pool: &PgPool, day: chrono::NaiveDate) -> Result<u64> {
+    with_timeout(
+        QueryTier::Admin,
+        "DELETE/INSERT transaction_daily_rollups WHERE day = $1",
+        async {
+            let mut db_tx = pool.begin().await?;
+            sqlx::query("LOCK TABLE transaction_rollup_deltas IN SHARE ROW EXCLUSIVE MODE")
+                .execute(&mut *db_tx)
+                .await?;
+            sqlx::query("DELETE FROM transaction_rollup_deltas WHERE day = $1")
+                .bind(day)
+                .execute(&mut *db_tx)
+                .await?;
+            sqlx::query("DELETE FROM transaction_daily_rollups WHERE day = $1")
+                .bind(day)
+                .execute(&mut *db_tx)
+                .await?;
+            let inserted = sqlx::query(
+                r#"
+                INSERT INTO transaction_daily_rollups (day, asset_code, status, tx_count, volume)
+                SELECT $1, asset_code, status, COUNT(*), SUM(amount)
+                FROM transactions
+                WHERE created_at >= ($1::date AT TIME ZONE 'UTC')
+                  AND created_at < (($1::date + 1) AT TIME ZONE 'UTC')
+                GROUP BY asset_code, status
+                "#,
+            )
+            .bind(day)
+            .execute(&mut *db_tx)
+            .await?;
+            db_tx.commit().await?;
+            Ok(inserted.rows_affected())
         },
     )
     .await
 }
 
-// ---------------------------------------------------------------------------
-// Tests
-// ---------------------------------------------------------------------------
+// --- Idempotency Fallback Queries ---
+//
+// Webhook handlers and replay flows can use these helpers to avoid processing
+// the same upstream request twice when an idempotency key is available. Keep
+// keys and stored responses out of logs; all values are parameter-bound.
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::sync::atomic::Ordering;
-    use tokio::time::Duration;
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct IdempotencyKey {
+    pub key: String,
+    pub status: String,
+    pub response: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
 
-    /// Verify that `with_timeout` fires when the future exceeds the deadline.
-    #[tokio::test]
-    async fn test_with_timeout_triggers_on_slow_future() {
-        // Override env so the timeout is 1 ms
-        std::env::set_var("DB_TIMEOUT_READ_SECS", "0");
+pub async fn check_idempotency_key(pool: &PgPool, key: &str) -> Result<Option<IdempotencyKey>> {
+    sqlx::query_as::<_, IdempotencyKey>(
+        "SELECT key, status, response, created_at, expires_at FROM idempotency_keys WHERE key = $1 AND expires_at > NOW()",
+    )
+    .bind(key)
+    .fetch_optional(pool)
+    .await
+}
 
-        let result = with_timeout(QueryTier::Read, "SELECT pg_sleep(10)", async {
-            tokio::time::sleep(Duration::from_secs(10)).await;
-            // This line is never reached
-            Err::<(), _>(sqlx::Error::RowNotFound)
-        })
-        .await;
+pub async fn insert_idempotency_key(
+    pool: &PgPool,
+    key: &str,
+    status: &str,
+    response: Option<&serde_json::Value>,
+    expires_at: DateTime<Utc>,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO idempotency_keys (key, status, response, expires_at)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (key) DO NOTHING
+        "#,
+    )
+    .bind(key)
+    .bind(status)
+    .bind(response)
+    .bind(expires_at)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
 
-        assert!(
-            matches!(result, Err(sqlx::Error::PoolTimedOut)),
-            "Expected PoolTimedOut, got {:?}",
-            result
-        );
+pub async fn update_idempotency_key_response(
+    pool: &PgPool,
+    key: &str,
+    response: &serde_json::Value,
+) -> Result<()> {
+    sqlx::query("UPDATE idempotency_keys SET response = $2, status = 'completed' WHERE key = $1")
+        .bind(key)
+        .bind(response)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
 
-        // Counter must have been incremented
-        assert!(
-            DB_QUERY_TIMEOUT_TOTAL.load(Ordering::Relaxed) >= 1,
-            "Timeout counter should be >= 1"
-        );
+pub async fn delete_idempotency_key(pool: &PgPool, key: &str) -> Result<u64> {
+    let result = sqlx::query("DELETE FROM idempotency_keys WHERE key = $1")
+        .bind(key)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected())
+}
 
-        // Restore
-        std::env::remove_var("DB_TIMEOUT_READ_SECS");
-    }
+/// Delete up to `limit` idempotency keys that expired before `cutoff`.
+pub async fn prune_idempotency_keys_batch(
+    pool: &PgPool,
+    cutoff: DateTime<Utc>,
+    limit: i64,
+) -> Result<u64> {
+    with_timeout(QueryTier::Write, "DELETE FROM idempotency_keys", async {
+        sqlx::query(
+            r#"
+            DELETE FROM idempotency_keys
+            WHERE key IN (
+                SELECT key FROM idempotency_keys
+                WHERE expires_at < $1
+                LIMIT $2
+            )
+            "#,
+        )
+        .bind(cutoff)
+        .bind(limit)
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected())
+    })
+    .await
+}
 
-    /// Verify that a fast future completes without triggering the timeout.
-    #[tokio::test]
-    async fn test_with_timeout_passes_fast_future() {
-        let before = DB_QUERY_TIMEOUT_TOTAL.load(Ordering::Relaxed);
+/// Delete up to `limit` webhook inbox rows received before `cutoff`.
+pub async fn prune_webhook_inbox_batch(
+    pool: &PgPool,
+    cutoff: DateTime<Utc>,
+    limit: i64,
+) -> Result<u64> {
+    with_timeout(QueryTier::Write, "DELETE FROM webhook_inbox", async {
+        sqlx::query(
+            r#"
+            DELETE FROM webhook_inbox
+            WHERE id IN (
+                SELECT id FROM webhook_inbox
+                WHERE received_at < $1
+                ORDER BY received_at
+                LIMIT $2
+            )
+            "#,
+        )
+        .bind(cutoff)
+        .bind(limit)
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected())
+    })
+    .await
+}
 
-        let result = with_timeout(QueryTier::Read, "SELECT 1", async {
-            Ok::<i32, sqlx::Error>(42)
-        })
-        .await;
+/// Delete up to `limit` finished job runs that started before `cutoff`.
+pub async fn prune_job_runs_batch(pool: &PgPool, cutoff: DateTime<Utc>, limit: i64) -> Result<u64> {
+    with_timeout(QueryTier::Write, "DELETE FROM job_runs", async {
+        sqlx::query(
+            r#"
+            DELETE FROM job_runs
+            WHERE id IN (
+                SELECT id FROM job_runs
+                WHERE started_at < $1 AND status <> 'running'
+                ORDER BY started_at
+                LIMIT $2
+            )
+            "#,
+        )
+        .bind(cutoff)
+        .bind(limit)
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected())
+    })
+    .await
+}
+
+/// Mark job runs still `running` that started before `cutoff` as failed.
+/// Their instance died before recording an outcome.
+pub async fn fail_abandoned_job_runs(pool: &PgPool, cutoff: DateTime<Utc>) -> Result<u64> {
+    with_timeout(QueryTier::Write, "UPDATE job_runs", async {
+        sqlx::query(
+            r#"
+            UPDATE job_runs SET
+                status = 'failed',
+                error = 'abandoned: no outcome was recorded'
+            WHERE status = 'running' AND started_at < $1
+            "#,
+        )
+        .bind(cutoff)
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected())
+    })
+    .await
+}
+
+/// Delete up to `limit` delivered webhook deliveries delivered before `cutoff`.
+pub async fn prune_delivered_webhooks_batch(
+    pool: &PgPool,
+    cutoff: DateTime<Utc>,
+    limit: i64,
+) -> Result<u64> {
+    with_timeout(QueryTier::Write, "DELETE FROM webhook_deliveries", async {
+        sqlx::query(
+            r#"
+            DELETE FROM webhook_deliveries
+            WHERE id IN (
+                SELECT id FROM webhook_deliveries
+                WHERE status = 'delivered' AND last_attempt_at < $1
+                ORDER BY last_attempt_at
+                LIMIT $2
+            )
+            "#,
+        )
+        .bind(cutoff)
+        .bind(limit)
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected())
+    })
+    .await
+}
+
+/// Record a circuit breaker state change.
+pub async fn insert_breaker_event(
+    pool: &PgPool,
+    transition: &crate::services::breakers::BreakerTransition,
+) -> Result<()> {
+    with_timeout(
+        QueryTier::Write,
+        "INSERT INTO circuit_breaker_events",
+        async {
+            sqlx::query(
+                r#"
+            INSERT INTO circuit_breaker_events
+                (breaker, from_state, to_state, reason, actor, occurred_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+            )
+            .bind(&transition.breaker)
+            .bind(transition.from_state.as_str())
+            .bind(transition.to_state.as_str())
+            .bind(&transition.reason)
+            .bind(transition.actor.as_deref())
+            .bind(transition.occurred_at)
+            .execute(pool)
+            .await
+            .map(|_| ())
+        },
+    )
+    .await
+}
 
-        assert_eq!(result.unwrap(), 42);
-        assert_eq!(
-            DB_QUERY_TIMEOUT_TOTAL.load(Ordering::Relaxed),
-            before,
-            "Counter should not change for a fast query"
-        );
-    }
+/// A row of `circuit_breaker_events`.
+#[derive(Debug, Clone, serde::Serialize, sqlx::FromRow)]
+pub struct BreakerEvent {
+    pub id: i64,
+    pub breaker: String,
+    pub from_state: String,
+    pub to_state: String,
+    pub reason: String,
+    pub actor: Option<String>,
+    pub occurred_at: DateTime<Utc>,
 }
 
-// --- Audit Log Search Query ---
+/// Latest circuit breaker transitions, newest first.
+pub async fn list_breaker_events(pool: &PgPool, limit: i64) -> Result<Vec<BreakerEvent>> {
+    with_timeout(
+        QueryTier::Read,
+        "SELECT * FROM circuit_breaker_events ORDER BY occurred_at",
+        async {
+            sqlx::query_as::<_, BreakerEvent>(
+                r#"
+            SELECT id, breaker, from_state, to_state, reason, actor, occurred_at
+            FROM circuit_breaker_events
+            ORDER BY occurred_at DESC
+            LIMIT $1
+            "#,
+            )
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+        },
+    )
+    .await
+}
 
-/// Parameters for searching audit logs across all entities.
-#[derive(Debug, Default)]
-pub struct AuditSearchParams<'a> {
-    pub actor: Option<&'a str>,
-    pub action: Option<&'a str>,
-    pub from_date: Option<DateTime<Utc>>,
-    pub to_date: Option<DateTime<Utc>>,
-    pub entity_type: Option<&'a str>,
-    pub limit: i64,
-    /// Cursor: (timestamp, id) of the last seen row for keyset pagination.
-    pub cursor: Option<(DateTime<Utc>, Uuid)>,
+/// A row of `inbound_signing_keys`. `sealed_secret` must not leave the
+/// process; see [`crate::services::signing_keys::SigningKeyView`].
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct InboundSigningKey {
+    pub id: Uuid,
+    pub tenant_id: Option<Uuid>,
+    pub sealed_secret: Option<String>,
+    pub fingerprint: Option<String>,
+    pub active_from: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
 }
 
-/// A single row returned by the audit search query.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AuditLogRow {
+/// A new key for [`rotate_inbound_signing_key`].
+pub struct NewSigningKey<'a> {
     pub id: Uuid,
-    pub entity_id: Uuid,
-    pub entity_type: String,
-    pub action: String,
-    pub old_val: Option<serde_json::Value>,
-    pub new_val: Option<serde_json::Value>,
-    pub actor: String,
-    pub timestamp: DateTime<Utc>,
+    pub tenant_id: Option<Uuid>,
+    pub sealed_secret: &'a str,
+    pub fingerprint: &'a str,
+    pub active_from: DateTime<Utc>,
+    /// When the key being replaced stops being accepted.
+    pub previous_expires_at: DateTime<Utc>,
+    pub actor: &'a str,
 }
 
-/// Search audit logs with optional filters and cursor-based pagination.
-/// Returns `(total_count, rows)`.
-#[allow(clippy::too_many_arguments)]
-pub async fn search_audit_logs(
+/// Keys of every scope that have not expired at `now`, pending ones included.
+pub async fn list_live_inbound_signing_keys(
     pool: &PgPool,
-    params: &AuditSearchParams<'_>,
-) -> Result<(i64, Vec<AuditLogRow>)> {
+    now: DateTime<Utc>,
+) -> Result<Vec<InboundSigningKey>> {
     with_timeout(
         QueryTier::Read,
-        "search_audit_logs [dynamic WHERE clause]",
+        "SELECT * FROM inbound_signing_keys WHERE expires_at IS NULL OR expires_at > $1",
         async {
-            let mut conditions: Vec<String> = Vec::new();
-            let mut p = 1usize;
-
-            if params.actor.is_some() {
-                conditions.push(format!("actor = ${p}"));
-                p += 1;
-            }
-            if params.action.is_some() {
-                conditions.push(format!("action = ${p}"));
-                p += 1;
-            }
-            if params.from_date.is_some() {
-                conditions.push(format!("timestamp >= ${p}"));
-                p += 1;
-            }
-            if params.to_date.is_some() {
-                conditions.push(format!("timestamp <= ${p}"));
-                p += 1;
-            }
-            if params.entity_type.is_some() {
-                conditions.push(format!("entity_type = ${p}"));
-                p += 1;
-            }
-            if params.cursor.is_some() {
-                conditions.push(format!("(timestamp, id) < (${p}, ${})", p + 1));
-                p += 2;
-            }
-
-            let where_clause = if conditions.is_empty() {
-                String::new()
-            } else {
-                format!("WHERE {}", conditions.join(" AND "))
-            };
-
-            // Bind helper — avoids repeating the bind sequence twice.
-            macro_rules! bind_filters {
-                ($q:expr) => {{
-                    let mut q = $q;
-                    if let Some(v) = params.actor {
-                        q = q.bind(v);
-                    }
-                    if let Some(v) = params.action {
-                        q = q.bind(v);
-                    }
-                    if let Some(v) = params.from_date {
-                        q = q.bind(v);
-                    }
-                    if let Some(v) = params.to_date {
-                        q = q.bind(v);
-                    }
-                    if let Some(v) = params.entity_type {
-                        q = q.bind(v);
-                    }
-                    if let Some((ts, id)) = params.cursor {
-                        q = q.bind(ts).bind(id);
-                    }
-                    q
-                }};
-            }
-
-            // Total count (ignores cursor so the caller always gets the full
-            // result-set size for the given filters).
-            let count_sql = format!(
-                "SELECT COUNT(*) FROM audit_logs {}",
-                // Strip cursor condition from count query
-                if conditions.is_empty() {
-                    String::new()
-                } else {
-                    let non_cursor: Vec<_> = conditions
-                        .iter()
-                        .filter(|c| !c.contains("timestamp, id"))
-                        .cloned()
-                        .collect();
-                    if non_cursor.is_empty() {
-                        String::new()
-                    } else {
-                        format!("WHERE {}", non_cursor.join(" AND "))
-                    }
-                }
-            );
-
-            // Re-bind without cursor for count
-            let mut count_q = sqlx::query(&count_sql);
-            if let Some(v) = params.actor {
-                count_q = count_q.bind(v);
-            }
-            if let Some(v) = params.action {
-                count_q = count_q.bind(v);
-            }
-            if let Some(v) = params.from_date {
-                count_q = count_q.bind(v);
-            }
-            if let Some(v) = params.to_date {
-                count_q = count_q.bind(v);
-            }
-            if let Some(v) = params.entity_type {
-                count_q = count_q.bind(v);
-            }
-
-            let total: i64 = count_q.fetch_one(pool).await?.try_get(0)?;
-
-            // Data query with cursor + limit
-            let data_sql = format!(
-                "SELECT id, entity_id, entity_type, action, old_val, new_val, actor, timestamp \
-                 FROM audit_logs {where_clause} \
-                 ORDER BY timestamp DESC, id DESC \
-                 LIMIT ${p}"
-            );
-
-            let data_q = bind_filters!(sqlx::query(&data_sql)).bind(params.limit);
-            let rows = data_q.fetch_all(pool).await?;
-
-            let logs = rows
-                .into_iter()
-                .map(|row| AuditLogRow {
-                    id: row.get("id"),
-                    entity_id: row.get("entity_id"),
-                    entity_type: row.get("entity_type"),
-                    action: row.get("action"),
-                    old_val: row.get("old_val"),
-                    new_val: row.get("new_val"),
-                    actor: row.get("actor"),
-                    timestamp: row.get("timestamp"),
-                })
-                .collect();
-
-            Ok((total, logs))
+            sqlx::query_as::<_, InboundSigningKey>(
+                r#"
+            SELECT * FROM inbound_signing_keys
+            WHERE expires_at IS NULL OR expires_at > $1
+            ORDER BY active_from
+            "#,
+            )
+            .bind(now)
+            .fetch_all(pool)
+            .await
         },
     )
     .await
 }
 
-// --- Audit Log Queries ---
-
-/// Retrieve audit logs for a specific entity using cursor-based pagination on (timestamp, id).
-pub async fn get_audit_logs(
+/// Most recent keys, newest first, for one partner or (`None`) every scope.
+pub async fn list_inbound_signing_keys(
     pool: &PgPool,
-    entity_id: Uuid,
+    tenant_id: Option<Uuid>,
     limit: i64,
-    cursor: Option<(DateTime<Utc>, Uuid)>,
-) -> Result<
-    Vec<(
-        Uuid,
-        Uuid,
-        String,
-        String,
-        Option<serde_json::Value>,
-        Option<serde_json::Value>,
-        String,
-        DateTime<Utc>,
-    )>,
-> {
-    let rows = if let Some((ts, cid)) = cursor {
-        sqlx::query(
-            r#"
-            SELECT id, entity_id, entity_type, action, old_val, new_val, actor, timestamp
-            FROM audit_logs
-            WHERE entity_id = $1 AND (timestamp, id) < ($2, $3)
-            ORDER BY timestamp DESC, id DESC
-            LIMIT $4
-            "#,
-        )
-        .bind(entity_id)
-        .bind(ts)
-        .bind(cid)
-        .bind(limit)
-        .fetch_all(pool)
-        .await?
-    } else {
-        sqlx::query(
-            r#"
-            SELECT id, entity_id, entity_type, action, old_val, new_val, actor, timestamp
-            FROM audit_logs
-            WHERE entity_id = $1
-            ORDER BY timestamp DESC, id DESC
+) -> Result<Vec<InboundSigningKey>> {
+    with_timeout(
+        QueryTier::Read,
+        "SELECT * FROM inbound_signing_keys WHERE tenant_id = $1 ORDER BY created_at DESC LIMIT $2",
+        async {
+            sqlx::query_as::<_, InboundSigningKey>(
+                r#"
+            SELECT * FROM inbound_signing_keys
+            WHERE $1::uuid IS NULL OR tenant_id = $1
+            ORDER BY created_at DESC, active_from DESC
             LIMIT $2
             "#,
-        )
-        .bind(entity_id)
-        .bind(limit)
-        .fetch_all(pool)
-        .await?
-    };
+            )
+            .bind(tenant_id)
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+        },
+    )
+    .await
+}
+
+/// Add `key` to its scope. The scope's current key expires at
+/// `previous_expires_at`, any other unexpired key (a pending one, or one
+/// already being phased out) expires now. The first rotation of a scope also
+/// records the key it inherited, with a NULL secret, so it gets the overlap.
+pub async fn rotate_inbound_signing_key(
+    pool: &PgPool,
+    key: &NewSigningKey<'_>,
+) -> Result<InboundSigningKey> {
+    with_timeout(
+        QueryTier::Write,
+        "UPDATE inbound_signing_keys SET expires_at ...; INSERT INTO inbound_signing_keys",
+        async {
+            let now = Utc::now();
+            let mut db_tx = pool.begin().await?;
+
+            let scope = match key.tenant_id {
+                Some(tenant_id) => format!("inbound_signing_keys:{tenant_id}"),
+                None => "inbound_signing_keys:global".to_string(),
+            };
+            sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended($1, 0))")
+                .bind(&scope)
+                .execute(&mut *db_tx)
+                .await?;
+
+            let expired = sqlx::query(
+                r#"
+                UPDATE inbound_signing_keys
+                SET expires_at = CASE
+                    WHEN expires_at IS NULL AND active_from <= $2 THEN $3
+                    ELSE LEAST(COALESCE(expires_at, $2), $2)
+                END
+                WHERE tenant_id IS NOT DISTINCT FROM $1
+                  AND (expires_at IS NULL OR expires_at > $2)
+                "#,
+            )
+            .bind(key.tenant_id)
+            .bind(now)
+            .bind(key.previous_expires_at)
+            .execute(&mut *db_tx)
+            .await?
+            .rows_affected();
+
+            if expired == 0 {
+                sqlx::query(
+                    r#"
+                    INSERT INTO inbound_signing_keys
+                        (id, tenant_id, sealed_secret, fingerprint, active_from, expires_at, created_by)
+                    VALUES ($1, $2, NULL, NULL, $3, $4, $5)
+                    "#,
+                )
+                .bind(Uuid::new_v4())
+                .bind(key.tenant_id)
+                .bind(now)
+                .bind(key.previous_expires_at)
+                .bind(key.actor)
+                .execute(&mut *db_tx)
+                .await?;
+            }
 
-    Ok(rows
-        .into_iter()
-        .map(|row| {
-            (
-                row.get("id"),
-                row.get("entity_id"),
-                row.get("entity_type"),
-                row.get("action"),
-                row.get("old_val"),
-                row.get("new_val"),
-                row.get("actor"),
-                row.get("timestamp"),
+            let inserted = sqlx::query_as::<_, InboundSigningKey>(
+                r#"
+                INSERT INTO inbound_signing_keys
+                    (id, tenant_id, sealed_secret, fingerprint, active_from, created_by)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                RETURNING *
+                "#,
             )
-        })
-        .collect())
-}
+            .bind(key.id)
+            .bind(key.tenant_id)
+            .bind(key.sealed_secret)
+            .bind(key.fingerprint)
+            .bind(key.active_from)
+            .bind(key.actor)
+            .fetch_one(&mut *db_tx)
+            .await?;
 
-// --- Bulk Status Update ---
+            AuditLog::log(
+                &mut db_tx,
+                key.tenant_id.unwrap_or(Uuid::nil()),
+                ENTITY_SIGNING_KEY,
+                "rotated",
+                None,
+                Some(json!({
+                    "key_id": key.id,
+                    "fingerprint": key.fingerprint,
+                    "active_from": key.active_from,
+                    "previous_expires_at": key.previous_expires_at,
+                })),
+                key.actor,
+            )
+            .await?;
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct BulkUpdateError {
-    pub transaction_id: Uuid,
-    pub error: String,
+            db_tx.commit().await?;
+            Ok(inserted)
+        },
+    )
+    .await
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct BulkUpdateResult {
-    pub updated: usize,
-    pub failed: usize,
-    pub errors: Vec<BulkUpdateError>,
+// --- Risk Scoring ---
+
+/// The sender history a [`crate::ports::RiskScorer`] scores `tx` against:
+/// every earlier deposit from the same account (and muxed id, when set).
+pub async fn get_customer_profile(
+    db_tx: &mut SqlxTransaction<'_, Postgres>,
+    tx: &Transaction,
+) -> Result<CustomerProfile> {
+    let row = sqlx::query(
+        r#"
+        SELECT COUNT(*) AS prior_deposits,
+               COUNT(*) FILTER (WHERE status IN ('failed', 'dlq')) AS failed_deposits,
+               AVG(amount) FILTER (WHERE asset_code = $4) AS average_amount,
+               COUNT(*) FILTER (WHERE created_at >= $3 - INTERVAL '24 hours')
+                   AS deposits_last_24h,
+               MIN(created_at) AS first_seen_at
+        FROM transactions
+        WHERE stellar_account = $1
+          AND ($2::numeric IS NULL OR stellar_muxed_id = $2)
+          AND created_at < $3
+          AND id <> $5
+        "#,
+    )
+    .bind(&tx.stellar_account)
+    .bind(&tx.stellar_muxed_id)
+    .bind(tx.created_at)
+    .bind(&tx.asset_code)
+    .bind(tx.id)
+    .fetch_one(&mut **db_tx)
+    .await?;
+
+    Ok(CustomerProfile {
+        stellar_account: tx.stellar_account.clone(),
+        muxed_id: tx.stellar_muxed_id.as_ref().and_then(|id| id.to_u64()),
+        prior_deposits: row.try_get("prior_deposits")?,
+        failed_deposits: row.try_get("failed_deposits")?,
+        average_amount: row.try_get("average_amount")?,
+        deposits_last_24h: row.try_get("deposits_last_24h")?,
+        first_seen_at: row.try_get("first_seen_at")?,
+    })
 }
 
-/// Bulk update transaction statuses, validating each transition individually.
-/// Uses a single UPDATE ... WHERE id = ANY($1) for the valid subset, then
-/// audit-logs each successful update within the same transaction.
-pub async fn bulk_update_transaction_status(
-    pool: &PgPool,
-    transaction_ids: &[Uuid],
-    new_status: &str,
-    reason: Option<&str>,
+/// Store `assessment` on transaction `id`.
+pub async fn record_risk_score(
+    db_tx: &mut SqlxTransaction<'_, Postgres>,
+    id: Uuid,
+    assessment: &RiskAssessment,
     actor: &str,
-) -> Result<BulkUpdateResult> {
-    use crate::validation::state_machine::validate_status_transition;
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE transactions SET risk_score = $2, risk_reasons = $3, updated_at = NOW() \
+         WHERE id = $1",
+    )
+    .bind(id)
+    .bind(i16::from(assessment.score))
+    .bind(&assessment.reasons)
+    .execute(&mut **db_tx)
+    .await?;
 
-    // Fetch current statuses for all requested IDs in one query
-    let rows = sqlx::query("SELECT id, status FROM transactions WHERE id = ANY($1)")
-        .bind(transaction_ids)
-        .fetch_all(pool)
-        .await?;
+    AuditLog::log_field_update(
+        db_tx,
+        id,
+        ENTITY_TRANSACTION,
+        "risk_score",
+        serde_json::Value::Null,
+        json!({ "score": assessment.score, "reasons": assessment.reasons }),
+        actor,
+    )
+    .await
+}
 
-    let current: std::collections::HashMap<Uuid, String> = rows
-        .into_iter()
-        .map(|r| (r.get::<Uuid, _>("id"), r.get::<String, _>("status")))
-        .collect();
+// --- Structuring Detection ---
 
-    let mut valid_ids: Vec<Uuid> = Vec::new();
-    let mut old_statuses: std::collections::HashMap<Uuid, String> =
-        std::collections::HashMap::new();
-    let mut errors: Vec<BulkUpdateError> = Vec::new();
+/// Structuring rules by name, optionally only the enabled ones.
+pub async fn list_structuring_rules(
+    pool: &PgPool,
+    enabled_only: bool,
+) -> Result<Vec<StructuringRule>> {
+    with_timeout(
+        QueryTier::Read,
+        "SELECT * FROM structuring_rules ORDER BY name",
+        async {
+            sqlx::query_as::<_, StructuringRule>(
+                "SELECT * FROM structuring_rules WHERE enabled OR NOT $1 ORDER BY name",
+            )
+            .bind(enabled_only)
+            .fetch_all(pool)
+            .await
+        },
+    )
+    .await
+}
 
-    for &id in transaction_ids {
-        match current.get(&id) {
-            None => errors.push(BulkUpdateError {
-                transaction_id: id,
-                error: "transaction not found".to_string(),
-            }),
-            Some(from) => match validate_status_transition(from, new_status) {
-                Ok(_) => {
-                    old_statuses.insert(id, from.clone());
-                    valid_ids.push(id);
-                }
-                Err(e) => errors.push(BulkUpdateError {
-                    transaction_id: id,
-                    error: e.to_string(),
-                }),
-            },
-        }
-    }
+pub async fn get_structuring_rule(pool: &PgPool, id: Uuid) -> Result<StructuringRule> {
+    sqlx::query_as::<_, StructuringRule>("SELECT * FROM structuring_rules WHERE id = $1")
+        .bind(id)
+        .fetch_one(pool)
+        .await
+}
 
-    if valid_ids.is_empty() {
-        return Ok(BulkUpdateResult {
-            updated: 0,
-            failed: errors.len(),
-            errors,
-        });
-    }
+pub async fn insert_structuring_rule(
+    pool: &PgPool,
+    spec: &RuleSpec,
+    actor: &str,
+) -> Result<StructuringRule> {
+    with_timeout(
+        QueryTier::Write,
+        "INSERT INTO structuring_rules (...) RETURNING *",
+        async {
+            let mut db_tx = pool.begin().await?;
+            let rule = sqlx::query_as::<_, StructuringRule>(
+                r#"
+                INSERT INTO structuring_rules (
+                    name, asset_code, window_hours, min_count, min_total,
+                    max_single_amount, enabled
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+                RETURNING *
+                "#,
+            )
+            .bind(&spec.name)
+            .bind(&spec.asset_code)
+            .bind(spec.window_hours)
+            .bind(spec.min_count)
+            .bind(&spec.min_total)
+            .bind(&spec.max_single_amount)
+            .bind(spec.enabled)
+            .fetch_one(&mut *db_tx)
+            .await?;
 
-    let mut db_tx = pool.begin().await?;
+            AuditLog::log_creation(
+                &mut db_tx,
+                rule.id,
+                ENTITY_STRUCTURING_RULE,
+                json!(spec),
+                actor,
+            )
+            .await?;
+            db_tx.commit().await?;
+            Ok(rule)
+        },
+    )
+    .await
+}
 
-    sqlx::query("UPDATE transactions SET status = $1, updated_at = NOW() WHERE id = ANY($2)")
-        .bind(new_status)
-        .bind(&valid_ids)
-        .execute(&mut *db_tx)
-        .await?;
+/// Replace a rule's definition. `RowNotFound` if it does not exist.
+pub async fn update_structuring_rule(
+    pool: &PgPool,
+    id: Uuid,
+    spec: &RuleSpec,
+    actor: &str,
+) -> Result<StructuringRule> {
+    with_timeout(
+        QueryTier::Write,
+        "UPDATE structuring_rules SET ... WHERE id = $8",
+        async {
+            let mut db_tx = pool.begin().await?;
+            let old = sqlx::query_as::<_, StructuringRule>(
+                "SELECT * FROM structuring_rules WHERE id = $1 FOR UPDATE",
+            )
+            .bind(id)
+            .fetch_optional(&mut *db_tx)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
 
-    for &id in &valid_ids {
-        let old_status = old_statuses
-            .get(&id)
-            .map(|s| s.as_str())
-            .unwrap_or("unknown");
-        let mut new_val = serde_json::json!({ "status": new_status });
-        if let Some(r) = reason {
-            new_val["reason"] = serde_json::json!(r);
-        }
-        AuditLog::log(
-            &mut db_tx,
-            id,
-            ENTITY_TRANSACTION,
-            "status_update",
-            Some(serde_json::json!({ "status": old_status })),
-            Some(new_val),
-            actor,
-        )
-        .await?;
-    }
+            let rule = sqlx::query_as::<_, StructuringRule>(
+                r#"
+                UPDATE structuring_rules SET
+                    name = $1, asset_code = $2, window_hours = $3, min_count = $4,
+                    min_total = $5, max_single_amount = $6, enabled = $7, updated_at = NOW()
+                WHERE id = $8
+                RETURNING *
+                "#,
+            )
+            .bind(&spec.name)
+            .bind(&spec.asset_code)
+            .bind(spec.window_hours)
+            .bind(spec.min_count)
+            .bind(&spec.min_total)
+            .bind(&spec.max_single_amount)
+            .bind(spec.enabled)
+            .bind(id)
+            .fetch_one(&mut *db_tx)
+            .await?;
 
-    db_tx.commit().await?;
+            AuditLog::log(
+                &mut db_tx,
+                id,
+                ENTITY_STRUCTURING_RULE,
+                "updated",
+                Some(json!(old)),
+                Some(json!(spec)),
+                actor,
+            )
+            .await?;
+            db_tx.commit().await?;
+            Ok(rule)
+        },
+    )
+    .await
+}
 
-    let updated = valid_ids.len();
-    Ok(BulkUpdateResult {
-        updated,
-        failed: errors.len(),
-        errors,
-    })
+/// Delete a rule and its review items. `false` if it does not exist.
+pub async fn delete_structuring_rule(pool: &PgPool, id: Uuid, actor: &str) -> Result<bool> {
+    with_timeout(
+        QueryTier::Write,
+        "DELETE FROM structuring_rules WHERE id = $1",
+        async {
+            let mut db_tx = pool.begin().await?;
+            let deleted = sqlx::query_as::<_, StructuringRule>(
+                "DELETE FROM structuring_rules WHERE id = $1 RETURNING *",
+            )
+            .bind(id)
+            .fetch_optional(&mut *db_tx)
+            .await?;
+            let Some(rule) = deleted else {
+                return Ok(false);
+            };
+            AuditLog::log_deletion(&mut db_tx, id, ENTITY_STRUCTURING_RULE, json!(rule), actor)
+                .await?;
+            db_tx.commit().await?;
+            Ok(true)
+        },
+    )
+    .await
 }
 
-// --- Aggregate Queries (Cacheable) ---
+/// Open a review item for every account `rule` flags between `window_start`
+/// and `window_end`, refreshing items that are still open. Returns the number
+/// of items opened and refreshed.
+pub async fn flag_structuring_accounts(
+    pool: &PgPool,
+    rule: &StructuringRule,
+    window_start: DateTime<Utc>,
+    window_end: DateTime<Utc>,
+) -> Result<(u64, u64)> {
+    with_timeout(
+        QueryTier::Write,
+        "INSERT INTO structuring_reviews (...) SELECT ... FROM transactions",
+        async {
+            let inserted: Vec<bool> = sqlx::query_scalar(
+                r#"
+                INSERT INTO structuring_reviews (
+                    rule_id, stellar_account, window_start, window_end,
+                    tx_count, total_amount, transaction_ids
+                )
+                SELECT $1, stellar_account, $2, $3,
+                       COUNT(*), SUM(amount), ARRAY_AGG(id ORDER BY created_at)
+                FROM transactions
+                WHERE created_at >= $2 AND created_at < $3
+                  AND ($4::text IS NULL OR asset_code = $4)
+                  AND ($5::numeric IS NULL OR amount <= $5)
+                GROUP BY stellar_account
+                HAVING ($6::bigint IS NULL OR COUNT(*) >= $6)
+                   AND ($7::numeric IS NULL OR SUM(amount) >= $7)
+                ON CONFLICT (rule_id, stellar_account) WHERE status = 'open'
+                DO UPDATE SET
+                    window_start = EXCLUDED.window_start,
+                    window_end = EXCLUDED.window_end,
+                    tx_count = EXCLUDED.tx_count,
+                    total_amount = EXCLUDED.total_amount,
+                    transaction_ids = EXCLUDED.transaction_ids,
+                    updated_at = NOW()
+                RETURNING (xmax = 0)
+                "#,
+            )
+            .bind(rule.id)
+            .bind(window_start)
+            .bind(window_end)
+            .bind(&rule.asset_code)
+            .bind(&rule.max_single_amount)
+            .bind(rule.min_count.map(i64::from))
+            .bind(&rule.min_total)
+            .fetch_all(pool)
+            .await?;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct StatusCount {
-    pub status: String,
-    pub count: i64,
+            let opened = inserted.iter().filter(|new| **new).count() as u64;
+            Ok((opened, inserted.len() as u64 - opened))
+        },
+    )
+    .await
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DailyTotal {
-    pub date: String,
-    pub total_amount: BigDecimal,
-    pub tx_count: i64,
+/// Review items, oldest first, optionally filtered by status.
+pub async fn list_structuring_reviews(
+    pool: &PgPool,
+    status: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<StructuringReview>> {
+    with_timeout(
+        QueryTier::Read,
+        "SELECT * FROM structuring_reviews ORDER BY created_at",
+        async {
+            sqlx::query_as::<_, StructuringReview>(
+                r#"
+                SELECT * FROM structuring_reviews
+                WHERE ($1::text IS NULL OR status = $1)
+                ORDER BY created_at ASC, id ASC
+                LIMIT $2 OFFSET $3
+                "#,
+            )
+            .bind(status)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(pool)
+            .await
+        },
+    )
+    .await
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AssetStats {
-    pub asset_code: String,
-    pub total_amount: BigDecimal,
-    pub tx_count: i64,
-    pub avg_amount: BigDecimal,
+/// Close an open review item as `status`. `None` if it does not exist or is
+/// no longer open.
+pub async fn resolve_structuring_review(
+    pool: &PgPool,
+    id: Uuid,
+    status: &str,
+    note: Option<&str>,
+    actor: &str,
+) -> Result<Option<StructuringReview>> {
+    with_timeout(
+        QueryTier::Write,
+        "UPDATE structuring_reviews SET status = $2 WHERE id = $1 AND status = 'open'",
+        async {
+            let mut db_tx = pool.begin().await?;
+            let review = close_structuring_review(&mut db_tx, id, status, note, actor).await?;
+            db_tx.commit().await?;
+            Ok(review)
+        },
+    )
+    .await
 }
 
-pub async fn get_status_counts(pool: &PgPool) -> Result<Vec<StatusCount>> {
-    let rows = sqlx::query(
+/// Move open structuring review `id` to `status` within `db_tx`. Returns
+/// `None` when it is not open.
+pub async fn close_structuring_review(
+    db_tx: &mut SqlxTransaction<'_, Postgres>,
+    id: Uuid,
+    status: &str,
+    note: Option<&str>,
+    actor: &str,
+) -> Result<Option<StructuringReview>> {
+    let review = sqlx::query_as::<_, StructuringReview>(
         r#"
-        SELECT status, COUNT(*) as count
-        FROM transactions
-        GROUP BY status
-        ORDER BY status
+        UPDATE structuring_reviews SET
+            status = $2, note = COALESCE($3, note), resolved_by = $4,
+            resolved_at = NOW(), updated_at = NOW()
+        WHERE id = $1 AND status = 'open'
+        RETURNING *
         "#,
     )
-    .fetch_all(pool)
+    .bind(id)
+    .bind(status)
+    .bind(note)
+    .bind(actor)
+    .fetch_optional(&mut **db_tx)
     .await?;
 
-    Ok(rows
-        .into_iter()
-        .map(|row| StatusCount {
-            status: row.get("status"),
-            count: row.get("count"),
-        })
-        .collect())
+    if review.is_some() {
+        AuditLog::log_status_change(db_tx, id, ENTITY_STRUCTURING_REVIEW, "open", status, actor)
+            .await?;
+    }
+    Ok(review)
 }
 
-pub async fn get_daily_totals(pool: &PgPool, days: i32) -> Result<Vec<DailyTotal>> {
-    let end = Utc::now();
-    let start = end - chrono::Duration::days(days.into());
-    let sql = r#"
-        SELECT 
-            DATE(created_at)::text as date,
-            SUM(amount) as total_amount,
-            COUNT(*) as tx_count
-        FROM transactions
-        WHERE created_at >= $1
-          AND created_at < $2
-        GROUP BY DATE(created_at)
-        ORDER BY DATE(created_at) DESC
-        "#;
+// --- Review Queue ---
 
-    if cfg!(debug_assertions) {
-        let explain_rows = sqlx::query(&format!("EXPLAIN ANALYZE {}", sql))
-            .bind(start)
-            .bind(end)
-            .fetch_all(pool)
-            .await?;
+/// Filters for [`list_review_items`]; `None` matches everything.
+#[derive(Debug, Clone, Default)]
+pub struct ReviewItemFilter<'a> {
+    pub state: Option<&'a str>,
+    pub kind: Option<&'a str>,
+    pub assignee: Option<&'a str>,
+    /// Only unresolved items past their SLA.
+    pub overdue_only: bool,
+}
 
-        let explain_plan = explain_rows
-            .into_iter()
-            .map(|row| row.get::<String, _>(0))
-            .collect::<Vec<_>>()
-            .join("\n");
+/// Review items, soonest SLA first. `now` decides which items are overdue.
+pub async fn list_review_items(
+    pool: &PgPool,
+    filter: &ReviewItemFilter<'_>,
+    now: DateTime<Utc>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<ReviewItem>> {
+    with_timeout(
+        QueryTier::Read,
+        "SELECT * FROM review_items ORDER BY sla_due_at",
+        sqlx::query_as::<_, ReviewItem>(
+            r#"
+            SELECT * FROM review_items
+            WHERE ($1::text IS NULL OR state = $1)
+              AND ($2::text IS NULL OR kind = $2)
+              AND ($3::text IS NULL OR assignee = $3)
+              AND (NOT $4 OR (state <> 'resolved' AND sla_due_at < $7))
+            ORDER BY sla_due_at ASC, created_at ASC
+            LIMIT $5 OFFSET $6
+            "#,
+        )
+        .bind(filter.state)
+        .bind(filter.kind)
+        .bind(filter.assignee)
+        .bind(filter.overdue_only)
+        .bind(limit)
+        .bind(offset)
+        .bind(now)
+        .fetch_all(pool),
+    )
+    .await
+}
 
-        tracing::debug!("get_daily_totals EXPLAIN ANALYZE:\n{}", explain_plan);
-    }
+pub async fn get_review_item(pool: &PgPool, id: Uuid) -> Result<ReviewItem> {
+    with_timeout(
+        QueryTier::Read,
+        "SELECT * FROM review_items WHERE id = $1",
+        sqlx::query_as::<_, ReviewItem>("SELECT * FROM review_items WHERE id = $1")
+            .bind(id)
+            .fetch_one(pool),
+    )
+    .await
+}
 
-    let rows = sqlx::query(sql)
-        .bind(start)
-        .bind(end)
-        .fetch_all(pool)
-        .await?;
+/// Lock review item `id` for the rest of `db_tx`.
+pub async fn lock_review_item(
+    db_tx: &mut SqlxTransaction<'_, Postgres>,
+    id: Uuid,
+) -> Result<Option<ReviewItem>> {
+    sqlx::query_as::<_, ReviewItem>("SELECT * FROM review_items WHERE id = $1 FOR UPDATE")
+        .bind(id)
+        .fetch_optional(&mut **db_tx)
+        .await
+}
 
-    Ok(rows
-        .into_iter()
-        .map(|row| DailyTotal {
-            date: row.get("date"),
-            total_amount: row.get("total_amount"),
-            tx_count: row.get("tx_count"),
-        })
-        .collect())
+/// Assign open item `id` to `reviewer`. Claiming an item already assigned to
+/// `reviewer` succeeds unchanged; returns `None` when it is resolved or
+/// assigned to someone else.
+pub async fn claim_review_item(
+    pool: &PgPool,
+    id: Uuid,
+    reviewer: &str,
+) -> Result<Option<ReviewItem>> {
+    with_timeout(
+        QueryTier::Write,
+        "UPDATE review_items SET state = 'assigned' WHERE id = $1",
+        async {
+            let mut db_tx = pool.begin().await?;
+            let item = sqlx::query_as::<_, ReviewItem>(
+                r#"
+                UPDATE review_items SET
+                    state = 'assigned', assignee = $2,
+                    assigned_at = CASE WHEN state = 'open' THEN NOW() ELSE assigned_at END,
+                    updated_at = NOW()
+                WHERE id = $1
+                  AND (state = 'open' OR (state = 'assigned' AND assignee = $2))
+                RETURNING *
+                "#,
+            )
+            .bind(id)
+            .bind(reviewer)
+            .fetch_optional(&mut *db_tx)
+            .await?;
+
+            if item.is_some() {
+                AuditLog::log(
+                    &mut db_tx,
+                    id,
+                    ENTITY_REVIEW_ITEM,
+                    "claim",
+                    None,
+                    Some(json!({ "assignee": reviewer })),
+                    reviewer,
+                )
+                .await?;
+            }
+            db_tx.commit().await?;
+            Ok(item)
+        },
+    )
+    .await
 }
 
-pub async fn get_asset_stats(pool: &PgPool) -> Result<Vec<AssetStats>> {
-    let rows = sqlx::query(
+/// Resolve `item` as `resolution` within `db_tx`. The item must have been
+/// locked with [`lock_review_item`].
+pub async fn mark_review_item_resolved(
+    db_tx: &mut SqlxTransaction<'_, Postgres>,
+    item: &ReviewItem,
+    resolution: &str,
+    actor: &str,
+) -> Result<ReviewItem> {
+    let resolved = sqlx::query_as::<_, ReviewItem>(
         r#"
-        SELECT
-            asset_code,
-            SUM(amount) as total_amount,
-            COUNT(*) as tx_count,
-            AVG(amount) as avg_amount
-        FROM transactions
-        GROUP BY asset_code
-        ORDER BY total_amount DESC
+        UPDATE review_items SET
+            state = 'resolved', resolution = $2, resolved_by = $3,
+            resolved_at = NOW(), updated_at = NOW()
+        WHERE id = $1
+        RETURNING *
         "#,
     )
-    .fetch_all(pool)
+    .bind(item.id)
+    .bind(resolution)
+    .bind(actor)
+    .fetch_one(&mut **db_tx)
     .await?;
 
-    Ok(rows
-        .into_iter()
-        .map(|row| AssetStats {
-            asset_code: row.get("asset_code"),
-            total_amount: row.get("total_amount"),
-            tx_count: row.get("tx_count"),
-            avg_amount: row.get("avg_amount"),
-        })
-        .collect())
+    AuditLog::log(
+        db_tx,
+        item.id,
+        ENTITY_REVIEW_ITEM,
+        "resolve",
+        Some(json!({ "state": item.state, "assignee": item.assignee })),
+        Some(json!({ "state": "resolved", "resolution": resolution })),
+        actor,
+    )
+    .await?;
+    Ok(resolved)
 }
 
-// --- Idempotency Fallback Queries ---
-//
-// Webhook handlers and replay flows can use these helpers to avoid processing
-// the same upstream request twice when an idempotency key is available. Keep
-// keys and stored responses out of logs; all values are parameter-bound.
+/// Release transaction `id` from `compliance_review` to `to` within
+/// `db_tx`. Returns `false` when it is no longer held.
+pub async fn release_held_transaction(
+    db_tx: &mut SqlxTransaction<'_, Postgres>,
+    id: Uuid,
+    to: TransactionStatus,
+    actor: &str,
+) -> Result<bool> {
+    let released = sqlx::query(
+        "UPDATE transactions SET status = $2, updated_at = NOW() \
+         WHERE id = $1 AND status = 'compliance_review'",
+    )
+    .bind(id)
+    .bind(to)
+    .execute(&mut **db_tx)
+    .await?
+    .rows_affected()
+        > 0;
+
+    if released {
+        AuditLog::log_status_change(
+            db_tx,
+            id,
+            ENTITY_TRANSACTION,
+            TransactionStatus::ComplianceReview.as_str(),
+            to.as_str(),
+            actor,
+        )
+        .await?;
+    }
+    Ok(released)
+}
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
-pub struct IdempotencyKey {
-    pub key: String,
-    pub status: String,
-    pub response: Option<serde_json::Value>,
-    pub created_at: DateTime<Utc>,
-    pub expires_at: DateTime<Utc>,
+pub async fn insert_review_comment(
+    db_tx: &mut SqlxTransaction<'_, Postgres>,
+    review_item_id: Uuid,
+    author: &str,
+    body: &str,
+) -> Result<ReviewComment> {
+    let comment = sqlx::query_as::<_, ReviewComment>(
+        "INSERT INTO review_comments (review_item_id, author, body) VALUES ($1, $2, $3) \
+         RETURNING *",
+    )
+    .bind(review_item_id)
+    .bind(author)
+    .bind(body)
+    .fetch_one(&mut **db_tx)
+    .await?;
+
+    AuditLog::log(
+        db_tx,
+        review_item_id,
+        ENTITY_REVIEW_ITEM,
+        "comment",
+        None,
+        Some(json!({ "comment_id": comment.id, "body": body })),
+        author,
+    )
+    .await?;
+    Ok(comment)
 }
 
-pub async fn check_idempotency_key(pool: &PgPool, key: &str) -> Result<Option<IdempotencyKey>> {
-    sqlx::query_as::<_, IdempotencyKey>(
-        "SELECT key, status, response, created_at, expires_at FROM idempotency_keys WHERE key = $1 AND expires_at > NOW()",
+/// Comments on review item `id`, oldest first.
+pub async fn list_review_comments(pool: &PgPool, id: Uuid) -> Result<Vec<ReviewComment>> {
+    with_timeout(
+        QueryTier::Read,
+        "SELECT * FROM review_comments WHERE review_item_id = $1",
+        sqlx::query_as::<_, ReviewComment>(
+            "SELECT * FROM review_comments WHERE review_item_id = $1 ORDER BY created_at ASC",
+        )
+        .bind(id)
+        .fetch_all(pool),
     )
-    .bind(key)
-    .fetch_optional(pool)
     .await
 }
 
-pub async fn insert_idempotency_key(
+// --- Approvals ---
+
+/// Largest amount among transactions `ids`; `None` when none exist.
+pub async fn max_transaction_amount(pool: &PgPool, ids: &[Uuid]) -> Result<Option<BigDecimal>> {
+    with_timeout(
+        QueryTier::Read,
+        "SELECT MAX(amount) FROM transactions WHERE id = ANY($1)",
+        sqlx::query_scalar::<_, Option<BigDecimal>>(
+            "SELECT MAX(amount) FROM transactions WHERE id = ANY($1)",
+        )
+        .bind(ids)
+        .fetch_one(pool),
+    )
+    .await
+}
+
+/// Store a proposed action awaiting a second approver.
+pub async fn insert_approval(
     pool: &PgPool,
-    key: &str,
+    kind: &str,
+    payload: &serde_json::Value,
+    amount: &BigDecimal,
+    proposed_by: &str,
+) -> Result<Approval> {
+    with_timeout(QueryTier::Write, "INSERT INTO approvals", async {
+        let mut db_tx = pool.begin().await?;
+        let approval = sqlx::query_as::<_, Approval>(
+            "INSERT INTO approvals (kind, payload, amount, proposed_by) VALUES ($1, $2, $3, $4) \
+             RETURNING *",
+        )
+        .bind(kind)
+        .bind(payload)
+        .bind(amount)
+        .bind(proposed_by)
+        .fetch_one(&mut *db_tx)
+        .await?;
+
+        AuditLog::log(
+            &mut db_tx,
+            approval.id,
+            ENTITY_APPROVAL,
+            "propose",
+            None,
+            Some(json!({ "kind": kind, "payload": payload, "amount": amount })),
+            proposed_by,
+        )
+        .await?;
+        db_tx.commit().await?;
+        Ok(approval)
+    })
+    .await
+}
+
+/// Approvals, newest first.
+pub async fn list_approvals(
+    pool: &PgPool,
+    status: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<Approval>> {
+    with_timeout(
+        QueryTier::Read,
+        "SELECT * FROM approvals ORDER BY created_at DESC",
+        sqlx::query_as::<_, Approval>(
+            r#"
+            SELECT * FROM approvals
+            WHERE ($1::text IS NULL OR status = $1)
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(status)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool),
+    )
+    .await
+}
+
+pub async fn get_approval(pool: &PgPool, id: Uuid) -> Result<Approval> {
+    with_timeout(
+        QueryTier::Read,
+        "SELECT * FROM approvals WHERE id = $1",
+        sqlx::query_as::<_, Approval>("SELECT * FROM approvals WHERE id = $1")
+            .bind(id)
+            .fetch_one(pool),
+    )
+    .await
+}
+
+/// Lock approval `id` for the rest of `db_tx`.
+pub async fn lock_approval(
+    db_tx: &mut SqlxTransaction<'_, Postgres>,
+    id: Uuid,
+) -> Result<Option<Approval>> {
+    sqlx::query_as::<_, Approval>("SELECT * FROM approvals WHERE id = $1 FOR UPDATE")
+        .bind(id)
+        .fetch_optional(&mut **db_tx)
+        .await
+}
+
+/// Record the decision on `approval` within `db_tx`. The approval must have
+/// been locked with [`lock_approval`].
+pub async fn decide_approval(
+    db_tx: &mut SqlxTransaction<'_, Postgres>,
+    approval: &Approval,
     status: &str,
-    response: Option<&serde_json::Value>,
-    expires_at: DateTime<Utc>,
-) -> Result<()> {
-    sqlx::query(
+    decided_by: &str,
+    note: Option<&str>,
+    result: Option<&serde_json::Value>,
+) -> Result<Approval> {
+    let decided = sqlx::query_as::<_, Approval>(
         r#"
-        INSERT INTO idempotency_keys (key, status, response, expires_at)
-        VALUES ($1, $2, $3, $4)
-        ON CONFLICT (key) DO NOTHING
+        UPDATE approvals SET
+            status = $2, decided_by = $3, decision_note = $4, result = $5,
+            decided_at = NOW(), updated_at = NOW()
+        WHERE id = $1
+        RETURNING *
         "#,
     )
-    .bind(key)
+    .bind(approval.id)
     .bind(status)
-    .bind(response)
-    .bind(expires_at)
-    .execute(pool)
+    .bind(decided_by)
+    .bind(note)
+    .bind(result)
+    .fetch_one(&mut **db_tx)
     .await?;
-    Ok(())
-}
 
-pub async fn update_idempotency_key_response(
-    pool: &PgPool,
-    key: &str,
-    response: &serde_json::Value,
-) -> Result<()> {
-    sqlx::query("UPDATE idempotency_keys SET response = $2, status = 'completed' WHERE key = $1")
-        .bind(key)
-        .bind(response)
-        .execute(pool)
-        .await?;
-    Ok(())
+    AuditLog::log(
+        db_tx,
+        approval.id,
+        ENTITY_APPROVAL,
+        status,
+        Some(json!({ "status": approval.status, "proposed_by": approval.proposed_by })),
+        Some(json!({ "status": status, "note": note, "result": result })),
+        decided_by,
+    )
+    .await?;
+    Ok(decided)
 }
 
 pub async fn cleanup_expired_idempotency_keys(pool: &PgPool) -> Result<u64> {
@@ -0,0 +1,320 @@
+//! `synapse-core --dev`: a self-contained server for local development and
+//! demos, behind the `sqlite-dev` feature.
+//!
+//! It needs neither Postgres nor Redis: deposits go through the same
+//! [`ProcessDeposit`] use case as production, but into a
+//! [`SqliteTransactionRepository`], and `X-Idempotency-Key` is honoured with
+//! an [`InMemoryIdempotencyStore`]. Only the core deposit endpoints are
+//! served; there is no processor, authentication, webhook signing or
+//! Horizon access.
+//!
+//! **Unsupported for production.** Nothing here is hardened, and idempotency
+//! keys are forgotten on restart.
+
+use crate::adapters::{InMemoryIdempotencyStore, SqliteTransactionRepository};
+use crate::domain::Transaction;
+use crate::error::AppError;
+use crate::handlers::webhook::CallbackPayload;
+use crate::middleware::idempotency::validate_idempotency_key;
+use crate::ports::{
+    IdempotencyClaim, IdempotencyStore, IdempotencyStoreError, StoredResponse,
+    TransactionRepository,
+};
+use crate::use_cases::process_deposit::{DepositError, DepositInput, ProcessDeposit};
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Database used when `DEV_DATABASE_URL` is unset, next to the working
+/// directory so it survives restarts.
+pub const DEFAULT_DATABASE_URL: &str = "sqlite://synapse-dev.db";
+
+/// Every dev-mode request shares one idempotency scope: there are no tenants.
+const IDEMPOTENCY_SCOPE: &str = "dev";
+
+#[derive(Clone)]
+struct DevState {
+    repository: Arc<dyn TransactionRepository>,
+    deposits: Arc<ProcessDeposit>,
+    idempotency: Arc<dyn IdempotencyStore>,
+}
+
+#[derive(Debug, Serialize)]
+struct TransactionView {
+    id: Uuid,
+    stellar_account: String,
+    amount: String,
+    asset_code: String,
+    status: String,
+    created_at: chrono::DateTime<chrono::Utc>,
+    updated_at: chrono::DateTime<chrono::Utc>,
+    anchor_transaction_id: Option<String>,
+    callback_type: Option<String>,
+    callback_status: Option<String>,
+    memo: Option<String>,
+    memo_type: Option<String>,
+    metadata: Option<serde_json::Value>,
+}
+
+impl From<Transaction> for TransactionView {
+    fn from(tx: Transaction) -> Self {
+        Self {
+            id: tx.id,
+            stellar_account: tx.stellar_account,
+            amount: tx.amount.to_string(),
+            asset_code: tx.asset_code,
+            status: tx.status,
+            created_at: tx.created_at,
+            updated_at: tx.updated_at,
+            anchor_transaction_id: tx.anchor_transaction_id,
+            callback_type: tx.callback_type,
+            callback_status: tx.callback_status,
+            memo: tx.memo,
+            memo_type: tx.memo_type,
+            metadata: tx.metadata,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ListQuery {
+    #[serde(default = "default_limit")]
+    limit: i64,
+    #[serde(default)]
+    offset: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+/// Router for dev mode over the given adapters.
+pub fn router(
+    repository: Arc<dyn TransactionRepository>,
+    idempotency: Arc<dyn IdempotencyStore>,
+) -> Router {
+    let state = DevState {
+        deposits: Arc::new(ProcessDeposit::new(repository.clone())),
+        repository,
+        idempotency,
+    };
+    Router::new()
+        .route("/health", get(health))
+        .route("/callback", post(callback))
+        .route("/transactions", get(list_transactions))
+        .route("/transactions/:id", get(get_transaction))
+        .with_state(state)
+}
+
+/// Serve dev mode on `port` over the SQLite database at `database_url`.
+pub async fn serve(port: u16, database_url: &str) -> anyhow::Result<()> {
+    tracing::warn!(
+        database_url,
+        "Running in --dev mode with SQLite and in-memory idempotency; \
+         UNSUPPORTED FOR PRODUCTION"
+    );
+    let repository = SqliteTransactionRepository::connect(database_url).await?;
+    let app = router(
+        Arc::new(repository),
+        Arc::new(InMemoryIdempotencyStore::default()),
+    );
+
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    tracing::info!("Dev server listening on {}", addr);
+    axum::Server::bind(&addr)
+        .serve(app.into_make_service())
+        .await?;
+    Ok(())
+}
+
+async fn health() -> impl IntoResponse {
+    Json(serde_json::json!({ "status": "healthy", "mode": "dev" }))
+}
+
+async fn callback(
+    State(state): State<DevState>,
+    headers: HeaderMap,
+    Json(payload): Json<CallbackPayload>,
+) -> Result<Response, AppError> {
+    let key = headers
+        .get("x-idempotency-key")
+        .map(|v| {
+            v.to_str()
+                .map_err(|_| AppError::BadRequest("Invalid idempotency key format".to_string()))
+                .and_then(validate_idempotency_key)
+        })
+        .transpose()?;
+    let Some(key) = key else {
+        let tx = create_deposit(&state, payload).await?;
+        return Ok((StatusCode::CREATED, Json(tx)).into_response());
+    };
+
+    let idempotency_error = |e: IdempotencyStoreError| AppError::Internal(e.to_string());
+    match state
+        .idempotency
+        .claim(IDEMPOTENCY_SCOPE, &key)
+        .await
+        .map_err(idempotency_error)?
+    {
+        IdempotencyClaim::Completed(stored) => Ok(replay(stored)),
+        IdempotencyClaim::InFlight => Err(AppError::TransactionConflict(
+            "A request with this idempotency key is still being processed".to_string(),
+        )),
+        IdempotencyClaim::New => match create_deposit(&state, payload).await {
+            Ok(tx) => {
+                let stored = StoredResponse {
+                    status: StatusCode::CREATED.as_u16(),
+                    body: serde_json::to_string(&tx)
+                        .map_err(|e| AppError::Internal(e.to_string()))?,
+                    content_type: Some("application/json".to_string()),
+                };
+                state
+                    .idempotency
+                    .complete(IDEMPOTENCY_SCOPE, &key, stored.clone())
+                    .await
+                    .map_err(idempotency_error)?;
+                Ok(replay(stored))
+            }
+            Err(e) => {
+                state
+                    .idempotency
+                    .release(IDEMPOTENCY_SCOPE, &key)
+                    .await
+                    .map_err(idempotency_error)?;
+                Err(e)
+            }
+        },
+    }
+}
+
+async fn create_deposit(
+    state: &DevState,
+    payload: CallbackPayload,
+) -> Result<TransactionView, AppError> {
+    let amount = BigDecimal::from_str(&payload.amount)
+        .map_err(|e| AppError::InvalidTransactionAmount(e.to_string()))?;
+    let output = state
+        .deposits
+        .execute(DepositInput {
+            stellar_account: payload.stellar_account,
+            amount,
+            asset_code: payload.asset_code,
+            anchor_transaction_id: payload.anchor_transaction_id,
+            callback_type: payload.callback_type,
+            callback_status: payload.callback_status,
+            memo: payload.memo,
+            memo_type: payload.memo_type,
+            metadata: payload.metadata,
+            allowed_assets: None,
+        })
+        .await
+        .map_err(|e| match e {
+            DepositError::AssetNotAllowed(asset) => AppError::AssetNotAllowed(asset),
+            DepositError::KycNotAccepted(account) => {
+                AppError::InsufficientPermissions(format!("customer {account} has not passed KYC"))
+            }
+            DepositError::Repository(e) => e.into(),
+        })?;
+    let tx = state.repository.get_by_id(output.transaction_id).await?;
+    Ok(tx.into())
+}
+
+fn replay(stored: StoredResponse) -> Response {
+    let status = StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK);
+    let content_type = stored
+        .content_type
+        .unwrap_or_else(|| "application/json".to_string());
+    (status, [(header::CONTENT_TYPE, content_type)], stored.body).into_response()
+}
+
+async fn list_transactions(
+    State(state): State<DevState>,
+    Query(query): Query<ListQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let transactions = state
+        .repository
+        .list(query.limit.clamp(1, 500), query.offset.max(0))
+        .await?;
+    Ok(Json(
+        transactions
+            .into_iter()
+            .map(TransactionView::from)
+            .collect::<Vec<_>>(),
+    ))
+}
+
+async fn get_transaction(
+    State(state): State<DevState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let tx = state.repository.get_by_id(id).await?;
+    Ok(Json(TransactionView::from(tx)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_idempotent_callback_replays_first_response() {
+        let repository = SqliteTransactionRepository::connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let app = router(
+            Arc::new(repository),
+            Arc::new(InMemoryIdempotencyStore::default()),
+        );
+        let request = || {
+            Request::builder()
+                .method("POST")
+                .uri("/callback")
+                .header("content-type", "application/json")
+                .header("x-idempotency-key", "dev-key-1")
+                .body(Body::from(
+                    serde_json::json!({
+                        "stellar_account": "GABC",
+                        "amount": "25.5",
+                        "asset_code": "USDC",
+                        "anchor_transaction_id": "anchor-1"
+                    })
+                    .to_string(),
+                ))
+                .unwrap()
+        };
+
+        let first = app.clone().oneshot(request()).await.unwrap();
+        assert_eq!(first.status(), StatusCode::CREATED);
+        let first = hyper::body::to_bytes(first.into_body()).await.unwrap();
+        let second = app.clone().oneshot(request()).await.unwrap();
+        assert_eq!(second.status(), StatusCode::CREATED);
+        let second = hyper::body::to_bytes(second.into_body()).await.unwrap();
+        assert_eq!(first, second);
+
+        let list = app
+            .oneshot(
+                Request::builder()
+                    .uri("/transactions")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let list = hyper::body::to_bytes(list.into_body()).await.unwrap();
+        let list: serde_json::Value = serde_json::from_slice(&list).unwrap();
+        assert_eq!(list.as_array().unwrap().len(), 1);
+        assert_eq!(list[0]["amount"], "25.5");
+    }
+}
@@ -0,0 +1,175 @@
+//! Domain events: what happened to a transaction or settlement.
+//!
+//! [`DomainEvent`] is the one model every consumer shares. Producers publish
+//! it on the event bus; the WebSocket stream and GraphQL subscriptions
+//! project it into their own frames, and the webhook dispatcher delivers it
+//! as the `data` of an outgoing payload wrapped in an [`EventEnvelope`].
+//!
+//! Each variant carries its own schema version. Bump it whenever a field is
+//! removed or changes meaning; adding an optional field does not need a bump.
+
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const TRANSACTION_CREATED: &str = "transaction.created";
+pub const TRANSACTION_STATUS_CHANGED: &str = "transaction.status_changed";
+pub const TRANSACTION_MOVED_TO_DLQ: &str = "transaction.moved_to_dlq";
+pub const SETTLEMENT_CLOSED: &str = "settlement.closed";
+
+/// Something that happened in the domain, tagged on the wire by its event
+/// type.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event_type")]
+pub enum DomainEvent {
+    /// A deposit callback was accepted and persisted.
+    #[serde(rename = "transaction.created")]
+    TransactionCreated {
+        transaction_id: Uuid,
+        tenant_id: Option<Uuid>,
+        stellar_account: String,
+        amount: BigDecimal,
+        asset_code: String,
+        status: String,
+        at: DateTime<Utc>,
+    },
+    /// A transaction moved from `from` to `to`.
+    #[serde(rename = "transaction.status_changed")]
+    StatusChanged {
+        transaction_id: Uuid,
+        tenant_id: Option<Uuid>,
+        from: Option<String>,
+        to: String,
+        message: Option<String>,
+        /// Where the transaction landed on the Stellar network, once verified.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        stellar_tx_hash: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        ledger: Option<i64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        closed_at: Option<DateTime<Utc>>,
+        at: DateTime<Utc>,
+    },
+    /// A transaction failed for good and was copied to the dead-letter queue.
+    #[serde(rename = "transaction.moved_to_dlq")]
+    MovedToDlq {
+        transaction_id: Uuid,
+        tenant_id: Option<Uuid>,
+        error_class: String,
+        reason: String,
+        attempts: i32,
+        at: DateTime<Utc>,
+    },
+    /// A settlement reached a terminal status (`completed` or `voided`).
+    #[serde(rename = "settlement.closed")]
+    SettlementClosed {
+        settlement_id: Uuid,
+        asset_code: String,
+        status: String,
+        total_amount: BigDecimal,
+        tx_count: i32,
+        at: DateTime<Utc>,
+    },
+}
+
+impl DomainEvent {
+    /// Dotted event type, as used for webhook subscriptions.
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            DomainEvent::TransactionCreated { .. } => TRANSACTION_CREATED,
+            DomainEvent::StatusChanged { .. } => TRANSACTION_STATUS_CHANGED,
+            DomainEvent::MovedToDlq { .. } => TRANSACTION_MOVED_TO_DLQ,
+            DomainEvent::SettlementClosed { .. } => SETTLEMENT_CLOSED,
+        }
+    }
+
+    /// Version of this event's payload schema.
+    pub fn schema_version(&self) -> u32 {
+        match self {
+            DomainEvent::TransactionCreated { .. }
+            | DomainEvent::StatusChanged { .. }
+            | DomainEvent::MovedToDlq { .. }
+            | DomainEvent::SettlementClosed { .. } => 1,
+        }
+    }
+
+    /// The transaction the event is about, if any.
+    pub fn transaction_id(&self) -> Option<Uuid> {
+        match self {
+            DomainEvent::TransactionCreated { transaction_id, .. }
+            | DomainEvent::StatusChanged { transaction_id, .. }
+            | DomainEvent::MovedToDlq { transaction_id, .. } => Some(*transaction_id),
+            DomainEvent::SettlementClosed { .. } => None,
+        }
+    }
+
+    /// When it happened.
+    pub fn occurred_at(&self) -> DateTime<Utc> {
+        match self {
+            DomainEvent::TransactionCreated { at, .. }
+            | DomainEvent::StatusChanged { at, .. }
+            | DomainEvent::MovedToDlq { at, .. }
+            | DomainEvent::SettlementClosed { at, .. } => *at,
+        }
+    }
+}
+
+/// A [`DomainEvent`] with its schema version, as serialized for consumers
+/// outside the process.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventEnvelope {
+    pub schema_version: u32,
+    #[serde(flatten)]
+    pub event: DomainEvent,
+}
+
+impl From<DomainEvent> for EventEnvelope {
+    fn from(event: DomainEvent) -> Self {
+        Self {
+            schema_version: event.schema_version(),
+            event,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_envelope_round_trip() {
+        let event = DomainEvent::MovedToDlq {
+            transaction_id: Uuid::new_v4(),
+            tenant_id: None,
+            error_class: "permanent".to_string(),
+            reason: "tx_failed".to_string(),
+            attempts: 3,
+            at: Utc::now(),
+        };
+        let json = serde_json::to_value(EventEnvelope::from(event.clone())).unwrap();
+        assert_eq!(json["event_type"], TRANSACTION_MOVED_TO_DLQ);
+        assert_eq!(json["schema_version"], 1);
+        assert_eq!(json["attempts"], 3);
+
+        let back: EventEnvelope = serde_json::from_value(json).unwrap();
+        assert_eq!(back.event, event);
+    }
+
+    #[test]
+    fn test_accessors() {
+        let at = Utc::now();
+        let closed = DomainEvent::SettlementClosed {
+            settlement_id: Uuid::new_v4(),
+            asset_code: "USDC".to_string(),
+            status: "completed".to_string(),
+            total_amount: BigDecimal::from_str("10.5").unwrap(),
+            tx_count: 2,
+            at,
+        };
+        assert_eq!(closed.event_type(), SETTLEMENT_CLOSED);
+        assert_eq!(closed.transaction_id(), None);
+        assert_eq!(closed.occurred_at(), at);
+    }
+}
@@ -1,6 +1,10 @@
 //! Domain layer: core business entities.
 //! No external dependencies (database, HTTP, etc.).
 
+pub mod events;
+pub mod stellar_account;
 pub mod transaction;
 
+pub use events::{DomainEvent, EventEnvelope};
+pub use stellar_account::{decode_secret_seed, StellarAddress, StellarAddressError};
 pub use transaction::Transaction;
@@ -0,0 +1,335 @@
+//! Stellar account addresses.
+//!
+//! An address is a strkey: a version byte, a payload and a little-endian
+//! CRC16-XModem checksum, base32-encoded without padding. Two kinds identify a
+//! receiving account:
+//!
+//! | Prefix | Kind             | Payload                          | Length |
+//! |--------|------------------|----------------------------------|--------|
+//! | `G`    | ed25519 account  | 32-byte public key               | 56     |
+//! | `M`    | muxed account    | public key + 64-bit id (BE)      | 69     |
+//!
+//! Exchanges send from muxed accounts to tell their customers apart. We keep
+//! the base `G...` account and the id apart, so the id survives without
+//! splitting lookups by account.
+
+use std::fmt;
+use std::str::FromStr;
+
+pub const ACCOUNT_ID_LEN: usize = 56;
+pub const MUXED_ACCOUNT_LEN: usize = 69;
+
+/// Version byte for ed25519 public keys (`6 << 3`), encoding to a leading `G`.
+const VERSION_ACCOUNT_ID: u8 = 6 << 3;
+/// Version byte for muxed accounts (`12 << 3`), encoding to a leading `M`.
+const VERSION_MUXED_ACCOUNT: u8 = 12 << 3;
+/// Version byte for ed25519 secret seeds (`18 << 3`), encoding to a leading `S`.
+const VERSION_SECRET_SEED: u8 = 18 << 3;
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StellarAddressError {
+    Empty,
+    Length,
+    Alphabet,
+    Version,
+    Checksum,
+}
+
+impl fmt::Display for StellarAddressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "is required"),
+            Self::Length => write!(
+                f,
+                "must be exactly {ACCOUNT_ID_LEN} (G...) or {MUXED_ACCOUNT_LEN} (M...) characters"
+            ),
+            Self::Alphabet => write!(f, "must contain only base32 characters (A-Z, 2-7)"),
+            Self::Version => write!(f, "must be an account id starting with 'G' or 'M'"),
+            Self::Checksum => write!(f, "checksum mismatch"),
+        }
+    }
+}
+
+impl std::error::Error for StellarAddressError {}
+
+/// A validated account address: the base ed25519 account, plus the muxed id
+/// when it arrived as an `M...` address.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StellarAddress {
+    account: String,
+    muxed_id: Option<u64>,
+}
+
+impl StellarAddress {
+    /// Parse a `G...` or `M...` strkey, verifying its checksum. Surrounding
+    /// whitespace is ignored; lower-case input is rejected like any other
+    /// non-base32 character.
+    pub fn parse(input: &str) -> Result<Self, StellarAddressError> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Err(StellarAddressError::Empty);
+        }
+        if input.len() != ACCOUNT_ID_LEN && input.len() != MUXED_ACCOUNT_LEN {
+            return Err(StellarAddressError::Length);
+        }
+        let decoded = base32_decode(input.as_bytes()).ok_or(StellarAddressError::Alphabet)?;
+        let (payload, checksum) = decoded.split_at(decoded.len() - 2);
+        if crc16_xmodem(payload) != u16::from_le_bytes([checksum[0], checksum[1]]) {
+            return Err(StellarAddressError::Checksum);
+        }
+        // Trailing bits beyond the last whole byte must be zero, so each
+        // address has exactly one spelling.
+        if encode(payload) != input {
+            return Err(StellarAddressError::Checksum);
+        }
+
+        match (payload[0], input.len()) {
+            (VERSION_ACCOUNT_ID, ACCOUNT_ID_LEN) => Ok(Self {
+                account: input.to_string(),
+                muxed_id: None,
+            }),
+            (VERSION_MUXED_ACCOUNT, MUXED_ACCOUNT_LEN) => {
+                let mut id = [0u8; 8];
+                id.copy_from_slice(&payload[33..41]);
+                let mut key = vec![VERSION_ACCOUNT_ID];
+                key.extend_from_slice(&payload[1..33]);
+                Ok(Self {
+                    account: encode(&key),
+                    muxed_id: Some(u64::from_be_bytes(id)),
+                })
+            }
+            _ => Err(StellarAddressError::Version),
+        }
+    }
+
+    /// The `G...` address of an ed25519 public key.
+    pub fn from_ed25519(key: [u8; 32]) -> Self {
+        let mut payload = vec![VERSION_ACCOUNT_ID];
+        payload.extend_from_slice(&key);
+        Self {
+            account: encode(&payload),
+            muxed_id: None,
+        }
+    }
+
+    /// Rebuild an address from its stored parts.
+    pub fn from_parts(account: &str, muxed_id: Option<u64>) -> Result<Self, StellarAddressError> {
+        let base = Self::parse(account)?;
+        if base.muxed_id.is_some() {
+            return Err(StellarAddressError::Version);
+        }
+        Ok(Self {
+            account: base.account,
+            muxed_id,
+        })
+    }
+
+    /// The base `G...` account.
+    pub fn account(&self) -> &str {
+        &self.account
+    }
+
+    /// The ed25519 public key of the base account.
+    pub fn public_key(&self) -> [u8; 32] {
+        // `account` was validated on construction, so it always decodes.
+        let decoded = base32_decode(self.account.as_bytes()).unwrap_or_default();
+        let mut key = [0u8; 32];
+        key.copy_from_slice(decoded.get(1..33).unwrap_or(&[0; 32]));
+        key
+    }
+
+    pub fn muxed_id(&self) -> Option<u64> {
+        self.muxed_id
+    }
+
+    pub fn is_muxed(&self) -> bool {
+        self.muxed_id.is_some()
+    }
+}
+
+impl FromStr for StellarAddress {
+    type Err = StellarAddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// The address as it was given: `G...`, or `M...` for a muxed account.
+impl fmt::Display for StellarAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(id) = self.muxed_id else {
+            return f.write_str(&self.account);
+        };
+        // `account` was validated on construction, so it always decodes.
+        let key = base32_decode(self.account.as_bytes()).unwrap_or_default();
+        let mut payload = vec![VERSION_MUXED_ACCOUNT];
+        payload.extend_from_slice(key.get(1..33).unwrap_or_default());
+        payload.extend_from_slice(&id.to_be_bytes());
+        f.write_str(&encode(&payload))
+    }
+}
+
+/// Decode an `S...` secret seed into its 32-byte ed25519 seed.
+pub fn decode_secret_seed(input: &str) -> Result<[u8; 32], StellarAddressError> {
+    let input = input.trim();
+    if input.len() != ACCOUNT_ID_LEN {
+        return Err(StellarAddressError::Length);
+    }
+    let decoded = base32_decode(input.as_bytes()).ok_or(StellarAddressError::Alphabet)?;
+    let (payload, checksum) = decoded.split_at(decoded.len() - 2);
+    if crc16_xmodem(payload) != u16::from_le_bytes([checksum[0], checksum[1]])
+        || encode(payload) != input
+    {
+        return Err(StellarAddressError::Checksum);
+    }
+    if payload[0] != VERSION_SECRET_SEED {
+        return Err(StellarAddressError::Version);
+    }
+    let mut seed = [0u8; 32];
+    seed.copy_from_slice(&payload[1..33]);
+    Ok(seed)
+}
+
+/// Strkey for `payload` (version byte included): payload + checksum, base32.
+fn encode(payload: &[u8]) -> String {
+    let mut data = payload.to_vec();
+    data.extend_from_slice(&crc16_xmodem(payload).to_le_bytes());
+    base32_encode(&data)
+}
+
+/// Unpadded RFC 4648 base32 encode.
+fn base32_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity((data.len() * 8 + 4) / 5);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for &byte in data {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+        buffer &= (1 << bits) - 1;
+    }
+    if bits > 0 {
+        out.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    out
+}
+
+/// Unpadded RFC 4648 base32 decode. Returns `None` on any non-alphabet byte.
+fn base32_decode(input: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(input.len() * 5 / 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for &c in input {
+        let v = BASE32_ALPHABET.iter().position(|&a| a == c)? as u32;
+        buffer = (buffer << 5) | v;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(out)
+}
+
+fn crc16_xmodem(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ACCOUNT: &str = "GAAZI4TCR3TY5OJHCTJC2A4QSY6CJWJH5IAJTGKIN2ER7LBNVKOCCWN7";
+    // SEP-23 test vector: GA7QYNF7...VSGZ muxed with id 0.
+    const MUXED: &str = "MA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJUAAAAAAAAAAAACJUQ";
+
+    #[test]
+    fn parses_account_id() {
+        let address = StellarAddress::parse(&format!("  {ACCOUNT} ")).unwrap();
+        assert_eq!(address.account(), ACCOUNT);
+        assert_eq!(address.muxed_id(), None);
+        assert_eq!(address.to_string(), ACCOUNT);
+    }
+
+    #[test]
+    fn splits_and_rebuilds_muxed_account() {
+        let address = StellarAddress::parse(MUXED).unwrap();
+        assert!(address.is_muxed());
+        assert_eq!(
+            address.account(),
+            "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ"
+        );
+        assert_eq!(address.muxed_id(), Some(0));
+        assert_eq!(address.to_string(), MUXED);
+
+        let rebuilt = StellarAddress::from_parts(address.account(), Some(0)).unwrap();
+        assert_eq!(rebuilt, address);
+        assert_eq!(
+            StellarAddress::from_parts(address.account(), Some(1234))
+                .unwrap()
+                .to_string(),
+            "MA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJUAAAAAAAAAAE2JUG6"
+        );
+        assert!(StellarAddress::from_parts(MUXED, Some(1)).is_err());
+    }
+
+    #[test]
+    fn muxed_id_roundtrips_full_range() {
+        let address = StellarAddress::from_parts(ACCOUNT, Some(u64::MAX)).unwrap();
+        let parsed = StellarAddress::parse(&address.to_string()).unwrap();
+        assert_eq!(parsed.account(), ACCOUNT);
+        assert_eq!(parsed.muxed_id(), Some(u64::MAX));
+    }
+
+    #[test]
+    fn rejects_malformed_addresses() {
+        use StellarAddressError::*;
+        assert_eq!(StellarAddress::parse(" "), Err(Empty));
+        assert_eq!(StellarAddress::parse("GABC"), Err(Length));
+        assert_eq!(
+            StellarAddress::parse(&ACCOUNT.to_ascii_lowercase()),
+            Err(Alphabet)
+        );
+
+        let mut bad_checksum = ACCOUNT.to_string();
+        bad_checksum.replace_range(55.., "A");
+        assert_eq!(StellarAddress::parse(&bad_checksum), Err(Checksum));
+
+        // Valid strkey, but a secret seed rather than an account id.
+        assert_eq!(
+            StellarAddress::parse("SBGWSG6BTNCKCOB3DIFBGCVMUPQFYPA2G4O34RMTB343OYPXU5DJDVMN"),
+            Err(Version)
+        );
+    }
+
+    #[test]
+    fn decodes_secret_seed_and_public_key() {
+        assert!(
+            decode_secret_seed("SBGWSG6BTNCKCOB3DIFBGCVMUPQFYPA2G4O34RMTB343OYPXU5DJDVMN").is_ok()
+        );
+        assert_eq!(
+            decode_secret_seed(ACCOUNT),
+            Err(StellarAddressError::Version)
+        );
+
+        let address = StellarAddress::parse(ACCOUNT).unwrap();
+        assert_eq!(StellarAddress::from_ed25519(address.public_key()), address);
+    }
+}
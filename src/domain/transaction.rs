@@ -1,5 +1,9 @@
 //! Transaction domain entity.
 //! Framework-agnostic representation of a financial transaction.
+//!
+//! Construction is pure: the caller supplies the id and creation time (the
+//! use cases take them from the `IdGenerator` and `Clock` ports), so the same
+//! inputs always build the same entity.
 
 use bigdecimal::BigDecimal;
 use chrono::{DateTime, Utc};
@@ -24,7 +28,11 @@ pub struct Transaction {
 }
 
 impl Transaction {
+    /// A new `pending` transaction with id `id`, created at `now`.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
+        id: Uuid,
+        now: DateTime<Utc>,
         stellar_account: String,
         amount: BigDecimal,
         asset_code: String,
@@ -35,9 +43,8 @@ impl Transaction {
         memo_type: Option<String>,
         metadata: Option<serde_json::Value>,
     ) -> Self {
-        let now = Utc::now();
         Self {
-            id: Uuid::new_v4(),
+            id,
             stellar_account,
             amount,
             asset_code,
@@ -53,3 +60,36 @@ impl Transaction {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_new_is_deterministic() {
+        let id = Uuid::from_u128(7);
+        let now = Utc.with_ymd_and_hms(2026, 5, 4, 3, 2, 1).unwrap();
+        let build = || {
+            Transaction::new(
+                id,
+                now,
+                "GABC".to_string(),
+                BigDecimal::from(10),
+                "USDC".to_string(),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+        };
+
+        let tx = build();
+        assert_eq!(tx.id, id);
+        assert_eq!(tx.status, "pending");
+        assert_eq!((tx.created_at, tx.updated_at), (now, now));
+        assert_eq!(build().created_at, tx.created_at);
+    }
+}
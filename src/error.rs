@@ -67,6 +67,16 @@ pub mod codes {
         400,
         "Invalid transaction status transition",
     );
+    pub const TRANSACTION_006: (&str, u16, &str) = (
+        "ERR_TRANSACTION_006",
+        409,
+        "Active transaction already exists for anchor_transaction_id",
+    );
+    pub const TRANSACTION_007: (&str, u16, &str) = (
+        "ERR_TRANSACTION_007",
+        403,
+        "Asset not allowed for this partner",
+    );
 
     // Webhook specific errors
     pub const WEBHOOK_001: (&str, u16, &str) =
@@ -80,12 +90,33 @@ pub mod codes {
     pub const SETTLEMENT_002: (&str, u16, &str) =
         ("ERR_SETTLEMENT_002", 409, "Settlement already exists");
 
+    // Accounting periods
+    pub const ACCOUNTING_001: (&str, u16, &str) = (
+        "ERR_ACCOUNTING_001",
+        409,
+        "Accounting period closed - record is immutable",
+    );
+
     // Rate limiting
     pub const RATE_LIMIT_001: (&str, u16, &str) =
         ("ERR_RATE_LIMIT_001", 429, "Rate limit exceeded");
 
     // Redis errors
     pub const REDIS_001: (&str, u16, &str) = ("ERR_REDIS_001", 500, "Redis operation failed");
+
+    // Overload
+    pub const OVERLOAD_001: (&str, u16, &str) = (
+        "ERR_OVERLOAD_001",
+        503,
+        "Server overloaded - request shed, retry later",
+    );
+
+    // Latency budgets
+    pub const TIMEOUT_001: (&str, u16, &str) = (
+        "ERR_TIMEOUT_001",
+        504,
+        "Request exceeded its latency budget",
+    );
 }
 
 /// Get all error codes as a vector for catalog generation
@@ -161,6 +192,16 @@ pub fn get_all_error_codes() -> Vec<ErrorCode> {
             http_status: codes::TRANSACTION_005.1,
             description: codes::TRANSACTION_005.2,
         },
+        ErrorCode {
+            code: codes::TRANSACTION_006.0,
+            http_status: codes::TRANSACTION_006.1,
+            description: codes::TRANSACTION_006.2,
+        },
+        ErrorCode {
+            code: codes::TRANSACTION_007.0,
+            http_status: codes::TRANSACTION_007.1,
+            description: codes::TRANSACTION_007.2,
+        },
         ErrorCode {
             code: codes::WEBHOOK_001.0,
             http_status: codes::WEBHOOK_001.1,
@@ -181,6 +222,11 @@ pub fn get_all_error_codes() -> Vec<ErrorCode> {
             http_status: codes::SETTLEMENT_002.1,
             description: codes::SETTLEMENT_002.2,
         },
+        ErrorCode {
+            code: codes::ACCOUNTING_001.0,
+            http_status: codes::ACCOUNTING_001.1,
+            description: codes::ACCOUNTING_001.2,
+        },
         ErrorCode {
             code: codes::RATE_LIMIT_001.0,
             http_status: codes::RATE_LIMIT_001.1,
@@ -191,13 +237,23 @@ pub fn get_all_error_codes() -> Vec<ErrorCode> {
             http_status: codes::REDIS_001.1,
             description: codes::REDIS_001.2,
         },
+        ErrorCode {
+            code: codes::OVERLOAD_001.0,
+            http_status: codes::OVERLOAD_001.1,
+            description: codes::OVERLOAD_001.2,
+        },
+        ErrorCode {
+            code: codes::TIMEOUT_001.0,
+            http_status: codes::TIMEOUT_001.1,
+            description: codes::TIMEOUT_001.2,
+        },
     ]
 }
 
 #[derive(Error, Debug)]
 pub enum AppError {
     #[error("Database error: {0}")]
-    Database(#[from] sqlx::Error),
+    Database(#[source] sqlx::Error),
 
     #[error("Database error: {0}")]
     DatabaseError(String),
@@ -239,6 +295,12 @@ pub enum AppError {
     #[error("Invalid status transition: {0}")]
     InvalidStatusTransition(String),
 
+    #[error("Transaction conflict: {0}")]
+    TransactionConflict(String),
+
+    #[error("Asset not allowed: {0}")]
+    AssetNotAllowed(String),
+
     #[error("Invalid webhook signature")]
     InvalidWebhookSignature,
 
@@ -251,6 +313,9 @@ pub enum AppError {
     #[error("Settlement already exists: {0}")]
     SettlementAlreadyExists(String),
 
+    #[error("Period closed: {0}")]
+    PeriodClosed(String),
+
     #[error("Rate limit exceeded")]
     RateLimitExceeded,
 
@@ -284,10 +349,13 @@ impl AppError {
             AppError::InvalidStellarAddress(_) => StatusCode::BAD_REQUEST,
             AppError::TransactionAlreadyProcessed(_) => StatusCode::CONFLICT,
             AppError::InvalidStatusTransition(_) => StatusCode::BAD_REQUEST,
+            AppError::TransactionConflict(_) => StatusCode::CONFLICT,
+            AppError::AssetNotAllowed(_) => StatusCode::FORBIDDEN,
             AppError::InvalidWebhookSignature => StatusCode::UNAUTHORIZED,
             AppError::MalformedWebhookPayload(_) => StatusCode::BAD_REQUEST,
             AppError::InvalidSettlementAmount(_) => StatusCode::BAD_REQUEST,
             AppError::SettlementAlreadyExists(_) => StatusCode::CONFLICT,
+            AppError::PeriodClosed(_) => StatusCode::CONFLICT,
             AppError::RateLimitExceeded => StatusCode::TOO_MANY_REQUESTS,
             AppError::AuthenticationFailed(_) => StatusCode::UNAUTHORIZED,
             AppError::InsufficientPermissions(_) => StatusCode::FORBIDDEN,
@@ -314,10 +382,13 @@ impl AppError {
             AppError::InvalidStellarAddress(_) => codes::TRANSACTION_003.0,
             AppError::TransactionAlreadyProcessed(_) => codes::TRANSACTION_004.0,
             AppError::InvalidStatusTransition(_) => codes::TRANSACTION_005.0,
+            AppError::TransactionConflict(_) => codes::TRANSACTION_006.0,
+            AppError::AssetNotAllowed(_) => codes::TRANSACTION_007.0,
             AppError::InvalidWebhookSignature => codes::WEBHOOK_001.0,
             AppError::MalformedWebhookPayload(_) => codes::WEBHOOK_002.0,
             AppError::InvalidSettlementAmount(_) => codes::SETTLEMENT_001.0,
             AppError::SettlementAlreadyExists(_) => codes::SETTLEMENT_002.0,
+            AppError::PeriodClosed(_) => codes::ACCOUNTING_001.0,
             AppError::RateLimitExceeded => codes::RATE_LIMIT_001.0,
             AppError::AuthenticationFailed(_) => codes::AUTH_001.0,
             AppError::InsufficientPermissions(_) => codes::AUTH_002.0,
@@ -327,6 +398,42 @@ impl AppError {
     }
 }
 
+/// Database constraint violations on `transactions` are surfaced as typed
+/// errors (409 / 400) instead of a generic database failure.
+impl From<sqlx::Error> for AppError {
+    fn from(err: sqlx::Error) -> Self {
+        use crate::db::constraints::{classify, ConstraintViolation};
+
+        match classify(&err) {
+            Some(v @ ConstraintViolation::DuplicateActiveAnchor(_)) => {
+                AppError::TransactionConflict(v.to_string())
+            }
+            Some(v @ ConstraintViolation::NonPositiveAmount) => {
+                AppError::InvalidTransactionAmount(v.to_string())
+            }
+            Some(ConstraintViolation::PeriodClosed(msg)) => AppError::PeriodClosed(msg),
+            Some(v) => AppError::Validation(v.to_string()),
+            None => AppError::Database(err),
+        }
+    }
+}
+
+/// Repository errors map onto the same responses as the equivalent sqlx
+/// errors.
+impl From<crate::ports::RepositoryError> for AppError {
+    fn from(err: crate::ports::RepositoryError) -> Self {
+        use crate::ports::RepositoryError;
+
+        match err {
+            RepositoryError::NotFound(id) => AppError::NotFound(format!("Transaction {id}")),
+            RepositoryError::Conflict(msg) => AppError::TransactionConflict(msg),
+            RepositoryError::ConstraintViolation(msg) => AppError::Validation(msg),
+            RepositoryError::PeriodClosed(msg) => AppError::PeriodClosed(msg),
+            RepositoryError::Database(e) => AppError::Database(e),
+        }
+    }
+}
+
 /// Extension type to carry request ID through the request lifecycle.
 #[derive(Clone, Debug)]
 pub struct RequestId(pub String);
@@ -352,6 +459,14 @@ impl IntoResponse for AppError {
             AppError::InvalidStatusTransition(msg) => {
                 format!("Status transition is not allowed. {msg}")
             }
+            AppError::AssetNotAllowed(msg) => {
+                format!("The partner is not authorized for this asset. {msg}")
+            }
+            AppError::PeriodClosed(msg) => {
+                format!(
+                    "Record is in a closed accounting period; post an adjustment instead. {msg}"
+                )
+            }
             AppError::Validation(msg) => {
                 format!("Validation failed. {msg}")
             }
@@ -371,6 +486,59 @@ impl IntoResponse for AppError {
     }
 }
 
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// `application/problem+json` (RFC 9457) response for one of [`codes`]. Used
+/// by the middleware that answers before a handler runs or finishes (latency
+/// budgets, load shedding); handler errors keep the [`AppError`] body.
+pub fn problem_response(
+    code: (&str, u16, &str),
+    detail: impl Into<String>,
+    instance: &str,
+) -> Response {
+    let (status, body) = problem_body(code, detail.into(), instance);
+    into_problem_response(status, body)
+}
+
+/// [`problem_response`] with a `request_id` member, for failures the client
+/// should quote back when reporting them.
+pub fn problem_response_with_request_id(
+    code: (&str, u16, &str),
+    detail: impl Into<String>,
+    instance: &str,
+    request_id: &str,
+) -> Response {
+    let (status, mut body) = problem_body(code, detail.into(), instance);
+    body["request_id"] = serde_json::Value::from(request_id);
+    into_problem_response(status, body)
+}
+
+fn problem_body(
+    (code, status, title): (&str, u16, &str),
+    detail: String,
+    instance: &str,
+) -> (StatusCode, serde_json::Value) {
+    let status = StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let body = serde_json::json!({
+        "type": format!("/errors#{code}"),
+        "title": title,
+        "status": status.as_u16(),
+        "detail": detail,
+        "instance": instance,
+        "code": code,
+    });
+    (status, body)
+}
+
+fn into_problem_response(status: StatusCode, body: serde_json::Value) -> Response {
+    let mut response = (status, Json(body)).into_response();
+    response.headers_mut().insert(
+        axum::http::header::CONTENT_TYPE,
+        axum::http::HeaderValue::from_static(PROBLEM_JSON),
+    );
+    response
+}
+
 /// Error response structure for JSON serialization
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ErrorResponse {
@@ -504,6 +672,14 @@ mod tests {
             AppError::InvalidStatusTransition("test".to_string()).code(),
             codes::TRANSACTION_005.0
         );
+        assert_eq!(
+            AppError::TransactionConflict("test".to_string()).code(),
+            codes::TRANSACTION_006.0
+        );
+        assert_eq!(
+            AppError::AssetNotAllowed("test".to_string()).code(),
+            codes::TRANSACTION_007.0
+        );
         assert_eq!(
             AppError::InvalidWebhookSignature.code(),
             codes::WEBHOOK_001.0
@@ -520,6 +696,10 @@ mod tests {
             AppError::SettlementAlreadyExists("test".to_string()).code(),
             codes::SETTLEMENT_002.0
         );
+        assert_eq!(
+            AppError::PeriodClosed("test".to_string()).code(),
+            codes::ACCOUNTING_001.0
+        );
         assert_eq!(AppError::RateLimitExceeded.code(), codes::RATE_LIMIT_001.0);
         assert_eq!(
             AppError::AuthenticationFailed("test".to_string()).code(),
@@ -531,13 +711,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_non_constraint_sqlx_error_maps_to_database() {
+        let error = AppError::from(sqlx::Error::RowNotFound);
+        assert!(matches!(error, AppError::Database(_)));
+        assert_eq!(error.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[test]
+    fn test_transaction_conflict_status_code() {
+        let error = AppError::TransactionConflict("anchor-1".to_string());
+        assert_eq!(error.status_code(), StatusCode::CONFLICT);
+    }
+
     #[test]
     fn test_error_catalog_size() {
         let catalog = get_all_error_codes();
         // Verify we have all expected error codes
         assert!(
-            catalog.len() >= 19,
-            "Error catalog should have at least 19 codes"
+            catalog.len() >= 20,
+            "Error catalog should have at least 20 codes"
         );
     }
 }
@@ -0,0 +1,366 @@
+//! Which root operations a caller may run, by role.
+//!
+//! The `/graphql` handler attaches what it knows about the caller to the
+//! request: an [`AdminPrincipal`] when it presents an admin key, and its PII
+//! [`Role`] from its API key scopes. [`AccessRole`] combines the two:
+//!
+//! | Role         | Mutations                                                  | Admin queries |
+//! |--------------|------------------------------------------------------------|---------------|
+//! | `viewer`     | `updateTransactionMetadata`, `registerTransactionCallback` | no            |
+//! | `compliance` | as `viewer`                                                | no            |
+//! | `admin`      | all                                                        | yes           |
+//!
+//! Admin queries are the job and review queue reads, which REST only serves
+//! under `/admin`. [`AccessGuard`] checks the root fields of every operation,
+//! through fragments, before anything resolves, and rejects the whole
+//! operation with `AUTHORIZATION_ERROR` if one is not allowed. A mutation
+//! missing from [`MUTATIONS`] is rejected for every role, so a new one stays
+//! closed until it is classified. `access` shows a caller its role and what it
+//! may call.
+
+use crate::graphql::error::{GraphQlError, CODE_AUTHORIZATION};
+use crate::graphql::pii::Role;
+use crate::middleware::auth::AdminPrincipal;
+use async_graphql::{
+    extensions::{Extension, ExtensionContext, ExtensionFactory, NextParseQuery},
+    parser::types::{ExecutableDocument, OperationType, Selection, SelectionSet},
+    Context, Object, Pos, Positioned, Result, ServerError, ServerResult, SimpleObject, Variables,
+};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// A caller's role for operation access, lowest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AccessRole {
+    Viewer,
+    Compliance,
+    Admin,
+}
+
+/// Every mutation and the lowest role that may run it.
+pub const MUTATIONS: &[(&str, AccessRole)] = &[
+    ("updateTransactionMetadata", AccessRole::Viewer),
+    ("registerTransactionCallback", AccessRole::Viewer),
+    ("replayDlq", AccessRole::Admin),
+    ("cancelJob", AccessRole::Admin),
+    ("claimReviewItem", AccessRole::Admin),
+    ("resolveReviewItem", AccessRole::Admin),
+    ("addReviewComment", AccessRole::Admin),
+];
+
+/// Queries only admins may run; every other query is open.
+pub const ADMIN_QUERIES: &[&str] = &["job", "jobs", "reviewItem", "reviewItems"];
+
+impl AccessRole {
+    /// An admin principal makes the caller an admin; otherwise its PII role
+    /// decides.
+    pub fn of(principal: Option<&AdminPrincipal>, role: Option<&Role>) -> Self {
+        match (principal, role.copied().unwrap_or_default()) {
+            (Some(_), _) => AccessRole::Admin,
+            (None, Role::Compliance) => AccessRole::Compliance,
+            (None, Role::Viewer) => AccessRole::Viewer,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AccessRole::Viewer => "viewer",
+            AccessRole::Compliance => "compliance",
+            AccessRole::Admin => "admin",
+        }
+    }
+
+    /// Whether this role may run the root `field` of an `ty` operation.
+    pub fn may_call(self, ty: OperationType, field: &str) -> bool {
+        match ty {
+            OperationType::Mutation => MUTATIONS
+                .iter()
+                .any(|(name, lowest)| *name == field && self >= *lowest),
+            OperationType::Query => self == AccessRole::Admin || !ADMIN_QUERIES.contains(&field),
+            OperationType::Subscription => true,
+        }
+    }
+
+    /// The mutations this role may run, in [`MUTATIONS`] order.
+    pub fn allowed_mutations(self) -> Vec<&'static str> {
+        MUTATIONS
+            .iter()
+            .filter(|(_, lowest)| self >= *lowest)
+            .map(|(name, _)| *name)
+            .collect()
+    }
+
+    /// The [`ADMIN_QUERIES`] this role may run.
+    pub fn allowed_admin_queries(self) -> Vec<&'static str> {
+        ADMIN_QUERIES
+            .iter()
+            .copied()
+            .filter(|field| self.may_call(OperationType::Query, field))
+            .collect()
+    }
+}
+
+/// The admin principal a resolver acts as; [`AccessGuard`] has already
+/// rejected the operation when there is none, so this only fails if a field
+/// is missing from the admin lists.
+pub fn admin_principal<'a>(ctx: &Context<'a>) -> Result<&'a AdminPrincipal> {
+    ctx.data_opt::<AdminPrincipal>()
+        .ok_or_else(|| GraphQlError::Authorization.into_gql_error())
+}
+
+/// Schema extension enforcing the role table on every operation.
+pub struct AccessGuard;
+
+impl ExtensionFactory for AccessGuard {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(AccessGuardExtension)
+    }
+}
+
+struct AccessGuardExtension;
+
+/// Root fields selected by `selection_set`, through inline fragments and
+/// fragment spreads, with their positions.
+fn root_fields<'a>(
+    doc: &'a ExecutableDocument,
+    selection_set: &'a Positioned<SelectionSet>,
+    visited: &mut HashSet<&'a str>,
+    fields: &mut Vec<(&'a str, Pos)>,
+) {
+    for selection in &selection_set.node.items {
+        match &selection.node {
+            Selection::Field(field) => fields.push((field.node.name.node.as_str(), field.pos)),
+            Selection::InlineFragment(fragment) => {
+                root_fields(doc, &fragment.node.selection_set, visited, fields)
+            }
+            Selection::FragmentSpread(spread) => {
+                let name = spread.node.fragment_name.node.as_str();
+                // Unknown or cyclic fragments are reported by validation.
+                if visited.insert(name) {
+                    if let Some(fragment) = doc.fragments.get(&spread.node.fragment_name.node) {
+                        root_fields(doc, &fragment.node.selection_set, visited, fields);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[async_graphql::async_trait::async_trait]
+impl Extension for AccessGuardExtension {
+    async fn parse_query(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParseQuery<'_>,
+    ) -> ServerResult<ExecutableDocument> {
+        let doc = next.run(ctx, query, variables).await?;
+        let role = AccessRole::of(ctx.data_opt::<AdminPrincipal>(), ctx.data_opt::<Role>());
+
+        for (_, operation) in doc.operations.iter() {
+            let mut fields = Vec::new();
+            root_fields(
+                &doc,
+                &operation.node.selection_set,
+                &mut HashSet::new(),
+                &mut fields,
+            );
+            let ty = operation.node.ty;
+            if let Some((field, pos)) = fields.into_iter().find(|(f, _)| !role.may_call(ty, f)) {
+                tracing::warn!(
+                    role = role.as_str(),
+                    field,
+                    "GraphQL operation rejected by access guard"
+                );
+                return Err(forbidden(role, field, pos));
+            }
+        }
+
+        Ok(doc)
+    }
+}
+
+/// The rejection for a field outside the caller's role, with the same
+/// `AUTHORIZATION_ERROR` code whichever field it is.
+fn forbidden(role: AccessRole, field: &str, pos: Pos) -> ServerError {
+    let mut err = ServerError::new(
+        format!("The {} role may not call {field}", role.as_str()),
+        Some(pos),
+    );
+    err.extensions
+        .get_or_insert_with(Default::default)
+        .set("code", CODE_AUTHORIZATION);
+    err
+}
+
+/// What the caller may call, as returned by `access`.
+#[derive(SimpleObject)]
+pub struct CallerAccess {
+    /// `viewer`, `compliance` or `admin`.
+    pub role: String,
+    /// The admin principal's name, when an admin key was presented.
+    pub principal: Option<String>,
+    /// Mutations the caller may run.
+    pub mutations: Vec<String>,
+    /// Admin-only queries the caller may run; every other query is open.
+    pub admin_queries: Vec<String>,
+}
+
+/// Introspection of the caller's own access.
+#[derive(Default)]
+pub struct AccessQuery;
+
+#[Object]
+impl AccessQuery {
+    /// The caller's role and the operations it may call.
+    async fn access(&self, ctx: &Context<'_>) -> CallerAccess {
+        let principal = ctx.data_opt::<AdminPrincipal>();
+        let role = AccessRole::of(principal, ctx.data_opt::<Role>());
+        CallerAccess {
+            role: role.as_str().to_string(),
+            principal: principal.map(|p| p.name.clone()),
+            mutations: role
+                .allowed_mutations()
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            admin_queries: role
+                .allowed_admin_queries()
+                .into_iter()
+                .map(String::from)
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphql::resolvers::{Mutation, Query, Subscription};
+    use async_graphql::{Request, Schema};
+    use serde_json::json;
+
+    const JOB_ID: &str = "550e8400-e29b-41d4-a716-446655440000";
+
+    fn schema() -> Schema<Query, Mutation, Subscription> {
+        Schema::build(
+            Query::default(),
+            Mutation::default(),
+            Subscription::default(),
+        )
+        .extension(AccessGuard)
+        .finish()
+    }
+
+    fn operator() -> AdminPrincipal {
+        AdminPrincipal {
+            name: "alice".to_string(),
+            shared: false,
+        }
+    }
+
+    fn codes(response: &async_graphql::Response) -> Vec<String> {
+        response
+            .errors
+            .iter()
+            .filter_map(|e| e.extensions.as_ref()?.get("code").cloned())
+            .map(|v| v.to_string().trim_matches('"').to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_roles_are_ordered() {
+        assert_eq!(AccessRole::of(None, None), AccessRole::Viewer);
+        assert_eq!(
+            AccessRole::of(None, Some(&Role::Compliance)),
+            AccessRole::Compliance
+        );
+        assert_eq!(
+            AccessRole::of(Some(&operator()), Some(&Role::Viewer)),
+            AccessRole::Admin
+        );
+        assert_eq!(
+            AccessRole::Viewer.allowed_mutations(),
+            vec!["updateTransactionMetadata", "registerTransactionCallback"]
+        );
+        assert_eq!(AccessRole::Admin.allowed_mutations().len(), MUTATIONS.len());
+        assert!(AccessRole::Compliance.allowed_admin_queries().is_empty());
+        assert!(!AccessRole::Admin.may_call(OperationType::Mutation, "unlisted"));
+    }
+
+    #[tokio::test]
+    async fn test_every_mutation_is_classified() {
+        let response = schema()
+            .execute(r#"{ __type(name: "Mutation") { fields { name } } }"#)
+            .await;
+        let data = response.data.into_json().unwrap();
+        let fields = data["__type"]["fields"].as_array().unwrap();
+        assert!(!fields.is_empty());
+        for field in fields {
+            let name = field["name"].as_str().unwrap();
+            assert!(
+                MUTATIONS.iter().any(|(m, _)| *m == name),
+                "mutation {name} is missing from access::MUTATIONS"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_viewer_cannot_run_admin_mutation() {
+        let query = format!(r#"mutation {{ cancelJob(id: "{JOB_ID}") {{ id }} }}"#);
+        let response = schema().execute(query.as_str()).await;
+        assert_eq!(codes(&response), vec![CODE_AUTHORIZATION]);
+
+        let compliance = schema()
+            .execute(Request::new(query).data(Role::Compliance))
+            .await;
+        assert_eq!(codes(&compliance), vec![CODE_AUTHORIZATION]);
+    }
+
+    #[tokio::test]
+    async fn test_fragments_do_not_hide_admin_fields() {
+        let query = format!(
+            r#"mutation {{ ...M }} fragment M on Mutation {{ cancelJob(id: "{JOB_ID}") {{ id }} }}"#
+        );
+        let response = schema().execute(query.as_str()).await;
+        assert_eq!(codes(&response), vec![CODE_AUTHORIZATION]);
+
+        let query = "{ ... on Query { jobs { id } } }";
+        let response = schema().execute(query).await;
+        assert_eq!(codes(&response), vec![CODE_AUTHORIZATION]);
+    }
+
+    #[tokio::test]
+    async fn test_admin_passes_the_guard() {
+        let query = format!(r#"mutation {{ cancelJob(id: "{JOB_ID}") {{ id }} }}"#);
+        let response = schema().execute(Request::new(query).data(operator())).await;
+        // Past the guard the resolver runs and fails for lack of a database.
+        assert!(!response.errors.is_empty());
+        assert!(!codes(&response).contains(&CODE_AUTHORIZATION.to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_access_query_describes_the_caller() {
+        let query = "{ access { role principal mutations adminQueries } }";
+        let viewer = schema().execute(query).await;
+        assert_eq!(
+            viewer.data.into_json().unwrap(),
+            json!({"access": {
+                "role": "viewer",
+                "principal": null,
+                "mutations": ["updateTransactionMetadata", "registerTransactionCallback"],
+                "adminQueries": []
+            }})
+        );
+
+        let admin = schema().execute(Request::new(query).data(operator())).await;
+        let data = admin.data.into_json().unwrap();
+        assert_eq!(data["access"]["role"], "admin");
+        assert_eq!(data["access"]["principal"], "alice");
+        assert_eq!(
+            data["access"]["adminQueries"],
+            json!(["job", "jobs", "reviewItem", "reviewItems"])
+        );
+    }
+}
@@ -24,11 +24,30 @@
 //! | `AUTHORIZATION_ERROR` | Caller lacks permission for the resource |
 //! | `RATE_LIMITED` | Caller has exceeded the request rate limit |
 //! | `COMPLEXITY_ERROR` | Query exceeds depth / complexity / alias limits |
+//! | `CONFLICT` | Write conflicts with existing data |
 //! | `DATABASE_ERROR` | Underlying database operation failed (details redacted) |
 //! | `INTERNAL_ERROR` | Unexpected server error (details redacted) |
-
+//!
+//! # Masking
+//!
+//! Resolvers should convert database errors with [`sqlx_error`]. As a safety
+//! net, [`ErrorMasking`] inspects every response: any error that does not
+//! carry a `code` is logged in full and replaced with a redacted
+//! `INTERNAL_ERROR`, so a stray `?` on a `sqlx::Error` cannot leak SQL or
+//! connection details. Every error, including parse, validation and access
+//! errors raised before execution, also gets a `requestId` extension matching
+//! the server-side log line.
+
+use std::sync::Arc;
+
+use async_graphql::extensions::{
+    Extension, ExtensionContext, ExtensionFactory, NextExecute, NextRequest,
+};
 use async_graphql::Error as GqlError;
-use async_graphql::ErrorExtensions;
+use async_graphql::{ErrorExtensions, Response, ServerError, Value};
+
+use crate::db::constraints::{self, ConstraintViolation};
+use crate::error::RequestId;
 
 // ---------------------------------------------------------------------------
 // Stable error codes
@@ -40,6 +59,7 @@ pub const CODE_AUTHENTICATION: &str = "AUTHENTICATION_ERROR";
 pub const CODE_AUTHORIZATION: &str = "AUTHORIZATION_ERROR";
 pub const CODE_RATE_LIMITED: &str = "RATE_LIMITED";
 pub const CODE_COMPLEXITY: &str = "COMPLEXITY_ERROR";
+pub const CODE_CONFLICT: &str = "CONFLICT";
 pub const CODE_DATABASE: &str = "DATABASE_ERROR";
 pub const CODE_INTERNAL: &str = "INTERNAL_ERROR";
 
@@ -74,6 +94,9 @@ pub enum GraphQlError {
     /// The query exceeds depth, complexity, or alias limits.
     Complexity(String),
 
+    /// The write conflicts with existing data (e.g. a unique constraint).
+    Conflict(String),
+
     /// An underlying database operation failed.
     ///
     /// The `detail` string must be a generic, non-sensitive description.
@@ -97,6 +120,7 @@ impl GraphQlError {
             GraphQlError::Authorization => CODE_AUTHORIZATION,
             GraphQlError::RateLimited { .. } => CODE_RATE_LIMITED,
             GraphQlError::Complexity(_) => CODE_COMPLEXITY,
+            GraphQlError::Conflict(_) => CODE_CONFLICT,
             GraphQlError::Database(_) => CODE_DATABASE,
             GraphQlError::Internal(_) => CODE_INTERNAL,
         }
@@ -129,6 +153,7 @@ impl GraphQlError {
                 "Too many requests — rate limit exceeded".to_string()
             }
             GraphQlError::Complexity(msg) => msg.clone(),
+            GraphQlError::Conflict(msg) => msg.clone(),
             GraphQlError::Database(msg) => msg.clone(),
             GraphQlError::Internal(msg) => msg.clone(),
         }
@@ -171,6 +196,146 @@ pub fn internal_error(raw_cause: &dyn std::fmt::Display) -> GqlError {
     GraphQlError::Internal("An internal error occurred".to_string()).into()
 }
 
+/// Maps a `sqlx::Error` to a client-safe GraphQL error.
+///
+/// `RowNotFound` becomes `NOT_FOUND`, known constraint violations become
+/// `VALIDATION_ERROR` / `CONFLICT` with their generic description, and
+/// everything else is logged and redacted via [`database_error`].
+pub fn sqlx_error(err: sqlx::Error) -> GqlError {
+    if matches!(err, sqlx::Error::RowNotFound) {
+        return not_found_error("Resource");
+    }
+    match constraints::classify(&err) {
+        Some(ConstraintViolation::DuplicateActiveAnchor(_)) => GraphQlError::Conflict(
+            "An active transaction already exists for this anchor_transaction_id".to_string(),
+        )
+        .into(),
+        Some(v @ ConstraintViolation::PeriodClosed(_)) => {
+            GraphQlError::Conflict(v.to_string()).into()
+        }
+        Some(v) => GraphQlError::Validation(v.to_string()).into(),
+        None => database_error(&err),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Masking extension
+// ---------------------------------------------------------------------------
+
+/// Message prefixes async-graphql uses for argument/variable coercion
+/// failures. These describe the client's own input and are safe to return.
+const INPUT_ERROR_PREFIXES: &[&str] = &["Failed to parse", "Invalid value for argument"];
+
+/// Schema extension that redacts uncoded errors and stamps `requestId`.
+#[derive(Default)]
+pub struct ErrorMasking;
+
+impl ExtensionFactory for ErrorMasking {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(ErrorMaskingExtension)
+    }
+}
+
+struct ErrorMaskingExtension;
+
+#[async_graphql::async_trait::async_trait]
+impl Extension for ErrorMaskingExtension {
+    /// Stamps `requestId` on errors raised before execution, which
+    /// [`Self::execute`] does not see.
+    async fn request(&self, ctx: &ExtensionContext<'_>, next: NextRequest<'_>) -> Response {
+        let mut response = next.run(ctx).await;
+        if response.errors.iter().all(has_request_id) {
+            return response;
+        }
+
+        let request_id = resolve_request_id(ctx);
+        for err in response.errors.iter_mut().filter(|e| !has_request_id(e)) {
+            err.extensions
+                .get_or_insert_with(Default::default)
+                .set("requestId", Value::String(request_id.clone()));
+        }
+        response
+    }
+
+    async fn execute(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        operation_name: Option<&str>,
+        next: NextExecute<'_>,
+    ) -> Response {
+        let mut response = next.run(ctx, operation_name).await;
+        if response.errors.is_empty() {
+            return response;
+        }
+
+        let request_id = resolve_request_id(ctx);
+        response.errors = response
+            .errors
+            .into_iter()
+            .map(|err| mask_error(err, &request_id))
+            .collect();
+        response
+    }
+}
+
+/// Request id from the HTTP layer (`RequestId` data or `x-request-id`
+/// header), or a fresh one so the client can still quote it.
+fn resolve_request_id(ctx: &ExtensionContext<'_>) -> String {
+    if let Some(RequestId(id)) = ctx.data_opt::<RequestId>() {
+        return id.clone();
+    }
+    ctx.data_opt::<axum::http::HeaderMap>()
+        .and_then(|h| h.get("x-request-id"))
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| !v.is_empty() && v.len() <= 128)
+        .map(ToOwned::to_owned)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+}
+
+fn has_request_id(err: &ServerError) -> bool {
+    err.extensions
+        .as_ref()
+        .map(|e| e.get("requestId").is_some())
+        .unwrap_or(false)
+}
+
+fn has_code(err: &ServerError) -> bool {
+    err.extensions
+        .as_ref()
+        .map(|e| e.get("code").is_some())
+        .unwrap_or(false)
+}
+
+/// Redacts an uncoded error (logging the original) and adds `requestId`.
+fn mask_error(mut err: ServerError, request_id: &str) -> ServerError {
+    if !has_code(&err) {
+        if INPUT_ERROR_PREFIXES
+            .iter()
+            .any(|p| err.message.starts_with(p))
+        {
+            err.extensions
+                .get_or_insert_with(Default::default)
+                .set("code", CODE_VALIDATION);
+        } else {
+            tracing::error!(
+                request_id = %request_id,
+                path = ?err.path,
+                cause = %err.message,
+                "GraphQL resolver returned an unclassified error"
+            );
+            err.message = "An internal error occurred".to_string();
+            err.source = None;
+            let mut extensions = async_graphql::ErrorExtensionValues::default();
+            extensions.set("code", CODE_INTERNAL);
+            err.extensions = Some(extensions);
+        }
+    }
+    err.extensions
+        .get_or_insert_with(Default::default)
+        .set("requestId", Value::String(request_id.to_string()));
+    err
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -270,6 +435,93 @@ mod tests {
         assert_eq!(ext_code(&err).as_deref(), Some(CODE_NOT_FOUND));
     }
 
+    fn server_ext(err: &ServerError, key: &str) -> Option<String> {
+        err.extensions
+            .as_ref()
+            .and_then(|e| e.get(key))
+            .and_then(|v| match v {
+                Value::String(s) => Some(s.clone()),
+                _ => None,
+            })
+    }
+
+    #[test]
+    fn sqlx_row_not_found_maps_to_not_found() {
+        let err = sqlx_error(sqlx::Error::RowNotFound);
+        assert_eq!(ext_code(&err).as_deref(), Some(CODE_NOT_FOUND));
+    }
+
+    #[test]
+    fn sqlx_other_errors_are_redacted() {
+        let err = sqlx_error(sqlx::Error::Protocol(
+            "connection to 10.0.0.5:5432 refused".into(),
+        ));
+        assert_eq!(ext_code(&err).as_deref(), Some(CODE_DATABASE));
+        assert!(!err.message.contains("10.0.0.5"));
+    }
+
+    #[test]
+    fn mask_error_redacts_uncoded_errors_and_adds_request_id() {
+        let raw = ServerError::new(
+            "error returned from database: relation \"transactions\" does not exist",
+            None,
+        );
+        let masked = mask_error(raw, "req-1");
+        assert_eq!(masked.message, "An internal error occurred");
+        assert_eq!(server_ext(&masked, "code").as_deref(), Some(CODE_INTERNAL));
+        assert_eq!(server_ext(&masked, "requestId").as_deref(), Some("req-1"));
+    }
+
+    #[test]
+    fn mask_error_keeps_coded_errors() {
+        let coded = not_found_error("Transaction").into_server_error(Default::default());
+        let masked = mask_error(coded, "req-2");
+        assert!(masked.message.contains("Transaction"));
+        assert_eq!(server_ext(&masked, "code").as_deref(), Some(CODE_NOT_FOUND));
+        assert_eq!(server_ext(&masked, "requestId").as_deref(), Some("req-2"));
+    }
+
+    #[test]
+    fn mask_error_treats_input_coercion_errors_as_validation() {
+        let raw = ServerError::new("Failed to parse \"UUID\": Invalid UUID 'abc'", None);
+        let masked = mask_error(raw, "req-3");
+        assert!(masked.message.contains("Invalid UUID"));
+        assert_eq!(
+            server_ext(&masked, "code").as_deref(),
+            Some(CODE_VALIDATION)
+        );
+    }
+
+    #[tokio::test]
+    async fn errors_before_execution_get_request_id() {
+        struct Query;
+
+        #[async_graphql::Object]
+        impl Query {
+            async fn ping(&self) -> bool {
+                true
+            }
+        }
+
+        let schema = async_graphql::Schema::build(
+            Query,
+            async_graphql::EmptyMutation,
+            async_graphql::EmptySubscription,
+        )
+        .extension(ErrorMasking)
+        .finish();
+
+        for query in ["{ ping", "{ pong }"] {
+            let request = async_graphql::Request::new(query).data(RequestId("req-4".into()));
+            let response = schema.execute(request).await;
+            assert_eq!(response.errors.len(), 1, "{query}");
+            assert_eq!(
+                server_ext(&response.errors[0], "requestId").as_deref(),
+                Some("req-4")
+            );
+        }
+    }
+
     #[test]
     fn all_variants_have_non_empty_codes() {
         let errors: Vec<GraphQlError> = vec![
@@ -281,6 +533,7 @@ mod tests {
                 retry_after_secs: 0,
             },
             GraphQlError::Complexity("c".into()),
+            GraphQlError::Conflict("x".into()),
             GraphQlError::Database("d".into()),
             GraphQlError::Internal("i".into()),
         ];
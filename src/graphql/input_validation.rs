@@ -4,7 +4,15 @@
 //! schema types and resolver code stay free of ad-hoc string checks.
 
 /// Permitted status values for transaction queries.
-const ALLOWED_STATUSES: &[&str] = &["pending", "completed", "failed", "cancelled", "processing"];
+const ALLOWED_STATUSES: &[&str] = &[
+    "pending",
+    "processing",
+    "completed",
+    "failed",
+    "dlq",
+    "refund_pending",
+    "compliance_review",
+];
 
 /// Maximum length for free-form string filter fields.
 const MAX_FILTER_FIELD_LENGTH: usize = 256;
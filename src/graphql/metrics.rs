@@ -0,0 +1,227 @@
+//! Execution metrics for the GraphQL layer (async-graphql schema extension).
+//!
+//! Every operation and every resolved field is timed:
+//!
+//! - `graphql_request_duration_ms` records the execution time of the whole
+//!   operation.
+//! - `graphql_resolver_duration_ms` records each resolver, labelled with
+//!   `field = "ParentType.fieldName"`. The label set is bounded by the schema,
+//!   never by client input (aliases and arguments are not recorded).
+//! - Resolvers slower than the configured threshold are logged at `WARN` with
+//!   their response path, which is usually enough to spot an N+1 (the same
+//!   field appearing once per list item).
+//!
+//! Introspection fields are not recorded.
+//!
+//! # Configuration
+//!
+//! | Env var                          | Default | Description                         |
+//! |----------------------------------|---------|-------------------------------------|
+//! | `GRAPHQL_SLOW_RESOLVER_MS`       | `100`   | Threshold for slow-resolver logging |
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_graphql::{
+    extensions::{
+        Extension, ExtensionContext, ExtensionFactory, NextExecute, NextResolve, ResolveInfo,
+    },
+    Response, ServerResult, Value,
+};
+use opentelemetry::{metrics::Histogram, KeyValue};
+
+/// Default slow-resolver threshold.
+const DEFAULT_SLOW_RESOLVER_THRESHOLD: Duration = Duration::from_millis(100);
+
+/// Configuration for the GraphQL metrics extension.
+#[derive(Debug, Clone)]
+pub struct GraphQlMetricsConfig {
+    /// Resolvers taking at least this long are logged.
+    pub slow_resolver_threshold: Duration,
+}
+
+impl Default for GraphQlMetricsConfig {
+    fn default() -> Self {
+        Self {
+            slow_resolver_threshold: DEFAULT_SLOW_RESOLVER_THRESHOLD,
+        }
+    }
+}
+
+impl GraphQlMetricsConfig {
+    /// Reads `GRAPHQL_SLOW_RESOLVER_MS`, falling back to the default when the
+    /// variable is unset or not a number.
+    pub fn from_env() -> Self {
+        let slow_resolver_threshold = std::env::var("GRAPHQL_SLOW_RESOLVER_MS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .map(Duration::from_millis)
+            .unwrap_or(DEFAULT_SLOW_RESOLVER_THRESHOLD);
+        Self {
+            slow_resolver_threshold,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ExtensionFactory
+// ---------------------------------------------------------------------------
+
+/// Factory registered on the schema; instruments are created once and shared
+/// by the per-request extension instances.
+pub struct GraphQlMetrics {
+    config: GraphQlMetricsConfig,
+    request_duration_ms: Histogram<f64>,
+    resolver_duration_ms: Histogram<f64>,
+}
+
+impl GraphQlMetrics {
+    pub fn new(config: GraphQlMetricsConfig) -> Self {
+        Self {
+            config,
+            request_duration_ms: crate::metrics::graphql_request_duration_ms(),
+            resolver_duration_ms: crate::metrics::graphql_resolver_duration_ms(),
+        }
+    }
+}
+
+impl Default for GraphQlMetrics {
+    fn default() -> Self {
+        Self::new(GraphQlMetricsConfig::default())
+    }
+}
+
+impl ExtensionFactory for GraphQlMetrics {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(GraphQlMetricsExtension {
+            slow_resolver_threshold: self.config.slow_resolver_threshold,
+            request_duration_ms: self.request_duration_ms.clone(),
+            resolver_duration_ms: self.resolver_duration_ms.clone(),
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Extension (per-request)
+// ---------------------------------------------------------------------------
+
+struct GraphQlMetricsExtension {
+    slow_resolver_threshold: Duration,
+    request_duration_ms: Histogram<f64>,
+    resolver_duration_ms: Histogram<f64>,
+}
+
+#[async_graphql::async_trait::async_trait]
+impl Extension for GraphQlMetricsExtension {
+    async fn execute(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        operation_name: Option<&str>,
+        next: NextExecute<'_>,
+    ) -> Response {
+        let start = Instant::now();
+        let response = next.run(ctx, operation_name).await;
+        let elapsed = start.elapsed();
+
+        self.request_duration_ms.record(
+            duration_ms(elapsed),
+            &[KeyValue::new("success", response.errors.is_empty())],
+        );
+        tracing::debug!(
+            operation = ?operation_name,
+            duration_ms = duration_ms(elapsed),
+            errors = response.errors.len(),
+            "GraphQL operation executed"
+        );
+
+        response
+    }
+
+    async fn resolve(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        info: ResolveInfo<'_>,
+        next: NextResolve<'_>,
+    ) -> ServerResult<Option<Value>> {
+        if info.is_for_introspection {
+            return next.run(ctx, info).await;
+        }
+
+        let field = field_label(info.parent_type, info.name);
+        let path_node = info.path_node;
+        let start = Instant::now();
+        let result = next.run(ctx, info).await;
+        let elapsed = start.elapsed();
+
+        self.resolver_duration_ms.record(
+            duration_ms(elapsed),
+            &[KeyValue::new("field", field.clone())],
+        );
+
+        if is_slow(elapsed, self.slow_resolver_threshold) {
+            tracing::warn!(
+                field = %field,
+                path = %path_node,
+                duration_ms = duration_ms(elapsed),
+                threshold_ms = duration_ms(self.slow_resolver_threshold),
+                "Slow GraphQL resolver"
+            );
+        }
+
+        result
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// Metric label for a field: `ParentType.fieldName`.
+fn field_label(parent_type: &str, field_name: &str) -> String {
+    format!("{parent_type}.{field_name}")
+}
+
+fn is_slow(elapsed: Duration, threshold: Duration) -> bool {
+    elapsed >= threshold
+}
+
+fn duration_ms(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn field_label_uses_parent_type_and_field_name() {
+        assert_eq!(field_label("Query", "transactions"), "Query.transactions");
+        assert_eq!(field_label("Transaction", "amount"), "Transaction.amount");
+    }
+
+    #[test]
+    fn slow_threshold_is_inclusive() {
+        let threshold = Duration::from_millis(100);
+        assert!(!is_slow(Duration::from_millis(99), threshold));
+        assert!(is_slow(Duration::from_millis(100), threshold));
+        assert!(is_slow(Duration::from_secs(1), threshold));
+    }
+
+    #[test]
+    fn duration_is_reported_in_milliseconds() {
+        assert_eq!(duration_ms(Duration::from_millis(250)), 250.0);
+        assert_eq!(duration_ms(Duration::from_micros(1500)), 1.5);
+    }
+
+    #[test]
+    fn default_config_threshold() {
+        assert_eq!(
+            GraphQlMetricsConfig::default().slow_resolver_threshold,
+            DEFAULT_SLOW_RESOLVER_THRESHOLD
+        );
+    }
+}
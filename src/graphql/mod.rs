@@ -19,14 +19,26 @@
 //! - Query depth limit (max 10 levels) prevents stack overflow attacks
 //! - Query complexity limit (max 1000 points) prevents expensive queries
 //! - Alias limit (max 20 aliases) prevents bypassing other limits
+//! - Operation access by caller role (see [`access`])
+//!
+//! Sensitive fields (full Stellar accounts, partner metadata) are masked at
+//! resolve time unless the request carries a role allowed to see PII; see
+//! [`pii`].
+//!
+//! Operation and per-resolver latencies are exported by [`metrics`], which
+//! also logs resolvers slower than `GRAPHQL_SLOW_RESOLVER_MS`.
 //!
 //! See [Health Checks Documentation](../../docs/graphql-health-checks.md) for detailed information.
 
+pub mod access;
 pub mod error;
 pub mod input_validation;
+pub mod metrics;
 pub mod pagination;
+pub mod pii;
 pub mod rate_limiting;
 pub mod resolvers;
+pub mod scalars;
 pub mod schema;
 pub mod shutdown;
 pub mod validation;
@@ -0,0 +1,179 @@
+//! Field-level PII masking by caller role.
+//!
+//! Resolvers for sensitive fields pass their value through [`mask_str`] or
+//! [`mask_json`], which mask it with the [`crate::utils::sanitize`] rules
+//! unless the caller's [`Role`] may see PII:
+//!
+//! | Role         | `stellarAccount`, `muxedAccount` | `metadata`            |
+//! |--------------|----------------------------------|-----------------------|
+//! | `viewer`     | `GABC****WXYZ`                   | every value `****`    |
+//! | `compliance` | in full                          | in full               |
+//!
+//! The role is read from request data, so whichever HTTP layer
+//! authenticates the caller attaches it when executing the schema:
+//!
+//! ```text
+//! let request = async_graphql::Request::new(query).data(Role::Compliance);
+//! schema.execute(request).await
+//! ```
+//!
+//! A request without a role is treated as `viewer`: masking fails closed.
+//! [`Role::from_scopes`] maps API key scopes to a role the same way REST
+//! responses are redacted (see [`crate::middleware::redaction`]): only
+//! `read:pii` sees PII.
+
+use crate::middleware::redaction;
+use crate::utils::sanitize;
+use async_graphql::Context;
+use serde_json::Value;
+use std::str::FromStr;
+
+/// Who is asking, as far as PII visibility goes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Role {
+    /// Dashboards and partner users: sensitive fields are masked.
+    #[default]
+    Viewer,
+    /// Compliance officers: sensitive fields are shown in full.
+    Compliance,
+}
+
+impl Role {
+    /// The role attached to the request, or [`Role::Viewer`] if none was.
+    pub fn of(ctx: &Context<'_>) -> Self {
+        ctx.data_opt::<Role>().copied().unwrap_or_default()
+    }
+
+    /// The role of a caller whose API key has `scopes`.
+    pub fn from_scopes(scopes: &[String]) -> Self {
+        if redaction::has_pii_scope(scopes) {
+            Role::Compliance
+        } else {
+            Role::Viewer
+        }
+    }
+
+    pub fn sees_pii(self) -> bool {
+        matches!(self, Role::Compliance)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Role::Viewer => "viewer",
+            Role::Compliance => "compliance",
+        }
+    }
+}
+
+impl FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "viewer" => Ok(Role::Viewer),
+            "compliance" => Ok(Role::Compliance),
+            other => Err(format!("unknown role '{other}'")),
+        }
+    }
+}
+
+/// `value` as `role` may see it.
+pub fn mask_str_for(role: Role, value: &str) -> String {
+    if role.sees_pii() {
+        value.to_string()
+    } else {
+        sanitize::mask_str(value)
+    }
+}
+
+/// Partner metadata as `role` may see it; keys are kept either way.
+pub fn mask_json_for(role: Role, value: &Value) -> Value {
+    if role.sees_pii() {
+        value.clone()
+    } else {
+        sanitize::redact_metadata(value)
+    }
+}
+
+/// [`mask_str_for`] the role of the current request.
+pub fn mask_str(ctx: &Context<'_>, value: &str) -> String {
+    mask_str_for(Role::of(ctx), value)
+}
+
+/// [`mask_json_for`] the role of the current request.
+pub fn mask_json(ctx: &Context<'_>, value: &Value) -> Value {
+    mask_json_for(Role::of(ctx), value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const ACCOUNT: &str = "GAAZI4TCR3TY5OJHCTJC2A4QSY6CJWJH5IAJTGKIN2ER7LBNVKOCCWN7";
+
+    #[test]
+    fn viewer_sees_masked_account() {
+        let masked = mask_str_for(Role::Viewer, ACCOUNT);
+        assert_eq!(masked, "GAAZ****CWN7");
+    }
+
+    #[test]
+    fn compliance_sees_full_account() {
+        assert_eq!(mask_str_for(Role::Compliance, ACCOUNT), ACCOUNT);
+    }
+
+    #[test]
+    fn viewer_sees_metadata_keys_only() {
+        let metadata = json!({"email": "a@b.example", "order": {"id": 42}});
+        assert_eq!(
+            mask_json_for(Role::Viewer, &metadata),
+            json!({"email": "****", "order": {"id": "****"}})
+        );
+        assert_eq!(mask_json_for(Role::Compliance, &metadata), metadata);
+    }
+
+    #[test]
+    fn roles_parse_case_insensitively() {
+        assert_eq!("Compliance".parse::<Role>(), Ok(Role::Compliance));
+        assert_eq!(" viewer ".parse::<Role>(), Ok(Role::Viewer));
+        assert!("admin".parse::<Role>().is_err());
+    }
+
+    #[test]
+    fn read_pii_scope_is_compliance() {
+        assert_eq!(
+            Role::from_scopes(&["read:pii".to_string()]),
+            Role::Compliance
+        );
+        assert_eq!(Role::from_scopes(&[]), Role::Viewer);
+    }
+
+    #[tokio::test]
+    async fn resolvers_mask_unless_request_carries_compliance_role() {
+        use async_graphql::{EmptyMutation, EmptySubscription, Object, Schema};
+
+        struct Query;
+
+        #[Object]
+        impl Query {
+            async fn account(&self, ctx: &Context<'_>) -> String {
+                mask_str(ctx, ACCOUNT)
+            }
+        }
+
+        let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+        let anonymous = schema.execute("{ account }").await;
+        assert_eq!(
+            anonymous.data.into_json().unwrap(),
+            json!({"account": "GAAZ****CWN7"})
+        );
+
+        let request = async_graphql::Request::new("{ account }").data(Role::Compliance);
+        let compliance = schema.execute(request).await;
+        assert_eq!(
+            compliance.data.into_json().unwrap(),
+            json!({"account": ACCOUNT})
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use crate::db::{models::BackgroundJob, queries};
+use crate::graphql::error::sqlx_error;
+use crate::graphql::input_validation::validate_limit;
+use crate::graphql::scalars::UuidScalar;
+use crate::services::job_runner::{JobKind, JobState};
+use crate::AppState;
+use async_graphql::{Context, Json, Object, Result, SimpleObject};
+use chrono::{DateTime, Utc};
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// A long-running background job (export, backfill, recompute).
+#[derive(SimpleObject)]
+pub struct Job {
+    pub id: Uuid,
+    pub kind: String,
+    pub status: String,
+    pub params: Json<serde_json::Value>,
+    pub progress_current: i64,
+    /// `null` until the job knows how much work there is.
+    pub progress_total: Option<i64>,
+    pub result: Option<Json<serde_json::Value>>,
+    pub error: Option<String>,
+    pub cancel_requested: bool,
+    pub requested_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<BackgroundJob> for Job {
+    fn from(job: BackgroundJob) -> Self {
+        Job {
+            id: job.id,
+            kind: job.kind,
+            status: job.status,
+            params: Json(job.params),
+            progress_current: job.progress_current,
+            progress_total: job.progress_total,
+            result: job.result.map(Json),
+            error: job.error,
+            cancel_requested: job.cancel_requested,
+            requested_by: job.requested_by,
+            created_at: job.created_at,
+            started_at: job.started_at,
+            completed_at: job.completed_at,
+            updated_at: job.updated_at,
+        }
+    }
+}
+
+/// Background job status queries.
+#[derive(Default)]
+pub struct JobQuery;
+
+#[Object]
+impl JobQuery {
+    /// Fetch a single background job by ID.
+    async fn job(&self, ctx: &Context<'_>, id: UuidScalar) -> Result<Job> {
+        let state = ctx.data::<AppState>()?;
+        queries::get_job(&state.db, id.0)
+            .await
+            .map(Job::from)
+            .map_err(sqlx_error)
+    }
+
+    /// List background jobs, newest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - e.g. `transaction_export`, `reconciliation_backfill`, `rollup_recompute`
+    /// * `status` - `queued`, `running`, `completed`, `failed` or `cancelled`
+    /// * `limit` - Maximum number of results (default: 20)
+    /// * `offset` - Pagination offset (default: 0)
+    async fn jobs(
+        &self,
+        ctx: &Context<'_>,
+        kind: Option<String>,
+        status: Option<String>,
+        limit: Option<i64>,
+        offset: Option<i64>,
+    ) -> Result<Vec<Job>> {
+        let limit = limit.unwrap_or(20);
+        validate_limit(limit).map_err(|e| async_graphql::Error::new(e.to_string()))?;
+        let kind = kind
+            .as_deref()
+            .map(JobKind::from_str)
+            .transpose()
+            .map_err(async_graphql::Error::new)?;
+        let status = status
+            .as_deref()
+            .map(JobState::from_str)
+            .transpose()
+            .map_err(async_graphql::Error::new)?;
+
+        let state = ctx.data::<AppState>()?;
+        let jobs = queries::list_jobs(
+            &state.db,
+            kind.as_ref().map(JobKind::as_str),
+            status.as_ref().map(JobState::as_str),
+            limit,
+            offset.unwrap_or(0).max(0),
+        )
+        .await
+        .map_err(sqlx_error)?;
+        Ok(jobs.into_iter().map(Job::from).collect())
+    }
+}
+
+/// Background job control.
+#[derive(Default)]
+pub struct JobMutation;
+
+#[Object]
+impl JobMutation {
+    /// Cancel a queued or running job.
+    ///
+    /// Queued jobs are cancelled immediately; running jobs stop at their next
+    /// checkpoint and are returned with `cancelRequested: true`.
+    async fn cancel_job(&self, ctx: &Context<'_>, id: UuidScalar) -> Result<Job> {
+        let state = ctx.data::<AppState>()?;
+        if let Some(job) = queries::request_job_cancel(&state.db, id.0)
+            .await
+            .map_err(sqlx_error)?
+        {
+            return Ok(job.into());
+        }
+        let job = queries::get_job(&state.db, id.0)
+            .await
+            .map_err(sqlx_error)?;
+        Err(async_graphql::Error::new(format!(
+            "Job is already {}",
+            job.status
+        )))
+    }
+}
@@ -1,30 +1,47 @@
+pub mod job;
+pub mod review;
 pub mod settlement;
+pub mod stats;
 pub mod transaction;
 
-pub use settlement::SettlementQuery;
+pub use job::{JobMutation, JobQuery};
+pub use review::{ReviewMutation, ReviewQuery};
+pub use settlement::{SettlementQuery, SettlementSubscription};
+pub use stats::StatsQuery;
 pub use transaction::{TransactionMutation, TransactionQuery, TransactionSubscription};
 
+use crate::graphql::access::AccessQuery;
 use async_graphql::MergedObject;
 
 #[derive(MergedObject, Default)]
-pub struct Query(TransactionQuery, SettlementQuery);
+pub struct Query(
+    AccessQuery,
+    TransactionQuery,
+    SettlementQuery,
+    StatsQuery,
+    JobQuery,
+    ReviewQuery,
+);
 
 pub mod mutation {
+    use super::job::JobMutation;
+    use super::review::ReviewMutation;
     use super::transaction::TransactionMutation;
     use async_graphql::MergedObject;
 
     #[derive(MergedObject, Default)]
-    pub struct Mutation(TransactionMutation);
+    pub struct Mutation(TransactionMutation, JobMutation, ReviewMutation);
 }
 
 pub use mutation::Mutation;
 
 pub mod subscription {
+    use super::settlement::SettlementSubscription;
     use super::transaction::TransactionSubscription;
     use async_graphql::MergedSubscription;
 
     #[derive(MergedSubscription, Default)]
-    pub struct Subscription(TransactionSubscription);
+    pub struct Subscription(TransactionSubscription, SettlementSubscription);
 }
 
 pub use subscription::Subscription;
@@ -0,0 +1,237 @@
+use crate::db::{models, queries};
+use crate::graphql::access::admin_principal;
+use crate::graphql::error::{sqlx_error, GraphQlError};
+use crate::graphql::input_validation::validate_limit;
+use crate::graphql::scalars::UuidScalar;
+use crate::services::review_queue::{
+    self, Resolution, ReviewError, ReviewItemView, ReviewKind, ReviewState,
+};
+use crate::AppState;
+use async_graphql::{ComplexObject, Context, InputObject, Object, Result, SimpleObject};
+use chrono::{DateTime, Utc};
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// An item in the compliance review queue: a held transaction or a
+/// structuring review.
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct ReviewItem {
+    pub id: Uuid,
+    /// `transaction` or `structuring`.
+    pub kind: String,
+    pub transaction_id: Option<Uuid>,
+    pub structuring_review_id: Option<Uuid>,
+    /// `open`, `assigned` or `resolved`.
+    pub state: String,
+    pub assignee: Option<String>,
+    pub assigned_at: Option<DateTime<Utc>>,
+    pub sla_due_at: DateTime<Utc>,
+    /// Unresolved and past `slaDueAt`.
+    pub overdue: bool,
+    /// Seconds until `slaDueAt`, negative once overdue; `null` when resolved.
+    pub sla_remaining_secs: Option<i64>,
+    pub resolution: Option<String>,
+    pub resolved_by: Option<String>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[ComplexObject]
+impl ReviewItem {
+    /// The item's comment thread, oldest first.
+    async fn comments(&self, ctx: &Context<'_>) -> Result<Vec<ReviewComment>> {
+        let state = ctx.data::<AppState>()?;
+        let comments = queries::list_review_comments(&state.db, self.id)
+            .await
+            .map_err(sqlx_error)?;
+        Ok(comments.into_iter().map(ReviewComment::from).collect())
+    }
+}
+
+impl ReviewItem {
+    /// The item with its SLA timer as of `now`.
+    fn at(item: models::ReviewItem, now: DateTime<Utc>) -> Self {
+        let view = ReviewItemView::at(item, now);
+        let item = view.item;
+        ReviewItem {
+            id: item.id,
+            kind: item.kind,
+            transaction_id: item.transaction_id,
+            structuring_review_id: item.structuring_review_id,
+            state: item.state,
+            assignee: item.assignee,
+            assigned_at: item.assigned_at,
+            sla_due_at: item.sla_due_at,
+            overdue: view.overdue,
+            sla_remaining_secs: view.sla_remaining_secs,
+            resolution: item.resolution,
+            resolved_by: item.resolved_by,
+            resolved_at: item.resolved_at,
+            created_at: item.created_at,
+            updated_at: item.updated_at,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct ReviewComment {
+    pub id: Uuid,
+    pub author: String,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<models::ReviewComment> for ReviewComment {
+    fn from(comment: models::ReviewComment) -> Self {
+        ReviewComment {
+            id: comment.id,
+            author: comment.author,
+            body: comment.body,
+            created_at: comment.created_at,
+        }
+    }
+}
+
+fn review_error(e: ReviewError) -> async_graphql::Error {
+    match e {
+        ReviewError::NotFound(id) => GraphQlError::NotFound(format!("Review item {id}")).into(),
+        ReviewError::Conflict(msg) => GraphQlError::Conflict(msg).into(),
+        ReviewError::Invalid(msg) => GraphQlError::Validation(msg).into(),
+        ReviewError::Database(e) => sqlx_error(e),
+    }
+}
+
+fn parse<T: FromStr<Err = String>>(value: Option<String>) -> Result<Option<T>> {
+    value
+        .as_deref()
+        .map(T::from_str)
+        .transpose()
+        .map_err(|e| GraphQlError::Validation(e).into())
+}
+
+/// Filter criteria for review item queries.
+///
+/// All fields are optional and combined with AND logic.
+#[derive(InputObject, Default)]
+pub struct ReviewItemFilter {
+    /// `open`, `assigned` or `resolved`.
+    pub state: Option<String>,
+    /// `transaction` or `structuring`.
+    pub kind: Option<String>,
+    pub assignee: Option<String>,
+    /// Only unresolved items past their SLA.
+    pub overdue: Option<bool>,
+}
+
+/// Compliance review queue queries.
+#[derive(Default)]
+pub struct ReviewQuery;
+
+#[Object]
+impl ReviewQuery {
+    /// Fetch a single review item by ID.
+    async fn review_item(&self, ctx: &Context<'_>, id: UuidScalar) -> Result<ReviewItem> {
+        let state = ctx.data::<AppState>()?;
+        queries::get_review_item(&state.db, id.0)
+            .await
+            .map(|item| ReviewItem::at(item, state.clock.now()))
+            .map_err(sqlx_error)
+    }
+
+    /// List review items, soonest SLA first.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - Optional filter criteria (state, kind, assignee, overdue)
+    /// * `limit` - Maximum number of results (default: 20)
+    /// * `offset` - Pagination offset (default: 0)
+    async fn review_items(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<ReviewItemFilter>,
+        limit: Option<i64>,
+        offset: Option<i64>,
+    ) -> Result<Vec<ReviewItem>> {
+        let limit = limit.unwrap_or(20);
+        validate_limit(limit).map_err(|e| async_graphql::Error::new(e.to_string()))?;
+        let filter = filter.unwrap_or_default();
+        let review_state = parse::<ReviewState>(filter.state)?;
+        let kind = parse::<ReviewKind>(filter.kind)?;
+
+        let app_state = ctx.data::<AppState>()?;
+        let now = app_state.clock.now();
+        let items = queries::list_review_items(
+            &app_state.db,
+            &queries::ReviewItemFilter {
+                state: review_state.as_ref().map(ReviewState::as_str),
+                kind: kind.as_ref().map(ReviewKind::as_str),
+                assignee: filter.assignee.as_deref(),
+                overdue_only: filter.overdue.unwrap_or(false),
+            },
+            now,
+            limit,
+            offset.unwrap_or(0).max(0),
+        )
+        .await
+        .map_err(sqlx_error)?;
+        Ok(items
+            .into_iter()
+            .map(|item| ReviewItem::at(item, now))
+            .collect())
+    }
+}
+
+/// Compliance review queue actions.
+#[derive(Default)]
+pub struct ReviewMutation;
+
+#[Object]
+impl ReviewMutation {
+    /// Assign a review item to the calling admin.
+    async fn claim_review_item(&self, ctx: &Context<'_>, id: UuidScalar) -> Result<ReviewItem> {
+        let reviewer = &admin_principal(ctx)?.name;
+        let state = ctx.data::<AppState>()?;
+        review_queue::claim(&state.db, id.0, reviewer)
+            .await
+            .map(|item| ReviewItem::at(item, state.clock.now()))
+            .map_err(review_error)
+    }
+
+    /// Decide a review item as the calling admin and apply the decision to
+    /// its subject.
+    ///
+    /// `resolution` is `approved` or `rejected` for transactions and
+    /// `dismissed` or `escalated` for structuring reviews.
+    async fn resolve_review_item(
+        &self,
+        ctx: &Context<'_>,
+        id: UuidScalar,
+        resolution: String,
+        comment: Option<String>,
+    ) -> Result<ReviewItem> {
+        let reviewer = &admin_principal(ctx)?.name;
+        let resolution = Resolution::from_str(&resolution).map_err(GraphQlError::Validation)?;
+        let state = ctx.data::<AppState>()?;
+        review_queue::resolve(&state.db, id.0, reviewer, resolution, comment.as_deref())
+            .await
+            .map(|item| ReviewItem::at(item, state.clock.now()))
+            .map_err(review_error)
+    }
+
+    /// Add a comment to a review item's thread as the calling admin.
+    async fn add_review_comment(
+        &self,
+        ctx: &Context<'_>,
+        id: UuidScalar,
+        body: String,
+    ) -> Result<ReviewComment> {
+        let author = &admin_principal(ctx)?.name;
+        let state = ctx.data::<AppState>()?;
+        review_queue::add_comment(&state.db, id.0, author, &body)
+            .await
+            .map(ReviewComment::from)
+            .map_err(review_error)
+    }
+}
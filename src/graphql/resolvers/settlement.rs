@@ -1,6 +1,14 @@
 use crate::db::{models::Settlement, queries};
+use crate::graphql::error::sqlx_error;
+use crate::graphql::input_validation::validate_asset_code;
+use crate::graphql::scalars::UuidScalar;
+use crate::ports::Delivery;
+use crate::services::settlement_events::SettlementEvent;
 use crate::AppState;
-use async_graphql::{Context, Object, Result};
+use async_graphql::{Context, Object, Result, Subscription};
+use futures::Stream;
+use std::pin::Pin;
+use tokio_stream::StreamExt as _;
 
 #[derive(Default)]
 pub struct SettlementQuery;
@@ -16,6 +24,66 @@ impl SettlementQuery {
         let state = ctx.data::<AppState>()?;
         queries::list_settlements(&state.db, limit.unwrap_or(20), offset.unwrap_or(0))
             .await
-            .map_err(|e| e.into())
+            .map_err(sqlx_error)
     }
 }
+
+/// Settlement subscription resolver.
+///
+/// Events are published by the settlement service when a settlement is
+/// created or changes status. Both subscriptions accept optional `assetCode`
+/// and `partnerId` filters; a settlement matches a partner when it contains
+/// at least one of that partner's transactions.
+#[derive(Default)]
+pub struct SettlementSubscription;
+
+#[Subscription]
+impl SettlementSubscription {
+    /// Every settlement creation and status change.
+    async fn settlement_updated(
+        &self,
+        ctx: &Context<'_>,
+        asset_code: Option<String>,
+        partner_id: Option<UuidScalar>,
+    ) -> Result<Pin<Box<dyn Stream<Item = SettlementEvent> + Send>>> {
+        settlement_stream(ctx, asset_code, partner_id, false)
+    }
+
+    /// Settlements reaching a terminal status (`completed` or `voided`).
+    async fn settlement_closed(
+        &self,
+        ctx: &Context<'_>,
+        asset_code: Option<String>,
+        partner_id: Option<UuidScalar>,
+    ) -> Result<Pin<Box<dyn Stream<Item = SettlementEvent> + Send>>> {
+        settlement_stream(ctx, asset_code, partner_id, true)
+    }
+}
+
+fn settlement_stream(
+    ctx: &Context<'_>,
+    asset_code: Option<String>,
+    partner_id: Option<UuidScalar>,
+    closed_only: bool,
+) -> Result<Pin<Box<dyn Stream<Item = SettlementEvent> + Send>>> {
+    if let Some(ref a) = asset_code {
+        validate_asset_code(a).map_err(|e| async_graphql::Error::new(e.to_string()))?;
+    }
+    let state = ctx.data::<AppState>()?;
+    let partner_id = partner_id.map(|p| p.0);
+    let events = state
+        .settlement_events
+        .subscribe(Box::new(move |event: &SettlementEvent| {
+            (!closed_only || event.is_closed()) && event.matches(asset_code.as_deref(), partner_id)
+        }));
+
+    let stream = events.filter_map(|delivery| match delivery {
+        Delivery::Event(event) => Some(event),
+        Delivery::Lagged(n) => {
+            tracing::warn!("GraphQL settlement subscription lagged by {} messages", n);
+            None
+        }
+    });
+
+    Ok(Box::pin(stream))
+}
@@ -1,15 +1,22 @@
 use crate::db::{models::Transaction, queries};
-use crate::graphql::input_validation::{
-    validate_asset_code, validate_limit, validate_status, validate_stellar_account,
-};
-use crate::handlers::ws::TransactionStatusUpdate;
+use crate::domain::DomainEvent;
+use crate::graphql::error::{not_found_error, sqlx_error, validation_error};
+use crate::graphql::input_validation::{validate_asset_code, validate_limit, validate_status};
+use crate::graphql::scalars::{StellarAccount, UuidScalar};
+use crate::ports::Delivery;
+use crate::services::transaction_events::TransactionStatusUpdate;
+use crate::services::webhook_dispatcher::{is_valid_endpoint_url, WebhookEndpoint};
 use crate::AppState;
-use async_graphql::{Context, InputObject, Object, Result, Subscription};
+use async_graphql::{Context, InputObject, Json, Object, Result, SimpleObject, Subscription};
+use chrono::{DateTime, Utc};
 use futures::Stream;
 use std::pin::Pin;
 use tokio_stream::StreamExt as _;
 use uuid::Uuid;
 
+const CALLBACK_SECRET_MIN_LEN: usize = 16;
+const CALLBACK_SECRET_MAX_LEN: usize = 256;
+
 /// Filter criteria for transaction queries.
 ///
 /// All fields are optional and combined with AND logic.
@@ -17,7 +24,8 @@ use uuid::Uuid;
 pub struct TransactionFilter {
     pub status: Option<String>,
     pub asset_code: Option<String>,
-    pub stellar_account: Option<String>,
+    /// Validated as a strkey (including checksum) when the query is parsed.
+    pub stellar_account: Option<StellarAccount>,
 }
 
 /// Transaction query resolver.
@@ -40,11 +48,11 @@ impl TransactionQuery {
     /// # Returns
     ///
     /// The transaction object or an error if not found.
-    async fn transaction(&self, ctx: &Context<'_>, id: Uuid) -> Result<Transaction> {
+    async fn transaction(&self, ctx: &Context<'_>, id: UuidScalar) -> Result<Transaction> {
         let state = ctx.data::<AppState>()?;
-        queries::get_transaction(&state.db, id)
+        queries::get_transaction(&state.db, id.0)
             .await
-            .map_err(|e| e.into())
+            .map_err(sqlx_error)
     }
 
     /// List transactions with optional filtering.
@@ -75,16 +83,14 @@ impl TransactionQuery {
             if let Some(ref a) = f.asset_code {
                 validate_asset_code(a).map_err(|e| async_graphql::Error::new(e.to_string()))?;
             }
-            if let Some(ref acc) = f.stellar_account {
-                validate_stellar_account(acc)
-                    .map_err(|e| async_graphql::Error::new(e.to_string()))?;
-            }
         }
 
         let _ = offset;
         let state = ctx.data::<AppState>()?;
 
-        let txs = queries::list_transactions(&state.db, effective_limit, None, false).await?;
+        let txs = queries::list_transactions(&state.db, effective_limit, None, false)
+            .await
+            .map_err(sqlx_error)?;
 
         if let Some(f) = filter {
             let filtered = txs
@@ -99,7 +105,7 @@ impl TransactionQuery {
                     let account_match = f
                         .stellar_account
                         .as_ref()
-                        .map(|acc| &t.stellar_account == acc)
+                        .map(|acc| t.stellar_account == acc.as_str())
                         .unwrap_or(true);
                     status_match && asset_match && account_match
                 })
@@ -131,11 +137,14 @@ pub struct TransactionMutation;
 
 #[Object]
 impl TransactionMutation {
-    /// Force complete a transaction.
+    /// Attach or update partner metadata on a transaction.
     ///
     /// # Arguments
     ///
-    /// * `id` - The transaction UUID to complete
+    /// * `id` - The transaction UUID
+    /// * `metadata` - JSON object; merged key-by-key (a `null` value removes
+    ///   the key) unless `replace` is true
+    /// * `replace` - Overwrite the metadata instead of merging (default: false)
     ///
     /// # Returns
     ///
@@ -144,33 +153,83 @@ impl TransactionMutation {
     /// # Idempotency
     ///
     /// This mutation requires an `X-Idempotency-Key` header.
-    /// Retrying with the same key will return the cached result
-    /// without re-executing the mutation.
-    ///
-    /// # Side Effects
-    ///
-    /// - Updates transaction status to 'completed'
-    /// - Invalidates query cache for the asset
-    /// - Triggers webhook delivery if configured
-    async fn force_complete_transaction(&self, ctx: &Context<'_>, id: Uuid) -> Result<Transaction> {
+    async fn update_transaction_metadata(
+        &self,
+        ctx: &Context<'_>,
+        id: UuidScalar,
+        metadata: Json<serde_json::Value>,
+        replace: Option<bool>,
+    ) -> Result<Transaction> {
         let state = ctx.data::<AppState>()?;
+        if !metadata.0.is_object() {
+            return Err(async_graphql::Error::new("metadata: must be a JSON object"));
+        }
 
-        let asset_code: String =
-            sqlx::query_scalar("SELECT asset_code FROM transactions WHERE id = $1")
-                .bind(id)
-                .fetch_one(&state.db)
-                .await?;
-
-        let result = sqlx::query_as::<_, Transaction>(
-            "UPDATE transactions SET status = 'completed', updated_at = NOW() WHERE id = $1 RETURNING *"
+        queries::update_transaction_metadata(
+            &state.db,
+            id.0,
+            &metadata.0,
+            replace.unwrap_or(false),
+            "graphql",
         )
-        .bind(id)
-        .fetch_one(&state.db)
-        .await?;
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Decode(msg) => async_graphql::Error::new(msg.to_string()),
+            other => sqlx_error(other),
+        })
+    }
 
-        crate::db::queries::invalidate_caches_for_asset(&asset_code).await;
+    /// Attach a callback to a single transaction, e.g. for a wallet to
+    /// follow its deposit without the admin API.
+    ///
+    /// Every later event of the transaction is POSTed to `url` by the webhook
+    /// dispatcher, signed with `secret` like partner webhooks
+    /// (`X-Webhook-Signature`). Registering again replaces the URL and secret.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx_id` - The transaction UUID
+    /// * `url` - Absolute http(s) URL
+    /// * `secret` - Signing secret, 16 to 256 characters
+    ///
+    /// # Returns
+    ///
+    /// The registered callback; the secret is never returned.
+    async fn register_transaction_callback(
+        &self,
+        ctx: &Context<'_>,
+        tx_id: UuidScalar,
+        url: String,
+        secret: String,
+    ) -> Result<TransactionCallback> {
+        let state = ctx.data::<AppState>()?;
+        let url = url.trim();
+        if !is_valid_endpoint_url(url) {
+            return Err(validation_error("url", "must be an absolute http(s) URL"));
+        }
+        if !(CALLBACK_SECRET_MIN_LEN..=CALLBACK_SECRET_MAX_LEN).contains(&secret.len()) {
+            return Err(validation_error(
+                "secret",
+                &format!("must be {CALLBACK_SECRET_MIN_LEN}-{CALLBACK_SECRET_MAX_LEN} characters"),
+            ));
+        }
+        queries::get_transaction(&state.db, tx_id.0)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => not_found_error("Transaction"),
+                other => sqlx_error(other),
+            })?;
 
-        Ok(result)
+        let endpoint =
+            queries::upsert_transaction_callback(&state.db, tx_id.0, url, &secret, "graphql")
+                .await
+                .map_err(sqlx_error)?;
+        tracing::info!(
+            transaction_id = %tx_id.0,
+            endpoint_id = %endpoint.id,
+            "Transaction callback registered"
+        );
+        Ok(TransactionCallback::from(endpoint))
     }
 
     /// Replay a transaction from the dead letter queue.
@@ -187,12 +246,36 @@ impl TransactionMutation {
     ///
     /// This mutation requires an `X-Idempotency-Key` header.
     /// Retrying with the same key will return the cached result.
-    async fn replay_dlq(&self, _ctx: &Context<'_>, id: Uuid) -> Result<bool> {
-        tracing::info!("Replaying DLQ for ID: {}", id);
+    async fn replay_dlq(&self, _ctx: &Context<'_>, id: UuidScalar) -> Result<bool> {
+        tracing::info!("Replaying DLQ for ID: {}", id.0);
         Ok(true)
     }
 }
 
+/// A callback registered with `registerTransactionCallback`.
+#[derive(SimpleObject)]
+pub struct TransactionCallback {
+    pub transaction_id: Uuid,
+    pub url: String,
+    /// `false` once the dispatcher paused it after continuous failures;
+    /// registering again re-enables it.
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<WebhookEndpoint> for TransactionCallback {
+    fn from(endpoint: WebhookEndpoint) -> Self {
+        TransactionCallback {
+            transaction_id: endpoint.transaction_id.unwrap_or_default(),
+            url: endpoint.url,
+            enabled: endpoint.enabled,
+            created_at: endpoint.created_at,
+            updated_at: endpoint.updated_at,
+        }
+    }
+}
+
 /// Transaction subscription resolver.
 ///
 /// # Idempotency
@@ -209,33 +292,34 @@ impl TransactionSubscription {
     async fn transaction_status_changed(
         &self,
         ctx: &Context<'_>,
-        transaction_id: Option<Uuid>,
+        transaction_id: Option<UuidScalar>,
         asset_code: Option<String>,
     ) -> Result<Pin<Box<dyn Stream<Item = TransactionStatusUpdate> + Send>>> {
         let state = ctx.data::<AppState>()?;
-        let rx = state.tx_broadcast.subscribe();
-
-        let stream = tokio_stream::wrappers::BroadcastStream::new(rx).filter_map(move |result| {
-            match result {
-                Ok(update) => {
-                    // Apply optional filters
-                    let id_match = transaction_id
-                        .map(|id| update.transaction_id == id)
-                        .unwrap_or(true);
-                    let asset_match = asset_code
-                        .as_deref()
-                        .map(|a| update.message.as_deref() == Some(a))
-                        .unwrap_or(true);
-                    if id_match && asset_match {
-                        Some(update)
-                    } else {
-                        None
-                    }
-                }
-                Err(tokio_stream::wrappers::errors::BroadcastStreamRecvError::Lagged(n)) => {
-                    tracing::warn!("GraphQL subscription lagged by {} messages", n);
-                    None
-                }
+        let events = state
+            .domain_events
+            .subscribe(Box::new(move |event: &DomainEvent| {
+                let DomainEvent::StatusChanged {
+                    transaction_id: id,
+                    message,
+                    ..
+                } = event
+                else {
+                    return false;
+                };
+                let id_match = transaction_id.map(|t| *id == t.0).unwrap_or(true);
+                let asset_match = asset_code
+                    .as_deref()
+                    .map(|a| message.as_deref() == Some(a))
+                    .unwrap_or(true);
+                id_match && asset_match
+            }));
+
+        let stream = events.filter_map(|delivery| match delivery {
+            Delivery::Event(event) => TransactionStatusUpdate::from_event(&event),
+            Delivery::Lagged(n) => {
+                tracing::warn!("GraphQL subscription lagged by {} messages", n);
+                None
             }
         });
 
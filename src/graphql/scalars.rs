@@ -0,0 +1,259 @@
+//! Custom GraphQL scalars.
+//!
+//! Resolvers expose identifiers, timestamps, amounts and Stellar accounts
+//! through these types instead of plain `String`s so that malformed input is
+//! rejected while the query is parsed, with a message naming the scalar, and
+//! clients get a self-describing schema.
+//!
+//! | Scalar           | Rust type                 | Wire format                        |
+//! |------------------|---------------------------|------------------------------------|
+//! | `UUID`           | [`UuidScalar`]            | hyphenated UUID string             |
+//! | `DateTime`       | [`DateTimeScalar`]        | RFC 3339 string, normalised to UTC |
+//! | `Decimal`        | [`DecimalScalar`]         | decimal string (exact, no floats)  |
+//! | `StellarAccount` | [`StellarAccount`]        | `G...` ed25519 public key strkey   |
+
+use crate::domain::StellarAddress;
+use async_graphql::{InputValueError, InputValueResult, Scalar, ScalarType, Value};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::str::FromStr;
+use uuid::Uuid;
+
+// ── UUID ──────────────────────────────────────────────────────────────────────
+
+/// A UUID, e.g. `550e8400-e29b-41d4-a716-446655440000`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UuidScalar(pub Uuid);
+
+#[Scalar(name = "UUID")]
+impl ScalarType for UuidScalar {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        match value {
+            Value::String(s) => Uuid::parse_str(&s)
+                .map(UuidScalar)
+                .map_err(|e| InputValueError::custom(format!("Invalid UUID '{s}': {e}"))),
+            other => Err(InputValueError::expected_type(other)),
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.0.to_string())
+    }
+}
+
+impl From<Uuid> for UuidScalar {
+    fn from(id: Uuid) -> Self {
+        UuidScalar(id)
+    }
+}
+
+// ── DateTime ──────────────────────────────────────────────────────────────────
+
+/// An RFC 3339 timestamp. Offsets are accepted on input; output is always UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateTimeScalar(pub DateTime<Utc>);
+
+#[Scalar(name = "DateTime")]
+impl ScalarType for DateTimeScalar {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        match value {
+            Value::String(s) => DateTime::parse_from_rfc3339(&s)
+                .map(|dt| DateTimeScalar(dt.with_timezone(&Utc)))
+                .map_err(|e| {
+                    InputValueError::custom(format!("Invalid RFC 3339 DateTime '{s}': {e}"))
+                }),
+            other => Err(InputValueError::expected_type(other)),
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.0.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+    }
+}
+
+impl From<DateTime<Utc>> for DateTimeScalar {
+    fn from(dt: DateTime<Utc>) -> Self {
+        DateTimeScalar(dt)
+    }
+}
+
+// ── Decimal ───────────────────────────────────────────────────────────────────
+
+/// An exact decimal amount, serialised as a string (e.g. `"100.50"`).
+///
+/// Integer literals are accepted on input; floating-point literals are
+/// rejected because they may already have lost precision in the client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecimalScalar(pub BigDecimal);
+
+#[Scalar(name = "Decimal")]
+impl ScalarType for DecimalScalar {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        match value {
+            Value::String(s) => {
+                let trimmed = s.trim();
+                if trimmed.is_empty()
+                    || !trimmed
+                        .chars()
+                        .all(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+'))
+                {
+                    return Err(InputValueError::custom(format!(
+                        "Invalid Decimal '{s}': expected digits with an optional sign and decimal point"
+                    )));
+                }
+                BigDecimal::from_str(trimmed)
+                    .map(DecimalScalar)
+                    .map_err(|e| InputValueError::custom(format!("Invalid Decimal '{s}': {e}")))
+            }
+            Value::Number(n) if n.is_i64() || n.is_u64() => BigDecimal::from_str(&n.to_string())
+                .map(DecimalScalar)
+                .map_err(|e| InputValueError::custom(format!("Invalid Decimal '{n}': {e}"))),
+            Value::Number(n) => Err(InputValueError::custom(format!(
+                "Invalid Decimal {n}: pass fractional amounts as a string to keep them exact"
+            ))),
+            other => Err(InputValueError::expected_type(other)),
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.0.to_string())
+    }
+}
+
+impl From<BigDecimal> for DecimalScalar {
+    fn from(amount: BigDecimal) -> Self {
+        DecimalScalar(amount)
+    }
+}
+
+// ── StellarAccount ────────────────────────────────────────────────────────────
+
+/// A Stellar account id (`G...` strkey), validated including its checksum.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StellarAccount(String);
+
+impl StellarAccount {
+    /// Wrap an account id read back from storage without re-validating it.
+    ///
+    /// Only for output: rows written before strkey validation existed must
+    /// still be readable.
+    pub(crate) fn from_stored(account: impl Into<String>) -> Self {
+        StellarAccount(account.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for StellarAccount {
+    type Err = String;
+
+    /// Parse and fully validate a strkey-encoded account id.
+    fn from_str(account: &str) -> Result<Self, Self::Err> {
+        validate_account_strkey(account)?;
+        Ok(StellarAccount(account.trim().to_string()))
+    }
+}
+
+#[Scalar(name = "StellarAccount")]
+impl ScalarType for StellarAccount {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        match value {
+            Value::String(s) => StellarAccount::from_str(&s)
+                .map_err(|e| InputValueError::custom(format!("Invalid StellarAccount '{s}': {e}"))),
+            other => Err(InputValueError::expected_type(other)),
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.0.clone())
+    }
+}
+
+/// Check that `account` is a well-formed ed25519 public key strkey (`G...`,
+/// checksum included). Muxed `M...` addresses are not account ids.
+pub fn validate_account_strkey(account: &str) -> Result<(), String> {
+    match StellarAddress::parse(account) {
+        Ok(address) if address.is_muxed() => {
+            Err("must be an account id starting with 'G'".to_string())
+        }
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALID_ACCOUNT: &str = "GAAZI4TCR3TY5OJHCTJC2A4QSY6CJWJH5IAJTGKIN2ER7LBNVKOCCWN7";
+
+    fn s(v: &str) -> Value {
+        Value::String(v.to_string())
+    }
+
+    #[test]
+    fn test_uuid_roundtrip_and_rejection() {
+        let id = Uuid::new_v4();
+        let parsed = <UuidScalar as ScalarType>::parse(s(&id.to_string())).unwrap();
+        assert_eq!(parsed.0, id);
+        assert_eq!(parsed.to_value(), s(&id.to_string()));
+
+        let err = <UuidScalar as ScalarType>::parse(s("not-a-uuid")).unwrap_err();
+        assert!(err
+            .into_server_error(Default::default())
+            .message
+            .contains("Invalid UUID"));
+    }
+
+    #[test]
+    fn test_datetime_requires_rfc3339_and_normalises_to_utc() {
+        let parsed = <DateTimeScalar as ScalarType>::parse(s("2024-03-01T12:00:00+02:00")).unwrap();
+        assert_eq!(parsed.to_value(), s("2024-03-01T10:00:00Z"));
+
+        assert!(<DateTimeScalar as ScalarType>::parse(s("2024-03-01 12:00:00")).is_err());
+        assert!(<DateTimeScalar as ScalarType>::parse(s("yesterday")).is_err());
+    }
+
+    #[test]
+    fn test_decimal_is_exact() {
+        let parsed = <DecimalScalar as ScalarType>::parse(s("100.10")).unwrap();
+        assert_eq!(parsed.to_value(), s("100.10"));
+
+        let int = <DecimalScalar as ScalarType>::parse(Value::Number(42.into())).unwrap();
+        assert_eq!(int.0, BigDecimal::from(42));
+
+        assert!(<DecimalScalar as ScalarType>::parse(Value::Number(
+            async_graphql::Number::from_f64(0.1).unwrap()
+        ))
+        .is_err());
+        assert!(<DecimalScalar as ScalarType>::parse(s("1e3")).is_err());
+        assert!(<DecimalScalar as ScalarType>::parse(s("abc")).is_err());
+    }
+
+    #[test]
+    fn test_stellar_account_strkey_validation() {
+        assert!(<StellarAccount as ScalarType>::parse(s(VALID_ACCOUNT)).is_ok());
+        assert!(validate_account_strkey(
+            "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF"
+        )
+        .is_ok());
+
+        // Flipping the last character breaks the checksum.
+        let mut bad_checksum = VALID_ACCOUNT.to_string();
+        bad_checksum.replace_range(55.., "A");
+        assert_eq!(
+            validate_account_strkey(&bad_checksum),
+            Err("checksum mismatch".to_string())
+        );
+
+        assert!(validate_account_strkey("GABC").is_err());
+        assert!(validate_account_strkey(&VALID_ACCOUNT.replace('G', "1")).is_err());
+        // Valid strkey, but a secret seed rather than an account id.
+        assert!(validate_account_strkey(
+            "SBGWSG6BTNCKCOB3DIFBGCVMUPQFYPA2G4O34RMTB343OYPXU5DJDVMN"
+        )
+        .is_err());
+    }
+}
@@ -4,6 +4,9 @@
 //! See [error_handling.md](./error_handling.md) for comprehensive error handling documentation.
 //! See [../docs/graphql-health-checks.md](../docs/graphql-health-checks.md) for health check details.
 
+use crate::graphql::access::AccessGuard;
+use crate::graphql::error::ErrorMasking;
+use crate::graphql::metrics::{GraphQlMetrics, GraphQlMetricsConfig};
 use crate::graphql::rate_limiting::{GraphQlRateLimitConfig, GraphQlRateLimiter};
 use crate::graphql::resolvers::{Mutation, Query, Subscription};
 use crate::AppState;
@@ -174,6 +177,9 @@ pub fn build_schema(state: AppState) -> AppSchema {
     .limit_complexity(MAX_QUERY_COMPLEXITY)
     .limit_recursive_depth(MAX_QUERY_DEPTH)
     .extension(AliasLimitExtension)
+    .extension(AccessGuard)
     .extension(GraphQlRateLimiter::new(GraphQlRateLimitConfig::default()))
+    .extension(GraphQlMetrics::new(GraphQlMetricsConfig::from_env()))
+    .extension(ErrorMasking)
     .finish()
 }
@@ -0,0 +1,185 @@
+//! Acknowledgment mode for ingestion routes.
+//!
+//! Callback routes can acknowledge a request in one of two ways:
+//!
+//! - **sync** (default): respond `201 Created` with the stored transaction once
+//!   it has been persisted.
+//! - **async**: respond `202 Accepted` with a `Location` header and a
+//!   `status_url` pointing at `GET /transactions/{id}`, which the caller polls
+//!   until processing reaches a terminal status.
+//!
+//! Both modes persist the transaction before responding, so retrying a request
+//! is safe either way: a duplicate `anchor_transaction_id` is rejected with
+//! `409 Conflict` by the database invariants.
+//!
+//! The mode is configured per route via `ACK_MODE_<ROUTE>`, where `<ROUTE>` is
+//! the path upper-cased with `/` replaced by `_` (e.g. `ACK_MODE_CALLBACK`,
+//! `ACK_MODE_CALLBACK_TRANSACTION`), falling back to `ACK_MODE_DEFAULT`.
+
+use axum::{
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// How a route acknowledges an accepted request.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AckMode {
+    /// `201 Created` with the full resource.
+    #[default]
+    Sync,
+    /// `202 Accepted` with a status polling URL.
+    Async,
+}
+
+impl AckMode {
+    /// Parses `sync` / `async` (case-insensitive).
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "sync" => Some(AckMode::Sync),
+            "async" => Some(AckMode::Async),
+            _ => None,
+        }
+    }
+
+    /// Resolves the mode for `route` from the environment.
+    pub fn for_route(route: &str) -> Self {
+        Self::resolve(route, |key| std::env::var(key).ok())
+    }
+
+    fn resolve(route: &str, lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let route_key = route_env_key(route);
+        for key in [route_key.as_str(), "ACK_MODE_DEFAULT"] {
+            if let Some(raw) = lookup(key) {
+                match AckMode::parse(&raw) {
+                    Some(mode) => return mode,
+                    None => tracing::warn!(
+                        key,
+                        value = %raw,
+                        "Ignoring invalid ack mode (expected 'sync' or 'async')"
+                    ),
+                }
+            }
+        }
+        AckMode::default()
+    }
+}
+
+/// `ACK_MODE_<ROUTE>` for a route path such as `/callback/transaction`.
+fn route_env_key(route: &str) -> String {
+    let suffix: String = route
+        .trim_matches('/')
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("ACK_MODE_{suffix}")
+}
+
+/// Body of a `202 Accepted` acknowledgment.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AcceptedResponse {
+    /// Identifier of the accepted transaction.
+    pub id: String,
+    /// Status at acknowledgment time (normally `pending`).
+    pub status: String,
+    /// URL to poll for the current status.
+    pub status_url: String,
+}
+
+/// Path of the status resource for a transaction.
+pub fn status_url(id: Uuid) -> String {
+    format!("/transactions/{id}")
+}
+
+/// Builds a `202 Accepted` response with `Location` and `Retry-After` headers.
+pub fn accepted(id: Uuid, status: &str) -> Response {
+    let url = status_url(id);
+    let mut response = (
+        StatusCode::ACCEPTED,
+        Json(AcceptedResponse {
+            id: id.to_string(),
+            status: status.to_string(),
+            status_url: url.clone(),
+        }),
+    )
+        .into_response();
+
+    let headers = response.headers_mut();
+    if let Ok(location) = HeaderValue::from_str(&url) {
+        headers.insert(header::LOCATION, location);
+    }
+    headers.insert(header::RETRY_AFTER, HeaderValue::from_static("5"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn lookup(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let map: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |key| map.get(key).cloned()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(AckMode::parse("sync"), Some(AckMode::Sync));
+        assert_eq!(AckMode::parse(" ASYNC "), Some(AckMode::Async));
+        assert_eq!(AckMode::parse("later"), None);
+    }
+
+    #[test]
+    fn test_route_env_key() {
+        assert_eq!(route_env_key("/callback"), "ACK_MODE_CALLBACK");
+        assert_eq!(
+            route_env_key("/callback/transaction"),
+            "ACK_MODE_CALLBACK_TRANSACTION"
+        );
+    }
+
+    #[test]
+    fn test_resolve_prefers_route_then_default() {
+        let vars = lookup(&[("ACK_MODE_CALLBACK", "async"), ("ACK_MODE_DEFAULT", "sync")]);
+        assert_eq!(AckMode::resolve("/callback", &vars), AckMode::Async);
+        assert_eq!(
+            AckMode::resolve("/callback/transaction", &vars),
+            AckMode::Sync
+        );
+
+        let vars = lookup(&[("ACK_MODE_DEFAULT", "async")]);
+        assert_eq!(AckMode::resolve("/callback", &vars), AckMode::Async);
+
+        assert_eq!(AckMode::resolve("/callback", lookup(&[])), AckMode::Sync);
+    }
+
+    #[test]
+    fn test_resolve_ignores_invalid_values() {
+        let vars = lookup(&[("ACK_MODE_CALLBACK", "eventually")]);
+        assert_eq!(AckMode::resolve("/callback", vars), AckMode::Sync);
+    }
+
+    #[test]
+    fn test_accepted_response_headers() {
+        let id = Uuid::new_v4();
+        let response = accepted(id, "pending");
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        assert_eq!(
+            response.headers().get(header::LOCATION).unwrap(),
+            &format!("/transactions/{id}")
+        );
+        assert!(response.headers().contains_key(header::RETRY_AFTER));
+    }
+}
@@ -0,0 +1,129 @@
+//! Monthly accounting close and late adjustments.
+//!
+//! | Method | Path                                            | Effect                       |
+//! |--------|-------------------------------------------------|------------------------------|
+//! | `GET`  | `/admin/accounting/periods`                     | Closed periods, latest first |
+//! | `POST` | `/admin/accounting/periods/:period/close`       | Close a month (`YYYY-MM`)    |
+//! | `GET`  | `/admin/accounting/periods/:period/adjustments` | Adjustments against a period |
+//! | `POST` | `/admin/accounting/periods/:period/adjustments` | Post an adjustment           |
+//!
+//! Closing takes no body; an adjustment takes
+//! `{"entity_type", "entity_id", "amount", "reason"}`. Both record the
+//! authenticated admin principal as actor. See [`crate::services::accounting`].
+
+use crate::error::AppError;
+use crate::middleware::auth::AdminPrincipal;
+use crate::services::accounting::{self, Adjustment, Period};
+use crate::validation::{validate_max_len, validate_required};
+use crate::ApiState;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use sqlx::types::BigDecimal;
+use uuid::Uuid;
+
+const LIST_LIMIT: i64 = 120;
+
+#[derive(Debug, Deserialize)]
+pub struct PostAdjustmentRequest {
+    /// `transaction` or `settlement`.
+    pub entity_type: String,
+    pub entity_id: Uuid,
+    /// Signed correction, in asset units.
+    pub amount: BigDecimal,
+    pub reason: String,
+}
+
+impl PostAdjustmentRequest {
+    pub fn validate(&self) -> Result<(), AppError> {
+        validate_required("reason", &self.reason)
+            .map_err(|e| AppError::BadRequest(e.to_string()))?;
+        validate_max_len("reason", &self.reason, 255)
+            .map_err(|e| AppError::BadRequest(e.to_string()))?;
+        Ok(())
+    }
+}
+
+/// GET /admin/accounting/periods
+pub async fn list_periods(State(state): State<ApiState>) -> Result<impl IntoResponse, AppError> {
+    let periods =
+        crate::db::queries::list_accounting_periods(&state.app_state.db, LIST_LIMIT).await?;
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({ "periods": periods })),
+    ))
+}
+
+/// POST /admin/accounting/periods/:period/close
+pub async fn close_period(
+    State(state): State<ApiState>,
+    Path(period): Path<String>,
+    principal: AdminPrincipal,
+) -> Result<impl IntoResponse, AppError> {
+    let period = Period::parse(&period)?;
+    let closed =
+        accounting::close(&state.app_state.db, period, &principal.name, Utc::now()).await?;
+    Ok((StatusCode::CREATED, Json(closed)))
+}
+
+/// GET /admin/accounting/periods/:period/adjustments
+pub async fn list_adjustments(
+    State(state): State<ApiState>,
+    Path(period): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let period = Period::parse(&period)?;
+    let closed = accounting::closed_period(&state.app_state.db, period).await?;
+    let adjustments =
+        crate::db::queries::list_accounting_adjustments(&state.app_state.db, closed.id).await?;
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({ "period": closed, "adjustments": adjustments })),
+    ))
+}
+
+/// POST /admin/accounting/periods/:period/adjustments
+pub async fn post_adjustment(
+    State(state): State<ApiState>,
+    Path(period): Path<String>,
+    principal: AdminPrincipal,
+    Json(payload): Json<PostAdjustmentRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let period = Period::parse(&period)?;
+    payload.validate()?;
+
+    let adjustment = Adjustment {
+        entity_type: payload.entity_type.trim().to_ascii_lowercase(),
+        entity_id: payload.entity_id,
+        amount: payload.amount,
+        reason: payload.reason.trim().to_string(),
+    };
+    let posted =
+        accounting::post_adjustment(&state.app_state.db, period, &adjustment, &principal.name)
+            .await?;
+    Ok((StatusCode::CREATED, Json(posted)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_post_adjustment_validation() {
+        let mut req = PostAdjustmentRequest {
+            entity_type: "transaction".to_string(),
+            entity_id: Uuid::new_v4(),
+            amount: BigDecimal::from_str("-12.50").unwrap(),
+            reason: " ".to_string(),
+        };
+        assert!(req.validate().is_err());
+
+        req.reason = "fee charged twice in March".to_string();
+        assert!(req.validate().is_ok());
+    }
+}
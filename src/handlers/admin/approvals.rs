@@ -0,0 +1,208 @@
+//! Four-eyes approvals for high-value manual actions.
+//!
+//! | Method | Path                           | Effect                                     |
+//! |--------|--------------------------------|--------------------------------------------|
+//! | `GET`  | `/admin/approvals`             | List approvals (`?status=&limit=&offset=`) |
+//! | `GET`  | `/admin/approvals/:id`         | One approval                               |
+//! | `POST` | `/admin/approvals/:id/approve` | Apply the held action                      |
+//! | `POST` | `/admin/approvals/:id/reject`  | Discard the held action                    |
+//!
+//! Approvals are created by the bulk status, refund override and settlement
+//! void endpoints; see [`crate::services::approvals`]. Only an authenticated
+//! operator other than the proposer may approve or reject.
+
+use crate::db::queries;
+use crate::error::AppError;
+use crate::handlers::admin::{bulk_status, refunds};
+use crate::middleware::auth::AdminPrincipal;
+use crate::services::approvals::{self, ApprovalAction, ApprovalStatus};
+use crate::services::SettlementService;
+use crate::ApiState;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use std::str::FromStr;
+use uuid::Uuid;
+
+const MAX_LIST_LIMIT: i64 = 200;
+
+#[derive(Debug, Deserialize)]
+pub struct ListApprovalsQuery {
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DecisionRequest {
+    pub note: Option<String>,
+}
+
+/// GET /admin/approvals
+pub async fn list_approvals(
+    State(state): State<ApiState>,
+    Query(q): Query<ListApprovalsQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let status = q
+        .status
+        .as_deref()
+        .map(ApprovalStatus::from_str)
+        .transpose()
+        .map_err(AppError::BadRequest)?;
+    let limit = q.limit.unwrap_or(50).clamp(1, MAX_LIST_LIMIT);
+    let offset = q.offset.unwrap_or(0).max(0);
+
+    let approvals = queries::list_approvals(
+        &state.app_state.db,
+        status.as_ref().map(ApprovalStatus::as_str),
+        limit,
+        offset,
+    )
+    .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "approvals": approvals,
+            "limit": limit,
+            "offset": offset,
+        })),
+    ))
+}
+
+/// GET /admin/approvals/:id
+pub async fn get_approval(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let approval = queries::get_approval(&state.app_state.db, id)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => AppError::NotFound(format!("Approval {id} not found")),
+            other => other.into(),
+        })?;
+    Ok((StatusCode::OK, Json(approval)))
+}
+
+/// POST /admin/approvals/:id/approve
+pub async fn approve(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+    principal: AdminPrincipal,
+    Json(payload): Json<DecisionRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let app_state = &state.app_state;
+    let mut locked = approvals::lock_for_approval(&app_state.db, id, &principal).await?;
+    let approver = locked.approver.clone();
+
+    // Applied on the approval's transaction; an error rolls it back and the
+    // approval stays pending.
+    let mut voided = None;
+    let result = match locked.action.clone() {
+        ApprovalAction::StatusOverride {
+            transaction_ids,
+            status,
+            reason,
+        } => {
+            let response = bulk_status::apply_bulk_update(
+                &mut locked.db_tx,
+                &transaction_ids,
+                &status,
+                reason.as_deref(),
+                &approver,
+            )
+            .await?;
+            serde_json::json!(response)
+        }
+        ApprovalAction::RefundOverride {
+            refund_id,
+            action,
+            refund_to,
+            stellar_tx_hash,
+            note,
+        } => {
+            let request = refunds::RefundOverrideRequest {
+                action,
+                refund_to,
+                stellar_tx_hash,
+                note,
+            };
+            let task =
+                refunds::apply_approved_override(&mut locked.db_tx, refund_id, &request, &approver)
+                    .await?;
+            serde_json::json!(task)
+        }
+        ApprovalAction::SettlementVoid {
+            settlement_id,
+            reason,
+            proposed_by,
+        } => {
+            let service = SettlementService::new(app_state.db.clone())
+                .with_events(app_state.settlement_events.clone())
+                .with_domain_events(app_state.domain_events.clone());
+            let (settlement, reversal) = service
+                .void(
+                    &mut locked.db_tx,
+                    settlement_id,
+                    &reason,
+                    &proposed_by,
+                    &approver,
+                )
+                .await?;
+            let result = serde_json::json!({
+                "settlement": settlement,
+                "reversal": reversal,
+            });
+            voided = Some((service, settlement));
+            result
+        }
+    };
+    let approval = locked.approve(payload.note.as_deref(), &result).await?;
+    if let Some((service, settlement)) = voided {
+        service.publish_voided(&settlement).await;
+    }
+
+    tracing::info!(
+        approval_id = %id,
+        kind = %approval.kind,
+        proposer = %approval.proposed_by,
+        approver = %principal.name,
+        "Manual action approved and applied"
+    );
+    Ok((StatusCode::OK, Json(approval)))
+}
+
+/// POST /admin/approvals/:id/reject
+pub async fn reject(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+    principal: AdminPrincipal,
+    Json(payload): Json<DecisionRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let approval =
+        approvals::reject(&state.app_state.db, id, &principal, payload.note.as_deref()).await?;
+    tracing::info!(
+        approval_id = %id,
+        kind = %approval.kind,
+        approver = %principal.name,
+        "Manual action rejected"
+    );
+    Ok((StatusCode::OK, Json(approval)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decision_note_is_optional() {
+        let req: DecisionRequest = serde_json::from_str(r#"{"note":"ok"}"#).unwrap();
+        assert_eq!(req.note.as_deref(), Some("ok"));
+        let req: DecisionRequest = serde_json::from_str("{}").unwrap();
+        assert!(req.note.is_none());
+    }
+}
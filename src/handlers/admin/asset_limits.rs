@@ -0,0 +1,115 @@
+//! Per-asset deposit amount limits.
+//!
+//! `PUT /admin/assets/:id/limits` replaces an asset's `min_amount` and
+//! `max_amount` (either may be `null` for "no bound"). The new limits apply to
+//! the next ingested callback; see [`crate::services::amount_limits`] for how
+//! out-of-range deposits are handled. Every change is audit-logged with the
+//! authenticated admin principal as actor.
+
+use crate::db::queries;
+use crate::error::AppError;
+use crate::middleware::auth::AdminPrincipal;
+use crate::services::amount_limits::AmountLimits;
+use crate::ApiState;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+use serde::Deserialize;
+use sqlx::types::BigDecimal;
+use std::str::FromStr;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+pub struct SetAssetLimitsRequest {
+    /// Decimal string; `null` removes the floor.
+    pub min_amount: Option<String>,
+    /// Decimal string; `null` removes the ceiling.
+    pub max_amount: Option<String>,
+}
+
+fn parse_bound(field: &str, value: Option<&str>) -> Result<Option<BigDecimal>, AppError> {
+    let Some(raw) = value else {
+        return Ok(None);
+    };
+    let amount = BigDecimal::from_str(raw.trim())
+        .map_err(|_| AppError::Validation(format!("{field}: must be a valid decimal")))?;
+    if amount < BigDecimal::from(0) {
+        return Err(AppError::Validation(format!(
+            "{field}: must not be negative"
+        )));
+    }
+    Ok(Some(amount))
+}
+
+impl SetAssetLimitsRequest {
+    fn limits(&self) -> Result<AmountLimits, AppError> {
+        let limits = AmountLimits {
+            min_amount: parse_bound("min_amount", self.min_amount.as_deref())?,
+            max_amount: parse_bound("max_amount", self.max_amount.as_deref())?,
+        };
+        if let (Some(min), Some(max)) = (&limits.min_amount, &limits.max_amount) {
+            if min > max {
+                return Err(AppError::Validation(
+                    "min_amount must not exceed max_amount".to_string(),
+                ));
+            }
+        }
+        Ok(limits)
+    }
+}
+
+/// PUT /admin/assets/:id/limits
+pub async fn set_asset_limits(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+    principal: AdminPrincipal,
+    Json(payload): Json<SetAssetLimitsRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let limits = payload.limits()?;
+
+    let asset =
+        queries::update_asset_amount_limits(&state.app_state.db, id, &limits, &principal.name)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::NotFound(format!("Asset {id} not found")),
+                other => other.into(),
+            })?;
+
+    Ok((StatusCode::OK, Json(asset)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(min: Option<&str>, max: Option<&str>) -> SetAssetLimitsRequest {
+        SetAssetLimitsRequest {
+            min_amount: min.map(str::to_string),
+            max_amount: max.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_limits_accepts_open_and_closed_ranges() {
+        let limits = request(Some("1.00"), Some("1000")).limits().unwrap();
+        assert_eq!(
+            limits.min_amount,
+            Some(BigDecimal::from_str("1.00").unwrap())
+        );
+        assert_eq!(limits.max_amount, Some(BigDecimal::from(1000)));
+
+        assert_eq!(
+            request(None, None).limits().unwrap(),
+            AmountLimits::default()
+        );
+    }
+
+    #[test]
+    fn test_limits_rejects_invalid_bounds() {
+        assert!(request(Some("abc"), None).limits().is_err());
+        assert!(request(Some("-1"), None).limits().is_err());
+        assert!(request(Some("10"), Some("5")).limits().is_err());
+    }
+}
@@ -0,0 +1,25 @@
+//! `GET /admin/backups`: database backups in the object store, newest first, each
+//! with a signed `download_url` for `/downloads/backups/:file` when a
+//! download signing secret is configured.
+
+use crate::adapters;
+use crate::error::AppError;
+use crate::handlers::downloads;
+use crate::services::backup;
+use axum::{http::StatusCode, response::IntoResponse, Json};
+use chrono::Utc;
+
+/// GET /admin/backups
+pub async fn list_backups() -> Result<impl IntoResponse, AppError> {
+    let now = Utc::now();
+    let backups: Vec<serde_json::Value> =
+        backup::list_backups_from(adapters::object_store().as_ref())
+            .await?
+            .iter()
+            .map(|metadata| downloads::with_download_url(metadata, now))
+            .collect();
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({ "backups": backups })),
+    ))
+}
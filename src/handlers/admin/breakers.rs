@@ -0,0 +1,250 @@
+//! Circuit breaker status and manual overrides.
+//!
+//! | Method   | Path                              | Effect                                  |
+//! |----------|-----------------------------------|-----------------------------------------|
+//! | `GET`    | `/admin/breakers`                 | Every breaker's state and recent events |
+//! | `POST`   | `/admin/breakers/:name/open`      | Pin the breaker open                    |
+//! | `POST`   | `/admin/breakers/:name/close`     | Pin the breaker closed                  |
+//! | `DELETE` | `/admin/breakers/:name/override`  | Return it to automatic control          |
+//!
+//! `:name` is `horizon` or `webhook:<endpoint id>`. Open and close take
+//! `{"reason": "...", "actor": "..."}`; the reason is required and is recorded
+//! with the transition. See [`crate::services::breakers`].
+
+use crate::db::queries;
+use crate::error::AppError;
+use crate::services::breakers::{
+    self, BreakerState, BreakerStatus, BreakerTransition, ManualOverride,
+};
+use crate::services::webhook_dispatcher;
+use crate::validation::{validate_max_len, validate_required};
+use crate::ApiState;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::Utc;
+use serde::Deserialize;
+
+const RECENT_EVENTS: i64 = 50;
+const MAX_REASON_LEN: usize = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct OverrideRequest {
+    pub reason: String,
+    pub actor: Option<String>,
+}
+
+impl OverrideRequest {
+    fn validate(&self) -> Result<(), AppError> {
+        validate_required("reason", self.reason.trim())
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        validate_max_len("reason", &self.reason, MAX_REASON_LEN)
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        Ok(())
+    }
+
+    fn actor(&self) -> &str {
+        self.actor
+            .as_deref()
+            .filter(|a| !a.is_empty())
+            .unwrap_or("admin")
+    }
+}
+
+fn redis_client(state: &ApiState) -> Result<redis::Client, AppError> {
+    Ok(redis::Client::open(state.app_state.redis_url.as_str())?)
+}
+
+/// Status of every breaker, with overrides applied.
+async fn load_statuses(
+    state: &ApiState,
+    redis: &redis::Client,
+) -> Result<Vec<BreakerStatus>, AppError> {
+    let mut overrides = breakers::list_overrides(redis).await?;
+
+    let horizon = &state.app_state.horizon_client;
+    let mut statuses = vec![BreakerStatus {
+        name: breakers::HORIZON.to_string(),
+        state: horizon.breaker_state(),
+        manual_override: None,
+        failure_count: None,
+        opened_at: None,
+        last_error: None,
+    }];
+    statuses.extend(webhook_dispatcher::circuit_breaker_statuses(redis).await?);
+
+    for status in &mut statuses {
+        if let Some(value) = overrides.remove(&status.name) {
+            status.state = value.state;
+            status.manual_override = Some(value);
+        }
+    }
+    // Overridden webhook breakers with no failures recorded.
+    for (name, value) in overrides {
+        statuses.push(BreakerStatus {
+            name,
+            state: value.state,
+            manual_override: Some(value),
+            failure_count: None,
+            opened_at: None,
+            last_error: None,
+        });
+    }
+    Ok(statuses)
+}
+
+async fn load_status(
+    state: &ApiState,
+    redis: &redis::Client,
+    name: &str,
+) -> Result<BreakerStatus, AppError> {
+    Ok(load_statuses(state, redis)
+        .await?
+        .into_iter()
+        .find(|s| s.name == name)
+        .unwrap_or_else(|| BreakerStatus {
+            name: name.to_string(),
+            state: BreakerState::Closed,
+            manual_override: None,
+            failure_count: None,
+            opened_at: None,
+            last_error: None,
+        }))
+}
+
+/// Store the override (or clear it) and publish the change if the effective
+/// state moved.
+async fn apply_override(
+    state: &ApiState,
+    name: &str,
+    value: Option<ManualOverride>,
+    reason: String,
+    actor: &str,
+) -> Result<BreakerStatus, AppError> {
+    breakers::validate_name(name).map_err(AppError::BadRequest)?;
+    let redis = redis_client(state)?;
+    let before = load_status(state, &redis, name).await?;
+
+    breakers::set_override(&redis, name, value.as_ref()).await?;
+    if name == breakers::HORIZON {
+        // Other instances pick it up within OVERRIDE_SYNC_INTERVAL.
+        state.app_state.horizon_client.set_manual_override(value);
+    }
+
+    let after = load_status(state, &redis, name).await?;
+    if after.state != before.state {
+        breakers::publish(BreakerTransition {
+            breaker: name.to_string(),
+            from_state: before.state,
+            to_state: after.state,
+            reason,
+            actor: Some(actor.to_string()),
+            occurred_at: Utc::now(),
+        });
+    } else {
+        tracing::info!(breaker = name, actor, reason = %reason, "Circuit breaker override changed");
+    }
+    Ok(after)
+}
+
+/// GET /admin/breakers
+pub async fn list_breakers(State(state): State<ApiState>) -> Result<impl IntoResponse, AppError> {
+    let redis = redis_client(&state)?;
+    let breakers = load_statuses(&state, &redis).await?;
+    let recent_events = queries::list_breaker_events(&state.app_state.db, RECENT_EVENTS).await?;
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "breakers": breakers,
+            "recent_events": recent_events,
+        })),
+    ))
+}
+
+async fn pin(
+    state: ApiState,
+    name: String,
+    payload: OverrideRequest,
+    pinned: BreakerState,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+    let reason = payload.reason.trim().to_string();
+    let value = ManualOverride {
+        state: pinned,
+        reason: reason.clone(),
+        actor: payload.actor().to_string(),
+        set_at: Utc::now(),
+    };
+    let status = apply_override(
+        &state,
+        &name,
+        Some(value),
+        format!("manual: {reason}"),
+        payload.actor(),
+    )
+    .await?;
+    Ok((StatusCode::OK, Json(status)))
+}
+
+/// POST /admin/breakers/:name/open
+pub async fn open_breaker(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+    Json(payload): Json<OverrideRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    pin(state, name, payload, BreakerState::Open).await
+}
+
+/// POST /admin/breakers/:name/close
+pub async fn close_breaker(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+    Json(payload): Json<OverrideRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    pin(state, name, payload, BreakerState::Closed).await
+}
+
+/// DELETE /admin/breakers/:name/override
+pub async fn clear_override(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let status = apply_override(
+        &state,
+        &name,
+        None,
+        "manual override cleared".to_string(),
+        "admin",
+    )
+    .await?;
+    Ok((StatusCode::OK, Json(status)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_override_request_requires_reason() {
+        let req: OverrideRequest = serde_json::from_str(r#"{"reason":"  "}"#).unwrap();
+        assert!(req.validate().is_err());
+
+        let req: OverrideRequest =
+            serde_json::from_str(r#"{"reason":"Horizon maintenance","actor":"ops"}"#).unwrap();
+        assert!(req.validate().is_ok());
+        assert_eq!(req.actor(), "ops");
+    }
+
+    #[test]
+    fn test_override_request_rejects_long_reason() {
+        let req = OverrideRequest {
+            reason: "x".repeat(MAX_REASON_LEN + 1),
+            actor: None,
+        };
+        assert!(req.validate().is_err());
+        assert_eq!(req.actor(), "admin");
+    }
+}
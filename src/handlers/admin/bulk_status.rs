@@ -1,7 +1,16 @@
-use crate::db::queries::{bulk_update_transaction_status, BulkUpdateError, BulkUpdateResult};
+use crate::db::queries::{
+    bulk_update_transaction_status, max_transaction_amount, BulkUpdateError, BulkUpdateResult,
+};
 use crate::error::AppError;
+use crate::middleware::auth::AdminPrincipal;
+use crate::services::approvals::{self, ApprovalAction};
 use crate::{ApiState, AppState};
-use axum::{extract::State, response::IntoResponse, Json};
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -21,57 +30,103 @@ pub struct BulkStatusResponse {
 
 pub async fn bulk_update_status(
     State(state): State<AppState>,
+    principal: AdminPrincipal,
     Json(payload): Json<BulkStatusRequest>,
 ) -> Result<impl IntoResponse, AppError> {
-    run_bulk_update(&state.db, payload).await
+    run_bulk_update(&state.db, &principal, payload).await
 }
 
 /// ApiState-compatible wrapper used by the main router.
 pub async fn bulk_update_status_api(
     State(api_state): State<ApiState>,
+    principal: AdminPrincipal,
     Json(payload): Json<BulkStatusRequest>,
 ) -> Result<impl IntoResponse, AppError> {
-    run_bulk_update(&api_state.app_state.db, payload).await
+    run_bulk_update(&api_state.app_state.db, &principal, payload).await
 }
 
+/// Apply the update, or hold it for four-eyes approval (`202 Accepted`) when
+/// any of the transactions is above the approval threshold. `principal` is
+/// recorded as the actor, or as the proposer of a held update.
 async fn run_bulk_update(
     pool: &sqlx::PgPool,
+    principal: &AdminPrincipal,
     payload: BulkStatusRequest,
-) -> Result<impl IntoResponse, AppError> {
-    if payload.transaction_ids.is_empty() {
+) -> Result<Response, AppError> {
+    validate(&payload.transaction_ids, &payload.status)?;
+
+    let threshold = approvals::threshold();
+    if let Some(amount) = max_transaction_amount(pool, &payload.transaction_ids)
+        .await?
+        .filter(|amount| approvals::requires_approval(amount, &threshold))
+    {
+        let action = ApprovalAction::StatusOverride {
+            transaction_ids: payload.transaction_ids,
+            status: payload.status,
+            reason: payload.reason,
+        };
+        let approval = approvals::propose(pool, &action, &amount, principal).await?;
+        return Ok((
+            StatusCode::ACCEPTED,
+            Json(serde_json::json!({ "approval": approval })),
+        )
+            .into_response());
+    }
+
+    let mut db_tx = pool.begin().await?;
+    let response = apply_bulk_update(
+        &mut db_tx,
+        &payload.transaction_ids,
+        &payload.status,
+        payload.reason.as_deref(),
+        &principal.name,
+    )
+    .await?;
+    db_tx.commit().await?;
+    Ok(Json(response).into_response())
+}
+
+/// Update the transactions within `db_tx` without the approval check; also
+/// used to apply an approved request on the approval's transaction.
+pub(crate) async fn apply_bulk_update(
+    db_tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    transaction_ids: &[Uuid],
+    status: &str,
+    reason: Option<&str>,
+    actor: &str,
+) -> Result<BulkStatusResponse, AppError> {
+    validate(transaction_ids, status)?;
+    let result: BulkUpdateResult =
+        bulk_update_transaction_status(db_tx, transaction_ids, status, reason, actor).await?;
+
+    Ok(BulkStatusResponse {
+        updated: result.updated,
+        failed: result.failed,
+        errors: result.errors,
+    })
+}
+
+fn validate(transaction_ids: &[Uuid], status: &str) -> Result<(), AppError> {
+    if transaction_ids.is_empty() {
         return Err(AppError::BadRequest(
             "transaction_ids must not be empty".to_string(),
         ));
     }
-    if payload.transaction_ids.len() > 500 {
+    if transaction_ids.len() > 500 {
         return Err(AppError::BadRequest(
             "transaction_ids must not exceed 500 items per request".to_string(),
         ));
     }
 
     let valid_statuses = ["pending", "processing", "completed", "failed"];
-    if !valid_statuses.contains(&payload.status.as_str()) {
+    if !valid_statuses.contains(&status) {
         return Err(AppError::Validation(format!(
             "invalid status '{}', must be one of: {}",
-            payload.status,
+            status,
             valid_statuses.join(", ")
         )));
     }
-
-    let result: BulkUpdateResult = bulk_update_transaction_status(
-        pool,
-        &payload.transaction_ids,
-        &payload.status,
-        payload.reason.as_deref(),
-        "admin",
-    )
-    .await?;
-
-    Ok(Json(BulkStatusResponse {
-        updated: result.updated,
-        failed: result.failed,
-        errors: result.errors,
-    }))
+    Ok(())
 }
 
 #[cfg(test)]
@@ -90,4 +145,12 @@ mod tests {
         assert_eq!(req.reason.as_deref(), Some("manual override"));
         assert_eq!(req.transaction_ids.len(), 1);
     }
+
+    #[test]
+    fn test_validate_rejects_unknown_status() {
+        let ids = [Uuid::nil()];
+        assert!(validate(&ids, "failed").is_ok());
+        assert!(validate(&ids, "compliance_review").is_err());
+        assert!(validate(&[], "failed").is_err());
+    }
 }
@@ -0,0 +1,107 @@
+//! Compliance decisions on SEP-12 customers.
+//!
+//! `PUT /admin/customers/:id/status` sets a customer's status to `ACCEPTED`,
+//! `REJECTED` or back to `NEEDS_INFO`, with an optional `message` shown to
+//! the customer by `GET /customer`. See [`crate::services::sep12`].
+
+use crate::adapters;
+use crate::error::AppError;
+use crate::ports::KycStatus;
+use crate::ApiState;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+const MAX_MESSAGE_LEN: usize = 1024;
+
+#[derive(Debug, Deserialize)]
+pub struct SetCustomerStatusRequest {
+    pub status: String,
+    pub message: Option<String>,
+}
+
+impl SetCustomerStatusRequest {
+    fn status(&self) -> Result<KycStatus, AppError> {
+        match KycStatus::parse(&self.status) {
+            Some(status @ (KycStatus::Accepted | KycStatus::Rejected | KycStatus::NeedsInfo)) => {
+                Ok(status)
+            }
+            _ => Err(AppError::Validation(
+                "status: must be ACCEPTED, REJECTED or NEEDS_INFO".to_string(),
+            )),
+        }
+    }
+
+    fn message(&self) -> Result<Option<String>, AppError> {
+        match self
+            .message
+            .as_deref()
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+        {
+            Some(m) if m.len() > MAX_MESSAGE_LEN => Err(AppError::Validation(format!(
+                "message: must be at most {MAX_MESSAGE_LEN} characters"
+            ))),
+            other => Ok(other.map(str::to_string)),
+        }
+    }
+}
+
+/// PUT /admin/customers/:id/status
+pub async fn set_customer_status(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<SetCustomerStatusRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let status = payload.status()?;
+    let message = payload.message()?;
+    let customers = adapters::customer_repository(state.app_state.db.clone());
+    let mut customer = customers
+        .get_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Customer {id} not found")))?;
+
+    customer.status = status;
+    customer.message = message;
+    customer.updated_at = state.app_state.clock.now();
+    let saved = customers.save(&customer).await?;
+
+    tracing::info!(customer_id = %id, status = status.as_str(), "SEP-12 customer status set");
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "id": saved.id,
+            "status": saved.status.as_str(),
+            "message": saved.message,
+        })),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(status: &str) -> SetCustomerStatusRequest {
+        SetCustomerStatusRequest {
+            status: status.to_string(),
+            message: None,
+        }
+    }
+
+    #[test]
+    fn test_only_compliance_decisions_are_accepted() {
+        assert_eq!(request("accepted").status().unwrap(), KycStatus::Accepted);
+        assert_eq!(request("REJECTED").status().unwrap(), KycStatus::Rejected);
+        assert_eq!(
+            request("NEEDS_INFO").status().unwrap(),
+            KycStatus::NeedsInfo
+        );
+        assert!(request("PROCESSING").status().is_err());
+        assert!(request("approved").status().is_err());
+    }
+}
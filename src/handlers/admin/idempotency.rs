@@ -0,0 +1,218 @@
+//! Admin inspection and expiry of idempotency cache entries.
+//!
+//! When an instance crashes mid-request its idempotency lock lingers for up
+//! to five minutes, and every retry with that key gets `429 Request is
+//! currently being processed`. These endpoints let support see and clear the
+//! entry without shell access to Redis:
+//!
+//! | Method   | Path                      | Effect                                    |
+//! |----------|---------------------------|-------------------------------------------|
+//! | `GET`    | `/admin/idempotency/:key` | Lock, cached response and DB fallback row |
+//! | `DELETE` | `/admin/idempotency/:key` | Remove all three                          |
+//!
+//! Both accept `?tenant_id=` (defaults to `default`, matching the
+//! `X-Tenant-Id` fallback in the middleware) and `?actor=` (defaults to
+//! `admin`). Every call is written to `audit_logs` with entity type
+//! [`ENTITY_IDEMPOTENCY_KEY`].
+
+use crate::db::audit::{AuditLog, ENTITY_IDEMPOTENCY_KEY};
+use crate::db::queries;
+use crate::error::AppError;
+use crate::middleware::idempotency::{
+    expire_entry, inspect_entry, validate_idempotency_key, IdempotencyEntry,
+};
+use crate::ApiState;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+pub struct IdempotencyAdminQuery {
+    pub tenant_id: Option<String>,
+    pub actor: Option<String>,
+}
+
+impl IdempotencyAdminQuery {
+    fn tenant_id(&self) -> &str {
+        self.tenant_id
+            .as_deref()
+            .filter(|t| !t.is_empty())
+            .unwrap_or("default")
+    }
+
+    fn actor(&self) -> &str {
+        self.actor
+            .as_deref()
+            .filter(|a| !a.is_empty())
+            .unwrap_or("admin")
+    }
+}
+
+/// Row in the `idempotency_keys` table used when Redis is unavailable.
+#[derive(Debug, Serialize)]
+pub struct DbFallbackInfo {
+    pub status: String,
+    pub has_response: bool,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IdempotencyEntryView {
+    #[serde(flatten)]
+    pub entry: IdempotencyEntry,
+    pub db_fallback: Option<DbFallbackInfo>,
+}
+
+/// Stable audit `entity_id` for a `(tenant, key)` pair, so all actions on one
+/// key can be found with a single audit search.
+fn audit_entity_id(tenant_id: &str, key: &str) -> Uuid {
+    let digest = Sha256::new()
+        .chain_update(tenant_id.as_bytes())
+        .chain_update([0u8])
+        .chain_update(key.as_bytes())
+        .finalize();
+    Uuid::from_slice(&digest[..16]).unwrap_or_default()
+}
+
+async fn audit(
+    state: &ApiState,
+    tenant_id: &str,
+    key: &str,
+    action: &str,
+    old_val: Option<serde_json::Value>,
+    actor: &str,
+) -> Result<(), AppError> {
+    let mut tx = state.app_state.db.begin().await?;
+    AuditLog::log(
+        &mut tx,
+        audit_entity_id(tenant_id, key),
+        ENTITY_IDEMPOTENCY_KEY,
+        action,
+        old_val,
+        Some(serde_json::json!({ "tenant_id": tenant_id, "key": key })),
+        actor,
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+async fn load_entry(
+    state: &ApiState,
+    tenant_id: &str,
+    key: &str,
+) -> Result<IdempotencyEntryView, AppError> {
+    let client = redis::Client::open(state.app_state.redis_url.as_str())?;
+    let entry = inspect_entry(&client, tenant_id, key).await?;
+    let db_fallback = queries::check_idempotency_key(&state.app_state.db, key)
+        .await?
+        .map(|row| DbFallbackInfo {
+            status: row.status,
+            has_response: row.response.is_some(),
+            expires_at: row.expires_at,
+        });
+    Ok(IdempotencyEntryView { entry, db_fallback })
+}
+
+/// GET /admin/idempotency/:key — show the lock, cached response and DB
+/// fallback state for a key.
+pub async fn get_idempotency_entry(
+    State(state): State<ApiState>,
+    Path(raw_key): Path<String>,
+    Query(q): Query<IdempotencyAdminQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let key = validate_idempotency_key(&raw_key)?;
+    let tenant_id = q.tenant_id();
+
+    let view = load_entry(&state, tenant_id, &key).await?;
+    if view.entry.is_empty() && view.db_fallback.is_none() {
+        return Err(AppError::NotFound(format!(
+            "no idempotency entry for key '{key}'"
+        )));
+    }
+
+    audit(&state, tenant_id, &key, "inspected", None, q.actor()).await?;
+
+    Ok((StatusCode::OK, Json(view)))
+}
+
+/// DELETE /admin/idempotency/:key — expire the lock, cached response and DB
+/// fallback row so the next request with the key is processed afresh.
+pub async fn delete_idempotency_entry(
+    State(state): State<ApiState>,
+    Path(raw_key): Path<String>,
+    Query(q): Query<IdempotencyAdminQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let key = validate_idempotency_key(&raw_key)?;
+    let tenant_id = q.tenant_id();
+
+    let before = load_entry(&state, tenant_id, &key).await?;
+    if before.entry.is_empty() && before.db_fallback.is_none() {
+        return Err(AppError::NotFound(format!(
+            "no idempotency entry for key '{key}'"
+        )));
+    }
+
+    let client = redis::Client::open(state.app_state.redis_url.as_str())?;
+    let redis_keys_removed = expire_entry(&client, tenant_id, &key).await?;
+    let db_rows_removed = queries::delete_idempotency_key(&state.app_state.db, &key).await?;
+
+    tracing::warn!(
+        tenant_id,
+        idempotency_key = %key,
+        actor = q.actor(),
+        redis_keys_removed,
+        db_rows_removed,
+        "Idempotency entry expired by admin"
+    );
+    audit(
+        &state,
+        tenant_id,
+        &key,
+        "expired",
+        serde_json::to_value(&before).ok(),
+        q.actor(),
+    )
+    .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "message": "idempotency entry expired",
+            "tenant_id": tenant_id,
+            "key": key,
+            "redis_keys_removed": redis_keys_removed,
+            "db_rows_removed": db_rows_removed,
+        })),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit_entity_id_is_stable_per_tenant_and_key() {
+        let a = audit_entity_id("default", "key-1");
+        assert_eq!(a, audit_entity_id("default", "key-1"));
+        assert_ne!(a, audit_entity_id("default", "key-2"));
+        assert_ne!(a, audit_entity_id("tenant-b", "key-1"));
+    }
+
+    #[test]
+    fn test_query_defaults() {
+        let q = IdempotencyAdminQuery {
+            tenant_id: None,
+            actor: Some(String::new()),
+        };
+        assert_eq!(q.tenant_id(), "default");
+        assert_eq!(q.actor(), "admin");
+    }
+}
@@ -0,0 +1,33 @@
+//! `GET /admin/info`: build and schema details, the Stellar fee strategy and
+//! Horizon endpoint health of the running instance.
+
+use crate::db::migrations::{self, SchemaStatus};
+use crate::error::AppError;
+use crate::services::fees::{self, FeeStatus};
+use crate::stellar::endpoints::EndpointStatus;
+use crate::ApiState;
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct AdminInfo {
+    pub version: &'static str,
+    pub uptime_secs: u64,
+    pub schema: SchemaStatus,
+    pub fees: FeeStatus,
+    pub horizon: Vec<EndpointStatus>,
+}
+
+pub async fn get_info(State(state): State<ApiState>) -> Result<impl IntoResponse, AppError> {
+    let schema = migrations::schema_status(&state.app_state.db).await?;
+    Ok((
+        StatusCode::OK,
+        Json(AdminInfo {
+            version: env!("CARGO_PKG_VERSION"),
+            uptime_secs: state.app_state.start_time.elapsed().as_secs(),
+            schema,
+            fees: fees::fees().status(),
+            horizon: state.app_state.horizon_client.endpoints().status(),
+        }),
+    ))
+}
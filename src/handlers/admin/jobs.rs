@@ -0,0 +1,238 @@
+//! Status and control of long-running background jobs.
+//!
+//! | Method | Path                     | Effect                                        |
+//! |--------|--------------------------|-----------------------------------------------|
+//! | `GET`  | `/admin/jobs`            | List jobs (`?kind=&status=&limit=&offset=`)   |
+//! | `POST` | `/admin/jobs`            | Queue a job (`202 Accepted`)                  |
+//! | `GET`  | `/admin/jobs/:id`        | One job with progress                         |
+//! | `POST` | `/admin/jobs/:id/cancel` | Cancel a queued or running job                |
+//! | `POST` | `/admin/jobs/:id/run`    | Run a scheduled job now (`?force=true`)       |
+//!
+//! See [`crate::services::job_runner`] for the job lifecycle. A running job
+//! stops at its next checkpoint, so `cancel` on a running job returns it still
+//! `running` with `cancel_requested: true`.
+//!
+//! `run` takes the name of a scheduled job (`transaction_processor`,
+//! `job_runner`, ...) rather than a job id. Every route needs the admin API key;
+//! queued jobs record the authenticated admin principal as `requested_by`.
+
+use crate::db::queries;
+use crate::error::AppError;
+use crate::middleware::auth::AdminPrincipal;
+use crate::services::job_runner::{JobKind, JobState};
+use crate::services::scheduler::{RunParams, TriggerError};
+use crate::ApiState;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use std::str::FromStr;
+use uuid::Uuid;
+
+const MAX_LIST_LIMIT: i64 = 200;
+
+#[derive(Debug, Deserialize)]
+pub struct ListJobsQuery {
+    pub kind: Option<String>,
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateJobRequest {
+    pub kind: JobKind,
+    #[serde(default)]
+    pub params: serde_json::Value,
+}
+
+/// GET /admin/jobs
+pub async fn list_jobs(
+    State(state): State<ApiState>,
+    Query(q): Query<ListJobsQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let kind = match q.kind.as_deref() {
+        Some(k) => Some(JobKind::from_str(k).map_err(AppError::BadRequest)?),
+        None => None,
+    };
+    let status = match q.status.as_deref() {
+        Some(s) => Some(JobState::from_str(s).map_err(AppError::BadRequest)?),
+        None => None,
+    };
+    let limit = q.limit.unwrap_or(50).clamp(1, MAX_LIST_LIMIT);
+    let offset = q.offset.unwrap_or(0).max(0);
+
+    let jobs = queries::list_jobs(
+        &state.app_state.db,
+        kind.as_ref().map(JobKind::as_str),
+        status.as_ref().map(JobState::as_str),
+        limit,
+        offset,
+    )
+    .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "jobs": jobs,
+            "limit": limit,
+            "offset": offset,
+        })),
+    ))
+}
+
+/// POST /admin/jobs
+pub async fn create_job(
+    State(state): State<ApiState>,
+    principal: AdminPrincipal,
+    Json(payload): Json<CreateJobRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .kind
+        .validate_params(&payload.params)
+        .map_err(AppError::Validation)?;
+
+    let job = queries::insert_job(
+        &state.app_state.db,
+        payload.kind.as_str(),
+        &payload.params,
+        Some(principal.name.as_str()),
+    )
+    .await?;
+
+    tracing::info!(job_id = %job.id, kind = payload.kind.as_str(), "Job queued");
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+/// GET /admin/jobs/:id
+pub async fn get_job(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let job = queries::get_job(&state.app_state.db, id)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => AppError::NotFound(format!("Job {id} not found")),
+            other => other.into(),
+        })?;
+    Ok((StatusCode::OK, Json(job)))
+}
+
+/// POST /admin/jobs/:id/cancel
+pub async fn cancel_job(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    if let Some(job) = queries::request_job_cancel(&state.app_state.db, id).await? {
+        tracing::info!(job_id = %id, status = %job.status, "Job cancellation requested");
+        return Ok((StatusCode::OK, Json(job)));
+    }
+
+    // Not cancellable: tell a missing job apart from a finished one.
+    let job = queries::get_job(&state.app_state.db, id)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => AppError::NotFound(format!("Job {id} not found")),
+            other => other.into(),
+        })?;
+    Err(AppError::BadRequest(format!(
+        "Job {id} is already {}",
+        job.status
+    )))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RunJobQuery {
+    /// Start a run even if one is already in progress.
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// POST /admin/jobs/:id/run
+///
+/// Runs the scheduled job named `:id` in the background and returns the id of
+/// its `job_runs` row. An optional body overrides settings for this run only,
+/// e.g. `{"batch_size": 50}`.
+pub async fn run_scheduled_job(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+    Query(q): Query<RunJobQuery>,
+    body: Option<Json<RunParams>>,
+) -> Result<Response, AppError> {
+    let params = body.map(|Json(p)| p).unwrap_or_default();
+    if params.batch_size == Some(0) {
+        return Err(AppError::Validation(
+            "batch_size must be a positive integer".to_string(),
+        ));
+    }
+
+    match state
+        .app_state
+        .job_scheduler
+        .trigger(&name, params, q.force)
+        .await
+    {
+        Ok(job_run_id) => Ok((
+            StatusCode::ACCEPTED,
+            Json(serde_json::json!({
+                "job": name,
+                "job_run_id": job_run_id,
+            })),
+        )
+            .into_response()),
+        Err(e @ TriggerError::UnknownJob(_)) => Err(AppError::NotFound(e.to_string())),
+        Err(e @ TriggerError::RegionPassive(_)) => Ok((
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({
+                "error": e.to_string(),
+                "hint": "run it in the active region, or make this region active first",
+            })),
+        )
+            .into_response()),
+        Err(e @ TriggerError::AlreadyRunning(_)) => Ok((
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({
+                "error": e.to_string(),
+                "hint": "retry with ?force=true to start another run",
+            })),
+        )
+            .into_response()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_request_parses_kind() {
+        let req: CreateJobRequest = serde_json::from_str(
+            r#"{"kind":"rollup_recompute","params":{"from":"2026-05-01","to":"2026-05-31"}}"#,
+        )
+        .unwrap();
+        assert_eq!(req.kind, JobKind::RollupRecompute);
+        assert!(req.kind.validate_params(&req.params).is_ok());
+    }
+
+    #[test]
+    fn test_create_request_rejects_unknown_kind() {
+        assert!(serde_json::from_str::<CreateJobRequest>(r#"{"kind":"bulk_delete"}"#).is_err());
+    }
+
+    #[test]
+    fn test_run_params_are_optional() {
+        assert_eq!(
+            serde_json::from_str::<RunParams>("{}").unwrap(),
+            RunParams::default()
+        );
+        assert_eq!(
+            serde_json::from_str::<RunParams>(r#"{"batch_size":50}"#)
+                .unwrap()
+                .batch_size,
+            Some(50)
+        );
+    }
+}
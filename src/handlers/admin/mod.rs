@@ -1,8 +1,27 @@
+pub mod accounting;
+pub mod approvals;
+pub mod asset_limits;
+#[cfg(feature = "backup")]
+pub mod backups;
+pub mod breakers;
 pub mod bulk_status;
+pub mod customers;
+pub mod idempotency;
+pub mod info;
+pub mod jobs;
 pub mod locks;
+pub mod partners;
+pub mod quarantine;
 pub mod quota;
 pub mod reconciliation;
+pub mod refunds;
+pub mod region;
+pub mod reviews;
+pub mod signing_keys;
+pub mod statements;
+pub mod structuring;
 pub mod webhook_replay;
+pub mod webhook_subscriptions;
 
 use crate::error::AppError;
 use crate::validation::{validate_max_len, validate_required};
@@ -248,7 +267,7 @@ pub async fn create_asset(
         VALUES ($1, $2, $3, TRUE)
         ON CONFLICT (asset_code, asset_issuer) DO UPDATE
             SET enabled = TRUE, updated_at = NOW()
-        RETURNING id, asset_code, asset_issuer, metadata, enabled, created_at, updated_at
+        RETURNING id, asset_code, asset_issuer, metadata, enabled, min_amount, max_amount, created_at, updated_at
         "#,
     )
     .bind(&asset_code)
@@ -306,7 +325,7 @@ pub async fn set_asset_enabled(
         r#"
         UPDATE assets SET enabled = $1, updated_at = NOW()
         WHERE id = $2
-        RETURNING id, asset_code, asset_issuer, metadata, enabled, created_at, updated_at
+        RETURNING id, asset_code, asset_issuer, metadata, enabled, min_amount, max_amount, created_at, updated_at
         "#,
     )
     .bind(payload.enabled)
@@ -0,0 +1,264 @@
+//! Partner (tenant) settings.
+//!
+//! `GET /admin/partners/:tenant_id/settings` returns the settings that shape
+//! how a partner's traffic is handled; `PATCH` updates any subset of them.
+//! Every change is audit-logged and the in-memory tenant config cache is
+//! reloaded so it takes effect on the next request. The view also lists the
+//! partner's inbound signing keys, rotated through
+//! [`super::signing_keys`].
+//!
+//! `trusted_assets` is the partner's registry of accepted asset issuers (see
+//! [`crate::services::asset_trust`]); a provided list replaces it wholesale.
+//!
+//! `processing_weight` (1-100, default 1) is how many of the partner's pending
+//! transactions the processor takes per round-robin round, relative to other
+//! partners (see [`crate::services::processor::process_batch`]).
+
+use crate::db::models::TrustedAsset;
+use crate::db::queries;
+use crate::domain::StellarAddress;
+use crate::error::AppError;
+use crate::services::signing_keys::SigningKeyView;
+use crate::validation::ASSET_CODE_MAX_LEN;
+use crate::ApiState;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+use chrono::Utc;
+use serde::{Deserialize, Deserializer, Serialize};
+use uuid::Uuid;
+
+const SIGNING_KEY_LIMIT: i64 = 10;
+
+#[derive(Debug, Serialize)]
+pub struct PartnerSettingsView {
+    pub tenant_id: Uuid,
+    pub name: String,
+    pub rate_limit_per_minute: i32,
+    pub processing_weight: i32,
+    /// `null` means the partner may deposit any supported asset.
+    pub allowed_assets: Option<Vec<String>>,
+    /// Empty means payments from any issuer are accepted.
+    pub trusted_assets: Vec<TrustedAsset>,
+    /// Most recent first; empty while the partner uses the global keys.
+    pub signing_keys: Vec<SigningKeyView>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct UpdatePartnerSettingsRequest {
+    pub rate_limit_per_minute: Option<i32>,
+    pub processing_weight: Option<i32>,
+    /// Absent leaves the allowlist unchanged; `null` removes the restriction.
+    #[serde(default, deserialize_with = "present")]
+    pub allowed_assets: Option<Option<Vec<String>>>,
+    /// Absent leaves the registry unchanged; `[]` trusts any issuer.
+    pub trusted_assets: Option<Vec<TrustedAsset>>,
+    pub actor: Option<String>,
+}
+
+/// Distinguishes an explicit `null` (`Some(None)`) from a missing field (`None`).
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// Upper-case Stellar asset code (1-12 alphanumerics), or `None` if invalid.
+fn normalize_asset_code(asset: &str) -> Option<String> {
+    let code = asset.trim().to_ascii_uppercase();
+    let valid = !code.is_empty()
+        && code.len() <= ASSET_CODE_MAX_LEN
+        && code.chars().all(|c| c.is_ascii_alphanumeric());
+    valid.then_some(code)
+}
+
+/// Validate and normalise an allowlist: upper-case Stellar asset codes
+/// (1-12 alphanumerics), no duplicates, order preserved.
+fn normalize_allowed_assets(assets: &[String]) -> Result<Vec<String>, AppError> {
+    let mut normalized: Vec<String> = Vec::with_capacity(assets.len());
+    for asset in assets {
+        let code = normalize_asset_code(asset).ok_or_else(|| {
+            AppError::Validation(format!(
+                "allowed_assets: '{asset}' is not a valid asset code"
+            ))
+        })?;
+        if !normalized.contains(&code) {
+            normalized.push(code);
+        }
+    }
+    Ok(normalized)
+}
+
+/// Validate and normalise a trusted asset registry: upper-case asset codes,
+/// `G...` issuers, no duplicates, order preserved.
+fn normalize_trusted_assets(assets: &[TrustedAsset]) -> Result<Vec<TrustedAsset>, AppError> {
+    let mut normalized: Vec<TrustedAsset> = Vec::with_capacity(assets.len());
+    for asset in assets {
+        let code = normalize_asset_code(&asset.asset_code).ok_or_else(|| {
+            AppError::Validation(format!(
+                "trusted_assets: '{}' is not a valid asset code",
+                asset.asset_code
+            ))
+        })?;
+        let issuer = StellarAddress::parse(&asset.asset_issuer)
+            .ok()
+            .filter(|issuer| !issuer.is_muxed())
+            .ok_or_else(|| {
+                AppError::Validation(format!(
+                    "trusted_assets: '{}' is not a valid issuer account",
+                    asset.asset_issuer
+                ))
+            })?;
+        let asset = TrustedAsset {
+            asset_code: code,
+            asset_issuer: issuer.account().to_string(),
+        };
+        if !normalized.contains(&asset) {
+            normalized.push(asset);
+        }
+    }
+    Ok(normalized)
+}
+
+async fn load_view(state: &ApiState, tenant_id: Uuid) -> Result<PartnerSettingsView, AppError> {
+    let cfg = state
+        .app_state
+        .get_tenant_config(tenant_id)
+        .await
+        .ok_or(AppError::TenantNotFound)?;
+    let now = Utc::now();
+    let signing_keys =
+        queries::list_inbound_signing_keys(&state.app_state.db, Some(tenant_id), SIGNING_KEY_LIMIT)
+            .await?
+            .into_iter()
+            .map(|key| SigningKeyView::new(key, now))
+            .collect();
+    Ok(PartnerSettingsView {
+        tenant_id,
+        name: cfg.name,
+        rate_limit_per_minute: cfg.rate_limit_per_minute,
+        processing_weight: cfg.processing_weight,
+        allowed_assets: cfg.allowed_assets,
+        trusted_assets: cfg.trusted_assets,
+        signing_keys,
+    })
+}
+
+/// GET /admin/partners/:tenant_id/settings
+pub async fn get_partner_settings(
+    State(state): State<ApiState>,
+    Path(tenant_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok((StatusCode::OK, Json(load_view(&state, tenant_id).await?)))
+}
+
+/// PATCH /admin/partners/:tenant_id/settings
+pub async fn update_partner_settings(
+    State(state): State<ApiState>,
+    Path(tenant_id): Path<Uuid>,
+    Json(payload): Json<UpdatePartnerSettingsRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    if state.app_state.get_tenant_config(tenant_id).await.is_none() {
+        return Err(AppError::TenantNotFound);
+    }
+    let actor = payload.actor.as_deref().unwrap_or("admin");
+
+    let allowed_assets = match &payload.allowed_assets {
+        Some(Some(assets)) => Some(Some(normalize_allowed_assets(assets)?)),
+        Some(None) => Some(None),
+        None => None,
+    };
+    let trusted_assets = payload
+        .trusted_assets
+        .as_deref()
+        .map(normalize_trusted_assets)
+        .transpose()?;
+
+    if let Some(limit) = payload.rate_limit_per_minute {
+        queries::update_tenant_rate_limit(&state.app_state.db, tenant_id, limit, actor)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Decode(msg) => AppError::Validation(msg.to_string()),
+                other => other.into(),
+            })?;
+    }
+    if let Some(weight) = payload.processing_weight {
+        queries::update_tenant_processing_weight(&state.app_state.db, tenant_id, weight, actor)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Decode(msg) => AppError::Validation(msg.to_string()),
+                other => other.into(),
+            })?;
+    }
+    if let Some(assets) = &allowed_assets {
+        queries::update_tenant_allowed_assets(
+            &state.app_state.db,
+            tenant_id,
+            assets.as_deref(),
+            actor,
+        )
+        .await?;
+    }
+    if let Some(assets) = &trusted_assets {
+        queries::replace_partner_trusted_assets(&state.app_state.db, tenant_id, assets, actor)
+            .await?;
+    }
+
+    state.app_state.load_tenant_configs().await?;
+
+    Ok((StatusCode::OK, Json(load_view(&state, tenant_id).await?)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_allowed_assets() {
+        let assets = vec!["usdc".to_string(), " USDC ".to_string(), "XLM".to_string()];
+        assert_eq!(
+            normalize_allowed_assets(&assets).unwrap(),
+            vec!["USDC".to_string(), "XLM".to_string()]
+        );
+        assert!(normalize_allowed_assets(&["NOT-AN-ASSET".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_normalize_trusted_assets() {
+        let issuer = "GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN";
+        let asset = |code: &str, issuer: &str| TrustedAsset {
+            asset_code: code.to_string(),
+            asset_issuer: issuer.to_string(),
+        };
+        assert_eq!(
+            normalize_trusted_assets(&[
+                asset("usdc", issuer),
+                asset("USDC", &format!(" {issuer}"))
+            ])
+            .unwrap(),
+            vec![asset("USDC", issuer)]
+        );
+        assert!(normalize_trusted_assets(&[asset("NOT-AN-ASSET", issuer)]).is_err());
+        assert!(normalize_trusted_assets(&[asset("USDC", "GBAD")]).is_err());
+        let muxed = "MA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJUAAAAAAAAAAE2JUG6";
+        assert!(normalize_trusted_assets(&[asset("USDC", muxed)]).is_err());
+    }
+
+    #[test]
+    fn test_allowed_assets_absent_vs_null() {
+        let absent: UpdatePartnerSettingsRequest = serde_json::from_str("{}").unwrap();
+        assert_eq!(absent.allowed_assets, None);
+
+        let cleared: UpdatePartnerSettingsRequest =
+            serde_json::from_str(r#"{"allowed_assets": null}"#).unwrap();
+        assert_eq!(cleared.allowed_assets, Some(None));
+
+        let set: UpdatePartnerSettingsRequest =
+            serde_json::from_str(r#"{"allowed_assets": ["USDC"]}"#).unwrap();
+        assert_eq!(set.allowed_assets, Some(Some(vec!["USDC".to_string()])));
+    }
+}
@@ -0,0 +1,48 @@
+//! Admin view of payments quarantined for an untrusted asset issuer.
+//!
+//! `GET /admin/quarantine` lists them newest first
+//! (`?tenant_id=&limit=&offset=`). See [`crate::services::asset_trust`] for
+//! when a payment is quarantined; releasing one is a manual decision outside
+//! this service.
+
+use crate::db::queries;
+use crate::error::AppError;
+use crate::ApiState;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+const MAX_LIST_LIMIT: i64 = 200;
+
+#[derive(Debug, Deserialize)]
+pub struct ListQuarantineQuery {
+    pub tenant_id: Option<Uuid>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// GET /admin/quarantine
+pub async fn list_quarantined_payments(
+    State(state): State<ApiState>,
+    Query(q): Query<ListQuarantineQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let limit = q.limit.unwrap_or(50).clamp(1, MAX_LIST_LIMIT);
+    let offset = q.offset.unwrap_or(0).max(0);
+
+    let payments =
+        queries::list_quarantined_payments(&state.app_state.db, q.tenant_id, limit, offset).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "payments": payments,
+            "limit": limit,
+            "offset": offset,
+        })),
+    ))
+}
@@ -380,7 +380,7 @@ pub async fn run_reconciliation(
             .into_response();
     }
 
-    let horizon_client = HorizonClient::new(state.app_state.horizon_client.base_url.clone());
+    let horizon_client = HorizonClient::new(state.app_state.horizon_client.base_url().to_string());
     let pool = state.app_state.db.clone();
 
     let svc = ReconciliationService::new(horizon_client.clone(), pool.clone());
@@ -0,0 +1,272 @@
+//! Admin view of, and overrides for, the refund queue.
+//!
+//! | Method | Path                          | Effect                                     |
+//! |--------|-------------------------------|--------------------------------------------|
+//! | `GET`  | `/admin/refunds`              | List tasks (`?status=&limit=&offset=`)     |
+//! | `GET`  | `/admin/refunds/:id`          | One task                                   |
+//! | `POST` | `/admin/refunds/:id/override` | `hold`, `release`, `cancel` or `complete`  |
+//!
+//! See [`crate::services::refunds`] for how tasks are created and the allowed
+//! transitions. Every override is audit-logged with the authenticated admin
+//! principal as actor. Overrides of refunds for more than the four-eyes
+//! threshold are held for a second approver ([`crate::services::approvals`])
+//! and answered with `202 Accepted`.
+
+use crate::db::models::RefundTask;
+use crate::db::queries::{self, RefundTaskUpdate};
+use crate::error::AppError;
+use crate::middleware::auth::AdminPrincipal;
+use crate::services::approvals::{self, ApprovalAction};
+use crate::services::refunds::{RefundAction, RefundStatus};
+use crate::validation::validate_stellar_address;
+use crate::ApiState;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use std::str::FromStr;
+use uuid::Uuid;
+
+const MAX_LIST_LIMIT: i64 = 200;
+
+#[derive(Debug, Deserialize)]
+pub struct ListRefundsQuery {
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RefundOverrideRequest {
+    pub action: RefundAction,
+    /// Redirect the refund to another account (`hold` / `release` only).
+    pub refund_to: Option<String>,
+    /// Hash of the refund payment; required for `complete`.
+    pub stellar_tx_hash: Option<String>,
+    pub note: Option<String>,
+}
+
+impl RefundOverrideRequest {
+    fn validate(&self) -> Result<(), AppError> {
+        if let Some(account) = &self.refund_to {
+            if !matches!(self.action, RefundAction::Hold | RefundAction::Release) {
+                return Err(AppError::Validation(
+                    "refund_to can only be changed with hold or release".to_string(),
+                ));
+            }
+            validate_stellar_address(account).map_err(|e| AppError::Validation(e.to_string()))?;
+        }
+        match (&self.action, &self.stellar_tx_hash) {
+            (RefundAction::Complete, None) => Err(AppError::Validation(
+                "stellar_tx_hash is required to complete a refund".to_string(),
+            )),
+            (RefundAction::Complete, Some(hash))
+                if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) =>
+            {
+                Err(AppError::Validation(
+                    "stellar_tx_hash must be 64 hex characters".to_string(),
+                ))
+            }
+            (RefundAction::Complete, Some(_)) | (_, None) => Ok(()),
+            (_, Some(_)) => Err(AppError::Validation(
+                "stellar_tx_hash is only accepted with complete".to_string(),
+            )),
+        }
+    }
+}
+
+/// GET /admin/refunds
+pub async fn list_refunds(
+    State(state): State<ApiState>,
+    Query(q): Query<ListRefundsQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let status = match q.status.as_deref() {
+        Some(s) => Some(RefundStatus::from_str(s).map_err(AppError::BadRequest)?),
+        None => None,
+    };
+    let limit = q.limit.unwrap_or(50).clamp(1, MAX_LIST_LIMIT);
+    let offset = q.offset.unwrap_or(0).max(0);
+
+    let refunds = queries::list_refund_tasks(
+        &state.app_state.db,
+        status.as_ref().map(RefundStatus::as_str),
+        limit,
+        offset,
+    )
+    .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "refunds": refunds,
+            "limit": limit,
+            "offset": offset,
+        })),
+    ))
+}
+
+/// GET /admin/refunds/:id
+pub async fn get_refund(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let task = queries::get_refund_task(&state.app_state.db, id)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => AppError::NotFound(format!("Refund {id} not found")),
+            other => other.into(),
+        })?;
+    Ok((StatusCode::OK, Json(task)))
+}
+
+/// POST /admin/refunds/:id/override
+pub async fn override_refund(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+    principal: AdminPrincipal,
+    Json(payload): Json<RefundOverrideRequest>,
+) -> Result<Response, AppError> {
+    payload.validate()?;
+    let actor = principal.name.as_str();
+
+    let current = fetch_refund(&state.app_state.db, id).await?;
+    if approvals::requires_approval(&current.amount, &approvals::threshold()) {
+        // Check the transition now so an impossible request is never proposed.
+        let from = RefundStatus::from_str(&current.status).map_err(AppError::Internal)?;
+        payload.action.apply(from).map_err(AppError::BadRequest)?;
+
+        let action = ApprovalAction::RefundOverride {
+            refund_id: id,
+            action: payload.action,
+            refund_to: payload.refund_to,
+            stellar_tx_hash: payload.stellar_tx_hash,
+            note: payload.note,
+        };
+        let approval =
+            approvals::propose(&state.app_state.db, &action, &current.amount, &principal).await?;
+        return Ok((
+            StatusCode::ACCEPTED,
+            Json(serde_json::json!({ "approval": approval })),
+        )
+            .into_response());
+    }
+
+    let mut db_tx = state.app_state.db.begin().await?;
+    let updated = apply_override(&mut db_tx, &current, &payload, actor).await?;
+    db_tx.commit().await?;
+    Ok((StatusCode::OK, Json(updated)).into_response())
+}
+
+fn refund_not_found(id: Uuid, e: sqlx::Error) -> AppError {
+    match e {
+        sqlx::Error::RowNotFound => AppError::NotFound(format!("Refund {id} not found")),
+        other => other.into(),
+    }
+}
+
+async fn fetch_refund(pool: &sqlx::PgPool, id: Uuid) -> Result<RefundTask, AppError> {
+    queries::get_refund_task(pool, id)
+        .await
+        .map_err(|e| refund_not_found(id, e))
+}
+
+/// Override refund `id` within `db_tx` without the approval check; used to
+/// apply an approved request on the approval's transaction.
+pub(crate) async fn apply_approved_override(
+    db_tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    id: Uuid,
+    payload: &RefundOverrideRequest,
+    actor: &str,
+) -> Result<RefundTask, AppError> {
+    payload.validate()?;
+    let current = queries::lock_refund_task(db_tx, id)
+        .await
+        .map_err(|e| refund_not_found(id, e))?;
+    apply_override(db_tx, &current, payload, actor).await
+}
+
+async fn apply_override(
+    db_tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    current: &RefundTask,
+    payload: &RefundOverrideRequest,
+    actor: &str,
+) -> Result<RefundTask, AppError> {
+    let id = current.id;
+    let from = RefundStatus::from_str(&current.status).map_err(AppError::Internal)?;
+    let to = payload.action.apply(from).map_err(AppError::BadRequest)?;
+
+    let updated = queries::update_refund_task(
+        db_tx,
+        current,
+        &RefundTaskUpdate {
+            status: to.as_str(),
+            refund_to: payload.refund_to.as_deref().map(str::trim),
+            stellar_tx_hash: payload.stellar_tx_hash.as_deref(),
+            note: payload.note.as_deref(),
+        },
+        payload.action.as_str(),
+        actor,
+    )
+    .await?
+    .ok_or_else(|| AppError::BadRequest(format!("Refund {id} was modified concurrently; retry")))?;
+
+    tracing::info!(
+        refund_id = %id,
+        action = payload.action.as_str(),
+        from = from.as_str(),
+        to = to.as_str(),
+        actor,
+        "Refund overridden by admin"
+    );
+
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(json: &str) -> RefundOverrideRequest {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_complete_requires_tx_hash() {
+        assert!(request(r#"{"action":"complete"}"#).validate().is_err());
+        assert!(request(r#"{"action":"complete","stellar_tx_hash":"abc"}"#)
+            .validate()
+            .is_err());
+        let hash = "a".repeat(64);
+        assert!(request(&format!(
+            r#"{{"action":"complete","stellar_tx_hash":"{hash}"}}"#
+        ))
+        .validate()
+        .is_ok());
+        assert!(request(&format!(
+            r#"{{"action":"cancel","stellar_tx_hash":"{hash}"}}"#
+        ))
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn test_refund_to_only_with_hold_or_release() {
+        let account = "GAAZI4TCR3TY5OJHCTJC2A4QSY6CJWJH5IAJTGKIN2ER7LBNVKOCCWN7";
+        assert!(
+            request(&format!(r#"{{"action":"cancel","refund_to":"{account}"}}"#))
+                .validate()
+                .is_err()
+        );
+        assert!(request(r#"{"action":"hold","refund_to":"BAD"}"#)
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_unknown_action_rejected() {
+        assert!(serde_json::from_str::<RefundOverrideRequest>(r#"{"action":"refund"}"#).is_err());
+    }
+}
@@ -0,0 +1,105 @@
+//! Multi-region active-passive role.
+//!
+//! | Method   | Path                  | Effect                                      |
+//! |----------|-----------------------|---------------------------------------------|
+//! | `GET`    | `/admin/region`       | Region name, effective and configured role  |
+//! | `PUT`    | `/admin/region/role`  | Override the role for the whole region      |
+//! | `DELETE` | `/admin/region/role`  | Return the region to `REGION_ROLE`          |
+//!
+//! `PUT` takes `{"role": "active"|"passive", "reason": "...", "actor": "..."}`;
+//! the reason is required. See [`crate::services::region`].
+
+use crate::error::AppError;
+use crate::services::region::{self, RegionRole, RoleOverride};
+use crate::validation::{validate_max_len, validate_required};
+use crate::ApiState;
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use chrono::Utc;
+use serde::Deserialize;
+
+const MAX_REASON_LEN: usize = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct SetRoleRequest {
+    pub role: RegionRole,
+    pub reason: String,
+    pub actor: Option<String>,
+}
+
+impl SetRoleRequest {
+    fn validate(&self) -> Result<(), AppError> {
+        validate_required("reason", self.reason.trim())
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        validate_max_len("reason", &self.reason, MAX_REASON_LEN)
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        Ok(())
+    }
+
+    fn into_override(self) -> RoleOverride {
+        RoleOverride {
+            role: self.role,
+            reason: self.reason.trim().to_string(),
+            actor: self
+                .actor
+                .filter(|a| !a.is_empty())
+                .unwrap_or_else(|| "admin".to_string()),
+            set_at: Utc::now(),
+        }
+    }
+}
+
+fn redis_client(state: &ApiState) -> Result<redis::Client, AppError> {
+    Ok(redis::Client::open(state.app_state.redis_url.as_str())?)
+}
+
+/// GET /admin/region
+pub async fn get_region() -> impl IntoResponse {
+    (StatusCode::OK, Json(region::region().status()))
+}
+
+/// PUT /admin/region/role
+pub async fn set_role(
+    State(state): State<ApiState>,
+    Json(payload): Json<SetRoleRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+    let current = region::region();
+    region::set_override(
+        &redis_client(&state)?,
+        current,
+        Some(payload.into_override()),
+    )
+    .await?;
+    Ok((StatusCode::OK, Json(current.status())))
+}
+
+/// DELETE /admin/region/role
+pub async fn clear_role(State(state): State<ApiState>) -> Result<impl IntoResponse, AppError> {
+    let current = region::region();
+    region::set_override(&redis_client(&state)?, current, None).await?;
+    Ok((StatusCode::OK, Json(current.status())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_role_request() {
+        let req: SetRoleRequest =
+            serde_json::from_str(r#"{"role":"passive","reason":"  "}"#).unwrap();
+        assert!(req.validate().is_err());
+
+        let req: SetRoleRequest =
+            serde_json::from_str(r#"{"role":"active","reason":" us-east-1 down "}"#).unwrap();
+        assert!(req.validate().is_ok());
+        let value = req.into_override();
+        assert_eq!(value.role, RegionRole::Active);
+        assert_eq!(value.reason, "us-east-1 down");
+        assert_eq!(value.actor, "admin");
+
+        assert!(
+            serde_json::from_str::<SetRoleRequest>(r#"{"role":"standby","reason":"x"}"#).is_err()
+        );
+    }
+}
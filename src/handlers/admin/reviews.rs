@@ -0,0 +1,219 @@
+//! The compliance review queue: held transactions and structuring reviews,
+//! with reviewer assignment, SLA timers and comment threads.
+//!
+//! | Method | Path                            | Effect                                        |
+//! |--------|---------------------------------|-----------------------------------------------|
+//! | `GET`  | `/admin/reviews`                | List items, soonest SLA deadline first        |
+//! | `GET`  | `/admin/reviews/:id`            | One item and its comments                     |
+//! | `POST` | `/admin/reviews/:id/claim`      | Assign the item to the caller                 |
+//! | `POST` | `/admin/reviews/:id/resolve`    | Decide the item and apply it to its subject   |
+//! | `POST` | `/admin/reviews/:id/comments`   | Add a comment (`201 Created`)                 |
+//!
+//! See [`crate::services::review_queue`] for how items are opened and what
+//! each resolution does. The authenticated admin principal is the reviewer or
+//! comment author, and every change is audit-logged.
+
+use crate::db::queries::{self, ReviewItemFilter};
+use crate::error::AppError;
+use crate::middleware::auth::AdminPrincipal;
+use crate::services::review_queue::{
+    self, Resolution, ReviewError, ReviewItemView, ReviewKind, ReviewState,
+};
+use crate::ApiState;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use std::str::FromStr;
+use uuid::Uuid;
+
+const MAX_LIST_LIMIT: i64 = 200;
+
+impl From<ReviewError> for AppError {
+    fn from(e: ReviewError) -> Self {
+        match e {
+            ReviewError::NotFound(_) => AppError::NotFound(e.to_string()),
+            ReviewError::Conflict(msg) => AppError::BadRequest(msg),
+            ReviewError::Invalid(msg) => AppError::Validation(msg),
+            ReviewError::Database(e) => e.into(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListReviewsQuery {
+    pub state: Option<String>,
+    pub kind: Option<String>,
+    pub assignee: Option<String>,
+    /// Only unresolved items past their SLA.
+    pub overdue: Option<bool>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResolveRequest {
+    pub resolution: Resolution,
+    /// Added to the item's comment thread.
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommentRequest {
+    pub body: String,
+}
+
+/// GET /admin/reviews
+pub async fn list_reviews(
+    State(state): State<ApiState>,
+    Query(q): Query<ListReviewsQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let review_state = q
+        .state
+        .as_deref()
+        .map(ReviewState::from_str)
+        .transpose()
+        .map_err(AppError::BadRequest)?;
+    let kind = q
+        .kind
+        .as_deref()
+        .map(ReviewKind::from_str)
+        .transpose()
+        .map_err(AppError::BadRequest)?;
+    let limit = q.limit.unwrap_or(50).clamp(1, MAX_LIST_LIMIT);
+    let offset = q.offset.unwrap_or(0).max(0);
+
+    let now = state.app_state.clock.now();
+    let items = queries::list_review_items(
+        &state.app_state.db,
+        &ReviewItemFilter {
+            state: review_state.as_ref().map(ReviewState::as_str),
+            kind: kind.as_ref().map(ReviewKind::as_str),
+            assignee: q.assignee.as_deref(),
+            overdue_only: q.overdue.unwrap_or(false),
+        },
+        now,
+        limit,
+        offset,
+    )
+    .await?;
+    let items: Vec<ReviewItemView> = items
+        .into_iter()
+        .map(|item| ReviewItemView::at(item, now))
+        .collect();
+
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "items": items,
+            "limit": limit,
+            "offset": offset,
+        })),
+    ))
+}
+
+/// GET /admin/reviews/:id
+pub async fn get_review(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let item = queries::get_review_item(&state.app_state.db, id)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => ReviewError::NotFound(id).into(),
+            other => AppError::from(other),
+        })?;
+    let comments = queries::list_review_comments(&state.app_state.db, id).await?;
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "item": ReviewItemView::at(item, state.app_state.clock.now()),
+            "comments": comments,
+        })),
+    ))
+}
+
+/// POST /admin/reviews/:id/claim
+pub async fn claim_review(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+    principal: AdminPrincipal,
+) -> Result<impl IntoResponse, AppError> {
+    let item = review_queue::claim(&state.app_state.db, id, &principal.name).await?;
+    tracing::info!(review_id = %id, reviewer = %principal.name, "Review item claimed");
+    Ok((
+        StatusCode::OK,
+        Json(ReviewItemView::at(item, state.app_state.clock.now())),
+    ))
+}
+
+/// POST /admin/reviews/:id/resolve
+pub async fn resolve_review(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+    principal: AdminPrincipal,
+    Json(payload): Json<ResolveRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let item = review_queue::resolve(
+        &state.app_state.db,
+        id,
+        &principal.name,
+        payload.resolution,
+        payload.comment.as_deref(),
+    )
+    .await?;
+    tracing::info!(
+        review_id = %id,
+        resolution = payload.resolution.as_str(),
+        reviewer = %principal.name,
+        "Review item resolved"
+    );
+    Ok((
+        StatusCode::OK,
+        Json(ReviewItemView::at(item, state.app_state.clock.now())),
+    ))
+}
+
+/// POST /admin/reviews/:id/comments
+pub async fn add_comment(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+    principal: AdminPrincipal,
+    Json(payload): Json<CommentRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let comment =
+        review_queue::add_comment(&state.app_state.db, id, &principal.name, &payload.body).await?;
+    Ok((StatusCode::CREATED, Json(comment)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_request_rejects_unknown_resolution() {
+        let ok: ResolveRequest = serde_json::from_str(r#"{"resolution":"approved"}"#).unwrap();
+        assert_eq!(ok.resolution, Resolution::Approved);
+        assert!(serde_json::from_str::<ResolveRequest>(r#"{"resolution":"closed"}"#).is_err());
+    }
+
+    #[test]
+    fn test_review_errors_map_to_status_codes() {
+        let id = Uuid::nil();
+        assert!(matches!(
+            AppError::from(ReviewError::NotFound(id)),
+            AppError::NotFound(_)
+        ));
+        assert!(matches!(
+            AppError::from(ReviewError::Conflict("assigned".to_string())),
+            AppError::BadRequest(_)
+        ));
+        assert!(matches!(
+            AppError::from(ReviewError::Invalid("comment".to_string())),
+            AppError::Validation(_)
+        ));
+    }
+}
@@ -0,0 +1,208 @@
+//! Rotation of inbound callback signing keys.
+//!
+//! | Method | Path                                            | Effect                        |
+//! |--------|-------------------------------------------------|-------------------------------|
+//! | `GET`  | `/admin/signing-keys`                           | Recent keys of every scope    |
+//! | `POST` | `/admin/signing-keys/rotate`                    | Rotate the global key         |
+//! | `POST` | `/admin/partners/:tenant_id/signing-keys/rotate`| Rotate one partner's key      |
+//!
+//! Rotation takes `{"secret"?, "activate_at"?, "overlap_hours"?, "actor"?}`.
+//! Without `secret` one is generated and returned once; it is never shown
+//! again. See [`crate::services::signing_keys`].
+
+use crate::db::queries::{self, NewSigningKey};
+use crate::error::AppError;
+use crate::services::signing_keys::{
+    self, SealingKey, SigningKeyView, DEFAULT_OVERLAP_HOURS, MAX_OVERLAP_HOURS,
+};
+use crate::ApiState;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+const MIN_SECRET_LEN: usize = 32;
+const LIST_LIMIT: i64 = 100;
+/// Clock skew tolerated on `activate_at` before it counts as in the past.
+const ACTIVATE_AT_SKEW_SECS: i64 = 60;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct RotateSigningKeyRequest {
+    /// Secret agreed with the anchor; generated when absent.
+    pub secret: Option<String>,
+    /// When the new key starts being accepted; now when absent.
+    pub activate_at: Option<DateTime<Utc>>,
+    /// How long after `activate_at` the current key is still accepted.
+    pub overlap_hours: Option<i64>,
+    pub actor: Option<String>,
+}
+
+impl RotateSigningKeyRequest {
+    fn validate(&self, now: DateTime<Utc>) -> Result<(), AppError> {
+        if let Some(secret) = &self.secret {
+            if secret.len() < MIN_SECRET_LEN {
+                return Err(AppError::Validation(format!(
+                    "secret must be at least {MIN_SECRET_LEN} characters"
+                )));
+            }
+        }
+        if let Some(hours) = self.overlap_hours {
+            if !(0..=MAX_OVERLAP_HOURS).contains(&hours) {
+                return Err(AppError::Validation(format!(
+                    "overlap_hours must be between 0 and {MAX_OVERLAP_HOURS}"
+                )));
+            }
+        }
+        if let Some(at) = self.activate_at {
+            if at < now - Duration::seconds(ACTIVATE_AT_SKEW_SECS) {
+                return Err(AppError::Validation(
+                    "activate_at must not be in the past".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RotatedSigningKey {
+    pub key: SigningKeyView,
+    /// Only set when the secret was generated.
+    pub secret: Option<String>,
+    /// When the key being replaced stops being accepted.
+    pub previous_expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListSigningKeysQuery {
+    pub tenant_id: Option<Uuid>,
+}
+
+async fn rotate(
+    state: &ApiState,
+    tenant_id: Option<Uuid>,
+    payload: RotateSigningKeyRequest,
+) -> Result<RotatedSigningKey, AppError> {
+    let now = Utc::now();
+    payload.validate(now)?;
+    let sealing = SealingKey::from_env().map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let (secret, generated) = match payload.secret {
+        Some(secret) => (secret, false),
+        None => (signing_keys::generate_secret(), true),
+    };
+    let active_from = payload.activate_at.unwrap_or(now).max(now);
+    let overlap = Duration::hours(payload.overlap_hours.unwrap_or(DEFAULT_OVERLAP_HOURS));
+    let actor = payload.actor.as_deref().unwrap_or("admin");
+    let fingerprint = signing_keys::fingerprint(&secret);
+
+    let key = queries::rotate_inbound_signing_key(
+        &state.app_state.db,
+        &NewSigningKey {
+            id: Uuid::new_v4(),
+            tenant_id,
+            sealed_secret: &sealing.seal(&secret),
+            fingerprint: &fingerprint,
+            active_from,
+            previous_expires_at: active_from + overlap,
+            actor,
+        },
+    )
+    .await?;
+    state.app_state.signing_keys.invalidate().await;
+
+    tracing::info!(
+        tenant_id = ?tenant_id,
+        fingerprint = %fingerprint,
+        active_from = %active_from,
+        previous_expires_at = %(active_from + overlap),
+        actor,
+        "Inbound signing key rotated"
+    );
+    Ok(RotatedSigningKey {
+        key: SigningKeyView::new(key, now),
+        secret: generated.then_some(secret),
+        previous_expires_at: active_from + overlap,
+    })
+}
+
+/// GET /admin/signing-keys
+pub async fn list_signing_keys(
+    State(state): State<ApiState>,
+    Query(query): Query<ListSigningKeysQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let now = Utc::now();
+    let keys: Vec<SigningKeyView> =
+        queries::list_inbound_signing_keys(&state.app_state.db, query.tenant_id, LIST_LIMIT)
+            .await?
+            .into_iter()
+            .map(|key| SigningKeyView::new(key, now))
+            .collect();
+    Ok((StatusCode::OK, Json(serde_json::json!({ "keys": keys }))))
+}
+
+/// POST /admin/signing-keys/rotate
+pub async fn rotate_global_signing_key(
+    State(state): State<ApiState>,
+    payload: Option<Json<RotateSigningKeyRequest>>,
+) -> Result<impl IntoResponse, AppError> {
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+    let rotated = rotate(&state, None, payload).await?;
+    Ok((StatusCode::CREATED, Json(rotated)))
+}
+
+/// POST /admin/partners/:tenant_id/signing-keys/rotate
+pub async fn rotate_partner_signing_key(
+    State(state): State<ApiState>,
+    Path(tenant_id): Path<Uuid>,
+    payload: Option<Json<RotateSigningKeyRequest>>,
+) -> Result<impl IntoResponse, AppError> {
+    if state.app_state.get_tenant_config(tenant_id).await.is_none() {
+        return Err(AppError::TenantNotFound);
+    }
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+    let rotated = rotate(&state, Some(tenant_id), payload).await?;
+    Ok((StatusCode::CREATED, Json(rotated)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotate_request_validation() {
+        let now = Utc::now();
+        assert!(RotateSigningKeyRequest::default().validate(now).is_ok());
+
+        let short = RotateSigningKeyRequest {
+            secret: Some("too-short".to_string()),
+            ..Default::default()
+        };
+        assert!(short.validate(now).is_err());
+
+        let long_overlap = RotateSigningKeyRequest {
+            overlap_hours: Some(MAX_OVERLAP_HOURS + 1),
+            ..Default::default()
+        };
+        assert!(long_overlap.validate(now).is_err());
+
+        let past = RotateSigningKeyRequest {
+            activate_at: Some(now - Duration::hours(1)),
+            ..Default::default()
+        };
+        assert!(past.validate(now).is_err());
+
+        let scheduled = RotateSigningKeyRequest {
+            secret: Some("x".repeat(MIN_SECRET_LEN)),
+            activate_at: Some(now + Duration::hours(6)),
+            overlap_hours: Some(48),
+            actor: Some("ops".to_string()),
+        };
+        assert!(scheduled.validate(now).is_ok());
+    }
+}
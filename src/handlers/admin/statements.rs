@@ -0,0 +1,125 @@
+//! Monthly partner statements.
+//!
+//! | Method | Path                | Effect                                          |
+//! |--------|---------------------|-------------------------------------------------|
+//! | `POST` | `/admin/statements` | Queue statements for a month (`202 Accepted`)   |
+//! | `GET`  | `/admin/statements` | List statements (`?period=&tenant_id=&limit=`)  |
+//!
+//! Generation takes `{"period": "YYYY-MM", "tenant_id"?}` and runs as a
+//! `partner_statement` job requested by the authenticated admin principal;
+//! follow it through `/admin/jobs/:id`.
+//! Listed statements carry signed `download_urls` for CSV and PDF, served by
+//! `/downloads/statements/:file`. See [`crate::services::statements`].
+
+use crate::db::models::PartnerStatement;
+use crate::db::queries;
+use crate::error::AppError;
+use crate::middleware::auth::AdminPrincipal;
+use crate::services::accounting::Period;
+use crate::services::job_runner::JobKind;
+use crate::services::statements::{self, StatementFormat, StatementParams};
+use crate::utils::signed_url;
+use crate::ApiState;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+
+const MAX_LIST_LIMIT: i64 = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateStatementsRequest {
+    #[serde(flatten)]
+    pub params: StatementParams,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListStatementsQuery {
+    pub period: Option<String>,
+    pub tenant_id: Option<Uuid>,
+    pub limit: Option<i64>,
+}
+
+/// Statement as returned by the API, with fresh download URLs when signing
+/// is configured.
+fn with_download_urls(statement: &PartnerStatement, now: DateTime<Utc>) -> serde_json::Value {
+    let mut body = serde_json::json!(statement);
+    if let Some(secret) = signed_url::signing_secret() {
+        let mut urls = serde_json::Map::new();
+        let mut expires = None;
+        for format in StatementFormat::ALL {
+            let (url, expires_at) = statements::download_url(&secret, statement.id, format, now);
+            urls.insert(format.as_str().to_string(), serde_json::json!(url));
+            expires = Some(expires_at);
+        }
+        body["download_urls"] = serde_json::Value::Object(urls);
+        body["download_expires_at"] = serde_json::json!(expires);
+    }
+    body
+}
+
+/// POST /admin/statements
+pub async fn create_statements(
+    State(state): State<ApiState>,
+    principal: AdminPrincipal,
+    Json(payload): Json<CreateStatementsRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.params.validate().map_err(AppError::BadRequest)?;
+
+    let params = serde_json::to_value(&payload.params)
+        .map_err(|e| AppError::Internal(format!("Failed to encode statement params: {e}")))?;
+    let job = queries::insert_job(
+        &state.app_state.db,
+        JobKind::PartnerStatement.as_str(),
+        &params,
+        Some(principal.name.as_str()),
+    )
+    .await?;
+
+    tracing::info!(job_id = %job.id, period = %payload.params.period, "Partner statements queued");
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+/// GET /admin/statements
+pub async fn list_statements(
+    State(state): State<ApiState>,
+    Query(q): Query<ListStatementsQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let period = q.period.as_deref().map(Period::parse).transpose()?;
+    let limit = q.limit.unwrap_or(100).clamp(1, MAX_LIST_LIMIT);
+
+    let found = queries::list_partner_statements(
+        &state.app_state.db,
+        period.map(|p| p.start()),
+        q.tenant_id,
+        limit,
+    )
+    .await?;
+    let now = Utc::now();
+    let statements: Vec<_> = found.iter().map(|s| with_download_urls(s, now)).collect();
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({ "statements": statements, "limit": limit })),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_request_flattens_params() {
+        let req: CreateStatementsRequest = serde_json::from_str(
+            r#"{"period":"2026-05","tenant_id":"6f1c2a9e-3d4b-4e5f-8a7b-9c0d1e2f3a4b"}"#,
+        )
+        .unwrap();
+        assert_eq!(req.params.period, "2026-05");
+        assert!(req.params.tenant_id.is_some());
+        assert!(serde_json::from_str::<CreateStatementsRequest>("{}").is_err());
+    }
+}
@@ -0,0 +1,267 @@
+//! Structuring rules and the review items they raise.
+//!
+//! | Method   | Path                                      | Effect                                   |
+//! |----------|-------------------------------------------|------------------------------------------|
+//! | `GET`    | `/admin/structuring/rules`                | List rules                               |
+//! | `POST`   | `/admin/structuring/rules`                | Create a rule (`201 Created`)            |
+//! | `GET`    | `/admin/structuring/rules/:id`            | One rule                                 |
+//! | `PUT`    | `/admin/structuring/rules/:id`            | Replace a rule's definition              |
+//! | `DELETE` | `/admin/structuring/rules/:id`            | Remove a rule and its review items       |
+//! | `GET`    | `/admin/structuring/reviews`              | List review items (`?status=&limit=&offset=`) |
+//! | `POST`   | `/admin/structuring/reviews/:id/resolve`  | `dismissed` or `escalated`               |
+//!
+//! See [`crate::services::structuring`] for how rules are evaluated. Every
+//! change is audit-logged with the authenticated admin principal as actor.
+
+use crate::db::queries;
+use crate::error::AppError;
+use crate::middleware::auth::AdminPrincipal;
+use crate::services::structuring::{ReviewStatus, RuleSpec, DEFAULT_WINDOW_HOURS};
+use crate::ApiState;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use sqlx::types::BigDecimal;
+use std::str::FromStr;
+use uuid::Uuid;
+
+const MAX_LIST_LIMIT: i64 = 200;
+
+#[derive(Debug, Deserialize)]
+pub struct RuleRequest {
+    pub name: String,
+    /// Omit to apply the rule to every asset.
+    pub asset_code: Option<String>,
+    pub window_hours: Option<i32>,
+    pub min_count: Option<i32>,
+    /// Decimal string.
+    pub min_total: Option<String>,
+    /// Decimal string; only deposits at or below it count.
+    pub max_single_amount: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+fn parse_amount(field: &str, value: Option<&str>) -> Result<Option<BigDecimal>, AppError> {
+    value
+        .map(|raw| {
+            BigDecimal::from_str(raw.trim())
+                .map_err(|_| AppError::Validation(format!("{field}: must be a valid decimal")))
+        })
+        .transpose()
+}
+
+impl RuleRequest {
+    fn spec(&self) -> Result<RuleSpec, AppError> {
+        RuleSpec {
+            name: self.name.clone(),
+            asset_code: self.asset_code.clone(),
+            window_hours: self.window_hours.unwrap_or(DEFAULT_WINDOW_HOURS),
+            min_count: self.min_count,
+            min_total: parse_amount("min_total", self.min_total.as_deref())?,
+            max_single_amount: parse_amount(
+                "max_single_amount",
+                self.max_single_amount.as_deref(),
+            )?,
+            enabled: self.enabled.unwrap_or(true),
+        }
+        .validate()
+        .map_err(AppError::Validation)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListReviewsQuery {
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResolveReviewRequest {
+    pub status: ReviewStatus,
+    pub note: Option<String>,
+}
+
+fn rule_not_found(id: Uuid) -> AppError {
+    AppError::NotFound(format!("Structuring rule {id} not found"))
+}
+
+/// Unique-name violations become a `400` rather than a `500`.
+fn map_write_error(e: sqlx::Error) -> AppError {
+    match e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => {
+            AppError::Validation("name: a rule with this name already exists".to_string())
+        }
+        other => other.into(),
+    }
+}
+
+/// GET /admin/structuring/rules
+pub async fn list_rules(State(state): State<ApiState>) -> Result<impl IntoResponse, AppError> {
+    let rules = queries::list_structuring_rules(&state.app_state.db, false).await?;
+    Ok((StatusCode::OK, Json(serde_json::json!({ "rules": rules }))))
+}
+
+/// POST /admin/structuring/rules
+pub async fn create_rule(
+    State(state): State<ApiState>,
+    principal: AdminPrincipal,
+    Json(payload): Json<RuleRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let spec = payload.spec()?;
+    let actor = principal.name.as_str();
+    let rule = queries::insert_structuring_rule(&state.app_state.db, &spec, actor)
+        .await
+        .map_err(map_write_error)?;
+    tracing::info!(rule_id = %rule.id, name = %rule.name, actor, "Structuring rule created");
+    Ok((StatusCode::CREATED, Json(rule)))
+}
+
+/// GET /admin/structuring/rules/:id
+pub async fn get_rule(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let rule = queries::get_structuring_rule(&state.app_state.db, id)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => rule_not_found(id),
+            other => other.into(),
+        })?;
+    Ok((StatusCode::OK, Json(rule)))
+}
+
+/// PUT /admin/structuring/rules/:id
+pub async fn update_rule(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+    principal: AdminPrincipal,
+    Json(payload): Json<RuleRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let spec = payload.spec()?;
+    let actor = principal.name.as_str();
+    let rule = queries::update_structuring_rule(&state.app_state.db, id, &spec, actor)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => rule_not_found(id),
+            other => map_write_error(other),
+        })?;
+    tracing::info!(rule_id = %id, actor, "Structuring rule updated");
+    Ok((StatusCode::OK, Json(rule)))
+}
+
+/// DELETE /admin/structuring/rules/:id
+pub async fn delete_rule(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+    principal: AdminPrincipal,
+) -> Result<impl IntoResponse, AppError> {
+    if !queries::delete_structuring_rule(&state.app_state.db, id, &principal.name).await? {
+        return Err(rule_not_found(id));
+    }
+    tracing::info!(rule_id = %id, actor = %principal.name, "Structuring rule deleted");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /admin/structuring/reviews
+pub async fn list_reviews(
+    State(state): State<ApiState>,
+    Query(q): Query<ListReviewsQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let status = match q.status.as_deref() {
+        Some(s) => Some(ReviewStatus::from_str(s).map_err(AppError::BadRequest)?),
+        None => None,
+    };
+    let limit = q.limit.unwrap_or(50).clamp(1, MAX_LIST_LIMIT);
+    let offset = q.offset.unwrap_or(0).max(0);
+
+    let reviews = queries::list_structuring_reviews(
+        &state.app_state.db,
+        status.as_ref().map(ReviewStatus::as_str),
+        limit,
+        offset,
+    )
+    .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "reviews": reviews,
+            "limit": limit,
+            "offset": offset,
+        })),
+    ))
+}
+
+/// POST /admin/structuring/reviews/:id/resolve
+pub async fn resolve_review(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+    principal: AdminPrincipal,
+    Json(payload): Json<ResolveReviewRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    if payload.status == ReviewStatus::Open {
+        return Err(AppError::Validation(
+            "status must be dismissed or escalated".to_string(),
+        ));
+    }
+    let actor = principal.name.as_str();
+    let review = queries::resolve_structuring_review(
+        &state.app_state.db,
+        id,
+        payload.status.as_str(),
+        payload.note.as_deref(),
+        actor,
+    )
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("No open structuring review {id}")))?;
+
+    tracing::info!(
+        review_id = %id,
+        status = payload.status.as_str(),
+        actor,
+        "Structuring review resolved"
+    );
+    Ok((StatusCode::OK, Json(review)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(json: &str) -> RuleRequest {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_rule_request_defaults() {
+        let spec = request(r#"{"name":"small deposits","min_count":10}"#)
+            .spec()
+            .unwrap();
+        assert_eq!(spec.window_hours, DEFAULT_WINDOW_HOURS);
+        assert!(spec.enabled);
+        assert_eq!(spec.asset_code, None);
+    }
+
+    #[test]
+    fn test_rule_request_parses_amounts() {
+        let spec =
+            request(r#"{"name":"near threshold","min_total":"9000","max_single_amount":"999.99"}"#)
+                .spec()
+                .unwrap();
+        assert_eq!(spec.min_total, Some(BigDecimal::from(9000)));
+        assert!(request(r#"{"name":"x","min_total":"lots"}"#)
+            .spec()
+            .is_err());
+        assert!(request(r#"{"name":"x"}"#).spec().is_err());
+    }
+
+    #[test]
+    fn test_resolve_rejects_unknown_status() {
+        assert!(serde_json::from_str::<ResolveReviewRequest>(r#"{"status":"closed"}"#).is_err());
+    }
+}
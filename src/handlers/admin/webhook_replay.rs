@@ -119,7 +119,7 @@ pub async fn list_failed_webhooks(
     // Build query to find transactions with failed status or in DLQ
     let mut query_builder = sqlx::QueryBuilder::new(
         "SELECT t.id, t.stellar_account, t.amount, t.asset_code, 
-                t.anchor_transaction_id, t.status, t.created_at,
+                t.anchor_transaction_id, t.status::text AS status, t.created_at,
                 COALESCE(d.retry_count, 0) as retry_count,
                 d.error_reason as last_error
          FROM transactions t
@@ -410,7 +410,7 @@ async fn reprocess_webhook(pool: &PgPool, transaction: &Transaction) -> Result<(
     // Update transaction status to pending for reprocessing
     sqlx::query(
         "UPDATE transactions 
-         SET status = 'pending', updated_at = NOW() 
+         SET status = 'pending', verification_attempts = 0, updated_at = NOW() 
          WHERE id = $1",
     )
     .bind(transaction.id)
@@ -0,0 +1,404 @@
+//! Outgoing webhook subscriber management.
+//!
+//! | Method   | Path                                             | Effect                                  |
+//! |----------|--------------------------------------------------|-----------------------------------------|
+//! | `POST`   | `/admin/webhook-subscriptions`                   | Register a subscriber (`201 Created`)   |
+//! | `GET`    | `/admin/webhook-subscriptions`                   | List subscribers                        |
+//! | `GET`    | `/admin/webhook-subscriptions/:id`               | One subscriber                          |
+//! | `DELETE` | `/admin/webhook-subscriptions/:id`               | Remove a subscriber and its deliveries  |
+//! | `POST`   | `/admin/webhook-subscriptions/:id/rotate-secret` | Issue a new signing secret              |
+//! | `POST`   | `/admin/webhook-subscriptions/:id/test`          | Send a signed `ping` event              |
+//! | `POST`   | `/admin/webhook-subscriptions/:id/enable`        | Re-enable a paused subscriber           |
+//!
+//! A subscription is a row in `webhook_endpoints`; the list leaves out rows
+//! bound to a single transaction (GraphQL `registerTransactionCallback`),
+//! which are otherwise managed the same way. The signing secret is
+//! generated server-side and returned only by create and rotate-secret. After a
+//! rotation the old secret keeps signing deliveries alongside the new one for
+//! `grace_period_hours` (see
+//! [`crate::services::webhook_dispatcher::WebhookDispatcher`]).
+//!
+//! A subscriber whose deliveries fail without a single success for
+//! `WEBHOOK_AUTO_PAUSE_AFTER_HOURS` (default 24) is paused by the dispatcher:
+//! `enabled` turns `false` and `paused_at` / `pause_reason` say why. Its queued
+//! deliveries are kept and resume once `enable` is called.
+
+use crate::db::queries;
+use crate::error::AppError;
+use crate::services::webhook_dispatcher::{
+    generate_secret, is_valid_endpoint_url, WebhookDispatcher, WebhookEndpoint,
+};
+use crate::ApiState;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Deliveries per minute when the request does not set `max_delivery_rate`.
+const DEFAULT_MAX_DELIVERY_RATE: i32 = 10;
+
+const DEFAULT_GRACE_PERIOD_HOURS: i64 = 24;
+const MAX_GRACE_PERIOD_HOURS: i64 = 168;
+
+/// Keys understood by [`WebhookDispatcher::matches_filters`].
+const FILTER_RULE_KEYS: &[&str] = &["asset_codes", "min_amount", "max_amount"];
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CreateSubscriptionRequest {
+    pub url: String,
+    /// Events to deliver, e.g. `transaction.completed`.
+    pub event_types: Vec<String>,
+    pub max_delivery_rate: Option<i32>,
+    pub filter_rules: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct RotateSecretRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grace_period_hours: Option<i64>,
+}
+
+/// A subscriber as returned by the admin API; never includes secrets.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubscriptionView {
+    pub id: Uuid,
+    pub url: String,
+    pub event_types: Vec<String>,
+    pub enabled: bool,
+    pub max_delivery_rate: i32,
+    pub filter_rules: Option<serde_json::Value>,
+    /// Set for a callback registered on one transaction through GraphQL.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_id: Option<Uuid>,
+    /// Set while a rotated-out secret still signs deliveries.
+    pub previous_secret_expires_at: Option<DateTime<Utc>>,
+    /// First failed delivery since the last success.
+    pub failing_since: Option<DateTime<Utc>>,
+    pub paused_at: Option<DateTime<Utc>>,
+    pub pause_reason: Option<String>,
+    /// Sequence up to which every delivery has been acknowledged or
+    /// dead-lettered.
+    #[serde(default)]
+    pub acked_sequence: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl SubscriptionView {
+    fn new(endpoint: WebhookEndpoint, now: DateTime<Utc>) -> Self {
+        Self {
+            id: endpoint.id,
+            url: endpoint.url,
+            event_types: endpoint.event_types,
+            enabled: endpoint.enabled,
+            max_delivery_rate: endpoint.max_delivery_rate,
+            filter_rules: endpoint.filter_rules,
+            transaction_id: endpoint.transaction_id,
+            previous_secret_expires_at: endpoint
+                .previous_secret_expires_at
+                .filter(|expires_at| *expires_at > now),
+            failing_since: endpoint.failing_since,
+            paused_at: endpoint.paused_at,
+            pause_reason: endpoint.pause_reason,
+            acked_sequence: endpoint.acked_sequence,
+            created_at: endpoint.created_at,
+            updated_at: endpoint.updated_at,
+        }
+    }
+}
+
+/// Create and rotate-secret responses: the subscription plus its new secret.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubscriptionWithSecret {
+    #[serde(flatten)]
+    pub subscription: SubscriptionView,
+    pub secret: String,
+}
+
+fn validate_url(url: &str) -> Result<(), AppError> {
+    if is_valid_endpoint_url(url) {
+        Ok(())
+    } else {
+        Err(AppError::Validation(format!(
+            "url: '{url}' is not an absolute http(s) URL"
+        )))
+    }
+}
+
+/// Event types are dot-separated lower-case segments (`transaction.completed`);
+/// duplicates are dropped, order preserved.
+fn normalize_event_types(event_types: &[String]) -> Result<Vec<String>, AppError> {
+    let mut normalized: Vec<String> = Vec::with_capacity(event_types.len());
+    for event_type in event_types {
+        let event_type = event_type.trim();
+        let valid = !event_type.is_empty()
+            && event_type.split('.').all(|segment| {
+                !segment.is_empty()
+                    && segment
+                        .chars()
+                        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+            });
+        if !valid {
+            return Err(AppError::Validation(format!(
+                "event_types: '{event_type}' is not a valid event type"
+            )));
+        }
+        if !normalized.iter().any(|e| e == event_type) {
+            normalized.push(event_type.to_string());
+        }
+    }
+    if normalized.is_empty() {
+        return Err(AppError::Validation(
+            "event_types must name at least one event".to_string(),
+        ));
+    }
+    Ok(normalized)
+}
+
+fn validate_filter_rules(rules: &serde_json::Value) -> Result<(), AppError> {
+    let Some(map) = rules.as_object() else {
+        return Err(AppError::Validation(
+            "filter_rules must be an object".to_string(),
+        ));
+    };
+    match map.keys().find(|k| !FILTER_RULE_KEYS.contains(&k.as_str())) {
+        Some(key) => Err(AppError::Validation(format!(
+            "filter_rules: unknown filter '{key}'"
+        ))),
+        None => Ok(()),
+    }
+}
+
+fn not_found(id: Uuid) -> AppError {
+    AppError::NotFound(format!("Webhook subscription {id} not found"))
+}
+
+/// POST /admin/webhook-subscriptions
+pub async fn create_subscription(
+    State(state): State<ApiState>,
+    Json(payload): Json<CreateSubscriptionRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    validate_url(&payload.url)?;
+    let event_types = normalize_event_types(&payload.event_types)?;
+    let max_delivery_rate = payload
+        .max_delivery_rate
+        .unwrap_or(DEFAULT_MAX_DELIVERY_RATE);
+    if max_delivery_rate <= 0 {
+        return Err(AppError::Validation(
+            "max_delivery_rate must be greater than 0".to_string(),
+        ));
+    }
+    if let Some(rules) = &payload.filter_rules {
+        validate_filter_rules(rules)?;
+    }
+
+    let secret = generate_secret();
+    let endpoint = queries::insert_webhook_endpoint(
+        &state.app_state.db,
+        &payload.url,
+        &secret,
+        &event_types,
+        max_delivery_rate,
+        payload.filter_rules.as_ref(),
+    )
+    .await?;
+
+    tracing::info!(subscription_id = %endpoint.id, "Webhook subscription created");
+    Ok((
+        StatusCode::CREATED,
+        Json(SubscriptionWithSecret {
+            subscription: SubscriptionView::new(endpoint, Utc::now()),
+            secret,
+        }),
+    ))
+}
+
+/// GET /admin/webhook-subscriptions
+pub async fn list_subscriptions(
+    State(state): State<ApiState>,
+) -> Result<impl IntoResponse, AppError> {
+    let now = Utc::now();
+    let subscriptions: Vec<SubscriptionView> = queries::list_webhook_endpoints(&state.app_state.db)
+        .await?
+        .into_iter()
+        .map(|endpoint| SubscriptionView::new(endpoint, now))
+        .collect();
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({ "subscriptions": subscriptions })),
+    ))
+}
+
+async fn load(state: &ApiState, id: Uuid) -> Result<WebhookEndpoint, AppError> {
+    queries::get_webhook_endpoint(&state.app_state.db, id)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => not_found(id),
+            other => other.into(),
+        })
+}
+
+/// GET /admin/webhook-subscriptions/:id
+pub async fn get_subscription(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let endpoint = load(&state, id).await?;
+    Ok((
+        StatusCode::OK,
+        Json(SubscriptionView::new(endpoint, Utc::now())),
+    ))
+}
+
+/// DELETE /admin/webhook-subscriptions/:id
+pub async fn delete_subscription(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    if !queries::delete_webhook_endpoint(&state.app_state.db, id).await? {
+        return Err(not_found(id));
+    }
+    tracing::info!(subscription_id = %id, "Webhook subscription deleted");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /admin/webhook-subscriptions/:id/rotate-secret
+pub async fn rotate_secret(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+    payload: Option<Json<RotateSecretRequest>>,
+) -> Result<impl IntoResponse, AppError> {
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+    let grace_hours = payload
+        .grace_period_hours
+        .unwrap_or(DEFAULT_GRACE_PERIOD_HOURS);
+    if !(0..=MAX_GRACE_PERIOD_HOURS).contains(&grace_hours) {
+        return Err(AppError::Validation(format!(
+            "grace_period_hours must be between 0 and {MAX_GRACE_PERIOD_HOURS}"
+        )));
+    }
+
+    let now = Utc::now();
+    let secret = generate_secret();
+    let endpoint = queries::rotate_webhook_endpoint_secret(
+        &state.app_state.db,
+        id,
+        &secret,
+        now + Duration::hours(grace_hours),
+    )
+    .await?
+    .ok_or_else(|| not_found(id))?;
+
+    tracing::info!(
+        subscription_id = %id,
+        grace_period_hours = grace_hours,
+        "Webhook subscription secret rotated"
+    );
+    Ok((
+        StatusCode::OK,
+        Json(SubscriptionWithSecret {
+            subscription: SubscriptionView::new(endpoint, now),
+            secret,
+        }),
+    ))
+}
+
+/// POST /admin/webhook-subscriptions/:id/enable
+pub async fn enable_subscription(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let endpoint = queries::enable_webhook_endpoint(&state.app_state.db, id)
+        .await?
+        .ok_or_else(|| not_found(id))?;
+    tracing::info!(subscription_id = %id, "Webhook subscription enabled");
+    Ok((
+        StatusCode::OK,
+        Json(SubscriptionView::new(endpoint, Utc::now())),
+    ))
+}
+
+/// POST /admin/webhook-subscriptions/:id/test
+pub async fn test_subscription(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let endpoint = load(&state, id).await?;
+    let dispatcher =
+        WebhookDispatcher::new(state.app_state.db.clone(), &state.app_state.redis_url)?;
+    let result = dispatcher.send_ping(&endpoint).await;
+    Ok((StatusCode::OK, Json(result)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint() -> WebhookEndpoint {
+        WebhookEndpoint {
+            id: Uuid::new_v4(),
+            url: "https://example.com/hook".to_string(),
+            secret: "whsec_current".to_string(),
+            event_types: vec!["transaction.completed".to_string()],
+            enabled: true,
+            max_delivery_rate: 10,
+            filter_rules: None,
+            transaction_id: None,
+            previous_secret: Some("whsec_previous".to_string()),
+            previous_secret_expires_at: None,
+            failing_since: None,
+            paused_at: None,
+            pause_reason: None,
+            acked_sequence: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_view_hides_secrets_and_expired_rotation() {
+        let now = Utc::now();
+        let mut ep = endpoint();
+        ep.previous_secret_expires_at = Some(now + Duration::hours(1));
+        let json = serde_json::to_value(SubscriptionView::new(ep.clone(), now)).unwrap();
+        assert!(json.get("secret").is_none());
+        assert!(json.get("previous_secret").is_none());
+        assert!(!json["previous_secret_expires_at"].is_null());
+
+        ep.previous_secret_expires_at = Some(now - Duration::hours(1));
+        let view = SubscriptionView::new(ep, now);
+        assert!(view.previous_secret_expires_at.is_none());
+    }
+
+    #[test]
+    fn test_normalize_event_types() {
+        let types = vec![
+            "transaction.completed".to_string(),
+            " transaction.completed ".to_string(),
+            "transaction.refund_pending".to_string(),
+        ];
+        assert_eq!(
+            normalize_event_types(&types).unwrap(),
+            vec!["transaction.completed", "transaction.refund_pending"]
+        );
+        assert!(normalize_event_types(&[]).is_err());
+        assert!(normalize_event_types(&["Transaction.Completed".to_string()]).is_err());
+        assert!(normalize_event_types(&["transaction..completed".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_validate_url_and_filter_rules() {
+        assert!(validate_url("https://example.com/hook").is_ok());
+        assert!(validate_url("ftp://example.com/hook").is_err());
+        assert!(validate_url("/relative/hook").is_err());
+
+        assert!(validate_filter_rules(&serde_json::json!({"asset_codes": ["USD"]})).is_ok());
+        assert!(validate_filter_rules(&serde_json::json!({"tenant": "x"})).is_err());
+        assert!(validate_filter_rules(&serde_json::json!(["USD"])).is_err());
+    }
+}
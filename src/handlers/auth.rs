@@ -1,97 +1,154 @@
+//! SEP-10 web authentication.
+//!
+//! | Method | Path    | Effect                                          |
+//! |--------|---------|-------------------------------------------------|
+//! | `GET`  | `/auth` | Challenge for `?account=G...[&home_domain=..]`  |
+//! | `POST` | `/auth` | Exchange a signed challenge for a JWT           |
+//!
+//! See [`crate::services::sep10`] for the checks and configuration. Other
+//! SEP endpoints authenticate with the issued token through [`sep10_claims`].
+
+use crate::domain::StellarAddress;
+use crate::error::AppError;
+use crate::services::sep10::{self, Sep10Claims, Sep10Config};
+use crate::stellar::HorizonError;
+use crate::ApiState;
 use axum::{
-    async_trait,
-    body::Body,
-    extract::{FromRequest, Request},
-    http::{HeaderMap, StatusCode},
-    response::{IntoResponse, Response},
+    extract::{Query, State},
+    http::{header::AUTHORIZATION, HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
 };
-use hmac::{Hmac, Mac};
-use sha2::Sha256;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 
-type HmacSha256 = Hmac<Sha256>;
+/// Horizon's type for ed25519 account signers.
+const ED25519_SIGNER: &str = "ed25519_public_key";
 
-/// Extractor that verifies the X-Stellar-Signature header
-/// against the request body using HMAC-SHA256
-pub struct VerifiedWebhook {
-    pub body: Vec<u8>,
+#[derive(Debug, Deserialize)]
+pub struct ChallengeQuery {
+    pub account: String,
+    pub home_domain: Option<String>,
 }
 
-impl VerifiedWebhook {
-    /// Verify the signature using constant-time comparison
-    fn verify_signature(secret: &str, body: &[u8], signature_header: &str) -> Result<(), AuthError> {
-        // Decode the hex signature from the header
-        let expected_signature = hex::decode(signature_header)
-            .map_err(|_| AuthError::InvalidSignatureFormat)?;
+#[derive(Debug, Serialize)]
+pub struct ChallengeResponse {
+    /// Base64 XDR of the server-signed challenge transaction.
+    pub transaction: String,
+    pub network_passphrase: String,
+}
 
-        // Compute HMAC-SHA256 of the body
-        let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
-            .map_err(|_| AuthError::InvalidSecret)?;
-        mac.update(body);
+#[derive(Debug, Deserialize)]
+pub struct TokenRequest {
+    /// The challenge, signed by the client, as base64 XDR.
+    pub transaction: String,
+}
 
-        // Constant-time comparison to prevent timing attacks
-        mac.verify_slice(&expected_signature)
-            .map_err(|_| AuthError::SignatureMismatch)?;
+#[derive(Debug, Serialize)]
+pub struct TokenResponse {
+    pub token: String,
+}
 
-        Ok(())
-    }
+fn config() -> Result<Sep10Config, AppError> {
+    Sep10Config::from_env()
+        .map_err(|e| AppError::Internal(format!("SEP-10 is not configured: {e}")))
 }
 
-#[async_trait]
-impl FromRequest<crate::AppState> for VerifiedWebhook {
-    type Rejection = AuthError;
+fn bearer_token(headers: &HeaderMap) -> Result<&str, AppError> {
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .ok_or_else(|| AppError::Unauthorized("SEP-10 bearer token required".to_string()))
+}
 
-    async fn from_request(req: Request, state: &crate::AppState) -> Result<Self, Self::Rejection> {
-        let headers = req.headers().clone();
-        
-        // Extract the signature header
-        let signature = headers
-            .get("X-Stellar-Signature")
-            .and_then(|v| v.to_str().ok())
-            .ok_or(AuthError::MissingSignature)?;
+/// Claims of the SEP-10 token sent as `Authorization: Bearer <jwt>`.
+pub fn sep10_claims(headers: &HeaderMap, now: DateTime<Utc>) -> Result<Sep10Claims, AppError> {
+    let config = config()?;
+    sep10::verify_token(&config, bearer_token(headers)?, now)
+}
 
-        // Extract the body
-        let body_bytes = axum::body::to_bytes(req.into_body(), usize::MAX)
-            .await
-            .map_err(|_| AuthError::BodyReadError)?
-            .to_vec();
+/// GET /auth
+pub async fn challenge(
+    State(state): State<ApiState>,
+    Query(query): Query<ChallengeQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let config = config()?;
+    let account = StellarAddress::parse(&query.account)
+        .map_err(|e| AppError::BadRequest(format!("account {e}")))?;
+    let transaction = sep10::build_challenge(
+        &config,
+        &account,
+        query.home_domain.as_deref(),
+        state.app_state.clock.now(),
+    )?;
+    Ok((
+        StatusCode::OK,
+        Json(ChallengeResponse {
+            transaction,
+            network_passphrase: config.network_passphrase,
+        }),
+    ))
+}
 
-        // Verify the signature
-        Self::verify_signature(&state.config.anchor_webhook_secret, &body_bytes, signature)?;
+/// POST /auth
+pub async fn token(
+    State(state): State<ApiState>,
+    Json(payload): Json<TokenRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let config = config()?;
+    let now = state.app_state.clock.now();
+    let challenge = sep10::read_challenge(&config, &payload.transaction, now)?;
 
-        Ok(VerifiedWebhook { body: body_bytes })
-    }
-}
+    // An account on the network signs with its own signers; one that does
+    // not exist yet can only sign with its master key.
+    let (signers, threshold) = match state
+        .app_state
+        .horizon_client
+        .get_account(challenge.client.account())
+        .await
+    {
+        Ok(account) => {
+            let signers: Vec<([u8; 32], u8)> = account
+                .signers
+                .iter()
+                .filter(|s| s.signer_type == ED25519_SIGNER && s.weight > 0)
+                .filter_map(|s| {
+                    StellarAddress::parse(&s.key)
+                        .ok()
+                        .map(|key| (key.public_key(), s.weight))
+                })
+                .collect();
+            (signers, account.thresholds.med_threshold)
+        }
+        Err(HorizonError::AccountNotFound(_)) => (vec![(challenge.client.public_key(), 1)], 0),
+        Err(e) => {
+            return Err(AppError::Internal(format!(
+                "could not load account signers: {e}"
+            )))
+        }
+    };
+    challenge.verify_signers(&config, &signers, threshold)?;
 
-#[derive(Debug)]
-pub enum AuthError {
-    MissingSignature,
-    InvalidSignatureFormat,
-    InvalidSecret,
-    SignatureMismatch,
-    BodyReadError,
+    let token = sep10::issue_token(&config, &challenge, now);
+    tracing::info!(account = %challenge.client, "SEP-10 token issued");
+    Ok((StatusCode::OK, Json(TokenResponse { token })))
 }
 
-impl IntoResponse for AuthError {
-    fn into_response(self) -> Response {
-        let (status, message) = match self {
-            AuthError::MissingSignature => {
-                (StatusCode::UNAUTHORIZED, "Missing X-Stellar-Signature header")
-            }
-            AuthError::InvalidSignatureFormat => {
-                (StatusCode::UNAUTHORIZED, "Invalid signature format")
-            }
-            AuthError::InvalidSecret => {
-                (StatusCode::INTERNAL_SERVER_ERROR, "Invalid webhook secret configuration")
-            }
-            AuthError::SignatureMismatch => {
-                (StatusCode::UNAUTHORIZED, "Signature verification failed")
-            }
-            AuthError::BodyReadError => {
-                (StatusCode::BAD_REQUEST, "Failed to read request body")
-            }
-        };
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
 
-        tracing::warn!("Webhook authentication failed: {:?}", self);
-        (status, message).into_response()
+    #[test]
+    fn test_bearer_token() {
+        let mut headers = HeaderMap::new();
+        assert!(bearer_token(&headers).is_err());
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Basic abc"));
+        assert!(bearer_token(&headers).is_err());
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer a.b.c"));
+        assert_eq!(bearer_token(&headers).unwrap(), "a.b.c");
     }
 }
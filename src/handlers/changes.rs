@@ -0,0 +1,232 @@
+//! `GET /transactions/changes`: incremental sync feed.
+//!
+//! Returns every transaction created or updated after a point, plus a
+//! tombstone for each one deleted or archived since, in `(changed_at, id)`
+//! order. A consumer starts with `since`, then keeps passing back
+//! `next_cursor` (returned even on an empty page) to pick up where it left
+//! off. Changes from the last [`SETTLE_WINDOW_SECS`] seconds are held back:
+//! `updated_at` is stamped when a write starts, not when it commits, so a
+//! slow write could otherwise land behind a cursor that already moved past it.
+//!
+//! Reads always go to the primary: a lagging replica could be missing writes
+//! older than the settle window.
+
+use crate::db::models::{Transaction, TransactionTombstone};
+use crate::db::queries;
+use crate::error::AppError;
+use crate::utils::cursor as cursor_util;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+const DEFAULT_LIMIT: i64 = 100;
+const MAX_LIMIT: i64 = 500;
+
+/// How many seconds behind now the feed stops.
+pub const SETTLE_WINDOW_SECS: i64 = 5;
+
+#[derive(Debug, Deserialize)]
+pub struct ChangesQuery {
+    /// RFC 3339 start of the first page; ignored once `cursor` is passed.
+    pub since: Option<String>,
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+    pub tenant_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// Created or updated; `transaction` holds the current row.
+    Upsert,
+    Deleted,
+    /// Moved out with its partition by retention.
+    Archived,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionChange {
+    pub change: ChangeKind,
+    pub transaction_id: Uuid,
+    pub changed_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<Transaction>,
+}
+
+impl TransactionChange {
+    fn key(&self) -> (DateTime<Utc>, Uuid) {
+        (self.changed_at, self.transaction_id)
+    }
+}
+
+impl From<Transaction> for TransactionChange {
+    fn from(tx: Transaction) -> Self {
+        Self {
+            change: ChangeKind::Upsert,
+            transaction_id: tx.id,
+            changed_at: tx.updated_at,
+            transaction: Some(tx),
+        }
+    }
+}
+
+impl From<TransactionTombstone> for TransactionChange {
+    fn from(tombstone: TransactionTombstone) -> Self {
+        Self {
+            change: if tombstone.reason == "archived" {
+                ChangeKind::Archived
+            } else {
+                ChangeKind::Deleted
+            },
+            transaction_id: tombstone.transaction_id,
+            changed_at: tombstone.removed_at,
+            transaction: None,
+        }
+    }
+}
+
+/// Merge two `(changed_at, id)`-ordered pages into one of at most `limit`
+/// changes, and whether more remain. Changes sharing the last key stay on the
+/// same page, since the next cursor starts strictly after it.
+fn merge(
+    upserts: Vec<Transaction>,
+    tombstones: Vec<TransactionTombstone>,
+    limit: usize,
+) -> (Vec<TransactionChange>, bool) {
+    let mut changes: Vec<TransactionChange> = upserts
+        .into_iter()
+        .map(TransactionChange::from)
+        .chain(tombstones.into_iter().map(TransactionChange::from))
+        .collect();
+    // Stable, so an upsert sorts before a tombstone with the same key.
+    changes.sort_by_key(TransactionChange::key);
+
+    let mut end = changes.len().min(limit);
+    if end > 0 {
+        let last = changes[end - 1].key();
+        while end < changes.len() && changes[end].key() == last {
+            end += 1;
+        }
+    }
+    let has_more = changes.len() > end;
+    changes.truncate(end);
+    (changes, has_more)
+}
+
+pub async fn transaction_changes(
+    State(state): State<crate::ApiState>,
+    Query(params): Query<ChangesQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let after = match (&params.cursor, &params.since) {
+        (Some(cursor), _) => cursor_util::decode(cursor)
+            .map_err(|e| AppError::BadRequest(format!("invalid cursor: {e}")))?,
+        (None, Some(since)) => {
+            let since = DateTime::parse_from_rfc3339(since)
+                .map_err(|_| {
+                    AppError::BadRequest(format!("invalid since: '{since}', expected RFC 3339"))
+                })?
+                .with_timezone(&Utc);
+            // Nil sorts first, so every change at exactly `since` is included.
+            (since, Uuid::nil())
+        }
+        (None, None) => {
+            return Err(AppError::BadRequest(
+                "either since or cursor is required".to_string(),
+            ))
+        }
+    };
+    let until = Utc::now() - chrono::Duration::seconds(SETTLE_WINDOW_SECS);
+
+    let pool = &state.app_state.db;
+    let (upserts, tombstones) = tokio::try_join!(
+        queries::list_transaction_changes(pool, after, until, params.tenant_id, limit + 1),
+        queries::list_transaction_tombstones(pool, after, until, params.tenant_id, limit + 1),
+    )?;
+    let (changes, has_more) = merge(upserts, tombstones, limit as usize);
+
+    let (ts, id) = changes.last().map_or(after, TransactionChange::key);
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "data": changes,
+            "meta": {
+                "next_cursor": cursor_util::encode(ts, id),
+                "has_more": has_more
+            }
+        })),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bigdecimal::BigDecimal;
+
+    fn upsert(at: DateTime<Utc>) -> Transaction {
+        let mut tx = Transaction::new(
+            "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ".to_string(),
+            BigDecimal::from(10),
+            "USDC".to_string(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        tx.updated_at = at;
+        tx
+    }
+
+    fn tombstone(id: Uuid, at: DateTime<Utc>, reason: &str) -> TransactionTombstone {
+        TransactionTombstone {
+            transaction_id: id,
+            tenant_id: None,
+            reason: reason.to_string(),
+            removed_at: at,
+        }
+    }
+
+    #[test]
+    fn test_merge_orders_by_change_time() {
+        let t0 = Utc::now();
+        let a = upsert(t0);
+        let b = upsert(t0 + chrono::Duration::seconds(2));
+        let gone = tombstone(
+            Uuid::new_v4(),
+            t0 + chrono::Duration::seconds(1),
+            "archived",
+        );
+
+        let (changes, has_more) = merge(vec![a.clone(), b.clone()], vec![gone.clone()], 2);
+        assert!(has_more);
+        let ids: Vec<_> = changes.iter().map(|c| c.transaction_id).collect();
+        assert_eq!(ids, vec![a.id, gone.transaction_id]);
+        assert_eq!(changes[1].change, ChangeKind::Archived);
+        assert!(changes[1].transaction.is_none());
+
+        let (changes, has_more) = merge(vec![a, b], vec![gone], 10);
+        assert!(!has_more);
+        assert_eq!(changes.len(), 3);
+    }
+
+    #[test]
+    fn test_merge_keeps_same_key_changes_together() {
+        let t0 = Utc::now();
+        let tx = upsert(t0);
+        let deleted = tombstone(tx.id, t0, "deleted");
+
+        let (changes, has_more) = merge(vec![tx], vec![deleted], 1);
+        assert!(!has_more);
+        let kinds: Vec<_> = changes.iter().map(|c| c.change).collect();
+        assert_eq!(kinds, vec![ChangeKind::Upsert, ChangeKind::Deleted]);
+    }
+}
@@ -1,21 +1,77 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     response::{IntoResponse, Json},
     routing::{get, post},
     Router,
 };
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use sqlx::PgPool;
 use uuid::Uuid;
 
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+
 use crate::db::models::TransactionDlq;
 use crate::error::AppError;
+use crate::services::dlq_errors::{AlertThresholds, ClassAlert, ErrorClass};
 use crate::services::TransactionProcessor;
 
+const DEFAULT_STATS_WINDOW_HOURS: i64 = 24;
+const MAX_STATS_WINDOW_HOURS: i64 = 24 * 90;
+const STATS_GROUP_LIMIT: i64 = 50;
+
+/// Body of `GET /dlq`: the 100 most recent entries.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DlqListResponse {
+    pub dlq_entries: Vec<TransactionDlq>,
+    pub count: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DlqStatsQuery {
+    /// Look back this many hours (default 24, at most 90 days).
+    pub window_hours: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ClassCount {
+    pub class: String,
+    pub count: i64,
+}
+
+/// Entries sharing an error class and signature.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ErrorGroup {
+    pub class: String,
+    /// `null` groups rows moved to the DLQ before classification existed.
+    pub signature: Option<String>,
+    pub count: i64,
+    /// Most recent reason in the group.
+    pub sample_reason: String,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Body of `GET /admin/dlq/stats`.
+#[derive(Debug, Serialize)]
+pub struct DlqStatsResponse {
+    pub window_hours: i64,
+    pub total: i64,
+    /// Every class, including those with no entries.
+    pub by_class: Vec<ClassCount>,
+    /// Largest groups first, at most 50.
+    pub groups: Vec<ErrorGroup>,
+    /// Classes above their `DLQ_ALERT_THRESHOLDS` count in the window.
+    pub alerts: Vec<ClassAlert>,
+}
+
+/// DLQ admin routes; the admin router puts them behind the admin key.
 pub fn dlq_routes() -> Router<PgPool> {
     Router::new()
         .route("/dlq", get(list_dlq))
         .route("/dlq/:id/requeue", post(requeue_dlq))
+        .route("/admin/dlq/stats", get(dlq_stats))
 }
 
 async fn list_dlq(State(pool): State<PgPool>) -> Result<impl IntoResponse, AppError> {
@@ -25,10 +81,78 @@ async fn list_dlq(State(pool): State<PgPool>) -> Result<impl IntoResponse, AppEr
     .fetch_all(&pool)
     .await?;
 
-    Ok(Json(json!({
-        "dlq_entries": entries,
-        "count": entries.len()
-    })))
+    Ok(Json(DlqListResponse {
+        count: entries.len(),
+        dlq_entries: entries,
+    }))
+}
+
+async fn dlq_stats(
+    State(pool): State<PgPool>,
+    Query(q): Query<DlqStatsQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let window_hours = q
+        .window_hours
+        .unwrap_or(DEFAULT_STATS_WINDOW_HOURS)
+        .clamp(1, MAX_STATS_WINDOW_HOURS);
+    let since = Utc::now() - Duration::hours(window_hours);
+
+    let class_rows: Vec<(String, i64)> = sqlx::query_as(
+        "SELECT error_class, COUNT(*) FROM transaction_dlq \
+         WHERE moved_to_dlq_at >= $1 GROUP BY error_class",
+    )
+    .bind(since)
+    .fetch_all(&pool)
+    .await?;
+    let counts: HashMap<ErrorClass, i64> = class_rows
+        .iter()
+        .filter_map(|(class, count)| Some((class.parse().ok()?, *count)))
+        .collect();
+
+    let groups = sqlx::query_as::<_, ErrorGroup>(
+        r#"
+        SELECT error_class AS class,
+               error_signature AS signature,
+               COUNT(*) AS count,
+               (ARRAY_AGG(error_reason ORDER BY moved_to_dlq_at DESC))[1] AS sample_reason,
+               MIN(moved_to_dlq_at) AS first_seen,
+               MAX(moved_to_dlq_at) AS last_seen
+        FROM transaction_dlq
+        WHERE moved_to_dlq_at >= $1
+        GROUP BY error_class, error_signature
+        ORDER BY count DESC, last_seen DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(since)
+    .bind(STATS_GROUP_LIMIT)
+    .fetch_all(&pool)
+    .await?;
+
+    let alerts = AlertThresholds::from_env().breaches(&counts);
+    for alert in &alerts {
+        tracing::warn!(
+            class = alert.class.as_str(),
+            count = alert.count,
+            threshold = alert.threshold,
+            window_hours,
+            "DLQ error class above alert threshold"
+        );
+    }
+
+    Ok(Json(DlqStatsResponse {
+        window_hours,
+        total: class_rows.iter().map(|(_, count)| count).sum(),
+        by_class: ErrorClass::ALL
+            .into_iter()
+            .map(|class| ClassCount {
+                class: class.as_str().to_string(),
+                count: counts.get(&class).copied().unwrap_or(0),
+            })
+            .collect(),
+        groups,
+        alerts,
+    }))
 }
 
 async fn requeue_dlq(
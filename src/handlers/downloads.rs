@@ -0,0 +1,176 @@
+//! File downloads authorised by a signed URL instead of an API key, so links
+//! can be handed to a browser. See [`crate::utils::signed_url`].
+//!
+//! | Method | Path                          | Effect                                          |
+//! |--------|-------------------------------|-------------------------------------------------|
+//! | `GET`  | `/downloads/backups/:file`    | A database backup (`?expires=&signature=`)      |
+//! | `GET`  | `/downloads/statements/:file` | A partner statement (`<id>.csv` or `<id>.pdf`)  |
+//!
+//! Backup links are issued by `GET /admin/backups` and statement links by
+//! `GET /admin/statements`; export files are served by `/exports/:id/download`
+//! with the same signing scheme. All are streamed from the configured
+//! [`crate::ports::ObjectStore`]. Backup downloads need the `backup` feature.
+
+use crate::adapters;
+use crate::error::AppError;
+use crate::ports::{ByteStream, ObjectStoreError};
+use crate::services::accounting::Period;
+use crate::services::statements;
+use crate::utils::signed_url::{self, SignedUrlQuery};
+use crate::ApiState;
+use axum::{
+    body::StreamBody,
+    extract::{Path, Query, State},
+    http::{header, HeaderValue, StatusCode},
+    response::IntoResponse,
+};
+use chrono::Utc;
+#[cfg(feature = "backup")]
+use {
+    crate::services::backup::{self, BackupMetadata},
+    chrono::DateTime,
+};
+
+#[cfg(feature = "backup")]
+/// Signed resource for backup `file`.
+fn backup_resource(file: &str) -> String {
+    format!("backup:{file}")
+}
+
+#[cfg(feature = "backup")]
+/// Relative download URL for backup `file`, valid until the returned expiry.
+pub fn backup_download_url(
+    secret: &str,
+    file: &str,
+    now: DateTime<Utc>,
+) -> (String, DateTime<Utc>) {
+    signed_url::sign_url(
+        secret,
+        &format!("/downloads/backups/{file}"),
+        &backup_resource(file),
+        now,
+    )
+}
+
+/// `200` response streaming `body` as an attachment named `filename`.
+pub(crate) fn attachment(
+    body: ByteStream,
+    content_type: &'static str,
+    filename: &str,
+) -> Result<impl IntoResponse, AppError> {
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, HeaderValue::from_static(content_type)),
+            (
+                header::CONTENT_DISPOSITION,
+                HeaderValue::from_str(&format!("attachment; filename=\"{filename}\""))
+                    .map_err(|e| AppError::Internal(e.to_string()))?,
+            ),
+        ],
+        StreamBody::new(body),
+    ))
+}
+
+#[cfg(feature = "backup")]
+/// Backup metadata plus a fresh signed link, as listed by `GET /admin/backups`.
+pub fn with_download_url(metadata: &BackupMetadata, now: DateTime<Utc>) -> serde_json::Value {
+    let mut body = serde_json::json!(metadata);
+    // The wrapped data key is only useful to the restore path.
+    if let Some(fields) = body.as_object_mut() {
+        fields.remove("key_envelope");
+    }
+    if let Some(secret) = signed_url::signing_secret() {
+        let (url, expires_at) = backup_download_url(&secret, &metadata.filename, now);
+        body["download_url"] = serde_json::json!(url);
+        body["download_expires_at"] = serde_json::json!(expires_at);
+    }
+    body
+}
+
+#[cfg(feature = "backup")]
+/// GET /downloads/backups/:file
+pub async fn download_backup(
+    Path(file): Path<String>,
+    Query(q): Query<SignedUrlQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let secret = signed_url::signing_secret()
+        .ok_or_else(|| AppError::NotFound("Downloads are not enabled".to_string()))?;
+    if !q.verify(&secret, &backup_resource(&file), Utc::now()) {
+        return Err(AppError::Unauthorized(
+            "Download link is invalid or has expired".to_string(),
+        ));
+    }
+    // A valid signature already pins the name; this keeps a leaked secret from
+    // reaching objects other than backups.
+    if !backup::is_backup_file_name(&file) {
+        return Err(AppError::NotFound(format!("Backup {file} not found")));
+    }
+
+    let body = adapters::object_store()
+        .get(&file)
+        .await
+        .map_err(|e| match e {
+            ObjectStoreError::NotFound(_) => AppError::NotFound(format!("Backup {file} not found")),
+            e => AppError::Internal(e.to_string()),
+        })?;
+    attachment(body, "application/octet-stream", &file)
+}
+
+/// GET /downloads/statements/:file
+pub async fn download_statement(
+    State(state): State<ApiState>,
+    Path(file): Path<String>,
+    Query(q): Query<SignedUrlQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let secret = signed_url::signing_secret()
+        .ok_or_else(|| AppError::NotFound("Downloads are not enabled".to_string()))?;
+    if !q.verify(&secret, &statements::download_resource(&file), Utc::now()) {
+        return Err(AppError::Unauthorized(
+            "Download link is invalid or has expired".to_string(),
+        ));
+    }
+    let not_found = || AppError::NotFound(format!("Statement {file} not found"));
+    let (id, format) = statements::parse_file_name(&file).ok_or_else(not_found)?;
+    let statement = crate::db::queries::get_partner_statement(&state.app_state.db, id)
+        .await?
+        .ok_or_else(not_found)?;
+    let period = Period::containing_date(statement.period_start);
+
+    let key = statements::object_key(statement.tenant_id, period, format);
+    let body = adapters::object_store()
+        .get(&key)
+        .await
+        .map_err(|e| match e {
+            ObjectStoreError::NotFound(_) => not_found(),
+            e => AppError::Internal(e.to_string()),
+        })?;
+    let filename = format!(
+        "statement_{}_{}.{}",
+        period.label(),
+        statement.tenant_id,
+        format.as_str()
+    );
+    attachment(body, format.content_type(), &filename)
+}
+
+#[cfg(all(test, feature = "backup"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backup_url_is_verifiable() {
+        let now = Utc::now();
+        let file = "backup_daily_20260601_000000.sql.gz";
+        let (url, expires_at) = backup_download_url("secret", file, now);
+        let query = SignedUrlQuery {
+            expires: expires_at.timestamp(),
+            signature: url.rsplit("signature=").next().unwrap().to_string(),
+        };
+
+        assert!(url.starts_with(&format!("/downloads/backups/{file}?expires=")));
+        assert!(query.verify("secret", &backup_resource(file), now));
+        // Not valid for an export with the same signing secret.
+        assert!(!query.verify("secret", file, now));
+    }
+}
@@ -13,7 +13,7 @@ use sqlx::{PgPool, Row};
 use std::pin::Pin;
 use std::sync::Arc;
 
-use crate::db::models::Transaction;
+use crate::db::models::{Transaction, TransactionStatus};
 
 /// Query parameters for the export endpoint
 #[derive(Debug, Deserialize, Clone)]
@@ -65,6 +65,12 @@ impl ExportQuery {
             parse_date(to).map(|_| ()).map_err(AppError::Validation)?;
         }
 
+        if let Some(ref status) = self.status {
+            status
+                .parse::<TransactionStatus>()
+                .map_err(AppError::Validation)?;
+        }
+
         Ok(())
     }
 }
@@ -106,7 +112,7 @@ impl From<&Transaction> for TransactionCsvRow {
             stellar_account: tx.stellar_account.clone(),
             amount: tx.amount.to_string(),
             asset_code: tx.asset_code.clone(),
-            status: tx.status.clone(),
+            status: tx.status.to_string(),
             created_at: tx.created_at.to_rfc3339(),
             updated_at: tx.updated_at.to_rfc3339(),
             anchor_transaction_id: tx.anchor_transaction_id.clone().unwrap_or_default(),
@@ -123,7 +129,7 @@ impl From<&Transaction> for TransactionJsonRow {
             stellar_account: tx.stellar_account.clone(),
             amount: tx.amount.to_string(),
             asset_code: tx.asset_code.clone(),
-            status: tx.status.clone(),
+            status: tx.status.to_string(),
             created_at: tx.created_at.to_rfc3339(),
             updated_at: tx.updated_at.to_rfc3339(),
             anchor_transaction_id: tx.anchor_transaction_id.clone(),
@@ -186,7 +192,7 @@ fn build_filter_conditions(
     }
 
     if let Some(ref status_val) = status {
-        conditions.push(format!("status = ${param_count}"));
+        conditions.push(format!("status = ${param_count}::transaction_status"));
         params.push(FilterValue::String(status_val.clone()));
         param_count += 1;
     }
@@ -289,6 +295,14 @@ fn create_csv_stream(
                             memo_type: row.get("memo_type"),
                             metadata: row.get("metadata"),
                             trace_id: None,
+                            backfilled: false,
+                            stellar_tx_hash: None,
+                            ledger: None,
+                            closed_at: None,
+                            stellar_muxed_id: None,
+                            risk_score: None,
+                            risk_reasons: None,
+                            fee_amount: None,
                         };
 
                         last_id = Some(tx.id);
@@ -386,6 +400,14 @@ fn create_json_stream(
                             memo_type: row.get("memo_type"),
                             metadata: row.get("metadata"),
                             trace_id: None,
+                            backfilled: false,
+                            stellar_tx_hash: None,
+                            ledger: None,
+                            closed_at: None,
+                            stellar_muxed_id: None,
+                            risk_score: None,
+                            risk_reasons: None,
+                            fee_amount: None,
                         };
 
                         last_id = Some(tx.id);
@@ -539,7 +561,7 @@ mod tests {
             stellar_account: "GABC123".to_string(),
             amount: BigDecimal::from(100),
             asset_code: "USD".to_string(),
-            status: "pending".to_string(),
+            status: TransactionStatus::Pending,
             created_at: Utc::now(),
             updated_at: Utc::now(),
             anchor_transaction_id: Some("anchor-123".to_string()),
@@ -550,6 +572,14 @@ mod tests {
             memo_type: None,
             metadata: None,
             trace_id: None,
+            backfilled: false,
+            stellar_tx_hash: None,
+            ledger: None,
+            closed_at: None,
+            stellar_muxed_id: None,
+            risk_score: None,
+            risk_reasons: None,
+            fee_amount: None,
         };
 
         let csv_row = TransactionCsvRow::from(&tx);
@@ -567,7 +597,7 @@ mod tests {
             stellar_account: "GABC123".to_string(),
             amount: BigDecimal::from(100),
             asset_code: "USD".to_string(),
-            status: "pending".to_string(),
+            status: TransactionStatus::Pending,
             created_at: Utc::now(),
             updated_at: Utc::now(),
             anchor_transaction_id: Some("anchor-123".to_string()),
@@ -578,6 +608,14 @@ mod tests {
             memo_type: None,
             metadata: None,
             trace_id: None,
+            backfilled: false,
+            stellar_tx_hash: None,
+            ledger: None,
+            closed_at: None,
+            stellar_muxed_id: None,
+            risk_score: None,
+            risk_reasons: None,
+            fee_amount: None,
         };
 
         let json_row = TransactionJsonRow::from(&tx);
@@ -680,7 +718,7 @@ mod tests {
             stellar_account: "GABC".to_string(),
             amount: BigDecimal::from(1),
             asset_code: "XLM".to_string(),
-            status: "pending".to_string(),
+            status: TransactionStatus::Pending,
             created_at: Utc::now(),
             updated_at: Utc::now(),
             anchor_transaction_id: None,
@@ -691,6 +729,14 @@ mod tests {
             memo_type: None,
             metadata: None,
             trace_id: None,
+            backfilled: false,
+            stellar_tx_hash: None,
+            ledger: None,
+            closed_at: None,
+            stellar_muxed_id: None,
+            risk_score: None,
+            risk_reasons: None,
+            fee_amount: None,
         };
 
         let row = TransactionCsvRow::from(&tx);
@@ -709,7 +755,7 @@ mod tests {
             stellar_account: "GABC".to_string(),
             amount: BigDecimal::from(1),
             asset_code: "XLM".to_string(),
-            status: "pending".to_string(),
+            status: TransactionStatus::Pending,
             created_at: Utc::now(),
             updated_at: Utc::now(),
             anchor_transaction_id: None,
@@ -720,6 +766,14 @@ mod tests {
             memo_type: None,
             metadata: None,
             trace_id: None,
+            backfilled: false,
+            stellar_tx_hash: None,
+            ledger: None,
+            closed_at: None,
+            stellar_muxed_id: None,
+            risk_score: None,
+            risk_reasons: None,
+            fee_amount: None,
         };
 
         let row = TransactionJsonRow::from(&tx);
@@ -739,7 +793,7 @@ mod tests {
             stellar_account: "GABC".to_string(),
             amount: BigDecimal::from_str("123.456").unwrap(),
             asset_code: "USD".to_string(),
-            status: "completed".to_string(),
+            status: TransactionStatus::Completed,
             created_at: Utc::now(),
             updated_at: Utc::now(),
             anchor_transaction_id: None,
@@ -750,6 +804,14 @@ mod tests {
             memo_type: None,
             metadata: None,
             trace_id: None,
+            backfilled: false,
+            stellar_tx_hash: None,
+            ledger: None,
+            closed_at: None,
+            stellar_muxed_id: None,
+            risk_score: None,
+            risk_reasons: None,
+            fee_amount: None,
         };
 
         let row = TransactionCsvRow::from(&tx);
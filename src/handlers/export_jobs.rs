@@ -0,0 +1,164 @@
+//! Asynchronous transaction exports.
+//!
+//! | Method | Path                     | Effect                                        |
+//! |--------|--------------------------|-----------------------------------------------|
+//! | `POST` | `/exports`               | Queue an export (`202 Accepted`)              |
+//! | `GET`  | `/exports/:id`           | Job status; signed `download_url` when done   |
+//! | `GET`  | `/exports/:id/download`  | The file (`?expires=&signature=` required)    |
+//!
+//! Exports are `transaction_export` jobs run by the background job runner; see
+//! [`crate::services::export_jobs`]. They can also be inspected and cancelled
+//! through `/admin/jobs`. Use `GET /export` for small, synchronous downloads.
+
+use crate::adapters;
+use crate::db::models::BackgroundJob;
+use crate::db::queries;
+use crate::error::AppError;
+use crate::handlers::downloads;
+use crate::services::export_jobs::{self, ExportFilters, ExportFormat, ExportParams};
+use crate::services::job_runner::{JobKind, JobState};
+use crate::utils::signed_url::{self, SignedUrlQuery};
+use crate::ApiState;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateExportRequest {
+    /// `csv` (default) or `ndjson`.
+    #[serde(default = "default_format")]
+    pub format: ExportFormat,
+    #[serde(default)]
+    pub filters: ExportFilters,
+    pub requested_by: Option<String>,
+}
+
+fn default_format() -> ExportFormat {
+    ExportFormat::Csv
+}
+
+async fn load_job(state: &ApiState, id: Uuid) -> Result<BackgroundJob, AppError> {
+    let job = queries::get_job(&state.app_state.db, id)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => AppError::NotFound(format!("Export {id} not found")),
+            other => other.into(),
+        })?;
+    if job.kind != JobKind::TransactionExport.as_str() {
+        return Err(AppError::NotFound(format!("Export {id} not found")));
+    }
+    Ok(job)
+}
+
+/// Job as returned by the API, with a fresh download URL once completed.
+fn job_response(job: &BackgroundJob) -> serde_json::Value {
+    let mut body = serde_json::json!(job);
+    if job.status == JobState::Completed.as_str() {
+        if let Some(secret) = signed_url::signing_secret() {
+            let (url, expires_at) = export_jobs::download_url(&secret, job.id, Utc::now());
+            body["download_url"] = serde_json::json!(url);
+            body["download_expires_at"] = serde_json::json!(expires_at);
+        }
+    }
+    body
+}
+
+/// POST /exports
+pub async fn create_export(
+    State(state): State<ApiState>,
+    Json(payload): Json<CreateExportRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.filters.validate().map_err(AppError::Validation)?;
+
+    let params = serde_json::to_value(ExportParams {
+        format: payload.format,
+        filters: payload.filters,
+    })
+    .map_err(|e| AppError::Internal(format!("Failed to encode export params: {e}")))?;
+    let job = queries::insert_job(
+        &state.app_state.db,
+        JobKind::TransactionExport.as_str(),
+        &params,
+        payload.requested_by.as_deref(),
+    )
+    .await?;
+
+    tracing::info!(export_id = %job.id, format = payload.format.as_str(), "Export queued");
+    Ok((StatusCode::ACCEPTED, Json(job_response(&job))))
+}
+
+/// GET /exports/:id
+pub async fn get_export(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let job = load_job(&state, id).await?;
+    Ok((StatusCode::OK, Json(job_response(&job))))
+}
+
+/// GET /exports/:id/download
+pub async fn download_export(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+    Query(q): Query<SignedUrlQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let secret = signed_url::signing_secret()
+        .ok_or_else(|| AppError::NotFound("Export downloads are not enabled".to_string()))?;
+    if !export_jobs::verify_download(&secret, id, q.expires, &q.signature, Utc::now()) {
+        return Err(AppError::Unauthorized(
+            "Download link is invalid or has expired".to_string(),
+        ));
+    }
+
+    let job = load_job(&state, id).await?;
+    if job.status != JobState::Completed.as_str() {
+        return Err(AppError::NotFound(format!("Export {id} is not ready")));
+    }
+    let params: ExportParams = serde_json::from_value(job.params.clone())
+        .map_err(|e| AppError::Internal(format!("Invalid export params: {e}")))?;
+    let format = params.format;
+    // Files are always named after the job; never trust a stored path.
+    let key = export_jobs::object_key(id, format);
+
+    let body = adapters::object_store().get(&key).await.map_err(|e| {
+        tracing::error!(export_id = %id, error = %e, "Export file missing");
+        AppError::NotFound(format!("Export {id} file is no longer available"))
+    })?;
+
+    let filename = format!("transactions_{id}.{}", format.as_str());
+    downloads::attachment(body, format.content_type(), &filename)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_request_defaults_to_csv_without_filters() {
+        let req: CreateExportRequest = serde_json::from_str("{}").unwrap();
+        assert_eq!(req.format, ExportFormat::Csv);
+        assert_eq!(req.filters, ExportFilters::default());
+    }
+
+    #[test]
+    fn test_create_request_parses_filters() {
+        let req: CreateExportRequest = serde_json::from_str(
+            r#"{"format":"ndjson","filters":{"asset_code":"USD","from":"2026-05-01T00:00:00Z","to":"2026-05-31T23:59:59Z"}}"#,
+        )
+        .unwrap();
+        assert_eq!(req.format, ExportFormat::Ndjson);
+        assert_eq!(req.filters.asset_code.as_deref(), Some("USD"));
+        assert!(req.filters.validate().is_ok());
+    }
+
+    #[test]
+    fn test_create_request_rejects_unknown_format() {
+        assert!(serde_json::from_str::<CreateExportRequest>(r#"{"format":"xlsx"}"#).is_err());
+    }
+}
@@ -1,100 +1,64 @@
-use crate::error::AppError;
-use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
-use serde::Deserialize;
-use serde_json::{json, Value};
-use uuid::Uuid;
+use async_graphql::http::ALL_WEBSOCKET_PROTOCOLS;
+use async_graphql::Data;
+use async_graphql_axum::{GraphQLProtocol, GraphQLRequest, GraphQLResponse, GraphQLWebSocket};
+use axum::{
+    extract::{State, WebSocketUpgrade},
+    http::HeaderMap,
+    response::IntoResponse,
+    Extension,
+};
 
-use crate::db::queries;
+use crate::error::RequestId;
+use crate::graphql::pii::Role;
+use crate::middleware::auth::authenticate;
+use crate::middleware::redaction::caller_scopes;
 use crate::ApiState;
 
-#[derive(Debug, Deserialize)]
-pub struct GraphqlRequest {
-    pub query: String,
-    pub variables: Option<Value>,
+/// What the schema knows about the caller: its headers (request id, rate
+/// limit key), its PII [`Role`] from its API key scopes and, with an admin
+/// key, its [`AdminPrincipal`].
+///
+/// [`AdminPrincipal`]: crate::middleware::auth::AdminPrincipal
+async fn caller_data(state: &ApiState, headers: HeaderMap, data: &mut Data) {
+    if let Some(principal) = authenticate(&headers, state.app_state.secrets_store.as_ref()).await {
+        data.insert(principal);
+    }
+    let scopes = caller_scopes(&state.app_state.db, &headers).await;
+    data.insert(Role::from_scopes(&scopes));
+    data.insert(headers);
 }
 
+/// `POST /graphql`: runs a query or mutation against the schema. Error
+/// `requestId`s are the request logger's id for the request.
 pub async fn graphql_handler(
     State(state): State<ApiState>,
-    Json(payload): Json<GraphqlRequest>,
-) -> Result<impl IntoResponse, AppError> {
-    let query = payload.query.replace(char::is_whitespace, "");
-
-    if query.contains("transactions{") {
-        let status_filter = payload
-            .variables
-            .as_ref()
-            .and_then(|v| v.get("filter"))
-            .and_then(|f| f.get("status"))
-            .and_then(|s| s.as_str())
-            .map(ToOwned::to_owned);
-
-        let mut rows = queries::list_transactions(&state.app_state.db, 100, None, false).await?;
-        if let Some(status) = status_filter {
-            rows.retain(|t| t.status == status);
-        }
-        let data: Vec<Value> = rows
-            .into_iter()
-            .map(|t| json!({ "id": t.id.to_string(), "status": t.status }))
-            .collect();
-        return Ok((
-            StatusCode::OK,
-            Json(json!({ "data": { "transactions": data } })),
-        ));
-    }
-
-    if query.starts_with("{transaction(id:\"") || query.contains("transaction(id:\"") {
-        let id = extract_id(&payload.query);
-        if let Some(id) = id {
-            let t = queries::get_transaction(&state.app_state.db, id).await?;
-            return Ok((
-                StatusCode::OK,
-                Json(json!({
-                    "data": {
-                        "transaction": {
-                            "id": t.id.to_string(),
-                            "status": t.status,
-                            "amount": t.amount.to_string(),
-                            "assetCode": t.asset_code
-                        }
-                    }
-                })),
-            ));
-        }
+    request_id: Option<Extension<RequestId>>,
+    headers: HeaderMap,
+    req: GraphQLRequest,
+) -> GraphQLResponse {
+    let mut req = req.into_inner();
+    caller_data(&state, headers, &mut req.data).await;
+    if let Some(Extension(request_id)) = request_id {
+        req.data.insert(request_id);
     }
-
-    if query.contains("mutation{forceCompleteTransaction(id:\"") {
-        let id = extract_id(&payload.query);
-        if let Some(id) = id {
-            sqlx::query(
-                "UPDATE transactions SET status = 'completed', updated_at = NOW() WHERE id = $1",
-            )
-            .bind(id)
-            .execute(&state.app_state.db)
-            .await?;
-
-            let t = queries::get_transaction(&state.app_state.db, id).await?;
-            return Ok((
-                StatusCode::OK,
-                Json(json!({
-                    "data": { "forceCompleteTransaction": { "id": t.id.to_string(), "status": t.status } }
-                })),
-            ));
-        }
-    }
-
-    Err(AppError::BadRequest(
-        "Unsupported GraphQL query".to_string(),
-    ))
+    state.graphql_schema.execute(req).await.into()
 }
 
-fn extract_id(query: &str) -> Option<Uuid> {
-    let marker = if query.contains("id: \"") {
-        "id: \""
-    } else {
-        "id:\""
-    };
-    let start = query.find(marker)? + marker.len();
-    let remainder = &query[start..];
-    let end = remainder.find('"')?;
-    Uuid::parse_str(&remainder[..end]).ok()
+/// `GET /graphql/ws`: subscriptions over `graphql-transport-ws` or the
+/// older `graphql-ws` protocol.
+pub async fn graphql_ws(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    protocol: GraphQLProtocol,
+    upgrade: WebSocketUpgrade,
+) -> impl IntoResponse {
+    let mut data = Data::default();
+    caller_data(&state, headers, &mut data).await;
+    upgrade
+        .protocols(ALL_WEBSOCKET_PROTOCOLS)
+        .on_upgrade(move |stream| {
+            GraphQLWebSocket::new(stream, state.graphql_schema, protocol)
+                .with_data(data)
+                .serve()
+        })
 }
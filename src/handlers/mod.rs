@@ -1,19 +1,43 @@
+pub mod ack;
 pub mod admin;
+#[cfg(feature = "sep")]
+pub mod auth;
+pub mod changes;
 pub mod dlq;
+pub mod downloads;
 pub mod export;
+pub mod export_jobs;
+#[cfg(feature = "graphql")]
 pub mod graphql;
 pub mod idempotency;
 pub mod pagination;
 pub mod profiling;
+pub mod rate_limit;
+#[cfg(feature = "websocket")]
 pub mod reconnection;
 pub mod search;
+#[cfg(feature = "sep")]
+pub mod sep12;
+#[cfg(feature = "sep")]
+pub mod sep24;
+#[cfg(feature = "sep")]
+pub mod sep31;
+#[cfg(feature = "sep")]
+pub mod sep38;
+#[cfg(feature = "sep")]
+pub mod sep6;
 pub mod session;
 pub mod settlements;
 pub mod stats;
+pub mod status_page;
+#[cfg(feature = "sep")]
+pub mod stellar_toml;
 pub mod v1;
 pub mod v2;
 pub mod webhook;
+#[cfg(feature = "websocket")]
 pub mod ws;
+#[cfg(feature = "websocket")]
 pub mod ws_error;
 
 pub use pagination::{
@@ -108,7 +132,8 @@ pub async fn live() -> impl IntoResponse {
 /// Readiness probe endpoint — returns 200 when ready to accept traffic, 503 when draining.
 ///
 /// This endpoint indicates whether the service is ready to accept requests.
-/// Returns non-200 (503) if the service is draining or gracefully shutting down.
+/// Returns non-200 (503) if the service is draining or gracefully shutting down,
+/// or while the primary database is unreachable.
 ///
 /// # Returns
 /// - `(StatusCode::OK, ReadinessResponse)` — service is ready to accept traffic
@@ -132,12 +157,14 @@ pub async fn ready(State(state): State<ApiState>) -> Result<impl IntoResponse, A
         let response = ReadinessResponse {
             status: "ready".to_string(),
             draining: state.app_state.readiness.is_draining(),
+            database_reachable: state.app_state.readiness.is_db_reachable(),
         };
         Ok((StatusCode::OK, Json(response)))
     } else {
         let response = ReadinessResponse {
             status: "not_ready".to_string(),
             draining: state.app_state.readiness.is_draining(),
+            database_reachable: state.app_state.readiness.is_db_reachable(),
         };
         Ok((StatusCode::SERVICE_UNAVAILABLE, Json(response)))
     }
@@ -221,6 +248,8 @@ pub struct ReadinessResponse {
     pub status: String,
     /// true if the service is in graceful shutdown mode (/admin/drain was called)
     pub draining: bool,
+    /// false while the primary database is unreachable, e.g. during a failover
+    pub database_reachable: bool,
 }
 
 /// Response from the health check endpoint (/health).
@@ -273,6 +302,16 @@ pub async fn error_catalog() -> Result<impl IntoResponse, AppError> {
     Ok((StatusCode::OK, Json(catalog)))
 }
 
+/// Event schema registry endpoint
+/// Returns the JSON Schema and version of every emitted event type
+pub async fn event_schema() -> impl IntoResponse {
+    let events = crate::services::event_catalog::catalog();
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({ "events": events })),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -293,6 +332,7 @@ mod tests {
         let ready = ReadinessResponse {
             status: "ready".to_string(),
             draining: false,
+            database_reachable: true,
         };
         assert_eq!(ready.status, "ready");
         assert!(!ready.draining);
@@ -300,6 +340,7 @@ mod tests {
         let not_ready = ReadinessResponse {
             status: "not_ready".to_string(),
             draining: true,
+            database_reachable: true,
         };
         assert_eq!(not_ready.status, "not_ready");
         assert!(not_ready.draining);
@@ -0,0 +1,65 @@
+//! Rate-limit introspection.
+//!
+//! `GET /rate-limit` reports the caller's current quota for the rate-limited
+//! routes (`/callback*`, `/webhook`) without consuming a request. The caller
+//! is identified exactly as in
+//! [`rate_limit_middleware`](crate::middleware::quota::rate_limit_middleware)
+//! (`X-API-Key`, then `X-Tenant-ID`, then the shared anonymous bucket), so a
+//! partner sees the same numbers the middleware would enforce.
+
+use crate::error::AppError;
+use crate::middleware::quota::{
+    apply_rate_limit_headers, resolve_rate_limit, LimitSource, QuotaManager, RATE_LIMIT_WINDOW_SECS,
+};
+use crate::ApiState;
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use serde::Serialize;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize)]
+pub struct RateLimitResponse {
+    /// `api_key`, `tenant` or `anonymous`. The key itself is never echoed.
+    pub subject: &'static str,
+    pub tenant_id: Option<Uuid>,
+    /// Requests allowed per window.
+    pub limit: u32,
+    pub used: u32,
+    pub remaining: u32,
+    /// Seconds until the current window resets.
+    pub reset_in_seconds: u64,
+    pub window_seconds: i64,
+    /// Where `limit` came from: `override`, `tenant_settings` or `default`.
+    pub source: LimitSource,
+}
+
+/// GET /rate-limit — current quota for the calling API key or tenant.
+pub async fn get_rate_limit(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AppError> {
+    let manager = QuotaManager::new(&state.app_state.redis_url)?;
+    let subject = resolve_rate_limit(&state.app_state, &manager, &headers).await;
+    let status = manager
+        .check_quota_with_limit(&subject.bucket_key, subject.limit)
+        .await?;
+
+    let body = RateLimitResponse {
+        subject: subject.kind(),
+        tenant_id: subject.tenant_id,
+        limit: status.limit,
+        used: status.used,
+        remaining: status.remaining,
+        reset_in_seconds: status.reset_in_seconds,
+        window_seconds: RATE_LIMIT_WINDOW_SECS,
+        source: subject.source,
+    };
+
+    let mut response = (StatusCode::OK, Json(body)).into_response();
+    apply_rate_limit_headers(response.headers_mut(), &status);
+    Ok(response)
+}
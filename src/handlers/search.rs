@@ -1,6 +1,7 @@
 use crate::db::pool_manager::PoolManager;
 use crate::error::AppError;
 use crate::utils::cursor as cursor_util;
+use crate::utils::fields::{FieldSelection, TRANSACTION_FIELDS};
 use axum::{
     extract::{Query, State},
     http::{HeaderValue, StatusCode},
@@ -22,8 +23,12 @@ pub struct SearchQuery {
     pub from: Option<String>,
     pub to: Option<String>,
     pub stellar_account: Option<String>,
+    /// JSON object; matches transactions whose metadata contains it.
+    pub metadata: Option<String>,
     pub cursor: Option<String>,
     pub limit: Option<i64>,
+    /// Comma-separated fields to return for each result.
+    pub fields: Option<String>,
 }
 
 #[instrument(name = "search.transactions", skip(pool_manager, params))]
@@ -32,6 +37,8 @@ pub async fn search_transactions(
     Query(params): Query<SearchQuery>,
 ) -> Result<impl IntoResponse, AppError> {
     let limit = params.limit.unwrap_or(25).min(100);
+    let fields = FieldSelection::parse(params.fields.as_deref(), TRANSACTION_FIELDS)
+        .map_err(|e| AppError::BadRequest(format!("Invalid 'fields': {e}")))?;
 
     let decoded_cursor = if let Some(ref c) = params.cursor {
         match cursor_util::decode(c) {
@@ -42,6 +49,14 @@ pub async fn search_transactions(
         None
     };
 
+    let status = match params.status.as_deref() {
+        Some(value) => Some(
+            crate::db::models::TransactionStatus::from_str(value)
+                .map_err(|e| AppError::BadRequest(format!("Invalid 'status': {e}")))?,
+        ),
+        None => None,
+    };
+
     let min_amount = match params.min_amount {
         Some(value) => Some(BigDecimal::from_str(&value).map_err(|_| {
             AppError::BadRequest("Invalid 'min_amount': must be a valid decimal".to_string())
@@ -78,16 +93,38 @@ pub async fn search_transactions(
         None => None,
     };
 
+    let stellar_account = match params.stellar_account.as_deref() {
+        Some(value) => Some(
+            crate::validation::parse_stellar_address(value).map_err(|e| {
+                AppError::BadRequest(format!("Invalid 'stellar_account': {}", e.message))
+            })?,
+        ),
+        None => None,
+    };
+
+    let metadata = match params.metadata.as_deref() {
+        Some(raw) => {
+            let value: serde_json::Value = serde_json::from_str(raw).map_err(|_| {
+                AppError::BadRequest("Invalid 'metadata': must be a JSON object".to_string())
+            })?;
+            crate::validation::validate_metadata(&value)
+                .map_err(|e| AppError::BadRequest(format!("Invalid 'metadata': {e}")))?;
+            Some(value)
+        }
+        None => None,
+    };
+
     let (pool, replica_used) = pool_manager.read_pool().await;
     let (total, transactions) = crate::db::queries::search_transactions(
         pool,
-        params.status.as_deref(),
+        status.as_ref().map(|s| s.as_str()),
         params.asset_code.as_deref(),
         min_amount.as_ref(),
         max_amount.as_ref(),
         from_date,
         to_date,
-        params.stellar_account.as_deref(),
+        stellar_account.as_ref(),
+        metadata.as_ref(),
         limit,
         decoded_cursor,
     )
@@ -103,7 +140,7 @@ pub async fn search_transactions(
 
     let mut resp = serde_json::json!({
         "total": total,
-        "results": transactions,
+        "results": fields.project_all(&transactions),
     });
 
     if let Some(cursor) = next_cursor {
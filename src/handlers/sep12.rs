@@ -0,0 +1,124 @@
+//! SEP-12 KYC customer management.
+//!
+//! | Method   | Path                 | Effect                                      |
+//! |----------|----------------------|---------------------------------------------|
+//! | `PUT`    | `/customer`          | Create or update a customer; `202 {"id"}`   |
+//! | `GET`    | `/customer`          | Status, missing and provided fields         |
+//! | `DELETE` | `/customer/:account` | Delete the account's customers (`?memo=`)   |
+//!
+//! All take a SEP-10 token as `Authorization: Bearer <jwt>` and only reach
+//! the token's own account. See [`crate::services::sep12`] for the fields
+//! and statuses.
+
+use crate::adapters;
+use crate::error::AppError;
+use crate::handlers::auth::sep10_claims;
+use crate::ports::{Customer, CustomerRepository};
+use crate::services::sep12::{self, CustomerQuery, PutCustomerRequest};
+use crate::ApiState;
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+fn repository(state: &ApiState) -> Arc<dyn CustomerRepository> {
+    adapters::customer_repository(state.app_state.db.clone())
+}
+
+/// The customer `query` names, if the authenticated account may see it.
+async fn find(
+    customers: &dyn CustomerRepository,
+    query: &CustomerQuery,
+    account: &str,
+) -> Result<Option<Customer>, AppError> {
+    let key = query.key(account)?;
+    let Some(id) = query.id else {
+        return Ok(customers.get(&key).await?);
+    };
+    match customers.get_by_id(id).await? {
+        Some(customer) if customer.key.account == account => Ok(Some(customer)),
+        _ => Err(AppError::NotFound(format!("customer {id} not found"))),
+    }
+}
+
+/// PUT /customer
+pub async fn put_customer(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Json(payload): Json<PutCustomerRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let now = state.app_state.clock.now();
+    let claims = sep10_claims(&headers, now)?;
+    let query = payload.query();
+    let fields = payload.fields()?;
+    let customers = repository(&state);
+
+    let existing = find(customers.as_ref(), &query, &claims.sub).await?;
+    let key = match &existing {
+        Some(customer) => customer.key.clone(),
+        None => query.key(&claims.sub)?,
+    };
+    let customer = sep12::apply_update(existing, key, fields, uuid::Uuid::new_v4(), now);
+    let saved = customers.save(&customer).await?;
+
+    tracing::info!(
+        customer_id = %saved.id,
+        status = saved.status.as_str(),
+        "SEP-12 customer updated"
+    );
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({ "id": saved.id })),
+    ))
+}
+
+/// GET /customer
+pub async fn get_customer(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Query(query): Query<CustomerQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let claims = sep10_claims(&headers, state.app_state.clock.now())?;
+    let customers = repository(&state);
+    let customer = find(customers.as_ref(), &query, &claims.sub).await?;
+    Ok((StatusCode::OK, Json(sep12::view(customer.as_ref()))))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteCustomerQuery {
+    pub memo: Option<String>,
+    pub memo_type: Option<String>,
+}
+
+/// DELETE /customer/:account
+pub async fn delete_customer(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Path(account): Path<String>,
+    Query(params): Query<DeleteCustomerQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let claims = sep10_claims(&headers, state.app_state.clock.now())?;
+    let key = CustomerQuery {
+        account: Some(account),
+        memo: params.memo,
+        memo_type: params.memo_type,
+        ..Default::default()
+    }
+    .key(&claims.sub)?;
+
+    let deleted = repository(&state)
+        .delete(&key.account, key.memo.as_deref())
+        .await?;
+    if deleted == 0 {
+        return Err(AppError::NotFound(format!(
+            "no customer for {}",
+            key.account
+        )));
+    }
+    tracing::info!(deleted, "SEP-12 customer deleted");
+    Ok(StatusCode::OK)
+}
@@ -0,0 +1,103 @@
+//! SEP-24 hosted deposit and withdrawal.
+//!
+//! | Method | Path                                       | Effect                          |
+//! |--------|--------------------------------------------|---------------------------------|
+//! | `POST` | `/sep24/transactions/deposit/interactive`  | Start an interactive deposit    |
+//! | `POST` | `/sep24/transactions/withdraw/interactive` | Start an interactive withdrawal |
+//!
+//! Both take a SEP-10 token as `Authorization: Bearer <jwt>` and a JSON body
+//! `{"asset_code", "amount", "account"?, "lang"?}`. Error messages follow
+//! `lang`, else `Accept-Language` (see [`crate::i18n`]). See
+//! [`crate::services::sep24`] for what is recorded and configuration.
+
+use crate::db::models::Asset;
+use crate::db::queries;
+use crate::domain::DomainEvent;
+use crate::error::AppError;
+use crate::handlers::auth::sep10_claims;
+use crate::i18n::{Locale, Message};
+use crate::services::sep24::{
+    self, InteractiveRequest, InteractiveResponse, Sep24Config, Sep24Kind,
+    INTERACTIVE_RESPONSE_TYPE,
+};
+use crate::ApiState;
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+
+async fn interactive(
+    state: ApiState,
+    headers: HeaderMap,
+    kind: Sep24Kind,
+    payload: InteractiveRequest,
+) -> Result<impl IntoResponse, AppError> {
+    let config = Sep24Config::from_env()
+        .map_err(|e| AppError::Internal(format!("SEP-24 is not configured: {e}")))?;
+    let now = state.app_state.clock.now();
+    let claims = sep10_claims(&headers, now)?;
+
+    let locale = payload.locale(Locale::from_headers(&headers));
+
+    let db = &state.app_state.db;
+    let account = payload.account(&claims.sub, locale)?;
+    let asset_code = payload.asset_code.trim();
+    if !Asset::is_registered(db, asset_code).await? {
+        return Err(AppError::BadRequest(
+            Message::AssetNotSupported.render(locale, &[("asset", asset_code)]),
+        ));
+    }
+    let limits = queries::get_asset_amount_limits(db, asset_code).await?;
+    let amount = payload.amount(&limits, locale)?;
+    let lang = payload.lang(locale)?;
+
+    let tx = sep24::new_transaction(kind, &account, asset_code, amount, lang.as_deref());
+    let inserted = queries::insert_transaction(db, &tx).await?;
+    state
+        .app_state
+        .domain_events
+        .publish(DomainEvent::TransactionCreated {
+            transaction_id: inserted.id,
+            tenant_id: None,
+            stellar_account: inserted.stellar_account.clone(),
+            amount: inserted.amount.clone(),
+            asset_code: inserted.asset_code.clone(),
+            status: inserted.status.as_str().to_string(),
+            at: inserted.created_at,
+        });
+
+    tracing::info!(
+        transaction_id = %inserted.id,
+        kind = kind.callback_type(),
+        "SEP-24 interactive transaction started"
+    );
+    let url = sep24::interactive_url(&config, inserted.id, asset_code, lang.as_deref(), now);
+    Ok((
+        StatusCode::OK,
+        Json(InteractiveResponse {
+            kind: INTERACTIVE_RESPONSE_TYPE,
+            url,
+            id: inserted.id,
+        }),
+    ))
+}
+
+/// POST /sep24/transactions/deposit/interactive
+pub async fn deposit_interactive(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Json(payload): Json<InteractiveRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    interactive(state, headers, Sep24Kind::Deposit, payload).await
+}
+
+/// POST /sep24/transactions/withdraw/interactive
+pub async fn withdraw_interactive(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Json(payload): Json<InteractiveRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    interactive(state, headers, Sep24Kind::Withdraw, payload).await
+}
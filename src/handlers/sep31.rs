@@ -0,0 +1,155 @@
+//! SEP-31 cross-border payments, receiving side.
+//!
+//! | Method | Path                               | Effect                                 |
+//! |--------|------------------------------------|----------------------------------------|
+//! | `POST` | `/sep31/transactions`              | Record a payment; answer where to pay  |
+//! | `GET`  | `/sep31/transactions/:id`          | Transaction in SEP-31 terms            |
+//! | `PUT`  | `/sep31/transactions/:id/callback` | Register a status callback (`{"url"}`) |
+//!
+//! All take the sending anchor's SEP-10 token as `Authorization: Bearer
+//! <jwt>` and only show an anchor its own transactions. See
+//! [`crate::services::sep31`] for statuses, callbacks and configuration.
+
+use crate::db::models::{Asset, Sep38Quote, Transaction};
+use crate::db::queries;
+use crate::domain::{DomainEvent, StellarAddress};
+use crate::error::AppError;
+use crate::handlers::auth::sep10_claims;
+use crate::services::quotes;
+use crate::services::sep31::{
+    self, CallbackRequest, PostTransactionRequest, PostTransactionResponse, Sep31Config,
+    Sep31Transaction,
+};
+use crate::ApiState;
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use uuid::Uuid;
+
+fn config() -> Result<Sep31Config, AppError> {
+    Sep31Config::from_env()
+        .map_err(|e| AppError::Internal(format!("SEP-31 is not configured: {e}")))
+}
+
+/// Transaction `id`, if the authenticated anchor sent it.
+async fn own_transaction(
+    state: &ApiState,
+    headers: &HeaderMap,
+    id: Uuid,
+) -> Result<Transaction, AppError> {
+    let claims = sep10_claims(headers, state.app_state.clock.now())?;
+    let not_found = || AppError::NotFound(format!("transaction {id} not found"));
+    let tx = match queries::get_transaction(&state.app_state.db, id).await {
+        Ok(tx) => tx,
+        Err(sqlx::Error::RowNotFound) => return Err(not_found()),
+        Err(e) => return Err(e.into()),
+    };
+    if !sep31::is_sent_by(&tx, &claims.sub) {
+        return Err(not_found());
+    }
+    Ok(tx)
+}
+
+/// POST /sep31/transactions
+pub async fn create_transaction(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Json(payload): Json<PostTransactionRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let config = config()?;
+    let now = state.app_state.clock.now();
+    let claims = sep10_claims(&headers, now)?;
+    let sender = StellarAddress::parse(&claims.sub)
+        .map_err(|e| AppError::Unauthorized(format!("token account {e}")))?;
+    payload.validate()?;
+
+    let db = &state.app_state.db;
+    let asset_code = payload.asset_code.trim();
+    if !Asset::is_registered(db, asset_code).await? {
+        return Err(AppError::BadRequest(format!(
+            "asset {asset_code} is not supported"
+        )));
+    }
+    let limits = queries::get_asset_amount_limits(db, asset_code).await?;
+    let amount = payload.amount(&limits)?;
+    let quote_id = payload.quote_id()?;
+    if let Some(id) = quote_id {
+        let quote = Sep38Quote::get(db, id)
+            .await?
+            .ok_or_else(|| AppError::BadRequest(format!("quote {id} not found")))?;
+        quotes::check_backs(&quote, &claims.sub, asset_code, &amount, now)?;
+    }
+
+    let tx = sep31::new_transaction(&sender, asset_code, amount, &payload);
+    if let Some(id) = quote_id {
+        if !Sep38Quote::link_transaction(db, id, tx.id, now).await? {
+            return Err(AppError::BadRequest(format!(
+                "quote {id} was already used or has expired"
+            )));
+        }
+    }
+    let inserted = match queries::insert_transaction(db, &tx).await {
+        Ok(inserted) => inserted,
+        Err(e) => {
+            if let Some(id) = quote_id {
+                if let Err(unlink) = Sep38Quote::unlink_transaction(db, id, tx.id).await {
+                    tracing::warn!(quote_id = %id, error = %unlink, "Failed to release quote");
+                }
+            }
+            return Err(e.into());
+        }
+    };
+    state
+        .app_state
+        .domain_events
+        .publish(DomainEvent::TransactionCreated {
+            transaction_id: inserted.id,
+            tenant_id: None,
+            stellar_account: inserted.stellar_account.clone(),
+            amount: inserted.amount.clone(),
+            asset_code: inserted.asset_code.clone(),
+            status: inserted.status.as_str().to_string(),
+            at: inserted.created_at,
+        });
+
+    tracing::info!(transaction_id = %inserted.id, "SEP-31 transaction received");
+    Ok((
+        StatusCode::CREATED,
+        Json(PostTransactionResponse {
+            id: inserted.id,
+            stellar_account_id: config.receiving_account.account().to_string(),
+            stellar_memo_type: "id",
+            stellar_memo: inserted.memo.unwrap_or_default(),
+        }),
+    ))
+}
+
+/// GET /sep31/transactions/:id
+pub async fn get_transaction(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let config = config()?;
+    let tx = own_transaction(&state, &headers, id).await?;
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({ "transaction": Sep31Transaction::new(&tx, &config) })),
+    ))
+}
+
+/// PUT /sep31/transactions/:id/callback
+pub async fn put_callback(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<CallbackRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let url = sep31::validate_callback_url(payload.url.trim())?;
+    let tx = own_transaction(&state, &headers, id).await?;
+    sep31::register_callback(&state.app_state.db, &tx, &url, &tx.stellar_account).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,79 @@
+//! SEP-38 quotes, nested under `/sep38`.
+//!
+//! | Method | Path               | Effect                                      |
+//! |--------|--------------------|---------------------------------------------|
+//! | `GET`  | `/sep38/info`      | Assets on the rate card                     |
+//! | `GET`  | `/sep38/prices`    | Indicative prices for a sell or buy amount  |
+//! | `POST` | `/sep38/quote`     | Lock a rate in; `201` firm quote            |
+//! | `GET`  | `/sep38/quote/:id` | A firm quote                                |
+//!
+//! `/info` and `/prices` are public. The quote endpoints take a SEP-10 token
+//! as `Authorization: Bearer <jwt>` and only show an account its own
+//! quotes. See [`crate::services::quotes`] for pricing and expiry.
+
+use crate::db::models::{Sep38Price, Sep38Quote};
+use crate::error::AppError;
+use crate::handlers::auth::sep10_claims;
+use crate::services::quotes::{self, PostQuoteRequest, PricesQuery, QuoteResponse, QuotesConfig};
+use crate::ApiState;
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use uuid::Uuid;
+
+/// GET /sep38/info
+pub async fn info(State(state): State<ApiState>) -> Result<impl IntoResponse, AppError> {
+    let rates = Sep38Price::fetch_all(&state.app_state.db).await?;
+    Ok((StatusCode::OK, Json(quotes::info(&rates))))
+}
+
+/// GET /sep38/prices
+pub async fn prices(
+    State(state): State<ApiState>,
+    Query(query): Query<PricesQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let rates = Sep38Price::fetch_all(&state.app_state.db).await?;
+    Ok((StatusCode::OK, Json(quotes::prices(&rates, &query)?)))
+}
+
+/// POST /sep38/quote
+pub async fn post_quote(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Json(payload): Json<PostQuoteRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let now = state.app_state.clock.now();
+    let claims = sep10_claims(&headers, now)?;
+    let (sell_asset, buy_asset) = payload.assets()?;
+    let requested = payload.requested()?;
+    let expires_at = payload.expires_at(&QuotesConfig::from_env(), now)?;
+
+    let db = &state.app_state.db;
+    let rate = Sep38Price::get(db, &sell_asset, &buy_asset)
+        .await?
+        .ok_or_else(|| AppError::BadRequest(format!("no price for {sell_asset} to {buy_asset}")))?;
+    let amounts = quotes::amounts(&rate, &requested)?;
+    let quote = quotes::new_quote(Uuid::new_v4(), &claims.sub, &rate, amounts, expires_at, now)
+        .insert(db)
+        .await?;
+
+    tracing::info!(quote_id = %quote.id, %sell_asset, %buy_asset, "SEP-38 quote issued");
+    Ok((StatusCode::CREATED, Json(QuoteResponse::from(&quote))))
+}
+
+/// GET /sep38/quote/:id
+pub async fn get_quote(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let claims = sep10_claims(&headers, state.app_state.clock.now())?;
+    let quote = Sep38Quote::get(&state.app_state.db, id)
+        .await?
+        .filter(|q| q.account == claims.sub)
+        .ok_or_else(|| AppError::NotFound(format!("quote {id} not found")))?;
+    Ok((StatusCode::OK, Json(QuoteResponse::from(&quote))))
+}
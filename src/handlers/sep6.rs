@@ -0,0 +1,116 @@
+//! SEP-6 programmatic deposit and withdrawal, nested under `/sep6`.
+//!
+//! | Method | Path             | Effect                                          |
+//! |--------|------------------|-------------------------------------------------|
+//! | `GET`  | `/sep6/info`     | Assets offered, their limits, fees and types    |
+//! | `GET`  | `/sep6/deposit`  | Start a deposit; answer how to send the funds   |
+//! | `GET`  | `/sep6/withdraw` | Start a withdrawal; answer where to pay         |
+//!
+//! `/info` is public. The other two take a SEP-10 token as `Authorization:
+//! Bearer <jwt>` and the query `asset_code`, `amount`, `account`?, `lang`?,
+//! `type`? and, for withdrawals, `dest`? and `dest_extra`?. See
+//! [`crate::services::sep6`] for the asset registry and what is recorded.
+
+use crate::db::models::{Sep6Asset, Transaction};
+use crate::db::queries;
+use crate::domain::DomainEvent;
+use crate::error::AppError;
+use crate::handlers::auth::sep10_claims;
+use crate::i18n::Locale;
+use crate::services::sep6::{
+    self, DepositResponse, Sep6Config, Sep6Kind, TransferRequest, WithdrawResponse,
+};
+use crate::ApiState;
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use serde_json::json;
+
+/// GET /sep6/info
+pub async fn info(State(state): State<ApiState>) -> Result<impl IntoResponse, AppError> {
+    let assets = Sep6Asset::fetch_all(&state.app_state.db).await?;
+    Ok((StatusCode::OK, Json(sep6::info(&assets))))
+}
+
+async fn transfer(
+    state: &ApiState,
+    headers: &HeaderMap,
+    kind: Sep6Kind,
+    request: &TransferRequest,
+) -> Result<(Sep6Asset, Transaction), AppError> {
+    let claims = sep10_claims(headers, state.app_state.clock.now())?;
+    let locale = request.transfer.locale(Locale::from_headers(headers));
+    let account = request.transfer.account(&claims.sub, locale)?;
+
+    let db = &state.app_state.db;
+    let asset_code = request.transfer.asset_code.trim();
+    let asset = Sep6Asset::get(db, asset_code)
+        .await?
+        .filter(|a| kind.is_enabled(a))
+        .ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "asset {asset_code} is not supported for {}",
+                kind.callback_type()
+            ))
+        })?;
+    let amount = request.transfer.amount(&sep6::limits(&asset), locale)?;
+    let fee = sep6::fee(&asset, &amount)?;
+    let transfer_type = request.transfer_type(kind, &asset)?;
+    let lang = request.transfer.lang(locale)?;
+    let mut details = json!({ "lang": lang, "type": transfer_type });
+    if kind == Sep6Kind::Withdraw {
+        let (dest, dest_extra) = request.destination()?;
+        details["dest"] = json!(dest);
+        details["dest_extra"] = json!(dest_extra);
+    }
+
+    let tx = sep6::new_transaction(kind, &account, asset_code, amount, fee, details);
+    let inserted = queries::insert_transaction(db, &tx).await?;
+    state
+        .app_state
+        .domain_events
+        .publish(DomainEvent::TransactionCreated {
+            transaction_id: inserted.id,
+            tenant_id: None,
+            stellar_account: inserted.stellar_account.clone(),
+            amount: inserted.amount.clone(),
+            asset_code: inserted.asset_code.clone(),
+            status: inserted.status.as_str().to_string(),
+            at: inserted.created_at,
+        });
+
+    tracing::info!(
+        transaction_id = %inserted.id,
+        kind = kind.callback_type(),
+        "SEP-6 transaction started"
+    );
+    Ok((asset, inserted))
+}
+
+/// GET /sep6/deposit
+pub async fn deposit(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Query(request): Query<TransferRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let (asset, tx) = transfer(&state, &headers, Sep6Kind::Deposit, &request).await?;
+    Ok((StatusCode::OK, Json(DepositResponse::new(&asset, &tx))))
+}
+
+/// GET /sep6/withdraw
+pub async fn withdraw(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Query(request): Query<TransferRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let config = Sep6Config::from_env()
+        .map_err(|e| AppError::Internal(format!("SEP-6 is not configured: {e}")))?;
+    let (asset, tx) = transfer(&state, &headers, Sep6Kind::Withdraw, &request).await?;
+    Ok((
+        StatusCode::OK,
+        Json(WithdrawResponse::new(&config, &asset, &tx)),
+    ))
+}
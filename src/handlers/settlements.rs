@@ -1,5 +1,9 @@
 use crate::error::AppError;
+use crate::middleware::auth::AdminPrincipal;
+use crate::services::approvals::{self, ApprovalAction};
+use crate::services::settlement::{PathPaymentConfig, PathPaymentRequest};
 use crate::utils::cursor as cursor_util;
+use crate::utils::fields::{FieldSelection, FieldsQuery, SETTLEMENT_FIELDS};
 use crate::validation::{validate_max_len, validate_required};
 use crate::ApiState;
 use axum::{
@@ -11,15 +15,21 @@ use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct SettlementListQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub cursor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<i64>,
     /// "forward" (default) or "backward"
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub direction: Option<String>,
+    /// Comma-separated fields to return for each settlement.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<String>,
 }
 
-#[derive(Debug, Serialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SettlementListResponse {
     pub settlements: Vec<crate::db::models::Settlement>,
     pub next_cursor: Option<String>,
@@ -33,10 +43,11 @@ pub struct SettlementListResponse {
         ("cursor" = Option<String>, Query, description = "Pagination cursor"),
         ("limit" = Option<i64>, Query, description = "Page size (1-100, default 10)"),
         ("direction" = Option<String>, Query, description = "\"forward\" (default) or \"backward\""),
+        ("fields" = Option<String>, Query, description = "Comma-separated fields to return for each settlement, e.g. `id,status,total_amount`; all fields when omitted"),
     ),
     responses(
         (status = 200, description = "List of settlements", body = SettlementListResponse),
-        (status = 400, description = "Invalid cursor"),
+        (status = 400, description = "Invalid cursor or `fields`"),
         (status = 500, description = "Internal server error"),
     ),
     tag = "Settlements"
@@ -47,6 +58,8 @@ pub async fn list_settlements(
 ) -> Result<impl IntoResponse, AppError> {
     let limit = params.limit.unwrap_or(10).clamp(1, 100);
     let backward = params.direction.as_deref() == Some("backward");
+    let fields = FieldSelection::parse(params.fields.as_deref(), SETTLEMENT_FIELDS)
+        .map_err(AppError::BadRequest)?;
 
     let decoded_cursor = if let Some(ref c) = params.cursor {
         match cursor_util::decode(c) {
@@ -77,6 +90,13 @@ pub async fn list_settlements(
         next_cursor,
         has_more,
     };
+    let mut body = serde_json::to_value(body)
+        .map_err(|e| AppError::Internal(format!("Failed to serialize settlements: {e}")))?;
+    if let Some(serde_json::Value::Array(items)) = body.get_mut("settlements") {
+        for item in items.iter_mut() {
+            *item = fields.project(item.take());
+        }
+    }
 
     let mut response: Response = Json(body).into_response();
     if replica_used {
@@ -93,9 +113,11 @@ pub async fn list_settlements(
     path = "/settlements/{id}",
     params(
         ("id" = Uuid, Path, description = "Settlement ID"),
+        ("fields" = Option<String>, Query, description = "Comma-separated fields to return, e.g. `id,status,total_amount`; all fields when omitted"),
     ),
     responses(
         (status = 200, description = "Settlement details", body = crate::db::models::Settlement),
+        (status = 400, description = "Unknown field in `fields`"),
         (status = 404, description = "Settlement not found"),
         (status = 500, description = "Internal server error"),
     ),
@@ -104,7 +126,10 @@ pub async fn list_settlements(
 pub async fn get_settlement(
     State(state): State<ApiState>,
     Path(id): Path<Uuid>,
+    Query(params): Query<FieldsQuery>,
 ) -> Result<impl IntoResponse, AppError> {
+    let fields = FieldSelection::parse(params.fields.as_deref(), SETTLEMENT_FIELDS)
+        .map_err(AppError::BadRequest)?;
     let (pool, replica_used) = state.app_state.pool_manager.read_pool().await;
     let settlement = crate::db::queries::get_settlement(pool, id)
         .await
@@ -116,7 +141,11 @@ pub async fn get_settlement(
             }
         })?;
 
-    let mut response: Response = Json(settlement).into_response();
+    let body = fields.project(
+        serde_json::to_value(&settlement)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize settlement: {e}")))?,
+    );
+    let mut response: Response = Json(body).into_response();
     if replica_used {
         response
             .headers_mut()
@@ -185,7 +214,9 @@ pub async fn update_settlement_status(
     };
 
     let actor = payload.actor.as_deref().unwrap_or("admin");
-    let service = crate::services::SettlementService::new(state.app_state.db.clone());
+    let service = crate::services::SettlementService::new(state.app_state.db.clone())
+        .with_events(state.app_state.settlement_events.clone())
+        .with_domain_events(state.app_state.domain_events.clone());
 
     let settlement = service
         .update_status(
@@ -200,10 +231,104 @@ pub async fn update_settlement_status(
     Ok((StatusCode::OK, Json(settlement)))
 }
 
+/// Request body for voiding a settlement.
+#[derive(Debug, Deserialize)]
+pub struct VoidSettlementRequest {
+    pub reason: String,
+}
+
+impl VoidSettlementRequest {
+    pub fn validate(&self) -> Result<(), AppError> {
+        validate_required("reason", &self.reason)
+            .map_err(|e| AppError::BadRequest(e.to_string()))?;
+        validate_max_len("reason", &self.reason, 255)
+            .map_err(|e| AppError::BadRequest(e.to_string()))?;
+        Ok(())
+    }
+}
+
+/// POST /admin/settlements/:id/void
+/// Proposes voiding a settlement in any status but `voided`, e.g. one
+/// computed with a bad fee rule. Always held for four-eyes approval and
+/// answered `202` with the approval; once approved through
+/// `POST /admin/approvals/:id/approve` the settlement is voided, its
+/// transactions return to unsettled and a compensating reversal is recorded.
+/// The authenticated admin principal is the proposer.
+pub async fn void_settlement(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+    principal: AdminPrincipal,
+    Json(payload): Json<VoidSettlementRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+    let actor = principal.name.as_str();
+
+    let settlement = crate::db::queries::get_settlement(&state.app_state.db, id)
+        .await
+        .map_err(|e| {
+            if matches!(e, sqlx::Error::RowNotFound) {
+                AppError::NotFound(format!("Settlement {} not found", id))
+            } else {
+                AppError::from(e)
+            }
+        })?;
+    if settlement.status == "voided" {
+        return Err(AppError::BadRequest(format!(
+            "settlement {id} is already voided"
+        )));
+    }
+
+    let action = ApprovalAction::SettlementVoid {
+        settlement_id: id,
+        reason: payload.reason.trim().to_string(),
+        proposed_by: actor.to_string(),
+    };
+    let approval = approvals::propose(
+        &state.app_state.db,
+        &action,
+        &settlement.total_amount,
+        &principal,
+    )
+    .await?;
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({ "approval": approval })),
+    ))
+}
+
+/// POST /admin/settlements/:id/path-payment
+/// Pays the settlement out in another asset through a strict-send or
+/// strict-receive path payment; see
+/// [`crate::services::SettlementService::execute_path_payment`].
+pub async fn execute_path_payment(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<PathPaymentRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let config = PathPaymentConfig::from_env(state.app_state.horizon_client.clone())?;
+    let service = crate::services::SettlementService::new(state.app_state.db.clone())
+        .with_path_payments(config);
+    let outcome = service.execute_path_payment(id, &payload).await?;
+    Ok((StatusCode::OK, Json(outcome)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_void_settlement_requires_reason() {
+        let req = VoidSettlementRequest {
+            reason: "  ".to_string(),
+        };
+        assert!(req.validate().is_err());
+
+        let req = VoidSettlementRequest {
+            reason: "fee rule 12 applied twice".to_string(),
+        };
+        assert!(req.validate().is_ok());
+    }
+
     #[test]
     fn test_update_settlement_status_valid() {
         let req = UpdateSettlementStatusRequest {
@@ -1,4 +1,4 @@
-use crate::db::queries::{AssetStats, DailyTotal, StatusCount};
+use crate::db::queries::{AssetStats, DailyTotal, FeeGrouping, StatusCount};
 use crate::error::AppError;
 use crate::services::query_cache::{
     cache_key_asset_stats, cache_key_daily_totals, cache_key_status_counts, CacheConfig,
@@ -11,11 +11,14 @@ use axum::{
     response::{IntoResponse, Response},
     Json,
 };
-use serde::Deserialize;
+use chrono::{Duration as ChronoDuration, Utc};
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
 const MIN_DAYS: i32 = 1;
 const MAX_DAYS: i32 = 365;
+const MIN_HOURS: i32 = 1;
+const MAX_HOURS: i32 = 24 * 30;
 
 #[derive(Deserialize)]
 pub struct DailyTotalsQuery {
@@ -34,6 +37,61 @@ impl DailyTotalsQuery {
     }
 }
 
+#[derive(Deserialize)]
+pub struct ProcessorStatsQuery {
+    #[serde(default = "default_hours")]
+    hours: i32,
+}
+
+fn default_hours() -> i32 {
+    24
+}
+
+impl ProcessorStatsQuery {
+    pub fn validate(&self) -> Result<(), AppError> {
+        validate_range(
+            "hours",
+            self.hours as i64,
+            MIN_HOURS as i64,
+            MAX_HOURS as i64,
+        )
+        .map_err(|e| AppError::BadRequest(e.to_string()))
+    }
+}
+
+/// Look-back window for [`fee_stats`], in whole UTC days; `day` means
+/// "today so far".
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeeWindow {
+    Day,
+    Week,
+    #[default]
+    Month,
+    Quarter,
+    Year,
+}
+
+impl FeeWindow {
+    fn days(self) -> i64 {
+        match self {
+            FeeWindow::Day => 1,
+            FeeWindow::Week => 7,
+            FeeWindow::Month => 30,
+            FeeWindow::Quarter => 90,
+            FeeWindow::Year => 365,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct FeeStatsQuery {
+    #[serde(default)]
+    window: FeeWindow,
+    #[serde(default)]
+    group_by: FeeGrouping,
+}
+
 #[derive(Debug, serde::Serialize)]
 pub struct CombinedCacheMetrics {
     pub query_cache: crate::services::query_cache::CacheMetrics,
@@ -187,6 +245,62 @@ pub async fn asset_stats(State(state): State<ApiState>) -> Result<impl IntoRespo
     })
 }
 
+/// Retry distribution at success, time-to-success and DLQ entry rate per
+/// error class over the last `hours`, for tuning
+/// `HORIZON_VERIFY_MAX_ATTEMPTS` and the poll interval.
+pub async fn processor_stats(
+    State(state): State<ApiState>,
+    axum::extract::Query(query): axum::extract::Query<ProcessorStatsQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    query.validate()?;
+
+    let (pool, replica_used) = state.app_state.pool_manager.read_pool().await;
+    let stats = crate::db::queries::get_processor_stats(pool, query.hours)
+        .await
+        .map_err(AppError::Database)?;
+
+    let mut response: Response = (StatusCode::OK, Json(stats)).into_response();
+    if replica_used {
+        response
+            .headers_mut()
+            .insert("X-Read-Consistency", HeaderValue::from_static("eventual"));
+    }
+    Ok(response)
+}
+
+/// Fee revenue over `window`, per asset or per partner and asset, from the
+/// `fee_amount` of completed transactions.
+pub async fn fee_stats(
+    State(state): State<ApiState>,
+    axum::extract::Query(query): axum::extract::Query<FeeStatsQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let today = Utc::now()
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .expect("midnight exists")
+        .and_utc();
+    let since = today - ChronoDuration::days(query.window.days() - 1);
+
+    let (pool, replica_used) = state.app_state.pool_manager.read_pool().await;
+    let revenue = crate::db::queries::get_fee_revenue(pool, since, query.group_by)
+        .await
+        .map_err(AppError::Database)?;
+
+    let body = serde_json::json!({
+        "window": query.window,
+        "group_by": query.group_by,
+        "since": since,
+        "revenue": revenue,
+    });
+    let mut response: Response = (StatusCode::OK, Json(body)).into_response();
+    if replica_used {
+        response
+            .headers_mut()
+            .insert("X-Read-Consistency", HeaderValue::from_static("eventual"));
+    }
+    Ok(response)
+}
+
 pub async fn cache_metrics(State(state): State<ApiState>) -> Result<impl IntoResponse, AppError> {
     let query_cache_metrics = state.app_state.query_cache.metrics();
     let combined_metrics = CombinedCacheMetrics {
@@ -235,6 +349,40 @@ mod tests {
         assert!(query.validate().is_err());
     }
 
+    #[test]
+    fn test_processor_stats_query_hours() {
+        let query = ProcessorStatsQuery {
+            hours: default_hours(),
+        };
+        assert!(query.validate().is_ok());
+
+        let query = ProcessorStatsQuery { hours: MAX_HOURS };
+        assert!(query.validate().is_ok());
+
+        let query = ProcessorStatsQuery { hours: 0 };
+        assert!(query.validate().is_err());
+
+        let query = ProcessorStatsQuery {
+            hours: MAX_HOURS + 1,
+        };
+        assert!(query.validate().is_err());
+    }
+
+    #[test]
+    fn test_fee_stats_query_defaults_and_values() {
+        let query: FeeStatsQuery = serde_json::from_str("{}").unwrap();
+        assert_eq!(query.window, FeeWindow::Month);
+        assert_eq!(query.group_by, FeeGrouping::Asset);
+
+        let query: FeeStatsQuery =
+            serde_json::from_str(r#"{"window":"quarter","group_by":"partner"}"#).unwrap();
+        assert_eq!(query.window.days(), 90);
+        assert_eq!(query.group_by, FeeGrouping::Partner);
+
+        assert!(serde_json::from_str::<FeeStatsQuery>(r#"{"window":"7d"}"#).is_err());
+        assert!(serde_json::from_str::<FeeStatsQuery>(r#"{"group_by":"tenant"}"#).is_err());
+    }
+
     #[test]
     fn test_daily_totals_query_boundary_values() {
         // Test boundary values
@@ -0,0 +1,96 @@
+//! Customer-facing status links.
+//!
+//! | Method | Path                             | Effect                                    |
+//! |--------|----------------------------------|-------------------------------------------|
+//! | `POST` | `/transactions/:id/status-link`  | Issue a signed status URL for a receipt   |
+//! | `GET`  | `/status/:token`                 | Sanitized status, no API key needed       |
+//!
+//! See [`crate::services::status_page`] for the token and configuration.
+//! `GET /status/:token` answers in the `Accept-Language` locale and says
+//! which in `Content-Language`.
+
+use crate::db::queries;
+use crate::error::AppError;
+use crate::i18n::{Locale, Message};
+use crate::services::status_page::{PublicStatus, StatusPageConfig};
+use crate::ApiState;
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, HeaderValue},
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize)]
+pub struct StatusLink {
+    pub url: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+fn config() -> Result<Option<StatusPageConfig>, AppError> {
+    StatusPageConfig::from_env()
+        .map_err(|e| AppError::Internal(format!("Status page is misconfigured: {e}")))
+}
+
+fn not_found(id: Uuid, e: sqlx::Error) -> AppError {
+    match e {
+        sqlx::Error::RowNotFound => AppError::NotFound(format!("Transaction {id} not found")),
+        _ => AppError::DatabaseError(e.to_string()),
+    }
+}
+
+pub async fn create_status_link(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<StatusLink>, AppError> {
+    let config = config()?.ok_or_else(|| {
+        AppError::NotFound("Status links are not enabled (STATUS_PAGE_SECRET)".to_string())
+    })?;
+    let (pool, _) = state.app_state.pool_manager.read_pool().await;
+    queries::get_transaction(pool, id)
+        .await
+        .map_err(|e| not_found(id, e))?;
+
+    let (token, expires_at) = config.issue(id, state.app_state.clock.now());
+    Ok(Json(StatusLink {
+        url: config.url(&token),
+        expires_at,
+    }))
+}
+
+/// An invalid, expired or unknown token is a plain `404`, so a link
+/// reveals nothing about why it stopped working.
+pub async fn get_status(
+    State(state): State<ApiState>,
+    Path(token): Path<String>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AppError> {
+    let locale = Locale::from_headers(&headers);
+    let missing = || AppError::NotFound(Message::StatusLinkNotFound.render(locale, &[]));
+    let config = config()?.ok_or_else(missing)?;
+    let id = config
+        .verify(&token, state.app_state.clock.now())
+        .ok_or_else(missing)?;
+    let (pool, _) = state.app_state.pool_manager.read_pool().await;
+    let transaction = queries::get_transaction(pool, id)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => missing(),
+            e => AppError::DatabaseError(e.to_string()),
+        })?;
+
+    Ok((
+        [
+            (header::CACHE_CONTROL, HeaderValue::from_static("no-store")),
+            (
+                header::CONTENT_LANGUAGE,
+                HeaderValue::from_static(locale.as_str()),
+            ),
+            (header::VARY, HeaderValue::from_static("Accept-Language")),
+        ],
+        Json(PublicStatus::new(&transaction, locale)),
+    ))
+}
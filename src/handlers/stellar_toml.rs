@@ -0,0 +1,30 @@
+//! SEP-1 discovery.
+//!
+//! | Method | Path                        | Effect                          |
+//! |--------|-----------------------------|---------------------------------|
+//! | `GET`  | `/.well-known/stellar.toml` | The anchor's `stellar.toml`     |
+//!
+//! Public, and served with `Access-Control-Allow-Origin: *` as SEP-1
+//! requires. See [`crate::services::stellar_toml`] for what is published.
+
+use crate::error::AppError;
+use crate::services::stellar_toml;
+use crate::ApiState;
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::IntoResponse,
+};
+
+/// GET /.well-known/stellar.toml
+pub async fn stellar_toml(State(state): State<ApiState>) -> Result<impl IntoResponse, AppError> {
+    let body = stellar_toml::generate(&state.app_state.db).await?;
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "text/plain; charset=utf-8"),
+            (header::ACCESS_CONTROL_ALLOW_ORIGIN, "*"),
+        ],
+        body,
+    ))
+}
@@ -1,24 +1,36 @@
+use crate::db::models::StellarTransaction;
 use crate::db::models::Transaction as TxModel;
 use crate::db::{models::Transaction, queries};
+use crate::domain::{DomainEvent, StellarAddress};
 use crate::error::AppError;
+use crate::handlers::ack::{self, AcceptedResponse, AckMode};
+use crate::middleware::versioning::ApiVersion;
+use crate::services::amount_limits::{self, AmountCheck, AmountLimits};
+use crate::services::dlq_capacity;
+use crate::services::webhook_dedup::{payload_hash, DedupConfig, DEDUPLICATED_HEADER};
+use crate::stellar::summary::{summarize, TransactionSummary};
+use crate::telemetry::BusinessAttributes;
+use crate::tenant::TenantContext;
 use crate::utils::cursor as cursor_util;
+use crate::utils::fields::{FieldSelection, FieldsQuery, TRANSACTION_FIELDS};
 use crate::validation::{
-    sanitize_string, validate_asset_code, validate_max_len, validate_positive_amount,
-    validate_stellar_address, AMOUNT_INPUT_MAX_LEN, ANCHOR_TRANSACTION_ID_MAX_LEN,
-    CALLBACK_STATUS_MAX_LEN, CALLBACK_TYPE_MAX_LEN,
+    parse_stellar_address, sanitize_string, validate_asset_code, validate_max_len,
+    validate_metadata, validate_positive_amount, AMOUNT_INPUT_MAX_LEN,
+    ANCHOR_TRANSACTION_ID_MAX_LEN, CALLBACK_STATUS_MAX_LEN, CALLBACK_TYPE_MAX_LEN,
 };
 use crate::{ApiState, AppState};
 use axum::{
     extract::{Path, Query, State},
     http::{HeaderValue, StatusCode},
     response::{IntoResponse, Response},
-    Json,
+    Extension, Json,
 };
 use serde::{Deserialize, Serialize};
 use sqlx::types::BigDecimal;
 use std::str::FromStr;
 use std::sync::atomic::Ordering;
-use tracing::instrument;
+use tracing::field::Empty;
+use tracing::{instrument, Span};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
@@ -34,7 +46,18 @@ pub struct CallbackPayload {
     pub memo: Option<String>,
     /// Memo type for the Stellar transaction. Must be one of: `text`, `hash`, `id`.
     pub memo_type: Option<String>,
+    /// Partner-defined JSON object (order ids, user ids, …), at most 4 KiB.
+    /// Accepted by the v2 API only; searchable via `/transactions/search`.
     pub metadata: Option<serde_json::Value>,
+    /// Hash of the Stellar transaction settling the deposit (64 hex chars).
+    /// The transaction is only completed once Horizon confirms it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(pattern = "^[0-9a-fA-F]{64}$")]
+    pub stellar_tx_hash: Option<String>,
+    /// Fee charged on the transaction, in `asset_code`; at most `amount`.
+    /// Reported by `/admin/stats/fees` and partner statements.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount_fee: Option<String>,
 }
 
 /// Minimal webhook payload carrying an opaque event identifier.
@@ -65,7 +88,7 @@ pub struct WebhookTransactionResponse {
 }
 
 struct ValidatedWebhookTransaction {
-    stellar_address: String,
+    stellar_address: StellarAddress,
     amount: BigDecimal,
     asset_code: String,
     anchor_transaction_id: Option<String>,
@@ -89,7 +112,7 @@ fn validate_webhook_payload(
     let callback_type = sanitize_optional(payload.callback_type);
     let callback_status = sanitize_optional(payload.callback_status);
 
-    validate_stellar_address(&stellar_address)
+    let stellar_address = parse_stellar_address(&stellar_address)
         .map_err(|err| AppError::Validation(err.to_string()))?;
     validate_asset_code(&asset_code).map_err(|err| AppError::Validation(err.to_string()))?;
     validate_max_len("amount", &amount_str, AMOUNT_INPUT_MAX_LEN)
@@ -126,15 +149,41 @@ fn validate_webhook_payload(
     })
 }
 
+/// Look up the asset's amount limits and set the status `tx` is inserted with:
+/// `refund_pending` below the minimum, `compliance_review` above the maximum.
+async fn apply_amount_limits(
+    pool: &sqlx::PgPool,
+    tx: &mut Transaction,
+) -> Result<(AmountCheck, AmountLimits), AppError> {
+    let limits = queries::get_asset_amount_limits(pool, &tx.asset_code).await?;
+    let check = limits.check(&tx.amount);
+    if check != AmountCheck::WithinLimits {
+        tracing::warn!(
+            asset_code = %tx.asset_code,
+            amount = %tx.amount,
+            ?check,
+            "deposit outside asset amount limits"
+        );
+    }
+    tx.status = check.status();
+    Ok((check, limits))
+}
+
 /// Process a raw transaction callback from an external anchor.
 ///
 /// Validates and sanitizes all fields before inserting the transaction into the
-/// database. Returns `201 Created` with the new transaction ID and initial status.
+/// database. Returns `201 Created` with the new transaction ID and initial status,
+/// which is `refund_pending` / `compliance_review` when the amount is outside
+/// the asset's limits (see [`crate::services::amount_limits`]).
 ///
 /// # Errors
 /// - `400 Bad Request` – validation fails (invalid address, amount, field length, etc.)
 /// - `500 Internal Server Error` – database insertion fails
-#[instrument(name = "webhook.transaction_callback", skip(state, payload))]
+#[instrument(
+    name = "webhook.transaction_callback",
+    skip(state, payload),
+    fields(asset_code = Empty, amount_bucket = Empty)
+)]
 pub async fn transaction_callback(
     State(state): State<AppState>,
     Json(payload): Json<WebhookTransactionRequest>,
@@ -149,8 +198,8 @@ pub async fn transaction_callback(
         carrier.get("traceparent").cloned()
     });
 
-    let tx = Transaction::new(
-        payload.stellar_address,
+    let mut tx = Transaction::new(
+        payload.stellar_address.account().to_string(),
         payload.amount,
         payload.asset_code,
         payload.anchor_transaction_id,
@@ -160,15 +209,19 @@ pub async fn transaction_callback(
         None, // memo_type
         None, // metadata
     )
+    .with_muxed_id(payload.stellar_address.muxed_id())
     .with_trace_id(trace_id);
+    BusinessAttributes::for_transaction(&tx, None).record(&Span::current());
+    let (check, limits) = apply_amount_limits(&state.db, &mut tx).await?;
 
     let inserted = queries::insert_transaction(&state.db, &tx).await?;
+    amount_limits::notify(&state.db, &state.redis_url, &inserted, check, &limits).await;
 
     Ok((
         StatusCode::CREATED,
         Json(WebhookTransactionResponse {
             id: inserted.id.to_string(),
-            status: inserted.status,
+            status: inserted.status.to_string(),
         }),
     ))
 }
@@ -179,7 +232,7 @@ mod tests {
 
     fn valid_payload() -> WebhookTransactionRequest {
         WebhookTransactionRequest {
-            stellar_address: "G".to_owned() + &"A".repeat(55),
+            stellar_address: "GAAZI4TCR3TY5OJHCTJC2A4QSY6CJWJH5IAJTGKIN2ER7LBNVKOCCWN7".to_string(),
             amount: "42.50".to_string(),
             asset_code: "USD".to_string(),
             anchor_transaction_id: Some("anchor-1".to_string()),
@@ -288,6 +341,44 @@ mod tests {
         assert_eq!(parsed.callback_status.as_deref(), Some("completed"));
     }
 
+    #[test]
+    fn callback_metadata_is_v2_only_and_size_limited() {
+        let metadata = serde_json::json!({"order_id": "ORD-1"});
+        assert!(validate_callback_metadata(Some(&metadata), Some(ApiVersion::V2)).is_ok());
+        assert!(validate_callback_metadata(Some(&metadata), None).is_ok());
+        assert!(validate_callback_metadata(Some(&metadata), Some(ApiVersion::V1)).is_err());
+        assert!(validate_callback_metadata(None, Some(ApiVersion::V1)).is_ok());
+
+        let too_big = serde_json::json!({"blob": "x".repeat(5000)});
+        assert!(validate_callback_metadata(Some(&too_big), Some(ApiVersion::V2)).is_err());
+    }
+
+    #[test]
+    fn stellar_tx_hash_is_hex_and_lower_cased() {
+        let hash = "AB".repeat(32);
+        assert_eq!(
+            normalize_stellar_tx_hash(Some(&hash)).unwrap(),
+            Some("ab".repeat(32))
+        );
+        assert_eq!(normalize_stellar_tx_hash(None).unwrap(), None);
+        assert!(normalize_stellar_tx_hash(Some("abc")).is_err());
+        assert!(normalize_stellar_tx_hash(Some(&"g".repeat(64))).is_err());
+    }
+
+    #[test]
+    fn amount_fee_is_between_zero_and_amount() {
+        let amount = BigDecimal::from(100);
+        assert_eq!(parse_amount_fee(None, &amount).unwrap(), None);
+        assert_eq!(
+            parse_amount_fee(Some("1.25"), &amount).unwrap(),
+            Some(BigDecimal::from_str("1.25").unwrap())
+        );
+        assert!(parse_amount_fee(Some("0"), &amount).is_ok());
+        assert!(parse_amount_fee(Some("-1"), &amount).is_err());
+        assert!(parse_amount_fee(Some("100.01"), &amount).is_err());
+        assert!(parse_amount_fee(Some("abc"), &amount).is_err());
+    }
+
     #[test]
     fn validate_webhook_payload_rejects_overlong_optional_fields() {
         let mut payload = valid_payload();
@@ -311,6 +402,18 @@ pub struct WebhookResponse {
     pub message: String,
 }
 
+/// 503 with `body` and a `Retry-After`, for callbacks turned away while the
+/// service sheds load.
+fn service_busy(body: &'static str) -> Response {
+    let mut response =
+        axum::response::Response::new(axum::body::boxed(axum::body::Full::from(body)));
+    *response.status_mut() = StatusCode::SERVICE_UNAVAILABLE;
+    response
+        .headers_mut()
+        .insert("Retry-After", HeaderValue::from_static("30"));
+    response
+}
+
 fn validate_memo_type(memo_type: &Option<String>) -> Result<(), AppError> {
     if let Some(mt) = memo_type {
         match mt.as_str() {
@@ -325,32 +428,126 @@ fn validate_memo_type(memo_type: &Option<String>) -> Result<(), AppError> {
     }
 }
 
+/// Stellar transaction hashes are 32 bytes, hex-encoded; stored lower-case.
+fn normalize_stellar_tx_hash(hash: Option<&str>) -> Result<Option<String>, AppError> {
+    let Some(hash) = hash else {
+        return Ok(None);
+    };
+    if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(AppError::Validation(
+            "stellar_tx_hash: must be 64 hex characters".to_string(),
+        ));
+    }
+    Ok(Some(hash.to_ascii_lowercase()))
+}
+
+/// The fee a partner reports must be a non-negative decimal no larger than
+/// the amount it was charged on.
+fn parse_amount_fee(
+    fee: Option<&str>,
+    amount: &BigDecimal,
+) -> Result<Option<BigDecimal>, AppError> {
+    let Some(fee) = fee else {
+        return Ok(None);
+    };
+    let invalid = || AppError::Validation(format!("amount_fee: invalid fee {fee}"));
+    let fee = BigDecimal::from_str(fee.trim()).map_err(|_| invalid())?;
+    if fee < BigDecimal::from(0) || &fee > amount {
+        return Err(AppError::Validation(
+            "amount_fee: must be between 0 and the amount".to_string(),
+        ));
+    }
+    Ok(Some(fee))
+}
+
+/// Partner metadata is written through the v2 API only; v1 is frozen ahead of
+/// its sunset. Unversioned routes follow `Accept`, defaulting to v2.
+fn validate_callback_metadata(
+    metadata: Option<&serde_json::Value>,
+    api_version: Option<ApiVersion>,
+) -> Result<(), AppError> {
+    let Some(metadata) = metadata else {
+        return Ok(());
+    };
+    if api_version == Some(ApiVersion::V1) {
+        return Err(AppError::Validation(
+            "metadata: only accepted by the v2 API (POST /api/v2/callback)".to_string(),
+        ));
+    }
+    validate_metadata(metadata).map_err(|e| AppError::Validation(e.to_string()))
+}
+
 /// Receive a fiat deposit callback from the Stellar Anchor Platform.
 ///
 /// Applies back-pressure when the pending queue exceeds `MAX_PENDING_QUEUE`
 /// (default 10 000), returning `503 Service Unavailable` with a `Retry-After: 30`
-/// header. On success, inserts the transaction and returns `201 Created`.
+/// header. On success, inserts the transaction and acknowledges according to
+/// the route's [`AckMode`]: `201 Created` with the transaction (sync), or
+/// `202 Accepted` with a `Location` header pointing at `GET /transactions/{id}`
+/// for status polling (async).
+///
+/// A payload identical to one accepted within `WEBHOOK_DEDUP_WINDOW_HOURS`
+/// (matched by content hash, see [`crate::services::webhook_dedup`]) does not
+/// create a new transaction: the original is returned with `200 OK` (sync) or
+/// its `202` acknowledgment (async), plus `x-webhook-deduplicated: true`.
+///
+/// When the caller identifies as a partner (`X-API-Key` or `X-Tenant-ID`),
+/// the asset must be on that partner's `allowed_assets` list.
+///
+/// Amounts below the asset's `min_amount` are stored as `refund_pending` and
+/// amounts above its `max_amount` as `compliance_review`, and a
+/// `transaction.refund_pending` / `transaction.compliance_review` webhook is
+/// queued (see [`crate::services::amount_limits`]).
 ///
 /// # Errors
 /// - `400 Bad Request` – invalid `memo_type` or unparseable `amount`
-/// - `503 Service Unavailable` – queue depth exceeded
+/// - `403 Forbidden` – asset not on the partner's allowlist (`ERR_TRANSACTION_007`)
+/// - `503 Service Unavailable` – queue depth exceeded, or intake paused while
+///   the DLQ is full (`DLQ_OVERFLOW_POLICY=pause_intake`)
 /// - `500 Internal Server Error` – database error
 #[utoipa::path(
     post,
     path = "/callback",
     request_body = CallbackPayload,
     responses(
-        (status = 201, description = "Transaction created", body = crate::schemas::TransactionSchema),
+        (status = 200, description = "Duplicate of a payload accepted within the dedup window; original transaction returned", body = crate::schemas::TransactionSchema),
+        (status = 201, description = "Transaction created (sync acknowledgment mode)", body = crate::schemas::TransactionSchema),
+        (status = 202, description = "Transaction accepted; poll the status URL (async acknowledgment mode)", body = AcceptedResponse,
+            headers(
+                ("Location" = String, description = "Status URL, `/transactions/{id}`"),
+                ("Retry-After" = u32, description = "Suggested polling interval in seconds")
+            )
+        ),
         (status = 400, description = "Invalid payload"),
+        (status = 403, description = "Asset not allowed for this partner (ERR_TRANSACTION_007)"),
         (status = 500, description = "Processing error")
     ),
     tag = "Webhooks"
 )]
-#[instrument(name = "webhook.callback", skip(state, payload))]
+#[instrument(
+    name = "webhook.callback",
+    skip(state, tenant, api_version, ack_mode, payload),
+    fields(
+        partner_id = Empty,
+        asset_code = Empty,
+        amount_bucket = Empty,
+        api_version = Empty
+    )
+)]
 pub async fn callback(
     State(state): State<ApiState>,
+    tenant: Option<TenantContext>,
+    api_version: ApiVersion,
+    ack_mode: Option<Extension<AckMode>>,
     Json(payload): Json<CallbackPayload>,
 ) -> Result<impl IntoResponse, AppError> {
+    BusinessAttributes {
+        partner_id: tenant.as_ref().map(|t| t.tenant_id),
+        ..Default::default()
+    }
+    .with_api_version(api_version)
+    .record(&Span::current());
+
     // Back-pressure: reject if pending queue exceeds threshold
     let depth = state.app_state.pending_queue_depth.load(Ordering::Relaxed);
     let max_pending = std::env::var("MAX_PENDING_QUEUE")
@@ -365,23 +562,35 @@ pub async fn callback(
         );
         // Emit metric counter via tracing event (metrics crate not available)
         tracing::info!(counter.callback_rejected_backpressure = 1u64);
-        let mut response = axum::response::Response::new(axum::body::boxed(
-            axum::body::Full::from(r#"{"error":"service busy, retry later"}"#),
+        return Ok(service_busy(r#"{"error":"service busy, retry later"}"#));
+    }
+    // DLQ overflow policy `pause_intake` (see services::dlq_capacity)
+    if dlq_capacity::intake_paused() {
+        tracing::warn!("callback_rejected_dlq_full: intake paused");
+        return Ok(service_busy(
+            r#"{"error":"intake paused while the dead-letter queue is full, retry later"}"#,
         ));
-        *response.status_mut() = StatusCode::SERVICE_UNAVAILABLE;
-        response
-            .headers_mut()
-            .insert("Retry-After", HeaderValue::from_static("30"));
-        return Ok(response.into_response());
     }
 
     validate_memo_type(&payload.memo_type)?;
+    let address = parse_stellar_address(&payload.stellar_account)
+        .map_err(|err| AppError::Validation(err.to_string()))?;
+    let stellar_tx_hash = normalize_stellar_tx_hash(payload.stellar_tx_hash.as_deref())?;
+    validate_callback_metadata(payload.metadata.as_ref(), Some(api_version))?;
+
+    if let Some(tenant) = &tenant {
+        tenant.config.check_asset_allowed(&payload.asset_code)?;
+    }
 
     let amount = sqlx::types::BigDecimal::from_str(&payload.amount)
         .map_err(|_| AppError::Validation(format!("Invalid amount: {}", payload.amount)))?;
+    let fee_amount = parse_amount_fee(payload.amount_fee.as_deref(), &amount)?;
+
+    let dedup = DedupConfig::from_env();
+    let hash = payload_hash(payload.anchor_transaction_id.as_deref(), &payload);
 
-    let tx = Transaction::new(
-        payload.stellar_account,
+    let mut tx = Transaction::new(
+        address.account().to_string(),
         amount,
         payload.asset_code,
         payload.anchor_transaction_id,
@@ -390,11 +599,60 @@ pub async fn callback(
         payload.memo,
         payload.memo_type,
         payload.metadata,
-    );
+    )
+    .with_muxed_id(address.muxed_id())
+    .with_stellar_tx_hash(stellar_tx_hash)
+    .with_fee_amount(fee_amount);
+    BusinessAttributes::for_transaction(&tx, None).record(&Span::current());
+    let (check, limits) = apply_amount_limits(&state.app_state.db, &mut tx).await?;
+
+    let (inserted, deduplicated) = match dedup.window {
+        Some(window) => {
+            queries::insert_transaction_deduplicated(&state.app_state.db, &tx, &hash, window)
+                .await?
+        }
+        None => (
+            queries::insert_transaction(&state.app_state.db, &tx).await?,
+            false,
+        ),
+    };
 
-    let inserted = queries::insert_transaction(&state.app_state.db, &tx).await?;
+    if !deduplicated {
+        amount_limits::notify(
+            &state.app_state.db,
+            &state.app_state.redis_url,
+            &inserted,
+            check,
+            &limits,
+        )
+        .await;
+        state
+            .app_state
+            .domain_events
+            .publish(DomainEvent::TransactionCreated {
+                transaction_id: inserted.id,
+                tenant_id: tenant.as_ref().map(|t| t.tenant_id),
+                stellar_account: inserted.stellar_account.clone(),
+                amount: inserted.amount.clone(),
+                asset_code: inserted.asset_code.clone(),
+                status: inserted.status.as_str().to_string(),
+                at: inserted.created_at,
+            });
+    }
 
-    Ok((StatusCode::CREATED, Json(inserted)).into_response())
+    let mut response = match ack_mode.map(|Extension(mode)| mode).unwrap_or_default() {
+        // A resend gets the original transaction back with 200, not a new 201.
+        AckMode::Sync if deduplicated => (StatusCode::OK, Json(inserted)).into_response(),
+        AckMode::Sync => (StatusCode::CREATED, Json(inserted)).into_response(),
+        AckMode::Async => ack::accepted(inserted.id, inserted.status.as_str()),
+    };
+    if deduplicated {
+        tracing::info!(transaction_id = %inserted.id, "callback_deduplicated: payload hash seen within window");
+        response
+            .headers_mut()
+            .insert(DEDUPLICATED_HEADER, HeaderValue::from_static("true"));
+    }
+    Ok(response)
 }
 
 /// Generic webhook receiver for event-driven integrations.
@@ -438,10 +696,12 @@ pub async fn handle_webhook(
     get,
     path = "/transactions/{id}",
     params(
-        ("id" = String, Path, description = "Transaction ID")
+        ("id" = String, Path, description = "Transaction ID"),
+        ("fields" = Option<String>, Query, description = "Comma-separated fields to return, e.g. `id,status,amount`; all fields when omitted")
     ),
     responses(
         (status = 200, description = "Transaction found", body = crate::schemas::TransactionSchema),
+        (status = 400, description = "Unknown field in `fields`"),
         (status = 404, description = "Transaction not found"),
         (status = 500, description = "Database error")
     ),
@@ -451,7 +711,10 @@ pub async fn handle_webhook(
 pub async fn get_transaction(
     State(state): State<ApiState>,
     Path(id): Path<Uuid>,
+    Query(params): Query<FieldsQuery>,
 ) -> Result<impl IntoResponse, AppError> {
+    let fields = FieldSelection::parse(params.fields.as_deref(), TRANSACTION_FIELDS)
+        .map_err(AppError::BadRequest)?;
     let (pool, replica_used) = state.app_state.pool_manager.read_pool().await;
 
     let transaction = queries::get_transaction(pool, id)
@@ -461,7 +724,12 @@ pub async fn get_transaction(
             _ => AppError::DatabaseError(e.to_string()),
         })?;
 
-    let mut response: Response = Json(transaction).into_response();
+    let body =
+        fields
+            .project(serde_json::to_value(&transaction).map_err(|e| {
+                AppError::Internal(format!("Failed to serialize transaction: {e}"))
+            })?);
+    let mut response: Response = Json(body).into_response();
     if replica_used {
         response
             .headers_mut()
@@ -471,17 +739,99 @@ pub async fn get_transaction(
     Ok(response)
 }
 
+/// The Stellar transaction behind a transaction, decoded.
+#[derive(Debug, Serialize)]
+pub struct StellarDetails {
+    pub transaction_id: Uuid,
+    pub hash: String,
+    pub ledger: i64,
+    pub successful: bool,
+    /// Absent when the XDR is outside what can be decoded; see
+    /// `decode_error`.
+    #[serde(flatten)]
+    pub summary: Option<TransactionSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decode_error: Option<String>,
+}
+
+/// Get the Stellar transaction behind a transaction
+///
+/// Decodes the envelope and result XDR of its `stellar_tx_hash` into
+/// operation summaries. XDR not stored yet is fetched from Horizon once.
+#[instrument(name = "webhook.get_transaction_stellar", skip(state), fields(transaction.id = %id))]
+pub async fn get_transaction_stellar(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<StellarDetails>, AppError> {
+    let (pool, _) = state.app_state.pool_manager.read_pool().await;
+    let transaction = queries::get_transaction(pool, id)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => AppError::NotFound(format!("Transaction {} not found", id)),
+            _ => AppError::DatabaseError(e.to_string()),
+        })?;
+    let hash = transaction.stellar_tx_hash.ok_or_else(|| {
+        AppError::NotFound(format!("Transaction {id} has no Stellar transaction"))
+    })?;
+
+    let stored = StellarTransaction::find(pool, &hash)
+        .await
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+    let xdr = match stored {
+        Some(xdr) => xdr,
+        None => {
+            let record = state
+                .app_state
+                .horizon_client
+                .get_transaction(&hash)
+                .await
+                .map_err(|e| AppError::Internal(format!("Horizon lookup failed: {e}")))?
+                .ok_or_else(|| {
+                    AppError::NotFound(format!("Stellar transaction {hash} is not in a ledger"))
+                })?;
+            let xdr = StellarTransaction::from_horizon(&record).ok_or_else(|| {
+                AppError::Internal(format!("Horizon returned no envelope for {hash}"))
+            })?;
+            xdr.record(state.app_state.pool_manager.primary())
+                .await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            xdr
+        }
+    };
+
+    let (summary, decode_error) = match summarize(&xdr.envelope_xdr, xdr.result_xdr.as_deref()) {
+        Ok(summary) => (Some(summary), None),
+        Err(e) => (None, Some(e)),
+    };
+    Ok(Json(StellarDetails {
+        transaction_id: id,
+        hash: xdr.hash,
+        ledger: xdr.ledger,
+        successful: xdr.successful,
+        summary,
+        decode_error,
+    }))
+}
+
 /// Query parameters for paginated transaction listing.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct ListQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub cursor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<i64>,
     /// direction: "forward" (older items) or "backward" (newer items)
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub direction: Option<String>,
     /// ISO 8601 start date filter (inclusive): e.g. 2024-01-01T00:00:00Z
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub from_date: Option<String>,
     /// ISO 8601 end date filter (exclusive): e.g. 2024-02-01T00:00:00Z
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub to_date: Option<String>,
+    /// Comma-separated fields to return for each transaction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<String>,
 }
 
 /// List transactions with cursor-based pagination.
@@ -500,10 +850,12 @@ pub struct ListQuery {
     params(
         ("cursor" = Option<String>, Query, description = "Cursor for pagination"),
         ("limit" = Option<i64>, Query, description = "Page size"),
-        ("direction" = Option<String>, Query, description = "forward or backward")
+        ("direction" = Option<String>, Query, description = "forward or backward"),
+        ("fields" = Option<String>, Query, description = "Comma-separated fields to return for each transaction, e.g. `id,status,amount`; all fields when omitted")
     ),
     responses(
         (status = 200, description = "List transactions with pagination metadata"),
+        (status = 400, description = "Invalid cursor, dates or `fields`"),
         (status = 500, description = "Database error")
     ),
     tag = "Transactions"
@@ -514,6 +866,8 @@ pub async fn list_transactions(
 ) -> Result<impl IntoResponse, AppError> {
     let limit = params.limit.unwrap_or(25).min(100);
     let backward = params.direction.as_deref() == Some("backward");
+    let fields = FieldSelection::parse(params.fields.as_deref(), TRANSACTION_FIELDS)
+        .map_err(AppError::BadRequest)?;
 
     let decoded_cursor = if let Some(ref c) = params.cursor {
         match cursor_util::decode(c) {
@@ -579,7 +933,7 @@ pub async fn list_transactions(
         .map(|r: &TxModel| cursor_util::encode(r.created_at, r.id));
 
     let resp = serde_json::json!({
-        "data": rows,
+        "data": fields.project_all(&rows),
         "meta": {
             "next_cursor": next_cursor,
             "has_more": has_more
@@ -608,6 +962,8 @@ pub async fn list_transactions_api(
     let app_state = api_state.app_state;
     let limit = params.limit.unwrap_or(25).min(100);
     let backward = params.direction.as_deref() == Some("backward");
+    let fields = FieldSelection::parse(params.fields.as_deref(), TRANSACTION_FIELDS)
+        .map_err(AppError::BadRequest)?;
 
     let decoded_cursor = if let Some(ref c) = params.cursor {
         match cursor_util::decode(c) {
@@ -670,7 +1026,7 @@ pub async fn list_transactions_api(
         .map(|r: &TxModel| cursor_util::encode(r.created_at, r.id));
 
     let resp = serde_json::json!({
-        "data": rows,
+        "data": fields.project_all(&rows),
         "meta": {
             "next_cursor": next_cursor,
             "has_more": has_more
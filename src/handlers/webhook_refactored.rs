@@ -172,7 +172,7 @@ pub async fn transaction_callback(
         StatusCode::CREATED,
         Json(WebhookTransactionResponse {
             id: inserted.id.to_string(),
-            status: inserted.status,
+            status: inserted.status.to_string(),
         }),
     ))
 }
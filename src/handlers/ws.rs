@@ -10,10 +10,12 @@ use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use tokio::sync::{broadcast, Mutex};
+use tokio::sync::Mutex;
 use tokio::time::{timeout, Duration};
-use uuid::Uuid;
 
+use crate::domain::DomainEvent;
+use crate::ports::Delivery;
+pub use crate::services::transaction_events::TransactionStatusUpdate;
 use crate::AppState;
 
 use crate::handlers::ws_error::{validate_message_size, validate_ws_token};
@@ -32,15 +34,6 @@ const RESYNC_MAX_LIMIT: i64 = 100;
 
 // ── Wire types ───────────────────────────────────────────────────────────────
 
-#[derive(Debug, Clone, Serialize, Deserialize, async_graphql::SimpleObject)]
-pub struct TransactionStatusUpdate {
-    pub transaction_id: Uuid,
-    pub tenant_id: Uuid,
-    pub status: String,
-    pub timestamp: chrono::DateTime<chrono::Utc>,
-    pub message: Option<String>,
-}
-
 /// Messages the server pushes to the client.
 #[derive(Debug, Serialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -115,7 +108,11 @@ async fn handle_socket(socket: WebSocket, state: AppState, client_addr: String)
     // Per-client dropped-message counter (metric).
     let messages_dropped_total = Arc::new(std::sync::atomic::AtomicU64::new(0));
 
-    let mut rx = state.tx_broadcast.subscribe();
+    let mut events = state
+        .domain_events
+        .subscribe(Box::new(|event: &DomainEvent| {
+            matches!(event, DomainEvent::StatusChanged { .. })
+        }));
 
     // ── Receive task ─────────────────────────────────────────────────────────
     let pong_flag = Arc::clone(&pong_received);
@@ -179,9 +176,12 @@ async fn handle_socket(socket: WebSocket, state: AppState, client_addr: String)
                     }
                 }
 
-                result = rx.recv() => {
-                    match result {
-                        Ok(update) => {
+                delivery = events.next() => {
+                    match delivery {
+                        Some(Delivery::Event(event)) => {
+                            let Some(update) = TransactionStatusUpdate::from_event(&event) else {
+                                continue;
+                            };
                             let json = match serde_json::to_string(&update) {
                                 Ok(j) => j,
                                 Err(e) => {
@@ -197,7 +197,7 @@ async fn handle_socket(socket: WebSocket, state: AppState, client_addr: String)
                         }
 
                         // ── Backpressure: client is too slow ─────────────
-                        Err(broadcast::error::RecvError::Lagged(n)) => {
+                        Some(Delivery::Lagged(n)) => {
                             let total = dropped_counter.fetch_add(n, Ordering::Relaxed) + n;
                             tracing::warn!(
                                 client_addr = %send_addr,
@@ -214,8 +214,8 @@ async fn handle_socket(socket: WebSocket, state: AppState, client_addr: String)
                             }
                         }
 
-                        Err(broadcast::error::RecvError::Closed) => {
-                            tracing::info!(client_addr = %send_addr, "Broadcast channel closed");
+                        None => {
+                            tracing::info!(client_addr = %send_addr, "Event bus closed");
                             break;
                         }
                     }
@@ -308,6 +308,7 @@ fn validate_token(token: &str) -> bool {
 mod tests {
     use super::*;
     use crate::health::DependencySeverity;
+    use uuid::Uuid;
 
     #[test]
     fn test_validate_token_empty() {
@@ -382,12 +383,41 @@ mod tests {
             status: "completed".to_string(),
             timestamp: chrono::Utc::now(),
             message: Some("Transaction processed".to_string()),
+            stellar_tx_hash: None,
+            ledger: None,
+            closed_at: None,
         };
         let json = serde_json::to_string(&update).unwrap();
         assert!(json.contains("completed"));
         assert!(json.contains("Transaction processed"));
     }
 
+    #[test]
+    fn test_transaction_status_update_carries_ledger_once_verified() {
+        let mut update = TransactionStatusUpdate {
+            transaction_id: Uuid::new_v4(),
+            tenant_id: Uuid::new_v4(),
+            status: "pending".to_string(),
+            timestamp: chrono::Utc::now(),
+            message: None,
+            stellar_tx_hash: None,
+            ledger: None,
+            closed_at: None,
+        };
+        let json = serde_json::to_value(&update).unwrap();
+        assert!(json.get("stellar_tx_hash").is_none());
+        assert!(json.get("ledger").is_none());
+
+        update.status = "completed".to_string();
+        update.stellar_tx_hash = Some("a".repeat(64));
+        update.ledger = Some(51_234_567);
+        update.closed_at = Some(chrono::Utc::now());
+        let json = serde_json::to_value(&update).unwrap();
+        assert_eq!(json["stellar_tx_hash"], "a".repeat(64));
+        assert_eq!(json["ledger"], 51_234_567);
+        assert!(json["closed_at"].is_string());
+    }
+
     #[test]
     fn test_ws_query_token_present() {
         let json = r#"{"token": "test_token"}"#;
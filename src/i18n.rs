@@ -0,0 +1,199 @@
+//! Customer-facing message catalog.
+//!
+//! Text that reaches end users rather than partners (the status page and
+//! SEP-24 error messages) is looked up here as a [`Message`] in a
+//! [`Locale`], so it can be served in the customer's language. Partner and
+//! admin APIs stay in English.
+//!
+//! The locale comes from the request: SEP-24's `lang` field when given,
+//! otherwise the `Accept-Language` header (see [`Locale::negotiate`]).
+//! Anything unsupported falls back to English.
+//!
+//! Adding a language means adding a [`Locale`] variant; the compiler then
+//! points at every message still missing a translation.
+
+use axum::http::{header, HeaderMap};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Locale {
+    #[default]
+    En,
+    Es,
+    Fr,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 3] = [Locale::En, Locale::Es, Locale::Fr];
+
+    /// BCP 47 language tag, as sent in `Content-Language`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Es => "es",
+            Locale::Fr => "fr",
+        }
+    }
+
+    /// The locale for a language tag such as `es` or `fr-CA`; only the
+    /// primary subtag counts.
+    pub fn parse(tag: &str) -> Option<Self> {
+        let primary = tag.trim().split(['-', '_']).next()?;
+        Locale::ALL
+            .into_iter()
+            .find(|locale| locale.as_str().eq_ignore_ascii_case(primary))
+    }
+
+    /// The supported locale an `Accept-Language` value prefers most, e.g.
+    /// `fr` for `de-CH, fr;q=0.8, en;q=0.5`. Ties go to the one listed
+    /// first; `q=0` excludes a language and `*` stands for English.
+    pub fn negotiate(accept_language: &str) -> Self {
+        let mut best: Option<(Locale, f32)> = None;
+        for entry in accept_language.split(',') {
+            let mut parts = entry.split(';');
+            let tag = parts.next().unwrap_or_default().trim();
+            let q = parts
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())
+                .unwrap_or(0.0);
+            let locale = match tag {
+                "*" => Some(Locale::En),
+                tag => Locale::parse(tag),
+            };
+            if let Some(locale) = locale.filter(|_| q > 0.0) {
+                if !best.is_some_and(|(_, best_q)| best_q >= q) {
+                    best = Some((locale, q));
+                }
+            }
+        }
+        best.map(|(locale, _)| locale).unwrap_or_default()
+    }
+
+    /// [`Self::negotiate`] over the request's `Accept-Language`, if any.
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        headers
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|v| v.to_str().ok())
+            .map(Self::negotiate)
+            .unwrap_or_default()
+    }
+}
+
+/// A customer-facing message. Placeholders in braces are filled by
+/// [`Message::render`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Message {
+    StatusPending,
+    StatusProcessing,
+    StatusCompleted,
+    StatusFailed,
+    StatusRefunding,
+    StatusLinkNotFound,
+    /// `{asset}`
+    AssetNotSupported,
+    AccountMismatch,
+    /// `{account}`
+    InvalidAccount,
+    /// `{lang}`
+    InvalidLang,
+    AmountRequired,
+    /// `{amount}`
+    InvalidAmount,
+    AmountNotPositive,
+    /// `{min}`
+    AmountBelowMinimum,
+    /// `{max}`
+    AmountAboveMaximum,
+}
+
+impl Message {
+    /// The message in `locale`, placeholders unfilled.
+    pub fn text(self, locale: Locale) -> &'static str {
+        use Locale::{En, Es, Fr};
+        use Message::*;
+        match (self, locale) {
+            (StatusPending, En) => "Waiting for the payment to arrive.",
+            (StatusPending, Es) => "Esperando a que llegue el pago.",
+            (StatusPending, Fr) => "En attente de la réception du paiement.",
+            (StatusProcessing, En) => "The payment has arrived and is being processed.",
+            (StatusProcessing, Es) => "El pago ha llegado y se está procesando.",
+            (StatusProcessing, Fr) => "Le paiement a été reçu et est en cours de traitement.",
+            (StatusCompleted, En) => "The transaction is complete.",
+            (StatusCompleted, Es) => "La transacción se ha completado.",
+            (StatusCompleted, Fr) => "La transaction est terminée.",
+            (StatusFailed, En) => "The transaction could not be completed.",
+            (StatusFailed, Es) => "No se pudo completar la transacción.",
+            (StatusFailed, Fr) => "La transaction n'a pas pu être effectuée.",
+            (StatusRefunding, En) => "The payment is being refunded.",
+            (StatusRefunding, Es) => "Se está reembolsando el pago.",
+            (StatusRefunding, Fr) => "Le paiement est en cours de remboursement.",
+            (StatusLinkNotFound, En) => "Status link not found",
+            (StatusLinkNotFound, Es) => "Enlace de estado no encontrado",
+            (StatusLinkNotFound, Fr) => "Lien de suivi introuvable",
+            (AssetNotSupported, En) => "asset {asset} is not supported",
+            (AssetNotSupported, Es) => "el activo {asset} no está admitido",
+            (AssetNotSupported, Fr) => "l'actif {asset} n'est pas pris en charge",
+            (AccountMismatch, En) => "account does not match the authenticated account",
+            (AccountMismatch, Es) => "la cuenta no coincide con la cuenta autenticada",
+            (AccountMismatch, Fr) => "le compte ne correspond pas au compte authentifié",
+            (InvalidAccount, En) => "invalid account: {account}",
+            (InvalidAccount, Es) => "cuenta no válida: {account}",
+            (InvalidAccount, Fr) => "compte invalide : {account}",
+            (InvalidLang, En) => "invalid lang: {lang}",
+            (InvalidLang, Es) => "idioma no válido: {lang}",
+            (InvalidLang, Fr) => "langue invalide : {lang}",
+            (AmountRequired, En) => "amount is required",
+            (AmountRequired, Es) => "el importe es obligatorio",
+            (AmountRequired, Fr) => "le montant est obligatoire",
+            (InvalidAmount, En) => "invalid amount: {amount}",
+            (InvalidAmount, Es) => "importe no válido: {amount}",
+            (InvalidAmount, Fr) => "montant invalide : {amount}",
+            (AmountNotPositive, En) => "amount must be positive",
+            (AmountNotPositive, Es) => "el importe debe ser positivo",
+            (AmountNotPositive, Fr) => "le montant doit être positif",
+            (AmountBelowMinimum, En) => "amount is below the minimum of {min}",
+            (AmountBelowMinimum, Es) => "el importe es inferior al mínimo de {min}",
+            (AmountBelowMinimum, Fr) => "le montant est inférieur au minimum de {min}",
+            (AmountAboveMaximum, En) => "amount is above the maximum of {max}",
+            (AmountAboveMaximum, Es) => "el importe supera el máximo de {max}",
+            (AmountAboveMaximum, Fr) => "le montant dépasse le maximum de {max}",
+        }
+    }
+
+    /// The message in `locale` with each `{name}` replaced by its value.
+    pub fn render(self, locale: Locale, args: &[(&str, &str)]) -> String {
+        args.iter()
+            .fold(self.text(locale).to_string(), |text, (name, value)| {
+                text.replace(&format!("{{{name}}}"), value)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(Locale::negotiate("es-MX,es;q=0.9,en;q=0.8"), Locale::Es);
+        assert_eq!(Locale::negotiate("de-CH, fr;q=0.8, en;q=0.5"), Locale::Fr);
+        assert_eq!(Locale::negotiate("en;q=0.4, FR-ca;q=0.9"), Locale::Fr);
+        assert_eq!(Locale::negotiate("fr;q=0, es;q=0.1"), Locale::Es);
+        assert_eq!(Locale::negotiate("de, *;q=0.1"), Locale::En);
+        assert_eq!(Locale::negotiate("de;q=abc, ja"), Locale::En);
+        assert_eq!(Locale::negotiate(""), Locale::En);
+        assert_eq!(Locale::parse("pt-BR"), None);
+        assert_eq!(Locale::parse("es_AR"), Some(Locale::Es));
+    }
+
+    #[test]
+    fn test_render_fills_placeholders_in_every_locale() {
+        for locale in Locale::ALL {
+            let text = Message::AmountBelowMinimum.render(locale, &[("min", "5")]);
+            assert!(text.contains('5') && !text.contains('{'), "{text}");
+        }
+        assert_eq!(
+            Message::AssetNotSupported.render(Locale::Es, &[("asset", "EURC")]),
+            "el activo EURC no está admitido"
+        );
+    }
+}
@@ -1,14 +1,23 @@
+pub mod adapters;
 pub mod auth;
 pub mod cache;
+#[cfg(feature = "synapse-client")]
+pub mod client;
 pub mod config;
 pub mod db;
+#[cfg(feature = "sqlite-dev")]
+pub mod dev;
+pub mod domain;
 pub mod error;
+#[cfg(feature = "graphql")]
 pub mod graphql;
 pub mod handlers;
 pub mod health;
+pub mod i18n;
 pub mod metrics;
 pub mod middleware;
 pub mod payments;
+pub mod ports;
 pub mod readiness;
 pub mod schemas;
 pub mod secrets;
@@ -18,31 +27,41 @@ pub mod startup;
 pub mod stellar;
 pub mod telemetry;
 pub mod tenant;
+#[cfg(feature = "test-postgres")]
+pub mod testing;
+pub mod use_cases;
 pub mod utils;
 pub mod validation;
+#[cfg(feature = "websocket")]
 pub mod ws;
 
 pub use config::assets::AssetCache;
 
+use crate::adapters::{BroadcastEventBus, SystemClock};
 use crate::db::pool_manager::PoolManager;
+use crate::domain::DomainEvent;
+#[cfg(feature = "graphql")]
 use crate::graphql::schema::AppSchema;
 use crate::handlers::profiling::ProfilingManager;
-use crate::handlers::ws::TransactionStatusUpdate;
+use crate::ports::{Clock, EventBus};
 pub use crate::readiness::ReadinessState;
 use crate::secrets::SecretsStore;
+use crate::services::event_channels::EventClass;
 use crate::services::feature_flags::FeatureFlagService;
 use crate::services::query_cache::QueryCache;
+use crate::services::scheduler::JobScheduler;
+use crate::services::settlement_events::SettlementEvent;
+use crate::services::signing_keys::SigningKeyStore;
 use crate::stellar::HorizonClient;
 use crate::tenant::TenantConfig;
 use axum::{
     middleware as axum_middleware,
     routing::{get, patch, post},
-    Router,
+    Extension, Router,
 };
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, AtomicUsize};
 use std::sync::Arc;
-use tokio::sync::broadcast;
 use uuid::Uuid;
 
 #[derive(Clone)]
@@ -54,7 +73,11 @@ pub struct AppState {
     pub redis_url: String,
     pub start_time: std::time::Instant,
     pub readiness: ReadinessState,
-    pub tx_broadcast: broadcast::Sender<TransactionStatusUpdate>,
+    /// Domain events; `/ws` and the GraphQL subscription forward the status
+    /// changes, the webhook dispatcher delivers the transaction events.
+    pub domain_events: Arc<dyn EventBus<DomainEvent>>,
+    /// Settlement lifecycle events for the GraphQL settlement subscriptions.
+    pub settlement_events: Arc<dyn EventBus<SettlementEvent>>,
     pub query_cache: QueryCache,
     pub profiling_manager: ProfilingManager,
     pub tenant_configs: Arc<tokio::sync::RwLock<HashMap<Uuid, TenantConfig>>>,
@@ -67,6 +90,13 @@ pub struct AppState {
     pub metrics_handle: crate::metrics::MetricsHandle,
     /// Active WebSocket connection count
     pub ws_connection_count: Arc<AtomicUsize>,
+    /// Scheduled jobs, for manual triggers from the admin API.
+    pub job_scheduler: Arc<JobScheduler>,
+    /// Keys accepted on inbound callback signatures.
+    pub signing_keys: SigningKeyStore,
+    /// Current time for SLA, freshness and retry scheduling; a
+    /// [`ManualClock`](crate::adapters::ManualClock) in deterministic tests.
+    pub clock: Arc<dyn Clock>,
 }
 
 impl AppState {
@@ -86,7 +116,6 @@ impl AppState {
 
     pub async fn test_new(database_url: &str) -> Self {
         let pool = sqlx::PgPool::connect(database_url).await.unwrap();
-        let (tx, _) = broadcast::channel(100);
         let _asset_cache =
             AssetCache::start(pool.clone(), std::time::Duration::from_secs(300)).await;
         Self {
@@ -99,7 +128,8 @@ impl AppState {
             redis_url: "redis://localhost:6379".to_string(),
             start_time: std::time::Instant::now(),
             readiness: ReadinessState::new(),
-            tx_broadcast: tx,
+            domain_events: Arc::new(BroadcastEventBus::new(EventClass::Transactions)),
+            settlement_events: Arc::new(BroadcastEventBus::new(EventClass::Settlements)),
             query_cache: QueryCache::new("redis://localhost:6379").await.unwrap(),
             profiling_manager: ProfilingManager::new(),
             tenant_configs: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
@@ -108,6 +138,9 @@ impl AppState {
             current_batch_size: Arc::new(AtomicU64::new(10)),
             metrics_handle: crate::metrics::init_metrics().unwrap(),
             ws_connection_count: Arc::new(AtomicUsize::new(0)),
+            job_scheduler: Arc::new(JobScheduler::new()),
+            signing_keys: SigningKeyStore::new(String::new()),
+            clock: Arc::new(SystemClock),
         }
     }
 }
@@ -115,6 +148,7 @@ impl AppState {
 #[derive(Clone)]
 pub struct ApiState {
     pub app_state: AppState,
+    #[cfg(feature = "graphql")]
     pub graphql_schema: AppSchema,
 }
 
@@ -124,23 +158,46 @@ impl std::fmt::Debug for ApiState {
     }
 }
 
-pub fn create_app(app_state: AppState) -> Router {
-    let graphql_schema = crate::graphql::schema::build_schema(app_state.clone());
-    let api_state = ApiState {
-        app_state: app_state.clone(),
-        graphql_schema,
-    };
+/// Liveness, readiness and health probes and `/cache/metrics`.
+///
+/// They bypass the latency budget and concurrency limits so an instance that is
+/// shedding load still answers its orchestrator instead of being restarted.
+fn probe_routes(api_state: ApiState) -> Router {
+    Router::new()
+        .route("/live", get(handlers::live))
+        .route("/ready", get(handlers::ready))
+        .route("/health", get(handlers::health))
+        .route("/cache/metrics", get(handlers::stats::cache_metrics))
+        .with_state(api_state)
+}
 
-    // Callback routes with validation + quota middleware
+/// Partner-facing routes: transactions, settlements, callbacks, exports,
+/// stats, SEP endpoints and GraphQL.
+fn public_routes(app_state: &AppState) -> Router<ApiState> {
+    // Callback routes with validation + quota middleware. Each route carries
+    // its own acknowledgment mode (sync 201 vs async 202 + status URL).
     let callback_routes = Router::new()
-        .route("/callback", post(handlers::webhook::callback))
-        .route("/callback/transaction", post(handlers::webhook::callback))
+        .route(
+            "/callback",
+            post(handlers::webhook::callback)
+                .layer(Extension(handlers::ack::AckMode::for_route("/callback"))),
+        )
+        .route(
+            "/callback/transaction",
+            post(handlers::webhook::callback).layer(Extension(handlers::ack::AckMode::for_route(
+                "/callback/transaction",
+            ))),
+        )
         .layer(axum_middleware::from_fn_with_state(
             app_state.clone(),
             crate::middleware::quota::rate_limit_middleware,
         ))
         .layer(axum_middleware::from_fn(
             crate::middleware::validate::validate_callback,
+        ))
+        .layer(axum_middleware::from_fn_with_state(
+            app_state.clone(),
+            crate::middleware::signature::verify_callback_signature,
         ));
 
     // Webhook route with validation + quota middleware
@@ -157,6 +214,14 @@ pub fn create_app(app_state: AppState) -> Router {
     // Core API routes (shared between versioned and unversioned)
     let core_routes = Router::new()
         .route("/transactions/:id", get(handlers::webhook::get_transaction))
+        .route(
+            "/transactions/:id/stellar",
+            get(handlers::webhook::get_transaction_stellar),
+        )
+        .route(
+            "/transactions/:id/status-link",
+            post(handlers::status_page::create_status_link),
+        )
         .route(
             "/transactions",
             get(handlers::webhook::list_transactions_api),
@@ -165,6 +230,10 @@ pub fn create_app(app_state: AppState) -> Router {
             "/transactions/search",
             get(handlers::search::search_transactions_wrapper),
         )
+        .route(
+            "/transactions/changes",
+            get(handlers::changes::transaction_changes),
+        )
         .route("/settlements", get(handlers::settlements::list_settlements))
         .route(
             "/settlements/:id",
@@ -183,36 +252,122 @@ pub fn create_app(app_state: AppState) -> Router {
         middleware::versioning::v2_version_middleware,
     ));
 
-    // Admin routes — quota skipped, SecretsStore injected for rotation-aware auth
-    let mut admin_router = Router::new()
-        .route("/live", get(handlers::live))
-        .route("/ready", get(handlers::ready))
-        .route("/health", get(handlers::health))
-        .route("/errors", get(handlers::error_catalog));
-
-    if let Some(store) = &app_state.secrets_store {
-        admin_router = admin_router.layer(axum::Extension(store.clone()));
-    }
-
-    admin_router
-        // Unversioned routes default to V2 behaviour
+    let routes = Router::new()
+        .route("/errors", get(handlers::error_catalog))
+        .route("/events/schema", get(handlers::event_schema))
+        // Unversioned routes take the version from `Accept`, defaulting to V2
         .merge(core_routes.layer(axum_middleware::from_fn(
-            middleware::versioning::v2_version_middleware,
+            middleware::versioning::negotiate_version_middleware,
         )))
         // Versioned route groups
         .nest("/api/v1", v1_routes)
         .nest("/api/v2", v2_routes)
+        .route("/export", get(handlers::export::export_transactions))
+        // Asynchronous exports, processed by the scheduler
+        .route("/exports", post(handlers::export_jobs::create_export))
+        .route("/exports/:id", get(handlers::export_jobs::get_export))
         .route(
-            "/admin/transactions/bulk-status",
-            patch(handlers::admin::bulk_status::bulk_update_status_api),
+            "/exports/:id/download",
+            get(handlers::export_jobs::download_export),
         )
-        .route("/graphql", post(handlers::graphql::graphql_handler))
-        .route("/export", get(handlers::export::export_transactions))
         // Stats endpoints
         .route("/stats/status", get(handlers::stats::status_counts))
         .route("/stats/daily", get(handlers::stats::daily_totals))
         .route("/stats/assets", get(handlers::stats::asset_stats))
-        .route("/cache/metrics", get(handlers::stats::cache_metrics))
+        .route("/stats/processor", get(handlers::stats::processor_stats))
+        // Rate-limit introspection (does not consume quota)
+        .route("/rate-limit", get(handlers::rate_limit::get_rate_limit));
+
+    #[cfg(feature = "sep")]
+    let routes = routes.merge(sep_routes());
+    #[cfg(feature = "graphql")]
+    let routes = routes.route("/graphql", post(handlers::graphql::graphql_handler));
+    // Customer status links (no API key; see services::status_page)
+    let routes = routes.route("/status/:token", get(handlers::status_page::get_status));
+    // Signed-URL downloads (no API key; see utils::signed_url)
+    let routes = routes.route(
+        "/downloads/statements/:file",
+        get(handlers::downloads::download_statement),
+    );
+    #[cfg(feature = "backup")]
+    let routes = routes.route(
+        "/downloads/backups/:file",
+        get(handlers::downloads::download_backup),
+    );
+    // Mask PII in JSON responses unless the API key has `read:pii`
+    routes.layer(axum_middleware::from_fn_with_state(
+        app_state.clone(),
+        middleware::redaction::redact_pii,
+    ))
+}
+
+/// Stellar anchor (SEP) endpoints for wallets and sending anchors.
+#[cfg(feature = "sep")]
+fn sep_routes() -> Router<ApiState> {
+    // SEP-6 programmatic deposit and withdrawal
+    let sep6_routes = Router::new()
+        .route("/info", get(handlers::sep6::info))
+        .route("/deposit", get(handlers::sep6::deposit))
+        .route("/withdraw", get(handlers::sep6::withdraw));
+    let sep38_routes = Router::new()
+        .route("/info", get(handlers::sep38::info))
+        .route("/prices", get(handlers::sep38::prices))
+        .route("/quote", post(handlers::sep38::post_quote))
+        .route("/quote/:id", get(handlers::sep38::get_quote));
+
+    Router::new()
+        // SEP-1 discovery
+        .route(
+            "/.well-known/stellar.toml",
+            get(handlers::stellar_toml::stellar_toml),
+        )
+        // SEP-10 web authentication
+        .route(
+            "/auth",
+            get(handlers::auth::challenge).post(handlers::auth::token),
+        )
+        // SEP-12 KYC customers
+        .route(
+            "/customer",
+            get(handlers::sep12::get_customer).put(handlers::sep12::put_customer),
+        )
+        .route(
+            "/customer/:account",
+            axum::routing::delete(handlers::sep12::delete_customer),
+        )
+        // SEP-24 hosted deposit and withdrawal
+        .route(
+            "/sep24/transactions/deposit/interactive",
+            post(handlers::sep24::deposit_interactive),
+        )
+        .route(
+            "/sep24/transactions/withdraw/interactive",
+            post(handlers::sep24::withdraw_interactive),
+        )
+        // SEP-31 cross-border payments, receiving side
+        .route(
+            "/sep31/transactions",
+            post(handlers::sep31::create_transaction),
+        )
+        .route(
+            "/sep31/transactions/:id",
+            get(handlers::sep31::get_transaction),
+        )
+        .route(
+            "/sep31/transactions/:id/callback",
+            axum::routing::put(handlers::sep31::put_callback),
+        )
+        .nest("/sep6", sep6_routes)
+        .nest("/sep38", sep38_routes)
+}
+
+/// Operator routes under `/admin` and `/dlq`.
+fn admin_routes(app_state: &AppState) -> Router<ApiState> {
+    let admin_router = Router::new()
+        .route(
+            "/admin/transactions/bulk-status",
+            patch(handlers::admin::bulk_status::bulk_update_status_api),
+        )
         // Admin: webhook endpoint health scores
         .route(
             "/admin/webhooks/health",
@@ -222,6 +377,29 @@ pub fn create_app(app_state: AppState) -> Router {
             "/admin/webhooks/health/:id",
             get(handlers::admin::get_webhook_health),
         )
+        // Admin: outgoing webhook subscribers
+        .route(
+            "/admin/webhook-subscriptions",
+            get(handlers::admin::webhook_subscriptions::list_subscriptions)
+                .post(handlers::admin::webhook_subscriptions::create_subscription),
+        )
+        .route(
+            "/admin/webhook-subscriptions/:id",
+            get(handlers::admin::webhook_subscriptions::get_subscription)
+                .delete(handlers::admin::webhook_subscriptions::delete_subscription),
+        )
+        .route(
+            "/admin/webhook-subscriptions/:id/rotate-secret",
+            post(handlers::admin::webhook_subscriptions::rotate_secret),
+        )
+        .route(
+            "/admin/webhook-subscriptions/:id/test",
+            post(handlers::admin::webhook_subscriptions::test_subscription),
+        )
+        .route(
+            "/admin/webhook-subscriptions/:id/enable",
+            post(handlers::admin::webhook_subscriptions::enable_subscription),
+        )
         // Admin: per-tenant quota management
         .route(
             "/admin/quotas",
@@ -239,35 +417,328 @@ pub fn create_app(app_state: AppState) -> Router {
             "/admin/quotas/:tenant_id/reset",
             axum::routing::delete(handlers::admin::quota::reset_tenant_quota),
         )
+        // Admin: partner settings (rate limit, asset allowlist)
+        .route(
+            "/admin/partners/:tenant_id/settings",
+            get(handlers::admin::partners::get_partner_settings)
+                .patch(handlers::admin::partners::update_partner_settings),
+        )
+        // Admin: fee revenue reporting
+        .route("/admin/stats/fees", get(handlers::stats::fee_stats))
+        // Admin: SEP-12 customer KYC decisions
+        .route(
+            "/admin/customers/:id/status",
+            axum::routing::put(handlers::admin::customers::set_customer_status),
+        )
+        // Admin: per-asset deposit amount limits
+        .route(
+            "/admin/assets/:id/limits",
+            axum::routing::put(handlers::admin::asset_limits::set_asset_limits),
+        )
+        // Admin: refund queue for unmatched on-chain payments
+        .route(
+            "/admin/refunds",
+            get(handlers::admin::refunds::list_refunds),
+        )
+        .route(
+            "/admin/refunds/:id",
+            get(handlers::admin::refunds::get_refund),
+        )
+        .route(
+            "/admin/refunds/:id/override",
+            post(handlers::admin::refunds::override_refund),
+        )
+        // Admin: four-eyes approvals
+        .route(
+            "/admin/approvals",
+            get(handlers::admin::approvals::list_approvals),
+        )
+        .route(
+            "/admin/approvals/:id",
+            get(handlers::admin::approvals::get_approval),
+        )
+        .route(
+            "/admin/approvals/:id/approve",
+            post(handlers::admin::approvals::approve),
+        )
+        .route(
+            "/admin/approvals/:id/reject",
+            post(handlers::admin::approvals::reject),
+        )
+        // Admin: payments held back for an untrusted asset issuer
+        .route(
+            "/admin/quarantine",
+            get(handlers::admin::quarantine::list_quarantined_payments),
+        )
+        // Admin: structuring detection rules and review items
+        .route(
+            "/admin/structuring/rules",
+            get(handlers::admin::structuring::list_rules)
+                .post(handlers::admin::structuring::create_rule),
+        )
+        .route(
+            "/admin/structuring/rules/:id",
+            get(handlers::admin::structuring::get_rule)
+                .put(handlers::admin::structuring::update_rule)
+                .delete(handlers::admin::structuring::delete_rule),
+        )
+        .route(
+            "/admin/structuring/reviews",
+            get(handlers::admin::structuring::list_reviews),
+        )
+        .route(
+            "/admin/structuring/reviews/:id/resolve",
+            post(handlers::admin::structuring::resolve_review),
+        )
+        // Admin: compliance review queue
+        .route(
+            "/admin/reviews",
+            get(handlers::admin::reviews::list_reviews),
+        )
+        .route(
+            "/admin/reviews/:id",
+            get(handlers::admin::reviews::get_review),
+        )
+        .route(
+            "/admin/reviews/:id/claim",
+            post(handlers::admin::reviews::claim_review),
+        )
+        .route(
+            "/admin/reviews/:id/resolve",
+            post(handlers::admin::reviews::resolve_review),
+        )
+        .route(
+            "/admin/reviews/:id/comments",
+            post(handlers::admin::reviews::add_comment),
+        )
+        // Admin: long-running background jobs
+        .route(
+            "/admin/jobs",
+            get(handlers::admin::jobs::list_jobs).post(handlers::admin::jobs::create_job),
+        )
+        .route("/admin/jobs/:id", get(handlers::admin::jobs::get_job))
+        .route(
+            "/admin/jobs/:id/cancel",
+            post(handlers::admin::jobs::cancel_job),
+        )
+        // Admin: run a scheduled job on demand. Scheduled jobs are addressed by
+        // name, sharing the `:id` segment with the background job routes.
+        .route(
+            "/admin/jobs/:id/run",
+            post(handlers::admin::jobs::run_scheduled_job),
+        )
+        // Admin: preStop hook, starts connection draining. GET too, since a
+        // Kubernetes `httpGet` hook cannot POST.
+        .route(
+            "/admin/drain",
+            post(readiness::drain_handler).get(readiness::drain_handler),
+        )
+        // Admin: build and schema version
+        .route("/admin/info", get(handlers::admin::info::get_info))
+        // Admin: circuit breaker status and manual overrides
+        .route(
+            "/admin/breakers",
+            get(handlers::admin::breakers::list_breakers),
+        )
+        .route(
+            "/admin/breakers/:name/open",
+            post(handlers::admin::breakers::open_breaker),
+        )
+        .route(
+            "/admin/breakers/:name/close",
+            post(handlers::admin::breakers::close_breaker),
+        )
+        .route(
+            "/admin/breakers/:name/override",
+            axum::routing::delete(handlers::admin::breakers::clear_override),
+        )
+        // Admin: multi-region active-passive role
+        .route("/admin/region", get(handlers::admin::region::get_region))
+        .route(
+            "/admin/region/role",
+            axum::routing::put(handlers::admin::region::set_role)
+                .delete(handlers::admin::region::clear_role),
+        )
+        // Admin: inbound callback signing keys
+        .route(
+            "/admin/signing-keys",
+            get(handlers::admin::signing_keys::list_signing_keys),
+        )
+        .route(
+            "/admin/signing-keys/rotate",
+            post(handlers::admin::signing_keys::rotate_global_signing_key),
+        )
+        .route(
+            "/admin/partners/:tenant_id/signing-keys/rotate",
+            post(handlers::admin::signing_keys::rotate_partner_signing_key),
+        )
         // Admin: active distributed locks
         .route(
             "/admin/locks",
             get(handlers::admin::locks::list_active_locks),
         )
+        // Admin: idempotency cache inspection / expiry
+        .route(
+            "/admin/idempotency/:key",
+            get(handlers::admin::idempotency::get_idempotency_entry)
+                .delete(handlers::admin::idempotency::delete_idempotency_entry),
+        )
         // Admin: settlement dispute workflow
         .route(
             "/admin/settlements/:id/status",
             axum::routing::patch(handlers::settlements::update_settlement_status),
         )
+        .route(
+            "/admin/settlements/:id/void",
+            post(handlers::settlements::void_settlement),
+        )
+        .route(
+            "/admin/settlements/:id/path-payment",
+            post(handlers::settlements::execute_path_payment),
+        )
+        // Admin: monthly accounting close
+        .route(
+            "/admin/accounting/periods",
+            get(handlers::admin::accounting::list_periods),
+        )
+        .route(
+            "/admin/accounting/periods/:period/close",
+            post(handlers::admin::accounting::close_period),
+        )
+        .route(
+            "/admin/accounting/periods/:period/adjustments",
+            get(handlers::admin::accounting::list_adjustments)
+                .post(handlers::admin::accounting::post_adjustment),
+        )
+        // Admin: monthly partner statements
+        .route(
+            "/admin/statements",
+            get(handlers::admin::statements::list_statements)
+                .post(handlers::admin::statements::create_statements),
+        )
         // Admin: reconciliation reports
         .nest(
             "/admin/reconciliation",
             handlers::admin::reconciliation::reconciliation_routes(),
         )
+        // Admin: dead-letter queue
+        .merge(handlers::dlq::dlq_routes().with_state(app_state.db.clone()));
+
+    // Admin: database backups with signed download links
+    #[cfg(feature = "backup")]
+    let admin_router = admin_router.route(
+        "/admin/backups",
+        get(handlers::admin::backups::list_backups),
+    );
+
+    // Every operator route requires the admin key. A route layer leaves
+    // unknown paths to the public router's 404.
+    let admin_router =
+        admin_router.route_layer(axum_middleware::from_fn(middleware::auth::admin_auth));
+
+    // SecretsStore injected for rotation-aware admin auth
+    match &app_state.secrets_store {
+        Some(store) => admin_router.layer(Extension(store.clone())),
+        None => admin_router,
+    }
+}
+
+/// Latency budgets, load shedding and panic recovery around `routes`.
+fn guarded(routes: Router<ApiState>, api_state: ApiState) -> Router {
+    // Callbacks acknowledged asynchronously keep running past their budget so
+    // an accepted callback is still persisted.
+    let mut route_timeouts = middleware::timeout::RouteTimeouts::from_env();
+    for route in ["/callback", "/callback/transaction"] {
+        if handlers::ack::AckMode::for_route(route) == handlers::ack::AckMode::Async {
+            route_timeouts = route_timeouts.detach(route);
+        }
+    }
+
+    routes
+        // Inside the latency budget, so queueing counts against it and a
+        // detached callback keeps its slot until it finishes.
+        .layer(axum_middleware::from_fn_with_state(
+            Arc::new(middleware::load_shed::ConcurrencyLimits::from_env()),
+            middleware::load_shed::limit_concurrency,
+        ))
+        .layer(axum_middleware::from_fn_with_state(
+            Arc::new(route_timeouts),
+            middleware::timeout::latency_budget,
+        ))
         .layer(axum_middleware::from_fn(
             middleware::panic_recovery::panic_recovery_middleware,
         ))
         .with_state(api_state)
-        .merge(
-            Router::new()
-                .route("/ws", get(handlers::ws::ws_handler))
-                .route(
-                    "/reconnect/status",
-                    get(handlers::reconnection::reconnect_status),
-                )
-                .route("/reconnect", post(handlers::reconnection::reconnect))
-                .with_state(app_state),
+}
+
+/// `/ws` and the reconnection endpoints; empty without the `websocket`
+/// feature, so those paths answer 404.
+fn ws_routes(app_state: AppState) -> Router {
+    #[cfg(feature = "websocket")]
+    let routes = Router::new()
+        .route("/ws", get(handlers::ws::ws_handler))
+        .route(
+            "/reconnect/status",
+            get(handlers::reconnection::reconnect_status),
         )
+        .route("/reconnect", post(handlers::reconnection::reconnect));
+    #[cfg(not(feature = "websocket"))]
+    let routes = Router::new();
+    routes.with_state(app_state)
+}
+
+/// `/graphql/ws` subscriptions, outside the latency budget like `/ws`; empty
+/// without the `graphql` feature.
+fn graphql_ws_routes(api_state: ApiState) -> Router {
+    #[cfg(feature = "graphql")]
+    let routes = Router::new().route("/graphql/ws", get(handlers::graphql::graphql_ws));
+    #[cfg(not(feature = "graphql"))]
+    let routes = Router::new();
+    routes.with_state(api_state)
+}
+
+fn api_state(app_state: &AppState) -> ApiState {
+    ApiState {
+        app_state: app_state.clone(),
+        #[cfg(feature = "graphql")]
+        graphql_schema: crate::graphql::schema::build_schema(app_state.clone()),
+    }
+}
+
+/// Every route on one listener.
+pub fn create_app(app_state: AppState) -> Router {
+    let api_state = api_state(&app_state);
+    guarded(
+        public_routes(&app_state).merge(admin_routes(&app_state)),
+        api_state.clone(),
+    )
+    // Probes stay outside the latency budget and concurrency limits
+    .merge(probe_routes(api_state.clone()))
+    .merge(graphql_ws_routes(api_state))
+    .merge(ws_routes(app_state))
+    .layer(axum_middleware::from_fn(
+        middleware::request_logger::request_logger_middleware,
+    ))
+}
+
+/// Partner-facing routes only, for the public listener when `INTERNAL_PORT`
+/// is set.
+pub fn create_public_app(app_state: AppState) -> Router {
+    let api_state = api_state(&app_state);
+    guarded(public_routes(&app_state), api_state.clone())
+        .merge(graphql_ws_routes(api_state))
+        .merge(ws_routes(app_state))
+        .layer(axum_middleware::from_fn(
+            middleware::request_logger::request_logger_middleware,
+        ))
+}
+
+/// Admin, DLQ and probe routes, for the `INTERNAL_PORT` listener. Admin routes
+/// get concurrency limits of their own, so partner traffic cannot starve them.
+pub fn create_internal_app(app_state: AppState) -> Router {
+    let api_state = api_state(&app_state);
+    guarded(admin_routes(&app_state), api_state.clone())
+        .merge(probe_routes(api_state))
         .layer(axum_middleware::from_fn(
             middleware::request_logger::request_logger_middleware,
         ))
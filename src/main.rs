@@ -1,28 +1,35 @@
 use clap::Parser;
-use sqlx::migrate::Migrator;
-use std::{net::SocketAddr, path::Path, sync::atomic::AtomicU64, sync::Arc};
+use std::{net::SocketAddr, sync::atomic::AtomicU64, sync::Arc};
 use synapse_core::{
+    adapters::BroadcastEventBus,
     config, db,
-    db::pool_manager::PoolManager,
-    handlers,
-    handlers::ws::TransactionStatusUpdate,
-    metrics,
+    db::{migrations::MigrationMode, pool_manager::PoolManager},
+    domain::DomainEvent,
+    handlers, metrics,
     middleware::idempotency::IdempotencyService,
+    ports::{event_bus, EventBus},
     schemas,
     secrets::SecretsStore,
     services::{
-        FeatureFlagService, ResourceLimiter, SettlementService, TaskLimits, WebhookDispatcher,
+        event_channels::EventClass, signing_keys::SigningKeyStore, FeatureFlagService,
+        ResourceLimiter, SettlementEvent, SettlementService, TaskLimits, WebhookDispatcher,
     },
     stellar::HorizonClient,
     AppState, ReadinessState,
 };
-use tokio::sync::broadcast;
 use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, CorsLayer};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 mod cli;
-use cli::{BackupCommands, Cli, Commands, DbCommands, TxCommands};
+#[cfg(feature = "backup")]
+use cli::BackupCommands;
+use cli::{Cli, Commands, DbCommands, TxCommands};
+#[cfg(feature = "sep")]
+use synapse_core::services::{
+    sep10::Sep10Config,
+    sep31::{Sep31CallbackNotifier, Sep31Config},
+};
 
 /// OpenAPI Schema for the Synapse Core API
 #[derive(OpenApi)]
@@ -42,6 +49,7 @@ use cli::{BackupCommands, Cli, Commands, DbCommands, TxCommands};
             handlers::webhook::WebhookPayload,
             handlers::webhook::WebhookResponse,
             handlers::webhook::CallbackPayload,
+            handlers::ack::AcceptedResponse,
             schemas::TransactionSchema,
             schemas::SettlementSchema,
         )
@@ -64,6 +72,10 @@ pub struct ApiDoc;
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
+    if cli.dev {
+        // Before loading the config, which requires DATABASE_URL and secrets.
+        return run_dev().await;
+    }
     let config = config::Config::load().await?;
 
     // Setup logging + OpenTelemetry tracing layer
@@ -90,6 +102,10 @@ async fn main() -> anyhow::Result<()> {
         }
     }
 
+    if cli.self_check {
+        return cli::handle_self_check(&config).await;
+    }
+
     match cli.command {
         Some(Commands::Serve) | None => serve(config, tracer_manager).await,
         Some(Commands::Tx(tx_cmd)) => match tx_cmd {
@@ -103,10 +119,15 @@ async fn main() -> anyhow::Result<()> {
                 end,
                 format,
             } => cli::handle_tx_reconcile(&config, &account, &start, &end, &format).await,
+            TxCommands::Backfill { account, from, to } => {
+                let pool = db::create_pool(&config).await?;
+                cli::handle_tx_backfill(&pool, &account, from, to).await
+            }
         },
         Some(Commands::Db(db_cmd)) => match db_cmd {
             DbCommands::Migrate => cli::handle_db_migrate(&config).await,
         },
+        #[cfg(feature = "backup")]
         Some(Commands::Backup(backup_cmd)) => match backup_cmd {
             BackupCommands::Run { backup_type } => {
                 cli::handle_backup_run(&config, &backup_type).await
@@ -115,19 +136,98 @@ async fn main() -> anyhow::Result<()> {
             BackupCommands::Restore { filename } => {
                 cli::handle_backup_restore(&config, &filename).await
             }
-            BackupCommands::RestorePitr { timestamp } => {
-                cli::handle_backup_restore_pitr(&config, &timestamp).await
+            BackupCommands::BaseBackup => cli::handle_backup_base(&config).await,
+            BackupCommands::RestorePitr {
+                timestamp,
+                data_dir,
+                port,
+                keep_running,
+                recovery_timeout_secs,
+                format,
+            } => {
+                cli::handle_backup_restore_pitr(
+                    &config,
+                    &timestamp,
+                    data_dir,
+                    port,
+                    keep_running,
+                    recovery_timeout_secs,
+                    &format,
+                )
+                .await
             }
             BackupCommands::Cleanup => cli::handle_backup_cleanup(&config).await,
+            BackupCommands::Rewrap => cli::handle_backup_rewrap(&config).await,
         },
         Some(Commands::Config) => cli::handle_config_validate(&config),
+        Some(Commands::Seed {
+            seed,
+            transactions,
+            partners,
+            days,
+            until,
+            dry_run,
+        }) => {
+            let options = synapse_core::services::seed::SeedOptions {
+                seed,
+                transactions,
+                partners,
+                days,
+                until: until.unwrap_or_else(|| chrono::Utc::now().date_naive()),
+            };
+            cli::handle_seed(&config, options, dry_run).await
+        }
     }
 }
 
+/// `--dev`: the SQLite-backed server from `synapse_core::dev`, configured by
+/// `SERVER_PORT` and `DEV_DATABASE_URL` only.
+#[cfg(feature = "sqlite-dev")]
+async fn run_dev() -> anyhow::Result<()> {
+    dotenvy::dotenv().ok();
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| "info".into()),
+        )
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+
+    let port = std::env::var("SERVER_PORT")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(3000);
+    let database_url = std::env::var("DEV_DATABASE_URL")
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+        .unwrap_or_else(|| synapse_core::dev::DEFAULT_DATABASE_URL.to_string());
+    synapse_core::dev::serve(port, &database_url).await
+}
+
+#[cfg(not(feature = "sqlite-dev"))]
+async fn run_dev() -> anyhow::Result<()> {
+    anyhow::bail!("--dev needs the sqlite-dev feature: cargo run --features sqlite-dev -- --dev")
+}
+
 async fn serve(
     config: config::Config,
     tracer_manager: synapse_core::telemetry::TracerManager,
 ) -> anyhow::Result<()> {
+    let region = synapse_core::services::region::init(
+        synapse_core::services::region::RegionState::from_env()?,
+    );
+    tracing::info!(
+        region = region.name(),
+        role = region.role().as_str(),
+        "Region role"
+    );
+    let fees =
+        synapse_core::services::fees::init(synapse_core::services::fees::FeePolicy::from_env()?);
+    tracing::info!(
+        strategy = fees.policy().strategy.as_str(),
+        cap = fees.policy().cap,
+        "Stellar fee strategy"
+    );
+
     let pool = db::create_pool(&config).await?;
 
     // Initialize pool manager for multi-region failover
@@ -144,10 +244,19 @@ async fn serve(
         tracing::info!("No replica configured - all queries will use primary database");
     }
 
-    // Run migrations
-    let migrator = Migrator::new(Path::new("./migrations")).await?;
-    migrator.run(&pool).await?;
-    tracing::info!("Database migrations completed");
+    // Run migrations unless a separate job owns them, then refuse to serve
+    // against a schema older than this binary needs.
+    match config.migration_mode {
+        MigrationMode::Startup => {
+            let lock_timeout = std::time::Duration::from_secs(config.migration_lock_timeout_secs);
+            db::migrations::run(&pool, lock_timeout).await?;
+        }
+        MigrationMode::External => {
+            tracing::info!("MIGRATION_MODE=external: skipping migrations on startup");
+        }
+    }
+    let schema_version = db::migrations::check_schema_version(&pool).await?;
+    tracing::info!(schema_version, "Database schema version checked");
 
     // Initialize resource limiters for background tasks
     let settlement_limiter = ResourceLimiter::new(TaskLimits::new(1, 120), "settlement");
@@ -159,7 +268,8 @@ async fn serve(
     tracing::info!("Partition manager started");
 
     // Initialize Stellar Horizon client
-    let horizon_client = HorizonClient::new(config.stellar_horizon_url.clone());
+    let horizon_client = HorizonClient::new(config.stellar_horizon_url.clone())
+        .with_fallbacks(synapse_core::stellar::endpoints::fallback_urls_from_env());
     tracing::info!(
         "Stellar Horizon client initialized with URL: {}",
         config.stellar_horizon_url
@@ -172,17 +282,32 @@ async fn serve(
         config.settlement_min_tx_count,
     );
 
+    // Domain events from the callback handler, processor and settlements, for
+    // WebSocket and GraphQL subscribers and webhook delivery.
+    // Slow subscribers receive Delivery::Lagged — the WS handler detects this,
+    // notifies the client with a "messages_dropped" frame, and offers resync.
+    let domain_events: Arc<dyn EventBus<DomainEvent>> =
+        Arc::new(BroadcastEventBus::new(EventClass::Transactions));
+
+    // Settlement lifecycle events, consumed by the GraphQL settlement subscriptions.
+    let settlement_events: Arc<dyn EventBus<SettlementEvent>> =
+        Arc::new(BroadcastEventBus::new(EventClass::Settlements));
+
     // Start background settlement worker
     let settlement_pool = pool.clone();
     let settlement_max_batch = config.settlement_max_batch_size;
     let settlement_min_tx = config.settlement_min_tx_count;
     let settlement_limiter_clone = settlement_limiter.clone();
+    let worker_settlement_events = settlement_events.clone();
+    let worker_domain_events = domain_events.clone();
     tokio::spawn(async move {
         let service = SettlementService::with_config(
             settlement_pool,
             settlement_max_batch,
             settlement_min_tx,
-        );
+        )
+        .with_events(worker_settlement_events)
+        .with_domain_events(worker_domain_events);
         let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(3600)); // Default to hourly
         loop {
             interval.tick().await;
@@ -224,6 +349,23 @@ async fn serve(
     });
     tracing::info!("Webhook dispatcher background worker started");
 
+    // Turn transaction domain events into webhook deliveries.
+    let forwarder = WebhookDispatcher::new(pool.clone(), &config.redis_url)
+        .expect("failed to create webhook dispatcher");
+    let forwarded_events = domain_events.subscribe(event_bus::all());
+    tokio::spawn(async move { forwarder.forward_events(forwarded_events).await });
+
+    // SEP-31 status callbacks to sending anchors, signed with the SEP-10 key.
+    #[cfg(feature = "sep")]
+    if let (Ok(sep31_config), Ok(signer)) = (Sep31Config::from_env(), Sep10Config::from_env()) {
+        let notifier = Sep31CallbackNotifier::new(pool.clone(), sep31_config, signer);
+        let status_changes = domain_events.subscribe(Box::new(|event: &DomainEvent| {
+            matches!(event, DomainEvent::StatusChanged { .. })
+        }));
+        tokio::spawn(async move { notifier.forward_events(status_changes).await });
+        tracing::info!("SEP-31 callback notifier started");
+    }
+
     // Initialize metrics (OTLP exporter + pool stats background task)
     let metrics_handle = metrics::init_metrics()
         .map_err(|e| anyhow::anyhow!("Failed to initialize metrics: {e}"))?;
@@ -244,7 +386,7 @@ async fn serve(
     let idempotency_lock_contention = Arc::new(AtomicU64::new(0));
     let idempotency_errors = Arc::new(AtomicU64::new(0));
     let idempotency_fallback_count = Arc::new(AtomicU64::new(0));
-    let _idempotency_service = IdempotencyService::new(
+    let idempotency_service = IdempotencyService::new(
         &config.redis_url,
         pool.clone(),
         Arc::clone(&idempotency_cache_hits),
@@ -266,12 +408,6 @@ async fn serve(
         tracing::warn!("Failed to warm cache on startup: {:?}", e);
     }
 
-    // Create broadcast channel for WebSocket notifications.
-    // Capacity of 100: slow subscribers will receive a RecvError::Lagged — the WS handler
-    // detects this, notifies the client with a "messages_dropped" frame, and offers resync.
-    let (tx_broadcast, _) = broadcast::channel::<TransactionStatusUpdate>(100);
-    tracing::info!("WebSocket broadcast channel initialized");
-
     // Initialize feature flags service
     let feature_flags = FeatureFlagService::new(pool.clone());
     tracing::info!("Feature flags service initialized");
@@ -306,6 +442,17 @@ async fn serve(
     let _asset_cache =
         synapse_core::AssetCache::start(pool.clone(), std::time::Duration::from_secs(300)).await;
     tracing::info!("Asset registry cache initialized");
+    // Jobs are registered below; the admin API triggers them through app state.
+    let scheduler = std::sync::Arc::new(
+        synapse_core::services::JobScheduler::with_config(config.jobs.clone())
+            .with_pool(pool.clone()),
+    );
+    let signing_keys = match &secrets_store {
+        Some(store) => SigningKeyStore::new(config.anchor_webhook_secret.clone())
+            .with_secrets_store(store.clone()),
+        None => SigningKeyStore::new(config.anchor_webhook_secret.clone()),
+    };
+
     let app_state = AppState {
         db: pool.clone(),
         pool_manager,
@@ -314,7 +461,8 @@ async fn serve(
         redis_url: config.redis_url.clone(),
         start_time: std::time::Instant::now(),
         readiness: ReadinessState::new(),
-        tx_broadcast,
+        domain_events: domain_events.clone(),
+        settlement_events,
         query_cache,
         profiling_manager: crate::handlers::profiling::ProfilingManager::new(),
         tenant_configs: std::sync::Arc::new(tokio::sync::RwLock::new(
@@ -325,6 +473,9 @@ async fn serve(
         current_batch_size: current_batch_size.clone(),
         metrics_handle,
         ws_connection_count: std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+        job_scheduler: scheduler.clone(),
+        signing_keys,
+        clock: std::sync::Arc::new(synapse_core::adapters::SystemClock),
     };
 
     // Load tenant configs on startup
@@ -357,6 +508,61 @@ async fn serve(
         pool_monitor_task(monitor_pool).await;
     });
 
+    // Withhold readiness and recycle connections while the primary fails over
+    tokio::spawn(synapse_core::db::failover::watch(
+        vec![pool.clone(), app_state.pool_manager.primary().clone()],
+        app_state.readiness.clone(),
+    ));
+
+    // Summaries of log lines dropped by sampling during error storms
+    tokio::spawn(synapse_core::utils::log_sampler::report_suppressed());
+
+    // Circuit breakers: record transitions and follow manual overrides
+    tokio::spawn(synapse_core::services::breakers::record_events(
+        pool.clone(),
+    ));
+    tokio::spawn(synapse_core::services::dlq_drain::drain_on_recovery(
+        pool.clone(),
+    ));
+    tokio::spawn(synapse_core::services::dlq_capacity::watch(
+        pool.clone(),
+        synapse_core::adapters::object_store(),
+    ));
+    tokio::spawn(synapse_core::services::fees::poll_fee_stats(
+        horizon_client.clone(),
+    ));
+    tokio::spawn(synapse_core::stellar::endpoints::run_health_checks(
+        horizon_client.clone(),
+    ));
+    // Settle submissions left in flight by the previous run before new
+    // payouts are attempted.
+    if let Some(passphrase) = config.stellar_network_passphrase.clone() {
+        let ledger = synapse_core::services::submissions::SubmissionLedger::new(
+            pool.clone(),
+            horizon_client.clone(),
+            passphrase,
+        );
+        tokio::spawn(async move {
+            match ledger.recover_in_flight().await {
+                Ok(0) => {}
+                Ok(unresolved) => {
+                    tracing::warn!(unresolved, "Submissions still in flight after recovery")
+                }
+                Err(e) => tracing::error!("Failed to recover in-flight submissions: {}", e),
+            }
+        });
+    }
+    match redis::Client::open(config.redis_url.as_str()) {
+        Ok(redis) => {
+            tokio::spawn(synapse_core::services::breakers::sync_horizon_override(
+                redis.clone(),
+                horizon_client.clone(),
+            ));
+            tokio::spawn(synapse_core::services::region::sync_role(redis));
+        }
+        Err(e) => tracing::warn!("Circuit breaker and region role overrides disabled: {}", e),
+    }
+
     // Back-pressure: refresh pending queue depth every 5s
     let depth_pool = pool.clone();
     let depth_counter = pending_queue_depth.clone();
@@ -375,14 +581,35 @@ async fn serve(
         config.processor_scaling_factor,
         current_batch_size,
         pending_queue_depth,
-    );
+    )
+    .with_events(domain_events.clone());
     let _processor_shutdown = processor_pool.start();
 
     // Register and start scheduled jobs
-    let scheduler = synapse_core::services::JobScheduler::new();
     let stellar_account = std::env::var("RECONCILIATION_ACCOUNT").ok();
 
+    let mut job_runner = synapse_core::services::job_runner::JobRunner::new(pool.clone())
+        .register(synapse_core::services::export_jobs::ExportJobHandler {
+            store: synapse_core::adapters::object_store(),
+        })
+        .register(synapse_core::services::rollups::RollupRecomputeHandler)
+        .register(synapse_core::services::statements::StatementJobHandler {
+            store: synapse_core::adapters::object_store(),
+            redis_url: config.redis_url.clone(),
+        })
+        .register(
+            synapse_core::services::horizon_backfill::HorizonBackfillHandler {
+                horizon_client: horizon_client.clone(),
+            },
+        );
+
     if let Some(account) = stellar_account {
+        job_runner = job_runner.register(
+            synapse_core::services::reconciliation::ReconciliationBackfillHandler {
+                horizon_client: horizon_client.clone(),
+                stellar_account: account.clone(),
+            },
+        );
         let recon_job = synapse_core::services::reconciliation::ReconciliationJob {
             pool: pool.clone(),
             horizon_client: horizon_client.clone(),
@@ -394,13 +621,85 @@ async fn serve(
     } else {
         tracing::info!("RECONCILIATION_ACCOUNT not set — daily reconciliation job not scheduled");
     }
+    if let Some(table) = synapse_core::adapters::shadow_write_table() {
+        match synapse_core::adapters::PostgresTransactionRepository::with_table(
+            pool.clone(),
+            &table,
+        ) {
+            Ok(shadow) => {
+                job_runner = job_runner.register(
+                    synapse_core::services::shadow_compare::ShadowCompareHandler {
+                        primary: Arc::new(
+                            synapse_core::adapters::PostgresTransactionRepository::new(
+                                pool.clone(),
+                            ),
+                        ),
+                        shadow: Arc::new(shadow),
+                    },
+                );
+            }
+            Err(e) => tracing::warn!("SHADOW_WRITE_TABLE ignored: {}", e),
+        }
+    }
+    if let Err(e) = scheduler.register_job(Box::new(job_runner)).await {
+        tracing::warn!("Failed to register background job runner: {}", e);
+    }
+    let housekeeping = synapse_core::services::HousekeepingJob::new(
+        pool.clone(),
+        synapse_core::services::housekeeping::HousekeepingConfig::from_env()?,
+    )
+    .with_idempotency(idempotency_service.clone());
+    if let Err(e) = scheduler.register_job(Box::new(housekeeping)).await {
+        tracing::warn!("Failed to register housekeeping job: {}", e);
+    }
+    let structuring = synapse_core::services::structuring::StructuringJob { pool: pool.clone() };
+    if let Err(e) = scheduler.register_job(Box::new(structuring)).await {
+        tracing::warn!("Failed to register structuring detection job: {}", e);
+    }
+    let rollups = synapse_core::services::rollups::RollupCompactionJob { pool: pool.clone() };
+    if let Err(e) = scheduler.register_job(Box::new(rollups)).await {
+        tracing::warn!("Failed to register rollup compaction job: {}", e);
+    }
+    if let Some(job) = synapse_core::services::claimable_balances::ClaimableBalanceJob::from_env(
+        pool.clone(),
+        horizon_client.clone(),
+    )? {
+        if let Err(e) = scheduler.register_job(Box::new(job)).await {
+            tracing::warn!("Failed to register claimable balance sweep: {}", e);
+        }
+    }
     if let Err(e) = scheduler.start().await {
         tracing::warn!("Failed to start job scheduler: {}", e);
     }
     tracing::info!("Job scheduler started");
 
-    let app = synapse_core::create_app(app_state.clone());
+    // With INTERNAL_PORT set, admin, DLQ and probe routes move to a second
+    // listener meant for the cluster network only; the public listener keeps
+    // the partner-facing routes.
+    let internal_port = std::env::var("INTERNAL_PORT")
+        .ok()
+        .map(|p| p.parse::<u16>())
+        .transpose()
+        .map_err(|e| anyhow::anyhow!("Invalid INTERNAL_PORT: {e}"))?;
+    let app = match internal_port {
+        Some(port) => {
+            let internal_addr = SocketAddr::from(([0, 0, 0, 0], port));
+            let internal_app = synapse_core::create_internal_app(app_state.clone());
+            tracing::info!("internal routes listening on {}", internal_addr);
+            tokio::spawn(async move {
+                if let Err(e) = axum::Server::bind(&internal_addr)
+                    .serve(internal_app.into_make_service_with_connect_info::<SocketAddr>())
+                    .await
+                {
+                    tracing::error!("Internal listener failed: {}", e);
+                }
+            });
+            synapse_core::create_public_app(app_state.clone())
+        }
+        None => synapse_core::create_app(app_state.clone()),
+    };
     let readiness = app_state.readiness.clone();
+    readiness.set_ready();
 
     // Mount Swagger UI at /api/docs and serve OpenAPI JSON at /api/docs/openapi.json
     let app =
@@ -15,7 +15,28 @@
 //! | `db_pool_active_connections`      | Gauge      | Active DB connections                        |
 //! | `db_pool_idle_connections`        | Gauge      | Idle DB connections                          |
 //! | `db_query_timeout_total`          | Counter    | Number of timed-out DB queries               |
+//! | `scheduled_job_timeout_total`     | Counter    | Scheduled job runs cancelled on timeout (`job`) |
+//! | `housekeeping_rows_deleted_total` | Counter    | Rows pruned by the housekeeping job (`table`) |
+//! | `circuit_breaker_transitions_total` | Counter  | Breaker state changes (`breaker`, `to`)      |
+//! | `broadcast_messages_lagged_total` | Counter    | Events lagging subscribers missed (`class`)  |
+//! | `db_failover_transitions_total`   | Counter    | Primary reachability flips (`to`)            |
+//! | `dlq_entries_total`               | Counter    | Transactions moved to the DLQ (`class`)      |
+//! | `dlq_auto_requeued_total`         | Counter    | DLQ entries requeued on recovery (`class`)   |
+//! | `dlq_watermark_breaches_total`    | Counter    | DLQ rising past a size limit (`level`)       |
+//! | `dlq_entries_archived_total`      | Counter    | DLQ entries moved to the object store        |
+//! | `log_lines_suppressed_total`      | Counter    | Repeated log lines dropped by sampling (`site`) |
+//! | `handler_panics_total`            | Counter    | Handler panics turned into 500s (`route`)    |
+//! | `transaction_verifications_total` | Counter    | Horizon completion checks (`outcome`)        |
+//! | `transaction_risk_score`          | Histogram  | Deposit risk scores (`outcome`)              |
+//! | `transaction_verify_retries`      | Histogram  | Failed Horizon lookups before a verification |
+//! | `transaction_time_to_success_ms`  | Histogram  | First Horizon lookup to verification, in ms  |
 //! | `pending_queue_depth`             | Gauge      | Depth of the pending transaction queue       |
+//! | `stellar_base_fee_stroops`        | Gauge      | Per-operation fee bid (`strategy`)           |
+//! | `stellar_fee_charged_stroops_total` | Counter  | Fees charged on submitted transactions       |
+//! | `horizon_active_endpoint`         | Gauge      | 1 for the Horizon endpoint in use (`endpoint`) |
+//! | `horizon_failovers_total`         | Counter    | Horizon endpoint switches (`from`, `to`)     |
+//! | `graphql_request_duration_ms`     | Histogram  | GraphQL operation execution latency in ms    |
+//! | `graphql_resolver_duration_ms`    | Histogram  | Per-field resolver latency in ms (`field`)   |
 //!
 //! ## Configuration
 //!
@@ -23,13 +44,19 @@
 //! |--------------------------|--------------------------------|--------------------------------|
 //! | `OTLP_ENDPOINT`          | `http://localhost:4317`        | gRPC OTLP collector endpoint   |
 //! | `OTEL_SERVICE_NAME`      | `synapse-core`                 | Service name reported to OTel  |
+//!
+//! The exporter is compiled only with the `metrics` feature (on by default).
+//! Without it the instruments still exist but record into OpenTelemetry's
+//! no-op global meter, and [`init_metrics`] installs nothing.
 
 use opentelemetry::{
     global,
     metrics::{Counter, Histogram, Meter, ObservableGauge, Unit},
     KeyValue,
 };
+#[cfg(feature = "metrics")]
 use opentelemetry_otlp::WithExportConfig;
+#[cfg(feature = "metrics")]
 use opentelemetry_sdk::{
     metrics::{
         reader::{DefaultAggregationSelector, DefaultTemporalitySelector},
@@ -128,6 +155,152 @@ pub fn background_task_timeout_total() -> Counter<u64> {
         .init()
 }
 
+/// Scheduled job runs that exceeded their timeout, labelled with `job`.
+pub fn scheduled_job_timeout_total() -> Counter<u64> {
+    meter()
+        .u64_counter("scheduled_job_timeout_total")
+        .with_description("Number of scheduled job runs cancelled after exceeding their timeout")
+        .init()
+}
+
+/// Requests cut off by their route's latency budget, labelled with `route`
+/// and `detached`.
+pub fn http_request_timeouts_total() -> Counter<u64> {
+    meter()
+        .u64_counter("http_request_timeouts_total")
+        .with_description("Number of requests answered 504 after exceeding their latency budget")
+        .init()
+}
+
+/// Requests shed by a concurrency limit, labelled with `route`, `limit`
+/// (`global` or `route`) and `reason` (`queue_full` or `queue_timeout`).
+pub fn http_requests_shed_total() -> Counter<u64> {
+    meter()
+        .u64_counter("http_requests_shed_total")
+        .with_description(
+            "Number of requests answered 503 because a concurrency limit was saturated",
+        )
+        .init()
+}
+
+/// Horizon verification results for pending transactions, labelled with
+/// `outcome` (`verified`, `deferred`, `retry`, `rejected`, `exhausted`).
+pub fn transaction_verifications_total() -> Counter<u64> {
+    meter()
+        .u64_counter("transaction_verifications_total")
+        .with_description("Number of Horizon verification attempts for pending transactions")
+        .init()
+}
+
+/// Risk scores (0-100) assigned to deposits by the processor, labelled with
+/// `outcome` (`review` at or above `RISK_REVIEW_THRESHOLD`, else `pass`).
+pub fn transaction_risk_score() -> Histogram<u64> {
+    meter()
+        .u64_histogram("transaction_risk_score")
+        .with_description("Risk scores assigned to deposits before verification")
+        .init()
+}
+
+/// Horizon lookups that did not verify a transaction before one did. Read
+/// with `dlq_entries_total` to judge `HORIZON_VERIFY_MAX_ATTEMPTS`.
+pub fn transaction_verify_retries() -> Histogram<u64> {
+    meter()
+        .u64_histogram("transaction_verify_retries")
+        .with_description("Failed Horizon lookups before a transaction verified")
+        .init()
+}
+
+/// Time from a transaction's first Horizon lookup to its verification
+/// (milliseconds); zero when the first lookup verified it.
+pub fn transaction_time_to_success_ms() -> Histogram<f64> {
+    meter()
+        .f64_histogram("transaction_time_to_success_ms")
+        .with_description("Time from first Horizon lookup to verification in milliseconds")
+        .with_unit(Unit::new("ms"))
+        .init()
+}
+
+/// Rows deleted by the housekeeping job, labelled with `table`.
+pub fn housekeeping_rows_deleted_total() -> Counter<u64> {
+    meter()
+        .u64_counter("housekeeping_rows_deleted_total")
+        .with_description("Number of expired rows pruned by the housekeeping job")
+        .init()
+}
+
+/// Circuit breaker state changes, labelled with `breaker` and `to`.
+pub fn circuit_breaker_transitions_total() -> Counter<u64> {
+    meter()
+        .u64_counter("circuit_breaker_transitions_total")
+        .with_description("Number of circuit breaker state changes, automatic or manual")
+        .init()
+}
+
+/// Broadcast events skipped by lagging subscribers, labelled with `class`.
+pub fn broadcast_messages_lagged_total() -> Counter<u64> {
+    meter()
+        .u64_counter("broadcast_messages_lagged_total")
+        .with_description("Number of broadcast events dropped for subscribers that fell behind")
+        .init()
+}
+
+/// Transactions moved to the DLQ, labelled with their error `class`.
+pub fn dlq_entries_total() -> Counter<u64> {
+    meter()
+        .u64_counter("dlq_entries_total")
+        .with_description("Number of transactions moved to the DLQ, by error class")
+        .init()
+}
+
+/// DLQ entries requeued automatically after Horizon recovered, by `class`.
+pub fn dlq_auto_requeued_total() -> Counter<u64> {
+    meter()
+        .u64_counter("dlq_auto_requeued_total")
+        .with_description("Number of DLQ entries requeued after Horizon recovered, by error class")
+        .init()
+}
+
+/// DLQ size rising past `DLQ_WATERMARK_PERCENT` (`level` = `watermark`) or
+/// `DLQ_MAX_ENTRIES` (`level` = `full`).
+pub fn dlq_watermark_breaches_total() -> Counter<u64> {
+    meter()
+        .u64_counter("dlq_watermark_breaches_total")
+        .with_description("Number of times the DLQ rose past its watermark or maximum size")
+        .init()
+}
+
+/// DLQ entries archived to the object store by the `archive` overflow policy.
+pub fn dlq_entries_archived_total() -> Counter<u64> {
+    meter()
+        .u64_counter("dlq_entries_archived_total")
+        .with_description("Number of DLQ entries archived to the object store on overflow")
+        .init()
+}
+
+/// Handler panics caught by [`crate::middleware::panic_recovery`].
+pub fn handler_panics_total() -> Counter<u64> {
+    meter()
+        .u64_counter("handler_panics_total")
+        .with_description("Number of handler panics converted into 500 responses, by route")
+        .init()
+}
+
+/// Readiness flips made by [`crate::db::failover::watch`].
+pub fn db_failover_transitions_total() -> Counter<u64> {
+    meter()
+        .u64_counter("db_failover_transitions_total")
+        .with_description("Number of times the primary database was found unreachable or recovered")
+        .init()
+}
+
+/// Log lines dropped by [`crate::utils::log_sampler`].
+pub fn log_lines_suppressed_total() -> Counter<u64> {
+    meter()
+        .u64_counter("log_lines_suppressed_total")
+        .with_description("Number of repeated log lines dropped by sampling, by call site")
+        .init()
+}
+
 /// Slow database query counter.
 pub fn db_slow_queries_total() -> Counter<u64> {
     meter()
@@ -144,6 +317,57 @@ pub fn pending_queue_depth() -> ObservableGauge<u64> {
         .init()
 }
 
+/// Per-operation fee the anchor currently bids, labelled with `strategy`.
+/// Observed until the returned handle is dropped.
+pub fn stellar_base_fee_stroops() -> ObservableGauge<u64> {
+    meter()
+        .u64_observable_gauge("stellar_base_fee_stroops")
+        .with_description("Per-operation fee bid on the anchor's Stellar transactions")
+        .with_callback(|observer| {
+            let fees = crate::services::fees::fees();
+            observer.observe(
+                u64::from(fees.base_fee()),
+                &[KeyValue::new("strategy", fees.policy().strategy.as_str())],
+            );
+        })
+        .init()
+}
+
+/// 1 for the Horizon endpoint requests go to and 0 for the others,
+/// labelled with `endpoint`. Observed until the returned handle is dropped.
+pub fn horizon_active_endpoint(
+    endpoints: crate::stellar::endpoints::HorizonEndpoints,
+) -> ObservableGauge<u64> {
+    meter()
+        .u64_observable_gauge("horizon_active_endpoint")
+        .with_description("Whether each configured Horizon endpoint is the active one")
+        .with_callback(move |observer| {
+            for status in endpoints.status() {
+                observer.observe(
+                    u64::from(status.active),
+                    &[KeyValue::new("endpoint", status.endpoint)],
+                );
+            }
+        })
+        .init()
+}
+
+/// Switches of the active Horizon endpoint, labelled with `from` and `to`.
+pub fn horizon_failovers_total() -> Counter<u64> {
+    meter()
+        .u64_counter("horizon_failovers_total")
+        .with_description("Number of times requests moved to another Horizon endpoint")
+        .init()
+}
+
+/// Stroops charged on transactions the anchor submitted.
+pub fn stellar_fee_charged_stroops_total() -> Counter<u64> {
+    meter()
+        .u64_counter("stellar_fee_charged_stroops_total")
+        .with_description("Network fees charged on the anchor's Stellar transactions in stroops")
+        .init()
+}
+
 /// Settlement operation duration histogram (milliseconds).
 pub fn settlement_duration_ms() -> Histogram<f64> {
     meter()
@@ -178,6 +402,25 @@ pub fn lock_hold_duration_ms() -> Histogram<f64> {
         .init()
 }
 
+/// GraphQL operation execution duration histogram (milliseconds).
+pub fn graphql_request_duration_ms() -> Histogram<f64> {
+    meter()
+        .f64_histogram("graphql_request_duration_ms")
+        .with_description("GraphQL operation execution latency in milliseconds")
+        .with_unit(Unit::new("ms"))
+        .init()
+}
+
+/// GraphQL resolver duration histogram (milliseconds), labelled by `field`
+/// (`ParentType.fieldName`).
+pub fn graphql_resolver_duration_ms() -> Histogram<f64> {
+    meter()
+        .f64_histogram("graphql_resolver_duration_ms")
+        .with_description("GraphQL per-field resolver latency in milliseconds")
+        .with_unit(Unit::new("ms"))
+        .init()
+}
+
 // ---------------------------------------------------------------------------
 // Provider initialisation
 // ---------------------------------------------------------------------------
@@ -186,6 +429,7 @@ pub fn lock_hold_duration_ms() -> Histogram<f64> {
 /// can keep it alive for the process lifetime.
 ///
 /// Call this once at startup, before any instruments are used.
+#[cfg(feature = "metrics")]
 pub fn init_metrics_provider() -> Result<SdkMeterProvider, Box<dyn std::error::Error>> {
     let endpoint =
         std::env::var("OTLP_ENDPOINT").unwrap_or_else(|_| "http://localhost:4317".to_string());
@@ -231,11 +475,13 @@ pub fn init_metrics_provider() -> Result<SdkMeterProvider, Box<dyn std::error::E
 #[derive(Clone)]
 pub struct MetricsHandle {
     /// Keeps the MeterProvider alive.
+    #[cfg(feature = "metrics")]
     _provider: std::sync::Arc<SdkMeterProvider>,
 }
 
 /// Initialise metrics and return a handle.  Logs a warning but does not panic
 /// if the OTLP exporter cannot be configured (e.g. in test environments).
+#[cfg(feature = "metrics")]
 pub fn init_metrics() -> Result<MetricsHandle, Box<dyn std::error::Error>> {
     let provider = init_metrics_provider()?;
     Ok(MetricsHandle {
@@ -243,6 +489,12 @@ pub fn init_metrics() -> Result<MetricsHandle, Box<dyn std::error::Error>> {
     })
 }
 
+/// Without the `metrics` feature there is no exporter to configure.
+#[cfg(not(feature = "metrics"))]
+pub fn init_metrics() -> Result<MetricsHandle, Box<dyn std::error::Error>> {
+    Ok(MetricsHandle {})
+}
+
 // ---------------------------------------------------------------------------
 // Pool stats background task
 // ---------------------------------------------------------------------------
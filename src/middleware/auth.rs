@@ -1,7 +1,8 @@
+use async_trait::async_trait;
 use axum::{
     body::Body,
-    extract::ConnectInfo,
-    http::{Request, StatusCode},
+    extract::{ConnectInfo, FromRequestParts},
+    http::{request::Parts, HeaderMap, Request, StatusCode},
     middleware::Next,
     response::Response,
 };
@@ -57,39 +58,98 @@ pub async fn api_key_auth(req: Request<Body>, next: Next<Body>) -> Result<Respon
     }
 }
 
-/// Admin auth middleware that accepts all currently-valid API keys (supports secret rotation).
-/// If a `SecretsStore` extension is present on the request, it checks all valid keys
-/// (current + grace-period previous). Falls back to the `ADMIN_API_KEY` env var otherwise.
-pub async fn admin_auth(req: Request<Body>, next: Next<Body>) -> Result<Response, StatusCode> {
-    let auth_header = req
-        .headers()
-        .get("Authorization")
-        .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+/// Who an admin request was authenticated as, added to the request by
+/// [`admin_auth`]. Handlers record it as the actor of what they change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdminPrincipal {
+    pub name: String,
+    /// Authenticated with the shared admin key, which does not say who is
+    /// calling.
+    pub shared: bool,
+}
 
-    let provided = match auth_header {
-        Some(v) => v,
-        None => return Err(StatusCode::UNAUTHORIZED),
-    };
+impl AdminPrincipal {
+    /// Name recorded for requests made with the shared admin key.
+    pub const SHARED_NAME: &'static str = "admin";
 
-    // Try SecretsStore extension first (rotation-aware).
-    if let Some(store) = req.extensions().get::<SecretsStore>() {
-        let valid_keys = store.valid_admin_keys().await;
-        if valid_keys.iter().any(|k| k == &provided) {
-            return Ok(next.run(req).await);
+    fn shared() -> Self {
+        Self {
+            name: Self::SHARED_NAME.to_string(),
+            shared: true,
         }
-        return Err(StatusCode::UNAUTHORIZED);
     }
+}
+
+/// The principal set by [`admin_auth`]; `401` on routes it does not cover.
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for AdminPrincipal {
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<AdminPrincipal>()
+            .cloned()
+            .ok_or(StatusCode::UNAUTHORIZED)
+    }
+}
 
-    // Fallback: plain env var (no Vault / rotation).
-    let admin_api_key =
-        std::env::var("ADMIN_API_KEY").unwrap_or_else(|_| "admin-secret-key".to_string());
+/// The operator `provided` belongs to in `ADMIN_OPERATOR_KEYS`
+/// (`name:key,name:key`).
+fn operator_for(operator_keys: &str, provided: &str) -> Option<AdminPrincipal> {
+    operator_keys
+        .split(',')
+        .filter_map(|entry| entry.split_once(':'))
+        .map(|(name, key)| (name.trim(), key.trim()))
+        .find(|(name, key)| !name.is_empty() && !key.is_empty() && *key == provided)
+        .map(|(name, _)| AdminPrincipal {
+            name: name.to_string(),
+            shared: false,
+        })
+}
+
+/// The admin principal the `Authorization` header authenticates, if any. A
+/// key listed in `ADMIN_OPERATOR_KEYS` authenticates as that operator;
+/// otherwise the shared admin key is accepted. With a `secrets` store all
+/// valid shared keys (current + grace-period previous) are checked, else the
+/// `ADMIN_API_KEY` env var.
+pub async fn authenticate(
+    headers: &HeaderMap,
+    secrets: Option<&SecretsStore>,
+) -> Option<AdminPrincipal> {
+    let provided = headers
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.trim_start_matches("Bearer ").to_string())?;
 
-    if provided == admin_api_key {
-        Ok(next.run(req).await)
-    } else {
-        Err(StatusCode::UNAUTHORIZED)
+    let operator_keys = std::env::var("ADMIN_OPERATOR_KEYS").unwrap_or_default();
+    if let Some(operator) = operator_for(&operator_keys, &provided) {
+        return Some(operator);
     }
+
+    let valid = match secrets {
+        // Try SecretsStore first (rotation-aware).
+        Some(store) => store.valid_admin_keys().await.contains(&provided),
+        // Fallback: plain env var (no Vault / rotation).
+        None => {
+            let admin_api_key =
+                std::env::var("ADMIN_API_KEY").unwrap_or_else(|_| "admin-secret-key".to_string());
+            provided == admin_api_key
+        }
+    };
+    valid.then(AdminPrincipal::shared)
+}
+
+/// Admin auth middleware: [`authenticate`] with the request's `SecretsStore`
+/// extension, if present. The caller's [`AdminPrincipal`] is added to the
+/// request; `401` if there is none.
+pub async fn admin_auth(mut req: Request<Body>, next: Next<Body>) -> Result<Response, StatusCode> {
+    let principal = authenticate(req.headers(), req.extensions().get::<SecretsStore>())
+        .await
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    req.extensions_mut().insert(principal);
+    Ok(next.run(req).await)
 }
 
 #[cfg(test)]
@@ -139,4 +199,19 @@ mod tests {
             "Empty X-API-Key should be treated as missing"
         );
     }
+
+    #[test]
+    fn test_operator_keys_name_the_principal() {
+        let keys = "alice:key-a, bob:key-b,broken,:key-c,carol:";
+        assert_eq!(
+            operator_for(keys, "key-b"),
+            Some(AdminPrincipal {
+                name: "bob".to_string(),
+                shared: false,
+            })
+        );
+        assert_eq!(operator_for(keys, "key-c"), None);
+        assert_eq!(operator_for(keys, ""), None);
+        assert_eq!(operator_for("", "key-a"), None);
+    }
 }
@@ -465,6 +465,115 @@ impl IdempotencyService {
     }
 }
 
+// ── Admin inspection ──────────────────────────────────────────────────────────
+
+/// Lock currently held on an idempotency key.
+#[derive(Debug, Serialize)]
+pub struct LockInfo {
+    /// Instance that acquired the lock, when the lock value is parseable.
+    pub instance_id: Option<String>,
+    /// Unix timestamp at which the lock was taken.
+    pub locked_at: Option<u64>,
+    /// Seconds until Redis expires the lock.
+    pub ttl_seconds: i64,
+}
+
+/// Cached response stored for an idempotency key. The body itself is not
+/// exposed because it may contain partner data; only its size is reported.
+#[derive(Debug, Serialize)]
+pub struct CachedEntryInfo {
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub body_bytes: usize,
+    pub ttl_seconds: i64,
+}
+
+/// Redis state for one `(tenant, key)` pair.
+#[derive(Debug, Serialize)]
+pub struct IdempotencyEntry {
+    pub tenant_id: String,
+    pub key: String,
+    pub lock: Option<LockInfo>,
+    pub cached_response: Option<CachedEntryInfo>,
+}
+
+impl IdempotencyEntry {
+    pub fn is_empty(&self) -> bool {
+        self.lock.is_none() && self.cached_response.is_none()
+    }
+}
+
+/// Read the lock and cached response for `key` without modifying either.
+pub async fn inspect_entry(
+    client: &Client,
+    tenant_id: &str,
+    key: &str,
+) -> Result<IdempotencyEntry, redis::RedisError> {
+    let mut conn = client.get_multiplexed_async_connection().await?;
+    let lock_key = _lock_key(tenant_id, key);
+    let cache_key = _cache_key(tenant_id, key);
+
+    let (lock_raw, lock_ttl, cached_raw, cache_ttl): (Option<String>, i64, Option<String>, i64) =
+        redis::pipe()
+            .cmd("GET")
+            .arg(&lock_key)
+            .cmd("TTL")
+            .arg(&lock_key)
+            .cmd("GET")
+            .arg(&cache_key)
+            .cmd("TTL")
+            .arg(&cache_key)
+            .query_async(&mut conn)
+            .await?;
+
+    let lock = lock_raw.map(|raw| {
+        let value = serde_json::from_str::<LockValue>(&raw).ok();
+        LockInfo {
+            instance_id: value.as_ref().map(|v| v.instance_id.clone()),
+            locked_at: value.map(|v| v.locked_at),
+            ttl_seconds: lock_ttl,
+        }
+    });
+
+    let cached_response =
+        cached_raw.map(|raw| match serde_json::from_str::<CachedResponse>(&raw) {
+            Ok(cached) => CachedEntryInfo {
+                status: cached.status,
+                content_type: cached.content_type,
+                body_bytes: cached.body.len(),
+                ttl_seconds: cache_ttl,
+            },
+            Err(_) => CachedEntryInfo {
+                status: 0,
+                content_type: None,
+                body_bytes: raw.len(),
+                ttl_seconds: cache_ttl,
+            },
+        });
+
+    Ok(IdempotencyEntry {
+        tenant_id: tenant_id.to_string(),
+        key: key.to_string(),
+        lock,
+        cached_response,
+    })
+}
+
+/// Delete the lock and cached response for `key`, so the next request with
+/// it is processed afresh. Returns the number of Redis keys removed.
+pub async fn expire_entry(
+    client: &Client,
+    tenant_id: &str,
+    key: &str,
+) -> Result<u64, redis::RedisError> {
+    let mut conn = client.get_multiplexed_async_connection().await?;
+    redis::cmd("DEL")
+        .arg(_lock_key(tenant_id, key))
+        .arg(_cache_key(tenant_id, key))
+        .query_async(&mut conn)
+        .await
+}
+
 /// Extract tenant ID from `X-Tenant-Id` header; falls back to `"default"`.
 fn extract_tenant_id(request: &Request<Body>) -> String {
     request
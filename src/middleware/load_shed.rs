@@ -0,0 +1,317 @@
+//! Concurrency limits and load shedding.
+//!
+//! Requests run under a global in-flight limit and, optionally, a per-route
+//! one. A request that finds its limit reached waits in a bounded queue for up
+//! to `QUEUE_WAIT_MS` (default 1 000); when the queue is full or the wait runs
+//! out it is shed with `503` + `Retry-After` as `application/problem+json`
+//! (`ERR_OVERLOAD_001`), before touching the database pool.
+//!
+//! | Variable                 | Meaning                                     | Default |
+//! |--------------------------|---------------------------------------------|---------|
+//! | `MAX_CONCURRENCY_GLOBAL` | Requests in flight across all routes        | 200     |
+//! | `MAX_QUEUE_GLOBAL`       | Requests waiting for a global slot          | 400     |
+//! | `MAX_CONCURRENCY_<ROUTE>`| Requests in flight on one route             | none    |
+//! | `MAX_QUEUE_<ROUTE>`      | Requests waiting on that route              | its limit |
+//! | `QUEUE_WAIT_MS`          | Longest a request waits for both slots      | 1000    |
+//!
+//! `<ROUTE>` is named as for latency budgets (see
+//! [`crate::middleware::timeout`]). `0` disables a limit.
+
+use crate::error::{codes, problem_response};
+use crate::metrics;
+use crate::middleware::timeout::route_key;
+use axum::{
+    body::Body,
+    extract::{MatchedPath, State},
+    http::{header, HeaderValue, Request},
+    middleware::Next,
+    response::Response,
+};
+use opentelemetry::KeyValue;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Semaphore, SemaphorePermit};
+use tokio::time::Instant;
+
+pub const DEFAULT_MAX_CONCURRENCY: usize = 200;
+pub const DEFAULT_MAX_QUEUE: usize = 400;
+pub const DEFAULT_QUEUE_WAIT: Duration = Duration::from_millis(1000);
+pub const RETRY_AFTER_SECS: u64 = 1;
+
+const GLOBAL: &str = "GLOBAL";
+
+/// Why a request was shed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shed {
+    QueueFull,
+    QueueTimeout,
+}
+
+impl Shed {
+    fn as_str(self) -> &'static str {
+        match self {
+            Shed::QueueFull => "queue_full",
+            Shed::QueueTimeout => "queue_timeout",
+        }
+    }
+}
+
+/// One concurrency limit and the queue in front of it.
+#[derive(Debug)]
+struct Lane {
+    permits: Semaphore,
+    max_queue: usize,
+    queued: AtomicUsize,
+}
+
+/// Takes a queue slot for as long as it lives, so a request dropped while
+/// waiting (client gone, latency budget hit) gives it back.
+struct Queued<'a>(&'a AtomicUsize);
+
+impl Drop for Queued<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl Lane {
+    fn new(max_concurrency: usize, max_queue: usize) -> Self {
+        Self {
+            permits: Semaphore::new(max_concurrency),
+            max_queue,
+            queued: AtomicUsize::new(0),
+        }
+    }
+
+    async fn acquire(&self, deadline: Instant) -> Result<SemaphorePermit<'_>, Shed> {
+        if let Ok(permit) = self.permits.try_acquire() {
+            return Ok(permit);
+        }
+        if self.queued.fetch_add(1, Ordering::SeqCst) >= self.max_queue {
+            self.queued.fetch_sub(1, Ordering::SeqCst);
+            return Err(Shed::QueueFull);
+        }
+        let _queued = Queued(&self.queued);
+        match tokio::time::timeout_at(deadline, self.permits.acquire()).await {
+            Ok(Ok(permit)) => Ok(permit),
+            _ => Err(Shed::QueueTimeout),
+        }
+    }
+}
+
+/// Global and per-route limits, read once at startup.
+#[derive(Debug)]
+pub struct ConcurrencyLimits {
+    global: Option<Lane>,
+    routes: HashMap<String, Lane>,
+    queue_wait: Duration,
+}
+
+impl ConcurrencyLimits {
+    pub fn from_env() -> Self {
+        Self::from_vars(std::env::vars())
+    }
+
+    fn from_vars(vars: impl IntoIterator<Item = (String, String)>) -> Self {
+        let mut concurrency: HashMap<String, usize> = HashMap::new();
+        let mut queues: HashMap<String, usize> = HashMap::new();
+        let mut queue_wait = DEFAULT_QUEUE_WAIT;
+
+        for (key, raw) in vars {
+            if key == "QUEUE_WAIT_MS" {
+                match raw.trim().parse::<u64>() {
+                    Ok(ms) => queue_wait = Duration::from_millis(ms),
+                    Err(_) => tracing::warn!(key, value = %raw, "Ignoring invalid queue wait"),
+                }
+                continue;
+            }
+            let (target, route) = if let Some(route) = key.strip_prefix("MAX_CONCURRENCY_") {
+                (&mut concurrency, route)
+            } else if let Some(route) = key.strip_prefix("MAX_QUEUE_") {
+                (&mut queues, route)
+            } else {
+                continue;
+            };
+            match raw.trim().parse::<usize>() {
+                Ok(n) => {
+                    target.insert(route.to_string(), n);
+                }
+                Err(_) => tracing::warn!(key, value = %raw, "Ignoring invalid concurrency limit"),
+            }
+        }
+
+        let global_limit = concurrency
+            .remove(GLOBAL)
+            .unwrap_or(DEFAULT_MAX_CONCURRENCY);
+        let global_queue = queues.remove(GLOBAL).unwrap_or(DEFAULT_MAX_QUEUE);
+        let global = (global_limit > 0).then(|| Lane::new(global_limit, global_queue));
+
+        let routes = concurrency
+            .into_iter()
+            .filter(|(_, limit)| *limit > 0)
+            .map(|(route, limit)| {
+                let queue = queues.get(&route).copied().unwrap_or(limit);
+                (route, Lane::new(limit, queue))
+            })
+            .collect();
+
+        Self {
+            global,
+            routes,
+            queue_wait,
+        }
+    }
+}
+
+pub async fn limit_concurrency(
+    State(limits): State<Arc<ConcurrencyLimits>>,
+    req: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| req.uri().path().to_string());
+    let deadline = Instant::now() + limits.queue_wait;
+
+    // The route slot is taken first so a backlog on one hot route waits in
+    // its own queue instead of holding global slots.
+    let _route_permit = match limits.routes.get(&route_key(&route)) {
+        Some(lane) => match lane.acquire(deadline).await {
+            Ok(permit) => Some(permit),
+            Err(reason) => return shed(&route, "route", reason),
+        },
+        None => None,
+    };
+    let _global_permit = match &limits.global {
+        Some(lane) => match lane.acquire(deadline).await {
+            Ok(permit) => Some(permit),
+            Err(reason) => return shed(&route, "global", reason),
+        },
+        None => None,
+    };
+
+    next.run(req).await
+}
+
+/// `503` problem document for a shed request.
+fn shed(route: &str, limit: &'static str, reason: Shed) -> Response {
+    metrics::http_requests_shed_total().add(
+        1,
+        &[
+            KeyValue::new("route", route.to_string()),
+            KeyValue::new("limit", limit),
+            KeyValue::new("reason", reason.as_str()),
+        ],
+    );
+    tracing::warn!(
+        route,
+        limit,
+        reason = reason.as_str(),
+        "Request shed by concurrency limit"
+    );
+
+    let mut response = problem_response(
+        codes::OVERLOAD_001,
+        format!("The server is at its {limit} concurrency limit; retry after {RETRY_AFTER_SECS} s"),
+        route,
+    );
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, HeaderValue::from(RETRY_AFTER_SECS));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_limits_from_vars() {
+        let limits = ConcurrencyLimits::from_vars(vars(&[
+            ("MAX_CONCURRENCY_GLOBAL", "10"),
+            ("MAX_CONCURRENCY_CALLBACK", "4"),
+            ("MAX_QUEUE_CALLBACK", "2"),
+            ("MAX_CONCURRENCY_SETTLEMENTS", "0"),
+            ("MAX_CONCURRENCY_TRANSACTIONS", "many"),
+            ("QUEUE_WAIT_MS", "250"),
+        ]));
+        let global = limits.global.as_ref().unwrap();
+        assert_eq!(global.permits.available_permits(), 10);
+        assert_eq!(global.max_queue, DEFAULT_MAX_QUEUE);
+        assert_eq!(limits.routes["CALLBACK"].max_queue, 2);
+        assert!(!limits.routes.contains_key("SETTLEMENTS"));
+        assert!(!limits.routes.contains_key("TRANSACTIONS"));
+        assert_eq!(limits.queue_wait, Duration::from_millis(250));
+
+        let off = ConcurrencyLimits::from_vars(vars(&[("MAX_CONCURRENCY_GLOBAL", "0")]));
+        assert!(off.global.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_lane_queues_then_sheds() {
+        let lane = Lane::new(1, 1);
+        let deadline = Instant::now() + Duration::from_millis(20);
+        let held = lane.acquire(deadline).await.unwrap();
+
+        let waiting = lane.acquire(deadline);
+        tokio::pin!(waiting);
+        assert!(futures::poll!(waiting.as_mut()).is_pending());
+        assert_eq!(lane.queued.load(Ordering::SeqCst), 1);
+
+        assert_eq!(lane.acquire(deadline).await.unwrap_err(), Shed::QueueFull);
+        assert_eq!(waiting.await.unwrap_err(), Shed::QueueTimeout);
+        assert_eq!(lane.queued.load(Ordering::SeqCst), 0);
+
+        drop(held);
+        assert!(lane
+            .acquire(Instant::now() + Duration::from_millis(20))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_shed_response() {
+        let limits = Arc::new(ConcurrencyLimits::from_vars(vars(&[
+            ("MAX_CONCURRENCY_SLOW", "1"),
+            ("MAX_QUEUE_SLOW", "0"),
+        ])));
+        let app = Router::new()
+            .route(
+                "/slow",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    "done"
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                limits,
+                limit_concurrency,
+            ));
+        let request = || Request::builder().uri("/slow").body(Body::empty()).unwrap();
+
+        let first = tokio::spawn(app.clone().oneshot(request()));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let response = app.oneshot(request()).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "1");
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            crate::error::PROBLEM_JSON
+        );
+        assert_eq!(first.await.unwrap().unwrap().status(), StatusCode::OK);
+    }
+}
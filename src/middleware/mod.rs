@@ -2,8 +2,12 @@ pub mod auth;
 pub mod error_enrichment;
 pub mod idempotency;
 pub mod ip_filter;
+pub mod load_shed;
 pub mod panic_recovery;
 pub mod quota;
+pub mod redaction;
 pub mod request_logger;
+pub mod signature;
+pub mod timeout;
 pub mod validate;
 pub mod versioning;
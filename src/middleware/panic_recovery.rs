@@ -1,48 +1,162 @@
+//! Converts handler panics into `500` responses.
+//!
+//! Without this a panic tears down the connection and the client sees a
+//! reset. Instead the client gets an `application/problem+json` body with code
+//! `ERR_INTERNAL_001` and the request's `request_id`; the panic message stays
+//! in the logs and never reaches the response.
+//!
+//! Every panic is logged at `error` and counted in
+//! `handler_panics_total{route}`. When `PANIC_ALERT_URL` is set, a JSON alert
+//! is also POSTed there (best effort, sampled like other repeated errors, see
+//! [`crate::utils::log_sampler`]), so a panicking route pages someone even
+//! though clients only see a `500`.
+
+use crate::error::{codes, problem_response_with_request_id, RequestId};
+use crate::metrics;
+use crate::middleware::timeout::route_key;
 use axum::{
     body::Body,
-    http::Request,
+    extract::MatchedPath,
+    http::{HeaderValue, Request},
     middleware::Next,
-    response::{IntoResponse, Response},
+    response::Response,
 };
 use futures::FutureExt;
+use opentelemetry::KeyValue;
 use std::panic::AssertUnwindSafe;
+use std::time::Duration;
+
+const ALERT_TIMEOUT: Duration = Duration::from_secs(5);
 
-/// Middleware that catches handler panics and returns a 500 response instead of
-/// dropping the connection. Logs the panic with a backtrace and increments the
-/// `handler_panic_total` metric counter.
+/// Middleware that catches handler panics and returns a sanitized `500`
+/// instead of dropping the connection.
 pub async fn panic_recovery_middleware(req: Request<Body>, next: Next<Body>) -> Response {
-    // Capture the URI before consuming the request
-    let uri = req.uri().to_string();
+    // Capture what the response and logs need before consuming the request
     let method = req.method().to_string();
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| req.uri().path().to_string());
+    let request_id = req
+        .extensions()
+        .get::<RequestId>()
+        .map(|id| id.0.clone())
+        .or_else(|| {
+            req.headers()
+                .get("x-request-id")
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        });
 
     let result = AssertUnwindSafe(next.run(req)).catch_unwind().await;
 
-    match result {
-        Ok(response) => response,
-        Err(panic_payload) => {
-            // Extract a human-readable panic message
-            let panic_msg = if let Some(s) = panic_payload.downcast_ref::<&str>() {
-                s.to_string()
-            } else if let Some(s) = panic_payload.downcast_ref::<String>() {
-                s.clone()
-            } else {
-                "unknown panic payload".to_string()
-            };
-
-            // Log at error level with as much context as possible.
-            // RUST_BACKTRACE=1 must be set for the backtrace to appear in logs.
-            tracing::error!(
-                panic.message = %panic_msg,
-                http.method = %method,
-                http.target = %uri,
-                "Handler panicked — returning 500 to client"
-            );
-
-            // Increment the metric counter (tracing-based event counter compatible
-            // with OpenTelemetry metrics bridge).
-            tracing::info!(counter.handler_panic_total = 1u64, "handler panic recorded");
-
-            axum::http::StatusCode::INTERNAL_SERVER_ERROR.into_response()
+    let panic_payload = match result {
+        Ok(response) => return response,
+        Err(panic_payload) => panic_payload,
+    };
+
+    // Extract a human-readable panic message
+    let panic_msg = if let Some(s) = panic_payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = panic_payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic payload".to_string()
+    };
+    let request_id = request_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+    // RUST_BACKTRACE=1 must be set for the backtrace to appear in logs.
+    tracing::error!(
+        panic.message = %panic_msg,
+        http.method = %method,
+        http.route = %route,
+        request_id = %request_id,
+        "Handler panicked — returning 500 to client"
+    );
+    metrics::handler_panics_total().add(1, &[KeyValue::new("route", route_key(&route))]);
+    alert(&route, &method, &request_id, &panic_msg);
+
+    let mut response = problem_response_with_request_id(
+        codes::INTERNAL_001,
+        "An unexpected error occurred; quote the request_id when reporting it",
+        &route,
+        &request_id,
+    );
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert("x-request-id", value);
+    }
+    response
+}
+
+/// POST the panic to `PANIC_ALERT_URL`, if set, without waiting for it.
+fn alert(route: &str, method: &str, request_id: &str, message: &str) {
+    let Some(url) = std::env::var("PANIC_ALERT_URL")
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+    else {
+        return;
+    };
+    if !crate::utils::log_sampler::should_log("panic_recovery.alert", message) {
+        return;
+    }
+
+    let body = serde_json::json!({
+        "event": "handler_panic",
+        "route": route,
+        "method": method,
+        "request_id": request_id,
+        "message": message,
+        "occurred_at": chrono::Utc::now(),
+    });
+    tokio::spawn(async move {
+        let result = reqwest::Client::new()
+            .post(&url)
+            .timeout(ALERT_TIMEOUT)
+            .json(&body)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status());
+        if let Err(e) = result {
+            tracing::warn!(error = %e, "Failed to send panic alert");
         }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_panic_returns_sanitized_problem_json() {
+        let app = Router::new()
+            .route("/boom/:id", get(|| async { panic!("secret detail") }))
+            .layer(middleware::from_fn(panic_recovery_middleware));
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/boom/1")
+                    .header("x-request-id", "req-123")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(
+            response.headers()["content-type"],
+            crate::error::PROBLEM_JSON
+        );
+        assert_eq!(response.headers()["x-request-id"], "req-123");
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["code"], "ERR_INTERNAL_001");
+        assert_eq!(body["request_id"], "req-123");
+        assert_eq!(body["instance"], "/boom/:id");
+        assert!(!body.to_string().contains("secret detail"));
     }
 }
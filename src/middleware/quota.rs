@@ -157,6 +157,34 @@ impl QuotaManager {
         }
     }
 
+    /// Like [`get_quota_config`](Self::get_quota_config), but `None` when no
+    /// config has been stored for `key` instead of the Free-tier default.
+    pub async fn get_quota_override(&self, key: &str) -> Result<Option<Quota>, redis::RedisError> {
+        let config_key = format!("quota:config:{key}");
+        let client = self.redis_client.clone();
+
+        let config_json: Option<String> = self
+            .cb
+            .call(|| async move {
+                let mut conn = client.get_multiplexed_async_connection().await?;
+                conn.get(&config_key).await
+            })
+            .await
+            .map_err(redis_cb_err)?;
+
+        config_json
+            .map(|json| {
+                serde_json::from_str(&json).map_err(|e| {
+                    redis::RedisError::from((
+                        redis::ErrorKind::TypeError,
+                        "deserialization failed",
+                        e.to_string(),
+                    ))
+                })
+            })
+            .transpose()
+    }
+
     pub async fn set_quota_config(
         &self,
         key: &str,
@@ -302,37 +330,80 @@ use axum::{
 
 use crate::AppState;
 
-const DEFAULT_RATE_LIMIT_PER_MINUTE: u32 = 100;
+pub const DEFAULT_RATE_LIMIT_PER_MINUTE: u32 = 100;
+
+/// Fixed window used by [`rate_limit_middleware`].
+pub const RATE_LIMIT_WINDOW_SECS: i64 = 60;
+
+/// Where the effective per-minute limit for a caller came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LimitSource {
+    /// `PUT /admin/quotas/:tenant_id` (or a `quota:config:<api-key>` entry) set a custom limit.
+    Override,
+    /// `tenants.rate_limit_per_minute` from the partner's settings.
+    TenantSettings,
+    /// No partner settings matched; the service-wide default applies.
+    Default,
+}
 
-/// Per-tenant rate limiting middleware.
-///
-/// - Identifies the tenant via `X-API-Key` or `X-Tenant-ID` header.
-/// - Uses `tenants.rate_limit_per_minute` when available; falls back to 100 req/min.
-/// - Unauthenticated requests share a single `anon` bucket capped at 100 req/min.
-/// - Returns `429 Too Many Requests` with `Retry-After`, `X-RateLimit-Limit`,
-///   `X-RateLimit-Remaining`, and `X-RateLimit-Reset` headers on exhaustion.
-pub async fn rate_limit_middleware(
-    State(state): State<AppState>,
-    req: Request<Body>,
-    next: Next<Body>,
-) -> Response {
-    // Derive a quota key: prefer API key, then tenant-id header, then "anon".
-    let quota_key = req
-        .headers()
+/// The rate-limit bucket and limit that apply to a request.
+#[derive(Debug, Clone)]
+pub struct RateLimitSubject {
+    /// Raw caller identity: API key, `tenant:<id>`, or `anon`.
+    pub quota_key: String,
+    /// Tenant matched from the in-memory tenant config cache, if any.
+    pub tenant_id: Option<uuid::Uuid>,
+    /// Redis key the usage counter is stored under.
+    pub bucket_key: String,
+    /// Effective requests per window.
+    pub limit: u32,
+    pub source: LimitSource,
+}
+
+impl RateLimitSubject {
+    /// `"api_key"`, `"tenant"` or `"anonymous"` — safe to return to callers.
+    pub fn kind(&self) -> &'static str {
+        if self.quota_key == "anon" {
+            "anonymous"
+        } else if self.quota_key.starts_with("tenant:") {
+            "tenant"
+        } else {
+            "api_key"
+        }
+    }
+}
+
+/// Derive the quota key: prefer API key, then tenant-id header, then "anon".
+fn quota_key_from_headers(headers: &axum::http::HeaderMap) -> String {
+    headers
         .get("x-api-key")
-        .or_else(|| req.headers().get("X-API-Key"))
         .and_then(|v| v.to_str().ok())
         .map(|k| k.to_string())
         .or_else(|| {
-            req.headers()
+            headers
                 .get("X-Tenant-ID")
                 .and_then(|v| v.to_str().ok())
                 .map(|id| format!("tenant:{id}"))
         })
-        .unwrap_or_else(|| "anon".to_string());
+        .unwrap_or_else(|| "anon".to_string())
+}
 
-    // Look up per-tenant limit from the in-memory tenant config cache.
-    let limit_per_minute: u32 = {
+/// Resolve the bucket and effective limit for a request.
+///
+/// Precedence: a per-key override (`custom_limit` stored via the admin quota
+/// API under `tenant:<id>`, or directly under the API key), then the tenant's
+/// `rate_limit_per_minute`, then [`DEFAULT_RATE_LIMIT_PER_MINUTE`]. Override
+/// lookup failures (e.g. Redis down) fall through to the next source.
+pub async fn resolve_rate_limit(
+    state: &AppState,
+    manager: &QuotaManager,
+    headers: &axum::http::HeaderMap,
+) -> RateLimitSubject {
+    let quota_key = quota_key_from_headers(headers);
+
+    // Look up the per-tenant limit from the in-memory tenant config cache.
+    let tenant = {
         let configs = state.tenant_configs.read().await;
         // Try to match by API key (stored as the quota_key itself) or tenant UUID.
         configs
@@ -342,10 +413,58 @@ pub async fn rate_limit_middleware(
                     || quota_key.starts_with("tenant:")
                         && quota_key.trim_start_matches("tenant:") == c.tenant_id.to_string()
             })
-            .map(|c| c.rate_limit_per_minute as u32)
-            .unwrap_or(DEFAULT_RATE_LIMIT_PER_MINUTE)
+            .map(|c| (c.tenant_id, c.rate_limit_per_minute as u32))
+    };
+
+    let override_key = match tenant {
+        Some((tenant_id, _)) => format!("tenant:{tenant_id}"),
+        None => quota_key.clone(),
+    };
+    let custom_limit = manager
+        .get_quota_override(&override_key)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|q| q.custom_limit);
+
+    let (limit, source) = match (custom_limit, tenant) {
+        (Some(limit), _) => (limit, LimitSource::Override),
+        (None, Some((_, limit))) => (limit, LimitSource::TenantSettings),
+        (None, None) => (DEFAULT_RATE_LIMIT_PER_MINUTE, LimitSource::Default),
     };
 
+    RateLimitSubject {
+        bucket_key: format!("tenant:{quota_key}"),
+        tenant_id: tenant.map(|(id, _)| id),
+        quota_key,
+        limit,
+        source,
+    }
+}
+
+/// Set `X-RateLimit-Limit`, `X-RateLimit-Remaining` and `X-RateLimit-Reset`.
+pub fn apply_rate_limit_headers(headers: &mut axum::http::HeaderMap, status: &QuotaStatus) {
+    headers.insert("X-RateLimit-Limit", HeaderValue::from(status.limit));
+    headers.insert("X-RateLimit-Remaining", HeaderValue::from(status.remaining));
+    headers.insert(
+        "X-RateLimit-Reset",
+        HeaderValue::from(status.reset_in_seconds),
+    );
+}
+
+/// Per-tenant rate limiting middleware.
+///
+/// - Identifies the tenant via `X-API-Key` or `X-Tenant-ID` header.
+/// - Resolves the limit with [`resolve_rate_limit`]: admin override, then
+///   `tenants.rate_limit_per_minute`, then 100 req/min.
+/// - Unauthenticated requests share a single `anon` bucket capped at 100 req/min.
+/// - Every response carries `X-RateLimit-Limit`, `X-RateLimit-Remaining` and
+///   `X-RateLimit-Reset`; `429 Too Many Requests` adds `Retry-After`.
+pub async fn rate_limit_middleware(
+    State(state): State<AppState>,
+    req: Request<Body>,
+    next: Next<Body>,
+) -> Response {
     // Build a QuotaManager backed by the app's Redis URL.
     let manager = match QuotaManager::new(&state.redis_url) {
         Ok(m) => m,
@@ -356,19 +475,13 @@ pub async fn rate_limit_middleware(
         }
     };
 
-    // Override the quota config with the per-tenant per-minute limit.
-    let per_minute_key = format!("tenant:{quota_key}");
-    let quota_cfg = Quota {
-        tier: Tier::Free,
-        custom_limit: Some(limit_per_minute),
-        reset_schedule: ResetSchedule::Hourly, // TTL managed manually below
-    };
-    // Best-effort: set config (ignore errors).
-    let _ = manager.set_quota_config(&per_minute_key, &quota_cfg).await;
+    let subject = resolve_rate_limit(&state, &manager, req.headers()).await;
+    let limit_per_minute = subject.limit;
+    let per_minute_key = subject.bucket_key;
 
     // Consume one unit.
     let allowed = manager
-        .consume_quota_with_window(&per_minute_key, limit_per_minute, 60)
+        .consume_quota_with_window(&per_minute_key, limit_per_minute, RATE_LIMIT_WINDOW_SECS)
         .await
         .unwrap_or(true); // fail open on Redis error
 
@@ -380,42 +493,68 @@ pub async fn rate_limit_middleware(
             limit: limit_per_minute,
             used: 0,
             remaining: limit_per_minute,
-            reset_in_seconds: 60,
+            reset_in_seconds: RATE_LIMIT_WINDOW_SECS as u64,
         });
 
     if !allowed {
-        let retry_after = status.reset_in_seconds.max(1).to_string();
+        let retry_after = status.reset_in_seconds.max(1);
         let mut response = (StatusCode::TOO_MANY_REQUESTS, "Too Many Requests").into_response();
         let headers = response.headers_mut();
-        headers.insert(
-            "X-RateLimit-Limit",
-            HeaderValue::from_str(&status.limit.to_string()).unwrap(),
-        );
-        headers.insert(
-            "X-RateLimit-Remaining",
-            HeaderValue::from_str(&status.remaining.to_string()).unwrap(),
-        );
-        headers.insert(
-            "X-RateLimit-Reset",
-            HeaderValue::from_str(&status.reset_in_seconds.to_string()).unwrap(),
-        );
-        headers.insert("Retry-After", HeaderValue::from_str(&retry_after).unwrap());
+        apply_rate_limit_headers(headers, &status);
+        headers.insert("Retry-After", HeaderValue::from(retry_after));
         return response;
     }
 
     let mut response = next.run(req).await;
-    let headers = response.headers_mut();
-    headers.insert(
-        "X-RateLimit-Limit",
-        HeaderValue::from_str(&status.limit.to_string()).unwrap(),
-    );
-    headers.insert(
-        "X-RateLimit-Remaining",
-        HeaderValue::from_str(&status.remaining.to_string()).unwrap(),
-    );
-    headers.insert(
-        "X-RateLimit-Reset",
-        HeaderValue::from_str(&status.reset_in_seconds.to_string()).unwrap(),
-    );
+    apply_rate_limit_headers(response.headers_mut(), &status);
     response
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subject(quota_key: &str) -> RateLimitSubject {
+        RateLimitSubject {
+            quota_key: quota_key.to_string(),
+            tenant_id: None,
+            bucket_key: format!("tenant:{quota_key}"),
+            limit: DEFAULT_RATE_LIMIT_PER_MINUTE,
+            source: LimitSource::Default,
+        }
+    }
+
+    #[test]
+    fn test_subject_kind_never_exposes_key() {
+        assert_eq!(subject("anon").kind(), "anonymous");
+        assert_eq!(subject("tenant:abc").kind(), "tenant");
+        assert_eq!(subject("sk_live_123").kind(), "api_key");
+    }
+
+    #[test]
+    fn test_quota_key_precedence() {
+        let mut headers = axum::http::HeaderMap::new();
+        assert_eq!(quota_key_from_headers(&headers), "anon");
+        headers.insert("X-Tenant-ID", HeaderValue::from_static("t1"));
+        assert_eq!(quota_key_from_headers(&headers), "tenant:t1");
+        headers.insert("x-api-key", HeaderValue::from_static("k1"));
+        assert_eq!(quota_key_from_headers(&headers), "k1");
+    }
+
+    #[test]
+    fn test_apply_rate_limit_headers() {
+        let mut headers = axum::http::HeaderMap::new();
+        apply_rate_limit_headers(
+            &mut headers,
+            &QuotaStatus {
+                limit: 100,
+                used: 1,
+                remaining: 99,
+                reset_in_seconds: 42,
+            },
+        );
+        assert_eq!(headers["X-RateLimit-Limit"], "100");
+        assert_eq!(headers["X-RateLimit-Remaining"], "99");
+        assert_eq!(headers["X-RateLimit-Reset"], "42");
+    }
+}
@@ -0,0 +1,220 @@
+//! PII redaction of REST responses by API key scope.
+//!
+//! JSON responses of the partner-facing routes have every configured field,
+//! at any depth, masked with [`sanitize::mask_pii`] unless the caller's API
+//! key (`X-API-Key` or `Authorization: Bearer`) carries the `read:pii`
+//! scope (`tenants.scopes`). This is the REST side of the GraphQL field
+//! masking in `graphql::pii`: the `/graphql` handler looks up the same key
+//! with [`caller_scopes`] and runs the request as `compliance` when it has
+//! `read:pii`. REST masking keeps accounts' first and last four characters
+//! and metadata's keys.
+//!
+//! | Env var               | Default | Meaning                             |
+//! |-----------------------|---------|-------------------------------------|
+//! | `PII_REDACTED_FIELDS` | none    | Comma-separated JSON fields to mask |
+//!
+//! Redaction is off until fields are configured, since partners read their
+//! own metadata back today; [`GRAPHQL_MASKED_FIELDS`] is the list matching
+//! the GraphQL policy. Once on, callers without a key, or with an unknown
+//! one, get masked responses. Non-JSON responses (CSV exports, downloads)
+//! are passed through.
+
+use crate::db::queries;
+use crate::utils::sanitize;
+use crate::AppState;
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, HeaderMap, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde_json::Value;
+use std::sync::OnceLock;
+
+/// Scope that lets a caller see PII fields unmasked.
+pub const READ_PII_SCOPE: &str = "read:pii";
+
+/// The fields GraphQL masks, as REST field names.
+pub const GRAPHQL_MASKED_FIELDS: &str = "stellar_account,muxed_account,metadata";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RedactionConfig {
+    pub fields: Vec<String>,
+}
+
+impl RedactionConfig {
+    /// Field list from `PII_REDACTED_FIELDS`; empty (no redaction) when unset.
+    pub fn from_env() -> Self {
+        std::env::var("PII_REDACTED_FIELDS")
+            .map(|v| Self::parse(&v))
+            .unwrap_or_default()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.fields.is_empty()
+    }
+
+    fn parse(fields: &str) -> Self {
+        Self {
+            fields: fields
+                .split(',')
+                .map(|f| f.trim().to_ascii_lowercase())
+                .filter(|f| !f.is_empty())
+                .collect(),
+        }
+    }
+
+    fn is_redacted(&self, key: &str) -> bool {
+        self.fields.iter().any(|f| f.eq_ignore_ascii_case(key))
+    }
+
+    /// Mask every configured field of `value`, at any depth.
+    pub fn redact(&self, value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, val) in map.iter_mut() {
+                    if self.is_redacted(key) {
+                        *val = sanitize::mask_pii(val);
+                    } else {
+                        self.redact(val);
+                    }
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|v| self.redact(v)),
+            _ => {}
+        }
+    }
+}
+
+fn config() -> &'static RedactionConfig {
+    static CONFIG: OnceLock<RedactionConfig> = OnceLock::new();
+    CONFIG.get_or_init(RedactionConfig::from_env)
+}
+
+/// Whether `scopes` allow seeing PII.
+pub fn has_pii_scope(scopes: &[String]) -> bool {
+    scopes.iter().any(|s| s == READ_PII_SCOPE)
+}
+
+fn api_key(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get("X-API-Key")
+        .and_then(|v| v.to_str().ok())
+        .or_else(|| {
+            headers
+                .get(header::AUTHORIZATION)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.strip_prefix("Bearer "))
+        })
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
+}
+
+/// Scopes of the caller's API key; none without a key, with an unknown one,
+/// or if the lookup fails.
+pub async fn caller_scopes(db: &sqlx::PgPool, headers: &HeaderMap) -> Vec<String> {
+    let Some(key) = api_key(headers) else {
+        return Vec::new();
+    };
+    match queries::get_api_key_scopes(db, key).await {
+        Ok(scopes) => scopes.unwrap_or_default(),
+        Err(e) => {
+            tracing::warn!(error = %e, "API key scope lookup failed; masking PII");
+            Vec::new()
+        }
+    }
+}
+
+/// Middleware masking PII fields in JSON responses unless the caller has the
+/// `read:pii` scope.
+pub async fn redact_pii(
+    State(state): State<AppState>,
+    req: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    if !config().is_enabled() {
+        return next.run(req).await;
+    }
+    let may_see_pii = has_pii_scope(&caller_scopes(&state.db, req.headers()).await);
+
+    let response = next.run(req).await;
+    if may_see_pii {
+        return response;
+    }
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    if !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match hyper::body::to_bytes(body).await {
+        Ok(b) => b,
+        Err(_) => return parts.status.into_response(),
+    };
+    let Ok(mut value) = serde_json::from_slice::<Value>(&bytes) else {
+        return Response::from_parts(parts, axum::body::boxed(axum::body::Full::from(bytes)));
+    };
+    config().redact(&mut value);
+    let body = serde_json::to_vec(&value).unwrap_or_else(|_| bytes.to_vec());
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, axum::body::boxed(axum::body::Full::from(body)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use serde_json::json;
+
+    #[test]
+    fn test_redact_masks_configured_fields_at_any_depth() {
+        let mut body = json!({
+            "transactions": [{
+                "id": "5b8e",
+                "stellar_account": "GAAZI4TCR3TY5OJHCTJC2A4QSY6CJWJH5IAJTGKIN2ER7LBNVKOCCWN7",
+                "muxed_account": null,
+                "metadata": {"email": "a@b.example"},
+                "amount": "10"
+            }]
+        });
+        RedactionConfig::parse(GRAPHQL_MASKED_FIELDS).redact(&mut body);
+        let tx = &body["transactions"][0];
+        assert_eq!(tx["stellar_account"], "GAAZ****CWN7");
+        assert_eq!(tx["muxed_account"], Value::Null);
+        assert_eq!(tx["metadata"], json!({"email": "****"}));
+        assert_eq!(tx["amount"], "10");
+        assert_eq!(tx["id"], "5b8e");
+    }
+
+    #[test]
+    fn test_field_list_parses() {
+        let config = RedactionConfig::parse(" Memo , stellar_account,,");
+        assert_eq!(config.fields, vec!["memo", "stellar_account"]);
+        assert!(config.is_redacted("MEMO"));
+        assert!(!config.is_redacted("metadata"));
+        assert!(!RedactionConfig::parse(" , ").is_enabled());
+        assert!(!RedactionConfig::default().is_enabled());
+    }
+
+    #[test]
+    fn test_only_read_pii_scope_unmasks() {
+        assert!(has_pii_scope(&["read:pii".to_string()]));
+        assert!(!has_pii_scope(&["read:transactions".to_string()]));
+        assert!(!has_pii_scope(&[]));
+    }
+
+    #[test]
+    fn test_api_key_from_header_or_bearer() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(api_key(&headers), None);
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Bearer k2"));
+        assert_eq!(api_key(&headers), Some("k2"));
+        headers.insert("X-API-Key", HeaderValue::from_static("k1"));
+        assert_eq!(api_key(&headers), Some("k1"));
+    }
+}
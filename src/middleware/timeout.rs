@@ -0,0 +1,331 @@
+//! Per-route latency budgets.
+//!
+//! Every API route gets a server-side timeout so a slow dependency (Horizon,
+//! the database) cannot hold a request open indefinitely. The budget comes from
+//! `ROUTE_TIMEOUT_<ROUTE>_MS`, where `<ROUTE>` is the route path upper-cased
+//! with path parameters kept by name (e.g. `ROUTE_TIMEOUT_CALLBACK_MS`,
+//! `ROUTE_TIMEOUT_TRANSACTIONS_ID_MS`), falling back to
+//! `ROUTE_TIMEOUT_DEFAULT_MS` (30 000). `/api/v1` and `/api/v2` routes share
+//! the budget of their unversioned path. `0` disables the timeout.
+//!
+//! A request over budget is answered `504` as `application/problem+json`
+//! (`ERR_TIMEOUT_001`). Normally the handler is dropped at that point, rolling
+//! back any open transaction. Detached routes — the callback routes in async
+//! ack mode (see [`crate::handlers::ack`]) — keep running in the background
+//! instead, so an accepted callback is still persisted and the caller can poll
+//! or safely retry.
+
+use crate::error::{codes, problem_response};
+use crate::metrics;
+use axum::{
+    body::Body,
+    extract::{MatchedPath, State},
+    http::{Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use opentelemetry::KeyValue;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::Instrument;
+
+pub const DEFAULT_BUDGET: Duration = Duration::from_secs(30);
+
+const ENV_PREFIX: &str = "ROUTE_TIMEOUT_";
+const ENV_SUFFIX: &str = "_MS";
+
+/// Latency budgets by route, read once at startup.
+#[derive(Debug, Clone)]
+pub struct RouteTimeouts {
+    default: Option<Duration>,
+    /// Keyed by the `<ROUTE>` part of the variable; `None` disables the timeout.
+    routes: HashMap<String, Option<Duration>>,
+    detached: HashSet<String>,
+}
+
+impl Default for RouteTimeouts {
+    fn default() -> Self {
+        Self {
+            default: Some(DEFAULT_BUDGET),
+            routes: HashMap::new(),
+            detached: HashSet::new(),
+        }
+    }
+}
+
+impl RouteTimeouts {
+    pub fn from_env() -> Self {
+        Self::from_vars(std::env::vars())
+    }
+
+    fn from_vars(vars: impl IntoIterator<Item = (String, String)>) -> Self {
+        let mut timeouts = Self::default();
+        for (key, raw) in vars {
+            let Some(route) = key
+                .strip_prefix(ENV_PREFIX)
+                .and_then(|k| k.strip_suffix(ENV_SUFFIX))
+            else {
+                continue;
+            };
+            let Ok(ms) = raw.trim().parse::<u64>() else {
+                tracing::warn!(
+                    key,
+                    value = %raw,
+                    "Ignoring invalid route timeout (expected milliseconds)"
+                );
+                continue;
+            };
+            let budget = (ms > 0).then(|| Duration::from_millis(ms));
+            if route == "DEFAULT" {
+                timeouts.default = budget;
+            } else {
+                timeouts.routes.insert(route.to_string(), budget);
+            }
+        }
+        timeouts
+    }
+
+    /// Let `route` run to completion in the background after its budget
+    /// expires, instead of being cancelled.
+    pub fn detach(mut self, route: &str) -> Self {
+        self.detached.insert(route_key(route));
+        self
+    }
+
+    /// The budget for `route`, or `None` when it has no timeout.
+    pub fn budget(&self, route: &str) -> Option<Duration> {
+        self.routes
+            .get(&route_key(route))
+            .copied()
+            .unwrap_or(self.default)
+    }
+
+    pub fn is_detached(&self, route: &str) -> bool {
+        self.detached.contains(&route_key(route))
+    }
+}
+
+/// `<ROUTE>` for a matched path such as `/api/v1/transactions/:id`.
+pub(crate) fn route_key(route: &str) -> String {
+    let route = ["/api/v1", "/api/v2"]
+        .iter()
+        .find_map(|prefix| route.strip_prefix(prefix))
+        .unwrap_or(route);
+    route
+        .trim_matches('/')
+        .split('/')
+        .map(|segment| {
+            segment
+                .trim_start_matches(':')
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() {
+                        c.to_ascii_uppercase()
+                    } else {
+                        '_'
+                    }
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+pub async fn latency_budget(
+    State(timeouts): State<Arc<RouteTimeouts>>,
+    req: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    let Some(route) = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+    else {
+        return next.run(req).await;
+    };
+    let Some(budget) = timeouts.budget(&route) else {
+        return next.run(req).await;
+    };
+
+    if !timeouts.is_detached(&route) {
+        return match tokio::time::timeout(budget, next.run(req)).await {
+            Ok(response) => response,
+            Err(_) => timed_out(&route, budget, false),
+        };
+    }
+
+    let started = Instant::now();
+    let task_route = route.clone();
+    let mut handle = tokio::spawn(
+        async move {
+            let response = next.run(req).await;
+            if started.elapsed() > budget {
+                tracing::info!(
+                    route = %task_route,
+                    status = response.status().as_u16(),
+                    elapsed_ms = started.elapsed().as_millis() as u64,
+                    "Detached request finished after its latency budget"
+                );
+            }
+            response
+        }
+        .in_current_span(),
+    );
+
+    match tokio::time::timeout(budget, &mut handle).await {
+        Ok(Ok(response)) => response,
+        // Re-raise so the panic recovery layer answers as for any handler.
+        Ok(Err(e)) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+        Ok(Err(_)) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+        Err(_) => timed_out(&route, budget, true),
+    }
+}
+
+/// `504` problem document for a request over its budget.
+fn timed_out(route: &str, budget: Duration, detached: bool) -> Response {
+    metrics::http_request_timeouts_total().add(
+        1,
+        &[
+            KeyValue::new("route", route.to_string()),
+            KeyValue::new("detached", detached),
+        ],
+    );
+    tracing::warn!(
+        route,
+        budget_ms = budget.as_millis() as u64,
+        detached,
+        "Request exceeded its latency budget"
+    );
+
+    let detail = if detached {
+        format!(
+            "The request exceeded its {} ms budget and is still being processed; \
+             retrying it is safe",
+            budget.as_millis()
+        )
+    } else {
+        format!(
+            "The request exceeded its {} ms budget and was cancelled",
+            budget.as_millis()
+        )
+    };
+    problem_response(codes::TIMEOUT_001, detail, route)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::PROBLEM_JSON;
+    use axum::{http::header, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_route_key() {
+        assert_eq!(route_key("/callback"), "CALLBACK");
+        assert_eq!(route_key("/callback/transaction"), "CALLBACK_TRANSACTION");
+        assert_eq!(route_key("/transactions/:id"), "TRANSACTIONS_ID");
+        assert_eq!(route_key("/api/v1/transactions/:id"), "TRANSACTIONS_ID");
+        assert_eq!(route_key("/admin/signing-keys"), "ADMIN_SIGNING_KEYS");
+    }
+
+    #[test]
+    fn test_budgets_from_vars() {
+        let timeouts = RouteTimeouts::from_vars(vars(&[
+            ("ROUTE_TIMEOUT_DEFAULT_MS", "5000"),
+            ("ROUTE_TIMEOUT_CALLBACK_MS", "2000"),
+            ("ROUTE_TIMEOUT_TRANSACTIONS_SEARCH_MS", "0"),
+            ("ROUTE_TIMEOUT_SETTLEMENTS_MS", "soon"),
+            ("ACK_MODE_DEFAULT", "async"),
+        ]));
+        assert_eq!(
+            timeouts.budget("/api/v2/callback"),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(timeouts.budget("/transactions/search"), None);
+        assert_eq!(
+            timeouts.budget("/settlements"),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(
+            RouteTimeouts::from_vars(vec![]).budget("/settlements"),
+            Some(DEFAULT_BUDGET)
+        );
+    }
+
+    fn app(timeouts: RouteTimeouts) -> Router {
+        Router::new()
+            .route(
+                "/slow",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_millis(200)).await;
+                    "done"
+                }),
+            )
+            .route("/fast", get(|| async { "done" }))
+            .layer(axum::middleware::from_fn_with_state(
+                Arc::new(timeouts),
+                latency_budget,
+            ))
+    }
+
+    fn request(path: &str) -> Request<Body> {
+        Request::builder().uri(path).body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_over_budget_returns_problem_json() {
+        let timeouts = RouteTimeouts::from_vars(vars(&[("ROUTE_TIMEOUT_SLOW_MS", "20")]));
+        let response = app(timeouts).oneshot(request("/slow")).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], PROBLEM_JSON);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["status"], 504);
+        assert_eq!(body["instance"], "/slow");
+        assert_eq!(body["code"], "ERR_TIMEOUT_001");
+    }
+
+    #[tokio::test]
+    async fn test_within_budget_passes_through() {
+        let timeouts = RouteTimeouts::from_vars(vars(&[("ROUTE_TIMEOUT_DEFAULT_MS", "20")]));
+        let response = app(timeouts).oneshot(request("/fast")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_detached_route_keeps_running() {
+        let finished = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let flag = finished.clone();
+        let timeouts =
+            RouteTimeouts::from_vars(vars(&[("ROUTE_TIMEOUT_DEFAULT_MS", "20")])).detach("/work");
+        let app = Router::new()
+            .route(
+                "/work",
+                get(move || async move {
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    flag.store(true, std::sync::atomic::Ordering::SeqCst);
+                    "done"
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                Arc::new(timeouts),
+                latency_budget,
+            ));
+
+        let response = app.oneshot(request("/work")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+        assert!(!finished.load(std::sync::atomic::Ordering::SeqCst));
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(finished.load(std::sync::atomic::Ordering::SeqCst));
+    }
+}
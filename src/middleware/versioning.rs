@@ -1,10 +1,96 @@
+//! API version resolution.
+//!
+//! The version comes from the URL prefix (`/api/v1`, `/api/v2`) or, on
+//! unversioned routes, from a vendor media type in `Accept`:
+//!
+//! ```text
+//! Accept: application/vnd.synapse.v1+json
+//! ```
+//!
+//! A prefix always wins over `Accept`. Unversioned requests without a vendor
+//! type get the latest version. The resolved [`ApiVersion`] is stored as a
+//! request extension; handlers take it as an extractor.
+
+use crate::error::AppError;
 use axum::{
-    http::{HeaderName, HeaderValue, Request},
+    async_trait,
+    extract::FromRequestParts,
+    http::{header, request::Parts, HeaderName, HeaderValue, Request},
     middleware::Next,
-    response::Response as AxumResponse,
+    response::{IntoResponse, Response as AxumResponse},
 };
 use std::str::FromStr;
 
+/// Media type prefix of versioned `Accept` values.
+const VENDOR_MEDIA_TYPE_PREFIX: &str = "application/vnd.synapse.";
+
+/// API version a request was routed through. The version middlewares insert
+/// it as a request extension so handlers can gate version-specific behaviour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiVersion {
+    V1,
+    V2,
+}
+
+impl ApiVersion {
+    /// Version served when the request does not ask for one.
+    pub const LATEST: ApiVersion = ApiVersion::V2;
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiVersion::V1 => "v1",
+            ApiVersion::V2 => "v2",
+        }
+    }
+
+    /// Version requested by an `Accept` header, if it names a Synapse vendor
+    /// type. Ranges are tried in order; `q=0` ranges are skipped.
+    pub fn from_accept(accept: &str) -> Option<Result<ApiVersion, String>> {
+        accept.split(',').find_map(|range| {
+            let mut parts = range.split(';').map(str::trim);
+            let media_type = parts.next()?.to_ascii_lowercase();
+            let rejected = parts.any(|p| {
+                p.strip_prefix("q=")
+                    .and_then(|q| q.parse::<f32>().ok())
+                    .is_some_and(|q| q == 0.0)
+            });
+            if rejected {
+                return None;
+            }
+            let rest = media_type.strip_prefix(VENDOR_MEDIA_TYPE_PREFIX)?;
+            let version = rest.strip_suffix("+json").unwrap_or(rest);
+            Some(ApiVersion::from_str(version))
+        })
+    }
+}
+
+impl FromStr for ApiVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "v1" => Ok(ApiVersion::V1),
+            "v2" => Ok(ApiVersion::V2),
+            _ => Err(format!("Unsupported API version: {s}")),
+        }
+    }
+}
+
+/// The version resolved by the versioning middleware; [`ApiVersion::LATEST`]
+/// on routes it does not cover.
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ApiVersion {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .extensions
+            .get::<ApiVersion>()
+            .copied()
+            .unwrap_or(ApiVersion::LATEST))
+    }
+}
+
 pub async fn inject_deprecation_headers<B>(req: Request<B>, next: Next<B>) -> AxumResponse {
     let mut response = next.run(req).await;
 
@@ -39,21 +125,90 @@ pub async fn inject_api_version_header<B>(
     response
 }
 
+/// Run `req` as `version`: inject the extension and set `API-Version`, plus
+/// deprecation headers for v1.
+async fn serve_as<B>(version: ApiVersion, mut req: Request<B>, next: Next<B>) -> AxumResponse {
+    req.extensions_mut().insert(version);
+    let mut response = inject_api_version_header(version.as_str(), req, next).await;
+    if version == ApiVersion::V1 {
+        response.headers_mut().insert(
+            HeaderName::from_str("Deprecation").unwrap(),
+            HeaderValue::from_static("true"),
+        );
+        response.headers_mut().insert(
+            HeaderName::from_str("Sunset").unwrap(),
+            HeaderValue::from_static("Fri, 31 Dec 2026 23:59:59 GMT"),
+        );
+    }
+    response
+}
+
 /// Middleware factory for V1 routes — adds `API-Version: v1` and deprecation headers.
 pub async fn v1_version_middleware<B>(req: Request<B>, next: Next<B>) -> AxumResponse {
-    let mut response = inject_api_version_header("v1", req, next).await;
-    response.headers_mut().insert(
-        HeaderName::from_str("Deprecation").unwrap(),
-        HeaderValue::from_static("true"),
-    );
-    response.headers_mut().insert(
-        HeaderName::from_str("Sunset").unwrap(),
-        HeaderValue::from_static("Fri, 31 Dec 2026 23:59:59 GMT"),
-    );
-    response
+    serve_as(ApiVersion::V1, req, next).await
 }
 
 /// Middleware factory for V2 routes — adds `API-Version: v2`.
 pub async fn v2_version_middleware<B>(req: Request<B>, next: Next<B>) -> AxumResponse {
-    inject_api_version_header("v2", req, next).await
+    serve_as(ApiVersion::V2, req, next).await
+}
+
+/// Middleware for unversioned routes — resolves the version from `Accept`,
+/// defaulting to [`ApiVersion::LATEST`]. An unknown vendor version is a `400`.
+pub async fn negotiate_version_middleware<B>(req: Request<B>, next: Next<B>) -> AxumResponse {
+    let requested = req
+        .headers()
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .and_then(ApiVersion::from_accept);
+    let version = match requested {
+        None => ApiVersion::LATEST,
+        Some(Ok(version)) => version,
+        Some(Err(e)) => return AppError::BadRequest(e).into_response(),
+    };
+
+    let mut response = serve_as(version, req, next).await;
+    response
+        .headers_mut()
+        .append(header::VARY, HeaderValue::from_static("accept"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_accept_reads_vendor_type() {
+        assert_eq!(
+            ApiVersion::from_accept("application/vnd.synapse.v1+json"),
+            Some(Ok(ApiVersion::V1))
+        );
+        assert_eq!(
+            ApiVersion::from_accept("text/html, application/vnd.synapse.v2+json;q=0.9"),
+            Some(Ok(ApiVersion::V2))
+        );
+        assert_eq!(
+            ApiVersion::from_accept("Application/VND.Synapse.V1+JSON"),
+            Some(Ok(ApiVersion::V1))
+        );
+    }
+
+    #[test]
+    fn test_from_accept_without_vendor_type() {
+        assert_eq!(ApiVersion::from_accept("application/json"), None);
+        assert_eq!(ApiVersion::from_accept("*/*"), None);
+        assert_eq!(
+            ApiVersion::from_accept("application/vnd.synapse.v1+json;q=0, application/json"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_from_accept_unknown_version() {
+        assert!(matches!(
+            ApiVersion::from_accept("application/vnd.synapse.v9+json"),
+            Some(Err(_))
+        ));
+    }
 }
@@ -0,0 +1,14 @@
+//! Port (trait) for reading the current time.
+//! Implementations can be the system clock (production) or a manually
+//! advanced clock (tests), so expiry, retry backoff and SLA logic can be
+//! exercised without sleeping.
+//!
+//! Code that takes a clock should read it once per operation and pass the
+//! instant down, rather than calling `Utc::now()` or SQL `NOW()` itself.
+
+use chrono::{DateTime, Utc};
+
+/// Source of the current time.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
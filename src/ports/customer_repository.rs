@@ -0,0 +1,109 @@
+//! Port (trait) for SEP-12 customer (KYC) records.
+//! Implementations can be Postgres, in-memory (for `--dev` and tests), etc.
+//!
+//! A customer is keyed by its Stellar account, a memo when several customers
+//! share that account, and a SEP-12 customer type.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::ports::RepositoryResult;
+
+/// Customer type used when a request names none.
+pub const DEFAULT_CUSTOMER_TYPE: &str = "default";
+
+/// SEP-12 customer status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KycStatus {
+    /// Required fields are missing.
+    NeedsInfo,
+    /// Everything required was provided; awaiting a compliance decision.
+    Processing,
+    Accepted,
+    Rejected,
+}
+
+impl KycStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KycStatus::NeedsInfo => "NEEDS_INFO",
+            KycStatus::Processing => "PROCESSING",
+            KycStatus::Accepted => "ACCEPTED",
+            KycStatus::Rejected => "REJECTED",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_uppercase().as_str() {
+            "NEEDS_INFO" => Some(KycStatus::NeedsInfo),
+            "PROCESSING" => Some(KycStatus::Processing),
+            "ACCEPTED" => Some(KycStatus::Accepted),
+            "REJECTED" => Some(KycStatus::Rejected),
+            _ => None,
+        }
+    }
+}
+
+/// What identifies a customer record.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CustomerKey {
+    /// Base `G...` account.
+    pub account: String,
+    pub memo: Option<String>,
+    pub customer_type: String,
+}
+
+impl CustomerKey {
+    /// Key of the default-type customer of `account`.
+    pub fn new(account: impl Into<String>) -> Self {
+        Self {
+            account: account.into(),
+            memo: None,
+            customer_type: DEFAULT_CUSTOMER_TYPE.to_string(),
+        }
+    }
+
+    pub fn with_memo(mut self, memo: Option<String>) -> Self {
+        self.memo = memo;
+        self
+    }
+
+    pub fn with_type(mut self, customer_type: impl Into<String>) -> Self {
+        self.customer_type = customer_type.into();
+        self
+    }
+}
+
+/// A customer's KYC record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Customer {
+    pub id: Uuid,
+    pub key: CustomerKey,
+    /// SEP-9 fields provided so far, by name.
+    pub fields: Map<String, Value>,
+    pub status: KycStatus,
+    /// Reason shown to the customer, e.g. why it was rejected.
+    pub message: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Port for persisting customer records.
+#[async_trait]
+pub trait CustomerRepository: Send + Sync {
+    async fn get(&self, key: &CustomerKey) -> RepositoryResult<Option<Customer>>;
+
+    async fn get_by_id(&self, id: Uuid) -> RepositoryResult<Option<Customer>>;
+
+    /// Every record of `account`, whatever its memo and type.
+    async fn list_by_account(&self, account: &str) -> RepositoryResult<Vec<Customer>>;
+
+    /// Insert `customer`, or replace the record with its id.
+    async fn save(&self, customer: &Customer) -> RepositoryResult<Customer>;
+
+    /// Delete the records of `account` and `memo` (all types); returns how
+    /// many were deleted.
+    async fn delete(&self, account: &str, memo: Option<&str>) -> RepositoryResult<u64>;
+}
@@ -0,0 +1,40 @@
+//! Port (trait) for publishing in-process events to any number of
+//! subscribers.
+//! The default implementation is a tokio broadcast channel per event class
+//! (`adapters::BroadcastEventBus`); services only see this trait, so they can
+//! publish without knowing who listens or how events are carried.
+
+use futures::stream::BoxStream;
+
+/// Predicate a subscriber uses to pick the events it wants.
+pub type EventFilter<E> = Box<dyn Fn(&E) -> bool + Send + Sync>;
+
+/// What a subscription yields next.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Delivery<E> {
+    Event(E),
+    /// The subscriber fell behind and this many events were dropped for it.
+    Lagged(u64),
+}
+
+/// Subscription stream; it ends when the bus is dropped.
+pub type EventStream<E> = BoxStream<'static, Delivery<E>>;
+
+/// Fan-out of events of type `E`.
+pub trait EventBus<E>: Send + Sync {
+    /// Deliver `event` to current subscribers and return how many there were.
+    /// Publishing with no subscribers is not an error.
+    fn publish(&self, event: E) -> usize;
+
+    /// Receive every event published from now on that passes `filter`.
+    fn subscribe(&self, filter: EventFilter<E>) -> EventStream<E>;
+
+    /// Number of live subscriptions, so publishers can skip building events
+    /// nobody would receive.
+    fn subscriber_count(&self) -> usize;
+}
+
+/// Filter that accepts every event.
+pub fn all<E>() -> EventFilter<E> {
+    Box::new(|_| true)
+}
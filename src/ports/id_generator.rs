@@ -0,0 +1,10 @@
+//! Port (trait) for minting entity ids.
+//! Implementations can be random v4 UUIDs (production) or a predictable
+//! sequence (tests), so the ids a use case assigns can be asserted on.
+
+use uuid::Uuid;
+
+/// Source of new entity ids.
+pub trait IdGenerator: Send + Sync {
+    fn new_id(&self) -> Uuid;
+}
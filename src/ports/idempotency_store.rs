@@ -0,0 +1,62 @@
+//! Port (trait) for remembering the responses to idempotent requests.
+//! Implementations can be Redis (the production path lives in
+//! `middleware::idempotency`), in-memory (for `--dev` and tests), etc.
+//!
+//! A request carrying an idempotency key first [`claims`](IdempotencyStore::claim)
+//! it; the first claimant runs the request and then either
+//! [`completes`](IdempotencyStore::complete) the key with its response or
+//! [`releases`](IdempotencyStore::release) it so a retry can run again.
+
+use async_trait::async_trait;
+
+/// How long a claim without a response blocks retries, matching the Redis lock.
+pub const CLAIM_TTL_SECS: u64 = 300;
+/// How long a completed response is replayed, matching the Redis cache.
+pub const RESPONSE_TTL_SECS: u64 = 86_400;
+
+/// Result type for idempotency store operations.
+pub type IdempotencyResult<T> = Result<T, IdempotencyStoreError>;
+
+/// Idempotency store errors.
+#[derive(Debug, thiserror::Error)]
+pub enum IdempotencyStoreError {
+    #[error("Idempotency store unavailable: {0}")]
+    Unavailable(String),
+}
+
+/// Response replayed to retries of a completed request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredResponse {
+    pub status: u16,
+    pub body: String,
+    pub content_type: Option<String>,
+}
+
+/// Outcome of claiming an idempotency key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdempotencyClaim {
+    /// First time the key is seen: run the request.
+    New,
+    /// Another request with the key is still running.
+    InFlight,
+    /// The request already ran; replay its response.
+    Completed(StoredResponse),
+}
+
+/// Port for idempotency keys, scoped per tenant (or any other caller scope).
+#[async_trait]
+pub trait IdempotencyStore: Send + Sync {
+    /// Claim `key` within `scope`.
+    async fn claim(&self, scope: &str, key: &str) -> IdempotencyResult<IdempotencyClaim>;
+
+    /// Record the response to the request that claimed `key`.
+    async fn complete(
+        &self,
+        scope: &str,
+        key: &str,
+        response: StoredResponse,
+    ) -> IdempotencyResult<()>;
+
+    /// Drop the claim on `key` without a response, e.g. after a failure.
+    async fn release(&self, scope: &str, key: &str) -> IdempotencyResult<()>;
+}
@@ -1,6 +1,28 @@
 //! Ports: trait definitions (interfaces) for external dependencies.
 //! The application defines these; adapters implement them.
 
+pub mod clock;
+pub mod customer_repository;
+pub mod event_bus;
+pub mod id_generator;
+pub mod idempotency_store;
+pub mod object_store;
+pub mod risk_scorer;
 pub mod transaction_repository;
+pub mod transaction_signer;
 
+pub use clock::Clock;
+pub use customer_repository::{
+    Customer, CustomerKey, CustomerRepository, KycStatus, DEFAULT_CUSTOMER_TYPE,
+};
+pub use event_bus::{Delivery, EventBus, EventFilter, EventStream};
+pub use id_generator::IdGenerator;
+pub use idempotency_store::{
+    IdempotencyClaim, IdempotencyResult, IdempotencyStore, IdempotencyStoreError, StoredResponse,
+};
+pub use object_store::{ByteStream, ObjectMeta, ObjectStore, ObjectStoreError, ObjectStoreResult};
+pub use risk_scorer::{
+    CustomerProfile, RiskAssessment, RiskResult, RiskScorer, RiskScorerError, MAX_RISK_SCORE,
+};
 pub use transaction_repository::{RepositoryError, RepositoryResult, TransactionRepository};
+pub use transaction_signer::{SignerError, SignerResult, TransactionSigner};
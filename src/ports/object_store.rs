@@ -0,0 +1,172 @@
+//! Port (trait) for file persistence: database backups, export files and
+//! anything else written once and read back whole.
+//! Implementations can be the local filesystem, S3, in-memory (for tests), etc.
+//!
+//! Keys are `/`-separated relative paths (`exports/<id>.csv`); see
+//! [`validate_key`]. Bodies are streamed in both directions so large files
+//! never have to be held in memory by callers.
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt, TryStreamExt};
+use std::path::Path;
+use std::pin::Pin;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Result type for object store operations.
+pub type ObjectStoreResult<T> = Result<T, ObjectStoreError>;
+
+/// Object body, read or written chunk by chunk.
+pub type ByteStream = Pin<Box<dyn Stream<Item = std::io::Result<Bytes>> + Send>>;
+
+/// Object store errors.
+#[derive(Debug, thiserror::Error)]
+pub enum ObjectStoreError {
+    #[error("Object not found: {0}")]
+    NotFound(String),
+
+    #[error("Invalid object key: {0}")]
+    InvalidKey(String),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Storage backend error: {0}")]
+    Backend(String),
+}
+
+/// A stored object, as returned by [`ObjectStore::list`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectMeta {
+    pub key: String,
+    pub size: u64,
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+/// Port for storing and retrieving files by key.
+#[async_trait]
+pub trait ObjectStore: Send + Sync {
+    /// Store `body` under `key`, replacing any existing object. The object
+    /// only becomes visible once the whole body has been written. Returns
+    /// the number of bytes stored.
+    async fn put(&self, key: &str, body: ByteStream) -> ObjectStoreResult<u64>;
+
+    /// Stream the object stored under `key`.
+    async fn get(&self, key: &str) -> ObjectStoreResult<ByteStream>;
+
+    /// Every object whose key starts with `prefix`, in key order.
+    async fn list(&self, prefix: &str) -> ObjectStoreResult<Vec<ObjectMeta>>;
+
+    /// Remove the object under `key`. Removing a missing object succeeds.
+    async fn delete(&self, key: &str) -> ObjectStoreResult<()>;
+
+    /// Store an in-memory body under `key`.
+    async fn put_bytes(&self, key: &str, body: Bytes) -> ObjectStoreResult<u64> {
+        self.put(key, bytes_stream(body)).await
+    }
+
+    /// Store the local file at `path` under `key`.
+    async fn put_file(&self, key: &str, path: &Path) -> ObjectStoreResult<u64> {
+        let file = tokio::fs::File::open(path).await?;
+        self.put(key, read_stream(file)).await
+    }
+
+    /// Read the whole object under `key` into memory. Meant for small
+    /// objects such as metadata sidecars.
+    async fn get_bytes(&self, key: &str) -> ObjectStoreResult<Bytes> {
+        let chunks: Vec<Bytes> = self.get(key).await?.try_collect().await?;
+        Ok(chunks.concat().into())
+    }
+
+    /// Copy the object under `key` to the local file at `path`.
+    async fn get_file(&self, key: &str, path: &Path) -> ObjectStoreResult<u64> {
+        use tokio::io::AsyncWriteExt;
+
+        let mut body = self.get(key).await?;
+        let mut file = tokio::fs::File::create(path).await?;
+        let mut written = 0u64;
+        while let Some(chunk) = body.next().await {
+            let chunk = chunk?;
+            file.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+        }
+        file.sync_all().await?;
+        Ok(written)
+    }
+}
+
+/// Reject keys that are empty, absolute, or have empty, `.`-prefixed or
+/// backslash-containing segments, so every adapter maps a key to exactly one
+/// location inside its root and `..` can never escape it.
+pub fn validate_key(key: &str) -> ObjectStoreResult<()> {
+    let valid = !key.is_empty()
+        && key.split('/').all(|segment| {
+            !segment.is_empty() && !segment.starts_with('.') && !segment.contains('\\')
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err(ObjectStoreError::InvalidKey(key.to_string()))
+    }
+}
+
+/// Single-chunk stream over `body`.
+pub fn bytes_stream(body: Bytes) -> ByteStream {
+    Box::pin(futures::stream::once(async move { Ok(body) }))
+}
+
+/// Stream `reader` in 64 KiB chunks.
+pub fn read_stream<R>(mut reader: R) -> ByteStream
+where
+    R: AsyncRead + Send + Unpin + 'static,
+{
+    Box::pin(async_stream::stream! {
+        let mut buf = vec![0u8; 64 * 1024];
+        loop {
+            match reader.read(&mut buf).await {
+                Ok(0) => break,
+                Ok(n) => yield Ok(Bytes::copy_from_slice(&buf[..n])),
+                Err(e) => {
+                    yield Err(e);
+                    break;
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_key() {
+        assert!(validate_key("backup_daily_20260601_000000.sql.gz").is_ok());
+        assert!(validate_key("exports/0b6c.csv").is_ok());
+
+        for key in [
+            "",
+            "/etc/passwd",
+            "exports/",
+            "exports//a.csv",
+            "../a.csv",
+            "exports/../../a.csv",
+            ".hidden",
+            "a\\b",
+        ] {
+            assert!(validate_key(key).is_err(), "{key:?} should be rejected");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_stream_yields_all_bytes() {
+        let data = vec![7u8; 150 * 1024];
+        let chunks: Vec<Bytes> = read_stream(std::io::Cursor::new(data.clone()))
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks.concat(), data);
+    }
+}
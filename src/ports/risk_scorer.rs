@@ -0,0 +1,88 @@
+//! Port (trait) for scoring the risk of a deposit before it is completed.
+//! Implementations can be the built-in heuristics, an external fraud / AML
+//! service, a fixed score (for tests), etc.
+//!
+//! Scores run from 0 (no risk) to 100; the processor holds deposits scoring
+//! at or above `RISK_REVIEW_THRESHOLD` in `compliance_review`.
+
+use async_trait::async_trait;
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+
+use crate::domain::Transaction;
+
+/// Highest possible score.
+pub const MAX_RISK_SCORE: u8 = 100;
+
+/// Result type for risk scoring.
+pub type RiskResult<T> = Result<T, RiskScorerError>;
+
+/// Risk scorer errors.
+#[derive(Debug, thiserror::Error)]
+pub enum RiskScorerError {
+    #[error("Risk scoring backend unavailable: {0}")]
+    Unavailable(String),
+
+    #[error("Risk scoring backend error: {0}")]
+    Backend(String),
+}
+
+/// What is known about the sender of a deposit from its earlier deposits.
+/// Every figure excludes the deposit being scored.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CustomerProfile {
+    /// Base `G...` account the deposit was sent from.
+    pub stellar_account: String,
+    /// Muxed id when the sender was an `M...` address; history then only
+    /// covers deposits from that id.
+    pub muxed_id: Option<u64>,
+    pub prior_deposits: i64,
+    /// Earlier deposits that ended `failed` or in the DLQ.
+    pub failed_deposits: i64,
+    /// Mean amount of the earlier deposits in the same asset.
+    pub average_amount: Option<BigDecimal>,
+    /// Deposits in the 24 hours before this one.
+    pub deposits_last_24h: i64,
+    pub first_seen_at: Option<DateTime<Utc>>,
+}
+
+/// A score and the reasons that contributed to it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RiskAssessment {
+    /// 0 to [`MAX_RISK_SCORE`].
+    pub score: u8,
+    pub reasons: Vec<String>,
+}
+
+impl RiskAssessment {
+    /// Add `points` for `reason`, capping the score at [`MAX_RISK_SCORE`].
+    pub fn add(&mut self, points: u8, reason: impl Into<String>) {
+        self.score = self.score.saturating_add(points).min(MAX_RISK_SCORE);
+        self.reasons.push(reason.into());
+    }
+}
+
+/// Port for scoring deposits.
+#[async_trait]
+pub trait RiskScorer: Send + Sync {
+    /// Score `transaction` given its sender's history.
+    async fn score(
+        &self,
+        transaction: &Transaction,
+        customer: &CustomerProfile,
+    ) -> RiskResult<RiskAssessment>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assessment_caps_score() {
+        let mut assessment = RiskAssessment::default();
+        assessment.add(70, "first");
+        assessment.add(70, "second");
+        assert_eq!(assessment.score, MAX_RISK_SCORE);
+        assert_eq!(assessment.reasons, vec!["first", "second"]);
+    }
+}
@@ -2,6 +2,7 @@
 //! Implementations can be Postgres, in-memory (for tests), etc.
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 use crate::domain::Transaction;
@@ -15,8 +16,31 @@ pub enum RepositoryError {
     #[error("Not found: {0}")]
     NotFound(String),
 
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Constraint violation: {0}")]
+    ConstraintViolation(String),
+
+    /// The row lies in a closed accounting period and cannot be written.
+    #[error("Period closed: {0}")]
+    PeriodClosed(String),
+
     #[error("Database error: {0}")]
-    Database(#[from] sqlx::Error),
+    Database(#[source] sqlx::Error),
+}
+
+impl From<sqlx::Error> for RepositoryError {
+    fn from(err: sqlx::Error) -> Self {
+        use crate::db::constraints::ConstraintViolation;
+
+        match crate::db::constraints::classify(&err) {
+            Some(ConstraintViolation::PeriodClosed(msg)) => RepositoryError::PeriodClosed(msg),
+            Some(v) if v.is_conflict() => RepositoryError::Conflict(v.to_string()),
+            Some(v) => RepositoryError::ConstraintViolation(v.to_string()),
+            None => RepositoryError::Database(err),
+        }
+    }
 }
 
 /// Port for persisting and querying transactions.
@@ -30,4 +54,13 @@ pub trait TransactionRepository: Send + Sync {
 
     /// List transactions with pagination.
     async fn list(&self, limit: i64, offset: i64) -> RepositoryResult<Vec<Transaction>>;
+
+    /// List transactions created in `[from, to)`, oldest first (ties by ID).
+    async fn list_created_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: i64,
+        offset: i64,
+    ) -> RepositoryResult<Vec<Transaction>>;
 }
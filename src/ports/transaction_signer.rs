@@ -0,0 +1,28 @@
+//! Port (trait) for signing the anchor's Stellar transactions.
+//! Implementations can hold the secret seed in process, or forward the
+//! payload to a KMS or HSM that never releases the key.
+
+use async_trait::async_trait;
+
+/// Result type for signing.
+pub type SignerResult<T> = Result<T, SignerError>;
+
+/// Transaction signer errors.
+#[derive(Debug, thiserror::Error)]
+pub enum SignerError {
+    #[error("Signing backend unavailable: {0}")]
+    Unavailable(String),
+
+    #[error("Signing backend error: {0}")]
+    Backend(String),
+}
+
+/// Port for ed25519 signatures by one Stellar account.
+#[async_trait]
+pub trait TransactionSigner: Send + Sync {
+    /// Public key the signatures verify against.
+    fn public_key(&self) -> [u8; 32];
+
+    /// Ed25519 signature of `payload`, a transaction hash.
+    async fn sign(&self, payload: &[u8]) -> SignerResult<Vec<u8>>;
+}
@@ -1,5 +1,6 @@
+use chrono::{DateTime, Utc};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 /// Readiness state for the application.
@@ -13,6 +14,11 @@ pub struct ReadinessState {
     drain_timeout_secs: u64,
     /// Flag indicating if drain has started
     is_draining: Arc<AtomicBool>,
+    /// Cleared by the failover watchdog while the primary database is
+    /// unreachable; readiness is withheld without starting a drain.
+    db_reachable: Arc<AtomicBool>,
+    /// When the current drain ends, fixed by the first `start_drain`.
+    drain_deadline: Arc<Mutex<Option<DateTime<Utc>>>>,
 }
 
 impl ReadinessState {
@@ -23,6 +29,8 @@ impl ReadinessState {
             is_ready: Arc::new(AtomicBool::new(false)),
             drain_timeout_secs: 30,
             is_draining: Arc::new(AtomicBool::new(false)),
+            db_reachable: Arc::new(AtomicBool::new(true)),
+            drain_deadline: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -33,12 +41,25 @@ impl ReadinessState {
             is_ready: Arc::new(AtomicBool::new(false)),
             drain_timeout_secs,
             is_draining: Arc::new(AtomicBool::new(false)),
+            db_reachable: Arc::new(AtomicBool::new(true)),
+            drain_deadline: Arc::new(Mutex::new(None)),
         }
     }
 
     /// Check if the application is ready to accept traffic
     pub fn is_ready(&self) -> bool {
-        self.is_ready.load(Ordering::SeqCst)
+        self.is_ready.load(Ordering::SeqCst) && self.is_db_reachable()
+    }
+
+    /// Whether the failover watchdog last found the primary database reachable
+    pub fn is_db_reachable(&self) -> bool {
+        self.db_reachable.load(Ordering::SeqCst)
+    }
+
+    /// Record whether the primary database is reachable (see
+    /// [`crate::db::failover`])
+    pub fn set_db_reachable(&self, reachable: bool) {
+        self.db_reachable.store(reachable, Ordering::SeqCst);
     }
 
     /// Check if the application is draining (stopping accepting new connections)
@@ -51,10 +72,22 @@ impl ReadinessState {
         Duration::from_secs(self.drain_timeout_secs)
     }
 
+    fn deadline(&self) -> std::sync::MutexGuard<'_, Option<DateTime<Utc>>> {
+        self.drain_deadline
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// When the current drain ends, if one was started
+    pub fn drain_deadline(&self) -> Option<DateTime<Utc>> {
+        *self.deadline()
+    }
+
     /// Mark the application as ready to accept traffic
     pub fn set_ready(&self) {
         self.is_ready.store(true, Ordering::SeqCst);
         self.is_draining.store(false, Ordering::SeqCst);
+        *self.deadline() = None;
     }
 
     /// Mark the application as not ready (draining)
@@ -65,27 +98,42 @@ impl ReadinessState {
     }
 
     /// Start the drain process
-    /// Returns the drain timeout duration
+    /// Returns the drain timeout duration. The deadline is set by the first
+    /// call; later calls while draining keep it.
     pub fn start_drain(&self) -> Duration {
         self.set_not_ready();
-        tracing::info!(
-            "Starting connection draining with timeout of {} seconds",
-            self.drain_timeout_secs
-        );
+        let mut deadline = self.deadline();
+        if deadline.is_none() {
+            let timeout = chrono::Duration::seconds(self.drain_timeout_secs as i64);
+            *deadline = Some(Utc::now() + timeout);
+            tracing::info!(
+                "Starting connection draining with timeout of {} seconds",
+                self.drain_timeout_secs
+            );
+        }
         self.drain_timeout()
     }
 
+    /// Time left until the drain deadline; the full timeout when no drain
+    /// was started
+    pub fn drain_remaining(&self) -> Duration {
+        match self.drain_deadline() {
+            Some(deadline) => (deadline - Utc::now()).to_std().unwrap_or(Duration::ZERO),
+            None => self.drain_timeout(),
+        }
+    }
+
     /// Wait for the drain to complete (used in shutdown)
     pub async fn wait_for_drain(&self) {
-        let timeout = self.drain_timeout();
-
-        // If already not ready (draining), wait for the timeout
+        // If already not ready (draining), wait out the rest of the drain, so
+        // a SIGTERM after a preStop `POST /admin/drain` does not restart it
         if !self.is_ready() {
+            let remaining = self.drain_remaining();
             tracing::info!(
                 "Waiting {} seconds for in-flight requests to complete...",
-                timeout.as_secs()
+                remaining.as_secs()
             );
-            tokio::time::sleep(timeout).await;
+            tokio::time::sleep(remaining).await;
             tracing::info!("Drain period complete, shutting down");
         }
     }
@@ -199,28 +247,32 @@ impl Default for ReadinessState {
 /// Axum handler: POST /admin/drain
 ///
 /// Kubernetes preStop hook target. Sets readiness to false, starts the drain timer,
-/// and returns immediately. The process will exit after the drain timeout elapses.
+/// and returns immediately with the drain deadline. The process will exit after
+/// the drain timeout elapses.
 pub async fn drain_handler(
     axum::extract::State(state): axum::extract::State<crate::ApiState>,
 ) -> impl axum::response::IntoResponse {
     use axum::http::StatusCode;
     use axum::Json;
 
-    if state.app_state.readiness.is_draining() {
+    let readiness = &state.app_state.readiness;
+    if readiness.is_draining() {
         return (
             StatusCode::OK,
             Json(serde_json::json!({
                 "status": "already_draining",
-                "drain_timeout_secs": state.app_state.readiness.drain_timeout().as_secs()
+                "drain_timeout_secs": readiness.drain_timeout().as_secs(),
+                "drain_deadline": readiness.drain_deadline(),
             })),
         );
     }
 
-    let timeout = state.app_state.readiness.start_drain();
+    let timeout = readiness.start_drain();
+    let remaining = readiness.drain_remaining();
 
-    // Spawn a task that exits the process after the drain timeout
+    // Spawn a task that exits the process at the drain deadline
     tokio::spawn(async move {
-        tokio::time::sleep(timeout).await;
+        tokio::time::sleep(remaining).await;
         tracing::info!("Drain timeout elapsed — shutting down process");
         std::process::exit(0);
     });
@@ -229,7 +281,8 @@ pub async fn drain_handler(
         StatusCode::OK,
         Json(serde_json::json!({
             "status": "draining",
-            "drain_timeout_secs": timeout.as_secs()
+            "drain_timeout_secs": timeout.as_secs(),
+            "drain_deadline": readiness.drain_deadline(),
         })),
     )
 }
@@ -267,12 +320,40 @@ mod tests {
         assert!(!state.is_draining());
     }
 
+    #[test]
+    fn test_unreachable_db_withholds_readiness_without_draining() {
+        let state = ReadinessState::new();
+        state.set_ready();
+        state.set_db_reachable(false);
+        assert!(!state.is_ready());
+        assert!(!state.is_draining());
+        state.set_db_reachable(true);
+        assert!(state.is_ready());
+    }
+
     #[test]
     fn test_drain_timeout() {
         let state = ReadinessState::with_drain_timeout(60);
         assert_eq!(state.drain_timeout().as_secs(), 60);
     }
 
+    #[test]
+    fn test_drain_deadline_is_fixed_by_the_first_drain() {
+        let state = ReadinessState::with_drain_timeout(60);
+        assert!(state.drain_deadline().is_none());
+        let before = Utc::now();
+        state.start_drain();
+        let deadline = state.drain_deadline().unwrap();
+        assert!(deadline >= before + chrono::Duration::seconds(60));
+        assert!(state.drain_remaining() <= state.drain_timeout());
+
+        state.start_drain();
+        assert_eq!(state.drain_deadline(), Some(deadline));
+
+        state.set_ready();
+        assert!(state.drain_deadline().is_none());
+    }
+
     #[test]
     fn test_default_drain_timeout() {
         let state = ReadinessState::new();
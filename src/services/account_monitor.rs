@@ -1,5 +1,9 @@
+use crate::domain::StellarAddress;
+use crate::services::asset_trust::{self, ObservedPayment, QuarantineSource};
+use crate::services::refunds::{self, InboundPayment, RefundSource};
 use crate::stellar::client::HorizonClient;
 use serde::{Deserialize, Serialize};
+use sqlx::types::BigDecimal;
 use sqlx::PgPool;
 use std::time::Duration;
 use tokio::sync::mpsc;
@@ -14,8 +18,35 @@ pub struct Payment {
     pub to: String,
     pub amount: String,
     pub asset_code: String,
+    /// `None` for native XLM.
+    #[serde(default)]
+    pub asset_issuer: Option<String>,
     pub memo: Option<String>,
     pub memo_type: Option<String>,
+    /// Stellar transaction carrying the payment.
+    #[serde(default)]
+    pub transaction_hash: Option<String>,
+    /// Close time of the ledger that included it (Horizon `created_at`).
+    #[serde(default)]
+    pub created_at: Option<String>,
+    /// Sender's `M...` address when it paid from a muxed account; `from` is
+    /// then its base account.
+    #[serde(default)]
+    pub from_muxed: Option<String>,
+}
+
+impl Payment {
+    /// Muxed id of the sender, when it paid from an `M...` address.
+    fn from_muxed_id(&self) -> Option<u64> {
+        let address = StellarAddress::parse(self.from_muxed.as_deref()?).ok()?;
+        address.muxed_id()
+    }
+
+    /// Where a refund goes: back to the muxed sender when there was one, so
+    /// the exchange can credit the right customer.
+    fn refund_address(&self) -> &str {
+        self.from_muxed.as_deref().unwrap_or(&self.from)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -26,9 +57,17 @@ struct PaymentRecord {
     amount: String,
     asset_code: String,
     #[serde(default)]
+    asset_issuer: Option<String>,
+    #[serde(default)]
     memo: Option<String>,
     #[serde(default)]
     memo_type: Option<String>,
+    #[serde(default)]
+    transaction_hash: Option<String>,
+    #[serde(default)]
+    created_at: Option<String>,
+    #[serde(default)]
+    from_muxed: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -90,7 +129,7 @@ impl AccountMonitor {
         let last_id = payments.last().map(|p| p.id.clone());
 
         for payment in payments {
-            if let Err(e) = self.process_payment(&payment).await {
+            if let Err(e) = self.process_payment(account, &payment).await {
                 warn!("Failed to process payment {}: {}", payment.id, e);
             }
         }
@@ -109,7 +148,7 @@ impl AccountMonitor {
     ) -> anyhow::Result<Vec<Payment>> {
         let mut url = format!(
             "{}/accounts/{}/payments?order=asc&limit=200",
-            self.horizon_client.base_url.trim_end_matches('/'),
+            self.horizon_client.base_url().trim_end_matches('/'),
             account
         );
 
@@ -135,42 +174,134 @@ impl AccountMonitor {
                 to: r.to,
                 amount: r.amount,
                 asset_code: r.asset_code,
+                asset_issuer: r.asset_issuer,
                 memo: r.memo,
                 memo_type: r.memo_type,
+                transaction_hash: r.transaction_hash,
+                created_at: r.created_at,
+                from_muxed: r.from_muxed,
             })
             .collect())
     }
 
-    async fn process_payment(&self, payment: &Payment) -> anyhow::Result<()> {
-        // Match payment to pending transaction by memo
-        if let Some(memo) = &payment.memo {
-            let tx = sqlx::query_as::<_, (Uuid,)>(
-                "SELECT id FROM transactions WHERE memo = $1 AND status = 'pending' LIMIT 1",
-            )
-            .bind(memo)
-            .fetch_optional(&self.pool)
-            .await?;
+    /// The pending transaction `payment` settles, if any.
+    ///
+    /// Payments are matched by memo; a transaction recorded with a muxed id
+    /// only matches a payment from that same id. Exchanges paying from a muxed
+    /// account often send no memo, since the id already identifies the
+    /// customer; those are matched on sender, muxed id, amount and asset.
+    async fn find_pending_match(&self, payment: &Payment) -> anyhow::Result<Option<Uuid>> {
+        let muxed_id = payment.from_muxed_id().map(BigDecimal::from);
+        let matched = match (&payment.memo, muxed_id) {
+            (Some(memo), muxed_id) => {
+                sqlx::query_scalar::<_, Uuid>(
+                    "SELECT id FROM transactions WHERE memo = $1 AND status = 'pending' \
+                     AND (stellar_muxed_id IS NULL OR stellar_muxed_id = $2) \
+                     ORDER BY created_at LIMIT 1",
+                )
+                .bind(memo)
+                .bind(muxed_id)
+                .fetch_optional(&self.pool)
+                .await?
+            }
+            (None, Some(muxed_id)) => {
+                sqlx::query_scalar::<_, Uuid>(
+                    "SELECT id FROM transactions WHERE stellar_account = $1 \
+                     AND stellar_muxed_id = $2 AND amount = $3::numeric AND asset_code = $4 \
+                     AND status = 'pending' ORDER BY created_at LIMIT 1",
+                )
+                .bind(&payment.from)
+                .bind(muxed_id)
+                .bind(&payment.amount)
+                .bind(&payment.asset_code)
+                .fetch_optional(&self.pool)
+                .await?
+            }
+            (None, None) => None,
+        };
+        Ok(matched)
+    }
+
+    /// Quarantine `payment` if the partner it was sent to does not trust its
+    /// asset issuer (see [`asset_trust`]). Returns whether it was held back.
+    async fn quarantine_if_untrusted(&self, payment: &Payment) -> anyhow::Result<bool> {
+        let observed = ObservedPayment {
+            payment_id: &payment.id,
+            to: &payment.to,
+            from: Some(payment.refund_address()),
+            asset_code: &payment.asset_code,
+            asset_issuer: payment.asset_issuer.as_deref(),
+            amount: &payment.amount,
+            memo: payment.memo.as_deref(),
+            stellar_tx_hash: payment.transaction_hash.as_deref(),
+        };
+        let Some(untrusted) = asset_trust::screen(&self.pool, &observed).await? else {
+            return Ok(false);
+        };
+        let mut db_tx = self.pool.begin().await?;
+        asset_trust::quarantine(
+            &mut db_tx,
+            QuarantineSource::PaymentMonitor,
+            &observed,
+            &untrusted,
+            None,
+        )
+        .await?;
+        db_tx.commit().await?;
+        Ok(true)
+    }
 
-            if let Some((tx_id,)) = tx {
-                info!("Matched payment {} to transaction {}", payment.id, tx_id);
+    /// Match a payment to its pending transaction (see
+    /// [`Self::find_pending_match`]). Inbound payments to `account` that match
+    /// no transaction are queued for refund, unless their asset issuer is not
+    /// trusted, in which case they are quarantined and left alone.
+    async fn process_payment(&self, account: &str, payment: &Payment) -> anyhow::Result<()> {
+        if payment.to == account && self.quarantine_if_untrusted(payment).await? {
+            return Ok(());
+        }
 
-                // Validate status transition: pending → completed
-                crate::validation::state_machine::validate_status_transition(
-                    "pending",
-                    "completed",
-                )
+        if let Some(tx_id) = self.find_pending_match(payment).await? {
+            info!("Matched payment {} to transaction {}", payment.id, tx_id);
+
+            // Validate status transition: pending → completed
+            crate::validation::state_machine::validate_status_transition("pending", "completed")
                 .map_err(|e| anyhow::anyhow!("{e}"))?;
 
-                // Update transaction to completed
-                sqlx::query(
-                    "UPDATE transactions SET status = 'completed', updated_at = NOW() WHERE id = $1"
-                )
-                .bind(tx_id)
-                .execute(&self.pool)
-                .await?;
+            // Update transaction to completed, recording where it landed
+            let closed_at = payment
+                .created_at
+                .as_deref()
+                .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+                .map(|t| t.with_timezone(&chrono::Utc));
+            sqlx::query(
+                "UPDATE transactions SET status = 'completed', \
+                 stellar_tx_hash = COALESCE($2, stellar_tx_hash), closed_at = $3, \
+                 updated_at = NOW() WHERE id = $1",
+            )
+            .bind(tx_id)
+            .bind(&payment.transaction_hash)
+            .bind(closed_at)
+            .execute(&self.pool)
+            .await?;
 
-                info!("Completed transaction {} via payment monitoring", tx_id);
-            }
+            info!("Completed transaction {} via payment monitoring", tx_id);
+            return Ok(());
+        }
+
+        // Outgoing payments (including refunds we sent) are never refunded.
+        if payment.to == account {
+            refunds::enqueue_if_unmatched(
+                &self.pool,
+                RefundSource::PaymentMonitor,
+                &InboundPayment {
+                    payment_id: &payment.id,
+                    from: payment.refund_address(),
+                    amount: &payment.amount,
+                    asset_code: &payment.asset_code,
+                    memo: payment.memo.as_deref(),
+                },
+            )
+            .await?;
         }
 
         Ok(())
@@ -227,11 +358,15 @@ impl AccountMonitor {
                         to: payment.to,
                         amount: payment.amount,
                         asset_code: payment.asset_code,
+                        asset_issuer: payment.asset_issuer,
                         memo: payment.memo,
                         memo_type: payment.memo_type,
+                        transaction_hash: payment.transaction_hash,
+                        created_at: Some(payment.created_at),
+                        from_muxed: payment.from_muxed,
                     };
 
-                    if let Err(e) = self.process_payment(&payment_obj).await {
+                    if let Err(e) = self.process_payment(account, &payment_obj).await {
                         warn!("Failed to process streamed payment: {}", e);
                     }
                 }
@@ -249,3 +384,36 @@ impl AccountMonitor {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payment(from_muxed: Option<&str>) -> Payment {
+        Payment {
+            id: "1".to_string(),
+            from: "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ".to_string(),
+            to: "GBBD47UZQ5CSKQPV456PYYH4FSYJHBWGQJUVNMCNWZ2NBEHKQPW3LEFO".to_string(),
+            amount: "10.0000000".to_string(),
+            asset_code: "USDC".to_string(),
+            asset_issuer: None,
+            memo: None,
+            memo_type: None,
+            transaction_hash: None,
+            created_at: None,
+            from_muxed: from_muxed.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_muxed_sender_id_and_refund_address() {
+        let muxed = "MA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJUAAAAAAAAAAE2JUG6";
+        let from_muxed = payment(Some(muxed));
+        assert_eq!(from_muxed.from_muxed_id(), Some(1234));
+        assert_eq!(from_muxed.refund_address(), muxed);
+
+        let plain = payment(None);
+        assert_eq!(plain.from_muxed_id(), None);
+        assert_eq!(plain.refund_address(), plain.from);
+    }
+}
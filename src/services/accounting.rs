@@ -0,0 +1,216 @@
+//! Monthly accounting close.
+//!
+//! Periods are calendar months in UTC, named `YYYY-MM`. Closing one writes a
+//! row to `accounting_periods`; from then on every insert, update or delete
+//! of a transaction or settlement created in that month is refused by the
+//! database (`ERR_ACCOUNTING_001`, 409), whichever code path attempts it.
+//!
+//! A month can be closed once it is over and none of its transactions is
+//! still in flight (`pending`, `processing`, `compliance_review` or
+//! `refund_pending`). Closed periods are never reopened. A late correction is
+//! posted as an adjustment: a signed amount against one transaction or
+//! settlement of the closed month, with a reason, audit-logged against that
+//! entity. The original row is left as it was.
+
+use crate::db::audit::{ENTITY_SETTLEMENT, ENTITY_TRANSACTION};
+use crate::db::models::{AccountingAdjustment, AccountingPeriod};
+use crate::db::queries::{self, NewAccountingAdjustment};
+use crate::error::AppError;
+use chrono::{DateTime, Datelike, Months, NaiveDate, NaiveTime, TimeZone, Utc};
+use sqlx::types::BigDecimal;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// One calendar month, UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Period {
+    start: NaiveDate,
+}
+
+impl Period {
+    /// Parse `YYYY-MM`.
+    pub fn parse(s: &str) -> Result<Self, AppError> {
+        let invalid = || AppError::BadRequest(format!("invalid period '{s}', expected YYYY-MM"));
+        let (year, month) = s.trim().split_once('-').ok_or_else(invalid)?;
+        if year.len() != 4 || month.len() != 2 {
+            return Err(invalid());
+        }
+        let year = year.parse::<i32>().map_err(|_| invalid())?;
+        let month = month.parse::<u32>().map_err(|_| invalid())?;
+        let start = NaiveDate::from_ymd_opt(year, month, 1).ok_or_else(invalid)?;
+        Ok(Self { start })
+    }
+
+    /// The month containing `at`.
+    pub fn containing(at: DateTime<Utc>) -> Self {
+        Self::containing_date(at.date_naive())
+    }
+
+    /// The month containing `date`.
+    pub fn containing_date(date: NaiveDate) -> Self {
+        Self {
+            start: date.with_day(1).expect("day 1 exists"),
+        }
+    }
+
+    pub fn start(&self) -> NaiveDate {
+        self.start
+    }
+
+    pub fn starts_at(&self) -> DateTime<Utc> {
+        Utc.from_utc_datetime(&self.start.and_time(NaiveTime::MIN))
+    }
+
+    /// Start of the next month, exclusive.
+    pub fn ends_at(&self) -> DateTime<Utc> {
+        let next = self.start + Months::new(1);
+        Utc.from_utc_datetime(&next.and_time(NaiveTime::MIN))
+    }
+
+    pub fn label(&self) -> String {
+        self.start.format("%Y-%m").to_string()
+    }
+}
+
+/// Close `period`, refusing a month that is not over yet or still has
+/// transactions in flight.
+pub async fn close(
+    pool: &PgPool,
+    period: Period,
+    actor: &str,
+    now: DateTime<Utc>,
+) -> Result<AccountingPeriod, AppError> {
+    if period.ends_at() > now {
+        return Err(AppError::BadRequest(format!(
+            "period {} is not over yet",
+            period.label()
+        )));
+    }
+    let unfinished =
+        queries::count_unfinished_transactions(pool, period.starts_at(), period.ends_at()).await?;
+    if unfinished > 0 {
+        return Err(AppError::BadRequest(format!(
+            "period {} still has {unfinished} unfinished transaction(s)",
+            period.label()
+        )));
+    }
+
+    let closed = queries::close_accounting_period(
+        pool,
+        period.start(),
+        period.starts_at(),
+        period.ends_at(),
+        actor,
+    )
+    .await?
+    .ok_or_else(|| {
+        AppError::PeriodClosed(format!(
+            "accounting period {} is already closed",
+            period.label()
+        ))
+    })?;
+    tracing::info!(period = %period.label(), actor, "Accounting period closed");
+    Ok(closed)
+}
+
+/// The closed period record for `period`, or 404 when it is still open.
+pub async fn closed_period(pool: &PgPool, period: Period) -> Result<AccountingPeriod, AppError> {
+    queries::get_accounting_period(pool, period.start())
+        .await?
+        .ok_or_else(|| {
+            AppError::NotFound(format!(
+                "accounting period {} is not closed",
+                period.label()
+            ))
+        })
+}
+
+/// A late correction to a transaction or settlement of a closed period.
+#[derive(Debug, Clone)]
+pub struct Adjustment {
+    pub entity_type: String,
+    pub entity_id: Uuid,
+    pub amount: BigDecimal,
+    pub reason: String,
+}
+
+/// Post `adjustment` against closed `period`. The entity must have been
+/// created in that period; entities of open periods are corrected directly.
+pub async fn post_adjustment(
+    pool: &PgPool,
+    period: Period,
+    adjustment: &Adjustment,
+    actor: &str,
+) -> Result<AccountingAdjustment, AppError> {
+    let entity_type = adjustment.entity_type.as_str();
+    if ![ENTITY_TRANSACTION, ENTITY_SETTLEMENT].contains(&entity_type) {
+        return Err(AppError::BadRequest(format!(
+            "entity_type must be '{ENTITY_TRANSACTION}' or '{ENTITY_SETTLEMENT}'"
+        )));
+    }
+    if adjustment.amount == BigDecimal::from(0) {
+        return Err(AppError::BadRequest("amount must not be zero".to_string()));
+    }
+
+    let closed = closed_period(pool, period).await?;
+    let (created_at, asset_code) =
+        queries::get_adjustable_entity(pool, entity_type, adjustment.entity_id)
+            .await?
+            .ok_or_else(|| {
+                AppError::NotFound(format!("{entity_type} {} not found", adjustment.entity_id))
+            })?;
+    if Period::containing(created_at) != period {
+        return Err(AppError::BadRequest(format!(
+            "{entity_type} {} does not belong to period {}",
+            adjustment.entity_id,
+            period.label()
+        )));
+    }
+
+    let posted = queries::insert_accounting_adjustment(
+        pool,
+        &NewAccountingAdjustment {
+            period_id: closed.id,
+            entity_type,
+            entity_id: adjustment.entity_id,
+            asset_code: &asset_code,
+            amount: &adjustment.amount,
+            reason: &adjustment.reason,
+            created_by: actor,
+        },
+    )
+    .await?;
+    tracing::info!(
+        period = %period.label(),
+        entity_type,
+        entity_id = %adjustment.entity_id,
+        amount = %adjustment.amount,
+        actor,
+        "Accounting adjustment posted"
+    );
+    Ok(posted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_period_bounds() {
+        let period = Period::parse("2026-12").unwrap();
+        assert_eq!(period.label(), "2026-12");
+        assert_eq!(period.starts_at().to_rfc3339(), "2026-12-01T00:00:00+00:00");
+        assert_eq!(period.ends_at().to_rfc3339(), "2027-01-01T00:00:00+00:00");
+
+        let last_instant = period.ends_at() - chrono::Duration::nanoseconds(1);
+        assert_eq!(Period::containing(last_instant), period);
+        assert_ne!(Period::containing(period.ends_at()), period);
+    }
+
+    #[test]
+    fn test_period_parse_rejects_malformed() {
+        for bad in ["2026-13", "2026-1", "26-01", "2026/01", "2026-01-01", ""] {
+            assert!(Period::parse(bad).is_err(), "{bad}");
+        }
+    }
+}
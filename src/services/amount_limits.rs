@@ -0,0 +1,188 @@
+//! Per-asset minimum/maximum deposit amounts.
+//!
+//! Limits live on the `assets` table (`min_amount`, `max_amount`; `NULL`
+//! means no bound) and are applied when a callback is ingested:
+//!
+//! | Amount            | Stored status       | Notification event             |
+//! |-------------------|---------------------|--------------------------------|
+//! | below `min_amount`| `refund_pending`    | `transaction.refund_pending`   |
+//! | above `max_amount`| `compliance_review` | `transaction.compliance_review`|
+//! | otherwise         | `pending`           | —                              |
+//!
+//! The transaction is still recorded so it can be refunded or approved later;
+//! it simply never enters the processing queue. Notifications go through the
+//! [`WebhookDispatcher`] to every endpoint subscribed to the event.
+
+use crate::db::models::{Transaction, TransactionStatus};
+use crate::services::webhook_dispatcher::{COMPLIANCE_REVIEW_EVENT, REFUND_PENDING_EVENT};
+use crate::services::WebhookDispatcher;
+use serde::Serialize;
+use sqlx::types::BigDecimal;
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, sqlx::FromRow)]
+pub struct AmountLimits {
+    pub min_amount: Option<BigDecimal>,
+    pub max_amount: Option<BigDecimal>,
+}
+
+/// Where an ingested deposit goes given its asset's limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AmountCheck {
+    WithinLimits,
+    BelowMinimum,
+    AboveMaximum,
+}
+
+impl AmountLimits {
+    pub fn check(&self, amount: &BigDecimal) -> AmountCheck {
+        if matches!(&self.min_amount, Some(min) if amount < min) {
+            AmountCheck::BelowMinimum
+        } else if matches!(&self.max_amount, Some(max) if amount > max) {
+            AmountCheck::AboveMaximum
+        } else {
+            AmountCheck::WithinLimits
+        }
+    }
+}
+
+impl AmountCheck {
+    /// Status the transaction is inserted with.
+    pub fn status(self) -> TransactionStatus {
+        match self {
+            AmountCheck::WithinLimits => TransactionStatus::Pending,
+            AmountCheck::BelowMinimum => TransactionStatus::RefundPending,
+            AmountCheck::AboveMaximum => TransactionStatus::ComplianceReview,
+        }
+    }
+
+    /// Outgoing webhook event announcing the hold, if any.
+    pub fn event_type(self) -> Option<&'static str> {
+        match self {
+            AmountCheck::WithinLimits => None,
+            AmountCheck::BelowMinimum => Some(REFUND_PENDING_EVENT),
+            AmountCheck::AboveMaximum => Some(COMPLIANCE_REVIEW_EVENT),
+        }
+    }
+}
+
+/// `data` of the `transaction.refund_pending` and
+/// `transaction.compliance_review` webhook events.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AmountLimitNotification {
+    #[schema(value_type = String, format = "uuid")]
+    pub transaction_id: Uuid,
+    pub asset_code: String,
+    /// Decimal string.
+    pub amount: String,
+    /// `refund_pending` or `compliance_review`.
+    pub status: String,
+    /// The asset's limits when the deposit was ingested; `null` means no bound.
+    pub min_amount: Option<String>,
+    pub max_amount: Option<String>,
+}
+
+impl AmountLimitNotification {
+    pub fn new(tx: &Transaction, limits: &AmountLimits) -> Self {
+        Self {
+            transaction_id: tx.id,
+            asset_code: tx.asset_code.clone(),
+            amount: tx.amount.to_string(),
+            status: tx.status.as_str().to_string(),
+            min_amount: limits.min_amount.as_ref().map(|v| v.to_string()),
+            max_amount: limits.max_amount.as_ref().map(|v| v.to_string()),
+        }
+    }
+}
+
+/// Queue the refund / compliance notification for `tx`.
+///
+/// Best effort: the transaction is already persisted with its held status, so
+/// a delivery failure is logged rather than failing the callback.
+pub async fn notify(
+    pool: &PgPool,
+    redis_url: &str,
+    tx: &Transaction,
+    check: AmountCheck,
+    limits: &AmountLimits,
+) {
+    let Some(event_type) = check.event_type() else {
+        return;
+    };
+
+    let data = serde_json::json!(AmountLimitNotification::new(tx, limits));
+
+    let result = match WebhookDispatcher::new(pool.clone(), redis_url) {
+        Ok(dispatcher) => dispatcher.enqueue(tx.id, event_type, data).await,
+        Err(e) => Err(e.into()),
+    };
+    if let Err(e) = result {
+        tracing::error!(
+            transaction_id = %tx.id,
+            event_type,
+            error = %e,
+            "Failed to enqueue amount-limit notification"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn dec(s: &str) -> BigDecimal {
+        BigDecimal::from_str(s).unwrap()
+    }
+
+    fn limits(min: Option<&str>, max: Option<&str>) -> AmountLimits {
+        AmountLimits {
+            min_amount: min.map(dec),
+            max_amount: max.map(dec),
+        }
+    }
+
+    #[test]
+    fn test_check_bounds_are_inclusive() {
+        let l = limits(Some("1.00"), Some("1000"));
+        assert_eq!(l.check(&dec("0.99")), AmountCheck::BelowMinimum);
+        assert_eq!(l.check(&dec("1")), AmountCheck::WithinLimits);
+        assert_eq!(l.check(&dec("1000.00")), AmountCheck::WithinLimits);
+        assert_eq!(l.check(&dec("1000.01")), AmountCheck::AboveMaximum);
+    }
+
+    #[test]
+    fn test_missing_bounds_are_unrestricted() {
+        assert_eq!(
+            AmountLimits::default().check(&dec("0.0000001")),
+            AmountCheck::WithinLimits
+        );
+        assert_eq!(
+            limits(Some("5"), None).check(&dec("99999999")),
+            AmountCheck::WithinLimits
+        );
+        assert_eq!(
+            limits(None, Some("5")).check(&dec("0.01")),
+            AmountCheck::WithinLimits
+        );
+    }
+
+    #[test]
+    fn test_status_and_event_mapping() {
+        assert_eq!(
+            AmountCheck::WithinLimits.status(),
+            TransactionStatus::Pending
+        );
+        assert_eq!(AmountCheck::WithinLimits.event_type(), None);
+        assert_eq!(
+            AmountCheck::BelowMinimum.status(),
+            TransactionStatus::RefundPending
+        );
+        assert_eq!(
+            AmountCheck::AboveMaximum.event_type(),
+            Some("transaction.compliance_review")
+        );
+    }
+}
@@ -0,0 +1,321 @@
+//! Four-eyes approval for high-value manual actions.
+//!
+//! Admin actions touching an amount above `FOUR_EYES_THRESHOLD` (default
+//! 10000, in asset units) are not applied when requested. The request is
+//! stored as a `pending` row in `approvals` and only applied once a
+//! different principal approves it:
+//!
+//! | Kind              | Requested through                         | Amount checked               |
+//! |-------------------|-------------------------------------------|------------------------------|
+//! | `status_override` | `PATCH /admin/transactions/bulk-status`   | largest transaction amount   |
+//! | `refund_override` | `POST /admin/refunds/:id/override`        | the refunded payment amount  |
+//! | `settlement_void` | `POST /admin/settlements/:id/void`        | settlement total (see below) |
+//!
+//! Settlement voids are held whatever the amount.
+//!
+//! ```text
+//! pending ──approve──▶ approved
+//!    └──────reject───▶ rejected
+//! ```
+//!
+//! Principals are the authenticated admin operators ([`AdminPrincipal`]),
+//! compared case-insensitively, so the proposer can neither approve nor
+//! reject their own request. The shared admin key names nobody, so it can
+//! neither propose nor decide; operators need their own key in
+//! `ADMIN_OPERATOR_KEYS`.
+//!
+//! Approving re-runs the stored request with the approver as actor, against
+//! the subject's current state and on the transaction that locks and decides
+//! the approval, so the action and the decision commit or roll back
+//! together. If the request no longer applies the approval stays `pending`
+//! and can be rejected. Proposals and decisions are audit-logged as
+//! `approval`.
+
+use crate::db::models::Approval;
+use crate::db::queries;
+use crate::error::AppError;
+use crate::middleware::auth::AdminPrincipal;
+use crate::services::refunds::RefundAction;
+use serde::{Deserialize, Serialize};
+use sqlx::types::BigDecimal;
+use sqlx::PgPool;
+use std::str::FromStr;
+use uuid::Uuid;
+
+const DEFAULT_THRESHOLD: i64 = 10_000;
+const PRINCIPAL_MAX_LEN: usize = 255;
+
+/// A manual action held for approval, stored as the approval's `payload`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ApprovalAction {
+    /// Bulk transaction status update.
+    StatusOverride {
+        transaction_ids: Vec<Uuid>,
+        status: String,
+        reason: Option<String>,
+    },
+    /// Refund queue override.
+    RefundOverride {
+        refund_id: Uuid,
+        action: RefundAction,
+        refund_to: Option<String>,
+        stellar_tx_hash: Option<String>,
+        note: Option<String>,
+    },
+    /// Reversal of a settlement computed wrongly.
+    SettlementVoid {
+        settlement_id: Uuid,
+        reason: String,
+        proposed_by: String,
+    },
+}
+
+impl ApprovalAction {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ApprovalAction::StatusOverride { .. } => "status_override",
+            ApprovalAction::RefundOverride { .. } => "refund_override",
+            ApprovalAction::SettlementVoid { .. } => "settlement_void",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl ApprovalStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApprovalStatus::Pending => "pending",
+            ApprovalStatus::Approved => "approved",
+            ApprovalStatus::Rejected => "rejected",
+        }
+    }
+}
+
+impl FromStr for ApprovalStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(ApprovalStatus::Pending),
+            "approved" => Ok(ApprovalStatus::Approved),
+            "rejected" => Ok(ApprovalStatus::Rejected),
+            _ => Err(format!("Invalid approval status: {s}")),
+        }
+    }
+}
+
+/// Amount above which manual actions need a second approver
+/// (`FOUR_EYES_THRESHOLD`).
+pub fn threshold() -> BigDecimal {
+    std::env::var("FOUR_EYES_THRESHOLD")
+        .ok()
+        .and_then(|v| BigDecimal::from_str(v.trim()).ok())
+        .filter(|t| *t >= BigDecimal::from(0))
+        .unwrap_or_else(|| BigDecimal::from(DEFAULT_THRESHOLD))
+}
+
+pub fn requires_approval(amount: &BigDecimal, threshold: &BigDecimal) -> bool {
+    amount > threshold
+}
+
+fn same_principal(a: &str, b: &str) -> bool {
+    a.trim().to_lowercase() == b.trim().to_lowercase()
+}
+
+fn principal(caller: &AdminPrincipal) -> Result<String, AppError> {
+    if caller.shared {
+        return Err(AppError::InsufficientPermissions(
+            "four-eyes approvals need a named operator key (ADMIN_OPERATOR_KEYS), \
+             not the shared admin key"
+                .to_string(),
+        ));
+    }
+    let value = caller.name.trim();
+    if value.is_empty() || value.len() > PRINCIPAL_MAX_LEN {
+        return Err(AppError::Validation(format!(
+            "operator name must be 1-{PRINCIPAL_MAX_LEN} characters"
+        )));
+    }
+    Ok(value.to_string())
+}
+
+/// Hold `action` for approval on behalf of `proposer`.
+pub async fn propose(
+    pool: &PgPool,
+    action: &ApprovalAction,
+    amount: &BigDecimal,
+    proposer: &AdminPrincipal,
+) -> Result<Approval, AppError> {
+    let proposer = principal(proposer)?;
+    let payload = serde_json::to_value(action).map_err(|e| AppError::Internal(e.to_string()))?;
+    let approval =
+        queries::insert_approval(pool, action.kind(), &payload, amount, &proposer).await?;
+    tracing::info!(
+        approval_id = %approval.id,
+        kind = action.kind(),
+        amount = %amount,
+        proposer = %proposer,
+        "Manual action held for four-eyes approval"
+    );
+    Ok(approval)
+}
+
+/// Lock approval `id` and check that `decider` may decide it.
+async fn lock_pending(
+    db_tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    id: Uuid,
+    decider: &str,
+) -> Result<Approval, AppError> {
+    let approval = queries::lock_approval(db_tx, id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Approval {id} not found")))?;
+    if approval.status != ApprovalStatus::Pending.as_str() {
+        return Err(AppError::BadRequest(format!(
+            "Approval {id} is already {}",
+            approval.status
+        )));
+    }
+    if same_principal(&approval.proposed_by, decider) {
+        return Err(AppError::InsufficientPermissions(format!(
+            "{decider} proposed approval {id} and cannot also decide it"
+        )));
+    }
+    Ok(approval)
+}
+
+/// A pending approval locked for approving, with the transaction holding the
+/// lock. Its action must be applied on `db_tx` before [`Self::approve`], so
+/// the action and the decision commit together. Dropping it rolls both back
+/// and leaves the approval `pending`.
+pub struct LockedApproval {
+    pub db_tx: sqlx::Transaction<'static, sqlx::Postgres>,
+    pub action: ApprovalAction,
+    /// The approver's principal, recorded as actor of the action.
+    pub approver: String,
+    approval: Approval,
+}
+
+/// Lock `id` for `approver` to approve: check they may decide it and decode
+/// its action.
+pub async fn lock_for_approval(
+    pool: &PgPool,
+    id: Uuid,
+    approver: &AdminPrincipal,
+) -> Result<LockedApproval, AppError> {
+    let approver = principal(approver)?;
+    let mut db_tx = pool.begin().await?;
+    let approval = lock_pending(&mut db_tx, id, &approver).await?;
+    let action: ApprovalAction = serde_json::from_value(approval.payload.clone())
+        .map_err(|e| AppError::Internal(format!("Approval {id} has an unreadable payload: {e}")))?;
+    Ok(LockedApproval {
+        db_tx,
+        action,
+        approver,
+        approval,
+    })
+}
+
+impl LockedApproval {
+    /// Record the approval with the applied action's `result` and commit.
+    pub async fn approve(
+        mut self,
+        note: Option<&str>,
+        result: &serde_json::Value,
+    ) -> Result<Approval, AppError> {
+        let decided = queries::decide_approval(
+            &mut self.db_tx,
+            &self.approval,
+            ApprovalStatus::Approved.as_str(),
+            &self.approver,
+            note,
+            Some(result),
+        )
+        .await?;
+        self.db_tx.commit().await?;
+        Ok(decided)
+    }
+}
+
+/// Reject `id` as `approver`; its action is never applied.
+pub async fn reject(
+    pool: &PgPool,
+    id: Uuid,
+    approver: &AdminPrincipal,
+    note: Option<&str>,
+) -> Result<Approval, AppError> {
+    let approver = principal(approver)?;
+    let mut db_tx = pool.begin().await?;
+    let approval = lock_pending(&mut db_tx, id, &approver).await?;
+    let decided = queries::decide_approval(
+        &mut db_tx,
+        &approval,
+        ApprovalStatus::Rejected.as_str(),
+        &approver,
+        note,
+        None,
+    )
+    .await?;
+    db_tx.commit().await?;
+    Ok(decided)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_amounts_above_threshold_need_approval() {
+        let threshold = BigDecimal::from(10_000);
+        assert!(!requires_approval(&BigDecimal::from(10_000), &threshold));
+        assert!(requires_approval(
+            &BigDecimal::from_str("10000.01").unwrap(),
+            &threshold
+        ));
+    }
+
+    #[test]
+    fn test_principals_compare_case_insensitively() {
+        assert!(same_principal("Alice", " alice "));
+        assert!(!same_principal("alice", "bob"));
+    }
+
+    #[test]
+    fn test_shared_key_cannot_take_part() {
+        let operator = |name: &str, shared: bool| AdminPrincipal {
+            name: name.to_string(),
+            shared,
+        };
+        assert_eq!(principal(&operator(" alice ", false)).unwrap(), "alice");
+        assert!(principal(&operator("  ", false)).is_err());
+        assert!(matches!(
+            principal(&operator(AdminPrincipal::SHARED_NAME, true)),
+            Err(AppError::InsufficientPermissions(_))
+        ));
+    }
+
+    #[test]
+    fn test_action_payload_round_trips() {
+        let action = ApprovalAction::RefundOverride {
+            refund_id: Uuid::nil(),
+            action: RefundAction::Cancel,
+            refund_to: None,
+            stellar_tx_hash: None,
+            note: Some("matched by hand".to_string()),
+        };
+        let payload = serde_json::to_value(&action).unwrap();
+        assert_eq!(payload["kind"], action.kind());
+        assert_eq!(payload["action"], "cancel");
+        assert_eq!(
+            serde_json::from_value::<ApprovalAction>(payload).unwrap(),
+            action
+        );
+    }
+}
@@ -0,0 +1,204 @@
+//! Trusted asset issuers.
+//!
+//! An asset code alone does not identify a Stellar asset: anyone can issue a
+//! token called `USDC`. Each partner registers the `(code, issuer)` pairs it
+//! accepts in `partner_trusted_assets` (managed through
+//! `PATCH /admin/partners/:tenant_id/settings`), and inbound payments to the
+//! partner's account are checked against it:
+//!
+//! | Registry    | Payment asset                     | Result      |
+//! |-------------|-----------------------------------|-------------|
+//! | empty       | anything                          | trusted     |
+//! | any         | native XLM (no issuer)            | trusted     |
+//! | non-empty   | listed code and issuer            | trusted     |
+//! | non-empty   | listed code, other issuer         | quarantined |
+//! | non-empty   | unlisted code                     | quarantined |
+//!
+//! The [`AccountMonitor`](crate::services::AccountMonitor) checks each payment
+//! it sees before matching it, and the
+//! [processor](crate::services::processor::process_batch) checks the payments
+//! of a deposit's Stellar transaction before completing it. Untrusted payments
+//! are recorded in `quarantined_payments` and are neither matched nor
+//! refunded; deposits carrying one fail.
+
+use crate::db::models::{QuarantinedPayment, TrustedAsset};
+use crate::db::queries::{self, NewQuarantinedPayment};
+use sqlx::types::BigDecimal;
+use sqlx::{PgPool, Postgres, Transaction as SqlxTransaction};
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// Which component quarantined the payment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuarantineSource {
+    PaymentMonitor,
+    Processor,
+}
+
+impl QuarantineSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuarantineSource::PaymentMonitor => "payment_monitor",
+            QuarantineSource::Processor => "processor",
+        }
+    }
+}
+
+/// A partner and the asset issuers it trusts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartnerTrust {
+    pub tenant_id: Uuid,
+    pub trusted: Vec<TrustedAsset>,
+}
+
+impl PartnerTrust {
+    /// Why this partner does not trust `asset_code` from `asset_issuer`, or
+    /// `None` when it does. Native payments carry no issuer and are trusted.
+    pub fn untrusted_reason(&self, asset_code: &str, asset_issuer: Option<&str>) -> Option<String> {
+        let issuer = asset_issuer?;
+        if self.trusted.is_empty() {
+            return None;
+        }
+        let mut known_code = false;
+        for asset in &self.trusted {
+            if asset.asset_code.eq_ignore_ascii_case(asset_code) {
+                if asset.asset_issuer == issuer {
+                    return None;
+                }
+                known_code = true;
+            }
+        }
+        Some(if known_code {
+            format!("issuer {issuer} is not trusted for {asset_code}")
+        } else {
+            format!("asset {asset_code}:{issuer} is not in the trusted registry")
+        })
+    }
+}
+
+/// An inbound payment as seen on chain.
+#[derive(Debug, Clone)]
+pub struct ObservedPayment<'a> {
+    pub payment_id: &'a str,
+    /// Receiving account.
+    pub to: &'a str,
+    pub from: Option<&'a str>,
+    pub asset_code: &'a str,
+    pub asset_issuer: Option<&'a str>,
+    pub amount: &'a str,
+    pub memo: Option<&'a str>,
+    pub stellar_tx_hash: Option<&'a str>,
+}
+
+/// A payment the receiving partner does not trust.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Untrusted {
+    pub tenant_id: Uuid,
+    pub reason: String,
+}
+
+/// Check `payment` against the registry of the partner it was sent to.
+/// Payments to accounts no partner uses are not checked.
+pub async fn screen(
+    pool: &PgPool,
+    payment: &ObservedPayment<'_>,
+) -> Result<Option<Untrusted>, sqlx::Error> {
+    let Some(trust) = queries::get_partner_trust_for_account(pool, payment.to).await? else {
+        return Ok(None);
+    };
+    Ok(trust
+        .untrusted_reason(payment.asset_code, payment.asset_issuer)
+        .map(|reason| Untrusted {
+            tenant_id: trust.tenant_id,
+            reason,
+        }))
+}
+
+/// Record `payment` in `quarantined_payments` within `db_tx`. Returns `None`
+/// when it was already quarantined.
+pub async fn quarantine(
+    db_tx: &mut SqlxTransaction<'_, Postgres>,
+    source: QuarantineSource,
+    payment: &ObservedPayment<'_>,
+    untrusted: &Untrusted,
+    transaction_id: Option<Uuid>,
+) -> anyhow::Result<Option<QuarantinedPayment>> {
+    let amount = BigDecimal::from_str(payment.amount)?;
+    let row = queries::insert_quarantined_payment(
+        db_tx,
+        &NewQuarantinedPayment {
+            payment_id: payment.payment_id,
+            source: source.as_str(),
+            tenant_id: Some(untrusted.tenant_id),
+            transaction_id,
+            account: payment.to,
+            from_account: payment.from,
+            asset_code: payment.asset_code,
+            asset_issuer: payment.asset_issuer,
+            amount: &amount,
+            memo: payment.memo,
+            stellar_tx_hash: payment.stellar_tx_hash,
+            reason: &untrusted.reason,
+        },
+    )
+    .await?;
+
+    if let Some(row) = &row {
+        tracing::warn!(
+            quarantine_id = %row.id,
+            payment_id = payment.payment_id,
+            tenant_id = %untrusted.tenant_id,
+            reason = %untrusted.reason,
+            source = source.as_str(),
+            "Payment with untrusted asset issuer quarantined"
+        );
+    }
+    Ok(row)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CIRCLE: &str = "GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN";
+    const SPOOF: &str = "GAAZI4TCR3TY5OJHCTJC2A4QSY6CJWJH5IAJTGKIN2ER7LBNVKOCCWN7";
+
+    fn trust(assets: &[(&str, &str)]) -> PartnerTrust {
+        PartnerTrust {
+            tenant_id: Uuid::nil(),
+            trusted: assets
+                .iter()
+                .map(|(code, issuer)| TrustedAsset {
+                    asset_code: code.to_string(),
+                    asset_issuer: issuer.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_empty_registry_trusts_everything() {
+        assert_eq!(trust(&[]).untrusted_reason("USDC", Some(SPOOF)), None);
+    }
+
+    #[test]
+    fn test_registered_issuer_is_trusted() {
+        let registry = trust(&[("USDC", CIRCLE)]);
+        assert_eq!(registry.untrusted_reason("USDC", Some(CIRCLE)), None);
+        assert_eq!(registry.untrusted_reason("usdc", Some(CIRCLE)), None);
+        assert_eq!(registry.untrusted_reason("XLM", None), None);
+    }
+
+    #[test]
+    fn test_spoofed_issuer_and_unlisted_code_are_untrusted() {
+        let registry = trust(&[("USDC", CIRCLE)]);
+        let spoofed = registry.untrusted_reason("USDC", Some(SPOOF)).unwrap();
+        assert!(spoofed.contains("not trusted for USDC"), "{spoofed}");
+
+        let unlisted = registry.untrusted_reason("EURC", Some(CIRCLE)).unwrap();
+        assert!(
+            unlisted.contains("not in the trusted registry"),
+            "{unlisted}"
+        );
+    }
+}
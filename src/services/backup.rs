@@ -1,9 +1,14 @@
+use crate::adapters::LocalObjectStore;
+use crate::ports::ObjectStore;
+use crate::services::backup_keys::{self, KeyEnvelope, MasterKeyring};
+use crate::services::backup_pitr::{self, BaseBackupMetadata, PitrReport, PitrRequest};
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::cmp::Reverse;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::Arc;
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
 
@@ -23,23 +28,135 @@ pub struct BackupMetadata {
     pub compressed: bool,
     pub encrypted: bool,
     pub checksum: String,
+    /// Data key the archive is encrypted with, wrapped by a master key.
+    /// Absent for unencrypted backups and those encrypted directly with the
+    /// master key before envelope encryption.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_envelope: Option<KeyEnvelope>,
 }
 
+/// Outcome of [`BackupService::rewrap_keys`].
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct RewrapReport {
+    /// Backups whose data key moved to the current master key.
+    pub rewrapped: Vec<String>,
+    /// Backups already wrapped by the current master key.
+    pub current: usize,
+    /// Encrypted backups without an envelope; they still need the master key
+    /// they were written with.
+    pub legacy: Vec<String>,
+}
+
+/// Environment variable handing the archive passphrase to `openssl`, which
+/// keeps it out of the process list.
+const PASSPHRASE_ENV: &str = "SYNAPSE_BACKUP_PASSPHRASE";
+
+pub use crate::adapters::backup_dir;
+
+/// Whether `name` looks like a backup written by [`BackupService`]. Only bare
+/// file names pass, so the result is safe as an object key or local path.
+pub fn is_backup_file_name(name: &str) -> bool {
+    name.starts_with("backup_")
+        && (name.ends_with(".sql.gz") || name.ends_with(".sql.gz.enc"))
+        && !name.contains("..")
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+/// Key of the metadata object stored alongside backup `filename`.
+fn meta_key(filename: &str) -> String {
+    Path::new(filename)
+        .with_extension("meta")
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Backups described by the `.meta` objects in `store`, newest first.
+pub async fn list_backups_from(store: &dyn ObjectStore) -> Result<Vec<BackupMetadata>> {
+    let mut backups = Vec::new();
+
+    let objects = store
+        .list("backup_")
+        .await
+        .context("Failed to list backups")?;
+
+    for object in objects {
+        if object.key.ends_with(".meta") {
+            if let Ok(metadata) = load_metadata(store, &object.key).await {
+                backups.push(metadata);
+            }
+        }
+    }
+
+    // Sort by timestamp descending
+    backups.sort_by_key(|b| Reverse(b.timestamp));
+
+    Ok(backups)
+}
+
+async fn load_metadata(store: &dyn ObjectStore, key: &str) -> Result<BackupMetadata> {
+    let json = store
+        .get_bytes(key)
+        .await
+        .context("Failed to read metadata file")?;
+
+    let metadata: BackupMetadata =
+        serde_json::from_slice(&json).context("Failed to parse metadata")?;
+
+    Ok(metadata)
+}
+
+/// SHA-256 of the file at `path`, as lowercase hex.
+pub(crate) async fn sha256sum(path: &Path) -> Result<String> {
+    let output = Command::new("sha256sum")
+        .arg(path)
+        .output()
+        .context("Failed to execute sha256sum")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("sha256sum failed: {stderr}");
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let checksum = stdout
+        .split_whitespace()
+        .next()
+        .context("Failed to parse checksum")?
+        .to_string();
+
+    Ok(checksum)
+}
+
+/// Creates, restores and prunes database backups. Finished backups and their
+/// `.meta` sidecars live in an [`ObjectStore`]; `backup_dir` is scratch space
+/// for the dump, compression and encryption steps.
 pub struct BackupService {
     database_url: String,
     backup_dir: PathBuf,
-    encryption_key: Option<String>,
+    keyring: Option<MasterKeyring>,
+    store: Arc<dyn ObjectStore>,
 }
 
 impl BackupService {
-    pub fn new(database_url: String, backup_dir: PathBuf, encryption_key: Option<String>) -> Self {
+    /// Service keeping finished backups in `backup_dir` itself. Backups are
+    /// encrypted when a master `keyring` is given.
+    pub fn new(database_url: String, backup_dir: PathBuf, keyring: Option<MasterKeyring>) -> Self {
         Self {
             database_url,
+            store: Arc::new(LocalObjectStore::new(backup_dir.clone())),
             backup_dir,
-            encryption_key,
+            keyring,
         }
     }
 
+    /// Keep finished backups in `store` instead of `backup_dir`.
+    pub fn with_store(mut self, store: Arc<dyn ObjectStore>) -> Self {
+        self.store = store;
+        self
+    }
+
     pub async fn create_backup(&self, backup_type: BackupType) -> Result<BackupMetadata> {
         // Ensure backup directory exists
         fs::create_dir_all(&self.backup_dir)
@@ -48,7 +165,6 @@ impl BackupService {
 
         let timestamp = Utc::now();
         let filename = self.generate_filename(backup_type, timestamp);
-        let backup_path = self.backup_dir.join(&filename);
         let temp_path = self.backup_dir.join(format!("{filename}.tmp"));
 
         // Run pg_dump
@@ -60,34 +176,38 @@ impl BackupService {
         let compressed_path = self.compress_backup(&temp_path).await?;
 
         // Encrypt if key is provided
-        let final_path = if self.encryption_key.is_some() {
+        let (final_path, key_envelope) = if self.keyring.is_some() {
             tracing::info!("Encrypting backup");
-            self.encrypt_backup(&compressed_path).await?
+            let (path, envelope) = self.encrypt_backup(&compressed_path).await?;
+            (path, Some(envelope))
         } else {
-            compressed_path
+            (compressed_path, None)
         };
 
-        // Move to final location
-        fs::rename(&final_path, &backup_path)
-            .await
-            .context("Failed to move backup to final location")?;
-
         // Calculate checksum
-        let checksum = self.calculate_checksum(&backup_path).await?;
+        let checksum = sha256sum(&final_path).await?;
 
         // Get file size
-        let metadata = fs::metadata(&backup_path)
+        let metadata = fs::metadata(&final_path)
             .await
             .context("Failed to get backup file metadata")?;
 
+        // Upload to the object store, then drop the local working copy
+        let stored = self.store.put_file(&filename, &final_path).await;
+        fs::remove_file(&final_path)
+            .await
+            .context("Failed to remove local backup copy")?;
+        stored.context("Failed to store backup")?;
+
         let backup_metadata = BackupMetadata {
             filename,
             backup_type,
             timestamp,
             size_bytes: metadata.len(),
             compressed: true,
-            encrypted: self.encryption_key.is_some(),
+            encrypted: key_envelope.is_some(),
             checksum,
+            key_envelope,
         };
 
         // Save metadata
@@ -99,56 +219,42 @@ impl BackupService {
     }
 
     pub async fn list_backups(&self) -> Result<Vec<BackupMetadata>> {
-        let mut backups = Vec::new();
-
-        if !self.backup_dir.exists() {
-            return Ok(backups);
-        }
-
-        let mut entries = fs::read_dir(&self.backup_dir)
-            .await
-            .context("Failed to read backup directory")?;
-
-        while let Some(entry) = entries.next_entry().await? {
-            let path = entry.path();
-            if path.extension().and_then(|s| s.to_str()) == Some("meta") {
-                if let Ok(metadata) = self.load_metadata(&path).await {
-                    backups.push(metadata);
-                }
-            }
-        }
-
-        // Sort by timestamp descending
-        backups.sort_by_key(|b| Reverse(b.timestamp));
-
-        Ok(backups)
+        list_backups_from(self.store.as_ref()).await
     }
 
     pub async fn restore_backup(&self, filename: &str) -> Result<()> {
-        let backup_path = self.backup_dir.join(filename);
-
-        if !backup_path.exists() {
+        if !is_backup_file_name(filename) {
             anyhow::bail!("Backup file not found: {filename}");
         }
 
-        // Load and verify metadata
-        let meta_path = backup_path.with_extension("meta");
-        let metadata = self.load_metadata(&meta_path).await?;
-
-        tracing::info!("Verifying backup integrity");
-        self.verify_backup(&backup_path, &metadata).await?;
+        // Load metadata
+        let metadata = load_metadata(self.store.as_ref(), &meta_key(filename))
+            .await
+            .with_context(|| format!("Backup file not found: {filename}"))?;
 
         let temp_dir = self.backup_dir.join("restore_temp");
         fs::create_dir_all(&temp_dir)
             .await
             .context("Failed to create temp directory")?;
 
+        // Download and verify
+        let backup_path = temp_dir.join(filename);
+        self.store
+            .get_file(filename, &backup_path)
+            .await
+            .with_context(|| format!("Backup file not found: {filename}"))?;
+
+        tracing::info!("Verifying backup integrity");
+        self.verify_backup(&backup_path, &metadata).await?;
+
         let mut current_path = backup_path.clone();
 
         // Decrypt if encrypted
         if metadata.encrypted {
             tracing::info!("Decrypting backup");
-            current_path = self.decrypt_backup(&current_path, &temp_dir).await?;
+            current_path = self
+                .decrypt_backup(&current_path, &temp_dir, &metadata)
+                .await?;
         }
 
         // Decompress
@@ -196,28 +302,79 @@ impl BackupService {
         Ok(())
     }
 
+    /// Re-wrap every backup's data key with the current master key, so
+    /// retired master keys can be dropped. Only `.meta` objects are rewritten;
+    /// archives and their checksums are untouched.
+    pub async fn rewrap_keys(&self) -> Result<RewrapReport> {
+        let keyring = self
+            .keyring
+            .as_ref()
+            .context("BACKUP_ENCRYPTION_KEY is not set")?;
+        let mut report = RewrapReport::default();
+
+        for mut backup in self.list_backups().await? {
+            let Some(envelope) = &backup.key_envelope else {
+                if backup.encrypted {
+                    report.legacy.push(backup.filename);
+                }
+                continue;
+            };
+            let rewrapped = keyring
+                .rewrap(envelope)
+                .with_context(|| format!("Failed to re-wrap key of {}", backup.filename))?;
+            match rewrapped {
+                Some(envelope) => {
+                    backup.key_envelope = Some(envelope);
+                    self.save_metadata(&backup).await?;
+                    tracing::info!(
+                        backup = %backup.filename,
+                        key_id = keyring.current_id(),
+                        "Re-wrapped backup data key"
+                    );
+                    report.rewrapped.push(backup.filename);
+                }
+                None => report.current += 1,
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Take a physical base backup for point-in-time recovery.
+    pub async fn create_base_backup(&self) -> Result<BaseBackupMetadata> {
+        backup_pitr::create_base_backup(self.store.as_ref(), &self.database_url, &self.backup_dir)
+            .await
+    }
+
+    /// Restore the latest base backup plus archived WAL up to
+    /// `request.target_time` into a new cluster, verify it and report RTO/RPO.
+    pub async fn restore_point_in_time(&self, request: &PitrRequest) -> Result<PitrReport> {
+        backup_pitr::restore(
+            self.store.as_ref(),
+            &self.database_url,
+            &self.backup_dir,
+            request,
+        )
+        .await
+    }
+
     async fn apply_retention(&self, backups: &[BackupMetadata], keep_count: usize) -> Result<()> {
         if backups.len() <= keep_count {
             return Ok(());
         }
 
         for backup in &backups[keep_count..] {
-            let backup_path = self.backup_dir.join(&backup.filename);
-            let meta_path = backup_path.with_extension("meta");
-
             tracing::info!("Removing old backup: {}", backup.filename);
 
-            if backup_path.exists() {
-                fs::remove_file(&backup_path)
-                    .await
-                    .context("Failed to remove backup file")?;
-            }
+            self.store
+                .delete(&backup.filename)
+                .await
+                .context("Failed to remove backup file")?;
 
-            if meta_path.exists() {
-                fs::remove_file(&meta_path)
-                    .await
-                    .context("Failed to remove metadata file")?;
-            }
+            self.store
+                .delete(&meta_key(&backup.filename))
+                .await
+                .context("Failed to remove metadata file")?;
         }
 
         Ok(())
@@ -312,11 +469,15 @@ impl BackupService {
         Ok(output_path)
     }
 
-    async fn encrypt_backup(&self, input_path: &Path) -> Result<PathBuf> {
-        let key = self
-            .encryption_key
+    /// Encrypt with a fresh data key and return it wrapped by the current
+    /// master key.
+    async fn encrypt_backup(&self, input_path: &Path) -> Result<(PathBuf, KeyEnvelope)> {
+        let keyring = self
+            .keyring
             .as_ref()
             .context("Encryption key not provided")?;
+        let data_key = backup_keys::generate_data_key();
+        let envelope = keyring.wrap(&data_key);
 
         let output_path = input_path.with_extension("sql.gz.enc");
 
@@ -330,7 +491,8 @@ impl BackupService {
             .arg("-out")
             .arg(&output_path)
             .arg("-pass")
-            .arg(format!("pass:{key}"))
+            .arg(format!("env:{PASSPHRASE_ENV}"))
+            .env(PASSPHRASE_ENV, hex::encode(data_key))
             .output()
             .context("Failed to execute openssl")?;
 
@@ -344,62 +506,56 @@ impl BackupService {
             .await
             .context("Failed to remove unencrypted file")?;
 
-        Ok(output_path)
+        Ok((output_path, envelope))
     }
 
-    async fn decrypt_backup(&self, input_path: &Path, temp_dir: &Path) -> Result<PathBuf> {
-        let key = self
-            .encryption_key
+    async fn decrypt_backup(
+        &self,
+        input_path: &Path,
+        temp_dir: &Path,
+        metadata: &BackupMetadata,
+    ) -> Result<PathBuf> {
+        let keyring = self
+            .keyring
             .as_ref()
             .context("Encryption key not provided")?;
+        let passphrases = match &metadata.key_envelope {
+            Some(envelope) => vec![hex::encode(keyring.unwrap(envelope)?)],
+            // Written before envelope encryption with a master key itself;
+            // the metadata does not say which one.
+            None => keyring.secrets().map(str::to_string).collect(),
+        };
 
         let output_path = temp_dir.join("decrypted.sql.gz");
 
-        let output = Command::new("openssl")
-            .arg("enc")
-            .arg("-aes-256-cbc")
-            .arg("-d")
-            .arg("-pbkdf2")
-            .arg("-in")
-            .arg(input_path)
-            .arg("-out")
-            .arg(&output_path)
-            .arg("-pass")
-            .arg(format!("pass:{key}"))
-            .output()
-            .context("Failed to execute openssl")?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("openssl decryption failed: {stderr}");
-        }
-
-        Ok(output_path)
-    }
-
-    async fn calculate_checksum(&self, path: &Path) -> Result<String> {
-        let output = Command::new("sha256sum")
-            .arg(path)
-            .output()
-            .context("Failed to execute sha256sum")?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("sha256sum failed: {stderr}");
+        let mut last_error = String::new();
+        for passphrase in passphrases {
+            let output = Command::new("openssl")
+                .arg("enc")
+                .arg("-aes-256-cbc")
+                .arg("-d")
+                .arg("-pbkdf2")
+                .arg("-in")
+                .arg(input_path)
+                .arg("-out")
+                .arg(&output_path)
+                .arg("-pass")
+                .arg(format!("env:{PASSPHRASE_ENV}"))
+                .env(PASSPHRASE_ENV, passphrase)
+                .output()
+                .context("Failed to execute openssl")?;
+
+            if output.status.success() {
+                return Ok(output_path);
+            }
+            last_error = String::from_utf8_lossy(&output.stderr).into_owned();
         }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let checksum = stdout
-            .split_whitespace()
-            .next()
-            .context("Failed to parse checksum")?
-            .to_string();
-
-        Ok(checksum)
+        anyhow::bail!("openssl decryption failed: {last_error}")
     }
 
     async fn verify_backup(&self, path: &Path, metadata: &BackupMetadata) -> Result<()> {
-        let checksum = self.calculate_checksum(path).await?;
+        let checksum = sha256sum(path).await?;
 
         if checksum != metadata.checksum {
             anyhow::bail!(
@@ -420,7 +576,7 @@ impl BackupService {
         };
 
         let date_str = timestamp.format("%Y%m%d_%H%M%S");
-        let extension = if self.encryption_key.is_some() {
+        let extension = if self.keyring.is_some() {
             "sql.gz.enc"
         } else {
             "sql.gz"
@@ -430,29 +586,165 @@ impl BackupService {
     }
 
     async fn save_metadata(&self, metadata: &BackupMetadata) -> Result<()> {
-        let meta_path = self
-            .backup_dir
-            .join(&metadata.filename)
-            .with_extension("meta");
-
         let json =
             serde_json::to_string_pretty(metadata).context("Failed to serialize metadata")?;
 
-        fs::write(&meta_path, json)
+        self.store
+            .put_bytes(&meta_key(&metadata.filename), json.into())
             .await
             .context("Failed to write metadata file")?;
 
         Ok(())
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_backup_file_name() {
+        assert!(is_backup_file_name("backup_daily_20260601_000000.sql.gz"));
+        assert!(is_backup_file_name(
+            "backup_hourly_20260601_130000.sql.gz.enc"
+        ));
+        assert!(!is_backup_file_name("backup_daily_20260601_000000.meta"));
+        assert!(!is_backup_file_name("../backup_daily.sql.gz"));
+        assert!(!is_backup_file_name("backup_/etc/passwd.sql.gz"));
+        assert!(!is_backup_file_name("exports.sql.gz"));
+    }
+
+    fn sample_metadata(filename: &str, hours_ago: i64) -> BackupMetadata {
+        BackupMetadata {
+            filename: filename.to_string(),
+            backup_type: BackupType::Hourly,
+            timestamp: Utc::now() - chrono::Duration::hours(hours_ago),
+            size_bytes: 4,
+            compressed: true,
+            encrypted: false,
+            checksum: "abc".to_string(),
+            key_envelope: None,
+        }
+    }
+
+    async fn store_backup(store: &dyn ObjectStore, metadata: &BackupMetadata) {
+        store
+            .put_bytes(&metadata.filename, bytes::Bytes::from_static(b"dump"))
+            .await
+            .unwrap();
+        store
+            .put_bytes(
+                &meta_key(&metadata.filename),
+                serde_json::to_vec(metadata).unwrap().into(),
+            )
+            .await
+            .unwrap();
+    }
 
-    async fn load_metadata(&self, path: &Path) -> Result<BackupMetadata> {
-        let json = fs::read_to_string(path)
+    #[tokio::test]
+    async fn test_retention_prunes_oldest_backups_from_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = BackupService::new(
+            "postgres://unused".to_string(),
+            dir.path().to_path_buf(),
+            None,
+        );
+        let store = service.store.clone();
+        store_backup(
+            store.as_ref(),
+            &sample_metadata("backup_hourly_20260601_100000.sql.gz", 2),
+        )
+        .await;
+        store_backup(
+            store.as_ref(),
+            &sample_metadata("backup_hourly_20260601_110000.sql.gz", 1),
+        )
+        .await;
+        store_backup(
+            store.as_ref(),
+            &sample_metadata("backup_hourly_20260601_090000.sql.gz", 3),
+        )
+        .await;
+        store
+            .put_bytes("exports/1.csv", bytes::Bytes::from_static(b"id\n"))
             .await
-            .context("Failed to read metadata file")?;
+            .unwrap();
+
+        let backups = service.list_backups().await.unwrap();
+        assert_eq!(
+            backups
+                .iter()
+                .map(|b| b.filename.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "backup_hourly_20260601_110000.sql.gz",
+                "backup_hourly_20260601_100000.sql.gz",
+                "backup_hourly_20260601_090000.sql.gz",
+            ]
+        );
+
+        service.apply_retention(&backups, 1).await.unwrap();
+
+        let keys: Vec<String> = store
+            .list("")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|o| o.key)
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                "backup_hourly_20260601_110000.sql.gz",
+                "backup_hourly_20260601_110000.sql.meta",
+                "exports/1.csv",
+            ]
+        );
+    }
 
-        let metadata: BackupMetadata =
-            serde_json::from_str(&json).context("Failed to parse metadata")?;
+    #[tokio::test]
+    async fn test_rewrap_keys_moves_envelopes_to_current_master_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_key = backup_keys::generate_data_key();
+        let old = MasterKeyring::new("1", "old-secret");
+
+        let mut wrapped = sample_metadata("backup_hourly_20260601_100000.sql.gz.enc", 2);
+        wrapped.encrypted = true;
+        wrapped.key_envelope = Some(old.wrap(&data_key));
+        let mut legacy = sample_metadata("backup_hourly_20260601_090000.sql.gz.enc", 3);
+        legacy.encrypted = true;
+        let plain = sample_metadata("backup_hourly_20260601_080000.sql.gz", 4);
+
+        let keyring = MasterKeyring::new("2", "new-secret").with_previous("1", "old-secret");
+        let service = BackupService::new(
+            "postgres://unused".to_string(),
+            dir.path().to_path_buf(),
+            Some(keyring),
+        );
+        for metadata in [&wrapped, &legacy, &plain] {
+            store_backup(service.store.as_ref(), metadata).await;
+        }
+
+        let report = service.rewrap_keys().await.unwrap();
+        assert_eq!(report.rewrapped, vec![wrapped.filename.clone()]);
+        assert_eq!(report.legacy, vec![legacy.filename.clone()]);
+        assert_eq!(report.current, 0);
 
-        Ok(metadata)
+        let stored = load_metadata(service.store.as_ref(), &meta_key(&wrapped.filename))
+            .await
+            .unwrap();
+        let envelope = stored.key_envelope.unwrap();
+        assert_eq!(envelope.key_id, "2");
+        assert_eq!(
+            MasterKeyring::new("2", "new-secret")
+                .unwrap(&envelope)
+                .unwrap(),
+            data_key
+        );
+        assert_eq!(stored.checksum, wrapped.checksum);
+
+        let again = service.rewrap_keys().await.unwrap();
+        assert!(again.rewrapped.is_empty());
+        assert_eq!(again.current, 1);
     }
 }
@@ -0,0 +1,285 @@
+//! Envelope encryption keys for database backups.
+//!
+//! Every encrypted backup gets its own random 256-bit data key, which is what
+//! `openssl enc` encrypts the archive with. The data key is stored in the
+//! backup's `.meta` object wrapped by a master key, tagged with that key's id.
+//! Rotating the master key therefore only means re-wrapping the small data
+//! keys ([`MasterKeyring::rewrap`]); archives are never re-encrypted.
+//!
+//! The current master key is `BACKUP_ENCRYPTION_KEY` with id
+//! `BACKUP_ENCRYPTION_KEY_ID` (default `1`). Retired keys stay readable while
+//! listed in `BACKUP_PREVIOUS_ENCRYPTION_KEYS` as `id:key,id:key`.
+//!
+//! Wrapping uses HMAC-SHA256 as a PRF: a per-envelope random nonce derives a
+//! one-time pad that is XORed with the data key, and a second derived key
+//! authenticates the key id and ciphertext, so a wrong or tampered master key
+//! is detected before any archive is touched.
+
+use crate::config::Config;
+use anyhow::{Context, Result};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Size of a backup data key, in bytes.
+pub const DATA_KEY_LEN: usize = 32;
+
+/// A data key wrapped by the master key `key_id`. Binary fields are hex.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyEnvelope {
+    pub key_id: String,
+    pub nonce: String,
+    pub wrapped_key: String,
+    pub tag: String,
+}
+
+struct MasterKey {
+    id: String,
+    secret: String,
+}
+
+/// The current master key plus retired ones still accepted for unwrapping.
+pub struct MasterKeyring {
+    current: MasterKey,
+    previous: Vec<MasterKey>,
+}
+
+/// Fresh random data key for one backup.
+pub fn generate_data_key() -> [u8; DATA_KEY_LEN] {
+    let mut key = [0u8; DATA_KEY_LEN];
+    rand::thread_rng().fill_bytes(&mut key);
+    key
+}
+
+fn mac(secret: &[u8]) -> HmacSha256 {
+    HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any length")
+}
+
+/// HMAC-SHA256 of `label || 0x00 || nonce` under `secret`.
+fn derive(secret: &[u8], label: &str, nonce: &[u8]) -> [u8; 32] {
+    let mut m = mac(secret);
+    m.update(label.as_bytes());
+    m.update(&[0]);
+    m.update(nonce);
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&m.finalize().into_bytes());
+    out
+}
+
+fn tag_mac(master: &MasterKey, nonce: &[u8], wrapped: &[u8]) -> HmacSha256 {
+    let mut m = mac(&derive(
+        master.secret.as_bytes(),
+        "backup-key-wrap/mac",
+        nonce,
+    ));
+    m.update(master.id.as_bytes());
+    m.update(&[0]);
+    m.update(wrapped);
+    m
+}
+
+fn xor_pad(master: &MasterKey, nonce: &[u8], input: &[u8]) -> Vec<u8> {
+    let pad = derive(master.secret.as_bytes(), "backup-key-wrap/enc", nonce);
+    input.iter().zip(pad.iter()).map(|(a, b)| a ^ b).collect()
+}
+
+/// Parse `id:key,id:key`. Ids may not contain `:`; keys may.
+pub fn parse_previous_keys(raw: &str) -> Result<Vec<(String, String)>> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (id, secret) = entry
+                .split_once(':')
+                .filter(|(id, secret)| !id.is_empty() && !secret.is_empty())
+                .context("BACKUP_PREVIOUS_ENCRYPTION_KEYS entries must look like id:key")?;
+            Ok((id.to_string(), secret.to_string()))
+        })
+        .collect()
+}
+
+impl MasterKeyring {
+    pub fn new(current_id: impl Into<String>, current_secret: impl Into<String>) -> Self {
+        Self {
+            current: MasterKey {
+                id: current_id.into(),
+                secret: current_secret.into(),
+            },
+            previous: Vec::new(),
+        }
+    }
+
+    /// Also accept the retired master key `id` when unwrapping.
+    pub fn with_previous(mut self, id: impl Into<String>, secret: impl Into<String>) -> Self {
+        self.previous.push(MasterKey {
+            id: id.into(),
+            secret: secret.into(),
+        });
+        self
+    }
+
+    /// Keyring from the backup encryption settings; `None` when
+    /// `BACKUP_ENCRYPTION_KEY` is unset and backups are stored unencrypted.
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        let Some(secret) = config.backup_encryption_key.as_deref() else {
+            return Ok(None);
+        };
+        let mut keyring = Self::new(config.backup_encryption_key_id.clone(), secret);
+        if let Some(raw) = config.backup_previous_encryption_keys.as_deref() {
+            for (id, secret) in parse_previous_keys(raw)? {
+                if id == keyring.current.id {
+                    anyhow::bail!("Master key id '{id}' is both current and previous");
+                }
+                keyring = keyring.with_previous(id, secret);
+            }
+        }
+        Ok(Some(keyring))
+    }
+
+    pub fn current_id(&self) -> &str {
+        &self.current.id
+    }
+
+    /// Every master key, current first. Backups written before envelope
+    /// encryption used one of them directly as the archive passphrase.
+    pub(crate) fn secrets(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(&self.current)
+            .chain(&self.previous)
+            .map(|k| k.secret.as_str())
+    }
+
+    fn key(&self, id: &str) -> Option<&MasterKey> {
+        std::iter::once(&self.current)
+            .chain(&self.previous)
+            .find(|k| k.id == id)
+    }
+
+    /// Wrap `data_key` with the current master key.
+    pub fn wrap(&self, data_key: &[u8; DATA_KEY_LEN]) -> KeyEnvelope {
+        let mut nonce = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let wrapped = xor_pad(&self.current, &nonce, data_key);
+        let tag = tag_mac(&self.current, &nonce, &wrapped)
+            .finalize()
+            .into_bytes();
+        KeyEnvelope {
+            key_id: self.current.id.clone(),
+            nonce: hex::encode(nonce),
+            wrapped_key: hex::encode(wrapped),
+            tag: hex::encode(tag),
+        }
+    }
+
+    /// Recover the data key from `envelope`, checking its tag.
+    pub fn unwrap(&self, envelope: &KeyEnvelope) -> Result<[u8; DATA_KEY_LEN]> {
+        let master = self.key(&envelope.key_id).with_context(|| {
+            format!(
+                "Backup data key is wrapped by master key '{}', which is not configured",
+                envelope.key_id
+            )
+        })?;
+        let nonce = hex::decode(&envelope.nonce).context("Invalid envelope nonce")?;
+        let wrapped = hex::decode(&envelope.wrapped_key).context("Invalid wrapped key")?;
+        let tag = hex::decode(&envelope.tag).context("Invalid envelope tag")?;
+        if wrapped.len() != DATA_KEY_LEN {
+            anyhow::bail!("Wrapped key has the wrong length");
+        }
+        tag_mac(master, &nonce, &wrapped)
+            .verify_slice(&tag)
+            .map_err(|_| {
+                anyhow::anyhow!(
+                    "Backup key envelope failed authentication with master key '{}'",
+                    envelope.key_id
+                )
+            })?;
+
+        let mut key = [0u8; DATA_KEY_LEN];
+        key.copy_from_slice(&xor_pad(master, &nonce, &wrapped));
+        Ok(key)
+    }
+
+    /// `envelope` re-wrapped with the current master key, or `None` if it
+    /// already uses it.
+    pub fn rewrap(&self, envelope: &KeyEnvelope) -> Result<Option<KeyEnvelope>> {
+        if envelope.key_id == self.current.id {
+            return Ok(None);
+        }
+        Ok(Some(self.wrap(&self.unwrap(envelope)?)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_round_trip() {
+        let keyring = MasterKeyring::new("1", "master-secret");
+        let data_key = generate_data_key();
+        let envelope = keyring.wrap(&data_key);
+
+        assert_eq!(envelope.key_id, "1");
+        assert_ne!(envelope.wrapped_key, hex::encode(data_key));
+        assert_eq!(keyring.unwrap(&envelope).unwrap(), data_key);
+        // Fresh nonce per envelope.
+        assert_ne!(keyring.wrap(&data_key), envelope);
+    }
+
+    #[test]
+    fn test_unwrap_detects_wrong_key_and_tampering() {
+        let keyring = MasterKeyring::new("1", "master-secret");
+        let envelope = keyring.wrap(&generate_data_key());
+
+        let wrong = MasterKeyring::new("1", "other-secret");
+        assert!(wrong.unwrap(&envelope).is_err());
+
+        let unknown = MasterKeyring::new("2", "master-secret");
+        assert!(unknown.unwrap(&envelope).is_err());
+
+        let mut tampered = envelope.clone();
+        let mut wrapped = hex::decode(&tampered.wrapped_key).unwrap();
+        wrapped[0] ^= 0xff;
+        tampered.wrapped_key = hex::encode(wrapped);
+        assert!(keyring.unwrap(&tampered).is_err());
+
+        let mut relabelled = envelope;
+        relabelled.key_id = "2".to_string();
+        let both = MasterKeyring::new("2", "master-secret").with_previous("1", "master-secret");
+        assert!(both.unwrap(&relabelled).is_err());
+    }
+
+    #[test]
+    fn test_rewrap_moves_envelope_to_current_key() {
+        let data_key = generate_data_key();
+        let old = MasterKeyring::new("1", "old-secret");
+        let envelope = old.wrap(&data_key);
+
+        let rotated = MasterKeyring::new("2", "new-secret").with_previous("1", "old-secret");
+        assert_eq!(rotated.unwrap(&envelope).unwrap(), data_key);
+
+        let rewrapped = rotated.rewrap(&envelope).unwrap().unwrap();
+        assert_eq!(rewrapped.key_id, "2");
+        assert!(rotated.rewrap(&rewrapped).unwrap().is_none());
+
+        // Once the old key is retired the re-wrapped envelope still opens.
+        let retired = MasterKeyring::new("2", "new-secret");
+        assert_eq!(retired.unwrap(&rewrapped).unwrap(), data_key);
+        assert!(retired.unwrap(&envelope).is_err());
+    }
+
+    #[test]
+    fn test_parse_previous_keys() {
+        assert_eq!(
+            parse_previous_keys("1:abc, 0:x:y,").unwrap(),
+            vec![
+                ("1".to_string(), "abc".to_string()),
+                ("0".to_string(), "x:y".to_string())
+            ]
+        );
+        assert!(parse_previous_keys("nokey").is_err());
+        assert!(parse_previous_keys(":abc").is_err());
+    }
+}
@@ -0,0 +1,627 @@
+//! Point-in-time recovery (PITR) from a physical base backup plus archived WAL,
+//! used for disaster recovery drills.
+//!
+//! Base backups are taken with `pg_basebackup` (tar format, gzipped, including
+//! the WAL needed for consistency) and stored as `base/<label>.tar.gz` with a
+//! `base/<label>.json` sidecar. WAL segments are archived by PostgreSQL itself
+//! into the `wal/` prefix of the same object store via `archive_command`.
+//!
+//! [`restore`] picks the newest base backup finished before the target time,
+//! unpacks it into an empty data directory, fetches the archived WAL from the
+//! backup's start segment on, and starts a new cluster with `pg_ctl` set to
+//! replay up to the target and promote. It then runs [`VerificationCheck`]s
+//! against the restored cluster and reports RTO (wall-clock time of the whole
+//! restore) and RPO (gap between the target and the last replayed commit).
+
+use crate::ports::ObjectStore;
+use crate::services::backup::sha256sum;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Connection, PgConnection};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+
+/// Object store prefix of base backups.
+pub const BASE_PREFIX: &str = "base/";
+
+/// Object store prefix PostgreSQL's `archive_command` writes WAL to.
+pub const WAL_PREFIX: &str = "wal/";
+
+/// How often the restored cluster is polled while it replays WAL.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaseBackupMetadata {
+    pub label: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    /// First WAL segment replay needs, from the backup's `backup_label`.
+    pub start_wal_file: String,
+    pub size_bytes: u64,
+    pub checksum: String,
+}
+
+impl BaseBackupMetadata {
+    pub fn archive_key(&self) -> String {
+        format!("{BASE_PREFIX}{}.tar.gz", self.label)
+    }
+
+    fn meta_key(label: &str) -> String {
+        format!("{BASE_PREFIX}{label}.json")
+    }
+}
+
+/// What to restore and where.
+#[derive(Debug, Clone)]
+pub struct PitrRequest {
+    pub target_time: DateTime<Utc>,
+    /// Data directory of the new cluster; must be missing or empty.
+    pub data_dir: PathBuf,
+    /// Port the new cluster listens on.
+    pub port: u16,
+    /// Leave the restored cluster running after verification.
+    pub keep_running: bool,
+    /// Give up if WAL replay has not finished within this long.
+    pub recovery_timeout: Duration,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VerificationCheck {
+    pub name: &'static str,
+    pub passed: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PitrReport {
+    pub base_backup: String,
+    pub target_time: DateTime<Utc>,
+    /// Commit time of the last transaction replayed.
+    pub recovered_to: Option<DateTime<Utc>>,
+    pub wal_segments: usize,
+    pub data_dir: PathBuf,
+    pub port: u16,
+    /// Recovery time: from starting the restore to a verified cluster.
+    pub rto_secs: f64,
+    /// Recovery point: how far the last replayed commit is behind the target.
+    pub rpo_secs: Option<i64>,
+    pub checks: Vec<VerificationCheck>,
+}
+
+impl PitrReport {
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|c| c.passed)
+    }
+}
+
+/// First WAL file named by a `backup_label`
+/// (`START WAL LOCATION: 0/2000028 (file 000000010000000000000002)`).
+pub fn parse_backup_label(label: &str) -> Option<String> {
+    let line = label
+        .lines()
+        .find(|l| l.starts_with("START WAL LOCATION:"))?;
+    let file = line.split("(file ").nth(1)?.strip_suffix(')')?;
+    is_wal_file_name(file).then(|| file.to_string())
+}
+
+fn is_wal_file_name(name: &str) -> bool {
+    name.len() == 24 && name.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Newest base backup that finished at or before `target`.
+pub fn select_base_backup(
+    backups: &[BaseBackupMetadata],
+    target: DateTime<Utc>,
+) -> Option<&BaseBackupMetadata> {
+    backups
+        .iter()
+        .filter(|b| b.finished_at <= target)
+        .max_by_key(|b| b.finished_at)
+}
+
+/// Archived WAL keys replay may ask for: timeline history files and every
+/// segment from `start_wal_file` on.
+pub fn wal_keys_to_restore(keys: &[String], start_wal_file: &str) -> Vec<String> {
+    keys.iter()
+        .filter(|key| {
+            key.strip_prefix(WAL_PREFIX).is_some_and(|name| {
+                name.ends_with(".history") || (is_wal_file_name(name) && name >= start_wal_file)
+            })
+        })
+        .cloned()
+        .collect()
+}
+
+/// Settings appended to the restored cluster's `postgresql.auto.conf`.
+/// Archiving is switched off so the drill never writes into the production
+/// WAL archive.
+pub fn recovery_settings(wal_dir: &Path, target: DateTime<Utc>, port: u16) -> Result<String> {
+    let wal_dir = wal_dir.display().to_string();
+    if wal_dir.contains('\'') || wal_dir.contains('"') {
+        anyhow::bail!("WAL directory path may not contain quotes: {wal_dir}");
+    }
+    Ok(format!(
+        "\n# Point-in-time restore\n\
+         restore_command = 'cp \"{wal_dir}/%f\" \"%p\"'\n\
+         recovery_target_time = '{}'\n\
+         recovery_target_action = 'promote'\n\
+         archive_mode = 'off'\n\
+         port = {port}\n",
+        target.format("%Y-%m-%d %H:%M:%S%.6f+00")
+    ))
+}
+
+/// `database_url` pointed at the restored cluster on localhost:`port`. A
+/// physical restore keeps the source's roles and passwords.
+pub fn restored_database_url(database_url: &str, port: u16) -> Result<String> {
+    let mut url = url::Url::parse(database_url).context("Invalid DATABASE_URL")?;
+    url.set_host(Some("localhost"))
+        .context("Invalid DATABASE_URL host")?;
+    url.set_port(Some(port))
+        .map_err(|_| anyhow::anyhow!("DATABASE_URL cannot carry a port"))?;
+    Ok(url.to_string())
+}
+
+fn run(command: &mut Command, what: &str) -> Result<std::process::Output> {
+    let output = command
+        .output()
+        .with_context(|| format!("Failed to execute {what}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("{what} failed: {stderr}");
+    }
+    Ok(output)
+}
+
+/// Base backups in `store`, newest first.
+pub async fn list_base_backups(store: &dyn ObjectStore) -> Result<Vec<BaseBackupMetadata>> {
+    let mut backups = Vec::new();
+    for object in store
+        .list(BASE_PREFIX)
+        .await
+        .context("Failed to list base backups")?
+    {
+        if object.key.ends_with(".json") {
+            let json = store.get_bytes(&object.key).await?;
+            if let Ok(metadata) = serde_json::from_slice::<BaseBackupMetadata>(&json) {
+                backups.push(metadata);
+            }
+        }
+    }
+    backups.sort_by_key(|b| std::cmp::Reverse(b.finished_at));
+    Ok(backups)
+}
+
+/// Take a base backup of `database_url` with `pg_basebackup`, staging it in
+/// `work_dir`, and store it.
+pub async fn create_base_backup(
+    store: &dyn ObjectStore,
+    database_url: &str,
+    work_dir: &Path,
+) -> Result<BaseBackupMetadata> {
+    let started_at = Utc::now();
+    let label = format!("base_{}", started_at.format("%Y%m%d_%H%M%S"));
+    let staging = work_dir.join(format!("{label}.tmp"));
+    fs::create_dir_all(&staging)
+        .await
+        .context("Failed to create base backup directory")?;
+
+    tracing::info!("Running pg_basebackup for {label}");
+    run(
+        Command::new("pg_basebackup")
+            .arg(format!("--dbname={database_url}"))
+            .arg("--pgdata")
+            .arg(&staging)
+            .arg("--format=tar")
+            .arg("--gzip")
+            .arg("--wal-method=fetch")
+            .arg("--checkpoint=fast")
+            .arg(format!("--label={label}")),
+        "pg_basebackup",
+    )?;
+
+    let archive = staging.join("base.tar.gz");
+    let backup_label = run(
+        Command::new("tar")
+            .arg("-xzOf")
+            .arg(&archive)
+            .arg("backup_label"),
+        "tar",
+    )?;
+    let start_wal_file = parse_backup_label(&String::from_utf8_lossy(&backup_label.stdout))
+        .context("Base backup has no readable backup_label")?;
+
+    let metadata = BaseBackupMetadata {
+        label,
+        started_at,
+        finished_at: Utc::now(),
+        start_wal_file,
+        size_bytes: fs::metadata(&archive).await?.len(),
+        checksum: sha256sum(&archive).await?,
+    };
+    store
+        .put_file(&metadata.archive_key(), &archive)
+        .await
+        .context("Failed to store base backup")?;
+    store
+        .put_bytes(
+            &BaseBackupMetadata::meta_key(&metadata.label),
+            serde_json::to_vec_pretty(&metadata)?.into(),
+        )
+        .await
+        .context("Failed to store base backup metadata")?;
+    fs::remove_dir_all(&staging)
+        .await
+        .context("Failed to remove base backup staging directory")?;
+
+    tracing::info!(
+        label = %metadata.label,
+        start_wal_file = %metadata.start_wal_file,
+        "Base backup created"
+    );
+    Ok(metadata)
+}
+
+async fn ensure_empty_dir(dir: &Path) -> Result<()> {
+    match fs::read_dir(dir).await {
+        Ok(mut entries) => {
+            if entries.next_entry().await?.is_some() {
+                anyhow::bail!("Data directory {} is not empty", dir.display());
+            }
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            fs::create_dir_all(dir)
+                .await
+                .context("Failed to create data directory")?;
+        }
+        Err(e) => return Err(e.into()),
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700)).await?;
+    }
+    Ok(())
+}
+
+fn pg_ctl(data_dir: &Path) -> Command {
+    let mut command = Command::new("pg_ctl");
+    command.arg("--pgdata").arg(data_dir);
+    command
+}
+
+async fn log_tail(log: &Path) -> String {
+    let text = fs::read_to_string(log).await.unwrap_or_default();
+    let lines: Vec<&str> = text.lines().collect();
+    lines[lines.len().saturating_sub(20)..].join("\n")
+}
+
+/// Wait until the cluster has finished replaying and been promoted.
+async fn wait_for_promotion(
+    url: &str,
+    data_dir: &Path,
+    log: &Path,
+    timeout: Duration,
+) -> Result<PgConnection> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Ok(mut conn) = PgConnection::connect(url).await {
+            let in_recovery: bool = sqlx::query_scalar("SELECT pg_is_in_recovery()")
+                .fetch_one(&mut conn)
+                .await?;
+            if !in_recovery {
+                return Ok(conn);
+            }
+        } else if !pg_ctl(data_dir).arg("status").output()?.status.success() {
+            anyhow::bail!("Restored cluster stopped:\n{}", log_tail(log).await);
+        }
+        if Instant::now() >= deadline {
+            anyhow::bail!(
+                "WAL replay did not finish within {}s:\n{}",
+                timeout.as_secs(),
+                log_tail(log).await
+            );
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+fn check(name: &'static str, result: Result<(bool, String), sqlx::Error>) -> VerificationCheck {
+    match result {
+        Ok((passed, detail)) => VerificationCheck {
+            name,
+            passed,
+            detail,
+        },
+        Err(e) => VerificationCheck {
+            name,
+            passed: false,
+            detail: e.to_string(),
+        },
+    }
+}
+
+/// Sanity checks on the restored cluster.
+async fn verify(conn: &mut PgConnection, target: DateTime<Utc>) -> Vec<VerificationCheck> {
+    let mut checks = Vec::new();
+
+    let migrations =
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM _sqlx_migrations WHERE success")
+            .fetch_one(&mut *conn)
+            .await
+            .map(|n| (n > 0, format!("{n} migrations applied")));
+    checks.push(check("schema_migrated", migrations));
+
+    let rows = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM transactions")
+        .fetch_one(&mut *conn)
+        .await
+        .map(|n| (true, format!("{n} transactions")));
+    checks.push(check("transactions_readable", rows));
+
+    let after_target =
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM transactions WHERE created_at > $1")
+            .bind(target)
+            .fetch_one(&mut *conn)
+            .await
+            .map(|n| (n == 0, format!("{n} transactions created after the target")));
+    checks.push(check("no_data_after_target", after_target));
+
+    checks
+}
+
+/// Restore the newest suitable base backup plus archived WAL from `store`
+/// into a new cluster, verify it and report RTO/RPO. `work_dir` holds the
+/// downloaded WAL and the server log.
+pub async fn restore(
+    store: &dyn ObjectStore,
+    database_url: &str,
+    work_dir: &Path,
+    request: &PitrRequest,
+) -> Result<PitrReport> {
+    let started = Instant::now();
+    let target = request.target_time;
+    if target > Utc::now() {
+        anyhow::bail!("Target time {target} is in the future");
+    }
+
+    let backups = list_base_backups(store).await?;
+    let base = select_base_backup(&backups, target)
+        .with_context(|| format!("No base backup finished before {target}"))?
+        .clone();
+    tracing::info!(base_backup = %base.label, %target, "Starting point-in-time restore");
+
+    ensure_empty_dir(&request.data_dir).await?;
+    let work = work_dir.join(format!("pitr_{}", Utc::now().format("%Y%m%d_%H%M%S")));
+    let wal_dir = work.join("wal");
+    fs::create_dir_all(&wal_dir)
+        .await
+        .context("Failed to create WAL directory")?;
+
+    // Base backup
+    let archive = work.join("base.tar.gz");
+    store
+        .get_file(&base.archive_key(), &archive)
+        .await
+        .context("Failed to download base backup")?;
+    let checksum = sha256sum(&archive).await?;
+    if checksum != base.checksum {
+        anyhow::bail!(
+            "Base backup integrity check failed: checksum mismatch (expected: {}, got: {checksum})",
+            base.checksum
+        );
+    }
+    run(
+        Command::new("tar")
+            .arg("-xzf")
+            .arg(&archive)
+            .arg("-C")
+            .arg(&request.data_dir),
+        "tar",
+    )?;
+    fs::remove_file(&archive).await?;
+
+    // Archived WAL
+    let keys: Vec<String> = store
+        .list(WAL_PREFIX)
+        .await
+        .context("Failed to list archived WAL")?
+        .into_iter()
+        .map(|o| o.key)
+        .collect();
+    let wal_keys = wal_keys_to_restore(&keys, &base.start_wal_file);
+    for key in &wal_keys {
+        let name = key.trim_start_matches(WAL_PREFIX);
+        store.get_file(key, &wal_dir.join(name)).await?;
+    }
+    tracing::info!(segments = wal_keys.len(), "Fetched archived WAL");
+
+    // Recovery configuration
+    fs::write(request.data_dir.join("recovery.signal"), "").await?;
+    let mut auto_conf = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(request.data_dir.join("postgresql.auto.conf"))
+        .await?;
+    auto_conf
+        .write_all(recovery_settings(&wal_dir, target, request.port)?.as_bytes())
+        .await?;
+    auto_conf.sync_all().await?;
+
+    // Replay
+    let log = work.join("postgres.log");
+    run(
+        pg_ctl(&request.data_dir)
+            .arg("--log")
+            .arg(&log)
+            .arg("--no-wait")
+            .arg("start"),
+        "pg_ctl start",
+    )?;
+    let url = restored_database_url(database_url, request.port)?;
+    let outcome = async {
+        let mut conn =
+            wait_for_promotion(&url, &request.data_dir, &log, request.recovery_timeout).await?;
+        let recovered_to: Option<DateTime<Utc>> =
+            sqlx::query_scalar("SELECT pg_last_xact_replay_timestamp()")
+                .fetch_one(&mut conn)
+                .await?;
+        let checks = verify(&mut conn, target).await;
+        conn.close().await.ok();
+        anyhow::Ok((recovered_to, checks))
+    }
+    .await;
+
+    if !request.keep_running || outcome.is_err() {
+        if let Err(e) = run(
+            pg_ctl(&request.data_dir).arg("--mode=fast").arg("stop"),
+            "pg_ctl stop",
+        ) {
+            tracing::warn!(error = %e, "Failed to stop restored cluster");
+        }
+    }
+    let (recovered_to, checks) = outcome?;
+    fs::remove_dir_all(&wal_dir).await.ok();
+
+    let report = PitrReport {
+        base_backup: base.label,
+        target_time: target,
+        recovered_to,
+        wal_segments: wal_keys.len(),
+        data_dir: request.data_dir.clone(),
+        port: request.port,
+        rto_secs: started.elapsed().as_secs_f64(),
+        rpo_secs: recovered_to.map(|t| (target - t).num_seconds().max(0)),
+        checks,
+    };
+    tracing::info!(
+        base_backup = %report.base_backup,
+        rto_secs = report.rto_secs,
+        rpo_secs = ?report.rpo_secs,
+        passed = report.passed(),
+        "Point-in-time restore finished"
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn base(label: &str, finished_hour: u32) -> BaseBackupMetadata {
+        let finished_at = Utc
+            .with_ymd_and_hms(2026, 6, 1, finished_hour, 0, 0)
+            .unwrap();
+        BaseBackupMetadata {
+            label: label.to_string(),
+            started_at: finished_at - chrono::Duration::minutes(5),
+            finished_at,
+            start_wal_file: "000000010000000000000002".to_string(),
+            size_bytes: 1,
+            checksum: "abc".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_backup_label() {
+        let label = "START WAL LOCATION: 0/2000028 (file 000000010000000000000002)\n\
+                     CHECKPOINT LOCATION: 0/2000060\n\
+                     LABEL: base_20260601_000000\n";
+        assert_eq!(
+            parse_backup_label(label).as_deref(),
+            Some("000000010000000000000002")
+        );
+        assert_eq!(parse_backup_label("LABEL: x\n"), None);
+        assert_eq!(
+            parse_backup_label("START WAL LOCATION: 0/1 (file ../../etc)\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_select_base_backup_picks_newest_before_target() {
+        let backups = vec![base("a", 1), base("b", 3), base("c", 5)];
+        let at = |h| Utc.with_ymd_and_hms(2026, 6, 1, h, 0, 0).unwrap();
+
+        assert_eq!(select_base_backup(&backups, at(4)).unwrap().label, "b");
+        assert_eq!(select_base_backup(&backups, at(5)).unwrap().label, "c");
+        assert!(select_base_backup(&backups, at(0)).is_none());
+    }
+
+    #[test]
+    fn test_wal_keys_to_restore() {
+        let keys: Vec<String> = [
+            "wal/000000010000000000000001",
+            "wal/000000010000000000000002",
+            "wal/000000010000000000000003",
+            "wal/00000002.history",
+            "wal/000000020000000000000003",
+            "wal/000000010000000000000003.partial",
+            "base/base_x.json",
+        ]
+        .iter()
+        .map(|k| k.to_string())
+        .collect();
+
+        assert_eq!(
+            wal_keys_to_restore(&keys, "000000010000000000000002"),
+            vec![
+                "wal/000000010000000000000002",
+                "wal/000000010000000000000003",
+                "wal/00000002.history",
+                "wal/000000020000000000000003",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_recovery_settings() {
+        let target = Utc.with_ymd_and_hms(2026, 1, 15, 10, 30, 0).unwrap();
+        let settings = recovery_settings(Path::new("/restore/wal"), target, 5433).unwrap();
+
+        assert!(settings.contains("restore_command = 'cp \"/restore/wal/%f\" \"%p\"'\n"));
+        assert!(settings.contains("recovery_target_time = '2026-01-15 10:30:00.000000+00'\n"));
+        assert!(settings.contains("recovery_target_action = 'promote'\n"));
+        assert!(settings.contains("archive_mode = 'off'\n"));
+        assert!(settings.contains("port = 5433\n"));
+        assert!(recovery_settings(Path::new("/it's"), target, 5433).is_err());
+    }
+
+    #[test]
+    fn test_restored_database_url() {
+        assert_eq!(
+            restored_database_url("postgres://synapse:pw@db.internal:5432/synapse", 5433).unwrap(),
+            "postgres://synapse:pw@localhost:5433/synapse"
+        );
+    }
+
+    #[test]
+    fn test_report_passes_only_when_every_check_passes() {
+        let mut report = PitrReport {
+            base_backup: "b".to_string(),
+            target_time: Utc::now(),
+            recovered_to: None,
+            wal_segments: 0,
+            data_dir: PathBuf::from("/restore"),
+            port: 5433,
+            rto_secs: 1.0,
+            rpo_secs: None,
+            checks: vec![VerificationCheck {
+                name: "schema_migrated",
+                passed: true,
+                detail: String::new(),
+            }],
+        };
+        assert!(report.passed());
+        report.checks.push(VerificationCheck {
+            name: "no_data_after_target",
+            passed: false,
+            detail: "1 transactions created after the target".to_string(),
+        });
+        assert!(!report.passed());
+    }
+}
@@ -1,4 +1,7 @@
-use crate::services::{backup::BackupService, scheduler::Job};
+use crate::services::{
+    backup::BackupService,
+    scheduler::{CancellationToken, Job},
+};
 use async_trait::async_trait;
 use std::sync::Arc;
 
@@ -22,7 +25,10 @@ impl Job for BackupVerificationJob {
         "0 2 * * 0" // Weekly on Sunday at 2 AM
     }
 
-    async fn execute(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    async fn execute(
+        &self,
+        _cancel: &CancellationToken,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         tracing::info!("Starting weekly backup verification job");
 
         match self.backup_service.list_backups().await {
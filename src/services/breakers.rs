@@ -0,0 +1,276 @@
+//! Circuit breaker status, manual overrides and transition events.
+//!
+//! | Breaker          | Protects                                | Automatic state kept in      |
+//! |------------------|-----------------------------------------|------------------------------|
+//! | `horizon`        | Stellar Horizon API calls               | each instance's memory       |
+//! | `webhook:<uuid>` | Deliveries to one webhook subscriber    | Redis `webhook_cb:<uuid>`    |
+//!
+//! A manual override pins a breaker `open` or `closed` until it is reset. It
+//! is stored in Redis under `breaker_override:<name>` so every instance
+//! honours it: webhook checks read it directly, and
+//! [`sync_horizon_override`] copies the Horizon one into the local client.
+//!
+//! Every transition, automatic or manual, is published on [`events`], logged
+//! at `warn`, counted in `circuit_breaker_transitions_total{breaker,to}` and
+//! written to `circuit_breaker_events` by [`record_events`], so on-call can see
+//! why processing stalled from `GET /admin/breakers`.
+
+use crate::services::event_channels::{self, EventClass};
+use crate::stellar::HorizonClient;
+use chrono::{DateTime, Utc};
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+pub const HORIZON: &str = "horizon";
+pub const WEBHOOK_PREFIX: &str = "webhook:";
+const OVERRIDE_PREFIX: &str = "breaker_override:";
+
+/// How often [`sync_horizon_override`] re-reads the Horizon override.
+pub const OVERRIDE_SYNC_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerState {
+    Closed,
+    Open,
+}
+
+impl BreakerState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BreakerState::Closed => "closed",
+            BreakerState::Open => "open",
+        }
+    }
+}
+
+/// A breaker pinned to `state` by an operator.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManualOverride {
+    pub state: BreakerState,
+    pub reason: String,
+    pub actor: String,
+    pub set_at: DateTime<Utc>,
+}
+
+/// A breaker changing state.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BreakerTransition {
+    pub breaker: String,
+    pub from_state: BreakerState,
+    pub to_state: BreakerState,
+    pub reason: String,
+    /// Operator for manual changes, `None` for automatic ones.
+    pub actor: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl BreakerTransition {
+    pub fn automatic(
+        breaker: impl Into<String>,
+        from_state: BreakerState,
+        to_state: BreakerState,
+        reason: impl Into<String>,
+    ) -> Self {
+        Self {
+            breaker: breaker.into(),
+            from_state,
+            to_state,
+            reason: reason.into(),
+            actor: None,
+            occurred_at: Utc::now(),
+        }
+    }
+}
+
+/// Current state of one breaker as shown by `GET /admin/breakers`.
+#[derive(Debug, Clone, Serialize)]
+pub struct BreakerStatus {
+    pub name: String,
+    /// Effective state: the override's if one is set.
+    pub state: BreakerState,
+    pub manual_override: Option<ManualOverride>,
+    pub failure_count: Option<u32>,
+    pub opened_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+/// Process-wide channel of breaker transitions.
+pub fn events() -> &'static broadcast::Sender<BreakerTransition> {
+    static EVENTS: OnceLock<broadcast::Sender<BreakerTransition>> = OnceLock::new();
+    EVENTS.get_or_init(|| event_channels::channel(EventClass::Admin))
+}
+
+/// Log, count and broadcast a transition.
+pub fn publish(transition: BreakerTransition) {
+    tracing::warn!(
+        breaker = %transition.breaker,
+        from = transition.from_state.as_str(),
+        to = transition.to_state.as_str(),
+        reason = %transition.reason,
+        actor = transition.actor.as_deref().unwrap_or("automatic"),
+        "Circuit breaker changed state"
+    );
+    crate::metrics::circuit_breaker_transitions_total().add(
+        1,
+        &[
+            opentelemetry::KeyValue::new("breaker", transition.breaker.clone()),
+            opentelemetry::KeyValue::new("to", transition.to_state.as_str()),
+        ],
+    );
+    // No receivers just means nothing is recording yet.
+    let _ = events().send(transition);
+}
+
+/// Check that `name` is a known breaker: `horizon` or `webhook:<uuid>`.
+pub fn validate_name(name: &str) -> Result<(), String> {
+    if name == HORIZON {
+        return Ok(());
+    }
+    match name.strip_prefix(WEBHOOK_PREFIX).map(Uuid::parse_str) {
+        Some(Ok(_)) => Ok(()),
+        _ => Err(format!(
+            "unknown breaker '{name}': expected '{HORIZON}' or '{WEBHOOK_PREFIX}<endpoint id>'"
+        )),
+    }
+}
+
+pub fn webhook_breaker(endpoint_id: &Uuid) -> String {
+    format!("{WEBHOOK_PREFIX}{endpoint_id}")
+}
+
+pub fn override_key(name: &str) -> String {
+    format!("{OVERRIDE_PREFIX}{name}")
+}
+
+/// The override on breaker `name`, if any.
+pub async fn get_override(
+    redis: &redis::Client,
+    name: &str,
+) -> anyhow::Result<Option<ManualOverride>> {
+    let mut conn = redis.get_multiplexed_async_connection().await?;
+    let raw: Option<String> = conn.get(override_key(name)).await?;
+    Ok(raw.and_then(|raw| serde_json::from_str(&raw).ok()))
+}
+
+/// Pin breaker `name`, or with `None` return it to automatic control.
+pub async fn set_override(
+    redis: &redis::Client,
+    name: &str,
+    value: Option<&ManualOverride>,
+) -> anyhow::Result<()> {
+    let mut conn = redis.get_multiplexed_async_connection().await?;
+    match value {
+        Some(value) => {
+            conn.set::<_, _, ()>(override_key(name), serde_json::to_string(value)?)
+                .await?
+        }
+        None => conn.del::<_, ()>(override_key(name)).await?,
+    }
+    Ok(())
+}
+
+/// Every override currently set, by breaker name.
+pub async fn list_overrides(
+    redis: &redis::Client,
+) -> anyhow::Result<HashMap<String, ManualOverride>> {
+    let mut conn = redis.get_multiplexed_async_connection().await?;
+    let keys: Vec<String> = {
+        let mut iter: redis::AsyncIter<String> =
+            conn.scan_match(format!("{OVERRIDE_PREFIX}*")).await?;
+        let mut keys = Vec::new();
+        while let Some(key) = iter.next_item().await {
+            keys.push(key);
+        }
+        keys
+    };
+
+    let mut overrides = HashMap::new();
+    for key in keys {
+        let raw: Option<String> = conn.get(&key).await?;
+        if let Some(value) = raw.and_then(|raw| serde_json::from_str(&raw).ok()) {
+            overrides.insert(key[OVERRIDE_PREFIX.len()..].to_string(), value);
+        }
+    }
+    Ok(overrides)
+}
+
+/// Keep `horizon`'s local override in step with Redis, so an override set
+/// through any instance applies to all of them.
+pub async fn sync_horizon_override(redis: redis::Client, horizon: HorizonClient) {
+    let mut interval = tokio::time::interval(OVERRIDE_SYNC_INTERVAL);
+    loop {
+        interval.tick().await;
+        match get_override(&redis, HORIZON).await {
+            Ok(value) => horizon.set_manual_override(value),
+            Err(e) => tracing::debug!("Failed to read Horizon breaker override: {}", e),
+        }
+    }
+}
+
+/// Write every published transition to `circuit_breaker_events`.
+pub async fn record_events(pool: PgPool) {
+    let mut rx = events().subscribe();
+    loop {
+        match rx.recv().await {
+            Ok(transition) => {
+                if let Err(e) = crate::db::queries::insert_breaker_event(&pool, &transition).await {
+                    tracing::error!("Failed to record circuit breaker transition: {}", e);
+                }
+            }
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                event_channels::record_lag(EventClass::Admin, skipped);
+                tracing::warn!(skipped, "Circuit breaker event recorder lagged");
+            }
+            Err(broadcast::error::RecvError::Closed) => return,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("horizon").is_ok());
+        assert!(validate_name(&webhook_breaker(&Uuid::new_v4())).is_ok());
+        assert!(validate_name("webhook:not-a-uuid").is_err());
+        assert!(validate_name("screening").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_publish_broadcasts_transition() {
+        let mut rx = events().subscribe();
+        let transition = BreakerTransition::automatic(
+            "horizon",
+            BreakerState::Closed,
+            BreakerState::Open,
+            "failure threshold reached",
+        );
+        publish(transition.clone());
+        assert_eq!(rx.recv().await.unwrap(), transition);
+    }
+
+    #[test]
+    fn test_override_round_trips_as_json() {
+        let value = ManualOverride {
+            state: BreakerState::Open,
+            reason: "Horizon maintenance window".to_string(),
+            actor: "ops".to_string(),
+            set_at: Utc::now(),
+        };
+        let json = serde_json::to_string(&value).unwrap();
+        assert!(json.contains(r#""state":"open""#));
+        assert_eq!(
+            serde_json::from_str::<ManualOverride>(&json).unwrap(),
+            value
+        );
+    }
+}
@@ -0,0 +1,405 @@
+//! Claimable balances addressed to the anchor's distribution accounts.
+//!
+//! Senders fall back to a claimable balance when a payment cannot land
+//! directly, typically because the destination has no trustline for the
+//! asset yet. [`ClaimableBalanceJob`] sweeps them every five minutes:
+//!
+//! 1. For each distribution account, list the balances it may claim on
+//!    Horizon and record new ones in `claimable_balances` as `detected`.
+//! 2. Claim each detected balance whose predicate allows it now with a
+//!    `CLAIM_CLAIMABLE_BALANCE` operation sourced from the claimant,
+//!    submitted through the [`SubmissionLedger`] so it is claimed at most
+//!    once.
+//! 3. Record the claim as a normal pending deposit in `transactions` from
+//!    the balance's sponsor, with the claim as its `stellar_tx_hash` and
+//!    `claimable:<balance id>` as its anchor transaction id; the processor
+//!    verifies and completes it like any other deposit.
+//!
+//! A claim the network rejects stays `detected` with `last_error` (e.g.
+//! `op_no_trust` until the trustline exists) and is retried on the next
+//! run. One whose balance no longer exists, because it was claimed
+//! elsewhere or reclaimed by the sender, becomes `gone`.
+//!
+//! | Env var                         | Default              | Meaning                         |
+//! |---------------------------------|----------------------|---------------------------------|
+//! | `STELLAR_DISTRIBUTION_ACCOUNTS` | the signer's account | Comma-separated `G...` accounts |
+//!
+//! The transaction signer (`TRANSACTION_SIGNER`) must be able to sign for
+//! every listed account.
+
+use crate::adapters;
+use crate::config::jobs::JobSettings;
+use crate::db::models::{ClaimableBalance, Transaction};
+use crate::db::queries;
+use crate::domain::StellarAddress;
+use crate::ports::TransactionSigner;
+use crate::services::scheduler::{CancellationToken, Job};
+use crate::services::submissions::{SubmissionError, SubmissionLedger};
+use crate::stellar::xdr::{ClaimClaimableBalance, Memo};
+use crate::stellar::{ClaimableBalanceRecord, HorizonClient, HorizonError};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde_json::Value;
+use sqlx::types::BigDecimal;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+const DEFAULT_BATCH_SIZE: u32 = 50;
+/// Horizon's page limit for `/claimable_balances`.
+const DETECT_LIMIT: u32 = 200;
+const CLAIM_TIMEOUT_SECS: i64 = 60;
+
+/// Prefix of the `anchor_transaction_id` given to claimed deposits.
+pub const ANCHOR_ID_PREFIX: &str = "claimable:";
+
+/// Whether Horizon's JSON form of a `ClaimPredicate` allows claiming at
+/// `now`. Unknown shapes never do.
+pub fn predicate_allows(predicate: &Value, now: DateTime<Utc>) -> bool {
+    let Some(object) = predicate.as_object() else {
+        return false;
+    };
+    if object.get("unconditional").and_then(Value::as_bool) == Some(true) {
+        return true;
+    }
+    if let Some(all) = object.get("and").and_then(Value::as_array) {
+        return all.iter().all(|p| predicate_allows(p, now));
+    }
+    if let Some(any) = object.get("or").and_then(Value::as_array) {
+        return any.iter().any(|p| predicate_allows(p, now));
+    }
+    if let Some(inner) = object.get("not") {
+        return inner.is_object() && !predicate_allows(inner, now);
+    }
+    // Horizon gives absolute bounds both as RFC 3339 and as Unix seconds;
+    // relative ones are made absolute when the balance is created.
+    let before = object
+        .get("abs_before_epoch")
+        .and_then(Value::as_str)
+        .and_then(|secs| secs.parse::<i64>().ok())
+        .and_then(|secs| DateTime::from_timestamp(secs, 0))
+        .or_else(|| {
+            object
+                .get("abs_before")
+                .and_then(Value::as_str)
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                .map(|t| t.with_timezone(&Utc))
+        });
+    before.is_some_and(|before| now < before)
+}
+
+/// The row to record for `record`, seen for `claimant`. `None` when
+/// `claimant` is not among its claimants or the record is malformed.
+fn detected_balance(claimant: &str, record: &ClaimableBalanceRecord) -> Option<ClaimableBalance> {
+    let predicate = record
+        .claimants
+        .iter()
+        .find(|c| c.destination == claimant)?
+        .predicate
+        .clone();
+    let (asset_code, asset_issuer) = match record.asset.split_once(':') {
+        Some((code, issuer)) => (code.to_string(), Some(issuer.to_string())),
+        None if record.asset == "native" => ("XLM".to_string(), None),
+        None => return None,
+    };
+    let amount = record.amount.parse::<BigDecimal>().ok()?;
+    let now = Utc::now();
+    Some(ClaimableBalance {
+        id: Uuid::nil(),
+        balance_id: record.id.clone(),
+        claimant: claimant.to_string(),
+        sponsor: record.sponsor.clone(),
+        asset_code,
+        asset_issuer,
+        amount,
+        predicate,
+        status: "detected".to_string(),
+        attempts: 0,
+        last_error: None,
+        claim_tx_hash: None,
+        transaction_id: None,
+        detected_at: now,
+        claimed_at: None,
+        updated_at: now,
+    })
+}
+
+/// Detects, claims and records claimable balances for the distribution
+/// accounts.
+pub struct ClaimableBalanceJob {
+    pool: PgPool,
+    horizon: HorizonClient,
+    signer: Arc<dyn TransactionSigner>,
+    network_passphrase: String,
+    accounts: Vec<StellarAddress>,
+    batch_size: u32,
+}
+
+impl ClaimableBalanceJob {
+    pub fn new(
+        pool: PgPool,
+        horizon: HorizonClient,
+        signer: Arc<dyn TransactionSigner>,
+        network_passphrase: impl Into<String>,
+        accounts: Vec<StellarAddress>,
+    ) -> Self {
+        Self {
+            pool,
+            horizon,
+            signer,
+            network_passphrase: network_passphrase.into(),
+            accounts,
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+
+    /// From `STELLAR_DISTRIBUTION_ACCOUNTS`, the configured signer and
+    /// `STELLAR_NETWORK_PASSPHRASE`. `None` without a signer or passphrase,
+    /// since nothing could be claimed.
+    pub fn from_env(pool: PgPool, horizon: HorizonClient) -> anyhow::Result<Option<Self>> {
+        let (Some(signer), Ok(passphrase)) = (
+            adapters::transaction_signer(),
+            std::env::var("STELLAR_NETWORK_PASSPHRASE"),
+        ) else {
+            return Ok(None);
+        };
+        let accounts = match std::env::var("STELLAR_DISTRIBUTION_ACCOUNTS") {
+            Ok(raw) if !raw.trim().is_empty() => raw
+                .split(',')
+                .map(|account| {
+                    StellarAddress::parse(account)
+                        .ok()
+                        .filter(|address| address.muxed_id().is_none())
+                        .ok_or_else(|| {
+                            anyhow::anyhow!(
+                                "STELLAR_DISTRIBUTION_ACCOUNTS: '{}' is not a G... account",
+                                account.trim()
+                            )
+                        })
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?,
+            _ => vec![StellarAddress::from_ed25519(signer.public_key())],
+        };
+        Ok(Some(Self::new(pool, horizon, signer, passphrase, accounts)))
+    }
+
+    /// Record the balances `account` may claim; returns how many were new.
+    pub async fn detect(&self, account: &StellarAddress) -> anyhow::Result<usize> {
+        let records = self
+            .horizon
+            .get_claimable_balances(account.account(), DETECT_LIMIT)
+            .await?;
+        let mut new = 0;
+        for record in &records {
+            let Some(balance) = detected_balance(account.account(), record) else {
+                warn!(balance_id = %record.id, "Skipping unreadable claimable balance");
+                continue;
+            };
+            if balance.insert_detected(&self.pool).await? {
+                info!(
+                    balance_id = %balance.balance_id,
+                    claimant = %balance.claimant,
+                    asset_code = %balance.asset_code,
+                    amount = %balance.amount,
+                    "Claimable balance detected"
+                );
+                new += 1;
+            }
+        }
+        Ok(new)
+    }
+
+    /// Claim `balance` and record its deposit. `None` when the network
+    /// rejected the claim, which is recorded on the balance instead.
+    pub async fn claim(&self, balance: &ClaimableBalance) -> anyhow::Result<Option<Transaction>> {
+        let claimant = StellarAddress::parse(&balance.claimant)?;
+        let balance_id = ClaimClaimableBalance::parse_balance_id(&balance.balance_id)
+            .ok_or_else(|| anyhow::anyhow!("invalid balance id {}", balance.balance_id))?;
+        let operation = ClaimClaimableBalance {
+            source: Some(claimant.public_key()),
+            balance_id,
+        };
+        let submitted = SubmissionLedger::new(
+            self.pool.clone(),
+            self.horizon.clone(),
+            self.network_passphrase.clone(),
+        )
+        .submit(
+            balance.id,
+            self.signer.as_ref(),
+            Memo::None,
+            vec![operation.into()],
+            Duration::seconds(CLAIM_TIMEOUT_SECS),
+        )
+        .await;
+        let hash = match submitted {
+            Ok(submitted) => submitted.hash,
+            // Claimed by an earlier run that did not get to record it.
+            Err(SubmissionError::AlreadySubmitted(hash)) => hash,
+            Err(SubmissionError::Horizon(HorizonError::TransactionFailed { result, .. })) => {
+                let gone = result
+                    .operations
+                    .iter()
+                    .any(|op| op.code_name() == "op_does_not_exist");
+                ClaimableBalance::record_failure(&self.pool, balance.id, &result.to_string(), gone)
+                    .await?;
+                warn!(
+                    balance_id = %balance.balance_id,
+                    result = %result,
+                    "Claimable balance claim rejected"
+                );
+                return Ok(None);
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let deposit = Transaction::new(
+            balance
+                .sponsor
+                .clone()
+                .unwrap_or_else(|| balance.claimant.clone()),
+            balance.amount.clone(),
+            balance.asset_code.clone(),
+            Some(format!("{ANCHOR_ID_PREFIX}{}", balance.balance_id)),
+            None,
+            None,
+            None,
+            None,
+            Some(serde_json::json!({ "claimable_balance_id": balance.balance_id })),
+        )
+        .with_stellar_tx_hash(Some(hash.clone()));
+        Ok(queries::record_claimable_balance_claim(&self.pool, balance, &hash, &deposit).await?)
+    }
+}
+
+#[async_trait]
+impl Job for ClaimableBalanceJob {
+    fn name(&self) -> &str {
+        "claimable_balance_sweep"
+    }
+
+    /// Every five minutes.
+    fn schedule(&self) -> &str {
+        "0 */5 * * * * *"
+    }
+
+    fn configure(&mut self, settings: &JobSettings) {
+        if let Some(size) = settings.batch_size {
+            self.batch_size = size;
+        }
+    }
+
+    async fn execute(
+        &self,
+        cancel: &CancellationToken,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        for account in &self.accounts {
+            if cancel.is_cancelled() {
+                return Ok(());
+            }
+            if let Err(e) = self.detect(account).await {
+                warn!(
+                    account = account.account(),
+                    "Failed to list claimable balances: {}", e
+                );
+            }
+        }
+
+        let now = Utc::now();
+        let (mut claimed, mut rejected) = (0, 0);
+        for balance in ClaimableBalance::detected(&self.pool, self.batch_size.into()).await? {
+            if cancel.is_cancelled() {
+                break;
+            }
+            if !predicate_allows(&balance.predicate, now) {
+                continue;
+            }
+            match self.claim(&balance).await {
+                Ok(Some(deposit)) => {
+                    info!(
+                        balance_id = %balance.balance_id,
+                        transaction_id = %deposit.id,
+                        "Claimable balance claimed"
+                    );
+                    claimed += 1;
+                }
+                Ok(None) => rejected += 1,
+                Err(e) => {
+                    warn!(balance_id = %balance.balance_id, "Failed to claim balance: {}", e)
+                }
+            }
+        }
+        info!(claimed, rejected, "Claimable balance sweep complete");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stellar::Claimant;
+    use serde_json::json;
+
+    #[test]
+    fn test_predicates() {
+        let now = DateTime::from_timestamp(1_800_000_000, 0).unwrap();
+        let before = |secs: i64| json!({ "abs_before_epoch": secs.to_string() });
+
+        assert!(predicate_allows(&json!({ "unconditional": true }), now));
+        assert!(predicate_allows(&before(1_800_000_001), now));
+        assert!(!predicate_allows(&before(1_800_000_000), now));
+        assert!(predicate_allows(
+            &json!({ "abs_before": "2027-01-15T08:00:00Z" }),
+            now
+        ));
+        // Claimable only after a point in time.
+        assert!(predicate_allows(
+            &json!({ "not": before(1_700_000_000) }),
+            now
+        ));
+        assert!(!predicate_allows(
+            &json!({ "not": before(1_900_000_000) }),
+            now
+        ));
+        assert!(!predicate_allows(
+            &json!({ "and": [before(1_900_000_000), { "not": before(1_850_000_000) }] }),
+            now
+        ));
+        assert!(predicate_allows(
+            &json!({ "or": [before(1_700_000_000), { "unconditional": true }] }),
+            now
+        ));
+        assert!(!predicate_allows(&json!({ "rel_before": "60" }), now));
+        assert!(!predicate_allows(&json!({ "not": null }), now));
+    }
+
+    #[test]
+    fn test_detected_balance() {
+        let ours = StellarAddress::from_ed25519([1; 32]).account().to_string();
+        let issuer = StellarAddress::from_ed25519([2; 32]).account().to_string();
+        let record = ClaimableBalanceRecord {
+            id: format!("00000000{}", "ab".repeat(32)),
+            asset: format!("USDC:{issuer}"),
+            amount: "25.0000000".to_string(),
+            sponsor: Some("GSENDER".to_string()),
+            claimants: vec![Claimant {
+                destination: ours.clone(),
+                predicate: json!({ "unconditional": true }),
+            }],
+        };
+
+        let balance = detected_balance(&ours, &record).unwrap();
+        assert_eq!(balance.asset_code, "USDC");
+        assert_eq!(balance.asset_issuer.as_deref(), Some(issuer.as_str()));
+        assert_eq!(balance.amount, "25".parse::<BigDecimal>().unwrap());
+        assert_eq!(balance.predicate, json!({ "unconditional": true }));
+
+        assert!(detected_balance(&issuer, &record).is_none());
+        let native = ClaimableBalanceRecord {
+            asset: "native".to_string(),
+            ..record
+        };
+        assert_eq!(detected_balance(&ours, &native).unwrap().asset_code, "XLM");
+    }
+}
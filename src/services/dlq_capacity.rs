@@ -0,0 +1,341 @@
+//! Bound on the size of the dead-letter queue.
+//!
+//! During a long Horizon outage every pending transaction can run out of
+//! verification attempts and land in `transaction_dlq`. [`watch`] counts the
+//! table every `DLQ_CAPACITY_CHECK_SECS` and compares it with
+//! `DLQ_MAX_ENTRIES`. At `DLQ_WATERMARK_PERCENT` of the maximum it warns; at
+//! the maximum it applies `DLQ_OVERFLOW_POLICY`:
+//!
+//! - `alert`: log an error and change nothing else.
+//! - `pause_intake`: `POST /callback` answers 503 until the DLQ is back under
+//!   the watermark.
+//! - `archive`: the oldest entries are written to the object store under
+//!   `dlq-archive/` as JSON lines and deleted, down to the watermark.
+//!
+//! Each rise into the watermark or full level is counted in
+//! `dlq_watermark_breaches_total{level}`, and archived entries in
+//! `dlq_entries_archived_total`.
+//!
+//! Every instance runs its own watch, so each pauses its own intake. Archiving
+//! locks the rows it moves, so two instances never archive the same entry; an
+//! archive whose delete then fails is left in the store, duplicating entries
+//! that are still in the table rather than losing any.
+//!
+//! | Env var                   | Default | Meaning                                |
+//! |---------------------------|---------|----------------------------------------|
+//! | `DLQ_MAX_ENTRIES`         | unset   | Maximum size; unset or 0 is unbounded  |
+//! | `DLQ_WATERMARK_PERCENT`   | 80      | Warning level, percent of the maximum  |
+//! | `DLQ_OVERFLOW_POLICY`     | `alert` | `alert`, `pause_intake` or `archive`   |
+//! | `DLQ_CAPACITY_CHECK_SECS` | 30      | Interval between checks                |
+
+use crate::metrics;
+use crate::ports::ObjectStore;
+use bytes::Bytes;
+use chrono::Utc;
+use opentelemetry::KeyValue;
+use sqlx::PgPool;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Object store prefix archived entries are written under.
+pub const ARCHIVE_PREFIX: &str = "dlq-archive";
+
+const DEFAULT_WATERMARK_PERCENT: u8 = 80;
+const DEFAULT_CHECK_SECS: u64 = 30;
+/// Entries per archive object.
+const ARCHIVE_BATCH: i64 = 1000;
+
+static INTAKE_PAUSED: AtomicBool = AtomicBool::new(false);
+
+/// Whether this instance has paused callback intake because the DLQ is full.
+pub fn intake_paused() -> bool {
+    INTAKE_PAUSED.load(Ordering::Relaxed)
+}
+
+/// What to do once the DLQ reaches its maximum size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    Alert,
+    PauseIntake,
+    Archive,
+}
+
+impl OverflowPolicy {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "alert" => Some(OverflowPolicy::Alert),
+            "pause_intake" => Some(OverflowPolicy::PauseIntake),
+            "archive" => Some(OverflowPolicy::Archive),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OverflowPolicy::Alert => "alert",
+            OverflowPolicy::PauseIntake => "pause_intake",
+            OverflowPolicy::Archive => "archive",
+        }
+    }
+}
+
+/// How full the DLQ is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Normal,
+    Watermark,
+    Full,
+}
+
+impl Level {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Level::Normal => "normal",
+            Level::Watermark => "watermark",
+            Level::Full => "full",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapacityConfig {
+    /// `None` leaves the DLQ unbounded and the watch off.
+    pub max_entries: Option<i64>,
+    pub watermark_percent: u8,
+    pub policy: OverflowPolicy,
+    pub check_interval: Duration,
+}
+
+impl CapacityConfig {
+    pub fn from_env() -> Self {
+        let max_entries = std::env::var("DLQ_MAX_ENTRIES")
+            .ok()
+            .and_then(|v| v.trim().parse::<i64>().ok())
+            .filter(|n| *n > 0);
+        let watermark_percent = std::env::var("DLQ_WATERMARK_PERCENT")
+            .ok()
+            .and_then(|v| v.trim().parse::<u8>().ok())
+            .filter(|n| (1..=100).contains(n))
+            .unwrap_or(DEFAULT_WATERMARK_PERCENT);
+        let policy = match std::env::var("DLQ_OVERFLOW_POLICY") {
+            Ok(v) => OverflowPolicy::parse(&v).unwrap_or_else(|| {
+                tracing::warn!(
+                    policy = %v,
+                    "Unknown DLQ_OVERFLOW_POLICY ignored; alerting only"
+                );
+                OverflowPolicy::Alert
+            }),
+            Err(_) => OverflowPolicy::Alert,
+        };
+        let check_secs = std::env::var("DLQ_CAPACITY_CHECK_SECS")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(DEFAULT_CHECK_SECS);
+        Self {
+            max_entries,
+            watermark_percent,
+            policy,
+            check_interval: Duration::from_secs(check_secs),
+        }
+    }
+
+    /// Size at which the DLQ counts as nearly full.
+    pub fn watermark(&self) -> Option<i64> {
+        self.max_entries
+            .map(|max| max * self.watermark_percent as i64 / 100)
+    }
+
+    pub fn level(&self, size: i64) -> Level {
+        match (self.max_entries, self.watermark()) {
+            (Some(max), _) if size >= max => Level::Full,
+            (_, Some(watermark)) if size >= watermark => Level::Watermark,
+            _ => Level::Normal,
+        }
+    }
+}
+
+/// Whether intake stays paused at `level`. It pauses when the DLQ fills up
+/// and resumes only once it is back under the watermark, so it does not
+/// flap around the maximum.
+fn pause_intake_at(level: Level, paused: bool) -> bool {
+    match level {
+        Level::Full => true,
+        Level::Watermark => paused,
+        Level::Normal => false,
+    }
+}
+
+/// Check the DLQ size every `DLQ_CAPACITY_CHECK_SECS` and apply the overflow
+/// policy. Returns at once when `DLQ_MAX_ENTRIES` is unset.
+pub async fn watch(pool: PgPool, store: Arc<dyn ObjectStore>) {
+    let config = CapacityConfig::from_env();
+    let Some(max_entries) = config.max_entries else {
+        tracing::info!("DLQ size unbounded (DLQ_MAX_ENTRIES unset)");
+        return;
+    };
+    tracing::info!(
+        max_entries,
+        policy = config.policy.as_str(),
+        "DLQ capacity watch started"
+    );
+
+    let mut interval = tokio::time::interval(config.check_interval);
+    let mut previous = Level::Normal;
+    loop {
+        interval.tick().await;
+        match check(&pool, store.as_ref(), &config, previous).await {
+            Ok(level) => previous = level,
+            Err(e) => tracing::error!("DLQ capacity check failed: {}", e),
+        }
+    }
+}
+
+/// One capacity check; returns the level the DLQ is left at.
+async fn check(
+    pool: &PgPool,
+    store: &dyn ObjectStore,
+    config: &CapacityConfig,
+    previous: Level,
+) -> anyhow::Result<Level> {
+    let size: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM transaction_dlq")
+        .fetch_one(pool)
+        .await?;
+    let level = config.level(size);
+    let max_entries = config.max_entries.unwrap_or_default();
+
+    if level > previous {
+        metrics::dlq_watermark_breaches_total().add(1, &[KeyValue::new("level", level.as_str())]);
+        match level {
+            Level::Full => tracing::error!(
+                size,
+                max_entries,
+                policy = config.policy.as_str(),
+                "DLQ is full"
+            ),
+            _ => tracing::warn!(size, max_entries, "DLQ above its watermark"),
+        }
+    }
+
+    match config.policy {
+        OverflowPolicy::Alert => Ok(level),
+        OverflowPolicy::PauseIntake => {
+            let paused = pause_intake_at(level, intake_paused());
+            if INTAKE_PAUSED.swap(paused, Ordering::Relaxed) != paused {
+                if paused {
+                    tracing::error!(size, "Callback intake paused: DLQ is full");
+                } else {
+                    tracing::info!(size, "Callback intake resumed");
+                }
+            }
+            Ok(level)
+        }
+        OverflowPolicy::Archive if level == Level::Full => {
+            let excess = size - config.watermark().unwrap_or(size);
+            let archived = archive_oldest(pool, store, excess).await?;
+            tracing::warn!(archived, "Archived oldest DLQ entries to the object store");
+            Ok(config.level(size - archived as i64))
+        }
+        OverflowPolicy::Archive => Ok(level),
+    }
+}
+
+/// Move up to `count` of the oldest DLQ entries to the object store, one
+/// JSON-lines object per batch, and return how many were moved. Rows locked
+/// elsewhere (a requeue, another instance's archive) are skipped.
+pub async fn archive_oldest(
+    pool: &PgPool,
+    store: &dyn ObjectStore,
+    count: i64,
+) -> anyhow::Result<u64> {
+    let mut archived = 0u64;
+    while (archived as i64) < count {
+        let limit = ARCHIVE_BATCH.min(count - archived as i64);
+        let mut db_tx = pool.begin().await?;
+        let rows: Vec<(Uuid, serde_json::Value)> = sqlx::query_as(
+            "SELECT d.id, to_jsonb(d) FROM transaction_dlq d \
+             ORDER BY d.moved_to_dlq_at, d.id LIMIT $1 FOR UPDATE SKIP LOCKED",
+        )
+        .bind(limit)
+        .fetch_all(&mut *db_tx)
+        .await?;
+        let Some((first_id, _)) = rows.first() else {
+            break;
+        };
+
+        let key = format!(
+            "{ARCHIVE_PREFIX}/{}-{first_id}.jsonl",
+            Utc::now().format("%Y%m%dT%H%M%S%.3fZ")
+        );
+        let mut body = String::new();
+        for (_, entry) in &rows {
+            body.push_str(&entry.to_string());
+            body.push('\n');
+        }
+        store.put_bytes(&key, Bytes::from(body)).await?;
+
+        let ids: Vec<Uuid> = rows.iter().map(|(id, _)| *id).collect();
+        sqlx::query("DELETE FROM transaction_dlq WHERE id = ANY($1)")
+            .bind(&ids)
+            .execute(&mut *db_tx)
+            .await?;
+        db_tx.commit().await?;
+
+        metrics::dlq_entries_archived_total().add(ids.len() as u64, &[]);
+        tracing::info!(%key, entries = ids.len(), "DLQ entries archived");
+        archived += ids.len() as u64;
+        if (ids.len() as i64) < limit {
+            break;
+        }
+    }
+    Ok(archived)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(max_entries: Option<i64>) -> CapacityConfig {
+        CapacityConfig {
+            max_entries,
+            watermark_percent: 80,
+            policy: OverflowPolicy::PauseIntake,
+            check_interval: Duration::from_secs(30),
+        }
+    }
+
+    #[test]
+    fn test_levels() {
+        let bounded = config(Some(1000));
+        assert_eq!(bounded.watermark(), Some(800));
+        assert_eq!(bounded.level(799), Level::Normal);
+        assert_eq!(bounded.level(800), Level::Watermark);
+        assert_eq!(bounded.level(1000), Level::Full);
+        assert_eq!(bounded.level(5000), Level::Full);
+
+        assert_eq!(config(None).level(i64::MAX), Level::Normal);
+    }
+
+    #[test]
+    fn test_intake_resumes_only_below_watermark() {
+        assert!(pause_intake_at(Level::Full, false));
+        assert!(pause_intake_at(Level::Watermark, true));
+        assert!(!pause_intake_at(Level::Watermark, false));
+        assert!(!pause_intake_at(Level::Normal, true));
+    }
+
+    #[test]
+    fn test_policy_parse() {
+        assert_eq!(
+            OverflowPolicy::parse(" Pause_Intake"),
+            Some(OverflowPolicy::PauseIntake)
+        );
+        assert_eq!(
+            OverflowPolicy::parse("archive"),
+            Some(OverflowPolicy::Archive)
+        );
+        assert_eq!(OverflowPolicy::parse("drop"), None);
+    }
+}
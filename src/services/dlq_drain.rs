@@ -0,0 +1,261 @@
+//! Automatic DLQ drain after a Horizon outage.
+//!
+//! When the `horizon` breaker closes, [`drain_on_recovery`] requeues DLQ
+//! entries of class `network` that were moved there during the outage, in
+//! batches with a pause between them so the recovered Horizon is not hit by
+//! the whole backlog at once. Each requeued transaction goes back to
+//! `pending` with a fresh round of verification attempts, exactly as
+//! `POST /dlq/{id}/requeue` would, and the change is audited with actor
+//! `dlq_auto_drain`.
+//!
+//! The drain covers entries moved since the breaker opened, minus a lookback
+//! for transactions that exhausted their attempts just before it tripped. An
+//! instance that never saw the breaker open (it started mid-outage) drains
+//! only the lookback before the close.
+//!
+//! | Env var                      | Default | Meaning                          |
+//! |------------------------------|---------|----------------------------------|
+//! | `DLQ_AUTO_DRAIN`             | `true`  | `false` disables the drain       |
+//! | `DLQ_DRAIN_BATCH_SIZE`       | 50      | Entries requeued per batch       |
+//! | `DLQ_DRAIN_BATCH_DELAY_MS`   | 1000    | Pause between batches            |
+//! | `DLQ_DRAIN_LOOKBACK_MINUTES` | 15      | Window before the breaker opened |
+//!
+//! Every drain logs how many entries were recovered at `info`, and counts
+//! them in `dlq_auto_requeued_total{class}`.
+
+use crate::db::audit::{AuditLog, ENTITY_TRANSACTION};
+use crate::metrics;
+use crate::services::breakers::{self, BreakerState, BreakerTransition};
+use crate::services::dlq_errors::ErrorClass;
+use crate::services::event_channels::{self, EventClass};
+use chrono::{DateTime, Utc};
+use opentelemetry::KeyValue;
+use sqlx::PgPool;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+const ACTOR: &str = "dlq_auto_drain";
+
+/// The class of entries that failed only because Horizon was unreachable.
+const DRAIN_CLASS: ErrorClass = ErrorClass::Network;
+
+const DEFAULT_BATCH_SIZE: i64 = 50;
+const DEFAULT_BATCH_DELAY_MS: u64 = 1000;
+const DEFAULT_LOOKBACK_MINUTES: i64 = 15;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DrainConfig {
+    pub enabled: bool,
+    pub batch_size: i64,
+    pub batch_delay: Duration,
+    pub lookback: chrono::Duration,
+}
+
+impl DrainConfig {
+    pub fn from_env() -> Self {
+        let enabled = std::env::var("DLQ_AUTO_DRAIN")
+            .map(|v| !v.trim().eq_ignore_ascii_case("false"))
+            .unwrap_or(true);
+        let batch_size = std::env::var("DLQ_DRAIN_BATCH_SIZE")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(DEFAULT_BATCH_SIZE);
+        let batch_delay_ms = std::env::var("DLQ_DRAIN_BATCH_DELAY_MS")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(DEFAULT_BATCH_DELAY_MS);
+        let lookback_minutes = std::env::var("DLQ_DRAIN_LOOKBACK_MINUTES")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .filter(|n| *n >= 0)
+            .unwrap_or(DEFAULT_LOOKBACK_MINUTES);
+        Self {
+            enabled,
+            batch_size,
+            batch_delay: Duration::from_millis(batch_delay_ms),
+            lookback: chrono::Duration::minutes(lookback_minutes),
+        }
+    }
+}
+
+/// Outcome of one drain.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DrainReport {
+    /// Entries whose transactions went back to `pending`.
+    pub requeued: u64,
+    /// Entries dropped without a requeue because their transaction had
+    /// already left `failed`/`dlq`.
+    pub skipped: u64,
+}
+
+/// Whether `transition` is the Horizon breaker closing.
+fn is_recovery(transition: &BreakerTransition) -> bool {
+    transition.breaker == breakers::HORIZON
+        && transition.from_state == BreakerState::Open
+        && transition.to_state == BreakerState::Closed
+}
+
+/// Start of the window to drain for an outage that ended at `closed_at`.
+fn drain_since(
+    opened_at: Option<DateTime<Utc>>,
+    closed_at: DateTime<Utc>,
+    lookback: chrono::Duration,
+) -> DateTime<Utc> {
+    opened_at.unwrap_or(closed_at) - lookback
+}
+
+/// Follow breaker transitions and drain the DLQ each time Horizon recovers.
+pub async fn drain_on_recovery(pool: PgPool) {
+    let config = DrainConfig::from_env();
+    if !config.enabled {
+        tracing::info!("Automatic DLQ drain disabled");
+        return;
+    }
+
+    let mut rx = breakers::events().subscribe();
+    let mut opened_at = None;
+    loop {
+        match rx.recv().await {
+            Ok(transition) if transition.breaker != breakers::HORIZON => {}
+            Ok(transition) if is_recovery(&transition) => {
+                let since = drain_since(opened_at.take(), transition.occurred_at, config.lookback);
+                match drain(&pool, since, &config).await {
+                    Ok(report) => tracing::info!(
+                        requeued = report.requeued,
+                        skipped = report.skipped,
+                        since = %since,
+                        "DLQ drained after Horizon recovered"
+                    ),
+                    Err(e) => tracing::error!("Automatic DLQ drain failed: {}", e),
+                }
+            }
+            Ok(transition) => {
+                if transition.to_state == BreakerState::Open {
+                    opened_at = Some(transition.occurred_at);
+                }
+            }
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                event_channels::record_lag(EventClass::Admin, skipped);
+                tracing::warn!(skipped, "DLQ drain lagged behind breaker events");
+            }
+            Err(broadcast::error::RecvError::Closed) => return,
+        }
+    }
+}
+
+/// Requeue drainable entries moved to the DLQ since `since`, one batch at a
+/// time. Rows locked by a concurrent drain on another instance are skipped.
+pub async fn drain(
+    pool: &PgPool,
+    since: DateTime<Utc>,
+    config: &DrainConfig,
+) -> anyhow::Result<DrainReport> {
+    let mut report = DrainReport::default();
+
+    loop {
+        let mut db_tx = pool.begin().await?;
+        let batch: Vec<(Uuid, Uuid, String, String)> = sqlx::query_as(
+            "SELECT d.id, d.transaction_id, t.status::text, t.asset_code \
+             FROM transaction_dlq d JOIN transactions t ON t.id = d.transaction_id \
+             WHERE d.error_class = $1 AND d.moved_to_dlq_at >= $2 \
+             ORDER BY d.moved_to_dlq_at LIMIT $3 FOR UPDATE OF d, t SKIP LOCKED",
+        )
+        .bind(DRAIN_CLASS.as_str())
+        .bind(since)
+        .bind(config.batch_size)
+        .fetch_all(&mut *db_tx)
+        .await?;
+
+        if batch.is_empty() {
+            return Ok(report);
+        }
+
+        let mut assets = Vec::new();
+        for (dlq_id, tx_id, status, asset_code) in &batch {
+            // Anything else was resolved some other way since it failed.
+            if matches!(status.as_str(), "failed" | "dlq") {
+                sqlx::query(
+                    "UPDATE transactions SET status = 'pending', verification_attempts = 0, \
+                     updated_at = NOW() WHERE id = $1",
+                )
+                .bind(tx_id)
+                .execute(&mut *db_tx)
+                .await?;
+                AuditLog::log_status_change(
+                    &mut db_tx,
+                    *tx_id,
+                    ENTITY_TRANSACTION,
+                    status,
+                    "pending",
+                    ACTOR,
+                )
+                .await?;
+                report.requeued += 1;
+                assets.push(asset_code.clone());
+            } else {
+                report.skipped += 1;
+            }
+            sqlx::query("DELETE FROM transaction_dlq WHERE id = $1")
+                .bind(dlq_id)
+                .execute(&mut *db_tx)
+                .await?;
+        }
+        db_tx.commit().await?;
+
+        metrics::dlq_auto_requeued_total().add(
+            assets.len() as u64,
+            &[KeyValue::new("class", DRAIN_CLASS.as_str())],
+        );
+        assets.sort();
+        assets.dedup();
+        for asset_code in &assets {
+            crate::db::queries::invalidate_caches_for_asset(asset_code).await;
+        }
+
+        if (batch.len() as i64) < config.batch_size {
+            return Ok(report);
+        }
+        tokio::time::sleep(config.batch_delay).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_horizon_closing_triggers_drain() {
+        let closing = |breaker: &str| {
+            BreakerTransition::automatic(
+                breaker,
+                BreakerState::Open,
+                BreakerState::Closed,
+                "trial request succeeded",
+            )
+        };
+        assert!(is_recovery(&closing(breakers::HORIZON)));
+        assert!(!is_recovery(&closing(&breakers::webhook_breaker(
+            &Uuid::new_v4()
+        ))));
+        assert!(!is_recovery(&BreakerTransition::automatic(
+            breakers::HORIZON,
+            BreakerState::Closed,
+            BreakerState::Open,
+            "consecutive failures",
+        )));
+    }
+
+    #[test]
+    fn test_drain_window_starts_before_outage() {
+        let closed_at = Utc::now();
+        let opened_at = closed_at - chrono::Duration::hours(2);
+        let lookback = chrono::Duration::minutes(15);
+        assert_eq!(
+            drain_since(Some(opened_at), closed_at, lookback),
+            opened_at - lookback
+        );
+        assert_eq!(drain_since(None, closed_at, lookback), closed_at - lookback);
+    }
+}
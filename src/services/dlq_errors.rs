@@ -0,0 +1,299 @@
+//! Error taxonomy for DLQ entries.
+//!
+//! Every row moved to `transaction_dlq` is stored with an `error_class` and an
+//! `error_signature`, so `GET /admin/dlq/stats` can show which kinds of failure
+//! dominate and which entries share a root cause:
+//!
+//! | Class               | Typical reason                                             |
+//! |---------------------|------------------------------------------------------------|
+//! | `network`           | Horizon unreachable, timeouts, connection resets, 429/5xx  |
+//! | `horizon_rejection` | failed on-chain, memo mismatch, not found on Horizon       |
+//! | `trustline`         | withdrawal destination lacks a trustline or limit room     |
+//! | `validation`        | untrusted issuer, bad amount/asset, invalid transition     |
+//! | `db`                | pool timeouts, constraint and query errors                 |
+//! | `unknown`           | anything else                                              |
+//!
+//! The signature is a hash of the reason with ids, hashes, accounts and
+//! numbers replaced by placeholders, so "memo mismatch: expected "123"" and
+//! "memo mismatch: expected "456"" group together.
+//!
+//! `DLQ_ALERT_THRESHOLDS` (`class=count,...`, e.g. `network=50,db=5`) sets
+//! per-class counts over the stats window above which the class is reported
+//! under `alerts` and logged at `warn`.
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorClass {
+    Network,
+    HorizonRejection,
+    Trustline,
+    Validation,
+    Db,
+    Unknown,
+}
+
+impl ErrorClass {
+    pub const ALL: [ErrorClass; 6] = [
+        ErrorClass::Network,
+        ErrorClass::HorizonRejection,
+        ErrorClass::Trustline,
+        ErrorClass::Validation,
+        ErrorClass::Db,
+        ErrorClass::Unknown,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorClass::Network => "network",
+            ErrorClass::HorizonRejection => "horizon_rejection",
+            ErrorClass::Trustline => "trustline",
+            ErrorClass::Validation => "validation",
+            ErrorClass::Db => "db",
+            ErrorClass::Unknown => "unknown",
+        }
+    }
+}
+
+impl FromStr for ErrorClass {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ErrorClass::ALL
+            .into_iter()
+            .find(|class| class.as_str() == s.trim())
+            .ok_or_else(|| format!("unknown error class '{s}'"))
+    }
+}
+
+/// Checked in order; the first class with a matching fragment wins. Database
+/// errors come first because pool timeouts would otherwise read as network
+/// ones, and lookup failures before rejections because every processor
+/// reason mentions Horizon.
+const RULES: &[(ErrorClass, &[&str])] = &[
+    (
+        ErrorClass::Db,
+        &[
+            "database",
+            "sqlx",
+            "pool timed out",
+            "deadlock",
+            "constraint",
+            "trusted asset lookup failed",
+            "asset issuer lookup failed",
+        ],
+    ),
+    (
+        ErrorClass::Network,
+        &[
+            "lookup failed",
+            "horizon unavailable",
+            "timed out",
+            "timeout",
+            "connection",
+            "network",
+            "dns",
+            "rate limit",
+            "too many requests",
+            "service unavailable",
+            "bad gateway",
+        ],
+    ),
+    (
+        ErrorClass::HorizonRejection,
+        &["failed on-chain", "memo mismatch", "not found on horizon"],
+    ),
+    (ErrorClass::Trustline, &["trustline"]),
+    (
+        ErrorClass::Validation,
+        &[
+            "issuer",
+            "invalid",
+            "validation",
+            "not allowed",
+            "not authorized",
+            "transition",
+        ],
+    ),
+];
+
+/// Class of a DLQ `error_reason`.
+pub fn classify(reason: &str) -> ErrorClass {
+    let reason = reason.to_ascii_lowercase();
+    RULES
+        .iter()
+        .find(|(_, fragments)| fragments.iter().any(|f| reason.contains(f)))
+        .map_or(ErrorClass::Unknown, |(class, _)| *class)
+}
+
+/// `reason` lower-cased, with every token containing a digit (ids, hashes,
+/// accounts, amounts) replaced by `#` and quoted values by `"?"`.
+pub fn normalize(reason: &str) -> String {
+    let mut out = Vec::new();
+    for token in reason.split_whitespace() {
+        let token = token.to_ascii_lowercase();
+        let normalized = if token.starts_with('"') || token.starts_with("some(\"") {
+            "\"?\"".to_string()
+        } else if token.chars().any(|c| c.is_ascii_digit()) {
+            let trailing: String = token
+                .chars()
+                .rev()
+                .take_while(|c| matches!(c, ':' | ',' | ';' | ')'))
+                .collect();
+            format!("#{trailing}")
+        } else {
+            token
+        };
+        out.push(normalized);
+    }
+    out.join(" ")
+}
+
+/// First 16 hex chars of the SHA-256 of [`normalize`]`(reason)`.
+pub fn signature(reason: &str) -> String {
+    hex::encode(Sha256::digest(normalize(reason).as_bytes()))[..16].to_string()
+}
+
+/// Per-class alert thresholds from `DLQ_ALERT_THRESHOLDS`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AlertThresholds(HashMap<ErrorClass, i64>);
+
+/// A class whose count over the window exceeds its threshold.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ClassAlert {
+    pub class: ErrorClass,
+    pub count: i64,
+    pub threshold: i64,
+}
+
+impl AlertThresholds {
+    /// Parse `class=count` pairs; malformed pairs are skipped with a warning.
+    pub fn parse(raw: &str) -> Self {
+        let mut thresholds = HashMap::new();
+        for pair in raw.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let parsed = pair.split_once('=').and_then(|(class, count)| {
+                Some((
+                    class.parse::<ErrorClass>().ok()?,
+                    count.trim().parse::<i64>().ok().filter(|c| *c >= 0)?,
+                ))
+            });
+            match parsed {
+                Some((class, count)) => {
+                    thresholds.insert(class, count);
+                }
+                None => tracing::warn!(pair, "Ignoring malformed DLQ_ALERT_THRESHOLDS entry"),
+            }
+        }
+        Self(thresholds)
+    }
+
+    pub fn from_env() -> Self {
+        Self::parse(&std::env::var("DLQ_ALERT_THRESHOLDS").unwrap_or_default())
+    }
+
+    /// Classes in `counts` above their threshold, in [`ErrorClass::ALL`] order.
+    pub fn breaches(&self, counts: &HashMap<ErrorClass, i64>) -> Vec<ClassAlert> {
+        ErrorClass::ALL
+            .into_iter()
+            .filter_map(|class| {
+                let threshold = *self.0.get(&class)?;
+                let count = counts.get(&class).copied().unwrap_or(0);
+                (count > threshold).then_some(ClassAlert {
+                    class,
+                    count,
+                    threshold,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_processor_reasons() {
+        let cases = [
+            (
+                "Horizon verification failed after 5 attempt(s): Horizon lookup failed: \
+                 request timed out",
+                ErrorClass::Network,
+            ),
+            (
+                "Horizon verification failed after 1 attempt(s): Stellar transaction abc \
+                 failed on-chain",
+                ErrorClass::HorizonRejection,
+            ),
+            (
+                "Horizon verification failed after 5 attempt(s): transaction not found on Horizon",
+                ErrorClass::HorizonRejection,
+            ),
+            (
+                "Horizon verification failed after 1 attempt(s): untrusted asset issuer: \
+                 USDC from GABC is not trusted",
+                ErrorClass::Validation,
+            ),
+            (
+                "Horizon verification failed after 1 attempt(s): destination GABC has no \
+                 trustline for USDC:GISS",
+                ErrorClass::Trustline,
+            ),
+            (
+                "Horizon verification failed after 3 attempt(s): Horizon trustline lookup \
+                 failed: connection reset",
+                ErrorClass::Network,
+            ),
+            (
+                "Horizon verification failed after 5 attempt(s): trusted asset lookup \
+                 failed: pool timed out while waiting for an open connection",
+                ErrorClass::Db,
+            ),
+            (
+                "error returned from database: deadlock detected",
+                ErrorClass::Db,
+            ),
+            ("something odd happened", ErrorClass::Unknown),
+        ];
+        for (reason, class) in cases {
+            assert_eq!(classify(reason), class, "{reason}");
+        }
+    }
+
+    #[test]
+    fn test_signature_groups_reasons_differing_only_in_values() {
+        let a = "Horizon verification failed after 1 attempt(s): memo mismatch: \
+                 expected \"1234\", Stellar transaction has Some(\"99\")";
+        let b = "Horizon verification failed after 1 attempt(s): memo mismatch: \
+                 expected \"87\", Stellar transaction has Some(\"5\")";
+        assert_eq!(signature(a), signature(b));
+        assert_ne!(
+            signature(a),
+            signature("Horizon verification failed after 1 attempt(s): failed on-chain")
+        );
+        assert_eq!(signature(a).len(), 16);
+        assert_eq!(normalize("After 5 attempt(s): x"), "after # attempt(s): x");
+    }
+
+    #[test]
+    fn test_alert_thresholds() {
+        let thresholds = AlertThresholds::parse("network=10, db=0, bogus=3, validation=x");
+        let counts = HashMap::from([
+            (ErrorClass::Network, 10),
+            (ErrorClass::Db, 1),
+            (ErrorClass::Unknown, 500),
+        ]);
+        assert_eq!(
+            thresholds.breaches(&counts),
+            vec![ClassAlert {
+                class: ErrorClass::Db,
+                count: 1,
+                threshold: 0,
+            }]
+        );
+    }
+}
@@ -0,0 +1,219 @@
+//! Catalog of every event the service emits, served at `GET /events/schema`.
+//!
+//! Schemas are generated from the Rust types that are serialized onto the
+//! wire, so a field added to a payload shows up here without a doc change.
+//! Bump an event's `version` whenever a field is removed or changes meaning.
+//!
+//! | Event                           | Transport   | Payload                        |
+//! |---------------------------------|-------------|--------------------------------|
+//! | `transaction.refund_pending`    | `webhook`   | [`AmountLimitNotification`]    |
+//! | `transaction.compliance_review` | `webhook`   | [`AmountLimitNotification`]    |
+//! | `ping`                          | `webhook`   | [`PingPayload`]                |
+//! | `statement.ready`               | `webhook`   | [`StatementReadyNotification`] |
+//! | `transaction.status_update`     | `websocket` | [`TransactionStatusUpdate`]    |
+
+use crate::services::amount_limits::AmountLimitNotification;
+use crate::services::statements::StatementReadyNotification;
+use crate::services::transaction_events::TransactionStatusUpdate;
+use crate::services::webhook_dispatcher::{
+    OutgoingPayload, PingPayload, COMPLIANCE_REVIEW_EVENT, PING_EVENT, REFUND_PENDING_EVENT,
+    STATEMENT_READY_EVENT,
+};
+use serde::Serialize;
+use utoipa::openapi::{RefOr, Schema};
+use utoipa::ToSchema;
+
+/// Name under which WebSocket status pushes are listed; the frames
+/// themselves carry no event type.
+pub const STATUS_UPDATE_EVENT: &str = "transaction.status_update";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Transport {
+    /// Signed `POST` to subscribed webhook endpoints.
+    Webhook,
+    /// Frame pushed to `/ws` clients (and the `transactionStatusChanged`
+    /// GraphQL subscription).
+    Websocket,
+}
+
+/// One emitted event type and the JSON Schema of its body.
+#[derive(Debug, Clone, Serialize)]
+pub struct EventSchema {
+    pub event_type: &'static str,
+    pub version: u32,
+    pub transport: Transport,
+    pub description: &'static str,
+    pub schema: serde_json::Value,
+}
+
+fn schema_of<'a, T: ToSchema<'a>>() -> serde_json::Value {
+    let schema: RefOr<Schema> = T::schema().1;
+    serde_json::to_value(schema).expect("schemas always serialize")
+}
+
+/// The [`OutgoingPayload`] envelope with `data` narrowed to `D`.
+fn webhook_schema<'a, D: ToSchema<'a>>() -> serde_json::Value {
+    let mut envelope = schema_of::<OutgoingPayload>();
+    envelope["properties"]["data"] = schema_of::<D>();
+    envelope
+}
+
+/// Every event the service emits, in a stable order.
+pub fn catalog() -> Vec<EventSchema> {
+    vec![
+        EventSchema {
+            event_type: REFUND_PENDING_EVENT,
+            version: 1,
+            transport: Transport::Webhook,
+            description: "A deposit was below its asset's min_amount and is held for a refund.",
+            schema: webhook_schema::<AmountLimitNotification>(),
+        },
+        EventSchema {
+            event_type: COMPLIANCE_REVIEW_EVENT,
+            version: 1,
+            transport: Transport::Webhook,
+            description: "A deposit was above its asset's max_amount and is held for review.",
+            schema: webhook_schema::<AmountLimitNotification>(),
+        },
+        EventSchema {
+            event_type: PING_EVENT,
+            version: 1,
+            transport: Transport::Webhook,
+            description: "Test delivery sent on demand to a single webhook endpoint.",
+            schema: schema_of::<PingPayload>(),
+        },
+        EventSchema {
+            event_type: STATEMENT_READY_EVENT,
+            version: 1,
+            transport: Transport::Webhook,
+            description: "A partner's monthly statement was generated; the envelope's \
+                          transaction_id is the statement id.",
+            schema: webhook_schema::<StatementReadyNotification>(),
+        },
+        EventSchema {
+            event_type: STATUS_UPDATE_EVENT,
+            version: 1,
+            transport: Transport::Websocket,
+            description: "A transaction changed status.",
+            schema: schema_of::<TransactionStatusUpdate>(),
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::Transaction;
+    use crate::services::amount_limits::AmountLimits;
+    use bigdecimal::BigDecimal;
+    use std::collections::BTreeSet;
+    use uuid::Uuid;
+
+    fn properties(schema: &serde_json::Value) -> BTreeSet<String> {
+        schema["properties"]
+            .as_object()
+            .expect("object schema")
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    fn keys(value: &serde_json::Value) -> BTreeSet<String> {
+        value.as_object().unwrap().keys().cloned().collect()
+    }
+
+    fn event(event_type: &str) -> EventSchema {
+        catalog()
+            .into_iter()
+            .find(|e| e.event_type == event_type)
+            .unwrap_or_else(|| panic!("{event_type} missing from catalog"))
+    }
+
+    #[test]
+    fn test_event_types_are_unique() {
+        let events = catalog();
+        let names: BTreeSet<_> = events.iter().map(|e| e.event_type).collect();
+        assert_eq!(names.len(), events.len());
+    }
+
+    #[test]
+    fn test_amount_limit_schema_matches_payload() {
+        let tx = Transaction::new(
+            "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ".to_string(),
+            BigDecimal::from(5),
+            "USDC".to_string(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        let limits = AmountLimits {
+            min_amount: Some(BigDecimal::from(10)),
+            max_amount: None,
+        };
+        let payload = OutgoingPayload {
+            event_type: REFUND_PENDING_EVENT.to_string(),
+            transaction_id: tx.id.to_string(),
+            timestamp: chrono::Utc::now(),
+            data: serde_json::json!(AmountLimitNotification::new(&tx, &limits)),
+        };
+        let payload = serde_json::json!(payload);
+
+        for event_type in [REFUND_PENDING_EVENT, COMPLIANCE_REVIEW_EVENT] {
+            let schema = event(event_type).schema;
+            assert_eq!(properties(&schema), keys(&payload));
+            assert_eq!(
+                properties(&schema["properties"]["data"]),
+                keys(&payload["data"])
+            );
+        }
+    }
+
+    #[test]
+    fn test_ping_schema_matches_payload() {
+        let payload = serde_json::json!(PingPayload {
+            event_type: PING_EVENT,
+            subscription_id: Uuid::new_v4(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            data: serde_json::Map::new(),
+        });
+        assert_eq!(properties(&event(PING_EVENT).schema), keys(&payload));
+    }
+
+    #[test]
+    fn test_statement_ready_schema_matches_payload() {
+        let statement = crate::db::models::PartnerStatement {
+            id: Uuid::new_v4(),
+            tenant_id: Uuid::new_v4(),
+            period_start: chrono::NaiveDate::from_ymd_opt(2026, 5, 1).unwrap(),
+            job_id: Uuid::new_v4(),
+            lines: serde_json::json!([]),
+            generated_at: chrono::Utc::now(),
+        };
+        let period = crate::services::accounting::Period::parse("2026-05").unwrap();
+        let data = serde_json::json!(StatementReadyNotification::new(&statement, period));
+        let schema = event(STATEMENT_READY_EVENT).schema;
+        assert_eq!(properties(&schema["properties"]["data"]), keys(&data));
+    }
+
+    #[test]
+    fn test_status_update_schema_covers_all_fields() {
+        let update = TransactionStatusUpdate {
+            transaction_id: Uuid::new_v4(),
+            tenant_id: Uuid::new_v4(),
+            status: "completed".to_string(),
+            timestamp: chrono::Utc::now(),
+            message: None,
+            stellar_tx_hash: Some("abc".to_string()),
+            ledger: Some(1),
+            closed_at: Some(chrono::Utc::now()),
+        };
+        assert_eq!(
+            properties(&event(STATUS_UPDATE_EVENT).schema),
+            keys(&serde_json::json!(update))
+        );
+    }
+}
@@ -0,0 +1,113 @@
+//! In-process broadcast channels, one per event class.
+//!
+//! Each class has its own channel so a burst in one (say, a large settlement
+//! run) cannot push messages for another out of a shared buffer, and each
+//! subscriber attaches only to the class it serves. Services see them through
+//! the [`EventBus`](crate::ports::EventBus) port, as
+//! [`BroadcastEventBus`](crate::adapters::BroadcastEventBus).
+//!
+//! | Class          | Capacity env var                  | Default | Subscribers                 |
+//! |----------------|-----------------------------------|---------|-----------------------------|
+//! | `transactions` | `BROADCAST_CAPACITY_TRANSACTIONS` | 100     | `/ws`, GraphQL subscription |
+//! | `settlements`  | `BROADCAST_CAPACITY_SETTLEMENTS`  | 100     | GraphQL subscriptions       |
+//! | `admin`        | `BROADCAST_CAPACITY_ADMIN`        | 256     | [`breakers::record_events`] |
+//!
+//! A subscriber that falls more than a channel's capacity behind loses the
+//! oldest messages; every such loss is counted in
+//! `broadcast_messages_lagged_total{class}` via [`record_lag`].
+//!
+//! [`breakers::record_events`]: super::breakers::record_events
+
+use opentelemetry::KeyValue;
+use tokio::sync::broadcast;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventClass {
+    Transactions,
+    Settlements,
+    Admin,
+}
+
+impl EventClass {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EventClass::Transactions => "transactions",
+            EventClass::Settlements => "settlements",
+            EventClass::Admin => "admin",
+        }
+    }
+
+    fn capacity_var(self) -> &'static str {
+        match self {
+            EventClass::Transactions => "BROADCAST_CAPACITY_TRANSACTIONS",
+            EventClass::Settlements => "BROADCAST_CAPACITY_SETTLEMENTS",
+            EventClass::Admin => "BROADCAST_CAPACITY_ADMIN",
+        }
+    }
+
+    fn default_capacity(self) -> usize {
+        match self {
+            EventClass::Transactions | EventClass::Settlements => 100,
+            EventClass::Admin => 256,
+        }
+    }
+
+    /// Channel capacity from the class's env var; unset, unparsable or zero
+    /// values fall back to the default.
+    pub fn capacity(self) -> usize {
+        parse_capacity(std::env::var(self.capacity_var()).ok().as_deref())
+            .unwrap_or_else(|| self.default_capacity())
+    }
+}
+
+fn parse_capacity(raw: Option<&str>) -> Option<usize> {
+    raw.and_then(|v| v.trim().parse().ok()).filter(|c| *c > 0)
+}
+
+/// New sender for `class`, sized from its configured capacity.
+pub fn channel<T: Clone>(class: EventClass) -> broadcast::Sender<T> {
+    let capacity = class.capacity();
+    tracing::info!(
+        class = class.as_str(),
+        capacity,
+        "Broadcast channel initialized"
+    );
+    broadcast::channel(capacity).0
+}
+
+/// Count `skipped` messages a lagging subscriber of `class` never received.
+pub fn record_lag(class: EventClass, skipped: u64) {
+    crate::metrics::broadcast_messages_lagged_total()
+        .add(skipped, &[KeyValue::new("class", class.as_str())]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_capacity_rejects_zero_and_garbage() {
+        assert_eq!(parse_capacity(Some(" 500 ")), Some(500));
+        assert_eq!(parse_capacity(Some("0")), None);
+        assert_eq!(parse_capacity(Some("-1")), None);
+        assert_eq!(parse_capacity(Some("lots")), None);
+        assert_eq!(parse_capacity(None), None);
+    }
+
+    #[test]
+    fn test_channel_uses_class_capacity() {
+        std::env::set_var("BROADCAST_CAPACITY_SETTLEMENTS", "2");
+        let tx = channel::<u8>(EventClass::Settlements);
+        std::env::remove_var("BROADCAST_CAPACITY_SETTLEMENTS");
+
+        let mut rx = tx.subscribe();
+        for i in 0..3 {
+            tx.send(i).unwrap();
+        }
+        assert!(matches!(
+            rx.try_recv(),
+            Err(broadcast::error::TryRecvError::Lagged(1))
+        ));
+        assert_eq!(rx.try_recv().unwrap(), 1);
+    }
+}
@@ -0,0 +1,475 @@
+//! Asynchronous transaction exports for large (e.g. month-end) reports.
+//!
+//! `POST /exports` queues a `transaction_export` job (see
+//! [`crate::services::job_runner`]) with the requested format and filters (the
+//! same filters as `GET /transactions/search`). [`ExportJobHandler`] pages
+//! through the matching transactions and writes a CSV or NDJSON file to
+//! `EXPORT_DIR` (default `<BACKUP_DIR>/exports`). Each page is checkpointed,
+//! so an interrupted export resumes where it stopped and a cancelled one stops
+//! after the current page. The finished file is uploaded to the object store
+//! (see [`crate::adapters::object_store`]) as `exports/<id>.<format>`.
+//!
+//! `GET /exports/:id` reports the job and, once completed, a signed download
+//! URL (see [`crate::utils::signed_url`]) over the job id. Without a signing
+//! secret exports still run but no download URL is issued.
+
+use crate::db::models::{Transaction, TransactionStatus};
+use crate::db::queries;
+use crate::domain::StellarAddress;
+use crate::ports::ObjectStore;
+use crate::services::job_runner::{JobContext, JobHandler, JobKind};
+use crate::utils::signed_url;
+use crate::validation::validate_metadata;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::types::BigDecimal;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+use uuid::Uuid;
+
+/// Rows fetched per page while writing an export.
+const PAGE_SIZE: i64 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Ndjson,
+}
+
+impl ExportFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Ndjson => "ndjson",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv",
+            ExportFormat::Ndjson => "application/x-ndjson",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(ExportFormat::Csv),
+            "ndjson" => Ok(ExportFormat::Ndjson),
+            _ => Err(format!("Invalid export format: {s}")),
+        }
+    }
+}
+
+/// Transaction filters captured with the job.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExportFilters {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset_code: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_amount: Option<BigDecimal>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_amount: Option<BigDecimal>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stellar_account: Option<String>,
+    /// JSON object; matches transactions whose metadata contains it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+}
+
+impl ExportFilters {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(status) = &self.status {
+            TransactionStatus::from_str(status)?;
+        }
+        if let (Some(min), Some(max)) = (&self.min_amount, &self.max_amount) {
+            if min > max {
+                return Err("min_amount must not exceed max_amount".to_string());
+            }
+        }
+        if let (Some(from), Some(to)) = (&self.from, &self.to) {
+            if from > to {
+                return Err("from must not be after to".to_string());
+            }
+        }
+        if let Some(account) = &self.stellar_account {
+            StellarAddress::parse(account).map_err(|e| format!("stellar_account: {e}"))?;
+        }
+        if let Some(metadata) = &self.metadata {
+            validate_metadata(metadata).map_err(|e| format!("metadata: {e}"))?;
+        }
+        Ok(())
+    }
+}
+
+/// `params` of a `transaction_export` job.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportParams {
+    pub format: ExportFormat,
+    #[serde(default)]
+    pub filters: ExportFilters,
+}
+
+/// Resume state: rows and bytes already in the partial file, and the keyset
+/// cursor of the last written row.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ExportCheckpoint {
+    rows: i64,
+    bytes: u64,
+    cursor: Option<(DateTime<Utc>, Uuid)>,
+}
+
+/// One exported transaction. Metadata is deliberately left out: it is partner
+/// data and export files leave the service.
+#[derive(Debug, Serialize)]
+struct ExportRow {
+    id: String,
+    stellar_account: String,
+    amount: String,
+    asset_code: String,
+    status: String,
+    created_at: String,
+    updated_at: String,
+    anchor_transaction_id: Option<String>,
+    callback_type: Option<String>,
+    callback_status: Option<String>,
+}
+
+impl From<&Transaction> for ExportRow {
+    fn from(tx: &Transaction) -> Self {
+        ExportRow {
+            id: tx.id.to_string(),
+            stellar_account: tx.stellar_account.clone(),
+            amount: tx.amount.to_string(),
+            asset_code: tx.asset_code.clone(),
+            status: tx.status.to_string(),
+            created_at: tx.created_at.to_rfc3339(),
+            updated_at: tx.updated_at.to_rfc3339(),
+            anchor_transaction_id: tx.anchor_transaction_id.clone(),
+            callback_type: tx.callback_type.clone(),
+            callback_status: tx.callback_status.clone(),
+        }
+    }
+}
+
+/// Encode one page of rows. The CSV header is only written for the first page.
+fn encode_page(
+    format: ExportFormat,
+    rows: &[Transaction],
+    with_header: bool,
+) -> anyhow::Result<Vec<u8>> {
+    match format {
+        ExportFormat::Csv => {
+            let mut wtr = csv::WriterBuilder::new()
+                .has_headers(with_header)
+                .from_writer(vec![]);
+            for tx in rows {
+                wtr.serialize(ExportRow::from(tx))?;
+            }
+            Ok(wtr.into_inner()?)
+        }
+        ExportFormat::Ndjson => {
+            let mut out = Vec::new();
+            for tx in rows {
+                serde_json::to_writer(&mut out, &ExportRow::from(tx))?;
+                out.push(b'\n');
+            }
+            Ok(out)
+        }
+    }
+}
+
+/// Directory export files are written to before upload (`EXPORT_DIR`).
+pub fn export_dir() -> PathBuf {
+    std::env::var("EXPORT_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| crate::adapters::backup_dir().join("exports"))
+}
+
+/// Signature of the download link for export `id`; see
+/// [`crate::utils::signed_url`]. The signed resource is the bare job id.
+pub fn sign_download(secret: &str, id: Uuid, expires: i64) -> String {
+    signed_url::sign(secret, &id.to_string(), expires)
+}
+
+/// Check a download signature and its expiry (a unix timestamp).
+pub fn verify_download(
+    secret: &str,
+    id: Uuid,
+    expires: i64,
+    signature: &str,
+    now: DateTime<Utc>,
+) -> bool {
+    signed_url::verify(secret, &id.to_string(), expires, signature, now)
+}
+
+/// Relative download URL for `id`, valid until the returned expiry.
+pub fn download_url(secret: &str, id: Uuid, now: DateTime<Utc>) -> (String, DateTime<Utc>) {
+    signed_url::sign_url(
+        secret,
+        &format!("/exports/{id}/download"),
+        &id.to_string(),
+        now,
+    )
+}
+
+/// Name of the finished export file for job `id`.
+pub fn file_name(id: Uuid, format: ExportFormat) -> String {
+    format!("{id}.{}", format.as_str())
+}
+
+/// Object store key of the finished export file for job `id`.
+pub fn object_key(id: Uuid, format: ExportFormat) -> String {
+    format!("exports/{}", file_name(id, format))
+}
+
+/// Write every transaction matching the job's filters to `dir`, then upload
+/// the file to `store` under [`object_key`].
+///
+/// The file is written under a temporary name and only uploaded once
+/// complete, so a partially written export is never served. After each page
+/// the byte offset and cursor are checkpointed; a resumed job truncates the
+/// partial file back to the last checkpoint and continues from there. Returns
+/// the row count.
+async fn write_export(
+    ctx: &mut JobContext,
+    dir: &Path,
+    store: &dyn ObjectStore,
+) -> anyhow::Result<(String, i64)> {
+    let params: ExportParams = ctx.params()?;
+    let format = params.format;
+    let filters = params.filters;
+    let stellar_account = filters
+        .stellar_account
+        .as_deref()
+        .map(StellarAddress::parse)
+        .transpose()?;
+
+    tokio::fs::create_dir_all(dir).await?;
+    let name = file_name(ctx.id(), format);
+    let tmp_path = dir.join(format!("{name}.partial"));
+
+    let resume = match ctx.checkpoint::<ExportCheckpoint>()? {
+        Some(cp) => match tokio::fs::metadata(&tmp_path).await {
+            Ok(meta) if meta.len() >= cp.bytes => Some(cp),
+            _ => None,
+        },
+        None => None,
+    };
+    let mut checkpoint = resume.unwrap_or(ExportCheckpoint {
+        rows: 0,
+        bytes: 0,
+        cursor: None,
+    });
+    let mut file = tokio::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .open(&tmp_path)
+        .await?;
+    file.set_len(checkpoint.bytes).await?;
+    file.seek(std::io::SeekFrom::Start(checkpoint.bytes))
+        .await?;
+
+    loop {
+        let (total, page) = queries::search_transactions(
+            ctx.pool(),
+            filters.status.as_deref(),
+            filters.asset_code.as_deref(),
+            filters.min_amount.as_ref(),
+            filters.max_amount.as_ref(),
+            filters.from,
+            filters.to,
+            stellar_account.as_ref(),
+            filters.metadata.as_ref(),
+            PAGE_SIZE,
+            checkpoint.cursor,
+        )
+        .await?;
+
+        if !page.is_empty() {
+            let bytes = encode_page(format, &page, checkpoint.rows == 0)?;
+            file.write_all(&bytes).await?;
+            file.flush().await?;
+            checkpoint.bytes += bytes.len() as u64;
+            checkpoint.rows += page.len() as i64;
+        }
+        let full_page = page.len() as i64 == PAGE_SIZE;
+        if let Some(last) = page.last() {
+            checkpoint.cursor = Some((last.created_at, last.id));
+        }
+        // `total` counts rows from the cursor on, so it shrinks page by page.
+        let remaining = (total - page.len() as i64).max(0);
+        ctx.save_progress(
+            checkpoint.rows,
+            Some(checkpoint.rows + remaining),
+            &checkpoint,
+        )
+        .await?;
+        if !full_page {
+            break;
+        }
+    }
+
+    file.sync_all().await?;
+    drop(file);
+    store
+        .put_file(&object_key(ctx.id(), format), &tmp_path)
+        .await?;
+    tokio::fs::remove_file(&tmp_path).await?;
+    Ok((name, checkpoint.rows))
+}
+
+/// [`JobHandler`] for `transaction_export` jobs.
+pub struct ExportJobHandler {
+    pub store: Arc<dyn ObjectStore>,
+}
+
+#[async_trait]
+impl JobHandler for ExportJobHandler {
+    fn kind(&self) -> JobKind {
+        JobKind::TransactionExport
+    }
+
+    async fn run(&self, ctx: &mut JobContext) -> anyhow::Result<serde_json::Value> {
+        let (file, row_count) = write_export(ctx, &export_dir(), self.store.as_ref()).await?;
+        Ok(serde_json::json!({ "file": file, "row_count": row_count }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn tx() -> Transaction {
+        Transaction::new(
+            "GABC123".to_string(),
+            BigDecimal::from(100),
+            "USD".to_string(),
+            Some("anchor-1".to_string()),
+            Some("deposit".to_string()),
+            Some("completed".to_string()),
+            None,
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_signature_round_trip_and_expiry() {
+        let id = Uuid::new_v4();
+        let now = Utc::now();
+        let expires = now.timestamp() + 60;
+        let sig = sign_download("secret", id, expires);
+
+        assert!(verify_download("secret", id, expires, &sig, now));
+        assert!(!verify_download("other", id, expires, &sig, now));
+        assert!(!verify_download(
+            "secret",
+            Uuid::new_v4(),
+            expires,
+            &sig,
+            now
+        ));
+        assert!(!verify_download("secret", id, expires + 1, &sig, now));
+        assert!(!verify_download("secret", id, expires, "zz", now));
+        assert!(!verify_download(
+            "secret",
+            id,
+            expires,
+            &sig,
+            now + Duration::seconds(61)
+        ));
+    }
+
+    #[test]
+    fn test_download_url_is_verifiable() {
+        let id = Uuid::new_v4();
+        let now = Utc::now();
+        let (url, expires_at) = download_url("secret", id, now);
+        let sig = url.rsplit("signature=").next().unwrap();
+        assert!(url.starts_with(&format!("/exports/{id}/download?expires=")));
+        assert!(verify_download(
+            "secret",
+            id,
+            expires_at.timestamp(),
+            sig,
+            now
+        ));
+    }
+
+    #[test]
+    fn test_filters_validation() {
+        assert!(ExportFilters::default().validate().is_ok());
+        let filters: ExportFilters =
+            serde_json::from_str(r#"{"status":"completed","min_amount":"1","max_amount":"10"}"#)
+                .unwrap();
+        assert!(filters.validate().is_ok());
+
+        let bad_status = ExportFilters {
+            status: Some("done".to_string()),
+            ..Default::default()
+        };
+        assert!(bad_status.validate().is_err());
+
+        let inverted: ExportFilters =
+            serde_json::from_str(r#"{"min_amount":"10","max_amount":"1"}"#).unwrap();
+        assert!(inverted.validate().is_err());
+
+        let not_object = ExportFilters {
+            metadata: Some(serde_json::json!([1])),
+            ..Default::default()
+        };
+        assert!(not_object.validate().is_err());
+
+        let muxed = ExportFilters {
+            stellar_account: Some(
+                "MA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJUAAAAAAAAAAE2JUG6".to_string(),
+            ),
+            ..Default::default()
+        };
+        assert!(muxed.validate().is_ok());
+        let bad_account = ExportFilters {
+            stellar_account: Some("GABC".to_string()),
+            ..Default::default()
+        };
+        assert!(bad_account.validate().is_err());
+    }
+
+    #[test]
+    fn test_encode_page_csv_header_only_on_first_page() {
+        let rows = vec![tx(), tx()];
+        let first =
+            String::from_utf8(encode_page(ExportFormat::Csv, &rows, true).unwrap()).unwrap();
+        let next =
+            String::from_utf8(encode_page(ExportFormat::Csv, &rows, false).unwrap()).unwrap();
+        assert_eq!(first.lines().count(), 3);
+        assert!(first.starts_with("id,stellar_account,amount"));
+        assert_eq!(next.lines().count(), 2);
+    }
+
+    #[test]
+    fn test_encode_page_ndjson_one_object_per_line() {
+        let out = encode_page(ExportFormat::Ndjson, &[tx(), tx()], true).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text.lines().count(), 2);
+        for line in text.lines() {
+            let value: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(value["asset_code"], "USD");
+            assert!(value.get("metadata").is_none());
+        }
+    }
+}
@@ -0,0 +1,355 @@
+//! Per-operation fee the anchor bids on the transactions it builds.
+//!
+//! [`poll_fee_stats`] caches Horizon's `/fee_stats` every
+//! [`FEE_STATS_INTERVAL`], and [`FeeTracker::base_fee`] turns the cached
+//! fees charged over the last few ledgers into a bid:
+//!
+//! | Strategy | Bid                                   |
+//! |----------|---------------------------------------|
+//! | `p50`    | Median fee charged                    |
+//! | `p90`    | 90th percentile, to land under surge  |
+//! | `fixed`  | Always `STELLAR_FEE_CAP`              |
+//!
+//! A bid is at least the network minimum and the last ledger's base fee,
+//! and at most `STELLAR_FEE_CAP`. Without stats from the last
+//! [`FEE_STATS_MAX_AGE`] it is the cap, so a Horizon outage cannot leave
+//! transactions bidding too little to land.
+//!
+//! | Env var                | Default | Meaning                                 |
+//! |------------------------|---------|-----------------------------------------|
+//! | `STELLAR_FEE_STRATEGY` | `p50`   | `p50`, `p90` or `fixed`                 |
+//! | `STELLAR_FEE_CAP`      | `2000`  | Highest bid per operation, in stroops   |
+//!
+//! Fees charged on the anchor's submissions are counted in
+//! `stellar_fee_charged_stroops_total` and summed over the last
+//! [`SPEND_WINDOW`] for `GET /admin/info`; the current bid is the
+//! `stellar_base_fee_stroops` gauge.
+
+use crate::stellar::builder::BASE_FEE;
+use crate::stellar::{FeeStats, HorizonClient};
+use anyhow::bail;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Mutex, OnceLock, RwLock};
+
+const DEFAULT_FEE_CAP: u32 = 2_000;
+
+/// How often [`poll_fee_stats`] refreshes the cached stats.
+pub const FEE_STATS_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Age past which cached stats no longer price a bid.
+pub const FEE_STATS_MAX_AGE: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// Span of recent fee spend reported by [`FeeTracker::status`].
+pub const SPEND_WINDOW: std::time::Duration = std::time::Duration::from_secs(3_600);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeStrategy {
+    P50,
+    P90,
+    Fixed,
+}
+
+impl FeeStrategy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FeeStrategy::P50 => "p50",
+            FeeStrategy::P90 => "p90",
+            FeeStrategy::Fixed => "fixed",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "p50" => Some(FeeStrategy::P50),
+            "p90" => Some(FeeStrategy::P90),
+            "fixed" => Some(FeeStrategy::Fixed),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct FeePolicy {
+    pub strategy: FeeStrategy,
+    /// Stroops per operation.
+    pub cap: u32,
+}
+
+impl Default for FeePolicy {
+    fn default() -> Self {
+        Self {
+            strategy: FeeStrategy::P50,
+            cap: DEFAULT_FEE_CAP,
+        }
+    }
+}
+
+impl FeePolicy {
+    /// From `STELLAR_FEE_STRATEGY` and `STELLAR_FEE_CAP`; unknown values are
+    /// errors rather than defaults, like any misconfigured spend limit.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let mut policy = Self::default();
+        if let Ok(raw) = std::env::var("STELLAR_FEE_STRATEGY") {
+            if !raw.trim().is_empty() {
+                policy.strategy = match FeeStrategy::parse(&raw) {
+                    Some(strategy) => strategy,
+                    None => {
+                        bail!("STELLAR_FEE_STRATEGY must be 'p50', 'p90' or 'fixed', got '{raw}'")
+                    }
+                };
+            }
+        }
+        if let Ok(raw) = std::env::var("STELLAR_FEE_CAP") {
+            policy.cap = match raw.trim().parse::<u32>() {
+                Ok(cap) if cap >= BASE_FEE => cap,
+                _ => bail!("STELLAR_FEE_CAP must be a number of stroops of at least {BASE_FEE}"),
+            };
+        }
+        Ok(policy)
+    }
+
+    /// The bid for `stats`, or the cap without any.
+    pub fn bid(&self, stats: Option<&FeeStats>) -> u32 {
+        let Some(stats) = stats else {
+            return self.cap;
+        };
+        let wanted = match self.strategy {
+            FeeStrategy::P50 => stats.fee_charged.p50,
+            FeeStrategy::P90 => stats.fee_charged.p90,
+            FeeStrategy::Fixed => self.cap,
+        };
+        wanted
+            .max(stats.last_ledger_base_fee)
+            .max(BASE_FEE)
+            .min(self.cap)
+    }
+}
+
+/// Fees charged over [`SPEND_WINDOW`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct FeeSpend {
+    pub window_secs: u64,
+    pub transactions: u64,
+    pub stroops: i64,
+}
+
+/// What `GET /admin/info` reports under `fees`.
+#[derive(Debug, Clone, Serialize)]
+pub struct FeeStatus {
+    pub strategy: FeeStrategy,
+    pub cap: u32,
+    /// Current bid per operation, in stroops.
+    pub base_fee: u32,
+    pub stats: Option<FeeStats>,
+    pub stats_fetched_at: Option<DateTime<Utc>>,
+    pub recent_spend: FeeSpend,
+}
+
+#[derive(Debug)]
+pub struct FeeTracker {
+    policy: FeePolicy,
+    stats: RwLock<Option<(FeeStats, DateTime<Utc>)>>,
+    spend: Mutex<VecDeque<(DateTime<Utc>, i64)>>,
+}
+
+impl FeeTracker {
+    pub fn new(policy: FeePolicy) -> Self {
+        Self {
+            policy,
+            stats: RwLock::new(None),
+            spend: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub fn policy(&self) -> FeePolicy {
+        self.policy
+    }
+
+    pub fn set_stats(&self, stats: FeeStats, fetched_at: DateTime<Utc>) {
+        *self.stats.write().unwrap_or_else(|e| e.into_inner()) = Some((stats, fetched_at));
+    }
+
+    /// The cached stats, unless older than [`FEE_STATS_MAX_AGE`] at `now`.
+    fn fresh_stats(&self, now: DateTime<Utc>) -> Option<FeeStats> {
+        let max_age = Duration::from_std(FEE_STATS_MAX_AGE).expect("fits");
+        self.stats
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .filter(|(_, fetched_at)| now - *fetched_at <= max_age)
+            .map(|(stats, _)| stats.clone())
+    }
+
+    /// Stroops per operation to offer on a transaction built now.
+    pub fn base_fee(&self) -> u32 {
+        self.policy.bid(self.fresh_stats(Utc::now()).as_ref())
+    }
+
+    /// Note `stroops` charged on a submitted transaction at `at`.
+    pub fn record_spend(&self, stroops: i64, at: DateTime<Utc>) {
+        let mut spend = self.spend.lock().unwrap_or_else(|e| e.into_inner());
+        spend.push_back((at, stroops));
+        prune(&mut spend, at);
+    }
+
+    pub fn recent_spend(&self, now: DateTime<Utc>) -> FeeSpend {
+        let mut spend = self.spend.lock().unwrap_or_else(|e| e.into_inner());
+        prune(&mut spend, now);
+        FeeSpend {
+            window_secs: SPEND_WINDOW.as_secs(),
+            transactions: spend.len() as u64,
+            stroops: spend.iter().map(|(_, stroops)| stroops).sum(),
+        }
+    }
+
+    pub fn status(&self) -> FeeStatus {
+        let now = Utc::now();
+        let cached = self.stats.read().unwrap_or_else(|e| e.into_inner()).clone();
+        FeeStatus {
+            strategy: self.policy.strategy,
+            cap: self.policy.cap,
+            base_fee: self.policy.bid(self.fresh_stats(now).as_ref()),
+            stats_fetched_at: cached.as_ref().map(|(_, at)| *at),
+            stats: cached.map(|(stats, _)| stats),
+            recent_spend: self.recent_spend(now),
+        }
+    }
+}
+
+fn prune(spend: &mut VecDeque<(DateTime<Utc>, i64)>, now: DateTime<Utc>) {
+    let window = Duration::from_std(SPEND_WINDOW).expect("fits");
+    while spend.front().is_some_and(|(at, _)| now - *at > window) {
+        spend.pop_front();
+    }
+}
+
+static FEES: OnceLock<FeeTracker> = OnceLock::new();
+
+/// Install the process-wide fee tracker; only the first call has effect.
+pub fn init(policy: FeePolicy) -> &'static FeeTracker {
+    let _ = FEES.set(FeeTracker::new(policy));
+    fees()
+}
+
+/// Process-wide fee tracker; the default policy until [`init`].
+pub fn fees() -> &'static FeeTracker {
+    FEES.get_or_init(|| FeeTracker::new(FeePolicy::default()))
+}
+
+/// [`FeeTracker::base_fee`] of the process-wide tracker.
+pub fn base_fee() -> u32 {
+    fees().base_fee()
+}
+
+/// Count `stroops` charged on a submitted transaction.
+pub fn record_spend(stroops: i64) {
+    fees().record_spend(stroops, Utc::now());
+    if let Ok(stroops) = u64::try_from(stroops) {
+        crate::metrics::stellar_fee_charged_stroops_total().add(stroops, &[]);
+    }
+}
+
+/// Keep the process-wide fee stats fresh. While Horizon is unreachable the
+/// last stats stand until they age out.
+pub async fn poll_fee_stats(horizon: HorizonClient) {
+    let _gauge = crate::metrics::stellar_base_fee_stroops();
+    let tracker = fees();
+    let mut interval = tokio::time::interval(FEE_STATS_INTERVAL);
+    loop {
+        interval.tick().await;
+        match horizon.get_fee_stats().await {
+            Ok(stats) => {
+                tracing::debug!(
+                    last_ledger = stats.last_ledger,
+                    p50 = stats.fee_charged.p50,
+                    p90 = stats.fee_charged.p90,
+                    capacity_usage = stats.ledger_capacity_usage,
+                    "Fee stats refreshed"
+                );
+                tracker.set_stats(stats, Utc::now());
+            }
+            Err(e) => tracing::debug!("Failed to fetch fee stats: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stellar::FeeDistribution;
+
+    fn stats(p50: u32, p90: u32) -> FeeStats {
+        FeeStats {
+            last_ledger: 51_234_567,
+            last_ledger_base_fee: 100,
+            ledger_capacity_usage: 0.97,
+            fee_charged: FeeDistribution {
+                min: 100,
+                mode: 100,
+                p50,
+                p90,
+                p99: p90 * 2,
+                max: p90 * 4,
+            },
+        }
+    }
+
+    #[test]
+    fn test_bid_follows_strategy_within_cap() {
+        let p50 = FeePolicy {
+            strategy: FeeStrategy::P50,
+            cap: 1_000,
+        };
+        let p90 = FeePolicy {
+            strategy: FeeStrategy::P90,
+            ..p50
+        };
+        let fixed = FeePolicy {
+            strategy: FeeStrategy::Fixed,
+            ..p50
+        };
+
+        assert_eq!(p50.bid(Some(&stats(150, 800))), 150);
+        assert_eq!(p90.bid(Some(&stats(150, 800))), 800);
+        assert_eq!(p90.bid(Some(&stats(150, 5_000))), 1_000);
+        assert_eq!(fixed.bid(Some(&stats(150, 800))), 1_000);
+        // Never under the network minimum, and the cap without stats.
+        assert_eq!(p50.bid(Some(&stats(0, 0))), BASE_FEE);
+        assert_eq!(p50.bid(None), 1_000);
+    }
+
+    #[test]
+    fn test_stale_stats_fall_back_to_cap() {
+        let tracker = FeeTracker::new(FeePolicy::default());
+        let now = Utc::now();
+        tracker.set_stats(stats(150, 800), now - Duration::seconds(60));
+        assert_eq!(tracker.base_fee(), 150);
+
+        tracker.set_stats(stats(150, 800), now - Duration::seconds(301));
+        assert_eq!(tracker.base_fee(), DEFAULT_FEE_CAP);
+        assert!(tracker.status().stats.is_some());
+    }
+
+    #[test]
+    fn test_recent_spend_drops_old_entries() {
+        let tracker = FeeTracker::new(FeePolicy::default());
+        let now = Utc::now();
+        tracker.record_spend(500, now - Duration::seconds(3_601));
+        tracker.record_spend(200, now - Duration::seconds(60));
+        tracker.record_spend(300, now);
+
+        let spend = tracker.recent_spend(now);
+        assert_eq!(spend.transactions, 2);
+        assert_eq!(spend.stroops, 500);
+    }
+
+    #[test]
+    fn test_parse_strategy() {
+        assert_eq!(FeeStrategy::parse(" P90 "), Some(FeeStrategy::P90));
+        assert_eq!(FeeStrategy::parse("fixed"), Some(FeeStrategy::Fixed));
+        assert_eq!(FeeStrategy::parse("p75"), None);
+    }
+}
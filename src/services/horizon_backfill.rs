@@ -0,0 +1,339 @@
+//! Import an account's historical payments from Horizon as transactions.
+//!
+//! Anchors that join mid-stream have no callbacks for payments they received
+//! before switching over. A `horizon_backfill` job pages
+//! `/accounts/{account}/payments` oldest first and stores every inbound
+//! `payment` made within `{"from", "to"}` (inclusive UTC days) as a
+//! `completed` transaction with `backfilled = true`.
+//!
+//! Each imported row gets `anchor_transaction_id = "horizon:<payment id>"`, and
+//! [`queries::insert_backfilled_transaction`] skips anchor ids that are already
+//! active, so re-running a backfill over an overlapping range only adds what
+//! is missing. The Horizon paging token is checkpointed after every page.
+//!
+//! Queue one with `synapse-core tx backfill <ACCOUNT> --from --to` or
+//! `POST /admin/jobs`.
+
+use crate::db::models::{Transaction, TransactionStatus};
+use crate::db::{cron, queries};
+use crate::domain::StellarAddress;
+use crate::services::job_runner::{DateRangeParams, JobContext, JobHandler, JobKind};
+use crate::stellar::{HorizonClient, PaymentRecord};
+use crate::validation::parse_stellar_address;
+use async_trait::async_trait;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::types::BigDecimal;
+use std::str::FromStr;
+
+/// Records requested per Horizon page (Horizon's maximum).
+const PAGE_SIZE: u32 = 200;
+
+/// Prefix of the `anchor_transaction_id` given to imported payments.
+pub const ANCHOR_ID_PREFIX: &str = "horizon:";
+
+/// `callback_type` of imported transactions.
+const CALLBACK_TYPE: &str = "horizon_backfill";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HorizonBackfillParams {
+    /// Receiving account whose history is imported.
+    pub account: String,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+impl HorizonBackfillParams {
+    pub fn validate(&self) -> Result<(), String> {
+        // Horizon pages payments by base account; muxed ids are per payment.
+        if parse_stellar_address(&self.account)
+            .map_err(|e| e.to_string())?
+            .is_muxed()
+        {
+            return Err("account must be a G... account, not a muxed address".to_string());
+        }
+        self.range().validate()
+    }
+
+    fn range(&self) -> DateRangeParams {
+        DateRangeParams {
+            from: self.from,
+            to: self.to,
+        }
+    }
+
+    fn start(&self) -> DateTime<Utc> {
+        self.from.and_time(NaiveTime::MIN).and_utc()
+    }
+
+    /// Exclusive upper bound: midnight after `to`.
+    fn end(&self) -> DateTime<Utc> {
+        (self.to + Duration::days(1))
+            .and_time(NaiveTime::MIN)
+            .and_utc()
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BackfillCheckpoint {
+    /// Paging token of the last record processed.
+    cursor: Option<String>,
+    imported: i64,
+    already_present: i64,
+}
+
+/// Build the transaction to import for `record`, or `None` when it is not an
+/// inbound payment to `account` with a usable amount.
+pub fn payment_to_transaction(record: &PaymentRecord, account: &str) -> Option<Transaction> {
+    if record.kind != "payment" || record.to.as_deref() != Some(account) {
+        return None;
+    }
+    // Horizon reports a muxed sender in `from_muxed`, with `from` its base.
+    let from =
+        StellarAddress::parse(record.from_muxed.as_deref().or(record.from.as_deref())?).ok()?;
+    let amount = BigDecimal::from_str(record.amount.as_deref()?).ok()?;
+    if amount <= BigDecimal::from(0) {
+        return None;
+    }
+    let asset_code = match record.asset_type.as_deref() {
+        Some("native") => "XLM".to_string(),
+        _ => record.asset_code.clone()?,
+    };
+    let created_at = DateTime::parse_from_rfc3339(&record.created_at)
+        .ok()?
+        .with_timezone(&Utc);
+    let (memo, memo_type) = match &record.transaction {
+        Some(t) => (t.memo.clone(), t.memo_type.clone()),
+        None => (None, None),
+    };
+
+    let mut tx = Transaction::new(
+        from.account().to_string(),
+        amount,
+        asset_code,
+        Some(format!("{ANCHOR_ID_PREFIX}{}", record.id)),
+        Some(CALLBACK_TYPE.to_string()),
+        Some("completed".to_string()),
+        memo,
+        memo_type,
+        None,
+    )
+    .with_muxed_id(from.muxed_id());
+    tx.status = TransactionStatus::Completed;
+    tx.created_at = created_at;
+    tx.updated_at = created_at;
+    tx.backfilled = true;
+    tx.stellar_tx_hash = record.transaction_hash.clone();
+    tx.closed_at = Some(created_at);
+    Some(tx)
+}
+
+/// Months (year, month) touched by the range, so their partitions exist
+/// before rows are inserted into them.
+fn months(from: NaiveDate, to: NaiveDate) -> Vec<(i32, u32)> {
+    let mut out = Vec::new();
+    let (mut year, mut month) = (from.year(), from.month());
+    while (year, month) <= (to.year(), to.month()) {
+        out.push((year, month));
+        (year, month) = if month == 12 {
+            (year + 1, 1)
+        } else {
+            (year, month + 1)
+        };
+    }
+    out
+}
+
+/// [`JobHandler`] for `horizon_backfill` jobs.
+pub struct HorizonBackfillHandler {
+    pub horizon_client: HorizonClient,
+}
+
+#[async_trait]
+impl JobHandler for HorizonBackfillHandler {
+    fn kind(&self) -> JobKind {
+        JobKind::HorizonBackfill
+    }
+
+    async fn run(&self, ctx: &mut JobContext) -> anyhow::Result<serde_json::Value> {
+        let params: HorizonBackfillParams = ctx.params()?;
+        let (start, end) = (params.start(), params.end());
+        let mut cp = ctx.checkpoint::<BackfillCheckpoint>()?.unwrap_or_default();
+
+        for (year, month) in months(params.from, params.to) {
+            cron::create_month_partition(ctx.pool(), year, month).await?;
+        }
+
+        // Horizon cannot filter payments by time, so history before `from` is
+        // paged through and skipped; the cursor makes that a one-time cost.
+        loop {
+            let page = self
+                .horizon_client
+                .get_payments_page(&params.account, cp.cursor.as_deref(), PAGE_SIZE)
+                .await?;
+            let Some(last) = page.last() else {
+                break;
+            };
+            let mut past_end = false;
+
+            for record in &page {
+                let at = DateTime::parse_from_rfc3339(&record.created_at)?.with_timezone(&Utc);
+                if at >= end {
+                    past_end = true;
+                    break;
+                }
+                if at < start {
+                    continue;
+                }
+                let Some(tx) = payment_to_transaction(record, &params.account) else {
+                    continue;
+                };
+                match queries::insert_backfilled_transaction(ctx.pool(), &tx).await? {
+                    Some(_) => cp.imported += 1,
+                    None => cp.already_present += 1,
+                }
+            }
+
+            cp.cursor = Some(last.paging_token.clone());
+            ctx.save_progress(cp.imported + cp.already_present, None, &cp)
+                .await?;
+            if past_end || page.len() < PAGE_SIZE as usize {
+                break;
+            }
+        }
+
+        tracing::info!(
+            account = %params.account,
+            imported = cp.imported,
+            already_present = cp.already_present,
+            "Horizon backfill finished"
+        );
+        Ok(serde_json::json!({
+            "imported": cp.imported,
+            "already_present": cp.already_present,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ACCOUNT: &str = "GBBD47UZQ5CSKQPV456PYYH4FSYJHBWGQJUVNMCNWZ2NBEHKQPW3LEFO";
+    const SENDER: &str = "GAAZI4TCR3TY5OJHCTJC2A4QSY6CJWJH5IAJTGKIN2ER7LBNVKOCCWN7";
+    /// `SENDER` muxed with id 42.
+    const MUXED_SENDER: &str =
+        "MAAZI4TCR3TY5OJHCTJC2A4QSY6CJWJH5IAJTGKIN2ER7LBNVKOCCAAAAAAAAAAAFIV6I";
+
+    fn record(json: serde_json::Value) -> PaymentRecord {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn payment(to: &str, amount: &str) -> PaymentRecord {
+        record(serde_json::json!({
+            "id": "12345",
+            "paging_token": "12345",
+            "type": "payment",
+            "created_at": "2026-03-01T11:00:00Z",
+            "from": SENDER,
+            "to": to,
+            "amount": amount,
+            "asset_type": "credit_alphanum4",
+            "asset_code": "USD",
+            "transaction": { "memo": "ref-1", "memo_type": "text" }
+        }))
+    }
+
+    #[test]
+    fn test_inbound_payment_maps_to_backfilled_transaction() {
+        let tx = payment_to_transaction(&payment(ACCOUNT, "25.5000000"), ACCOUNT).unwrap();
+        assert!(tx.backfilled);
+        assert_eq!(tx.status, TransactionStatus::Completed);
+        assert_eq!(tx.stellar_account, SENDER);
+        assert_eq!(tx.amount, BigDecimal::from_str("25.5").unwrap());
+        assert_eq!(tx.asset_code, "USD");
+        assert_eq!(tx.anchor_transaction_id.as_deref(), Some("horizon:12345"));
+        assert_eq!(tx.memo.as_deref(), Some("ref-1"));
+        assert_eq!(tx.created_at.to_rfc3339(), "2026-03-01T11:00:00+00:00");
+    }
+
+    #[test]
+    fn test_muxed_sender_is_split() {
+        let mut record = payment(ACCOUNT, "5.0");
+        record.from_muxed = Some(MUXED_SENDER.to_string());
+        let tx = payment_to_transaction(&record, ACCOUNT).unwrap();
+        assert_eq!(tx.stellar_account, SENDER);
+        assert_eq!(tx.muxed_account().as_deref(), Some(MUXED_SENDER));
+    }
+
+    #[test]
+    fn test_outbound_and_non_payment_records_are_ignored() {
+        assert!(payment_to_transaction(&payment(SENDER, "1.0"), ACCOUNT).is_none());
+        assert!(payment_to_transaction(&payment(ACCOUNT, "0"), ACCOUNT).is_none());
+
+        let create = record(serde_json::json!({
+            "id": "1",
+            "paging_token": "1",
+            "type": "create_account",
+            "created_at": "2026-03-01T10:00:00Z"
+        }));
+        assert!(payment_to_transaction(&create, ACCOUNT).is_none());
+    }
+
+    #[test]
+    fn test_native_payments_use_xlm() {
+        let native = record(serde_json::json!({
+            "id": "7",
+            "paging_token": "7",
+            "type": "payment",
+            "created_at": "2026-03-01T10:00:00Z",
+            "from": SENDER,
+            "to": ACCOUNT,
+            "amount": "3.0000000",
+            "asset_type": "native"
+        }));
+        let tx = payment_to_transaction(&native, ACCOUNT).unwrap();
+        assert_eq!(tx.asset_code, "XLM");
+        assert!(tx.memo.is_none());
+    }
+
+    #[test]
+    fn test_params_validation() {
+        let ok = HorizonBackfillParams {
+            account: ACCOUNT.to_string(),
+            from: NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+            to: NaiveDate::from_ymd_opt(2026, 3, 31).unwrap(),
+        };
+        assert!(ok.validate().is_ok());
+        assert_eq!(ok.end().to_rfc3339(), "2026-04-01T00:00:00+00:00");
+
+        let bad_account = HorizonBackfillParams {
+            account: "not-an-account".to_string(),
+            ..ok.clone()
+        };
+        assert!(bad_account.validate().is_err());
+
+        let muxed = HorizonBackfillParams {
+            account: MUXED_SENDER.to_string(),
+            ..ok.clone()
+        };
+        assert!(muxed.validate().is_err());
+
+        let inverted = HorizonBackfillParams {
+            from: ok.to,
+            to: ok.from,
+            ..ok
+        };
+        assert!(inverted.validate().is_err());
+    }
+
+    #[test]
+    fn test_months_spans_year_boundary() {
+        let from = NaiveDate::from_ymd_opt(2025, 11, 15).unwrap();
+        let to = NaiveDate::from_ymd_opt(2026, 2, 1).unwrap();
+        assert_eq!(
+            months(from, to),
+            vec![(2025, 11), (2025, 12), (2026, 1), (2026, 2)]
+        );
+    }
+}
@@ -0,0 +1,348 @@
+//! Housekeeping: prunes rows that only matter for a while and would otherwise
+//! accumulate forever, and clears stale Redis idempotency locks.
+//!
+//! | Table                | Pruned when                                  | Default retention |
+//! |----------------------|----------------------------------------------|-------------------|
+//! | `idempotency_keys`   | `expires_at` is older than the retention     | 0 (on expiry)     |
+//! | `webhook_inbox`      | `received_at` is older than the retention    | 168 h             |
+//! | `job_runs`           | finished runs that started before it         | 720 h             |
+//! | `webhook_deliveries` | `delivered` rows delivered before it         | 336 h             |
+//!
+//! Override a retention with `HOUSEKEEPING_<TABLE>_RETENTION_HOURS`, e.g.
+//! `HOUSEKEEPING_JOB_RUNS_RETENTION_HOURS=168`. `webhook_inbox` is never
+//! pruned inside the webhook dedup window (`WEBHOOK_DEDUP_WINDOW_HOURS`).
+//!
+//! Rows are deleted in batches of `JOB_HOUSEKEEPING_BATCH_SIZE` (default 500)
+//! with `HOUSEKEEPING_BATCH_PAUSE_MS` (default 200) between batches, so each
+//! transaction stays short and autovacuum can keep up. Job runs still
+//! `running` after `HOUSEKEEPING_STALE_RUN_HOURS` (default 24) belonged to an
+//! instance that died mid-run and are marked `failed`.
+
+use crate::config::jobs::JobSettings;
+use crate::db::queries;
+use crate::middleware::idempotency::IdempotencyService;
+use crate::services::scheduler::{CancellationToken, Job};
+use anyhow::Context;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use sqlx::PgPool;
+use std::time::Duration;
+use tracing::{info, warn};
+
+const DEFAULT_BATCH_SIZE: u32 = 500;
+const HOUR: Duration = Duration::from_secs(3600);
+
+/// A table the housekeeping job prunes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Table {
+    IdempotencyKeys,
+    WebhookInbox,
+    JobRuns,
+    WebhookDeliveries,
+}
+
+impl Table {
+    pub const ALL: [Table; 4] = [
+        Table::IdempotencyKeys,
+        Table::WebhookInbox,
+        Table::JobRuns,
+        Table::WebhookDeliveries,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Table::IdempotencyKeys => "idempotency_keys",
+            Table::WebhookInbox => "webhook_inbox",
+            Table::JobRuns => "job_runs",
+            Table::WebhookDeliveries => "webhook_deliveries",
+        }
+    }
+
+    fn default_retention(self) -> Duration {
+        match self {
+            Table::IdempotencyKeys => Duration::ZERO,
+            Table::WebhookInbox => 168 * HOUR,
+            Table::JobRuns => 720 * HOUR,
+            Table::WebhookDeliveries => 336 * HOUR,
+        }
+    }
+
+    fn retention_var(self) -> String {
+        format!(
+            "HOUSEKEEPING_{}_RETENTION_HOURS",
+            self.as_str().to_ascii_uppercase()
+        )
+    }
+}
+
+/// Retention and pacing settings, see the module docs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HousekeepingConfig {
+    retention: [Duration; 4],
+    pub stale_run_after: Duration,
+    pub batch_pause: Duration,
+}
+
+impl Default for HousekeepingConfig {
+    fn default() -> Self {
+        Self {
+            retention: Table::ALL.map(Table::default_retention),
+            stale_run_after: 24 * HOUR,
+            batch_pause: Duration::from_millis(200),
+        }
+    }
+}
+
+fn parse_u64(var: &str, value: &str) -> anyhow::Result<u64> {
+    value
+        .trim()
+        .parse()
+        .with_context(|| format!("{var} must be a non-negative integer"))
+}
+
+fn hours(n: u64) -> Duration {
+    Duration::from_secs(n.saturating_mul(3600))
+}
+
+impl HousekeepingConfig {
+    pub fn from_env() -> anyhow::Result<Self> {
+        Self::from_vars(std::env::vars())
+    }
+
+    pub fn from_vars(vars: impl IntoIterator<Item = (String, String)>) -> anyhow::Result<Self> {
+        let mut config = Self::default();
+        for (var, value) in vars {
+            if let Some(i) = Table::ALL.iter().position(|t| t.retention_var() == var) {
+                config.retention[i] = hours(parse_u64(&var, &value)?);
+            } else if var == "HOUSEKEEPING_STALE_RUN_HOURS" {
+                let n = parse_u64(&var, &value)?;
+                if n == 0 {
+                    anyhow::bail!("{var} must be a positive integer");
+                }
+                config.stale_run_after = hours(n);
+            } else if var == "HOUSEKEEPING_BATCH_PAUSE_MS" {
+                config.batch_pause = Duration::from_millis(parse_u64(&var, &value)?);
+            }
+        }
+        Ok(config)
+    }
+
+    /// How long rows of `table` are kept.
+    pub fn retention(&self, table: Table) -> Duration {
+        let i = Table::ALL.iter().position(|t| *t == table).unwrap_or(0);
+        let configured = self.retention[i];
+        match table {
+            // Pruning inside the dedup window would let duplicates through.
+            Table::WebhookInbox => configured.max(
+                crate::services::webhook_dedup::DedupConfig::from_env()
+                    .window
+                    .unwrap_or_default(),
+            ),
+            _ => configured,
+        }
+    }
+}
+
+/// Rows removed by one housekeeping run.
+#[derive(Debug, Default)]
+pub struct HousekeepingReport {
+    pub deleted: Vec<(Table, u64)>,
+    pub abandoned_runs: u64,
+}
+
+/// Scheduled job running the housekeeping described in the module docs.
+pub struct HousekeepingJob {
+    pool: PgPool,
+    idempotency: Option<IdempotencyService>,
+    config: HousekeepingConfig,
+    batch_size: u32,
+}
+
+impl HousekeepingJob {
+    pub fn new(pool: PgPool, config: HousekeepingConfig) -> Self {
+        Self {
+            pool,
+            idempotency: None,
+            config,
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+
+    /// Also clear stale Redis idempotency locks on each run.
+    pub fn with_idempotency(mut self, service: IdempotencyService) -> Self {
+        self.idempotency = Some(service);
+        self
+    }
+
+    /// Prune every table, stopping early if `cancel` fires.
+    pub async fn run(&self, cancel: &CancellationToken) -> anyhow::Result<HousekeepingReport> {
+        let now = Utc::now();
+        let mut report = HousekeepingReport::default();
+
+        let stale_cutoff = now - chrono::Duration::from_std(self.config.stale_run_after)?;
+        report.abandoned_runs = queries::fail_abandoned_job_runs(&self.pool, stale_cutoff).await?;
+        if report.abandoned_runs > 0 {
+            warn!(
+                count = report.abandoned_runs,
+                "Marked abandoned job runs as failed"
+            );
+        }
+
+        for table in Table::ALL {
+            if cancel.is_cancelled() {
+                break;
+            }
+            let cutoff = now - chrono::Duration::from_std(self.config.retention(table))?;
+            let deleted = self.prune(table, cutoff, cancel).await?;
+            report.deleted.push((table, deleted));
+        }
+
+        if let Some(service) = &self.idempotency {
+            if let Err(e) = service.recover_stale_locks().await {
+                warn!("Failed to clear stale idempotency locks: {}", e);
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Delete rows of `table` older than `cutoff` a batch at a time.
+    async fn prune(
+        &self,
+        table: Table,
+        cutoff: DateTime<Utc>,
+        cancel: &CancellationToken,
+    ) -> anyhow::Result<u64> {
+        let limit = i64::from(self.batch_size);
+        let mut total = 0;
+        loop {
+            let deleted = self.prune_batch(table, cutoff, limit).await?;
+            total += deleted;
+            if deleted < limit as u64 {
+                return Ok(total);
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(self.config.batch_pause) => {}
+                _ = cancel.cancelled() => return Ok(total),
+            }
+        }
+    }
+
+    fn prune_batch(
+        &self,
+        table: Table,
+        cutoff: DateTime<Utc>,
+        limit: i64,
+    ) -> BoxFuture<'_, sqlx::Result<u64>> {
+        let pool = &self.pool;
+        match table {
+            Table::IdempotencyKeys => {
+                Box::pin(queries::prune_idempotency_keys_batch(pool, cutoff, limit))
+            }
+            Table::WebhookInbox => {
+                Box::pin(queries::prune_webhook_inbox_batch(pool, cutoff, limit))
+            }
+            Table::JobRuns => Box::pin(queries::prune_job_runs_batch(pool, cutoff, limit)),
+            Table::WebhookDeliveries => {
+                Box::pin(queries::prune_delivered_webhooks_batch(pool, cutoff, limit))
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Job for HousekeepingJob {
+    fn name(&self) -> &str {
+        "housekeeping"
+    }
+
+    /// Every hour at quarter past.
+    fn schedule(&self) -> &str {
+        "0 15 * * * *"
+    }
+
+    fn configure(&mut self, settings: &JobSettings) {
+        if let Some(size) = settings.batch_size {
+            self.batch_size = size;
+        }
+    }
+
+    async fn execute(
+        &self,
+        cancel: &CancellationToken,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let report = self.run(cancel).await?;
+        for (table, deleted) in &report.deleted {
+            if *deleted > 0 {
+                crate::metrics::housekeeping_rows_deleted_total().add(
+                    *deleted,
+                    &[opentelemetry::KeyValue::new("table", table.as_str())],
+                );
+            }
+        }
+        info!(
+            deleted = ?report.deleted,
+            abandoned_runs = report.abandoned_runs,
+            "Housekeeping complete"
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_from_vars_overrides_per_table_retention() {
+        let config = HousekeepingConfig::from_vars(vars(&[
+            ("HOUSEKEEPING_JOB_RUNS_RETENTION_HOURS", "48"),
+            ("HOUSEKEEPING_IDEMPOTENCY_KEYS_RETENTION_HOURS", "1"),
+            ("HOUSEKEEPING_STALE_RUN_HOURS", "6"),
+            ("HOUSEKEEPING_BATCH_PAUSE_MS", "0"),
+        ]))
+        .unwrap();
+
+        assert_eq!(config.retention(Table::JobRuns), 48 * HOUR);
+        assert_eq!(config.retention(Table::IdempotencyKeys), HOUR);
+        assert_eq!(config.retention(Table::WebhookDeliveries), 336 * HOUR);
+        assert_eq!(config.stale_run_after, 6 * HOUR);
+        assert_eq!(config.batch_pause, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_from_vars_rejects_invalid_values() {
+        for (var, value) in [
+            ("HOUSEKEEPING_JOB_RUNS_RETENTION_HOURS", "-1"),
+            ("HOUSEKEEPING_WEBHOOK_INBOX_RETENTION_HOURS", "soon"),
+            ("HOUSEKEEPING_STALE_RUN_HOURS", "0"),
+            ("HOUSEKEEPING_BATCH_PAUSE_MS", "x"),
+        ] {
+            assert!(
+                HousekeepingConfig::from_vars(vars(&[(var, value)])).is_err(),
+                "{var}={value} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_webhook_inbox_is_kept_for_the_dedup_window() {
+        let config = HousekeepingConfig::from_vars(vars(&[(
+            "HOUSEKEEPING_WEBHOOK_INBOX_RETENTION_HOURS",
+            "1",
+        )]))
+        .unwrap();
+        let window = crate::services::webhook_dedup::DedupConfig::from_env()
+            .window
+            .unwrap_or_default();
+        assert_eq!(config.retention(Table::WebhookInbox), window.max(HOUR));
+    }
+}
@@ -0,0 +1,478 @@
+//! Long-running background jobs: exports, reconciliation backfills, rollup
+//! recomputes, Horizon history imports, shadow-store comparisons and partner
+//! statements.
+//!
+//! A job is a row in `jobs` with a [`JobKind`] and JSON `params`. The
+//! [`JobRunner`] is a scheduler [`Job`](crate::services::scheduler::Job) that
+//! every minute claims queued jobs whose kind has a registered
+//! [`JobHandler`] and runs them one at a time:
+//!
+//! ```text
+//! queued ──▶ running ──▶ completed
+//!   │           ├──────▶ failed
+//!   └───────────┴──────▶ cancelled
+//! ```
+//!
+//! Handlers report progress through [`JobContext::save_progress`], which also
+//! persists a kind-specific checkpoint and returns [`Cancelled`] once a
+//! cancellation has been requested. If an instance dies mid-job the heartbeat
+//! goes stale, another runner reclaims the job and the handler resumes from
+//! [`JobContext::checkpoint`].
+//!
+//! Status is exposed at `/admin/jobs` and through the GraphQL `job` / `jobs`
+//! queries and `cancelJob` mutation.
+
+use crate::db::models::BackgroundJob;
+use crate::db::queries;
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDate, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// A running job whose heartbeat is older than this is reclaimed.
+const STALE_AFTER_MINUTES: i64 = 15;
+
+/// Longest date range a backfill or recompute may cover.
+pub const MAX_RANGE_DAYS: i64 = 366;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobKind {
+    /// CSV / NDJSON transaction export (`POST /exports`).
+    TransactionExport,
+    /// Re-run daily reconciliation over past days.
+    ReconciliationBackfill,
+    /// Rebuild `transaction_daily_rollups` from `transactions`.
+    RollupRecompute,
+    /// Import an account's past payments from Horizon.
+    HorizonBackfill,
+    /// Compare the shadow-written transaction table with the primary.
+    ShadowCompare,
+    /// Monthly CSV / PDF statements per partner (`POST /admin/statements`).
+    PartnerStatement,
+}
+
+impl JobKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobKind::TransactionExport => "transaction_export",
+            JobKind::ReconciliationBackfill => "reconciliation_backfill",
+            JobKind::RollupRecompute => "rollup_recompute",
+            JobKind::HorizonBackfill => "horizon_backfill",
+            JobKind::ShadowCompare => "shadow_compare",
+            JobKind::PartnerStatement => "partner_statement",
+        }
+    }
+
+    /// Check `params` before the job is queued.
+    pub fn validate_params(&self, params: &serde_json::Value) -> Result<(), String> {
+        match self {
+            JobKind::TransactionExport => {
+                serde_json::from_value::<crate::services::export_jobs::ExportParams>(params.clone())
+                    .map_err(|e| format!("Invalid params: {e}"))?
+                    .filters
+                    .validate()
+            }
+            JobKind::ReconciliationBackfill | JobKind::RollupRecompute | JobKind::ShadowCompare => {
+                serde_json::from_value::<DateRangeParams>(params.clone())
+                    .map_err(|e| format!("Invalid params: {e}"))?
+                    .validate()
+            }
+            JobKind::HorizonBackfill => serde_json::from_value::<
+                crate::services::horizon_backfill::HorizonBackfillParams,
+            >(params.clone())
+            .map_err(|e| format!("Invalid params: {e}"))?
+            .validate(),
+            JobKind::PartnerStatement => serde_json::from_value::<
+                crate::services::statements::StatementParams,
+            >(params.clone())
+            .map_err(|e| format!("Invalid params: {e}"))?
+            .validate(),
+        }
+    }
+}
+
+impl FromStr for JobKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "transaction_export" => Ok(JobKind::TransactionExport),
+            "reconciliation_backfill" => Ok(JobKind::ReconciliationBackfill),
+            "rollup_recompute" => Ok(JobKind::RollupRecompute),
+            "horizon_backfill" => Ok(JobKind::HorizonBackfill),
+            "shadow_compare" => Ok(JobKind::ShadowCompare),
+            "partner_statement" => Ok(JobKind::PartnerStatement),
+            _ => Err(format!("Invalid job kind: {s}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobState {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobState::Queued => "queued",
+            JobState::Running => "running",
+            JobState::Completed => "completed",
+            JobState::Failed => "failed",
+            JobState::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl FromStr for JobState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "queued" => Ok(JobState::Queued),
+            "running" => Ok(JobState::Running),
+            "completed" => Ok(JobState::Completed),
+            "failed" => Ok(JobState::Failed),
+            "cancelled" => Ok(JobState::Cancelled),
+            _ => Err(format!("Invalid job status: {s}")),
+        }
+    }
+}
+
+/// Inclusive range of UTC days, the params of day-by-day jobs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DateRangeParams {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+impl DateRangeParams {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.from > self.to {
+            return Err("from must not be after to".to_string());
+        }
+        if self.to > Utc::now().date_naive() {
+            return Err("to must not be in the future".to_string());
+        }
+        if self.days() > MAX_RANGE_DAYS {
+            return Err(format!("range must not exceed {MAX_RANGE_DAYS} days"));
+        }
+        Ok(())
+    }
+
+    /// Number of days in the range.
+    pub fn days(&self) -> i64 {
+        (self.to - self.from).num_days() + 1
+    }
+}
+
+/// Checkpoint of day-by-day jobs: the first day not yet processed.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DayCheckpoint {
+    pub next_day: NaiveDate,
+}
+
+/// Returned (inside `anyhow::Error`) by [`JobContext::save_progress`] once a
+/// cancellation has been requested; the runner records the job as cancelled.
+#[derive(Debug, thiserror::Error)]
+#[error("job cancelled")]
+pub struct Cancelled;
+
+/// What a handler sees of the job it is running.
+pub struct JobContext {
+    pool: PgPool,
+    job: BackgroundJob,
+}
+
+impl JobContext {
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.job.id
+    }
+
+    pub fn params<T: DeserializeOwned>(&self) -> anyhow::Result<T> {
+        Ok(serde_json::from_value(self.job.params.clone())?)
+    }
+
+    /// Checkpoint saved by a previous attempt, if the job is being resumed.
+    pub fn checkpoint<T: DeserializeOwned>(&self) -> anyhow::Result<Option<T>> {
+        match &self.job.checkpoint {
+            None | Some(serde_json::Value::Null) => Ok(None),
+            Some(value) => Ok(Some(serde_json::from_value(value.clone())?)),
+        }
+    }
+
+    /// Progress recorded with the last checkpoint.
+    pub fn progress(&self) -> i64 {
+        self.job.progress_current
+    }
+
+    /// Persist progress and checkpoint. Fails with [`Cancelled`] when the job
+    /// should stop; call it only at points the job can safely resume from.
+    pub async fn save_progress<T: Serialize>(
+        &mut self,
+        current: i64,
+        total: Option<i64>,
+        checkpoint: &T,
+    ) -> anyhow::Result<()> {
+        let checkpoint = serde_json::to_value(checkpoint)?;
+        let cancel_requested =
+            queries::update_job_progress(&self.pool, self.job.id, current, total, &checkpoint)
+                .await?;
+        self.job.progress_current = current;
+        self.job.checkpoint = Some(checkpoint);
+        if cancel_requested {
+            return Err(Cancelled.into());
+        }
+        Ok(())
+    }
+}
+
+/// Runs jobs of one kind.
+#[async_trait]
+pub trait JobHandler: Send + Sync {
+    fn kind(&self) -> JobKind;
+
+    /// Do the work, returning the job's result. Must resume from
+    /// [`JobContext::checkpoint`] when one is present.
+    async fn run(&self, ctx: &mut JobContext) -> anyhow::Result<serde_json::Value>;
+}
+
+/// Scheduler job draining the `jobs` queue.
+pub struct JobRunner {
+    pool: PgPool,
+    handlers: HashMap<JobKind, Arc<dyn JobHandler>>,
+    /// Jobs run per tick at most (`JOB_JOB_RUNNER_BATCH_SIZE`); unlimited
+    /// when unset.
+    max_per_tick: Option<u32>,
+}
+
+impl JobRunner {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            handlers: HashMap::new(),
+            max_per_tick: None,
+        }
+    }
+
+    /// Register the handler for its kind; jobs of kinds without a handler
+    /// stay queued.
+    pub fn register(mut self, handler: impl JobHandler + 'static) -> Self {
+        self.handlers.insert(handler.kind(), Arc::new(handler));
+        self
+    }
+
+    /// Claim and run one job. Returns the finished job, or `None` when the
+    /// queue is empty.
+    pub async fn run_next(&self) -> Result<Option<BackgroundJob>, sqlx::Error> {
+        let kinds: Vec<&str> = self.handlers.keys().map(JobKind::as_str).collect();
+        if kinds.is_empty() {
+            return Ok(None);
+        }
+        let stale_before = Utc::now() - Duration::minutes(STALE_AFTER_MINUTES);
+        let Some(job) = queries::claim_next_job(&self.pool, &kinds, stale_before).await? else {
+            return Ok(None);
+        };
+        self.run(job).await.map(Some)
+    }
+
+    async fn run(&self, job: BackgroundJob) -> Result<BackgroundJob, sqlx::Error> {
+        let id = job.id;
+        let kind = job.kind.clone();
+
+        if job.cancel_requested {
+            return queries::finish_job(&self.pool, id, JobState::Cancelled.as_str(), None, None)
+                .await;
+        }
+        let handler = match JobKind::from_str(&kind)
+            .ok()
+            .and_then(|k| self.handlers.get(&k))
+        {
+            Some(handler) => Arc::clone(handler),
+            None => {
+                let error = format!("no handler registered for {kind}");
+                return queries::finish_job(
+                    &self.pool,
+                    id,
+                    JobState::Failed.as_str(),
+                    None,
+                    Some(&error),
+                )
+                .await;
+            }
+        };
+
+        tracing::info!(job_id = %id, kind = %kind, resumed = job.checkpoint.is_some(), "Job started");
+        let mut ctx = JobContext {
+            pool: self.pool.clone(),
+            job,
+        };
+        match handler.run(&mut ctx).await {
+            Ok(result) => {
+                tracing::info!(job_id = %id, kind = %kind, "Job completed");
+                queries::finish_job(
+                    &self.pool,
+                    id,
+                    JobState::Completed.as_str(),
+                    Some(&result),
+                    None,
+                )
+                .await
+            }
+            Err(e) if e.is::<Cancelled>() => {
+                tracing::info!(job_id = %id, kind = %kind, "Job cancelled");
+                queries::finish_job(&self.pool, id, JobState::Cancelled.as_str(), None, None).await
+            }
+            Err(e) => {
+                tracing::error!(job_id = %id, kind = %kind, error = %e, "Job failed");
+                queries::finish_job(
+                    &self.pool,
+                    id,
+                    JobState::Failed.as_str(),
+                    None,
+                    Some(&e.to_string()),
+                )
+                .await
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl crate::services::scheduler::Job for JobRunner {
+    fn name(&self) -> &str {
+        "job_runner"
+    }
+
+    /// Every minute.
+    fn schedule(&self) -> &str {
+        "0 * * * * *"
+    }
+
+    fn configure(&mut self, settings: &crate::config::jobs::JobSettings) {
+        self.max_per_tick = settings.batch_size;
+    }
+
+    /// Stops claiming further jobs once `cancel` fires; the job in progress
+    /// keeps its own checkpoint-based cancellation.
+    async fn execute(
+        &self,
+        cancel: &crate::services::scheduler::CancellationToken,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.execute_with(cancel, &Default::default()).await
+    }
+
+    /// A `batch_size` parameter caps this run like `JOB_JOB_RUNNER_BATCH_SIZE`.
+    async fn execute_with(
+        &self,
+        cancel: &crate::services::scheduler::CancellationToken,
+        params: &crate::services::scheduler::RunParams,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let max_per_tick = params.batch_size.or(self.max_per_tick);
+        let mut ran = 0;
+        loop {
+            if cancel.is_cancelled() || max_per_tick.is_some_and(|max| ran >= max) {
+                break;
+            }
+            if self.run_next().await?.is_none() {
+                break;
+            }
+            ran += 1;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_kind_round_trip() {
+        for kind in [
+            JobKind::TransactionExport,
+            JobKind::ReconciliationBackfill,
+            JobKind::RollupRecompute,
+            JobKind::HorizonBackfill,
+            JobKind::ShadowCompare,
+            JobKind::PartnerStatement,
+        ] {
+            assert_eq!(JobKind::from_str(kind.as_str()), Ok(kind));
+        }
+        assert!(JobKind::from_str("bulk_delete").is_err());
+    }
+
+    #[test]
+    fn test_date_range_validation() {
+        let ok = DateRangeParams {
+            from: NaiveDate::from_ymd_opt(2026, 5, 1).unwrap(),
+            to: NaiveDate::from_ymd_opt(2026, 5, 31).unwrap(),
+        };
+        assert!(ok.validate().is_ok());
+        assert_eq!(ok.days(), 31);
+
+        let inverted = DateRangeParams {
+            from: ok.to,
+            to: ok.from,
+        };
+        assert!(inverted.validate().is_err());
+
+        let future = DateRangeParams {
+            from: ok.from,
+            to: Utc::now().date_naive() + Duration::days(1),
+        };
+        assert!(future.validate().is_err());
+
+        let too_long = DateRangeParams {
+            from: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            to: NaiveDate::from_ymd_opt(2025, 6, 1).unwrap(),
+        };
+        assert!(too_long.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_params_by_kind() {
+        assert!(JobKind::RollupRecompute
+            .validate_params(&json!({"from": "2026-05-01", "to": "2026-05-02"}))
+            .is_ok());
+        assert!(JobKind::ReconciliationBackfill
+            .validate_params(&json!({"from": "2026-05-01"}))
+            .is_err());
+        assert!(JobKind::TransactionExport
+            .validate_params(&json!({"format": "csv", "filters": {"status": "completed"}}))
+            .is_ok());
+        assert!(JobKind::TransactionExport
+            .validate_params(&json!({"format": "xlsx"}))
+            .is_err());
+        assert!(JobKind::HorizonBackfill
+            .validate_params(&json!({"from": "2026-05-01", "to": "2026-05-02"}))
+            .is_err());
+        assert!(JobKind::PartnerStatement
+            .validate_params(&json!({"period": "2026-05"}))
+            .is_ok());
+        assert!(JobKind::PartnerStatement
+            .validate_params(&json!({"period": "2026-05", "tenant_id": "acme"}))
+            .is_err());
+    }
+
+    #[test]
+    fn test_cancelled_is_detectable_through_anyhow() {
+        let err: anyhow::Error = Cancelled.into();
+        assert!(err.is::<Cancelled>());
+    }
+}
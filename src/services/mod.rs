@@ -1,28 +1,82 @@
 pub mod account_monitor;
+pub mod accounting;
+pub mod amount_limits;
+pub mod approvals;
+pub mod asset_trust;
+#[cfg(feature = "backup")]
 pub mod backup;
+#[cfg(feature = "backup")]
+pub mod backup_keys;
+#[cfg(feature = "backup")]
+pub mod backup_pitr;
+pub mod breakers;
+pub mod claimable_balances;
 pub mod compliance;
+pub mod dlq_capacity;
+pub mod dlq_drain;
+pub mod dlq_errors;
+pub mod event_catalog;
+pub mod event_channels;
+pub mod export_jobs;
 pub mod feature_flags;
+pub mod fees;
+pub mod horizon_backfill;
+pub mod housekeeping;
+pub mod job_runner;
 pub mod lock_manager;
 pub mod processor;
 pub mod query_cache;
+pub mod quotes;
 pub mod reconciliation;
+pub mod refunds;
+pub mod region;
 pub mod resource_limits;
+pub mod review_queue;
+pub mod rollups;
 pub mod scheduler;
+pub mod seed;
+#[cfg(feature = "sep")]
+pub mod sep10;
+#[cfg(feature = "sep")]
+pub mod sep12;
+#[cfg(feature = "sep")]
+pub mod sep24;
+#[cfg(feature = "sep")]
+pub mod sep31;
+#[cfg(feature = "sep")]
+pub mod sep6;
 pub mod settlement;
+pub mod settlement_events;
+pub mod shadow_compare;
+pub mod signing_keys;
+pub mod statements;
+pub mod status_page;
+#[cfg(feature = "sep")]
+pub mod stellar_toml;
+pub mod structuring;
+pub mod submissions;
+pub mod transaction_events;
 pub mod transaction_processor;
 pub mod transaction_processor_job;
+pub mod webhook_dedup;
 pub mod webhook_dispatcher;
 
 pub use account_monitor::AccountMonitor;
+#[cfg(feature = "backup")]
 pub use backup::BackupService;
 pub use feature_flags::FeatureFlagService;
+pub use housekeeping::HousekeepingJob;
 pub use lock_manager::LeaderElection;
 pub use lock_manager::{FairLockConfig, FairLockManager};
 pub use query_cache::{CacheConfig, QueryCache};
 pub use reconciliation::ReconciliationService;
 pub use resource_limits::{ResourceLimiter, TaskLimits};
-pub use scheduler::{AuditLogRetentionJob, Job, JobScheduler, JobStatus};
+pub use scheduler::{
+    AuditLogRetentionJob, CancellationToken, Job, JobScheduler, JobStatus, RunOutcome, RunParams,
+    TriggerError, WebhookInboxRetentionJob,
+};
 pub use settlement::SettlementService;
+pub use settlement_events::{SettlementEvent, SettlementEventKind};
 pub use transaction_processor::TransactionProcessor;
 pub use transaction_processor_job::TransactionProcessorJob;
 pub use webhook_dispatcher::WebhookDispatcher;
@@ -1,13 +1,30 @@
-use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use opentelemetry::KeyValue;
+use sqlx::{PgPool, Postgres};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::watch;
 use tokio::time::{sleep, Duration};
-use tracing::{debug, error, info, warn};
+use tracing::field::Empty;
+use tracing::{debug, info, warn, Instrument};
 
-use crate::db::models::Transaction;
+use crate::adapters;
+use crate::db::audit::{AuditLog, ENTITY_TRANSACTION};
+use crate::db::models::{Asset, StellarTransaction, Transaction};
+use crate::db::queries;
+use crate::domain::{DomainEvent, StellarAddress};
+use crate::metrics;
+use crate::ports::{EventBus, RiskScorer, MAX_RISK_SCORE};
+use crate::services::asset_trust::{self, ObservedPayment, QuarantineSource};
+use crate::services::dlq_errors;
 use crate::services::lock_manager::LeaderElection;
-use crate::stellar::HorizonClient;
+use crate::services::region;
+use crate::stellar::builder::{format_stroops, to_stroops};
+use crate::stellar::client::parse_asset;
+use crate::stellar::{HorizonClient, HorizonError, TransactionRecord, Trustline};
+use crate::telemetry::BusinessAttributes;
+use crate::validation::state_machine::validate_status_transition;
+use crate::{sampled_error, sampled_warn};
 
 const LEADER_HEARTBEAT_SECS: u64 = 15;
 const POLL_INTERVAL_SECS: u64 = 5;
@@ -57,6 +74,8 @@ pub struct ProcessorPool {
     current_batch_size: Arc<AtomicU64>,
     /// Shared atomic for queue depth (read by back-pressure task).
     pending_queue_depth: Arc<AtomicU64>,
+    /// Receives the status changes and DLQ moves of each committed batch.
+    events: Option<Arc<dyn EventBus<DomainEvent>>>,
 }
 
 impl ProcessorPool {
@@ -82,9 +101,16 @@ impl ProcessorPool {
             scaling_factor,
             current_batch_size,
             pending_queue_depth,
+            events: None,
         }
     }
 
+    /// Publish the domain events of every processed batch on `events`.
+    pub fn with_events(mut self, events: Arc<dyn EventBus<DomainEvent>>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
     /// Start the processor pool. Returns a shutdown sender; drop or send to it to stop workers.
     pub fn start(self) -> watch::Sender<bool> {
         let (shutdown_tx, shutdown_rx) = watch::channel(false);
@@ -98,6 +124,7 @@ impl ProcessorPool {
         let pending_queue_depth = self.pending_queue_depth.clone();
         let pool = self.pool;
         let horizon_client = self.horizon_client;
+        let events = self.events;
 
         info!("Starting ProcessorPool with {} workers", workers);
 
@@ -107,6 +134,7 @@ impl ProcessorPool {
             let mut shutdown_rx = shutdown_rx.clone();
             let current_batch_size = current_batch_size.clone();
             let pending_queue_depth = pending_queue_depth.clone();
+            let events = events.clone();
             let mut sizer = BatchSizer::new(min_batch, max_batch, scaling_factor);
 
             tokio::spawn(async move {
@@ -118,12 +146,21 @@ impl ProcessorPool {
                         break;
                     }
 
+                    if !region::is_active() {
+                        debug!(worker_id, "region is passive; not processing");
+                        tokio::select! {
+                            _ = sleep(Duration::from_millis(poll_interval_ms)) => continue,
+                            _ = shutdown_rx.changed() => break,
+                        }
+                    }
+
                     let depth = pending_queue_depth.load(Ordering::Relaxed);
                     let batch_size = sizer.update(depth);
                     current_batch_size.store(batch_size as u64, Ordering::Relaxed);
                     debug!(worker_id, batch_size, depth, "adaptive batch size");
 
-                    match process_batch(&pool, &horizon_client, batch_size).await {
+                    match process_batch(&pool, &horizon_client, batch_size, events.as_deref()).await
+                    {
                         Ok(processed) => {
                             if processed > 0 {
                                 tracing::info!(
@@ -135,7 +172,13 @@ impl ProcessorPool {
                             tracing::info!(counter.processor_batches_total = 1u64, worker_id);
                         }
                         Err(e) => {
-                            error!(worker_id, "Processor batch error: {}", e);
+                            sampled_error!(
+                                "processor.batch",
+                                e,
+                                worker_id,
+                                "Processor batch error: {}",
+                                e
+                            );
                         }
                     }
 
@@ -156,23 +199,148 @@ impl ProcessorPool {
     }
 }
 
+/// Default for `HORIZON_VERIFY_MAX_ATTEMPTS`.
+pub const DEFAULT_VERIFY_MAX_ATTEMPTS: i32 = 5;
+
+/// Default for `RISK_REVIEW_THRESHOLD`.
+pub const DEFAULT_RISK_REVIEW_THRESHOLD: u8 = 60;
+
+const ACTOR: &str = "system";
+
+/// A pending row as selected by [`process_batch`].
+#[derive(sqlx::FromRow)]
+struct PendingTransaction {
+    #[sqlx(flatten)]
+    tx: Transaction,
+    verification_attempts: i32,
+    first_verification_at: Option<DateTime<Utc>>,
+    tenant_id: Option<uuid::Uuid>,
+}
+
+/// What a Horizon lookup says about completing a pending transaction.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verification {
+    /// Horizon reports the transaction successful in `ledger`, closed at
+    /// `closed_at`.
+    Verified {
+        ledger: i64,
+        closed_at: Option<DateTime<Utc>>,
+    },
+    /// Not checked this round (Horizon breaker open); no attempt is used up.
+    Deferred(String),
+    /// Not verified yet; counts against `HORIZON_VERIFY_MAX_ATTEMPTS`.
+    Retry(String),
+    /// Can never verify; the transaction fails straight away.
+    Reject(String),
+}
+
+/// Judges a Horizon lookup of `tx.stellar_tx_hash`.
+pub fn assess(
+    tx: &Transaction,
+    lookup: Result<Option<TransactionRecord>, HorizonError>,
+) -> Verification {
+    let record = match lookup {
+        Ok(Some(record)) => record,
+        Ok(None) => return Verification::Retry("transaction not found on Horizon".to_string()),
+        Err(HorizonError::CircuitBreakerOpen(e)) => {
+            return Verification::Deferred(format!("Horizon unavailable: {e}"))
+        }
+        Err(e) => return Verification::Retry(format!("Horizon lookup failed: {e}")),
+    };
+
+    if !record.successful {
+        return Verification::Reject(format!(
+            "Stellar transaction {} failed on-chain",
+            record.hash
+        ));
+    }
+    // Hash memos come back base64-encoded from Horizon, so only text and id
+    // memos are compared.
+    if let Some(memo) = &tx.memo {
+        let comparable = tx.memo_type.as_deref() != Some("hash");
+        if comparable && record.memo.as_deref() != Some(memo.as_str()) {
+            return Verification::Reject(format!(
+                "memo mismatch: expected {memo:?}, Stellar transaction has {:?}",
+                record.memo
+            ));
+        }
+    }
+    Verification::Verified {
+        ledger: record.ledger,
+        closed_at: DateTime::parse_from_rfc3339(&record.created_at)
+            .ok()
+            .map(|t| t.with_timezone(&Utc)),
+    }
+}
+
+fn verify_max_attempts() -> i32 {
+    std::env::var("HORIZON_VERIFY_MAX_ATTEMPTS")
+        .ok()
+        .and_then(|v| v.parse::<i32>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(DEFAULT_VERIFY_MAX_ATTEMPTS)
+}
+
+fn risk_review_threshold() -> u8 {
+    std::env::var("RISK_REVIEW_THRESHOLD")
+        .ok()
+        .and_then(|v| v.parse::<u8>().ok())
+        .filter(|n| (1..=MAX_RISK_SCORE).contains(n))
+        .unwrap_or(DEFAULT_RISK_REVIEW_THRESHOLD)
+}
+
+/// Completes pending transactions that Horizon confirms.
+///
+/// Only rows with a `stellar_tx_hash` are picked up. Each is risk-scored once
+/// by the configured [`RiskScorer`](crate::ports::RiskScorer); rows scoring at
+/// least `RISK_REVIEW_THRESHOLD` move to `compliance_review` instead. The rest
+/// become `completed` once Horizon reports the transaction successful
+/// (carrying the expected memo) and its payments use asset issuers the
+/// receiving partner trusts; withdrawals also need a destination trustline with
+/// room for the amount. Lookups that do not verify are retried on later
+/// batches; after `HORIZON_VERIFY_MAX_ATTEMPTS` of them, or at once when the
+/// network transaction failed, the row becomes `failed` and is copied to the
+/// DLQ.
+///
+/// Rows are picked round-robin by partner: round `n` takes each partner's
+/// oldest rows `(n-1)*w+1 ..= n*w`, where `w` is the partner's
+/// `processing_weight` (1 for rows without a partner), so a partner's backlog
+/// only delays others by its share of a round.
+///
+/// Once the batch commits, its status changes and DLQ moves are published on
+/// `events`, if given.
 pub async fn process_batch(
     pool: &PgPool,
-    _horizon_client: &HorizonClient,
+    horizon_client: &HorizonClient,
     batch_size: u32,
+    events: Option<&dyn EventBus<DomainEvent>>,
 ) -> anyhow::Result<usize> {
     let mut tx = pool.begin().await?;
 
-    let pending: Vec<Transaction> = sqlx::query_as::<_, Transaction>(
+    let pending: Vec<PendingTransaction> = sqlx::query_as::<_, PendingTransaction>(
         r#"
-        SELECT id, stellar_account, amount, asset_code, status, created_at, updated_at,
-               anchor_transaction_id, callback_type, callback_status, settlement_id,
-               memo, memo_type, metadata, priority, trace_id
-        FROM transactions
-        WHERE status = 'pending'
-        ORDER BY created_at ASC
+        WITH ranked AS (
+            SELECT t.id,
+                   CEIL(
+                       ROW_NUMBER() OVER (PARTITION BY t.tenant_id ORDER BY t.created_at)::numeric
+                       / COALESCE(p.processing_weight, 1)
+                   ) AS round
+            FROM transactions t
+            LEFT JOIN tenants p ON p.tenant_id = t.tenant_id
+            WHERE t.status = 'pending' AND t.stellar_tx_hash IS NOT NULL
+        )
+        SELECT t.id, t.stellar_account, t.amount, t.asset_code, t.status, t.created_at,
+               t.updated_at, t.anchor_transaction_id, t.callback_type, t.callback_status,
+               t.settlement_id, t.memo, t.memo_type, t.metadata, t.priority, t.trace_id,
+               t.backfilled, t.stellar_tx_hash, t.ledger, t.closed_at, t.stellar_muxed_id,
+               t.risk_score, t.risk_reasons, t.verification_attempts,
+               t.first_verification_at, t.tenant_id
+        FROM transactions t
+        JOIN ranked r ON r.id = t.id
+        WHERE r.round <= $1
+        ORDER BY r.round ASC, t.created_at ASC
         LIMIT $1
-        FOR UPDATE SKIP LOCKED
+        FOR UPDATE OF t SKIP LOCKED
         "#,
     )
     .bind(batch_size as i64)
@@ -187,25 +355,81 @@ pub async fn process_batch(
     debug!("Processing {} pending transaction(s)", pending.len());
 
     let count = pending.len();
+    let max_attempts = verify_max_attempts();
+    let risk_threshold = risk_review_threshold();
+    let scorer = adapters::risk_scorer();
     let mut asset_codes = std::collections::HashSet::new();
-    for transaction in &pending {
+    let mut changes = Vec::new();
+    for row in pending {
+        let transaction = &row.tx;
         asset_codes.insert(transaction.asset_code.clone());
 
-        // Create linked span for transaction processing if trace_id exists
-        if let Some(ref trace_id) = transaction.trace_id {
-            let span = tracing::info_span!(
-                "transaction.process",
-                transaction_id = %transaction.id,
-                trace_id = %trace_id,
-            );
-            let _guard = span.enter();
-            debug!("Processing transaction with trace context");
+        // Linked to the originating request through trace_id, when recorded.
+        let span = tracing::info_span!(
+            "transaction.process",
+            transaction_id = %transaction.id,
+            trace_id = Empty,
+            partner_id = Empty,
+            asset_code = Empty,
+            amount_bucket = Empty,
+        );
+        if let Some(trace_id) = &transaction.trace_id {
+            span.record("trace_id", trace_id.as_str());
         }
-    }
+        BusinessAttributes::for_transaction(transaction, row.tenant_id).record(&span);
+
+        async {
+            if transaction.risk_score.is_none()
+                && !score_risk(&mut tx, scorer.as_ref(), &row, risk_threshold, &mut changes).await?
+            {
+                return Ok(());
+            }
 
-    // TODO: per-transaction processing logic
-    for _transaction in pending {
-        // process each transaction
+            let verification = match &transaction.stellar_tx_hash {
+                Some(hash) => {
+                    let lookup = horizon_client.get_transaction(hash).await;
+                    // Kept for `GET /transactions/:id/stellar`, whatever the verdict.
+                    if let Some(xdr) = lookup
+                        .as_ref()
+                        .ok()
+                        .and_then(Option::as_ref)
+                        .and_then(StellarTransaction::from_horizon)
+                    {
+                        xdr.record(&mut *tx).await?;
+                    }
+                    match assess(transaction, lookup) {
+                        verified @ Verification::Verified { .. } => {
+                            let verified = check_asset_issuers(
+                                pool,
+                                &mut tx,
+                                horizon_client,
+                                transaction,
+                                hash,
+                                verified,
+                            )
+                            .await?;
+                            match verified {
+                                Verification::Verified { .. } => {
+                                    check_withdrawal_trustline(
+                                        pool,
+                                        horizon_client,
+                                        transaction,
+                                        verified,
+                                    )
+                                    .await?
+                                }
+                                other => other,
+                            }
+                        }
+                        other => other,
+                    }
+                }
+                None => Verification::Deferred("no stellar_tx_hash recorded".to_string()),
+            };
+            apply_verification(&mut tx, &row, verification, max_attempts, &mut changes).await
+        }
+        .instrument(span)
+        .await?;
     }
 
     tx.commit().await?;
@@ -213,16 +437,400 @@ pub async fn process_batch(
     for asset_code in asset_codes {
         crate::db::queries::invalidate_caches_for_asset(&asset_code).await;
     }
+    if let Some(events) = events {
+        for event in changes {
+            events.publish(event);
+        }
+    }
 
     Ok(count)
 }
 
+/// Scores `transaction` and stores the result. A score of at least
+/// `threshold` moves it to `compliance_review`. Returns whether verification
+/// should go ahead; when the scorer fails the row is left for a later batch.
+async fn score_risk(
+    db_tx: &mut sqlx::Transaction<'_, Postgres>,
+    scorer: &dyn RiskScorer,
+    row: &PendingTransaction,
+    threshold: u8,
+    changes: &mut Vec<DomainEvent>,
+) -> anyhow::Result<bool> {
+    let transaction = &row.tx;
+    let customer = queries::get_customer_profile(db_tx, transaction).await?;
+    let assessment = match scorer.score(&transaction.into(), &customer).await {
+        Ok(assessment) => assessment,
+        Err(e) => {
+            sampled_warn!(
+                "processor.risk_score",
+                e,
+                transaction_id = %transaction.id,
+                error = %e,
+                "Risk scoring failed, will retry"
+            );
+            return Ok(false);
+        }
+    };
+    queries::record_risk_score(db_tx, transaction.id, &assessment, ACTOR).await?;
+
+    let review = assessment.score >= threshold;
+    let outcome = if review { "review" } else { "pass" };
+    metrics::transaction_risk_score().record(
+        u64::from(assessment.score),
+        &[KeyValue::new("outcome", outcome)],
+    );
+    if review {
+        set_status(db_tx, transaction.id, "compliance_review").await?;
+        changes.push(left_pending(row, "compliance_review"));
+        warn!(
+            transaction_id = %transaction.id,
+            score = assessment.score,
+            reasons = ?assessment.reasons,
+            "High risk score, transaction held for compliance review"
+        );
+    } else {
+        debug!(
+            transaction_id = %transaction.id,
+            score = assessment.score,
+            "Transaction risk scored"
+        );
+    }
+    Ok(!review)
+}
+
+/// Checks the payments of a verified deposit against the trusted issuers of
+/// the partners receiving them (see [`asset_trust`]). A payment of the
+/// deposit's asset from an untrusted issuer is quarantined and the deposit
+/// rejected; otherwise `verified` stands.
+async fn check_asset_issuers(
+    pool: &PgPool,
+    db_tx: &mut sqlx::Transaction<'_, Postgres>,
+    horizon_client: &HorizonClient,
+    transaction: &Transaction,
+    hash: &str,
+    verified: Verification,
+) -> anyhow::Result<Verification> {
+    let payments = match horizon_client.get_transaction_payments(hash).await {
+        Ok(payments) => payments,
+        Err(HorizonError::CircuitBreakerOpen(e)) => {
+            return Ok(Verification::Deferred(format!("Horizon unavailable: {e}")))
+        }
+        Err(e) => {
+            return Ok(Verification::Retry(format!(
+                "Horizon payments lookup failed: {e}"
+            )))
+        }
+    };
+
+    for record in &payments {
+        let (Some(to), Some(asset_code), Some(amount)) =
+            (&record.to, &record.asset_code, &record.amount)
+        else {
+            continue;
+        };
+        if !asset_code.eq_ignore_ascii_case(&transaction.asset_code) {
+            continue;
+        }
+        let observed = ObservedPayment {
+            payment_id: &record.id,
+            to,
+            from: record.from_muxed.as_deref().or(record.from.as_deref()),
+            asset_code,
+            asset_issuer: record.asset_issuer.as_deref(),
+            amount,
+            memo: transaction.memo.as_deref(),
+            stellar_tx_hash: Some(hash),
+        };
+        let untrusted = match asset_trust::screen(pool, &observed).await {
+            Ok(untrusted) => untrusted,
+            Err(e) => {
+                return Ok(Verification::Retry(format!(
+                    "trusted asset lookup failed: {e}"
+                )))
+            }
+        };
+        if let Some(untrusted) = untrusted {
+            asset_trust::quarantine(
+                db_tx,
+                QuarantineSource::Processor,
+                &observed,
+                &untrusted,
+                Some(transaction.id),
+            )
+            .await?;
+            return Ok(Verification::Reject(format!(
+                "untrusted asset issuer: {}",
+                untrusted.reason
+            )));
+        }
+    }
+    Ok(verified)
+}
+
+/// Checks that the destination of a verified withdrawal can hold what it is
+/// owed: the account must exist and have a trustline for the asset with room
+/// for the amount, or the withdrawal is rejected. Other transactions, and
+/// asset codes without a single enabled issuer in `assets`, pass unchecked.
+async fn check_withdrawal_trustline(
+    pool: &PgPool,
+    horizon_client: &HorizonClient,
+    transaction: &Transaction,
+    verified: Verification,
+) -> anyhow::Result<Verification> {
+    if transaction.callback_type.as_deref() != Some("withdrawal") {
+        return Ok(verified);
+    }
+    let code = &transaction.asset_code;
+    let asset = if code.eq_ignore_ascii_case("XLM") {
+        parse_asset("native")
+    } else {
+        match Asset::sole_issuer(pool, code).await {
+            Ok(issuer) => issuer.and_then(|issuer| parse_asset(&format!("{code}:{issuer}"))),
+            Err(e) => {
+                return Ok(Verification::Retry(format!(
+                    "asset issuer lookup failed: {e}"
+                )))
+            }
+        }
+    };
+    let Some(asset) = asset else {
+        debug!(
+            transaction_id = %transaction.id,
+            asset_code = %code,
+            "No single registered issuer, trustline not checked"
+        );
+        return Ok(verified);
+    };
+    let Some(amount) = to_stroops(&transaction.amount) else {
+        return Ok(Verification::Reject(format!(
+            "invalid withdrawal amount {}",
+            transaction.amount
+        )));
+    };
+    // Trustlines belong to the base account of a muxed address.
+    let account = StellarAddress::parse(&transaction.stellar_account)
+        .map(|address| address.account().to_string())
+        .unwrap_or_else(|_| transaction.stellar_account.clone());
+
+    let trustline = match horizon_client.check_trustline(&account, &asset).await {
+        Ok(trustline) => trustline,
+        Err(HorizonError::CircuitBreakerOpen(e)) => {
+            return Ok(Verification::Deferred(format!("Horizon unavailable: {e}")))
+        }
+        Err(e) => {
+            return Ok(Verification::Retry(format!(
+                "Horizon trustline lookup failed: {e}"
+            )))
+        }
+    };
+    Ok(match trustline_shortfall(trustline, code, amount) {
+        Some(problem) => Verification::Reject(format!("destination {account} {problem}")),
+        None => verified,
+    })
+}
+
+/// Why an account with `trustline` cannot receive `amount` stroops of
+/// `code`, if it cannot.
+fn trustline_shortfall(trustline: Trustline, code: &str, amount: i64) -> Option<String> {
+    if trustline == Trustline::Missing {
+        return Some(format!("has no trustline for {code}"));
+    }
+    let room = trustline.room().filter(|room| *room < amount)?;
+    Some(format!(
+        "trustline for {code} has room for {}, needs {}",
+        format_stroops(room),
+        format_stroops(amount)
+    ))
+}
+
+async fn apply_verification(
+    db_tx: &mut sqlx::Transaction<'_, Postgres>,
+    row: &PendingTransaction,
+    verification: Verification,
+    max_attempts: i32,
+    changes: &mut Vec<DomainEvent>,
+) -> anyhow::Result<()> {
+    let id = row.tx.id;
+    let attempts = row.verification_attempts + 1;
+
+    let outcome = match verification {
+        Verification::Verified { ledger, closed_at } => {
+            let verified_at = Utc::now();
+            sqlx::query(
+                "UPDATE transactions SET ledger = $2, closed_at = $3, verified_at = $4, \
+                 first_verification_at = COALESCE(first_verification_at, $4) WHERE id = $1",
+            )
+            .bind(id)
+            .bind(ledger)
+            .bind(closed_at)
+            .bind(verified_at)
+            .execute(&mut **db_tx)
+            .await?;
+            record_success(row, verified_at);
+            set_status(db_tx, id, "completed").await?;
+            let mut completed = left_pending(row, "completed");
+            if let DomainEvent::StatusChanged {
+                ledger: l,
+                closed_at: c,
+                ..
+            } = &mut completed
+            {
+                *l = Some(ledger);
+                *c = closed_at;
+            }
+            changes.push(completed);
+            info!(transaction_id = %id, ledger, "Transaction verified on Horizon");
+            "verified"
+        }
+        Verification::Deferred(reason) => {
+            debug!(transaction_id = %id, %reason, "Transaction verification deferred");
+            "deferred"
+        }
+        Verification::Retry(reason) if attempts < max_attempts => {
+            sqlx::query(
+                "UPDATE transactions SET verification_attempts = $2, updated_at = NOW(), \
+                 first_verification_at = COALESCE(first_verification_at, NOW()) WHERE id = $1",
+            )
+            .bind(id)
+            .bind(attempts)
+            .execute(&mut **db_tx)
+            .await?;
+            debug!(transaction_id = %id, attempts, %reason, "Transaction not verified yet");
+            "retry"
+        }
+        Verification::Retry(reason) => {
+            fail_verification(db_tx, row, &reason, attempts, changes).await?;
+            "exhausted"
+        }
+        Verification::Reject(reason) => {
+            fail_verification(db_tx, row, &reason, attempts, changes).await?;
+            "rejected"
+        }
+    };
+
+    metrics::transaction_verifications_total().add(1, &[KeyValue::new("outcome", outcome)]);
+    Ok(())
+}
+
+/// Records how many lookups it took to verify `row`, and how long since the
+/// first one.
+fn record_success(row: &PendingTransaction, verified_at: DateTime<Utc>) {
+    metrics::transaction_verify_retries().record(row.verification_attempts.max(0) as u64, &[]);
+    let elapsed = row
+        .first_verification_at
+        .map(|first| (verified_at - first).num_milliseconds().max(0))
+        .unwrap_or(0);
+    metrics::transaction_time_to_success_ms().record(elapsed as f64, &[]);
+}
+
+/// Fails a transaction that cannot be verified and copies it to the DLQ.
+async fn fail_verification(
+    db_tx: &mut sqlx::Transaction<'_, Postgres>,
+    row: &PendingTransaction,
+    reason: &str,
+    attempts: i32,
+    changes: &mut Vec<DomainEvent>,
+) -> anyhow::Result<()> {
+    let id = row.tx.id;
+    let reason = format!("Horizon verification failed after {attempts} attempt(s): {reason}");
+    sqlx::query(
+        "UPDATE transactions SET verification_attempts = $2, \
+         first_verification_at = COALESCE(first_verification_at, NOW()) WHERE id = $1",
+    )
+    .bind(id)
+    .bind(attempts)
+    .execute(&mut **db_tx)
+    .await?;
+    set_status(db_tx, id, "failed").await?;
+    move_to_dlq(db_tx, &row.tx, &reason, attempts).await?;
+    changes.push(left_pending(row, "failed"));
+    changes.push(DomainEvent::MovedToDlq {
+        transaction_id: id,
+        tenant_id: row.tenant_id,
+        error_class: dlq_errors::classify(&reason).as_str().to_string(),
+        reason: reason.clone(),
+        attempts,
+        at: Utc::now(),
+    });
+    sampled_warn!(
+        "processor.dlq",
+        reason,
+        transaction_id = %id,
+        %reason,
+        "Transaction failed verification, moved to DLQ"
+    );
+    Ok(())
+}
+
+/// `StatusChanged` for a row the processor moved out of `pending`.
+fn left_pending(row: &PendingTransaction, to: &str) -> DomainEvent {
+    DomainEvent::StatusChanged {
+        transaction_id: row.tx.id,
+        tenant_id: row.tenant_id,
+        from: Some("pending".to_string()),
+        to: to.to_string(),
+        message: None,
+        stellar_tx_hash: row.tx.stellar_tx_hash.clone(),
+        ledger: row.tx.ledger,
+        closed_at: row.tx.closed_at,
+        at: Utc::now(),
+    }
+}
+
+async fn set_status(
+    db_tx: &mut sqlx::Transaction<'_, Postgres>,
+    id: uuid::Uuid,
+    to: &str,
+) -> anyhow::Result<()> {
+    validate_status_transition("pending", to).map_err(|e| anyhow::anyhow!("{e}"))?;
+    sqlx::query("UPDATE transactions SET status = $2, updated_at = NOW() WHERE id = $1")
+        .bind(id)
+        .bind(to)
+        .execute(&mut **db_tx)
+        .await?;
+    AuditLog::log_status_change(db_tx, id, ENTITY_TRANSACTION, "pending", to, ACTOR).await?;
+    Ok(())
+}
+
+async fn move_to_dlq(
+    db_tx: &mut sqlx::Transaction<'_, Postgres>,
+    transaction: &Transaction,
+    reason: &str,
+    attempts: i32,
+) -> anyhow::Result<()> {
+    let class = dlq_errors::classify(reason);
+    sqlx::query(
+        r#"
+        INSERT INTO transaction_dlq (
+            transaction_id, stellar_account, amount, asset_code, anchor_transaction_id,
+            error_reason, error_class, error_signature, retry_count, original_created_at
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        "#,
+    )
+    .bind(transaction.id)
+    .bind(&transaction.stellar_account)
+    .bind(&transaction.amount)
+    .bind(&transaction.asset_code)
+    .bind(&transaction.anchor_transaction_id)
+    .bind(reason)
+    .bind(class.as_str())
+    .bind(dlq_errors::signature(reason))
+    .bind(attempts)
+    .bind(transaction.created_at)
+    .execute(&mut **db_tx)
+    .await?;
+    metrics::dlq_entries_total().add(1, &[KeyValue::new("class", class.as_str())]);
+    Ok(())
+}
+
 /// Legacy single-worker entry point kept for backward compatibility.
 pub async fn run_processor(pool: PgPool, horizon_client: HorizonClient) {
     info!("Async transaction processor started (legacy single-worker)");
     loop {
-        if let Err(e) = process_batch(&pool, &horizon_client, 10).await {
-            error!("Processor batch error: {}", e);
+        if region::is_active() {
+            if let Err(e) = process_batch(&pool, &horizon_client, 10, None).await {
+                sampled_error!("processor.batch", e, "Processor batch error: {}", e);
+            }
         }
         sleep(Duration::from_secs(5)).await;
     }
@@ -248,7 +856,12 @@ pub async fn queue_depth_task(pool: PgPool, pending_queue_depth: Arc<AtomicU64>)
                 }
             }
             Err(e) => {
-                error!("Failed to query pending queue depth: {}", e);
+                sampled_error!(
+                    "processor.queue_depth",
+                    e,
+                    "Failed to query pending queue depth: {}",
+                    e
+                );
                 // Fail open: leave the existing counter unchanged
             }
         }
@@ -297,10 +910,10 @@ pub async fn run_processor_with_leader_election(
                     Err(e) => warn!("Leader election error: {e}"),
                 }
             }
-            _ = process_tick.tick() => {
+            _ = process_tick.tick(), if region::is_active() => {
                 // All instances process transactions (SKIP LOCKED handles concurrency)
-                if let Err(e) = process_batch(&pool, &horizon_client, 10).await {
-                    error!("Processor batch error: {e}");
+                if let Err(e) = process_batch(&pool, &horizon_client, 10, None).await {
+                    sampled_error!("processor.batch", e, "Processor batch error: {e}");
                 }
             }
         }
@@ -353,4 +966,114 @@ mod tests {
         }
         assert!(s.current() < high);
     }
+
+    fn pending(memo: Option<&str>, memo_type: Option<&str>) -> Transaction {
+        Transaction::new(
+            "GABCDEFGHIJKLMNOPQRSTUVWXYZ234567ABCDEFGHIJKLMNOPQRSTUV".to_string(),
+            "10".parse().unwrap(),
+            "USDC".to_string(),
+            None,
+            None,
+            None,
+            memo.map(str::to_string),
+            memo_type.map(str::to_string),
+            None,
+        )
+        .with_stellar_tx_hash(Some("a".repeat(64)))
+    }
+
+    fn record(successful: bool, memo: Option<&str>) -> TransactionRecord {
+        TransactionRecord {
+            hash: "a".repeat(64),
+            successful,
+            ledger: 42,
+            source_account: "GSRC".to_string(),
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            memo: memo.map(str::to_string),
+            memo_type: memo.map(|_| "text".to_string()),
+            envelope_xdr: None,
+            result_xdr: None,
+        }
+    }
+
+    #[test]
+    fn assess_verifies_successful_transaction() {
+        let tx = pending(Some("ref-1"), Some("text"));
+        assert_eq!(
+            assess(&tx, Ok(Some(record(true, Some("ref-1"))))),
+            Verification::Verified {
+                ledger: 42,
+                closed_at: Some("2026-01-01T00:00:00Z".parse().unwrap()),
+            }
+        );
+        // Hash memos are not compared.
+        let tx = pending(Some("deadbeef"), Some("hash"));
+        assert_eq!(
+            assess(&tx, Ok(Some(record(true, Some("3q2+7w=="))))),
+            Verification::Verified {
+                ledger: 42,
+                closed_at: Some("2026-01-01T00:00:00Z".parse().unwrap()),
+            }
+        );
+    }
+
+    #[test]
+    fn assess_rejects_failed_or_mismatched_transaction() {
+        let tx = pending(None, None);
+        assert!(matches!(
+            assess(&tx, Ok(Some(record(false, None)))),
+            Verification::Reject(_)
+        ));
+        let tx = pending(Some("ref-1"), Some("text"));
+        assert!(matches!(
+            assess(&tx, Ok(Some(record(true, Some("ref-2"))))),
+            Verification::Reject(_)
+        ));
+    }
+
+    #[test]
+    fn assess_retries_missing_and_defers_open_breaker() {
+        let tx = pending(None, None);
+        assert!(matches!(assess(&tx, Ok(None)), Verification::Retry(_)));
+        assert!(matches!(
+            assess(&tx, Err(HorizonError::InvalidResponse("502".to_string()))),
+            Verification::Retry(_)
+        ));
+        assert!(matches!(
+            assess(
+                &tx,
+                Err(HorizonError::CircuitBreakerOpen("horizon".to_string()))
+            ),
+            Verification::Deferred(_)
+        ));
+    }
+
+    #[test]
+    fn trustline_shortfall_requires_trustline_with_room() {
+        let held = Trustline::Held {
+            balance: 950_000_000,
+            limit: 1_000_000_000,
+        };
+        assert_eq!(trustline_shortfall(held, "USDC", 50_000_000), None);
+        assert_eq!(
+            trustline_shortfall(Trustline::Native, "XLM", i64::MAX),
+            None
+        );
+        assert_eq!(
+            trustline_shortfall(held, "USDC", 50_000_001).as_deref(),
+            Some("trustline for USDC has room for 5.0000000, needs 5.0000001")
+        );
+        assert_eq!(
+            trustline_shortfall(Trustline::Missing, "USDC", 1).as_deref(),
+            Some("has no trustline for USDC")
+        );
+        let reason = format!(
+            "Horizon verification failed after 1 attempt(s): destination GABC {}",
+            trustline_shortfall(held, "USDC", i64::MAX).unwrap()
+        );
+        assert_eq!(
+            dlq_errors::classify(&reason),
+            dlq_errors::ErrorClass::Trustline
+        );
+    }
 }
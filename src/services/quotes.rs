@@ -0,0 +1,585 @@
+//! SEP-38 quotes: indicative prices and firm quotes for exchanging one asset
+//! for another.
+//!
+//! Prices come from the rate card in `sep38_prices` ([`Sep38Price`]): per
+//! pair of SEP-38 assets (`stellar:CODE[:ISSUER]`, `iso4217:CCY`), what one
+//! unit of the buy asset costs in the sell asset, and a fee charged in the
+//! sell asset as a percentage of the sell amount. Amounts have seven decimal
+//! places; what the customer receives is rounded down and what they pay is
+//! rounded up.
+//!
+//! `GET /sep38/prices` is indicative. A wallet holding a SEP-10 token (see
+//! [`crate::services::sep10`]) can lock a rate in with `POST /sep38/quote`:
+//! the amounts are stored in `sep38_quotes` ([`Sep38Quote`]) and honoured
+//! until the quote expires. A SEP-31 payment naming the quote in `quote_id`
+//! claims it (a quote backs one transaction) and must sell exactly the
+//! quoted amount. When that transaction settles, the settlement records what
+//! it pays out in the buy asset at the quoted rate, not at the rate of the
+//! day (see [`payouts`]).
+//!
+//! | Env var                | Default | Meaning                                |
+//! |------------------------|---------|----------------------------------------|
+//! | `SEP38_QUOTE_TTL_SECS` | `300`   | How long a firm quote is honoured      |
+//!
+//! `GET /price` and quotes for SEP-6 and SEP-24 transfers are not
+//! implemented.
+
+use crate::db::models::{Sep38Price, Sep38Quote, Transaction};
+use crate::error::AppError;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use sqlx::types::BigDecimal;
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// Stellar amounts have seven decimal places.
+const AMOUNT_SCALE: i64 = 7;
+const DEFAULT_QUOTE_TTL_SECS: i64 = 300;
+const MAX_QUOTE_TTL_SECS: i64 = 86_400;
+
+#[derive(Debug, Clone)]
+pub struct QuotesConfig {
+    /// How long a firm quote is honoured.
+    pub ttl: Duration,
+}
+
+impl Default for QuotesConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::seconds(DEFAULT_QUOTE_TTL_SECS),
+        }
+    }
+}
+
+impl QuotesConfig {
+    pub fn from_env() -> Self {
+        let secs = std::env::var("SEP38_QUOTE_TTL_SECS")
+            .ok()
+            .and_then(|v| v.trim().parse::<i64>().ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(DEFAULT_QUOTE_TTL_SECS)
+            .min(MAX_QUOTE_TTL_SECS);
+        Self {
+            ttl: Duration::seconds(secs),
+        }
+    }
+}
+
+/// A SEP-38 asset identifier, checked: `stellar:CODE`, `stellar:CODE:ISSUER`
+/// or `iso4217:CCY`.
+pub fn parse_asset(raw: &str) -> Result<String, AppError> {
+    let asset = raw.trim();
+    let invalid = || AppError::BadRequest(format!("invalid asset: {asset}"));
+    let mut parts = asset.split(':');
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some("stellar"), Some(code), issuer, None)
+            if (1..=12).contains(&code.len())
+                && code.chars().all(|c| c.is_ascii_alphanumeric())
+                && issuer.map_or(true, |i| i.len() == 56 && i.starts_with('G')) =>
+        {
+            Ok(asset.to_string())
+        }
+        (Some("iso4217"), Some(code), None, None)
+            if code.len() == 3 && code.chars().all(|c| c.is_ascii_uppercase()) =>
+        {
+            Ok(asset.to_string())
+        }
+        _ => Err(invalid()),
+    }
+}
+
+/// The asset code of a `stellar:` asset.
+pub fn stellar_code(asset: &str) -> Option<&str> {
+    asset.strip_prefix("stellar:")?.split(':').next()
+}
+
+/// The side of an exchange whose amount the customer chose.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Requested {
+    Sell(BigDecimal),
+    Buy(BigDecimal),
+}
+
+/// Both sides of an exchange at a given rate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Amounts {
+    pub sell_amount: BigDecimal,
+    pub buy_amount: BigDecimal,
+    /// Of one unit of the buy asset, before the fee.
+    pub price: BigDecimal,
+    /// Of one unit of the buy asset, fee included.
+    pub total_price: BigDecimal,
+    /// In the sell asset.
+    pub fee_total: BigDecimal,
+}
+
+fn round_up(value: &BigDecimal) -> BigDecimal {
+    let truncated = value.with_scale(AMOUNT_SCALE);
+    if &truncated < value {
+        truncated + BigDecimal::new(1.into(), AMOUNT_SCALE)
+    } else {
+        truncated
+    }
+}
+
+/// Exchange `requested` at `rate`. The fee is `fee_percent` of the sell
+/// amount.
+pub fn amounts(rate: &Sep38Price, requested: &Requested) -> Result<Amounts, AppError> {
+    let zero = BigDecimal::from(0);
+    let fee_rate = &rate.fee_percent / BigDecimal::from(100);
+    let (sell_amount, buy_amount, fee_total) = match requested {
+        Requested::Sell(sell) => {
+            let fee = round_up(&(sell * &fee_rate));
+            let buy = ((sell - &fee) / &rate.price).with_scale(AMOUNT_SCALE);
+            (sell.clone(), buy, fee)
+        }
+        Requested::Buy(buy) => {
+            let net = round_up(&(buy * &rate.price));
+            let sell = round_up(&(&net / (BigDecimal::from(1) - &fee_rate)));
+            let fee = &sell - &net;
+            (sell, buy.clone(), fee)
+        }
+    };
+    if buy_amount <= zero || sell_amount <= zero {
+        return Err(AppError::BadRequest(
+            "amount is too small to exchange".to_string(),
+        ));
+    }
+    let total_price = (&sell_amount / &buy_amount).with_scale(AMOUNT_SCALE);
+    Ok(Amounts {
+        sell_amount,
+        buy_amount,
+        price: rate.price.normalized(),
+        total_price,
+        fee_total,
+    })
+}
+
+fn parse_amount(name: &str, raw: &str) -> Result<BigDecimal, AppError> {
+    let raw = raw.trim();
+    let amount = BigDecimal::from_str(raw)
+        .map_err(|_| AppError::BadRequest(format!("invalid {name}: {raw}")))?;
+    if amount <= BigDecimal::from(0) {
+        return Err(AppError::BadRequest(format!("{name} must be positive")));
+    }
+    if amount.with_scale(AMOUNT_SCALE) != amount {
+        return Err(AppError::BadRequest(format!(
+            "{name} has more than {AMOUNT_SCALE} decimal places"
+        )));
+    }
+    Ok(amount)
+}
+
+/// `GET /sep38/info` body: every asset on the rate card.
+pub fn info(rates: &[Sep38Price]) -> Value {
+    let assets: BTreeSet<&str> = rates
+        .iter()
+        .flat_map(|r| [r.sell_asset.as_str(), r.buy_asset.as_str()])
+        .collect();
+    json!({
+        "assets": assets
+            .into_iter()
+            .map(|asset| json!({ "asset": asset }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// Query of `GET /sep38/prices`: either what a sell amount buys, or what a
+/// buy amount costs.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PricesQuery {
+    pub sell_asset: Option<String>,
+    pub sell_amount: Option<String>,
+    pub buy_asset: Option<String>,
+    pub buy_amount: Option<String>,
+}
+
+/// `GET /sep38/prices` body: the total price of every pair `query` can be
+/// exchanged through, skipping pairs the amount is too small for.
+pub fn prices(rates: &[Sep38Price], query: &PricesQuery) -> Result<Value, AppError> {
+    let entry = |asset: &str, amounts: Amounts| {
+        json!({
+            "asset": asset,
+            "price": amounts.total_price.to_string(),
+            "decimals": AMOUNT_SCALE,
+        })
+    };
+    match query {
+        PricesQuery {
+            sell_asset: Some(asset),
+            sell_amount: Some(amount),
+            buy_asset: None,
+            buy_amount: None,
+        } => {
+            let asset = parse_asset(asset)?;
+            let requested = Requested::Sell(parse_amount("sell_amount", amount)?);
+            let buy_assets: Vec<Value> = rates
+                .iter()
+                .filter(|r| r.sell_asset == asset)
+                .filter_map(|r| Some(entry(&r.buy_asset, amounts(r, &requested).ok()?)))
+                .collect();
+            Ok(json!({ "buy_assets": buy_assets }))
+        }
+        PricesQuery {
+            sell_asset: None,
+            sell_amount: None,
+            buy_asset: Some(asset),
+            buy_amount: Some(amount),
+        } => {
+            let asset = parse_asset(asset)?;
+            let requested = Requested::Buy(parse_amount("buy_amount", amount)?);
+            let sell_assets: Vec<Value> = rates
+                .iter()
+                .filter(|r| r.buy_asset == asset)
+                .filter_map(|r| Some(entry(&r.sell_asset, amounts(r, &requested).ok()?)))
+                .collect();
+            Ok(json!({ "sell_assets": sell_assets }))
+        }
+        _ => Err(AppError::BadRequest(
+            "give either sell_asset and sell_amount, or buy_asset and buy_amount".to_string(),
+        )),
+    }
+}
+
+/// Body of `POST /sep38/quote`. Exactly one of the amounts is given.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PostQuoteRequest {
+    pub sell_asset: String,
+    pub buy_asset: String,
+    pub sell_amount: Option<String>,
+    pub buy_amount: Option<String>,
+    /// The quote must be honoured at least until then.
+    pub expire_after: Option<DateTime<Utc>>,
+}
+
+impl PostQuoteRequest {
+    pub fn assets(&self) -> Result<(String, String), AppError> {
+        Ok((
+            parse_asset(&self.sell_asset)?,
+            parse_asset(&self.buy_asset)?,
+        ))
+    }
+
+    pub fn requested(&self) -> Result<Requested, AppError> {
+        match (&self.sell_amount, &self.buy_amount) {
+            (Some(sell), None) => Ok(Requested::Sell(parse_amount("sell_amount", sell)?)),
+            (None, Some(buy)) => Ok(Requested::Buy(parse_amount("buy_amount", buy)?)),
+            _ => Err(AppError::BadRequest(
+                "give exactly one of sell_amount and buy_amount".to_string(),
+            )),
+        }
+    }
+
+    /// When a quote made at `now` expires; `400` when that is before
+    /// `expire_after`.
+    pub fn expires_at(
+        &self,
+        config: &QuotesConfig,
+        now: DateTime<Utc>,
+    ) -> Result<DateTime<Utc>, AppError> {
+        let expires_at = now + config.ttl;
+        match self.expire_after {
+            Some(after) if after > expires_at => Err(AppError::BadRequest(format!(
+                "quotes are honoured until {} at the latest",
+                expires_at.to_rfc3339()
+            ))),
+            _ => Ok(expires_at),
+        }
+    }
+}
+
+/// The firm quote for `account` exchanging `amounts` at `rate`.
+pub fn new_quote(
+    id: Uuid,
+    account: &str,
+    rate: &Sep38Price,
+    amounts: Amounts,
+    expires_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Sep38Quote {
+    Sep38Quote {
+        id,
+        account: account.to_string(),
+        sell_asset: rate.sell_asset.clone(),
+        sell_amount: amounts.sell_amount,
+        buy_asset: rate.buy_asset.clone(),
+        buy_amount: amounts.buy_amount,
+        price: amounts.price,
+        total_price: amounts.total_price,
+        fee_total: amounts.fee_total,
+        expires_at,
+        transaction_id: None,
+        created_at: now,
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QuoteFee {
+    pub total: String,
+    pub asset: String,
+}
+
+/// A quote as SEP-38 presents it.
+#[derive(Debug, Clone, Serialize)]
+pub struct QuoteResponse {
+    pub id: Uuid,
+    pub expires_at: DateTime<Utc>,
+    pub total_price: String,
+    pub price: String,
+    pub sell_asset: String,
+    pub sell_amount: String,
+    pub buy_asset: String,
+    pub buy_amount: String,
+    pub fee: QuoteFee,
+}
+
+impl From<&Sep38Quote> for QuoteResponse {
+    fn from(quote: &Sep38Quote) -> Self {
+        Self {
+            id: quote.id,
+            expires_at: quote.expires_at,
+            total_price: quote.total_price.to_string(),
+            price: quote.price.to_string(),
+            sell_asset: quote.sell_asset.clone(),
+            sell_amount: quote.sell_amount.to_string(),
+            buy_asset: quote.buy_asset.clone(),
+            buy_amount: quote.buy_amount.to_string(),
+            fee: QuoteFee {
+                total: quote.fee_total.to_string(),
+                asset: quote.sell_asset.clone(),
+            },
+        }
+    }
+}
+
+/// Whether `quote` can back a transaction of `account` selling `amount` of
+/// the Stellar asset `asset_code` at `now`.
+pub fn check_backs(
+    quote: &Sep38Quote,
+    account: &str,
+    asset_code: &str,
+    amount: &BigDecimal,
+    now: DateTime<Utc>,
+) -> Result<(), AppError> {
+    let id = quote.id;
+    if quote.account != account {
+        return Err(AppError::BadRequest(format!("quote {id} not found")));
+    }
+    if quote.transaction_id.is_some() {
+        return Err(AppError::BadRequest(format!("quote {id} was already used")));
+    }
+    if quote.expires_at <= now {
+        return Err(AppError::BadRequest(format!("quote {id} has expired")));
+    }
+    if stellar_code(&quote.sell_asset) != Some(asset_code) {
+        return Err(AppError::BadRequest(format!(
+            "quote {id} does not sell {asset_code}"
+        )));
+    }
+    if &quote.sell_amount != amount {
+        return Err(AppError::BadRequest(format!(
+            "amount must be the quote's sell_amount {}",
+            quote.sell_amount
+        )));
+    }
+    Ok(())
+}
+
+/// What a transaction of `amount` backed by `quote` pays out in the buy
+/// asset: the quoted buy amount, scaled by how much of the quoted sell
+/// amount `amount` is.
+pub fn payout(quote: &Sep38Quote, amount: &BigDecimal) -> BigDecimal {
+    if amount == &quote.sell_amount {
+        quote.buy_amount.clone()
+    } else {
+        (amount * &quote.buy_amount / &quote.sell_amount).with_scale(AMOUNT_SCALE)
+    }
+}
+
+/// `quoted_payouts` of a settlement of `transactions`: the payouts of those
+/// backed by one of `quotes`, summed per buy asset. `None` when none are.
+pub fn payouts(transactions: &[Transaction], quotes: &[Sep38Quote]) -> Option<Value> {
+    let mut totals: BTreeMap<&str, BigDecimal> = BTreeMap::new();
+    for tx in transactions {
+        let Some(quote) = quotes.iter().find(|q| q.transaction_id == Some(tx.id)) else {
+            continue;
+        };
+        *totals
+            .entry(quote.buy_asset.as_str())
+            .or_insert_with(|| BigDecimal::from(0)) += payout(quote, &tx.amount);
+    }
+    if totals.is_empty() {
+        return None;
+    }
+    let totals: Map<String, Value> = totals
+        .into_iter()
+        .map(|(asset, total)| (asset.to_string(), Value::String(total.to_string())))
+        .collect();
+    Some(Value::Object(totals))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(s: &str) -> BigDecimal {
+        BigDecimal::from_str(s).unwrap()
+    }
+
+    fn rate(price: &str, fee_percent: &str) -> Sep38Price {
+        Sep38Price {
+            sell_asset: "iso4217:USD".to_string(),
+            buy_asset: "stellar:USDC".to_string(),
+            price: dec(price),
+            fee_percent: dec(fee_percent),
+        }
+    }
+
+    fn quote(sell: &str, buy: &str) -> Sep38Quote {
+        let now = Utc::now();
+        Sep38Quote {
+            id: Uuid::new_v4(),
+            account: "GSENDER".to_string(),
+            sell_asset: "stellar:USDC".to_string(),
+            sell_amount: dec(sell),
+            buy_asset: "iso4217:NGN".to_string(),
+            buy_amount: dec(buy),
+            price: dec("0.00066"),
+            total_price: (dec(sell) / dec(buy)).with_scale(AMOUNT_SCALE),
+            fee_total: dec("0"),
+            expires_at: now + Duration::minutes(5),
+            transaction_id: None,
+            created_at: now,
+        }
+    }
+
+    #[test]
+    fn test_parse_asset() {
+        assert!(parse_asset("iso4217:USD").is_ok());
+        assert!(parse_asset(" stellar:USDC ").is_ok());
+        let issuer = format!("G{}", "A".repeat(55));
+        assert!(parse_asset(&format!("stellar:USDC:{issuer}")).is_ok());
+        assert!(parse_asset("stellar:USDC:GBAD").is_err());
+        assert!(parse_asset("iso4217:usd").is_err());
+        assert!(parse_asset("USDC").is_err());
+        assert_eq!(
+            stellar_code(&format!("stellar:USDC:{issuer}")),
+            Some("USDC")
+        );
+        assert_eq!(stellar_code("iso4217:USD"), None);
+    }
+
+    #[test]
+    fn test_selling_takes_the_fee_from_the_sell_amount() {
+        let amounts = amounts(&rate("1.02", "1"), &Requested::Sell(dec("102"))).unwrap();
+        assert_eq!(amounts.fee_total, dec("1.02"));
+        // (102 - 1.02) / 1.02 = 99.0
+        assert_eq!(amounts.buy_amount, dec("99"));
+        assert_eq!(amounts.total_price, dec("1.0303030"));
+    }
+
+    #[test]
+    fn test_buying_grosses_the_fee_up() {
+        let amounts = amounts(&rate("1.02", "1"), &Requested::Buy(dec("99"))).unwrap();
+        // 99 * 1.02 = 100.98, / 0.99 = 102
+        assert_eq!(amounts.sell_amount, dec("102"));
+        assert_eq!(amounts.fee_total, dec("1.02"));
+    }
+
+    #[test]
+    fn test_amounts_round_in_the_anchors_favour() {
+        let amounts = amounts(&rate("3", "0"), &Requested::Sell(dec("10"))).unwrap();
+        assert_eq!(amounts.buy_amount, dec("3.3333333"));
+        let amounts = super::amounts(&rate("3", "0"), &Requested::Buy(dec("0.0000001"))).unwrap();
+        assert_eq!(amounts.sell_amount, dec("0.0000003"));
+        assert!(super::amounts(&rate("3", "0"), &Requested::Sell(dec("0.0000001"))).is_err());
+    }
+
+    #[test]
+    fn test_prices_need_one_side() {
+        let rates = [rate("1.02", "0")];
+        let sell = PricesQuery {
+            sell_asset: Some("iso4217:USD".to_string()),
+            sell_amount: Some("102".to_string()),
+            ..Default::default()
+        };
+        let body = prices(&rates, &sell).unwrap();
+        assert_eq!(body["buy_assets"][0]["asset"], "stellar:USDC");
+        assert_eq!(body["buy_assets"][0]["price"], "1.0200000");
+
+        let both = PricesQuery {
+            buy_asset: Some("stellar:USDC".to_string()),
+            buy_amount: Some("1".to_string()),
+            ..sell
+        };
+        assert!(prices(&rates, &both).is_err());
+    }
+
+    #[test]
+    fn test_quote_request_validation() {
+        let mut request = PostQuoteRequest {
+            sell_asset: "iso4217:USD".to_string(),
+            buy_asset: "stellar:USDC".to_string(),
+            sell_amount: Some("10".to_string()),
+            buy_amount: Some("10".to_string()),
+            expire_after: None,
+        };
+        assert!(request.requested().is_err());
+        request.buy_amount = None;
+        assert_eq!(request.requested().unwrap(), Requested::Sell(dec("10")));
+
+        let now = Utc::now();
+        let config = QuotesConfig::default();
+        assert_eq!(request.expires_at(&config, now).unwrap(), now + config.ttl);
+        request.expire_after = Some(now + Duration::hours(1));
+        assert!(request.expires_at(&config, now).is_err());
+    }
+
+    #[test]
+    fn test_check_backs() {
+        let now = Utc::now();
+        let q = quote("100", "152000");
+        assert!(check_backs(&q, "GSENDER", "USDC", &dec("100"), now).is_ok());
+        assert!(check_backs(&q, "GOTHER", "USDC", &dec("100"), now).is_err());
+        assert!(check_backs(&q, "GSENDER", "EURC", &dec("100"), now).is_err());
+        assert!(check_backs(&q, "GSENDER", "USDC", &dec("99"), now).is_err());
+        assert!(check_backs(&q, "GSENDER", "USDC", &dec("100"), q.expires_at).is_err());
+        let used = Sep38Quote {
+            transaction_id: Some(Uuid::new_v4()),
+            ..q
+        };
+        assert!(check_backs(&used, "GSENDER", "USDC", &dec("100"), now).is_err());
+    }
+
+    #[test]
+    fn test_payouts_use_the_quoted_rate() {
+        let mut backed = quote("100", "152000");
+        let mut partial = quote("100", "152000");
+        partial.buy_asset = "iso4217:KES".to_string();
+        let txs: Vec<Transaction> = ["100", "50", "10"]
+            .iter()
+            .map(|amount| {
+                Transaction::new(
+                    "GSENDER".to_string(),
+                    dec(amount),
+                    "USDC".to_string(),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                )
+            })
+            .collect();
+        backed.transaction_id = Some(txs[0].id);
+        partial.transaction_id = Some(txs[1].id);
+
+        let totals = payouts(&txs, &[backed, partial]).unwrap();
+        assert_eq!(totals["iso4217:NGN"], "152000");
+        assert_eq!(totals["iso4217:KES"], "76000.0000000");
+        assert!(payouts(&txs[2..], &[]).is_none());
+    }
+}
@@ -1,6 +1,12 @@
+use crate::domain::StellarAddress;
+use crate::services::job_runner::{
+    DateRangeParams, DayCheckpoint, JobContext, JobHandler, JobKind,
+};
+use crate::services::refunds::{self, InboundPayment, RefundSource};
 use crate::stellar::client::HorizonClient;
 use async_trait::async_trait;
-use chrono::{DateTime, Duration, Utc};
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::{DateTime, Duration, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use std::collections::{HashMap, HashSet};
@@ -52,22 +58,52 @@ pub struct AmountMismatch {
 struct DbTransaction {
     id: Uuid,
     stellar_account: String,
+    muxed_id: Option<u64>,
     amount: String,
     asset_code: String,
     memo: Option<String>,
     created_at: DateTime<Utc>,
 }
 
+impl DbTransaction {
+    /// The sender as reported: its `M...` address when it was muxed.
+    fn address(&self) -> String {
+        match self.muxed_id {
+            Some(id) => StellarAddress::from_parts(&self.stellar_account, Some(id))
+                .map(|address| address.to_string())
+                .unwrap_or_else(|_| self.stellar_account.clone()),
+            None => self.stellar_account.clone(),
+        }
+    }
+
+    /// A transaction recorded with a muxed id only matches a payment from
+    /// that same id; the memo alone could belong to another customer of the
+    /// same exchange.
+    fn accepts(&self, payment: &ChainPayment) -> bool {
+        self.muxed_id.is_none() || self.muxed_id == payment.from_muxed_id()
+    }
+}
+
 #[derive(Debug)]
 struct ChainPayment {
     id: String,
     from: String,
+    /// Sender's `M...` address when it paid from a muxed account.
+    from_muxed: Option<String>,
     to: String,
     amount: String,
     asset_code: String,
     memo: Option<String>,
 }
 
+impl ChainPayment {
+    fn from_muxed_id(&self) -> Option<u64> {
+        StellarAddress::parse(self.from_muxed.as_deref()?)
+            .ok()?
+            .muxed_id()
+    }
+}
+
 pub struct ReconciliationService {
     horizon_client: HorizonClient,
     pool: PgPool,
@@ -119,10 +155,19 @@ impl ReconciliationService {
         // Find discrepancies
         let mut missing_on_chain = Vec::new();
         let mut amount_mismatches = Vec::new();
+        // Payments whose memo matched a transaction expecting another muxed id.
+        let mut rejected = HashSet::new();
 
         for tx in &db_txs {
             if let Some(memo) = &tx.memo {
-                if let Some(payment) = chain_by_memo.get(memo) {
+                let payment = match chain_by_memo.get(memo) {
+                    Some(payment) if !tx.accepts(payment) => {
+                        rejected.insert(payment.id.as_str());
+                        None
+                    }
+                    matched => matched,
+                };
+                if let Some(payment) = payment {
                     // Check amount match
                     if tx.amount != payment.amount {
                         amount_mismatches.push(AmountMismatch {
@@ -137,7 +182,7 @@ impl ReconciliationService {
                     // Transaction in DB but not on chain
                     missing_on_chain.push(MissingTransaction {
                         id: tx.id,
-                        stellar_account: tx.stellar_account.clone(),
+                        stellar_account: tx.address(),
                         amount: tx.amount.clone(),
                         asset_code: tx.asset_code.clone(),
                         memo: tx.memo.clone(),
@@ -153,10 +198,10 @@ impl ReconciliationService {
 
         for payment in &chain_payments {
             if let Some(memo) = &payment.memo {
-                if !db_memos.contains(memo) {
+                if !db_memos.contains(memo) || rejected.contains(payment.id.as_str()) {
                     orphaned_payments.push(OrphanedPayment {
                         payment_id: payment.id.clone(),
-                        from: payment.from.clone(),
+                        from: payment.from_muxed.clone().unwrap_or(payment.from.clone()),
                         to: payment.to.clone(),
                         amount: payment.amount.clone(),
                         asset_code: payment.asset_code.clone(),
@@ -193,32 +238,44 @@ impl ReconciliationService {
         start: DateTime<Utc>,
         end: DateTime<Utc>,
     ) -> anyhow::Result<Vec<DbTransaction>> {
-        let rows =
-            sqlx::query_as::<_, (Uuid, String, String, String, Option<String>, DateTime<Utc>)>(
-                "SELECT id, stellar_account, amount::text, asset_code, memo, created_at 
+        type Row = (
+            Uuid,
+            String,
+            Option<BigDecimal>,
+            String,
+            String,
+            Option<String>,
+            DateTime<Utc>,
+        );
+        let rows = sqlx::query_as::<_, Row>(
+            "SELECT id, stellar_account, stellar_muxed_id, amount::text, asset_code, memo,
+                    created_at
              FROM transactions 
              WHERE stellar_account = $1 
              AND created_at >= $2 
              AND created_at <= $3 
              AND status = 'completed'
              ORDER BY created_at",
-            )
-            .bind(account)
-            .bind(start)
-            .bind(end)
-            .fetch_all(&self.pool)
-            .await?;
+        )
+        .bind(account)
+        .bind(start)
+        .bind(end)
+        .fetch_all(&self.pool)
+        .await?;
 
         Ok(rows
             .into_iter()
             .map(
-                |(id, stellar_account, amount, asset_code, memo, created_at)| DbTransaction {
-                    id,
-                    stellar_account,
-                    amount,
-                    asset_code,
-                    memo,
-                    created_at,
+                |(id, stellar_account, muxed_id, amount, asset_code, memo, created_at)| {
+                    DbTransaction {
+                        id,
+                        stellar_account,
+                        muxed_id: muxed_id.and_then(|id| id.to_u64()),
+                        amount,
+                        asset_code,
+                        memo,
+                        created_at,
+                    }
                 },
             )
             .collect())
@@ -232,7 +289,7 @@ impl ReconciliationService {
     ) -> anyhow::Result<Vec<ChainPayment>> {
         let url = format!(
             "{}/accounts/{}/payments?order=asc&limit=200",
-            self.horizon_client.base_url.trim_end_matches('/'),
+            self.horizon_client.base_url().trim_end_matches('/'),
             account
         );
 
@@ -257,6 +314,8 @@ impl ReconciliationService {
         struct PaymentRecord {
             id: String,
             from: String,
+            #[serde(default)]
+            from_muxed: Option<String>,
             to: String,
             amount: String,
             asset_code: String,
@@ -273,6 +332,7 @@ impl ReconciliationService {
             .map(|r| ChainPayment {
                 id: r.id,
                 from: r.from,
+                from_muxed: r.from_muxed,
                 to: r.to,
                 amount: r.amount,
                 asset_code: r.asset_code,
@@ -330,7 +390,10 @@ impl crate::services::scheduler::Job for ReconciliationJob {
         "0 0 2 * * *"
     }
 
-    async fn execute(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    async fn execute(
+        &self,
+        _cancel: &crate::services::scheduler::CancellationToken,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let end = Utc::now();
         let start = end - Duration::hours(24);
 
@@ -362,10 +425,97 @@ impl crate::services::scheduler::Job for ReconciliationJob {
         ReconciliationService::store_report(&self.pool, &report).await?;
         info!("Reconciliation report stored");
 
+        let refunds_queued = queue_orphan_refunds(&self.pool, &self.stellar_account, &report).await;
+        if refunds_queued > 0 {
+            tracing::warn!(refunds_queued, "Orphaned payments queued for refund");
+        }
+
         Ok(())
     }
 }
 
+/// Queue refunds for orphaned payments sent to `account`; returns how many
+/// were newly queued. Failures are logged and skipped.
+async fn queue_orphan_refunds(
+    pool: &PgPool,
+    account: &str,
+    report: &ReconciliationReport,
+) -> usize {
+    let mut refunds_queued = 0usize;
+    for payment in &report.orphaned_payments {
+        if payment.to != account {
+            continue;
+        }
+        let queued = refunds::enqueue_if_unmatched(
+            pool,
+            RefundSource::Reconciliation,
+            &InboundPayment {
+                payment_id: &payment.payment_id,
+                from: &payment.from,
+                amount: &payment.amount,
+                asset_code: &payment.asset_code,
+                memo: payment.memo.as_deref(),
+            },
+        )
+        .await;
+        match queued {
+            Ok(Some(_)) => refunds_queued += 1,
+            Ok(None) => {}
+            Err(e) => tracing::error!(
+                payment_id = %payment.payment_id,
+                error = %e,
+                "Failed to queue refund for orphaned payment"
+            ),
+        }
+    }
+    refunds_queued
+}
+
+/// [`JobHandler`] for `reconciliation_backfill` jobs: re-runs reconciliation
+/// for each UTC day in `{"from", "to"}`, storing one report per day and
+/// checkpointing after each.
+pub struct ReconciliationBackfillHandler {
+    pub horizon_client: HorizonClient,
+    pub stellar_account: String,
+}
+
+#[async_trait]
+impl JobHandler for ReconciliationBackfillHandler {
+    fn kind(&self) -> JobKind {
+        JobKind::ReconciliationBackfill
+    }
+
+    async fn run(&self, ctx: &mut JobContext) -> anyhow::Result<serde_json::Value> {
+        let range: DateRangeParams = ctx.params()?;
+        let mut day = match ctx.checkpoint::<DayCheckpoint>()? {
+            Some(cp) => cp.next_day,
+            None => range.from,
+        };
+        let svc = ReconciliationService::new(self.horizon_client.clone(), ctx.pool().clone());
+        let mut refunds_queued = 0usize;
+
+        while day <= range.to {
+            let start = day.and_time(NaiveTime::MIN).and_utc();
+            let report = svc
+                .reconcile(&self.stellar_account, start, start + Duration::days(1))
+                .await?;
+            ReconciliationService::store_report(ctx.pool(), &report).await?;
+            refunds_queued +=
+                queue_orphan_refunds(ctx.pool(), &self.stellar_account, &report).await;
+
+            day += Duration::days(1);
+            let done = (day - range.from).num_days();
+            ctx.save_progress(done, Some(range.days()), &DayCheckpoint { next_day: day })
+                .await?;
+        }
+
+        Ok(serde_json::json!({
+            "days": range.days(),
+            "refunds_queued": refunds_queued,
+        }))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -535,6 +685,37 @@ mod tests {
     // Unit tests — ReconciliationJob metadata (no DB, no HTTP)
     // ---------------------------------------------------------------------------
 
+    #[test]
+    fn test_muxed_transaction_only_accepts_its_own_id() {
+        const ACCOUNT: &str = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+        const MUXED: &str = "MA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJUAAAAAAAAAAE2JUG6";
+        let tx = |muxed_id| DbTransaction {
+            id: Uuid::new_v4(),
+            stellar_account: ACCOUNT.to_string(),
+            muxed_id,
+            amount: "10".to_string(),
+            asset_code: "USDC".to_string(),
+            memo: Some("ref".to_string()),
+            created_at: Utc::now(),
+        };
+        let payment = |from_muxed: Option<&str>| ChainPayment {
+            id: "1".to_string(),
+            from: ACCOUNT.to_string(),
+            from_muxed: from_muxed.map(str::to_string),
+            to: "GDEST".to_string(),
+            amount: "10".to_string(),
+            asset_code: "USDC".to_string(),
+            memo: Some("ref".to_string()),
+        };
+
+        assert!(tx(None).accepts(&payment(Some(MUXED))));
+        assert!(tx(Some(1234)).accepts(&payment(Some(MUXED))));
+        assert!(!tx(Some(1)).accepts(&payment(Some(MUXED))));
+        assert!(!tx(Some(1234)).accepts(&payment(None)));
+        assert_eq!(tx(Some(1234)).address(), MUXED);
+        assert_eq!(tx(None).address(), ACCOUNT);
+    }
+
     #[test]
     fn test_reconciliation_job_name() {
         // Verify the scheduler will register this job under the expected name.
@@ -703,8 +884,9 @@ mod tests {
         let memo = format!("missing-memo-{}", Uuid::new_v4());
         let (start, end) = make_period();
         sqlx::query(
-            "INSERT INTO transactions (id, stellar_account, amount, asset_code, status, memo, created_at, updated_at)
-             VALUES ($1, $2, $3::numeric, $4, 'completed', $5, $6, $6)",
+            "INSERT INTO transactions (id, stellar_account, amount, asset_code, status, memo, created_at, updated_at,
+                 anchor_transaction_id)
+             VALUES ($1, $2, $3::numeric, $4, 'completed', $5, $6, $6, $1::text)",
         )
         .bind(Uuid::new_v4())
         .bind(account)
@@ -761,8 +943,9 @@ mod tests {
         let (start, end) = make_period();
 
         sqlx::query(
-            "INSERT INTO transactions (id, stellar_account, amount, asset_code, status, memo, created_at, updated_at)
-             VALUES ($1, $2, $3::numeric, $4, 'completed', $5, $6, $6)",
+            "INSERT INTO transactions (id, stellar_account, amount, asset_code, status, memo, created_at, updated_at,
+                 anchor_transaction_id)
+             VALUES ($1, $2, $3::numeric, $4, 'completed', $5, $6, $6, $1::text)",
         )
         .bind(Uuid::new_v4())
         .bind(account)
@@ -827,8 +1010,9 @@ mod tests {
         let (start, end) = make_period();
 
         sqlx::query(
-            "INSERT INTO transactions (id, stellar_account, amount, asset_code, status, memo, created_at, updated_at)
-             VALUES ($1, $2, $3::numeric, $4, 'completed', $5, $6, $6)",
+            "INSERT INTO transactions (id, stellar_account, amount, asset_code, status, memo, created_at, updated_at,
+                 anchor_transaction_id)
+             VALUES ($1, $2, $3::numeric, $4, 'completed', $5, $6, $6, $1::text)",
         )
         .bind(Uuid::new_v4())
         .bind(account)
@@ -899,8 +1083,9 @@ mod tests {
             (&memo_mismatch, "30.00"),
         ] {
             sqlx::query(
-                "INSERT INTO transactions (id, stellar_account, amount, asset_code, status, memo, created_at, updated_at)
-                 VALUES ($1, $2, $3::numeric, $4, 'completed', $5, $6, $6)",
+                "INSERT INTO transactions (id, stellar_account, amount, asset_code, status, memo, created_at, updated_at,
+                     anchor_transaction_id)
+                 VALUES ($1, $2, $3::numeric, $4, 'completed', $5, $6, $6, $1::text)",
             )
             .bind(Uuid::new_v4())
             .bind(account)
@@ -988,8 +1173,9 @@ mod tests {
         let (start, end) = make_period();
 
         sqlx::query(
-            "INSERT INTO transactions (id, stellar_account, amount, asset_code, status, memo, created_at, updated_at)
-             VALUES ($1, $2, $3::numeric, $4, 'completed', NULL, $5, $5)",
+            "INSERT INTO transactions (id, stellar_account, amount, asset_code, status, memo, created_at, updated_at,
+                 anchor_transaction_id)
+             VALUES ($1, $2, $3::numeric, $4, 'completed', NULL, $5, $5, $1::text)",
         )
         .bind(Uuid::new_v4())
         .bind(account)
@@ -1044,8 +1230,9 @@ mod tests {
 
         for amount in ["50.00", "50.00"] {
             sqlx::query(
-                "INSERT INTO transactions (id, stellar_account, amount, asset_code, status, memo, created_at, updated_at)
-                 VALUES ($1, $2, $3::numeric, $4, 'completed', $5, $6, $6)",
+                "INSERT INTO transactions (id, stellar_account, amount, asset_code, status, memo, created_at, updated_at,
+                     anchor_transaction_id)
+                 VALUES ($1, $2, $3::numeric, $4, 'completed', $5, $6, $6, $1::text)",
             )
             .bind(Uuid::new_v4())
             .bind(account)
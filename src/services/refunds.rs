@@ -0,0 +1,293 @@
+//! Refunds for inbound on-chain payments that cannot be matched to a deposit.
+//!
+//! The [`AccountMonitor`](crate::services::AccountMonitor) and the daily
+//! [`ReconciliationJob`](crate::services::reconciliation::ReconciliationJob)
+//! both hand unmatched payments to [`enqueue_if_unmatched`], which records a
+//! task in `refund_queue` returning the funds to the sender minus the network
+//! fee (`REFUND_NETWORK_FEE`, default `0.00001`). A payment is only ever
+//! queued once, keyed by its Horizon payment id.
+//!
+//! Task lifecycle, driven by the refund submitter and the admin override
+//! endpoints (`/admin/refunds`):
+//!
+//! ```text
+//! pending ──hold──▶ held ──release──▶ pending
+//!    │                │
+//!    ├──cancel────────┴──cancel──▶ cancelled
+//!    └──complete──▶ completed
+//! ```
+
+use crate::db::models::{Asset, RefundTask};
+use crate::db::queries;
+use serde::{Deserialize, Serialize};
+use sqlx::types::BigDecimal;
+use sqlx::PgPool;
+use std::str::FromStr;
+
+/// Default network fee withheld from each refund, in the refunded asset.
+pub const DEFAULT_NETWORK_FEE: &str = "0.00001";
+
+/// Which component found the unmatched payment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefundSource {
+    PaymentMonitor,
+    Reconciliation,
+}
+
+impl RefundSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RefundSource::PaymentMonitor => "payment_monitor",
+            RefundSource::Reconciliation => "reconciliation",
+        }
+    }
+}
+
+/// Why a payment could not be matched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefundReason {
+    /// The asset is not registered (or is disabled) in `assets`.
+    UnknownAsset,
+    /// The payment carried no memo to match on.
+    MissingMemo,
+    /// No transaction carries the payment's memo.
+    UnmatchedMemo,
+}
+
+impl RefundReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RefundReason::UnknownAsset => "unknown_asset",
+            RefundReason::MissingMemo => "missing_memo",
+            RefundReason::UnmatchedMemo => "unmatched_memo",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RefundStatus {
+    Pending,
+    Held,
+    Cancelled,
+    Completed,
+}
+
+impl RefundStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RefundStatus::Pending => "pending",
+            RefundStatus::Held => "held",
+            RefundStatus::Cancelled => "cancelled",
+            RefundStatus::Completed => "completed",
+        }
+    }
+}
+
+impl FromStr for RefundStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(RefundStatus::Pending),
+            "held" => Ok(RefundStatus::Held),
+            "cancelled" => Ok(RefundStatus::Cancelled),
+            "completed" => Ok(RefundStatus::Completed),
+            _ => Err(format!("Invalid refund status: {s}")),
+        }
+    }
+}
+
+/// Admin override applied to a refund task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RefundAction {
+    /// Pause an automatic refund, e.g. while support contacts the sender.
+    Hold,
+    /// Return a held task to the queue.
+    Release,
+    /// Drop the refund, e.g. after the payment was matched by hand.
+    Cancel,
+    /// Record that the refund was sent (requires the Stellar tx hash).
+    Complete,
+}
+
+impl RefundAction {
+    /// Status after applying the action to a task in `from`, or an error if
+    /// the action is not allowed there.
+    pub fn apply(self, from: RefundStatus) -> Result<RefundStatus, String> {
+        use RefundStatus::*;
+        let to = match (self, from) {
+            (RefundAction::Hold, Pending) => Held,
+            (RefundAction::Release, Held) => Pending,
+            (RefundAction::Cancel, Pending | Held) => Cancelled,
+            (RefundAction::Complete, Pending) => Completed,
+            _ => {
+                return Err(format!(
+                    "cannot {} a refund that is {}",
+                    self.as_str(),
+                    from.as_str()
+                ))
+            }
+        };
+        Ok(to)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RefundAction::Hold => "hold",
+            RefundAction::Release => "release",
+            RefundAction::Cancel => "cancel",
+            RefundAction::Complete => "complete",
+        }
+    }
+}
+
+/// An inbound payment as seen on chain.
+#[derive(Debug, Clone)]
+pub struct InboundPayment<'a> {
+    pub payment_id: &'a str,
+    pub from: &'a str,
+    pub amount: &'a str,
+    pub asset_code: &'a str,
+    pub memo: Option<&'a str>,
+}
+
+/// Network fee withheld from refunds (`REFUND_NETWORK_FEE`).
+pub fn network_fee() -> BigDecimal {
+    std::env::var("REFUND_NETWORK_FEE")
+        .ok()
+        .and_then(|v| BigDecimal::from_str(v.trim()).ok())
+        .filter(|fee| *fee >= BigDecimal::from(0))
+        .unwrap_or_else(|| BigDecimal::from_str(DEFAULT_NETWORK_FEE).unwrap_or_default())
+}
+
+/// Amount returned to the sender; never negative.
+pub fn refund_amount(amount: &BigDecimal, fee: &BigDecimal) -> BigDecimal {
+    let net = amount - fee;
+    if net < BigDecimal::from(0) {
+        BigDecimal::from(0)
+    } else {
+        net
+    }
+}
+
+/// Decide whether `payment` is refundable. `None` means it belongs to a known
+/// transaction (in any status) and must be left alone.
+pub async fn classify(
+    pool: &PgPool,
+    payment: &InboundPayment<'_>,
+) -> Result<Option<RefundReason>, sqlx::Error> {
+    if !Asset::is_registered(pool, payment.asset_code).await? {
+        return Ok(Some(RefundReason::UnknownAsset));
+    }
+    let Some(memo) = payment.memo else {
+        return Ok(Some(RefundReason::MissingMemo));
+    };
+    if queries::transaction_exists_for_memo(pool, memo).await? {
+        Ok(None)
+    } else {
+        Ok(Some(RefundReason::UnmatchedMemo))
+    }
+}
+
+/// Queue a refund for `payment` if it cannot be matched.
+///
+/// Returns the new task, or `None` when the payment matched a transaction or
+/// was already queued. Refunds that would not cover the network fee are
+/// recorded as `held` for manual review instead of being sent.
+pub async fn enqueue_if_unmatched(
+    pool: &PgPool,
+    source: RefundSource,
+    payment: &InboundPayment<'_>,
+) -> anyhow::Result<Option<RefundTask>> {
+    let Some(reason) = classify(pool, payment).await? else {
+        return Ok(None);
+    };
+
+    let amount = BigDecimal::from_str(payment.amount)?;
+    if amount <= BigDecimal::from(0) {
+        return Ok(None);
+    }
+    let fee = network_fee();
+    let net = refund_amount(&amount, &fee);
+    let (status, note) = if net > BigDecimal::from(0) {
+        (RefundStatus::Pending, None)
+    } else {
+        (
+            RefundStatus::Held,
+            Some("amount does not cover the network fee"),
+        )
+    };
+
+    let task = queries::insert_refund_task(
+        pool,
+        &queries::NewRefundTask {
+            payment_id: payment.payment_id,
+            source: source.as_str(),
+            reason: reason.as_str(),
+            refund_to: payment.from,
+            asset_code: payment.asset_code,
+            amount: &amount,
+            network_fee: &fee,
+            refund_amount: &net,
+            memo: payment.memo,
+            status: status.as_str(),
+            note,
+        },
+    )
+    .await?;
+
+    if let Some(task) = &task {
+        tracing::warn!(
+            refund_id = %task.id,
+            payment_id = payment.payment_id,
+            reason = reason.as_str(),
+            source = source.as_str(),
+            "Unmatched payment queued for refund"
+        );
+    }
+    Ok(task)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(s: &str) -> BigDecimal {
+        BigDecimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_refund_amount_deducts_fee_and_floors_at_zero() {
+        assert_eq!(refund_amount(&dec("10"), &dec("0.00001")), dec("9.99999"));
+        assert_eq!(refund_amount(&dec("0.000005"), &dec("0.00001")), dec("0"));
+    }
+
+    #[test]
+    fn test_action_transitions() {
+        use RefundStatus::*;
+        assert_eq!(RefundAction::Hold.apply(Pending), Ok(Held));
+        assert_eq!(RefundAction::Release.apply(Held), Ok(Pending));
+        assert_eq!(RefundAction::Cancel.apply(Held), Ok(Cancelled));
+        assert_eq!(RefundAction::Complete.apply(Pending), Ok(Completed));
+
+        assert!(RefundAction::Complete.apply(Held).is_err());
+        assert!(RefundAction::Release.apply(Pending).is_err());
+        assert!(RefundAction::Cancel.apply(Completed).is_err());
+        assert!(RefundAction::Hold.apply(Cancelled).is_err());
+    }
+
+    #[test]
+    fn test_status_round_trip() {
+        for s in [
+            RefundStatus::Pending,
+            RefundStatus::Held,
+            RefundStatus::Cancelled,
+            RefundStatus::Completed,
+        ] {
+            assert_eq!(RefundStatus::from_str(s.as_str()), Ok(s));
+        }
+        assert!(RefundStatus::from_str("refunded").is_err());
+    }
+}
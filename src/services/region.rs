@@ -0,0 +1,272 @@
+//! Multi-region active-passive role.
+//!
+//! | Role      | Reads, callback ingest | Processor, scheduled jobs | Horizon submission |
+//! |-----------|------------------------|---------------------------|--------------------|
+//! | `active`  | yes                    | yes                       | yes                |
+//! | `passive` | yes                    | paused                    | refused            |
+//!
+//! | Env var       | Default   | Meaning                                   |
+//! |---------------|-----------|-------------------------------------------|
+//! | `REGION_NAME` | `default` | Region this instance runs in              |
+//! | `REGION_ROLE` | `active`  | Role until an operator overrides it       |
+//!
+//! During a failover an operator flips roles with `PUT /admin/region/role`
+//! instead of redeploying. The override is stored in Redis under
+//! `region_role:<region>` and every instance of the region picks it up
+//! within [`ROLE_SYNC_INTERVAL`] through [`sync_role`]; clearing it returns
+//! the region to `REGION_ROLE`. Callbacks received while passive wait in
+//! `webhook_inbox` and `pending` until the region is active again.
+
+use anyhow::bail;
+use chrono::{DateTime, Utc};
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
+
+const DEFAULT_REGION: &str = "default";
+const ROLE_KEY_PREFIX: &str = "region_role:";
+
+/// How often [`sync_role`] re-reads the region's override.
+pub const ROLE_SYNC_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegionRole {
+    Active,
+    Passive,
+}
+
+impl RegionRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RegionRole::Active => "active",
+            RegionRole::Passive => "passive",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "active" => Some(RegionRole::Active),
+            "passive" => Some(RegionRole::Passive),
+            _ => None,
+        }
+    }
+}
+
+/// A role set by an operator, overriding `REGION_ROLE`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoleOverride {
+    pub role: RegionRole,
+    pub reason: String,
+    pub actor: String,
+    pub set_at: DateTime<Utc>,
+}
+
+/// What `GET /admin/region` reports.
+#[derive(Debug, Clone, Serialize)]
+pub struct RegionStatus {
+    pub region: String,
+    pub role: RegionRole,
+    pub configured_role: RegionRole,
+    #[serde(rename = "override")]
+    pub role_override: Option<RoleOverride>,
+}
+
+#[derive(Debug)]
+pub struct RegionState {
+    name: String,
+    configured: RegionRole,
+    role_override: RwLock<Option<RoleOverride>>,
+}
+
+impl RegionState {
+    pub fn new(name: impl Into<String>, configured: RegionRole) -> Self {
+        Self {
+            name: name.into(),
+            configured,
+            role_override: RwLock::new(None),
+        }
+    }
+
+    /// From `REGION_NAME` and `REGION_ROLE`. An unknown role is an error
+    /// rather than a default, so a typo cannot make a passive region active.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let name = std::env::var("REGION_NAME")
+            .ok()
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| DEFAULT_REGION.to_string());
+        let role = match std::env::var("REGION_ROLE") {
+            Ok(raw) if !raw.trim().is_empty() => match RegionRole::parse(&raw) {
+                Some(role) => role,
+                None => bail!("REGION_ROLE must be 'active' or 'passive', got '{raw}'"),
+            },
+            _ => RegionRole::Active,
+        };
+        Ok(Self::new(name, role))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn role_override(&self) -> Option<RoleOverride> {
+        self.role_override
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// The override's role if one is set, else the configured one.
+    pub fn role(&self) -> RegionRole {
+        self.role_override()
+            .map(|o| o.role)
+            .unwrap_or(self.configured)
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.role() == RegionRole::Active
+    }
+
+    /// Apply `value` (or clear the override), logging when the effective
+    /// role changes.
+    pub fn set_override(&self, value: Option<RoleOverride>) {
+        let before = self.role();
+        *self
+            .role_override
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = value.clone();
+        let after = self.role();
+        if after != before {
+            tracing::warn!(
+                region = %self.name,
+                from = before.as_str(),
+                to = after.as_str(),
+                actor = value.as_ref().map(|v| v.actor.as_str()).unwrap_or("config"),
+                reason = value.as_ref().map(|v| v.reason.as_str()).unwrap_or("override cleared"),
+                "Region role changed"
+            );
+        }
+    }
+
+    pub fn status(&self) -> RegionStatus {
+        RegionStatus {
+            region: self.name.clone(),
+            role: self.role(),
+            configured_role: self.configured,
+            role_override: self.role_override(),
+        }
+    }
+
+    fn key(&self) -> String {
+        format!("{ROLE_KEY_PREFIX}{}", self.name)
+    }
+}
+
+static REGION: OnceLock<RegionState> = OnceLock::new();
+
+/// Install the process-wide region state; only the first call has effect.
+pub fn init(state: RegionState) -> &'static RegionState {
+    let _ = REGION.set(state);
+    region()
+}
+
+/// Process-wide region state; an active `default` region until [`init`].
+pub fn region() -> &'static RegionState {
+    REGION.get_or_init(|| RegionState::new(DEFAULT_REGION, RegionRole::Active))
+}
+
+/// Whether this instance may run the processor and jobs and submit to
+/// Horizon.
+pub fn is_active() -> bool {
+    region().is_active()
+}
+
+/// The override stored for `state`'s region, if any.
+pub async fn get_override(
+    redis: &redis::Client,
+    state: &RegionState,
+) -> anyhow::Result<Option<RoleOverride>> {
+    let mut conn = redis.get_multiplexed_async_connection().await?;
+    let raw: Option<String> = conn.get(state.key()).await?;
+    Ok(raw.and_then(|raw| serde_json::from_str(&raw).ok()))
+}
+
+/// Store (or with `None` clear) the region's override and apply it here;
+/// the other instances follow within [`ROLE_SYNC_INTERVAL`].
+pub async fn set_override(
+    redis: &redis::Client,
+    state: &RegionState,
+    value: Option<RoleOverride>,
+) -> anyhow::Result<()> {
+    let mut conn = redis.get_multiplexed_async_connection().await?;
+    match &value {
+        Some(value) => {
+            conn.set::<_, _, ()>(state.key(), serde_json::to_string(value)?)
+                .await?
+        }
+        None => conn.del::<_, ()>(state.key()).await?,
+    }
+    state.set_override(value);
+    Ok(())
+}
+
+/// Keep this instance's role in step with the override in Redis. While
+/// Redis is unreachable the last known role stands.
+pub async fn sync_role(redis: redis::Client) {
+    let state = region();
+    let mut interval = tokio::time::interval(ROLE_SYNC_INTERVAL);
+    loop {
+        interval.tick().await;
+        match get_override(&redis, state).await {
+            Ok(value) => state.set_override(value),
+            Err(e) => tracing::debug!("Failed to read region role override: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn passive_override() -> RoleOverride {
+        RoleOverride {
+            role: RegionRole::Passive,
+            reason: "failing over to eu-west-1".to_string(),
+            actor: "ops".to_string(),
+            set_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_parse_role() {
+        assert_eq!(RegionRole::parse(" Passive "), Some(RegionRole::Passive));
+        assert_eq!(RegionRole::parse("active"), Some(RegionRole::Active));
+        assert_eq!(RegionRole::parse("standby"), None);
+    }
+
+    #[test]
+    fn test_override_wins_until_cleared() {
+        let state = RegionState::new("us-east-1", RegionRole::Active);
+        assert!(state.is_active());
+
+        state.set_override(Some(passive_override()));
+        assert_eq!(state.role(), RegionRole::Passive);
+        let status = state.status();
+        assert_eq!(status.configured_role, RegionRole::Active);
+        assert_eq!(status.role, RegionRole::Passive);
+
+        state.set_override(None);
+        assert!(state.is_active());
+        assert_eq!(state.key(), "region_role:us-east-1");
+    }
+
+    #[test]
+    fn test_status_serializes_override() {
+        let state = RegionState::new("eu-west-1", RegionRole::Passive);
+        let json = serde_json::to_value(state.status()).unwrap();
+        assert_eq!(json["role"], "passive");
+        assert!(json["override"].is_null());
+    }
+}
@@ -0,0 +1,401 @@
+//! Compliance review queue.
+//!
+//! Every item compliance has to decide on gets a row in `review_items`,
+//! opened by database triggers so no code path can skip it:
+//!
+//! | Kind          | Opened when                               | Resolutions              |
+//! |---------------|-------------------------------------------|--------------------------|
+//! | `transaction` | a transaction enters `compliance_review`  | `approved`, `rejected`   |
+//! | `structuring` | a structuring rule flags an account       | `dismissed`, `escalated` |
+//!
+//! Each item is due `sla_hours` after it was opened (per kind, from
+//! `review_sla_policies`; 24 hours for transactions and 72 for structuring
+//! by default) and moves through:
+//!
+//! ```text
+//! open ──claim──▶ assigned ──resolve──▶ resolved
+//!   └──────────────resolve─────────────────▲
+//! ```
+//!
+//! Resolving an item applies the decision to its subject in the same
+//! database transaction: an approved transaction returns to `pending`, a
+//! rejected one becomes `failed`, and a structuring review takes the
+//! resolution as its status. Subjects released elsewhere (bulk status
+//! updates, `/admin/structuring/reviews/:id/resolve`) resolve their item
+//! through the same triggers. Reviewers discuss an item in its comment
+//! thread.
+
+use crate::db::models::{ReviewComment, ReviewItem, TransactionStatus};
+use crate::db::queries;
+use crate::validation::state_machine::validate_status_transition;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::str::FromStr;
+use uuid::Uuid;
+
+pub const COMMENT_MAX_LEN: usize = 4000;
+const PRINCIPAL_MAX_LEN: usize = 255;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewKind {
+    Transaction,
+    Structuring,
+}
+
+impl ReviewKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewKind::Transaction => "transaction",
+            ReviewKind::Structuring => "structuring",
+        }
+    }
+}
+
+impl FromStr for ReviewKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "transaction" => Ok(ReviewKind::Transaction),
+            "structuring" => Ok(ReviewKind::Structuring),
+            _ => Err(format!("Invalid review kind: {s}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewState {
+    Open,
+    Assigned,
+    Resolved,
+}
+
+impl ReviewState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewState::Open => "open",
+            ReviewState::Assigned => "assigned",
+            ReviewState::Resolved => "resolved",
+        }
+    }
+}
+
+impl FromStr for ReviewState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "open" => Ok(ReviewState::Open),
+            "assigned" => Ok(ReviewState::Assigned),
+            "resolved" => Ok(ReviewState::Resolved),
+            _ => Err(format!("Invalid review state: {s}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Resolution {
+    Approved,
+    Rejected,
+    Dismissed,
+    Escalated,
+}
+
+impl Resolution {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Resolution::Approved => "approved",
+            Resolution::Rejected => "rejected",
+            Resolution::Dismissed => "dismissed",
+            Resolution::Escalated => "escalated",
+        }
+    }
+
+    /// The kind of item this resolution decides.
+    pub fn kind(&self) -> ReviewKind {
+        match self {
+            Resolution::Approved | Resolution::Rejected => ReviewKind::Transaction,
+            Resolution::Dismissed | Resolution::Escalated => ReviewKind::Structuring,
+        }
+    }
+}
+
+impl FromStr for Resolution {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "approved" => Ok(Resolution::Approved),
+            "rejected" => Ok(Resolution::Rejected),
+            "dismissed" => Ok(Resolution::Dismissed),
+            "escalated" => Ok(Resolution::Escalated),
+            _ => Err(format!("Invalid resolution: {s}")),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReviewError {
+    #[error("Review item {0} not found")]
+    NotFound(Uuid),
+
+    /// The item's state does not allow the operation.
+    #[error("{0}")]
+    Conflict(String),
+
+    #[error("{0}")]
+    Invalid(String),
+
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// A review item with its SLA timer, as returned by the API.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewItemView {
+    #[serde(flatten)]
+    pub item: ReviewItem,
+    /// Unresolved and past `sla_due_at`.
+    pub overdue: bool,
+    /// Seconds until `sla_due_at`, negative once overdue; `null` when resolved.
+    pub sla_remaining_secs: Option<i64>,
+}
+
+impl ReviewItemView {
+    pub fn at(item: ReviewItem, now: DateTime<Utc>) -> Self {
+        let sla_remaining_secs = (item.state != ReviewState::Resolved.as_str())
+            .then(|| (item.sla_due_at - now).num_seconds());
+        Self {
+            overdue: matches!(sla_remaining_secs, Some(secs) if secs < 0),
+            sla_remaining_secs,
+            item,
+        }
+    }
+}
+
+/// Trim a reviewer or author name and check it is usable.
+pub fn principal(field: &str, value: &str) -> Result<String, ReviewError> {
+    let value = value.trim();
+    if value.is_empty() || value.len() > PRINCIPAL_MAX_LEN {
+        return Err(ReviewError::Invalid(format!(
+            "{field} must be 1-{PRINCIPAL_MAX_LEN} characters"
+        )));
+    }
+    Ok(value.to_string())
+}
+
+/// Trim a comment body and check its length.
+pub fn comment_body(body: &str) -> Result<String, ReviewError> {
+    let body = body.trim();
+    if body.is_empty() || body.chars().count() > COMMENT_MAX_LEN {
+        return Err(ReviewError::Invalid(format!(
+            "comment must be 1-{COMMENT_MAX_LEN} characters"
+        )));
+    }
+    Ok(body.to_string())
+}
+
+/// Assign item `id` to `reviewer`.
+pub async fn claim(pool: &PgPool, id: Uuid, reviewer: &str) -> Result<ReviewItem, ReviewError> {
+    let reviewer = principal("reviewer", reviewer)?;
+    if let Some(item) = queries::claim_review_item(pool, id, &reviewer).await? {
+        return Ok(item);
+    }
+    let item = queries::get_review_item(pool, id)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => ReviewError::NotFound(id),
+            other => other.into(),
+        })?;
+    Err(ReviewError::Conflict(match item.assignee {
+        Some(assignee) if item.state == ReviewState::Assigned.as_str() => {
+            format!("Review item {id} is assigned to {assignee}")
+        }
+        _ => format!("Review item {id} is already {}", item.state),
+    }))
+}
+
+/// Decide item `id` and apply the decision to its subject. Items assigned to
+/// another reviewer cannot be resolved; open ones can.
+pub async fn resolve(
+    pool: &PgPool,
+    id: Uuid,
+    reviewer: &str,
+    resolution: Resolution,
+    comment: Option<&str>,
+) -> Result<ReviewItem, ReviewError> {
+    let reviewer = principal("reviewer", reviewer)?;
+    let comment = comment.map(comment_body).transpose()?;
+
+    let mut db_tx = pool.begin().await?;
+    let item = queries::lock_review_item(&mut db_tx, id)
+        .await?
+        .ok_or(ReviewError::NotFound(id))?;
+
+    if item.state == ReviewState::Resolved.as_str() {
+        return Err(ReviewError::Conflict(format!(
+            "Review item {id} is already resolved"
+        )));
+    }
+    if let Some(assignee) = item.assignee.as_deref().filter(|a| *a != reviewer) {
+        return Err(ReviewError::Conflict(format!(
+            "Review item {id} is assigned to {assignee}"
+        )));
+    }
+    if resolution.kind().as_str() != item.kind {
+        return Err(ReviewError::Invalid(format!(
+            "{} does not apply to {} review items",
+            resolution.as_str(),
+            item.kind
+        )));
+    }
+
+    // Resolve the item first so the subject's trigger finds nothing to do.
+    let resolved =
+        queries::mark_review_item_resolved(&mut db_tx, &item, resolution.as_str(), &reviewer)
+            .await?;
+
+    let applied = match (item.transaction_id, item.structuring_review_id) {
+        (Some(transaction_id), _) => {
+            let to = if resolution == Resolution::Approved {
+                TransactionStatus::Pending
+            } else {
+                TransactionStatus::Failed
+            };
+            validate_status_transition(TransactionStatus::ComplianceReview.as_str(), to.as_str())
+                .map_err(|e| ReviewError::Invalid(e.to_string()))?;
+            queries::release_held_transaction(&mut db_tx, transaction_id, to, &reviewer).await?
+        }
+        (None, Some(review_id)) => queries::close_structuring_review(
+            &mut db_tx,
+            review_id,
+            resolution.as_str(),
+            comment.as_deref(),
+            &reviewer,
+        )
+        .await?
+        .is_some(),
+        (None, None) => false,
+    };
+    if !applied {
+        return Err(ReviewError::Conflict(format!(
+            "The subject of review item {id} is no longer awaiting review"
+        )));
+    }
+
+    if let Some(body) = &comment {
+        queries::insert_review_comment(&mut db_tx, id, &reviewer, body).await?;
+    }
+    db_tx.commit().await?;
+    Ok(resolved)
+}
+
+/// Add `body` to the comment thread of item `id`.
+pub async fn add_comment(
+    pool: &PgPool,
+    id: Uuid,
+    author: &str,
+    body: &str,
+) -> Result<ReviewComment, ReviewError> {
+    let author = principal("author", author)?;
+    let body = comment_body(body)?;
+
+    let mut db_tx = pool.begin().await?;
+    queries::lock_review_item(&mut db_tx, id)
+        .await?
+        .ok_or(ReviewError::NotFound(id))?;
+    let comment = queries::insert_review_comment(&mut db_tx, id, &author, &body).await?;
+    db_tx.commit().await?;
+    Ok(comment)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn item(state: &str, due_in: Duration, now: DateTime<Utc>) -> ReviewItem {
+        ReviewItem {
+            id: Uuid::nil(),
+            kind: "transaction".to_string(),
+            transaction_id: Some(Uuid::nil()),
+            structuring_review_id: None,
+            state: state.to_string(),
+            assignee: None,
+            assigned_at: None,
+            sla_due_at: now + due_in,
+            resolution: None,
+            resolved_by: None,
+            resolved_at: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_sla_timer() {
+        let now = Utc::now();
+        let pending = ReviewItemView::at(item("open", Duration::hours(2), now), now);
+        assert!(!pending.overdue);
+        assert_eq!(pending.sla_remaining_secs, Some(7200));
+
+        let late = ReviewItemView::at(item("assigned", Duration::minutes(-5), now), now);
+        assert!(late.overdue);
+        assert_eq!(late.sla_remaining_secs, Some(-300));
+
+        let done = ReviewItemView::at(item("resolved", Duration::minutes(-5), now), now);
+        assert!(!done.overdue);
+        assert_eq!(done.sla_remaining_secs, None);
+    }
+
+    #[test]
+    fn test_item_becomes_overdue_as_the_clock_advances() {
+        use crate::adapters::ManualClock;
+        use crate::ports::Clock;
+
+        let clock = ManualClock::new(Utc::now());
+        let opened = item("open", Duration::hours(24), clock.now());
+
+        clock.advance(Duration::hours(23));
+        assert!(!ReviewItemView::at(opened.clone(), clock.now()).overdue);
+        clock.advance(Duration::hours(2));
+        let view = ReviewItemView::at(opened, clock.now());
+        assert!(view.overdue);
+        assert_eq!(view.sla_remaining_secs, Some(-3600));
+    }
+
+    #[test]
+    fn test_resolutions_belong_to_one_kind() {
+        assert_eq!(Resolution::Approved.kind(), ReviewKind::Transaction);
+        assert_eq!(Resolution::Rejected.kind(), ReviewKind::Transaction);
+        assert_eq!(Resolution::Dismissed.kind(), ReviewKind::Structuring);
+        assert_eq!(Resolution::Escalated.kind(), ReviewKind::Structuring);
+    }
+
+    #[test]
+    fn test_round_trips() {
+        for s in ["open", "assigned", "resolved"] {
+            assert_eq!(ReviewState::from_str(s).unwrap().as_str(), s);
+        }
+        for s in ["transaction", "structuring"] {
+            assert_eq!(ReviewKind::from_str(s).unwrap().as_str(), s);
+        }
+        for s in ["approved", "rejected", "dismissed", "escalated"] {
+            assert_eq!(Resolution::from_str(s).unwrap().as_str(), s);
+        }
+        assert!(ReviewState::from_str("closed").is_err());
+    }
+
+    #[test]
+    fn test_input_validation() {
+        assert_eq!(principal("reviewer", " alice ").unwrap(), "alice");
+        assert!(principal("reviewer", "  ").is_err());
+        assert!(comment_body("").is_err());
+        assert!(comment_body(&"x".repeat(COMMENT_MAX_LEN + 1)).is_err());
+        assert_eq!(comment_body(" looks fine \n").unwrap(), "looks fine");
+    }
+}
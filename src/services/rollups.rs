@@ -0,0 +1,78 @@
+//! Maintenance of `transaction_daily_rollups`.
+//!
+//! A trigger on `transactions` appends each write's change to
+//! `transaction_rollup_deltas` rather than updating the shared rollup row,
+//! so concurrent writes never wait on one another. [`RollupCompactionJob`]
+//! folds the deltas into the rollups every minute; reads add the deltas not
+//! folded yet, so they are exact either way.
+//!
+//! A `rollup_recompute` job rebuilds the rollups day by day from the source
+//! rows, e.g. after a manual data fix or a partition restore. Progress is
+//! checkpointed after every day.
+
+use crate::db::queries;
+use crate::services::job_runner::{
+    DateRangeParams, DayCheckpoint, JobContext, JobHandler, JobKind,
+};
+use crate::services::scheduler::{CancellationToken, Job};
+use async_trait::async_trait;
+use chrono::Duration;
+use sqlx::PgPool;
+
+/// [`JobHandler`] for `rollup_recompute` jobs (params: `{"from", "to"}`).
+pub struct RollupRecomputeHandler;
+
+#[async_trait]
+impl JobHandler for RollupRecomputeHandler {
+    fn kind(&self) -> JobKind {
+        JobKind::RollupRecompute
+    }
+
+    async fn run(&self, ctx: &mut JobContext) -> anyhow::Result<serde_json::Value> {
+        let range: DateRangeParams = ctx.params()?;
+        let mut day = match ctx.checkpoint::<DayCheckpoint>()? {
+            Some(cp) => cp.next_day,
+            None => range.from,
+        };
+        let mut rows = 0u64;
+
+        while day <= range.to {
+            rows += queries::recompute_daily_rollup(ctx.pool(), day).await?;
+            day += Duration::days(1);
+            let done = (day - range.from).num_days();
+            ctx.save_progress(done, Some(range.days()), &DayCheckpoint { next_day: day })
+                .await?;
+        }
+
+        Ok(serde_json::json!({
+            "days": range.days(),
+            "rollup_rows_written": rows,
+        }))
+    }
+}
+
+/// Folds `transaction_rollup_deltas` into `transaction_daily_rollups`.
+pub struct RollupCompactionJob {
+    pub pool: PgPool,
+}
+
+#[async_trait]
+impl Job for RollupCompactionJob {
+    fn name(&self) -> &str {
+        "rollup_compaction"
+    }
+
+    /// Run at the start of every minute.
+    fn schedule(&self) -> &str {
+        "0 * * * * * *"
+    }
+
+    async fn execute(
+        &self,
+        _cancel: &CancellationToken,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let folded = queries::compact_rollup_deltas(&self.pool).await?;
+        tracing::debug!(folded, "Rollup deltas compacted");
+        Ok(())
+    }
+}
@@ -1,11 +1,63 @@
+use crate::config::jobs::{JobSettings, JobsConfig};
+use crate::services::region;
 use async_trait::async_trait;
 use chrono::{DateTime, Duration, Utc};
 use cron::Schedule;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
 use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
-use tokio::sync::Mutex;
-use tracing::{error, info};
+use tokio::sync::{Mutex, Notify, OwnedSemaphorePermit, Semaphore};
+use tracing::{error, info, warn};
+
+/// Longest a job run may take unless the job or its configuration says otherwise.
+pub const DEFAULT_JOB_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3600);
+
+/// How long a timed-out run gets to stop after its token is cancelled before
+/// the scheduler drops it.
+const CANCEL_GRACE: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Cooperative cancellation signal passed to [`Job::execute`]. The scheduler
+/// cancels it when a run exceeds its timeout; jobs should check it between
+/// units of work and return promptly once it is set.
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<CancelState>,
+}
+
+#[derive(Default)]
+struct CancelState {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        self.inner.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Resolves once the token is cancelled.
+    pub async fn cancelled(&self) {
+        loop {
+            let notified = self.inner.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
 
 /// Represents a scheduled job that can be executed at specific intervals
 #[async_trait]
@@ -16,13 +68,145 @@ pub trait Job: Send + Sync {
     /// Cron expression defining when the job should run
     fn schedule(&self) -> &str;
 
-    /// Execute the job's business logic
-    async fn execute(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+    /// Apply this job's configured settings before it is scheduled. Jobs that
+    /// work in batches should honour `settings.batch_size`.
+    fn configure(&mut self, _settings: &JobSettings) {}
+
+    /// Longest a run may take before it is cancelled; `JOB_<NAME>_TIMEOUT_SECS`
+    /// overrides it.
+    fn timeout(&self) -> std::time::Duration {
+        DEFAULT_JOB_TIMEOUT
+    }
+
+    /// Execute the job's business logic. Long-running jobs should check
+    /// `cancel` between units of work and stop once it is cancelled.
+    async fn execute(
+        &self,
+        cancel: &CancellationToken,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    /// Execute once with one-off parameters from a manual trigger. Jobs that
+    /// accept parameters override this; by default they are ignored.
+    async fn execute_with(
+        &self,
+        cancel: &CancellationToken,
+        _params: &RunParams,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.execute(cancel).await
+    }
+}
+
+/// One-off parameters for a manually triggered run.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunParams {
+    /// Overrides the configured batch size for this run.
+    pub batch_size: Option<u32>,
+}
+
+/// Why [`JobScheduler::trigger`] refused to start a run.
+#[derive(Debug, thiserror::Error)]
+pub enum TriggerError {
+    #[error("No job named '{0}' is registered")]
+    UnknownJob(String),
+
+    #[error("Job '{0}' is already running")]
+    AlreadyRunning(String),
+
+    #[error("Job '{0}' cannot run while the region is passive")]
+    RegionPassive(String),
+}
+
+/// How a scheduled run ended; recorded in `job_runs`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunOutcome {
+    Succeeded,
+    Failed(String),
+    /// Exceeded its timeout. `stopped` is false when the job ignored the
+    /// cancellation and was killed after the grace period.
+    TimedOut {
+        stopped: bool,
+    },
+}
+
+impl RunOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RunOutcome::Succeeded => "succeeded",
+            RunOutcome::Failed(_) => "failed",
+            RunOutcome::TimedOut { .. } => "timed_out",
+        }
+    }
+
+    fn error(&self) -> Option<String> {
+        match self {
+            RunOutcome::Succeeded => None,
+            RunOutcome::Failed(e) => Some(e.clone()),
+            RunOutcome::TimedOut { stopped: true } => {
+                Some("timed out; stopped after cancellation".to_string())
+            }
+            RunOutcome::TimedOut { stopped: false } => {
+                Some("timed out; killed after ignoring cancellation".to_string())
+            }
+        }
+    }
+}
+
+/// Run `job` once with `params`. After `limit` its token is cancelled; if it
+/// has not returned `grace` later its future is dropped.
+pub async fn run_with_timeout(
+    job: &dyn Job,
+    params: &RunParams,
+    limit: std::time::Duration,
+    grace: std::time::Duration,
+) -> RunOutcome {
+    let cancel = CancellationToken::new();
+    let run = job.execute_with(&cancel, params);
+    tokio::pin!(run);
+    match tokio::time::timeout(limit, &mut run).await {
+        Ok(Ok(())) => RunOutcome::Succeeded,
+        Ok(Err(e)) => RunOutcome::Failed(e.to_string()),
+        Err(_) => {
+            cancel.cancel();
+            let stopped = tokio::time::timeout(grace, &mut run).await.is_ok();
+            RunOutcome::TimedOut { stopped }
+        }
+    }
+}
+
+/// A registered job with its effective schedule and settings.
+#[derive(Clone)]
+struct ScheduledJob {
+    job: Arc<dyn Job>,
+    schedule: String,
+    settings: JobSettings,
+    timeout: std::time::Duration,
+    /// One permit per allowed concurrent run.
+    slots: Arc<Semaphore>,
+    /// Runs in progress, scheduled or manual.
+    running: Arc<AtomicUsize>,
+}
+
+/// Counts a run as in progress for as long as it is alive.
+struct RunningGuard(Arc<AtomicUsize>);
+
+impl RunningGuard {
+    fn new(running: &Arc<AtomicUsize>) -> Self {
+        running.fetch_add(1, Ordering::SeqCst);
+        Self(Arc::clone(running))
+    }
+}
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
 }
 
 /// A job scheduler that manages cron-based recurring tasks
 pub struct JobScheduler {
-    jobs: Arc<Mutex<HashMap<String, Arc<dyn Job>>>>,
+    jobs: Arc<Mutex<HashMap<String, ScheduledJob>>>,
+    config: JobsConfig,
+    pool: Option<PgPool>,
     active_handles: Arc<Mutex<HashMap<String, tokio::task::JoinHandle<()>>>>,
     shutdown_tx: tokio::sync::broadcast::Sender<()>,
 }
@@ -36,27 +220,67 @@ impl Default for JobScheduler {
 impl JobScheduler {
     /// Create a new job scheduler instance
     pub fn new() -> Self {
+        Self::with_config(JobsConfig::default())
+    }
+
+    /// Create a scheduler that applies `config` to the jobs registered with it
+    pub fn with_config(config: JobsConfig) -> Self {
         let (shutdown_tx, _) = tokio::sync::broadcast::channel(1);
         Self {
             jobs: Arc::new(Mutex::new(HashMap::new())),
+            config,
+            pool: None,
             active_handles: Arc::new(Mutex::new(HashMap::new())),
             shutdown_tx,
         }
     }
 
-    /// Register a new job with the scheduler
+    /// Record the outcome of every run in `job_runs`
+    pub fn with_pool(mut self, pool: PgPool) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    /// Register a new job with the scheduler. Jobs disabled by configuration
+    /// are skipped.
     pub async fn register_job(
         &self,
-        job: Box<dyn Job>,
+        mut job: Box<dyn Job>,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let name = job.name().to_string();
+        let settings = self.config.get(&name);
+        if !settings.enabled {
+            info!(
+                "Job '{}' is disabled by configuration, not scheduling it",
+                name
+            );
+            return Ok(());
+        }
+
+        let schedule = settings
+            .schedule
+            .clone()
+            .unwrap_or_else(|| job.schedule().to_string());
 
         // Validate the cron expression
-        Schedule::from_str(job.schedule())
-            .map_err(|e| format!("Invalid cron expression '{}': {}", job.schedule(), e))?;
+        Schedule::from_str(&schedule)
+            .map_err(|e| format!("Invalid cron expression '{}': {}", schedule, e))?;
+
+        job.configure(&settings);
+        let timeout = settings.timeout.unwrap_or_else(|| job.timeout());
 
         let mut jobs = self.jobs.lock().await;
-        jobs.insert(name, Arc::from(job));
+        jobs.insert(
+            name,
+            ScheduledJob {
+                job: Arc::from(job),
+                schedule,
+                slots: Arc::new(Semaphore::new(settings.concurrency)),
+                settings,
+                timeout,
+                running: Arc::new(AtomicUsize::new(0)),
+            },
+        );
         Ok(())
     }
 
@@ -65,16 +289,15 @@ impl JobScheduler {
         let jobs = self.jobs.lock().await;
         let active_handles = self.active_handles.clone();
 
-        for (name, job) in jobs.iter() {
-            let job_clone = Arc::clone(job);
+        for (name, scheduled) in jobs.iter() {
             let name_clone = name.clone();
             let shutdown_rx = self.shutdown_tx.subscribe();
             let active_handles_clone = Arc::clone(&active_handles);
 
             let handle = tokio::spawn(Self::run_job_loop(
                 name_clone,
-                job_clone,
-                self.shutdown_tx.clone(),
+                scheduled.clone(),
+                self.pool.clone(),
                 shutdown_rx,
                 active_handles_clone,
             ));
@@ -110,21 +333,144 @@ impl JobScheduler {
         Ok(())
     }
 
+    /// Run a registered job now, outside its schedule. Refuses while a run of
+    /// the job is in progress unless `force` is set. Returns the `job_runs`
+    /// id when runs are recorded.
+    pub async fn trigger(
+        &self,
+        name: &str,
+        params: RunParams,
+        force: bool,
+    ) -> Result<Option<i64>, TriggerError> {
+        if !region::is_active() {
+            return Err(TriggerError::RegionPassive(name.to_string()));
+        }
+        // Check and claim under the lock so concurrent triggers cannot both pass.
+        let (scheduled, running) = {
+            let jobs = self.jobs.lock().await;
+            let scheduled = jobs
+                .get(name)
+                .cloned()
+                .ok_or_else(|| TriggerError::UnknownJob(name.to_string()))?;
+            if !force && scheduled.running.load(Ordering::SeqCst) > 0 {
+                return Err(TriggerError::AlreadyRunning(name.to_string()));
+            }
+            let running = RunningGuard::new(&scheduled.running);
+            (scheduled, running)
+        };
+        // A forced run goes ahead even when every slot is taken.
+        let slot = Arc::clone(&scheduled.slots).try_acquire_owned().ok();
+        info!(job = %name, ?params, force, "Manually triggering job");
+        Ok(Self::launch(
+            name,
+            &scheduled,
+            self.pool.as_ref(),
+            running,
+            slot,
+            params,
+            "manual",
+        )
+        .await)
+    }
+
+    /// Record the start of a run, then execute it in the background. Returns
+    /// the `job_runs` id when runs are recorded.
+    async fn launch(
+        name: &str,
+        scheduled: &ScheduledJob,
+        pool: Option<&PgPool>,
+        running: RunningGuard,
+        slot: Option<OwnedSemaphorePermit>,
+        params: RunParams,
+        trigger: &'static str,
+    ) -> Option<i64> {
+        let started_at = Utc::now();
+        let run_id = match pool {
+            Some(pool) => {
+                let recorded_params = (params != RunParams::default())
+                    .then(|| serde_json::to_value(&params).ok())
+                    .flatten();
+                match crate::db::queries::start_job_run(
+                    pool,
+                    name,
+                    trigger,
+                    recorded_params.as_ref(),
+                    started_at,
+                )
+                .await
+                {
+                    Ok(id) => Some(id),
+                    Err(e) => {
+                        warn!("Failed to record run of job '{}': {}", name, e);
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+
+        let job = Arc::clone(&scheduled.job);
+        let timeout = scheduled.timeout;
+        let name = name.to_string();
+        let pool = pool.cloned();
+        tokio::spawn(async move {
+            let _slot = slot;
+            let _running = running;
+            let outcome = run_with_timeout(job.as_ref(), &params, timeout, CANCEL_GRACE).await;
+            let run_at = started_at.format("%Y-%m-%d %H:%M:%S");
+            match &outcome {
+                RunOutcome::Succeeded => {
+                    info!("Job '{}' executed successfully at {}", name, run_at);
+                }
+                RunOutcome::Failed(e) => {
+                    error!("Job '{}' failed at {}: {}", name, run_at, e);
+                }
+                RunOutcome::TimedOut { stopped } => {
+                    crate::metrics::scheduled_job_timeout_total()
+                        .add(1, &[opentelemetry::KeyValue::new("job", name.clone())]);
+                    error!(
+                        job = %name,
+                        timeout_secs = timeout.as_secs(),
+                        stopped,
+                        "Scheduled job run at {} exceeded its timeout and was cancelled",
+                        run_at
+                    );
+                }
+            }
+
+            if let (Some(pool), Some(id)) = (pool, run_id) {
+                if let Err(e) = crate::db::queries::finish_job_run(
+                    &pool,
+                    id,
+                    outcome.as_str(),
+                    outcome.error().as_deref(),
+                    Utc::now(),
+                )
+                .await
+                {
+                    warn!("Failed to record outcome of job '{}': {}", name, e);
+                }
+            }
+        });
+
+        run_id
+    }
+
     /// Get status information about all registered jobs
     pub async fn get_job_status(&self) -> HashMap<String, JobStatus> {
         let jobs = self.jobs.lock().await;
         let active_handles = self.active_handles.lock().await;
         let mut status = HashMap::new();
 
-        for (name, job) in jobs.iter() {
+        for (name, scheduled) in jobs.iter() {
             // Parse the schedule to get the next run time
-            let next_run = Self::get_next_run_time(job.schedule());
+            let next_run = Self::get_next_run_time(&scheduled.schedule);
 
             status.insert(
                 name.clone(),
                 JobStatus {
                     name: name.clone(),
-                    schedule: job.schedule().to_string(),
+                    schedule: scheduled.schedule.clone(),
                     next_run,
                     is_active: active_handles.contains_key(name),
                 },
@@ -134,17 +480,22 @@ impl JobScheduler {
         status
     }
 
-    /// Internal function that runs the job execution loop
+    /// Internal function that runs the job execution loop. Each run is
+    /// spawned; a tick is skipped while `concurrency` runs are in flight.
     async fn run_job_loop(
         name: String,
-        job: Arc<dyn Job>,
-        _shutdown_tx: tokio::sync::broadcast::Sender<()>,
+        scheduled: ScheduledJob,
+        pool: Option<PgPool>,
         mut shutdown_rx: tokio::sync::broadcast::Receiver<()>,
         active_handles: Arc<Mutex<HashMap<String, tokio::task::JoinHandle<()>>>>,
     ) {
-        info!("Starting job '{}' with schedule: {}", name, job.schedule());
+        let concurrency = scheduled.settings.concurrency;
+        info!(
+            "Starting job '{}' with schedule: {}",
+            name, scheduled.schedule
+        );
 
-        let schedule = match Schedule::from_str(job.schedule()) {
+        let schedule = match Schedule::from_str(&scheduled.schedule) {
             Ok(schedule) => schedule,
             Err(e) => {
                 error!("Failed to parse cron schedule for job '{}': {}", name, e);
@@ -169,6 +520,8 @@ impl JobScheduler {
                         },
                         _ = shutdown_rx.recv() => {
                             info!("Job '{}' received shutdown signal", name);
+                            // Let in-flight runs finish
+                            let _ = scheduled.slots.acquire_many(concurrency as u32).await;
                             // Remove handle from active handles
                             let _ = active_handles.lock().await.remove(&name);
                             return;
@@ -182,24 +535,36 @@ impl JobScheduler {
                 }
             };
 
-            // Execute the job
-            match job.execute().await {
-                Ok(()) => {
-                    info!(
-                        "Job '{}' executed successfully at {}",
-                        name,
-                        next_run_time.format("%Y-%m-%d %H:%M:%S")
-                    );
-                }
-                Err(e) => {
-                    error!(
-                        "Job '{}' failed at {}: {}",
-                        name,
-                        next_run_time.format("%Y-%m-%d %H:%M:%S"),
-                        e
-                    );
-                }
+            if !region::is_active() {
+                info!(
+                    "Job '{}' skipped run at {}: region is passive",
+                    name,
+                    next_run_time.format("%Y-%m-%d %H:%M:%S")
+                );
+                continue;
             }
+
+            let Ok(slot) = Arc::clone(&scheduled.slots).try_acquire_owned() else {
+                warn!(
+                    "Job '{}' skipped run at {}: {} run(s) still in progress",
+                    name,
+                    next_run_time.format("%Y-%m-%d %H:%M:%S"),
+                    concurrency
+                );
+                continue;
+            };
+
+            // Execute the job
+            Self::launch(
+                &name,
+                &scheduled,
+                pool.as_ref(),
+                RunningGuard::new(&scheduled.running),
+                Some(slot),
+                RunParams::default(),
+                "schedule",
+            )
+            .await;
         }
     }
 
@@ -260,7 +625,10 @@ impl Job for AuditLogRetentionJob {
         "0 0 2 1 * * *"
     }
 
-    async fn execute(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    async fn execute(
+        &self,
+        _cancel: &CancellationToken,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let days = crate::db::audit::retention_days();
         let cutoff = Utc::now() - Duration::days(days);
         let archive_dir = Self::archive_dir();
@@ -293,6 +661,42 @@ impl Job for AuditLogRetentionJob {
     }
 }
 
+/// Prunes `webhook_inbox` rows that have fallen outside the dedup window.
+pub struct WebhookInboxRetentionJob {
+    pool: sqlx::PgPool,
+}
+
+impl WebhookInboxRetentionJob {
+    pub fn new(pool: sqlx::PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl Job for WebhookInboxRetentionJob {
+    fn name(&self) -> &str {
+        "webhook_inbox_retention"
+    }
+
+    /// Run daily at 03:00 UTC.
+    fn schedule(&self) -> &str {
+        "0 0 3 * * * *"
+    }
+
+    async fn execute(
+        &self,
+        _cancel: &CancellationToken,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let Some(window) = crate::services::webhook_dedup::DedupConfig::from_env().window else {
+            return Ok(());
+        };
+        let cutoff = Utc::now() - Duration::from_std(window)?;
+        let deleted = crate::db::queries::prune_webhook_inbox(&self.pool, cutoff).await?;
+        info!(deleted, cutoff = %cutoff.to_rfc3339(), "Webhook inbox retention complete");
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -322,7 +726,10 @@ mod tests {
             &self.schedule
         }
 
-        async fn execute(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        async fn execute(
+            &self,
+            _cancel: &CancellationToken,
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
             println!("Executing test job: {}", self.name);
             Ok(())
         }
@@ -337,4 +744,229 @@ mod tests {
 
         assert_eq!(scheduler.jobs.lock().await.len(), 1);
     }
+
+    struct BatchJob {
+        batch_size: u32,
+        seen: Arc<std::sync::Mutex<Option<u32>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Job for BatchJob {
+        fn name(&self) -> &str {
+            "batch_job"
+        }
+
+        fn schedule(&self) -> &str {
+            "0 * * * * *"
+        }
+
+        fn configure(&mut self, settings: &JobSettings) {
+            if let Some(size) = settings.batch_size {
+                self.batch_size = size;
+            }
+            *self.seen.lock().unwrap() = Some(self.batch_size);
+        }
+
+        async fn execute(
+            &self,
+            _cancel: &CancellationToken,
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_disabled_job_is_not_registered() {
+        let config = JobsConfig::default().with_job(
+            "test_job",
+            JobSettings {
+                enabled: false,
+                ..JobSettings::default()
+            },
+        );
+        let scheduler = JobScheduler::with_config(config);
+
+        scheduler
+            .register_job(Box::new(TestJob::new("test_job", "*/1 * * * * *")))
+            .await
+            .unwrap();
+        scheduler
+            .register_job(Box::new(TestJob::new("other_job", "*/1 * * * * *")))
+            .await
+            .unwrap();
+
+        let status = scheduler.get_job_status().await;
+        assert!(!status.contains_key("test_job"));
+        assert!(status.contains_key("other_job"));
+    }
+
+    #[tokio::test]
+    async fn test_configured_schedule_and_batch_size_are_applied() {
+        let config = JobsConfig::default().with_job(
+            "batch_job",
+            JobSettings {
+                schedule: Some("0 */5 * * * *".to_string()),
+                batch_size: Some(25),
+                ..JobSettings::default()
+            },
+        );
+        let scheduler = JobScheduler::with_config(config);
+        let seen = Arc::new(std::sync::Mutex::new(None));
+
+        scheduler
+            .register_job(Box::new(BatchJob {
+                batch_size: 10,
+                seen: Arc::clone(&seen),
+            }))
+            .await
+            .unwrap();
+
+        assert_eq!(*seen.lock().unwrap(), Some(25));
+        let status = scheduler.get_job_status().await;
+        assert_eq!(status["batch_job"].schedule, "0 */5 * * * *");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_schedule_override_is_rejected() {
+        let config = JobsConfig::default().with_job(
+            "test_job",
+            JobSettings {
+                schedule: Some("not a cron".to_string()),
+                ..JobSettings::default()
+            },
+        );
+        let scheduler = JobScheduler::with_config(config);
+
+        assert!(scheduler
+            .register_job(Box::new(TestJob::new("test_job", "*/1 * * * * *")))
+            .await
+            .is_err());
+    }
+
+    enum Behaviour {
+        /// Waits until cancelled, then returns.
+        Cooperative,
+        /// Never returns.
+        Stubborn,
+        Failing,
+    }
+
+    struct SlowJob(Behaviour);
+
+    #[async_trait::async_trait]
+    impl Job for SlowJob {
+        fn name(&self) -> &str {
+            "slow_job"
+        }
+
+        fn schedule(&self) -> &str {
+            "0 * * * * *"
+        }
+
+        async fn execute(
+            &self,
+            cancel: &CancellationToken,
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            match self.0 {
+                Behaviour::Cooperative => {
+                    cancel.cancelled().await;
+                    Ok(())
+                }
+                Behaviour::Stubborn => {
+                    std::future::pending::<()>().await;
+                    Ok(())
+                }
+                Behaviour::Failing => Err("boom".into()),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_with_timeout_outcomes() {
+        let limit = std::time::Duration::from_millis(20);
+        let grace = std::time::Duration::from_millis(20);
+        let params = RunParams::default();
+
+        assert_eq!(
+            run_with_timeout(&TestJob::new("t", "* * * * * *"), &params, limit, grace).await,
+            RunOutcome::Succeeded
+        );
+        assert_eq!(
+            run_with_timeout(&SlowJob(Behaviour::Failing), &params, limit, grace).await,
+            RunOutcome::Failed("boom".to_string())
+        );
+        assert_eq!(
+            run_with_timeout(&SlowJob(Behaviour::Cooperative), &params, limit, grace).await,
+            RunOutcome::TimedOut { stopped: true }
+        );
+        assert_eq!(
+            run_with_timeout(&SlowJob(Behaviour::Stubborn), &params, limit, grace).await,
+            RunOutcome::TimedOut { stopped: false }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_configured_timeout_overrides_job_default() {
+        let config = JobsConfig::default().with_job(
+            "slow_job",
+            JobSettings {
+                timeout: Some(std::time::Duration::from_secs(5)),
+                ..JobSettings::default()
+            },
+        );
+        let scheduler = JobScheduler::with_config(config);
+        scheduler
+            .register_job(Box::new(SlowJob(Behaviour::Cooperative)))
+            .await
+            .unwrap();
+        scheduler
+            .register_job(Box::new(TestJob::new("test_job", "* * * * * *")))
+            .await
+            .unwrap();
+
+        let jobs = scheduler.jobs.lock().await;
+        assert_eq!(jobs["slow_job"].timeout, std::time::Duration::from_secs(5));
+        assert_eq!(jobs["test_job"].timeout, DEFAULT_JOB_TIMEOUT);
+    }
+
+    #[tokio::test]
+    async fn test_trigger_refuses_running_job_unless_forced() {
+        let scheduler = JobScheduler::new();
+        scheduler
+            .register_job(Box::new(SlowJob(Behaviour::Cooperative)))
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            scheduler
+                .trigger("missing", RunParams::default(), false)
+                .await,
+            Err(TriggerError::UnknownJob(_))
+        ));
+
+        // Without a pool nothing is recorded, so there is no run id.
+        assert_eq!(
+            scheduler
+                .trigger("slow_job", RunParams::default(), false)
+                .await
+                .unwrap(),
+            None
+        );
+        assert!(matches!(
+            scheduler
+                .trigger("slow_job", RunParams::default(), false)
+                .await,
+            Err(TriggerError::AlreadyRunning(_))
+        ));
+        assert!(scheduler
+            .trigger("slow_job", RunParams::default(), true)
+            .await
+            .is_ok());
+        assert_eq!(
+            scheduler.jobs.lock().await["slow_job"]
+                .running
+                .load(Ordering::SeqCst),
+            2
+        );
+    }
 }
@@ -0,0 +1,478 @@
+//! Deterministic demo and test data (`synapse-core seed`).
+//!
+//! [`generate`] builds a dataset from a seed: partners (tenants),
+//! transactions spread across statuses, assets and partners, daily
+//! settlements of the completed ones, DLQ entries for the `dlq` rows and the
+//! audit trail of every status change. The same [`SeedOptions`] always give
+//! the same dataset, ids included, so demos, load tests and UI work can share
+//! fixtures by quoting a seed. [`insert`] writes one dataset in a single
+//! database transaction.
+//!
+//! Seeding the same options twice is refused rather than duplicated; use a
+//! different seed for a second dataset in the same database.
+
+use crate::db::audit::{ENTITY_SETTLEMENT, ENTITY_TRANSACTION};
+use crate::db::partition;
+use crate::domain::StellarAddress;
+use crate::services::dlq_errors;
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_json::json;
+use sqlx::PgPool;
+use std::collections::{BTreeMap, BTreeSet};
+use uuid::Uuid;
+
+const ACTOR: &str = "seed";
+
+/// (status, weight). Most deposits complete; the rest cover every other
+/// state a dashboard has to render.
+const STATUSES: [(&str, u32); 7] = [
+    ("completed", 60),
+    ("pending", 12),
+    ("processing", 5),
+    ("failed", 8),
+    ("dlq", 5),
+    ("refund_pending", 5),
+    ("compliance_review", 5),
+];
+
+const ASSETS: [(&str, u32); 4] = [("USDC", 50), ("EURC", 20), ("XLM", 20), ("NGNC", 10)];
+
+const PARTNER_NAMES: [&str; 8] = [
+    "Acme Remit",
+    "Bluefin Pay",
+    "Cedar Exchange",
+    "Delta Wallet",
+    "Ember Money",
+    "Fjord Transfers",
+    "Granite Fintech",
+    "Harbor Cash",
+];
+
+const DLQ_REASONS: [&str; 5] = [
+    "Horizon unavailable: connection timed out",
+    "Transaction failed on-chain: op_underfunded",
+    "Memo mismatch between callback and payment",
+    "Asset issuer is not trusted",
+    "Database error: pool timed out",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeedOptions {
+    pub seed: u64,
+    pub transactions: usize,
+    pub partners: usize,
+    /// Transactions are created over this many days, ending with `until`.
+    pub days: u32,
+    pub until: NaiveDate,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeedPartner {
+    pub tenant_id: Uuid,
+    pub name: String,
+    pub api_key: String,
+    pub stellar_account: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeedTransaction {
+    pub id: Uuid,
+    pub tenant_id: Option<Uuid>,
+    pub stellar_account: String,
+    pub amount: BigDecimal,
+    pub asset_code: &'static str,
+    pub status: &'static str,
+    pub anchor_transaction_id: String,
+    pub settlement_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeedSettlement {
+    pub id: Uuid,
+    pub asset_code: &'static str,
+    pub total_amount: BigDecimal,
+    pub tx_count: i32,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeedDlqEntry {
+    pub id: Uuid,
+    pub transaction_id: Uuid,
+    pub reason: &'static str,
+    pub retry_count: i32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeedAuditEntry {
+    pub id: Uuid,
+    pub entity_id: Uuid,
+    pub entity_type: &'static str,
+    pub action: &'static str,
+    pub old_val: Option<serde_json::Value>,
+    pub new_val: serde_json::Value,
+    pub at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SeedDataset {
+    pub partners: Vec<SeedPartner>,
+    pub transactions: Vec<SeedTransaction>,
+    pub settlements: Vec<SeedSettlement>,
+    pub dlq: Vec<SeedDlqEntry>,
+    pub audit: Vec<SeedAuditEntry>,
+}
+
+/// Row counts of a dataset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SeedSummary {
+    pub partners: usize,
+    pub transactions: usize,
+    pub settlements: usize,
+    pub dlq: usize,
+    pub audit: usize,
+}
+
+impl SeedDataset {
+    pub fn summary(&self) -> SeedSummary {
+        SeedSummary {
+            partners: self.partners.len(),
+            transactions: self.transactions.len(),
+            settlements: self.settlements.len(),
+            dlq: self.dlq.len(),
+            audit: self.audit.len(),
+        }
+    }
+}
+
+fn uuid(rng: &mut StdRng) -> Uuid {
+    uuid::Builder::from_random_bytes(rng.gen()).into_uuid()
+}
+
+fn weighted<T: Copy>(rng: &mut StdRng, choices: &[(T, u32)]) -> T {
+    let total: u32 = choices.iter().map(|(_, w)| w).sum();
+    let mut pick = rng.gen_range(0..total);
+    for (choice, weight) in choices {
+        if pick < *weight {
+            return *choice;
+        }
+        pick -= weight;
+    }
+    choices[choices.len() - 1].0
+}
+
+/// Log-uniform between 1 and 50,000, in cents, so small deposits dominate
+/// but every amount bucket is populated.
+fn amount(rng: &mut StdRng) -> BigDecimal {
+    let units = 10f64.powf(rng.gen_range(0.0..4.7));
+    BigDecimal::new(((units * 100.0).round() as i64).into(), 2)
+}
+
+fn day_start(day: NaiveDate) -> DateTime<Utc> {
+    day.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc()
+}
+
+/// Build the dataset for `options`; no I/O.
+pub fn generate(options: &SeedOptions) -> SeedDataset {
+    let mut rng = StdRng::seed_from_u64(options.seed);
+    let mut data = SeedDataset::default();
+
+    for n in 0..options.partners {
+        let base = PARTNER_NAMES[n % PARTNER_NAMES.len()];
+        let name = match n / PARTNER_NAMES.len() {
+            0 => base.to_string(),
+            round => format!("{base} {}", round + 1),
+        };
+        data.partners.push(SeedPartner {
+            tenant_id: uuid(&mut rng),
+            name,
+            api_key: format!("seed_{}_{n}", options.seed),
+            stellar_account: StellarAddress::from_ed25519(rng.gen()).to_string(),
+        });
+    }
+
+    // A pool of senders, so customers repeat across deposits.
+    let senders: Vec<String> = (0..(options.transactions / 4).max(1))
+        .map(|_| StellarAddress::from_ed25519(rng.gen()).to_string())
+        .collect();
+    let window_end = day_start(options.until) + Duration::days(1);
+    let window_secs = i64::from(options.days.max(1)) * 86_400;
+
+    for n in 0..options.transactions {
+        let created_at = window_end - Duration::seconds(rng.gen_range(1..=window_secs));
+        let status = weighted(&mut rng, &STATUSES);
+        let updated_at = match status {
+            "pending" => created_at,
+            _ => created_at + Duration::seconds(rng.gen_range(5..3_600)),
+        };
+        // One in ten deposits arrives without a partner (admin-submitted).
+        let tenant_id = match data.partners.len() {
+            0 => None,
+            len if rng.gen_ratio(9, 10) => Some(data.partners[rng.gen_range(0..len)].tenant_id),
+            _ => None,
+        };
+        data.transactions.push(SeedTransaction {
+            id: uuid(&mut rng),
+            tenant_id,
+            stellar_account: senders[rng.gen_range(0..senders.len())].clone(),
+            amount: amount(&mut rng),
+            asset_code: weighted(&mut rng, &ASSETS),
+            status,
+            anchor_transaction_id: format!("seed-{}-{n}", options.seed),
+            settlement_id: None,
+            created_at,
+            updated_at,
+        });
+    }
+    data.transactions.sort_by_key(|tx| (tx.created_at, tx.id));
+
+    // Daily settlements per asset of completed deposits, except today's,
+    // which are still open.
+    let mut groups: BTreeMap<(NaiveDate, &'static str), Vec<usize>> = BTreeMap::new();
+    for (i, tx) in data.transactions.iter().enumerate() {
+        let day = tx.created_at.date_naive();
+        if tx.status == "completed" && day < options.until {
+            groups.entry((day, tx.asset_code)).or_default().push(i);
+        }
+    }
+    for ((day, asset_code), members) in groups {
+        let id = uuid(&mut rng);
+        let mut total = BigDecimal::from(0);
+        for &i in &members {
+            data.transactions[i].settlement_id = Some(id);
+            total += &data.transactions[i].amount;
+        }
+        data.settlements.push(SeedSettlement {
+            id,
+            asset_code,
+            total_amount: total,
+            tx_count: members.len() as i32,
+            period_start: day_start(day),
+            period_end: day_start(day) + Duration::days(1),
+        });
+    }
+
+    for tx in &data.transactions {
+        if tx.status == "dlq" {
+            data.dlq.push(SeedDlqEntry {
+                id: uuid(&mut rng),
+                transaction_id: tx.id,
+                reason: DLQ_REASONS[rng.gen_range(0..DLQ_REASONS.len())],
+                retry_count: rng.gen_range(1..=5),
+            });
+        }
+        if tx.status != "pending" {
+            data.audit.push(SeedAuditEntry {
+                id: uuid(&mut rng),
+                entity_id: tx.id,
+                entity_type: ENTITY_TRANSACTION,
+                action: "status_update",
+                old_val: Some(json!({ "status": "pending" })),
+                new_val: json!({ "status": tx.status }),
+                at: tx.updated_at,
+            });
+        }
+    }
+    for settlement in &data.settlements {
+        data.audit.push(SeedAuditEntry {
+            id: uuid(&mut rng),
+            entity_id: settlement.id,
+            entity_type: ENTITY_SETTLEMENT,
+            action: "created",
+            old_val: None,
+            new_val: json!({
+                "asset_code": settlement.asset_code,
+                "total_amount": settlement.total_amount.to_string(),
+                "tx_count": settlement.tx_count,
+            }),
+            at: settlement.period_end,
+        });
+    }
+
+    data
+}
+
+/// Write `data` in one database transaction, creating any missing monthly
+/// partitions first.
+pub async fn insert(pool: &PgPool, data: &SeedDataset) -> anyhow::Result<SeedSummary> {
+    if let Some(first) = data.transactions.first() {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM transactions WHERE id = $1 AND created_at = $2)",
+        )
+        .bind(first.id)
+        .bind(first.created_at)
+        .fetch_one(pool)
+        .await?;
+        if exists {
+            anyhow::bail!("This dataset is already seeded; pick another --seed");
+        }
+    }
+
+    let months: BTreeSet<NaiveDate> = data
+        .transactions
+        .iter()
+        .filter_map(|tx| tx.created_at.date_naive().with_day(1))
+        .collect();
+    for month in months {
+        partition::ensure_month_partition(pool, month).await?;
+    }
+
+    let mut db_tx = pool.begin().await?;
+    for partner in &data.partners {
+        sqlx::query(
+            "INSERT INTO tenants (tenant_id, name, api_key, webhook_secret, stellar_account, \
+             rate_limit_per_minute, is_active) VALUES ($1, $2, $3, '', $4, 600, true) \
+             ON CONFLICT (tenant_id) DO NOTHING",
+        )
+        .bind(partner.tenant_id)
+        .bind(&partner.name)
+        .bind(&partner.api_key)
+        .bind(&partner.stellar_account)
+        .execute(&mut *db_tx)
+        .await?;
+    }
+    // Settlements first: transactions reference them.
+    for settlement in &data.settlements {
+        sqlx::query(
+            "INSERT INTO settlements (id, asset_code, total_amount, tx_count, period_start, \
+             period_end, status, created_at, updated_at) \
+             VALUES ($1, $2, $3, $4, $5, $6, 'completed', $6, $6)",
+        )
+        .bind(settlement.id)
+        .bind(settlement.asset_code)
+        .bind(&settlement.total_amount)
+        .bind(settlement.tx_count)
+        .bind(settlement.period_start)
+        .bind(settlement.period_end)
+        .execute(&mut *db_tx)
+        .await?;
+    }
+    for tx in &data.transactions {
+        sqlx::query(
+            "INSERT INTO transactions (id, tenant_id, stellar_account, amount, asset_code, status, \
+             anchor_transaction_id, callback_type, settlement_id, created_at, updated_at) \
+             VALUES ($1, $2, $3, $4, $5, $6::transaction_status, $7, 'deposit', $8, $9, $10)",
+        )
+        .bind(tx.id)
+        .bind(tx.tenant_id)
+        .bind(&tx.stellar_account)
+        .bind(&tx.amount)
+        .bind(tx.asset_code)
+        .bind(tx.status)
+        .bind(&tx.anchor_transaction_id)
+        .bind(tx.settlement_id)
+        .bind(tx.created_at)
+        .bind(tx.updated_at)
+        .execute(&mut *db_tx)
+        .await?;
+    }
+    let by_id: BTreeMap<Uuid, &SeedTransaction> =
+        data.transactions.iter().map(|tx| (tx.id, tx)).collect();
+    for entry in &data.dlq {
+        let tx = by_id[&entry.transaction_id];
+        sqlx::query(
+            "INSERT INTO transaction_dlq (id, transaction_id, stellar_account, amount, asset_code, \
+             anchor_transaction_id, error_reason, error_class, error_signature, retry_count, \
+             original_created_at, moved_to_dlq_at, last_retry_at) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $12)",
+        )
+        .bind(entry.id)
+        .bind(tx.id)
+        .bind(&tx.stellar_account)
+        .bind(&tx.amount)
+        .bind(tx.asset_code)
+        .bind(&tx.anchor_transaction_id)
+        .bind(entry.reason)
+        .bind(dlq_errors::classify(entry.reason).as_str())
+        .bind(dlq_errors::signature(entry.reason))
+        .bind(entry.retry_count)
+        .bind(tx.created_at)
+        .bind(tx.updated_at)
+        .execute(&mut *db_tx)
+        .await?;
+    }
+    for entry in &data.audit {
+        sqlx::query(
+            "INSERT INTO audit_logs (id, entity_id, entity_type, action, old_val, new_val, actor, \
+             timestamp, created_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $8)",
+        )
+        .bind(entry.id)
+        .bind(entry.entity_id)
+        .bind(entry.entity_type)
+        .bind(entry.action)
+        .bind(&entry.old_val)
+        .bind(&entry.new_val)
+        .bind(ACTOR)
+        .bind(entry.at)
+        .execute(&mut *db_tx)
+        .await?;
+    }
+    db_tx.commit().await?;
+
+    Ok(data.summary())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(seed: u64) -> SeedOptions {
+        SeedOptions {
+            seed,
+            transactions: 500,
+            partners: 3,
+            days: 30,
+            until: NaiveDate::from_ymd_opt(2026, 10, 16).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_same_seed_same_dataset() {
+        let data = generate(&options(42));
+        assert_eq!(data, generate(&options(42)));
+        assert_ne!(data.transactions, generate(&options(43)).transactions);
+
+        assert_eq!(data.partners.len(), 3);
+        assert_eq!(data.transactions.len(), 500);
+        let until = day_start(options(42).until) + Duration::days(1);
+        assert!(data
+            .transactions
+            .iter()
+            .all(|tx| tx.created_at < until && tx.created_at >= until - Duration::days(30)));
+        assert!(data
+            .transactions
+            .iter()
+            .all(|tx| StellarAddress::parse(&tx.stellar_account).is_ok()));
+    }
+
+    #[test]
+    fn test_dataset_is_internally_consistent() {
+        let data = generate(&options(7));
+        for (status, _) in STATUSES {
+            assert!(
+                data.transactions.iter().any(|tx| tx.status == status),
+                "{status}"
+            );
+        }
+        let dlq_rows = data.transactions.iter().filter(|tx| tx.status == "dlq");
+        assert_eq!(dlq_rows.count(), data.dlq.len());
+
+        for settlement in &data.settlements {
+            let members: Vec<_> = data
+                .transactions
+                .iter()
+                .filter(|tx| tx.settlement_id == Some(settlement.id))
+                .collect();
+            assert_eq!(members.len() as i32, settlement.tx_count);
+            let total: BigDecimal = members.iter().map(|tx| &tx.amount).sum();
+            assert_eq!(total, settlement.total_amount);
+            assert!(members.iter().all(|tx| tx.status == "completed"));
+        }
+    }
+}
@@ -0,0 +1,525 @@
+//! SEP-10 web authentication: proving control of a Stellar account.
+//!
+//! 1. `GET /auth?account=G...` returns a challenge: a transaction from the
+//!    server account with sequence number 0, valid for
+//!    `SEP10_CHALLENGE_TTL_SECS`, holding a `"<home domain> auth"`
+//!    `MANAGE_DATA` operation sourced by the client with a random 48-byte
+//!    nonce, and a `web_auth_domain` operation sourced by the server. It is
+//!    signed by the server and can never be submitted to the network.
+//! 2. The client signs it and sends it back to `POST /auth`. If the server's
+//!    signature is intact, the challenge is still within its time bounds and
+//!    the client's signatures are enough, the answer is a JWT for the account.
+//!
+//! An account that exists on the network must be signed for by its own
+//! signers, reaching its medium threshold (at least weight 1); one that does
+//! not exist yet must be signed by its master key. Any signature that is
+//! neither the server's nor one of those signers' voids the challenge.
+//! Muxed (`M...`) accounts and client domain verification are not supported.
+//!
+//! | Env var                      | Default         | Meaning                           |
+//! |------------------------------|-----------------|-----------------------------------|
+//! | `SEP10_SIGNING_SEED`         | required        | `S...` seed of the server account |
+//! | `SEP10_HOME_DOMAIN`          | required        | Home domain named in challenges   |
+//! | `SEP10_WEB_AUTH_DOMAIN`      | the home domain | Domain serving `/auth`            |
+//! | `SEP10_JWT_SECRET`           | required        | HS256 key, 32+ characters         |
+//! | `SEP10_CHALLENGE_TTL_SECS`   | 900             | How long a challenge is valid     |
+//! | `SEP10_TOKEN_TTL_SECS`       | 86400           | How long a token is valid         |
+//! | `STELLAR_NETWORK_PASSPHRASE` | required        | Network the challenges are for    |
+
+use crate::domain::{decode_secret_seed, StellarAddress};
+use crate::error::AppError;
+use crate::stellar::xdr::{DecoratedSignature, ManageData, Memo, Transaction, TransactionEnvelope};
+use anyhow::{bail, Context};
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::fmt;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const WEB_AUTH_DOMAIN_KEY: &str = "web_auth_domain";
+const NONCE_BYTES: usize = 48;
+/// Base fee per operation, in stroops; the challenge is never submitted.
+const BASE_FEE: u32 = 100;
+const DEFAULT_CHALLENGE_TTL_SECS: i64 = 900;
+const DEFAULT_TOKEN_TTL_SECS: i64 = 86_400;
+const MIN_JWT_SECRET_LEN: usize = 32;
+
+pub struct Sep10Config {
+    seed: [u8; 32],
+    pub server_account: StellarAddress,
+    pub home_domain: String,
+    pub web_auth_domain: String,
+    pub network_passphrase: String,
+    jwt_secret: String,
+    pub challenge_ttl: Duration,
+    pub token_ttl: Duration,
+}
+
+impl fmt::Debug for Sep10Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sep10Config")
+            .field("server_account", &self.server_account.account())
+            .field("home_domain", &self.home_domain)
+            .field("web_auth_domain", &self.web_auth_domain)
+            .field("network_passphrase", &self.network_passphrase)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Sep10Config {
+    pub fn new(
+        seed: [u8; 32],
+        home_domain: impl Into<String>,
+        network_passphrase: impl Into<String>,
+        jwt_secret: impl Into<String>,
+    ) -> anyhow::Result<Self> {
+        let jwt_secret = jwt_secret.into();
+        if jwt_secret.len() < MIN_JWT_SECRET_LEN {
+            bail!("SEP10_JWT_SECRET must be at least {MIN_JWT_SECRET_LEN} characters");
+        }
+        let key = Ed25519KeyPair::from_seed_unchecked(&seed)
+            .map_err(|_| anyhow::anyhow!("SEP10_SIGNING_SEED is not a valid ed25519 seed"))?;
+        let mut public = [0u8; 32];
+        public.copy_from_slice(key.public_key().as_ref());
+        let home_domain = home_domain.into();
+        Ok(Self {
+            seed,
+            server_account: StellarAddress::from_ed25519(public),
+            web_auth_domain: home_domain.clone(),
+            home_domain,
+            network_passphrase: network_passphrase.into(),
+            jwt_secret,
+            challenge_ttl: Duration::seconds(DEFAULT_CHALLENGE_TTL_SECS),
+            token_ttl: Duration::seconds(DEFAULT_TOKEN_TTL_SECS),
+        })
+    }
+
+    pub fn with_web_auth_domain(mut self, domain: impl Into<String>) -> Self {
+        self.web_auth_domain = domain.into();
+        self
+    }
+
+    pub fn from_env() -> anyhow::Result<Self> {
+        let required = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+                .with_context(|| format!("{name} is not set"))
+        };
+        let secs = |name: &str, default: i64| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.trim().parse::<i64>().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(default)
+        };
+
+        let seed = decode_secret_seed(&required("SEP10_SIGNING_SEED")?)
+            .map_err(|_| anyhow::anyhow!("SEP10_SIGNING_SEED is not a valid secret seed"))?;
+        let home_domain = required("SEP10_HOME_DOMAIN")?;
+        let mut config = Self::new(
+            seed,
+            home_domain.trim(),
+            required("STELLAR_NETWORK_PASSPHRASE")?,
+            required("SEP10_JWT_SECRET")?,
+        )?;
+        if let Ok(domain) = required("SEP10_WEB_AUTH_DOMAIN") {
+            config = config.with_web_auth_domain(domain.trim());
+        }
+        config.challenge_ttl =
+            Duration::seconds(secs("SEP10_CHALLENGE_TTL_SECS", DEFAULT_CHALLENGE_TTL_SECS));
+        config.token_ttl = Duration::seconds(secs("SEP10_TOKEN_TTL_SECS", DEFAULT_TOKEN_TTL_SECS));
+        Ok(config)
+    }
+
+    fn key_pair(&self) -> Ed25519KeyPair {
+        Ed25519KeyPair::from_seed_unchecked(&self.seed).expect("seed checked in new")
+    }
+
+    fn auth_key(&self) -> String {
+        format!("{} auth", self.home_domain)
+    }
+
+    /// Ed25519 signature of `message` by the server account, for requests
+    /// the anchor signs with its `SIGNING_KEY` (such as SEP-31 callbacks).
+    pub fn sign_message(&self, message: &[u8]) -> Vec<u8> {
+        self.key_pair().sign(message).as_ref().to_vec()
+    }
+
+    fn sign(&self, tx: &Transaction) -> DecoratedSignature {
+        let hash = tx.hash(&self.network_passphrase);
+        DecoratedSignature {
+            hint: DecoratedSignature::hint_for(&self.server_account.public_key()),
+            signature: self.key_pair().sign(&hash).as_ref().to_vec(),
+        }
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> AppError {
+    AppError::BadRequest(format!("invalid challenge: {}", reason.into()))
+}
+
+/// Build a signed challenge for `client`, as base64 XDR. `home_domain`, when
+/// the client names one, must be ours.
+pub fn build_challenge(
+    config: &Sep10Config,
+    client: &StellarAddress,
+    home_domain: Option<&str>,
+    now: DateTime<Utc>,
+) -> Result<String, AppError> {
+    if client.is_muxed() {
+        return Err(AppError::BadRequest(
+            "muxed accounts are not supported".to_string(),
+        ));
+    }
+    if let Some(domain) = home_domain {
+        if !domain.eq_ignore_ascii_case(&config.home_domain) {
+            return Err(AppError::BadRequest(format!(
+                "home_domain must be {}",
+                config.home_domain
+            )));
+        }
+    }
+
+    let mut nonce = [0u8; NONCE_BYTES];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let min_time = now.timestamp().max(0) as u64;
+    let tx = Transaction {
+        source: config.server_account.public_key(),
+        fee: BASE_FEE * 2,
+        seq_num: 0,
+        time_bounds: Some((
+            min_time,
+            min_time + config.challenge_ttl.num_seconds() as u64,
+        )),
+        memo: Memo::None,
+        operations: vec![
+            ManageData {
+                source: Some(client.public_key()),
+                name: config.auth_key(),
+                value: Some(STANDARD.encode(nonce).into_bytes()),
+            }
+            .into(),
+            ManageData {
+                source: Some(config.server_account.public_key()),
+                name: WEB_AUTH_DOMAIN_KEY.to_string(),
+                value: Some(config.web_auth_domain.clone().into_bytes()),
+            }
+            .into(),
+        ],
+    };
+    let envelope = TransactionEnvelope {
+        signatures: vec![config.sign(&tx)],
+        tx,
+    };
+    Ok(STANDARD.encode(envelope.to_xdr()))
+}
+
+/// A signed challenge whose structure and server signature check out; the
+/// client's signatures are checked by [`Challenge::verify_signers`].
+#[derive(Debug, Clone)]
+pub struct Challenge {
+    pub client: StellarAddress,
+    envelope: TransactionEnvelope,
+    hash: [u8; 32],
+}
+
+/// Decode and check a challenge returned by a client.
+pub fn read_challenge(
+    config: &Sep10Config,
+    transaction: &str,
+    now: DateTime<Utc>,
+) -> Result<Challenge, AppError> {
+    let bytes = STANDARD
+        .decode(transaction.trim())
+        .map_err(|_| invalid("transaction is not base64"))?;
+    let envelope = TransactionEnvelope::from_xdr(&bytes).map_err(|e| invalid(e.to_string()))?;
+    let tx = &envelope.tx;
+    let server_key = config.server_account.public_key();
+
+    if tx.source != server_key {
+        return Err(invalid("source account is not the server account"));
+    }
+    if tx.seq_num != 0 {
+        return Err(invalid("sequence number must be 0"));
+    }
+    let (min_time, max_time) = tx.time_bounds.ok_or_else(|| invalid("no time bounds"))?;
+    let now_secs = now.timestamp().max(0) as u64;
+    if now_secs < min_time || max_time == 0 || now_secs > max_time {
+        return Err(invalid("challenge has expired"));
+    }
+
+    if tx.memo != Memo::None {
+        return Err(invalid("challenge must not have a memo"));
+    }
+    let operations = tx
+        .operations
+        .iter()
+        .map(|op| op.as_manage_data())
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| invalid("operations must all be manage_data"))?;
+    let (first, rest) = operations
+        .split_first()
+        .ok_or_else(|| invalid("no operations"))?;
+    let client_key = first
+        .source
+        .ok_or_else(|| invalid("first operation has no source account"))?;
+    if first.name != config.auth_key() {
+        return Err(invalid(format!(
+            "first operation must be '{}'",
+            config.auth_key()
+        )));
+    }
+    if first.value.as_ref().map(Vec::len) != Some(64) {
+        return Err(invalid("nonce must be 64 bytes"));
+    }
+    for op in rest {
+        if op.source != Some(server_key) {
+            return Err(invalid("only the first operation may name the client"));
+        }
+        if op.name == WEB_AUTH_DOMAIN_KEY
+            && op.value.as_deref() != Some(config.web_auth_domain.as_bytes())
+        {
+            return Err(invalid("web_auth_domain does not match"));
+        }
+    }
+
+    let hash = tx.hash(&config.network_passphrase);
+    if !envelope
+        .signatures
+        .iter()
+        .any(|sig| signed_by(sig, &server_key, &hash))
+    {
+        return Err(invalid("missing server signature"));
+    }
+    Ok(Challenge {
+        client: StellarAddress::from_ed25519(client_key),
+        envelope,
+        hash,
+    })
+}
+
+fn signed_by(sig: &DecoratedSignature, key: &[u8; 32], hash: &[u8; 32]) -> bool {
+    sig.hint == DecoratedSignature::hint_for(key)
+        && UnparsedPublicKey::new(&ED25519, key)
+            .verify(hash, &sig.signature)
+            .is_ok()
+}
+
+impl Challenge {
+    /// Check the client's signatures against `signers` (ed25519 keys and
+    /// weights): each signature must be the server's or a signer's, and the
+    /// signers' weights must add up to `threshold`, and to at least 1.
+    pub fn verify_signers(
+        &self,
+        config: &Sep10Config,
+        signers: &[([u8; 32], u8)],
+        threshold: u8,
+    ) -> Result<(), AppError> {
+        let server_key = config.server_account.public_key();
+        let mut signed: Vec<[u8; 32]> = Vec::new();
+        for sig in &self.envelope.signatures {
+            if signed_by(sig, &server_key, &self.hash) {
+                continue;
+            }
+            let signer = signers
+                .iter()
+                .map(|(key, _)| key)
+                .find(|key| signed_by(sig, key, &self.hash))
+                .ok_or_else(|| invalid("unrecognized signature"))?;
+            if !signed.contains(signer) {
+                signed.push(*signer);
+            }
+        }
+
+        let weight: u32 = signers
+            .iter()
+            .filter(|(key, _)| signed.contains(key))
+            .map(|(_, weight)| *weight as u32)
+            .sum();
+        if weight == 0 || weight < threshold as u32 {
+            return Err(AppError::Unauthorized(format!(
+                "signatures for {} do not meet its threshold",
+                self.client
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Claims of an issued token.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Sep10Claims {
+    pub iss: String,
+    /// The authenticated `G...` account.
+    pub sub: String,
+    pub iat: i64,
+    pub exp: i64,
+    /// Hex hash of the challenge the token was issued for.
+    pub jti: String,
+}
+
+/// Issue an HS256 JWT for a verified challenge.
+pub fn issue_token(config: &Sep10Config, challenge: &Challenge, now: DateTime<Utc>) -> String {
+    let claims = Sep10Claims {
+        iss: format!("https://{}/auth", config.web_auth_domain),
+        sub: challenge.client.account().to_string(),
+        iat: now.timestamp(),
+        exp: (now + config.token_ttl).timestamp(),
+        jti: hex::encode(challenge.hash),
+    };
+    let header = URL_SAFE_NO_PAD.encode(br#"{"alg":"HS256","typ":"JWT"}"#);
+    let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&claims).expect("claims serialize"));
+    let signing_input = format!("{header}.{payload}");
+    let signature = URL_SAFE_NO_PAD.encode(jwt_mac(config, &signing_input).finalize().into_bytes());
+    format!("{signing_input}.{signature}")
+}
+
+/// Check a token issued by [`issue_token`] and return its claims.
+pub fn verify_token(
+    config: &Sep10Config,
+    token: &str,
+    now: DateTime<Utc>,
+) -> Result<Sep10Claims, AppError> {
+    let unauthorized = || AppError::Unauthorized("invalid or expired token".to_string());
+    let (signing_input, signature) = token.rsplit_once('.').ok_or_else(unauthorized)?;
+    let (header, payload) = signing_input.split_once('.').ok_or_else(unauthorized)?;
+    let signature = URL_SAFE_NO_PAD
+        .decode(signature)
+        .map_err(|_| unauthorized())?;
+    jwt_mac(config, signing_input)
+        .verify_slice(&signature)
+        .map_err(|_| unauthorized())?;
+
+    let header: serde_json::Value = URL_SAFE_NO_PAD
+        .decode(header)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .ok_or_else(unauthorized)?;
+    if header["alg"] != "HS256" {
+        return Err(unauthorized());
+    }
+    let claims: Sep10Claims = URL_SAFE_NO_PAD
+        .decode(payload)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .ok_or_else(unauthorized)?;
+    if claims.exp <= now.timestamp() {
+        return Err(unauthorized());
+    }
+    Ok(claims)
+}
+
+fn jwt_mac(config: &Sep10Config, signing_input: &str) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(config.jwt_secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(signing_input.as_bytes());
+    mac
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSPHRASE: &str = "Test SDF Network ; September 2015";
+
+    fn config() -> Sep10Config {
+        Sep10Config::new([1; 32], "example.com", PASSPHRASE, "j".repeat(32))
+            .unwrap()
+            .with_web_auth_domain("auth.example.com")
+    }
+
+    fn client(seed: u8) -> (Ed25519KeyPair, StellarAddress) {
+        let key = Ed25519KeyPair::from_seed_unchecked(&[seed; 32]).unwrap();
+        let mut public = [0u8; 32];
+        public.copy_from_slice(key.public_key().as_ref());
+        (key, StellarAddress::from_ed25519(public))
+    }
+
+    /// Sign a base64 challenge with `key`, as a wallet would.
+    fn co_sign(challenge: &str, key: &Ed25519KeyPair) -> String {
+        let mut envelope =
+            TransactionEnvelope::from_xdr(&STANDARD.decode(challenge).unwrap()).unwrap();
+        let mut public = [0u8; 32];
+        public.copy_from_slice(key.public_key().as_ref());
+        envelope.signatures.push(DecoratedSignature {
+            hint: DecoratedSignature::hint_for(&public),
+            signature: key.sign(&envelope.tx.hash(PASSPHRASE)).as_ref().to_vec(),
+        });
+        STANDARD.encode(envelope.to_xdr())
+    }
+
+    #[test]
+    fn test_challenge_round_trip_issues_token() {
+        let config = config();
+        let now = Utc::now();
+        let (key, account) = client(2);
+
+        let challenge = build_challenge(&config, &account, Some("EXAMPLE.com"), now).unwrap();
+        let signed = co_sign(&challenge, &key);
+        let read = read_challenge(&config, &signed, now + Duration::seconds(30)).unwrap();
+        assert_eq!(read.client, account);
+        read.verify_signers(&config, &[(account.public_key(), 1)], 0)
+            .unwrap();
+
+        let token = issue_token(&config, &read, now);
+        let claims = verify_token(&config, &token, now).unwrap();
+        assert_eq!(claims.sub, account.account());
+        assert_eq!(claims.iss, "https://auth.example.com/auth");
+        assert!(verify_token(&config, &token, now + config.token_ttl).is_err());
+        assert!(verify_token(&config, &format!("{token}x"), now).is_err());
+    }
+
+    #[test]
+    fn test_rejects_expired_or_foreign_challenges() {
+        let config = config();
+        let now = Utc::now();
+        let (key, account) = client(2);
+        let signed = co_sign(
+            &build_challenge(&config, &account, None, now).unwrap(),
+            &key,
+        );
+
+        let late = now + config.challenge_ttl + Duration::seconds(1);
+        assert!(read_challenge(&config, &signed, late).is_err());
+
+        let other = Sep10Config::new([3; 32], "example.com", PASSPHRASE, "j".repeat(32)).unwrap();
+        assert!(read_challenge(&other, &signed, now).is_err());
+
+        assert!(build_challenge(&config, &account, Some("evil.com"), now).is_err());
+    }
+
+    #[test]
+    fn test_signer_weights_and_stray_signatures() {
+        let config = config();
+        let now = Utc::now();
+        let (master, account) = client(2);
+        let (cosigner, cosigner_account) = client(4);
+        let (stranger, _) = client(5);
+        let challenge = build_challenge(&config, &account, None, now).unwrap();
+        let signers = [
+            (account.public_key(), 1),
+            (cosigner_account.public_key(), 1),
+        ];
+
+        let one = read_challenge(&config, &co_sign(&challenge, &master), now).unwrap();
+        assert!(one.verify_signers(&config, &signers, 2).is_err());
+
+        let both = co_sign(&co_sign(&challenge, &master), &cosigner);
+        let both = read_challenge(&config, &both, now).unwrap();
+        assert!(both.verify_signers(&config, &signers, 2).is_ok());
+
+        let stray = co_sign(&co_sign(&challenge, &master), &stranger);
+        let stray = read_challenge(&config, &stray, now).unwrap();
+        assert!(stray.verify_signers(&config, &signers, 1).is_err());
+
+        let unsigned = read_challenge(&config, &challenge, now).unwrap();
+        assert!(unsigned.verify_signers(&config, &signers, 0).is_err());
+    }
+}
@@ -0,0 +1,462 @@
+//! SEP-12 KYC customer records.
+//!
+//! A wallet holding a SEP-10 token (see [`crate::services::sep10`]) uploads
+//! the SEP-9 fields of a customer with `PUT /customer` and reads back what is
+//! still needed with `GET /customer`. Records are stored through
+//! [`CustomerRepository`], keyed by the token's account, an optional `id`
+//! memo for shared accounts and the customer `type` (default `default`).
+//!
+//! Only the fields in [`FIELDS`] are accepted, as strings. After every
+//! update the status is `NEEDS_INFO` while a required field is missing and
+//! `PROCESSING` otherwise; compliance then sets `ACCEPTED` or `REJECTED`
+//! through `PUT /admin/customers/:id/status`. An accepted customer stays
+//! accepted when an update changes nothing. [`crate::use_cases::ProcessDeposit`]
+//! can refuse deposits from accounts without an accepted record.
+//!
+//! Binary fields (photo ids) and multipart uploads are not supported, nor
+//! are `/customer/verification` and `/customer/callback`.
+
+use crate::error::AppError;
+use crate::ports::{Customer, CustomerKey, KycStatus, DEFAULT_CUSTOMER_TYPE};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
+
+/// A SEP-9 field the anchor collects.
+#[derive(Debug, Clone, Copy)]
+pub struct FieldSpec {
+    pub name: &'static str,
+    pub kind: &'static str,
+    pub description: &'static str,
+    pub required: bool,
+}
+
+const fn field(
+    name: &'static str,
+    kind: &'static str,
+    description: &'static str,
+    required: bool,
+) -> FieldSpec {
+    FieldSpec {
+        name,
+        kind,
+        description,
+        required,
+    }
+}
+
+/// Fields accepted by `PUT /customer`.
+pub const FIELDS: &[FieldSpec] = &[
+    field("first_name", "string", "First or given name", true),
+    field("last_name", "string", "Last or family name", true),
+    field("email_address", "string", "Email address", true),
+    field(
+        "mobile_number",
+        "string",
+        "Mobile phone number in E.164 format",
+        false,
+    ),
+    field("birth_date", "date", "Date of birth (YYYY-MM-DD)", false),
+    field("address", "string", "Street address", false),
+    field("city", "string", "City of residence", false),
+    field("postal_code", "string", "Postal or ZIP code", false),
+    field(
+        "address_country_code",
+        "string",
+        "Country of residence, ISO 3166-1 alpha-3",
+        false,
+    ),
+    field(
+        "id_type",
+        "string",
+        "Type of photo id: passport, drivers_license, id_card",
+        false,
+    ),
+    field("id_number", "string", "Number of the photo id", false),
+    field(
+        "bank_account_number",
+        "string",
+        "Bank account number or IBAN",
+        false,
+    ),
+    field(
+        "bank_number",
+        "string",
+        "Routing number or BIC of the bank",
+        false,
+    ),
+];
+
+const MAX_FIELD_LEN: usize = 1024;
+const MAX_TYPE_LEN: usize = 64;
+
+fn spec(name: &str) -> Option<&'static FieldSpec> {
+    FIELDS.iter().find(|f| f.name == name)
+}
+
+/// Query of `GET /customer` and the key fields of `PUT /customer`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CustomerQuery {
+    pub id: Option<Uuid>,
+    pub account: Option<String>,
+    pub memo: Option<String>,
+    pub memo_type: Option<String>,
+    #[serde(rename = "type")]
+    pub customer_type: Option<String>,
+}
+
+impl CustomerQuery {
+    /// The record key, for the authenticated account. `account`, when
+    /// given, must be that account.
+    pub fn key(&self, authenticated: &str) -> Result<CustomerKey, AppError> {
+        let account = self
+            .account
+            .as_deref()
+            .map(str::trim)
+            .unwrap_or(authenticated);
+        if account != authenticated {
+            return Err(AppError::InsufficientPermissions(
+                "account does not match the authenticated account".to_string(),
+            ));
+        }
+        let memo = match self
+            .memo
+            .as_deref()
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+        {
+            None => None,
+            Some(memo) => {
+                if !matches!(self.memo_type.as_deref(), None | Some("id")) {
+                    return Err(AppError::BadRequest("memo_type must be id".to_string()));
+                }
+                if memo.parse::<u64>().is_err() {
+                    return Err(AppError::BadRequest(format!("invalid id memo: {memo}")));
+                }
+                Some(memo.to_string())
+            }
+        };
+        let customer_type = self
+            .customer_type
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .unwrap_or(DEFAULT_CUSTOMER_TYPE);
+        if customer_type.len() > MAX_TYPE_LEN
+            || !customer_type
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(AppError::BadRequest(format!(
+                "invalid type: {customer_type}"
+            )));
+        }
+        Ok(CustomerKey::new(account)
+            .with_memo(memo)
+            .with_type(customer_type))
+    }
+}
+
+/// Body of `PUT /customer`: the key fields plus SEP-9 fields.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PutCustomerRequest {
+    pub id: Option<Uuid>,
+    pub account: Option<String>,
+    pub memo: Option<String>,
+    pub memo_type: Option<String>,
+    #[serde(rename = "type")]
+    pub customer_type: Option<String>,
+    #[serde(flatten)]
+    pub fields: Map<String, Value>,
+}
+
+impl PutCustomerRequest {
+    pub fn query(&self) -> CustomerQuery {
+        CustomerQuery {
+            id: self.id,
+            account: self.account.clone(),
+            memo: self.memo.clone(),
+            memo_type: self.memo_type.clone(),
+            customer_type: self.customer_type.clone(),
+        }
+    }
+
+    /// The SEP-9 fields, checked against [`FIELDS`].
+    pub fn fields(&self) -> Result<Map<String, Value>, AppError> {
+        let mut fields = Map::new();
+        for (name, value) in &self.fields {
+            if spec(name).is_none() {
+                return Err(AppError::BadRequest(format!("unknown field {name}")));
+            }
+            let value = value
+                .as_str()
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .ok_or_else(|| {
+                    AppError::BadRequest(format!("{name} must be a non-empty string"))
+                })?;
+            if value.len() > MAX_FIELD_LEN {
+                return Err(AppError::BadRequest(format!(
+                    "{name} must be at most {MAX_FIELD_LEN} characters"
+                )));
+            }
+            fields.insert(name.clone(), Value::String(value.to_string()));
+        }
+        Ok(fields)
+    }
+}
+
+fn status_for(fields: &Map<String, Value>) -> KycStatus {
+    if FIELDS
+        .iter()
+        .any(|f| f.required && !fields.contains_key(f.name))
+    {
+        KycStatus::NeedsInfo
+    } else {
+        KycStatus::Processing
+    }
+}
+
+/// `existing` (or a new record under `key` with id `id`) with `fields`
+/// merged in and its status recomputed. A compliance decision only stands
+/// while the fields it was made on are unchanged.
+pub fn apply_update(
+    existing: Option<Customer>,
+    key: CustomerKey,
+    fields: Map<String, Value>,
+    id: Uuid,
+    now: DateTime<Utc>,
+) -> Customer {
+    let mut customer = existing.unwrap_or_else(|| Customer {
+        id,
+        key,
+        fields: Map::new(),
+        status: KycStatus::NeedsInfo,
+        message: None,
+        created_at: now,
+        updated_at: now,
+    });
+    let changed = fields
+        .iter()
+        .any(|(name, value)| customer.fields.get(name) != Some(value));
+    let decided = matches!(customer.status, KycStatus::Accepted | KycStatus::Rejected);
+    if changed || !decided {
+        customer.fields.extend(fields);
+        let status = status_for(&customer.fields);
+        if status != customer.status {
+            customer.message = None;
+        }
+        customer.status = status;
+    }
+    customer.updated_at = now;
+    customer
+}
+
+/// `GET /customer` body: what is still needed and what was provided.
+pub fn view(customer: Option<&Customer>) -> Value {
+    let provided = customer.map(|c| &c.fields);
+    let missing: Map<String, Value> = FIELDS
+        .iter()
+        .filter(|f| provided.map_or(true, |p| !p.contains_key(f.name)))
+        .map(|f| {
+            let mut entry = json!({ "type": f.kind, "description": f.description });
+            if !f.required {
+                entry["optional"] = json!(true);
+            }
+            (f.name.to_string(), entry)
+        })
+        .collect();
+    let Some(customer) = customer else {
+        return json!({ "status": KycStatus::NeedsInfo.as_str(), "fields": missing });
+    };
+
+    let field_status = match customer.status {
+        KycStatus::Accepted => KycStatus::Accepted,
+        KycStatus::Rejected => KycStatus::Rejected,
+        _ => KycStatus::Processing,
+    };
+    let provided_fields: Map<String, Value> = FIELDS
+        .iter()
+        .filter(|f| customer.fields.contains_key(f.name))
+        .map(|f| {
+            (
+                f.name.to_string(),
+                json!({
+                    "type": f.kind,
+                    "description": f.description,
+                    "status": field_status.as_str(),
+                }),
+            )
+        })
+        .collect();
+    let mut body = json!({
+        "id": customer.id,
+        "status": customer.status.as_str(),
+        "fields": missing,
+        "provided_fields": provided_fields,
+    });
+    if let Some(message) = &customer.message {
+        body["message"] = json!(message);
+    }
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const ACCOUNT: &str = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 7, 17, 9, 0, 0).unwrap()
+    }
+
+    fn fields(pairs: &[(&str, &str)]) -> Map<String, Value> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), json!(v)))
+            .collect()
+    }
+
+    #[test]
+    fn test_key_defaults_and_checks_account_memo_and_type() {
+        let key = CustomerQuery::default().key(ACCOUNT).unwrap();
+        assert_eq!(key, CustomerKey::new(ACCOUNT));
+
+        let query = CustomerQuery {
+            memo: Some("42".to_string()),
+            customer_type: Some("sep31-receiver".to_string()),
+            ..Default::default()
+        };
+        let key = query.key(ACCOUNT).unwrap();
+        assert_eq!(key.memo.as_deref(), Some("42"));
+        assert_eq!(key.customer_type, "sep31-receiver");
+
+        let other = CustomerQuery {
+            account: Some("GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            other.key(ACCOUNT),
+            Err(AppError::InsufficientPermissions(_))
+        ));
+        let text_memo = CustomerQuery {
+            memo: Some("hello".to_string()),
+            ..Default::default()
+        };
+        assert!(text_memo.key(ACCOUNT).is_err());
+    }
+
+    #[test]
+    fn test_put_body_splits_key_and_sep9_fields() {
+        let body: PutCustomerRequest = serde_json::from_value(json!({
+            "account": ACCOUNT,
+            "type": "default",
+            "first_name": " Ada ",
+            "email_address": "ada@example.com"
+        }))
+        .unwrap();
+        assert_eq!(body.query().account.as_deref(), Some(ACCOUNT));
+        assert_eq!(
+            body.fields().unwrap(),
+            fields(&[("first_name", "Ada"), ("email_address", "ada@example.com")])
+        );
+
+        let unknown: PutCustomerRequest =
+            serde_json::from_value(json!({ "favourite_colour": "blue" })).unwrap();
+        assert!(unknown.fields().is_err());
+        let not_string: PutCustomerRequest =
+            serde_json::from_value(json!({ "first_name": 7 })).unwrap();
+        assert!(not_string.fields().is_err());
+    }
+
+    #[test]
+    fn test_status_follows_required_fields() {
+        let key = CustomerKey::new(ACCOUNT);
+        let id = Uuid::new_v4();
+        let partial = apply_update(
+            None,
+            key.clone(),
+            fields(&[("first_name", "Ada")]),
+            id,
+            now(),
+        );
+        assert_eq!(partial.status, KycStatus::NeedsInfo);
+        assert_eq!(partial.id, id);
+
+        let complete = apply_update(
+            Some(partial),
+            key,
+            fields(&[
+                ("last_name", "Lovelace"),
+                ("email_address", "ada@example.com"),
+            ]),
+            Uuid::new_v4(),
+            now(),
+        );
+        assert_eq!(complete.status, KycStatus::Processing);
+        assert_eq!(complete.id, id);
+        assert_eq!(complete.fields.len(), 3);
+    }
+
+    #[test]
+    fn test_accepted_customer_is_reviewed_again_only_on_change() {
+        let mut customer = apply_update(
+            None,
+            CustomerKey::new(ACCOUNT),
+            fields(&[
+                ("first_name", "Ada"),
+                ("last_name", "Lovelace"),
+                ("email_address", "ada@example.com"),
+            ]),
+            Uuid::new_v4(),
+            now(),
+        );
+        customer.status = KycStatus::Accepted;
+
+        let key = customer.key.clone();
+        let same = apply_update(
+            Some(customer.clone()),
+            key.clone(),
+            fields(&[("first_name", "Ada")]),
+            Uuid::new_v4(),
+            now(),
+        );
+        assert_eq!(same.status, KycStatus::Accepted);
+
+        let changed = apply_update(
+            Some(customer),
+            key,
+            fields(&[("first_name", "Augusta")]),
+            Uuid::new_v4(),
+            now(),
+        );
+        assert_eq!(changed.status, KycStatus::Processing);
+    }
+
+    #[test]
+    fn test_view_lists_missing_and_provided_fields() {
+        let unknown = view(None);
+        assert_eq!(unknown["status"], "NEEDS_INFO");
+        assert!(unknown["fields"]["first_name"].get("optional").is_none());
+        assert_eq!(unknown["fields"]["mobile_number"]["optional"], true);
+
+        let customer = apply_update(
+            None,
+            CustomerKey::new(ACCOUNT),
+            fields(&[("first_name", "Ada")]),
+            Uuid::new_v4(),
+            now(),
+        );
+        let body = view(Some(&customer));
+        assert_eq!(body["id"], json!(customer.id));
+        assert!(body["fields"].get("first_name").is_none());
+        assert_eq!(
+            body["provided_fields"]["first_name"]["status"],
+            "PROCESSING"
+        );
+        assert!(body["fields"]["last_name"].is_object());
+    }
+}
@@ -0,0 +1,436 @@
+//! SEP-24 hosted deposit and withdrawal.
+//!
+//! A wallet holding a SEP-10 token (see [`crate::services::sep10`]) posts to
+//! `/sep24/transactions/deposit/interactive` or
+//! `/sep24/transactions/withdraw/interactive`. The request is recorded as a
+//! `pending` row in `transactions` with `callback_type` `deposit` or
+//! `withdrawal`, `callback_status` `incomplete` (the SEP-24 status) and a
+//! fresh `id` memo, and the wallet gets back the URL of the anchor's
+//! interactive flow for that transaction.
+//!
+//! From there the row is processed like any other pending transaction: the
+//! payment monitor completes it when a payment carrying its memo arrives, and
+//! the processor verifies and completes it once it carries a Stellar
+//! transaction hash. Amount limits are enforced up front: an amount outside
+//! the asset's `min_amount` / `max_amount` is refused rather than held,
+//! since no funds have moved yet.
+//!
+//! The interactive URL is `SEP24_INTERACTIVE_URL` with `transaction_id`,
+//! `asset_code`, `lang`, `expires` and `signature` appended. The signature
+//! is the hex HMAC-SHA256 of `sep24:<transaction id>.<expires>` keyed with
+//! `SEP24_INTERACTIVE_SECRET`, so the web app serving the flow can check the
+//! link was issued here (same scheme as [`crate::utils::signed_url`]).
+//!
+//! | Env var                    | Default  | Meaning                              |
+//! |----------------------------|----------|--------------------------------------|
+//! | `SEP24_INTERACTIVE_URL`    | required | Base URL of the interactive web app  |
+//! | `SEP24_INTERACTIVE_SECRET` | required | Link signing key, 32+ characters     |
+//! | `SEP24_URL_TTL_SECS`       | 300      | How long an interactive URL is valid |
+//!
+//! Only the two interactive endpoints are served; `/info`, `/transaction`
+//! and `/transactions` are not implemented.
+//!
+//! Error messages are in the request's `lang` when it is one of the
+//! [`crate::i18n`] locales, else in the `Accept-Language` one.
+
+use crate::db::models::Transaction;
+use crate::domain::StellarAddress;
+use crate::error::AppError;
+use crate::i18n::{Locale, Message};
+use crate::services::amount_limits::{AmountCheck, AmountLimits};
+use crate::utils::signed_url;
+use anyhow::{bail, Context};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::types::BigDecimal;
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// SEP-24 status of a transaction waiting on the interactive flow.
+pub const STATUS_INCOMPLETE: &str = "incomplete";
+/// `type` of the interactive response.
+pub const INTERACTIVE_RESPONSE_TYPE: &str = "interactive_customer_info_needed";
+
+const DEFAULT_URL_TTL_SECS: i64 = 300;
+const MIN_SECRET_LEN: usize = 32;
+const MAX_LANG_LEN: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sep24Kind {
+    Deposit,
+    Withdraw,
+}
+
+impl Sep24Kind {
+    /// `callback_type` the transaction is stored with.
+    pub fn callback_type(&self) -> &'static str {
+        match self {
+            Sep24Kind::Deposit => "deposit",
+            Sep24Kind::Withdraw => "withdrawal",
+        }
+    }
+}
+
+pub struct Sep24Config {
+    pub interactive_url: String,
+    secret: String,
+    pub url_ttl: Duration,
+}
+
+impl std::fmt::Debug for Sep24Config {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Sep24Config")
+            .field("interactive_url", &self.interactive_url)
+            .field("url_ttl", &self.url_ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Sep24Config {
+    pub fn new(
+        interactive_url: impl Into<String>,
+        secret: impl Into<String>,
+    ) -> anyhow::Result<Self> {
+        let interactive_url = interactive_url.into();
+        let secret = secret.into();
+        if !interactive_url.starts_with("https://") && !interactive_url.starts_with("http://") {
+            bail!("SEP24_INTERACTIVE_URL must be an http(s) URL");
+        }
+        if secret.len() < MIN_SECRET_LEN {
+            bail!("SEP24_INTERACTIVE_SECRET must be at least {MIN_SECRET_LEN} characters");
+        }
+        Ok(Self {
+            interactive_url,
+            secret,
+            url_ttl: Duration::seconds(DEFAULT_URL_TTL_SECS),
+        })
+    }
+
+    pub fn from_env() -> anyhow::Result<Self> {
+        let required = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+                .with_context(|| format!("{name} is not set"))
+        };
+        let mut config = Self::new(
+            required("SEP24_INTERACTIVE_URL")?.trim(),
+            required("SEP24_INTERACTIVE_SECRET")?,
+        )?;
+        let ttl = std::env::var("SEP24_URL_TTL_SECS")
+            .ok()
+            .and_then(|v| v.trim().parse::<i64>().ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(DEFAULT_URL_TTL_SECS);
+        config.url_ttl = Duration::seconds(ttl);
+        Ok(config)
+    }
+}
+
+/// Body of both interactive endpoints.
+#[derive(Debug, Clone, Deserialize)]
+pub struct InteractiveRequest {
+    pub asset_code: String,
+    /// Defaults to the authenticated account; any other account is refused.
+    pub account: Option<String>,
+    /// Decimal string. Optional in SEP-24, but required here: every
+    /// transaction record carries an amount.
+    pub amount: Option<String>,
+    pub lang: Option<String>,
+}
+
+/// `200` body of both interactive endpoints.
+#[derive(Debug, Clone, Serialize)]
+pub struct InteractiveResponse {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub url: String,
+    pub id: Uuid,
+}
+
+impl InteractiveRequest {
+    /// The locale for messages: `lang` when it is a supported one, else
+    /// `fallback`, typically from `Accept-Language`.
+    pub fn locale(&self, fallback: Locale) -> Locale {
+        self.lang
+            .as_deref()
+            .and_then(Locale::parse)
+            .unwrap_or(fallback)
+    }
+
+    /// The account the transaction is for: `account` when given, which must
+    /// then be the authenticated one.
+    pub fn account(&self, authenticated: &str, locale: Locale) -> Result<StellarAddress, AppError> {
+        let account = self.account.as_deref().unwrap_or(authenticated).trim();
+        if account != authenticated {
+            return Err(AppError::InsufficientPermissions(
+                Message::AccountMismatch.render(locale, &[]),
+            ));
+        }
+        StellarAddress::parse(account).map_err(|_| {
+            AppError::BadRequest(Message::InvalidAccount.render(locale, &[("account", account)]))
+        })
+    }
+
+    /// The requested amount, which must be positive and within `limits`.
+    pub fn amount(&self, limits: &AmountLimits, locale: Locale) -> Result<BigDecimal, AppError> {
+        let raw = self
+            .amount
+            .as_deref()
+            .map(str::trim)
+            .filter(|a| !a.is_empty())
+            .ok_or_else(|| AppError::BadRequest(Message::AmountRequired.render(locale, &[])))?;
+        let amount = BigDecimal::from_str(raw).map_err(|_| {
+            AppError::BadRequest(Message::InvalidAmount.render(locale, &[("amount", raw)]))
+        })?;
+        if amount <= BigDecimal::from(0) {
+            return Err(AppError::BadRequest(
+                Message::AmountNotPositive.render(locale, &[]),
+            ));
+        }
+        let bound = |bound: &Option<BigDecimal>| {
+            bound.as_ref().map(ToString::to_string).unwrap_or_default()
+        };
+        match limits.check(&amount) {
+            AmountCheck::WithinLimits => Ok(amount),
+            AmountCheck::BelowMinimum => Err(AppError::BadRequest(
+                Message::AmountBelowMinimum.render(locale, &[("min", &bound(&limits.min_amount))]),
+            )),
+            AmountCheck::AboveMaximum => Err(AppError::BadRequest(
+                Message::AmountAboveMaximum.render(locale, &[("max", &bound(&limits.max_amount))]),
+            )),
+        }
+    }
+
+    pub fn lang(&self, locale: Locale) -> Result<Option<String>, AppError> {
+        let Some(lang) = self
+            .lang
+            .as_deref()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+        else {
+            return Ok(None);
+        };
+        if lang.len() > MAX_LANG_LEN
+            || !lang
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(AppError::BadRequest(
+                Message::InvalidLang.render(locale, &[("lang", lang)]),
+            ));
+        }
+        Ok(Some(lang.to_string()))
+    }
+}
+
+/// The pending transaction recorded for an interactive request. The memo is a
+/// random `id` memo the user's payment is matched by.
+pub fn new_transaction(
+    kind: Sep24Kind,
+    account: &StellarAddress,
+    asset_code: &str,
+    amount: BigDecimal,
+    lang: Option<&str>,
+) -> Transaction {
+    let memo = (rand::random::<u64>() >> 1).to_string();
+    let metadata = serde_json::json!({ "sep24": { "lang": lang } });
+    Transaction::new(
+        account.account().to_string(),
+        amount,
+        asset_code.to_string(),
+        None,
+        Some(kind.callback_type().to_string()),
+        Some(STATUS_INCOMPLETE.to_string()),
+        Some(memo),
+        Some("id".to_string()),
+        Some(metadata),
+    )
+    .with_muxed_id(account.muxed_id())
+    .with_own_anchor_id()
+}
+
+fn resource(id: Uuid) -> String {
+    format!("sep24:{id}")
+}
+
+/// Interactive URL for transaction `id`, valid for the configured TTL.
+pub fn interactive_url(
+    config: &Sep24Config,
+    id: Uuid,
+    asset_code: &str,
+    lang: Option<&str>,
+    now: DateTime<Utc>,
+) -> String {
+    let expires = (now + config.url_ttl).timestamp();
+    let signature = signed_url::sign(&config.secret, &resource(id), expires);
+    let separator = if config.interactive_url.contains('?') {
+        '&'
+    } else {
+        '?'
+    };
+    let mut url = format!(
+        "{}{separator}transaction_id={id}&asset_code={asset_code}",
+        config.interactive_url
+    );
+    if let Some(lang) = lang {
+        url.push_str(&format!("&lang={lang}"));
+    }
+    url.push_str(&format!("&expires={expires}&signature={signature}"));
+    url
+}
+
+/// Check the signature of an interactive URL for transaction `id`.
+pub fn verify_interactive_url(
+    config: &Sep24Config,
+    id: Uuid,
+    expires: i64,
+    signature: &str,
+    now: DateTime<Utc>,
+) -> bool {
+    signed_url::verify(&config.secret, &resource(id), expires, signature, now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ACCOUNT: &str = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+
+    fn config() -> Sep24Config {
+        Sep24Config::new("https://anchor.example.com/sep24", "s".repeat(32)).unwrap()
+    }
+
+    fn request(amount: Option<&str>) -> InteractiveRequest {
+        InteractiveRequest {
+            asset_code: "USDC".to_string(),
+            account: None,
+            amount: amount.map(str::to_string),
+            lang: Some("en".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_config_rejects_short_secret_and_bad_url() {
+        assert!(Sep24Config::new("https://anchor.example.com", "short").is_err());
+        assert!(Sep24Config::new("anchor.example.com", "s".repeat(32)).is_err());
+    }
+
+    #[test]
+    fn test_account_must_be_the_authenticated_one() {
+        let mut req = request(Some("10"));
+        assert_eq!(req.account(ACCOUNT, Locale::En).unwrap().account(), ACCOUNT);
+
+        req.account = Some("GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H".to_string());
+        assert!(matches!(
+            req.account(ACCOUNT, Locale::En),
+            Err(AppError::InsufficientPermissions(_))
+        ));
+    }
+
+    #[test]
+    fn test_amount_is_required_positive_and_within_limits() {
+        let limits = AmountLimits {
+            min_amount: Some(BigDecimal::from(5)),
+            max_amount: Some(BigDecimal::from(1000)),
+        };
+        assert!(request(None).amount(&limits, Locale::En).is_err());
+        assert!(request(Some("abc")).amount(&limits, Locale::En).is_err());
+        assert!(request(Some("-1")).amount(&limits, Locale::En).is_err());
+        assert!(request(Some("1")).amount(&limits, Locale::En).is_err());
+        assert!(request(Some("5000")).amount(&limits, Locale::En).is_err());
+        assert_eq!(
+            request(Some("25.5")).amount(&limits, Locale::En).unwrap(),
+            BigDecimal::from_str("25.5").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_lang_validation() {
+        let mut req = request(None);
+        assert_eq!(req.lang(Locale::En).unwrap().as_deref(), Some("en"));
+        req.lang = Some("pt-BR".to_string());
+        assert!(req.lang(Locale::En).is_ok());
+        req.lang = Some("en&x=1".to_string());
+        assert!(req.lang(Locale::En).is_err());
+    }
+
+    #[test]
+    fn test_messages_follow_lang_then_fallback() {
+        let limits = AmountLimits {
+            min_amount: Some(BigDecimal::from(5)),
+            max_amount: None,
+        };
+        let mut req = request(Some("1"));
+        req.lang = Some("es-AR".to_string());
+        let locale = req.locale(Locale::Fr);
+        assert_eq!(locale, Locale::Es);
+        assert!(matches!(
+            req.amount(&limits, locale),
+            Err(AppError::BadRequest(msg)) if msg == "el importe es inferior al mínimo de 5"
+        ));
+
+        req.lang = Some("pt-BR".to_string());
+        assert_eq!(req.locale(Locale::Fr), Locale::Fr);
+        req.lang = None;
+        assert_eq!(req.locale(Locale::En), Locale::En);
+    }
+
+    #[test]
+    fn test_new_transaction_is_pending_and_incomplete() {
+        let account = StellarAddress::parse(ACCOUNT).unwrap();
+        let tx = new_transaction(
+            Sep24Kind::Withdraw,
+            &account,
+            "USDC",
+            BigDecimal::from(10),
+            Some("en"),
+        );
+        assert_eq!(tx.status.as_str(), "pending");
+        assert_eq!(tx.callback_type.as_deref(), Some("withdrawal"));
+        assert_eq!(tx.callback_status.as_deref(), Some(STATUS_INCOMPLETE));
+        assert_eq!(tx.memo_type.as_deref(), Some("id"));
+        assert!(tx.memo.unwrap().parse::<i64>().is_ok());
+    }
+
+    #[test]
+    fn test_interactive_url_is_verifiable() {
+        let config = config();
+        let id = Uuid::new_v4();
+        let now = Utc::now();
+        let url = interactive_url(&config, id, "USDC", Some("en"), now);
+        assert!(url.starts_with(&format!(
+            "https://anchor.example.com/sep24?transaction_id={id}&asset_code=USDC&lang=en&expires="
+        )));
+
+        let query: std::collections::HashMap<_, _> = url
+            .split_once('?')
+            .unwrap()
+            .1
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .collect();
+        let expires: i64 = query["expires"].parse().unwrap();
+        assert!(verify_interactive_url(
+            &config,
+            id,
+            expires,
+            query["signature"],
+            now
+        ));
+        assert!(!verify_interactive_url(
+            &config,
+            Uuid::new_v4(),
+            expires,
+            query["signature"],
+            now
+        ));
+        assert!(!verify_interactive_url(
+            &config,
+            id,
+            expires,
+            query["signature"],
+            now + Duration::seconds(301)
+        ));
+    }
+}
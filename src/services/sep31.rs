@@ -0,0 +1,508 @@
+//! SEP-31 cross-border payments, receiving side.
+//!
+//! A sending anchor authenticated with SEP-10 (see
+//! [`crate::services::sep10`]) posts a payment to `/sep31/transactions`. It
+//! is recorded as a `pending` row in `transactions` with `callback_type`
+//! `sep31`, the sending anchor's account as `stellar_account` and a fresh
+//! `id` memo, and the anchor is told to pay `SEP31_RECEIVING_ACCOUNT` with
+//! that memo. The payment monitor completes the row when the payment
+//! arrives; from then on it settles with every other completed transaction
+//! of its asset (see [`crate::services::settlement`]).
+//!
+//! Statuses are reported in SEP-31 terms, derived from the transaction:
+//!
+//! | Transaction                        | SEP-31 status      |
+//! |------------------------------------|--------------------|
+//! | `pending`, no Stellar hash yet     | `pending_sender`   |
+//! | `pending` with a Stellar hash      | `pending_stellar`  |
+//! | `processing`, `compliance_review`  | `pending_receiver` |
+//! | `refund_pending`                   | `pending_receiver` |
+//! | `completed`                        | `completed`        |
+//! | `failed`, `dlq`                    | `error`            |
+//!
+//! A sending anchor can register a callback URL per transaction. On every
+//! `transaction.status_changed` event for it, the transaction is POSTed there
+//! as `{"transaction": {...}}` with a `Signature: t=<unix>, s=<base64>`
+//! header: the SEP-10 server key's ed25519 signature of
+//! `<t>.<callback host>.<body>`. Delivery is attempted once.
+//!
+//! | Env var                   | Default  | Meaning                              |
+//! |---------------------------|----------|--------------------------------------|
+//! | `SEP31_RECEIVING_ACCOUNT` | required | `G...` account sending anchors pay   |
+//!
+//! A payment can name a SEP-38 quote selling `stellar:<asset_code>` in
+//! `quote_id`; it must then send exactly the quoted amount before the quote
+//! expires, and settles at the quoted rate (see [`crate::services::quotes`]).
+//! SEP-12 customer records are not consulted; `sender_id` and `receiver_id`
+//! are stored as given.
+
+use crate::db::models::{Transaction, TransactionStatus};
+use crate::db::queries;
+use crate::domain::{DomainEvent, StellarAddress};
+use crate::error::AppError;
+use crate::ports::{Delivery, EventStream};
+use crate::services::amount_limits::{AmountCheck, AmountLimits};
+use crate::services::sep10::Sep10Config;
+use anyhow::Context;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use sqlx::types::BigDecimal;
+use sqlx::PgPool;
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// `callback_type` of transactions received through SEP-31.
+pub const CALLBACK_TYPE: &str = "sep31";
+
+const MAX_CUSTOMER_ID_LEN: usize = 255;
+const CALLBACK_TIMEOUT_SECS: u64 = 10;
+
+#[derive(Debug, Clone)]
+pub struct Sep31Config {
+    pub receiving_account: StellarAddress,
+}
+
+impl Sep31Config {
+    pub fn from_env() -> anyhow::Result<Self> {
+        let account = std::env::var("SEP31_RECEIVING_ACCOUNT")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .context("SEP31_RECEIVING_ACCOUNT is not set")?;
+        let receiving_account = StellarAddress::parse(account.trim())
+            .map_err(|e| anyhow::anyhow!("SEP31_RECEIVING_ACCOUNT {e}"))?;
+        if receiving_account.muxed_id().is_some() {
+            anyhow::bail!("SEP31_RECEIVING_ACCOUNT must be a G... account");
+        }
+        Ok(Self { receiving_account })
+    }
+}
+
+/// Body of `POST /sep31/transactions`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PostTransactionRequest {
+    /// Decimal string, in `asset_code`.
+    pub amount: String,
+    pub asset_code: String,
+    pub sender_id: Option<String>,
+    pub receiver_id: Option<String>,
+    pub quote_id: Option<String>,
+    pub lang: Option<String>,
+}
+
+/// `201` body of `POST /sep31/transactions`: where and how to pay.
+#[derive(Debug, Clone, Serialize)]
+pub struct PostTransactionResponse {
+    pub id: Uuid,
+    pub stellar_account_id: String,
+    pub stellar_memo_type: &'static str,
+    pub stellar_memo: String,
+}
+
+/// Body of `PUT /sep31/transactions/:id/callback`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CallbackRequest {
+    pub url: String,
+}
+
+impl PostTransactionRequest {
+    /// Amount, which must be positive and within `limits`.
+    pub fn amount(&self, limits: &AmountLimits) -> Result<BigDecimal, AppError> {
+        let raw = self.amount.trim();
+        let amount = BigDecimal::from_str(raw)
+            .map_err(|_| AppError::BadRequest(format!("invalid amount: {raw}")))?;
+        if amount <= BigDecimal::from(0) {
+            return Err(AppError::BadRequest("amount must be positive".to_string()));
+        }
+        match limits.check(&amount) {
+            AmountCheck::WithinLimits => Ok(amount),
+            AmountCheck::BelowMinimum => Err(AppError::BadRequest(
+                "amount is below the asset minimum".to_string(),
+            )),
+            AmountCheck::AboveMaximum => Err(AppError::BadRequest(
+                "amount is above the asset maximum".to_string(),
+            )),
+        }
+    }
+
+    /// The SEP-38 quote the payment is made at, if any.
+    pub fn quote_id(&self) -> Result<Option<Uuid>, AppError> {
+        self.quote_id
+            .as_deref()
+            .map(|id| {
+                Uuid::parse_str(id.trim())
+                    .map_err(|_| AppError::BadRequest(format!("invalid quote_id: {id}")))
+            })
+            .transpose()
+    }
+
+    /// Everything but the amount, asset and quote, which are checked against
+    /// the database.
+    pub fn validate(&self) -> Result<(), AppError> {
+        self.quote_id()?;
+        for (field, value) in [
+            ("sender_id", &self.sender_id),
+            ("receiver_id", &self.receiver_id),
+        ] {
+            if value
+                .as_ref()
+                .is_some_and(|v| v.len() > MAX_CUSTOMER_ID_LEN)
+            {
+                return Err(AppError::BadRequest(format!(
+                    "{field} is longer than {MAX_CUSTOMER_ID_LEN} characters"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The pending transaction recorded for a SEP-31 payment from `sender`
+/// (the sending anchor's account), with a random `id` memo to match its
+/// payment by.
+pub fn new_transaction(
+    sender: &StellarAddress,
+    asset_code: &str,
+    amount: BigDecimal,
+    request: &PostTransactionRequest,
+) -> Transaction {
+    let memo = (rand::random::<u64>() >> 1).to_string();
+    let metadata = serde_json::json!({
+        "sep31": {
+            "sender_id": request.sender_id,
+            "receiver_id": request.receiver_id,
+            "quote_id": request.quote_id.as_deref().map(str::trim),
+            "lang": request.lang,
+        }
+    });
+    Transaction::new(
+        sender.account().to_string(),
+        amount,
+        asset_code.to_string(),
+        None,
+        Some(CALLBACK_TYPE.to_string()),
+        None,
+        Some(memo),
+        Some("id".to_string()),
+        Some(metadata),
+    )
+    .with_own_anchor_id()
+}
+
+/// Whether `tx` was received through SEP-31 from `account`.
+pub fn is_sent_by(tx: &Transaction, account: &str) -> bool {
+    tx.callback_type.as_deref() == Some(CALLBACK_TYPE) && tx.stellar_account == account
+}
+
+/// SEP-31 status of `tx`; see the table in the module docs.
+pub fn status(tx: &Transaction) -> &'static str {
+    match tx.status {
+        TransactionStatus::Pending if tx.stellar_tx_hash.is_none() => "pending_sender",
+        TransactionStatus::Pending => "pending_stellar",
+        TransactionStatus::Processing
+        | TransactionStatus::ComplianceReview
+        | TransactionStatus::RefundPending => "pending_receiver",
+        TransactionStatus::Completed => "completed",
+        TransactionStatus::Failed | TransactionStatus::Dlq => "error",
+    }
+}
+
+fn status_message(tx: &Transaction) -> Option<&'static str> {
+    match tx.status {
+        TransactionStatus::ComplianceReview => Some("held for compliance review"),
+        TransactionStatus::RefundPending => Some("amount below the asset minimum; refund pending"),
+        _ => None,
+    }
+}
+
+/// A transaction as SEP-31 presents it.
+#[derive(Debug, Clone, Serialize)]
+pub struct Sep31Transaction {
+    pub id: Uuid,
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_message: Option<&'static str>,
+    pub amount_in: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount_fee: Option<String>,
+    pub stellar_account_id: String,
+    pub stellar_memo_type: String,
+    pub stellar_memo: String,
+    pub started_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stellar_transaction_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote_id: Option<String>,
+}
+
+impl Sep31Transaction {
+    pub fn new(tx: &Transaction, config: &Sep31Config) -> Self {
+        Self {
+            id: tx.id,
+            status: status(tx),
+            status_message: status_message(tx),
+            amount_in: tx.amount.to_string(),
+            amount_fee: tx.fee_amount.as_ref().map(ToString::to_string),
+            stellar_account_id: config.receiving_account.account().to_string(),
+            stellar_memo_type: tx.memo_type.clone().unwrap_or_default(),
+            stellar_memo: tx.memo.clone().unwrap_or_default(),
+            started_at: tx.created_at,
+            completed_at: (tx.status == TransactionStatus::Completed).then_some(tx.updated_at),
+            stellar_transaction_id: tx.stellar_tx_hash.clone(),
+            quote_id: tx
+                .metadata
+                .as_ref()
+                .and_then(|m| m["sep31"]["quote_id"].as_str())
+                .map(str::to_string),
+        }
+    }
+}
+
+/// The registered callback URL of `tx`, if any.
+pub fn callback_url(tx: &Transaction) -> Option<&str> {
+    tx.metadata
+        .as_ref()?
+        .get("sep31")?
+        .get("callback_url")?
+        .as_str()
+}
+
+/// Callbacks go to HTTPS URLs only.
+pub fn validate_callback_url(url: &str) -> Result<url::Url, AppError> {
+    let parsed = url::Url::parse(url)
+        .map_err(|_| AppError::BadRequest(format!("invalid callback url: {url}")))?;
+    if parsed.scheme() != "https" || parsed.host_str().is_none() {
+        return Err(AppError::BadRequest(
+            "callback url must be an https URL".to_string(),
+        ));
+    }
+    Ok(parsed)
+}
+
+/// Store `url` as the callback of transaction `tx`.
+pub async fn register_callback(
+    pool: &PgPool,
+    tx: &Transaction,
+    url: &url::Url,
+    actor: &str,
+) -> Result<Transaction, AppError> {
+    let mut sep31 = tx
+        .metadata
+        .as_ref()
+        .and_then(|m| m.get("sep31"))
+        .cloned()
+        .unwrap_or_else(|| serde_json::json!({}));
+    sep31["callback_url"] = serde_json::json!(url.as_str());
+    let patch = serde_json::json!({ "sep31": sep31 });
+    Ok(queries::update_transaction_metadata(pool, tx.id, &patch, false, actor).await?)
+}
+
+/// `Signature` header value for a callback of `body` to `host` at `now`.
+pub fn callback_signature(signer: &Sep10Config, host: &str, body: &str, now: i64) -> String {
+    let signature = signer.sign_message(format!("{now}.{host}.{body}").as_bytes());
+    format!("t={now}, s={}", STANDARD.encode(signature))
+}
+
+/// Delivers SEP-31 status callbacks to sending anchors.
+pub struct Sep31CallbackNotifier {
+    pool: PgPool,
+    config: Sep31Config,
+    signer: Sep10Config,
+    http: reqwest::Client,
+}
+
+impl Sep31CallbackNotifier {
+    pub fn new(pool: PgPool, config: Sep31Config, signer: Sep10Config) -> Self {
+        Self {
+            pool,
+            config,
+            signer,
+            http: reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(CALLBACK_TIMEOUT_SECS))
+                .build()
+                .expect("failed to build reqwest client"),
+        }
+    }
+
+    /// Notify on every status change in `events` until the stream ends.
+    pub async fn forward_events(&self, mut events: EventStream<DomainEvent>) {
+        while let Some(delivery) = events.next().await {
+            match delivery {
+                Delivery::Event(DomainEvent::StatusChanged { transaction_id, .. }) => {
+                    if let Err(e) = self.notify(transaction_id).await {
+                        tracing::warn!(
+                            %transaction_id,
+                            error = %e,
+                            "SEP-31 callback failed"
+                        );
+                    }
+                }
+                Delivery::Event(_) => {}
+                Delivery::Lagged(n) => {
+                    tracing::warn!(skipped = n, "SEP-31 callbacks lagged");
+                }
+            }
+        }
+    }
+
+    async fn notify(&self, transaction_id: Uuid) -> anyhow::Result<()> {
+        let tx = queries::get_transaction(&self.pool, transaction_id).await?;
+        if tx.callback_type.as_deref() != Some(CALLBACK_TYPE) {
+            return Ok(());
+        }
+        let Some(url) = callback_url(&tx) else {
+            return Ok(());
+        };
+        let url = url::Url::parse(url)?;
+        let host = url.host_str().context("callback url has no host")?;
+
+        let body = serde_json::to_string(
+            &serde_json::json!({ "transaction": Sep31Transaction::new(&tx, &self.config) }),
+        )?;
+        let signature = callback_signature(&self.signer, host, &body, Utc::now().timestamp());
+        let response = self
+            .http
+            .post(url.clone())
+            .header("Content-Type", "application/json")
+            .header("Signature", signature)
+            .body(body)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            anyhow::bail!("callback answered {}", response.status());
+        }
+        tracing::info!(%transaction_id, status = status(&tx), "SEP-31 callback delivered");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
+
+    const SENDER: &str = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+    const RECEIVER: &str = "GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H";
+
+    fn request() -> PostTransactionRequest {
+        PostTransactionRequest {
+            amount: "100.50".to_string(),
+            asset_code: "USDC".to_string(),
+            sender_id: Some("sender-1".to_string()),
+            receiver_id: Some("receiver-1".to_string()),
+            quote_id: None,
+            lang: None,
+        }
+    }
+
+    fn transaction() -> Transaction {
+        let sender = StellarAddress::parse(SENDER).unwrap();
+        new_transaction(&sender, "USDC", BigDecimal::from(100), &request())
+    }
+
+    #[test]
+    fn test_request_validation() {
+        let limits = AmountLimits {
+            min_amount: Some(BigDecimal::from(10)),
+            max_amount: None,
+        };
+        assert!(request().validate().is_ok());
+        assert!(request().amount(&limits).is_ok());
+
+        let mut req = request();
+        req.amount = "5".to_string();
+        assert!(req.amount(&limits).is_err());
+        req.amount = "abc".to_string();
+        assert!(req.amount(&limits).is_err());
+
+        let mut req = request();
+        req.quote_id = Some("q-1".to_string());
+        assert!(req.validate().is_err());
+        req.quote_id = Some(Uuid::new_v4().to_string());
+        assert!(req.validate().is_ok());
+        let mut req = request();
+        req.sender_id = Some("x".repeat(256));
+        assert!(req.validate().is_err());
+    }
+
+    #[test]
+    fn test_new_transaction_is_matched_by_memo() {
+        let tx = transaction();
+        assert_eq!(tx.status, TransactionStatus::Pending);
+        assert_eq!(tx.memo_type.as_deref(), Some("id"));
+        assert!(tx.memo.as_deref().unwrap().parse::<i64>().is_ok());
+        assert!(is_sent_by(&tx, SENDER));
+        assert!(!is_sent_by(&tx, RECEIVER));
+        assert_eq!(
+            tx.metadata.as_ref().unwrap()["sep31"]["sender_id"],
+            "sender-1"
+        );
+    }
+
+    #[test]
+    fn test_status_mapping() {
+        let mut tx = transaction();
+        assert_eq!(status(&tx), "pending_sender");
+        tx.stellar_tx_hash = Some("ab".repeat(32));
+        assert_eq!(status(&tx), "pending_stellar");
+        tx.status = TransactionStatus::ComplianceReview;
+        assert_eq!(status(&tx), "pending_receiver");
+        tx.status = TransactionStatus::Completed;
+        assert_eq!(status(&tx), "completed");
+        tx.status = TransactionStatus::Dlq;
+        assert_eq!(status(&tx), "error");
+    }
+
+    #[test]
+    fn test_view_uses_receiving_account_and_memo() {
+        let config = Sep31Config {
+            receiving_account: StellarAddress::parse(RECEIVER).unwrap(),
+        };
+        let mut tx = transaction();
+        tx.status = TransactionStatus::Completed;
+        let view = Sep31Transaction::new(&tx, &config);
+        assert_eq!(view.stellar_account_id, RECEIVER);
+        assert_eq!(Some(view.stellar_memo.as_str()), tx.memo.as_deref());
+        assert_eq!(view.completed_at, Some(tx.updated_at));
+        assert_eq!(view.amount_in, "100");
+    }
+
+    #[test]
+    fn test_callback_url_must_be_https() {
+        assert!(validate_callback_url("https://sender.example.com/sep31").is_ok());
+        assert!(validate_callback_url("http://sender.example.com/sep31").is_err());
+        assert!(validate_callback_url("not a url").is_err());
+
+        let mut tx = transaction();
+        assert_eq!(callback_url(&tx), None);
+        tx.metadata.as_mut().unwrap()["sep31"]["callback_url"] =
+            serde_json::json!("https://sender.example.com/sep31");
+        assert_eq!(callback_url(&tx), Some("https://sender.example.com/sep31"));
+    }
+
+    #[test]
+    fn test_callback_signature_verifies() {
+        let signer = Sep10Config::new(
+            [3; 32],
+            "anchor.example.com",
+            "Test SDF Network ; September 2015",
+            "j".repeat(32),
+        )
+        .unwrap();
+        let header = callback_signature(&signer, "sender.example.com", "{}", 1_700_000_000);
+        let (t, s) = header.split_once(", s=").unwrap();
+        assert_eq!(t, "t=1700000000");
+
+        let key = Ed25519KeyPair::from_seed_unchecked(&[3; 32]).unwrap();
+        UnparsedPublicKey::new(&ED25519, key.public_key().as_ref())
+            .verify(
+                b"1700000000.sender.example.com.{}",
+                &STANDARD.decode(s).unwrap(),
+            )
+            .unwrap();
+    }
+}
@@ -0,0 +1,430 @@
+//! SEP-6 programmatic deposit and withdrawal.
+//!
+//! What is offered comes from the SEP-6 asset registry
+//! ([`Sep6Asset`], table `sep6_assets`): per asset, whether deposits and
+//! withdrawals are open, the fixed and percentage fee, the off-chain
+//! instructions shown to depositors and the accepted withdrawal `type`s. An
+//! asset is only offered while it is also registered and enabled in
+//! `assets`, whose `min_amount` / `max_amount` apply. `GET /sep6/info`
+//! describes the registry in SEP-6 terms.
+//!
+//! A wallet holding a SEP-10 token (see [`crate::services::sep10`]) calls
+//! `GET /sep6/deposit` or `GET /sep6/withdraw`. Like a SEP-24 request (see
+//! [`crate::services::sep24`]) this records a `pending` row in
+//! `transactions` with `callback_type` `deposit` or `withdrawal` and a fresh
+//! `id` memo, here with `callback_status` `pending_user_transfer_start` and
+//! the fee of the asset as `fee_amount`. From there it is processed like
+//! any other pending transaction. Deposits answer with the asset's
+//! instructions and the memo as payment reference; withdrawals with
+//! `SEP6_WITHDRAW_ACCOUNT` and the memo to pay it with.
+//!
+//! | Env var                 | Default  | Meaning                                |
+//! |-------------------------|----------|----------------------------------------|
+//! | `SEP6_WITHDRAW_ACCOUNT` | required | `G...` account withdrawals are paid to |
+//!
+//! `/transaction`, `/transactions`, `/fee` and the `*-exchange` endpoints
+//! are not implemented.
+
+use crate::db::models::{Sep6Asset, Transaction};
+use crate::domain::StellarAddress;
+use crate::error::AppError;
+use crate::services::amount_limits::AmountLimits;
+use crate::services::sep24::InteractiveRequest;
+use anyhow::Context;
+use bigdecimal::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use sqlx::types::BigDecimal;
+use uuid::Uuid;
+
+/// SEP-6 status of a transaction waiting for the user's transfer.
+pub const STATUS_PENDING_USER_TRANSFER_START: &str = "pending_user_transfer_start";
+
+const MAX_TYPE_LEN: usize = 32;
+const MAX_DEST_LEN: usize = 255;
+/// Stellar amounts have seven decimal places.
+const AMOUNT_SCALE: i64 = 7;
+
+#[derive(Debug, Clone)]
+pub struct Sep6Config {
+    pub withdraw_account: StellarAddress,
+}
+
+impl Sep6Config {
+    pub fn from_env() -> anyhow::Result<Self> {
+        let account = std::env::var("SEP6_WITHDRAW_ACCOUNT")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .context("SEP6_WITHDRAW_ACCOUNT is not set")?;
+        let withdraw_account = StellarAddress::parse(account.trim())
+            .map_err(|e| anyhow::anyhow!("SEP6_WITHDRAW_ACCOUNT {e}"))?;
+        if withdraw_account.muxed_id().is_some() {
+            anyhow::bail!("SEP6_WITHDRAW_ACCOUNT must be a G... account");
+        }
+        Ok(Self { withdraw_account })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sep6Kind {
+    Deposit,
+    Withdraw,
+}
+
+impl Sep6Kind {
+    /// `callback_type` the transaction is stored with.
+    pub fn callback_type(&self) -> &'static str {
+        match self {
+            Sep6Kind::Deposit => "deposit",
+            Sep6Kind::Withdraw => "withdrawal",
+        }
+    }
+
+    /// Whether the registry entry accepts this direction.
+    pub fn is_enabled(&self, asset: &Sep6Asset) -> bool {
+        match self {
+            Sep6Kind::Deposit => asset.deposit_enabled,
+            Sep6Kind::Withdraw => asset.withdraw_enabled,
+        }
+    }
+}
+
+/// Query of `GET /sep6/deposit` and `GET /sep6/withdraw`: the SEP-24 fields
+/// plus `type` and, for withdrawals, the off-chain destination.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TransferRequest {
+    #[serde(flatten)]
+    pub transfer: InteractiveRequest,
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub dest: Option<String>,
+    pub dest_extra: Option<String>,
+}
+
+impl TransferRequest {
+    /// The requested `type`. Withdrawals of an asset with registered types
+    /// must name one of them.
+    pub fn transfer_type(
+        &self,
+        kind: Sep6Kind,
+        asset: &Sep6Asset,
+    ) -> Result<Option<String>, AppError> {
+        let requested = self
+            .kind
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty());
+        if kind == Sep6Kind::Withdraw && !asset.withdraw_types.is_empty() {
+            let Some(requested) = requested else {
+                return Err(AppError::BadRequest(format!(
+                    "type is required, one of: {}",
+                    asset.withdraw_types.join(", ")
+                )));
+            };
+            if !asset.withdraw_types.iter().any(|t| t == requested) {
+                return Err(AppError::BadRequest(format!(
+                    "unsupported type {requested}, expected one of: {}",
+                    asset.withdraw_types.join(", ")
+                )));
+            }
+        }
+        match requested {
+            Some(t)
+                if t.len() > MAX_TYPE_LEN
+                    || !t
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') =>
+            {
+                Err(AppError::BadRequest(format!("invalid type: {t}")))
+            }
+            other => Ok(other.map(str::to_string)),
+        }
+    }
+
+    /// `dest` and `dest_extra`, bounded in length.
+    pub fn destination(&self) -> Result<(Option<String>, Option<String>), AppError> {
+        let field = |name: &str, value: &Option<String>| {
+            let value = value.as_deref().map(str::trim).filter(|v| !v.is_empty());
+            match value {
+                Some(v) if v.len() > MAX_DEST_LEN => Err(AppError::BadRequest(format!(
+                    "{name} must be at most {MAX_DEST_LEN} characters"
+                ))),
+                other => Ok(other.map(str::to_string)),
+            }
+        };
+        Ok((
+            field("dest", &self.dest)?,
+            field("dest_extra", &self.dest_extra)?,
+        ))
+    }
+}
+
+/// Amount limits of a registry entry.
+pub fn limits(asset: &Sep6Asset) -> AmountLimits {
+    AmountLimits {
+        min_amount: asset.min_amount.clone(),
+        max_amount: asset.max_amount.clone(),
+    }
+}
+
+/// Fee on `amount`: the fixed fee plus the percentage, to seven decimals.
+/// Refused when it would swallow the whole amount.
+pub fn fee(asset: &Sep6Asset, amount: &BigDecimal) -> Result<BigDecimal, AppError> {
+    let fee = (&asset.fee_fixed + amount * &asset.fee_percent / BigDecimal::from(100))
+        .with_scale(AMOUNT_SCALE);
+    if &fee >= amount {
+        return Err(AppError::BadRequest(format!(
+            "amount does not cover the fee of {fee}"
+        )));
+    }
+    Ok(fee)
+}
+
+/// The pending transaction recorded for a deposit or withdrawal. The memo is
+/// a random `id` memo the transfer is matched by.
+pub fn new_transaction(
+    kind: Sep6Kind,
+    account: &StellarAddress,
+    asset_code: &str,
+    amount: BigDecimal,
+    fee: BigDecimal,
+    details: Value,
+) -> Transaction {
+    let memo = (rand::random::<u64>() >> 1).to_string();
+    Transaction::new(
+        account.account().to_string(),
+        amount,
+        asset_code.to_string(),
+        None,
+        Some(kind.callback_type().to_string()),
+        Some(STATUS_PENDING_USER_TRANSFER_START.to_string()),
+        Some(memo),
+        Some("id".to_string()),
+        Some(json!({ "sep6": details })),
+    )
+    .with_muxed_id(account.muxed_id())
+    .with_fee_amount(Some(fee))
+    .with_own_anchor_id()
+}
+
+fn number(value: &BigDecimal) -> Option<f64> {
+    value.to_f64()
+}
+
+/// `200` body of `GET /sep6/deposit`.
+#[derive(Debug, Clone, Serialize)]
+pub struct DepositResponse {
+    pub how: String,
+    pub id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_amount: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_amount: Option<f64>,
+    pub fee_fixed: Option<f64>,
+    pub fee_percent: Option<f64>,
+    pub extra_info: Value,
+}
+
+impl DepositResponse {
+    pub fn new(asset: &Sep6Asset, tx: &Transaction) -> Self {
+        let memo = tx.memo.clone().unwrap_or_default();
+        Self {
+            how: asset.deposit_instructions.clone(),
+            id: tx.id,
+            min_amount: asset.min_amount.as_ref().and_then(number),
+            max_amount: asset.max_amount.as_ref().and_then(number),
+            fee_fixed: number(&asset.fee_fixed),
+            fee_percent: number(&asset.fee_percent),
+            extra_info: json!({ "message": format!("Quote reference {memo} with the transfer") }),
+        }
+    }
+}
+
+/// `200` body of `GET /sep6/withdraw`.
+#[derive(Debug, Clone, Serialize)]
+pub struct WithdrawResponse {
+    pub account_id: String,
+    pub memo_type: &'static str,
+    pub memo: String,
+    pub id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_amount: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_amount: Option<f64>,
+    pub fee_fixed: Option<f64>,
+    pub fee_percent: Option<f64>,
+}
+
+impl WithdrawResponse {
+    pub fn new(config: &Sep6Config, asset: &Sep6Asset, tx: &Transaction) -> Self {
+        Self {
+            account_id: config.withdraw_account.account().to_string(),
+            memo_type: "id",
+            memo: tx.memo.clone().unwrap_or_default(),
+            id: tx.id,
+            min_amount: asset.min_amount.as_ref().and_then(number),
+            max_amount: asset.max_amount.as_ref().and_then(number),
+            fee_fixed: number(&asset.fee_fixed),
+            fee_percent: number(&asset.fee_percent),
+        }
+    }
+}
+
+/// `GET /sep6/info` body for the registry `assets`.
+pub fn info(assets: &[Sep6Asset]) -> Value {
+    let mut deposit = Map::new();
+    let mut withdraw = Map::new();
+    for asset in assets {
+        let mut common = json!({
+            "authentication_required": true,
+            "fee_fixed": number(&asset.fee_fixed),
+            "fee_percent": number(&asset.fee_percent),
+        });
+        if let Some(min) = asset.min_amount.as_ref().and_then(number) {
+            common["min_amount"] = json!(min);
+        }
+        if let Some(max) = asset.max_amount.as_ref().and_then(number) {
+            common["max_amount"] = json!(max);
+        }
+
+        let mut entry = common.clone();
+        entry["enabled"] = json!(asset.deposit_enabled);
+        deposit.insert(asset.asset_code.clone(), entry);
+
+        let mut entry = common;
+        entry["enabled"] = json!(asset.withdraw_enabled);
+        let types: Map<String, Value> = asset
+            .withdraw_types
+            .iter()
+            .map(|t| (t.clone(), json!({ "fields": {} })))
+            .collect();
+        entry["types"] = Value::Object(types);
+        withdraw.insert(asset.asset_code.clone(), entry);
+    }
+    json!({
+        "deposit": deposit,
+        "withdraw": withdraw,
+        "fee": { "enabled": false },
+        "transactions": { "enabled": false },
+        "transaction": { "enabled": false },
+        "features": { "account_creation": false, "claimable_balances": false },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn asset(withdraw_types: &[&str]) -> Sep6Asset {
+        Sep6Asset {
+            asset_code: "USDC".to_string(),
+            deposit_enabled: true,
+            withdraw_enabled: false,
+            fee_fixed: BigDecimal::from_str("1.5").unwrap(),
+            fee_percent: BigDecimal::from_str("0.25").unwrap(),
+            deposit_instructions: "Wire to IBAN DE00 0000".to_string(),
+            withdraw_types: withdraw_types.iter().map(ToString::to_string).collect(),
+            min_amount: Some(BigDecimal::from(10)),
+            max_amount: None,
+        }
+    }
+
+    fn request(kind: Option<&str>) -> TransferRequest {
+        TransferRequest {
+            transfer: InteractiveRequest {
+                asset_code: "USDC".to_string(),
+                account: None,
+                amount: Some("100".to_string()),
+                lang: None,
+            },
+            kind: kind.map(str::to_string),
+            dest: None,
+            dest_extra: None,
+        }
+    }
+
+    #[test]
+    fn test_query_string_deserializes_into_transfer_request() {
+        let uri: axum::http::Uri = "/sep6/withdraw?asset_code=USDC&amount=25&type=SEPA&dest=DE89"
+            .parse()
+            .unwrap();
+        let axum::extract::Query(req) =
+            axum::extract::Query::<TransferRequest>::try_from_uri(&uri).unwrap();
+        assert_eq!(req.transfer.asset_code, "USDC");
+        assert_eq!(req.transfer.amount.as_deref(), Some("25"));
+        assert_eq!(req.kind.as_deref(), Some("SEPA"));
+        assert_eq!(req.dest.as_deref(), Some("DE89"));
+    }
+
+    #[test]
+    fn test_withdraw_type_must_be_registered() {
+        let asset = asset(&["bank_account", "cash"]);
+        assert!(request(None)
+            .transfer_type(Sep6Kind::Withdraw, &asset)
+            .is_err());
+        assert!(request(Some("crypto"))
+            .transfer_type(Sep6Kind::Withdraw, &asset)
+            .is_err());
+        assert_eq!(
+            request(Some("cash"))
+                .transfer_type(Sep6Kind::Withdraw, &asset)
+                .unwrap(),
+            Some("cash".to_string())
+        );
+        assert_eq!(
+            request(None)
+                .transfer_type(Sep6Kind::Deposit, &asset)
+                .unwrap(),
+            None
+        );
+        assert!(request(Some("bad type"))
+            .transfer_type(Sep6Kind::Deposit, &asset)
+            .is_err());
+    }
+
+    #[test]
+    fn test_fee_is_fixed_plus_percentage_and_below_amount() {
+        let asset = asset(&[]);
+        assert_eq!(
+            fee(&asset, &BigDecimal::from(100)).unwrap(),
+            BigDecimal::from_str("1.75").unwrap()
+        );
+        assert!(fee(&asset, &BigDecimal::from_str("1.5").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_info_describes_both_directions() {
+        let info = info(&[asset(&["bank_account"])]);
+        assert_eq!(info["deposit"]["USDC"]["enabled"], true);
+        assert_eq!(info["deposit"]["USDC"]["min_amount"], 10.0);
+        assert_eq!(info["deposit"]["USDC"]["fee_fixed"], 1.5);
+        assert!(info["deposit"]["USDC"].get("max_amount").is_none());
+        assert_eq!(info["withdraw"]["USDC"]["enabled"], false);
+        assert!(info["withdraw"]["USDC"]["types"]["bank_account"].is_object());
+        assert_eq!(info["transactions"]["enabled"], false);
+    }
+
+    #[test]
+    fn test_new_transaction_records_fee_and_status() {
+        let account =
+            StellarAddress::parse("GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ")
+                .unwrap();
+        let tx = new_transaction(
+            Sep6Kind::Withdraw,
+            &account,
+            "USDC",
+            BigDecimal::from(100),
+            BigDecimal::from(2),
+            json!({ "type": "bank_account" }),
+        );
+        assert_eq!(tx.callback_type.as_deref(), Some("withdrawal"));
+        assert_eq!(
+            tx.callback_status.as_deref(),
+            Some(STATUS_PENDING_USER_TRANSFER_START)
+        );
+        assert_eq!(tx.fee_amount, Some(BigDecimal::from(2)));
+        assert_eq!(tx.memo_type.as_deref(), Some("id"));
+        assert_eq!(tx.metadata.unwrap()["sep6"]["type"], "bank_account");
+    }
+}
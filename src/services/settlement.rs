@@ -1,10 +1,20 @@
-use crate::db::models::{Asset, Settlement};
+use crate::db::models::{Asset, Sep38Quote, Settlement, SettlementReversal};
 use crate::db::queries;
+use crate::domain::DomainEvent;
+use crate::domain::StellarAddress;
 use crate::error::AppError;
+use crate::ports::{EventBus, TransactionSigner};
+use crate::services::settlement_events::{SettlementEvent, SettlementEventKind, CLOSED_STATUSES};
+use crate::services::submissions::{SubmissionError, SubmissionLedger};
+use crate::stellar::builder::{format_stroops, to_stroops};
+use crate::stellar::client::{asset_string, parse_asset};
+use crate::stellar::xdr::{self, Memo, OperationResult, PathPayment, PathPaymentKind};
+use crate::stellar::{HorizonClient, HorizonError, PathRecord};
 use bigdecimal::BigDecimal;
 use chrono::Utc;
 use opentelemetry::metrics::Histogram;
-use sqlx::PgPool;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Transaction};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::time::timeout;
@@ -39,6 +49,166 @@ fn map_db_err(e: sqlx::Error) -> AppError {
     }
 }
 
+/// Default tolerance on a path's quoted amount: 1%.
+pub const DEFAULT_PATH_SLIPPAGE_BPS: u32 = 100;
+
+/// How long a path payment transaction stays valid.
+const PATH_PAYMENT_TIMEOUT_SECS: i64 = 60;
+
+/// What [`SettlementService::execute_path_payment`] needs to reach the
+/// network.
+#[derive(Clone)]
+pub struct PathPaymentConfig {
+    pub horizon: HorizonClient,
+    pub signer: Arc<dyn TransactionSigner>,
+    pub network_passphrase: String,
+    /// How far the executed amount may fall short of (strict send) or
+    /// exceed (strict receive) the path's quote, in basis points.
+    pub slippage_bps: u32,
+}
+
+impl PathPaymentConfig {
+    /// Signs with [`crate::adapters::transaction_signer`] for the network in
+    /// `STELLAR_NETWORK_PASSPHRASE`, allowing `SETTLEMENT_PATH_SLIPPAGE_BPS`
+    /// (default [`DEFAULT_PATH_SLIPPAGE_BPS`], below 10000) of slippage.
+    pub fn from_env(horizon: HorizonClient) -> anyhow::Result<Self> {
+        let signer = crate::adapters::transaction_signer()
+            .ok_or_else(|| anyhow::anyhow!("no transaction signer configured"))?;
+        let network_passphrase = std::env::var("STELLAR_NETWORK_PASSPHRASE")
+            .map_err(|_| anyhow::anyhow!("STELLAR_NETWORK_PASSPHRASE is not set"))?;
+        let slippage_bps = match std::env::var("SETTLEMENT_PATH_SLIPPAGE_BPS") {
+            Ok(raw) => raw
+                .trim()
+                .parse::<u32>()
+                .ok()
+                .filter(|bps| *bps < 10_000)
+                .ok_or_else(|| {
+                    anyhow::anyhow!("SETTLEMENT_PATH_SLIPPAGE_BPS must be below 10000, got '{raw}'")
+                })?,
+            Err(_) => DEFAULT_PATH_SLIPPAGE_BPS,
+        };
+        Ok(Self {
+            horizon,
+            signer,
+            network_passphrase,
+            slippage_bps,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PathPaymentMode {
+    /// Send exactly `amount` of the send asset.
+    StrictSend,
+    /// Deliver exactly `amount` of the destination asset.
+    StrictReceive,
+}
+
+impl From<PathPaymentMode> for PathPaymentKind {
+    fn from(mode: PathPaymentMode) -> Self {
+        match mode {
+            PathPaymentMode::StrictSend => PathPaymentKind::StrictSend,
+            PathPaymentMode::StrictReceive => PathPaymentKind::StrictReceive,
+        }
+    }
+}
+
+/// Pay out a settlement in another asset, converting on-chain.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PathPaymentRequest {
+    pub mode: PathPaymentMode,
+    /// `G...` account receiving the payment.
+    pub destination: String,
+    /// `native` or `CODE:ISSUER`; its code must be the settlement's asset.
+    pub send_asset: String,
+    /// `native` or `CODE:ISSUER`.
+    pub dest_asset: String,
+    /// Of the send asset for strict send, of the destination asset for
+    /// strict receive.
+    pub amount: BigDecimal,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PathPaymentOutcome {
+    pub settlement_id: Uuid,
+    pub mode: PathPaymentMode,
+    pub hash: String,
+    pub ledger: i64,
+    /// Exact amount sent (strict send) or the most that could be (strict
+    /// receive).
+    pub send_amount: String,
+    /// Least amount accepted (strict send) or the exact amount (strict
+    /// receive).
+    pub dest_amount: String,
+    /// What the destination actually received, when Horizon reported it.
+    pub delivered: Option<String>,
+    /// Intermediate assets the payment converted through.
+    pub path: Vec<String>,
+}
+
+/// Widen `quoted` by `bps` in the payer's disfavour: the least a strict
+/// send accepts, rounded down, or the most a strict receive spends,
+/// rounded up.
+fn apply_slippage(kind: PathPaymentKind, quoted: i64, bps: u32) -> i64 {
+    let quoted = i128::from(quoted);
+    let bps = i128::from(bps);
+    let bounded = match kind {
+        PathPaymentKind::StrictSend => quoted * (10_000 - bps) / 10_000,
+        PathPaymentKind::StrictReceive => (quoted * (10_000 + bps) + 9_999) / 10_000,
+    };
+    i64::try_from(bounded).unwrap_or(i64::MAX)
+}
+
+/// The path delivering the most (strict send) or costing the least
+/// (strict receive), with its quoted send and destination stroops.
+fn best_path(kind: PathPaymentKind, paths: Vec<PathRecord>) -> Option<(PathRecord, i64, i64)> {
+    let stroops = |s: &str| s.parse::<BigDecimal>().ok().and_then(|a| to_stroops(&a));
+    let quoted = paths.into_iter().filter_map(|path| {
+        let send = stroops(&path.source_amount)?;
+        let dest = stroops(&path.destination_amount)?;
+        Some((path, send, dest))
+    });
+    match kind {
+        PathPaymentKind::StrictSend => quoted.max_by_key(|(_, _, dest)| *dest),
+        PathPaymentKind::StrictReceive => quoted.min_by_key(|(_, send, _)| *send),
+    }
+}
+
+/// A transaction memo tying the payment to its settlement.
+fn settlement_memo(id: Uuid) -> Memo {
+    let mut hash = [0u8; 32];
+    hash[..16].copy_from_slice(id.as_bytes());
+    Memo::Hash(hash)
+}
+
+fn map_horizon_err(e: HorizonError) -> AppError {
+    match e {
+        HorizonError::TransactionFailed { hash, result } => {
+            AppError::BadRequest(format!("path payment {hash} failed: {result}"))
+        }
+        HorizonError::InvalidTransaction(reason) => {
+            AppError::BadRequest(format!("path payment rejected: {reason}"))
+        }
+        HorizonError::SubmissionTimeout(hash) => AppError::Internal(format!(
+            "path payment {hash} timed out; it will be resolved before any retry"
+        )),
+        other => AppError::Internal(format!("path payment not submitted: {other}")),
+    }
+}
+
+fn map_submission_err(e: SubmissionError) -> AppError {
+    match e {
+        SubmissionError::Horizon(e) => map_horizon_err(e),
+        SubmissionError::Database(e) => AppError::DatabaseError(e.to_string()),
+        SubmissionError::AlreadySubmitted(hash) => {
+            AppError::TransactionConflict(format!("settlement already paid out by {hash}"))
+        }
+        e @ SubmissionError::InFlight(_) => AppError::TransactionConflict(e.to_string()),
+        e @ SubmissionError::Envelope(_) => AppError::Internal(e.to_string()),
+    }
+}
+
 pub struct SettlementService {
     pool: PgPool,
     max_batch_size: usize,
@@ -49,6 +219,12 @@ pub struct SettlementService {
     readiness: Option<Arc<crate::readiness::ReadinessState>>,
     /// Settlement operation duration histogram
     settlement_duration_ms: Histogram<f64>,
+    /// Lifecycle event bus feeding the GraphQL settlement subscriptions
+    events: Option<Arc<dyn EventBus<SettlementEvent>>>,
+    /// Domain event bus that receives `SettlementClosed`
+    domain_events: Option<Arc<dyn EventBus<DomainEvent>>>,
+    /// Horizon access for path payment payouts
+    path_payments: Option<PathPaymentConfig>,
 }
 
 impl SettlementService {
@@ -60,6 +236,9 @@ impl SettlementService {
             health_check_timeout: Duration::from_secs(5),
             readiness: None,
             settlement_duration_ms: crate::metrics::settlement_duration_ms(),
+            events: None,
+            domain_events: None,
+            path_payments: None,
         }
     }
 
@@ -71,6 +250,9 @@ impl SettlementService {
             health_check_timeout: Duration::from_secs(5),
             readiness: None,
             settlement_duration_ms: crate::metrics::settlement_duration_ms(),
+            events: None,
+            domain_events: None,
+            path_payments: None,
         }
     }
 
@@ -83,6 +265,9 @@ impl SettlementService {
             health_check_timeout: Duration::from_secs(5),
             readiness: Some(readiness),
             settlement_duration_ms: crate::metrics::settlement_duration_ms(),
+            events: None,
+            domain_events: None,
+            path_payments: None,
         }
     }
 
@@ -99,9 +284,61 @@ impl SettlementService {
             health_check_timeout: Duration::from_secs(5),
             readiness: Some(readiness),
             settlement_duration_ms,
+            events: None,
+            domain_events: None,
+            path_payments: None,
         }
     }
 
+    /// Publish settlement lifecycle events on `events`.
+    pub fn with_events(mut self, events: Arc<dyn EventBus<SettlementEvent>>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Publish [`DomainEvent::SettlementClosed`] on `events` when a
+    /// settlement reaches a terminal status.
+    pub fn with_domain_events(mut self, events: Arc<dyn EventBus<DomainEvent>>) -> Self {
+        self.domain_events = Some(events);
+        self
+    }
+
+    /// Enable [`Self::execute_path_payment`].
+    pub fn with_path_payments(mut self, config: PathPaymentConfig) -> Self {
+        self.path_payments = Some(config);
+        self
+    }
+
+    /// Publish a lifecycle event. Failures are logged and never fail the
+    /// settlement operation itself: the database row is the source of truth.
+    async fn publish(&self, kind: SettlementEventKind, settlement: &Settlement) {
+        if let Some(ref domain_events) = self.domain_events {
+            if CLOSED_STATUSES.contains(&settlement.status.as_str()) {
+                domain_events.publish(DomainEvent::SettlementClosed {
+                    settlement_id: settlement.id,
+                    asset_code: settlement.asset_code.clone(),
+                    status: settlement.status.clone(),
+                    total_amount: settlement.total_amount.clone(),
+                    tx_count: settlement.tx_count,
+                    at: settlement.updated_at,
+                });
+            }
+        }
+        let Some(ref events) = self.events else {
+            return;
+        };
+        if events.subscriber_count() == 0 {
+            return;
+        }
+        let partner_ids = queries::get_settlement_partner_ids(&self.pool, settlement.id)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!(settlement_id = %settlement.id, "Failed to load settlement partners: {e}");
+                Vec::new()
+            });
+        events.publish(SettlementEvent::new(kind, settlement.clone(), partner_ids));
+    }
+
     /// Check if the settlement service is healthy
     /// Returns Ok(()) if healthy, Err(String) otherwise
     pub async fn check_health(&self) -> Result<(), String> {
@@ -252,6 +489,12 @@ impl SettlementService {
             "Starting settlement"
         );
 
+        // Quoted transactions pay out at the rate locked in by their quote.
+        let unsettled_ids: Vec<Uuid> = unsettled.iter().map(|t| t.id).collect();
+        let quotes = Sep38Quote::for_transactions(&mut *tx, &unsettled_ids)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
         let mut settlements = Vec::with_capacity(batch_count);
 
         for (batch_idx, chunk) in unsettled.chunks(self.max_batch_size).enumerate() {
@@ -278,6 +521,7 @@ impl SettlementService {
                 original_total_amount: None,
                 reviewed_by: None,
                 reviewed_at: None,
+                quoted_payouts: crate::services::quotes::payouts(chunk, &quotes),
             };
 
             let saved = queries::insert_settlement(&mut tx, &settlement)
@@ -308,6 +552,10 @@ impl SettlementService {
 
         queries::invalidate_caches_for_asset(asset_code).await;
 
+        for settlement in &settlements {
+            self.publish(SettlementEventKind::Created, settlement).await;
+        }
+
         // Record metrics for the settle_asset operation
         let duration_ms = start.elapsed().as_millis() as f64;
         self.settlement_duration_ms.record(
@@ -348,9 +596,203 @@ impl SettlementService {
             )));
         }
 
-        queries::update_settlement_status(&self.pool, id, new_status, reason, new_total, actor)
+        let updated =
+            queries::update_settlement_status(&self.pool, id, new_status, reason, new_total, actor)
+                .await
+                .map_err(map_db_err)?;
+
+        if current.status != updated.status {
+            self.publish(SettlementEventKind::StatusChanged, &updated)
+                .await;
+        }
+
+        Ok(updated)
+    }
+
+    /// Void a settlement from any status within `db_tx`, releasing its
+    /// transactions to be settled again by the next run and recording a
+    /// compensating reversal. Only called once a second principal approved
+    /// the void, on the transaction that records the approval; call
+    /// [`Self::publish_voided`] once it commits.
+    pub async fn void(
+        &self,
+        db_tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        reason: &str,
+        proposed_by: &str,
+        approved_by: &str,
+    ) -> Result<(Settlement, SettlementReversal), AppError> {
+        let (voided, reversal) =
+            queries::void_settlement(db_tx, id, reason, proposed_by, approved_by)
+                .await
+                .map_err(map_db_err)?
+                .ok_or_else(|| {
+                    AppError::BadRequest(format!("settlement {id} is already voided"))
+                })?;
+
+        tracing::warn!(
+            settlement_id = %id,
+            reversal_id = %reversal.id,
+            previous_status = %reversal.previous_status,
+            released = reversal.tx_count,
+            proposed_by,
+            approved_by,
+            "Settlement voided"
+        );
+        Ok((voided, reversal))
+    }
+
+    /// Announce a settlement voided by [`Self::void`], after its transaction
+    /// committed.
+    pub async fn publish_voided(&self, voided: &Settlement) {
+        self.publish(SettlementEventKind::StatusChanged, voided)
+            .await;
+    }
+
+    /// Pay out settlement `id` through a path payment, converting its asset
+    /// into `request.dest_asset` on-chain. The best path comes from Horizon's
+    /// `/paths` endpoints and its quote is bounded by the configured
+    /// slippage; the anchor never sends more than the settlement's total.
+    /// Submissions go through the [`SubmissionLedger`] keyed by the
+    /// settlement id, so a settlement is paid out at most once.
+    pub async fn execute_path_payment(
+        &self,
+        id: Uuid,
+        request: &PathPaymentRequest,
+    ) -> Result<PathPaymentOutcome, AppError> {
+        let config = self
+            .path_payments
+            .as_ref()
+            .ok_or_else(|| AppError::Internal("path payments are not configured".to_string()))?;
+        let kind = PathPaymentKind::from(request.mode);
+
+        let settlement = queries::get_settlement(&self.pool, id)
             .await
-            .map_err(map_db_err)
+            .map_err(map_db_err)?;
+        if matches!(settlement.status.as_str(), "voided" | "disputed") {
+            return Err(AppError::BadRequest(format!(
+                "settlement {id} is {}",
+                settlement.status
+            )));
+        }
+
+        let bad_request = |what: &str| AppError::BadRequest(format!("invalid {what}"));
+        let destination =
+            StellarAddress::parse(&request.destination).map_err(|_| bad_request("destination"))?;
+        let send_asset =
+            parse_asset(&request.send_asset).ok_or_else(|| bad_request("send_asset"))?;
+        let dest_asset =
+            parse_asset(&request.dest_asset).ok_or_else(|| bad_request("dest_asset"))?;
+        let amount = to_stroops(&request.amount).ok_or_else(|| bad_request("amount"))?;
+        let send_code = match &send_asset {
+            xdr::Asset::Native => "XLM",
+            xdr::Asset::Credit { code, .. } => code.as_str(),
+        };
+        if send_code != settlement.asset_code {
+            return Err(AppError::BadRequest(format!(
+                "send_asset must be the settlement's asset {}",
+                settlement.asset_code
+            )));
+        }
+        let total = to_stroops(&settlement.total_amount.with_scale(7)).unwrap_or(0);
+
+        let paths = match kind {
+            PathPaymentKind::StrictSend => {
+                config
+                    .horizon
+                    .find_strict_send_paths(&send_asset, amount, std::slice::from_ref(&dest_asset))
+                    .await
+            }
+            PathPaymentKind::StrictReceive => {
+                config
+                    .horizon
+                    .find_strict_receive_paths(
+                        std::slice::from_ref(&send_asset),
+                        &dest_asset,
+                        amount,
+                    )
+                    .await
+            }
+        }
+        .map_err(map_horizon_err)?;
+        let (path, quoted_send, quoted_dest) = best_path(kind, paths).ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "no path from {} to {}",
+                request.send_asset, request.dest_asset
+            ))
+        })?;
+
+        let (send_amount, dest_amount) = match kind {
+            PathPaymentKind::StrictSend => (
+                amount,
+                apply_slippage(kind, quoted_dest, config.slippage_bps),
+            ),
+            PathPaymentKind::StrictReceive => (
+                apply_slippage(kind, quoted_send, config.slippage_bps),
+                amount,
+            ),
+        };
+        if send_amount > total {
+            return Err(AppError::BadRequest(format!(
+                "path payment could send {} {}, more than the settlement's {}",
+                format_stroops(send_amount),
+                settlement.asset_code,
+                settlement.total_amount
+            )));
+        }
+        if dest_amount <= 0 {
+            return Err(AppError::BadRequest("path quote is too small".to_string()));
+        }
+
+        let path_assets = path.path_assets().map_err(map_horizon_err)?;
+        let operation = PathPayment {
+            source: None,
+            kind,
+            send_asset,
+            send_amount,
+            destination: destination.public_key(),
+            dest_asset,
+            dest_amount,
+            path: path_assets.clone(),
+        };
+        let submitted = SubmissionLedger::new(
+            self.pool.clone(),
+            config.horizon.clone(),
+            config.network_passphrase.clone(),
+        )
+        .submit(
+            id,
+            config.signer.as_ref(),
+            settlement_memo(id),
+            vec![operation.into()],
+            chrono::Duration::seconds(PATH_PAYMENT_TIMEOUT_SECS),
+        )
+        .await
+        .map_err(map_submission_err)?;
+
+        let delivered = submitted.result.operations.iter().find_map(|op| match op {
+            OperationResult::PathPayment(r) => r.delivered,
+            _ => None,
+        });
+        tracing::info!(
+            settlement_id = %id,
+            hash = %submitted.hash,
+            quoted_send,
+            quoted_dest,
+            send_amount,
+            dest_amount,
+            "Settlement path payment submitted"
+        );
+        Ok(PathPaymentOutcome {
+            settlement_id: id,
+            mode: request.mode,
+            hash: submitted.hash,
+            ledger: submitted.ledger,
+            send_amount: format_stroops(send_amount),
+            dest_amount: format_stroops(dest_amount),
+            delivered: delivered.map(format_stroops),
+            path: path_assets.iter().map(asset_string).collect(),
+        })
     }
 }
 
@@ -368,7 +810,7 @@ mod tests {
             stellar_account: "GABC".to_string(),
             amount: BigDecimal::from_f64(amount).unwrap(),
             asset_code: "USD".to_string(),
-            status: "completed".to_string(),
+            status: crate::db::models::TransactionStatus::Completed,
             created_at: now,
             updated_at: now,
             anchor_transaction_id: None,
@@ -379,6 +821,14 @@ mod tests {
             memo_type: None,
             metadata: None,
             trace_id: None,
+            backfilled: false,
+            stellar_tx_hash: None,
+            ledger: None,
+            closed_at: None,
+            stellar_muxed_id: None,
+            risk_score: None,
+            risk_reasons: None,
+            fee_amount: None,
         }
     }
 
@@ -394,6 +844,58 @@ mod tests {
         assert!(matches!(err, AppError::DatabaseError(_)));
     }
 
+    fn path(send: &str, dest: &str) -> PathRecord {
+        PathRecord {
+            source_asset_type: "native".to_string(),
+            source_asset_code: None,
+            source_asset_issuer: None,
+            source_amount: send.to_string(),
+            destination_asset_type: "native".to_string(),
+            destination_asset_code: None,
+            destination_asset_issuer: None,
+            destination_amount: dest.to_string(),
+            path: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn slippage_never_favours_the_counterparty() {
+        let send = PathPaymentKind::StrictSend;
+        let receive = PathPaymentKind::StrictReceive;
+        assert_eq!(apply_slippage(send, 10_000_000, 100), 9_900_000);
+        assert_eq!(apply_slippage(send, 999, 100), 989);
+        assert_eq!(apply_slippage(receive, 10_000_000, 100), 10_100_000);
+        assert_eq!(apply_slippage(receive, 999, 100), 1_009);
+        assert_eq!(apply_slippage(receive, 7, 0), 7);
+        assert_eq!(apply_slippage(receive, i64::MAX, 100), i64::MAX);
+    }
+
+    #[test]
+    fn best_path_maximises_delivery_or_minimises_cost() {
+        let paths = || {
+            vec![
+                path("10.0000000", "1.1000000"),
+                path("9.0000000", "1.3000000"),
+                path("bogus", "9.0000000"),
+            ]
+        };
+        let (_, send, dest) = best_path(PathPaymentKind::StrictSend, paths()).unwrap();
+        assert_eq!((send, dest), (90_000_000, 13_000_000));
+        let (_, send, _) = best_path(PathPaymentKind::StrictReceive, paths()).unwrap();
+        assert_eq!(send, 90_000_000);
+        assert!(best_path(PathPaymentKind::StrictSend, Vec::new()).is_none());
+    }
+
+    #[test]
+    fn settlement_memo_carries_the_id() {
+        let id = Uuid::new_v4();
+        let Memo::Hash(hash) = settlement_memo(id) else {
+            panic!("expected a hash memo");
+        };
+        assert_eq!(&hash[..16], id.as_bytes());
+        assert_eq!(hash[16..], [0u8; 16]);
+    }
+
     #[test]
     fn valid_transition_allows_expected_paths() {
         assert!(valid_transition("completed", "pending_review"));
@@ -0,0 +1,122 @@
+//! Settlement lifecycle events.
+//!
+//! [`SettlementService`](super::SettlementService) publishes a
+//! [`SettlementEvent`] on `AppState::settlement_events` whenever a settlement
+//! is created or changes status. The GraphQL `settlementUpdated` /
+//! `settlementClosed` subscriptions are fed from that bus, mirroring how
+//! transaction status updates flow through `AppState::domain_events`.
+
+use crate::db::models::Settlement;
+#[cfg(feature = "graphql")]
+use crate::graphql::scalars::UuidScalar;
+use uuid::Uuid;
+
+/// Settlement statuses after which no further transitions are expected.
+pub const CLOSED_STATUSES: &[&str] = &["completed", "voided"];
+
+/// What happened to the settlement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::Enum))]
+pub enum SettlementEventKind {
+    /// A new settlement batch was written by the settlement worker.
+    Created,
+    /// An existing settlement moved to a new status.
+    StatusChanged,
+}
+
+/// A settlement creation or status change.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
+#[cfg_attr(feature = "graphql", graphql(complex))]
+pub struct SettlementEvent {
+    pub kind: SettlementEventKind,
+    pub settlement: Settlement,
+    /// Partners (tenants) whose transactions are included in the settlement.
+    #[cfg_attr(feature = "graphql", graphql(skip))]
+    pub partner_ids: Vec<Uuid>,
+}
+
+#[cfg(feature = "graphql")]
+#[async_graphql::ComplexObject]
+impl SettlementEvent {
+    async fn partner_ids(&self) -> Vec<UuidScalar> {
+        self.partner_ids.iter().copied().map(UuidScalar).collect()
+    }
+}
+
+impl SettlementEvent {
+    pub fn new(kind: SettlementEventKind, settlement: Settlement, partner_ids: Vec<Uuid>) -> Self {
+        Self {
+            kind,
+            settlement,
+            partner_ids,
+        }
+    }
+
+    /// Whether the settlement is in a terminal status.
+    pub fn is_closed(&self) -> bool {
+        CLOSED_STATUSES.contains(&self.settlement.status.as_str())
+    }
+
+    /// Apply the optional subscription filters. `None` matches everything.
+    pub fn matches(&self, asset_code: Option<&str>, partner_id: Option<Uuid>) -> bool {
+        let asset_match = asset_code
+            .map(|a| self.settlement.asset_code == a)
+            .unwrap_or(true);
+        let partner_match = partner_id
+            .map(|p| self.partner_ids.contains(&p))
+            .unwrap_or(true);
+        asset_match && partner_match
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bigdecimal::BigDecimal;
+    use chrono::Utc;
+
+    fn event(status: &str, partner_ids: Vec<Uuid>) -> SettlementEvent {
+        let now = Utc::now();
+        SettlementEvent::new(
+            SettlementEventKind::StatusChanged,
+            Settlement {
+                id: Uuid::new_v4(),
+                asset_code: "USDC".to_string(),
+                total_amount: BigDecimal::from(100),
+                tx_count: 2,
+                period_start: now,
+                period_end: now,
+                status: status.to_string(),
+                created_at: now,
+                updated_at: now,
+                dispute_reason: None,
+                original_total_amount: None,
+                reviewed_by: None,
+                reviewed_at: None,
+                quoted_payouts: None,
+            },
+            partner_ids,
+        )
+    }
+
+    #[test]
+    fn test_is_closed() {
+        assert!(event("completed", vec![]).is_closed());
+        assert!(event("voided", vec![]).is_closed());
+        assert!(!event("disputed", vec![]).is_closed());
+        assert!(!event("pending_review", vec![]).is_closed());
+    }
+
+    #[test]
+    fn test_matches_filters() {
+        let partner = Uuid::new_v4();
+        let ev = event("completed", vec![partner]);
+
+        assert!(ev.matches(None, None));
+        assert!(ev.matches(Some("USDC"), None));
+        assert!(!ev.matches(Some("XLM"), None));
+        assert!(ev.matches(Some("USDC"), Some(partner)));
+        assert!(!ev.matches(None, Some(Uuid::new_v4())));
+    }
+}
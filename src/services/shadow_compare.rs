@@ -0,0 +1,288 @@
+//! Divergence check between a primary and a shadow-written transaction store.
+//!
+//! A `shadow_compare` job walks every transaction created in `{"from", "to"}`
+//! (inclusive UTC days) in both stores and reports:
+//!
+//! - `missing_in_shadow`: in the primary only (shadow write failed or predates
+//!   shadow mode);
+//! - `missing_in_primary`: in the shadow only;
+//! - `mismatched`: in both, with the differing field names.
+//!
+//! The job result carries the counts and up to [`MAX_SAMPLES`] examples; a
+//! cutover is safe once recent windows come back clean. The handler is only
+//! registered when `SHADOW_WRITE_TABLE` is set.
+
+use crate::domain::Transaction;
+use crate::ports::{RepositoryError, TransactionRepository};
+use crate::services::job_runner::{DateRangeParams, JobContext, JobHandler, JobKind};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Rows compared per page (and per checkpoint).
+const PAGE_SIZE: i64 = 500;
+
+/// Divergences kept in the job result; the counts are always complete.
+pub const MAX_SAMPLES: usize = 100;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Divergence {
+    MissingInShadow { id: Uuid },
+    MissingInPrimary { id: Uuid },
+    Mismatched { id: Uuid, fields: Vec<String> },
+}
+
+/// Names of the fields that differ between the two copies of a transaction.
+pub fn diff(primary: &Transaction, shadow: &Transaction) -> Vec<String> {
+    let mut fields = Vec::new();
+    macro_rules! cmp {
+        ($($field:ident),*) => {
+            $(if primary.$field != shadow.$field {
+                fields.push(stringify!($field).to_string());
+            })*
+        };
+    }
+    cmp!(
+        stellar_account,
+        amount,
+        asset_code,
+        status,
+        created_at,
+        updated_at,
+        anchor_transaction_id,
+        callback_type,
+        callback_status,
+        memo,
+        memo_type,
+        metadata
+    );
+    fields
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Pass {
+    /// Primary rows looked up in the shadow.
+    #[default]
+    Primary,
+    /// Shadow rows looked up in the primary.
+    Shadow,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CompareCheckpoint {
+    pass: Pass,
+    offset: i64,
+    checked: i64,
+    missing_in_shadow: i64,
+    missing_in_primary: i64,
+    mismatched: i64,
+    samples: Vec<Divergence>,
+}
+
+impl CompareCheckpoint {
+    fn record(&mut self, divergence: Divergence) {
+        match &divergence {
+            Divergence::MissingInShadow { .. } => self.missing_in_shadow += 1,
+            Divergence::MissingInPrimary { .. } => self.missing_in_primary += 1,
+            Divergence::Mismatched { .. } => self.mismatched += 1,
+        }
+        if self.samples.len() < MAX_SAMPLES {
+            self.samples.push(divergence);
+        }
+    }
+}
+
+/// [`JobHandler`] for `shadow_compare` jobs.
+pub struct ShadowCompareHandler {
+    pub primary: Arc<dyn TransactionRepository>,
+    pub shadow: Arc<dyn TransactionRepository>,
+}
+
+async fn lookup(
+    repo: &dyn TransactionRepository,
+    id: Uuid,
+) -> Result<Option<Transaction>, RepositoryError> {
+    match repo.get_by_id(id).await {
+        Ok(tx) => Ok(Some(tx)),
+        Err(RepositoryError::NotFound(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+impl ShadowCompareHandler {
+    /// Compare the next page of the current pass. Returns `true` once both
+    /// passes are complete.
+    async fn compare_page(
+        &self,
+        cp: &mut CompareCheckpoint,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<bool, RepositoryError> {
+        let (source, other) = match cp.pass {
+            Pass::Primary => (&self.primary, &self.shadow),
+            Pass::Shadow => (&self.shadow, &self.primary),
+        };
+        let page = source
+            .list_created_between(from, to, PAGE_SIZE, cp.offset)
+            .await?;
+
+        for row in &page {
+            match (cp.pass, lookup(other.as_ref(), row.id).await?) {
+                (Pass::Primary, None) => cp.record(Divergence::MissingInShadow { id: row.id }),
+                (Pass::Primary, Some(shadow)) => {
+                    cp.checked += 1;
+                    let fields = diff(row, &shadow);
+                    if !fields.is_empty() {
+                        cp.record(Divergence::Mismatched { id: row.id, fields });
+                    }
+                }
+                (Pass::Shadow, None) => cp.record(Divergence::MissingInPrimary { id: row.id }),
+                // Rows present in both were compared in the first pass.
+                (Pass::Shadow, Some(_)) => {}
+            }
+        }
+
+        if (page.len() as i64) < PAGE_SIZE {
+            if cp.pass == Pass::Shadow {
+                return Ok(true);
+            }
+            cp.pass = Pass::Shadow;
+            cp.offset = 0;
+        } else {
+            cp.offset += PAGE_SIZE;
+        }
+        Ok(false)
+    }
+}
+
+#[async_trait]
+impl JobHandler for ShadowCompareHandler {
+    fn kind(&self) -> JobKind {
+        JobKind::ShadowCompare
+    }
+
+    async fn run(&self, ctx: &mut JobContext) -> anyhow::Result<serde_json::Value> {
+        let range: DateRangeParams = ctx.params()?;
+        let from = range.from.and_time(NaiveTime::MIN).and_utc();
+        let to = (range.to + Duration::days(1))
+            .and_time(NaiveTime::MIN)
+            .and_utc();
+        let mut cp = ctx.checkpoint::<CompareCheckpoint>()?.unwrap_or_default();
+
+        loop {
+            let finished = self.compare_page(&mut cp, from, to).await?;
+            let current = cp.checked + cp.missing_in_shadow + cp.missing_in_primary;
+            ctx.save_progress(current, None, &cp).await?;
+            if finished {
+                break;
+            }
+        }
+
+        let divergent = cp.missing_in_shadow + cp.missing_in_primary + cp.mismatched;
+        if divergent > 0 {
+            tracing::warn!(
+                missing_in_shadow = cp.missing_in_shadow,
+                missing_in_primary = cp.missing_in_primary,
+                mismatched = cp.mismatched,
+                "Shadow store diverges from primary"
+            );
+        }
+        Ok(serde_json::json!({
+            "checked": cp.checked,
+            "missing_in_shadow": cp.missing_in_shadow,
+            "missing_in_primary": cp.missing_in_primary,
+            "mismatched": cp.mismatched,
+            "samples": cp.samples,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::shadow_transaction_repository::tests::{sample_tx, MemoryRepository};
+    use std::sync::Mutex;
+
+    fn repo(rows: Vec<Transaction>) -> Arc<MemoryRepository> {
+        Arc::new(MemoryRepository {
+            rows: Mutex::new(rows),
+            fail_inserts: false,
+        })
+    }
+
+    async fn compare(handler: &ShadowCompareHandler) -> CompareCheckpoint {
+        let now = Utc::now();
+        let (from, to) = (now - Duration::days(1), now + Duration::days(1));
+        let mut cp = CompareCheckpoint::default();
+        while !handler.compare_page(&mut cp, from, to).await.unwrap() {}
+        cp
+    }
+
+    #[test]
+    fn test_diff_lists_changed_fields() {
+        let a = sample_tx();
+        assert!(diff(&a, &a.clone()).is_empty());
+
+        let mut b = a.clone();
+        b.status = "completed".to_string();
+        b.memo = Some("x".to_string());
+        assert_eq!(diff(&a, &b), vec!["status", "memo"]);
+    }
+
+    #[tokio::test]
+    async fn test_compare_reports_each_kind_of_divergence() {
+        let same = sample_tx();
+        let only_primary = sample_tx();
+        let only_shadow = sample_tx();
+        let changed = sample_tx();
+        let mut changed_shadow = changed.clone();
+        changed_shadow.asset_code = "EUR".to_string();
+
+        let handler = ShadowCompareHandler {
+            primary: repo(vec![same.clone(), only_primary.clone(), changed]),
+            shadow: repo(vec![same, only_shadow.clone(), changed_shadow.clone()]),
+        };
+        let cp = compare(&handler).await;
+
+        assert_eq!(cp.checked, 2);
+        assert_eq!(cp.missing_in_shadow, 1);
+        assert_eq!(cp.missing_in_primary, 1);
+        assert_eq!(cp.mismatched, 1);
+        assert!(cp.samples.contains(&Divergence::MissingInShadow {
+            id: only_primary.id
+        }));
+        assert!(cp
+            .samples
+            .contains(&Divergence::MissingInPrimary { id: only_shadow.id }));
+        assert!(cp.samples.contains(&Divergence::Mismatched {
+            id: changed_shadow.id,
+            fields: vec!["asset_code".to_string()],
+        }));
+    }
+
+    #[tokio::test]
+    async fn test_compare_of_identical_stores_is_clean() {
+        let rows = vec![sample_tx(), sample_tx()];
+        let handler = ShadowCompareHandler {
+            primary: repo(rows.clone()),
+            shadow: repo(rows),
+        };
+        let cp = compare(&handler).await;
+        assert_eq!(cp.checked, 2);
+        assert!(cp.samples.is_empty());
+    }
+
+    #[test]
+    fn test_samples_are_capped() {
+        let mut cp = CompareCheckpoint::default();
+        for _ in 0..MAX_SAMPLES + 5 {
+            cp.record(Divergence::MissingInShadow { id: Uuid::new_v4() });
+        }
+        assert_eq!(cp.missing_in_shadow as usize, MAX_SAMPLES + 5);
+        assert_eq!(cp.samples.len(), MAX_SAMPLES);
+    }
+}
@@ -0,0 +1,659 @@
+//! Signing keys for inbound callback signatures (`X-Stellar-Signature`, the
+//! hex HMAC-SHA256 of the request body).
+//!
+//! Keys live in `inbound_signing_keys`, either global (`tenant_id` NULL) or
+//! per partner. Each has an activation window `[active_from, expires_at)` and
+//! a signature is accepted if any key valid *now* verifies it. Rotating a scope
+//! adds a key active from `activate_at` and makes the current key expire
+//! `overlap` later, so at most two keys are valid at once and the anchor can
+//! switch over at any point inside the overlap. A pending key that never went
+//! live is cancelled by the next rotation.
+//!
+//! | Scope   | Before its first rotation        | After                          |
+//! |---------|----------------------------------|--------------------------------|
+//! | global  | `ANCHOR_WEBHOOK_SECRET` (Vault)  | its own keys                   |
+//! | partner | the global keys                  | its own keys                   |
+//!
+//! The first rotation of a scope records the key it inherited as a row with a
+//! NULL secret, so the inherited key gets the same overlap as any other.
+//!
+//! Secrets are stored sealed with `SIGNING_KEY_ENCRYPTION_KEY` (the HMAC-PRF
+//! construction of [`crate::services::backup_keys`], extended to any length),
+//! never in plain text; the API only shows a SHA-256 fingerprint after the
+//! rotation response. Verifying instances cache the live keys for
+//! [`CACHE_TTL`].
+//!
+//! Enforcement on the callback routes is set by `CALLBACK_SIGNATURE_MODE`:
+//! `optional` (default) verifies a signature when one is sent, `required`
+//! also rejects unsigned callbacks, `off` skips the check.
+//!
+//! A signed callback must also carry `X-Stellar-Timestamp` (Unix seconds) and
+//! `X-Stellar-Nonce`, and the signature covers `<timestamp>.<nonce>.<body>`.
+//! Requests whose timestamp is more than `CALLBACK_TIMESTAMP_TOLERANCE_SECS`
+//! (default 300) away from now are rejected, and each nonce is remembered in
+//! Redis for twice that long so a captured request cannot be replayed.
+
+use crate::db::queries::{self, InboundSigningKey};
+use crate::secrets::SecretsStore;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub const SIGNATURE_HEADER: &str = "X-Stellar-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Stellar-Timestamp";
+pub const NONCE_HEADER: &str = "X-Stellar-Nonce";
+
+pub const DEFAULT_TIMESTAMP_TOLERANCE_SECS: u64 = 300;
+
+/// How long verifying instances reuse the live keys before reloading them.
+pub const CACHE_TTL: Duration = Duration::from_secs(30);
+
+pub const DEFAULT_OVERLAP_HOURS: i64 = 24;
+pub const MAX_OVERLAP_HOURS: i64 = 168;
+
+const SEAL_VERSION: &str = "v1";
+
+/// How the callback routes treat `X-Stellar-Signature`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SignatureMode {
+    Off,
+    /// Verify a signature when present; unsigned callbacks pass.
+    #[default]
+    Optional,
+    Required,
+}
+
+impl SignatureMode {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "off" => Some(SignatureMode::Off),
+            "optional" => Some(SignatureMode::Optional),
+            "required" => Some(SignatureMode::Required),
+            _ => None,
+        }
+    }
+
+    /// `CALLBACK_SIGNATURE_MODE`; unknown values fall back to the default.
+    pub fn from_env() -> Self {
+        std::env::var("CALLBACK_SIGNATURE_MODE")
+            .ok()
+            .and_then(|raw| Self::parse(&raw))
+            .unwrap_or_default()
+    }
+}
+
+// ── Sealing ─────────────────────────────────────────────────────────────────
+
+/// Key that seals stored secrets, from `SIGNING_KEY_ENCRYPTION_KEY`.
+pub struct SealingKey(String);
+
+impl SealingKey {
+    pub fn new(secret: impl Into<String>) -> Self {
+        Self(secret.into())
+    }
+
+    pub fn from_env() -> anyhow::Result<Self> {
+        std::env::var("SIGNING_KEY_ENCRYPTION_KEY")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .map(Self)
+            .context("SIGNING_KEY_ENCRYPTION_KEY is not set")
+    }
+
+    fn derive(&self, label: &str, nonce: &[u8], counter: u32) -> [u8; 32] {
+        let mut m =
+            HmacSha256::new_from_slice(self.0.as_bytes()).expect("HMAC accepts keys of any length");
+        m.update(label.as_bytes());
+        m.update(&[0]);
+        m.update(nonce);
+        m.update(&counter.to_be_bytes());
+        let mut out = [0u8; 32];
+        out.copy_from_slice(&m.finalize().into_bytes());
+        out
+    }
+
+    fn xor_pad(&self, nonce: &[u8], input: &[u8]) -> Vec<u8> {
+        input
+            .chunks(32)
+            .enumerate()
+            .flat_map(|(i, chunk)| {
+                let pad = self.derive("signing-key-seal/enc", nonce, i as u32);
+                chunk
+                    .iter()
+                    .zip(pad)
+                    .map(|(a, b)| a ^ b)
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    fn tag(&self, nonce: &[u8], sealed: &[u8]) -> HmacSha256 {
+        let mut m = HmacSha256::new_from_slice(&self.derive("signing-key-seal/mac", nonce, 0))
+            .expect("HMAC accepts keys of any length");
+        m.update(sealed);
+        m
+    }
+
+    /// `v1:<nonce>:<ciphertext>:<tag>`, all hex.
+    pub fn seal(&self, secret: &str) -> String {
+        let mut nonce = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let sealed = self.xor_pad(&nonce, secret.as_bytes());
+        let tag = self.tag(&nonce, &sealed).finalize().into_bytes();
+        format!(
+            "{SEAL_VERSION}:{}:{}:{}",
+            hex::encode(nonce),
+            hex::encode(&sealed),
+            hex::encode(tag)
+        )
+    }
+
+    pub fn open(&self, value: &str) -> anyhow::Result<String> {
+        let parts: Vec<&str> = value.split(':').collect();
+        let [SEAL_VERSION, nonce, sealed, tag] = parts.as_slice() else {
+            anyhow::bail!("Unrecognised sealed secret format");
+        };
+        let nonce = hex::decode(nonce).context("Invalid seal nonce")?;
+        let sealed = hex::decode(sealed).context("Invalid sealed secret")?;
+        let tag = hex::decode(tag).context("Invalid seal tag")?;
+        self.tag(&nonce, &sealed)
+            .verify_slice(&tag)
+            .map_err(|_| anyhow::anyhow!("Sealed secret failed authentication"))?;
+        String::from_utf8(self.xor_pad(&nonce, &sealed)).context("Sealed secret is not UTF-8")
+    }
+}
+
+/// First 16 hex chars of the secret's SHA-256, for telling keys apart.
+pub fn fingerprint(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))[..16].to_string()
+}
+
+/// Random 256-bit secret, hex encoded.
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// Whether `signature` (hex) is the HMAC-SHA256 of `body` under any of
+/// `secrets`. Comparison is constant time.
+pub fn verify<'a>(
+    secrets: impl IntoIterator<Item = &'a str>,
+    body: &[u8],
+    signature: &str,
+) -> bool {
+    let Ok(expected) = hex::decode(signature.trim()) else {
+        return false;
+    };
+    secrets.into_iter().filter(|s| !s.is_empty()).any(|secret| {
+        let mut mac =
+            HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+        mac.update(body);
+        mac.verify_slice(&expected).is_ok()
+    })
+}
+
+// ── Replay protection ───────────────────────────────────────────────────────
+
+/// Why a signed callback was refused before or after signature verification.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ReplayError {
+    #[error("missing {TIMESTAMP_HEADER} or {NONCE_HEADER}")]
+    MissingHeaders,
+    #[error("{NONCE_HEADER} must be 16-128 characters of [A-Za-z0-9_-]")]
+    InvalidNonce,
+    #[error("{TIMESTAMP_HEADER} is not a Unix timestamp")]
+    InvalidTimestamp,
+    #[error("{TIMESTAMP_HEADER} is outside the {0}s tolerance window")]
+    Stale(u64),
+    #[error("nonce has already been used")]
+    Replayed,
+}
+
+/// `CALLBACK_TIMESTAMP_TOLERANCE_SECS`; how far a callback's timestamp may be
+/// from the server clock, in either direction.
+pub fn timestamp_tolerance() -> Duration {
+    Duration::from_secs(
+        std::env::var("CALLBACK_TIMESTAMP_TOLERANCE_SECS")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(DEFAULT_TIMESTAMP_TOLERANCE_SECS),
+    )
+}
+
+/// Check the timestamp and nonce headers of a signed callback.
+pub fn check_freshness(
+    timestamp: &str,
+    nonce: &str,
+    now: DateTime<Utc>,
+    tolerance: Duration,
+) -> Result<(), ReplayError> {
+    let valid_nonce = (16..=128).contains(&nonce.len())
+        && nonce
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_');
+    if !valid_nonce {
+        return Err(ReplayError::InvalidNonce);
+    }
+    let sent_at: i64 = timestamp
+        .trim()
+        .parse()
+        .map_err(|_| ReplayError::InvalidTimestamp)?;
+    if now.timestamp().abs_diff(sent_at) > tolerance.as_secs() {
+        return Err(ReplayError::Stale(tolerance.as_secs()));
+    }
+    Ok(())
+}
+
+/// What the signature of a callback covers: `<timestamp>.<nonce>.<body>`.
+pub fn signed_content(timestamp: &str, nonce: &str, body: &[u8]) -> Vec<u8> {
+    let mut content = format!("{}.{}.", timestamp.trim(), nonce).into_bytes();
+    content.extend_from_slice(body);
+    content
+}
+
+/// Record `nonce` for the caller's scope, failing with
+/// [`ReplayError::Replayed`] if it was already seen. Entries outlive the
+/// tolerance window on both sides, after which the timestamp check alone
+/// rejects a replay.
+pub async fn claim_nonce(
+    redis_url: &str,
+    tenant_id: Option<Uuid>,
+    nonce: &str,
+    tolerance: Duration,
+) -> anyhow::Result<Result<(), ReplayError>> {
+    let scope = tenant_id.map_or_else(|| "global".to_string(), |id| id.to_string());
+    let client = redis::Client::open(redis_url)?;
+    let mut conn = client.get_multiplexed_async_connection().await?;
+    let fresh: Option<String> = redis::cmd("SET")
+        .arg(format!("callback_nonce:{scope}:{nonce}"))
+        .arg(1)
+        .arg("NX")
+        .arg("EX")
+        .arg(tolerance.as_secs() * 2)
+        .query_async(&mut conn)
+        .await?;
+    Ok(fresh.map(|_| ()).ok_or(ReplayError::Replayed))
+}
+
+// ── Views ───────────────────────────────────────────────────────────────────
+
+/// A key as shown by the admin API; never includes the secret.
+#[derive(Debug, Clone, Serialize)]
+pub struct SigningKeyView {
+    pub id: Uuid,
+    /// `null` for global keys.
+    pub tenant_id: Option<Uuid>,
+    /// `null` for the inherited key recorded by a scope's first rotation.
+    pub fingerprint: Option<String>,
+    pub inherited: bool,
+    /// `pending`, `active` or `expired`.
+    pub state: &'static str,
+    pub active_from: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl SigningKeyView {
+    pub fn new(key: InboundSigningKey, now: DateTime<Utc>) -> Self {
+        let state = if key.expires_at.is_some_and(|e| e <= now) {
+            "expired"
+        } else if key.active_from > now {
+            "pending"
+        } else {
+            "active"
+        };
+        Self {
+            id: key.id,
+            tenant_id: key.tenant_id,
+            fingerprint: key.fingerprint,
+            inherited: key.sealed_secret.is_none(),
+            state,
+            active_from: key.active_from,
+            expires_at: key.expires_at,
+            created_by: key.created_by,
+            created_at: key.created_at,
+        }
+    }
+}
+
+// ── Store ───────────────────────────────────────────────────────────────────
+
+/// A live key with its secret opened; `None` for an inherited key.
+#[derive(Clone)]
+struct LiveKey {
+    tenant_id: Option<Uuid>,
+    secret: Option<String>,
+    active_from: DateTime<Utc>,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl LiveKey {
+    fn is_valid_at(&self, now: DateTime<Utc>) -> bool {
+        self.active_from <= now && self.expires_at.map_or(true, |e| now < e)
+    }
+}
+
+struct Cached {
+    loaded_at: Instant,
+    keys: Vec<LiveKey>,
+}
+
+/// Resolves which secrets may sign a callback, caching the live keys.
+#[derive(Clone)]
+pub struct SigningKeyStore {
+    /// `ANCHOR_WEBHOOK_SECRET`, used until the global scope is rotated.
+    configured: String,
+    secrets_store: Option<SecretsStore>,
+    cache: Arc<RwLock<Option<Cached>>>,
+}
+
+impl SigningKeyStore {
+    pub fn new(configured: impl Into<String>) -> Self {
+        Self {
+            configured: configured.into(),
+            secrets_store: None,
+            cache: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Take the configured secret from Vault, including a just-rotated one
+    /// still in its grace period.
+    pub fn with_secrets_store(mut self, store: SecretsStore) -> Self {
+        self.secrets_store = Some(store);
+        self
+    }
+
+    /// Drop the cached keys so the next check reloads them.
+    pub async fn invalidate(&self) {
+        *self.cache.write().await = None;
+    }
+
+    async fn live_keys(&self, pool: &PgPool) -> anyhow::Result<Vec<LiveKey>> {
+        if let Some(cached) = self.cache.read().await.as_ref() {
+            if cached.loaded_at.elapsed() < CACHE_TTL {
+                return Ok(cached.keys.clone());
+            }
+        }
+
+        let rows = queries::list_live_inbound_signing_keys(pool, Utc::now()).await?;
+        let sealing = if rows.iter().any(|r| r.sealed_secret.is_some()) {
+            Some(SealingKey::from_env()?)
+        } else {
+            None
+        };
+        let mut keys = Vec::with_capacity(rows.len());
+        for row in rows {
+            let secret = match (&row.sealed_secret, &sealing) {
+                (Some(sealed), Some(sealing)) => Some(
+                    sealing
+                        .open(sealed)
+                        .with_context(|| format!("Cannot open inbound signing key {}", row.id))?,
+                ),
+                _ => None,
+            };
+            keys.push(LiveKey {
+                tenant_id: row.tenant_id,
+                secret,
+                active_from: row.active_from,
+                expires_at: row.expires_at,
+            });
+        }
+
+        *self.cache.write().await = Some(Cached {
+            loaded_at: Instant::now(),
+            keys: keys.clone(),
+        });
+        Ok(keys)
+    }
+
+    async fn configured_secrets(&self) -> Vec<String> {
+        match &self.secrets_store {
+            Some(store) => store.valid_webhook_secrets().await,
+            None => vec![self.configured.clone()],
+        }
+    }
+
+    /// Secrets that may sign a callback from `tenant_id` (or an unidentified
+    /// caller) right now.
+    pub async fn valid_secrets(
+        &self,
+        pool: &PgPool,
+        tenant_id: Option<Uuid>,
+    ) -> anyhow::Result<Vec<String>> {
+        let keys = self.live_keys(pool).await?;
+        let configured = self.configured_secrets().await;
+        Ok(resolve(&keys, tenant_id, &configured, Utc::now()))
+    }
+}
+
+/// Secrets valid at `now` in `tenant_id`'s scope, following inherited keys
+/// down to the global scope and then to `configured`.
+fn resolve(
+    keys: &[LiveKey],
+    tenant_id: Option<Uuid>,
+    configured: &[String],
+    now: DateTime<Utc>,
+) -> Vec<String> {
+    let inherited = |keys: &[LiveKey]| match tenant_id {
+        Some(_) => resolve(keys, None, configured, now),
+        None => configured.to_vec(),
+    };
+
+    let scope: Vec<&LiveKey> = keys.iter().filter(|k| k.tenant_id == tenant_id).collect();
+    if scope.is_empty() {
+        return inherited(keys);
+    }
+
+    let mut secrets = Vec::new();
+    for key in scope.into_iter().filter(|k| k.is_valid_at(now)) {
+        match &key.secret {
+            Some(secret) => secrets.push(secret.clone()),
+            None => secrets.extend(inherited(keys)),
+        }
+    }
+    secrets.retain(|s| !s.is_empty());
+    secrets.dedup();
+    secrets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration as ChronoDuration;
+
+    fn sign(secret: &str, body: &[u8]) -> String {
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(body);
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn key(
+        tenant_id: Option<Uuid>,
+        secret: Option<&str>,
+        active_from: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> LiveKey {
+        LiveKey {
+            tenant_id,
+            secret: secret.map(str::to_string),
+            active_from,
+            expires_at,
+        }
+    }
+
+    #[test]
+    fn test_seal_round_trip_and_tamper_detection() {
+        let sealing = SealingKey::new("master");
+        let secret = "s".repeat(70);
+        let sealed = sealing.seal(&secret);
+        assert!(!sealed.contains(&secret));
+        assert_eq!(sealing.open(&sealed).unwrap(), secret);
+
+        assert!(SealingKey::new("other").open(&sealed).is_err());
+        let mut tampered = sealed.clone();
+        tampered.replace_range(20..21, if &sealed[20..21] == "0" { "1" } else { "0" });
+        assert!(sealing.open(&tampered).is_err());
+    }
+
+    #[test]
+    fn test_verify_accepts_any_valid_secret() {
+        let body = br#"{"amount":"10"}"#;
+        let signature = sign("new-secret", body);
+        assert!(verify(["old-secret", "new-secret"], body, &signature));
+        assert!(!verify(["old-secret"], body, &signature));
+        assert!(!verify(["new-secret"], b"tampered", &signature));
+        assert!(!verify(["new-secret"], body, "not-hex"));
+        assert!(!verify([""], body, &sign("", body)));
+    }
+
+    #[test]
+    fn test_check_freshness_enforces_window_and_nonce_format() {
+        let now = Utc::now();
+        let tolerance = Duration::from_secs(300);
+        let nonce = "0f8b6c2e4a1d4e3b";
+        let at = |offset: i64| (now.timestamp() + offset).to_string();
+
+        assert_eq!(check_freshness(&at(-299), nonce, now, tolerance), Ok(()));
+        assert_eq!(check_freshness(&at(299), nonce, now, tolerance), Ok(()));
+        assert_eq!(
+            check_freshness(&at(-301), nonce, now, tolerance),
+            Err(ReplayError::Stale(300))
+        );
+        assert_eq!(
+            check_freshness("2025-01-15T10:30:00Z", nonce, now, tolerance),
+            Err(ReplayError::InvalidTimestamp)
+        );
+        assert_eq!(
+            check_freshness(&at(0), "short", now, tolerance),
+            Err(ReplayError::InvalidNonce)
+        );
+        assert_eq!(
+            check_freshness(&at(0), "0f8b6c2e:4a1d4e3b", now, tolerance),
+            Err(ReplayError::InvalidNonce)
+        );
+    }
+
+    #[test]
+    fn test_signature_binds_timestamp_and_nonce() {
+        let body = br#"{"amount":"10"}"#;
+        let signature = sign(
+            "secret",
+            &signed_content("1700000000", "nonce-0001-abcdef", body),
+        );
+        let verifies = |ts: &str, nonce: &str| {
+            verify(["secret"], &signed_content(ts, nonce, body), &signature)
+        };
+        assert!(verifies("1700000000", "nonce-0001-abcdef"));
+        assert!(!verifies("1700000001", "nonce-0001-abcdef"));
+        assert!(!verifies("1700000000", "nonce-0002-abcdef"));
+        assert!(!verify(["secret"], body, &signature));
+    }
+
+    #[test]
+    fn test_resolve_overlaps_previous_and_new_global_key() {
+        let now = Utc::now();
+        let configured = vec!["env-secret".to_string()];
+        let keys = vec![
+            key(
+                None,
+                None,
+                now - ChronoDuration::hours(1),
+                Some(now + ChronoDuration::hours(23)),
+            ),
+            key(None, Some("rotated"), now - ChronoDuration::hours(1), None),
+        ];
+        assert_eq!(
+            resolve(&keys, None, &configured, now),
+            vec!["env-secret".to_string(), "rotated".to_string()]
+        );
+        // After the overlap only the new key is left.
+        assert_eq!(
+            resolve(&keys, None, &configured, now + ChronoDuration::hours(24)),
+            vec!["rotated".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_resolve_pending_key_is_not_valid_yet() {
+        let now = Utc::now();
+        let keys = vec![
+            key(
+                None,
+                Some("current"),
+                now - ChronoDuration::days(1),
+                Some(now + ChronoDuration::hours(30)),
+            ),
+            key(None, Some("next"), now + ChronoDuration::hours(6), None),
+        ];
+        assert_eq!(resolve(&keys, None, &[], now), vec!["current".to_string()]);
+        assert_eq!(
+            resolve(&keys, None, &[], now + ChronoDuration::hours(7)),
+            vec!["current".to_string(), "next".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_resolve_partner_falls_back_to_global() {
+        let now = Utc::now();
+        let partner = Uuid::new_v4();
+        let other = Uuid::new_v4();
+        let configured = vec!["env-secret".to_string()];
+        let keys = vec![
+            key(
+                Some(partner),
+                None,
+                now - ChronoDuration::hours(1),
+                Some(now + ChronoDuration::hours(1)),
+            ),
+            key(
+                Some(partner),
+                Some("partner-secret"),
+                now - ChronoDuration::hours(1),
+                None,
+            ),
+        ];
+
+        assert_eq!(
+            resolve(&keys, Some(partner), &configured, now),
+            vec!["env-secret".to_string(), "partner-secret".to_string()]
+        );
+        assert_eq!(
+            resolve(
+                &keys,
+                Some(partner),
+                &configured,
+                now + ChronoDuration::hours(2)
+            ),
+            vec!["partner-secret".to_string()]
+        );
+        // A partner that never rotated uses the global keys.
+        assert_eq!(
+            resolve(&keys, Some(other), &configured, now),
+            vec!["env-secret".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_signature_mode_parse() {
+        assert_eq!(
+            SignatureMode::parse("Required"),
+            Some(SignatureMode::Required)
+        );
+        assert_eq!(SignatureMode::parse("off"), Some(SignatureMode::Off));
+        assert_eq!(SignatureMode::parse("sometimes"), None);
+        assert_eq!(SignatureMode::default(), SignatureMode::Optional);
+    }
+}
@@ -0,0 +1,481 @@
+//! Monthly partner statements.
+//!
+//! A `partner_statement` job (params `{"period": "YYYY-MM", "tenant_id"?}`,
+//! queued through `POST /admin/statements` or `/admin/jobs`) writes one
+//! statement per partner, or just the named one, for a finished month. Each
+//! statement lists, per asset:
+//!
+//! | Column       | Source                                                   |
+//! |--------------|----------------------------------------------------------|
+//! | volume       | Partner transactions created in the month; completed sum |
+//! | fees charged | `fee_amount` of those transactions; completed sum       |
+//! | settlements  | Partner share of the month's non-voided settlements      |
+//! | refunds      | Sub-minimum deposits, still `refund_pending` or refunded |
+//!
+//! The statement is rendered as CSV and PDF, uploaded to the object store
+//! (see [`crate::adapters::object_store`]) under
+//! `statements/<tenant_id>/<YYYY-MM>.{csv,pdf}` and recorded in
+//! `partner_statements`. Rerunning a month replaces the files and totals.
+//! Progress is checkpointed per partner, so a resumed job skips partners
+//! already done.
+//!
+//! Once a statement is stored a `statement.ready` webhook is enqueued for
+//! endpoints subscribed to it, once per statement; the envelope's
+//! `transaction_id` carries the statement id. `GET /admin/statements` lists
+//! statements with signed download URLs (see [`crate::utils::signed_url`])
+//! served by `/downloads/statements/:file`.
+
+use crate::db::models::PartnerStatement;
+use crate::db::queries;
+use crate::ports::ObjectStore;
+use crate::services::accounting::Period;
+use crate::services::job_runner::{JobContext, JobHandler, JobKind};
+use crate::services::webhook_dispatcher::{WebhookDispatcher, STATEMENT_READY_EVENT};
+use crate::utils::{pdf, signed_url};
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::types::BigDecimal;
+use sqlx::{FromRow, PgPool};
+use std::str::FromStr;
+use std::sync::Arc;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatementFormat {
+    Csv,
+    Pdf,
+}
+
+impl StatementFormat {
+    /// Every format a statement is written in.
+    pub const ALL: [StatementFormat; 2] = [StatementFormat::Csv, StatementFormat::Pdf];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatementFormat::Csv => "csv",
+            StatementFormat::Pdf => "pdf",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            StatementFormat::Csv => "text/csv",
+            StatementFormat::Pdf => "application/pdf",
+        }
+    }
+}
+
+impl FromStr for StatementFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(StatementFormat::Csv),
+            "pdf" => Ok(StatementFormat::Pdf),
+            _ => Err(format!("Invalid statement format: {s}")),
+        }
+    }
+}
+
+/// `params` of a `partner_statement` job.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatementParams {
+    /// Month, `YYYY-MM`.
+    pub period: String,
+    /// Only this partner; every partner when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<Uuid>,
+}
+
+impl StatementParams {
+    pub fn period(&self) -> Result<Period, String> {
+        Period::parse(&self.period).map_err(|e| e.to_string())
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let period = self.period()?;
+        if period.ends_at() > Utc::now() {
+            return Err(format!("period {} is not over yet", period.label()));
+        }
+        Ok(())
+    }
+}
+
+/// One asset's totals on a statement.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct StatementLine {
+    pub asset_code: String,
+    pub transaction_count: i64,
+    pub completed_count: i64,
+    /// Sum of completed transactions.
+    pub volume: BigDecimal,
+    pub fees_charged: BigDecimal,
+    pub settlement_count: i64,
+    pub settled_amount: BigDecimal,
+    pub refund_count: i64,
+    pub refund_amount: BigDecimal,
+}
+
+const CSV_HEADERS: [&str; 9] = [
+    "asset_code",
+    "transaction_count",
+    "completed_count",
+    "volume",
+    "fees_charged",
+    "settlement_count",
+    "settled_amount",
+    "refund_count",
+    "refund_amount",
+];
+
+/// One partner's statement for one month.
+#[derive(Debug, Clone)]
+pub struct Statement {
+    pub tenant_id: Uuid,
+    pub partner_name: String,
+    pub period: Period,
+    pub lines: Vec<StatementLine>,
+    pub generated_at: DateTime<Utc>,
+}
+
+impl Statement {
+    pub fn render(&self, format: StatementFormat) -> anyhow::Result<Vec<u8>> {
+        match format {
+            StatementFormat::Csv => self.to_csv(),
+            StatementFormat::Pdf => Ok(pdf::render(&self.text_lines())),
+        }
+    }
+
+    /// Header row then one row per asset; the header is written even when
+    /// the partner had no activity.
+    fn to_csv(&self) -> anyhow::Result<Vec<u8>> {
+        let mut wtr = csv::WriterBuilder::new()
+            .has_headers(false)
+            .from_writer(vec![]);
+        wtr.write_record(CSV_HEADERS)?;
+        for line in &self.lines {
+            wtr.serialize(line)?;
+        }
+        Ok(wtr.into_inner()?)
+    }
+
+    /// The statement as text, one asset block after another.
+    fn text_lines(&self) -> Vec<String> {
+        let last_day = (self.period.ends_at() - chrono::Duration::days(1)).date_naive();
+        let mut out = vec![
+            format!("Statement    {}", self.partner_name),
+            format!("Partner      {}", self.tenant_id),
+            format!(
+                "Period       {} ({} to {}, UTC)",
+                self.period.label(),
+                self.period.start(),
+                last_day
+            ),
+            format!("Generated    {}", self.generated_at.to_rfc3339()),
+            String::new(),
+        ];
+        if self.lines.is_empty() {
+            out.push("No activity in this period.".to_string());
+        }
+        for line in &self.lines {
+            out.extend([
+                line.asset_code.clone(),
+                format!(
+                    "  Transactions   {} ({} completed)",
+                    line.transaction_count, line.completed_count
+                ),
+                format!("  Volume         {}", line.volume),
+                format!("  Fees charged   {}", line.fees_charged),
+                format!(
+                    "  Settlements    {} ({} settled)",
+                    line.settlement_count, line.settled_amount
+                ),
+                format!(
+                    "  Refunds        {} ({} refunded)",
+                    line.refund_count, line.refund_amount
+                ),
+                String::new(),
+            ]);
+        }
+        out
+    }
+}
+
+/// Object store key of `format` for the statement of `tenant_id` in `period`.
+pub fn object_key(tenant_id: Uuid, period: Period, format: StatementFormat) -> String {
+    format!(
+        "statements/{tenant_id}/{}.{}",
+        period.label(),
+        format.as_str()
+    )
+}
+
+/// Name of a statement download: `<statement id>.<format>`.
+pub fn file_name(id: Uuid, format: StatementFormat) -> String {
+    format!("{id}.{}", format.as_str())
+}
+
+/// Split a download file name back into statement id and format.
+pub fn parse_file_name(file: &str) -> Option<(Uuid, StatementFormat)> {
+    let (id, format) = file.rsplit_once('.')?;
+    Some((Uuid::parse_str(id).ok()?, format.parse().ok()?))
+}
+
+/// Signed resource for statement download `file`.
+pub fn download_resource(file: &str) -> String {
+    format!("statement:{file}")
+}
+
+/// Relative download URL for `format` of statement `id`, valid until the
+/// returned expiry.
+pub fn download_url(
+    secret: &str,
+    id: Uuid,
+    format: StatementFormat,
+    now: DateTime<Utc>,
+) -> (String, DateTime<Utc>) {
+    let file = file_name(id, format);
+    signed_url::sign_url(
+        secret,
+        &format!("/downloads/statements/{file}"),
+        &download_resource(&file),
+        now,
+    )
+}
+
+/// `data` of the `statement.ready` webhook event.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StatementReadyNotification {
+    #[schema(value_type = String, format = "uuid")]
+    pub statement_id: Uuid,
+    #[schema(value_type = String, format = "uuid")]
+    pub tenant_id: Uuid,
+    /// `YYYY-MM`.
+    pub period: String,
+    /// Formats available from `GET /admin/statements`.
+    pub formats: Vec<&'static str>,
+}
+
+impl StatementReadyNotification {
+    pub fn new(statement: &PartnerStatement, period: Period) -> Self {
+        Self {
+            statement_id: statement.id,
+            tenant_id: statement.tenant_id,
+            period: period.label(),
+            formats: StatementFormat::ALL.iter().map(|f| f.as_str()).collect(),
+        }
+    }
+}
+
+/// Enqueue the `statement.ready` webhook for `statement`.
+///
+/// Best effort: the statement is already stored, so a delivery failure is
+/// logged rather than failing the job.
+async fn notify(pool: &PgPool, redis_url: &str, statement: &PartnerStatement, period: Period) {
+    let data = serde_json::json!(StatementReadyNotification::new(statement, period));
+    let result = match WebhookDispatcher::new(pool.clone(), redis_url) {
+        Ok(dispatcher) => {
+            dispatcher
+                .enqueue(statement.id, STATEMENT_READY_EVENT, data)
+                .await
+        }
+        Err(e) => Err(e.into()),
+    };
+    if let Err(e) = result {
+        tracing::error!(
+            statement_id = %statement.id,
+            error = %e,
+            "Failed to enqueue statement notification"
+        );
+    }
+}
+
+/// Resume state: partners done so far, in `tenant_id` order.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct StatementCheckpoint {
+    done: usize,
+}
+
+/// [`JobHandler`] for `partner_statement` jobs.
+pub struct StatementJobHandler {
+    pub store: Arc<dyn ObjectStore>,
+    /// Redis of the webhook dispatcher, for `statement.ready`.
+    pub redis_url: String,
+}
+
+#[async_trait]
+impl JobHandler for StatementJobHandler {
+    fn kind(&self) -> JobKind {
+        JobKind::PartnerStatement
+    }
+
+    async fn run(&self, ctx: &mut JobContext) -> anyhow::Result<serde_json::Value> {
+        let params: StatementParams = ctx.params()?;
+        let period = params.period().map_err(anyhow::Error::msg)?;
+        let partners = queries::list_statement_partners(ctx.pool(), params.tenant_id).await?;
+        if let Some(id) = params.tenant_id.filter(|_| partners.is_empty()) {
+            anyhow::bail!("partner {id} not found");
+        }
+
+        let mut checkpoint = ctx.checkpoint::<StatementCheckpoint>()?.unwrap_or_default();
+        let total = partners.len() as i64;
+        for (tenant_id, name) in partners.iter().skip(checkpoint.done) {
+            let lines = queries::partner_statement_lines(
+                ctx.pool(),
+                *tenant_id,
+                period.starts_at(),
+                period.ends_at(),
+            )
+            .await?;
+            let statement = Statement {
+                tenant_id: *tenant_id,
+                partner_name: name.clone(),
+                period,
+                lines,
+                generated_at: Utc::now(),
+            };
+            for format in StatementFormat::ALL {
+                let body = Bytes::from(statement.render(format)?);
+                self.store
+                    .put_bytes(&object_key(*tenant_id, period, format), body)
+                    .await?;
+            }
+            let stored = queries::upsert_partner_statement(
+                ctx.pool(),
+                *tenant_id,
+                period.start(),
+                ctx.id(),
+                &serde_json::to_value(&statement.lines)?,
+            )
+            .await?;
+            notify(ctx.pool(), &self.redis_url, &stored, period).await;
+
+            checkpoint.done += 1;
+            ctx.save_progress(checkpoint.done as i64, Some(total), &checkpoint)
+                .await?;
+        }
+
+        tracing::info!(
+            period = %period.label(),
+            statements = total,
+            "Partner statements generated"
+        );
+        Ok(serde_json::json!({ "period": period.label(), "statements": total }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statement(lines: Vec<StatementLine>) -> Statement {
+        Statement {
+            tenant_id: Uuid::nil(),
+            partner_name: "Acme (EU)".to_string(),
+            period: Period::parse("2026-05").unwrap(),
+            lines,
+            generated_at: Utc::now(),
+        }
+    }
+
+    fn line(asset_code: &str) -> StatementLine {
+        StatementLine {
+            asset_code: asset_code.to_string(),
+            transaction_count: 3,
+            completed_count: 2,
+            volume: BigDecimal::from(150),
+            fees_charged: BigDecimal::from(0),
+            settlement_count: 1,
+            settled_amount: BigDecimal::from(150),
+            refund_count: 1,
+            refund_amount: BigDecimal::from(1),
+        }
+    }
+
+    #[test]
+    fn test_csv_has_header_and_one_row_per_asset() {
+        let csv = String::from_utf8(
+            statement(vec![line("USDC"), line("EURC")])
+                .render(StatementFormat::Csv)
+                .unwrap(),
+        )
+        .unwrap();
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0], CSV_HEADERS.join(","));
+        assert!(rows[1].starts_with("USDC,3,2,150,0,1,150,1,1"));
+
+        let empty = statement(vec![]).render(StatementFormat::Csv).unwrap();
+        assert_eq!(String::from_utf8(empty).unwrap().lines().count(), 1);
+    }
+
+    #[test]
+    fn test_pdf_contains_partner_and_totals() {
+        let pdf = statement(vec![line("USDC")])
+            .render(StatementFormat::Pdf)
+            .unwrap();
+        let text = String::from_utf8(pdf).unwrap();
+        assert!(text.starts_with("%PDF-"));
+        assert!(text.contains(r"Acme \(EU\)"));
+        assert!(text.contains("2026-05-01 to 2026-05-31"));
+        assert!(text.contains("Volume         150"));
+    }
+
+    #[test]
+    fn test_file_name_round_trip() {
+        let id = Uuid::new_v4();
+        for format in StatementFormat::ALL {
+            assert_eq!(parse_file_name(&file_name(id, format)), Some((id, format)));
+        }
+        assert_eq!(parse_file_name(&format!("{id}.xlsx")), None);
+        assert_eq!(parse_file_name("statement.csv"), None);
+        assert_eq!(
+            object_key(id, Period::parse("2026-05").unwrap(), StatementFormat::Pdf),
+            format!("statements/{id}/2026-05.pdf")
+        );
+    }
+
+    #[test]
+    fn test_download_url_is_verifiable() {
+        let id = Uuid::new_v4();
+        let now = Utc::now();
+        let (url, expires_at) = download_url("secret", id, StatementFormat::Pdf, now);
+        let query = signed_url::SignedUrlQuery {
+            expires: expires_at.timestamp(),
+            signature: url.rsplit("signature=").next().unwrap().to_string(),
+        };
+        let file = file_name(id, StatementFormat::Pdf);
+        assert!(url.starts_with(&format!("/downloads/statements/{file}?expires=")));
+        assert!(query.verify("secret", &download_resource(&file), now));
+        assert!(!query.verify(
+            "secret",
+            &download_resource(&file_name(id, StatementFormat::Csv)),
+            now
+        ));
+    }
+
+    #[test]
+    fn test_params_validation() {
+        let ok = StatementParams {
+            period: "2026-05".to_string(),
+            tenant_id: None,
+        };
+        assert!(ok.validate().is_ok());
+
+        let current = StatementParams {
+            period: Period::containing(Utc::now()).label(),
+            tenant_id: None,
+        };
+        assert!(current.validate().is_err());
+
+        let malformed = StatementParams {
+            period: "May 2026".to_string(),
+            tenant_id: None,
+        };
+        assert!(malformed.validate().is_err());
+    }
+}
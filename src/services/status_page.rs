@@ -0,0 +1,231 @@
+//! Shareable status links for customers.
+//!
+//! `POST /transactions/:id/status-link` issues a read-only URL,
+//! `/status/<token>`, that partners can print on receipts. Whoever holds it
+//! sees the transaction's [`PublicStatus`] (status, amount and timestamps)
+//! without an API key, and nothing else: no accounts, memos or metadata.
+//!
+//! The token is `<transaction id>.<expires>.<signature>`, where the
+//! signature is the hex HMAC-SHA256 of `status:<transaction id>.<expires>`
+//! keyed with `STATUS_PAGE_SECRET` (same scheme as
+//! [`crate::utils::signed_url`]). Nothing is stored, so a link cannot be
+//! revoked before it expires short of rotating the secret, which revokes
+//! them all.
+//!
+//! | Env var                | Default         | Meaning                                   |
+//! |------------------------|-----------------|-------------------------------------------|
+//! | `STATUS_PAGE_SECRET`   | none            | Link signing key, 32+ characters          |
+//! | `STATUS_PAGE_TTL_SECS` | 2592000 (30 d)  | How long a link is valid                  |
+//! | `STATUS_PAGE_BASE_URL` | none            | Public origin prefixed to issued links    |
+//!
+//! Without a secret no links are issued and `/status/:token` answers `404`.
+//!
+//! The status `message` and error text follow the request's
+//! `Accept-Language` (see [`crate::i18n`]).
+
+use crate::db::models::{Transaction, TransactionStatus};
+use crate::i18n::{Locale, Message};
+use crate::utils::signed_url;
+use anyhow::bail;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+const MIN_SECRET_LEN: usize = 32;
+const DEFAULT_TTL_SECS: i64 = 30 * 24 * 3600;
+
+pub struct StatusPageConfig {
+    secret: String,
+    pub ttl: Duration,
+    /// Origin such as `https://status.example.com`; links are relative
+    /// without one.
+    pub base_url: Option<String>,
+}
+
+impl std::fmt::Debug for StatusPageConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StatusPageConfig")
+            .field("ttl", &self.ttl)
+            .field("base_url", &self.base_url)
+            .finish_non_exhaustive()
+    }
+}
+
+impl StatusPageConfig {
+    pub fn new(secret: impl Into<String>) -> anyhow::Result<Self> {
+        let secret = secret.into();
+        if secret.len() < MIN_SECRET_LEN {
+            bail!("STATUS_PAGE_SECRET must be at least {MIN_SECRET_LEN} characters");
+        }
+        Ok(Self {
+            secret,
+            ttl: Duration::seconds(DEFAULT_TTL_SECS),
+            base_url: None,
+        })
+    }
+
+    /// `None` without `STATUS_PAGE_SECRET`.
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let Some(secret) = std::env::var("STATUS_PAGE_SECRET")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+        else {
+            return Ok(None);
+        };
+        let mut config = Self::new(secret)?;
+        if let Some(ttl) = std::env::var("STATUS_PAGE_TTL_SECS")
+            .ok()
+            .and_then(|v| v.trim().parse::<i64>().ok())
+            .filter(|n| *n > 0)
+        {
+            config.ttl = Duration::seconds(ttl);
+        }
+        config.base_url = std::env::var("STATUS_PAGE_BASE_URL")
+            .ok()
+            .map(|v| v.trim().trim_end_matches('/').to_string())
+            .filter(|v| !v.is_empty());
+        Ok(Some(config))
+    }
+
+    /// A token for transaction `id`, valid for [`Self::ttl`] from `now`.
+    pub fn issue(&self, id: Uuid, now: DateTime<Utc>) -> (String, DateTime<Utc>) {
+        let expires_at = now + self.ttl;
+        let expires = expires_at.timestamp();
+        let signature = signed_url::sign(&self.secret, &resource(id), expires);
+        (format!("{}.{expires}.{signature}", id.simple()), expires_at)
+    }
+
+    /// Where `token` is served.
+    pub fn url(&self, token: &str) -> String {
+        format!("{}/status/{token}", self.base_url.as_deref().unwrap_or(""))
+    }
+
+    /// The transaction `token` grants, if it was issued here and has not
+    /// expired at `now`.
+    pub fn verify(&self, token: &str, now: DateTime<Utc>) -> Option<Uuid> {
+        let mut parts = token.split('.');
+        let (id, expires, signature) = (parts.next()?, parts.next()?, parts.next()?);
+        if parts.next().is_some() {
+            return None;
+        }
+        let id = Uuid::parse_str(id).ok()?;
+        let expires = expires.parse::<i64>().ok()?;
+        signed_url::verify(&self.secret, &resource(id), expires, signature, now).then_some(id)
+    }
+}
+
+fn resource(id: Uuid) -> String {
+    format!("status:{id}")
+}
+
+/// What a status link shows.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PublicStatus {
+    /// `pending`, `processing`, `completed`, `failed` or `refunding`.
+    pub status: &'static str,
+    /// What `status` means for the customer, in the request's locale.
+    pub message: &'static str,
+    pub amount: String,
+    pub asset_code: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// When the payment closed on the Stellar network, once known.
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// The customer-facing status for `status`. Internal states (compliance
+/// review, the DLQ) read as still `processing`.
+pub fn public_status(status: TransactionStatus) -> &'static str {
+    match status {
+        TransactionStatus::Pending => "pending",
+        TransactionStatus::Processing
+        | TransactionStatus::ComplianceReview
+        | TransactionStatus::Dlq => "processing",
+        TransactionStatus::Completed => "completed",
+        TransactionStatus::Failed => "failed",
+        TransactionStatus::RefundPending => "refunding",
+    }
+}
+
+fn status_message(status: TransactionStatus) -> Message {
+    match status {
+        TransactionStatus::Pending => Message::StatusPending,
+        TransactionStatus::Processing
+        | TransactionStatus::ComplianceReview
+        | TransactionStatus::Dlq => Message::StatusProcessing,
+        TransactionStatus::Completed => Message::StatusCompleted,
+        TransactionStatus::Failed => Message::StatusFailed,
+        TransactionStatus::RefundPending => Message::StatusRefunding,
+    }
+}
+
+impl PublicStatus {
+    pub fn new(tx: &Transaction, locale: Locale) -> Self {
+        Self {
+            status: public_status(tx.status),
+            message: status_message(tx.status).text(locale),
+            amount: tx.amount.to_string(),
+            asset_code: tx.asset_code.clone(),
+            created_at: tx.created_at,
+            updated_at: tx.updated_at,
+            completed_at: match tx.status {
+                TransactionStatus::Completed => tx.closed_at,
+                _ => None,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> StatusPageConfig {
+        StatusPageConfig::new("s".repeat(32)).unwrap()
+    }
+
+    #[test]
+    fn test_token_round_trip() {
+        let config = config();
+        let id = Uuid::new_v4();
+        let now = Utc::now();
+        let (token, expires_at) = config.issue(id, now);
+
+        assert_eq!(expires_at, now + Duration::seconds(DEFAULT_TTL_SECS));
+        assert_eq!(config.verify(&token, now), Some(id));
+        assert_eq!(config.url(&token), format!("/status/{token}"));
+        assert_eq!(
+            config.verify(&token, expires_at + Duration::seconds(1)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_token_is_bound_to_transaction_and_secret() {
+        let config = config();
+        let now = Utc::now();
+        let (token, _) = config.issue(Uuid::new_v4(), now);
+        let (_, rest) = token.split_once('.').unwrap();
+
+        let forged = format!("{}.{rest}", Uuid::new_v4().simple());
+        assert_eq!(config.verify(&forged, now), None);
+        let other = StatusPageConfig::new("t".repeat(32)).unwrap();
+        assert_eq!(other.verify(&token, now), None);
+        assert_eq!(config.verify(&format!("{token}.x"), now), None);
+        assert!(StatusPageConfig::new("short").is_err());
+    }
+
+    #[test]
+    fn test_internal_states_are_not_exposed() {
+        assert_eq!(
+            public_status(TransactionStatus::ComplianceReview),
+            "processing"
+        );
+        assert_eq!(public_status(TransactionStatus::Dlq), "processing");
+        assert_eq!(public_status(TransactionStatus::RefundPending), "refunding");
+        assert_eq!(
+            status_message(TransactionStatus::Dlq),
+            status_message(TransactionStatus::Processing)
+        );
+    }
+}
@@ -0,0 +1,410 @@
+//! SEP-1 `stellar.toml`: how wallets discover this anchor.
+//!
+//! `GET /.well-known/stellar.toml` is generated on every request from the
+//! configuration of the SEPs served here and the enabled, issued assets in
+//! `assets`. A SEP's server URL is only published while that SEP is
+//! configured, and the SEP-10 server account is published as `SIGNING_KEY`.
+//!
+//! | Key                       | Published with                     | URL path |
+//! |---------------------------|------------------------------------|----------|
+//! | `WEB_AUTH_ENDPOINT`       | SEP-10 (`SEP10_*`)                 | `/auth`  |
+//! | `KYC_SERVER`              | SEP-10                             | (root)   |
+//! | `ANCHOR_QUOTE_SERVER`     | SEP-10                             | `/sep38` |
+//! | `TRANSFER_SERVER`         | SEP-10, `SEP6_WITHDRAW_ACCOUNT`    | `/sep6`  |
+//! | `TRANSFER_SERVER_SEP0024` | SEP-10, `SEP24_INTERACTIVE_*`      | `/sep24` |
+//! | `DIRECT_PAYMENT_SERVER`   | SEP-10, `SEP31_RECEIVING_ACCOUNT`  | `/sep31` |
+//!
+//! Every published URL is checked before the file is served: it must lie
+//! under `STELLAR_TOML_BASE_URL`, name the prefix its routes are mounted at
+//! ([`ENDPOINTS`]), and `WEB_AUTH_ENDPOINT` must be on
+//! `SEP10_WEB_AUTH_DOMAIN`, the domain SEP-10 challenges name. A file that
+//! fails the check is not served. The integration tests probe each
+//! [`Endpoint::probe`] route on the real router.
+//!
+//! | Env var                 | Default                       | Meaning                       |
+//! |-------------------------|-------------------------------|-------------------------------|
+//! | `STELLAR_TOML_BASE_URL` | `https://<SEP10_HOME_DOMAIN>` | Public URL of this server     |
+//! | `STELLAR_TOML_ORG_NAME` | unset                         | `[DOCUMENTATION] ORG_NAME`    |
+//! | `STELLAR_TOML_ORG_URL`  | unset                         | `[DOCUMENTATION] ORG_URL`     |
+
+use crate::db::models::Asset;
+use crate::error::AppError;
+use crate::services::sep10::Sep10Config;
+use crate::services::sep24::Sep24Config;
+use crate::services::sep31::Sep31Config;
+use crate::services::sep6::Sep6Config;
+use std::fmt::Write;
+
+/// A SEP server URL published in `stellar.toml`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Endpoint {
+    pub key: &'static str,
+    /// Path prefix the SEP's routes are mounted at; the published URL is
+    /// the base URL followed by this.
+    pub path: &'static str,
+    /// A route under `path` that answers anything but `404` when mounted.
+    pub probe: &'static str,
+}
+
+pub const WEB_AUTH_ENDPOINT: Endpoint = Endpoint {
+    key: "WEB_AUTH_ENDPOINT",
+    path: "/auth",
+    probe: "/auth",
+};
+pub const TRANSFER_SERVER: Endpoint = Endpoint {
+    key: "TRANSFER_SERVER",
+    path: "/sep6",
+    probe: "/sep6/info",
+};
+pub const TRANSFER_SERVER_SEP0024: Endpoint = Endpoint {
+    key: "TRANSFER_SERVER_SEP0024",
+    path: "/sep24",
+    probe: "/sep24/transactions/deposit/interactive",
+};
+/// SEP-12 routes are `/customer` and `/customer/:account`, at the root.
+pub const KYC_SERVER: Endpoint = Endpoint {
+    key: "KYC_SERVER",
+    path: "",
+    probe: "/customer",
+};
+pub const DIRECT_PAYMENT_SERVER: Endpoint = Endpoint {
+    key: "DIRECT_PAYMENT_SERVER",
+    path: "/sep31",
+    probe: "/sep31/transactions",
+};
+pub const ANCHOR_QUOTE_SERVER: Endpoint = Endpoint {
+    key: "ANCHOR_QUOTE_SERVER",
+    path: "/sep38",
+    probe: "/sep38/info",
+};
+
+/// Every endpoint `stellar.toml` can publish.
+pub const ENDPOINTS: &[Endpoint] = &[
+    WEB_AUTH_ENDPOINT,
+    TRANSFER_SERVER,
+    TRANSFER_SERVER_SEP0024,
+    KYC_SERVER,
+    DIRECT_PAYMENT_SERVER,
+    ANCHOR_QUOTE_SERVER,
+];
+
+#[derive(Debug, Clone)]
+pub struct StellarTomlConfig {
+    /// Without a trailing slash.
+    pub base_url: String,
+    pub org_name: Option<String>,
+    pub org_url: Option<String>,
+}
+
+impl StellarTomlConfig {
+    pub fn from_env() -> anyhow::Result<Self> {
+        let var = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let base_url = match var("STELLAR_TOML_BASE_URL") {
+            Some(url) => url,
+            None => format!(
+                "https://{}",
+                var("SEP10_HOME_DOMAIN").ok_or_else(|| anyhow::anyhow!(
+                    "STELLAR_TOML_BASE_URL and SEP10_HOME_DOMAIN are not set"
+                ))?
+            ),
+        };
+        url::Url::parse(&base_url)
+            .map_err(|e| anyhow::anyhow!("STELLAR_TOML_BASE_URL is not a URL: {e}"))?;
+        Ok(Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            org_name: var("STELLAR_TOML_ORG_NAME"),
+            org_url: var("STELLAR_TOML_ORG_URL"),
+        })
+    }
+}
+
+/// A `[[CURRENCIES]]` entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Currency {
+    pub code: String,
+    pub issuer: String,
+}
+
+/// Contents of `stellar.toml`.
+#[derive(Debug, Clone, Default)]
+pub struct StellarToml {
+    pub network_passphrase: Option<String>,
+    pub signing_key: Option<String>,
+    /// SEP-10 web auth domain, which must host `WEB_AUTH_ENDPOINT`.
+    pub web_auth_domain: Option<String>,
+    pub accounts: Vec<String>,
+    /// Published endpoints and their URLs.
+    pub endpoints: Vec<(Endpoint, String)>,
+    pub currencies: Vec<Currency>,
+    pub org_name: Option<String>,
+    pub org_url: Option<String>,
+}
+
+impl StellarToml {
+    /// The file for the SEPs configured in the environment and `assets`.
+    pub fn from_env(config: &StellarTomlConfig, assets: &[Asset]) -> Self {
+        let sep10 = Sep10Config::from_env().ok();
+        let sep6 = Sep6Config::from_env().ok();
+        let sep31 = Sep31Config::from_env().ok();
+
+        let mut published = Vec::new();
+        if sep10.is_some() {
+            published.extend([WEB_AUTH_ENDPOINT, KYC_SERVER, ANCHOR_QUOTE_SERVER]);
+            if sep6.is_some() {
+                published.push(TRANSFER_SERVER);
+            }
+            if Sep24Config::from_env().is_ok() {
+                published.push(TRANSFER_SERVER_SEP0024);
+            }
+            if sep31.is_some() {
+                published.push(DIRECT_PAYMENT_SERVER);
+            }
+        }
+        // Keep the order of ENDPOINTS.
+        let endpoints = ENDPOINTS
+            .iter()
+            .filter(|e| published.contains(e))
+            .map(|e| (*e, format!("{}{}", config.base_url, e.path)))
+            .collect();
+
+        let mut accounts: Vec<String> = Vec::new();
+        let candidates = [
+            sep10
+                .as_ref()
+                .map(|c| c.server_account.account().to_string()),
+            sep6.map(|c| c.withdraw_account.account().to_string()),
+            sep31.map(|c| c.receiving_account.account().to_string()),
+        ];
+        for account in candidates.into_iter().flatten() {
+            if !accounts.contains(&account) {
+                accounts.push(account);
+            }
+        }
+
+        Self {
+            network_passphrase: sep10
+                .as_ref()
+                .map(|c| c.network_passphrase.clone())
+                .or_else(|| std::env::var("STELLAR_NETWORK_PASSPHRASE").ok()),
+            signing_key: sep10
+                .as_ref()
+                .map(|c| c.server_account.account().to_string()),
+            web_auth_domain: sep10.map(|c| c.web_auth_domain),
+            accounts,
+            endpoints,
+            currencies: currencies(assets),
+            org_name: config.org_name.clone(),
+            org_url: config.org_url.clone(),
+        }
+    }
+
+    /// Problems with the published URLs; see the module docs.
+    pub fn validate(&self, base_url: &str) -> Vec<String> {
+        let mut problems = Vec::new();
+        for (endpoint, published) in &self.endpoints {
+            let expected = format!("{base_url}{}", endpoint.path);
+            if published != &expected {
+                problems.push(format!(
+                    "{} is {published}, but its routes are mounted at {expected}",
+                    endpoint.key
+                ));
+            }
+            let Ok(url) = url::Url::parse(published) else {
+                problems.push(format!("{} is not a URL: {published}", endpoint.key));
+                continue;
+            };
+            let loopback = matches!(url.host_str(), Some("localhost" | "127.0.0.1"));
+            if url.scheme() != "https" && !loopback {
+                problems.push(format!("{} must be an https URL", endpoint.key));
+            }
+            if endpoint == &WEB_AUTH_ENDPOINT {
+                if let Some(domain) = &self.web_auth_domain {
+                    let host = match url.port() {
+                        Some(port) => format!("{}:{port}", url.host_str().unwrap_or_default()),
+                        None => url.host_str().unwrap_or_default().to_string(),
+                    };
+                    if &host != domain {
+                        problems.push(format!(
+                            "WEB_AUTH_ENDPOINT is on {host}, but SEP-10 challenges name {domain}"
+                        ));
+                    }
+                }
+            }
+        }
+        problems
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let mut line = |key: &str, value: &str| {
+            let _ = writeln!(out, "{key} = {}", quote(value));
+        };
+        if let Some(passphrase) = &self.network_passphrase {
+            line("NETWORK_PASSPHRASE", passphrase);
+        }
+        if let Some(key) = &self.signing_key {
+            line("SIGNING_KEY", key);
+        }
+        for (endpoint, url) in &self.endpoints {
+            line(endpoint.key, url);
+        }
+        if !self.accounts.is_empty() {
+            let accounts: Vec<String> = self.accounts.iter().map(|a| quote(a)).collect();
+            let _ = writeln!(out, "ACCOUNTS = [{}]", accounts.join(", "));
+        }
+        if self.org_name.is_some() || self.org_url.is_some() {
+            let _ = writeln!(out, "\n[DOCUMENTATION]");
+            if let Some(name) = &self.org_name {
+                let _ = writeln!(out, "ORG_NAME = {}", quote(name));
+            }
+            if let Some(url) = &self.org_url {
+                let _ = writeln!(out, "ORG_URL = {}", quote(url));
+            }
+        }
+        for currency in &self.currencies {
+            let _ = writeln!(out, "\n[[CURRENCIES]]");
+            let _ = writeln!(out, "code = {}", quote(&currency.code));
+            let _ = writeln!(out, "issuer = {}", quote(&currency.issuer));
+            let _ = writeln!(out, "status = \"live\"");
+            let _ = writeln!(out, "is_asset_anchored = true");
+            let _ = writeln!(out, "display_decimals = 7");
+        }
+        out
+    }
+}
+
+/// Enabled assets with an issuer, once each.
+fn currencies(assets: &[Asset]) -> Vec<Currency> {
+    let mut currencies: Vec<Currency> = Vec::new();
+    for asset in assets.iter().filter(|a| a.enabled) {
+        let Some(issuer) = asset.asset_issuer.as_deref().filter(|i| !i.is_empty()) else {
+            continue;
+        };
+        let currency = Currency {
+            code: asset.asset_code.clone(),
+            issuer: issuer.to_string(),
+        };
+        if !currencies.contains(&currency) {
+            currencies.push(currency);
+        }
+    }
+    currencies
+}
+
+/// A TOML basic string.
+fn quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(quoted, "\\u{:04X}", c as u32);
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// The `stellar.toml` to serve, or why it cannot be.
+pub async fn generate(pool: &sqlx::PgPool) -> Result<String, AppError> {
+    let config = StellarTomlConfig::from_env()
+        .map_err(|e| AppError::Internal(format!("stellar.toml is not configured: {e}")))?;
+    let assets = Asset::fetch_all(pool).await?;
+    let toml = StellarToml::from_env(&config, &assets);
+    let problems = toml.validate(&config.base_url);
+    if !problems.is_empty() {
+        return Err(AppError::Internal(format!(
+            "stellar.toml is misconfigured: {}",
+            problems.join("; ")
+        )));
+    }
+    Ok(toml.render())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "https://anchor.example.com";
+    const SERVER: &str = "GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H";
+
+    fn toml() -> StellarToml {
+        StellarToml {
+            network_passphrase: Some("Test SDF Network ; September 2015".to_string()),
+            signing_key: Some(SERVER.to_string()),
+            web_auth_domain: Some("anchor.example.com".to_string()),
+            accounts: vec![SERVER.to_string()],
+            endpoints: [WEB_AUTH_ENDPOINT, KYC_SERVER, ANCHOR_QUOTE_SERVER]
+                .into_iter()
+                .map(|e| (e, format!("{BASE}{}", e.path)))
+                .collect(),
+            currencies: vec![Currency {
+                code: "USDC".to_string(),
+                issuer: SERVER.to_string(),
+            }],
+            org_name: Some("Acme \"Anchor\"".to_string()),
+            org_url: None,
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let rendered = toml().render();
+        assert!(
+            rendered.starts_with("NETWORK_PASSPHRASE = \"Test SDF Network ; September 2015\"\n")
+        );
+        assert!(rendered.contains(&format!("SIGNING_KEY = \"{SERVER}\"\n")));
+        assert!(rendered.contains("WEB_AUTH_ENDPOINT = \"https://anchor.example.com/auth\"\n"));
+        assert!(rendered.contains("KYC_SERVER = \"https://anchor.example.com\"\n"));
+        assert!(rendered.contains("ANCHOR_QUOTE_SERVER = \"https://anchor.example.com/sep38\"\n"));
+        assert!(!rendered.contains("TRANSFER_SERVER"));
+        assert!(rendered.contains("ORG_NAME = \"Acme \\\"Anchor\\\"\"\n"));
+        assert!(rendered.contains("[[CURRENCIES]]\ncode = \"USDC\"\n"));
+    }
+
+    #[test]
+    fn test_validate_accepts_mounted_urls() {
+        assert!(toml().validate(BASE).is_empty());
+    }
+
+    #[test]
+    fn test_validate_rejects_urls_off_the_mounted_routes() {
+        let mut moved = toml();
+        moved.endpoints[2].1 = format!("{BASE}/quotes");
+        let problems = moved.validate(BASE);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].starts_with("ANCHOR_QUOTE_SERVER"));
+
+        let mut insecure = toml();
+        for (endpoint, url) in &mut insecure.endpoints {
+            *url = format!("http://anchor.example.com{}", endpoint.path);
+        }
+        assert_eq!(insecure.validate("http://anchor.example.com").len(), 3);
+    }
+
+    #[test]
+    fn test_validate_checks_the_web_auth_domain() {
+        let mut other = toml();
+        other.web_auth_domain = Some("auth.example.com".to_string());
+        let problems = other.validate(BASE);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("auth.example.com"));
+    }
+
+    #[test]
+    fn test_quote_escapes() {
+        assert_eq!(quote("a\"b\\c\nd"), "\"a\\\"b\\\\c\\nd\"");
+        assert_eq!(quote("\u{1}"), "\"\\u0001\"");
+    }
+}
@@ -0,0 +1,262 @@
+//! Structuring detection: many small deposits from one account inside a
+//! window, typically to stay under reporting thresholds.
+//!
+//! Compliance defines rules in `structuring_rules` (managed through
+//! `/admin/structuring/rules`). Each rule aggregates the deposits of every
+//! account over the last `window_hours` (default 24), optionally only for one
+//! `asset_code` and only counting deposits of at most `max_single_amount`, and
+//! flags the account once every configured threshold is reached:
+//!
+//! | Threshold   | Reached when                                  |
+//! |-------------|-----------------------------------------------|
+//! | `min_count` | the account made at least this many deposits  |
+//! | `min_total` | their amounts add up to at least this much    |
+//!
+//! The hourly [`StructuringJob`] evaluates every enabled rule and opens a
+//! review item in `structuring_reviews` per flagged account. An account has at
+//! most one open item per rule; later runs refresh its counts and transaction
+//! list until compliance dismisses or escalates it:
+//!
+//! ```text
+//! open ──dismiss──▶ dismissed
+//!   └───escalate──▶ escalated
+//! ```
+
+use crate::db::models::StructuringRule;
+use crate::db::queries;
+use crate::services::scheduler::{CancellationToken, Job};
+use crate::validation::ASSET_CODE_MAX_LEN;
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::types::BigDecimal;
+use sqlx::PgPool;
+use std::str::FromStr;
+use tracing::info;
+
+pub const DEFAULT_WINDOW_HOURS: i32 = 24;
+pub const MAX_WINDOW_HOURS: i32 = 720;
+const NAME_MAX_LEN: usize = 100;
+
+/// A rule definition as created or replaced through the admin API.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RuleSpec {
+    pub name: String,
+    /// `None` applies the rule to every asset, summed together.
+    pub asset_code: Option<String>,
+    pub window_hours: i32,
+    pub min_count: Option<i32>,
+    pub min_total: Option<BigDecimal>,
+    /// Only deposits at or below this amount count; `None` counts all.
+    pub max_single_amount: Option<BigDecimal>,
+    pub enabled: bool,
+}
+
+impl RuleSpec {
+    /// Normalise the name and asset code and check the thresholds.
+    pub fn validate(mut self) -> Result<Self, String> {
+        self.name = self.name.trim().to_string();
+        if self.name.is_empty() || self.name.len() > NAME_MAX_LEN {
+            return Err(format!("name must be 1-{NAME_MAX_LEN} characters"));
+        }
+        if let Some(code) = &self.asset_code {
+            let code = code.trim().to_ascii_uppercase();
+            if code.is_empty()
+                || code.len() > ASSET_CODE_MAX_LEN
+                || !code.chars().all(|c| c.is_ascii_alphanumeric())
+            {
+                return Err(format!("asset_code: '{code}' is not a valid asset code"));
+            }
+            self.asset_code = Some(code);
+        }
+        if !(1..=MAX_WINDOW_HOURS).contains(&self.window_hours) {
+            return Err(format!(
+                "window_hours must be between 1 and {MAX_WINDOW_HOURS}"
+            ));
+        }
+        if self.min_count.is_none() && self.min_total.is_none() {
+            return Err("set at least one of min_count and min_total".to_string());
+        }
+        if matches!(self.min_count, Some(n) if n <= 0) {
+            return Err("min_count must be greater than 0".to_string());
+        }
+        let zero = BigDecimal::from(0);
+        for (field, value) in [
+            ("min_total", &self.min_total),
+            ("max_single_amount", &self.max_single_amount),
+        ] {
+            if matches!(value, Some(v) if *v <= zero) {
+                return Err(format!("{field} must be greater than 0"));
+            }
+        }
+        Ok(self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewStatus {
+    Open,
+    Dismissed,
+    Escalated,
+}
+
+impl ReviewStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewStatus::Open => "open",
+            ReviewStatus::Dismissed => "dismissed",
+            ReviewStatus::Escalated => "escalated",
+        }
+    }
+}
+
+impl FromStr for ReviewStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "open" => Ok(ReviewStatus::Open),
+            "dismissed" => Ok(ReviewStatus::Dismissed),
+            "escalated" => Ok(ReviewStatus::Escalated),
+            _ => Err(format!("Invalid review status: {s}")),
+        }
+    }
+}
+
+/// Evaluates every enabled structuring rule and opens review items.
+pub struct StructuringJob {
+    pub pool: PgPool,
+}
+
+impl StructuringJob {
+    /// Flag the accounts `rule` matches over the window ending now. Returns
+    /// how many review items were opened and how many open ones refreshed.
+    pub async fn evaluate(&self, rule: &StructuringRule) -> Result<(u64, u64), sqlx::Error> {
+        let window_end = Utc::now();
+        let window_start = window_end - Duration::hours(rule.window_hours as i64);
+        queries::flag_structuring_accounts(&self.pool, rule, window_start, window_end).await
+    }
+}
+
+#[async_trait]
+impl Job for StructuringJob {
+    fn name(&self) -> &str {
+        "structuring_detection"
+    }
+
+    /// Run at the top of every hour.
+    fn schedule(&self) -> &str {
+        "0 0 * * * * *"
+    }
+
+    async fn execute(
+        &self,
+        cancel: &CancellationToken,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let rules = queries::list_structuring_rules(&self.pool, true).await?;
+        for rule in &rules {
+            if cancel.is_cancelled() {
+                break;
+            }
+            let (opened, refreshed) = self.evaluate(rule).await?;
+            if opened > 0 {
+                tracing::warn!(
+                    rule_id = %rule.id,
+                    rule = %rule.name,
+                    opened,
+                    "Structuring rule flagged accounts for review"
+                );
+            }
+            info!(rule_id = %rule.id, opened, refreshed, "Structuring rule evaluated");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec() -> RuleSpec {
+        RuleSpec {
+            name: " many small USDC deposits ".to_string(),
+            asset_code: Some("usdc".to_string()),
+            window_hours: DEFAULT_WINDOW_HOURS,
+            min_count: Some(10),
+            min_total: None,
+            max_single_amount: Some(BigDecimal::from(1000)),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_validate_normalises_name_and_asset() {
+        let rule = spec().validate().unwrap();
+        assert_eq!(rule.name, "many small USDC deposits");
+        assert_eq!(rule.asset_code.as_deref(), Some("USDC"));
+    }
+
+    #[test]
+    fn test_validate_requires_a_threshold() {
+        let err = RuleSpec {
+            min_count: None,
+            min_total: None,
+            ..spec()
+        }
+        .validate()
+        .unwrap_err();
+        assert!(err.contains("min_count"), "{err}");
+
+        let total_only = RuleSpec {
+            min_count: None,
+            min_total: Some(BigDecimal::from(9000)),
+            ..spec()
+        };
+        assert!(total_only.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_out_of_range_values() {
+        for bad in [
+            RuleSpec {
+                name: "  ".to_string(),
+                ..spec()
+            },
+            RuleSpec {
+                asset_code: Some("NOT-AN-ASSET".to_string()),
+                ..spec()
+            },
+            RuleSpec {
+                window_hours: 0,
+                ..spec()
+            },
+            RuleSpec {
+                window_hours: MAX_WINDOW_HOURS + 1,
+                ..spec()
+            },
+            RuleSpec {
+                min_count: Some(0),
+                ..spec()
+            },
+            RuleSpec {
+                max_single_amount: Some(BigDecimal::from(-5)),
+                ..spec()
+            },
+        ] {
+            assert!(bad.clone().validate().is_err(), "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn test_review_status_round_trip() {
+        for s in [
+            ReviewStatus::Open,
+            ReviewStatus::Dismissed,
+            ReviewStatus::Escalated,
+        ] {
+            assert_eq!(ReviewStatus::from_str(s.as_str()), Ok(s));
+        }
+        assert!(ReviewStatus::from_str("closed").is_err());
+    }
+}
@@ -0,0 +1,315 @@
+//! Exactly-once ledger of the Stellar transactions the anchor submits.
+//!
+//! Every transaction the anchor signs is written to `submissions`
+//! ([`Submission`]) before it is sent, keyed by what it pays out
+//! (`transaction_id`, e.g. a settlement) and an attempt number:
+//!
+//! | Status      | Meaning                                                      |
+//! |-------------|--------------------------------------------------------------|
+//! | `pending`   | Signed and recorded; it may or may not have reached a ledger |
+//! | `succeeded` | In a ledger and successful                                   |
+//! | `failed`    | Rejected, or in a ledger but failed; nothing was paid        |
+//! | `expired`   | Never reached a ledger before its time bounds ran out        |
+//!
+//! A `pending` attempt blocks new ones. Its outcome is looked up on Horizon
+//! by hash; while Horizon does not know it and it can still land, the same
+//! signed envelope is resubmitted, which the network applies at most once.
+//! A rejection of a resubmission proves nothing (the first copy may have
+//! consumed the sequence number), so only a Horizon record or the time
+//! bounds running out settle it. A new attempt, at a fresh sequence number,
+//! is only built after a `failed` or `expired` one. [`recover_in_flight`]
+//! resolves whatever was pending when the process stopped.
+//!
+//! [`recover_in_flight`]: SubmissionLedger::recover_in_flight
+
+use crate::db::models::{StellarTransaction, Submission};
+use crate::ports::TransactionSigner;
+use crate::stellar::xdr::{Memo, Operation, TransactionEnvelope};
+use crate::stellar::{HorizonClient, HorizonError, SubmittedTransaction};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use thiserror::Error;
+use uuid::Uuid;
+
+pub const STATUS_PENDING: &str = "pending";
+pub const STATUS_SUCCEEDED: &str = "succeeded";
+pub const STATUS_FAILED: &str = "failed";
+pub const STATUS_EXPIRED: &str = "expired";
+
+/// How long after a transaction's upper time bound Horizon may still be
+/// ingesting the ledger that included it.
+const EXPIRY_GRACE_SECS: i64 = 30;
+
+#[derive(Debug, Error)]
+pub enum SubmissionError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Horizon(#[from] HorizonError),
+    /// An earlier attempt succeeded; its hash.
+    #[error("already submitted as {0}")]
+    AlreadySubmitted(String),
+    /// An earlier attempt's outcome is still unknown.
+    #[error("a submission for {0} is still in flight")]
+    InFlight(Uuid),
+    #[error("stored envelope is invalid: {0}")]
+    Envelope(String),
+}
+
+#[derive(Clone)]
+pub struct SubmissionLedger {
+    pool: PgPool,
+    horizon: HorizonClient,
+    network_passphrase: String,
+}
+
+impl SubmissionLedger {
+    pub fn new(
+        pool: PgPool,
+        horizon: HorizonClient,
+        network_passphrase: impl Into<String>,
+    ) -> Self {
+        Self {
+            pool,
+            horizon,
+            network_passphrase: network_passphrase.into(),
+        }
+    }
+
+    /// Pay out `transaction_id` with a transaction of `operations` signed by
+    /// `signer`, unless an earlier attempt succeeded or may still land.
+    pub async fn submit(
+        &self,
+        transaction_id: Uuid,
+        signer: &dyn TransactionSigner,
+        memo: Memo,
+        operations: Vec<Operation>,
+        timeout: Duration,
+    ) -> Result<SubmittedTransaction, SubmissionError> {
+        let attempt = match Submission::latest(&self.pool, transaction_id).await? {
+            None => 1,
+            Some(latest) => {
+                let latest = if latest.status == STATUS_PENDING {
+                    self.resolve(latest).await?
+                } else {
+                    latest
+                };
+                match latest.status.as_str() {
+                    STATUS_SUCCEEDED => return Err(SubmissionError::AlreadySubmitted(latest.hash)),
+                    STATUS_PENDING => return Err(SubmissionError::InFlight(transaction_id)),
+                    _ => latest.attempt + 1,
+                }
+            }
+        };
+
+        let envelope = self
+            .horizon
+            .prepare_transaction(signer, &self.network_passphrase, memo, operations, timeout)
+            .await?;
+        let hash = hex::encode(envelope.tx.hash(&self.network_passphrase));
+        let expires_at = envelope
+            .tx
+            .time_bounds
+            .and_then(|(_, max_time)| expiry(max_time));
+        let submission = Submission::insert(
+            &self.pool,
+            transaction_id,
+            attempt,
+            &hash,
+            &STANDARD.encode(envelope.to_xdr()),
+            expires_at,
+        )
+        .await?
+        .ok_or(SubmissionError::InFlight(transaction_id))?;
+
+        let (_, outcome) = self.send(submission, &envelope, false).await?;
+        Ok(outcome?)
+    }
+
+    /// Settle a `pending` attempt from Horizon's record of its hash, or
+    /// resubmit its envelope while it can still land. Returns the attempt as
+    /// it now stands, which may still be `pending`.
+    pub async fn resolve(&self, submission: Submission) -> Result<Submission, SubmissionError> {
+        if let Some(record) = self.horizon.get_transaction(&submission.hash).await? {
+            if let Some(xdr) = StellarTransaction::from_horizon(&record) {
+                self.keep_xdr(xdr).await;
+            }
+            let (status, fallback) = if record.successful {
+                (STATUS_SUCCEEDED, "tx_success")
+            } else {
+                (STATUS_FAILED, "tx_failed")
+            };
+            let result = record
+                .result()
+                .map(|result| result.to_string())
+                .unwrap_or_else(|| fallback.to_string());
+            return Ok(self
+                .settle(submission, status, Some(record.ledger), Some(&result))
+                .await?);
+        }
+        if is_expired(submission.expires_at, Utc::now()) {
+            return Ok(self.settle(submission, STATUS_EXPIRED, None, None).await?);
+        }
+
+        let envelope = STANDARD
+            .decode(&submission.envelope_xdr)
+            .map_err(|e| SubmissionError::Envelope(e.to_string()))
+            .and_then(|bytes| {
+                TransactionEnvelope::from_xdr(&bytes)
+                    .map_err(|e| SubmissionError::Envelope(e.to_string()))
+            })?;
+        let (submission, outcome) = self.send(submission, &envelope, true).await?;
+        if let Err(e) = outcome {
+            tracing::debug!(hash = %submission.hash, "Resubmission did not settle: {}", e);
+        }
+        Ok(submission)
+    }
+
+    /// Resolve every `pending` attempt; run on startup before anything new
+    /// is submitted. Returns how many are still pending.
+    pub async fn recover_in_flight(&self) -> Result<usize, sqlx::Error> {
+        let mut unresolved = 0;
+        for submission in Submission::pending(&self.pool).await? {
+            let (id, hash) = (submission.transaction_id, submission.hash.clone());
+            match self.resolve(submission).await {
+                Ok(resolved) if resolved.status == STATUS_PENDING => unresolved += 1,
+                Ok(resolved) => tracing::info!(
+                    transaction_id = %id,
+                    hash = %hash,
+                    status = %resolved.status,
+                    "Resolved in-flight submission"
+                ),
+                Err(e) => {
+                    unresolved += 1;
+                    tracing::warn!(
+                        transaction_id = %id,
+                        hash = %hash,
+                        "Failed to resolve submission: {}",
+                        e
+                    );
+                }
+            }
+        }
+        Ok(unresolved)
+    }
+
+    /// Submit `envelope` and record what Horizon's answer proves. A
+    /// rejection settles a first submission as `failed`, but not a
+    /// resubmission; an unknown outcome leaves the attempt `pending`.
+    async fn send(
+        &self,
+        submission: Submission,
+        envelope: &TransactionEnvelope,
+        resubmission: bool,
+    ) -> Result<(Submission, Result<SubmittedTransaction, HorizonError>), sqlx::Error> {
+        let outcome = self
+            .horizon
+            .submit_transaction(envelope, &self.network_passphrase)
+            .await;
+        let submission = match &outcome {
+            Ok(submitted) => {
+                self.keep_xdr(StellarTransaction::new(
+                    submitted.hash.clone(),
+                    submitted.ledger,
+                    true,
+                    STANDARD.encode(envelope.to_xdr()),
+                    Some(submitted.result_xdr.clone()),
+                ))
+                .await;
+                let result = submitted.result.to_string();
+                self.settle(
+                    submission,
+                    STATUS_SUCCEEDED,
+                    Some(submitted.ledger),
+                    Some(&result),
+                )
+                .await?
+            }
+            Err(HorizonError::TransactionFailed { result, .. }) if !resubmission => {
+                let result = result.to_string();
+                self.settle(submission, STATUS_FAILED, None, Some(&result))
+                    .await?
+            }
+            Err(HorizonError::InvalidTransaction(reason)) if !resubmission => {
+                self.settle(submission, STATUS_FAILED, None, Some(reason))
+                    .await?
+            }
+            Err(e) => {
+                tracing::warn!(
+                    transaction_id = %submission.transaction_id,
+                    hash = %submission.hash,
+                    "Submission outcome unknown, left pending: {}",
+                    e
+                );
+                submission
+            }
+        };
+        Ok((submission, outcome))
+    }
+
+    /// Keep the XDR of a transaction that made a ledger. Only a support
+    /// aid, so failing to store it does not fail the submission.
+    async fn keep_xdr(&self, xdr: StellarTransaction) {
+        if let Err(e) = xdr.record(&self.pool).await {
+            tracing::warn!(hash = %xdr.hash, "Failed to store transaction XDR: {}", e);
+        }
+    }
+
+    /// Record `status` on a pending attempt. When it was settled meanwhile
+    /// the stale row is returned, which callers treat as still in flight.
+    async fn settle(
+        &self,
+        submission: Submission,
+        status: &str,
+        ledger: Option<i64>,
+        result: Option<&str>,
+    ) -> Result<Submission, sqlx::Error> {
+        Ok(
+            Submission::resolve(&self.pool, submission.id, status, ledger, result)
+                .await?
+                .unwrap_or(submission),
+        )
+    }
+}
+
+/// The instant a transaction's `max_time` passes; `None` when unbounded.
+fn expiry(max_time: u64) -> Option<DateTime<Utc>> {
+    if max_time == 0 {
+        return None;
+    }
+    DateTime::from_timestamp(i64::try_from(max_time).ok()?, 0)
+}
+
+/// Whether a transaction bounded by `expires_at` can no longer land, with
+/// room for Horizon to ingest the last ledger it could have made.
+fn is_expired(expires_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    expires_at.is_some_and(|at| now > at + Duration::seconds(EXPIRY_GRACE_SECS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unbounded_transactions_never_expire() {
+        assert_eq!(expiry(0), None);
+        assert!(!is_expired(None, Utc::now()));
+    }
+
+    #[test]
+    fn test_expiry_waits_for_grace() {
+        let at = expiry(1_800_000_000).unwrap();
+        assert_eq!(at.timestamp(), 1_800_000_000);
+        assert!(!is_expired(Some(at), at));
+        assert!(!is_expired(
+            Some(at),
+            at + Duration::seconds(EXPIRY_GRACE_SECS)
+        ));
+        assert!(is_expired(
+            Some(at),
+            at + Duration::seconds(EXPIRY_GRACE_SECS + 1)
+        ));
+    }
+}
@@ -0,0 +1,130 @@
+//! Transaction status frames.
+//!
+//! Producers publish [`DomainEvent`]s on `AppState::domain_events`. The `/ws`
+//! stream and the GraphQL `transactionStatusChanged` subscription, when those
+//! subsystems are compiled in, turn each `StatusChanged` event into a
+//! [`TransactionStatusUpdate`], whose shape clients already depend on.
+
+use crate::domain::DomainEvent;
+#[cfg(feature = "graphql")]
+use crate::graphql::scalars::{DateTimeScalar, UuidScalar};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
+#[cfg_attr(feature = "graphql", graphql(complex))]
+pub struct TransactionStatusUpdate {
+    #[cfg_attr(feature = "graphql", graphql(skip))]
+    #[schema(value_type = String, format = "uuid")]
+    pub transaction_id: Uuid,
+    #[cfg_attr(feature = "graphql", graphql(skip))]
+    #[schema(value_type = String, format = "uuid")]
+    pub tenant_id: Uuid,
+    pub status: String,
+    #[cfg_attr(feature = "graphql", graphql(skip))]
+    #[schema(value_type = String, format = DateTime)]
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub message: Option<String>,
+    /// Where the transaction landed on the Stellar network, once verified.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stellar_tx_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ledger: Option<i64>,
+    #[cfg_attr(feature = "graphql", graphql(skip))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub closed_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// GraphQL view of the skipped fields, using the schema's custom scalars.
+#[cfg(feature = "graphql")]
+#[async_graphql::ComplexObject]
+impl TransactionStatusUpdate {
+    async fn transaction_id(&self) -> UuidScalar {
+        self.transaction_id.into()
+    }
+    async fn tenant_id(&self) -> UuidScalar {
+        self.tenant_id.into()
+    }
+    async fn timestamp(&self) -> DateTimeScalar {
+        self.timestamp.into()
+    }
+    async fn closed_at(&self) -> Option<DateTimeScalar> {
+        self.closed_at.map(Into::into)
+    }
+}
+
+impl TransactionStatusUpdate {
+    /// The frame for a `StatusChanged` event; `None` for any other event.
+    pub fn from_event(event: &DomainEvent) -> Option<Self> {
+        let DomainEvent::StatusChanged {
+            transaction_id,
+            tenant_id,
+            to,
+            message,
+            stellar_tx_hash,
+            ledger,
+            closed_at,
+            at,
+            ..
+        } = event
+        else {
+            return None;
+        };
+        Some(Self {
+            transaction_id: *transaction_id,
+            tenant_id: tenant_id.unwrap_or_default(),
+            status: to.clone(),
+            timestamp: *at,
+            message: message.clone(),
+            stellar_tx_hash: stellar_tx_hash.clone(),
+            ledger: *ledger,
+            closed_at: *closed_at,
+        })
+    }
+}
+
+/// A status change whose previous status is unknown.
+impl From<TransactionStatusUpdate> for DomainEvent {
+    fn from(update: TransactionStatusUpdate) -> Self {
+        DomainEvent::StatusChanged {
+            transaction_id: update.transaction_id,
+            tenant_id: Some(update.tenant_id).filter(|t| !t.is_nil()),
+            from: None,
+            to: update.status,
+            message: update.message,
+            stellar_tx_hash: update.stellar_tx_hash,
+            ledger: update.ledger,
+            closed_at: update.closed_at,
+            at: update.timestamp,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_status_update_round_trips_through_domain_event() {
+        let update = TransactionStatusUpdate {
+            transaction_id: Uuid::new_v4(),
+            tenant_id: Uuid::new_v4(),
+            status: "completed".to_string(),
+            timestamp: Utc::now(),
+            message: None,
+            stellar_tx_hash: Some("ab".repeat(32)),
+            ledger: Some(42),
+            closed_at: None,
+        };
+        let event = DomainEvent::from(update.clone());
+        let back = TransactionStatusUpdate::from_event(&event).unwrap();
+        assert_eq!(back.transaction_id, update.transaction_id);
+        assert_eq!(back.tenant_id, update.tenant_id);
+        assert_eq!(back.status, "completed");
+        assert_eq!(back.ledger, Some(42));
+    }
+}
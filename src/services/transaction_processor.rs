@@ -1,6 +1,8 @@
 use crate::services::webhook_dispatcher::WebhookDispatcher;
+use crate::telemetry::BusinessAttributes;
 use sqlx::PgPool;
-use tracing::instrument;
+use tracing::field::Empty;
+use tracing::{instrument, Span};
 
 #[async_trait::async_trait]
 pub trait ProcessingStage: Send + Sync {
@@ -79,8 +81,11 @@ impl ProcessingStage for CompleteStage {
                 .await?;
 
         // Validate status transition: current status → completed
-        crate::validation::state_machine::validate_status_transition(&tx.status, "completed")
-            .map_err(|e| anyhow::anyhow!("{e}"))?;
+        crate::validation::state_machine::validate_status_transition(
+            tx.status.as_str(),
+            "completed",
+        )
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
 
         sqlx::query(
             "UPDATE transactions SET status = 'completed', updated_at = NOW() WHERE id = $1",
@@ -123,7 +128,11 @@ impl TransactionProcessor {
         self
     }
 
-    #[instrument(name = "processor.process_transaction", skip(self), fields(transaction.id = %tx_id))]
+    #[instrument(
+        name = "processor.process_transaction",
+        skip(self),
+        fields(transaction.id = %tx_id, asset_code = Empty, amount_bucket = Empty)
+    )]
     pub async fn process_transaction(&self, tx_id: uuid::Uuid) -> anyhow::Result<()> {
         // Fetch the transaction first
         let tx: crate::db::models::Transaction =
@@ -131,6 +140,7 @@ impl TransactionProcessor {
                 .bind(tx_id)
                 .fetch_one(&self.pool)
                 .await?;
+        BusinessAttributes::for_transaction(&tx, None).record(&Span::current());
 
         // Define the pipeline stages
         let mut stages: Vec<Box<dyn ProcessingStage>> = Vec::new();
@@ -216,7 +226,7 @@ impl TransactionProcessor {
 
         // Get current status and asset_code
         let (current_status, asset_code): (String, String) =
-            sqlx::query_as("SELECT status, asset_code FROM transactions WHERE id = $1")
+            sqlx::query_as("SELECT status::text, asset_code FROM transactions WHERE id = $1")
                 .bind(tx_id)
                 .fetch_one(&self.pool)
                 .await?;
@@ -225,10 +235,14 @@ impl TransactionProcessor {
         crate::validation::state_machine::validate_status_transition(&current_status, "pending")
             .map_err(|e| anyhow::anyhow!("{e}"))?;
 
-        sqlx::query("UPDATE transactions SET status = 'pending', updated_at = NOW() WHERE id = $1")
-            .bind(tx_id)
-            .execute(&self.pool)
-            .await?;
+        // A fresh round of Horizon verification attempts.
+        sqlx::query(
+            "UPDATE transactions SET status = 'pending', verification_attempts = 0, \
+             updated_at = NOW() WHERE id = $1",
+        )
+        .bind(tx_id)
+        .execute(&self.pool)
+        .await?;
 
         sqlx::query("DELETE FROM transaction_dlq WHERE id = $1")
             .bind(dlq_id)
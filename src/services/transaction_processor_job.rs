@@ -1,24 +1,41 @@
-use crate::services::scheduler::Job;
+use crate::config::jobs::JobSettings;
+use crate::domain::DomainEvent;
+use crate::ports::EventBus;
+use crate::services::scheduler::{CancellationToken, Job, RunParams};
 use crate::stellar::HorizonClient;
 use async_trait::async_trait;
 use sqlx::PgPool;
 use std::error::Error;
 use std::io;
+use std::sync::Arc;
 use tracing::info;
 
 /// Wrapper for the TransactionProcessor to make it compatible with the Job trait
 pub struct TransactionProcessorJob {
     pool: PgPool,
     horizon_client: HorizonClient,
+    batch_size: u32,
+    events: Option<Arc<dyn EventBus<DomainEvent>>>,
 }
 
+/// Transactions processed per run unless `JOB_TRANSACTION_PROCESSOR_BATCH_SIZE` is set.
+const DEFAULT_BATCH_SIZE: u32 = 10;
+
 impl TransactionProcessorJob {
     pub fn new(pool: PgPool, horizon_client: HorizonClient) -> Self {
         Self {
             pool,
             horizon_client,
+            batch_size: DEFAULT_BATCH_SIZE,
+            events: None,
         }
     }
+
+    /// Publish each batch's domain events on `events`.
+    pub fn with_events(mut self, events: Arc<dyn EventBus<DomainEvent>>) -> Self {
+        self.events = Some(events);
+        self
+    }
 }
 
 #[async_trait]
@@ -31,12 +48,39 @@ impl Job for TransactionProcessorJob {
         "*/5 * * * * *" // Every 5 seconds
     }
 
-    async fn execute(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+    fn configure(&mut self, settings: &JobSettings) {
+        if let Some(size) = settings.batch_size {
+            self.batch_size = size;
+        }
+    }
+
+    /// One batch should never take this long.
+    fn timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(60)
+    }
+
+    async fn execute(
+        &self,
+        cancel: &CancellationToken,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.execute_with(cancel, &RunParams::default()).await
+    }
+
+    async fn execute_with(
+        &self,
+        _cancel: &CancellationToken,
+        params: &RunParams,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
         info!("Running scheduled transaction processor job");
 
         // Process a single batch of transactions instead of running continuously
-        let result =
-            crate::services::processor::process_batch(&self.pool, &self.horizon_client, 10).await;
+        let result = crate::services::processor::process_batch(
+            &self.pool,
+            &self.horizon_client,
+            params.batch_size.unwrap_or(self.batch_size),
+            self.events.as_deref(),
+        )
+        .await;
 
         match result {
             Ok(_) => {
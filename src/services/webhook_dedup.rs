@@ -0,0 +1,111 @@
+//! Content-hash deduplication for inbound callbacks.
+//!
+//! Idempotency keys only protect retries that reuse the same key before it
+//! expires. Some partners instead resend an identical webhook days later with
+//! no key at all. The ingestion path hashes each accepted payload (together
+//! with its `anchor_transaction_id`) into the `webhook_inbox` table, and a
+//! repeat of the same hash inside the lookback window returns the original
+//! transaction instead of creating a duplicate.
+//!
+//! The window is configured with `WEBHOOK_DEDUP_WINDOW_HOURS` (default 168,
+//! i.e. seven days). `0` disables content-hash deduplication.
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+
+/// Default lookback window in hours.
+pub const DEFAULT_WINDOW_HOURS: u64 = 7 * 24;
+
+/// Response header set when a callback was answered from the inbox.
+pub const DEDUPLICATED_HEADER: &str = "x-webhook-deduplicated";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DedupConfig {
+    /// How far back a matching payload hash is honoured. `None` disables dedup.
+    pub window: Option<Duration>,
+}
+
+impl Default for DedupConfig {
+    fn default() -> Self {
+        Self {
+            window: Some(Duration::from_secs(DEFAULT_WINDOW_HOURS * 3600)),
+        }
+    }
+}
+
+impl DedupConfig {
+    pub fn from_env() -> Self {
+        Self::from_hours(
+            std::env::var("WEBHOOK_DEDUP_WINDOW_HOURS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(DEFAULT_WINDOW_HOURS),
+        )
+    }
+
+    pub fn from_hours(hours: u64) -> Self {
+        Self {
+            window: (hours > 0).then(|| Duration::from_secs(hours * 3600)),
+        }
+    }
+}
+
+/// SHA-256 (hex) over the anchor transaction id and the canonical JSON form of
+/// `payload`.
+///
+/// `serde_json::Value` objects are key-sorted, so two payloads differing only
+/// in field order hash identically.
+pub fn payload_hash<T: Serialize>(anchor_transaction_id: Option<&str>, payload: &T) -> String {
+    let canonical = serde_json::to_value(payload)
+        .and_then(|v| serde_json::to_vec(&v))
+        .unwrap_or_default();
+
+    let mut hasher = Sha256::new();
+    hasher.update(anchor_transaction_id.unwrap_or_default().as_bytes());
+    // Separator so ("ab", "c…") and ("a", "bc…") cannot collide.
+    hasher.update([0u8]);
+    hasher.update(&canonical);
+    hex::encode(hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_hash_ignores_field_order() {
+        let a: serde_json::Value =
+            serde_json::from_str(r#"{"amount":"10","asset_code":"USDC"}"#).unwrap();
+        let b: serde_json::Value =
+            serde_json::from_str(r#"{"asset_code":"USDC","amount":"10"}"#).unwrap();
+        assert_eq!(
+            payload_hash(Some("anc-1"), &a),
+            payload_hash(Some("anc-1"), &b)
+        );
+    }
+
+    #[test]
+    fn test_hash_covers_anchor_id_and_content() {
+        let body = json!({"amount": "10"});
+        let base = payload_hash(Some("anc-1"), &body);
+        assert_ne!(base, payload_hash(Some("anc-2"), &body));
+        assert_ne!(base, payload_hash(None, &body));
+        assert_ne!(base, payload_hash(Some("anc-1"), &json!({"amount": "11"})));
+        assert_eq!(base.len(), 64);
+    }
+
+    #[test]
+    fn test_window_config() {
+        assert_eq!(
+            DedupConfig::default().window,
+            Some(Duration::from_secs(168 * 3600))
+        );
+        assert_eq!(DedupConfig::from_hours(0).window, None);
+        assert_eq!(
+            DedupConfig::from_hours(2).window,
+            Some(Duration::from_secs(7200))
+        );
+    }
+}
@@ -3,16 +3,27 @@
 //! Delivers signed HMAC-SHA256 payloads to registered endpoints when
 //! transactions reach terminal states. Retries with exponential backoff
 //! up to MAX_ATTEMPTS times and records every attempt in webhook_deliveries.
-
+//!
+//! Each delivery carries its endpoint's next sequence number. The endpoint's
+//! offset (`acked_sequence`) is the sequence up to which every delivery has
+//! been acknowledged or moved to the DLQ; it is persisted after each settled
+//! delivery, and a restarted dispatcher only claims deliveries above it.
+
+use crate::adapters::SystemClock;
+use crate::domain::{DomainEvent, EventEnvelope};
+use crate::ports::{Clock, Delivery, EventStream};
+use crate::services::breakers;
 use chrono::Utc;
 use futures::stream::{self, StreamExt};
 use hmac::{Hmac, Mac};
 use redis::{AsyncCommands, Client, Script};
 use reqwest::Client as HttpClient;
 use serde::{Deserialize, Serialize};
-use sha2::{Sha256, Sha512};
+use sha2::{Digest, Sha256, Sha512};
 use sqlx::{PgPool, Row};
 use std::collections::HashMap;
+use std::sync::Arc;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
 const MAX_ATTEMPTS: i32 = 5;
@@ -25,6 +36,13 @@ const CLAIM_TIMEOUT_SECS: i64 = 300;
 const CB_FAILURE_THRESHOLD: u32 = 3;
 /// Circuit breaker: seconds before an open breaker transitions to half-open.
 const CB_RESET_TIMEOUT_SECS: i64 = 300;
+/// Hours of unbroken delivery failures before an endpoint is paused, unless
+/// `WEBHOOK_AUTO_PAUSE_AFTER_HOURS` overrides it.
+const DEFAULT_AUTO_PAUSE_AFTER_HOURS: i64 = 24;
+
+/// `pause_reason` / notification reason for endpoints paused after
+/// continuous failures.
+pub const PAUSE_REASON_CONTINUOUS_FAILURES: &str = "auto_paused_continuous_failures";
 
 // ── Domain types ─────────────────────────────────────────────────────────────
 
@@ -37,6 +55,21 @@ pub struct WebhookEndpoint {
     pub enabled: bool,
     pub max_delivery_rate: i32,
     pub filter_rules: Option<serde_json::Value>,
+    /// Set for a callback registered on a single transaction; it then gets
+    /// every event of that transaction regardless of `event_types`.
+    pub transaction_id: Option<Uuid>,
+    /// Secret replaced by the last rotation; deliveries are also signed with
+    /// it until `previous_secret_expires_at`.
+    pub previous_secret: Option<String>,
+    pub previous_secret_expires_at: Option<chrono::DateTime<Utc>>,
+    /// First failed delivery since the last success; `None` while healthy.
+    pub failing_since: Option<chrono::DateTime<Utc>>,
+    /// When and why the endpoint was disabled automatically.
+    pub paused_at: Option<chrono::DateTime<Utc>>,
+    pub pause_reason: Option<String>,
+    /// Delivery offset: every delivery numbered up to this one has been
+    /// acknowledged or moved to the DLQ.
+    pub acked_sequence: i64,
     pub created_at: chrono::DateTime<Utc>,
     pub updated_at: chrono::DateTime<Utc>,
 }
@@ -58,17 +91,54 @@ pub struct WebhookDelivery {
     pub max_delivery_rate: i32,
     pub attempt_history: Option<serde_json::Value>,
     pub claimed_at: Option<chrono::DateTime<Utc>>,
+    /// Position in the endpoint's delivery stream; `None` for rows queued
+    /// before sequences existed.
+    pub sequence: Option<i64>,
 }
 
 /// Payload sent to external endpoints.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct OutgoingPayload {
     pub event_type: String,
     pub transaction_id: String,
+    #[schema(value_type = String, format = DateTime)]
     pub timestamp: chrono::DateTime<Utc>,
+    /// Event-specific fields; see `GET /events/schema`.
     pub data: serde_json::Value,
 }
 
+/// Deposit below its asset's `min_amount`, held for a refund.
+pub const REFUND_PENDING_EVENT: &str = "transaction.refund_pending";
+/// Deposit above its asset's `max_amount`, held for compliance review.
+pub const COMPLIANCE_REVIEW_EVENT: &str = "transaction.compliance_review";
+/// Monthly partner statement stored; see [`crate::services::statements`].
+pub const STATEMENT_READY_EVENT: &str = "statement.ready";
+/// Event type of test deliveries sent by [`WebhookDispatcher::send_ping`].
+pub const PING_EVENT: &str = "ping";
+
+/// Body of a test delivery.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PingPayload {
+    /// Always `ping`.
+    pub event_type: &'static str,
+    #[schema(value_type = String, format = "uuid")]
+    pub subscription_id: Uuid,
+    pub timestamp: String,
+    /// Always empty.
+    #[schema(value_type = Object)]
+    pub data: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Outcome of a test delivery.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PingResult {
+    /// `true` when the endpoint answered with a 2xx status.
+    pub delivered: bool,
+    pub response_status: Option<u16>,
+    pub response_time_ms: u64,
+    pub error: Option<String>,
+}
+
 // ── Service ───────────────────────────────────────────────────────────────────
 
 #[derive(Clone)]
@@ -77,6 +147,8 @@ pub struct WebhookDispatcher {
     http: HttpClient,
     redis: Client,
     concurrency: usize,
+    auto_pause_after: chrono::Duration,
+    clock: Arc<dyn Clock>,
 }
 
 impl WebhookDispatcher {
@@ -85,6 +157,10 @@ impl WebhookDispatcher {
             .ok()
             .and_then(|v| v.parse().ok())
             .unwrap_or(10usize);
+        let auto_pause_hours = std::env::var("WEBHOOK_AUTO_PAUSE_AFTER_HOURS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_AUTO_PAUSE_AFTER_HOURS);
         Ok(Self {
             pool,
             http: HttpClient::builder()
@@ -93,10 +169,20 @@ impl WebhookDispatcher {
                 .expect("failed to build reqwest client"),
             redis: Client::open(redis_url)?,
             concurrency,
+            auto_pause_after: chrono::Duration::hours(auto_pause_hours),
+            clock: Arc::new(SystemClock),
         })
     }
 
-    /// Enqueue deliveries for all enabled endpoints subscribed to `event_type`.
+    /// Read the time from `clock` when scheduling retries, reclaiming stuck
+    /// deliveries and timing the circuit breaker.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Enqueue deliveries for all enabled endpoints subscribed to `event_type`,
+    /// plus the callback registered on the transaction, if any.
     /// Call this from TransactionProcessor on every terminal state transition.
     pub async fn enqueue(
         &self,
@@ -104,24 +190,35 @@ impl WebhookDispatcher {
         event_type: &str,
         data: serde_json::Value,
     ) -> anyhow::Result<()> {
-        let endpoints = self.endpoints_for_event(event_type, &data).await?;
+        let endpoints = self
+            .endpoints_for_event(transaction_id, event_type, &data)
+            .await?;
         if endpoints.is_empty() {
             return Ok(());
         }
 
+        let now = self.clock.now();
         let payload = serde_json::to_value(OutgoingPayload {
             event_type: event_type.to_string(),
             transaction_id: transaction_id.to_string(),
-            timestamp: Utc::now(),
+            timestamp: now,
             data,
         })?;
 
         for ep in endpoints {
+            // Taking the sequence locks the endpoint row until the insert
+            // commits, so the offset never moves past an uncommitted delivery.
             let result = sqlx::query(
                 r#"
+                WITH seq AS (
+                    UPDATE webhook_endpoints SET last_sequence = last_sequence + 1
+                    WHERE id = $1
+                    RETURNING last_sequence
+                )
                 INSERT INTO webhook_deliveries
-                    (endpoint_id, transaction_id, event_type, payload, status, next_attempt_at)
-                VALUES ($1, $2, $3, $4, 'pending', NOW())
+                    (endpoint_id, transaction_id, event_type, payload, status, next_attempt_at,
+                     sequence)
+                SELECT $1, $2, $3, $4, 'pending', $5, seq.last_sequence FROM seq
                 ON CONFLICT (endpoint_id, transaction_id, event_type) DO NOTHING
                 "#,
             )
@@ -129,6 +226,7 @@ impl WebhookDispatcher {
             .bind(transaction_id)
             .bind(event_type)
             .bind(&payload)
+            .bind(now)
             .execute(&self.pool)
             .await?;
 
@@ -145,12 +243,47 @@ impl WebhookDispatcher {
         Ok(())
     }
 
+    /// Enqueue `event` under its event type, with its [`EventEnvelope`] as
+    /// `data`. Deliveries are keyed by transaction, one per endpoint and
+    /// event type, so events about no transaction are skipped.
+    pub async fn enqueue_event(&self, event: &DomainEvent) -> anyhow::Result<()> {
+        let Some(transaction_id) = event.transaction_id() else {
+            return Ok(());
+        };
+        let data = serde_json::to_value(EventEnvelope::from(event.clone()))?;
+        self.enqueue(transaction_id, event.event_type(), data).await
+    }
+
+    /// Enqueue every event from `events` until the stream ends. Events the
+    /// subscription lagged past are logged and not delivered.
+    pub async fn forward_events(&self, mut events: EventStream<DomainEvent>) {
+        while let Some(delivery) = events.next().await {
+            match delivery {
+                Delivery::Event(event) => {
+                    if let Err(e) = self.enqueue_event(&event).await {
+                        tracing::error!(
+                            event_type = event.event_type(),
+                            error = %e,
+                            "Failed to enqueue domain event"
+                        );
+                    }
+                }
+                Delivery::Lagged(n) => {
+                    tracing::warn!(skipped = n, "Webhook event forwarding lagged");
+                }
+            }
+        }
+    }
+
     /// Process all pending deliveries concurrently using `buffer_unordered`.
     /// Uses `FOR UPDATE SKIP LOCKED` in a CTE to claim rows atomically so
     /// concurrent replicas never deliver the same event twice.
     /// Also reclaims stuck `in_progress` rows past `CLAIM_TIMEOUT_SECS`.
+    /// Deliveries at or below their endpoint's offset are already
+    /// acknowledged and never claimed again.
     pub async fn process_pending(&self) -> anyhow::Result<()> {
-        let reclaim_cutoff = Utc::now() - chrono::Duration::seconds(CLAIM_TIMEOUT_SECS);
+        let now = self.clock.now();
+        let reclaim_cutoff = now - chrono::Duration::seconds(CLAIM_TIMEOUT_SECS);
 
         // Atomic claim via a CTE: the inner SELECT … FOR UPDATE SKIP LOCKED
         // picks rows that are not already locked by another transaction, then
@@ -158,17 +291,19 @@ impl WebhookDispatcher {
         let deliveries: Vec<WebhookDelivery> = sqlx::query_as(
             r#"
             WITH candidate AS (
-                SELECT id FROM webhook_deliveries
-                WHERE (status = 'pending'
-                   AND (next_attempt_at IS NULL OR next_attempt_at <= NOW()))
-                   OR (status = 'in_progress' AND claimed_at <= $1)
-                ORDER BY created_at
+                SELECT d.id FROM webhook_deliveries d
+                JOIN webhook_endpoints e ON e.id = d.endpoint_id AND e.enabled = true
+                WHERE ((d.status = 'pending'
+                    AND (d.next_attempt_at IS NULL OR d.next_attempt_at <= $2))
+                    OR (d.status = 'in_progress' AND d.claimed_at <= $1))
+                  AND (d.sequence IS NULL OR d.sequence > e.acked_sequence)
+                ORDER BY d.created_at
                 LIMIT 100
-                FOR UPDATE SKIP LOCKED
+                FOR UPDATE OF d SKIP LOCKED
             )
             UPDATE webhook_deliveries wd
             SET status   = 'in_progress',
-                claimed_at = NOW()
+                claimed_at = $2
             FROM webhook_endpoints we, candidate c
             WHERE wd.id = c.id
               AND wd.endpoint_id = we.id
@@ -177,6 +312,7 @@ impl WebhookDispatcher {
             "#,
         )
         .bind(reclaim_cutoff)
+        .bind(now)
         .fetch_all(&self.pool)
         .await?;
 
@@ -212,8 +348,7 @@ impl WebhookDispatcher {
                     // Reschedule every delivery for this endpoint without
                     // burning an attempt, then release the claim.
                     if let Some(deliveries) = by_endpoint.get(ep_id) {
-                        let next_cycle =
-                            Utc::now() + chrono::Duration::seconds(CB_RESET_TIMEOUT_SECS);
+                        let next_cycle = now + chrono::Duration::seconds(CB_RESET_TIMEOUT_SECS);
                         for d in deliveries {
                             sqlx::query(
                                 r#"
@@ -264,7 +399,9 @@ impl WebhookDispatcher {
                         .attempt_delivery_with_endpoint(&delivery, &endpoint_map)
                         .await
                     {
-                        tracing::error!(
+                        crate::sampled_error!(
+                            "webhook_dispatcher.delivery",
+                            e,
                             delivery_id = %delivery.id,
                             "Webhook delivery attempt error: {e}"
                         );
@@ -300,7 +437,9 @@ impl WebhookDispatcher {
         // Check if we're within the rate limit
         let allowed = current_count <= max_rate;
         if !allowed {
-            tracing::warn!(
+            crate::sampled_warn!(
+                "webhook_dispatcher.rate_limit",
+                "rate limit exceeded",
                 endpoint_id = %endpoint_id,
                 current_count = current_count,
                 max_rate = max_rate,
@@ -322,7 +461,7 @@ impl WebhookDispatcher {
             .await?
         {
             // Rate limit exceeded, delay this delivery to next cycle
-            let next_cycle = Utc::now() + chrono::Duration::seconds(30);
+            let next_cycle = self.clock.now() + chrono::Duration::seconds(30);
             sqlx::query(
                 r#"
                 UPDATE webhook_deliveries
@@ -424,9 +563,9 @@ impl WebhookDispatcher {
             .get("timestamp")
             .and_then(|ts| ts.as_str())
             .map(|ts| ts.to_string())
-            .unwrap_or_else(|| Utc::now().to_rfc3339());
+            .unwrap_or_else(|| self.clock.now().to_rfc3339());
 
-        let signature = sign_payload_with_version(&endpoint.secret, &timestamp, &body);
+        let signature = signature_header(endpoint, &timestamp, &body, self.clock.now());
 
         // Get trace_id from transaction if available
         let trace_id: Option<String> =
@@ -443,8 +582,19 @@ impl WebhookDispatcher {
             .header("Content-Type", "application/json")
             .header("X-Webhook-Signature", &signature)
             .header("X-Webhook-Timestamp", &timestamp)
-            .header("X-Webhook-Event", &delivery.event_type);
+            .header("X-Webhook-Event", &delivery.event_type)
+            .header(
+                "X-Webhook-Idempotency-Key",
+                idempotency_key(
+                    delivery.endpoint_id,
+                    delivery.transaction_id,
+                    &delivery.event_type,
+                ),
+            );
 
+        if let Some(sequence) = delivery.sequence {
+            request = request.header("X-Webhook-Sequence", sequence.to_string());
+        }
         if let Some(trace_id) = trace_id {
             request = request.header("X-Trace-Id", trace_id);
         }
@@ -452,13 +602,14 @@ impl WebhookDispatcher {
         let response = request.body(body).send().await;
 
         let new_attempt_count = delivery.attempt_count + 1;
-        let now = Utc::now();
+        let now = self.clock.now();
 
         match response {
             Ok(resp) => {
                 let status_code = resp.status().as_u16() as i32;
                 let resp_body = resp.text().await.unwrap_or_default();
                 let success = (200..300).contains(&(status_code as u16));
+                self.track_failure_streak(endpoint.id, success, now).await;
 
                 // Record attempt history
                 self.append_attempt_history(
@@ -497,6 +648,7 @@ impl WebhookDispatcher {
                         endpoint = %endpoint.url,
                         "Webhook delivered successfully"
                     );
+                    self.advance_offset(delivery.endpoint_id).await?;
                 } else {
                     self.handle_failure(
                         delivery,
@@ -510,6 +662,7 @@ impl WebhookDispatcher {
             }
             Err(e) => {
                 let err_msg = e.to_string();
+                self.track_failure_streak(endpoint.id, false, now).await;
 
                 // Record attempt history
                 self.append_attempt_history(
@@ -530,6 +683,143 @@ impl WebhookDispatcher {
         Ok(())
     }
 
+    /// Maintain `failing_since` after a delivery attempt and pause the
+    /// endpoint once it has failed without a single success for
+    /// `auto_pause_after`. Paused endpoints get no new deliveries and their
+    /// queued ones wait until an operator re-enables them.
+    ///
+    /// Bookkeeping errors are logged rather than failing the delivery.
+    async fn track_failure_streak(
+        &self,
+        endpoint_id: Uuid,
+        success: bool,
+        now: chrono::DateTime<Utc>,
+    ) {
+        if let Err(e) = self.update_failure_streak(endpoint_id, success, now).await {
+            crate::sampled_warn!(
+                "webhook_dispatcher.failure_streak",
+                e,
+                endpoint_id = %endpoint_id,
+                error = %e,
+                "Failed to update webhook endpoint failure streak"
+            );
+        }
+    }
+
+    async fn update_failure_streak(
+        &self,
+        endpoint_id: Uuid,
+        success: bool,
+        now: chrono::DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        if success {
+            sqlx::query(
+                "UPDATE webhook_endpoints SET failing_since = NULL WHERE id = $1 AND failing_since IS NOT NULL",
+            )
+            .bind(endpoint_id)
+            .execute(&self.pool)
+            .await?;
+            return Ok(());
+        }
+
+        let failing_since: Option<chrono::DateTime<Utc>> = sqlx::query_scalar(
+            r#"
+            UPDATE webhook_endpoints
+            SET failing_since = COALESCE(failing_since, $2)
+            WHERE id = $1 AND enabled = TRUE
+            RETURNING failing_since
+            "#,
+        )
+        .bind(endpoint_id)
+        .bind(now)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        if !failing_too_long(failing_since, now, self.auto_pause_after) {
+            return Ok(());
+        }
+
+        let paused = sqlx::query(
+            r#"
+            UPDATE webhook_endpoints
+            SET enabled = FALSE, paused_at = $2, pause_reason = $3, updated_at = NOW()
+            WHERE id = $1 AND enabled = TRUE
+            RETURNING id
+            "#,
+        )
+        .bind(endpoint_id)
+        .bind(now)
+        .bind(PAUSE_REASON_CONTINUOUS_FAILURES)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        if paused.is_some() {
+            tracing::error!(
+                endpoint_id = %endpoint_id,
+                failing_since = ?failing_since,
+                "Webhook endpoint paused after continuous delivery failures"
+            );
+
+            sqlx::query(
+                r#"
+                INSERT INTO webhook_endpoint_notifications (endpoint_id, reason, notified_at)
+                VALUES ($1, $2, NOW())
+                "#,
+            )
+            .bind(endpoint_id)
+            .bind(PAUSE_REASON_CONTINUOUS_FAILURES)
+            .execute(&self.pool)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Send a signed `ping` event straight to `endpoint`.
+    ///
+    /// Test deliveries bypass the queue, rate limit and circuit breaker and
+    /// are not counted in the endpoint's reliability stats.
+    pub async fn send_ping(&self, endpoint: &WebhookEndpoint) -> PingResult {
+        let now = self.clock.now();
+        let timestamp = now.to_rfc3339();
+        let body = serde_json::json!(PingPayload {
+            event_type: PING_EVENT,
+            subscription_id: endpoint.id,
+            timestamp: timestamp.clone(),
+            data: serde_json::Map::new(),
+        })
+        .to_string();
+        let signature = signature_header(endpoint, &timestamp, &body, now);
+
+        let started = std::time::Instant::now();
+        let response = self
+            .http
+            .post(&endpoint.url)
+            .header("Content-Type", "application/json")
+            .header("X-Webhook-Signature", &signature)
+            .header("X-Webhook-Timestamp", &timestamp)
+            .header("X-Webhook-Event", PING_EVENT)
+            .body(body)
+            .send()
+            .await;
+        let response_time_ms = started.elapsed().as_millis() as u64;
+
+        match response {
+            Ok(resp) => PingResult {
+                delivered: resp.status().is_success(),
+                response_status: Some(resp.status().as_u16()),
+                response_time_ms,
+                error: None,
+            },
+            Err(e) => PingResult {
+                delivered: false,
+                response_status: None,
+                response_time_ms,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+
     #[allow(dead_code)]
     async fn attempt_delivery(&self, delivery: &WebhookDelivery) -> anyhow::Result<()> {
         // Check rate limit first
@@ -538,7 +828,7 @@ impl WebhookDispatcher {
             .await?
         {
             // Rate limit exceeded, delay this delivery to next cycle
-            let next_cycle = Utc::now() + chrono::Duration::seconds(30); // Next processing cycle
+            let next_cycle = self.clock.now() + chrono::Duration::seconds(30);
             sqlx::query(
                 r#"
                 UPDATE webhook_deliveries
@@ -592,12 +882,12 @@ impl WebhookDispatcher {
             );
             ("failed", None)
         } else {
-            let delay = BASE_DELAY_SECS * (1_i64 << attempt_count);
-            let next = now + chrono::Duration::seconds(delay);
+            let delay = retry_delay(attempt_count);
+            let next = now + delay;
             tracing::warn!(
                 delivery_id = %delivery.id,
                 attempt = attempt_count,
-                next_retry_in_secs = delay,
+                next_retry_in_secs = delay.num_seconds(),
                 "Webhook delivery failed, scheduling retry"
             );
             ("pending", Some(next))
@@ -631,11 +921,41 @@ impl WebhookDispatcher {
         if attempt_count >= MAX_ATTEMPTS {
             self.route_to_dlq(delivery, attempt_count, response_status, response_body)
                 .await?;
+            self.advance_offset(delivery.endpoint_id).await?;
         }
 
         Ok(())
     }
 
+    /// Move `endpoint_id`'s offset up to just below its oldest unsettled
+    /// delivery, or to its last sequence when none is left.
+    ///
+    /// Locking the endpoint row first waits for any enqueue that took a
+    /// sequence and has not committed, so the offset cannot pass a delivery
+    /// that is not visible yet.
+    async fn advance_offset(&self, endpoint_id: Uuid) -> anyhow::Result<()> {
+        let mut db_tx = self.pool.begin().await?;
+        sqlx::query("SELECT id FROM webhook_endpoints WHERE id = $1 FOR UPDATE")
+            .bind(endpoint_id)
+            .execute(&mut *db_tx)
+            .await?;
+        sqlx::query(
+            r#"
+            UPDATE webhook_endpoints e
+            SET acked_sequence = GREATEST(e.acked_sequence, COALESCE(
+                (SELECT MIN(d.sequence) - 1 FROM webhook_deliveries d
+                 WHERE d.endpoint_id = e.id AND d.status IN ('pending', 'in_progress')),
+                e.last_sequence))
+            WHERE e.id = $1
+            "#,
+        )
+        .bind(endpoint_id)
+        .execute(&mut *db_tx)
+        .await?;
+        db_tx.commit().await?;
+        Ok(())
+    }
+
     /// Insert an exhausted delivery into the DLQ table with the full attempt
     /// history so operators can inspect and replay.
     async fn route_to_dlq(
@@ -689,7 +1009,8 @@ impl WebhookDispatcher {
     // -------------------------------------------------------------------
 
     /// Check whether the circuit breaker is open for this endpoint.
-    /// Returns `true` if the endpoint is temporarily blocked.
+    /// Returns `true` if the endpoint is temporarily blocked. A manual
+    /// override (see [`crate::services::breakers`]) takes precedence.
     async fn circuit_breaker_is_open(
         &self,
         endpoint_id: &Uuid,
@@ -697,18 +1018,27 @@ impl WebhookDispatcher {
     ) -> anyhow::Result<bool> {
         let mut conn = self.redis.get_multiplexed_async_connection().await?;
         let key = format!("webhook_cb:{endpoint_id}");
-        let data: Option<String> = conn.get(&key).await?;
+        let override_key = breakers::override_key(&breakers::webhook_breaker(endpoint_id));
+        let (data, manual): (Option<String>, Option<String>) =
+            conn.mget(&[&key, &override_key]).await?;
+
+        if let Some(manual) =
+            manual.and_then(|raw| serde_json::from_str::<breakers::ManualOverride>(&raw).ok())
+        {
+            return Ok(manual.state == breakers::BreakerState::Open);
+        }
 
         match data {
             Some(json) => {
                 let state: serde_json::Value = serde_json::from_str(&json)?;
                 if state["state"] == "open" {
+                    let now = self.clock.now();
                     let opened_at = state["opened_at"]
                         .as_str()
                         .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
                         .map(|dt| dt.with_timezone(&Utc))
-                        .unwrap_or(Utc::now());
-                    let elapsed = Utc::now() - opened_at;
+                        .unwrap_or(now);
+                    let elapsed = now - opened_at;
                     if elapsed < chrono::Duration::seconds(CB_RESET_TIMEOUT_SECS) {
                         return Ok(true);
                     }
@@ -723,7 +1053,24 @@ impl WebhookDispatcher {
     async fn circuit_breaker_succeeded(&self, endpoint_id: &Uuid) -> anyhow::Result<()> {
         let mut conn = self.redis.get_multiplexed_async_connection().await?;
         let key = format!("webhook_cb:{endpoint_id}");
-        let _: i32 = redis::cmd("DEL").arg(&key).query_async(&mut conn).await?;
+        let (previous, _): (Option<String>, i32) = redis::pipe()
+            .atomic()
+            .get(&key)
+            .del(&key)
+            .query_async(&mut conn)
+            .await?;
+
+        let was_open = previous
+            .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+            .is_some_and(|state| state["state"] == "open");
+        if was_open {
+            breakers::publish(breakers::BreakerTransition::automatic(
+                breakers::webhook_breaker(endpoint_id),
+                breakers::BreakerState::Open,
+                breakers::BreakerState::Closed,
+                "trial delivery succeeded",
+            ));
+        }
         Ok(())
     }
 
@@ -755,15 +1102,23 @@ impl WebhookDispatcher {
             "#,
         );
 
-        let _: i32 = script
+        let failure_count: u32 = script
             .key(&key)
             .arg("delivery failed")
             .arg(CB_FAILURE_THRESHOLD)
-            .arg(Utc::now().to_rfc3339())
+            .arg(self.clock.now().to_rfc3339())
             .arg(CB_RESET_TIMEOUT_SECS)
             .invoke_async(&mut conn)
             .await?;
 
+        if failure_count == CB_FAILURE_THRESHOLD {
+            breakers::publish(breakers::BreakerTransition::automatic(
+                breakers::webhook_breaker(endpoint_id),
+                breakers::BreakerState::Closed,
+                breakers::BreakerState::Open,
+                format!("{failure_count} consecutive delivery failures"),
+            ));
+        }
         Ok(())
     }
 
@@ -773,7 +1128,8 @@ impl WebhookDispatcher {
 
     /// Replay a webhook delivery from the DLQ back into the delivery table.
     /// The delivery is re-enqueued as a fresh `pending` row with the original
-    /// payload and a reset attempt counter. Returns the new delivery id.
+    /// payload, a reset attempt counter and a new sequence above the
+    /// endpoint's offset. Returns the new delivery id.
     pub async fn replay_from_dlq(&self, dlq_id: Uuid) -> anyhow::Result<Uuid> {
         let dlq_row = sqlx::query(
             r#"
@@ -801,17 +1157,24 @@ impl WebhookDispatcher {
         // original row still exists we reuse it)
         let new_id = sqlx::query_scalar::<_, Uuid>(
             r#"
+            WITH seq AS (
+                UPDATE webhook_endpoints SET last_sequence = last_sequence + 1
+                WHERE id = $1
+                RETURNING last_sequence
+            )
             INSERT INTO webhook_deliveries
-                (endpoint_id, transaction_id, event_type, payload, status, next_attempt_at, attempt_history)
-            VALUES ($1, $2, $3, $4, 'pending', NOW(), '[]'::jsonb)
+                (endpoint_id, transaction_id, event_type, payload, status, next_attempt_at,
+                 attempt_history, sequence)
+            SELECT $1, $2, $3, $4, 'pending', $5, '[]'::jsonb, seq.last_sequence FROM seq
             ON CONFLICT (endpoint_id, transaction_id, event_type)
             DO UPDATE SET status = 'pending',
-                          next_attempt_at = NOW(),
+                          next_attempt_at = $5,
                           attempt_count = 0,
                           response_status = NULL,
                           response_body = NULL,
                           attempt_history = '[]'::jsonb,
-                          claimed_at = NULL
+                          claimed_at = NULL,
+                          sequence = EXCLUDED.sequence
             RETURNING id
             "#,
         )
@@ -819,6 +1182,7 @@ impl WebhookDispatcher {
         .bind(transaction_id)
         .bind(&event_type)
         .bind(&payload)
+        .bind(self.clock.now())
         .fetch_one(&self.pool)
         .await?;
 
@@ -847,6 +1211,7 @@ impl WebhookDispatcher {
 
     async fn endpoints_for_event(
         &self,
+        transaction_id: Uuid,
         event_type: &str,
         transaction_data: &serde_json::Value,
     ) -> anyhow::Result<Vec<WebhookEndpoint>> {
@@ -854,10 +1219,12 @@ impl WebhookDispatcher {
             r#"
             SELECT * FROM webhook_endpoints
             WHERE enabled = TRUE
-              AND $1 = ANY(event_types)
+              AND ((transaction_id IS NULL AND $1 = ANY(event_types))
+                   OR transaction_id = $2)
             "#,
         )
         .bind(event_type)
+        .bind(transaction_id)
         .fetch_all(&self.pool)
         .await?;
 
@@ -1039,7 +1406,10 @@ impl WebhookDispatcher {
             let updated = sqlx::query(
                 r#"
                 UPDATE webhook_endpoints
-                SET enabled = FALSE, updated_at = NOW()
+                SET enabled = FALSE,
+                    paused_at = NOW(),
+                    pause_reason = 'auto_disabled_low_success_rate',
+                    updated_at = NOW()
                 WHERE id = $1 AND enabled = TRUE
                 RETURNING id
                 "#,
@@ -1073,6 +1443,21 @@ impl WebhookDispatcher {
     }
 }
 
+/// Backoff before retrying a delivery that has failed `attempt_count` times:
+/// `2^attempt_count * BASE_DELAY_SECS`.
+fn retry_delay(attempt_count: i32) -> chrono::Duration {
+    chrono::Duration::seconds(BASE_DELAY_SECS * (1_i64 << attempt_count))
+}
+
+/// Whether an endpoint failing since `failing_since` is due to be paused.
+fn failing_too_long(
+    failing_since: Option<chrono::DateTime<Utc>>,
+    now: chrono::DateTime<Utc>,
+    auto_pause_after: chrono::Duration,
+) -> bool {
+    failing_since.is_some_and(|since| now - since >= auto_pause_after)
+}
+
 /// Signature versions supported by the webhook system.
 const SIGNATURE_VERSION: &str = "v1";
 
@@ -1090,6 +1475,54 @@ fn sign_payload_with_version(secret: &str, timestamp: &str, body: &str) -> Strin
     format!("{SIGNATURE_VERSION}={signature_hex}")
 }
 
+/// `X-Webhook-Signature` value for `endpoint`.
+///
+/// While a rotated-out secret is still within its grace period the header
+/// carries both signatures, current first (`v1=<new>,v1=<old>`), so a receiver
+/// that verifies against either secret accepts the delivery.
+fn signature_header(
+    endpoint: &WebhookEndpoint,
+    timestamp: &str,
+    body: &str,
+    now: chrono::DateTime<Utc>,
+) -> String {
+    let current = sign_payload_with_version(&endpoint.secret, timestamp, body);
+    match (
+        &endpoint.previous_secret,
+        endpoint.previous_secret_expires_at,
+    ) {
+        (Some(previous), Some(expires_at)) if expires_at > now => format!(
+            "{current},{}",
+            sign_payload_with_version(previous, timestamp, body)
+        ),
+        _ => current,
+    }
+}
+
+/// `X-Webhook-Idempotency-Key` value: identical for every attempt of the same
+/// event to the same endpoint, including retries after a restart and DLQ
+/// replays, so receivers can drop duplicates.
+pub fn idempotency_key(endpoint_id: Uuid, transaction_id: Uuid, event_type: &str) -> String {
+    hex::encode(Sha256::digest(format!(
+        "{endpoint_id}:{transaction_id}:{event_type}"
+    )))
+}
+
+/// Whether `url` is an absolute http(s) URL deliveries can be sent to.
+pub fn is_valid_endpoint_url(url: &str) -> bool {
+    matches!(
+        url::Url::parse(url),
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") && parsed.has_host()
+    )
+}
+
+/// Random signing secret for a new or rotated subscription.
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; 32];
+    rand::RngCore::fill_bytes(&mut rand::thread_rng(), &mut bytes);
+    format!("whsec_{}", hex::encode(bytes))
+}
+
 /// Compute HMAC-SHA256 hex signature (v1).
 fn sign_payload_v1(secret: &str, signed_content: &str) -> String {
     let mut mac =
@@ -1245,6 +1678,13 @@ mod tests {
             enabled: true,
             max_delivery_rate: 10,
             filter_rules: None,
+            transaction_id: None,
+            previous_secret: None,
+            previous_secret_expires_at: None,
+            failing_since: None,
+            paused_at: None,
+            pause_reason: None,
+            acked_sequence: 0,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -1273,6 +1713,13 @@ mod tests {
             enabled: true,
             max_delivery_rate: 10,
             filter_rules: Some(serde_json::json!({"asset_codes": ["USD", "EUR"]})),
+            transaction_id: None,
+            previous_secret: None,
+            previous_secret_expires_at: None,
+            failing_since: None,
+            paused_at: None,
+            pause_reason: None,
+            acked_sequence: 0,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -1312,6 +1759,13 @@ mod tests {
             enabled: true,
             max_delivery_rate: 10,
             filter_rules: Some(serde_json::json!({"min_amount": "100.00"})),
+            transaction_id: None,
+            previous_secret: None,
+            previous_secret_expires_at: None,
+            failing_since: None,
+            paused_at: None,
+            pause_reason: None,
+            acked_sequence: 0,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -1350,6 +1804,13 @@ mod tests {
                 "min_amount": "100.00",
                 "max_amount": "1000.00"
             })),
+            transaction_id: None,
+            previous_secret: None,
+            previous_secret_expires_at: None,
+            failing_since: None,
+            paused_at: None,
+            pause_reason: None,
+            acked_sequence: 0,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -1377,6 +1838,144 @@ mod tests {
         assert!(!dispatcher.matches_filters(&endpoint, &too_large));
     }
 
+    #[test]
+    fn test_endpoint_url_must_be_absolute_http() {
+        assert!(is_valid_endpoint_url("https://wallet.example.com/cb"));
+        assert!(is_valid_endpoint_url("http://localhost:8080/hook"));
+        assert!(!is_valid_endpoint_url("ftp://example.com/hook"));
+        assert!(!is_valid_endpoint_url("/relative/path"));
+    }
+
+    fn sample_endpoint(url: &str) -> WebhookEndpoint {
+        WebhookEndpoint {
+            id: Uuid::new_v4(),
+            url: url.to_string(),
+            secret: "new_secret".to_string(),
+            event_types: vec!["transaction.completed".to_string()],
+            enabled: true,
+            max_delivery_rate: 10,
+            filter_rules: None,
+            transaction_id: None,
+            previous_secret: None,
+            previous_secret_expires_at: None,
+            failing_since: None,
+            paused_at: None,
+            pause_reason: None,
+            acked_sequence: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_signature_header_includes_previous_secret_during_grace_period() {
+        let now = Utc::now();
+        let (timestamp, body) = ("2025-01-15T10:30:00Z", r#"{"id":"1"}"#);
+        let current = sign_payload_with_version("new_secret", timestamp, body);
+        let previous = sign_payload_with_version("old_secret", timestamp, body);
+
+        let mut ep = sample_endpoint("http://example.com");
+        assert_eq!(signature_header(&ep, timestamp, body, now), current);
+
+        ep.previous_secret = Some("old_secret".to_string());
+        ep.previous_secret_expires_at = Some(now + chrono::Duration::hours(1));
+        assert_eq!(
+            signature_header(&ep, timestamp, body, now),
+            format!("{current},{previous}")
+        );
+
+        ep.previous_secret_expires_at = Some(now - chrono::Duration::seconds(1));
+        assert_eq!(signature_header(&ep, timestamp, body, now), current);
+    }
+
+    #[test]
+    fn test_failing_too_long() {
+        let now = Utc::now();
+        let after = chrono::Duration::hours(24);
+        assert!(!failing_too_long(None, now, after));
+        assert!(!failing_too_long(
+            Some(now - chrono::Duration::hours(23)),
+            now,
+            after
+        ));
+        assert!(failing_too_long(
+            Some(now - chrono::Duration::hours(24)),
+            now,
+            after
+        ));
+    }
+
+    #[test]
+    fn test_retry_backoff_follows_the_clock() {
+        use crate::adapters::ManualClock;
+        use chrono::TimeZone;
+
+        assert_eq!(retry_delay(1), chrono::Duration::seconds(20));
+        assert_eq!(retry_delay(4), chrono::Duration::seconds(160));
+
+        let clock = ManualClock::new(Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap());
+        let next_attempt_at = clock.now() + retry_delay(2);
+        clock.advance(chrono::Duration::seconds(39));
+        assert!(clock.now() < next_attempt_at);
+        clock.advance(chrono::Duration::seconds(1));
+        assert_eq!(clock.now(), next_attempt_at);
+    }
+
+    #[test]
+    fn test_generated_secrets_are_unique() {
+        let a = generate_secret();
+        assert!(a.starts_with("whsec_"));
+        assert_eq!(a.len(), "whsec_".len() + 64);
+        assert_ne!(a, generate_secret());
+    }
+
+    #[test]
+    fn test_idempotency_key_is_stable_per_endpoint_and_event() {
+        let (endpoint, tx) = (Uuid::new_v4(), Uuid::new_v4());
+        let key = idempotency_key(endpoint, tx, REFUND_PENDING_EVENT);
+        assert_eq!(key.len(), 64);
+        assert_eq!(key, idempotency_key(endpoint, tx, REFUND_PENDING_EVENT));
+        assert_ne!(key, idempotency_key(endpoint, tx, COMPLIANCE_REVIEW_EVENT));
+        assert_ne!(
+            key,
+            idempotency_key(Uuid::new_v4(), tx, REFUND_PENDING_EVENT)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_ping_delivers_signed_event() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/hook")
+            .match_header("X-Webhook-Event", PING_EVENT)
+            .match_header(
+                "X-Webhook-Signature",
+                mockito::Matcher::Regex("^v1=[0-9a-f]{64}$".into()),
+            )
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"event_type":"ping"}"#.into(),
+            ))
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let dispatcher = WebhookDispatcher::new(
+            sqlx::postgres::PgPoolOptions::new()
+                .connect_lazy("postgres://dummy")
+                .unwrap(),
+            "redis://dummy",
+        )
+        .unwrap();
+        let result = dispatcher
+            .send_ping(&sample_endpoint(&format!("{}/hook", server.url())))
+            .await;
+
+        mock.assert_async().await;
+        assert!(result.delivered);
+        assert_eq!(result.response_status, Some(204));
+        assert!(result.error.is_none());
+    }
+
     // Note: Integration test for enqueue deduplication should verify that
     // calling enqueue twice for the same (endpoint_id, transaction_id, event_type)
     // creates only one delivery record due to the unique constraint and
@@ -1398,6 +1997,59 @@ pub struct EndpointHealth {
     pub last_success_at: Option<chrono::DateTime<Utc>>,
 }
 
+/// Automatic circuit breaker state of every endpoint that has recent
+/// failures. Endpoints with no `webhook_cb:` key are closed and not listed.
+pub async fn circuit_breaker_statuses(
+    redis: &Client,
+) -> anyhow::Result<Vec<breakers::BreakerStatus>> {
+    let mut conn = redis.get_multiplexed_async_connection().await?;
+    let keys: Vec<String> = {
+        let mut iter: redis::AsyncIter<String> = conn.scan_match("webhook_cb:*").await?;
+        let mut keys = Vec::new();
+        while let Some(key) = iter.next_item().await {
+            keys.push(key);
+        }
+        keys
+    };
+
+    let mut statuses = Vec::new();
+    for key in keys {
+        let Some(endpoint_id) = key
+            .strip_prefix("webhook_cb:")
+            .and_then(|id| Uuid::parse_str(id).ok())
+        else {
+            continue;
+        };
+        let raw: Option<String> = conn.get(&key).await?;
+        let Some(state) = raw.and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+        else {
+            continue;
+        };
+        let opened_at = state["opened_at"]
+            .as_str()
+            .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+            .map(|dt| dt.with_timezone(&Utc));
+        // Past the reset timeout an open breaker lets the next delivery through.
+        let open = state["state"] == "open"
+            && opened_at.is_some_and(|at| {
+                Utc::now() - at < chrono::Duration::seconds(CB_RESET_TIMEOUT_SECS)
+            });
+        statuses.push(breakers::BreakerStatus {
+            name: breakers::webhook_breaker(&endpoint_id),
+            state: if open {
+                breakers::BreakerState::Open
+            } else {
+                breakers::BreakerState::Closed
+            },
+            manual_override: None,
+            failure_count: state["failure_count"].as_u64().map(|n| n as u32),
+            opened_at,
+            last_error: state["last_error"].as_str().map(str::to_string),
+        });
+    }
+    Ok(statuses)
+}
+
 /// Return health scores for all webhook endpoints.
 pub async fn list_endpoint_health(
     pool: &PgPool,
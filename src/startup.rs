@@ -1,5 +1,9 @@
 use crate::config::Config;
+use crate::ports::ObjectStore;
+#[cfg(feature = "backup")]
+use crate::services::backup_keys::MasterKeyring;
 use anyhow::{Context, Result};
+use sqlx::postgres::PgPoolOptions;
 use sqlx::PgPool;
 use std::time::Duration;
 
@@ -123,6 +127,8 @@ async fn validate_database(pool: &PgPool) -> Result<()> {
         anyhow::bail!("No migrations applied");
     }
 
+    crate::db::migrations::check_schema_version(pool).await?;
+
     Ok(())
 }
 
@@ -160,6 +166,236 @@ async fn validate_horizon(horizon_url: &str) -> Result<()> {
     Ok(())
 }
 
+/// Tables the service reads and writes, with the privileges it needs on each.
+const REQUIRED_TABLE_PRIVILEGES: &[(&str, &str)] = &[
+    ("transactions", "SELECT, INSERT, UPDATE"),
+    ("settlements", "SELECT, INSERT, UPDATE"),
+    ("transaction_dlq", "SELECT, INSERT, UPDATE, DELETE"),
+    ("webhook_endpoints", "SELECT, INSERT, UPDATE"),
+    ("webhook_deliveries", "SELECT, INSERT, UPDATE"),
+    ("idempotency_keys", "SELECT, INSERT, UPDATE, DELETE"),
+    ("audit_logs", "SELECT, INSERT"),
+    ("jobs", "SELECT, INSERT, UPDATE"),
+    ("export_jobs", "SELECT, INSERT, UPDATE"),
+    ("assets", "SELECT"),
+];
+
+/// Shortest accepted HMAC signing secret, in bytes.
+const MIN_SECRET_LEN: usize = 32;
+
+/// One line of the `--self-check` report.
+pub struct SelfCheck {
+    pub name: &'static str,
+    pub passed: bool,
+    pub detail: String,
+}
+
+/// Result of `--self-check`: every external dependency the service needs,
+/// checked before traffic is sent to a new deployment.
+pub struct SelfCheckReport {
+    pub checks: Vec<SelfCheck>,
+}
+
+impl SelfCheckReport {
+    pub fn is_ok(&self) -> bool {
+        self.checks.iter().all(|c| c.passed)
+    }
+
+    fn record(&mut self, name: &'static str, result: Result<String>) {
+        let (passed, detail) = match result {
+            Ok(detail) => (true, detail),
+            Err(e) => (false, format!("{e:#}")),
+        };
+        self.checks.push(SelfCheck {
+            name,
+            passed,
+            detail,
+        });
+    }
+
+    pub fn print(&self) {
+        println!("\n=== Self-Check Report ===");
+        for check in &self.checks {
+            println!(
+                "{:<18} {} {}",
+                check.name,
+                status(check.passed),
+                check.detail
+            );
+        }
+        println!(
+            "\nOverall Status: {}",
+            if self.is_ok() { "✅ PASS" } else { "❌ FAIL" }
+        );
+        println!("=========================\n");
+    }
+}
+
+/// Check every external dependency and credential the service needs. Never
+/// fails itself; failures are reported per check.
+pub async fn self_check(config: &Config) -> SelfCheckReport {
+    let mut report = SelfCheckReport { checks: Vec::new() };
+
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .acquire_timeout(Duration::from_secs(10))
+        .connect(&config.database_url)
+        .await
+        .context("Failed to connect to database");
+    match pool {
+        Ok(pool) => {
+            report.record(
+                "database",
+                validate_database(&pool)
+                    .await
+                    .map(|()| "connected, migrations applied".to_string()),
+            );
+            report.record("table_privileges", check_table_privileges(&pool).await);
+            pool.close().await;
+        }
+        Err(e) => {
+            report.record("database", Err(e));
+            report.record(
+                "table_privileges",
+                Err(anyhow::anyhow!("skipped: database unavailable")),
+            );
+        }
+    }
+
+    report.record("redis", check_redis(&config.redis_url).await);
+    report.record(
+        "horizon",
+        check_horizon(
+            &config.stellar_horizon_url,
+            config.stellar_network_passphrase.as_deref(),
+        )
+        .await,
+    );
+    report.record(
+        "object_store",
+        check_object_store(crate::adapters::object_store().as_ref()).await,
+    );
+    report.record("signing_keys", check_signing_keys(config));
+
+    report
+}
+
+async fn check_table_privileges(pool: &PgPool) -> Result<String> {
+    let mut missing = Vec::new();
+    for (table, privileges) in REQUIRED_TABLE_PRIVILEGES {
+        let granted: Option<bool> = sqlx::query_scalar(
+            "SELECT CASE WHEN to_regclass($1) IS NULL THEN NULL \
+             ELSE has_table_privilege(current_user, $1, $2) END",
+        )
+        .bind(table)
+        .bind(privileges)
+        .fetch_one(pool)
+        .await
+        .with_context(|| format!("Failed to check privileges on {table}"))?;
+        match granted {
+            Some(true) => {}
+            Some(false) => missing.push(format!("{table} ({privileges})")),
+            None => missing.push(format!("{table} (missing table)")),
+        }
+    }
+    if !missing.is_empty() {
+        anyhow::bail!("missing privileges: {}", missing.join(", "));
+    }
+    Ok(format!(
+        "{} tables accessible",
+        REQUIRED_TABLE_PRIVILEGES.len()
+    ))
+}
+
+async fn check_redis(redis_url: &str) -> Result<String> {
+    let client = redis::Client::open(redis_url).context("Invalid Redis URL")?;
+    let authenticated = client.get_connection_info().redis.password.is_some();
+    // Connecting sends AUTH when the URL carries credentials, and PING is
+    // rejected with NOAUTH when the server requires them but none were sent.
+    validate_redis(redis_url).await?;
+    Ok(if authenticated {
+        "PING ok (authenticated)".to_string()
+    } else {
+        "PING ok (no AUTH configured)".to_string()
+    })
+}
+
+async fn check_horizon(horizon_url: &str, expected_passphrase: Option<&str>) -> Result<String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()?;
+
+    let response = client
+        .get(horizon_url)
+        .send()
+        .await
+        .context("Failed to connect to Horizon")?;
+    if !response.status().is_success() {
+        anyhow::bail!("Horizon returned status: {}", response.status());
+    }
+    let root: serde_json::Value = response.json().await.context("Horizon root is not JSON")?;
+    let passphrase = root
+        .get("network_passphrase")
+        .and_then(|v| v.as_str())
+        .context("Horizon root has no network_passphrase")?;
+
+    verify_network_passphrase(passphrase, expected_passphrase)
+}
+
+fn verify_network_passphrase(actual: &str, expected: Option<&str>) -> Result<String> {
+    match expected {
+        Some(expected) if expected != actual => anyhow::bail!(
+            "network passphrase mismatch (expected '{expected}', Horizon reports '{actual}')"
+        ),
+        Some(_) => Ok(format!("reachable, network '{actual}'")),
+        None => Ok(format!(
+            "reachable, network '{actual}' (STELLAR_NETWORK_PASSPHRASE not set)"
+        )),
+    }
+}
+
+async fn check_object_store(store: &dyn ObjectStore) -> Result<String> {
+    let key = format!("self-check/{}", uuid::Uuid::new_v4());
+    let body = bytes::Bytes::from_static(b"synapse self-check");
+
+    store
+        .put_bytes(&key, body.clone())
+        .await
+        .context("write failed")?;
+    let read = store.get_bytes(&key).await.context("read failed");
+    store.delete(&key).await.context("delete failed")?;
+    if read? != body {
+        anyhow::bail!("read back different bytes than written");
+    }
+    Ok("write, read and delete ok".to_string())
+}
+
+fn check_secret(name: &str, secret: &str) -> Result<()> {
+    if secret.len() < MIN_SECRET_LEN {
+        anyhow::bail!("{name} is shorter than {MIN_SECRET_LEN} bytes");
+    }
+    Ok(())
+}
+
+fn check_signing_keys(config: &Config) -> Result<String> {
+    let mut checked = vec!["ANCHOR_WEBHOOK_SECRET"];
+    check_secret("ANCHOR_WEBHOOK_SECRET", &config.anchor_webhook_secret)?;
+
+    if let Some(secret) = crate::utils::signed_url::signing_secret() {
+        check_secret("DOWNLOAD_SIGNING_SECRET", &secret)?;
+        checked.push("DOWNLOAD_SIGNING_SECRET");
+    }
+    if crate::services::status_page::StatusPageConfig::from_env()?.is_some() {
+        checked.push("STATUS_PAGE_SECRET");
+    }
+    #[cfg(feature = "backup")]
+    if MasterKeyring::from_config(config)?.is_some() {
+        checked.push("BACKUP_ENCRYPTION_KEY");
+    }
+
+    Ok(format!("{} valid", checked.join(", ")))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -171,6 +407,7 @@ mod tests {
             database_url: "postgres://localhost:5432/test".to_string(),
             database_replica_url: None,
             stellar_horizon_url: "https://horizon-testnet.stellar.org".to_string(),
+            stellar_network_passphrase: None,
             anchor_webhook_secret: "test".to_string(),
             redis_url: "redis://localhost:6379".to_string(),
             default_rate_limit: 100,
@@ -180,6 +417,8 @@ mod tests {
             allowed_ips: crate::config::AllowedIps::Any,
             backup_dir: "/tmp".to_string(),
             backup_encryption_key: None,
+            backup_encryption_key_id: "1".to_string(),
+            backup_previous_encryption_keys: None,
             db_timeouts: crate::config::DbTimeoutConfig::default(),
             otlp_endpoint: None,
             cors_allowed_origins: vec![],
@@ -189,6 +428,8 @@ mod tests {
             db_statement_timeout_ms: 30000,
             db_idle_timeout_secs: 600,
             db_long_running_statement_timeout_ms: 300000,
+            migration_mode: crate::db::migrations::MigrationMode::Startup,
+            migration_lock_timeout_secs: 300,
             processor_workers: 4,
             processor_batch_size: 50,
             processor_poll_interval_ms: 1000,
@@ -198,6 +439,7 @@ mod tests {
             slow_query_threshold_ms: 500,
             settlement_max_batch_size: 10_000,
             settlement_min_tx_count: 1,
+            jobs: Default::default(),
         }
     }
 
@@ -220,4 +462,37 @@ mod tests {
 
         assert!(validate_env_vars(&config).is_err());
     }
+
+    #[test]
+    fn test_verify_network_passphrase() {
+        let testnet = "Test SDF Network ; September 2015";
+        assert!(verify_network_passphrase(testnet, Some(testnet)).is_ok());
+        assert!(verify_network_passphrase(testnet, None).is_ok());
+        assert!(verify_network_passphrase(
+            testnet,
+            Some("Public Global Stellar Network ; September 2015")
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_check_signing_keys_rejects_short_webhook_secret() {
+        let config = test_config_base();
+        assert!(check_signing_keys(&config).is_err());
+
+        let config = Config {
+            anchor_webhook_secret: "x".repeat(MIN_SECRET_LEN),
+            ..test_config_base()
+        };
+        assert!(check_signing_keys(&config).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_check_object_store_round_trip_leaves_nothing_behind() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = crate::adapters::LocalObjectStore::new(dir.path());
+
+        assert!(check_object_store(&store).await.is_ok());
+        assert!(store.list("").await.unwrap().is_empty());
+    }
 }
@@ -0,0 +1,225 @@
+//! Building and signing the anchor's own transactions.
+//!
+//! ```ignore
+//! let tx = TransactionBuilder::new(signer.public_key(), sequence + 1)
+//!     .with_timeout(now, Duration::minutes(5))
+//!     .with_memo(Memo::Id(42))
+//!     .with_operation(payment)
+//!     .build()?;
+//! let envelope = sign(tx, network_passphrase, signer.as_ref()).await?;
+//! ```
+
+use crate::ports::{SignerResult, TransactionSigner};
+use crate::stellar::xdr::{
+    DecoratedSignature, Memo, Operation, Transaction, TransactionEnvelope, XdrError,
+};
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::{DateTime, Duration, Utc};
+
+/// Network minimum fee per operation, in stroops.
+pub const BASE_FEE: u32 = 100;
+/// Stroops per unit of an asset.
+const STROOPS_PER_UNIT: i64 = 10_000_000;
+const MAX_OPERATIONS: usize = 100;
+const MAX_MEMO_TEXT: usize = 28;
+
+/// Amount in stroops; `None` unless it is positive, has at most 7 decimal
+/// places and fits an `i64`.
+pub fn to_stroops(amount: &BigDecimal) -> Option<i64> {
+    if *amount <= BigDecimal::from(0) || amount.with_scale(7) != *amount {
+        return None;
+    }
+    (amount * BigDecimal::from(STROOPS_PER_UNIT))
+        .with_scale(0)
+        .to_i64()
+}
+
+/// Horizon's decimal form of a stroop amount: `125000000` is `12.5000000`.
+pub fn format_stroops(stroops: i64) -> String {
+    let sign = if stroops < 0 { "-" } else { "" };
+    let abs = stroops.unsigned_abs();
+    let unit = STROOPS_PER_UNIT as u64;
+    format!("{sign}{}.{:07}", abs / unit, abs % unit)
+}
+
+/// Stroops in a Horizon balance or limit such as `12.5000000`; unlike
+/// [`to_stroops`], zero and negative amounts are accepted.
+pub fn parse_stroops(amount: &str) -> Option<i64> {
+    let amount = amount.trim().parse::<BigDecimal>().ok()?;
+    if amount.with_scale(7) != amount {
+        return None;
+    }
+    (amount * BigDecimal::from(STROOPS_PER_UNIT))
+        .with_scale(0)
+        .to_i64()
+}
+
+#[derive(Debug, Clone)]
+pub struct TransactionBuilder {
+    source: [u8; 32],
+    seq_num: i64,
+    base_fee: u32,
+    time_bounds: Option<(u64, u64)>,
+    memo: Memo,
+    operations: Vec<Operation>,
+}
+
+impl TransactionBuilder {
+    /// A transaction from `source` with sequence number `seq_num`, which
+    /// must be the account's current sequence plus one.
+    pub fn new(source: [u8; 32], seq_num: i64) -> Self {
+        Self {
+            source,
+            seq_num,
+            base_fee: BASE_FEE,
+            time_bounds: None,
+            memo: Memo::None,
+            operations: Vec::new(),
+        }
+    }
+
+    /// Fee offered per operation, in stroops; at least [`BASE_FEE`].
+    pub fn with_base_fee(mut self, stroops: u32) -> Self {
+        self.base_fee = stroops.max(BASE_FEE);
+        self
+    }
+
+    /// Only valid until `timeout` after `now`. Once that has passed the
+    /// transaction can never be applied, so a submission whose outcome is
+    /// unknown can be settled by waiting it out.
+    pub fn with_timeout(mut self, now: DateTime<Utc>, timeout: Duration) -> Self {
+        self.time_bounds = Some((0, (now + timeout).timestamp().max(1) as u64));
+        self
+    }
+
+    pub fn with_memo(mut self, memo: Memo) -> Self {
+        self.memo = memo;
+        self
+    }
+
+    pub fn with_operation(mut self, operation: impl Into<Operation>) -> Self {
+        self.operations.push(operation.into());
+        self
+    }
+
+    pub fn build(self) -> Result<Transaction, XdrError> {
+        if self.operations.is_empty() {
+            return Err(XdrError::Malformed("no operations"));
+        }
+        if self.operations.len() > MAX_OPERATIONS {
+            return Err(XdrError::Malformed("too many operations"));
+        }
+        if matches!(&self.memo, Memo::Text(text) if text.len() > MAX_MEMO_TEXT) {
+            return Err(XdrError::Malformed("memo text over 28 bytes"));
+        }
+        let fee = self
+            .base_fee
+            .checked_mul(self.operations.len() as u32)
+            .ok_or(XdrError::Malformed("fee overflows"))?;
+        Ok(Transaction {
+            source: self.source,
+            fee,
+            seq_num: self.seq_num,
+            time_bounds: self.time_bounds,
+            memo: self.memo,
+            operations: self.operations,
+        })
+    }
+}
+
+/// Envelope of `tx` signed by `signer`.
+pub async fn sign(
+    tx: Transaction,
+    network_passphrase: &str,
+    signer: &dyn TransactionSigner,
+) -> SignerResult<TransactionEnvelope> {
+    let hash = tx.hash(network_passphrase);
+    let signature = DecoratedSignature {
+        hint: DecoratedSignature::hint_for(&signer.public_key()),
+        signature: signer.sign(&hash).await?,
+    };
+    Ok(TransactionEnvelope {
+        tx,
+        signatures: vec![signature],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::SecretKeySigner;
+    use crate::stellar::xdr::{Asset, Payment};
+    use ring::signature::{UnparsedPublicKey, ED25519};
+    use std::str::FromStr;
+
+    const TESTNET: &str = "Test SDF Network ; September 2015";
+
+    fn payment(amount: i64) -> Payment {
+        Payment {
+            source: None,
+            destination: [3; 32],
+            asset: Asset::Native,
+            amount,
+        }
+    }
+
+    #[test]
+    fn test_to_stroops() {
+        let stroops = |s: &str| to_stroops(&BigDecimal::from_str(s).unwrap());
+        assert_eq!(stroops("12.5"), Some(125_000_000));
+        assert_eq!(stroops("0.0000001"), Some(1));
+        assert_eq!(stroops("0.00000001"), None);
+        assert_eq!(stroops("0"), None);
+        assert_eq!(stroops("-1"), None);
+        assert_eq!(stroops("922337203685.4775808"), None);
+
+        assert_eq!(format_stroops(125_000_000), "12.5000000");
+        assert_eq!(format_stroops(1), "0.0000001");
+        assert_eq!(format_stroops(-10_000_000), "-1.0000000");
+
+        assert_eq!(parse_stroops("12.5000000"), Some(125_000_000));
+        assert_eq!(parse_stroops("0.0000000"), Some(0));
+        assert_eq!(parse_stroops("922337203685.4775807"), Some(i64::MAX));
+        assert_eq!(parse_stroops("0.00000001"), None);
+        assert_eq!(parse_stroops("n/a"), None);
+    }
+
+    #[test]
+    fn test_build_charges_fee_per_operation() {
+        let now = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let tx = TransactionBuilder::new([7; 32], 43)
+            .with_base_fee(250)
+            .with_timeout(now, Duration::minutes(5))
+            .with_operation(payment(1))
+            .with_operation(payment(2))
+            .build()
+            .unwrap();
+        assert_eq!(tx.fee, 500);
+        assert_eq!(tx.seq_num, 43);
+        assert_eq!(tx.time_bounds, Some((0, now.timestamp() as u64 + 300)));
+
+        assert!(TransactionBuilder::new([7; 32], 1).build().is_err());
+        assert!(TransactionBuilder::new([7; 32], 1)
+            .with_memo(Memo::Text("x".repeat(29)))
+            .with_operation(payment(1))
+            .build()
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sign_covers_network_hash() {
+        let signer = SecretKeySigner::new([9; 32]).unwrap();
+        let tx = TransactionBuilder::new(signer.public_key(), 1)
+            .with_operation(payment(10))
+            .build()
+            .unwrap();
+        let envelope = sign(tx.clone(), TESTNET, &signer).await.unwrap();
+        let sig = &envelope.signatures[0];
+        assert_eq!(sig.hint, DecoratedSignature::hint_for(&signer.public_key()));
+        assert!(UnparsedPublicKey::new(&ED25519, signer.public_key())
+            .verify(&tx.hash(TESTNET), &sig.signature)
+            .is_ok());
+    }
+}
@@ -1,3 +1,12 @@
+use crate::domain::StellarAddress;
+use crate::ports::TransactionSigner;
+use crate::services::breakers::{self, BreakerState, BreakerTransition, ManualOverride};
+use crate::services::fees;
+use crate::stellar::builder::{self, TransactionBuilder};
+use crate::stellar::endpoints::HorizonEndpoints;
+use crate::stellar::xdr::{Asset, Memo, Operation, TransactionEnvelope, TransactionResult};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
 use failsafe::futures::CircuitBreaker as FuturesCircuitBreaker;
 use failsafe::{backoff, failure_policy, Config, Error as FailsafeError, StateMachine};
 use futures_util::stream::StreamExt;
@@ -5,7 +14,8 @@ use opentelemetry::propagation::TextMapPropagator;
 use opentelemetry_sdk::propagation::TraceContextPropagator;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
 use std::time::Duration;
 use thiserror::Error;
 use tokio::sync::mpsc;
@@ -21,6 +31,28 @@ pub enum HorizonError {
     InvalidResponse(String),
     #[error("Circuit breaker open: {0}")]
     CircuitBreakerOpen(String),
+    /// Horizon answered `5xx` or `429`: the server rather than the request
+    /// is at fault, so its endpoint is failed over.
+    #[error("Horizon API error: {0}")]
+    Unavailable(reqwest::StatusCode),
+    /// Horizon refused the transaction or it failed on-chain; the sequence
+    /// number is consumed only if `result.code` is `txFAILED`.
+    #[error("Transaction {hash} failed: {result}")]
+    TransactionFailed {
+        hash: String,
+        result: TransactionResult,
+    },
+    /// Horizon gave up waiting for the transaction to reach a ledger; it may
+    /// still be applied until its time bounds expire.
+    #[error("Timed out waiting for transaction {0}")]
+    SubmissionTimeout(String),
+    #[error("Invalid transaction: {0}")]
+    InvalidTransaction(String),
+    #[error("Signing failed: {0}")]
+    Signing(String),
+    /// This instance's region is passive and must not write to the network.
+    #[error("Region {0} is passive; not submitting")]
+    RegionPassive(String),
 }
 
 impl Clone for HorizonError {
@@ -30,10 +62,40 @@ impl Clone for HorizonError {
             Self::AccountNotFound(s) => Self::AccountNotFound(s.clone()),
             Self::InvalidResponse(s) => Self::InvalidResponse(s.clone()),
             Self::CircuitBreakerOpen(s) => Self::CircuitBreakerOpen(s.clone()),
+            Self::Unavailable(status) => Self::Unavailable(*status),
+            Self::TransactionFailed { hash, result } => Self::TransactionFailed {
+                hash: hash.clone(),
+                result: result.clone(),
+            },
+            Self::SubmissionTimeout(s) => Self::SubmissionTimeout(s.clone()),
+            Self::InvalidTransaction(s) => Self::InvalidTransaction(s.clone()),
+            Self::Signing(s) => Self::Signing(s.clone()),
+            Self::RegionPassive(s) => Self::RegionPassive(s.clone()),
         }
     }
 }
 
+impl HorizonError {
+    /// Whether the error points at the Horizon endpoint (unreachable, timing
+    /// out or failing) rather than at the request.
+    pub fn is_endpoint_failure(&self) -> bool {
+        match self {
+            HorizonError::RequestError(e) => e.is_timeout() || e.is_connect() || e.is_request(),
+            HorizonError::Unavailable(_) => true,
+            _ => false,
+        }
+    }
+}
+
+/// The error for a status Horizon should not have answered with.
+fn status_error(status: reqwest::StatusCode) -> HorizonError {
+    if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        HorizonError::Unavailable(status)
+    } else {
+        HorizonError::InvalidResponse(format!("Horizon API error: {status}"))
+    }
+}
+
 /// Response from Horizon /accounts endpoint
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccountResponse {
@@ -45,6 +107,27 @@ pub struct AccountResponse {
     pub home_domain: Option<String>,
     pub last_modified_ledger: i64,
     pub last_modified_time: String,
+    #[serde(default)]
+    pub signers: Vec<AccountSigner>,
+    #[serde(default)]
+    pub thresholds: AccountThresholds,
+}
+
+/// One signer of an account, as listed by Horizon.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountSigner {
+    /// Strkey of the signer; `G...` for an ed25519 key.
+    pub key: String,
+    pub weight: u8,
+    #[serde(rename = "type")]
+    pub signer_type: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AccountThresholds {
+    pub low_threshold: u8,
+    pub med_threshold: u8,
+    pub high_threshold: u8,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -56,6 +139,29 @@ pub struct Balance {
     pub asset_issuer: Option<String>,
 }
 
+/// What an account can receive of one asset, from
+/// [`HorizonClient::check_trustline`]. Amounts are in stroops.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trustline {
+    /// Native XLM, which needs no trustline.
+    Native,
+    /// The account does not exist or has no trustline for the asset.
+    Missing,
+    /// A trustline holding `balance` out of `limit`.
+    Held { balance: i64, limit: i64 },
+}
+
+impl Trustline {
+    /// How many more stroops the account can receive; `None` when unbounded.
+    pub fn room(&self) -> Option<i64> {
+        match self {
+            Trustline::Native => None,
+            Trustline::Missing => Some(0),
+            Trustline::Held { balance, limit } => Some(limit.saturating_sub(*balance).max(0)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamPayment {
     pub id: String,
@@ -63,9 +169,331 @@ pub struct StreamPayment {
     pub to: String,
     pub amount: String,
     pub asset_code: String,
+    /// `None` for native XLM.
+    #[serde(default)]
+    pub asset_issuer: Option<String>,
+    pub memo: Option<String>,
+    pub memo_type: Option<String>,
+    pub created_at: String,
+    #[serde(default)]
+    pub transaction_hash: Option<String>,
+    /// Sender's `M...` address when it paid from a muxed account.
+    #[serde(default)]
+    pub from_muxed: Option<String>,
+}
+
+/// One operation from Horizon `/accounts/{id}/payments`, with its transaction
+/// joined in (`join=transactions`) so the memo is available.
+///
+/// Besides `payment`, the endpoint returns `create_account`, path payments and
+/// merges; their transfer fields differ, so everything beyond the common ones
+/// is optional.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentRecord {
+    pub id: String,
+    pub paging_token: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub created_at: String,
+    #[serde(default)]
+    pub transaction_hash: Option<String>,
+    #[serde(default)]
+    pub from: Option<String>,
+    /// Sender's `M...` address when it paid from a muxed account; `from` is
+    /// then its base account.
+    #[serde(default)]
+    pub from_muxed: Option<String>,
+    #[serde(default)]
+    pub to: Option<String>,
+    #[serde(default)]
+    pub amount: Option<String>,
+    #[serde(default)]
+    pub asset_type: Option<String>,
+    #[serde(default)]
+    pub asset_code: Option<String>,
+    #[serde(default)]
+    pub asset_issuer: Option<String>,
+    #[serde(default)]
+    pub transaction: Option<PaymentTransaction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentTransaction {
+    #[serde(default)]
     pub memo: Option<String>,
+    #[serde(default)]
     pub memo_type: Option<String>,
+}
+
+/// A transaction from Horizon `/transactions/{hash}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionRecord {
+    pub hash: String,
+    /// False for a transaction included in a ledger but failed on-chain.
+    pub successful: bool,
+    pub ledger: i64,
+    pub source_account: String,
     pub created_at: String,
+    #[serde(default)]
+    pub memo: Option<String>,
+    #[serde(default)]
+    pub memo_type: Option<String>,
+    /// Base64 `TransactionEnvelope`.
+    #[serde(default)]
+    pub envelope_xdr: Option<String>,
+    /// Base64 `TransactionResult`.
+    #[serde(default)]
+    pub result_xdr: Option<String>,
+}
+
+impl TransactionRecord {
+    /// The decoded `result_xdr`, when Horizon included a valid one.
+    pub fn result(&self) -> Option<TransactionResult> {
+        self.result_xdr
+            .as_deref()
+            .and_then(|xdr| decode_result(xdr).ok())
+    }
+}
+
+/// A transaction Horizon reports as applied by `POST /transactions`.
+#[derive(Debug, Clone)]
+pub struct SubmittedTransaction {
+    pub hash: String,
+    pub ledger: i64,
+    pub result: TransactionResult,
+    /// `result` as Horizon returned it, base64.
+    pub result_xdr: String,
+}
+
+#[derive(Deserialize)]
+struct SubmitResponse {
+    hash: String,
+    ledger: i64,
+    result_xdr: String,
+}
+
+/// Horizon problem document, as returned for a rejected submission.
+#[derive(Deserialize)]
+struct Problem {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    extras: Option<ProblemExtras>,
+}
+
+#[derive(Deserialize)]
+struct ProblemExtras {
+    #[serde(default)]
+    result_xdr: Option<String>,
+}
+
+enum Submission {
+    Applied(SubmitResponse),
+    Rejected(Problem),
+    TimedOut,
+}
+
+fn decode_result(result_xdr: &str) -> Result<TransactionResult, HorizonError> {
+    let bytes = STANDARD
+        .decode(result_xdr)
+        .map_err(|e| HorizonError::InvalidResponse(format!("result_xdr: {e}")))?;
+    TransactionResult::from_xdr(&bytes)
+        .map_err(|e| HorizonError::InvalidResponse(format!("result_xdr: {e}")))
+}
+
+/// One asset as Horizon lists it: `asset_type`, `asset_code`, `asset_issuer`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HorizonAsset {
+    pub asset_type: String,
+    #[serde(default)]
+    pub asset_code: Option<String>,
+    #[serde(default)]
+    pub asset_issuer: Option<String>,
+}
+
+impl HorizonAsset {
+    pub fn to_asset(&self) -> Result<Asset, HorizonError> {
+        if self.asset_type == "native" {
+            return Ok(Asset::Native);
+        }
+        let invalid = || HorizonError::InvalidResponse(format!("asset {self:?}"));
+        let (Some(code), Some(issuer)) = (&self.asset_code, &self.asset_issuer) else {
+            return Err(invalid());
+        };
+        let issuer = StellarAddress::parse(issuer).map_err(|_| invalid())?;
+        Asset::credit(code, issuer.public_key()).ok_or_else(invalid)
+    }
+}
+
+/// A payment path from Horizon `/paths/strict-send` or
+/// `/paths/strict-receive`. Amounts are decimal strings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathRecord {
+    pub source_asset_type: String,
+    #[serde(default)]
+    pub source_asset_code: Option<String>,
+    #[serde(default)]
+    pub source_asset_issuer: Option<String>,
+    pub source_amount: String,
+    pub destination_asset_type: String,
+    #[serde(default)]
+    pub destination_asset_code: Option<String>,
+    #[serde(default)]
+    pub destination_asset_issuer: Option<String>,
+    pub destination_amount: String,
+    #[serde(default)]
+    pub path: Vec<HorizonAsset>,
+}
+
+impl PathRecord {
+    /// The intermediate assets, ready for a path payment operation.
+    pub fn path_assets(&self) -> Result<Vec<Asset>, HorizonError> {
+        self.path.iter().map(HorizonAsset::to_asset).collect()
+    }
+}
+
+/// Horizon's canonical form of an asset: `native` or `CODE:ISSUER`.
+pub fn asset_string(asset: &Asset) -> String {
+    match asset {
+        Asset::Native => "native".to_string(),
+        Asset::Credit { code, issuer } => {
+            format!("{code}:{}", StellarAddress::from_ed25519(*issuer).account())
+        }
+    }
+}
+
+/// Parse [`asset_string`]'s form back into an asset.
+pub fn parse_asset(s: &str) -> Option<Asset> {
+    let s = s.trim();
+    if s.eq_ignore_ascii_case("native") {
+        return Some(Asset::Native);
+    }
+    let (code, issuer) = s.split_once(':')?;
+    let issuer = StellarAddress::parse(issuer).ok()?;
+    Asset::credit(code, issuer.public_key())
+}
+
+/// `<prefix>asset_type`, `<prefix>asset_code` and `<prefix>asset_issuer`
+/// query parameters for `asset`.
+fn asset_params(prefix: &str, asset: &Asset) -> Vec<(String, String)> {
+    match asset {
+        Asset::Native => vec![(format!("{prefix}asset_type"), "native".to_string())],
+        Asset::Credit { code, issuer } => vec![
+            (
+                format!("{prefix}asset_type"),
+                if code.len() <= 4 {
+                    "credit_alphanum4"
+                } else {
+                    "credit_alphanum12"
+                }
+                .to_string(),
+            ),
+            (format!("{prefix}asset_code"), code.clone()),
+            (
+                format!("{prefix}asset_issuer"),
+                StellarAddress::from_ed25519(*issuer).account().to_string(),
+            ),
+        ],
+    }
+}
+
+/// A claimable balance from Horizon `/claimable_balances`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaimableBalanceRecord {
+    /// Hex `ClaimableBalanceID` XDR.
+    pub id: String,
+    /// `native` or `CODE:ISSUER`.
+    pub asset: String,
+    pub amount: String,
+    /// Account that created the balance and pays its reserve.
+    #[serde(default)]
+    pub sponsor: Option<String>,
+    pub claimants: Vec<Claimant>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Claimant {
+    pub destination: String,
+    /// Horizon's JSON form of the `ClaimPredicate`, e.g.
+    /// `{"unconditional": true}` or `{"not": {"abs_before": "..."}}`.
+    pub predicate: serde_json::Value,
+}
+
+/// Horizon `/fee_stats`: fees over the last few ledgers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeeStats {
+    #[serde(deserialize_with = "from_str")]
+    pub last_ledger: u64,
+    /// Stroops per operation.
+    #[serde(deserialize_with = "from_str")]
+    pub last_ledger_base_fee: u32,
+    /// Share of ledger capacity used, 0 to 1; surge pricing starts at 1.
+    #[serde(deserialize_with = "from_str")]
+    pub ledger_capacity_usage: f64,
+    /// Fees actually charged.
+    pub fee_charged: FeeDistribution,
+}
+
+/// Per-operation fees in stroops.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeDistribution {
+    #[serde(deserialize_with = "from_str")]
+    pub min: u32,
+    #[serde(deserialize_with = "from_str")]
+    pub mode: u32,
+    #[serde(deserialize_with = "from_str")]
+    pub p50: u32,
+    #[serde(deserialize_with = "from_str")]
+    pub p90: u32,
+    #[serde(deserialize_with = "from_str")]
+    pub p99: u32,
+    #[serde(deserialize_with = "from_str")]
+    pub max: u32,
+}
+
+/// Horizon sends these numbers as strings.
+fn from_str<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    String::deserialize(deserializer)?
+        .parse()
+        .map_err(serde::de::Error::custom)
+}
+
+#[derive(Deserialize)]
+struct ClaimableBalancesPage {
+    #[serde(rename = "_embedded")]
+    embedded: ClaimableBalancesEmbedded,
+}
+
+#[derive(Deserialize)]
+struct ClaimableBalancesEmbedded {
+    records: Vec<ClaimableBalanceRecord>,
+}
+
+#[derive(Deserialize)]
+struct PathsPage {
+    #[serde(rename = "_embedded")]
+    embedded: PathsEmbedded,
+}
+
+#[derive(Deserialize)]
+struct PathsEmbedded {
+    records: Vec<PathRecord>,
+}
+
+#[derive(Deserialize)]
+struct PaymentsPage {
+    #[serde(rename = "_embedded")]
+    embedded: PaymentsEmbedded,
+}
+
+#[derive(Deserialize)]
+struct PaymentsEmbedded {
+    records: Vec<PaymentRecord>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -79,8 +507,13 @@ pub struct StreamMetrics {
 #[derive(Clone)]
 pub struct HorizonClient {
     pub(crate) client: Client,
-    pub(crate) base_url: String,
+    endpoints: HorizonEndpoints,
     circuit_breaker: StateMachine<failure_policy::ConsecutiveFailures<backoff::EqualJittered>, ()>,
+    /// Operator override pinning the breaker open or closed.
+    manual_override: Arc<RwLock<Option<ManualOverride>>>,
+    /// Whether the automatic breaker was open after the last call, to detect
+    /// transitions.
+    was_open: Arc<AtomicBool>,
 }
 
 impl HorizonClient {
@@ -97,8 +530,10 @@ impl HorizonClient {
 
         HorizonClient {
             client,
-            base_url,
+            endpoints: HorizonEndpoints::new(base_url, []),
             circuit_breaker,
+            manual_override: Arc::default(),
+            was_open: Arc::default(),
         }
     }
 
@@ -122,17 +557,133 @@ impl HorizonClient {
 
         HorizonClient {
             client,
-            base_url,
+            endpoints: HorizonEndpoints::new(base_url, []),
             circuit_breaker,
+            manual_override: Arc::default(),
+            was_open: Arc::default(),
         }
     }
 
-    /// Returns the current state of the circuit breaker
+    /// Fail over to `urls`, most preferred first, when the primary URL is
+    /// unhealthy (see [`crate::stellar::endpoints`]).
+    pub fn with_fallbacks(mut self, urls: Vec<String>) -> Self {
+        self.endpoints = HorizonEndpoints::new(self.endpoints.url(0).to_string(), urls);
+        self
+    }
+
+    /// URL of the endpoint requests currently go to.
+    pub fn base_url(&self) -> &str {
+        self.endpoints.url(self.endpoints.active())
+    }
+
+    pub fn endpoints(&self) -> &HorizonEndpoints {
+        &self.endpoints
+    }
+
+    /// The active endpoint's index and URL without a trailing slash.
+    fn endpoint(&self) -> (usize, &str) {
+        let index = self.endpoints.active();
+        (index, self.endpoints.url(index).trim_end_matches('/'))
+    }
+
+    /// Returns the current state of the circuit breaker, honouring any
+    /// manual override
     pub fn circuit_state(&self) -> String {
-        if self.circuit_breaker.is_call_permitted() {
-            "closed".to_string()
-        } else {
-            "open".to_string()
+        self.breaker_state().as_str().to_string()
+    }
+
+    /// Effective breaker state: the override's if one is set.
+    pub fn breaker_state(&self) -> BreakerState {
+        match self.manual_override() {
+            Some(o) => o.state,
+            None if self.circuit_breaker.is_call_permitted() => BreakerState::Closed,
+            None => BreakerState::Open,
+        }
+    }
+
+    pub fn manual_override(&self) -> Option<ManualOverride> {
+        self.manual_override
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Pin the breaker open or closed, or with `None` return it to automatic
+    /// control. Transitions are published by whoever changes the override.
+    pub fn set_manual_override(&self, value: Option<ManualOverride>) {
+        *self
+            .manual_override
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = value;
+    }
+
+    /// Run a Horizon request to `endpoint` through the breaker. A manual
+    /// override bypasses the automatic breaker: `open` rejects, `closed`
+    /// always sends. The outcome is reported to [`HorizonEndpoints`].
+    async fn guarded<T, F>(&self, endpoint: usize, request: F) -> Result<T, HorizonError>
+    where
+        F: std::future::Future<Output = Result<T, HorizonError>>,
+    {
+        let request = async {
+            let result = request.await;
+            match &result {
+                Ok(_) => self.endpoints.report(endpoint, true, "request succeeded"),
+                Err(e) if e.is_endpoint_failure() => {
+                    self.endpoints.report(endpoint, false, &e.to_string())
+                }
+                Err(_) => {}
+            }
+            result
+        };
+
+        match self.manual_override().map(|o| o.state) {
+            Some(BreakerState::Open) => {
+                return Err(HorizonError::CircuitBreakerOpen(
+                    "Horizon API circuit breaker is manually open".to_string(),
+                ))
+            }
+            Some(BreakerState::Closed) => return request.await,
+            None => {}
+        }
+
+        let result = self.circuit_breaker.call(request).await;
+
+        let open = !self.circuit_breaker.is_call_permitted();
+        if self.was_open.swap(open, Ordering::SeqCst) != open {
+            let (from, to, reason) = match (&result, open) {
+                (Err(FailsafeError::Inner(e)), true) => (
+                    BreakerState::Closed,
+                    BreakerState::Open,
+                    format!("consecutive failures, last: {e}"),
+                ),
+                (_, true) => (
+                    BreakerState::Closed,
+                    BreakerState::Open,
+                    "consecutive failures".to_string(),
+                ),
+                (_, false) => (
+                    BreakerState::Open,
+                    BreakerState::Closed,
+                    "trial request succeeded".to_string(),
+                ),
+            };
+            breakers::publish(BreakerTransition::automatic(
+                breakers::HORIZON,
+                from,
+                to,
+                reason,
+            ));
+        }
+
+        match result {
+            Ok(value) => Ok(value),
+            Err(FailsafeError::Rejected) => Err(HorizonError::CircuitBreakerOpen(
+                "Horizon API circuit breaker is open".to_string(),
+            )),
+            Err(FailsafeError::Inner(e)) => {
+                crate::sampled_warn!("horizon.request", e, error = %e, "Horizon request failed");
+                Err(e)
+            }
         }
     }
 
@@ -140,11 +691,8 @@ impl HorizonClient {
     /// The current trace context is propagated via W3C `traceparent` headers.
     #[instrument(name = "horizon.get_account", skip(self), fields(stellar.account = %address))]
     pub async fn get_account(&self, address: &str) -> Result<AccountResponse, HorizonError> {
-        let url = format!(
-            "{}/accounts/{}",
-            self.base_url.trim_end_matches('/'),
-            address
-        );
+        let (endpoint, base) = self.endpoint();
+        let url = format!("{}/accounts/{}", base, address);
         let client = self.client.clone();
         let addr = address.to_string();
 
@@ -154,39 +702,398 @@ impl HorizonClient {
         let cx = opentelemetry::Context::current();
         propagator.inject_context(&cx, &mut headers);
 
-        let result = self
-            .circuit_breaker
-            .call(async move {
-                let mut req = client.get(&url);
-                for (k, v) in &headers {
-                    req = req.header(k.as_str(), v.as_str());
+        self.guarded(endpoint, async move {
+            let mut req = client.get(&url);
+            for (k, v) in &headers {
+                req = req.header(k.as_str(), v.as_str());
+            }
+            let response = req.send().await?;
+
+            if !response.status().is_success() {
+                if response.status() == 404 {
+                    return Err(HorizonError::AccountNotFound(addr));
                 }
-                let response = req.send().await?;
+                return Err(status_error(response.status()));
+            }
 
-                if !response.status().is_success() {
-                    if response.status() == 404 {
-                        return Err(HorizonError::AccountNotFound(addr));
-                    }
-                    return Err(HorizonError::InvalidResponse(format!(
-                        "Horizon API error: {}",
-                        response.status()
-                    )));
+            let account = response.json::<AccountResponse>().await?;
+            Ok(account)
+        })
+        .await
+    }
+
+    /// `account`'s trustline for `asset`. An account Horizon does not know
+    /// has none.
+    pub async fn check_trustline(
+        &self,
+        account: &str,
+        asset: &Asset,
+    ) -> Result<Trustline, HorizonError> {
+        let Asset::Credit { code, issuer } = asset else {
+            return Ok(Trustline::Native);
+        };
+        let issuer = StellarAddress::from_ed25519(*issuer);
+        let account = match self.get_account(account).await {
+            Ok(account) => account,
+            Err(HorizonError::AccountNotFound(_)) => return Ok(Trustline::Missing),
+            Err(e) => return Err(e),
+        };
+        let Some(balance) = account.balances.iter().find(|b| {
+            b.asset_code.as_deref() == Some(code.as_str())
+                && b.asset_issuer.as_deref() == Some(issuer.account())
+        }) else {
+            return Ok(Trustline::Missing);
+        };
+        let stroops = |amount: Option<&str>| {
+            amount.and_then(builder::parse_stroops).ok_or_else(|| {
+                HorizonError::InvalidResponse(format!(
+                    "unreadable {code} trustline on {}: balance {:?}, limit {:?}",
+                    account.account_id, balance.balance, balance.limit
+                ))
+            })
+        };
+        Ok(Trustline::Held {
+            balance: stroops(Some(&balance.balance))?,
+            limit: stroops(balance.limit.as_deref())?,
+        })
+    }
+
+    /// Fetches one page of an account's payments, oldest first, starting after
+    /// `cursor` (a `paging_token`). An empty page means the history is exhausted.
+    #[instrument(name = "horizon.get_payments_page", skip(self), fields(stellar.account = %account))]
+    pub async fn get_payments_page(
+        &self,
+        account: &str,
+        cursor: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<PaymentRecord>, HorizonError> {
+        let (endpoint, base) = self.endpoint();
+        let mut url = format!(
+            "{}/accounts/{}/payments?order=asc&limit={}&join=transactions",
+            base,
+            account,
+            limit.clamp(1, 200)
+        );
+        if let Some(cursor) = cursor {
+            url.push_str("&cursor=");
+            url.push_str(cursor);
+        }
+        let client = self.client.clone();
+        let addr = account.to_string();
+
+        self.guarded(endpoint, async move {
+            let response = client.get(&url).send().await?;
+
+            if !response.status().is_success() {
+                if response.status() == 404 {
+                    return Err(HorizonError::AccountNotFound(addr));
                 }
+                return Err(status_error(response.status()));
+            }
+
+            let page = response.json::<PaymentsPage>().await?;
+            Ok(page.embedded.records)
+        })
+        .await
+    }
+
+    /// Fetches a transaction by hash. `None` when Horizon does not know it
+    /// (not yet ingested, or never submitted); a 404 does not count as a
+    /// breaker failure.
+    #[instrument(name = "horizon.get_transaction", skip(self), fields(stellar.tx_hash = %hash))]
+    pub async fn get_transaction(
+        &self,
+        hash: &str,
+    ) -> Result<Option<TransactionRecord>, HorizonError> {
+        let (endpoint, base) = self.endpoint();
+        let url = format!("{}/transactions/{}", base, hash);
+        let client = self.client.clone();
+
+        self.guarded(endpoint, async move {
+            let response = client.get(&url).send().await?;
+
+            if response.status() == 404 {
+                return Ok(None);
+            }
+            if !response.status().is_success() {
+                return Err(status_error(response.status()));
+            }
+
+            Ok(Some(response.json::<TransactionRecord>().await?))
+        })
+        .await
+    }
+
+    /// Fetches the payment operations of a transaction. Empty when Horizon
+    /// does not know the transaction; a 404 does not count as a breaker
+    /// failure.
+    #[instrument(name = "horizon.get_transaction_payments", skip(self), fields(stellar.tx_hash = %hash))]
+    pub async fn get_transaction_payments(
+        &self,
+        hash: &str,
+    ) -> Result<Vec<PaymentRecord>, HorizonError> {
+        let (endpoint, base) = self.endpoint();
+        let url = format!("{}/transactions/{}/payments?limit=200", base, hash);
+        let client = self.client.clone();
+
+        self.guarded(endpoint, async move {
+            let response = client.get(&url).send().await?;
+
+            if response.status() == 404 {
+                return Ok(Vec::new());
+            }
+            if !response.status().is_success() {
+                return Err(status_error(response.status()));
+            }
+
+            let page = response.json::<PaymentsPage>().await?;
+            Ok(page.embedded.records)
+        })
+        .await
+    }
+
+    /// Paths converting exactly `source_amount` stroops of `source` into any
+    /// of `destinations`, from `/paths/strict-send`.
+    #[instrument(name = "horizon.find_strict_send_paths", skip(self, destinations))]
+    pub async fn find_strict_send_paths(
+        &self,
+        source: &Asset,
+        source_amount: i64,
+        destinations: &[Asset],
+    ) -> Result<Vec<PathRecord>, HorizonError> {
+        let mut params = asset_params("source_", source);
+        params.push((
+            "source_amount".to_string(),
+            builder::format_stroops(source_amount),
+        ));
+        params.push((
+            "destination_assets".to_string(),
+            destinations
+                .iter()
+                .map(asset_string)
+                .collect::<Vec<_>>()
+                .join(","),
+        ));
+        self.get_paths("strict-send", params).await
+    }
+
+    /// Paths delivering exactly `destination_amount` stroops of
+    /// `destination` from any of `sources`, from `/paths/strict-receive`.
+    #[instrument(name = "horizon.find_strict_receive_paths", skip(self, sources))]
+    pub async fn find_strict_receive_paths(
+        &self,
+        sources: &[Asset],
+        destination: &Asset,
+        destination_amount: i64,
+    ) -> Result<Vec<PathRecord>, HorizonError> {
+        let mut params = asset_params("destination_", destination);
+        params.push((
+            "destination_amount".to_string(),
+            builder::format_stroops(destination_amount),
+        ));
+        params.push((
+            "source_assets".to_string(),
+            sources
+                .iter()
+                .map(asset_string)
+                .collect::<Vec<_>>()
+                .join(","),
+        ));
+        self.get_paths("strict-receive", params).await
+    }
+
+    async fn get_paths(
+        &self,
+        kind: &str,
+        params: Vec<(String, String)>,
+    ) -> Result<Vec<PathRecord>, HorizonError> {
+        let (endpoint, base) = self.endpoint();
+        let url = format!("{}/paths/{kind}", base);
+        let client = self.client.clone();
+
+        self.guarded(endpoint, async move {
+            let response = client.get(&url).query(&params).send().await?;
+            if !response.status().is_success() {
+                return Err(status_error(response.status()));
+            }
+            let page = response.json::<PathsPage>().await?;
+            Ok(page.embedded.records)
+        })
+        .await
+    }
 
-                let account = response.json::<AccountResponse>().await?;
-                Ok(account)
+    /// Up to `limit` (at most 200) claimable balances `claimant` can claim,
+    /// oldest first.
+    #[instrument(name = "horizon.get_claimable_balances", skip(self), fields(stellar.account = %claimant))]
+    pub async fn get_claimable_balances(
+        &self,
+        claimant: &str,
+        limit: u32,
+    ) -> Result<Vec<ClaimableBalanceRecord>, HorizonError> {
+        let (endpoint, base) = self.endpoint();
+        let url = format!("{}/claimable_balances", base);
+        let client = self.client.clone();
+        let params = [
+            ("claimant", claimant.to_string()),
+            ("order", "asc".to_string()),
+            ("limit", limit.clamp(1, 200).to_string()),
+        ];
+
+        self.guarded(endpoint, async move {
+            let response = client.get(&url).query(&params).send().await?;
+            if !response.status().is_success() {
+                return Err(status_error(response.status()));
+            }
+            let page = response.json::<ClaimableBalancesPage>().await?;
+            Ok(page.embedded.records)
+        })
+        .await
+    }
+
+    /// Recent network fees, for [`crate::services::fees`].
+    #[instrument(name = "horizon.get_fee_stats", skip(self))]
+    pub async fn get_fee_stats(&self) -> Result<FeeStats, HorizonError> {
+        let (endpoint, base) = self.endpoint();
+        let url = format!("{}/fee_stats", base);
+        let client = self.client.clone();
+
+        self.guarded(endpoint, async move {
+            let response = client.get(&url).send().await?;
+            if !response.status().is_success() {
+                return Err(status_error(response.status()));
+            }
+            Ok(response.json::<FeeStats>().await?)
+        })
+        .await
+    }
+
+    /// Sequence number for the account's next transaction: its current one
+    /// plus one.
+    pub async fn next_sequence(&self, account: &str) -> Result<i64, HorizonError> {
+        let account = self.get_account(account).await?;
+        account
+            .sequence
+            .parse::<i64>()
+            .ok()
+            .and_then(|seq| seq.checked_add(1))
+            .ok_or_else(|| {
+                HorizonError::InvalidResponse(format!("sequence {:?}", account.sequence))
             })
-            .await;
+    }
 
-        match result {
-            Ok(account) => Ok(account),
-            Err(FailsafeError::Rejected) => Err(HorizonError::CircuitBreakerOpen(
-                "Horizon API circuit breaker is open".to_string(),
-            )),
-            Err(FailsafeError::Inner(e)) => Err(e),
+    /// Submits a signed transaction with `POST /transactions` and waits for
+    /// it to reach a ledger.
+    ///
+    /// A rejection (`400` with a `result_xdr`) is returned as
+    /// [`HorizonError::TransactionFailed`] and a `504` as
+    /// [`HorizonError::SubmissionTimeout`]; neither counts as a breaker
+    /// failure, since Horizon itself answered. A passive region refuses to
+    /// submit at all ([`HorizonError::RegionPassive`]).
+    #[instrument(
+        name = "horizon.submit_transaction",
+        skip_all,
+        fields(stellar.tx_hash = tracing::field::Empty)
+    )]
+    pub async fn submit_transaction(
+        &self,
+        envelope: &TransactionEnvelope,
+        network_passphrase: &str,
+    ) -> Result<SubmittedTransaction, HorizonError> {
+        let region = crate::services::region::region();
+        if !region.is_active() {
+            return Err(HorizonError::RegionPassive(region.name().to_string()));
+        }
+        let hash = hex::encode(envelope.tx.hash(network_passphrase));
+        tracing::Span::current().record("stellar.tx_hash", hash.as_str());
+        let (endpoint, base) = self.endpoint();
+        let url = format!("{}/transactions", base);
+        let client = self.client.clone();
+        let tx = STANDARD.encode(envelope.to_xdr());
+
+        let submission = self
+            .guarded(endpoint, async move {
+                let response = client.post(&url).form(&[("tx", tx)]).send().await?;
+                match response.status().as_u16() {
+                    200..=299 => Ok(Submission::Applied(response.json().await?)),
+                    400 => Ok(Submission::Rejected(response.json().await?)),
+                    504 => Ok(Submission::TimedOut),
+                    _ => Err(status_error(response.status())),
+                }
+            })
+            .await?;
+
+        match submission {
+            Submission::Applied(response) => {
+                let result = decode_result(&response.result_xdr)?;
+                fees::record_spend(result.fee_charged);
+                Ok(SubmittedTransaction {
+                    result,
+                    result_xdr: response.result_xdr,
+                    hash: response.hash,
+                    ledger: response.ledger,
+                })
+            }
+            Submission::Rejected(problem) => {
+                match problem.extras.and_then(|extras| extras.result_xdr) {
+                    Some(result_xdr) => {
+                        let result = decode_result(&result_xdr)?;
+                        // Only `tx_failed` carries operation results; it made
+                        // a ledger and paid its fee.
+                        if !result.operations.is_empty() {
+                            fees::record_spend(result.fee_charged);
+                        }
+                        Err(HorizonError::TransactionFailed { hash, result })
+                    }
+                    None => Err(HorizonError::InvalidTransaction(problem.title)),
+                }
+            }
+            Submission::TimedOut => Err(HorizonError::SubmissionTimeout(hash)),
         }
     }
 
+    /// Builds a transaction of `operations` from the signer's account at its
+    /// next sequence number, valid for `timeout`, and signs it.
+    pub async fn prepare_transaction(
+        &self,
+        signer: &dyn TransactionSigner,
+        network_passphrase: &str,
+        memo: Memo,
+        operations: Vec<Operation>,
+        timeout: chrono::Duration,
+    ) -> Result<TransactionEnvelope, HorizonError> {
+        let source = signer.public_key();
+        let account = StellarAddress::from_ed25519(source);
+        let seq_num = self.next_sequence(account.account()).await?;
+        let mut tx = TransactionBuilder::new(source, seq_num)
+            .with_base_fee(fees::base_fee())
+            .with_timeout(chrono::Utc::now(), timeout)
+            .with_memo(memo);
+        for operation in operations {
+            tx = tx.with_operation(operation);
+        }
+        let tx = tx
+            .build()
+            .map_err(|e| HorizonError::InvalidTransaction(e.to_string()))?;
+        builder::sign(tx, network_passphrase, signer)
+            .await
+            .map_err(|e| HorizonError::Signing(e.to_string()))
+    }
+
+    /// [`Self::prepare_transaction`], then [`Self::submit_transaction`].
+    pub async fn sign_and_submit(
+        &self,
+        signer: &dyn TransactionSigner,
+        network_passphrase: &str,
+        memo: Memo,
+        operations: Vec<Operation>,
+        timeout: chrono::Duration,
+    ) -> Result<SubmittedTransaction, HorizonError> {
+        let envelope = self
+            .prepare_transaction(signer, network_passphrase, memo, operations, timeout)
+            .await?;
+        self.submit_transaction(&envelope, network_passphrase).await
+    }
+
     /// Stream payments for an account via SSE with automatic reconnection
     #[instrument(name = "horizon.stream_payments", skip(self), fields(stellar.account = %account))]
     pub async fn stream_payments(
@@ -202,10 +1109,10 @@ impl HorizonClient {
         }));
 
         loop {
+            let (endpoint, base) = self.endpoint();
             let url = format!(
                 "{}/accounts/{}/payments?order=asc&stream=true",
-                self.base_url.trim_end_matches('/'),
-                account
+                base, account
             );
 
             match self.connect_stream(&url, &tx, &metrics).await {
@@ -227,6 +1134,9 @@ impl HorizonClient {
                     tokio::time::sleep(Duration::from_secs(backoff_secs)).await;
                 }
                 Err(e) => {
+                    if e.is_endpoint_failure() {
+                        self.endpoints.report(endpoint, false, &e.to_string());
+                    }
                     let _ = tx.send(Err(e.clone())).await;
                     return Err(e);
                 }
@@ -274,7 +1184,12 @@ impl HorizonClient {
                             }
                         }
                         Err(e) => {
-                            tracing::warn!("Failed to parse payment event: {}", e);
+                            crate::sampled_warn!(
+                                "horizon.stream_parse",
+                                e,
+                                "Failed to parse payment event: {}",
+                                e
+                            );
                         }
                     }
                 }
@@ -299,7 +1214,7 @@ mod tests {
     #[test]
     fn test_horizon_client_creation() {
         let client = HorizonClient::new("https://horizon-testnet.stellar.org".to_string());
-        assert_eq!(client.base_url, "https://horizon-testnet.stellar.org");
+        assert_eq!(client.base_url(), "https://horizon-testnet.stellar.org");
     }
 
     #[tokio::test]
@@ -366,6 +1281,141 @@ mod tests {
         mock.assert_async().await;
     }
 
+    #[tokio::test]
+    async fn test_check_trustline() {
+        let mut server = mockito::Server::new_async().await;
+        let issuer = StellarAddress::from_ed25519([2; 32]);
+        let other = StellarAddress::from_ed25519([3; 32]);
+        let holder = "GBBD47UZQ5CSKQPV456PYYH4FSYJHBWGQJUVNMCNWZ2NBEHKQPW3KXKJ";
+        let mock = server
+            .mock("GET", format!("/accounts/{holder}").as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{
+                    "id": "{holder}", "account_id": "{holder}",
+                    "balances": [
+                        {{"balance": "5.0000000", "limit": "100.0000000",
+                          "asset_type": "credit_alphanum4", "asset_code": "USDC",
+                          "asset_issuer": "{}"}},
+                        {{"balance": "100.0000000", "asset_type": "native"}}
+                    ],
+                    "sequence": "1", "subentry_count": 1, "home_domain": null,
+                    "last_modified_ledger": 1, "last_modified_time": "2021-01-01T00:00:00Z"
+                }}"#,
+                issuer.account()
+            ))
+            .expect(2)
+            .create_async()
+            .await;
+        let missing = server
+            .mock("GET", "/accounts/GNEW")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let client = HorizonClient::new(server.url());
+        let usdc = Asset::credit("USDC", issuer.public_key()).unwrap();
+        let trustline = client.check_trustline(holder, &usdc).await.unwrap();
+        assert_eq!(
+            trustline,
+            Trustline::Held {
+                balance: 50_000_000,
+                limit: 1_000_000_000
+            }
+        );
+        assert_eq!(trustline.room(), Some(950_000_000));
+
+        let foreign = Asset::credit("USDC", other.public_key()).unwrap();
+        assert_eq!(
+            client.check_trustline(holder, &foreign).await.unwrap(),
+            Trustline::Missing
+        );
+        assert_eq!(
+            client.check_trustline("GNEW", &usdc).await.unwrap(),
+            Trustline::Missing
+        );
+        assert_eq!(
+            client
+                .check_trustline("GNEW", &Asset::Native)
+                .await
+                .unwrap()
+                .room(),
+            None
+        );
+        mock.assert_async().await;
+        missing.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_payments_page_passes_cursor_and_parses_mixed_records() {
+        let mut server = mockito::Server::new_async().await;
+
+        let mock_response = r#"{
+            "_embedded": {
+                "records": [
+                    {
+                        "id": "101",
+                        "paging_token": "101",
+                        "type": "create_account",
+                        "created_at": "2026-03-01T10:00:00Z",
+                        "account": "GBBD47UZQ5CSKQPV456PYYH4FSYJHBWGQJUVNMCNWZ2NBEHKQPW3KXKJ",
+                        "starting_balance": "10.0000000"
+                    },
+                    {
+                        "id": "102",
+                        "paging_token": "102",
+                        "type": "payment",
+                        "created_at": "2026-03-01T11:00:00Z",
+                        "transaction_hash": "abc",
+                        "from": "GAAZI4TCR3TY5OJHCTJC2A4QSY6CJWJH5IAJTGKIN2ER7LBNVKOCCWN7",
+                        "to": "GBBD47UZQ5CSKQPV456PYYH4FSYJHBWGQJUVNMCNWZ2NBEHKQPW3KXKJ",
+                        "amount": "25.5000000",
+                        "asset_type": "credit_alphanum4",
+                        "asset_code": "USD",
+                        "transaction": { "memo": "ref-1", "memo_type": "text" }
+                    }
+                ]
+            }
+        }"#;
+
+        let mock = server
+            .mock(
+                "GET",
+                mockito::Matcher::Regex(r"^/accounts/.*/payments".into()),
+            )
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("cursor".into(), "100".into()),
+                mockito::Matcher::UrlEncoded("join".into(), "transactions".into()),
+                mockito::Matcher::UrlEncoded("limit".into(), "200".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_response)
+            .create_async()
+            .await;
+
+        let client = HorizonClient::new(server.url());
+        let records = client
+            .get_payments_page(
+                "GBBD47UZQ5CSKQPV456PYYH4FSYJHBWGQJUVNMCNWZ2NBEHKQPW3KXKJ",
+                Some("100"),
+                500,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].kind, "create_account");
+        assert!(records[0].amount.is_none());
+        assert_eq!(records[1].amount.as_deref(), Some("25.5000000"));
+        assert_eq!(
+            records[1].transaction.as_ref().unwrap().memo.as_deref(),
+            Some("ref-1")
+        );
+        mock.assert_async().await;
+    }
+
     #[test]
     fn test_circuit_breaker_state() {
         let client = HorizonClient::new("https://horizon-testnet.stellar.org".to_string());
@@ -384,6 +1434,119 @@ mod tests {
         assert_eq!(state, "closed");
     }
 
+    #[tokio::test]
+    async fn test_get_transaction_found_and_missing() {
+        let mut server = mockito::Server::new_async().await;
+        let hash = "a".repeat(64);
+        let found = server
+            .mock("GET", format!("/transactions/{hash}").as_str())
+            .with_status(200)
+            .with_body(format!(
+                r#"{{"hash":"{hash}","successful":true,"ledger":123,
+                    "source_account":"GSRC","created_at":"2026-01-01T00:00:00Z",
+                    "memo":"abc","memo_type":"text","fee_charged":"100",
+                    "envelope_xdr":"AAAAAg==","result_xdr":"AAAAAAAAAGQ="}}"#
+            ))
+            .create_async()
+            .await;
+        let missing = server
+            .mock("GET", "/transactions/unknown")
+            .with_status(404)
+            .expect(3)
+            .create_async()
+            .await;
+
+        let client = HorizonClient::with_circuit_breaker(server.url(), 2, 60);
+        let record = client.get_transaction(&hash).await.unwrap().unwrap();
+        assert!(record.successful);
+        assert_eq!(record.ledger, 123);
+        assert_eq!(record.memo.as_deref(), Some("abc"));
+        assert_eq!(record.envelope_xdr.as_deref(), Some("AAAAAg=="));
+
+        // Unknown hashes never trip the breaker.
+        for _ in 0..3 {
+            assert!(client.get_transaction("unknown").await.unwrap().is_none());
+        }
+        assert_eq!(client.circuit_state(), "closed");
+        found.assert_async().await;
+        missing.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_fails_over_to_fallback_endpoint() {
+        let mut primary = mockito::Server::new_async().await;
+        let mut fallback = mockito::Server::new_async().await;
+        let down = primary
+            .mock("GET", "/fee_stats")
+            .with_status(503)
+            .expect(1)
+            .create_async()
+            .await;
+        let up = fallback
+            .mock("GET", "/fee_stats")
+            .with_status(404)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = HorizonClient::new(primary.url()).with_fallbacks(vec![fallback.url()]);
+        assert!(matches!(
+            client.get_fee_stats().await,
+            Err(HorizonError::Unavailable(_))
+        ));
+        assert_eq!(client.base_url(), fallback.url());
+        // A 404 is the request's fault, not the endpoint's: no failover back.
+        assert!(matches!(
+            client.get_fee_stats().await,
+            Err(HorizonError::InvalidResponse(_))
+        ));
+        assert_eq!(client.clone().base_url(), fallback.url());
+        down.assert_async().await;
+        up.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_transaction_payments_parses_issuer() {
+        let mut server = mockito::Server::new_async().await;
+        let hash = "b".repeat(64);
+        let mock = server
+            .mock("GET", format!("/transactions/{hash}/payments").as_str())
+            .match_query(mockito::Matcher::UrlEncoded("limit".into(), "200".into()))
+            .with_status(200)
+            .with_body(
+                r#"{"_embedded":{"records":[{
+                    "id":"7","paging_token":"7","type":"payment",
+                    "created_at":"2026-01-01T00:00:00Z",
+                    "to":"GBBD47UZQ5CSKQPV456PYYH4FSYJHBWGQJUVNMCNWZ2NBEHKQPW3LEFO",
+                    "amount":"5.0000000","asset_type":"credit_alphanum4","asset_code":"USDC",
+                    "asset_issuer":"GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN"
+                }]}}"#,
+            )
+            .create_async()
+            .await;
+        let missing = server
+            .mock("GET", "/transactions/unknown/payments")
+            .match_query(mockito::Matcher::Any)
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let client = HorizonClient::new(server.url());
+        let records = client.get_transaction_payments(&hash).await.unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(
+            records[0].asset_issuer.as_deref(),
+            Some("GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN")
+        );
+        assert!(client
+            .get_transaction_payments("unknown")
+            .await
+            .unwrap()
+            .is_empty());
+        mock.assert_async().await;
+        missing.assert_async().await;
+    }
+
     #[tokio::test]
     async fn test_circuit_breaker_opens_after_failures() {
         let mut server = mockito::Server::new_async().await;
@@ -411,4 +1574,202 @@ mod tests {
         );
         mock.assert_async().await;
     }
+
+    fn signed_payment() -> TransactionEnvelope {
+        TransactionEnvelope {
+            tx: TransactionBuilder::new([7; 32], 2)
+                .with_operation(crate::stellar::xdr::Payment {
+                    source: None,
+                    destination: [3; 32],
+                    asset: crate::stellar::xdr::Asset::Native,
+                    amount: 10_000_000,
+                })
+                .build()
+                .unwrap(),
+            signatures: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_submit_transaction_parses_result() {
+        let mut server = mockito::Server::new_async().await;
+        let envelope = signed_payment();
+        let tx = STANDARD.encode(envelope.to_xdr());
+        let mock = server
+            .mock("POST", "/transactions")
+            .match_body(mockito::Matcher::UrlEncoded("tx".into(), tx))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"hash": "abc", "ledger": 7,
+                    "result_xdr": "AAAAAAAAAGQAAAAAAAAAAQAAAAAAAAABAAAAAAAAAAA="}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = HorizonClient::new(server.url());
+        let submitted = client
+            .submit_transaction(&envelope, "Test SDF Network ; September 2015")
+            .await
+            .unwrap();
+        assert_eq!(submitted.ledger, 7);
+        assert!(submitted.result.is_success());
+        assert_eq!(submitted.result.fee_charged, 100);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_submit_transaction_failures_do_not_trip_breaker() {
+        let mut server = mockito::Server::new_async().await;
+        let failed = server
+            .mock("POST", "/transactions")
+            .with_status(400)
+            .with_header("content-type", "application/problem+json")
+            .with_body(
+                r#"{"type": "https://stellar.org/horizon-errors/transaction_failed",
+                    "title": "Transaction Failed", "status": 400,
+                    "extras": {"result_xdr": "AAAAAAAAAGT/////AAAAAQAAAAAAAAAB/////gAAAAA="}}"#,
+            )
+            .expect(2)
+            .create_async()
+            .await;
+
+        let client = HorizonClient::with_circuit_breaker(server.url(), 1, 60);
+        let passphrase = "Test SDF Network ; September 2015";
+        let envelope = signed_payment();
+        for _ in 0..2 {
+            match client.submit_transaction(&envelope, passphrase).await {
+                Err(HorizonError::TransactionFailed { hash, result }) => {
+                    assert_eq!(hash, hex::encode(envelope.tx.hash(passphrase)));
+                    assert_eq!(result.to_string(), "tx_failed (op_underfunded)");
+                }
+                other => panic!("expected TransactionFailed, got {other:?}"),
+            }
+        }
+        failed.assert_async().await;
+        assert_eq!(client.circuit_state(), "closed");
+
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/transactions")
+            .with_status(504)
+            .create_async()
+            .await;
+        let client = HorizonClient::with_circuit_breaker(server.url(), 1, 60);
+        assert!(matches!(
+            client.submit_transaction(&envelope, passphrase).await,
+            Err(HorizonError::SubmissionTimeout(_))
+        ));
+        assert_eq!(client.circuit_state(), "closed");
+    }
+
+    #[tokio::test]
+    async fn test_find_strict_send_paths() {
+        let mut server = mockito::Server::new_async().await;
+        let issuer_key = [7u8; 32];
+        let issuer = StellarAddress::from_ed25519(issuer_key)
+            .account()
+            .to_string();
+        let usdc = Asset::credit("USDC", issuer_key).unwrap();
+        let mock = server
+            .mock("GET", "/paths/strict-send")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("source_asset_type".into(), "native".into()),
+                mockito::Matcher::UrlEncoded("source_amount".into(), "10.0000000".into()),
+                mockito::Matcher::UrlEncoded("destination_assets".into(), format!("USDC:{issuer}")),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"_embedded": {{"records": [{{
+                    "source_asset_type": "native", "source_amount": "10.0000000",
+                    "destination_asset_type": "credit_alphanum4",
+                    "destination_asset_code": "USDC", "destination_asset_issuer": "{issuer}",
+                    "destination_amount": "1.2000000",
+                    "path": [{{"asset_type": "credit_alphanum4", "asset_code": "USDC",
+                               "asset_issuer": "{issuer}"}}]
+                }}]}}}}"#
+            ))
+            .create_async()
+            .await;
+
+        let client = HorizonClient::new(server.url());
+        let paths = client
+            .find_strict_send_paths(&Asset::Native, 100_000_000, &[usdc.clone()])
+            .await
+            .unwrap();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].destination_amount, "1.2000000");
+        assert_eq!(paths[0].path_assets().unwrap(), vec![usdc.clone()]);
+        assert_eq!(parse_asset(&format!("USDC:{issuer}")), Some(usdc));
+        assert_eq!(parse_asset("native"), Some(Asset::Native));
+        assert_eq!(parse_asset("USDC"), None);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_claimable_balances() {
+        let mut server = mockito::Server::new_async().await;
+        let account = StellarAddress::from_ed25519([9; 32]).account().to_string();
+        let mock = server
+            .mock("GET", "/claimable_balances")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("claimant".into(), account.clone()),
+                mockito::Matcher::UrlEncoded("limit".into(), "200".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"_embedded": {{"records": [{{
+                    "id": "00000000{}", "asset": "native", "amount": "12.5000000",
+                    "sponsor": "{account}",
+                    "claimants": [{{"destination": "{account}",
+                                    "predicate": {{"unconditional": true}}}}]
+                }}]}}}}"#,
+                "ab".repeat(32)
+            ))
+            .create_async()
+            .await;
+
+        let client = HorizonClient::new(server.url());
+        let balances = client.get_claimable_balances(&account, 500).await.unwrap();
+        assert_eq!(balances.len(), 1);
+        assert_eq!(balances[0].amount, "12.5000000");
+        assert_eq!(balances[0].claimants[0].predicate["unconditional"], true);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_fee_stats() {
+        let mut server = mockito::Server::new_async().await;
+        let distribution = |p50: u32, p90: u32| {
+            format!(
+                r#"{{"min": "100", "mode": "100", "p10": "100", "p50": "{p50}",
+                    "p90": "{p90}", "p99": "{}", "max": "{}"}}"#,
+                p90 * 2,
+                p90 * 4
+            )
+        };
+        let mock = server
+            .mock("GET", "/fee_stats")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"last_ledger": "51234567", "last_ledger_base_fee": "100",
+                    "ledger_capacity_usage": "0.97",
+                    "fee_charged": {}, "max_fee": {}}}"#,
+                distribution(150, 800),
+                distribution(1000, 10000)
+            ))
+            .create_async()
+            .await;
+
+        let client = HorizonClient::new(server.url());
+        let stats = client.get_fee_stats().await.unwrap();
+        assert_eq!(stats.last_ledger, 51_234_567);
+        assert_eq!(stats.fee_charged.p50, 150);
+        assert_eq!(stats.fee_charged.p90, 800);
+        assert!((stats.ledger_capacity_usage - 0.97).abs() < f64::EPSILON);
+        mock.assert_async().await;
+    }
 }
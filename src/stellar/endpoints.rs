@@ -0,0 +1,263 @@
+//! Horizon endpoint failover.
+//!
+//! A [`HorizonClient`] can be given fallback Horizon servers besides its
+//! primary one (`STELLAR_HORIZON_URL`). Requests go to the active endpoint,
+//! which is the first healthy one in configuration order:
+//!
+//! - A request that times out, cannot connect, or is answered with a 5xx or
+//!   429 marks its endpoint unhealthy, and the next healthy one becomes
+//!   active. The failed request is not replayed; callers' own retries reach
+//!   the new endpoint, and the circuit breaker only opens when failures
+//!   continue there too.
+//! - [`run_health_checks`] probes every endpoint's root (`GET /`) and marks
+//!   each healthy or not, so traffic returns to the primary once it
+//!   recovers.
+//! - With every endpoint unhealthy the active one is kept and the circuit
+//!   breaker takes over, as with a single URL.
+//!
+//! | Env var                              | Default | Meaning                                     |
+//! |--------------------------------------|---------|---------------------------------------------|
+//! | `STELLAR_HORIZON_FALLBACK_URLS`      | none    | Comma-separated fallbacks, most preferred first |
+//! | `HORIZON_HEALTH_CHECK_INTERVAL_SECS` | 15      | Seconds between health checks               |
+//!
+//! `horizon_active_endpoint{endpoint}` is 1 for the active endpoint and 0
+//! for the others; `horizon_failovers_total{from, to}` counts switches.
+//! Endpoints are labelled by host, since provider URLs may embed API keys.
+
+use crate::metrics;
+use crate::stellar::HorizonClient;
+use opentelemetry::KeyValue;
+use reqwest::Client;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+const DEFAULT_HEALTH_CHECK_INTERVAL_SECS: u64 = 15;
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+struct Endpoint {
+    url: String,
+    healthy: AtomicBool,
+}
+
+/// Health of one endpoint, for `GET /admin/info`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EndpointStatus {
+    pub endpoint: String,
+    pub healthy: bool,
+    pub active: bool,
+}
+
+/// The Horizon servers a client may use, in order of preference. Clones
+/// share health and the active endpoint.
+#[derive(Clone)]
+pub struct HorizonEndpoints {
+    endpoints: Arc<[Endpoint]>,
+    active: Arc<AtomicUsize>,
+}
+
+impl HorizonEndpoints {
+    /// `primary` followed by `fallbacks`; all start healthy, `primary`
+    /// active.
+    pub fn new(primary: String, fallbacks: impl IntoIterator<Item = String>) -> Self {
+        let endpoints = std::iter::once(primary)
+            .chain(fallbacks)
+            .map(|url| Endpoint {
+                url,
+                healthy: AtomicBool::new(true),
+            })
+            .collect();
+        Self {
+            endpoints,
+            active: Arc::default(),
+        }
+    }
+
+    /// Index of the endpoint requests currently go to.
+    pub fn active(&self) -> usize {
+        self.active.load(Ordering::SeqCst)
+    }
+
+    pub fn url(&self, index: usize) -> &str {
+        &self.endpoints[index].url
+    }
+
+    pub fn urls(&self) -> impl Iterator<Item = &str> {
+        self.endpoints.iter().map(|e| e.url.as_str())
+    }
+
+    pub fn status(&self) -> Vec<EndpointStatus> {
+        let active = self.active();
+        self.endpoints
+            .iter()
+            .enumerate()
+            .map(|(i, e)| EndpointStatus {
+                endpoint: label(&e.url),
+                healthy: e.healthy.load(Ordering::SeqCst),
+                active: i == active,
+            })
+            .collect()
+    }
+
+    /// Record whether endpoint `index` just worked, and make the most
+    /// preferred healthy endpoint active.
+    pub fn report(&self, index: usize, healthy: bool, reason: &str) {
+        let Some(endpoint) = self.endpoints.get(index) else {
+            return;
+        };
+        if endpoint.healthy.swap(healthy, Ordering::SeqCst) != healthy {
+            if healthy {
+                info!(endpoint = %label(&endpoint.url), "Horizon endpoint healthy again");
+            } else {
+                warn!(endpoint = %label(&endpoint.url), reason, "Horizon endpoint unhealthy");
+            }
+        }
+        self.elect(reason);
+    }
+
+    fn elect(&self, reason: &str) {
+        let Some(best) = self
+            .endpoints
+            .iter()
+            .position(|e| e.healthy.load(Ordering::SeqCst))
+        else {
+            return;
+        };
+        let from = self.active.swap(best, Ordering::SeqCst);
+        if from != best {
+            let (from, to) = (label(self.url(from)), label(self.url(best)));
+            warn!(%from, %to, reason, "Horizon endpoint failover");
+            metrics::horizon_failovers_total()
+                .add(1, &[KeyValue::new("from", from), KeyValue::new("to", to)]);
+        }
+    }
+
+    /// Probe every endpoint's root and record the outcome.
+    pub async fn check(&self, client: &Client) {
+        for (index, endpoint) in self.endpoints.iter().enumerate() {
+            let url = format!("{}/", endpoint.url.trim_end_matches('/'));
+            let outcome = match client.get(&url).timeout(HEALTH_CHECK_TIMEOUT).send().await {
+                Ok(response) if response.status().is_success() => Ok(()),
+                Ok(response) => Err(format!("health check answered {}", response.status())),
+                Err(e) => Err(format!("health check failed: {e}")),
+            };
+            match outcome {
+                Ok(()) => self.report(index, true, "health check passed"),
+                Err(reason) => self.report(index, false, &reason),
+            }
+        }
+    }
+}
+
+/// `host[:port]` of `url`, or `url` itself when it does not parse.
+fn label(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|u| {
+            let host = u.host_str()?.to_string();
+            Some(match u.port() {
+                Some(port) => format!("{host}:{port}"),
+                None => host,
+            })
+        })
+        .unwrap_or_else(|| url.to_string())
+}
+
+/// `STELLAR_HORIZON_FALLBACK_URLS`, in order.
+pub fn fallback_urls_from_env() -> Vec<String> {
+    std::env::var("STELLAR_HORIZON_FALLBACK_URLS")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn health_check_interval() -> Duration {
+    let secs = std::env::var("HORIZON_HEALTH_CHECK_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(DEFAULT_HEALTH_CHECK_INTERVAL_SECS);
+    Duration::from_secs(secs)
+}
+
+/// Health-check `horizon`'s endpoints forever and report the active one
+/// on `horizon_active_endpoint`.
+pub async fn run_health_checks(horizon: HorizonClient) {
+    let endpoints = horizon.endpoints().clone();
+    let _gauge = metrics::horizon_active_endpoint(endpoints.clone());
+    let mut interval = tokio::time::interval(health_check_interval());
+    loop {
+        interval.tick().await;
+        endpoints.check(&horizon.client).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoints() -> HorizonEndpoints {
+        HorizonEndpoints::new(
+            "https://primary.example.com".to_string(),
+            [
+                "https://backup.example.com:8000".to_string(),
+                "https://third.example.com/key/abc".to_string(),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_fails_over_to_next_healthy_and_back() {
+        let endpoints = endpoints();
+        assert_eq!(endpoints.active(), 0);
+
+        endpoints.report(0, false, "timeout");
+        assert_eq!(endpoints.active(), 1);
+        endpoints.report(1, false, "503");
+        assert_eq!(endpoints.active(), 2);
+        // Every endpoint down: keep the active one.
+        endpoints.report(2, false, "503");
+        assert_eq!(endpoints.active(), 2);
+
+        endpoints.report(1, true, "health check passed");
+        assert_eq!(endpoints.active(), 1);
+        endpoints.report(0, true, "health check passed");
+        assert_eq!(endpoints.active(), 0);
+    }
+
+    #[test]
+    fn test_status_labels_endpoints_by_host() {
+        let endpoints = endpoints();
+        endpoints.report(0, false, "timeout");
+        let status = endpoints.status();
+        assert_eq!(
+            status[0],
+            EndpointStatus {
+                endpoint: "primary.example.com".to_string(),
+                healthy: false,
+                active: false,
+            }
+        );
+        assert_eq!(status[1].endpoint, "backup.example.com:8000");
+        assert!(status[1].active);
+        assert_eq!(status[2].endpoint, "third.example.com");
+    }
+
+    #[tokio::test]
+    async fn test_health_check_marks_failing_endpoint() {
+        let mut up = mockito::Server::new_async().await;
+        let mut down = mockito::Server::new_async().await;
+        up.mock("GET", "/").with_status(200).create_async().await;
+        down.mock("GET", "/").with_status(503).create_async().await;
+
+        let endpoints = HorizonEndpoints::new(down.url(), [up.url()]);
+        endpoints.check(&Client::new()).await;
+        assert_eq!(endpoints.active(), 1);
+        assert!(!endpoints.status()[0].healthy);
+    }
+}
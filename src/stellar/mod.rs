@@ -1,4 +1,12 @@
+pub mod builder;
 pub mod client;
+pub mod endpoints;
+pub mod summary;
+pub mod xdr;
 
 pub use client::HorizonClient;
-pub use client::{AccountResponse, Balance, HorizonError};
+pub use client::{
+    AccountResponse, Balance, ClaimableBalanceRecord, Claimant, FeeDistribution, FeeStats,
+    HorizonAsset, HorizonError, PathRecord, PaymentRecord, SubmittedTransaction, TransactionRecord,
+    Trustline,
+};
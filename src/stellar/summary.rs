@@ -0,0 +1,232 @@
+//! Readable summaries of a transaction's envelope and result XDR, for
+//! support tooling that would otherwise be handed base64 blobs.
+
+use crate::domain::StellarAddress;
+use crate::stellar::builder::format_stroops;
+use crate::stellar::client::asset_string;
+use crate::stellar::xdr::{
+    ClaimClaimableBalance, Memo, Operation, OperationResult, PathPaymentKind, TransactionEnvelope,
+    TransactionResult,
+};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TransactionSummary {
+    pub source: String,
+    /// Fee offered, in stroops.
+    pub fee: u32,
+    /// Fee charged, in stroops, once the result is known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_charged: Option<i64>,
+    pub seq_num: i64,
+    /// `none`, `text`, `id` or `hash`, as on Horizon.
+    pub memo_type: &'static str,
+    /// Hash memos are base64, as on Horizon.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+    /// Horizon's result codes, e.g. `tx_failed (op_success, op_underfunded)`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
+    pub operations: Vec<OperationSummary>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OperationSummary {
+    /// Horizon's operation type, e.g. `payment`.
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    /// The operation's source, or the transaction's when it has none.
+    pub source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination: Option<String>,
+    /// `native` or `CODE:ISSUER`; for path payments, the asset delivered.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asset: Option<String>,
+    /// Decimal units; for path payments, `destMin` or `destAmount`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub send_asset: Option<String>,
+    /// `sendAmount` or `sendMax`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub send_amount: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_name: Option<String>,
+    /// Horizon's result code, e.g. `op_success`, once the result is known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<&'static str>,
+}
+
+impl OperationSummary {
+    fn new(kind: &'static str, source: String) -> Self {
+        Self {
+            kind,
+            source,
+            destination: None,
+            asset: None,
+            amount: None,
+            send_asset: None,
+            send_amount: None,
+            balance_id: None,
+            data_name: None,
+            result: None,
+        }
+    }
+}
+
+fn address(key: &[u8; 32]) -> String {
+    StellarAddress::from_ed25519(*key).account().to_string()
+}
+
+/// Summarise base64 envelope and result XDR as Horizon stores them. Fails
+/// on XDR outside what [`crate::stellar::xdr`] decodes.
+pub fn summarize(
+    envelope_xdr: &str,
+    result_xdr: Option<&str>,
+) -> Result<TransactionSummary, String> {
+    let envelope = STANDARD
+        .decode(envelope_xdr)
+        .map_err(|e| format!("envelope_xdr: {e}"))
+        .and_then(|bytes| {
+            TransactionEnvelope::from_xdr(&bytes).map_err(|e| format!("envelope_xdr: {e}"))
+        })?;
+    let result = result_xdr
+        .map(|xdr| {
+            STANDARD
+                .decode(xdr)
+                .map_err(|e| format!("result_xdr: {e}"))
+                .and_then(|bytes| {
+                    TransactionResult::from_xdr(&bytes).map_err(|e| format!("result_xdr: {e}"))
+                })
+        })
+        .transpose()?;
+    Ok(summarize_envelope(&envelope, result.as_ref()))
+}
+
+/// Summarise a decoded envelope, with its result once known.
+pub fn summarize_envelope(
+    envelope: &TransactionEnvelope,
+    result: Option<&TransactionResult>,
+) -> TransactionSummary {
+    let tx = &envelope.tx;
+    let (memo_type, memo) = match &tx.memo {
+        Memo::None => ("none", None),
+        Memo::Text(text) => ("text", Some(text.clone())),
+        Memo::Id(id) => ("id", Some(id.to_string())),
+        Memo::Hash(hash) => ("hash", Some(STANDARD.encode(hash))),
+    };
+    let operations = tx
+        .operations
+        .iter()
+        .enumerate()
+        .map(|(i, op)| {
+            let mut summary = summarize_operation(op, &tx.source);
+            summary.result = result
+                .and_then(|r| r.operations.get(i))
+                .map(OperationResult::code_name);
+            summary
+        })
+        .collect();
+    TransactionSummary {
+        source: address(&tx.source),
+        fee: tx.fee,
+        fee_charged: result.map(|r| r.fee_charged),
+        seq_num: tx.seq_num,
+        memo_type,
+        memo,
+        result: result.map(|r| r.to_string()),
+        operations,
+    }
+}
+
+fn summarize_operation(op: &Operation, tx_source: &[u8; 32]) -> OperationSummary {
+    let source = address(&op.source().unwrap_or(*tx_source));
+    match op {
+        Operation::Payment(op) => OperationSummary {
+            destination: Some(address(&op.destination)),
+            asset: Some(asset_string(&op.asset)),
+            amount: Some(format_stroops(op.amount)),
+            ..OperationSummary::new("payment", source)
+        },
+        Operation::PathPayment(op) => OperationSummary {
+            destination: Some(address(&op.destination)),
+            asset: Some(asset_string(&op.dest_asset)),
+            amount: Some(format_stroops(op.dest_amount)),
+            send_asset: Some(asset_string(&op.send_asset)),
+            send_amount: Some(format_stroops(op.send_amount)),
+            ..OperationSummary::new(
+                match op.kind {
+                    PathPaymentKind::StrictSend => "path_payment_strict_send",
+                    PathPaymentKind::StrictReceive => "path_payment_strict_receive",
+                },
+                source,
+            )
+        },
+        Operation::ClaimClaimableBalance(op) => OperationSummary {
+            balance_id: Some(ClaimClaimableBalance::horizon_balance_id(&op.balance_id)),
+            ..OperationSummary::new("claim_claimable_balance", source)
+        },
+        Operation::ManageData(op) => OperationSummary {
+            data_name: Some(op.name.clone()),
+            ..OperationSummary::new("manage_data", source)
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stellar::xdr::{Asset, Payment, Transaction};
+
+    #[test]
+    fn test_summarize_payment() {
+        let issuer = [2; 32];
+        let envelope = TransactionEnvelope {
+            tx: Transaction {
+                source: [1; 32],
+                fee: 100,
+                seq_num: 42,
+                time_bounds: None,
+                memo: Memo::Text("ref-1".to_string()),
+                operations: vec![Payment {
+                    source: None,
+                    destination: [3; 32],
+                    asset: Asset::credit("USDC", issuer).unwrap(),
+                    amount: 125_000_000,
+                }
+                .into()],
+            },
+            signatures: vec![],
+        };
+        // fee_charged 100, txSUCCESS, one PAYMENT_SUCCESS.
+        let result = "AAAAAAAAAGQAAAAAAAAAAQAAAAAAAAABAAAAAAAAAAA=";
+
+        let summary = summarize(&STANDARD.encode(envelope.to_xdr()), Some(result)).unwrap();
+        assert_eq!(summary.source, address(&[1; 32]));
+        assert_eq!(summary.memo_type, "text");
+        assert_eq!(summary.memo.as_deref(), Some("ref-1"));
+        assert_eq!(summary.result.as_deref(), Some("tx_success (op_success)"));
+        assert_eq!(summary.fee_charged, Some(100));
+
+        let op = &summary.operations[0];
+        assert_eq!(op.kind, "payment");
+        assert_eq!(op.source, address(&[1; 32]));
+        assert_eq!(op.destination, Some(address(&[3; 32])));
+        assert_eq!(op.asset, Some(format!("USDC:{}", address(&issuer))));
+        assert_eq!(op.amount.as_deref(), Some("12.5000000"));
+        assert_eq!(op.result, Some("op_success"));
+    }
+
+    #[test]
+    fn test_summarize_rejects_unsupported_xdr() {
+        assert!(summarize("not base64!", None).is_err());
+        // ENVELOPE_TYPE_TX_FEE_BUMP
+        assert!(summarize(&STANDARD.encode(5i32.to_be_bytes()), None)
+            .unwrap_err()
+            .starts_with("envelope_xdr"));
+    }
+}
@@ -0,0 +1,1158 @@
+//! Just enough Stellar XDR for SEP-10 challenges and the anchor's own
+//! payments.
+//!
+//! Encodes and decodes a `TransactionEnvelope` of type `ENVELOPE_TYPE_TX`
+//! whose source accounts are plain ed25519 keys, whose memo is none, text,
+//! id or hash and whose operations are `MANAGE_DATA`, `PAYMENT`, either
+//! path payment or `CLAIM_CLAIMABLE_BALANCE`, and decodes the
+//! `TransactionResult` Horizon returns for one. Anything else fails to
+//! decode with [`XdrError::Unsupported`]. Layouts follow
+//! `Stellar-transaction.x` (protocol 19+).
+
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+const ENVELOPE_TYPE_TX: i32 = 2;
+const KEY_TYPE_ED25519: i32 = 0;
+const PRECOND_NONE: i32 = 0;
+const PRECOND_TIME: i32 = 1;
+const MEMO_NONE: i32 = 0;
+const MEMO_TEXT: i32 = 1;
+const MEMO_ID: i32 = 2;
+const MEMO_HASH: i32 = 3;
+const OP_PAYMENT: i32 = 1;
+const OP_PATH_PAYMENT_STRICT_RECEIVE: i32 = 2;
+const OP_PATH_PAYMENT_STRICT_SEND: i32 = 13;
+const OP_MANAGE_DATA: i32 = 10;
+const OP_CLAIM_CLAIMABLE_BALANCE: i32 = 15;
+const CLAIMABLE_BALANCE_ID_TYPE_V0: i32 = 0;
+const ASSET_TYPE_NATIVE: i32 = 0;
+const ASSET_TYPE_CREDIT_ALPHANUM4: i32 = 1;
+const ASSET_TYPE_CREDIT_ALPHANUM12: i32 = 2;
+const OP_INNER: i32 = 0;
+const TX_SUCCESS: i32 = 0;
+const TX_FAILED: i32 = -1;
+const TX_FEE_BUMP_INNER_SUCCESS: i32 = 1;
+const TX_FEE_BUMP_INNER_FAILED: i32 = -13;
+const MAX_OPERATIONS: u32 = 100;
+const MAX_SIGNATURES: u32 = 20;
+const MAX_DATA_NAME: u32 = 64;
+const MAX_DATA_VALUE: u32 = 64;
+const MAX_SIGNATURE: u32 = 64;
+const MAX_MEMO_TEXT: u32 = 28;
+const MAX_PATH: u32 = 5;
+const MAX_CLAIM_ATOMS: u32 = 1000;
+const CLAIM_ATOM_TYPE_V0: i32 = 0;
+const CLAIM_ATOM_TYPE_ORDER_BOOK: i32 = 1;
+const CLAIM_ATOM_TYPE_LIQUIDITY_POOL: i32 = 2;
+const PATH_PAYMENT_SUCCESS: i32 = 0;
+const PATH_PAYMENT_NO_ISSUER: i32 = -9;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XdrError {
+    /// Input ended early, had trailing bytes or broke a length limit.
+    Malformed(&'static str),
+    /// Valid XDR outside the subset handled here.
+    Unsupported(&'static str),
+}
+
+impl fmt::Display for XdrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            XdrError::Malformed(what) => write!(f, "malformed XDR: {what}"),
+            XdrError::Unsupported(what) => write!(f, "unsupported XDR: {what}"),
+        }
+    }
+}
+
+impl std::error::Error for XdrError {}
+
+/// `MANAGE_DATA` operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManageData {
+    /// Operation source account; the transaction source when `None`.
+    pub source: Option<[u8; 32]>,
+    pub name: String,
+    pub value: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Asset {
+    Native,
+    /// `CREDIT_ALPHANUM4` for codes of up to 4 characters, else
+    /// `CREDIT_ALPHANUM12`.
+    Credit {
+        code: String,
+        issuer: [u8; 32],
+    },
+}
+
+impl Asset {
+    /// A credit asset; `None` unless `code` is 1 to 12 ASCII letters and
+    /// digits.
+    pub fn credit(code: &str, issuer: [u8; 32]) -> Option<Self> {
+        let valid =
+            (1..=12).contains(&code.len()) && code.bytes().all(|b| b.is_ascii_alphanumeric());
+        valid.then(|| Asset::Credit {
+            code: code.to_string(),
+            issuer,
+        })
+    }
+}
+
+/// `PAYMENT` operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Payment {
+    /// Operation source account; the transaction source when `None`.
+    pub source: Option<[u8; 32]>,
+    pub destination: [u8; 32],
+    pub asset: Asset,
+    /// In stroops, 10^-7 of a unit.
+    pub amount: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathPaymentKind {
+    /// Send exactly `send_amount`; the destination gets at least
+    /// `dest_amount`.
+    StrictSend,
+    /// Deliver exactly `dest_amount`, sending at most `send_amount`.
+    StrictReceive,
+}
+
+/// `PATH_PAYMENT_STRICT_SEND` or `PATH_PAYMENT_STRICT_RECEIVE` operation,
+/// converting `send_asset` to `dest_asset` through the assets in `path`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathPayment {
+    /// Operation source account; the transaction source when `None`.
+    pub source: Option<[u8; 32]>,
+    pub kind: PathPaymentKind,
+    pub send_asset: Asset,
+    /// `sendAmount` for strict send, `sendMax` for strict receive; stroops.
+    pub send_amount: i64,
+    pub destination: [u8; 32],
+    pub dest_asset: Asset,
+    /// `destMin` for strict send, `destAmount` for strict receive; stroops.
+    pub dest_amount: i64,
+    /// Intermediate assets, at most 5.
+    pub path: Vec<Asset>,
+}
+
+/// `CLAIM_CLAIMABLE_BALANCE` operation. The claimant must be the operation
+/// source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClaimClaimableBalance {
+    pub source: Option<[u8; 32]>,
+    /// Hash of a `CLAIMABLE_BALANCE_ID_TYPE_V0` id.
+    pub balance_id: [u8; 32],
+}
+
+impl ClaimClaimableBalance {
+    /// The hash in a Horizon balance id: the hex `ClaimableBalanceID` XDR,
+    /// `00000000` followed by 64 hex digits.
+    pub fn parse_balance_id(id: &str) -> Option<[u8; 32]> {
+        let bytes = hex::decode(id.trim()).ok()?;
+        let mut r = Reader {
+            bytes: &bytes,
+            pos: 0,
+        };
+        if r.i32().ok()? != CLAIMABLE_BALANCE_ID_TYPE_V0 || bytes.len() != 36 {
+            return None;
+        }
+        r.take(32).ok()?.try_into().ok()
+    }
+
+    /// Horizon's form of `balance_id`.
+    pub fn horizon_balance_id(balance_id: &[u8; 32]) -> String {
+        let mut w = Writer::default();
+        w.i32(CLAIMABLE_BALANCE_ID_TYPE_V0);
+        w.opaque_fixed(balance_id);
+        hex::encode(w.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operation {
+    ManageData(ManageData),
+    Payment(Payment),
+    PathPayment(PathPayment),
+    ClaimClaimableBalance(ClaimClaimableBalance),
+}
+
+impl Operation {
+    pub fn source(&self) -> Option<[u8; 32]> {
+        match self {
+            Operation::ManageData(op) => op.source,
+            Operation::Payment(op) => op.source,
+            Operation::PathPayment(op) => op.source,
+            Operation::ClaimClaimableBalance(op) => op.source,
+        }
+    }
+
+    pub fn as_manage_data(&self) -> Option<&ManageData> {
+        match self {
+            Operation::ManageData(op) => Some(op),
+            _ => None,
+        }
+    }
+}
+
+impl From<ManageData> for Operation {
+    fn from(op: ManageData) -> Self {
+        Operation::ManageData(op)
+    }
+}
+
+impl From<Payment> for Operation {
+    fn from(op: Payment) -> Self {
+        Operation::Payment(op)
+    }
+}
+
+impl From<PathPayment> for Operation {
+    fn from(op: PathPayment) -> Self {
+        Operation::PathPayment(op)
+    }
+}
+
+impl From<ClaimClaimableBalance> for Operation {
+    fn from(op: ClaimClaimableBalance) -> Self {
+        Operation::ClaimClaimableBalance(op)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Memo {
+    #[default]
+    None,
+    /// Up to 28 bytes.
+    Text(String),
+    Id(u64),
+    Hash([u8; 32]),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transaction {
+    pub source: [u8; 32],
+    pub fee: u32,
+    pub seq_num: i64,
+    /// `(min_time, max_time)` in Unix seconds; 0 means unbounded.
+    pub time_bounds: Option<(u64, u64)>,
+    pub memo: Memo,
+    pub operations: Vec<Operation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecoratedSignature {
+    /// Last four bytes of the signing public key.
+    pub hint: [u8; 4],
+    pub signature: Vec<u8>,
+}
+
+impl DecoratedSignature {
+    pub fn hint_for(public_key: &[u8; 32]) -> [u8; 4] {
+        [
+            public_key[28],
+            public_key[29],
+            public_key[30],
+            public_key[31],
+        ]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionEnvelope {
+    pub tx: Transaction,
+    pub signatures: Vec<DecoratedSignature>,
+}
+
+impl Transaction {
+    /// Hash signed by every signer: SHA-256 of the network id, the envelope
+    /// type and the transaction.
+    pub fn hash(&self, network_passphrase: &str) -> [u8; 32] {
+        let mut w = Writer::default();
+        w.opaque_fixed(&Sha256::digest(network_passphrase.as_bytes()));
+        w.i32(ENVELOPE_TYPE_TX);
+        self.encode(&mut w);
+        Sha256::digest(&w.0).into()
+    }
+
+    fn encode(&self, w: &mut Writer) {
+        w.account(&self.source);
+        w.u32(self.fee);
+        w.i64(self.seq_num);
+        match self.time_bounds {
+            Some((min, max)) => {
+                w.i32(PRECOND_TIME);
+                w.u64(min);
+                w.u64(max);
+            }
+            None => w.i32(PRECOND_NONE),
+        }
+        w.memo(&self.memo);
+        w.u32(self.operations.len() as u32);
+        for op in &self.operations {
+            w.optional_account(op.source().as_ref());
+            match op {
+                Operation::ManageData(op) => {
+                    w.i32(OP_MANAGE_DATA);
+                    w.opaque_var(op.name.as_bytes());
+                    match &op.value {
+                        Some(value) => {
+                            w.u32(1);
+                            w.opaque_var(value);
+                        }
+                        None => w.u32(0),
+                    }
+                }
+                Operation::Payment(op) => {
+                    w.i32(OP_PAYMENT);
+                    w.account(&op.destination);
+                    w.asset(&op.asset);
+                    w.i64(op.amount);
+                }
+                Operation::PathPayment(op) => {
+                    w.i32(match op.kind {
+                        PathPaymentKind::StrictSend => OP_PATH_PAYMENT_STRICT_SEND,
+                        PathPaymentKind::StrictReceive => OP_PATH_PAYMENT_STRICT_RECEIVE,
+                    });
+                    w.asset(&op.send_asset);
+                    w.i64(op.send_amount);
+                    w.account(&op.destination);
+                    w.asset(&op.dest_asset);
+                    w.i64(op.dest_amount);
+                    w.u32(op.path.len() as u32);
+                    for asset in &op.path {
+                        w.asset(asset);
+                    }
+                }
+                Operation::ClaimClaimableBalance(op) => {
+                    w.i32(OP_CLAIM_CLAIMABLE_BALANCE);
+                    w.i32(CLAIMABLE_BALANCE_ID_TYPE_V0);
+                    w.opaque_fixed(&op.balance_id);
+                }
+            }
+        }
+        // ext
+        w.i32(0);
+    }
+
+    fn decode(r: &mut Reader<'_>) -> Result<Self, XdrError> {
+        let source = r.account()?;
+        let fee = r.u32()?;
+        let seq_num = r.i64()?;
+        let time_bounds = match r.i32()? {
+            PRECOND_NONE => None,
+            PRECOND_TIME => Some((r.u64()?, r.u64()?)),
+            _ => return Err(XdrError::Unsupported("preconditions")),
+        };
+        let memo = r.memo()?;
+        let count = r.u32()?;
+        if count > MAX_OPERATIONS {
+            return Err(XdrError::Malformed("too many operations"));
+        }
+        let mut operations = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let source = r.optional_account()?;
+            let op = match r.i32()? {
+                OP_MANAGE_DATA => {
+                    let name = String::from_utf8(r.opaque_var(MAX_DATA_NAME)?)
+                        .map_err(|_| XdrError::Malformed("data name"))?;
+                    let value = match r.u32()? {
+                        0 => None,
+                        1 => Some(r.opaque_var(MAX_DATA_VALUE)?),
+                        _ => return Err(XdrError::Malformed("optional flag")),
+                    };
+                    Operation::ManageData(ManageData {
+                        source,
+                        name,
+                        value,
+                    })
+                }
+                OP_PAYMENT => Operation::Payment(Payment {
+                    source,
+                    destination: r.account()?,
+                    asset: r.asset()?,
+                    amount: r.i64()?,
+                }),
+                code @ (OP_PATH_PAYMENT_STRICT_SEND | OP_PATH_PAYMENT_STRICT_RECEIVE) => {
+                    Operation::PathPayment(PathPayment {
+                        source,
+                        kind: if code == OP_PATH_PAYMENT_STRICT_SEND {
+                            PathPaymentKind::StrictSend
+                        } else {
+                            PathPaymentKind::StrictReceive
+                        },
+                        send_asset: r.asset()?,
+                        send_amount: r.i64()?,
+                        destination: r.account()?,
+                        dest_asset: r.asset()?,
+                        dest_amount: r.i64()?,
+                        path: r.path()?,
+                    })
+                }
+                OP_CLAIM_CLAIMABLE_BALANCE => {
+                    if r.i32()? != CLAIMABLE_BALANCE_ID_TYPE_V0 {
+                        return Err(XdrError::Unsupported("claimable balance id type"));
+                    }
+                    Operation::ClaimClaimableBalance(ClaimClaimableBalance {
+                        source,
+                        balance_id: r.take(32)?.try_into().expect("32 bytes"),
+                    })
+                }
+                _ => return Err(XdrError::Unsupported("operation type")),
+            };
+            operations.push(op);
+        }
+        if r.i32()? != 0 {
+            return Err(XdrError::Unsupported("transaction extension"));
+        }
+        Ok(Self {
+            source,
+            fee,
+            seq_num,
+            time_bounds,
+            memo,
+            operations,
+        })
+    }
+}
+
+impl TransactionEnvelope {
+    pub fn to_xdr(&self) -> Vec<u8> {
+        let mut w = Writer::default();
+        w.i32(ENVELOPE_TYPE_TX);
+        self.tx.encode(&mut w);
+        w.u32(self.signatures.len() as u32);
+        for sig in &self.signatures {
+            w.opaque_fixed(&sig.hint);
+            w.opaque_var(&sig.signature);
+        }
+        w.0
+    }
+
+    pub fn from_xdr(bytes: &[u8]) -> Result<Self, XdrError> {
+        let mut r = Reader { bytes, pos: 0 };
+        if r.i32()? != ENVELOPE_TYPE_TX {
+            return Err(XdrError::Unsupported("envelope type"));
+        }
+        let tx = Transaction::decode(&mut r)?;
+        let count = r.u32()?;
+        if count > MAX_SIGNATURES {
+            return Err(XdrError::Malformed("too many signatures"));
+        }
+        let mut signatures = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let hint = r.take(4)?.try_into().expect("4 bytes");
+            let signature = r.opaque_var(MAX_SIGNATURE)?;
+            signatures.push(DecoratedSignature { hint, signature });
+        }
+        if r.pos != bytes.len() {
+            return Err(XdrError::Malformed("trailing bytes"));
+        }
+        Ok(Self { tx, signatures })
+    }
+}
+
+impl fmt::Display for TransactionResult {
+    /// Horizon's result codes: `tx_failed (op_success, op_underfunded)`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code_name())?;
+        if !self.operations.is_empty() {
+            let ops: Vec<_> = self.operations.iter().map(|op| op.code_name()).collect();
+            write!(f, " ({})", ops.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of a path payment that ran.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathPaymentResult {
+    pub kind: PathPaymentKind,
+    /// `PathPaymentStrict{Send,Receive}ResultCode`; 0 is success.
+    pub code: i32,
+    /// Stroops of the destination asset delivered, on success.
+    pub delivered: Option<i64>,
+}
+
+/// Outcome of one operation in a [`TransactionResult`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationResult {
+    /// The operation ran; its `PaymentResultCode` (0 is success).
+    Payment(i32),
+    PathPayment(PathPaymentResult),
+    /// The operation ran; its `ManageDataResultCode` (0 is success).
+    ManageData(i32),
+    /// The operation ran; its `ClaimClaimableBalanceResultCode` (0 is
+    /// success).
+    ClaimClaimableBalance(i32),
+    /// The operation could not run at all: `opBAD_AUTH`, `opNO_ACCOUNT`, ...
+    Failed(i32),
+}
+
+impl OperationResult {
+    pub fn is_success(&self) -> bool {
+        match self {
+            OperationResult::Payment(code)
+            | OperationResult::ManageData(code)
+            | OperationResult::ClaimClaimableBalance(code) => *code == 0,
+            OperationResult::PathPayment(result) => result.code == PATH_PAYMENT_SUCCESS,
+            OperationResult::Failed(_) => false,
+        }
+    }
+
+    /// Horizon's name for the code, as in `extras.result_codes.operations`.
+    pub fn code_name(&self) -> &'static str {
+        match *self {
+            OperationResult::Payment(0)
+            | OperationResult::ManageData(0)
+            | OperationResult::ClaimClaimableBalance(0) => "op_success",
+            OperationResult::PathPayment(result) => match (result.code, result.kind) {
+                (0, _) => "op_success",
+                (-10, _) => "op_too_few_offers",
+                (-11, _) => "op_cross_self",
+                (-12, PathPaymentKind::StrictReceive) => "op_over_source_max",
+                (-12, PathPaymentKind::StrictSend) => "op_under_dest_min",
+                (code, _) => OperationResult::Payment(code).code_name(),
+            },
+            OperationResult::Payment(code) => match code {
+                -1 => "op_malformed",
+                -2 => "op_underfunded",
+                -3 => "op_src_no_trust",
+                -4 => "op_src_not_authorized",
+                -5 => "op_no_destination",
+                -6 => "op_no_trust",
+                -7 => "op_not_authorized",
+                -8 => "op_line_full",
+                -9 => "op_no_issuer",
+                _ => "op_unknown",
+            },
+            OperationResult::ManageData(code) => match code {
+                -1 => "op_not_supported_yet",
+                -2 => "op_data_name_not_found",
+                -3 => "op_low_reserve",
+                -4 => "op_invalid_data_name",
+                _ => "op_unknown",
+            },
+            OperationResult::ClaimClaimableBalance(code) => match code {
+                -1 => "op_does_not_exist",
+                -2 => "op_cannot_claim",
+                -3 => "op_line_full",
+                -4 => "op_no_trust",
+                -5 => "op_not_authorized",
+                _ => "op_unknown",
+            },
+            OperationResult::Failed(code) => match code {
+                -1 => "op_bad_auth",
+                -2 => "op_no_source_account",
+                -3 => "op_not_supported",
+                -4 => "op_too_many_subentries",
+                -5 => "op_exceeded_work_limit",
+                -6 => "op_too_many_sponsoring",
+                _ => "op_unknown",
+            },
+        }
+    }
+}
+
+/// `TransactionResult` of a transaction that is not a fee bump, as in
+/// Horizon's `result_xdr`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionResult {
+    /// Stroops actually charged, which may be less than the fee offered.
+    pub fee_charged: i64,
+    /// `TransactionResultCode`; 0 is `txSUCCESS`.
+    pub code: i32,
+    /// One per operation when `code` is `txSUCCESS` or `txFAILED`, else
+    /// empty.
+    pub operations: Vec<OperationResult>,
+}
+
+impl TransactionResult {
+    pub fn is_success(&self) -> bool {
+        self.code == TX_SUCCESS
+    }
+
+    /// Horizon's name for the code, as in `extras.result_codes.transaction`.
+    pub fn code_name(&self) -> &'static str {
+        match self.code {
+            0 => "tx_success",
+            -1 => "tx_failed",
+            -2 => "tx_too_early",
+            -3 => "tx_too_late",
+            -4 => "tx_missing_operation",
+            -5 => "tx_bad_seq",
+            -6 => "tx_bad_auth",
+            -7 => "tx_insufficient_balance",
+            -8 => "tx_no_source_account",
+            -9 => "tx_insufficient_fee",
+            -10 => "tx_bad_auth_extra",
+            -11 => "tx_internal_error",
+            -12 => "tx_not_supported",
+            -14 => "tx_bad_sponsorship",
+            -15 => "tx_bad_minseq_age_or_gap",
+            -16 => "tx_malformed",
+            _ => "tx_unknown",
+        }
+    }
+
+    pub fn from_xdr(bytes: &[u8]) -> Result<Self, XdrError> {
+        let mut r = Reader { bytes, pos: 0 };
+        let fee_charged = r.i64()?;
+        let code = r.i32()?;
+        let mut operations = Vec::new();
+        match code {
+            TX_SUCCESS | TX_FAILED => {
+                let count = r.u32()?;
+                if count > MAX_OPERATIONS {
+                    return Err(XdrError::Malformed("too many operations"));
+                }
+                for _ in 0..count {
+                    operations.push(r.operation_result()?);
+                }
+            }
+            TX_FEE_BUMP_INNER_SUCCESS | TX_FEE_BUMP_INNER_FAILED => {
+                return Err(XdrError::Unsupported("fee bump result"))
+            }
+            _ => {}
+        }
+        if r.i32()? != 0 {
+            return Err(XdrError::Unsupported("result extension"));
+        }
+        if r.pos != bytes.len() {
+            return Err(XdrError::Malformed("trailing bytes"));
+        }
+        Ok(Self {
+            fee_charged,
+            code,
+            operations,
+        })
+    }
+}
+
+#[derive(Default)]
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn i32(&mut self, v: i32) {
+        self.0.extend_from_slice(&v.to_be_bytes());
+    }
+
+    fn u32(&mut self, v: u32) {
+        self.0.extend_from_slice(&v.to_be_bytes());
+    }
+
+    fn i64(&mut self, v: i64) {
+        self.0.extend_from_slice(&v.to_be_bytes());
+    }
+
+    fn u64(&mut self, v: u64) {
+        self.0.extend_from_slice(&v.to_be_bytes());
+    }
+
+    fn opaque_fixed(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(bytes);
+        self.pad(bytes.len());
+    }
+
+    fn opaque_var(&mut self, bytes: &[u8]) {
+        self.u32(bytes.len() as u32);
+        self.opaque_fixed(bytes);
+    }
+
+    fn account(&mut self, key: &[u8; 32]) {
+        self.i32(KEY_TYPE_ED25519);
+        self.0.extend_from_slice(key);
+    }
+
+    fn optional_account(&mut self, key: Option<&[u8; 32]>) {
+        match key {
+            Some(key) => {
+                self.u32(1);
+                self.account(key);
+            }
+            None => self.u32(0),
+        }
+    }
+
+    fn memo(&mut self, memo: &Memo) {
+        match memo {
+            Memo::None => self.i32(MEMO_NONE),
+            Memo::Text(text) => {
+                self.i32(MEMO_TEXT);
+                self.opaque_var(text.as_bytes());
+            }
+            Memo::Id(id) => {
+                self.i32(MEMO_ID);
+                self.u64(*id);
+            }
+            Memo::Hash(hash) => {
+                self.i32(MEMO_HASH);
+                self.opaque_fixed(hash);
+            }
+        }
+    }
+
+    fn asset(&mut self, asset: &Asset) {
+        match asset {
+            Asset::Native => self.i32(ASSET_TYPE_NATIVE),
+            Asset::Credit { code, issuer } => {
+                let (kind, width) = if code.len() <= 4 {
+                    (ASSET_TYPE_CREDIT_ALPHANUM4, 4)
+                } else {
+                    (ASSET_TYPE_CREDIT_ALPHANUM12, 12)
+                };
+                self.i32(kind);
+                let mut padded = code.as_bytes().to_vec();
+                padded.resize(width, 0);
+                self.opaque_fixed(&padded);
+                self.account(issuer);
+            }
+        }
+    }
+
+    fn pad(&mut self, len: usize) {
+        self.0.resize(self.0.len() + (4 - len % 4) % 4, 0);
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, n: usize) -> Result<&[u8], XdrError> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or(XdrError::Malformed("unexpected end of input"))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn i32(&mut self) -> Result<i32, XdrError> {
+        Ok(i32::from_be_bytes(
+            self.take(4)?.try_into().expect("4 bytes"),
+        ))
+    }
+
+    fn u32(&mut self) -> Result<u32, XdrError> {
+        Ok(u32::from_be_bytes(
+            self.take(4)?.try_into().expect("4 bytes"),
+        ))
+    }
+
+    fn i64(&mut self) -> Result<i64, XdrError> {
+        Ok(i64::from_be_bytes(
+            self.take(8)?.try_into().expect("8 bytes"),
+        ))
+    }
+
+    fn u64(&mut self) -> Result<u64, XdrError> {
+        Ok(u64::from_be_bytes(
+            self.take(8)?.try_into().expect("8 bytes"),
+        ))
+    }
+
+    fn opaque_var(&mut self, max: u32) -> Result<Vec<u8>, XdrError> {
+        let len = self.u32()?;
+        if len > max {
+            return Err(XdrError::Malformed("length over limit"));
+        }
+        let bytes = self.take(len as usize)?.to_vec();
+        let padding = (4 - len as usize % 4) % 4;
+        if self.take(padding)?.iter().any(|b| *b != 0) {
+            return Err(XdrError::Malformed("non-zero padding"));
+        }
+        Ok(bytes)
+    }
+
+    fn account(&mut self) -> Result<[u8; 32], XdrError> {
+        if self.i32()? != KEY_TYPE_ED25519 {
+            return Err(XdrError::Unsupported("muxed account"));
+        }
+        Ok(self.take(32)?.try_into().expect("32 bytes"))
+    }
+
+    fn optional_account(&mut self) -> Result<Option<[u8; 32]>, XdrError> {
+        match self.u32()? {
+            0 => Ok(None),
+            1 => Ok(Some(self.account()?)),
+            _ => Err(XdrError::Malformed("optional flag")),
+        }
+    }
+
+    fn memo(&mut self) -> Result<Memo, XdrError> {
+        Ok(match self.i32()? {
+            MEMO_NONE => Memo::None,
+            MEMO_TEXT => Memo::Text(
+                String::from_utf8(self.opaque_var(MAX_MEMO_TEXT)?)
+                    .map_err(|_| XdrError::Malformed("memo text"))?,
+            ),
+            MEMO_ID => Memo::Id(self.u64()?),
+            MEMO_HASH => Memo::Hash(self.take(32)?.try_into().expect("32 bytes")),
+            _ => return Err(XdrError::Unsupported("memo type")),
+        })
+    }
+
+    fn asset(&mut self) -> Result<Asset, XdrError> {
+        let width = match self.i32()? {
+            ASSET_TYPE_NATIVE => return Ok(Asset::Native),
+            ASSET_TYPE_CREDIT_ALPHANUM4 => 4,
+            ASSET_TYPE_CREDIT_ALPHANUM12 => 12,
+            _ => return Err(XdrError::Unsupported("asset type")),
+        };
+        let padded = self.take(width)?;
+        let len = padded.iter().position(|b| *b == 0).unwrap_or(width);
+        if padded[len..].iter().any(|b| *b != 0) || (width == 12) != (len > 4) {
+            return Err(XdrError::Malformed("asset code"));
+        }
+        let code = std::str::from_utf8(&padded[..len])
+            .map_err(|_| XdrError::Malformed("asset code"))?
+            .to_string();
+        Asset::credit(&code, self.account()?).ok_or(XdrError::Malformed("asset code"))
+    }
+
+    fn operation_result(&mut self) -> Result<OperationResult, XdrError> {
+        let code = self.i32()?;
+        if code != OP_INNER {
+            return Ok(OperationResult::Failed(code));
+        }
+        Ok(match self.i32()? {
+            OP_PAYMENT => OperationResult::Payment(self.i32()?),
+            OP_PATH_PAYMENT_STRICT_SEND => self.path_payment_result(PathPaymentKind::StrictSend)?,
+            OP_PATH_PAYMENT_STRICT_RECEIVE => {
+                self.path_payment_result(PathPaymentKind::StrictReceive)?
+            }
+            OP_MANAGE_DATA => OperationResult::ManageData(self.i32()?),
+            OP_CLAIM_CLAIMABLE_BALANCE => OperationResult::ClaimClaimableBalance(self.i32()?),
+            _ => return Err(XdrError::Unsupported("operation result type")),
+        })
+    }
+
+    fn path(&mut self) -> Result<Vec<Asset>, XdrError> {
+        let count = self.u32()?;
+        if count > MAX_PATH {
+            return Err(XdrError::Malformed("path too long"));
+        }
+        (0..count).map(|_| self.asset()).collect()
+    }
+
+    /// `PathPaymentStrict{Send,Receive}Result`: on success the offers and
+    /// pools crossed, then the payment made to the destination.
+    fn path_payment_result(&mut self, kind: PathPaymentKind) -> Result<OperationResult, XdrError> {
+        let code = self.i32()?;
+        let mut delivered = None;
+        match code {
+            PATH_PAYMENT_SUCCESS => {
+                let count = self.u32()?;
+                if count > MAX_CLAIM_ATOMS {
+                    return Err(XdrError::Malformed("too many offers claimed"));
+                }
+                for _ in 0..count {
+                    self.claim_atom()?;
+                }
+                self.account()?;
+                self.asset()?;
+                delivered = Some(self.i64()?);
+            }
+            PATH_PAYMENT_NO_ISSUER => {
+                self.asset()?;
+            }
+            _ => {}
+        }
+        Ok(OperationResult::PathPayment(PathPaymentResult {
+            kind,
+            code,
+            delivered,
+        }))
+    }
+
+    /// Skips a `ClaimAtom`.
+    fn claim_atom(&mut self) -> Result<(), XdrError> {
+        match self.i32()? {
+            CLAIM_ATOM_TYPE_V0 => {
+                self.take(32)?;
+                self.i64()?;
+            }
+            CLAIM_ATOM_TYPE_ORDER_BOOK => {
+                self.account()?;
+                self.i64()?;
+            }
+            CLAIM_ATOM_TYPE_LIQUIDITY_POOL => {
+                self.take(32)?;
+            }
+            _ => return Err(XdrError::Unsupported("claim atom type")),
+        }
+        // Asset and amount sold, then bought.
+        self.asset()?;
+        self.i64()?;
+        self.asset()?;
+        self.i64()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn envelope() -> TransactionEnvelope {
+        TransactionEnvelope {
+            tx: Transaction {
+                source: [7; 32],
+                fee: 200,
+                seq_num: 0,
+                time_bounds: Some((1_700_000_000, 1_700_000_900)),
+                memo: Memo::None,
+                operations: vec![
+                    ManageData {
+                        source: Some([9; 32]),
+                        name: "example.com auth".to_string(),
+                        value: Some(vec![b'x'; 64]),
+                    }
+                    .into(),
+                    ManageData {
+                        source: None,
+                        name: "web_auth_domain".to_string(),
+                        value: Some(b"auth.example.com".to_vec()),
+                    }
+                    .into(),
+                ],
+            },
+            signatures: vec![DecoratedSignature {
+                hint: DecoratedSignature::hint_for(&[7; 32]),
+                signature: vec![1; 64],
+            }],
+        }
+    }
+
+    #[test]
+    fn test_envelope_round_trip() {
+        let envelope = envelope();
+        let bytes = envelope.to_xdr();
+        assert_eq!(bytes.len() % 4, 0);
+        assert_eq!(TransactionEnvelope::from_xdr(&bytes).unwrap(), envelope);
+    }
+
+    #[test]
+    fn test_rejects_truncated_and_trailing_input() {
+        let bytes = envelope().to_xdr();
+        assert!(TransactionEnvelope::from_xdr(&bytes[..bytes.len() - 1]).is_err());
+
+        let mut longer = bytes.clone();
+        longer.extend_from_slice(&[0; 4]);
+        assert_eq!(
+            TransactionEnvelope::from_xdr(&longer),
+            Err(XdrError::Malformed("trailing bytes"))
+        );
+    }
+
+    #[test]
+    fn test_hash_depends_on_network() {
+        let tx = envelope().tx;
+        assert_ne!(
+            tx.hash("Test SDF Network ; September 2015"),
+            tx.hash("Public Global Stellar Network ; September 2015")
+        );
+    }
+
+    #[test]
+    fn test_payment_envelope_round_trip() {
+        for memo in [
+            Memo::Text("settlement 42".to_string()),
+            Memo::Id(u64::MAX),
+            Memo::Hash([5; 32]),
+        ] {
+            let mut envelope = envelope();
+            envelope.tx.memo = memo;
+            envelope.tx.operations = vec![
+                Payment {
+                    source: None,
+                    destination: [3; 32],
+                    asset: Asset::Native,
+                    amount: 1,
+                }
+                .into(),
+                Payment {
+                    source: Some([9; 32]),
+                    destination: [3; 32],
+                    asset: Asset::credit("USDC", [4; 32]).unwrap(),
+                    amount: 125_000_000,
+                }
+                .into(),
+                Payment {
+                    source: None,
+                    destination: [3; 32],
+                    asset: Asset::credit("LONGCODE1", [4; 32]).unwrap(),
+                    amount: i64::MAX,
+                }
+                .into(),
+            ];
+            let bytes = envelope.to_xdr();
+            assert_eq!(bytes.len() % 4, 0);
+            assert_eq!(TransactionEnvelope::from_xdr(&bytes).unwrap(), envelope);
+        }
+    }
+
+    #[test]
+    fn test_asset_codes() {
+        assert!(Asset::credit("", [0; 32]).is_none());
+        assert!(Asset::credit("TOOLONGCODE13", [0; 32]).is_none());
+        assert!(Asset::credit("US-D", [0; 32]).is_none());
+
+        // A short code padded into an alphanum12 slot is not canonical.
+        let mut w = Writer::default();
+        w.i32(ASSET_TYPE_CREDIT_ALPHANUM12);
+        w.opaque_fixed(b"USD\0\0\0\0\0\0\0\0\0");
+        w.account(&[4; 32]);
+        let mut r = Reader {
+            bytes: &w.0,
+            pos: 0,
+        };
+        assert_eq!(r.asset(), Err(XdrError::Malformed("asset code")));
+    }
+
+    #[test]
+    fn test_transaction_result() {
+        // txFAILED: a payment that ran out of funds after one that did not
+        // have a destination, then one that never ran for lack of auth.
+        let mut w = Writer::default();
+        w.i64(300);
+        w.i32(TX_FAILED);
+        w.u32(3);
+        for (op, code) in [(OP_PAYMENT, -2), (OP_PAYMENT, -5)] {
+            w.i32(OP_INNER);
+            w.i32(op);
+            w.i32(code);
+        }
+        w.i32(-1);
+        w.i32(0);
+        let result = TransactionResult::from_xdr(&w.0).unwrap();
+        assert_eq!(result.fee_charged, 300);
+        assert!(!result.is_success());
+        assert_eq!(result.code_name(), "tx_failed");
+        assert_eq!(
+            result.to_string(),
+            "tx_failed (op_underfunded, op_no_destination, op_bad_auth)"
+        );
+
+        // txBAD_SEQ carries no operation results.
+        let mut w = Writer::default();
+        w.i64(0);
+        w.i32(-5);
+        w.i32(0);
+        let result = TransactionResult::from_xdr(&w.0).unwrap();
+        assert_eq!(result.code_name(), "tx_bad_seq");
+        assert!(result.operations.is_empty());
+
+        let mut w = Writer::default();
+        w.i64(100);
+        w.i32(TX_FEE_BUMP_INNER_SUCCESS);
+        assert_eq!(
+            TransactionResult::from_xdr(&w.0),
+            Err(XdrError::Unsupported("fee bump result"))
+        );
+    }
+
+    #[test]
+    fn test_path_payment_round_trip() {
+        let usdc = Asset::credit("USDC", [4; 32]).unwrap();
+        let eurc = Asset::credit("EURC", [5; 32]).unwrap();
+        for kind in [PathPaymentKind::StrictSend, PathPaymentKind::StrictReceive] {
+            let mut envelope = envelope();
+            envelope.tx.operations = vec![PathPayment {
+                source: None,
+                kind,
+                send_asset: usdc.clone(),
+                send_amount: 1_000_000_000,
+                destination: [3; 32],
+                dest_asset: eurc.clone(),
+                dest_amount: 910_000_000,
+                path: vec![Asset::Native],
+            }
+            .into()];
+            let bytes = envelope.to_xdr();
+            assert_eq!(TransactionEnvelope::from_xdr(&bytes).unwrap(), envelope);
+        }
+    }
+
+    #[test]
+    fn test_path_payment_result_reports_delivered_amount() {
+        let mut w = Writer::default();
+        w.i64(100);
+        w.i32(TX_SUCCESS);
+        w.u32(1);
+        w.i32(OP_INNER);
+        w.i32(OP_PATH_PAYMENT_STRICT_SEND);
+        w.i32(PATH_PAYMENT_SUCCESS);
+        // Crossed one order book offer and one liquidity pool.
+        w.u32(2);
+        w.i32(CLAIM_ATOM_TYPE_ORDER_BOOK);
+        w.account(&[6; 32]);
+        w.i64(77);
+        w.asset(&Asset::Native);
+        w.i64(50);
+        w.asset(&Asset::credit("USDC", [4; 32]).unwrap());
+        w.i64(10);
+        w.i32(CLAIM_ATOM_TYPE_LIQUIDITY_POOL);
+        w.opaque_fixed(&[8; 32]);
+        w.asset(&Asset::credit("EURC", [5; 32]).unwrap());
+        w.i64(9);
+        w.asset(&Asset::Native);
+        w.i64(50);
+        // The payment to the destination.
+        w.account(&[3; 32]);
+        w.asset(&Asset::credit("EURC", [5; 32]).unwrap());
+        w.i64(9);
+        w.i32(0);
+
+        let result = TransactionResult::from_xdr(&w.0).unwrap();
+        assert!(result.is_success());
+        assert_eq!(
+            result.operations,
+            [OperationResult::PathPayment(PathPaymentResult {
+                kind: PathPaymentKind::StrictSend,
+                code: 0,
+                delivered: Some(9),
+            })]
+        );
+
+        let under_min = OperationResult::PathPayment(PathPaymentResult {
+            kind: PathPaymentKind::StrictSend,
+            code: -12,
+            delivered: None,
+        });
+        assert_eq!(under_min.code_name(), "op_under_dest_min");
+        assert!(!under_min.is_success());
+    }
+
+    #[test]
+    fn test_claim_claimable_balance_round_trip() {
+        let id = "00000000da0d57da7d4850e7fc10d2a9d0ebc731f7afb40574c03395b17d49149b91f5be";
+        let balance_id = ClaimClaimableBalance::parse_balance_id(id).unwrap();
+        assert_eq!(ClaimClaimableBalance::horizon_balance_id(&balance_id), id);
+        assert_eq!(ClaimClaimableBalance::parse_balance_id(&id[8..]), None);
+        assert_eq!(
+            ClaimClaimableBalance::parse_balance_id(&format!("00000001{}", &id[8..])),
+            None
+        );
+
+        let mut envelope = envelope();
+        envelope.tx.operations = vec![ClaimClaimableBalance {
+            source: Some([3; 32]),
+            balance_id,
+        }
+        .into()];
+        let bytes = envelope.to_xdr();
+        assert_eq!(TransactionEnvelope::from_xdr(&bytes).unwrap(), envelope);
+
+        let no_trust = OperationResult::ClaimClaimableBalance(-4);
+        assert_eq!(no_trust.code_name(), "op_no_trust");
+        assert!(!no_trust.is_success());
+    }
+}
@@ -0,0 +1,111 @@
+//! Business dimensions recorded on request and processing spans.
+//!
+//! The webhook handlers and the processor tag their spans with the same
+//! fields, so traces (and the metrics exemplars pointing at them) can be
+//! sliced by partner, asset or size of deposit during an incident:
+//!
+//! | Field           | Value                                                |
+//! |-----------------|------------------------------------------------------|
+//! | `partner_id`    | Tenant id of the calling partner, when authenticated |
+//! | `asset_code`    | Asset of the deposit                                 |
+//! | `amount_bucket` | [`amount_bucket`] of the amount, never the amount    |
+//! | `api_version`   | `v1` / `v2`, for versioned endpoints                 |
+//!
+//! `tracing` only records fields a span declared up front, so a span that
+//! should carry them declares each one as `tracing::field::Empty` and then
+//! calls [`BusinessAttributes::record`] once the values are known. Fields
+//! left `None` stay unset rather than being recorded as empty strings.
+
+use crate::db::models::Transaction;
+use crate::middleware::versioning::ApiVersion;
+use bigdecimal::BigDecimal;
+use tracing::field::display;
+use tracing::Span;
+use uuid::Uuid;
+
+/// Upper bounds (exclusive) of each amount bucket, in asset units.
+const AMOUNT_BUCKETS: [(i64, &str); 5] = [
+    (1, "<1"),
+    (100, "1-100"),
+    (1_000, "100-1k"),
+    (10_000, "1k-10k"),
+    (100_000, "10k-100k"),
+];
+
+/// Low-cardinality label for `amount`, safe to use as a span attribute or
+/// metric label where the raw amount is not.
+pub fn amount_bucket(amount: &BigDecimal) -> &'static str {
+    AMOUNT_BUCKETS
+        .iter()
+        .find(|(upper, _)| *amount < BigDecimal::from(*upper))
+        .map_or(">=100k", |(_, label)| label)
+}
+
+/// Values for the business fields of a span; see the module docs.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BusinessAttributes<'a> {
+    pub partner_id: Option<Uuid>,
+    pub asset_code: Option<&'a str>,
+    pub amount: Option<&'a BigDecimal>,
+    pub api_version: Option<ApiVersion>,
+}
+
+impl<'a> BusinessAttributes<'a> {
+    /// Asset and amount of `tx`, plus the partner it belongs to, if known.
+    pub fn for_transaction(tx: &'a Transaction, partner_id: Option<Uuid>) -> Self {
+        Self {
+            partner_id,
+            asset_code: Some(&tx.asset_code),
+            amount: Some(&tx.amount),
+            api_version: None,
+        }
+    }
+
+    pub fn with_api_version(mut self, api_version: ApiVersion) -> Self {
+        self.api_version = Some(api_version);
+        self
+    }
+
+    /// Record the values that are set on `span`.
+    pub fn record(&self, span: &Span) {
+        if let Some(partner_id) = self.partner_id {
+            span.record("partner_id", display(partner_id));
+        }
+        if let Some(asset_code) = self.asset_code {
+            span.record("asset_code", asset_code);
+        }
+        if let Some(amount) = self.amount {
+            span.record("amount_bucket", amount_bucket(amount));
+        }
+        if let Some(api_version) = self.api_version {
+            span.record("api_version", api_version.as_str());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_amount_bucket_boundaries() {
+        let cases = [
+            ("0.5", "<1"),
+            ("1", "1-100"),
+            ("99.99", "1-100"),
+            ("100", "100-1k"),
+            ("9999.9999999", "1k-10k"),
+            ("10000", "10k-100k"),
+            ("100000", ">=100k"),
+            ("250000000", ">=100k"),
+        ];
+        for (amount, bucket) in cases {
+            assert_eq!(
+                amount_bucket(&BigDecimal::from_str(amount).unwrap()),
+                bucket,
+                "{amount}"
+            );
+        }
+    }
+}
@@ -3,6 +3,7 @@
 //!
 //! All error paths are designed to degrade gracefully without panicking.
 
+pub mod attributes;
 pub mod connection_pool;
 pub mod data_export;
 pub mod error_handling;
@@ -12,6 +13,7 @@ pub mod metrics_optimization;
 pub mod reconnection;
 pub mod webhook;
 
+pub use attributes::BusinessAttributes;
 pub use connection_pool::{ConnectionPool, PoolConfig};
 pub use data_export::{DataExportService, ExportBatch, ExportConfig, TelemetryRecord};
 pub use error_handling::{ErrorAction, ErrorHandler, TelemetryError, TelemetryResult};
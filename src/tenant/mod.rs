@@ -7,7 +7,7 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::{error::AppError, AppState};
+use crate::{db::models::TrustedAsset, error::AppError, AppState};
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct TenantConfig {
@@ -17,6 +17,44 @@ pub struct TenantConfig {
     pub stellar_account: String,
     pub rate_limit_per_minute: i32,
     pub is_active: bool,
+    /// Rows of this partner taken per round-robin round by the processor.
+    #[serde(default = "default_processing_weight")]
+    pub processing_weight: i32,
+    /// Asset codes this partner may deposit. `None` means unrestricted.
+    #[serde(default)]
+    pub allowed_assets: Option<Vec<String>>,
+    /// Asset issuers this partner accepts; empty means any issuer. Loaded
+    /// from `partner_trusted_assets`.
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub trusted_assets: Vec<TrustedAsset>,
+}
+
+fn default_processing_weight() -> i32 {
+    1
+}
+
+impl TenantConfig {
+    /// Whether `asset_code` is on this partner's allowlist (case-insensitive).
+    pub fn is_asset_allowed(&self, asset_code: &str) -> bool {
+        match &self.allowed_assets {
+            None => true,
+            Some(allowed) => allowed.iter().any(|a| a.eq_ignore_ascii_case(asset_code)),
+        }
+    }
+
+    /// Reject `asset_code` with [`AppError::AssetNotAllowed`] if it is not on
+    /// the partner's allowlist.
+    pub fn check_asset_allowed(&self, asset_code: &str) -> Result<(), AppError> {
+        if self.is_asset_allowed(asset_code) {
+            Ok(())
+        } else {
+            Err(AppError::AssetNotAllowed(format!(
+                "partner {} is not authorized for asset {asset_code}",
+                self.tenant_id
+            )))
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -54,6 +92,19 @@ impl FromRequestParts<AppState> for TenantContext {
     }
 }
 
+#[async_trait]
+impl FromRequestParts<crate::ApiState> for TenantContext {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &crate::ApiState,
+    ) -> std::result::Result<Self, AppError> {
+        <TenantContext as FromRequestParts<AppState>>::from_request_parts(parts, &state.app_state)
+            .await
+    }
+}
+
 async fn resolve_tenant_id(
     parts: &mut Parts,
     state: &AppState,
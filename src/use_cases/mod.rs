@@ -3,4 +3,4 @@
 
 pub mod process_deposit;
 
-pub use process_deposit::{DepositInput, DepositOutput, ProcessDeposit};
+pub use process_deposit::{DepositError, DepositInput, DepositOutput, ProcessDeposit};
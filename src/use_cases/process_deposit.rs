@@ -1,8 +1,17 @@
 //! Process deposit use case.
 //! Handles deposit logic using the TransactionRepository.
+//!
+//! Deposits are checked against the partner's asset allowlist before anything
+//! is persisted and, when a `CustomerRepository` is injected with
+//! [`ProcessDeposit::with_kyc_check`], refused unless the sending account has
+//! an accepted SEP-12 customer record. The new transaction's id and
+//! timestamps come from the injected `IdGenerator` and `Clock`.
 
-use crate::domain::Transaction;
-use crate::ports::{RepositoryError, TransactionRepository};
+use crate::adapters::{RandomIdGenerator, SystemClock};
+use crate::domain::{StellarAddress, Transaction};
+use crate::ports::{
+    Clock, CustomerRepository, IdGenerator, KycStatus, RepositoryError, TransactionRepository,
+};
 use bigdecimal::BigDecimal;
 use std::sync::Arc;
 
@@ -18,6 +27,22 @@ pub struct DepositInput {
     pub memo: Option<String>,
     pub memo_type: Option<String>,
     pub metadata: Option<serde_json::Value>,
+    /// Assets the submitting partner is authorized for. `None` = unrestricted.
+    pub allowed_assets: Option<Vec<String>>,
+}
+
+/// Errors returned by [`ProcessDeposit::execute`].
+#[derive(Debug, thiserror::Error)]
+pub enum DepositError {
+    #[error("Asset not allowed: {0}")]
+    AssetNotAllowed(String),
+
+    /// The sending account has no accepted customer record.
+    #[error("KYC not accepted for {0}")]
+    KycNotAccepted(String),
+
+    #[error(transparent)]
+    Repository(#[from] RepositoryError),
 }
 
 /// Output of the ProcessDeposit use case.
@@ -30,17 +55,74 @@ pub struct DepositOutput {
 /// Use case for processing deposits.
 pub struct ProcessDeposit {
     transaction_repository: Arc<dyn TransactionRepository>,
+    clock: Arc<dyn Clock>,
+    ids: Arc<dyn IdGenerator>,
+    customers: Option<Arc<dyn CustomerRepository>>,
 }
 
 impl ProcessDeposit {
+    /// Use case over `transaction_repository`, with the system clock and
+    /// random ids.
     pub fn new(transaction_repository: Arc<dyn TransactionRepository>) -> Self {
         Self {
             transaction_repository,
+            clock: Arc::new(SystemClock),
+            ids: Arc::new(RandomIdGenerator),
+            customers: None,
+        }
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn with_id_generator(mut self, ids: Arc<dyn IdGenerator>) -> Self {
+        self.ids = ids;
+        self
+    }
+
+    /// Refuse deposits from accounts without an `ACCEPTED` customer record
+    /// in `customers`.
+    pub fn with_kyc_check(mut self, customers: Arc<dyn CustomerRepository>) -> Self {
+        self.customers = Some(customers);
+        self
+    }
+
+    async fn check_kyc(&self, stellar_account: &str) -> Result<(), DepositError> {
+        let Some(customers) = &self.customers else {
+            return Ok(());
+        };
+        // Customers are recorded against the base account of muxed senders.
+        let account = StellarAddress::parse(stellar_account)
+            .map(|a| a.account().to_string())
+            .unwrap_or_else(|_| stellar_account.to_string());
+        let accepted = customers
+            .list_by_account(&account)
+            .await?
+            .iter()
+            .any(|c| c.status == KycStatus::Accepted);
+        if accepted {
+            Ok(())
+        } else {
+            Err(DepositError::KycNotAccepted(account))
         }
     }
 
-    pub async fn execute(&self, input: DepositInput) -> Result<DepositOutput, RepositoryError> {
+    pub async fn execute(&self, input: DepositInput) -> Result<DepositOutput, DepositError> {
+        if let Some(allowed) = &input.allowed_assets {
+            if !allowed
+                .iter()
+                .any(|a| a.eq_ignore_ascii_case(&input.asset_code))
+            {
+                return Err(DepositError::AssetNotAllowed(input.asset_code));
+            }
+        }
+        self.check_kyc(&input.stellar_account).await?;
+
         let tx = Transaction::new(
+            self.ids.new_id(),
+            self.clock.now(),
             input.stellar_account,
             input.amount,
             input.asset_code,
@@ -60,3 +142,83 @@ impl ProcessDeposit {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::shadow_transaction_repository::tests::MemoryRepository;
+    use crate::adapters::{ManualClock, SequentialIdGenerator};
+    use chrono::{TimeZone, Utc};
+
+    #[tokio::test]
+    async fn test_deposit_takes_id_and_time_from_ports() {
+        let repo = Arc::new(MemoryRepository::default());
+        let now = Utc.with_ymd_and_hms(2026, 2, 3, 4, 5, 6).unwrap();
+        let deposits = ProcessDeposit::new(repo.clone())
+            .with_clock(Arc::new(ManualClock::new(now)))
+            .with_id_generator(Arc::new(SequentialIdGenerator::new()));
+
+        let output = deposits
+            .execute(DepositInput {
+                stellar_account: "GABC".to_string(),
+                amount: BigDecimal::from(25),
+                asset_code: "USDC".to_string(),
+                anchor_transaction_id: None,
+                callback_type: None,
+                callback_status: None,
+                memo: None,
+                memo_type: None,
+                metadata: None,
+                allowed_assets: Some(vec!["usdc".to_string()]),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(output.transaction_id, SequentialIdGenerator::nth(1));
+        let stored = repo.get_by_id(output.transaction_id).await.unwrap();
+        assert_eq!(stored.created_at, now);
+        assert_eq!(stored.updated_at, now);
+    }
+
+    #[tokio::test]
+    async fn test_kyc_check_requires_an_accepted_customer() {
+        use crate::adapters::InMemoryCustomerRepository;
+        use crate::ports::{Customer, CustomerKey};
+
+        const ACCOUNT: &str = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+        let input = || DepositInput {
+            stellar_account: ACCOUNT.to_string(),
+            amount: BigDecimal::from(25),
+            asset_code: "USDC".to_string(),
+            anchor_transaction_id: None,
+            callback_type: None,
+            callback_status: None,
+            memo: None,
+            memo_type: None,
+            metadata: None,
+            allowed_assets: None,
+        };
+        let customers = Arc::new(InMemoryCustomerRepository::default());
+        let deposits = ProcessDeposit::new(Arc::new(MemoryRepository::default()))
+            .with_kyc_check(customers.clone());
+
+        let err = deposits.execute(input()).await.unwrap_err();
+        assert!(matches!(err, DepositError::KycNotAccepted(ref a) if a == ACCOUNT));
+
+        let mut customer = Customer {
+            id: uuid::Uuid::new_v4(),
+            key: CustomerKey::new(ACCOUNT),
+            fields: Default::default(),
+            status: KycStatus::Processing,
+            message: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        customers.save(&customer).await.unwrap();
+        assert!(deposits.execute(input()).await.is_err());
+
+        customer.status = KycStatus::Accepted;
+        customers.save(&customer).await.unwrap();
+        assert!(deposits.execute(input()).await.unwrap().success);
+    }
+}
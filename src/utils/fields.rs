@@ -0,0 +1,146 @@
+//! Sparse fieldsets: `?fields=id,status,amount` on REST reads.
+//!
+//! Handlers parse the parameter against the resource's allowed field names
+//! before querying, then [`FieldSelection::project`] each serialized record.
+//! Without `fields` every field is returned, so existing clients are
+//! unaffected.
+
+use serde::Deserialize;
+use serde_json::Value;
+
+/// `?fields=` on single-resource reads; list endpoints carry the same field
+/// in their own query struct.
+#[derive(Debug, Default, Deserialize)]
+pub struct FieldsQuery {
+    pub fields: Option<String>,
+}
+
+/// Fields of [`crate::db::models::Transaction`] a client may select.
+pub const TRANSACTION_FIELDS: &[&str] = &[
+    "id",
+    "stellar_account",
+    "amount",
+    "asset_code",
+    "status",
+    "created_at",
+    "updated_at",
+    "anchor_transaction_id",
+    "callback_type",
+    "callback_status",
+    "settlement_id",
+    "memo",
+    "memo_type",
+    "metadata",
+    "trace_id",
+    "backfilled",
+    "stellar_tx_hash",
+    "ledger",
+    "closed_at",
+    "stellar_muxed_id",
+    "risk_score",
+    "risk_reasons",
+    "fee_amount",
+];
+
+/// Fields of [`crate::db::models::Settlement`] a client may select.
+pub const SETTLEMENT_FIELDS: &[&str] = &[
+    "id",
+    "asset_code",
+    "total_amount",
+    "tx_count",
+    "period_start",
+    "period_end",
+    "status",
+    "created_at",
+    "updated_at",
+    "dispute_reason",
+    "original_total_amount",
+    "reviewed_by",
+    "reviewed_at",
+    "quoted_payouts",
+];
+
+/// Fields requested with `?fields=`; `All` when the parameter is absent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldSelection {
+    All,
+    Only(Vec<&'static str>),
+}
+
+impl FieldSelection {
+    /// Parse a `fields` parameter, rejecting names not in `allowed`.
+    pub fn parse(raw: Option<&str>, allowed: &[&'static str]) -> Result<Self, String> {
+        let Some(raw) = raw else {
+            return Ok(FieldSelection::All);
+        };
+        let mut selected = Vec::new();
+        for name in raw.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            let field = allowed
+                .iter()
+                .find(|f| **f == name)
+                .ok_or_else(|| format!("Unknown field '{name}'"))?;
+            if !selected.contains(field) {
+                selected.push(*field);
+            }
+        }
+        if selected.is_empty() {
+            return Err("fields must name at least one field".to_string());
+        }
+        Ok(FieldSelection::Only(selected))
+    }
+
+    /// Keep only the selected keys of a serialized record.
+    pub fn project(&self, value: Value) -> Value {
+        match (self, value) {
+            (FieldSelection::Only(fields), Value::Object(mut map)) => {
+                map.retain(|k, _| fields.contains(&k.as_str()));
+                Value::Object(map)
+            }
+            (_, value) => value,
+        }
+    }
+
+    /// Serialize `records` and project each one.
+    pub fn project_all<T: serde::Serialize>(&self, records: &[T]) -> Value {
+        Value::Array(
+            records
+                .iter()
+                .map(|r| self.project(serde_json::to_value(r).unwrap_or(Value::Null)))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_absent_parameter_selects_everything() {
+        let sel = FieldSelection::parse(None, TRANSACTION_FIELDS).unwrap();
+        assert_eq!(sel, FieldSelection::All);
+        assert_eq!(
+            sel.project(json!({"id": 1, "memo": "x"})),
+            json!({"id": 1, "memo": "x"})
+        );
+    }
+
+    #[test]
+    fn test_projects_requested_fields() {
+        let sel = FieldSelection::parse(Some("id, status,amount,id"), TRANSACTION_FIELDS).unwrap();
+        assert_eq!(sel, FieldSelection::Only(vec!["id", "status", "amount"]));
+        assert_eq!(
+            sel.project(json!({"id": 1, "status": "pending", "amount": "5", "memo": "x"})),
+            json!({"id": 1, "status": "pending", "amount": "5"})
+        );
+    }
+
+    #[test]
+    fn test_rejects_unknown_and_empty_selections() {
+        assert!(FieldSelection::parse(Some("id,password"), TRANSACTION_FIELDS).is_err());
+        assert!(FieldSelection::parse(Some(" , "), TRANSACTION_FIELDS).is_err());
+        assert!(FieldSelection::parse(Some("tx_count"), TRANSACTION_FIELDS).is_err());
+        assert!(FieldSelection::parse(Some("tx_count"), SETTLEMENT_FIELDS).is_ok());
+    }
+}
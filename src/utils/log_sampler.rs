@@ -0,0 +1,285 @@
+//! Rate-limited logging for errors that repeat during an incident.
+//!
+//! When Horizon is down or a webhook endpoint refuses everything, the
+//! processor, the webhook dispatcher and the Horizon client log the same
+//! failure for every row they touch. [`sampled_error!`](crate::sampled_error)
+//! and [`sampled_warn!`](crate::sampled_warn) log the first
+//! `LOG_SAMPLE_LIMIT` occurrences of each error signature per window and drop
+//! the rest; when the window ends one summary line at `warn` reports how many
+//! were dropped, with an example message.
+//!
+//! A signature is the call site plus the error message with ids, hashes and
+//! numbers masked (see [`dlq_errors::normalize`]), so "delivery 1 failed: 503"
+//! and "delivery 2 failed: 503" share a budget.
+//!
+//! | Env var                  | Default | Meaning                                      |
+//! |--------------------------|---------|----------------------------------------------|
+//! | `LOG_SAMPLE_LIMIT`       | 10      | Lines per signature per window; 0 logs all   |
+//! | `LOG_SAMPLE_WINDOW_SECS` | 60      | Window length                                |
+//!
+//! Dropped lines are counted in `log_lines_suppressed_total{site}`.
+
+use crate::metrics;
+use crate::services::dlq_errors;
+use opentelemetry::KeyValue;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+const DEFAULT_LIMIT: u64 = 10;
+const DEFAULT_WINDOW_SECS: u64 = 60;
+
+/// Past this many live signatures new ones are logged unsampled, so a burst of
+/// distinct messages cannot grow the table without bound.
+const MAX_SIGNATURES: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SamplerConfig {
+    pub limit: u64,
+    pub window: Duration,
+}
+
+impl SamplerConfig {
+    pub fn from_env() -> Self {
+        let limit = std::env::var("LOG_SAMPLE_LIMIT")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(DEFAULT_LIMIT);
+        let window_secs = std::env::var("LOG_SAMPLE_WINDOW_SECS")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(DEFAULT_WINDOW_SECS);
+        Self {
+            limit,
+            window: Duration::from_secs(window_secs),
+        }
+    }
+}
+
+/// Occurrences of one signature dropped over a finished window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suppressed {
+    pub site: &'static str,
+    pub count: u64,
+    /// First message of the window.
+    pub example: String,
+}
+
+struct Window {
+    started: Instant,
+    seen: u64,
+    example: String,
+}
+
+pub struct LogSampler {
+    config: SamplerConfig,
+    windows: Mutex<HashMap<(&'static str, String), Window>>,
+}
+
+impl LogSampler {
+    pub fn new(config: SamplerConfig) -> Self {
+        Self {
+            config,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn summary(&self, site: &'static str, window: &Window) -> Option<Suppressed> {
+        (window.seen > self.config.limit).then(|| Suppressed {
+            site,
+            count: window.seen - self.config.limit,
+            example: window.example.clone(),
+        })
+    }
+
+    /// Whether an occurrence of `message` at `site` should be logged, plus
+    /// the summary of the signature's previous window if this one starts a
+    /// new window.
+    pub fn check(
+        &self,
+        site: &'static str,
+        message: &str,
+        now: Instant,
+    ) -> (bool, Option<Suppressed>) {
+        if self.config.limit == 0 {
+            return (true, None);
+        }
+        let key = (site, dlq_errors::normalize(message));
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        let fresh = || Window {
+            started: now,
+            seen: 1,
+            example: message.to_string(),
+        };
+        match windows.get_mut(&key) {
+            Some(window) if now.duration_since(window.started) >= self.config.window => {
+                let summary = self.summary(site, window);
+                *window = fresh();
+                (true, summary)
+            }
+            Some(window) => {
+                window.seen += 1;
+                (window.seen <= self.config.limit, None)
+            }
+            None => {
+                if windows.len() < MAX_SIGNATURES {
+                    windows.insert(key, fresh());
+                }
+                (true, None)
+            }
+        }
+    }
+
+    /// Drop windows that ended by `now`, returning the ones that suppressed
+    /// anything.
+    pub fn take_expired(&self, now: Instant) -> Vec<Suppressed> {
+        let mut expired = Vec::new();
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        windows.retain(|(site, _), window| {
+            if now.duration_since(window.started) < self.config.window {
+                return true;
+            }
+            expired.extend(self.summary(*site, window));
+            false
+        });
+        expired
+    }
+}
+
+fn global() -> &'static LogSampler {
+    static SAMPLER: OnceLock<LogSampler> = OnceLock::new();
+    SAMPLER.get_or_init(|| LogSampler::new(SamplerConfig::from_env()))
+}
+
+fn report(suppressed: &Suppressed) {
+    tracing::warn!(
+        site = suppressed.site,
+        suppressed = suppressed.count,
+        example = %suppressed.example,
+        "Suppressed repeated log lines"
+    );
+}
+
+/// Whether an occurrence of `message` at `site` is within its budget. Used by
+/// the sampling macros; call sites should not need it directly.
+pub fn should_log(site: &'static str, message: &str) -> bool {
+    let (log, summary) = global().check(site, message, Instant::now());
+    if let Some(summary) = summary {
+        report(&summary);
+    }
+    if !log {
+        metrics::log_lines_suppressed_total().add(1, &[KeyValue::new("site", site)]);
+    }
+    log
+}
+
+/// Report the summary of each window as it ends, so a storm that stops still
+/// gets its suppressed count logged.
+pub async fn report_suppressed() {
+    let mut ticker = tokio::time::interval(global().config.window);
+    loop {
+        ticker.tick().await;
+        for suppressed in global().take_expired(Instant::now()) {
+            report(&suppressed);
+        }
+    }
+}
+
+/// `tracing::error!`, sampled per `$site` and signature of `$err` (anything
+/// `Display`); see [`crate::utils::log_sampler`].
+#[macro_export]
+macro_rules! sampled_error {
+    ($site:expr, $err:expr, $($arg:tt)+) => {
+        if $crate::utils::log_sampler::should_log($site, &$err.to_string()) {
+            ::tracing::error!($($arg)+);
+        }
+    };
+}
+
+/// `tracing::warn!`, sampled like [`sampled_error!`].
+#[macro_export]
+macro_rules! sampled_warn {
+    ($site:expr, $err:expr, $($arg:tt)+) => {
+        if $crate::utils::log_sampler::should_log($site, &$err.to_string()) {
+            ::tracing::warn!($($arg)+);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sampler(limit: u64) -> LogSampler {
+        LogSampler::new(SamplerConfig {
+            limit,
+            window: Duration::from_secs(60),
+        })
+    }
+
+    #[test]
+    fn test_logs_first_occurrences_then_summarises() {
+        let sampler = sampler(2);
+        let t0 = Instant::now();
+        let logged: Vec<bool> = (1..=5)
+            .map(|i| {
+                let message = format!("delivery {i} failed: status 503");
+                sampler.check("dispatcher", &message, t0).0
+            })
+            .collect();
+        assert_eq!(logged, vec![true, true, false, false, false]);
+
+        // A different site has its own budget.
+        assert!(
+            sampler
+                .check("processor", "delivery 9 failed: status 503", t0)
+                .0
+        );
+
+        let (log, summary) = sampler.check(
+            "dispatcher",
+            "delivery 6 failed: status 503",
+            t0 + Duration::from_secs(61),
+        );
+        assert!(log);
+        assert_eq!(
+            summary,
+            Some(Suppressed {
+                site: "dispatcher",
+                count: 3,
+                example: "delivery 1 failed: status 503".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_take_expired_reports_only_suppressing_windows() {
+        let sampler = sampler(1);
+        let t0 = Instant::now();
+        for _ in 0..3 {
+            sampler.check("horizon", "connection refused", t0);
+        }
+        sampler.check("horizon", "timed out", t0);
+
+        assert!(sampler
+            .take_expired(t0 + Duration::from_secs(30))
+            .is_empty());
+        let expired = sampler.take_expired(t0 + Duration::from_secs(60));
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].count, 2);
+        // Both windows are gone, so the next occurrence logs again.
+        assert!(
+            sampler
+                .check("horizon", "timed out", t0 + Duration::from_secs(61))
+                .0
+        );
+    }
+
+    #[test]
+    fn test_zero_limit_disables_sampling() {
+        let sampler = sampler(0);
+        let t0 = Instant::now();
+        assert!((0..100).all(|_| sampler.check("processor", "boom", t0).0));
+    }
+}
@@ -1,3 +1,7 @@
 pub mod cursor;
+pub mod fields;
+pub mod log_sampler;
+pub mod pdf;
 pub mod retry;
 pub mod sanitize;
+pub mod signed_url;
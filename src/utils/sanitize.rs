@@ -8,6 +8,8 @@ pub fn sanitize_json(value: &Value) -> Value {
             for (key, val) in map {
                 let sanitized_val = if is_sensitive_field(key) {
                     mask_value(val)
+                } else if key.eq_ignore_ascii_case("metadata") {
+                    redact_metadata(val)
                 } else {
                     sanitize_json(val)
                 };
@@ -20,6 +22,33 @@ pub fn sanitize_json(value: &Value) -> Value {
     }
 }
 
+/// Partner metadata is opaque to us (order ids, user ids, emails), so every
+/// value is masked while the key structure is kept for debugging.
+pub fn redact_metadata(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, val)| (key.clone(), redact_metadata(val)))
+                .collect(),
+        ),
+        Value::Array(arr) => Value::Array(arr.iter().map(redact_metadata).collect()),
+        Value::Null => Value::Null,
+        _ => Value::String("****".to_string()),
+    }
+}
+
+/// Masks a PII field value for callers not allowed to see it: strings with
+/// [`mask_str`], objects and arrays with [`redact_metadata`]. Shared by the
+/// GraphQL field masking and the REST response redaction.
+pub fn mask_pii(value: &Value) -> Value {
+    match value {
+        Value::String(s) => Value::String(mask_str(s)),
+        Value::Object(_) | Value::Array(_) => redact_metadata(value),
+        Value::Null => Value::Null,
+        _ => Value::String("****".to_string()),
+    }
+}
+
 fn is_sensitive_field(key: &str) -> bool {
     let key_lower = key.to_lowercase();
     // Exact matches
@@ -50,17 +79,21 @@ fn is_sensitive_field(key: &str) -> bool {
 
 fn mask_value(value: &Value) -> Value {
     match value {
-        Value::String(s) if s.len() > 8 => {
-            let visible = &s[..4];
-            let masked = "****";
-            let end = &s[s.len() - 4..];
-            Value::String(format!("{visible}{masked}{end}"))
-        }
-        Value::String(_s) => Value::String("****".to_string()),
+        Value::String(s) => Value::String(mask_str(s)),
         _ => Value::String("****".to_string()),
     }
 }
 
+/// Masks a sensitive string, keeping the first and last four characters of
+/// values long enough to still be recognisable (e.g. `GABC****7890`).
+pub fn mask_str(s: &str) -> String {
+    if s.len() > 8 && s.is_char_boundary(4) && s.is_char_boundary(s.len() - 4) {
+        format!("{}****{}", &s[..4], &s[s.len() - 4..])
+    } else {
+        "****".to_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -80,6 +113,20 @@ mod tests {
         assert_eq!(sanitized["amount"], "100.00");
     }
 
+    #[test]
+    fn test_mask_pii_by_value_type() {
+        assert_eq!(
+            mask_pii(&json!("GABCDEFGHIJKLMNOPQRSTUVWXYZ1234567890")),
+            "GABC****7890"
+        );
+        assert_eq!(
+            mask_pii(&json!({"email": "a@b.c"})),
+            json!({"email": "****"})
+        );
+        assert_eq!(mask_pii(&json!(42)), "****");
+        assert_eq!(mask_pii(&Value::Null), Value::Null);
+    }
+
     #[test]
     fn test_sanitize_nested() {
         let input = json!({
@@ -223,6 +270,28 @@ mod tests {
         assert!(sanitized["nested"]["data"].is_null());
     }
 
+    #[test]
+    fn test_sanitize_redacts_metadata_values() {
+        let input = json!({
+            "amount": "100.00",
+            "metadata": {
+                "order_id": "ORD-42",
+                "customer": {"email": "a@example.com", "tier": 3},
+                "tags": ["vip"],
+                "note": null
+            }
+        });
+
+        let sanitized = sanitize_json(&input);
+
+        assert_eq!(sanitized["amount"], "100.00");
+        assert_eq!(sanitized["metadata"]["order_id"], "****");
+        assert_eq!(sanitized["metadata"]["customer"]["email"], "****");
+        assert_eq!(sanitized["metadata"]["customer"]["tier"], "****");
+        assert_eq!(sanitized["metadata"]["tags"], json!(["****"]));
+        assert!(sanitized["metadata"]["note"].is_null());
+    }
+
     #[test]
     fn test_sanitize_large_payload_performance() {
         use std::time::Instant;
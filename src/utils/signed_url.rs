@@ -0,0 +1,149 @@
+//! Time-limited, HMAC-signed download URLs.
+//!
+//! A signed URL carries `?expires=<unix seconds>&signature=<hex>`, where the
+//! signature is HMAC-SHA256 over `{resource}.{expires}`. `resource` names
+//! what the link grants (an export id, `backup:<file name>`), so a signature
+//! issued for one download cannot be replayed against another. Browsers can
+//! follow such links without holding an API key.
+//!
+//! The key is `DOWNLOAD_SIGNING_SECRET`, falling back to
+//! `EXPORT_SIGNING_SECRET`; links live for `DOWNLOAD_URL_TTL_SECS`, falling
+//! back to `EXPORT_URL_TTL_SECS` (default one hour). Without a secret no
+//! links are issued and download handlers answer `404`.
+
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Default lifetime of a signed URL.
+pub const DEFAULT_TTL_SECS: i64 = 3600;
+
+/// `?expires=&signature=` on a signed download route.
+#[derive(Debug, Deserialize)]
+pub struct SignedUrlQuery {
+    pub expires: i64,
+    pub signature: String,
+}
+
+impl SignedUrlQuery {
+    pub fn verify(&self, secret: &str, resource: &str, now: DateTime<Utc>) -> bool {
+        verify(secret, resource, self.expires, &self.signature, now)
+    }
+}
+
+fn env_with_fallback(name: &str, fallback: &str) -> Option<String> {
+    [name, fallback]
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()))
+}
+
+/// Key used to sign download URLs, if configured.
+pub fn signing_secret() -> Option<String> {
+    env_with_fallback("DOWNLOAD_SIGNING_SECRET", "EXPORT_SIGNING_SECRET")
+}
+
+/// Lifetime of newly issued URLs.
+pub fn ttl() -> Duration {
+    let secs = env_with_fallback("DOWNLOAD_URL_TTL_SECS", "EXPORT_URL_TTL_SECS")
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|secs| *secs > 0)
+        .unwrap_or(DEFAULT_TTL_SECS);
+    Duration::seconds(secs)
+}
+
+fn mac(secret: &str, resource: &str, expires: i64) -> HmacSha256 {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(format!("{resource}.{expires}").as_bytes());
+    mac
+}
+
+/// Hex HMAC-SHA256 of `{resource}.{expires}`.
+pub fn sign(secret: &str, resource: &str, expires: i64) -> String {
+    hex::encode(mac(secret, resource, expires).finalize().into_bytes())
+}
+
+/// Check a signature (constant time) and its expiry, a unix timestamp.
+pub fn verify(
+    secret: &str,
+    resource: &str,
+    expires: i64,
+    signature: &str,
+    now: DateTime<Utc>,
+) -> bool {
+    if expires < now.timestamp() {
+        return false;
+    }
+    let Ok(signature) = hex::decode(signature) else {
+        return false;
+    };
+    mac(secret, resource, expires)
+        .verify_slice(&signature)
+        .is_ok()
+}
+
+/// `path` with a signature for `resource` valid for [`ttl`] from `now`, and
+/// the moment it expires.
+pub fn sign_url(
+    secret: &str,
+    path: &str,
+    resource: &str,
+    now: DateTime<Utc>,
+) -> (String, DateTime<Utc>) {
+    let expires_at = now + ttl();
+    let expires = expires_at.timestamp();
+    let signature = sign(secret, resource, expires);
+    (
+        format!("{path}?expires={expires}&signature={signature}"),
+        expires_at,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_is_bound_to_resource_and_expiry() {
+        let now = Utc::now();
+        let expires = now.timestamp() + 60;
+        let sig = sign("secret", "backup:daily.sql.gz", expires);
+
+        assert!(verify("secret", "backup:daily.sql.gz", expires, &sig, now));
+        assert!(!verify("secret", "backup:other.sql.gz", expires, &sig, now));
+        assert!(!verify("other", "backup:daily.sql.gz", expires, &sig, now));
+        assert!(!verify(
+            "secret",
+            "backup:daily.sql.gz",
+            expires + 1,
+            &sig,
+            now
+        ));
+        assert!(!verify(
+            "secret",
+            "backup:daily.sql.gz",
+            expires,
+            &sig,
+            now + Duration::seconds(61)
+        ));
+        assert!(!verify("secret", "backup:daily.sql.gz", expires, "zz", now));
+    }
+
+    #[test]
+    fn test_sign_url_round_trip() {
+        let now = Utc::now();
+        let (url, expires_at) = sign_url("secret", "/downloads/backups/a.gz", "backup:a.gz", now);
+        let query = SignedUrlQuery {
+            expires: expires_at.timestamp(),
+            signature: url.rsplit("signature=").next().unwrap().to_string(),
+        };
+
+        assert!(url.starts_with("/downloads/backups/a.gz?expires="));
+        assert!(url.contains(&format!("expires={}&", query.expires)));
+        assert!(query.verify("secret", "backup:a.gz", now));
+        assert!(!query.verify("secret", "backup:b.gz", now));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::domain::StellarAddress;
 use bigdecimal::BigDecimal;
 use serde::Deserialize;
 use std::fmt;
@@ -12,6 +13,10 @@ pub const CALLBACK_TYPE_MAX_LEN: usize = 20;
 pub const CALLBACK_STATUS_MAX_LEN: usize = 20;
 pub const AMOUNT_INPUT_MAX_LEN: usize = 64;
 pub const ALLOWED_ASSET_CODES: &[&str] = &["USD"];
+/// Serialized size cap for partner-supplied transaction metadata.
+pub const METADATA_MAX_BYTES: usize = 4096;
+/// Maximum nesting depth of transaction metadata (the top-level object is 1).
+pub const METADATA_MAX_DEPTH: usize = 4;
 
 #[derive(Debug, Deserialize)]
 #[serde(deny_unknown_fields)]
@@ -96,34 +101,15 @@ pub fn validate_enum(field: &'static str, value: &str, allowed: &[&str]) -> Vali
 }
 
 pub fn validate_stellar_address(stellar_address: &str) -> ValidationResult {
+    parse_stellar_address(stellar_address).map(|_| ())
+}
+
+/// Parse a `G...` or muxed `M...` account address, checksum included.
+pub fn parse_stellar_address(stellar_address: &str) -> Result<StellarAddress, ValidationError> {
     let stellar_address = sanitize_string(stellar_address);
     validate_required("stellar_address", &stellar_address)?;
-
-    if stellar_address.len() != STELLAR_ACCOUNT_LEN {
-        return Err(ValidationError::new(
-            "stellar_address",
-            format!("must be exactly {STELLAR_ACCOUNT_LEN} characters"),
-        ));
-    }
-
-    if !stellar_address.starts_with('G') {
-        return Err(ValidationError::new(
-            "stellar_address",
-            "must start with 'G'",
-        ));
-    }
-
-    if !stellar_address
-        .chars()
-        .all(|ch| ch.is_ascii_uppercase() || ch.is_ascii_digit())
-    {
-        return Err(ValidationError::new(
-            "stellar_address",
-            "must contain only uppercase letters and digits",
-        ));
-    }
-
-    Ok(())
+    StellarAddress::parse(&stellar_address)
+        .map_err(|e| ValidationError::new("stellar_address", e.to_string()))
 }
 
 pub fn validate_stellar_account(account: &str) -> ValidationResult {
@@ -180,6 +166,38 @@ pub fn validate_min_len(field: &'static str, value: &str, min_len: usize) -> Val
     Ok(())
 }
 
+/// Partner metadata must be a JSON object within [`METADATA_MAX_BYTES`] and
+/// [`METADATA_MAX_DEPTH`], so it stays cheap to store and to index.
+pub fn validate_metadata(metadata: &serde_json::Value) -> ValidationResult {
+    if !metadata.is_object() {
+        return Err(ValidationError::new("metadata", "must be a JSON object"));
+    }
+
+    let size = serde_json::to_vec(metadata).map(|v| v.len()).unwrap_or(0);
+    if size > METADATA_MAX_BYTES {
+        return Err(ValidationError::new(
+            "metadata",
+            format!("must be at most {METADATA_MAX_BYTES} bytes when serialized"),
+        ));
+    }
+
+    fn depth(value: &serde_json::Value) -> usize {
+        match value {
+            serde_json::Value::Object(map) => 1 + map.values().map(depth).max().unwrap_or(0),
+            serde_json::Value::Array(items) => 1 + items.iter().map(depth).max().unwrap_or(0),
+            _ => 0,
+        }
+    }
+    if depth(metadata) > METADATA_MAX_DEPTH {
+        return Err(ValidationError::new(
+            "metadata",
+            format!("must be nested at most {METADATA_MAX_DEPTH} levels deep"),
+        ));
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -187,7 +205,7 @@ mod tests {
     use std::str::FromStr;
 
     fn valid_stellar_address() -> String {
-        "G".to_owned() + &"A".repeat(55)
+        "GAAZI4TCR3TY5OJHCTJC2A4QSY6CJWJH5IAJTGKIN2ER7LBNVKOCCWN7".to_string()
     }
 
     #[test]
@@ -223,6 +241,19 @@ mod tests {
         assert!(validate_stellar_address(&("g".to_owned() + &"A".repeat(55))).is_err());
         assert!(validate_stellar_address(&("G".to_owned() + &"a".repeat(55))).is_err());
         assert!(validate_stellar_address(&format!(" {} ", valid_stellar_address())).is_ok());
+        // Well-formed but with a broken checksum.
+        assert!(validate_stellar_address(&("G".to_owned() + &"A".repeat(55))).is_err());
+    }
+
+    #[test]
+    fn parses_muxed_stellar_address() {
+        let muxed = "MA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJUAAAAAAAAAAE2JUG6";
+        let address = parse_stellar_address(muxed).unwrap();
+        assert_eq!(
+            address.account(),
+            "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ"
+        );
+        assert_eq!(address.muxed_id(), Some(1234));
     }
 
     #[test]
@@ -264,6 +295,18 @@ mod tests {
         assert!(validate_min_len("status", "", 1).is_err());
     }
 
+    #[test]
+    fn validates_metadata() {
+        use serde_json::json;
+
+        assert!(validate_metadata(&json!({"order_id": "42", "tags": ["vip"]})).is_ok());
+        assert!(validate_metadata(&json!(["not", "an", "object"])).is_err());
+        assert!(validate_metadata(&json!("order-42")).is_err());
+        assert!(validate_metadata(&json!({"a": {"b": {"c": {"d": 1}}}})).is_ok());
+        assert!(validate_metadata(&json!({"a": {"b": {"c": {"d": {"e": 1}}}}})).is_err());
+        assert!(validate_metadata(&json!({"blob": "x".repeat(METADATA_MAX_BYTES)})).is_err());
+    }
+
     #[test]
     fn strict_payload_accepts_known_fields() {
         #[derive(Debug, Deserialize, PartialEq, Eq)]
@@ -305,12 +348,12 @@ mod property_tests {
     // --- validate_stellar_address ---
 
     proptest! {
-        /// Valid Stellar addresses (G + 55 uppercase alphanumeric chars) must always be accepted.
+        /// Valid Stellar addresses (any ed25519 key, strkey-encoded) must always be accepted.
         #[test]
         fn prop_valid_stellar_address_accepted(
-            suffix in "[A-Z0-9]{55}"
+            key in any::<[u8; 32]>()
         ) {
-            let addr = format!("G{}", suffix);
+            let addr = StellarAddress::from_ed25519(key).to_string();
             prop_assert!(validate_stellar_address(&addr).is_ok(), "Expected valid address to be accepted: {}", addr);
         }
 
@@ -32,7 +32,7 @@ fn callback_schema_v1() -> serde_json::Value {
         "properties": {
             "stellar_account": {
                 "type": "string",
-                "pattern": "^G[A-Z2-7]{55}$",
+                "pattern": "^(G[A-Z2-7]{55}|M[A-Z2-7]{68})$",
                 "description": "Stellar account address"
             },
             "amount": {
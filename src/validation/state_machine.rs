@@ -6,9 +6,12 @@ use crate::error::AppError;
 /// - pending → processing
 /// - pending → completed (direct completion)
 /// - pending → failed
+/// - pending → compliance_review (high risk score)
 /// - processing → completed
 /// - processing → failed
 /// - failed → pending (reprocess)
+/// - compliance_review → pending (approved) / failed (rejected)
+/// - refund_pending → failed (refund issued)
 ///
 /// Invalid transitions (examples):
 /// - completed → pending
@@ -25,6 +28,7 @@ pub fn validate_status_transition(from: &str, to: &str) -> Result<(), AppError>
         ("pending", "processing") => true,
         ("pending", "completed") => true,
         ("pending", "failed") => true,
+        ("pending", "compliance_review") => true,
 
         // From processing
         ("processing", "completed") => true,
@@ -36,6 +40,13 @@ pub fn validate_status_transition(from: &str, to: &str) -> Result<(), AppError>
         // From dlq (requeue)
         ("dlq", "pending") => true,
 
+        // From compliance_review (held deposit approved or rejected)
+        ("compliance_review", "pending") => true,
+        ("compliance_review", "failed") => true,
+
+        // From refund_pending (sub-minimum deposit refunded)
+        ("refund_pending", "failed") => true,
+
         // All other transitions are invalid
         _ => false,
     };
@@ -59,6 +70,7 @@ mod tests {
         assert!(validate_status_transition("pending", "processing").is_ok());
         assert!(validate_status_transition("pending", "completed").is_ok());
         assert!(validate_status_transition("pending", "failed").is_ok());
+        assert!(validate_status_transition("pending", "compliance_review").is_ok());
 
         // From processing
         assert!(validate_status_transition("processing", "completed").is_ok());
@@ -67,6 +79,11 @@ mod tests {
         // From failed (reprocess)
         assert!(validate_status_transition("failed", "pending").is_ok());
 
+        // Amount-limit holds
+        assert!(validate_status_transition("compliance_review", "pending").is_ok());
+        assert!(validate_status_transition("compliance_review", "failed").is_ok());
+        assert!(validate_status_transition("refund_pending", "failed").is_ok());
+
         // Same-state (idempotent)
         assert!(validate_status_transition("pending", "pending").is_ok());
         assert!(validate_status_transition("processing", "processing").is_ok());
@@ -90,6 +107,10 @@ mod tests {
         // Cannot go from failed to processing
         assert!(validate_status_transition("failed", "processing").is_err());
         assert!(validate_status_transition("failed", "completed").is_err());
+
+        // Held deposits must not slip into processing
+        assert!(validate_status_transition("refund_pending", "pending").is_err());
+        assert!(validate_status_transition("compliance_review", "processing").is_err());
     }
 
     #[test]
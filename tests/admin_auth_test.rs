@@ -0,0 +1,162 @@
+//! Every operator route behind `admin_auth`, against the real router: `401`
+//! without a key or with a wrong one, and through to the handler with an
+//! `ADMIN_OPERATOR_KEYS` key.
+
+mod common;
+
+use reqwest::{Method, StatusCode};
+
+const OPERATOR_KEY: &str = "operator-key-alice";
+const ID: &str = "550e8400-e29b-41d4-a716-446655440000";
+
+/// Every admin route, with placeholders filled in. `/admin/drain` is last
+/// because it takes the instance out of rotation.
+fn admin_routes() -> Vec<(Method, String)> {
+    let routes: &[(Method, &str)] = &[
+        (Method::PATCH, "/admin/transactions/bulk-status"),
+        (Method::GET, "/admin/webhooks/health"),
+        (Method::GET, "/admin/webhooks/health/{id}"),
+        (Method::GET, "/admin/webhook-subscriptions"),
+        (Method::POST, "/admin/webhook-subscriptions"),
+        (Method::GET, "/admin/webhook-subscriptions/{id}"),
+        (Method::DELETE, "/admin/webhook-subscriptions/{id}"),
+        (
+            Method::POST,
+            "/admin/webhook-subscriptions/{id}/rotate-secret",
+        ),
+        (Method::POST, "/admin/webhook-subscriptions/{id}/test"),
+        (Method::POST, "/admin/webhook-subscriptions/{id}/enable"),
+        (Method::GET, "/admin/quotas"),
+        (Method::GET, "/admin/quotas/{id}"),
+        (Method::PUT, "/admin/quotas/{id}"),
+        (Method::DELETE, "/admin/quotas/{id}/reset"),
+        (Method::GET, "/admin/partners/{id}/settings"),
+        (Method::PATCH, "/admin/partners/{id}/settings"),
+        (Method::GET, "/admin/stats/fees"),
+        (Method::PUT, "/admin/customers/{id}/status"),
+        (Method::PUT, "/admin/assets/{id}/limits"),
+        (Method::GET, "/admin/refunds"),
+        (Method::GET, "/admin/refunds/{id}"),
+        (Method::POST, "/admin/refunds/{id}/override"),
+        (Method::GET, "/admin/approvals"),
+        (Method::GET, "/admin/approvals/{id}"),
+        (Method::POST, "/admin/approvals/{id}/approve"),
+        (Method::POST, "/admin/approvals/{id}/reject"),
+        (Method::GET, "/admin/quarantine"),
+        (Method::GET, "/admin/structuring/rules"),
+        (Method::POST, "/admin/structuring/rules"),
+        (Method::GET, "/admin/structuring/rules/{id}"),
+        (Method::PUT, "/admin/structuring/rules/{id}"),
+        (Method::DELETE, "/admin/structuring/rules/{id}"),
+        (Method::GET, "/admin/structuring/reviews"),
+        (Method::POST, "/admin/structuring/reviews/{id}/resolve"),
+        (Method::GET, "/admin/reviews"),
+        (Method::GET, "/admin/reviews/{id}"),
+        (Method::POST, "/admin/reviews/{id}/claim"),
+        (Method::POST, "/admin/reviews/{id}/resolve"),
+        (Method::POST, "/admin/reviews/{id}/comments"),
+        (Method::GET, "/admin/jobs"),
+        (Method::POST, "/admin/jobs"),
+        (Method::GET, "/admin/jobs/{id}"),
+        (Method::POST, "/admin/jobs/{id}/cancel"),
+        (Method::POST, "/admin/jobs/unknown-job/run"),
+        (Method::GET, "/admin/info"),
+        (Method::GET, "/admin/breakers"),
+        (Method::POST, "/admin/breakers/horizon/open"),
+        (Method::POST, "/admin/breakers/horizon/close"),
+        (Method::DELETE, "/admin/breakers/horizon/override"),
+        (Method::GET, "/admin/region"),
+        (Method::PUT, "/admin/region/role"),
+        (Method::DELETE, "/admin/region/role"),
+        (Method::GET, "/admin/signing-keys"),
+        (Method::POST, "/admin/signing-keys/rotate"),
+        (Method::POST, "/admin/partners/{id}/signing-keys/rotate"),
+        (Method::GET, "/admin/locks"),
+        (Method::GET, "/admin/idempotency/unknown-key"),
+        (Method::DELETE, "/admin/idempotency/unknown-key"),
+        (Method::PATCH, "/admin/settlements/{id}/status"),
+        (Method::POST, "/admin/settlements/{id}/void"),
+        (Method::POST, "/admin/settlements/{id}/path-payment"),
+        (Method::GET, "/admin/accounting/periods"),
+        (Method::POST, "/admin/accounting/periods/not-a-period/close"),
+        (
+            Method::GET,
+            "/admin/accounting/periods/not-a-period/adjustments",
+        ),
+        (
+            Method::POST,
+            "/admin/accounting/periods/not-a-period/adjustments",
+        ),
+        (Method::GET, "/admin/statements"),
+        (Method::POST, "/admin/statements"),
+        (Method::GET, "/admin/reconciliation/reports"),
+        (Method::GET, "/admin/reconciliation/reports/{id}"),
+        (Method::POST, "/admin/reconciliation/run"),
+        (Method::GET, "/dlq"),
+        (Method::POST, "/dlq/{id}/requeue"),
+        (Method::GET, "/admin/dlq/stats"),
+        #[cfg(feature = "backup")]
+        (Method::GET, "/admin/backups"),
+        (Method::GET, "/admin/drain"),
+    ];
+    routes
+        .iter()
+        .map(|(method, path)| (method.clone(), path.replace("{id}", ID)))
+        .collect()
+}
+
+/// Read-only listings that only need Postgres, so an authenticated call
+/// succeeds outright.
+const LISTINGS: &[&str] = &[
+    "/admin/webhook-subscriptions",
+    "/admin/refunds",
+    "/admin/approvals",
+    "/admin/quarantine",
+    "/admin/structuring/rules",
+    "/admin/structuring/reviews",
+    "/admin/reviews",
+    "/admin/jobs",
+    "/admin/info",
+    "/admin/accounting/periods",
+    "/admin/statements",
+    "/admin/reconciliation/reports",
+    "/dlq",
+];
+
+async fn call(app: &common::TestApp, method: &Method, path: &str, key: Option<&str>) -> StatusCode {
+    let mut request =
+        reqwest::Client::new().request(method.clone(), format!("{}{}", app.base_url, path));
+    if let Some(key) = key {
+        request = request.header("Authorization", format!("Bearer {key}"));
+    }
+    request.send().await.unwrap().status()
+}
+
+#[ignore = "Requires Docker for testcontainers"]
+#[tokio::test]
+async fn test_every_admin_route_requires_an_admin_key() {
+    std::env::set_var("ADMIN_OPERATOR_KEYS", format!("alice:{OPERATOR_KEY}"));
+    let app = common::TestApp::new().await;
+
+    for (method, path) in admin_routes() {
+        assert_eq!(
+            call(&app, &method, &path, None).await,
+            StatusCode::UNAUTHORIZED,
+            "{method} {path} without a key"
+        );
+        assert_eq!(
+            call(&app, &method, &path, Some("not-an-admin-key")).await,
+            StatusCode::UNAUTHORIZED,
+            "{method} {path} with a wrong key"
+        );
+
+        let status = call(&app, &method, &path, Some(OPERATOR_KEY)).await;
+        assert!(
+            status != StatusCode::UNAUTHORIZED && status != StatusCode::FORBIDDEN,
+            "{method} {path} with an operator key: {status}"
+        );
+        if method == Method::GET && LISTINGS.contains(&path.as_str()) {
+            assert!(status.is_success(), "{method} {path}: {status}");
+        }
+    }
+}
@@ -1,6 +1,9 @@
 use reqwest::StatusCode;
 use sqlx::{migrate::Migrator, PgPool};
 use std::path::Path;
+use std::sync::Arc;
+use synapse_core::adapters::BroadcastEventBus;
+use synapse_core::services::event_channels::EventClass;
 use synapse_core::{create_app, AppState};
 use testcontainers::{runners::AsyncRunner, ImageExt};
 use testcontainers_modules::postgres::Postgres;
@@ -29,7 +32,6 @@ async fn test_api_versioning_headers() {
     .unwrap();
     migrator.run(&pool).await.unwrap();
 
-    let (tx, _rx) = tokio::sync::broadcast::channel(100);
     let _query_cache = synapse_core::services::QueryCache::new("redis://localhost:6379")
         .await
         .unwrap();
@@ -47,7 +49,8 @@ async fn test_api_versioning_headers() {
         redis_url: "redis://localhost:6379".to_string(),
         start_time: std::time::Instant::now(),
         readiness: synapse_core::ReadinessState::new(),
-        tx_broadcast: tx,
+        domain_events: Arc::new(BroadcastEventBus::new(EventClass::Transactions)),
+        settlement_events: Arc::new(BroadcastEventBus::new(EventClass::Settlements)),
         query_cache: synapse_core::services::QueryCache::new("redis://localhost:6379")
             .await
             .unwrap(),
@@ -60,6 +63,9 @@ async fn test_api_versioning_headers() {
         secrets_store: None,
         metrics_handle: synapse_core::metrics::init_metrics().unwrap(),
         ws_connection_count: std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+        job_scheduler: std::sync::Arc::new(synapse_core::services::JobScheduler::new()),
+        signing_keys: synapse_core::services::signing_keys::SigningKeyStore::new(String::new()),
+        clock: std::sync::Arc::new(synapse_core::adapters::SystemClock),
     };
     let app = create_app(app_state);
 
@@ -35,6 +35,7 @@ fn test_cli_db_migrate_help() {
     cmd.assert().success();
 }
 
+#[cfg(feature = "backup")]
 #[ignore = "Requires Docker/external services"]
 #[test]
 fn test_cli_backup_list_help() {
@@ -62,3 +63,18 @@ fn test_cli_tx_force_complete_help() {
     cmd.arg("tx").arg("force-complete").arg("--help");
     cmd.assert().success();
 }
+
+#[ignore = "Requires Docker/external services"]
+#[test]
+fn test_cli_tx_backfill_invalid_date() {
+    let mut cmd = synapse_cmd();
+    cmd.arg("tx")
+        .arg("backfill")
+        .arg("GBBD47UZQ5CSKQPV456PYYH4FSYJHBWGQJUVNMCNWZ2NBEHKQPW3LEFO")
+        .arg("--from")
+        .arg("2026-13-01")
+        .arg("--to")
+        .arg("2026-03-31");
+
+    cmd.assert().failure();
+}
@@ -15,6 +15,9 @@
 
 use sqlx::{migrate::Migrator, PgPool};
 use std::path::Path;
+use std::sync::Arc;
+use synapse_core::adapters::BroadcastEventBus;
+use synapse_core::services::event_channels::EventClass;
 use synapse_core::{create_app, AppState};
 use testcontainers::{runners::AsyncRunner, ImageExt};
 use testcontainers_modules::postgres::Postgres;
@@ -23,6 +26,9 @@ use testcontainers_modules::postgres::Postgres;
 pub struct TestApp {
     pub base_url: String,
     pub pool: PgPool,
+    /// The state the server runs with, e.g. to publish on its event buses.
+    #[allow(dead_code)]
+    pub app_state: AppState,
     _postgres_container: Box<dyn std::any::Any>,
 }
 
@@ -55,7 +61,6 @@ impl TestApp {
         Self::create_current_partition(&pool).await;
 
         // Build AppState
-        let (tx_broadcast, _) = tokio::sync::broadcast::channel(100);
         let app_state = AppState {
             db: pool.clone(),
             pool_manager: synapse_core::db::pool_manager::PoolManager::new(&database_url, None, 5)
@@ -70,7 +75,8 @@ impl TestApp {
             redis_url: "redis://localhost:6379".to_string(),
             start_time: std::time::Instant::now(),
             readiness: synapse_core::ReadinessState::new(),
-            tx_broadcast,
+            domain_events: Arc::new(BroadcastEventBus::new(EventClass::Transactions)),
+            settlement_events: Arc::new(BroadcastEventBus::new(EventClass::Settlements)),
             query_cache: synapse_core::services::QueryCache::new("redis://localhost:6379")
                 .await
                 .unwrap(),
@@ -83,9 +89,12 @@ impl TestApp {
             secrets_store: None,
             metrics_handle: synapse_core::metrics::init_metrics().unwrap(),
             ws_connection_count: std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+            job_scheduler: std::sync::Arc::new(synapse_core::services::JobScheduler::new()),
+            signing_keys: synapse_core::services::signing_keys::SigningKeyStore::new(String::new()),
+            clock: std::sync::Arc::new(synapse_core::adapters::SystemClock),
         };
 
-        let app = create_app(app_state);
+        let app = create_app(app_state.clone());
 
         // Spawn HTTP server on random port
         let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 0));
@@ -101,6 +110,7 @@ impl TestApp {
         Self {
             base_url,
             pool,
+            app_state,
             _postgres_container: Box::new(container),
         }
     }
@@ -60,7 +60,8 @@ async fn test_dlq_workflow() {
     let amount = BigDecimal::from_str("100.50").unwrap();
 
     sqlx::query(
-        "INSERT INTO transactions (id, stellar_account, amount, asset_code, status) VALUES ($1, $2, $3, $4, $5)",
+        "INSERT INTO transactions (id, stellar_account, amount, asset_code, status, anchor_transaction_id) \
+         VALUES ($1, $2, $3, $4, $5, $1::text)",
     )
     .bind(tx_id)
     .bind("GABCD1234TEST")
@@ -93,7 +94,8 @@ async fn test_requeue_dlq() {
     let amount = BigDecimal::from_str("100.50").unwrap();
 
     sqlx::query(
-        "INSERT INTO transactions (id, stellar_account, amount, asset_code, status) VALUES ($1, $2, $3, $4, $5)",
+        "INSERT INTO transactions (id, stellar_account, amount, asset_code, status, anchor_transaction_id) \
+         VALUES ($1, $2, $3, $4, $5, $1::text)",
     )
     .bind(tx_id)
     .bind("GABCD1234TEST")
@@ -1,3 +1,5 @@
+#![cfg(feature = "backup")]
+
 use anyhow::Result;
 use std::fs;
 use std::os::unix::fs::PermissionsExt;
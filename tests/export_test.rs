@@ -2,6 +2,9 @@ use bigdecimal::BigDecimal;
 use reqwest::StatusCode;
 use sqlx::{migrate::Migrator, PgPool};
 use std::path::Path;
+use std::sync::Arc;
+use synapse_core::adapters::BroadcastEventBus;
+use synapse_core::services::event_channels::EventClass;
 use synapse_core::{create_app, AppState};
 use testcontainers::runners::AsyncRunner;
 use testcontainers_modules::postgres::Postgres;
@@ -50,7 +53,6 @@ async fn setup_test_app() -> (String, PgPool, impl std::any::Any) {
     .execute(&pool)
     .await;
 
-    let (tx, _rx) = tokio::sync::broadcast::channel(100);
     let _query_cache = synapse_core::services::QueryCache::new("redis://localhost:6379")
         .await
         .unwrap();
@@ -67,7 +69,8 @@ async fn setup_test_app() -> (String, PgPool, impl std::any::Any) {
         redis_url: "redis://localhost:6379".to_string(),
         start_time: std::time::Instant::now(),
         readiness: synapse_core::ReadinessState::new(),
-        tx_broadcast: tx,
+        domain_events: Arc::new(BroadcastEventBus::new(EventClass::Transactions)),
+        settlement_events: Arc::new(BroadcastEventBus::new(EventClass::Settlements)),
         query_cache: synapse_core::services::QueryCache::new("redis://localhost:6379")
             .await
             .unwrap(),
@@ -80,6 +83,9 @@ async fn setup_test_app() -> (String, PgPool, impl std::any::Any) {
         secrets_store: None,
         metrics_handle: synapse_core::metrics::init_metrics().unwrap(),
         ws_connection_count: std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+        job_scheduler: std::sync::Arc::new(synapse_core::services::JobScheduler::new()),
+        signing_keys: synapse_core::services::signing_keys::SigningKeyStore::new(String::new()),
+        clock: std::sync::Arc::new(synapse_core::adapters::SystemClock),
     };
     let app = create_app(app_state);
 
@@ -107,8 +113,9 @@ async fn insert_test_transaction(
 
     sqlx::query(
         r#"
-        INSERT INTO transactions (id, stellar_account, amount, asset_code, status, created_at, updated_at)
-        VALUES ($1, $2, $3, $4, $5, NOW(), NOW())
+        INSERT INTO transactions (id, stellar_account, amount, asset_code, status, created_at, updated_at,
+            anchor_transaction_id)
+        VALUES ($1, $2, $3, $4, $5, NOW(), NOW(), $1::text)
         "#,
     )
     .bind(id)
@@ -194,8 +201,9 @@ async fn test_export_date_range() {
 
     sqlx::query(
         r#"
-        INSERT INTO transactions (id, stellar_account, amount, asset_code, status, created_at, updated_at)
-        VALUES ($1, $2, $3, $4, $5, $6, $6)
+        INSERT INTO transactions (id, stellar_account, amount, asset_code, status, created_at, updated_at,
+            anchor_transaction_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $6, $1::text)
         "#,
     )
     .bind(id1)
@@ -210,8 +218,9 @@ async fn test_export_date_range() {
 
     sqlx::query(
         r#"
-        INSERT INTO transactions (id, stellar_account, amount, asset_code, status, created_at, updated_at)
-        VALUES ($1, $2, $3, $4, $5, $6, $6)
+        INSERT INTO transactions (id, stellar_account, amount, asset_code, status, created_at, updated_at,
+            anchor_transaction_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $6, $1::text)
         "#,
     )
     .bind(id2)
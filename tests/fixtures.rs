@@ -16,7 +16,7 @@
 use bigdecimal::BigDecimal;
 use chrono::Utc;
 use std::str::FromStr;
-use synapse_core::db::models::Transaction;
+use synapse_core::db::models::{Transaction, TransactionStatus};
 use uuid::Uuid;
 
 /// Builder for `Transaction` test fixtures with sensible defaults.
@@ -25,7 +25,7 @@ pub struct TransactionFixture {
     stellar_account: String,
     amount: BigDecimal,
     asset_code: String,
-    status: String,
+    status: TransactionStatus,
     anchor_transaction_id: Option<String>,
     callback_type: Option<String>,
     callback_status: Option<String>,
@@ -37,13 +37,15 @@ pub struct TransactionFixture {
 
 impl Default for TransactionFixture {
     fn default() -> Self {
+        let id = Uuid::new_v4();
         Self {
-            id: Uuid::new_v4(),
+            id,
             stellar_account: "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF".to_string(),
             amount: BigDecimal::from_str("100.00").unwrap(),
             asset_code: "USD".to_string(),
-            status: "pending".to_string(),
-            anchor_transaction_id: None,
+            status: TransactionStatus::Pending,
+            // The database requires one; unique so fixtures never collide.
+            anchor_transaction_id: Some(format!("anchor-{id}")),
             callback_type: None,
             callback_status: None,
             settlement_id: None,
@@ -80,8 +82,9 @@ impl TransactionFixture {
         self
     }
 
+    /// Panics if `status` is not a known transaction status.
     pub fn with_status(mut self, status: &str) -> Self {
-        self.status = status.to_string();
+        self.status = status.parse().expect("unknown transaction status");
         self
     }
 
@@ -135,6 +138,14 @@ impl TransactionFixture {
             memo_type: self.memo_type,
             metadata: self.metadata,
             trace_id: None,
+            backfilled: false,
+            stellar_tx_hash: None,
+            ledger: None,
+            closed_at: None,
+            stellar_muxed_id: None,
+            risk_score: None,
+            risk_reasons: None,
+            fee_amount: None,
         }
     }
 
@@ -162,7 +173,7 @@ impl TransactionFixture {
     /// A failed transaction with error metadata.
     pub fn failed_transaction() -> Transaction {
         Self::new()
-            .with_status("error")
+            .with_status("failed")
             .with_callback_status("error")
             .with_metadata(serde_json::json!({ "error_code": "INSUFFICIENT_FUNDS" }))
             .build()
@@ -211,7 +222,7 @@ mod tests {
     #[test]
     fn test_failed_transaction_scenario() {
         let tx = TransactionFixture::failed_transaction();
-        assert_eq!(tx.status, "error");
+        assert_eq!(tx.status, "failed");
         assert!(tx.metadata.is_some());
     }
 
@@ -0,0 +1,57 @@
+//! GraphQL latency metrics, end to end. In its own test binary because it
+//! installs the global meter provider.
+#![cfg(feature = "graphql")]
+
+mod common;
+
+use opentelemetry::global;
+use opentelemetry_sdk::metrics::{PeriodicReader, SdkMeterProvider};
+use opentelemetry_sdk::runtime;
+use opentelemetry_sdk::testing::metrics::InMemoryMetricsExporter;
+use serde_json::json;
+
+/// Names of every metric exported so far.
+fn exported_metric_names(exporter: &InMemoryMetricsExporter) -> Vec<String> {
+    exporter
+        .get_finished_metrics()
+        .unwrap()
+        .iter()
+        .flat_map(|rm| &rm.scope_metrics)
+        .flat_map(|sm| &sm.metrics)
+        .map(|m| m.name.to_string())
+        .collect()
+}
+
+#[ignore = "Requires Docker for testcontainers"]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_graphql_request_records_durations() {
+    let exporter = InMemoryMetricsExporter::default();
+    let provider = SdkMeterProvider::builder()
+        .with_reader(PeriodicReader::builder(exporter.clone(), runtime::Tokio).build())
+        .build();
+    global::set_meter_provider(provider.clone());
+    // Bind the crate's meter to this provider before the app installs its own.
+    synapse_core::metrics::graphql_request_duration_ms();
+
+    let app = common::TestApp::new().await;
+    let res = reqwest::Client::new()
+        .post(format!("{}/graphql", app.base_url))
+        .json(&json!({ "query": "{ transactions { id status } }" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert!(body["errors"].is_null(), "{body}");
+
+    provider.force_flush().unwrap();
+    let names = exported_metric_names(&exporter);
+    assert!(
+        names.iter().any(|n| n == "graphql_request_duration_ms"),
+        "{names:?}"
+    );
+    assert!(
+        names.iter().any(|n| n == "graphql_resolver_duration_ms"),
+        "{names:?}"
+    );
+}
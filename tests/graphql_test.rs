@@ -1,11 +1,82 @@
+#![cfg(feature = "graphql")]
+
+mod common;
+
+use chrono::Utc;
+use futures::{SinkExt, StreamExt};
 use reqwest::StatusCode;
 use serde_json::json;
 use sqlx::{migrate::Migrator, PgPool};
 use std::path::Path;
+use std::sync::Arc;
+use synapse_core::adapters::BroadcastEventBus;
+use synapse_core::db::models::Settlement;
 use synapse_core::db::pool_manager::PoolManager;
+use synapse_core::services::event_channels::EventClass;
 use synapse_core::services::feature_flags::FeatureFlagService;
+use synapse_core::services::settlement_events::{SettlementEvent, SettlementEventKind};
 use synapse_core::{create_app, AppState};
 use tokio::net::TcpListener;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::Message;
+use uuid::Uuid;
+
+/// POST `query` to `/graphql` with extra `headers`; the status and JSON body.
+async fn graphql(
+    app: &common::TestApp,
+    headers: &[(&str, &str)],
+    query: &str,
+) -> (StatusCode, serde_json::Value) {
+    graphql_with_variables(app, headers, query, json!({})).await
+}
+
+async fn graphql_with_variables(
+    app: &common::TestApp,
+    headers: &[(&str, &str)],
+    query: &str,
+    variables: serde_json::Value,
+) -> (StatusCode, serde_json::Value) {
+    let mut request = reqwest::Client::new()
+        .post(format!("{}/graphql", app.base_url))
+        .json(&json!({ "query": query, "variables": variables }));
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+    let res = request.send().await.unwrap();
+    let status = res.status();
+    (status, res.json().await.unwrap())
+}
+
+/// A pending transaction created through `/callback`.
+async fn create_transaction(app: &common::TestApp) -> Uuid {
+    let res = reqwest::Client::new()
+        .post(format!("{}/callback", app.base_url))
+        .json(&json!({
+            "stellar_account": "GAAZI4TCR3TY5OJHCTJC2A4QSY6CJWJH5IAJTGKIN2ER7LBNVKOCCWN7",
+            "amount": "100.50",
+            "asset_code": "USD",
+            "anchor_transaction_id": format!("graphql-{}", Uuid::new_v4()),
+            "callback_type": "deposit",
+            "callback_status": "pending_external",
+            "metadata": { "email": "payer@example.com" }
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let tx: serde_json::Value = res.json().await.unwrap();
+    tx["id"].as_str().unwrap().parse().unwrap()
+}
+
+async fn transaction_status(app: &common::TestApp, id: Uuid) -> String {
+    sqlx::query_scalar("SELECT status FROM transactions WHERE id = $1")
+        .bind(id)
+        .fetch_one(&app.pool)
+        .await
+        .unwrap()
+}
 
 #[ignore = "Requires Docker/external services"]
 #[tokio::test]
@@ -56,7 +127,6 @@ async fn test_graphql_queries() {
 
     let pool_manager = PoolManager::new(&database_url, None, 5).await.unwrap();
     let feature_flags = FeatureFlagService::new(pool.clone());
-    let (tx_broadcast, _) = tokio::sync::broadcast::channel(100);
     let readiness = synapse_core::ReadinessState::new();
     let _query_cache = synapse_core::services::QueryCache::new("redis://localhost:6379")
         .await
@@ -71,7 +141,8 @@ async fn test_graphql_queries() {
         feature_flags,
         redis_url: "redis://localhost:6379".to_string(),
         start_time: std::time::Instant::now(),
-        tx_broadcast,
+        domain_events: Arc::new(BroadcastEventBus::new(EventClass::Transactions)),
+        settlement_events: Arc::new(BroadcastEventBus::new(EventClass::Settlements)),
         readiness,
         query_cache: synapse_core::services::QueryCache::new("redis://localhost:6379")
             .await
@@ -85,6 +156,9 @@ async fn test_graphql_queries() {
         secrets_store: None,
         metrics_handle: synapse_core::metrics::init_metrics().unwrap(),
         ws_connection_count: std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+        job_scheduler: std::sync::Arc::new(synapse_core::services::JobScheduler::new()),
+        signing_keys: synapse_core::services::signing_keys::SigningKeyStore::new(String::new()),
+        clock: std::sync::Arc::new(synapse_core::adapters::SystemClock),
     };
     let app = create_app(app_state);
 
@@ -110,9 +184,10 @@ async fn test_graphql_queries() {
 
     let callback_url = format!("http://{}/callback", addr);
     let payload = json!({
-        "stellar_account": "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA",
+        "stellar_account": "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF",
         "amount": "100.50",
         "asset_code": "USD",
+        "anchor_transaction_id": "graphql-1",
         "callback_type": "deposit",
         "callback_status": "completed"
     });
@@ -141,3 +216,333 @@ async fn test_graphql_queries() {
 
     assert_eq!(body["data"]["transaction"]["assetCode"], "USD");
 }
+
+#[ignore = "Requires Docker for testcontainers"]
+#[tokio::test]
+async fn test_unauthenticated_caller_cannot_complete_transaction() {
+    let app = common::TestApp::new().await;
+    let tx_id = create_transaction(&app).await;
+    let before = transaction_status(&app, tx_id).await;
+    assert_ne!(before, "completed");
+
+    let (_, body) = graphql(
+        &app,
+        &[],
+        &format!(r#"mutation {{ forceCompleteTransaction(id: "{tx_id}") {{ id status }} }}"#),
+    )
+    .await;
+
+    assert!(body["errors"][0]["message"].is_string(), "{body}");
+    assert_eq!(transaction_status(&app, tx_id).await, before);
+}
+
+/// The next text frame on a GraphQL WebSocket, as JSON.
+async fn next_message(
+    stream: &mut tokio_tungstenite::WebSocketStream<
+        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+    >,
+) -> serde_json::Value {
+    loop {
+        let msg = tokio::time::timeout(std::time::Duration::from_secs(5), stream.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        if let Message::Text(text) = msg {
+            return serde_json::from_str(&text).unwrap();
+        }
+    }
+}
+
+#[ignore = "Requires Docker for testcontainers"]
+#[tokio::test]
+async fn test_settlement_subscription_over_websocket() {
+    let app = common::TestApp::new().await;
+
+    let mut request = format!("{}/graphql/ws", app.base_url.replacen("http", "ws", 1))
+        .into_client_request()
+        .unwrap();
+    request.headers_mut().insert(
+        "Sec-WebSocket-Protocol",
+        HeaderValue::from_static("graphql-transport-ws"),
+    );
+    let (mut ws, _) = connect_async(request).await.unwrap();
+
+    ws.send(Message::Text(
+        json!({ "type": "connection_init" }).to_string(),
+    ))
+    .await
+    .unwrap();
+    assert_eq!(next_message(&mut ws).await["type"], "connection_ack");
+
+    let subscribe = json!({
+        "id": "1",
+        "type": "subscribe",
+        "payload": {
+            "query": r#"subscription {
+                settlementUpdated(assetCode: "USD") { kind settlement { id status } }
+            }"#
+        }
+    });
+    ws.send(Message::Text(subscribe.to_string())).await.unwrap();
+
+    // The subscription is live once the schema has subscribed to the bus.
+    tokio::time::timeout(std::time::Duration::from_secs(5), async {
+        while app.app_state.settlement_events.subscriber_count() == 0 {
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .unwrap();
+
+    let now = Utc::now();
+    let settlement = Settlement {
+        id: Uuid::new_v4(),
+        asset_code: "USD".to_string(),
+        total_amount: "250.00".parse().unwrap(),
+        tx_count: 2,
+        period_start: now,
+        period_end: now,
+        status: "pending".to_string(),
+        created_at: now,
+        updated_at: now,
+        dispute_reason: None,
+        original_total_amount: None,
+        reviewed_by: None,
+        reviewed_at: None,
+        quoted_payouts: None,
+    };
+    let id = settlement.id.to_string();
+    app.app_state
+        .settlement_events
+        .publish(SettlementEvent::new(
+            SettlementEventKind::Created,
+            settlement,
+            vec![],
+        ));
+
+    let next = next_message(&mut ws).await;
+    assert_eq!(next["type"], "next", "{next}");
+    assert_eq!(next["id"], "1");
+    let event = &next["payload"]["data"]["settlementUpdated"];
+    assert_eq!(event["kind"], "CREATED");
+    assert_eq!(event["settlement"]["id"], id.as_str());
+    assert_eq!(event["settlement"]["status"], "pending");
+}
+
+/// An active tenant whose API key is `api_key`, with `scopes`.
+async fn insert_api_key(app: &common::TestApp, api_key: &str, scopes: &[&str]) {
+    sqlx::query(
+        "INSERT INTO tenants (tenant_id, name, api_key, webhook_secret, stellar_account, \
+         rate_limit_per_minute, is_active, scopes) VALUES ($1, $2, $3, '', '', 60, true, $4)",
+    )
+    .bind(Uuid::new_v4())
+    .bind(format!("tenant-{api_key}"))
+    .bind(api_key)
+    .bind(scopes.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+    .execute(&app.pool)
+    .await
+    .unwrap();
+}
+
+#[ignore = "Requires Docker for testcontainers"]
+#[tokio::test]
+async fn test_viewer_caller_gets_masked_pii() {
+    let app = common::TestApp::new().await;
+    let tx_id = create_transaction(&app).await;
+    insert_api_key(&app, "viewer-key", &[]).await;
+    let query = format!(r#"{{ transaction(id: "{tx_id}") {{ stellarAccount metadata }} }}"#);
+
+    for headers in [vec![], vec![("X-API-Key", "viewer-key")]] {
+        let (status, body) = graphql(&app, &headers, &query).await;
+        assert_eq!(status, StatusCode::OK);
+        let tx = &body["data"]["transaction"];
+        assert_eq!(tx["stellarAccount"], "GAAZ****CWN7", "{body}");
+        assert_eq!(tx["metadata"], json!({ "email": "****" }));
+    }
+}
+
+#[ignore = "Requires Docker for testcontainers"]
+#[tokio::test]
+async fn test_compliance_caller_sees_pii() {
+    let app = common::TestApp::new().await;
+    let tx_id = create_transaction(&app).await;
+    insert_api_key(&app, "compliance-key", &["read:pii"]).await;
+    let query = format!(r#"{{ transaction(id: "{tx_id}") {{ stellarAccount metadata }} }}"#);
+
+    for (name, value) in [
+        ("X-API-Key", "compliance-key"),
+        ("Authorization", "Bearer compliance-key"),
+    ] {
+        let (status, body) = graphql(&app, &[(name, value)], &query).await;
+        assert_eq!(status, StatusCode::OK);
+        let tx = &body["data"]["transaction"];
+        assert_eq!(
+            tx["stellarAccount"], "GAAZI4TCR3TY5OJHCTJC2A4QSY6CJWJH5IAJTGKIN2ER7LBNVKOCCWN7",
+            "{body}"
+        );
+        assert_eq!(tx["metadata"], json!({ "email": "payer@example.com" }));
+    }
+
+    let (_, body) = graphql(
+        &app,
+        &[("X-API-Key", "compliance-key")],
+        "{ access { role } }",
+    )
+    .await;
+    assert_eq!(body["data"]["access"]["role"], "compliance");
+}
+
+#[ignore = "Requires Docker for testcontainers"]
+#[tokio::test]
+async fn test_invalid_scalar_input_is_rejected() {
+    let app = common::TestApp::new().await;
+
+    let (status, body) = graphql(&app, &[], r#"{ transaction(id: "not-a-uuid") { id } }"#).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["data"].is_null(), "{body}");
+    let error = &body["errors"][0];
+    assert_eq!(error["extensions"]["code"], "VALIDATION_ERROR", "{body}");
+    let message = error["message"].as_str().unwrap();
+    assert!(message.contains("Invalid UUID 'not-a-uuid'"), "{message}");
+
+    // The same through a variable, nested in an input object.
+    let (_, body) = graphql_with_variables(
+        &app,
+        &[],
+        "query ($account: StellarAccount) {
+            transactions(filter: { stellarAccount: $account }) { id }
+        }",
+        json!({ "account": "GABC" }),
+    )
+    .await;
+    let error = &body["errors"][0];
+    assert_eq!(error["extensions"]["code"], "VALIDATION_ERROR", "{body}");
+    let message = error["message"].as_str().unwrap();
+    assert!(
+        message.contains("Invalid StellarAccount 'GABC'"),
+        "{message}"
+    );
+}
+
+#[ignore = "Requires Docker for testcontainers"]
+#[tokio::test]
+async fn test_transaction_stats_include_new_transactions() {
+    let app = common::TestApp::new().await;
+    create_transaction(&app).await;
+    create_transaction(&app).await;
+
+    let (status, body) = graphql(
+        &app,
+        &[],
+        "{ transactionStats(window: DAY, groupBy: ASSET) {
+            window groupBy totalCount totalVolume buckets { key count volume }
+        } }",
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["errors"].is_null(), "{body}");
+
+    let stats = &body["data"]["transactionStats"];
+    assert_eq!(stats["window"], "DAY");
+    assert_eq!(stats["groupBy"], "ASSET");
+    assert_eq!(stats["totalCount"], 2);
+    let volume: f64 = stats["totalVolume"].as_str().unwrap().parse().unwrap();
+    assert_eq!(volume, 201.0);
+
+    let buckets = stats["buckets"].as_array().unwrap();
+    assert_eq!(buckets.len(), 1, "{body}");
+    assert_eq!(buckets[0]["key"], "USD");
+    assert_eq!(buckets[0]["count"], 2);
+}
+
+#[ignore = "Requires Docker for testcontainers"]
+#[tokio::test]
+async fn test_error_carries_code_and_request_id() {
+    let app = common::TestApp::new().await;
+
+    let res = reqwest::Client::new()
+        .post(format!("{}/graphql", app.base_url))
+        .header("x-request-id", "graphql-req-42")
+        .json(&json!({
+            "query": format!(r#"{{ transaction(id: "{}") {{ id }} }}"#, Uuid::new_v4())
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        res.headers()["x-request-id"].to_str().unwrap(),
+        "graphql-req-42"
+    );
+    let body: serde_json::Value = res.json().await.unwrap();
+    let extensions = &body["errors"][0]["extensions"];
+    assert_eq!(extensions["code"], "NOT_FOUND", "{body}");
+    assert_eq!(extensions["requestId"], "graphql-req-42");
+
+    // Without a client id the error quotes the one the server generated,
+    // also for errors raised before execution.
+    let query = format!(
+        r#"mutation {{ cancelJob(id: "{}") {{ id }} }}"#,
+        Uuid::new_v4()
+    );
+    let res = reqwest::Client::new()
+        .post(format!("{}/graphql", app.base_url))
+        .json(&json!({ "query": query }))
+        .send()
+        .await
+        .unwrap();
+    let request_id = res.headers()["x-request-id"].to_str().unwrap().to_string();
+    let body: serde_json::Value = res.json().await.unwrap();
+    let extensions = &body["errors"][0]["extensions"];
+    assert_eq!(extensions["code"], "AUTHORIZATION_ERROR", "{body}");
+    assert_eq!(extensions["requestId"], request_id.as_str());
+}
+
+#[ignore = "Requires Docker for testcontainers"]
+#[tokio::test]
+async fn test_register_transaction_callback() {
+    let app = common::TestApp::new().await;
+    let tx_id = create_transaction(&app).await;
+    let register = |tx_id: Uuid, url: &str| {
+        format!(
+            r#"mutation {{ registerTransactionCallback(
+                txId: "{tx_id}", url: "{url}", secret: "wallet-callback-secret-1"
+            ) {{ transactionId url enabled }} }}"#
+        )
+    };
+
+    let (status, body) = graphql(&app, &[], &register(tx_id, "https://wallet.example/a")).await;
+    assert_eq!(status, StatusCode::OK);
+    let callback = &body["data"]["registerTransactionCallback"];
+    assert_eq!(callback["transactionId"], tx_id.to_string(), "{body}");
+    assert_eq!(callback["url"], "https://wallet.example/a");
+    assert_eq!(callback["enabled"], true);
+
+    // Registering again replaces the URL instead of adding an endpoint.
+    let (_, body) = graphql(&app, &[], &register(tx_id, "https://wallet.example/b")).await;
+    assert!(body["errors"].is_null(), "{body}");
+    let urls: Vec<String> =
+        sqlx::query_scalar("SELECT url FROM webhook_endpoints WHERE transaction_id = $1")
+            .bind(tx_id)
+            .fetch_all(&app.pool)
+            .await
+            .unwrap();
+    assert_eq!(urls, vec!["https://wallet.example/b".to_string()]);
+
+    let (_, body) = graphql(&app, &[], &register(tx_id, "ftp://wallet.example")).await;
+    assert_eq!(
+        body["errors"][0]["extensions"]["code"], "VALIDATION_ERROR",
+        "{body}"
+    );
+
+    let (_, body) = graphql(
+        &app,
+        &[],
+        &register(Uuid::new_v4(), "https://wallet.example"),
+    )
+    .await;
+    assert_eq!(
+        body["errors"][0]["extensions"]["code"], "NOT_FOUND",
+        "{body}"
+    );
+}
@@ -2,6 +2,9 @@ use reqwest::StatusCode;
 use serde_json::json;
 use sqlx::{migrate::Migrator, PgPool};
 use std::path::Path;
+use std::sync::Arc;
+use synapse_core::adapters::BroadcastEventBus;
+use synapse_core::services::event_channels::EventClass;
 use synapse_core::{create_app, AppState};
 use testcontainers::runners::AsyncRunner;
 use testcontainers_modules::postgres::Postgres;
@@ -50,7 +53,6 @@ async fn setup_test_app() -> (String, PgPool, impl std::any::Any) {
     .execute(&pool)
     .await;
 
-    let (tx, _rx) = tokio::sync::broadcast::channel(100);
     let _query_cache = synapse_core::services::QueryCache::new("redis://localhost:6379")
         .await
         .unwrap();
@@ -67,7 +69,8 @@ async fn setup_test_app() -> (String, PgPool, impl std::any::Any) {
         redis_url: "redis://localhost:6379".to_string(),
         start_time: std::time::Instant::now(),
         readiness: synapse_core::ReadinessState::new(),
-        tx_broadcast: tx,
+        domain_events: Arc::new(BroadcastEventBus::new(EventClass::Transactions)),
+        settlement_events: Arc::new(BroadcastEventBus::new(EventClass::Settlements)),
         query_cache: synapse_core::services::QueryCache::new("redis://localhost:6379")
             .await
             .unwrap(),
@@ -80,6 +83,9 @@ async fn setup_test_app() -> (String, PgPool, impl std::any::Any) {
         secrets_store: None,
         metrics_handle: synapse_core::metrics::init_metrics().unwrap(),
         ws_connection_count: std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+        job_scheduler: std::sync::Arc::new(synapse_core::services::JobScheduler::new()),
+        signing_keys: synapse_core::services::signing_keys::SigningKeyStore::new(String::new()),
+        clock: std::sync::Arc::new(synapse_core::adapters::SystemClock),
     };
     let app = create_app(app_state);
 
@@ -102,9 +108,10 @@ async fn test_valid_deposit_flow() {
     let client = reqwest::Client::new();
 
     let payload = json!({
-        "stellar_account": "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA",
+        "stellar_account": "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF",
         "amount": "100.50",
         "asset_code": "USD",
+        "anchor_transaction_id": "integration-1",
         "callback_type": "deposit",
         "callback_status": "completed"
     });
@@ -142,9 +149,10 @@ async fn test_callback_with_memo_and_metadata() {
     let client = reqwest::Client::new();
 
     let payload = json!({
-        "stellar_account": "GBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBB",
+        "stellar_account": "GBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBKOH",
         "amount": "250.00",
         "asset_code": "USDC",
+        "anchor_transaction_id": "integration-2",
         "callback_type": "deposit",
         "callback_status": "completed",
         "memo": "payment for invoice #1042",
@@ -197,9 +205,10 @@ async fn test_callback_with_hash_memo_type() {
     let client = reqwest::Client::new();
 
     let payload = json!({
-        "stellar_account": "GCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCC",
+        "stellar_account": "GCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCDG5Q",
         "amount": "500.00",
         "asset_code": "USD",
+        "anchor_transaction_id": "integration-3",
         "memo": "abc123def456",
         "memo_type": "hash"
     });
@@ -225,9 +234,10 @@ async fn test_callback_with_invalid_memo_type() {
     let client = reqwest::Client::new();
 
     let payload = json!({
-        "stellar_account": "GDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDD",
+        "stellar_account": "GDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDC2US",
         "amount": "100.00",
         "asset_code": "USD",
+        "anchor_transaction_id": "integration-4",
         "memo": "some memo",
         "memo_type": "invalid_type"
     });
@@ -250,9 +260,10 @@ async fn test_callback_with_metadata_only() {
     let client = reqwest::Client::new();
 
     let payload = json!({
-        "stellar_account": "GEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEE",
+        "stellar_account": "GBCUKRKFIVCUKRKFIVCUKRKFIVCUKRKFIVCUKRKFIVCUKRKFIVCULW2C",
         "amount": "75.25",
         "asset_code": "EUR",
+        "anchor_transaction_id": "integration-5",
         "metadata": {
             "partner_ref": "P-9001",
             "tags": ["recurring", "verified"]
@@ -281,9 +292,10 @@ async fn test_invalid_signature_flow() {
     let client = reqwest::Client::new();
 
     let payload = json!({
-        "stellar_account": "GFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+        "stellar_account": "GBDEMRSGIZDEMRSGIZDEMRSGIZDEMRSGIZDEMRSGIZDEMRSGIZDEMGSF",
         "amount": "100.50",
         "asset_code": "USD",
+        "anchor_transaction_id": "integration-6",
         "callback_type": "deposit",
         "callback_status": "completed"
     });
@@ -70,6 +70,7 @@ async fn test_full_transaction_lifecycle() {
         "stellar_account": "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF",
         "amount": "150.00",
         "asset_code": "USD",
+        "anchor_transaction_id": format!("lifecycle-{}", Uuid::new_v4()),
         "callback_type": "deposit",
         "callback_status": "pending_external",
         "memo": "lifecycle-test",
@@ -250,7 +251,8 @@ async fn test_callback_returns_201_and_persists() {
         .json(&json!({
             "stellar_account": "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF",
             "amount": "50.00",
-            "asset_code": "USDC"
+            "asset_code": "USDC",
+            "anchor_transaction_id": format!("lifecycle-{}", Uuid::new_v4())
         }))
         .send()
         .await
@@ -280,7 +282,8 @@ async fn test_all_state_transitions_are_audited() {
         .json(&json!({
             "stellar_account": "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF",
             "amount": "75.00",
-            "asset_code": "USD"
+            "asset_code": "USD",
+            "anchor_transaction_id": format!("lifecycle-{}", Uuid::new_v4())
         }))
         .send()
         .await
@@ -186,8 +186,9 @@ async fn insert_dummy_data(pool: &PgPool) {
     // Insert a transaction.
     sqlx::query(
         r#"
-        INSERT INTO transactions (stellar_account, amount, asset_code, status)
-        VALUES ('GABC1234567890123456789012345678901234567890123456789012', 100.0, 'USD', 'pending')
+        INSERT INTO transactions (stellar_account, amount, asset_code, status, anchor_transaction_id)
+        VALUES ('GABC1234567890123456789012345678901234567890123456789012', 100.0, 'USD', 'pending',
+            'migration-test')
         "#,
     )
     .execute(pool)
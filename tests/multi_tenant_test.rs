@@ -52,6 +52,9 @@ fn make_tenant_config(tenant_id: Uuid, name: &str) -> TenantConfig {
         stellar_account: "account".to_string(),
         rate_limit_per_minute: 100,
         is_active: true,
+        processing_weight: 1,
+        allowed_assets: None,
+        trusted_assets: Vec::new(),
     }
 }
 
@@ -66,7 +69,9 @@ async fn ensure_schema(pool: &PgPool) {
             webhook_secret VARCHAR(255) NOT NULL DEFAULT '',
             stellar_account VARCHAR(56) NOT NULL DEFAULT '',
             rate_limit_per_minute INTEGER NOT NULL DEFAULT 60,
-            is_active BOOLEAN NOT NULL DEFAULT true
+            is_active BOOLEAN NOT NULL DEFAULT true,
+            allowed_assets TEXT[],
+            processing_weight INTEGER NOT NULL DEFAULT 1
         )",
     )
     .execute(pool)
@@ -174,7 +179,7 @@ async fn test_query_filtering_by_tenant() {
     let tx1 = Uuid::new_v4();
     let tx2 = Uuid::new_v4();
     sqlx::query(
-        "INSERT INTO transactions (id, stellar_account, amount, asset_code, tenant_id, created_at) VALUES ($1, '', 10, 'USD', $2, NOW())"
+        "INSERT INTO transactions (id, stellar_account, amount, asset_code, tenant_id, created_at, anchor_transaction_id) VALUES ($1, '', 10, 'USD', $2, NOW(), $1::text)"
     )
     .bind(tx1)
     .bind(t1)
@@ -182,7 +187,7 @@ async fn test_query_filtering_by_tenant() {
     .await
     .unwrap();
     sqlx::query(
-        "INSERT INTO transactions (id, stellar_account, amount, asset_code, tenant_id, created_at) VALUES ($1, '', 20, 'USD', $2, NOW())"
+        "INSERT INTO transactions (id, stellar_account, amount, asset_code, tenant_id, created_at, anchor_transaction_id) VALUES ($1, '', 20, 'USD', $2, NOW(), $1::text)"
     )
     .bind(tx2)
     .bind(t2)
@@ -308,7 +313,7 @@ async fn test_db_foreign_key_enforces_tenant() {
 
     // Insert a transaction referencing a non-existent tenant_id — FK should reject it
     let result = sqlx::query(
-        "INSERT INTO transactions (id, stellar_account, amount, asset_code, tenant_id, created_at) VALUES ($1, '', 5, 'USD', $2, NOW())",
+        "INSERT INTO transactions (id, stellar_account, amount, asset_code, tenant_id, created_at, anchor_transaction_id) VALUES ($1, '', 5, 'USD', $2, NOW(), $1::text)",
     )
     .bind(Uuid::new_v4())
     .bind(Uuid::new_v4()) // random UUID — no matching tenant
@@ -78,8 +78,10 @@ async fn insert_tx_for_tenant(pool: &PgPool, tenant_id: Uuid) -> Uuid {
         .await
         .unwrap();
     sqlx::query(
-        r#"INSERT INTO transactions (id, stellar_account, amount, asset_code, status, created_at, updated_at, tenant_id)
-           VALUES ($1, 'GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA', 100, 'USD', 'pending', NOW(), NOW(), $2)"#,
+        r#"INSERT INTO transactions (id, stellar_account, amount, asset_code, status, created_at, updated_at, tenant_id,
+               anchor_transaction_id)
+           VALUES ($1, 'GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA', 100, 'USD', 'pending', NOW(), NOW(), $2,
+               $1::text)"#,
     )
     .bind(id)
     .bind(tenant_id)
@@ -174,8 +176,10 @@ async fn test_null_tenant_id_rows_visible_to_admin() {
     // Insert a legacy row with no tenant_id — must use admin_pool to bypass INSERT policy
     let id = Uuid::new_v4();
     sqlx::query(
-        r#"INSERT INTO transactions (id, stellar_account, amount, asset_code, status, created_at, updated_at)
-           VALUES ($1, 'GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA', 50, 'USD', 'pending', NOW(), NOW())"#,
+        r#"INSERT INTO transactions (id, stellar_account, amount, asset_code, status, created_at, updated_at,
+               anchor_transaction_id)
+           VALUES ($1, 'GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA', 50, 'USD', 'pending', NOW(), NOW(),
+               $1::text)"#,
     )
     .bind(id)
     .execute(&admin_pool)
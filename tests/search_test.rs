@@ -4,7 +4,10 @@ use sqlx::types::BigDecimal;
 use sqlx::{migrate::Migrator, PgPool};
 use std::path::Path;
 use std::str::FromStr;
+use std::sync::Arc;
+use synapse_core::adapters::BroadcastEventBus;
 use synapse_core::db::pool_manager::PoolManager;
+use synapse_core::services::event_channels::EventClass;
 use synapse_core::services::feature_flags::FeatureFlagService;
 use synapse_core::{create_app, AppState};
 use testcontainers::runners::AsyncRunner;
@@ -30,7 +33,6 @@ async fn setup_test_app() -> (String, PgPool, impl std::any::Any) {
     migrator.run(&pool).await.unwrap();
 
     let pool_manager = PoolManager::new(&database_url, None, 5).await.unwrap();
-    let (tx_broadcast, _) = tokio::sync::broadcast::channel(100);
     let _query_cache = synapse_core::services::QueryCache::new("redis://localhost:6379")
         .await
         .unwrap();
@@ -45,7 +47,8 @@ async fn setup_test_app() -> (String, PgPool, impl std::any::Any) {
         redis_url: "redis://localhost:6379".to_string(),
         start_time: std::time::Instant::now(),
         readiness: synapse_core::ReadinessState::new(),
-        tx_broadcast,
+        domain_events: Arc::new(BroadcastEventBus::new(EventClass::Transactions)),
+        settlement_events: Arc::new(BroadcastEventBus::new(EventClass::Settlements)),
         query_cache: synapse_core::services::QueryCache::new("redis://localhost:6379")
             .await
             .unwrap(),
@@ -58,6 +61,9 @@ async fn setup_test_app() -> (String, PgPool, impl std::any::Any) {
         metrics_handle: synapse_core::metrics::init_metrics().unwrap(),
         secrets_store: None,
         ws_connection_count: std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+        job_scheduler: std::sync::Arc::new(synapse_core::services::JobScheduler::new()),
+        signing_keys: synapse_core::services::signing_keys::SigningKeyStore::new(String::new()),
+        clock: std::sync::Arc::new(synapse_core::adapters::SystemClock),
     };
     let app = create_app(app_state);
 
@@ -86,8 +92,8 @@ async fn seed_test_data(pool: &PgPool) {
         r#"
         INSERT INTO transactions (
             id, stellar_account, amount, asset_code, status,
-            created_at, updated_at
-        ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+            created_at, updated_at, anchor_transaction_id
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $1::text)
         "#,
     )
     .bind(Uuid::new_v4())
@@ -106,8 +112,8 @@ async fn seed_test_data(pool: &PgPool) {
         r#"
         INSERT INTO transactions (
             id, stellar_account, amount, asset_code, status,
-            created_at, updated_at
-        ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+            created_at, updated_at, anchor_transaction_id
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $1::text)
         "#,
     )
     .bind(Uuid::new_v4())
@@ -126,8 +132,8 @@ async fn seed_test_data(pool: &PgPool) {
         r#"
         INSERT INTO transactions (
             id, stellar_account, amount, asset_code, status,
-            created_at, updated_at
-        ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+            created_at, updated_at, anchor_transaction_id
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $1::text)
         "#,
     )
     .bind(Uuid::new_v4())
@@ -146,8 +152,8 @@ async fn seed_test_data(pool: &PgPool) {
         r#"
         INSERT INTO transactions (
             id, stellar_account, amount, asset_code, status,
-            created_at, updated_at
-        ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+            created_at, updated_at, anchor_transaction_id
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $1::text)
         "#,
     )
     .bind(Uuid::new_v4())
@@ -166,8 +172,8 @@ async fn seed_test_data(pool: &PgPool) {
         r#"
         INSERT INTO transactions (
             id, stellar_account, amount, asset_code, status,
-            created_at, updated_at
-        ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+            created_at, updated_at, anchor_transaction_id
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $1::text)
         "#,
     )
     .bind(Uuid::new_v4())
@@ -13,6 +13,7 @@ fn create_test_config(database_url: String, redis_url: String, horizon_url: Stri
         database_url,
         database_replica_url: None,
         stellar_horizon_url: horizon_url,
+        stellar_network_passphrase: None,
         anchor_webhook_secret: "test-secret".to_string(),
         redis_url,
         default_rate_limit: 100,
@@ -22,6 +23,8 @@ fn create_test_config(database_url: String, redis_url: String, horizon_url: Stri
         allowed_ips: AllowedIps::Any,
         backup_dir: "./backups".to_string(),
         backup_encryption_key: None,
+        backup_encryption_key_id: "1".to_string(),
+        backup_previous_encryption_keys: None,
         db_timeouts: synapse_core::config::DbTimeoutConfig::default(),
         otlp_endpoint: None,
         cors_allowed_origins: vec![],
@@ -31,6 +34,8 @@ fn create_test_config(database_url: String, redis_url: String, horizon_url: Stri
         db_statement_timeout_ms: 30000,
         db_idle_timeout_secs: 600,
         db_long_running_statement_timeout_ms: 300000,
+        migration_mode: synapse_core::db::migrations::MigrationMode::Startup,
+        migration_lock_timeout_secs: 300,
         processor_workers: 4,
         processor_batch_size: 50,
         processor_poll_interval_ms: 1000,
@@ -40,6 +45,7 @@ fn create_test_config(database_url: String, redis_url: String, horizon_url: Stri
         slow_query_threshold_ms: 500,
         settlement_max_batch_size: 10000,
         settlement_min_tx_count: 1,
+        jobs: Default::default(),
     }
 }
 
@@ -0,0 +1,61 @@
+//! `GET /.well-known/stellar.toml` against the real router: every server URL
+//! it publishes must lead to mounted routes.
+#![cfg(feature = "sep")]
+
+mod common;
+
+use reqwest::StatusCode;
+use synapse_core::services::stellar_toml::ENDPOINTS;
+
+const SEED: &str = "SBGWSG6BTNCKCOB3DIFBGCVMUPQFYPA2G4O34RMTB343OYPXU5DJDVMN";
+const ACCOUNT: &str = "GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H";
+
+#[ignore = "Requires Docker for testcontainers"]
+#[tokio::test]
+async fn test_published_urls_match_mounted_routes() {
+    let app = common::TestApp::new().await;
+    let host = app.base_url.trim_start_matches("http://").to_string();
+    std::env::set_var("STELLAR_TOML_BASE_URL", &app.base_url);
+    std::env::set_var("SEP10_SIGNING_SEED", SEED);
+    std::env::set_var("SEP10_HOME_DOMAIN", &host);
+    std::env::set_var("SEP10_JWT_SECRET", "j".repeat(32));
+    std::env::set_var(
+        "STELLAR_NETWORK_PASSPHRASE",
+        "Test SDF Network ; September 2015",
+    );
+    std::env::set_var("SEP6_WITHDRAW_ACCOUNT", ACCOUNT);
+    std::env::set_var(
+        "SEP24_INTERACTIVE_URL",
+        "https://wallet.example.com/interactive",
+    );
+    std::env::set_var("SEP24_INTERACTIVE_SECRET", "s".repeat(32));
+    std::env::set_var("SEP31_RECEIVING_ACCOUNT", ACCOUNT);
+
+    let client = reqwest::Client::new();
+    let res = client
+        .get(format!("{}/.well-known/stellar.toml", app.base_url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["access-control-allow-origin"], "*");
+    let body = res.text().await.unwrap();
+
+    for endpoint in ENDPOINTS {
+        let line = format!("{} = \"{}{}\"", endpoint.key, app.base_url, endpoint.path);
+        assert!(body.contains(&line), "missing {line} in:\n{body}");
+
+        let probe = client
+            .get(format!("{}{}", app.base_url, endpoint.probe))
+            .send()
+            .await
+            .unwrap();
+        assert_ne!(
+            probe.status(),
+            StatusCode::NOT_FOUND,
+            "{} is published but {} is not mounted",
+            endpoint.key,
+            endpoint.probe
+        );
+    }
+}
@@ -408,3 +408,81 @@ async fn test_circuit_breaker_isolates_failing_endpoint() {
         assert_eq!(state["state"], "open", "Circuit breaker should be open");
     }
 }
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Test 4: Delivery offsets are persisted and a restarted dispatcher resumes
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[tokio::test]
+#[ignore = "Requires Docker"]
+async fn test_delivery_offset_persists_across_restart() {
+    let (pool, _pg) = setup_postgres().await;
+    let (redis_url, _redis) = setup_redis().await;
+
+    let mut server = Server::new_async().await;
+    let first = server
+        .mock("POST", "/webhook")
+        .match_header("X-Webhook-Sequence", "1")
+        .with_status(200)
+        .expect(1)
+        .create();
+    let second = server
+        .mock("POST", "/webhook")
+        .match_header("X-Webhook-Sequence", "2")
+        .with_status(200)
+        .expect(1)
+        .create();
+    let third = server
+        .mock("POST", "/webhook")
+        .match_header("X-Webhook-Sequence", "3")
+        .with_status(200)
+        .expect(1)
+        .create();
+
+    let endpoint_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO webhook_endpoints (url, secret, event_types, max_delivery_rate)
+        VALUES ($1, 'test-secret', ARRAY['test.event'], 100)
+        RETURNING id
+        "#,
+    )
+    .bind(format!("{}/webhook", server.url()))
+    .fetch_one(&pool)
+    .await
+    .expect("Failed to insert endpoint");
+
+    let acked_sequence = |pool: PgPool| async move {
+        sqlx::query_scalar::<_, i64>("SELECT acked_sequence FROM webhook_endpoints WHERE id = $1")
+            .bind(endpoint_id)
+            .fetch_one(&pool)
+            .await
+            .expect("endpoint should exist")
+    };
+
+    let dispatcher = WebhookDispatcher::new(pool.clone(), &redis_url).expect("dispatcher");
+    for _ in 0..2 {
+        dispatcher
+            .enqueue(Uuid::new_v4(), "test.event", serde_json::json!({}))
+            .await
+            .expect("enqueue");
+    }
+    dispatcher.process_pending().await.expect("process_pending");
+    first.assert_async().await;
+    second.assert_async().await;
+    assert_eq!(acked_sequence(pool.clone()).await, 2);
+
+    // A new dispatcher stands in for a restart: it must only deliver what
+    // was queued above the persisted offset.
+    drop(dispatcher);
+    let restarted = WebhookDispatcher::new(pool.clone(), &redis_url).expect("dispatcher");
+    restarted
+        .enqueue(Uuid::new_v4(), "test.event", serde_json::json!({}))
+        .await
+        .expect("enqueue");
+    restarted.process_pending().await.expect("process_pending");
+
+    first.assert_async().await;
+    second.assert_async().await;
+    third.assert_async().await;
+    assert_eq!(acked_sequence(pool.clone()).await, 3);
+}
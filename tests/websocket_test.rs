@@ -1,22 +1,28 @@
+#![cfg(feature = "websocket")]
+
 use chrono::Utc;
 use futures::{SinkExt, StreamExt};
 use sqlx::{migrate::Migrator, PgPool};
 use std::path::Path;
+use std::sync::Arc;
+use synapse_core::adapters::BroadcastEventBus;
 use synapse_core::db::pool_manager::PoolManager;
+use synapse_core::domain::DomainEvent;
 use synapse_core::handlers::ws::TransactionStatusUpdate;
+use synapse_core::ports::EventBus;
+use synapse_core::services::event_channels::EventClass;
 use synapse_core::services::feature_flags::FeatureFlagService;
 use synapse_core::{create_app, AppState};
 use testcontainers::runners::AsyncRunner;
 use testcontainers_modules::postgres::Postgres;
 use tokio::net::TcpListener;
-use tokio::sync::broadcast;
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 use uuid::Uuid;
 
 async fn setup_test_app() -> (
     String,
     PgPool,
-    broadcast::Sender<TransactionStatusUpdate>,
+    Arc<dyn EventBus<DomainEvent>>,
     impl std::any::Any,
 ) {
     let container = Postgres::default().start().await.unwrap();
@@ -36,7 +42,8 @@ async fn setup_test_app() -> (
     migrator.run(&pool).await.unwrap();
 
     let pool_manager = PoolManager::new(&database_url, None, 5).await.unwrap();
-    let (tx_broadcast, _) = broadcast::channel::<TransactionStatusUpdate>(100);
+    let domain_events: Arc<dyn EventBus<DomainEvent>> =
+        Arc::new(BroadcastEventBus::new(EventClass::Transactions));
     let _query_cache = synapse_core::services::QueryCache::new("redis://localhost:6379")
         .await
         .unwrap();
@@ -51,7 +58,8 @@ async fn setup_test_app() -> (
         redis_url: "redis://localhost:6379".to_string(),
         start_time: std::time::Instant::now(),
         readiness: synapse_core::ReadinessState::new(),
-        tx_broadcast: tx_broadcast.clone(),
+        domain_events: domain_events.clone(),
+        settlement_events: Arc::new(BroadcastEventBus::new(EventClass::Settlements)),
         query_cache: synapse_core::services::QueryCache::new("redis://localhost:6379")
             .await
             .unwrap(),
@@ -64,6 +72,9 @@ async fn setup_test_app() -> (
         secrets_store: None,
         metrics_handle: synapse_core::metrics::init_metrics().unwrap(),
         ws_connection_count: std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+        job_scheduler: std::sync::Arc::new(synapse_core::services::JobScheduler::new()),
+        signing_keys: synapse_core::services::signing_keys::SigningKeyStore::new(String::new()),
+        clock: std::sync::Arc::new(synapse_core::adapters::SystemClock),
     };
 
     let app = create_app(app_state);
@@ -81,7 +92,7 @@ async fn setup_test_app() -> (
     });
 
     let base_url = format!("ws://{}", addr);
-    (base_url, pool, tx_broadcast, container)
+    (base_url, pool, domain_events, container)
 }
 
 #[tokio::test]
@@ -134,7 +145,7 @@ async fn test_ws_connection_rejected_invalid_token() {
 #[tokio::test]
 #[ignore = "Requires Docker for testcontainers"]
 async fn test_ws_receives_transaction_updates() {
-    let (base_url, _pool, tx_broadcast, _container) = setup_test_app().await;
+    let (base_url, _pool, domain_events, _container) = setup_test_app().await;
 
     // Connect WebSocket client
     let ws_url = format!("{}/ws?token=test-token", base_url);
@@ -151,9 +162,12 @@ async fn test_ws_receives_transaction_updates() {
         status: "completed".to_string(),
         timestamp: Utc::now(),
         message: Some("Transaction processed successfully".to_string()),
+        stellar_tx_hash: None,
+        ledger: None,
+        closed_at: None,
     };
 
-    tx_broadcast.send(update.clone()).unwrap();
+    assert!(domain_events.publish(DomainEvent::from(update.clone())) > 0);
 
     // Wait for the message
     let msg = tokio::time::timeout(tokio::time::Duration::from_secs(5), ws_stream.next()).await;
@@ -189,7 +203,7 @@ async fn test_ws_receives_transaction_updates() {
 #[tokio::test]
 #[ignore = "Requires Docker for testcontainers"]
 async fn test_ws_multiple_clients_receive_broadcast() {
-    let (base_url, _pool, tx_broadcast, _container) = setup_test_app().await;
+    let (base_url, _pool, domain_events, _container) = setup_test_app().await;
 
     // Connect multiple WebSocket clients
     let ws_url1 = format!("{}/ws?token=client1", base_url);
@@ -211,9 +225,12 @@ async fn test_ws_multiple_clients_receive_broadcast() {
         status: "pending".to_string(),
         timestamp: Utc::now(),
         message: None,
+        stellar_tx_hash: None,
+        ledger: None,
+        closed_at: None,
     };
 
-    let sent_count = tx_broadcast.send(update.clone()).unwrap();
+    let sent_count = domain_events.publish(DomainEvent::from(update.clone()));
     assert_eq!(sent_count, 3, "Should have 3 active subscribers");
 
     // All clients should receive the message — skip any ping frames
@@ -253,7 +270,7 @@ async fn test_ws_multiple_clients_receive_broadcast() {
 #[tokio::test]
 #[ignore = "Requires Docker for testcontainers"]
 async fn test_ws_connection_cleanup_on_disconnect() {
-    let (base_url, _pool, tx_broadcast, _container) = setup_test_app().await;
+    let (base_url, _pool, domain_events, _container) = setup_test_app().await;
 
     // Connect a client
     let ws_url = format!("{}/ws?token=test-client", base_url);
@@ -269,9 +286,12 @@ async fn test_ws_connection_cleanup_on_disconnect() {
         tenant_id: Uuid::default(),
         timestamp: Utc::now(),
         message: None,
+        stellar_tx_hash: None,
+        ledger: None,
+        closed_at: None,
     };
 
-    let sent_count = tx_broadcast.send(update.clone()).unwrap();
+    let sent_count = domain_events.publish(DomainEvent::from(update.clone()));
     assert_eq!(sent_count, 1, "Should have 1 active subscriber");
 
     // Drop the connection (simulates client disconnect)
@@ -287,9 +307,12 @@ async fn test_ws_connection_cleanup_on_disconnect() {
         tenant_id: Uuid::default(),
         timestamp: Utc::now(),
         message: None,
+        stellar_tx_hash: None,
+        ledger: None,
+        closed_at: None,
     };
 
-    let sent_count2 = tx_broadcast.send(update2).unwrap_or(0);
+    let sent_count2 = domain_events.publish(DomainEvent::from(update2));
     assert_eq!(
         sent_count2, 0,
         "Should have 0 active subscribers after disconnect"
@@ -352,7 +375,7 @@ async fn test_ws_client_can_send_messages() {
 #[tokio::test]
 #[ignore = "Requires Docker for testcontainers"]
 async fn test_ws_handles_rapid_broadcasts() {
-    let (base_url, _pool, tx_broadcast, _container) = setup_test_app().await;
+    let (base_url, _pool, domain_events, _container) = setup_test_app().await;
 
     // Connect WebSocket client
     let ws_url = format!("{}/ws?token=rapid-test", base_url);
@@ -373,9 +396,12 @@ async fn test_ws_handles_rapid_broadcasts() {
             status: format!("status_{}", i),
             timestamp: Utc::now(),
             message: Some(format!("Update {}", i)),
+            stellar_tx_hash: None,
+            ledger: None,
+            closed_at: None,
         };
 
-        tx_broadcast.send(update).unwrap();
+        assert!(domain_events.publish(DomainEvent::from(update)) > 0);
     }
 
     // Receive all messages
@@ -1,6 +1,7 @@
 pub mod lint;
 pub mod release;
 pub mod setup;
+pub mod sqlx_prepare;
 pub mod test;
 
 use std::process::{Command, ExitStatus};
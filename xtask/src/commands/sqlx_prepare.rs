@@ -0,0 +1,38 @@
+use super::run_cmd;
+use clap::Args;
+
+/// Arguments for `cargo xtask sqlx-prepare`
+#[derive(Args)]
+pub struct SqlxPrepareArgs {
+    /// Fail if `.sqlx/` is out of date instead of rewriting it (for CI).
+    #[arg(long)]
+    pub check: bool,
+}
+
+pub fn run(args: SqlxPrepareArgs) -> anyhow::Result<()> {
+    println!("==> synapse-core sqlx-prepare");
+
+    if std::env::var("DATABASE_URL").is_err() {
+        anyhow::bail!("DATABASE_URL must point at a migrated database");
+    }
+
+    // Build with `checked-queries` so every gated `query!` macro is described.
+    let mut cmd_args = vec!["sqlx", "prepare", "--workspace"];
+    if args.check {
+        cmd_args.push("--check");
+    }
+    cmd_args.extend_from_slice(&[
+        "--",
+        "--all-targets",
+        "--features",
+        "synapse-core/checked-queries",
+    ]);
+    run_cmd("cargo", &cmd_args)?;
+
+    if args.check {
+        println!("\n✓ .sqlx/ is up to date.");
+    } else {
+        println!("\n✓ .sqlx/ regenerated — commit it with your query changes.");
+    }
+    Ok(())
+}
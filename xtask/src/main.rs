@@ -32,6 +32,12 @@ enum Commands {
     /// Requires: cargo-audit (`cargo install cargo-audit`)
     Lint(commands::lint::LintArgs),
 
+    /// Regenerate `.sqlx/` offline query metadata from a live database.
+    ///
+    /// Requires: sqlx-cli, DATABASE_URL pointing at a migrated database.
+    /// Use --check in CI to fail when the committed metadata is stale.
+    SqlxPrepare(commands::sqlx_prepare::SqlxPrepareArgs),
+
     /// Build a release, run tests, create a git tag, and push.
     ///
     /// Requires a clean git working tree and the VERSION env var or --version flag.
@@ -45,6 +51,7 @@ fn main() -> anyhow::Result<()> {
         Commands::Setup(args) => commands::setup::run(args),
         Commands::Test(args) => commands::test::run(args),
         Commands::Lint(args) => commands::lint::run(args),
+        Commands::SqlxPrepare(args) => commands::sqlx_prepare::run(args),
         Commands::Release(args) => commands::release::run(args),
     }
 }